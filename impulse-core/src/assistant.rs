@@ -0,0 +1,440 @@
+//! The assistant: chat with a language model, "explain selection" and
+//! commit message generation. Off until the `assistant_enabled` setting is
+//! turned on; nothing leaves the machine before then.
+//!
+//! Two backends are supported: any OpenAI-compatible chat completions API
+//! and a local Ollama server. API keys live in the system keyring, never in
+//! the settings file.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::secrets::SecretKind;
+use crate::settings::Settings;
+
+pub const PROVIDER_OPENAI: &str = "openai";
+pub const PROVIDER_OLLAMA: &str = "ollama";
+
+/// Local models can take a while to answer on modest hardware.
+const REQUEST_TIMEOUT_SECS: u64 = 120;
+
+/// Longest diff sent for a commit message, in characters; the rest is cut.
+const MAX_DIFF_CHARS: usize = 24_000;
+
+/// Longest selection sent to be explained, in characters.
+const MAX_SELECTION_CHARS: usize = 16_000;
+
+const SYSTEM_PROMPT: &str = "You are a coding assistant built into the Impulse editor. \
+     Answer concisely and use Markdown code blocks for code.";
+
+/// A backend the assistant can talk to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Provider {
+    /// OpenAI or any server with the same chat completions API.
+    OpenAi,
+    /// A local Ollama server.
+    Ollama,
+}
+
+/// Providers in the order settings list them.
+pub const PROVIDERS: &[Provider] = &[Provider::OpenAi, Provider::Ollama];
+
+impl Provider {
+    /// The provider with the `assistant_provider` id `id`.
+    pub fn from_id(id: &str) -> Option<Self> {
+        match id {
+            PROVIDER_OPENAI => Some(Provider::OpenAi),
+            PROVIDER_OLLAMA => Some(Provider::Ollama),
+            _ => None,
+        }
+    }
+
+    pub fn id(self) -> &'static str {
+        match self {
+            Provider::OpenAi => PROVIDER_OPENAI,
+            Provider::Ollama => PROVIDER_OLLAMA,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Provider::OpenAi => "OpenAI-compatible",
+            Provider::Ollama => "Ollama",
+        }
+    }
+
+    /// Base URL used when `assistant_endpoint` is empty.
+    pub fn default_endpoint(self) -> &'static str {
+        match self {
+            Provider::OpenAi => "https://api.openai.com/v1",
+            Provider::Ollama => "http://localhost:11434",
+        }
+    }
+
+    /// Model used when `assistant_model` is empty.
+    pub fn default_model(self) -> &'static str {
+        match self {
+            Provider::OpenAi => "gpt-4o-mini",
+            Provider::Ollama => "llama3.2",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    System,
+    User,
+    Assistant,
+}
+
+/// One message of a conversation, in the shape both APIs accept.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub role: Role,
+    pub content: String,
+}
+
+impl ChatMessage {
+    pub fn system(content: impl Into<String>) -> Self {
+        Self {
+            role: Role::System,
+            content: content.into(),
+        }
+    }
+
+    pub fn user(content: impl Into<String>) -> Self {
+        Self {
+            role: Role::User,
+            content: content.into(),
+        }
+    }
+
+    pub fn assistant(content: impl Into<String>) -> Self {
+        Self {
+            role: Role::Assistant,
+            content: content.into(),
+        }
+    }
+}
+
+/// Where and how to send requests, resolved from the settings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssistantConfig {
+    pub provider: Provider,
+    /// Base URL without a trailing slash.
+    pub endpoint: String,
+    pub model: String,
+    pub api_key: Option<String>,
+}
+
+impl AssistantConfig {
+    /// The configuration `settings` describe, with the provider's API key
+    /// from the keyring. Fails while the assistant is turned off.
+    pub fn from_settings(settings: &Settings) -> Result<Self, String> {
+        if !settings.assistant_enabled {
+            return Err("The assistant is turned off in Settings".to_string());
+        }
+        let mut config = Self::without_key(settings);
+        config.api_key = api_key(config.provider);
+        Ok(config)
+    }
+
+    fn without_key(settings: &Settings) -> Self {
+        let provider = Provider::from_id(&settings.assistant_provider).unwrap_or(Provider::OpenAi);
+        let endpoint = match settings.assistant_endpoint.trim() {
+            "" => provider.default_endpoint(),
+            endpoint => endpoint,
+        };
+        let model = match settings.assistant_model.trim() {
+            "" => provider.default_model(),
+            model => model,
+        };
+        Self {
+            provider,
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            model: model.to_string(),
+            api_key: None,
+        }
+    }
+
+    /// The URL chat requests are POSTed to.
+    pub fn chat_url(&self) -> String {
+        match self.provider {
+            Provider::OpenAi => format!("{}/chat/completions", self.endpoint),
+            Provider::Ollama => format!("{}/api/chat", self.endpoint),
+        }
+    }
+
+    /// The JSON body of a chat request for `messages`.
+    pub fn request_body(&self, messages: &[ChatMessage]) -> serde_json::Value {
+        serde_json::json!({
+            "model": self.model,
+            "messages": messages,
+            "stream": false,
+        })
+    }
+}
+
+/// The reply text in a chat response from `provider`, or the error the
+/// server reported. `status` is the HTTP status code.
+pub fn parse_reply(provider: Provider, status: u16, body: &str) -> Result<String, String> {
+    let json: serde_json::Value = match serde_json::from_str(body) {
+        Ok(json) => json,
+        Err(_) if status >= 400 => {
+            return Err(format!("The assistant request failed (HTTP {})", status))
+        }
+        Err(e) => return Err(format!("Invalid assistant response: {}", e)),
+    };
+    // OpenAI: {"error": {"message": ...}}; Ollama: {"error": "..."}.
+    let error = &json["error"];
+    let message = error["message"].as_str().or_else(|| error.as_str());
+    if let Some(message) = message {
+        return Err(format!("The assistant request failed: {}", message));
+    }
+    if status >= 400 {
+        return Err(format!("The assistant request failed (HTTP {})", status));
+    }
+    let content = match provider {
+        Provider::OpenAi => &json["choices"][0]["message"]["content"],
+        Provider::Ollama => &json["message"]["content"],
+    };
+    content
+        .as_str()
+        .map(|s| s.trim().to_string())
+        .ok_or_else(|| "The assistant response has no reply".to_string())
+}
+
+/// Send `messages` and wait for the reply. Blocks; call it off the UI
+/// thread.
+pub fn chat(config: &AssistantConfig, messages: &[ChatMessage]) -> Result<String, String> {
+    let body = serde_json::to_string(&config.request_body(messages))
+        .map_err(|e| format!("Failed to serialize assistant request: {}", e))?;
+    let agent = ureq::Agent::config_builder()
+        .timeout_global(Some(Duration::from_secs(REQUEST_TIMEOUT_SECS)))
+        .http_status_as_error(false)
+        .build()
+        .new_agent();
+    let mut request = agent
+        .post(&config.chat_url())
+        .header("Content-Type", "application/json")
+        .header(
+            "User-Agent",
+            &format!("impulse/{}", crate::update::CURRENT_VERSION),
+        );
+    if let Some(key) = &config.api_key {
+        request = request.header("Authorization", &format!("Bearer {}", key));
+    }
+    let mut response = request
+        .send(body.as_str())
+        .map_err(|e| format!("The assistant request failed: {}", e))?;
+    let status = response.status().as_u16();
+    let text = response
+        .body_mut()
+        .read_to_string()
+        .map_err(|e| format!("Failed to read assistant response: {}", e))?;
+    parse_reply(config.provider, status, &text)
+}
+
+/// A conversation with the system prompt first, for a chat panel to send.
+pub fn with_system_prompt(messages: &[ChatMessage]) -> Vec<ChatMessage> {
+    let mut all = vec![ChatMessage::system(SYSTEM_PROMPT)];
+    all.extend(messages.iter().cloned());
+    all
+}
+
+/// The question that asks for an explanation of `code`, selected in the
+/// file at `path` (if saved) written in `language`.
+pub fn explain_selection_prompt(code: &str, language: &str, path: Option<&str>) -> String {
+    let file = path
+        .and_then(|p| std::path::Path::new(p).file_name())
+        .map(|name| format!(" from {}", name.to_string_lossy()))
+        .unwrap_or_default();
+    format!(
+        "Explain what this {language} code{file} does, step by step:\n\n```{language}\n{}\n```",
+        truncate(code.trim_end(), MAX_SELECTION_CHARS)
+    )
+}
+
+/// Messages asking for a commit message describing `diff`, following
+/// Conventional Commits when `conventional`.
+pub fn commit_message_messages(diff: &str, conventional: bool) -> Vec<ChatMessage> {
+    let mut rules = String::from(
+        "Write a git commit message for the diff below. Use an imperative subject \
+         line of at most 72 characters, then a blank line and a short body only \
+         if the change needs explaining.",
+    );
+    if conventional {
+        rules.push_str(
+            " Format the subject as a Conventional Commit: `type(scope): subject`, \
+             with type one of feat, fix, docs, style, refactor, perf, test, build, \
+             ci or chore.",
+        );
+    }
+    rules.push_str(" Reply with the commit message only.");
+    let mut diff_text = truncate(diff, MAX_DIFF_CHARS).to_string();
+    if diff_text.len() < diff.len() {
+        diff_text.push_str("\n[diff truncated]");
+    }
+    vec![
+        ChatMessage::system(rules),
+        ChatMessage::user(format!("```diff\n{}\n```", diff_text.trim_end())),
+    ]
+}
+
+/// A model's commit message reply without the code fence or quotes some
+/// models wrap it in.
+pub fn clean_commit_message(reply: &str) -> String {
+    let mut text = reply.trim();
+    if let Some(rest) = text.strip_prefix("```") {
+        // Skip the fence's language tag.
+        let rest = rest.split_once('\n').map_or("", |(_, body)| body);
+        text = rest.trim_end().strip_suffix("```").unwrap_or(rest).trim();
+    }
+    for quote in ['"', '\''] {
+        if text.len() >= 2 && text.starts_with(quote) && text.ends_with(quote) {
+            text = text[1..text.len() - 1].trim();
+        }
+    }
+    text.to_string()
+}
+
+/// Ask the assistant to explain `code`. Blocks.
+pub fn explain_selection(
+    settings: &Settings,
+    code: &str,
+    language: &str,
+    path: Option<&str>,
+) -> Result<String, String> {
+    if code.trim().is_empty() {
+        return Err("Nothing is selected".to_string());
+    }
+    let config = AssistantConfig::from_settings(settings)?;
+    let prompt = explain_selection_prompt(code, language, path);
+    chat(&config, &with_system_prompt(&[ChatMessage::user(prompt)]))
+}
+
+/// A commit message for the uncommitted changes in the repository at
+/// `repo_path`: the staged ones when anything is staged, otherwise all of
+/// them. Blocks.
+pub fn generate_commit_message(settings: &Settings, repo_path: &str) -> Result<String, String> {
+    let config = AssistantConfig::from_settings(settings)?;
+    let mut diff = crate::git::uncommitted_patch(repo_path, true)?;
+    if diff.is_empty() {
+        diff = crate::git::uncommitted_patch(repo_path, false)?;
+    }
+    if diff.is_empty() {
+        return Err("There are no changes to describe".to_string());
+    }
+    let messages = commit_message_messages(&diff, settings.conventional_commits);
+    Ok(clean_commit_message(&chat(&config, &messages)?))
+}
+
+/// The first `max` characters of `text`.
+fn truncate(text: &str, max: usize) -> &str {
+    match text.char_indices().nth(max) {
+        Some((end, _)) => &text[..end],
+        None => text,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// API keys
+// ---------------------------------------------------------------------------
+
+/// The API key stored for `provider`, if any.
+pub fn api_key(provider: Provider) -> Option<String> {
+    crate::secrets::get(SecretKind::AssistantKey, provider.id())
+}
+
+/// Store `key` for `provider` in the system keyring; an empty key removes
+/// the stored one.
+pub fn set_api_key(provider: Provider, key: &str) -> Result<(), String> {
+    crate::secrets::set(SecretKind::AssistantKey, provider.id(), key.trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_follows_the_settings_and_provider_defaults() {
+        let mut settings = Settings::default();
+        assert!(AssistantConfig::from_settings(&settings).is_err());
+
+        let config = AssistantConfig::without_key(&settings);
+        assert_eq!(config.provider, Provider::OpenAi);
+        assert_eq!(
+            config.chat_url(),
+            "https://api.openai.com/v1/chat/completions"
+        );
+
+        settings.assistant_provider = PROVIDER_OLLAMA.to_string();
+        settings.assistant_endpoint = "http://gpu-box:11434/".to_string();
+        settings.assistant_model = "qwen2.5-coder".to_string();
+        let config = AssistantConfig::without_key(&settings);
+        assert_eq!(config.chat_url(), "http://gpu-box:11434/api/chat");
+        let body = config.request_body(&[ChatMessage::user("hi")]);
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "model": "qwen2.5-coder",
+                "messages": [{"role": "user", "content": "hi"}],
+                "stream": false,
+            })
+        );
+
+        settings.assistant_provider = "nope".to_string();
+        settings.validate();
+        assert_eq!(settings.assistant_provider, PROVIDER_OPENAI);
+    }
+
+    #[test]
+    fn parses_replies_and_errors_from_both_apis() {
+        let openai = r#"{"choices":[{"message":{"role":"assistant","content":" Hi! "}}]}"#;
+        assert_eq!(parse_reply(Provider::OpenAi, 200, openai).unwrap(), "Hi!");
+        let ollama =
+            r#"{"model":"llama3.2","message":{"role":"assistant","content":"Hello"},"done":true}"#;
+        assert_eq!(parse_reply(Provider::Ollama, 200, ollama).unwrap(), "Hello");
+
+        let bad_key =
+            r#"{"error":{"message":"Incorrect API key provided","type":"invalid_request_error"}}"#;
+        assert_eq!(
+            parse_reply(Provider::OpenAi, 401, bad_key).unwrap_err(),
+            "The assistant request failed: Incorrect API key provided"
+        );
+        let no_model = r#"{"error":"model \"llama9\" not found"}"#;
+        assert!(parse_reply(Provider::Ollama, 404, no_model)
+            .unwrap_err()
+            .contains("llama9"));
+        assert_eq!(
+            parse_reply(Provider::OpenAi, 502, "<html>Bad gateway</html>").unwrap_err(),
+            "The assistant request failed (HTTP 502)"
+        );
+        assert!(parse_reply(Provider::OpenAi, 200, "{}").is_err());
+    }
+
+    #[test]
+    fn builds_prompts_and_cleans_commit_messages() {
+        let prompt = explain_selection_prompt("fn main() {}\n", "rust", Some("/src/main.rs"));
+        assert!(prompt.starts_with("Explain what this rust code from main.rs does"));
+        assert!(prompt.ends_with("```rust\nfn main() {}\n```"));
+
+        let messages = commit_message_messages("+a\n", true);
+        assert_eq!(messages[0].role, Role::System);
+        assert!(messages[0].content.contains("Conventional Commit"));
+        assert_eq!(messages[1].content, "```diff\n+a\n```");
+        let long = "+x\n".repeat(MAX_DIFF_CHARS);
+        assert!(commit_message_messages(&long, false)[1]
+            .content
+            .contains("[diff truncated]"));
+
+        assert_eq!(
+            clean_commit_message("```text\nFix the parser\n\nDetails.\n```"),
+            "Fix the parser\n\nDetails."
+        );
+        assert_eq!(clean_commit_message("\"Add tests\""), "Add tests");
+        assert_eq!(clean_commit_message("  Plain  \n"), "Plain");
+    }
+}
@@ -0,0 +1,157 @@
+//! `impulse [--new-window] [--wait] <path[:line[:column]]>...`: the paths a
+//! command line asks to open. Frontends hand the request to the instance
+//! that is already running, so a second launch opens tabs instead of a
+//! second app.
+
+use std::path::{Component, Path, PathBuf};
+
+use serde::Serialize;
+
+/// Open the targets in a new window even when `cli_open_in_new_window` is
+/// off.
+pub const NEW_WINDOW_FLAG: &str = "--new-window";
+
+/// Don't return until the opened files' tabs are closed, so Impulse can be
+/// `$EDITOR` or git's `core.editor`.
+pub const WAIT_FLAG: &str = "--wait";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct OpenTarget {
+    /// Absolute path.
+    pub path: String,
+    /// 1-based position from a `:line[:column]` suffix.
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+    pub is_dir: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct OpenRequest {
+    pub targets: Vec<OpenTarget>,
+    pub new_window: bool,
+    pub wait: bool,
+}
+
+/// Parse the arguments after the program name. Relative paths resolve
+/// against `cwd` (the launching shell's, not the running instance's).
+/// Other `--flags` are left for the frontend; `--` ends flag parsing.
+pub fn parse_args<S: AsRef<str>>(args: &[S], cwd: &Path) -> OpenRequest {
+    let mut request = OpenRequest::default();
+    let mut flags_done = false;
+    for arg in args {
+        let arg = arg.as_ref();
+        if !flags_done && arg.starts_with('-') {
+            match arg {
+                "--" => flags_done = true,
+                NEW_WINDOW_FLAG => request.new_window = true,
+                WAIT_FLAG => request.wait = true,
+                _ => {}
+            }
+            continue;
+        }
+        if !arg.is_empty() {
+            request.targets.push(parse_target(arg, cwd));
+        }
+    }
+    request
+}
+
+/// `src/main.rs:42:7` → `src/main.rs`, line 42, column 7. A path that
+/// exists as written is taken whole, so file names containing `:` still
+/// open.
+pub fn parse_target(arg: &str, cwd: &Path) -> OpenTarget {
+    let whole = resolve(arg, cwd);
+    if whole.exists() {
+        return target(whole, None, None);
+    }
+
+    let mut rest = arg;
+    let mut numbers = Vec::new();
+    while numbers.len() < 2 {
+        match rest.rsplit_once(':') {
+            Some((head, n))
+                if !head.is_empty() && !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()) =>
+            {
+                numbers.push(n.parse::<u32>().ok().filter(|&n| n > 0));
+                rest = head;
+            }
+            _ => break,
+        }
+    }
+    numbers.reverse();
+    let line = numbers.first().copied().flatten();
+    let column = numbers.get(1).copied().flatten();
+    target(resolve(rest, cwd), line, column)
+}
+
+fn target(path: PathBuf, line: Option<u32>, column: Option<u32>) -> OpenTarget {
+    OpenTarget {
+        is_dir: path.is_dir(),
+        path: path.to_string_lossy().to_string(),
+        line,
+        column,
+    }
+}
+
+/// Absolute form of `path` with `.` components dropped.
+fn resolve(path: &str, cwd: &Path) -> PathBuf {
+    let path = Path::new(path);
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        cwd.join(path)
+    };
+    absolute
+        .components()
+        .filter(|c| !matches!(c, Component::CurDir))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_flags_and_positions() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("main.rs"), "").unwrap();
+        let request = parse_args(
+            &[
+                "--wait",
+                "./main.rs:42",
+                "main.rs:3:7",
+                "--dev",
+                "--",
+                "--new-window",
+            ],
+            dir.path(),
+        );
+        assert!(request.wait);
+        assert!(!request.new_window);
+
+        let main = dir.path().join("main.rs").to_string_lossy().to_string();
+        assert_eq!(request.targets.len(), 3);
+        assert_eq!(request.targets[0].path, main);
+        assert_eq!(request.targets[0].line, Some(42));
+        assert_eq!(request.targets[0].column, None);
+        assert_eq!(
+            (request.targets[1].line, request.targets[1].column),
+            (Some(3), Some(7))
+        );
+        // After `--`, a flag-looking argument is a path.
+        assert!(request.targets[2].path.ends_with("--new-window"));
+    }
+
+    #[test]
+    fn existing_paths_are_taken_whole() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("notes:12"), "").unwrap();
+        let target = parse_target("notes:12", dir.path());
+        assert!(target.path.ends_with("notes:12"));
+        assert_eq!(target.line, None);
+
+        let target = parse_target(".", dir.path());
+        assert!(target.is_dir);
+        assert_eq!(target.path, dir.path().to_string_lossy());
+    }
+}
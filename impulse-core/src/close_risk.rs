@@ -23,10 +23,20 @@ pub struct CloseRiskInput {
     pub action: CloseRiskAction,
     #[serde(default)]
     pub unsaved_editor_count: usize,
+    /// Paths (or tab titles for untitled buffers) of the modified editors.
+    /// When given, the summary lists them and `unsaved_editor_count` is
+    /// raised to match.
+    #[serde(default)]
+    pub unsaved_files: Vec<String>,
     #[serde(default)]
     pub running_terminal_process_count: usize,
     #[serde(default)]
     pub running_commands: Vec<RunningCommandRisk>,
+    /// Programs whose running commands don't count (the
+    /// `terminal_close_ignored_commands` setting); see
+    /// [`is_ignored_command`].
+    #[serde(default)]
+    pub ignored_commands: Vec<String>,
     #[serde(default)]
     pub now_ms: u64,
     #[serde(default = "default_long_command_threshold_seconds")]
@@ -42,6 +52,15 @@ pub struct CloseRiskCommandSummary {
     pub is_long_running: bool,
 }
 
+/// One modified editor, with the name to show for it in a close prompt.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct UnsavedFileSummary {
+    pub path: String,
+    /// The file name, followed by its parent directory when another
+    /// unsaved file has the same name.
+    pub display_name: String,
+}
+
 /// Summary used by frontends to decide whether to prompt and what to show.
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 pub struct CloseRiskSummary {
@@ -56,6 +75,8 @@ pub struct CloseRiskSummary {
     pub running_command_count: usize,
     pub long_running_command_count: usize,
     pub commands: Vec<CloseRiskCommandSummary>,
+    #[serde(default)]
+    pub unsaved_files: Vec<UnsavedFileSummary>,
 }
 
 impl CloseRiskInput {
@@ -65,6 +86,11 @@ impl CloseRiskInput {
 }
 
 pub fn summarize_close_risk(input: &CloseRiskInput) -> CloseRiskSummary {
+    let unsaved_files = summarize_unsaved_files(&input.unsaved_files);
+    let input = &CloseRiskInput {
+        unsaved_editor_count: input.unsaved_editor_count.max(unsaved_files.len()),
+        ..input.clone()
+    };
     let threshold = input.long_command_threshold_seconds.max(1);
     let commands = summarize_commands(input, threshold);
     let running_command_count = commands.len();
@@ -96,12 +122,13 @@ pub fn summarize_close_risk(input: &CloseRiskInput) -> CloseRiskSummary {
             running_command_count: 0,
             long_running_command_count: 0,
             commands,
+            unsaved_files,
         };
     }
 
     let title = close_title(input);
     let informative_text = close_informative_text(input, running_command_count);
-    let detail_lines = close_detail_lines(input, &commands);
+    let detail_lines = close_detail_lines(input, &unsaved_files, &commands);
 
     CloseRiskSummary {
         has_risk,
@@ -115,6 +142,7 @@ pub fn summarize_close_risk(input: &CloseRiskInput) -> CloseRiskSummary {
         running_command_count,
         long_running_command_count,
         commands,
+        unsaved_files,
     }
 }
 
@@ -122,10 +150,81 @@ fn default_long_command_threshold_seconds() -> u64 {
     30
 }
 
+/// The default `terminal_close_ignored_commands`: pagers and viewers that
+/// have nothing to lose when their terminal closes.
+pub const DEFAULT_IGNORED_COMMANDS: &[&str] = &["less", "more", "man", "top", "htop"];
+
+/// Whether `command` (a command line or a process name) runs one of the
+/// `ignored` programs. Programs match by file name, after any leading
+/// `NAME=value` assignments, so `PAGER=cat /usr/bin/less log` is `less`.
+pub fn is_ignored_command(command: &str, ignored: &[String]) -> bool {
+    let Some(program) = command.split_whitespace().find(|word| !word.contains('=')) else {
+        return false;
+    };
+    let program = program.rsplit('/').next().unwrap_or(program);
+    ignored.iter().any(|name| name.trim() == program)
+}
+
+fn summarize_unsaved_files(paths: &[String]) -> Vec<UnsavedFileSummary> {
+    // Components from the file name upwards, e.g. ["lib.rs", "src", "core"].
+    let components = |path: &str| -> Vec<String> {
+        std::path::Path::new(path)
+            .iter()
+            .rev()
+            .map(|part| part.to_string_lossy().to_string())
+            .collect()
+    };
+    let split: Vec<Vec<String>> = paths.iter().map(|path| components(path)).collect();
+    paths
+        .iter()
+        .zip(&split)
+        .map(|(path, parts)| {
+            let Some(name) = parts.first() else {
+                return UnsavedFileSummary {
+                    path: path.clone(),
+                    display_name: path.clone(),
+                };
+            };
+            let namesakes: Vec<&Vec<String>> = split
+                .iter()
+                .filter(|other| !std::ptr::eq(*other, parts) && other.first() == Some(name))
+                .collect();
+            // Show as many parent directories as it takes to tell this file
+            // apart from the others with the same name.
+            let mut depth = usize::from(!namesakes.is_empty() && parts.len() > 1);
+            while depth > 0
+                && depth + 1 < parts.len()
+                && namesakes
+                    .iter()
+                    .any(|other| other.get(1..=depth) == parts.get(1..=depth))
+            {
+                depth += 1;
+            }
+            let display_name = if depth == 0 {
+                name.clone()
+            } else {
+                let parents: Vec<&str> =
+                    parts[1..=depth].iter().rev().map(String::as_str).collect();
+                format!("{name} \u{2014} {}", parents.join("/"))
+            };
+            UnsavedFileSummary {
+                path: path.clone(),
+                display_name,
+            }
+        })
+        .collect()
+}
+
 fn summarize_commands(input: &CloseRiskInput, threshold: u64) -> Vec<CloseRiskCommandSummary> {
     input
         .running_commands
         .iter()
+        .filter(|command| {
+            !command
+                .command
+                .as_deref()
+                .is_some_and(|c| is_ignored_command(c, &input.ignored_commands))
+        })
         .map(|command| {
             let duration_seconds = input.now_ms.saturating_sub(command.started_at_ms) / 1000;
             CloseRiskCommandSummary {
@@ -187,9 +286,20 @@ fn close_informative_text(input: &CloseRiskInput, running_command_count: usize)
     sentences.join(" ")
 }
 
-fn close_detail_lines(input: &CloseRiskInput, commands: &[CloseRiskCommandSummary]) -> Vec<String> {
+fn close_detail_lines(
+    input: &CloseRiskInput,
+    unsaved_files: &[UnsavedFileSummary],
+    commands: &[CloseRiskCommandSummary],
+) -> Vec<String> {
     let mut lines = Vec::new();
-    if input.unsaved_editor_count > 0 {
+    if !unsaved_files.is_empty() {
+        for file in unsaved_files.iter().take(5) {
+            lines.push(file.display_name.clone());
+        }
+        if unsaved_files.len() > 5 {
+            lines.push(format!("{} more unsaved files", unsaved_files.len() - 5));
+        }
+    } else if input.unsaved_editor_count > 0 {
         lines.push(plural_line(input.unsaved_editor_count, "unsaved editor"));
     }
     if input.running_terminal_process_count > 0 {
@@ -260,8 +370,10 @@ mod tests {
         let summary = summarize_close_risk(&CloseRiskInput {
             action: CloseRiskAction::Quit,
             unsaved_editor_count: 0,
+            unsaved_files: Vec::new(),
             running_terminal_process_count: 0,
             running_commands: Vec::new(),
+            ignored_commands: Vec::new(),
             now_ms: 10_000,
             long_command_threshold_seconds: 30,
         });
@@ -276,12 +388,14 @@ mod tests {
         let summary = summarize_close_risk(&CloseRiskInput {
             action: CloseRiskAction::Quit,
             unsaved_editor_count: 0,
+            unsaved_files: Vec::new(),
             running_terminal_process_count: 1,
             running_commands: vec![RunningCommandRisk {
                 command: Some("cargo test -p impulse-core".to_string()),
                 cwd: Some("/tmp/project".to_string()),
                 started_at_ms: 1_000,
             }],
+            ignored_commands: Vec::new(),
             now_ms: 66_000,
             long_command_threshold_seconds: 30,
         });
@@ -304,8 +418,10 @@ mod tests {
         let summary = summarize_close_risk(&CloseRiskInput {
             action: CloseRiskAction::CloseWindow,
             unsaved_editor_count: 2,
+            unsaved_files: Vec::new(),
             running_terminal_process_count: 3,
             running_commands: Vec::new(),
+            ignored_commands: Vec::new(),
             now_ms: 0,
             long_command_threshold_seconds: 30,
         });
@@ -329,12 +445,14 @@ mod tests {
         let summary = summarize_close_risk(&CloseRiskInput {
             action: CloseRiskAction::CloseTab,
             unsaved_editor_count: 0,
+            unsaved_files: Vec::new(),
             running_terminal_process_count: 1,
             running_commands: vec![RunningCommandRisk {
                 command: Some("lazygit".to_string()),
                 cwd: None,
                 started_at_ms: 0,
             }],
+            ignored_commands: Vec::new(),
             now_ms: 31_000,
             long_command_threshold_seconds: 30,
         });
@@ -350,12 +468,14 @@ mod tests {
         let summary = summarize_close_risk(&CloseRiskInput {
             action: CloseRiskAction::CloseWindow,
             unsaved_editor_count: 0,
+            unsaved_files: Vec::new(),
             running_terminal_process_count: 0,
             running_commands: vec![RunningCommandRisk {
                 command: Some("  ".to_string()),
                 cwd: None,
                 started_at_ms: 12_000,
             }],
+            ignored_commands: Vec::new(),
             now_ms: 20_000,
             long_command_threshold_seconds: 30,
         });
@@ -364,4 +484,77 @@ mod tests {
         assert_eq!(summary.commands[0].duration_seconds, 8);
         assert!(!summary.commands[0].is_long_running);
     }
+
+    #[test]
+    fn lists_unsaved_files_and_disambiguates_duplicate_names() {
+        let summary = summarize_close_risk(&CloseRiskInput {
+            action: CloseRiskAction::CloseWindow,
+            unsaved_editor_count: 0,
+            unsaved_files: vec![
+                "/p/core/src/lib.rs".to_string(),
+                "/p/linux/src/lib.rs".to_string(),
+                "/p/README.md".to_string(),
+            ],
+            running_terminal_process_count: 0,
+            running_commands: Vec::new(),
+            ignored_commands: Vec::new(),
+            now_ms: 0,
+            long_command_threshold_seconds: 30,
+        });
+
+        assert!(summary.has_risk);
+        assert_eq!(summary.unsaved_editor_count, 3);
+        assert_eq!(summary.unsaved_files[0].path, "/p/core/src/lib.rs");
+        assert_eq!(
+            summary.detail_lines,
+            vec![
+                "lib.rs \u{2014} core/src",
+                "lib.rs \u{2014} linux/src",
+                "README.md"
+            ]
+        );
+    }
+
+    #[test]
+    fn ignored_programs_do_not_count_as_running_work() {
+        let ignored: Vec<String> = DEFAULT_IGNORED_COMMANDS
+            .iter()
+            .map(|c| c.to_string())
+            .collect();
+        assert!(is_ignored_command("less -R build.log", &ignored));
+        assert!(is_ignored_command("LESS=-R /usr/bin/less log", &ignored));
+        assert!(!is_ignored_command("npm run dev", &ignored));
+        assert!(!is_ignored_command("lesskey", &ignored));
+        assert!(!is_ignored_command("  ", &ignored));
+
+        let command = |c: &str| RunningCommandRisk {
+            command: Some(c.to_string()),
+            cwd: None,
+            started_at_ms: 0,
+        };
+        let summary = summarize_close_risk(&CloseRiskInput {
+            action: CloseRiskAction::CloseTab,
+            unsaved_editor_count: 0,
+            unsaved_files: Vec::new(),
+            running_terminal_process_count: 0,
+            running_commands: vec![command("man git"), command("cargo watch")],
+            ignored_commands: ignored.clone(),
+            now_ms: 0,
+            long_command_threshold_seconds: 30,
+        });
+        assert_eq!(summary.running_command_count, 1);
+        assert_eq!(summary.commands[0].command, "cargo watch");
+
+        let summary = summarize_close_risk(&CloseRiskInput {
+            action: CloseRiskAction::CloseTab,
+            unsaved_editor_count: 0,
+            unsaved_files: Vec::new(),
+            running_terminal_process_count: 0,
+            running_commands: vec![command("top")],
+            ignored_commands: ignored,
+            now_ms: 0,
+            long_command_threshold_seconds: 30,
+        });
+        assert!(!summary.has_risk);
+    }
 }
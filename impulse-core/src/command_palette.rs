@@ -38,177 +38,12 @@ pub struct RecentCommandStore {
     pub items: Vec<RecentCommandItem>,
 }
 
-#[derive(Clone, Copy, Debug)]
-struct BuiltinCommand {
-    id: &'static str,
-    title: &'static str,
-    category: &'static str,
-    keywords: &'static [&'static str],
-}
-
-const BUILTIN_COMMANDS: &[BuiltinCommand] = &[
-    BuiltinCommand {
-        id: "new_tab",
-        title: "New Terminal Tab",
-        category: "Tabs",
-        keywords: &["terminal", "shell"],
-    },
-    BuiltinCommand {
-        id: "close_tab",
-        title: "Close Tab",
-        category: "Tabs",
-        keywords: &["remove"],
-    },
-    BuiltinCommand {
-        id: "reopen_tab",
-        title: "Reopen Closed Tab",
-        category: "Tabs",
-        keywords: &["restore", "undo"],
-    },
-    BuiltinCommand {
-        id: "next_tab",
-        title: "Next Tab",
-        category: "Tabs",
-        keywords: &["navigate"],
-    },
-    BuiltinCommand {
-        id: "prev_tab",
-        title: "Previous Tab",
-        category: "Tabs",
-        keywords: &["navigate"],
-    },
-    BuiltinCommand {
-        id: "copy",
-        title: "Copy",
-        category: "Terminal",
-        keywords: &["clipboard"],
-    },
-    BuiltinCommand {
-        id: "paste",
-        title: "Paste",
-        category: "Terminal",
-        keywords: &["clipboard"],
-    },
-    BuiltinCommand {
-        id: "review_changes",
-        title: "Review Changes",
-        category: "Navigation",
-        keywords: &["git", "diff", "commit", "review"],
-    },
-    BuiltinCommand {
-        id: "new_file",
-        title: "New File",
-        category: "Editor",
-        keywords: &["editor"],
-    },
-    BuiltinCommand {
-        id: "save",
-        title: "Save File",
-        category: "Editor",
-        keywords: &["write"],
-    },
-    BuiltinCommand {
-        id: "find",
-        title: "Find",
-        category: "Editor",
-        keywords: &["search"],
-    },
-    BuiltinCommand {
-        id: "go_to_line",
-        title: "Go to Line",
-        category: "Editor",
-        keywords: &["jump", "navigate"],
-    },
-    BuiltinCommand {
-        id: "toggle_markdown_preview",
-        title: "Toggle Preview",
-        category: "Editor",
-        keywords: &["markdown", "preview"],
-    },
-    BuiltinCommand {
-        id: "toggle_sidebar",
-        title: "Toggle Sidebar",
-        category: "Navigation",
-        keywords: &["files"],
-    },
-    BuiltinCommand {
-        id: "quick_open",
-        title: "Quick Open File",
-        category: "Navigation",
-        keywords: &["file", "finder"],
-    },
-    BuiltinCommand {
-        id: "project_search",
-        title: "Find in Project",
-        category: "Navigation",
-        keywords: &["search", "files"],
-    },
-    BuiltinCommand {
-        id: "command_palette",
-        title: "Command Palette",
-        category: "Navigation",
-        keywords: &["commands"],
-    },
-    BuiltinCommand {
-        id: "open_settings",
-        title: "Open Settings",
-        category: "Navigation",
-        keywords: &["preferences"],
-    },
-    BuiltinCommand {
-        id: "font_increase",
-        title: "Increase Font Size",
-        category: "Font",
-        keywords: &["zoom"],
-    },
-    BuiltinCommand {
-        id: "font_decrease",
-        title: "Decrease Font Size",
-        category: "Font",
-        keywords: &["zoom"],
-    },
-    BuiltinCommand {
-        id: "font_reset",
-        title: "Reset Font Size",
-        category: "Font",
-        keywords: &["zoom"],
-    },
-    BuiltinCommand {
-        id: "new_window",
-        title: "New Window",
-        category: "App",
-        keywords: &["window"],
-    },
-    BuiltinCommand {
-        id: "fullscreen",
-        title: "Toggle Fullscreen",
-        category: "App",
-        keywords: &["window"],
-    },
-    BuiltinCommand {
-        id: "install_lsp",
-        title: "Install Web LSP Servers",
-        category: "Language Servers",
-        keywords: &["typescript", "php", "html", "css"],
-    },
-];
-
+/// The built-in commands as palette items, without shortcuts. See
+/// [`crate::commands::CommandRegistry`] for the shortcuts and enablement.
 pub fn builtin_items() -> Vec<CommandPaletteItem> {
-    BUILTIN_COMMANDS
+    crate::commands::COMMANDS
         .iter()
-        .map(|command| CommandPaletteItem {
-            id: command.id.to_string(),
-            title: command.title.to_string(),
-            category: command.category.to_string(),
-            keywords: command
-                .keywords
-                .iter()
-                .map(|keyword| (*keyword).to_string())
-                .collect(),
-            source: CommandPaletteSource::Builtin,
-            shortcut: None,
-            payload: BTreeMap::new(),
-        })
+        .map(crate::commands::Command::palette_item)
         .collect()
 }
 
@@ -550,6 +385,10 @@ mod tests {
             line_content: None,
             column_start: None,
             column_end: None,
+            match_start: None,
+            match_end: None,
+            context_before: Vec::new(),
+            context_after: Vec::new(),
             match_type: "file".to_string(),
         }];
 
@@ -578,6 +417,10 @@ mod tests {
             line_content: Some("pub fn search_items()".to_string()),
             column_start: Some(7),
             column_end: Some(19),
+            match_start: Some(7),
+            match_end: Some(19),
+            context_before: Vec::new(),
+            context_after: Vec::new(),
             match_type: "content".to_string(),
         }];
 
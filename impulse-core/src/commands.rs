@@ -0,0 +1,835 @@
+//! The command registry: every built-in command with its title, palette
+//! category, default shortcut on each platform and the tabs it applies to.
+//! The command palette, menus, keybinding tables and `impulse_execute_command`
+//! all read it, so both frontends expose the same commands under the same
+//! IDs. Frontends supply the handlers.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::command_palette::{custom_command_item, CommandPaletteItem, CommandPaletteSource};
+use crate::settings::{CustomKeybinding, Settings};
+
+/// When a command applies.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum When {
+    Always,
+    /// The active tab is a text editor.
+    Editor,
+    /// The active tab is a terminal.
+    Terminal,
+    /// The active tab shows a file on disk (an editor or image preview).
+    File,
+    /// The assistant is turned on.
+    Assistant,
+    /// The assistant is turned on and the active tab is a text editor.
+    AssistantEditor,
+    /// Agents may connect over MCP.
+    AgentAccess,
+}
+
+/// What the frontend has focused, for [`When`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CommandContext {
+    pub editor: bool,
+    pub terminal: bool,
+    pub file: bool,
+    /// The `assistant_enabled` setting is on and the frontend has an
+    /// assistant UI.
+    pub assistant: bool,
+    /// The `agent_access_enabled` setting is on and the frontend can share
+    /// workspaces with agents.
+    pub agent_access: bool,
+}
+
+impl When {
+    pub fn matches(self, context: &CommandContext) -> bool {
+        match self {
+            When::Always => true,
+            When::Editor => context.editor,
+            When::Terminal => context.terminal,
+            When::File => context.file,
+            When::Assistant => context.assistant,
+            When::AssistantEditor => context.assistant && context.editor,
+            When::AgentAccess => context.agent_access,
+        }
+    }
+}
+
+/// A built-in command. Shortcuts use the `"Ctrl+Shift+P"` form of
+/// `keybinding_overrides`; an empty string means no default shortcut.
+#[derive(Clone, Copy, Debug)]
+pub struct Command {
+    pub id: &'static str,
+    pub title: &'static str,
+    pub category: &'static str,
+    pub keywords: &'static [&'static str],
+    pub linux_shortcut: &'static str,
+    pub macos_shortcut: &'static str,
+    pub when: When,
+}
+
+impl Command {
+    /// The default shortcut on this platform.
+    pub fn default_shortcut(&self) -> Option<&'static str> {
+        let shortcut = if cfg!(target_os = "macos") {
+            self.macos_shortcut
+        } else {
+            self.linux_shortcut
+        };
+        Some(shortcut).filter(|s| !s.is_empty())
+    }
+
+    pub fn palette_item(&self) -> CommandPaletteItem {
+        CommandPaletteItem {
+            id: self.id.to_string(),
+            title: self.title.to_string(),
+            category: self.category.to_string(),
+            keywords: self.keywords.iter().map(|k| (*k).to_string()).collect(),
+            source: CommandPaletteSource::Builtin,
+            shortcut: None,
+            payload: Default::default(),
+        }
+    }
+}
+
+pub const COMMANDS: &[Command] = &[
+    Command {
+        id: "new_tab",
+        title: "New Terminal Tab",
+        category: "Tabs",
+        keywords: &["terminal", "shell"],
+        linux_shortcut: "Ctrl+T",
+        macos_shortcut: "Cmd+T",
+        when: When::Always,
+    },
+    Command {
+        id: "close_tab",
+        title: "Close Tab",
+        category: "Tabs",
+        keywords: &["remove"],
+        linux_shortcut: "Ctrl+W",
+        macos_shortcut: "Cmd+W",
+        when: When::Always,
+    },
+    Command {
+        id: "reopen_tab",
+        title: "Reopen Closed Tab",
+        category: "Tabs",
+        keywords: &["restore", "undo"],
+        linux_shortcut: "Ctrl+Shift+T",
+        macos_shortcut: "Cmd+Shift+T",
+        when: When::Always,
+    },
+    Command {
+        id: "next_tab",
+        title: "Next Tab",
+        category: "Tabs",
+        keywords: &["navigate"],
+        linux_shortcut: "Ctrl+Tab",
+        macos_shortcut: "Ctrl+Tab",
+        when: When::Always,
+    },
+    Command {
+        id: "prev_tab",
+        title: "Previous Tab",
+        category: "Tabs",
+        keywords: &["navigate"],
+        linux_shortcut: "Ctrl+Shift+Tab",
+        macos_shortcut: "Ctrl+Shift+Tab",
+        when: When::Always,
+    },
+    Command {
+        id: "show_all_tabs",
+        title: "Show All Tabs",
+        category: "Tabs",
+        keywords: &["list", "overflow", "switch", "search"],
+        linux_shortcut: "",
+        macos_shortcut: "",
+        when: When::Always,
+    },
+    Command {
+        id: "sort_tabs_by_path",
+        title: "Sort Tabs by Path",
+        category: "Tabs",
+        keywords: &["order", "arrange"],
+        linux_shortcut: "",
+        macos_shortcut: "",
+        when: When::Always,
+    },
+    Command {
+        id: "sort_tabs_by_recent_use",
+        title: "Sort Tabs by Recent Use",
+        category: "Tabs",
+        keywords: &["order", "arrange", "mru"],
+        linux_shortcut: "",
+        macos_shortcut: "",
+        when: When::Always,
+    },
+    Command {
+        id: "close_saved_tabs",
+        title: "Close Saved Tabs",
+        category: "Tabs",
+        keywords: &["unmodified", "clean"],
+        linux_shortcut: "",
+        macos_shortcut: "",
+        when: When::Always,
+    },
+    Command {
+        id: "close_tabs_to_right",
+        title: "Close Tabs to the Right",
+        category: "Tabs",
+        keywords: &["remove"],
+        linux_shortcut: "",
+        macos_shortcut: "",
+        when: When::Always,
+    },
+    Command {
+        id: "close_tabs_to_left",
+        title: "Close Tabs to the Left",
+        category: "Tabs",
+        keywords: &["remove"],
+        linux_shortcut: "",
+        macos_shortcut: "",
+        when: When::Always,
+    },
+    Command {
+        id: "copy",
+        title: "Copy",
+        category: "Terminal",
+        keywords: &["clipboard"],
+        linux_shortcut: "Ctrl+Shift+C",
+        macos_shortcut: "Cmd+C",
+        when: When::Always,
+    },
+    Command {
+        id: "paste",
+        title: "Paste",
+        category: "Terminal",
+        keywords: &["clipboard"],
+        linux_shortcut: "Ctrl+Shift+V",
+        macos_shortcut: "Cmd+V",
+        when: When::Always,
+    },
+    Command {
+        id: "open_scrollback_in_editor",
+        title: "Open Scrollback in Editor",
+        category: "Terminal",
+        keywords: &["output", "history", "buffer", "search", "copy"],
+        linux_shortcut: "",
+        macos_shortcut: "",
+        when: When::Terminal,
+    },
+    Command {
+        id: "review_changes",
+        title: "Review Changes",
+        category: "Navigation",
+        keywords: &["git", "diff", "commit", "review"],
+        linux_shortcut: "Ctrl+Shift+G",
+        macos_shortcut: "Cmd+Shift+G",
+        when: When::Always,
+    },
+    Command {
+        id: "new_file",
+        title: "New File",
+        category: "Editor",
+        keywords: &["editor"],
+        linux_shortcut: "Ctrl+N",
+        macos_shortcut: "Cmd+N",
+        when: When::Always,
+    },
+    Command {
+        id: "open_remote_file",
+        title: "Open Remote File…",
+        category: "Editor",
+        keywords: &["sftp", "ssh", "server", "uri", "url"],
+        linux_shortcut: "",
+        macos_shortcut: "",
+        when: When::Always,
+    },
+    Command {
+        id: "save",
+        title: "Save File",
+        category: "Editor",
+        keywords: &["write"],
+        linux_shortcut: "Ctrl+S",
+        macos_shortcut: "Cmd+S",
+        when: When::Editor,
+    },
+    Command {
+        id: "save_all",
+        title: "Save All",
+        category: "Editor",
+        keywords: &["write", "modified", "unsaved"],
+        linux_shortcut: "",
+        macos_shortcut: "Cmd+Alt+S",
+        when: When::Always,
+    },
+    Command {
+        id: "local_history",
+        title: "Local History…",
+        category: "Editor",
+        keywords: &["timeline", "versions", "restore", "revert", "saved"],
+        linux_shortcut: "",
+        macos_shortcut: "",
+        when: When::Editor,
+    },
+    Command {
+        id: "compare_with_saved",
+        title: "Compare Active File with Saved",
+        category: "Editor",
+        keywords: &["diff", "unsaved", "changes", "disk"],
+        linux_shortcut: "",
+        macos_shortcut: "",
+        when: When::Editor,
+    },
+    Command {
+        id: "revert_file",
+        title: "Revert File",
+        category: "Editor",
+        keywords: &["discard", "unsaved", "reload", "disk"],
+        linux_shortcut: "",
+        macos_shortcut: "",
+        when: When::Editor,
+    },
+    Command {
+        id: "find",
+        title: "Find",
+        category: "Editor",
+        keywords: &["search"],
+        linux_shortcut: "Ctrl+F",
+        macos_shortcut: "Cmd+F",
+        when: When::Always,
+    },
+    Command {
+        id: "go_to_line",
+        title: "Go to Line",
+        category: "Editor",
+        keywords: &["jump", "navigate"],
+        linux_shortcut: "Ctrl+G",
+        macos_shortcut: "Cmd+G",
+        when: When::Editor,
+    },
+    Command {
+        id: "toggle_read_only",
+        title: "Toggle Read-Only",
+        category: "Editor",
+        keywords: &["lock", "unlock", "readonly"],
+        linux_shortcut: "",
+        macos_shortcut: "",
+        when: When::Editor,
+    },
+    Command {
+        id: "open_containing_folder",
+        title: "Open Containing Folder",
+        category: "Editor",
+        keywords: &["reveal", "file manager", "finder", "show"],
+        linux_shortcut: "",
+        macos_shortcut: "",
+        when: When::File,
+    },
+    Command {
+        id: "copy_path",
+        title: "Copy Path",
+        category: "Editor",
+        keywords: &["absolute", "clipboard", "file path"],
+        linux_shortcut: "",
+        macos_shortcut: "",
+        when: When::File,
+    },
+    Command {
+        id: "copy_relative_path",
+        title: "Copy Relative Path",
+        category: "Editor",
+        keywords: &["workspace", "clipboard", "file path"],
+        linux_shortcut: "",
+        macos_shortcut: "",
+        when: When::File,
+    },
+    Command {
+        id: "copy_file_name",
+        title: "Copy File Name",
+        category: "Editor",
+        keywords: &["filename", "basename", "clipboard"],
+        linux_shortcut: "",
+        macos_shortcut: "",
+        when: When::File,
+    },
+    Command {
+        id: "copy_file_url",
+        title: "Copy File URL",
+        category: "Editor",
+        keywords: &["uri", "file://", "link", "clipboard"],
+        linux_shortcut: "",
+        macos_shortcut: "",
+        when: When::File,
+    },
+    Command {
+        id: "reveal_in_sidebar",
+        title: "Reveal in Sidebar",
+        category: "Editor",
+        keywords: &["file tree", "explorer", "locate", "show", "select"],
+        linux_shortcut: "",
+        macos_shortcut: "",
+        when: When::File,
+    },
+    Command {
+        id: "toggle_markdown_preview",
+        title: "Toggle Preview",
+        category: "Editor",
+        keywords: &["markdown", "preview"],
+        linux_shortcut: "Ctrl+Shift+M",
+        macos_shortcut: "Cmd+Shift+M",
+        when: When::Editor,
+    },
+    Command {
+        id: "toggle_sidebar",
+        title: "Toggle Sidebar",
+        category: "Navigation",
+        keywords: &["files"],
+        linux_shortcut: "Ctrl+Shift+B",
+        macos_shortcut: "Cmd+Shift+B",
+        when: When::Always,
+    },
+    Command {
+        id: "toggle_zen_mode",
+        title: "Toggle Zen Mode",
+        category: "App",
+        keywords: &["distraction", "focus", "layout"],
+        linux_shortcut: "Ctrl+Alt+Z",
+        macos_shortcut: "Ctrl+Cmd+Z",
+        when: When::Always,
+    },
+    Command {
+        id: "toggle_tab_bar",
+        title: "Toggle Tab Bar",
+        category: "App",
+        keywords: &["tabs", "layout"],
+        linux_shortcut: "",
+        macos_shortcut: "",
+        when: When::Always,
+    },
+    Command {
+        id: "toggle_status_bar",
+        title: "Toggle Status Bar",
+        category: "App",
+        keywords: &["layout"],
+        linux_shortcut: "",
+        macos_shortcut: "",
+        when: When::Always,
+    },
+    Command {
+        id: "toggle_centered_layout",
+        title: "Toggle Centered Layout",
+        category: "App",
+        keywords: &["zen", "width", "layout"],
+        linux_shortcut: "",
+        macos_shortcut: "",
+        when: When::Always,
+    },
+    Command {
+        id: "quick_open",
+        title: "Quick Open File",
+        category: "Navigation",
+        keywords: &["file", "finder"],
+        linux_shortcut: "",
+        macos_shortcut: "",
+        when: When::Always,
+    },
+    Command {
+        id: "project_search",
+        title: "Find in Project",
+        category: "Navigation",
+        keywords: &["search", "files"],
+        linux_shortcut: "Ctrl+Shift+F",
+        macos_shortcut: "Cmd+Shift+F",
+        when: When::Always,
+    },
+    Command {
+        id: "command_palette",
+        title: "Command Palette",
+        category: "Navigation",
+        keywords: &["commands"],
+        linux_shortcut: "Ctrl+Shift+P",
+        macos_shortcut: "Cmd+Shift+P",
+        when: When::Always,
+    },
+    Command {
+        id: "open_settings",
+        title: "Open Settings",
+        category: "Navigation",
+        keywords: &["preferences"],
+        linux_shortcut: "Ctrl+comma",
+        macos_shortcut: "Cmd+,",
+        when: When::Always,
+    },
+    Command {
+        id: "font_increase",
+        title: "Zoom In",
+        category: "Font",
+        keywords: &["font size", "bigger"],
+        linux_shortcut: "Ctrl+equal",
+        macos_shortcut: "Cmd+=",
+        when: When::Always,
+    },
+    Command {
+        id: "font_decrease",
+        title: "Zoom Out",
+        category: "Font",
+        keywords: &["font size", "smaller"],
+        linux_shortcut: "Ctrl+minus",
+        macos_shortcut: "Cmd+-",
+        when: When::Always,
+    },
+    Command {
+        id: "font_reset",
+        title: "Reset Zoom",
+        category: "Font",
+        keywords: &["font size", "default"],
+        linux_shortcut: "Ctrl+0",
+        macos_shortcut: "Cmd+0",
+        when: When::Always,
+    },
+    Command {
+        id: "new_window",
+        title: "New Window",
+        category: "App",
+        keywords: &["window"],
+        linux_shortcut: "Ctrl+Shift+N",
+        macos_shortcut: "Cmd+Shift+N",
+        when: When::Always,
+    },
+    Command {
+        id: "save_window_layout",
+        title: "Save Window Layout As…",
+        category: "App",
+        keywords: &["session", "workspace", "profile", "project"],
+        linux_shortcut: "",
+        macos_shortcut: "",
+        when: When::Always,
+    },
+    Command {
+        id: "open_window_layout",
+        title: "Open Layout",
+        category: "App",
+        keywords: &["session", "workspace", "profile", "project", "restore"],
+        linux_shortcut: "",
+        macos_shortcut: "",
+        when: When::Always,
+    },
+    Command {
+        id: "fullscreen",
+        title: "Toggle Fullscreen",
+        category: "App",
+        keywords: &["window"],
+        linux_shortcut: "F11",
+        macos_shortcut: "Ctrl+Cmd+F",
+        when: When::Always,
+    },
+    Command {
+        id: "check_for_updates",
+        title: "Check for Updates",
+        category: "App",
+        keywords: &["update", "upgrade", "version", "release"],
+        linux_shortcut: "",
+        macos_shortcut: "",
+        when: When::Always,
+    },
+    Command {
+        id: "manage_workspace_trust",
+        title: "Manage Workspace Trust",
+        category: "App",
+        keywords: &["trust", "restricted", "security", "folder"],
+        linux_shortcut: "",
+        macos_shortcut: "",
+        when: When::Always,
+    },
+    Command {
+        id: "show_notifications",
+        title: "Show Notifications",
+        category: "App",
+        keywords: &["bell", "errors", "messages", "history"],
+        linux_shortcut: "",
+        macos_shortcut: "",
+        when: When::Always,
+    },
+    Command {
+        id: "export_settings",
+        title: "Export Settings",
+        category: "App",
+        keywords: &["backup", "archive", "keybindings", "themes"],
+        linux_shortcut: "",
+        macos_shortcut: "",
+        when: When::Always,
+    },
+    Command {
+        id: "import_settings",
+        title: "Import Settings",
+        category: "App",
+        keywords: &["restore", "archive", "keybindings", "themes"],
+        linux_shortcut: "",
+        macos_shortcut: "",
+        when: When::Always,
+    },
+    Command {
+        id: "import_vscode_settings",
+        title: "Import VS Code Settings",
+        category: "App",
+        keywords: &["keybindings", "migrate", "switch"],
+        linux_shortcut: "",
+        macos_shortcut: "",
+        when: When::Always,
+    },
+    Command {
+        id: "sync_settings",
+        title: "Sync Settings",
+        category: "App",
+        keywords: &["git", "dotfiles", "repository"],
+        linux_shortcut: "",
+        macos_shortcut: "",
+        when: When::Always,
+    },
+    Command {
+        id: "reload_custom_css",
+        title: "Reload Custom CSS",
+        category: "App",
+        keywords: &["stylesheet", "theme", "editor.css"],
+        linux_shortcut: "",
+        macos_shortcut: "",
+        when: When::Always,
+    },
+    Command {
+        id: "toggle_assistant",
+        title: "Toggle Assistant Panel",
+        category: "Assistant",
+        keywords: &["ai", "chat", "llm", "ollama", "openai"],
+        linux_shortcut: "",
+        macos_shortcut: "",
+        when: When::Assistant,
+    },
+    Command {
+        id: "assistant_explain_selection",
+        title: "Explain Selection",
+        category: "Assistant",
+        keywords: &["ai", "chat", "describe", "code"],
+        linux_shortcut: "",
+        macos_shortcut: "",
+        when: When::AssistantEditor,
+    },
+    Command {
+        id: "manage_agent_access",
+        title: "Manage Agent Access (MCP)",
+        category: "Assistant",
+        keywords: &["mcp", "agent", "share", "claude", "codex", "socket"],
+        linux_shortcut: "",
+        macos_shortcut: "",
+        when: When::AgentAccess,
+    },
+    Command {
+        id: "install_lsp",
+        title: "Install Web LSP Servers",
+        category: "Language Servers",
+        keywords: &["typescript", "php", "html", "css"],
+        linux_shortcut: "",
+        macos_shortcut: "",
+        when: When::Always,
+    },
+];
+
+/// Look up a built-in command by ID.
+pub fn get(id: &str) -> Option<&'static Command> {
+    COMMANDS.iter().find(|command| command.id == id)
+}
+
+/// A registry entry as the FFI reports it: the palette item (with the
+/// effective shortcut) plus what a frontend needs to build menus and
+/// keybinding tables.
+#[derive(Clone, Debug, Serialize)]
+pub struct CommandEntry {
+    #[serde(flatten)]
+    pub item: CommandPaletteItem,
+    pub default_shortcut: Option<String>,
+    pub when: When,
+    pub enabled: bool,
+}
+
+/// The built-in commands with the shortcuts `settings` puts in effect
+/// (keymap preset and user overrides), plus the user's custom commands.
+#[derive(Clone, Debug, Default)]
+pub struct CommandRegistry {
+    overrides: HashMap<String, String>,
+    custom: Vec<CustomKeybinding>,
+}
+
+impl CommandRegistry {
+    pub fn new(settings: &Settings) -> Self {
+        Self {
+            overrides: settings.effective_keybinding_overrides(),
+            custom: settings
+                .custom_keybindings
+                .iter()
+                .filter(|kb| !kb.name.trim().is_empty() && !kb.command.trim().is_empty())
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// The shortcut in effect for a built-in command.
+    pub fn shortcut(&self, id: &str) -> Option<String> {
+        match self.overrides.get(id).filter(|s| !s.is_empty()) {
+            Some(shortcut) => Some(shortcut.clone()),
+            None => get(id)?.default_shortcut().map(str::to_string),
+        }
+    }
+
+    /// Whether `id` names a command that applies in `context`. Custom
+    /// commands always apply.
+    pub fn is_enabled(&self, id: &str, context: &CommandContext) -> bool {
+        match get(id) {
+            Some(command) => command.when.matches(context),
+            None => self.custom_items().any(|item| item.id == id),
+        }
+    }
+
+    /// Whether `id` names a built-in or custom command.
+    pub fn contains(&self, id: &str) -> bool {
+        get(id).is_some() || self.custom_items().any(|item| item.id == id)
+    }
+
+    /// Every command, enabled or not, built-ins first.
+    pub fn entries(&self, context: &CommandContext) -> Vec<CommandEntry> {
+        let builtins = COMMANDS.iter().map(|command| CommandEntry {
+            item: CommandPaletteItem {
+                shortcut: self.shortcut(command.id),
+                ..command.palette_item()
+            },
+            default_shortcut: command.default_shortcut().map(str::to_string),
+            when: command.when,
+            enabled: command.when.matches(context),
+        });
+        let custom = self.custom_items().map(|item| CommandEntry {
+            default_shortcut: item.shortcut.clone(),
+            item,
+            when: When::Always,
+            enabled: true,
+        });
+        builtins.chain(custom).collect()
+    }
+
+    /// The commands the palette lists in `context`.
+    pub fn palette_items(&self, context: &CommandContext) -> Vec<CommandPaletteItem> {
+        self.entries(context)
+            .into_iter()
+            .filter(|entry| entry.enabled)
+            .map(|entry| entry.item)
+            .collect()
+    }
+
+    fn custom_items(&self) -> impl Iterator<Item = CommandPaletteItem> + '_ {
+        self.custom
+            .iter()
+            .map(|kb| custom_command_item(&kb.name, Some(&kb.key), &kb.command, &kb.args))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn commands_are_unique_with_unique_default_shortcuts() {
+        let mut ids = HashSet::new();
+        let mut linux = HashSet::new();
+        let mut macos = HashSet::new();
+        for command in COMMANDS {
+            assert!(ids.insert(command.id), "{} listed twice", command.id);
+            assert!(!command.title.is_empty());
+            assert!(
+                command.linux_shortcut.is_empty() || linux.insert(command.linux_shortcut),
+                "{} reused on Linux",
+                command.linux_shortcut
+            );
+            assert!(
+                command.macos_shortcut.is_empty() || macos.insert(command.macos_shortcut),
+                "{} reused on macOS",
+                command.macos_shortcut
+            );
+        }
+    }
+
+    #[test]
+    fn registry_applies_overrides_and_enablement() {
+        let mut settings = Settings {
+            keymap_preset: "emacs".to_string(),
+            ..Default::default()
+        };
+        settings
+            .keybinding_overrides
+            .insert("go_to_line".to_string(), "Ctrl+L".to_string());
+        settings.custom_keybindings.push(CustomKeybinding {
+            name: "Tests".to_string(),
+            key: "Ctrl+Alt+T".to_string(),
+            command: "cargo".to_string(),
+            args: vec!["test".to_string()],
+        });
+        let registry = CommandRegistry::new(&settings);
+
+        assert_eq!(registry.shortcut("go_to_line").as_deref(), Some("Ctrl+L"));
+        assert_eq!(registry.shortcut("find").as_deref(), Some("Ctrl+S"));
+        assert_eq!(
+            registry.shortcut("new_tab").as_deref(),
+            get("new_tab").unwrap().default_shortcut()
+        );
+        assert_eq!(registry.shortcut("toggle_tab_bar"), None);
+
+        let terminal = CommandContext {
+            terminal: true,
+            ..Default::default()
+        };
+        let items = registry.palette_items(&terminal);
+        assert!(items.iter().any(|item| item.id == "new_tab"));
+        assert!(!items.iter().any(|item| item.id == "save"));
+        assert!(items
+            .iter()
+            .any(|item| item.source == CommandPaletteSource::Custom));
+        assert!(!registry.is_enabled("save", &terminal));
+        assert!(!registry.contains("nope"));
+
+        let editor = CommandContext {
+            editor: true,
+            file: true,
+            ..Default::default()
+        };
+        assert!(registry.is_enabled("save", &editor));
+        assert!(registry.is_enabled("open_containing_folder", &editor));
+        let custom = &items.last().unwrap().id;
+        assert!(registry.contains(custom) && registry.is_enabled(custom, &editor));
+
+        assert!(!registry.is_enabled("toggle_assistant", &editor));
+        let assistant = CommandContext {
+            assistant: true,
+            ..editor
+        };
+        assert!(registry.is_enabled("toggle_assistant", &assistant));
+        assert!(registry.is_enabled("assistant_explain_selection", &assistant));
+        assert!(!registry.is_enabled(
+            "assistant_explain_selection",
+            &CommandContext {
+                assistant: true,
+                ..terminal
+            }
+        ));
+        assert!(!registry.is_enabled("manage_agent_access", &assistant));
+        assert!(registry.is_enabled(
+            "manage_agent_access",
+            &CommandContext {
+                agent_access: true,
+                ..terminal
+            }
+        ));
+    }
+}
@@ -0,0 +1,418 @@
+//! Commit message composition and linting: subject/body assembly, the 50/72
+//! line-length conventions, Conventional Commits headers, `Co-authored-by`
+//! trailers, and message templates (`commit.template` / `.gitmessage`).
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// Subjects longer than this draw a warning.
+pub const SUBJECT_SOFT_LIMIT: usize = 50;
+/// Subjects longer than this are rejected by most tooling; body lines past it
+/// should be wrapped.
+pub const LINE_HARD_LIMIT: usize = 72;
+
+/// Conventional Commits types offered by the type picker, with a short
+/// description of each.
+pub const CONVENTIONAL_TYPES: &[(&str, &str)] = &[
+    ("feat", "A new feature"),
+    ("fix", "A bug fix"),
+    ("docs", "Documentation only changes"),
+    ("style", "Formatting, no code change"),
+    ("refactor", "Neither fixes a bug nor adds a feature"),
+    ("perf", "Improves performance"),
+    ("test", "Adds or corrects tests"),
+    ("build", "Build system or dependencies"),
+    ("ci", "CI configuration"),
+    ("chore", "Other changes that don't touch src or tests"),
+    ("revert", "Reverts a previous commit"),
+];
+
+/// A parsed `type(scope)!: description` subject line.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConventionalHeader {
+    pub kind: String,
+    pub scope: Option<String>,
+    pub breaking: bool,
+    pub description: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LintSeverity {
+    /// The commit should not be made as-is.
+    Error,
+    /// A style convention is not followed.
+    Warning,
+}
+
+/// One problem with a commit message. `line` is 0-based.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommitLint {
+    pub line: u32,
+    pub severity: LintSeverity,
+    pub message: String,
+}
+
+/// Join a subject and body into a full message, separated by a blank line.
+/// Trailing whitespace is trimmed; an empty body yields just the subject.
+pub fn compose(subject: &str, body: &str) -> String {
+    let subject = subject.trim();
+    let body = body.trim_end();
+    let body = body.trim_start_matches(['\n', '\r']);
+    if body.is_empty() {
+        subject.to_string()
+    } else {
+        format!("{}\n\n{}", subject, body)
+    }
+}
+
+/// Split a message into its subject line and body (without the separating
+/// blank line).
+pub fn split(message: &str) -> (String, String) {
+    let mut lines = message.splitn(2, '\n');
+    let subject = lines.next().unwrap_or_default().trim_end().to_string();
+    let body = lines
+        .next()
+        .unwrap_or_default()
+        .trim_start_matches(['\n', '\r'])
+        .trim_end()
+        .to_string();
+    (subject, body)
+}
+
+/// Remove `#` comment lines, as git does for template-based messages.
+pub fn strip_comments(message: &str) -> String {
+    message
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+/// Parse a Conventional Commits header, or `None` if `subject` doesn't
+/// follow the `type(scope)!: description` form.
+pub fn parse_conventional(subject: &str) -> Option<ConventionalHeader> {
+    let (head, description) = subject.split_once(": ")?;
+    let (head, breaking) = match head.strip_suffix('!') {
+        Some(head) => (head, true),
+        None => (head, false),
+    };
+    let (kind, scope) = match head.split_once('(') {
+        Some((kind, rest)) => {
+            let scope = rest.strip_suffix(')')?;
+            if scope.is_empty() || scope.contains(['(', ')']) {
+                return None;
+            }
+            (kind, Some(scope.to_string()))
+        }
+        None => (head, None),
+    };
+    if kind.is_empty() || !kind.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return None;
+    }
+    let description = description.trim();
+    if description.is_empty() {
+        return None;
+    }
+    Some(ConventionalHeader {
+        kind: kind.to_string(),
+        scope,
+        breaking,
+        description: description.to_string(),
+    })
+}
+
+/// Replace (or add) the Conventional Commits type of `subject`, keeping any
+/// scope, breaking marker, and description. An empty `kind` removes the
+/// header prefix.
+pub fn set_conventional_type(subject: &str, kind: &str) -> String {
+    let (scope, breaking, description) = match parse_conventional(subject) {
+        Some(header) => (header.scope, header.breaking, header.description),
+        None => (None, false, subject.trim().to_string()),
+    };
+    if kind.is_empty() {
+        return description;
+    }
+    let scope = scope.map(|s| format!("({})", s)).unwrap_or_default();
+    let bang = if breaking { "!" } else { "" };
+    format!("{}{}{}: {}", kind, scope, bang, description)
+}
+
+/// Check a full message against the 50/72 conventions and, when
+/// `conventional` is set, the Conventional Commits header format.
+pub fn lint(message: &str, conventional: bool) -> Vec<CommitLint> {
+    let mut lints = Vec::new();
+    let mut push = |line: usize, severity, message: String| {
+        lints.push(CommitLint {
+            line: line as u32,
+            severity,
+            message,
+        })
+    };
+    let lines: Vec<&str> = message.lines().collect();
+    let subject = lines.first().map(|l| l.trim_end()).unwrap_or_default();
+    let subject_len = subject.chars().count();
+
+    if subject.trim().is_empty() {
+        push(0, LintSeverity::Error, "Subject is empty".to_string());
+    } else if subject_len > LINE_HARD_LIMIT {
+        push(
+            0,
+            LintSeverity::Error,
+            format!(
+                "Subject is {} characters (limit {})",
+                subject_len, LINE_HARD_LIMIT
+            ),
+        );
+    } else if subject_len > SUBJECT_SOFT_LIMIT {
+        push(
+            0,
+            LintSeverity::Warning,
+            format!(
+                "Subject is {} characters (aim for {})",
+                subject_len, SUBJECT_SOFT_LIMIT
+            ),
+        );
+    }
+    if subject.ends_with('.') {
+        push(
+            0,
+            LintSeverity::Warning,
+            "Subject should not end with a period".to_string(),
+        );
+    }
+    if conventional && !subject.trim().is_empty() {
+        match parse_conventional(subject) {
+            None => push(
+                0,
+                LintSeverity::Warning,
+                "Subject is not a Conventional Commits header (type(scope): description)"
+                    .to_string(),
+            ),
+            Some(header) if !CONVENTIONAL_TYPES.iter().any(|(t, _)| *t == header.kind) => push(
+                0,
+                LintSeverity::Warning,
+                format!("Unknown commit type \"{}\"", header.kind),
+            ),
+            Some(_) => {}
+        }
+    }
+    if lines.get(1).is_some_and(|l| !l.trim().is_empty()) {
+        push(
+            1,
+            LintSeverity::Error,
+            "Separate the subject from the body with a blank line".to_string(),
+        );
+    }
+    for (index, line) in lines.iter().enumerate().skip(1) {
+        let len = line.trim_end().chars().count();
+        // Long URLs and trailers can't be wrapped.
+        if len > LINE_HARD_LIMIT && !line.contains("://") && !is_trailer(line) {
+            push(
+                index,
+                LintSeverity::Warning,
+                format!("Line is {} characters (wrap at {})", len, LINE_HARD_LIMIT),
+            );
+        }
+    }
+    lints
+}
+
+fn is_trailer(line: &str) -> bool {
+    line.split_once(": ").is_some_and(|(key, _)| {
+        !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    })
+}
+
+/// Append a `Co-authored-by` trailer for `author` (`Name <email>`) to
+/// `body`, keeping trailers in one block. Existing trailers for the same
+/// author are not duplicated.
+pub fn add_co_author(body: &str, author: &str) -> String {
+    let trailer = format!("Co-authored-by: {}", author.trim());
+    let body = body.trim_end();
+    if body.lines().any(|line| line.trim() == trailer) {
+        return body.to_string();
+    }
+    if body.is_empty() {
+        return trailer;
+    }
+    let last_is_trailer = body
+        .lines()
+        .last()
+        .is_some_and(|line| line.starts_with("Co-authored-by: ") || is_trailer(line));
+    let separator = if last_is_trailer { "\n" } else { "\n\n" };
+    format!("{}{}{}", body, separator, trailer)
+}
+
+/// Distinct authors (`Name <email>`) of the most recent `limit` commits
+/// reachable from HEAD in the repository containing `repo_path`, most recent
+/// first, excluding the configured user.
+pub fn recent_authors(repo_path: &str, limit: usize) -> Result<Vec<String>, String> {
+    let repo = crate::git::open_repo(Path::new(repo_path))?;
+    let me = repo
+        .signature()
+        .ok()
+        .and_then(|sig| sig.email().map(str::to_lowercase));
+    let mut walk = repo.revwalk().map_err(|e| e.to_string())?;
+    if walk.push_head().is_err() {
+        return Ok(Vec::new());
+    }
+    let mut seen = HashSet::new();
+    let mut authors = Vec::new();
+    for oid in walk.take(limit).flatten() {
+        let Ok(commit) = repo.find_commit(oid) else {
+            continue;
+        };
+        let author = commit.author();
+        let (Some(name), Some(email)) = (author.name(), author.email()) else {
+            continue;
+        };
+        let key = email.to_lowercase();
+        if me.as_deref() == Some(key.as_str()) || !seen.insert(key) {
+            continue;
+        }
+        authors.push(format!("{} <{}>", name, email));
+    }
+    Ok(authors)
+}
+
+/// The commit message template for the repository containing `repo_path`:
+/// the file named by `commit.template`, or a `.gitmessage` file at the
+/// repository root. Comment lines are removed. `None` when neither exists.
+pub fn load_template(repo_path: &str) -> Option<String> {
+    let repo = crate::git::open_repo(Path::new(repo_path)).ok()?;
+    let root = repo.workdir()?.to_path_buf();
+    let configured = repo
+        .config()
+        .ok()
+        .and_then(|config| config.get_path("commit.template").ok())
+        .map(|path| resolve_template_path(&root, &path));
+    let path = configured
+        .filter(|p| p.is_file())
+        .or_else(|| Some(root.join(".gitmessage")).filter(|p| p.is_file()))?;
+    let text = std::fs::read_to_string(&path)
+        .map_err(|e| log::warn!("Cannot read commit template {}: {}", path.display(), e))
+        .ok()?;
+    let text = strip_comments(&text);
+    (!text.is_empty()).then_some(text)
+}
+
+/// git resolves a relative `commit.template` against the work tree root.
+fn resolve_template_path(root: &Path, path: &Path) -> PathBuf {
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        root.join(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compose_and_split_round_trip() {
+        let message = compose("  Add thing ", "\nBody line\n\n");
+        assert_eq!(message, "Add thing\n\nBody line");
+        assert_eq!(
+            split(&message),
+            ("Add thing".to_string(), "Body line".to_string())
+        );
+        assert_eq!(compose("Subject", "  \n"), "Subject");
+        assert_eq!(strip_comments("# hint\nfix: x\n# more\n"), "fix: x");
+    }
+
+    #[test]
+    fn parses_and_rewrites_conventional_headers() {
+        let header = parse_conventional("feat(ui)!: add zen mode").unwrap();
+        assert_eq!(header.kind, "feat");
+        assert_eq!(header.scope.as_deref(), Some("ui"));
+        assert!(header.breaking);
+        assert_eq!(header.description, "add zen mode");
+        assert!(parse_conventional("Add zen mode").is_none());
+        assert!(parse_conventional("feat(): x").is_none());
+        assert!(parse_conventional("feat: ").is_none());
+
+        assert_eq!(
+            set_conventional_type("feat(ui)!: add zen mode", "fix"),
+            "fix(ui)!: add zen mode"
+        );
+        assert_eq!(
+            set_conventional_type("Add zen mode", "docs"),
+            "docs: Add zen mode"
+        );
+        assert_eq!(set_conventional_type("fix: typo", ""), "typo");
+    }
+
+    #[test]
+    fn lint_applies_50_72_rules() {
+        assert!(lint("Short subject\n\nBody.", false).is_empty());
+        let long_subject = "x".repeat(60);
+        let lints = lint(&long_subject, false);
+        assert_eq!(lints[0].severity, LintSeverity::Warning);
+        assert_eq!(
+            lint(&"x".repeat(80), false)[0].severity,
+            LintSeverity::Error
+        );
+
+        let lints = lint(&format!("Subject.\nno gap\n{}", "y".repeat(80)), false);
+        let lines: Vec<(u32, LintSeverity)> = lints.iter().map(|l| (l.line, l.severity)).collect();
+        assert_eq!(
+            lines,
+            vec![
+                (0, LintSeverity::Warning),
+                (1, LintSeverity::Error),
+                (2, LintSeverity::Warning)
+            ]
+        );
+        let url = format!("Subject\n\nhttps://example.com/{}", "z".repeat(80));
+        assert!(lint(&url, false).is_empty());
+        assert_eq!(lint("", false)[0].severity, LintSeverity::Error);
+    }
+
+    #[test]
+    fn lint_checks_conventional_headers_when_enabled() {
+        assert!(lint("feat: add x", true).is_empty());
+        assert_eq!(lint("add x", true).len(), 1);
+        assert!(lint("wip: add x", true)[0].message.contains("wip"));
+        assert!(lint("add x", false).is_empty());
+    }
+
+    #[test]
+    fn co_author_trailers_form_one_block() {
+        let body = add_co_author("", "A <a@x.io>");
+        assert_eq!(body, "Co-authored-by: A <a@x.io>");
+        let body = add_co_author(&format!("Explain.\n\n{}", body), "B <b@x.io>");
+        assert_eq!(
+            body,
+            "Explain.\n\nCo-authored-by: A <a@x.io>\nCo-authored-by: B <b@x.io>"
+        );
+        assert_eq!(add_co_author(&body, "B <b@x.io>"), body);
+        assert_eq!(
+            add_co_author("Explain.", "C <c@x.io>"),
+            "Explain.\n\nCo-authored-by: C <c@x.io>"
+        );
+    }
+
+    #[test]
+    fn template_from_config_or_gitmessage() {
+        let temp = tempfile::tempdir().unwrap();
+        let repo = git2::Repository::init(temp.path()).unwrap();
+        let root = temp.path().to_str().unwrap();
+        assert_eq!(load_template(root), None);
+
+        std::fs::write(temp.path().join(".gitmessage"), "# comment\nfeat: \n").unwrap();
+        assert_eq!(load_template(root).as_deref(), Some("feat:"));
+
+        std::fs::write(temp.path().join("tmpl.txt"), "Subject\n\nWhy:\n").unwrap();
+        repo.config()
+            .unwrap()
+            .set_str("commit.template", "tmpl.txt")
+            .unwrap();
+        assert_eq!(load_template(root).as_deref(), Some("Subject\n\nWhy:"));
+    }
+}
@@ -0,0 +1,429 @@
+//! Moving the user configuration between machines: `settings.json` (which
+//! includes keybindings and custom commands), `custom.css`, `editor.css`
+//! and the user themes in `themes/`.
+//!
+//! [`export_archive`] writes them to a zip archive and [`import_archive`]
+//! restores one. With `settings_sync_repository` set, [`sync`] keeps them in
+//! a git repository instead: a checkout in the data directory is pulled and
+//! pushed, and a side that changed since the last sync wins. When both
+//! changed, [`resolve_sync_conflict`] picks one.
+//!
+//! Imported and pulled files are written straight to the config directory,
+//! so the settings watcher reloads them; frontends can call
+//! `settings::check_external_change` to apply them right away. Other files
+//! in the config directory (workspace trust decisions, session state) never
+//! leave the machine.
+
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::settings::Settings;
+
+/// Portable files by path relative to the config directory.
+type ConfigFiles = BTreeMap<String, Vec<u8>>;
+
+const SETTINGS_FILE: &str = "settings.json";
+const CSS_FILES: &[&str] = &["custom.css", "editor.css"];
+const THEMES_DIR: &str = "themes";
+
+/// Result of a [`sync`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncOutcome {
+    UpToDate,
+    /// Local changes were committed and pushed.
+    Pushed,
+    /// The repository's files were written to the config directory.
+    Pulled,
+    /// Both sides changed since the last sync; nothing was written.
+    Conflict,
+}
+
+fn config_dir() -> Result<PathBuf, String> {
+    crate::settings::settings_path()
+        .and_then(|path| path.parent().map(Path::to_path_buf))
+        .ok_or_else(|| "Cannot determine config directory".to_string())
+}
+
+/// Whether `name` is a file that is exported, imported and synced.
+fn is_portable(name: &str) -> bool {
+    if name == SETTINGS_FILE || CSS_FILES.contains(&name) {
+        return true;
+    }
+    match name
+        .strip_prefix(THEMES_DIR)
+        .and_then(|n| n.strip_prefix('/'))
+    {
+        Some(theme) => {
+            !theme.is_empty()
+                && !theme.starts_with('.')
+                && !theme.contains(['/', '\\'])
+                && (theme.ends_with(".toml") || theme.ends_with(".json"))
+        }
+        None => false,
+    }
+}
+
+fn collect_files(dir: &Path) -> Result<ConfigFiles, String> {
+    let mut files = ConfigFiles::new();
+    let mut add = |name: String| -> Result<(), String> {
+        match std::fs::read(dir.join(&name)) {
+            Ok(contents) => {
+                files.insert(name, contents);
+                Ok(())
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(format!("Failed to read {}: {}", name, e)),
+        }
+    };
+    add(SETTINGS_FILE.to_string())?;
+    for css in CSS_FILES {
+        add(css.to_string())?;
+    }
+    if let Ok(entries) = std::fs::read_dir(dir.join(THEMES_DIR)) {
+        for entry in entries.flatten() {
+            let name = format!("{}/{}", THEMES_DIR, entry.file_name().to_string_lossy());
+            if is_portable(&name) && entry.path().is_file() {
+                add(name)?;
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// Write `files` into `dir`, checking `settings.json` first so a broken
+/// file never replaces working settings. Files missing from `files` are
+/// left alone.
+fn write_files(dir: &Path, files: &ConfigFiles) -> Result<(), String> {
+    if let Some(settings) = files.get(SETTINGS_FILE) {
+        std::str::from_utf8(settings)
+            .map_err(|e| format!("Failed to read settings as UTF-8: {e}"))
+            .and_then(Settings::from_json)?;
+    }
+    for (name, contents) in files {
+        if !is_portable(name) {
+            continue;
+        }
+        let path = dir.join(name);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        write_atomic(&path, contents)?;
+    }
+    Ok(())
+}
+
+fn write_atomic(path: &Path, contents: &[u8]) -> Result<(), String> {
+    let tmp_path = path.with_extension("impulse-tmp");
+    {
+        #[cfg(unix)]
+        use std::os::unix::fs::OpenOptionsExt;
+        let mut opts = std::fs::OpenOptions::new();
+        opts.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        opts.mode(0o600);
+        opts.open(&tmp_path)
+            .and_then(|mut file| file.write_all(contents))
+            .map_err(|e| format!("Failed to write {}: {}", tmp_path.display(), e))?;
+    }
+    std::fs::rename(&tmp_path, path)
+        .map_err(|e| format!("Failed to replace {}: {}", path.display(), e))
+}
+
+// ---------------------------------------------------------------------------
+// Archive export/import
+// ---------------------------------------------------------------------------
+
+/// Write the configuration to the zip archive `dest`. Returns how many
+/// files it holds.
+pub fn export_archive(dest: &Path) -> Result<usize, String> {
+    export_archive_from(&config_dir()?, dest)
+}
+
+/// Restore the configuration from the zip archive `src`. Entries other
+/// than the portable files are ignored. Returns how many files were
+/// written.
+pub fn import_archive(src: &Path) -> Result<usize, String> {
+    import_archive_to(src, &config_dir()?)
+}
+
+fn export_archive_from(dir: &Path, dest: &Path) -> Result<usize, String> {
+    let files = collect_files(dir)?;
+    if files.is_empty() {
+        return Err("There are no settings to export yet".to_string());
+    }
+    let file = std::fs::File::create(dest)
+        .map_err(|e| format!("Failed to create {}: {}", dest.display(), e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .unix_permissions(0o600);
+    for (name, contents) in &files {
+        zip.start_file(name.as_str(), options)
+            .and_then(|()| zip.write_all(contents).map_err(Into::into))
+            .map_err(|e| format!("Failed to write {} to the archive: {}", name, e))?;
+    }
+    zip.finish()
+        .map_err(|e| format!("Failed to write {}: {}", dest.display(), e))?;
+    Ok(files.len())
+}
+
+fn import_archive_to(src: &Path, dir: &Path) -> Result<usize, String> {
+    let file =
+        std::fs::File::open(src).map_err(|e| format!("Failed to open {}: {}", src.display(), e))?;
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|e| format!("{} is not a settings archive: {}", src.display(), e))?;
+    let mut files = ConfigFiles::new();
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| format!("Failed to read the archive: {}", e))?;
+        let name = entry.name().to_string();
+        if !entry.is_file() || !is_portable(&name) {
+            log::warn!("Skipping {} in settings archive", name);
+            continue;
+        }
+        let mut contents = Vec::new();
+        entry
+            .read_to_end(&mut contents)
+            .map_err(|e| format!("Failed to read {} from the archive: {}", name, e))?;
+        files.insert(name, contents);
+    }
+    if files.is_empty() {
+        return Err(format!("{} has no Impulse settings in it", src.display()));
+    }
+    write_files(dir, &files)?;
+    Ok(files.len())
+}
+
+// ---------------------------------------------------------------------------
+// Git sync
+// ---------------------------------------------------------------------------
+
+/// Where the sync repository is checked out.
+fn sync_checkout_dir() -> Result<PathBuf, String> {
+    dirs::data_local_dir()
+        .map(|d| d.join("impulse").join("settings-sync"))
+        .ok_or_else(|| "Cannot determine data directory".to_string())
+}
+
+/// Sync the configuration with the git repository at `url`: pull what
+/// changed there, or commit and push what changed here.
+pub fn sync(url: &str) -> Result<SyncOutcome, String> {
+    sync_with(&config_dir()?, &sync_checkout_dir()?, url)
+}
+
+/// Settle a [`SyncOutcome::Conflict`]: push this machine's configuration
+/// (`keep_local`) or write the repository's over it.
+pub fn resolve_sync_conflict(url: &str, keep_local: bool) -> Result<SyncOutcome, String> {
+    let checkout = sync_checkout_dir()?;
+    open_checkout(&checkout, url)?;
+    let dir = config_dir()?;
+    if keep_local {
+        push_local(&dir, &checkout)
+    } else {
+        write_files(&dir, &collect_files(&checkout)?)?;
+        Ok(SyncOutcome::Pulled)
+    }
+}
+
+fn sync_with(dir: &Path, checkout: &Path, url: &str) -> Result<SyncOutcome, String> {
+    let url = url.trim();
+    if url.is_empty() {
+        return Err("No settings sync repository is configured".to_string());
+    }
+    let cloned = open_checkout(checkout, url)?;
+    let local = collect_files(dir)?;
+    // The checkout holds what was last synced.
+    let synced = collect_files(checkout)?;
+
+    let path = checkout.to_string_lossy().to_string();
+    let (progress, _) = std::sync::mpsc::channel();
+    let has_commits = crate::git::open_repo(checkout)?.head().is_ok();
+    let pulled = has_commits
+        && !cloned
+        && matches!(
+            crate::git::pull(&path, &progress, None)?,
+            crate::git::PullOutcome::FastForwarded { .. }
+        );
+    let remote = collect_files(checkout)?;
+
+    // A fresh clone doesn't know what was synced last; any difference from
+    // a non-empty repository is a conflict.
+    let remote_changed = pulled || (cloned && !remote.is_empty());
+    let local_changed = local != synced;
+    match (local_changed, remote_changed) {
+        (false, false) => {
+            // A push that failed last time (offline) is retried.
+            let unpushed = crate::git::ahead_behind(&path)?.is_some_and(|ab| ab.ahead > 0);
+            if unpushed {
+                crate::git::push(&path, &progress, None)?;
+                Ok(SyncOutcome::Pushed)
+            } else {
+                Ok(SyncOutcome::UpToDate)
+            }
+        }
+        (false, true) => {
+            write_files(dir, &remote)?;
+            Ok(SyncOutcome::Pulled)
+        }
+        (true, false) => push_local(dir, checkout),
+        (true, true) => Ok(SyncOutcome::Conflict),
+    }
+}
+
+/// Open the checkout of `url`, cloning it when missing or when it tracks
+/// another repository. Returns whether it was cloned.
+fn open_checkout(checkout: &Path, url: &str) -> Result<bool, String> {
+    if let Ok(repo) = git2::Repository::open(checkout) {
+        let same_url = repo
+            .find_remote("origin")
+            .ok()
+            .is_some_and(|remote| remote.url() == Some(url));
+        if same_url {
+            return Ok(false);
+        }
+    }
+    if checkout.exists() {
+        std::fs::remove_dir_all(checkout)
+            .map_err(|e| format!("Failed to remove {}: {}", checkout.display(), e))?;
+    }
+    if let Some(parent) = checkout.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    crate::git::clone(url, checkout)?;
+    Ok(true)
+}
+
+/// Mirror the config directory into the checkout, then commit and push.
+fn push_local(dir: &Path, checkout: &Path) -> Result<SyncOutcome, String> {
+    let local = collect_files(dir)?;
+    for name in collect_files(checkout)?.keys() {
+        if !local.contains_key(name) {
+            std::fs::remove_file(checkout.join(name))
+                .map_err(|e| format!("Failed to remove {} from the checkout: {}", name, e))?;
+        }
+    }
+    write_files(checkout, &local)?;
+
+    let path = checkout.to_string_lossy().to_string();
+    match crate::git::commit_all(&path, "Update Impulse settings") {
+        Ok(_) => {}
+        Err(e) if e == "nothing to commit" => {}
+        Err(e) => return Err(e),
+    }
+    let (progress, _) = std::sync::mpsc::channel();
+    crate::git::push(&path, &progress, None)?;
+    Ok(SyncOutcome::Pushed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_portable_files_are_exported_and_imported() {
+        assert!(is_portable("settings.json"));
+        assert!(is_portable("themes/mine.toml"));
+        assert!(!is_portable("trusted_folders.json"));
+        assert!(!is_portable("themes/../settings.json"));
+        assert!(!is_portable("themes/nested/dark.toml"));
+
+        let from = tempfile::tempdir().unwrap();
+        std::fs::write(from.path().join("settings.json"), r#"{"font_size": 15}"#).unwrap();
+        std::fs::write(from.path().join("custom.css"), "window {}").unwrap();
+        std::fs::write(from.path().join("trusted_folders.json"), "{}").unwrap();
+        std::fs::create_dir(from.path().join("themes")).unwrap();
+        std::fs::write(from.path().join("themes/mine.toml"), "name = 'Mine'").unwrap();
+
+        let archive = from.path().join("impulse-settings.zip");
+        assert_eq!(export_archive_from(from.path(), &archive).unwrap(), 3);
+
+        let to = tempfile::tempdir().unwrap();
+        assert_eq!(import_archive_to(&archive, to.path()).unwrap(), 3);
+        assert_eq!(
+            std::fs::read_to_string(to.path().join("themes/mine.toml")).unwrap(),
+            "name = 'Mine'"
+        );
+        assert!(!to.path().join("trusted_folders.json").exists());
+        assert_eq!(collect_files(to.path()).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn invalid_settings_are_not_imported() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("settings.json"), r#"{"font_size": 12}"#).unwrap();
+        let mut files = ConfigFiles::new();
+        files.insert("settings.json".into(), b"{ not json".to_vec());
+        files.insert("custom.css".into(), b"window {}".to_vec());
+        assert!(write_files(dir.path(), &files).is_err());
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("settings.json")).unwrap(),
+            r#"{"font_size": 12}"#
+        );
+        assert!(!dir.path().join("custom.css").exists());
+    }
+
+    #[test]
+    fn sync_pushes_local_changes_and_pulls_remote_ones() {
+        let remote = tempfile::tempdir().unwrap();
+        git2::Repository::init_bare(remote.path()).unwrap();
+        let url = remote.path().to_string_lossy().to_string();
+        let identity = |checkout: &Path| {
+            let mut config = git2::Repository::open(checkout).unwrap().config().unwrap();
+            config.set_str("user.name", "Impulse Test").unwrap();
+            config.set_str("user.email", "impulse@example.com").unwrap();
+        };
+
+        // Machine A pushes to the empty repository.
+        let (a_dir, a_checkout) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        let a_checkout = a_checkout.path().join("sync");
+        std::fs::write(a_dir.path().join("custom.css"), "a").unwrap();
+        open_checkout(&a_checkout, &url).unwrap();
+        identity(&a_checkout);
+        assert_eq!(
+            sync_with(a_dir.path(), &a_checkout, &url).unwrap(),
+            SyncOutcome::Pushed
+        );
+        assert_eq!(
+            sync_with(a_dir.path(), &a_checkout, &url).unwrap(),
+            SyncOutcome::UpToDate
+        );
+
+        // Machine B has different files: the first sync asks.
+        let (b_dir, b_checkout) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        let b_checkout = b_checkout.path().join("sync");
+        std::fs::write(b_dir.path().join("custom.css"), "b").unwrap();
+        assert_eq!(
+            sync_with(b_dir.path(), &b_checkout, &url).unwrap(),
+            SyncOutcome::Conflict
+        );
+        // Taking the repository's version, B is in step.
+        write_files(b_dir.path(), &collect_files(&b_checkout).unwrap()).unwrap();
+        identity(&b_checkout);
+        assert_eq!(
+            sync_with(b_dir.path(), &b_checkout, &url).unwrap(),
+            SyncOutcome::UpToDate
+        );
+
+        // A change on A reaches B.
+        std::fs::write(a_dir.path().join("custom.css"), "a2").unwrap();
+        assert_eq!(
+            sync_with(a_dir.path(), &a_checkout, &url).unwrap(),
+            SyncOutcome::Pushed
+        );
+        assert_eq!(
+            sync_with(b_dir.path(), &b_checkout, &url).unwrap(),
+            SyncOutcome::Pulled
+        );
+        assert_eq!(
+            std::fs::read_to_string(b_dir.path().join("custom.css")).unwrap(),
+            "a2"
+        );
+    }
+}
@@ -0,0 +1,139 @@
+//! Git merge-conflict markers: locating `<<<<<<<` / `=======` / `>>>>>>>`
+//! regions, including diff3-style `|||||||` base sections, in a buffer.
+
+use serde::{Deserialize, Serialize};
+
+const CURRENT_MARKER: &str = "<<<<<<<";
+const BASE_MARKER: &str = "|||||||";
+const SEPARATOR_MARKER: &str = "=======";
+const INCOMING_MARKER: &str = ">>>>>>>";
+
+/// One conflict in a document. Line numbers are 0-based and point at the
+/// marker lines themselves.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConflictRegion {
+    /// The `<<<<<<<` line opening the current (ours) side.
+    pub start_line: u32,
+    /// The `|||||||` line opening the common ancestor, for diff3 conflicts.
+    pub base_line: Option<u32>,
+    /// The `=======` line between the two sides.
+    pub separator_line: u32,
+    /// The `>>>>>>>` line closing the incoming (theirs) side.
+    pub end_line: u32,
+    /// Text after the opening marker, e.g. `HEAD`.
+    pub current_label: String,
+    /// Text after the closing marker, e.g. the merged branch name.
+    pub incoming_label: String,
+}
+
+/// Cheap check for whether `content` may contain conflict markers.
+pub fn has_conflict_markers(content: &str) -> bool {
+    content.contains(CURRENT_MARKER)
+}
+
+/// If `line` is `marker` alone or followed by whitespace and a label,
+/// return the label.
+fn marker_label<'a>(line: &'a str, marker: &str) -> Option<&'a str> {
+    let rest = line.trim_end_matches('\r').strip_prefix(marker)?;
+    if rest.is_empty() {
+        Some("")
+    } else if rest.starts_with([' ', '\t']) {
+        Some(rest.trim())
+    } else {
+        None
+    }
+}
+
+/// Find every complete conflict region in `content`, in document order.
+///
+/// Unterminated or malformed regions are skipped; a new opening marker
+/// restarts the search so one stray marker doesn't hide later conflicts.
+pub fn find_conflict_regions(content: &str) -> Vec<ConflictRegion> {
+    struct Open {
+        start_line: u32,
+        current_label: String,
+        base_line: Option<u32>,
+        separator_line: Option<u32>,
+    }
+
+    let mut regions = Vec::new();
+    let mut open: Option<Open> = None;
+    for (index, line) in content.split('\n').enumerate() {
+        let index = index as u32;
+        if let Some(label) = marker_label(line, CURRENT_MARKER) {
+            open = Some(Open {
+                start_line: index,
+                current_label: label.to_string(),
+                base_line: None,
+                separator_line: None,
+            });
+            continue;
+        }
+        let Some(region) = open.as_mut() else {
+            continue;
+        };
+        if region.separator_line.is_none() {
+            if region.base_line.is_none() && marker_label(line, BASE_MARKER).is_some() {
+                region.base_line = Some(index);
+            } else if line.trim_end_matches('\r') == SEPARATOR_MARKER {
+                region.separator_line = Some(index);
+            }
+        } else if let Some(label) = marker_label(line, INCOMING_MARKER) {
+            let region = open.take().expect("region is open");
+            regions.push(ConflictRegion {
+                start_line: region.start_line,
+                base_line: region.base_line,
+                separator_line: region.separator_line.expect("separator seen"),
+                end_line: index,
+                current_label: region.current_label,
+                incoming_label: label.to_string(),
+            });
+        }
+    }
+    regions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_two_way_conflicts_with_labels() {
+        let content = "a\n<<<<<<< HEAD\nours\n=======\ntheirs\n>>>>>>> feature\nb\n";
+        assert!(has_conflict_markers(content));
+        assert_eq!(
+            find_conflict_regions(content),
+            vec![ConflictRegion {
+                start_line: 1,
+                base_line: None,
+                separator_line: 3,
+                end_line: 5,
+                current_label: "HEAD".to_string(),
+                incoming_label: "feature".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn finds_diff3_base_sections_and_crlf() {
+        let content =
+            "<<<<<<< HEAD\r\nours\r\n||||||| base\r\nold\r\n=======\r\ntheirs\r\n>>>>>>> other\r\n";
+        let regions = find_conflict_regions(content);
+        assert_eq!(regions.len(), 1);
+        assert_eq!(regions[0].base_line, Some(2));
+        assert_eq!(regions[0].separator_line, 4);
+        assert_eq!(regions[0].end_line, 6);
+        assert_eq!(regions[0].incoming_label, "other");
+    }
+
+    #[test]
+    fn skips_unterminated_and_lookalike_markers() {
+        let content = "<<<<<<< HEAD\nstray\n<<<<<<<< not a marker\n\
+                       <<<<<<< HEAD\nx\n=======\ny\n>>>>>>> b\n=======\n";
+        let regions = find_conflict_regions(content);
+        assert_eq!(regions.len(), 1);
+        assert_eq!(regions[0].start_line, 3);
+        assert_eq!(regions[0].end_line, 7);
+        assert!(find_conflict_regions("plain text\n").is_empty());
+    }
+}
@@ -0,0 +1,428 @@
+//! Control socket: scripts, git hooks and tools like `gh` drive the running
+//! editor over JSON-RPC 2.0 on `$XDG_RUNTIME_DIR/impulse/impulse.sock`, one
+//! message per line. The socket is accessible only to the user.
+//!
+//! Methods:
+//! - `open-file` `{path, line?, column?, new_window?}`: open an absolute path,
+//!   optionally at a 1-based position.
+//! - `goto-position` `{path?, line, column?}`: move the cursor in `path`, or
+//!   in the active editor when `path` is left out.
+//! - `run-command` `{id}`: run a command palette command in the active window.
+//! - `query-state`: the open windows, as a [`ControlState`].
+//!
+//! `impulse --control <method> [params]` sends one request from a shell.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::local_socket::{self, LineServer};
+
+const SOCKET_NAME: &str = "impulse.sock";
+
+/// The methods, as named on the wire.
+pub const METHODS: &[&str] = &["open-file", "goto-position", "run-command", "query-state"];
+
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+/// The frontend couldn't carry out a valid request.
+const REQUEST_FAILED: i64 = -32000;
+
+/// How long a client waits for the frontend; opening a file can wait on a
+/// workspace trust prompt.
+const CALL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// `$XDG_RUNTIME_DIR/impulse/impulse.sock` (the cache directory where there
+/// is no runtime directory).
+pub fn socket_path() -> Option<PathBuf> {
+    Some(
+        dirs::runtime_dir()
+            .or_else(dirs::cache_dir)?
+            .join("impulse")
+            .join(SOCKET_NAME),
+    )
+}
+
+/// A request for the frontend, already checked by [`ControlRequest::validate`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "method", content = "params", rename_all = "kebab-case")]
+pub enum ControlRequest {
+    OpenFile {
+        path: String,
+        #[serde(default)]
+        line: Option<u32>,
+        #[serde(default)]
+        column: Option<u32>,
+        #[serde(default)]
+        new_window: bool,
+    },
+    GotoPosition {
+        #[serde(default)]
+        path: Option<String>,
+        line: u32,
+        #[serde(default)]
+        column: Option<u32>,
+    },
+    RunCommand {
+        id: String,
+    },
+    QueryState,
+}
+
+impl ControlRequest {
+    /// Parse the `method` and `params` of a JSON-RPC message.
+    fn parse(method: &str, params: Value) -> Result<Self, (i64, String)> {
+        if !METHODS.contains(&method) {
+            return Err((METHOD_NOT_FOUND, format!("Unknown method: {}", method)));
+        }
+        let request = if method == "query-state" {
+            ControlRequest::QueryState
+        } else {
+            serde_json::from_value(json!({ "method": method, "params": params }))
+                .map_err(|e| (INVALID_PARAMS, format!("Invalid params: {}", e)))?
+        };
+        request.validate().map_err(|e| (INVALID_PARAMS, e))?;
+        Ok(request)
+    }
+
+    /// Paths must be absolute and exist, and positions are 1-based.
+    pub fn validate(&self) -> Result<(), String> {
+        let (path, line, column) = match self {
+            ControlRequest::OpenFile {
+                path, line, column, ..
+            } => (Some(path), *line, *column),
+            ControlRequest::GotoPosition { path, line, column } => {
+                (path.as_ref(), Some(*line), *column)
+            }
+            ControlRequest::RunCommand { id } => {
+                if id.trim().is_empty() {
+                    return Err("Missing command id".to_string());
+                }
+                return Ok(());
+            }
+            ControlRequest::QueryState => return Ok(()),
+        };
+        if let Some(path) = path {
+            if !Path::new(path).is_absolute() {
+                return Err(format!("Path must be absolute: {}", path));
+            }
+            if !Path::new(path).exists() {
+                return Err(format!("No such file or directory: {}", path));
+            }
+        }
+        if line == Some(0) || column == Some(0) {
+            return Err("Lines and columns start at 1".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// The answer to `query-state`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ControlState {
+    pub windows: Vec<WindowState>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowState {
+    pub focused: bool,
+    /// The folder open in the file tree.
+    pub workspace: Option<String>,
+    /// The file in the selected tab.
+    pub active_file: Option<String>,
+    pub open_files: Vec<String>,
+}
+
+/// A request a client is waiting on. Frontends answer with
+/// [`ControlCall::respond`]: a JSON result, or an error message.
+pub struct ControlCall {
+    pub id: u64,
+    pub request: ControlRequest,
+    reply: mpsc::Sender<Result<Value, String>>,
+}
+
+impl ControlCall {
+    pub fn respond(self, result: Result<Value, String>) {
+        // The client may have disconnected.
+        let _ = self.reply.send(result);
+    }
+}
+
+/// Handle one JSON-RPC message, passing valid requests to `perform`, and
+/// return the response line. Notifications are performed without one.
+fn handle_message(
+    line: &str,
+    perform: &dyn Fn(ControlRequest) -> Result<Value, String>,
+) -> Option<String> {
+    let message: Value = match serde_json::from_str(line) {
+        Ok(message) => message,
+        Err(e) => {
+            return Some(error_response(
+                Value::Null,
+                PARSE_ERROR,
+                &format!("Parse error: {}", e),
+            ))
+        }
+    };
+    let id = message.get("id").cloned();
+    let params = message.get("params").cloned().unwrap_or(json!({}));
+    let result = match message.get("method").and_then(Value::as_str) {
+        Some(method) => ControlRequest::parse(method, params)
+            .and_then(|request| perform(request).map_err(|e| (REQUEST_FAILED, e))),
+        None => Err((INVALID_PARAMS, "Missing method".to_string())),
+    };
+    let id = id?;
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }).to_string(),
+        Err((code, message)) => error_response(id, code, &message),
+    })
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> String {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+    .to_string()
+}
+
+/// The control socket of the running instance. Dropping it stops
+/// listening.
+pub struct ControlServer {
+    calls: mpsc::Receiver<ControlCall>,
+    server: LineServer,
+}
+
+impl ControlServer {
+    /// Listen on [`socket_path`]. Fails when another instance already does.
+    pub fn start() -> Result<Self, String> {
+        let socket = socket_path().ok_or("No runtime directory for the control socket")?;
+        Self::start_at(socket)
+    }
+
+    fn start_at(socket: PathBuf) -> Result<Self, String> {
+        if local_socket::is_listening(&socket) {
+            return Err(format!(
+                "Another Impulse instance is listening on {}",
+                socket.display()
+            ));
+        }
+        let (calls_tx, calls) = mpsc::channel();
+        let next_id = AtomicU64::new(1);
+        let perform = move |request: ControlRequest| {
+            let (reply, answer) = mpsc::channel();
+            let call = ControlCall {
+                id: next_id.fetch_add(1, Ordering::Relaxed),
+                request,
+                reply,
+            };
+            calls_tx
+                .send(call)
+                .map_err(|_| "Impulse is shutting down".to_string())?;
+            answer
+                .recv_timeout(CALL_TIMEOUT)
+                .map_err(|_| "Impulse didn't answer in time".to_string())?
+        };
+        let server = LineServer::start(
+            socket,
+            "control",
+            Arc::new(move |line: &str| handle_message(line, &perform)),
+        )?;
+        Ok(Self { calls, server })
+    }
+
+    pub fn socket_path(&self) -> &Path {
+        self.server.path()
+    }
+
+    /// The next request a client is waiting on, if any. Frontends poll this
+    /// on their main loop.
+    pub fn next_call(&self) -> Option<ControlCall> {
+        self.calls.try_recv().ok()
+    }
+}
+
+/// Send one request to the running instance and return its result.
+pub fn call(method: &str, params: Value) -> Result<Value, String> {
+    let socket = socket_path().ok_or("No runtime directory for the control socket")?;
+    call_at(&socket, method, params)
+}
+
+fn call_at(socket: &Path, method: &str, params: Value) -> Result<Value, String> {
+    if !local_socket::is_listening(socket) {
+        return Err("Impulse isn't running, or its control socket is turned off".to_string());
+    }
+    let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+    let reply = local_socket::request(socket, &request.to_string())?;
+    let mut reply: Value =
+        serde_json::from_str(&reply).map_err(|e| format!("Invalid reply: {}", e))?;
+    if let Some(error) = reply.get("error") {
+        return Err(error["message"]
+            .as_str()
+            .unwrap_or("The request failed")
+            .to_string());
+    }
+    Ok(reply["result"].take())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn respond(message: Value) -> Value {
+        let perform = |request: ControlRequest| match request {
+            ControlRequest::QueryState => Ok(json!(ControlState::default())),
+            ControlRequest::RunCommand { id } if id == "nope" => {
+                Err("Unknown command: nope".to_string())
+            }
+            _ => Ok(Value::Null),
+        };
+        serde_json::from_str(&handle_message(&message.to_string(), &perform).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn parses_and_validates_requests() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("main.rs");
+        std::fs::write(&file, "").unwrap();
+        let path = file.to_string_lossy().to_string();
+
+        assert_eq!(
+            ControlRequest::parse("open-file", json!({ "path": path, "line": 3 })).unwrap(),
+            ControlRequest::OpenFile {
+                path: path.clone(),
+                line: Some(3),
+                column: None,
+                new_window: false,
+            }
+        );
+        assert_eq!(
+            ControlRequest::parse("goto-position", json!({ "line": 1, "column": 5 })).unwrap(),
+            ControlRequest::GotoPosition {
+                path: None,
+                line: 1,
+                column: Some(5),
+            }
+        );
+        assert_eq!(
+            ControlRequest::parse("query-state", Value::Null).unwrap(),
+            ControlRequest::QueryState
+        );
+
+        let invalid =
+            |method: &str, params: Value| ControlRequest::parse(method, params).unwrap_err();
+        assert_eq!(
+            invalid("open-file", json!({ "path": "main.rs" })).0,
+            INVALID_PARAMS
+        );
+        assert_eq!(
+            invalid("open-file", json!({ "path": dir.path().join("gone.rs") })).0,
+            INVALID_PARAMS
+        );
+        assert_eq!(
+            invalid("goto-position", json!({ "line": 0 })).0,
+            INVALID_PARAMS
+        );
+        assert_eq!(
+            invalid("run-command", json!({ "id": " " })).0,
+            INVALID_PARAMS
+        );
+        assert_eq!(invalid("run-command", json!({})).0, INVALID_PARAMS);
+        assert_eq!(invalid("close-all", json!({})).0, METHOD_NOT_FOUND);
+    }
+
+    #[test]
+    fn answers_json_rpc_messages() {
+        let state = respond(json!({ "jsonrpc": "2.0", "id": 1, "method": "query-state" }));
+        assert_eq!(state["id"], 1);
+        assert_eq!(state["result"], json!({ "windows": [] }));
+
+        let failed = respond(json!({
+            "jsonrpc": "2.0", "id": "x", "method": "run-command", "params": { "id": "nope" },
+        }));
+        assert_eq!(failed["error"]["code"], REQUEST_FAILED);
+        assert_eq!(failed["error"]["message"], "Unknown command: nope");
+
+        let unknown = respond(json!({ "jsonrpc": "2.0", "id": 2, "method": "quit" }));
+        assert_eq!(unknown["error"]["code"], METHOD_NOT_FOUND);
+        let garbage: Value =
+            serde_json::from_str(&handle_message("{not json", &|_| Ok(Value::Null)).unwrap())
+                .unwrap();
+        assert_eq!(garbage["error"]["code"], PARSE_ERROR);
+
+        // Notifications are carried out without a reply.
+        let performed = std::cell::Cell::new(false);
+        let notification = json!({ "jsonrpc": "2.0", "method": "query-state" });
+        assert!(handle_message(&notification.to_string(), &|_| {
+            performed.set(true);
+            Ok(Value::Null)
+        })
+        .is_none());
+        assert!(performed.get());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn socket_directory_is_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let shared = dir.path().join("shared");
+        std::fs::create_dir(&shared).unwrap();
+        std::fs::set_permissions(&shared, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let _server = ControlServer::start_at(shared.join("impulse.sock")).unwrap();
+        let _nested = ControlServer::start_at(dir.path().join("a/b/impulse.sock")).unwrap();
+        let mode = |path: &std::path::Path| std::fs::metadata(path).unwrap().permissions().mode();
+        assert_eq!(mode(&shared) & 0o777, 0o700);
+        assert_eq!(mode(&dir.path().join("a/b")) & 0o777, 0o700);
+
+        let link = dir.path().join("link");
+        std::os::unix::fs::symlink(&shared, &link).unwrap();
+        assert!(ControlServer::start_at(link.join("other.sock")).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn serves_clients_over_the_socket() {
+        let dir = tempfile::tempdir().unwrap();
+        let socket = dir.path().join("impulse.sock");
+        assert!(call_at(&socket, "query-state", json!({})).is_err());
+
+        let server = ControlServer::start_at(socket.clone()).unwrap();
+        assert!(ControlServer::start_at(socket.clone()).is_err());
+
+        let client_socket = socket.clone();
+        let client = std::thread::spawn(move || {
+            call_at(
+                &client_socket,
+                "run-command",
+                json!({ "id": "toggle_sidebar" }),
+            )
+        });
+        // The frontend answers on its own loop.
+        let call = loop {
+            if let Some(call) = server.next_call() {
+                break call;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        };
+        assert_eq!(
+            call.request,
+            ControlRequest::RunCommand {
+                id: "toggle_sidebar".to_string()
+            }
+        );
+        call.respond(Err("No window is open".to_string()));
+        assert_eq!(client.join().unwrap().unwrap_err(), "No window is open");
+
+        drop(server);
+        assert!(!socket.exists());
+    }
+}
@@ -0,0 +1,259 @@
+//! Crash reports: a structured record of each panic (version, OS,
+//! backtrace and the log lines leading up to it) written next to
+//! `panic.log`. Frontends show unseen reports after the next launch and can
+//! submit them to the `crash_report_endpoint` setting when the user opts in.
+
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+/// Log lines kept in memory for the next report.
+const RECENT_LOG_LINES: usize = 200;
+
+/// Reports kept on disk; older ones are deleted when a new one is written.
+const MAX_REPORTS: usize = 20;
+
+const SUBMIT_TIMEOUT_SECS: u64 = 15;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CrashReport {
+    pub timestamp_ms: u64,
+    pub version: String,
+    pub os: String,
+    pub arch: String,
+    /// The panic message, including its source location.
+    pub message: String,
+    pub backtrace: String,
+    pub recent_log: Vec<String>,
+    /// Set once the report has been shown after a restart.
+    pub seen: bool,
+}
+
+impl CrashReport {
+    /// A report for a crash happening now, with the recent log attached.
+    pub fn new(message: &str, backtrace: &str) -> Self {
+        Self {
+            timestamp_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0),
+            version: crate::update::CURRENT_VERSION.to_string(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            message: message.to_string(),
+            backtrace: backtrace.to_string(),
+            recent_log: recent_log(),
+            seen: false,
+        }
+    }
+
+    /// Plain-text rendering for the in-app viewer and the clipboard.
+    pub fn to_text(&self) -> String {
+        let mut text = format!(
+            "Impulse {} on {} ({})\nTime: {}\n\n{}\n\nBacktrace:\n{}",
+            self.version,
+            self.os,
+            self.arch,
+            self.timestamp_ms,
+            self.message,
+            self.backtrace.trim_end()
+        );
+        if !self.recent_log.is_empty() {
+            text.push_str("\n\nRecent log:\n");
+            text.push_str(&self.recent_log.join("\n"));
+        }
+        text
+    }
+}
+
+/// `<state_dir>/crashes`.
+pub fn reports_dir(state_dir: &Path) -> PathBuf {
+    state_dir.join("crashes")
+}
+
+/// Write `report` into `dir` and prune the oldest reports beyond the limit.
+/// Called from panic hooks, so it avoids anything that could panic again.
+pub fn write_report(dir: &Path, report: &CrashReport) -> Result<PathBuf, String> {
+    std::fs::create_dir_all(dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let path = dir.join(format!("crash-{}.json", report.timestamp_ms));
+    let json = serde_json::to_string_pretty(report)
+        .map_err(|e| format!("Failed to serialize crash report: {}", e))?;
+    std::fs::write(&path, json)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    for (old, _) in reports(dir).into_iter().skip(MAX_REPORTS) {
+        let _ = std::fs::remove_file(old);
+    }
+    Ok(path)
+}
+
+/// Every report in `dir` with its file path, newest first.
+pub fn reports(dir: &Path) -> Vec<(PathBuf, CrashReport)> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut reports: Vec<(PathBuf, CrashReport)> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|p| {
+            let json = std::fs::read_to_string(&p).ok()?;
+            let report = serde_json::from_str(&json).ok()?;
+            Some((p, report))
+        })
+        .collect();
+    reports.sort_by_key(|r| std::cmp::Reverse(r.1.timestamp_ms));
+    reports
+}
+
+/// Reports not yet shown to the user, newest first, marking them seen so
+/// the "previous session crashed" banner appears only once per crash.
+pub fn take_unseen(dir: &Path) -> Vec<CrashReport> {
+    let mut unseen = Vec::new();
+    for (path, mut report) in reports(dir) {
+        if report.seen {
+            continue;
+        }
+        unseen.push(report.clone());
+        report.seen = true;
+        if let Ok(json) = serde_json::to_string_pretty(&report) {
+            if let Err(e) = std::fs::write(&path, json) {
+                log::warn!("Failed to update {}: {}", path.display(), e);
+            }
+        }
+    }
+    unseen
+}
+
+/// POST `report` as JSON to `endpoint`. Only called when the user chose to
+/// send it.
+pub fn submit(report: &CrashReport, endpoint: &str) -> Result<(), String> {
+    if endpoint.is_empty() {
+        return Err("No crash report endpoint is configured".to_string());
+    }
+    let json = serde_json::to_string(report)
+        .map_err(|e| format!("Failed to serialize crash report: {}", e))?;
+    let agent = ureq::Agent::config_builder()
+        .timeout_global(Some(std::time::Duration::from_secs(SUBMIT_TIMEOUT_SECS)))
+        .build()
+        .new_agent();
+    agent
+        .post(endpoint)
+        .header("Content-Type", "application/json")
+        .header(
+            "User-Agent",
+            &format!("impulse/{}", crate::update::CURRENT_VERSION),
+        )
+        .send(json.as_str())
+        .map_err(|e| format!("Failed to submit crash report: {}", e))?;
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Recent log capture
+// ---------------------------------------------------------------------------
+
+fn recent_lines() -> &'static parking_lot::Mutex<VecDeque<String>> {
+    static LINES: OnceLock<parking_lot::Mutex<VecDeque<String>>> = OnceLock::new();
+    LINES.get_or_init(|| parking_lot::Mutex::new(VecDeque::with_capacity(RECENT_LOG_LINES)))
+}
+
+/// The last log lines recorded by [`RecentLogger`], oldest first.
+pub fn recent_log() -> Vec<String> {
+    // try_lock: a panic inside the logger must not deadlock the report.
+    recent_lines()
+        .try_lock()
+        .map(|lines| lines.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Wraps the app's logger (if it has one) and keeps the last lines for
+/// crash reports.
+pub struct RecentLogger {
+    inner: Option<Box<dyn log::Log>>,
+}
+
+impl RecentLogger {
+    /// Install as the global logger in front of `inner`. Records at
+    /// `max_level` and above are passed on; `Info` and above are kept even
+    /// when `inner` filters them out.
+    pub fn install(inner: Option<Box<dyn log::Log>>, max_level: log::LevelFilter) {
+        let level = max_level.max(log::LevelFilter::Info);
+        let logger: &'static RecentLogger = Box::leak(Box::new(RecentLogger { inner }));
+        if log::set_logger(logger).is_ok() {
+            log::set_max_level(level);
+        }
+    }
+}
+
+impl log::Log for RecentLogger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::Level::Info
+            || self
+                .inner
+                .as_ref()
+                .is_some_and(|inner| inner.enabled(metadata))
+    }
+
+    fn log(&self, record: &log::Record) {
+        if record.level() <= log::Level::Info {
+            let mut lines = recent_lines().lock();
+            if lines.len() == RECENT_LOG_LINES {
+                lines.pop_front();
+            }
+            lines.push_back(format!(
+                "{} {}: {}",
+                record.level(),
+                record.target(),
+                record.args()
+            ));
+        }
+        if let Some(inner) = self.inner.as_ref().filter(|i| i.enabled(record.metadata())) {
+            inner.log(record);
+        }
+    }
+
+    fn flush(&self) {
+        if let Some(inner) = &self.inner {
+            inner.flush();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unseen_reports_are_returned_once() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut report = CrashReport::new("panicked at src/main.rs:1:1:\nboom", "0: main");
+        write_report(dir.path(), &report).unwrap();
+        report.timestamp_ms += 1;
+        report.message = "second".to_string();
+        write_report(dir.path(), &report).unwrap();
+
+        let unseen = take_unseen(dir.path());
+        assert_eq!(unseen.len(), 2);
+        assert_eq!(unseen[0].message, "second");
+        assert!(take_unseen(dir.path()).is_empty());
+        assert_eq!(reports(dir.path()).len(), 2);
+    }
+
+    #[test]
+    fn old_reports_are_pruned() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut report = CrashReport::new("boom", "");
+        for i in 0..MAX_REPORTS as u64 + 3 {
+            report.timestamp_ms = 1_000 + i;
+            write_report(dir.path(), &report).unwrap();
+        }
+        let kept = reports(dir.path());
+        assert_eq!(kept.len(), MAX_REPORTS);
+        assert_eq!(kept[0].1.timestamp_ms, 1_000 + MAX_REPORTS as u64 + 2);
+    }
+}
@@ -0,0 +1,178 @@
+//! EditorConfig support: reads the `.editorconfig` files from a file's
+//! folder up to the filesystem root (or the first one with `root = true`)
+//! and resolves the properties that apply to the file.
+//!
+//! Nearer files override farther ones and, within a file, later sections
+//! override earlier ones, as the spec requires. Section globs support `*`,
+//! `**`, `?`, `[...]` and `{a,b}`; numeric ranges (`{1..3}`) never match.
+//! Only the properties Impulse acts on are kept.
+
+use std::path::{Path, PathBuf};
+
+use globset::GlobBuilder;
+
+const FILE_NAME: &str = ".editorconfig";
+
+/// The EditorConfig properties Impulse uses. `None` means no file sets the
+/// property (or it is `unset`), so the user's settings apply.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EditorConfig {
+    pub trim_trailing_whitespace: Option<bool>,
+    pub insert_final_newline: Option<bool>,
+}
+
+impl EditorConfig {
+    fn apply(&mut self, key: &str, value: &str) {
+        let flag = match value {
+            "true" => Some(true),
+            "false" => Some(false),
+            "unset" => None,
+            _ => return,
+        };
+        match key {
+            "trim_trailing_whitespace" => self.trim_trailing_whitespace = flag,
+            "insert_final_newline" => self.insert_final_newline = flag,
+            _ => {}
+        }
+    }
+}
+
+struct Section {
+    pattern: String,
+    properties: Vec<(String, String)>,
+}
+
+struct ConfigFile {
+    root: bool,
+    sections: Vec<Section>,
+}
+
+fn parse(text: &str) -> ConfigFile {
+    let mut file = ConfigFile {
+        root: false,
+        sections: Vec::new(),
+    };
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if let Some(pattern) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            file.sections.push(Section {
+                pattern: pattern.to_string(),
+                properties: Vec::new(),
+            });
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim().to_ascii_lowercase();
+        let value = value.trim().to_ascii_lowercase();
+        match file.sections.last_mut() {
+            Some(section) => section.properties.push((key, value)),
+            None if key == "root" => file.root = value == "true",
+            None => {}
+        }
+    }
+    file
+}
+
+/// Whether section `pattern` of an `.editorconfig` applies to `relative`,
+/// the file's path relative to that `.editorconfig`'s folder. Patterns
+/// without a `/` match the file name at any depth.
+fn section_matches(pattern: &str, relative: &Path) -> bool {
+    let pattern = match pattern.strip_prefix('/') {
+        Some(anchored) => anchored.to_string(),
+        None if pattern.contains('/') => pattern.to_string(),
+        None => format!("**/{}", pattern),
+    };
+    match GlobBuilder::new(&pattern).literal_separator(true).build() {
+        Ok(glob) => glob.compile_matcher().is_match(relative),
+        Err(e) => {
+            log::warn!("Ignoring .editorconfig section [{}]: {}", pattern, e);
+            false
+        }
+    }
+}
+
+/// The EditorConfig properties for the file at `path`.
+pub fn for_file(path: &Path) -> EditorConfig {
+    let mut files: Vec<(PathBuf, ConfigFile)> = Vec::new();
+    let mut dir = path.parent();
+    while let Some(d) = dir {
+        if let Ok(text) = std::fs::read_to_string(d.join(FILE_NAME)) {
+            let file = parse(&text);
+            let root = file.root;
+            files.push((d.to_path_buf(), file));
+            if root {
+                break;
+            }
+        }
+        dir = d.parent();
+    }
+
+    let mut config = EditorConfig::default();
+    for (dir, file) in files.iter().rev() {
+        let Ok(relative) = path.strip_prefix(dir) else {
+            continue;
+        };
+        for section in &file.sections {
+            if section_matches(&section.pattern, relative) {
+                for (key, value) in &section.properties {
+                    config.apply(key, value);
+                }
+            }
+        }
+    }
+    config
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nearer_files_and_later_sections_win() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        std::fs::write(
+            root.join(FILE_NAME),
+            "root = true\n\n[*]\ntrim_trailing_whitespace = true\ninsert_final_newline = true\n\n\
+             # Markdown uses trailing spaces for line breaks.\n[*.{md,markdown}]\n\
+             trim_trailing_whitespace = false\n",
+        )
+        .unwrap();
+        std::fs::create_dir_all(root.join("vendor/lib")).unwrap();
+        std::fs::write(
+            root.join("vendor").join(FILE_NAME),
+            "[lib/**]\ninsert_final_newline = unset\n",
+        )
+        .unwrap();
+
+        let rust = for_file(&root.join("src/main.rs"));
+        assert_eq!(rust.trim_trailing_whitespace, Some(true));
+        assert_eq!(rust.insert_final_newline, Some(true));
+
+        let readme = for_file(&root.join("docs/README.md"));
+        assert_eq!(readme.trim_trailing_whitespace, Some(false));
+
+        let vendored = for_file(&root.join("vendor/lib/a.c"));
+        assert_eq!(vendored.trim_trailing_whitespace, Some(true));
+        assert_eq!(vendored.insert_final_newline, None);
+        assert_eq!(
+            for_file(&root.join("vendor/b.c")).insert_final_newline,
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn anchored_patterns_match_from_the_config_folder() {
+        let relative = Path::new("src/gen/out.rs");
+        assert!(section_matches("*.rs", relative));
+        assert!(section_matches("src/**/*.rs", relative));
+        assert!(section_matches("/src/gen/*", relative));
+        assert!(!section_matches("/gen/*", relative));
+        assert!(!section_matches("src/*.rs", relative));
+    }
+}
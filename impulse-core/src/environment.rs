@@ -0,0 +1,400 @@
+//! The environment for processes Impulse starts on the user's behalf:
+//! language servers, formatters and commands on save.
+//!
+//! Launched from a dock or desktop file, Impulse doesn't inherit the PATH
+//! that nvm, asdf, pyenv and friends set up in shell startup files, so a
+//! server like `typescript-language-server` would find the wrong `node` (or
+//! none). The login shell's environment is captured once, in the
+//! background, and layered over the process environment. With `use_direnv`
+//! on, a trusted folder's `.envrc` (evaluated by `direnv export json`) is
+//! layered on top of that for processes started in the folder.
+//!
+//! Last come the project's own variables: `env` in a trusted folder's
+//! `.impulse/settings.json`, over the dotenv file named by its `env_file`.
+//! Terminals started in the folder get these too (they already run the
+//! login shell).
+
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant, SystemTime};
+
+use parking_lot::Mutex;
+use serde::Deserialize;
+
+use crate::ignore_rules::PROJECT_SETTINGS_FILE;
+
+/// Set in the shell that reports the login environment, so startup files
+/// can skip slow or interactive setup.
+pub const RESOLVING_ENV_VAR: &str = "IMPULSE_RESOLVING_ENVIRONMENT";
+
+const LOGIN_SHELL_TIMEOUT: Duration = Duration::from_secs(10);
+const DIRENV_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Brackets `env -0` output so startup-file noise before or after it is
+/// ignored.
+const MARKER: &str = "__IMPULSE_ENV_4f2b__";
+
+/// Variables that describe the capturing shell rather than the user's
+/// environment.
+const SKIPPED_VARS: &[&str] = &["_", "PWD", "OLDPWD", "SHLVL", RESOLVING_ENV_VAR];
+
+static DIRENV: AtomicBool = AtomicBool::new(false);
+
+/// Apply the `use_direnv` setting.
+pub fn set_direnv_enabled(enabled: bool) {
+    DIRENV.store(enabled, Ordering::Relaxed);
+}
+
+/// Start capturing the login shell environment so the first language
+/// server doesn't wait for it.
+pub fn warm() {
+    std::thread::spawn(|| {
+        login_env();
+    });
+}
+
+/// The login shell's environment, or empty when it couldn't be read.
+fn login_env() -> &'static HashMap<String, String> {
+    static LOGIN_ENV: OnceLock<HashMap<String, String>> = OnceLock::new();
+    LOGIN_ENV.get_or_init(|| {
+        let started = Instant::now();
+        let env = capture_login_env().unwrap_or_else(|e| {
+            log::warn!("Using Impulse's own environment: {}", e);
+            HashMap::new()
+        });
+        log::info!(
+            "Read {} login shell variables in {:?}",
+            env.len(),
+            started.elapsed()
+        );
+        env
+    })
+}
+
+fn capture_login_env() -> Result<HashMap<String, String>, String> {
+    let shell = crate::shell::get_default_shell_path();
+    let script = format!("printf '{0}'; env -0; printf '{0}'", MARKER);
+    let mut command = Command::new(&shell);
+    command
+        .args(["-l", "-i", "-c", &script])
+        .env(RESOLVING_ENV_VAR, "1")
+        .current_dir(dirs::home_dir().unwrap_or_else(|| PathBuf::from("/")));
+    let stdout = output_with_timeout(command, LOGIN_SHELL_TIMEOUT)
+        .map_err(|e| format!("{} didn't report its environment: {}", shell, e))?;
+    parse_env_block(&stdout).ok_or_else(|| format!("{} printed no environment", shell))
+}
+
+/// Run `command` and return its stdout, killing it after `timeout`.
+fn output_with_timeout(mut command: Command, timeout: Duration) -> Result<Vec<u8>, String> {
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| e.to_string())?;
+    let mut stdout = child.stdout.take().ok_or("no stdout")?;
+    let reader = std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = stdout.read_to_end(&mut buf);
+        buf
+    });
+    let deadline = Instant::now() + timeout;
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => {
+                let output = reader.join().unwrap_or_default();
+                return if status.success() {
+                    Ok(output)
+                } else {
+                    Err(format!("exited with {}", status))
+                };
+            }
+            Ok(None) if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(20)),
+            Ok(None) => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("timed out after {:?}", timeout));
+            }
+            Err(e) => return Err(e.to_string()),
+        }
+    }
+}
+
+/// Parse `NAME=value` entries separated by NULs between two markers.
+fn parse_env_block(output: &[u8]) -> Option<HashMap<String, String>> {
+    let output = String::from_utf8_lossy(output);
+    let start = output.find(MARKER)? + MARKER.len();
+    let end = start + output[start..].find(MARKER)?;
+    Some(
+        output[start..end]
+            .split('\0')
+            .filter_map(|entry| entry.split_once('='))
+            .filter(|(name, _)| !name.is_empty() && !SKIPPED_VARS.contains(name))
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect(),
+    )
+}
+
+/// `direnv export json` output, cached per `.envrc` until it changes.
+struct DirenvEntry {
+    modified: Option<SystemTime>,
+    diff: HashMap<String, Option<String>>,
+}
+
+fn direnv_cache() -> &'static Mutex<HashMap<PathBuf, DirenvEntry>> {
+    static CACHE: OnceLock<Mutex<HashMap<PathBuf, DirenvEntry>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// The changes `dir`'s `.envrc` makes to `base`, if direnv is on, the
+/// folder is trusted and direnv has been allowed to load it.
+fn direnv_diff(dir: &Path, base: &HashMap<String, String>) -> HashMap<String, Option<String>> {
+    if !DIRENV.load(Ordering::Relaxed) {
+        return HashMap::new();
+    }
+    let Some(envrc_dir) = dir.ancestors().find(|d| d.join(".envrc").is_file()) else {
+        return HashMap::new();
+    };
+    if !crate::trust::is_trusted(envrc_dir) {
+        return HashMap::new();
+    }
+    let modified = std::fs::metadata(envrc_dir.join(".envrc"))
+        .and_then(|m| m.modified())
+        .ok();
+    if let Some(entry) = direnv_cache().lock().get(envrc_dir) {
+        if entry.modified == modified {
+            return entry.diff.clone();
+        }
+    }
+
+    let mut command = Command::new("direnv");
+    command
+        .args(["export", "json"])
+        .env_clear()
+        .envs(base)
+        .current_dir(envrc_dir);
+    let diff = match output_with_timeout(command, DIRENV_TIMEOUT) {
+        // Empty output: nothing to change, or the .envrc isn't allowed.
+        Ok(stdout) if stdout.iter().all(u8::is_ascii_whitespace) => HashMap::new(),
+        Ok(stdout) => serde_json::from_slice(&stdout).unwrap_or_else(|e| {
+            log::warn!("Unexpected direnv output in {}: {}", envrc_dir.display(), e);
+            HashMap::new()
+        }),
+        Err(e) => {
+            log::warn!("direnv failed in {}: {}", envrc_dir.display(), e);
+            HashMap::new()
+        }
+    };
+    direnv_cache().lock().insert(
+        envrc_dir.to_path_buf(),
+        DirenvEntry {
+            modified,
+            diff: diff.clone(),
+        },
+    );
+    diff
+}
+
+/// The variables part of `.impulse/settings.json`.
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct ProjectEnv {
+    env: HashMap<String, String>,
+    /// A dotenv file to load, relative to the project root (e.g. ".env").
+    env_file: Option<String>,
+}
+
+/// The project variables for processes started in `dir` (a file or
+/// folder): the project's `env_file`, overridden by its `env`. Empty
+/// outside a project or in an untrusted folder.
+pub fn project_env(dir: &Path) -> HashMap<String, String> {
+    let Some(root) = dir
+        .ancestors()
+        .find(|d| d.join(PROJECT_SETTINGS_FILE).is_file())
+    else {
+        return HashMap::new();
+    };
+    if !crate::trust::is_trusted(root) {
+        return HashMap::new();
+    }
+    let settings_path = root.join(PROJECT_SETTINGS_FILE);
+    let Ok(contents) = std::fs::read_to_string(&settings_path) else {
+        return HashMap::new();
+    };
+    let project: ProjectEnv = serde_json::from_str(&contents).unwrap_or_else(|e| {
+        log::warn!(
+            "Invalid project settings at {}: {}",
+            settings_path.display(),
+            e
+        );
+        ProjectEnv::default()
+    });
+    let mut env = HashMap::new();
+    if let Some(env_file) = project.env_file.filter(|f| !f.is_empty()) {
+        let path = root.join(env_file);
+        match std::fs::read_to_string(&path) {
+            Ok(contents) => env.extend(parse_dotenv(&contents)),
+            Err(e) => log::warn!("Couldn't read {}: {}", path.display(), e),
+        }
+    }
+    env.extend(project.env);
+    env
+}
+
+/// Parse a dotenv file: `NAME=value` lines, optionally prefixed with
+/// `export`, with `#` comments and single- or double-quoted values.
+/// Variables are not expanded.
+fn parse_dotenv(contents: &str) -> Vec<(String, String)> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            if line.starts_with('#') {
+                return None;
+            }
+            let (name, value) = line.split_once('=')?;
+            let name = name.trim();
+            if name.is_empty() || name.contains(char::is_whitespace) {
+                return None;
+            }
+            let value = value.trim();
+            let value = if let Some(quoted) = value
+                .strip_prefix('"')
+                .and_then(|v| v.rsplit_once('"'))
+                .map(|(v, _)| v)
+            {
+                quoted.replace("\\n", "\n").replace("\\\"", "\"")
+            } else if let Some(quoted) = value
+                .strip_prefix('\'')
+                .and_then(|v| v.rsplit_once('\''))
+                .map(|(v, _)| v)
+            {
+                quoted.to_string()
+            } else {
+                // Unquoted: a ` #` starts a comment.
+                value
+                    .split_once(" #")
+                    .map_or(value, |(v, _)| v)
+                    .trim_end()
+                    .to_string()
+            };
+            Some((name.to_string(), value))
+        })
+        .collect()
+}
+
+/// The full environment for a process started in `dir` (a file or folder,
+/// or `None` for no particular folder).
+pub fn resolve(dir: Option<&Path>) -> HashMap<String, String> {
+    let mut env: HashMap<String, String> = std::env::vars_os()
+        .filter_map(|(name, value)| Some((name.into_string().ok()?, value.into_string().ok()?)))
+        .collect();
+    env.extend(
+        login_env()
+            .iter()
+            .map(|(name, value)| (name.clone(), value.clone())),
+    );
+    if let Some(dir) = dir {
+        let dir = if dir.is_file() {
+            dir.parent().unwrap_or(dir)
+        } else {
+            dir
+        };
+        for (name, value) in direnv_diff(dir, &env) {
+            match value {
+                Some(value) => env.insert(name, value),
+                None => env.remove(&name),
+            };
+        }
+        env.extend(project_env(dir));
+    }
+    env
+}
+
+/// The `PATH` from [`resolve`].
+pub fn search_path(dir: Option<&Path>) -> Option<String> {
+    resolve(dir).remove("PATH")
+}
+
+/// Give `command` the environment from [`resolve`].
+pub fn apply(command: &mut Command, dir: Option<&Path>) {
+    command.env_clear().envs(resolve(dir));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn env_block_is_read_between_markers() {
+        let output = format!(
+            "Welcome!\n{m}PATH=/opt/node/bin:/usr/bin\0EMPTY=\0MULTI=a\nb\0SHLVL=2\0{m}bye",
+            m = MARKER
+        );
+        let env = parse_env_block(output.as_bytes()).unwrap();
+        assert_eq!(env["PATH"], "/opt/node/bin:/usr/bin");
+        assert_eq!(env["EMPTY"], "");
+        assert_eq!(env["MULTI"], "a\nb");
+        assert!(!env.contains_key("SHLVL"));
+        assert_eq!(env.len(), 3);
+
+        assert!(parse_env_block(b"no markers here").is_none());
+    }
+
+    #[test]
+    fn dotenv_values_are_unquoted() {
+        let env = parse_dotenv(
+            "# comment\n\
+             PLAIN=value # trailing\n\
+             export EXPORTED=1\n\
+             DOUBLE=\"a b\\nc\"\n\
+             SINGLE='$NOT_EXPANDED'\n\
+             EMPTY=\n\
+             not a variable\n",
+        );
+        assert_eq!(
+            env,
+            [
+                ("PLAIN", "value"),
+                ("EXPORTED", "1"),
+                ("DOUBLE", "a b\nc"),
+                ("SINGLE", "$NOT_EXPANDED"),
+                ("EMPTY", ""),
+            ]
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+        );
+    }
+
+    #[test]
+    fn project_env_needs_trust() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join(".impulse")).unwrap();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(
+            root.join(PROJECT_SETTINGS_FILE),
+            r#"{"env": {"MODE": "dev"}, "env_file": ".env"}"#,
+        )
+        .unwrap();
+        std::fs::write(root.join(".env"), "MODE=prod\nTOKEN=abc\n").unwrap();
+
+        assert!(project_env(&root.join("src")).is_empty());
+
+        crate::trust::trust_for_test(root);
+        let env = project_env(&root.join("src"));
+        assert_eq!(env["MODE"], "dev");
+        assert_eq!(env["TOKEN"], "abc");
+    }
+
+    #[test]
+    fn direnv_is_off_by_default() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(".envrc"), "export FOO=bar\n").unwrap();
+        assert!(direnv_diff(dir.path(), &HashMap::new()).is_empty());
+    }
+}
@@ -0,0 +1,217 @@
+//! Creating, renaming and duplicating files from the sidebar. New files
+//! start from the first matching template in `Settings::file_templates`,
+//! and duplicates are named like a file manager's: `"notes copy.md"`, then
+//! `"notes copy 2.md"`.
+
+use std::path::{Path, PathBuf};
+
+use crate::settings::FileTemplate;
+use crate::util::matches_file_pattern;
+
+/// The trimmed `name`, or why it can't name a file in a directory.
+pub fn validate_name(name: &str) -> Result<&str, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Name is empty".to_string());
+    }
+    if name == "." || name == ".." || name.contains(['/', '\\', '\0']) {
+        return Err(format!(
+            "\"{name}\" isn't a valid name: it can't be a dot entry or contain slashes"
+        ));
+    }
+    Ok(name)
+}
+
+/// The starting content for a new file at `path`: the first template whose
+/// pattern matches, with `${filename}` replaced by the file name and
+/// `${name}` by the name without its extension. Empty when none match.
+pub fn template_content(templates: &[FileTemplate], path: &str) -> String {
+    let Some(template) = templates
+        .iter()
+        .find(|t| matches_file_pattern(path, &t.pattern))
+    else {
+        return String::new();
+    };
+    let path = Path::new(path);
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let stem = path
+        .file_stem()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    template
+        .content
+        .replace("${filename}", &file_name)
+        .replace("${name}", &stem)
+}
+
+/// Create the file `name` in `dir` with its template content. Fails if
+/// something of that name already exists.
+pub fn create_file(dir: &Path, name: &str, templates: &[FileTemplate]) -> Result<PathBuf, String> {
+    let path = dir.join(validate_name(name)?);
+    let content = template_content(templates, &path.to_string_lossy());
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&path)
+        .map_err(|e| format!("Failed to create {}: {e}", path.display()))?;
+    std::io::Write::write_all(&mut file, content.as_bytes())
+        .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+    Ok(path)
+}
+
+/// Create the folder `name` in `dir`.
+pub fn create_dir(dir: &Path, name: &str) -> Result<PathBuf, String> {
+    let path = dir.join(validate_name(name)?);
+    std::fs::create_dir(&path).map_err(|e| format!("Failed to create {}: {e}", path.display()))?;
+    Ok(path)
+}
+
+/// Rename `path` to `new_name` in the same directory, without replacing
+/// anything already there.
+pub fn rename(path: &Path, new_name: &str) -> Result<PathBuf, String> {
+    let new_name = validate_name(new_name)?;
+    let parent = path
+        .parent()
+        .ok_or_else(|| format!("Can't rename {}", path.display()))?;
+    let new_path = parent.join(new_name);
+    if new_path == path {
+        return Ok(new_path);
+    }
+    // A case-only rename on a case-insensitive disk finds the old file.
+    let same_file = path
+        .file_name()
+        .is_some_and(|old| old.to_string_lossy().to_lowercase() == new_name.to_lowercase());
+    if new_path.symlink_metadata().is_ok() && !same_file {
+        return Err(format!("\"{new_name}\" already exists"));
+    }
+    std::fs::rename(path, &new_path)
+        .map_err(|e| format!("Failed to rename {}: {e}", path.display()))?;
+    Ok(new_path)
+}
+
+/// The first free name for a copy of `path` next to it.
+pub fn duplicate_path(path: &Path) -> PathBuf {
+    let parent = path.parent().unwrap_or(Path::new(""));
+    let is_dir = path.is_dir();
+    let (stem, ext) = match (path.file_stem(), path.extension()) {
+        (Some(stem), Some(ext)) if !is_dir => (
+            stem.to_string_lossy().to_string(),
+            format!(".{}", ext.to_string_lossy()),
+        ),
+        _ => (
+            path.file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            String::new(),
+        ),
+    };
+    let mut n = 1;
+    loop {
+        let suffix = if n == 1 {
+            " copy".to_string()
+        } else {
+            format!(" copy {n}")
+        };
+        let candidate = parent.join(format!("{stem}{suffix}{ext}"));
+        if candidate.symlink_metadata().is_err() {
+            return candidate;
+        }
+        n += 1;
+    }
+}
+
+/// Copy the file or folder at `path` to `duplicate_path(path)`.
+pub fn duplicate(path: &Path) -> Result<PathBuf, String> {
+    if path.symlink_metadata().is_err() {
+        return Err(format!("{} doesn't exist", path.display()));
+    }
+    let target = duplicate_path(path);
+    copy_recursive(path, &target)
+        .map_err(|e| format!("Failed to duplicate {}: {e}", path.display()))?;
+    Ok(target)
+}
+
+fn copy_recursive(src: &Path, dst: &Path) -> std::io::Result<()> {
+    let meta = src.symlink_metadata()?;
+    if meta.file_type().is_symlink() {
+        let target = std::fs::read_link(src)?;
+        #[cfg(unix)]
+        return std::os::unix::fs::symlink(target, dst);
+        #[cfg(not(unix))]
+        return std::fs::copy(src, dst).map(|_| ());
+    }
+    if meta.is_dir() {
+        std::fs::create_dir(dst)?;
+        for entry in std::fs::read_dir(src)? {
+            let entry = entry?;
+            copy_recursive(&entry.path(), &dst.join(entry.file_name()))?;
+        }
+        return Ok(());
+    }
+    std::fs::copy(src, dst).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_files_start_from_the_matching_template() {
+        let dir = tempfile::tempdir().unwrap();
+        let templates = vec![
+            FileTemplate {
+                pattern: "*.rs".to_string(),
+                content: "//! ${name} (${filename})\n".to_string(),
+            },
+            FileTemplate {
+                pattern: "*".to_string(),
+                content: "fallback".to_string(),
+            },
+        ];
+
+        let path = create_file(dir.path(), " parser.rs ", &templates).unwrap();
+        assert_eq!(path, dir.path().join("parser.rs"));
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "//! parser (parser.rs)\n"
+        );
+        assert_eq!(template_content(&templates, "/x/notes.md"), "fallback");
+        assert_eq!(template_content(&[], "/x/notes.md"), "");
+
+        assert!(create_file(dir.path(), "parser.rs", &templates).is_err());
+        assert!(create_file(dir.path(), "../escape", &templates).is_err());
+        assert!(create_dir(dir.path(), "..").is_err());
+        assert!(create_dir(dir.path(), "src").unwrap().is_dir());
+    }
+
+    #[test]
+    fn renames_and_duplicates_next_to_the_original() {
+        let dir = tempfile::tempdir().unwrap();
+        let notes = dir.path().join("notes.md");
+        std::fs::write(&notes, "hi").unwrap();
+        std::fs::write(dir.path().join("taken.md"), "").unwrap();
+
+        assert!(rename(&notes, "taken.md").is_err());
+        let renamed = rename(&notes, "todo.md").unwrap();
+        assert_eq!(std::fs::read_to_string(&renamed).unwrap(), "hi");
+        assert!(!notes.exists());
+
+        let copy = duplicate(&renamed).unwrap();
+        assert_eq!(copy, dir.path().join("todo copy.md"));
+        assert_eq!(std::fs::read_to_string(&copy).unwrap(), "hi");
+        assert_eq!(
+            duplicate(&renamed).unwrap(),
+            dir.path().join("todo copy 2.md")
+        );
+
+        let src = dir.path().join("src.d");
+        std::fs::create_dir(&src).unwrap();
+        std::fs::write(src.join("main.rs"), "fn main() {}").unwrap();
+        let src_copy = duplicate(&src).unwrap();
+        assert_eq!(src_copy, dir.path().join("src.d copy"));
+        assert!(src_copy.join("main.rs").is_file());
+    }
+}
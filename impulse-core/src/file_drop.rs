@@ -0,0 +1,104 @@
+//! Files dropped on the window from a file manager or another app: the
+//! paths in a `text/uri-list` payload, how a terminal inserts them, and
+//! when a drop on the file tree opens a folder as the workspace.
+
+use std::path::{Path, PathBuf};
+
+/// The local paths in `text/uri-list` data (RFC 2483): one URI per line,
+/// `#` comments and blank lines skipped. Non-`file:` URIs are dropped;
+/// bare absolute paths, which some apps send as text, are kept.
+pub fn parse_uri_list(text: &str) -> Vec<PathBuf> {
+    text.lines()
+        .map(|line| line.trim_end_matches('\r').trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            if line.starts_with("file:") {
+                let path = crate::util::uri_to_file_path(line);
+                (!path.starts_with("file:")).then(|| PathBuf::from(path))
+            } else if line.starts_with('/') {
+                Some(PathBuf::from(line))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// `paths` as shell words for a terminal's command line, quoted where
+/// needed and separated by spaces, with a trailing space so the user can
+/// keep typing.
+pub fn shell_quoted_paths(paths: &[PathBuf]) -> String {
+    let mut words = String::new();
+    for path in paths {
+        words.push_str(&shell_quote(&path.to_string_lossy()));
+        words.push(' ');
+    }
+    words
+}
+
+/// `s` as a single POSIX shell word, left bare when it has no special
+/// characters.
+fn shell_quote(s: &str) -> String {
+    let safe = |c: char| c.is_alphanumeric() || matches!(c, '/' | '_' | '.' | '-' | '+' | ',');
+    if !s.is_empty() && s.chars().all(safe) {
+        return s.to_string();
+    }
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+/// The folder to open as the workspace when `paths` are dropped on the file
+/// tree outside any row: a single dropped directory. Other drops copy into
+/// the tree.
+pub fn workspace_folder(paths: &[PathBuf]) -> Option<&Path> {
+    match paths {
+        [path] if path.is_dir() => Some(path),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_file_uris_and_skips_comments() {
+        let list = "# dragged from Files\r\nfile:///home/me/My%20Notes.md\r\n\
+                    https://example.com/x\r\n\r\nfile://localhost/tmp/a.txt\r\n/etc/hosts\n";
+        assert_eq!(
+            parse_uri_list(list),
+            vec![
+                PathBuf::from("/home/me/My Notes.md"),
+                PathBuf::from("/tmp/a.txt"),
+                PathBuf::from("/etc/hosts"),
+            ]
+        );
+        assert!(parse_uri_list("just some text").is_empty());
+    }
+
+    #[test]
+    fn quotes_paths_for_the_shell() {
+        let paths = [
+            PathBuf::from("/src/main.rs"),
+            PathBuf::from("/home/me/My Notes.md"),
+            PathBuf::from("/tmp/it's"),
+        ];
+        assert_eq!(
+            shell_quoted_paths(&paths),
+            "/src/main.rs '/home/me/My Notes.md' '/tmp/it'\\''s' "
+        );
+    }
+
+    #[test]
+    fn a_single_folder_opens_as_the_workspace() {
+        let temp = tempfile::tempdir().unwrap();
+        let file = temp.path().join("a.txt");
+        std::fs::write(&file, "").unwrap();
+        let dir = temp.path().to_path_buf();
+        assert_eq!(
+            workspace_folder(std::slice::from_ref(&dir)),
+            Some(dir.as_path())
+        );
+        assert_eq!(workspace_folder(std::slice::from_ref(&file)), None);
+        assert_eq!(workspace_folder(&[dir.clone(), dir.clone()]), None);
+    }
+}
@@ -0,0 +1,301 @@
+//! File metadata for the sidebar's Properties dialog: size, modified time,
+//! permissions, owner and symlink target, plus the line count and encoding
+//! of text files. On Unix the dialog edits the permission bits through
+//! [`set_mode`].
+
+use std::io::Read;
+use std::path::Path;
+
+use serde::Serialize;
+
+/// Files larger than this get no line count or encoding.
+const MAX_TEXT_SCAN_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Bytes read to guess a file's encoding.
+const ENCODING_SAMPLE_SIZE: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileKind {
+    File,
+    Directory,
+    Symlink,
+    Other,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileProperties {
+    pub path: String,
+    pub name: String,
+    /// What `path` itself is; a symlink's other fields describe its target,
+    /// or the link when the target is missing.
+    pub kind: FileKind,
+    pub size: u64,
+    /// Seconds since the Unix epoch.
+    pub modified: Option<u64>,
+    /// Permission bits (`0o755`), on Unix only.
+    pub mode: Option<u32>,
+    /// Owner and group names, or their numeric ids when unnamed.
+    pub owner: Option<String>,
+    pub group: Option<String>,
+    pub symlink_target: Option<String>,
+    /// Lines and encoding of a regular file; no line count for binary files.
+    pub line_count: Option<u64>,
+    pub encoding: Option<String>,
+}
+
+/// Read the properties of `path`.
+pub fn read(path: &Path) -> Result<FileProperties, String> {
+    let link_meta = std::fs::symlink_metadata(path)
+        .map_err(|e| format!("Cannot read {}: {e}", path.display()))?;
+    let is_symlink = link_meta.file_type().is_symlink();
+    let symlink_target = is_symlink
+        .then(|| std::fs::read_link(path).ok())
+        .flatten()
+        .map(|target| target.to_string_lossy().to_string());
+    let meta = if is_symlink {
+        std::fs::metadata(path).unwrap_or(link_meta)
+    } else {
+        link_meta
+    };
+    let kind = if is_symlink {
+        FileKind::Symlink
+    } else if meta.is_dir() {
+        FileKind::Directory
+    } else if meta.is_file() {
+        FileKind::File
+    } else {
+        FileKind::Other
+    };
+    let modified = meta
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs());
+
+    let (line_count, encoding) = if meta.is_file() && meta.len() <= MAX_TEXT_SCAN_SIZE {
+        scan_text(path).unwrap_or((None, None))
+    } else {
+        (None, None)
+    };
+
+    #[cfg(unix)]
+    let (mode, owner, group) = {
+        use std::os::unix::fs::MetadataExt;
+        (
+            Some(meta.mode() & 0o7777),
+            Some(id_name("/etc/passwd", meta.uid())),
+            Some(id_name("/etc/group", meta.gid())),
+        )
+    };
+    #[cfg(not(unix))]
+    let (mode, owner, group) = (None, None, None);
+
+    Ok(FileProperties {
+        path: path.to_string_lossy().to_string(),
+        name: path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.to_string_lossy().to_string()),
+        kind,
+        size: if meta.is_file() { meta.len() } else { 0 },
+        modified,
+        mode,
+        owner,
+        group,
+        symlink_target,
+        line_count,
+        encoding,
+    })
+}
+
+/// Set the permission bits of `path` to `mode`.
+#[cfg(unix)]
+pub fn set_mode(path: &Path, mode: u32) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode & 0o7777))
+        .map_err(|e| format!("Failed to change permissions of {}: {e}", path.display()))
+}
+
+#[cfg(not(unix))]
+pub fn set_mode(_path: &Path, _mode: u32) -> Result<(), String> {
+    Err("Permissions can only be changed on Unix".to_string())
+}
+
+/// `ls -l` style permissions for `mode`, like `"rwxr-xr--"`.
+pub fn mode_string(mode: u32) -> String {
+    let mut s = String::with_capacity(9);
+    for shift in [6, 3, 0] {
+        let bits = (mode >> shift) & 0o7;
+        s.push(if bits & 0o4 != 0 { 'r' } else { '-' });
+        s.push(if bits & 0o2 != 0 { 'w' } else { '-' });
+        s.push(if bits & 0o1 != 0 { 'x' } else { '-' });
+    }
+    s
+}
+
+/// A file size for display, like `"2.0 KiB (2,048 bytes)"`.
+pub fn size_label(bytes: u64) -> String {
+    const KIB: f64 = 1024.0;
+    let exact = bytes
+        .to_string()
+        .as_bytes()
+        .rchunks(3)
+        .rev()
+        .map(|digits| std::str::from_utf8(digits).unwrap_or_default())
+        .collect::<Vec<_>>()
+        .join(",");
+    let scaled = bytes as f64;
+    if scaled >= KIB * KIB * KIB {
+        format!("{:.1} GiB ({exact} bytes)", scaled / (KIB * KIB * KIB))
+    } else if scaled >= KIB * KIB {
+        format!("{:.1} MiB ({exact} bytes)", scaled / (KIB * KIB))
+    } else if scaled >= KIB {
+        format!("{:.1} KiB ({exact} bytes)", scaled / KIB)
+    } else if bytes == 1 {
+        "1 byte".to_string()
+    } else {
+        format!("{bytes} bytes")
+    }
+}
+
+/// The name for `id` in an `/etc/passwd` style file, or the id itself.
+#[cfg(unix)]
+fn id_name(file: &str, id: u32) -> String {
+    let id = id.to_string();
+    std::fs::read_to_string(file)
+        .ok()
+        .and_then(|contents| {
+            contents.lines().find_map(|line| {
+                let mut fields = line.split(':');
+                let name = fields.next()?;
+                (fields.nth(1)? == id).then(|| name.to_string())
+            })
+        })
+        .unwrap_or(id)
+}
+
+/// The line count and encoding of the file at `path`. Binary files get an
+/// encoding of `"Binary"` and no line count.
+fn scan_text(path: &Path) -> std::io::Result<(Option<u64>, Option<String>)> {
+    let mut file = std::fs::File::open(path)?;
+    let mut buf = vec![0u8; ENCODING_SAMPLE_SIZE];
+    let mut filled = 0;
+    while filled < buf.len() {
+        match file.read(&mut buf[filled..])? {
+            0 => break,
+            n => filled += n,
+        }
+    }
+    buf.truncate(filled);
+    let encoding = guess_encoding(&buf, filled < ENCODING_SAMPLE_SIZE);
+    if encoding == "Binary" {
+        return Ok((None, Some(encoding.to_string())));
+    }
+
+    let mut lines = memchr::memchr_iter(b'\n', &buf).count() as u64;
+    let mut last = buf.last().copied();
+    let mut chunk = vec![0u8; ENCODING_SAMPLE_SIZE];
+    loop {
+        let n = file.read(&mut chunk)?;
+        if n == 0 {
+            break;
+        }
+        lines += memchr::memchr_iter(b'\n', &chunk[..n]).count() as u64;
+        last = Some(chunk[n - 1]);
+    }
+    // A last line without a newline still counts.
+    if last.is_some_and(|b| b != b'\n') {
+        lines += 1;
+    }
+    Ok((Some(lines), Some(encoding.to_string())))
+}
+
+/// Guess the encoding of a file from its first bytes, `complete` when they
+/// are the whole file.
+fn guess_encoding(sample: &[u8], complete: bool) -> &'static str {
+    if sample.starts_with(&[0xEF, 0xBB, 0xBF]) {
+        return "UTF-8 with BOM";
+    }
+    if sample.starts_with(&[0xFF, 0xFE]) {
+        return "UTF-16 LE";
+    }
+    if sample.starts_with(&[0xFE, 0xFF]) {
+        return "UTF-16 BE";
+    }
+    if sample.contains(&0) {
+        return "Binary";
+    }
+    match std::str::from_utf8(sample) {
+        Ok(text) if text.is_ascii() => "ASCII",
+        Ok(_) => "UTF-8",
+        // The sample may end partway through a character.
+        Err(e) if e.error_len().is_none() && !complete => "UTF-8",
+        Err(_) => "Unknown (not UTF-8)",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_text_file_properties() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.md");
+        std::fs::write(&path, "one\ntwo\nthrée").unwrap();
+
+        let props = read(&path).unwrap();
+        assert_eq!(props.name, "notes.md");
+        assert_eq!(props.kind, FileKind::File);
+        assert_eq!(props.size, 14);
+        assert_eq!(props.line_count, Some(3));
+        assert_eq!(props.encoding.as_deref(), Some("UTF-8"));
+        assert!(props.modified.is_some());
+
+        let bin = dir.path().join("blob.bin");
+        std::fs::write(&bin, [1u8, 0, 2]).unwrap();
+        let props = read(&bin).unwrap();
+        assert_eq!(props.line_count, None);
+        assert_eq!(props.encoding.as_deref(), Some("Binary"));
+
+        assert_eq!(read(dir.path()).unwrap().kind, FileKind::Directory);
+        assert!(read(&dir.path().join("missing")).is_err());
+
+        assert_eq!(size_label(14), "14 bytes");
+        assert_eq!(size_label(2048), "2.0 KiB (2,048 bytes)");
+        assert_eq!(size_label(5 * 1024 * 1024), "5.0 MiB (5,242,880 bytes)");
+
+        assert_eq!(guess_encoding(b"plain\n", true), "ASCII");
+        assert_eq!(
+            guess_encoding(&[0xEF, 0xBB, 0xBF, b'x'], true),
+            "UTF-8 with BOM"
+        );
+        assert_eq!(guess_encoding(&[b'a', 0xC3], false), "UTF-8");
+        assert_eq!(guess_encoding(&[b'a', 0xC3], true), "Unknown (not UTF-8)");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn reads_and_sets_permissions() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run.sh");
+        std::fs::write(&path, "#!/bin/sh\n").unwrap();
+        let link = dir.path().join("link");
+        std::os::unix::fs::symlink(&path, &link).unwrap();
+
+        set_mode(&path, 0o750).unwrap();
+        let props = read(&path).unwrap();
+        assert_eq!(props.mode, Some(0o750));
+        assert_eq!(mode_string(0o750), "rwxr-x---");
+        assert!(props.owner.is_some_and(|o| !o.is_empty()));
+
+        let props = read(&link).unwrap();
+        assert_eq!(props.kind, FileKind::Symlink);
+        assert_eq!(
+            props.symlink_target,
+            Some(path.to_string_lossy().to_string())
+        );
+        assert_eq!(props.line_count, Some(1));
+    }
+}
@@ -12,9 +12,13 @@ pub struct FileTreeNode {
     pub path: String,
     pub is_dir: bool,
     pub is_symlink: bool,
+    #[serde(default)]
+    pub symlink_target: Option<String>,
     pub size: u64,
     pub modified: u64,
     pub git_status: Option<String>,
+    #[serde(default)]
+    pub is_ignored: bool,
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
@@ -66,7 +70,7 @@ pub struct FileTreeWatchEvent {
 }
 
 pub fn stable_node_id(path: &str) -> String {
-    let trimmed = path.trim_end_matches(|ch| ch == '/' || ch == '\\');
+    let trimmed = path.trim_end_matches(['/', '\\']);
     if trimmed.is_empty() {
         path.to_string()
     } else {
@@ -149,9 +153,11 @@ pub fn node_from_entry(parent_path: &str, entry: &FileEntry) -> FileTreeNode {
         path: entry.path.clone(),
         is_dir: entry.is_dir,
         is_symlink: entry.is_symlink,
+        symlink_target: entry.symlink_target.clone(),
         size: entry.size,
         modified: entry.modified,
         git_status: entry.git_status.clone(),
+        is_ignored: entry.is_ignored,
     }
 }
 
@@ -280,7 +286,9 @@ pub fn reconcile_view_state_for_batch(
 }
 
 fn requires_replacement(before: &FileTreeNode, after: &FileTreeNode) -> bool {
-    before.is_dir != after.is_dir || before.is_symlink != after.is_symlink
+    before.is_dir != after.is_dir
+        || before.is_symlink != after.is_symlink
+        || before.symlink_target != after.symlink_target
 }
 
 fn affected_parent_paths_with_snapshots(
@@ -354,6 +362,25 @@ fn is_removed_or_descendant(id: &str, removed_ids: &HashSet<&str>) -> bool {
         .any(|removed_id| id == *removed_id || path_is_descendant(id, removed_id))
 }
 
+/// The directories to expand, outermost first, to show `path` in a tree
+/// rooted at `root`. `None` when `path` is not inside `root`.
+pub fn reveal_ancestors(root: &str, path: &str) -> Option<Vec<String>> {
+    let root = Path::new(root);
+    let relative = Path::new(path).strip_prefix(root).ok()?;
+    let mut dir = root.to_path_buf();
+    let mut components: Vec<_> = relative.components().collect();
+    components.pop()?;
+    Some(
+        components
+            .into_iter()
+            .map(|component| {
+                dir.push(component);
+                dir.to_string_lossy().into_owned()
+            })
+            .collect(),
+    )
+}
+
 fn path_is_descendant(path: &str, ancestor: &str) -> bool {
     PathBuf::from(path).starts_with(ancestor) && path != ancestor
 }
@@ -368,9 +395,11 @@ mod tests {
             path: path.to_string(),
             is_dir,
             is_symlink: false,
+            symlink_target: None,
             size: 10,
             modified: 20,
             git_status: None,
+            is_ignored: false,
         }
     }
 
@@ -711,4 +740,19 @@ mod tests {
         assert_eq!(reconciled.selected_id, None);
         assert_eq!(reconciled.scroll_offset, 99.0);
     }
+
+    #[test]
+    fn reveal_ancestors_lists_directories_to_expand() {
+        assert_eq!(
+            reveal_ancestors("/repo", "/repo/src/ui/app.rs"),
+            Some(vec!["/repo/src".to_string(), "/repo/src/ui".to_string()])
+        );
+        assert_eq!(
+            reveal_ancestors("/repo/", "/repo/main.rs"),
+            Some(Vec::new())
+        );
+        assert_eq!(reveal_ancestors("/repo", "/repo"), None);
+        assert_eq!(reveal_ancestors("/repo", "/other/main.rs"), None);
+        assert_eq!(reveal_ancestors("/repo", "/repository/main.rs"), None);
+    }
 }
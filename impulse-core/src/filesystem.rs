@@ -9,9 +9,52 @@ pub struct FileEntry {
     pub path: String,
     pub is_dir: bool,
     pub is_symlink: bool,
+    /// Where a symlink points, as written in the link.
+    #[serde(default)]
+    pub symlink_target: Option<String>,
     pub size: u64,
     pub modified: u64,
     pub git_status: Option<String>,
+    /// Whether the entry is gitignored (see [`crate::ignore_rules`]).
+    #[serde(default)]
+    pub is_ignored: bool,
+}
+
+impl FileEntry {
+    /// What a screen reader announces for the entry's file tree row, e.g.
+    /// `main.rs, file, modified`. Whether a folder is expanded is a separate
+    /// accessibility state.
+    pub fn accessible_label(&self) -> String {
+        let kind = match (self.is_dir, self.is_symlink) {
+            (true, false) => "folder",
+            (true, true) => "folder link",
+            (false, false) => "file",
+            (false, true) => "file link",
+        };
+        let mut label = format!("{}, {}", self.name, kind);
+        if let Some(status) = self.git_status.as_deref().and_then(git_status_name) {
+            label.push_str(", ");
+            label.push_str(status);
+        } else if self.is_ignored {
+            label.push_str(", ignored");
+        }
+        label
+    }
+}
+
+/// The word for a git status code (`M` → `modified`), for labels that
+/// can't rely on badge letters or colors.
+pub fn git_status_name(code: &str) -> Option<&'static str> {
+    match code {
+        "M" => Some("modified"),
+        "A" => Some("added"),
+        "?" => Some("untracked"),
+        "D" => Some("deleted"),
+        "R" => Some("renamed"),
+        "C" => Some("conflicted"),
+        "I" => Some("ignored"),
+        _ => None,
+    }
 }
 
 /// Read directory contents, sorted: directories first, then files, alphabetical within each group.
@@ -22,6 +65,11 @@ pub fn read_directory_entries(path: &str, show_hidden: bool) -> Result<Vec<FileE
     }
 
     let mut entries = Vec::new();
+    let ignore = crate::ignore_rules::IgnoreMatcher::new(&dir_path);
+    let excludes = crate::ignore_rules::ExcludeMatcher::new(
+        &dir_path,
+        crate::ignore_rules::ExcludeScope::Files,
+    );
     let read_dir =
         fs::read_dir(&dir_path).map_err(|e| format!("Failed to read directory: {}", e))?;
 
@@ -44,20 +92,28 @@ pub fn read_directory_entries(path: &str, show_hidden: bool) -> Result<Vec<FileE
             continue;
         }
 
-        let metadata = match entry.metadata() {
-            Ok(m) => m,
+        let file_type = match entry.file_type() {
+            Ok(ft) => ft,
             Err(e) => {
-                log::warn!("Skipping entry '{}': failed to read metadata: {}", name, e);
+                log::warn!("Skipping entry '{}': failed to get file type: {}", name, e);
                 continue;
             }
         };
-        let file_type = match entry.file_type() {
-            Ok(ft) => ft,
+        // A symlink lists as what it points to, so linked folders expand;
+        // a broken link lists as the link itself.
+        let metadata = match entry.metadata() {
+            Ok(m) if file_type.is_symlink() => fs::metadata(entry.path()).unwrap_or(m),
+            Ok(m) => m,
             Err(e) => {
-                log::warn!("Skipping entry '{}': failed to get file type: {}", name, e);
+                log::warn!("Skipping entry '{}': failed to read metadata: {}", name, e);
                 continue;
             }
         };
+        let symlink_target = file_type
+            .is_symlink()
+            .then(|| fs::read_link(entry.path()).ok())
+            .flatten()
+            .map(|target| target.to_string_lossy().to_string());
 
         let modified = metadata
             .modified()
@@ -66,14 +122,20 @@ pub fn read_directory_entries(path: &str, show_hidden: bool) -> Result<Vec<FileE
             .map(|d| d.as_secs())
             .unwrap_or(0);
 
+        let path = entry.path();
+        if excludes.is_excluded(&path, metadata.is_dir()) {
+            continue;
+        }
         entries.push(FileEntry {
             name,
-            path: entry.path().to_string_lossy().to_string(),
+            path: path.to_string_lossy().to_string(),
             is_dir: metadata.is_dir(),
             is_symlink: file_type.is_symlink(),
+            symlink_target,
             size: metadata.len(),
             modified,
             git_status: None,
+            is_ignored: ignore.is_ignored(&path, metadata.is_dir()),
         });
     }
 
@@ -391,6 +453,47 @@ mod tests {
     use super::*;
     use std::fs;
 
+    #[cfg(unix)]
+    #[test]
+    fn symlinks_list_as_their_targets_with_the_link_text() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("src")).unwrap();
+        std::os::unix::fs::symlink("src", dir.path().join("linked")).unwrap();
+        std::os::unix::fs::symlink("..", dir.path().join("src/up")).unwrap();
+        std::os::unix::fs::symlink("missing", dir.path().join("broken")).unwrap();
+
+        let entries = read_directory_entries(dir.path().to_str().unwrap(), false).unwrap();
+        let linked = entries.iter().find(|e| e.name == "linked").unwrap();
+        assert!(linked.is_dir && linked.is_symlink);
+        assert_eq!(linked.symlink_target.as_deref(), Some("src"));
+        let broken = entries.iter().find(|e| e.name == "broken").unwrap();
+        assert!(!broken.is_dir && broken.is_symlink);
+    }
+
+    #[test]
+    fn accessible_labels_spell_out_kind_and_status() {
+        let entry = |name: &str, is_dir, git_status: Option<&str>| FileEntry {
+            name: name.to_string(),
+            path: format!("/p/{name}"),
+            is_dir,
+            is_symlink: false,
+            symlink_target: None,
+            size: 0,
+            modified: 0,
+            git_status: git_status.map(str::to_string),
+            is_ignored: false,
+        };
+        assert_eq!(
+            entry("main.rs", false, Some("M")).accessible_label(),
+            "main.rs, file, modified"
+        );
+        assert_eq!(entry("src", true, None).accessible_label(), "src, folder");
+        let mut target = entry("target", true, None);
+        target.is_ignored = true;
+        target.is_symlink = true;
+        assert_eq!(target.accessible_label(), "target, folder link, ignored");
+    }
+
     #[test]
     fn non_git_directory_returns_empty_map() {
         let dir = tempfile::tempdir().unwrap();
@@ -0,0 +1,426 @@
+//! External formatters (prettier, rustfmt, black, ...) run over stdin and
+//! stdout, for languages without LSP formatting or where the user prefers a
+//! dedicated tool.
+//!
+//! The registry is [`builtin_formatters`] plus the `formatters` setting; a
+//! user entry with a built-in's name replaces it. `default_formatters` picks
+//! the formatter per language (see [`choose`]).
+
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use crate::settings::{ExternalFormatter, Settings};
+
+/// `default_formatters` value that restricts a language to LSP formatting.
+pub const LSP_FORMATTER: &str = "lsp";
+
+/// Formatters are killed after this long; matches the editor's own timeout
+/// for formatting requests.
+const FORMAT_TIMEOUT: Duration = Duration::from_secs(10);
+
+fn builtin(name: &str, command: &str, args: &[&str], languages: &[&str]) -> ExternalFormatter {
+    ExternalFormatter {
+        name: name.to_string(),
+        command: command.to_string(),
+        args: args.iter().map(|a| a.to_string()).collect(),
+        languages: languages.iter().map(|l| l.to_string()).collect(),
+    }
+}
+
+/// The formatters Impulse knows out of the box. `{file}` in an argument is
+/// replaced with the path of the file being formatted.
+pub fn builtin_formatters() -> Vec<ExternalFormatter> {
+    vec![
+        builtin(
+            "prettier",
+            "prettier",
+            &["--stdin-filepath", "{file}"],
+            &[
+                "javascript",
+                "javascriptreact",
+                "typescript",
+                "typescriptreact",
+                "json",
+                "jsonc",
+                "css",
+                "scss",
+                "less",
+                "html",
+                "vue",
+                "graphql",
+                "yaml",
+            ],
+        ),
+        builtin("rustfmt", "rustfmt", &["--edition", "2021"], &["rust"]),
+        builtin(
+            "black",
+            "black",
+            &["--quiet", "--stdin-filename", "{file}", "-"],
+            &["python"],
+        ),
+        builtin("gofmt", "gofmt", &[], &["go"]),
+        builtin(
+            "clang-format",
+            "clang-format",
+            &["--assume-filename={file}"],
+            &["c", "cpp"],
+        ),
+        builtin(
+            "shfmt",
+            "shfmt",
+            &["--filename", "{file}"],
+            &["shellscript"],
+        ),
+        builtin(
+            "stylua",
+            "stylua",
+            &["--stdin-filepath", "{file}", "-"],
+            &["lua"],
+        ),
+    ]
+}
+
+/// Built-in formatters with user entries applied: same-named entries
+/// replace built-ins in place, new ones follow.
+pub fn registry(user: &[ExternalFormatter]) -> Vec<ExternalFormatter> {
+    let mut formatters = builtin_formatters();
+    for formatter in user {
+        match formatters.iter_mut().find(|f| f.name == formatter.name) {
+            Some(existing) => *existing = formatter.clone(),
+            None => formatters.push(formatter.clone()),
+        }
+    }
+    formatters
+}
+
+/// How to format a document of some language.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FormatterChoice {
+    /// Language servers only.
+    Lsp,
+    /// Language servers, or this formatter when none of them formats the
+    /// language.
+    LspOrFallback(ExternalFormatter),
+    /// Always this formatter.
+    External(ExternalFormatter),
+}
+
+impl FormatterChoice {
+    /// The external formatter this choice may run.
+    pub fn external(&self) -> Option<&ExternalFormatter> {
+        match self {
+            FormatterChoice::Lsp => None,
+            FormatterChoice::LspOrFallback(f) | FormatterChoice::External(f) => Some(f),
+        }
+    }
+}
+
+/// Pick the formatter for `language`. An explicit `default_formatters` entry
+/// wins; otherwise the first installed formatter for the language is the
+/// fallback for language servers. `file_path` locates project-local
+/// installs such as `node_modules/.bin/prettier`.
+pub fn choose(settings: &Settings, language: &str, file_path: &str) -> FormatterChoice {
+    let formatters = registry(&settings.formatters);
+    match settings
+        .default_formatters
+        .get(language)
+        .map(String::as_str)
+    {
+        Some(LSP_FORMATTER) => return FormatterChoice::Lsp,
+        Some(name) => match formatters.iter().find(|f| f.name == name) {
+            Some(f) => return FormatterChoice::External(f.clone()),
+            None => log::warn!(
+                "Default formatter '{}' for {} is not defined; using the language server",
+                name,
+                language
+            ),
+        },
+        None => {}
+    }
+    formatters
+        .into_iter()
+        .find(|f| {
+            f.languages.iter().any(|l| l == language)
+                && resolve_command(&f.command, file_path).is_some()
+        })
+        .map_or(FormatterChoice::Lsp, FormatterChoice::LspOrFallback)
+}
+
+/// Names of the formatters registered for `language`, for settings UIs.
+pub fn formatters_for_language(settings: &Settings, language: &str) -> Vec<String> {
+    registry(&settings.formatters)
+        .into_iter()
+        .filter(|f| f.languages.iter().any(|l| l == language))
+        .map(|f| f.name)
+        .collect()
+}
+
+/// Find `command` in a `node_modules/.bin` above `file_path` (trusted
+/// folders only), then in PATH.
+fn resolve_command(command: &str, file_path: &str) -> Option<PathBuf> {
+    if !crate::lsp::command_looks_like_path(command)
+        && crate::trust::is_trusted(Path::new(file_path))
+    {
+        for dir in Path::new(file_path).ancestors().skip(1) {
+            let candidate = dir.join("node_modules").join(".bin").join(command);
+            if crate::lsp::is_executable_file(&candidate) {
+                return Some(candidate);
+            }
+        }
+    }
+    let search_path = crate::environment::search_path(Some(Path::new(file_path)));
+    crate::lsp::find_command_in(command, search_path)
+}
+
+/// Run `formatter` over `text` (the contents of `file_path`) and return the
+/// formatted text. Runs in the file's directory so the tool finds project
+/// configuration (`.prettierrc`, `rustfmt.toml`, `pyproject.toml`, ...).
+pub fn format_text(
+    formatter: &ExternalFormatter,
+    file_path: &str,
+    text: &str,
+) -> Result<String, String> {
+    let program = resolve_command(&formatter.command, file_path)
+        .ok_or_else(|| format!("Formatter '{}' not found", formatter.command))?;
+    let args: Vec<String> = formatter
+        .args
+        .iter()
+        .map(|a| a.replace("{file}", file_path))
+        .collect();
+    let mut command = Command::new(program);
+    command
+        .args(&args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    crate::environment::apply(&mut command, Some(Path::new(file_path)));
+    if let Some(dir) = Path::new(file_path).parent().filter(|d| d.is_dir()) {
+        command.current_dir(dir);
+    }
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("Failed to run {}: {}", formatter.name, e))?;
+
+    // Feed stdin and drain both pipes on their own threads so a formatter
+    // that writes before reading all input cannot deadlock.
+    let mut stdin = child.stdin.take();
+    let input = text.to_string();
+    let writer = std::thread::spawn(move || {
+        if let Some(stdin) = stdin.as_mut() {
+            let _ = stdin.write_all(input.as_bytes());
+        }
+    });
+    let stdout = child.stdout.take();
+    let reader = std::thread::spawn(move || {
+        let mut out = Vec::new();
+        if let Some(mut stdout) = stdout {
+            let _ = stdout.read_to_end(&mut out);
+        }
+        out
+    });
+    let stderr = child.stderr.take();
+    let err_reader = std::thread::spawn(move || {
+        let mut err = String::new();
+        if let Some(mut stderr) = stderr {
+            let _ = stderr.read_to_string(&mut err);
+        }
+        err
+    });
+
+    let deadline = Instant::now() + FORMAT_TIMEOUT;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!(
+                    "{} timed out after {}s",
+                    formatter.name,
+                    FORMAT_TIMEOUT.as_secs()
+                ));
+            }
+            Ok(None) => std::thread::sleep(Duration::from_millis(10)),
+            Err(e) => return Err(format!("Failed to wait for {}: {}", formatter.name, e)),
+        }
+    };
+    let _ = writer.join();
+    let out = reader.join().unwrap_or_default();
+    let err = err_reader.join().unwrap_or_default();
+    if !status.success() {
+        let detail = err.lines().find(|l| !l.trim().is_empty()).unwrap_or("");
+        return Err(format!(
+            "{} failed ({}): {}",
+            formatter.name, status, detail
+        ));
+    }
+    String::from_utf8(out).map_err(|_| format!("{} produced invalid UTF-8", formatter.name))
+}
+
+/// The single edit turning `old` into `new`, covering only the lines that
+/// differ so the cursor and folds elsewhere stay put. `None` if the texts
+/// are equal.
+pub fn minimal_edit(old: &str, new: &str) -> Option<lsp_types::TextEdit> {
+    if old == new {
+        return None;
+    }
+    let old_lines: Vec<&str> = old.split_inclusive('\n').collect();
+    let new_lines: Vec<&str> = new.split_inclusive('\n').collect();
+    let prefix = old_lines
+        .iter()
+        .zip(&new_lines)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = old_lines[prefix..]
+        .iter()
+        .rev()
+        .zip(new_lines[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let start = lsp_types::Position::new(prefix as u32, 0);
+    let end = if suffix == 0 {
+        crate::lsp::document_end(old)
+    } else {
+        lsp_types::Position::new((old_lines.len() - suffix) as u32, 0)
+    };
+    Some(lsp_types::TextEdit {
+        range: lsp_types::Range::new(start, end),
+        new_text: new_lines[prefix..new_lines.len() - suffix].concat(),
+    })
+}
+
+/// Format `text` with `formatter`, as edits for the editor.
+pub fn format_edits(
+    formatter: &ExternalFormatter,
+    file_path: &str,
+    text: &str,
+) -> Result<Vec<lsp_types::TextEdit>, String> {
+    let formatted = format_text(formatter, file_path, text)?;
+    Ok(minimal_edit(text, &formatted).into_iter().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(old: &str, edit: &lsp_types::TextEdit) -> String {
+        let offset = |pos: lsp_types::Position| {
+            let line_start: usize = old
+                .split_inclusive('\n')
+                .take(pos.line as usize)
+                .map(str::len)
+                .sum();
+            line_start + pos.character as usize
+        };
+        let (start, end) = (offset(edit.range.start), offset(edit.range.end));
+        format!("{}{}{}", &old[..start], edit.new_text, &old[end..])
+    }
+
+    #[test]
+    fn minimal_edit_covers_only_changed_lines() {
+        let old = "fn main() {\n  let x=1;\n}\n";
+        let new = "fn main() {\n    let x = 1;\n}\n";
+        let edit = minimal_edit(old, new).unwrap();
+        assert_eq!(edit.range.start, lsp_types::Position::new(1, 0));
+        assert_eq!(edit.range.end, lsp_types::Position::new(2, 0));
+        assert_eq!(edit.new_text, "    let x = 1;\n");
+        assert_eq!(apply(old, &edit), new);
+
+        for (old, new) in [
+            ("a\nb", "a\nb\n"),
+            ("a\n\n\nb\n", "a\n\nb\n"),
+            ("x = 1", "x = 1\n"),
+            ("", "a\n"),
+        ] {
+            assert_eq!(apply(old, &minimal_edit(old, new).unwrap()), new);
+        }
+        assert!(minimal_edit("same\n", "same\n").is_none());
+    }
+
+    #[test]
+    fn default_formatters_select_per_language() {
+        let mut settings = Settings::default();
+        settings.formatters.push(ExternalFormatter {
+            name: "rustfmt".to_string(),
+            command: "rustfmt".to_string(),
+            args: vec!["--edition".to_string(), "2024".to_string()],
+            languages: vec!["rust".to_string()],
+        });
+        settings.formatters.push(ExternalFormatter {
+            name: "ruff".to_string(),
+            command: "ruff".to_string(),
+            args: vec!["format".to_string(), "-".to_string()],
+            languages: vec!["python".to_string()],
+        });
+        settings
+            .default_formatters
+            .insert("rust".to_string(), "rustfmt".to_string());
+        settings
+            .default_formatters
+            .insert("python".to_string(), "ruff".to_string());
+        settings
+            .default_formatters
+            .insert("go".to_string(), LSP_FORMATTER.to_string());
+
+        let registry = registry(&settings.formatters);
+        assert_eq!(registry.iter().filter(|f| f.name == "rustfmt").count(), 1);
+        match choose(&settings, "rust", "/p/src/main.rs") {
+            FormatterChoice::External(f) => assert_eq!(f.args, ["--edition", "2024"]),
+            other => panic!("unexpected {:?}", other),
+        }
+        match choose(&settings, "python", "/p/a.py") {
+            FormatterChoice::External(f) => assert_eq!(f.name, "ruff"),
+            other => panic!("unexpected {:?}", other),
+        }
+        assert_eq!(choose(&settings, "go", "/p/a.go"), FormatterChoice::Lsp);
+        assert_eq!(
+            formatters_for_language(&settings, "python"),
+            ["black", "ruff"]
+        );
+    }
+
+    #[test]
+    fn project_local_formatters_are_found_and_run() {
+        let temp = tempfile::tempdir().unwrap();
+        crate::trust::trust_for_test(temp.path());
+        let bin = temp.path().join("node_modules/.bin");
+        std::fs::create_dir_all(&bin).unwrap();
+        let script = bin.join("upper");
+        std::fs::write(&script, "#!/bin/sh\ntr a-z A-Z\n").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+        let file = temp.path().join("src/app.ts");
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+        let file = file.to_string_lossy().to_string();
+
+        let mut settings = Settings::default();
+        settings.formatters.push(ExternalFormatter {
+            name: "upper".to_string(),
+            command: "upper".to_string(),
+            args: Vec::new(),
+            languages: vec!["plaintext".to_string()],
+        });
+        let choice = choose(&settings, "plaintext", &file);
+        let formatter = match &choice {
+            FormatterChoice::LspOrFallback(f) => f,
+            other => panic!("unexpected {:?}", other),
+        };
+        assert_eq!(
+            format_edits(formatter, &file, "keep\nshout\n").unwrap()[0].new_text,
+            "KEEP\nSHOUT\n"
+        );
+
+        let missing = ExternalFormatter {
+            name: "missing".to_string(),
+            command: "impulse-no-such-formatter".to_string(),
+            args: Vec::new(),
+            languages: Vec::new(),
+        };
+        assert!(format_text(&missing, &file, "x").is_err());
+    }
+}
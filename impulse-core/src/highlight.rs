@@ -0,0 +1,299 @@
+//! Tree-sitter syntax highlighting for languages Monaco ships no grammar for.
+//!
+//! Highlighting produces flat, per-line token ranges whose `scope` is a Monaco
+//! theme token name (e.g. `keyword`, `string.escape`), so frontends can color
+//! them with the same theme rules Monaco's own tokenizers use.
+
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+use tree_sitter_highlight::{HighlightConfiguration, HighlightEvent, Highlighter};
+
+/// The published `tree-sitter-just` crate omits its query files, so the
+/// upstream highlights query is vendored.
+const JUST_HIGHLIGHTS_QUERY: &str = include_str!("../queries/just/highlights.scm");
+
+/// Documents larger than this are not highlighted.
+pub const MAX_HIGHLIGHT_SIZE: usize = 2 * 1024 * 1024; // 2 MB
+
+/// Tree-sitter capture names recognized by the highlighter, paired with the
+/// Monaco theme token each is rendered as. Captures are matched to the most
+/// specific entry, so `keyword.return` falls back to `keyword` unless listed.
+const CAPTURE_SCOPES: &[(&str, &str)] = &[
+    ("attribute", "annotation"),
+    ("boolean", "keyword.constant"),
+    ("character", "string"),
+    ("comment", "comment"),
+    ("comment.documentation", "comment.doc"),
+    ("constant", "constant"),
+    ("constant.builtin", "keyword.constant"),
+    ("constant.character.escape", "string.escape"),
+    ("constructor", "constructor"),
+    ("escape", "string.escape"),
+    ("function", "function"),
+    ("function.builtin", "predefined.function"),
+    ("function.call", "function.call"),
+    ("keyword", "keyword"),
+    ("keyword.conditional", "keyword.control"),
+    ("keyword.exception", "keyword.try"),
+    ("keyword.function", "keyword.declaration"),
+    ("keyword.modifier", "keyword.modifier"),
+    ("keyword.operator", "keyword.operator"),
+    ("keyword.repeat", "keyword.flow"),
+    ("keyword.return", "keyword.flow"),
+    ("keyword.type", "keyword.type"),
+    ("label", "annotation"),
+    ("module", "namespace"),
+    ("namespace", "namespace"),
+    ("number", "number"),
+    ("number.float", "number.float"),
+    ("operator", "operator"),
+    ("property", "attribute.name"),
+    ("punctuation", "delimiter"),
+    ("string", "string"),
+    ("string.escape", "string.escape"),
+    ("string.special", "string.link"),
+    ("type", "type"),
+    ("type.builtin", "type.identifier"),
+    ("variable", "variable"),
+    ("variable.builtin", "variable.predefined"),
+    ("variable.member", "attribute.name"),
+    ("variable.parameter", "variable"),
+];
+
+/// A highlighted range on a single line. Columns are 0-based UTF-16 code
+/// units, end-exclusive.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HighlightToken {
+    pub line: u32,
+    pub start_column: u32,
+    pub end_column: u32,
+    /// Monaco theme token name used to color the range.
+    pub scope: String,
+}
+
+/// Whether a tree-sitter grammar is bundled for `language_id`.
+pub fn supports_language(language_id: &str) -> bool {
+    matches!(language_id, "zig" | "nix" | "just")
+}
+
+fn configuration(language_id: &str) -> Option<&'static HighlightConfiguration> {
+    static ZIG: OnceLock<Option<HighlightConfiguration>> = OnceLock::new();
+    static NIX: OnceLock<Option<HighlightConfiguration>> = OnceLock::new();
+    static JUST: OnceLock<Option<HighlightConfiguration>> = OnceLock::new();
+
+    let (cell, language, highlights, injections, locals) = match language_id {
+        "zig" => (
+            &ZIG,
+            tree_sitter_zig::LANGUAGE,
+            tree_sitter_zig::HIGHLIGHTS_QUERY,
+            tree_sitter_zig::INJECTIONS_QUERY,
+            "",
+        ),
+        "nix" => (
+            &NIX,
+            tree_sitter_nix::LANGUAGE,
+            tree_sitter_nix::HIGHLIGHTS_QUERY,
+            tree_sitter_nix::INJECTIONS_QUERY,
+            "",
+        ),
+        "just" => (
+            &JUST,
+            tree_sitter_just::LANGUAGE,
+            JUST_HIGHLIGHTS_QUERY,
+            "",
+            "",
+        ),
+        _ => return None,
+    };
+
+    cell.get_or_init(|| {
+        match HighlightConfiguration::new(
+            language.into(),
+            language_id,
+            highlights,
+            injections,
+            locals,
+        ) {
+            Ok(mut config) => {
+                let names: Vec<&str> = CAPTURE_SCOPES.iter().map(|(name, _)| *name).collect();
+                config.configure(&names);
+                Some(config)
+            }
+            Err(e) => {
+                log::warn!("Failed to load {} highlight queries: {}", language_id, e);
+                None
+            }
+        }
+    })
+    .as_ref()
+}
+
+/// Highlight `content` as `language_id`, returning tokens in document order.
+///
+/// Unsupported languages and oversized documents yield no tokens. Ranges that
+/// span several lines are split so every token lies on one line.
+pub fn highlight(language_id: &str, content: &str) -> Result<Vec<HighlightToken>, String> {
+    if content.len() > MAX_HIGHLIGHT_SIZE {
+        return Ok(Vec::new());
+    }
+    let Some(config) = configuration(language_id) else {
+        return Ok(Vec::new());
+    };
+
+    let mut highlighter = Highlighter::new();
+    let events = highlighter
+        .highlight(config, content.as_bytes(), None, |_| None)
+        .map_err(|e| format!("Highlighting failed: {}", e))?;
+
+    let mut tokens = Vec::new();
+    let mut stack: Vec<usize> = Vec::new();
+    let mut cursor = LineCursor::new(content);
+    for event in events {
+        match event.map_err(|e| format!("Highlighting failed: {}", e))? {
+            HighlightEvent::HighlightStart(highlight) => stack.push(highlight.0),
+            HighlightEvent::HighlightEnd => {
+                stack.pop();
+            }
+            HighlightEvent::Source { start, end } => {
+                let Some(&index) = stack.last() else {
+                    continue;
+                };
+                let scope = CAPTURE_SCOPES[index].1;
+                cursor.push_range(start, end, scope, &mut tokens);
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// Converts ascending byte ranges into per-line UTF-16 token ranges without
+/// rescanning the document for each range.
+struct LineCursor<'a> {
+    content: &'a str,
+    offset: usize,
+    line: u32,
+    column: u32,
+}
+
+impl<'a> LineCursor<'a> {
+    fn new(content: &'a str) -> Self {
+        Self {
+            content,
+            offset: 0,
+            line: 0,
+            column: 0,
+        }
+    }
+
+    fn advance_to(&mut self, target: usize) {
+        for ch in self.content[self.offset..target].chars() {
+            if ch == '\n' {
+                self.line += 1;
+                self.column = 0;
+            } else {
+                self.column += ch.len_utf16() as u32;
+            }
+        }
+        self.offset = target;
+    }
+
+    fn push_range(&mut self, start: usize, end: usize, scope: &str, out: &mut Vec<HighlightToken>) {
+        if start < self.offset || end > self.content.len() {
+            return;
+        }
+        self.advance_to(start);
+        for (index, segment) in self.content[start..end].split('\n').enumerate() {
+            if index > 0 {
+                self.line += 1;
+                self.column = 0;
+            }
+            let width: u32 = segment
+                .trim_end_matches('\r')
+                .chars()
+                .map(|c| c.len_utf16() as u32)
+                .sum();
+            if width > 0 {
+                out.push(HighlightToken {
+                    line: self.line,
+                    start_column: self.column,
+                    end_column: self.column + width,
+                    scope: scope.to_string(),
+                });
+            }
+            self.column += segment.chars().map(|c| c.len_utf16() as u32).sum::<u32>();
+        }
+        self.offset = end;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scope_of<'a>(tokens: &'a [HighlightToken], content: &str, text: &str) -> Option<&'a str> {
+        let (line_index, line) = content
+            .lines()
+            .enumerate()
+            .find(|(_, line)| line.contains(text))?;
+        let column = line[..line.find(text)?].encode_utf16().count() as u32;
+        tokens
+            .iter()
+            .find(|t| t.line == line_index as u32 && t.start_column == column)
+            .map(|t| t.scope.as_str())
+    }
+
+    #[test]
+    fn supports_only_bundled_grammars() {
+        assert!(supports_language("zig"));
+        assert!(supports_language("nix"));
+        assert!(supports_language("just"));
+        assert!(!supports_language("rust"));
+        assert!(highlight("rust", "fn main() {}").unwrap().is_empty());
+    }
+
+    #[test]
+    fn highlights_zig_keywords_and_strings() {
+        let content = "const std = @import(\"std\");\n\npub fn main() void {\n    return;\n}\n";
+        let tokens = highlight("zig", content).unwrap();
+        assert_eq!(scope_of(&tokens, content, "const"), Some("keyword"));
+        assert_eq!(scope_of(&tokens, content, "\"std\""), Some("string"));
+        assert!(scope_of(&tokens, content, "return")
+            .unwrap()
+            .starts_with("keyword"));
+    }
+
+    #[test]
+    fn highlights_nix_and_just() {
+        let nix = "{ pkgs }:\nlet name = \"hello\"; in name\n";
+        let tokens = highlight("nix", nix).unwrap();
+        assert_eq!(scope_of(&tokens, nix, "let"), Some("keyword"));
+        assert_eq!(scope_of(&tokens, nix, "\"hello\""), Some("string"));
+
+        let just = "# build it\nbuild:\n    cargo build\n";
+        let tokens = highlight("just", just).unwrap();
+        assert_eq!(scope_of(&tokens, just, "# build it"), Some("comment"));
+    }
+
+    #[test]
+    fn multi_line_ranges_are_split_per_line() {
+        let content = "const s =\n    \\\\first\n    \\\\second\n;\n";
+        let tokens = highlight("zig", content).unwrap();
+        assert!(tokens.iter().all(|t| t.end_column > t.start_column));
+        let lines: Vec<u32> = tokens
+            .iter()
+            .filter(|t| t.scope == "string")
+            .map(|t| t.line)
+            .collect();
+        assert!(lines.contains(&1) && lines.contains(&2));
+    }
+
+    #[test]
+    fn columns_are_utf16() {
+        let content = "const é = \"😀\"; const x = 1;\n";
+        let tokens = highlight("zig", content).unwrap();
+        // "const é = \"😀\"; " is 16 UTF-16 units ("😀" counts as two).
+        assert_eq!(scope_of(&tokens, content, "1;"), Some("number"));
+        assert!(tokens.iter().any(|t| t.start_column == 16));
+    }
+}
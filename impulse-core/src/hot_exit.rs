@@ -0,0 +1,220 @@
+//! Hot exit: unsaved editor buffers are copied into the state directory
+//! every few seconds, so edits survive a crash or a killed process.
+//!
+//! A marker file is written while the app runs and removed on a clean exit.
+//! Finding it at startup means the last run ended uncleanly, and whatever
+//! backups it left behind are offered for restore.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+/// How often frontends back up modified buffers.
+pub const BACKUP_INTERVAL_SECS: u32 = 10;
+
+/// Present while the app is running.
+const RUNNING_MARKER: &str = "running";
+
+/// One unsaved buffer. `path` is the file path, or the frontend's untitled
+/// sentinel for a buffer that was never saved.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Backup {
+    pub path: String,
+    pub content: String,
+    pub saved_at_ms: u64,
+}
+
+/// The backup directory and what has been written to it this run.
+pub struct HotExit {
+    dir: PathBuf,
+    /// SHA-256 of the content last backed up for each path, so unchanged
+    /// buffers aren't rewritten every tick.
+    written: HashMap<String, Vec<u8>>,
+}
+
+impl HotExit {
+    /// Backups live in `<state_dir>/backups`.
+    pub fn new(state_dir: &Path) -> Self {
+        Self {
+            dir: state_dir.join("backups"),
+            written: HashMap::new(),
+        }
+    }
+
+    /// Mark the app as running. Returns the backups left by the previous run
+    /// if it didn't exit cleanly; backups from a clean run are stale and
+    /// removed.
+    pub fn start(&mut self) -> Result<Vec<Backup>, String> {
+        std::fs::create_dir_all(&self.dir)
+            .map_err(|e| format!("Failed to create {}: {}", self.dir.display(), e))?;
+        restrict_permissions(&self.dir, 0o700);
+        let marker = self.dir.join(RUNNING_MARKER);
+        let recovered = if marker.exists() {
+            self.backups()
+        } else {
+            self.clear();
+            Vec::new()
+        };
+        std::fs::write(&marker, std::process::id().to_string())
+            .map_err(|e| format!("Failed to write {}: {}", marker.display(), e))?;
+        Ok(recovered)
+    }
+
+    /// Back up the modified buffers in `modified` (path, content) and drop
+    /// the backups of every other buffer: saved, reverted or closed.
+    pub fn sync<'a>(&mut self, modified: impl IntoIterator<Item = (&'a str, &'a str)>) {
+        let mut keep = Vec::new();
+        for (path, content) in modified {
+            keep.push(path.to_string());
+            let digest = ring::digest::digest(&ring::digest::SHA256, content.as_bytes());
+            if self.written.get(path).map(Vec::as_slice) == Some(digest.as_ref()) {
+                continue;
+            }
+            match self.write(path, content) {
+                Ok(()) => {
+                    self.written
+                        .insert(path.to_string(), digest.as_ref().to_vec());
+                }
+                Err(e) => log::warn!("{}", e),
+            }
+        }
+        let stale: Vec<String> = self
+            .written
+            .keys()
+            .filter(|path| !keep.contains(path))
+            .cloned()
+            .collect();
+        for path in stale {
+            self.written.remove(&path);
+            let _ = std::fs::remove_file(self.backup_path(&path));
+        }
+    }
+
+    /// Every backup in the directory, ordered by path.
+    pub fn backups(&self) -> Vec<Backup> {
+        let Ok(entries) = std::fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        let mut backups: Vec<Backup> = entries
+            .flatten()
+            .filter(|e| e.path().extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|e| {
+                let json = std::fs::read_to_string(e.path()).ok()?;
+                match serde_json::from_str(&json) {
+                    Ok(backup) => Some(backup),
+                    Err(err) => {
+                        log::warn!("Ignoring unreadable backup {}: {}", e.path().display(), err);
+                        None
+                    }
+                }
+            })
+            .collect();
+        backups.sort_by(|a, b| a.path.cmp(&b.path));
+        backups
+    }
+
+    /// Remove every backup, e.g. when the user declines to restore them.
+    pub fn clear(&mut self) {
+        self.written.clear();
+        let Ok(entries) = std::fs::read_dir(&self.dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            if entry.path().extension().is_some_and(|ext| ext == "json") {
+                let _ = std::fs::remove_file(entry.path());
+            }
+        }
+    }
+
+    /// Clean exit: the user has saved or discarded everything, so drop the
+    /// backups and the running marker.
+    pub fn finish(&mut self) {
+        self.clear();
+        let _ = std::fs::remove_file(self.dir.join(RUNNING_MARKER));
+    }
+
+    fn write(&self, path: &str, content: &str) -> Result<(), String> {
+        let backup = Backup {
+            path: path.to_string(),
+            content: content.to_string(),
+            saved_at_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0),
+        };
+        let json = serde_json::to_string(&backup)
+            .map_err(|e| format!("Failed to serialize backup of {}: {}", path, e))?;
+        let dest = self.backup_path(path);
+        let tmp = dest.with_extension("tmp");
+        std::fs::write(&tmp, json)
+            .map_err(|e| format!("Failed to write backup {}: {}", tmp.display(), e))?;
+        restrict_permissions(&tmp, 0o600);
+        std::fs::rename(&tmp, &dest)
+            .map_err(|e| format!("Failed to move backup into {}: {}", dest.display(), e))
+    }
+
+    /// Backups are named after a hash of the path, which can contain any
+    /// character.
+    fn backup_path(&self, path: &str) -> PathBuf {
+        let digest = ring::digest::digest(&ring::digest::SHA256, path.as_bytes());
+        let name: String = digest.as_ref()[..16]
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        self.dir.join(format!("{}.json", name))
+    }
+}
+
+#[cfg(unix)]
+fn restrict_permissions(path: &Path, mode: u32) {
+    use std::os::unix::fs::PermissionsExt;
+    if let Err(e) = std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode)) {
+        log::warn!("Failed to set permissions on {}: {}", path.display(), e);
+    }
+}
+
+#[cfg(not(unix))]
+fn restrict_permissions(_path: &Path, _mode: u32) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sync_writes_modified_and_drops_the_rest() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut hot_exit = HotExit::new(dir.path());
+        assert!(hot_exit.start().unwrap().is_empty());
+
+        hot_exit.sync([("/a.rs", "fn a() {}"), ("untitled:/1", "notes")]);
+        let paths: Vec<String> = hot_exit.backups().into_iter().map(|b| b.path).collect();
+        assert_eq!(paths, ["/a.rs", "untitled:/1"]);
+
+        hot_exit.sync([("/a.rs", "fn a() { b() }")]);
+        let backups = hot_exit.backups();
+        assert_eq!(backups.len(), 1);
+        assert_eq!(backups[0].content, "fn a() { b() }");
+    }
+
+    #[test]
+    fn backups_survive_only_an_unclean_exit() {
+        let dir = tempfile::tempdir().unwrap();
+
+        // Crash: no finish() before the next start.
+        let mut crashed = HotExit::new(dir.path());
+        crashed.start().unwrap();
+        crashed.sync([("/a.rs", "edited")]);
+        let mut next = HotExit::new(dir.path());
+        let recovered = next.start().unwrap();
+        assert_eq!(recovered.len(), 1);
+        assert_eq!(recovered[0].content, "edited");
+
+        // Clean exit: nothing left to recover.
+        next.finish();
+        let mut after_clean = HotExit::new(dir.path());
+        assert!(after_clean.start().unwrap().is_empty());
+        assert!(after_clean.backups().is_empty());
+    }
+}
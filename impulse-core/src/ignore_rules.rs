@@ -0,0 +1,456 @@
+//! Shared `.gitignore` matching. The file tree uses [`IgnoreMatcher`] to mark
+//! ignored entries and every workspace walk (search, symbol index) goes
+//! through [`walk_builder`], so both always agree on what is ignored.
+//!
+//! Rules follow git: `.gitignore` files from the repository root down to the
+//! entry's directory (deeper files take precedence), `info/exclude` in the
+//! (possibly shared) git dir, and the global `core.excludesFile`. `.ignore`
+//! files are honored alongside `.gitignore`. Outside a git repository nothing
+//! is ignored.
+//!
+//! On top of that come the `files_exclude` and `search_exclude` settings
+//! (gitignore-style globs), set globally with [`set_exclude_globs`] and per
+//! project in [`PROJECT_SETTINGS_FILE`]. `files_exclude` hides paths from the
+//! file tree, watchers and search alike; `search_exclude` only from search.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::{Match, WalkBuilder};
+use parking_lot::{Mutex, RwLock};
+use serde::Deserialize;
+
+/// Per-directory ignore files, checked in this order of precedence.
+const IGNORE_FILES: &[&str] = &[".ignore", ".gitignore"];
+
+/// Project-local exclude settings, relative to the project root:
+/// `{"files_exclude": [...], "search_exclude": [...]}`.
+pub const PROJECT_SETTINGS_FILE: &str = ".impulse/settings.json";
+
+/// Which exclude settings a walk or matcher honors.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExcludeScope {
+    /// `files_exclude` only: the file tree, watchers and indexes.
+    Files,
+    /// `files_exclude` and `search_exclude`.
+    Search,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct ExcludeGlobs {
+    files_exclude: Vec<String>,
+    search_exclude: Vec<String>,
+}
+
+fn global_excludes() -> &'static RwLock<ExcludeGlobs> {
+    static GLOBAL: OnceLock<RwLock<ExcludeGlobs>> = OnceLock::new();
+    GLOBAL.get_or_init(|| RwLock::new(ExcludeGlobs::default()))
+}
+
+/// Bumped whenever the global globs change, so long-lived matchers know to
+/// rebuild.
+static EXCLUDE_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Set the global `files_exclude` / `search_exclude` globs. Content indexes
+/// are dropped so they rebuild without the newly excluded files.
+pub fn set_exclude_globs(files_exclude: Vec<String>, search_exclude: Vec<String>) {
+    {
+        let mut globs = global_excludes().write();
+        if globs.files_exclude == files_exclude && globs.search_exclude == search_exclude {
+            return;
+        }
+        *globs = ExcludeGlobs {
+            files_exclude,
+            search_exclude,
+        };
+    }
+    EXCLUDE_GENERATION.fetch_add(1, Ordering::Relaxed);
+    crate::search_index::clear_indexes();
+}
+
+/// Project settings started or stopped applying because a folder's
+/// workspace trust changed.
+pub(crate) fn project_settings_changed() {
+    EXCLUDE_GENERATION.fetch_add(1, Ordering::Relaxed);
+    crate::search_index::clear_indexes();
+}
+
+/// The directory exclude globs under `path` are relative to: the nearest
+/// ancestor with a project settings file, else the git work tree, else
+/// `path` itself.
+fn project_root(path: &Path) -> PathBuf {
+    path.ancestors()
+        .find(|dir| dir.join(PROJECT_SETTINGS_FILE).is_file())
+        .map(Path::to_path_buf)
+        .or_else(|| find_work_tree(path))
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+/// Project settings from untrusted folders are ignored.
+fn read_project_excludes(root: &Path) -> ExcludeGlobs {
+    if !crate::trust::is_trusted(root) {
+        return ExcludeGlobs::default();
+    }
+    let path = root.join(PROJECT_SETTINGS_FILE);
+    let Ok(contents) = std::fs::read_to_string(&path) else {
+        return ExcludeGlobs::default();
+    };
+    serde_json::from_str(&contents).unwrap_or_else(|e| {
+        log::warn!("Invalid project settings at {}: {}", path.display(), e);
+        ExcludeGlobs::default()
+    })
+}
+
+/// Matches paths against the global and project exclude globs.
+pub struct ExcludeMatcher {
+    root: PathBuf,
+    rules: Gitignore,
+}
+
+impl ExcludeMatcher {
+    /// Build a matcher for paths under `path` (a file or directory).
+    pub fn new(path: &Path, scope: ExcludeScope) -> Self {
+        let root = project_root(path);
+        let project = read_project_excludes(&root);
+        let global = global_excludes().read();
+        let mut globs: Vec<&String> = global
+            .files_exclude
+            .iter()
+            .chain(&project.files_exclude)
+            .collect();
+        if scope == ExcludeScope::Search {
+            globs.extend(global.search_exclude.iter().chain(&project.search_exclude));
+        }
+        let mut builder = GitignoreBuilder::new(&root);
+        for glob in globs {
+            if let Err(e) = builder.add_line(None, glob) {
+                log::warn!("Invalid exclude glob '{}': {}", glob, e);
+            }
+        }
+        let rules = builder.build().unwrap_or_else(|e| {
+            log::warn!("Failed to build exclude globs: {}", e);
+            Gitignore::empty()
+        });
+        Self { root, rules }
+    }
+
+    /// Whether `path`, or a directory above it, matches an exclude glob.
+    pub fn is_excluded(&self, path: &Path, is_dir: bool) -> bool {
+        if self.rules.is_empty() || path == self.root || !path.starts_with(&self.root) {
+            return false;
+        }
+        self.rules
+            .matched_path_or_any_parents(path, is_dir)
+            .is_ignore()
+    }
+}
+
+/// A `files_exclude` matcher for long-lived watchers. It rebuilds itself
+/// when the global globs change or the project settings file is touched.
+pub struct LiveExcludes {
+    root: PathBuf,
+    generation: u64,
+    matcher: ExcludeMatcher,
+}
+
+impl LiveExcludes {
+    pub fn new(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            generation: EXCLUDE_GENERATION.load(Ordering::Relaxed),
+            matcher: ExcludeMatcher::new(root, ExcludeScope::Files),
+        }
+    }
+
+    /// Whether a watcher event for `path` should be dropped.
+    pub fn is_excluded(&mut self, path: &Path) -> bool {
+        let generation = EXCLUDE_GENERATION.load(Ordering::Relaxed);
+        if generation != self.generation || path.ends_with(PROJECT_SETTINGS_FILE) {
+            self.generation = generation;
+            self.matcher = ExcludeMatcher::new(&self.root, ExcludeScope::Files);
+        }
+        self.matcher.is_excluded(path, path.is_dir())
+    }
+}
+
+/// Answers "is this path ignored?" for paths inside one git work tree.
+/// Ignore files are read lazily and cached for the matcher's lifetime, so
+/// build a fresh matcher to pick up edits to them.
+pub struct IgnoreMatcher {
+    /// The git work tree root, or `None` outside a repository.
+    top: Option<PathBuf>,
+    excludes: ExcludeMatcher,
+    exclude: Gitignore,
+    global: Gitignore,
+    dir_rules: Mutex<HashMap<PathBuf, Arc<Gitignore>>>,
+    dir_ignored: Mutex<HashMap<PathBuf, bool>>,
+}
+
+/// The nearest ancestor of `path` (inclusive) containing a `.git` entry.
+fn find_work_tree(path: &Path) -> Option<PathBuf> {
+    path.ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .map(Path::to_path_buf)
+}
+
+impl IgnoreMatcher {
+    /// Build a matcher for paths under `path` (a file or directory) that
+    /// also honors `files_exclude`.
+    pub fn new(path: &Path) -> Self {
+        Self::with_scope(path, ExcludeScope::Files)
+    }
+
+    /// Build a matcher honoring the exclude globs of `scope`.
+    pub fn with_scope(path: &Path, scope: ExcludeScope) -> Self {
+        let top = find_work_tree(path);
+        let (exclude, global) = match &top {
+            Some(top) => {
+                // Linked worktrees and submodules keep `info/exclude` in the
+                // shared git dir that their `.git` file points to.
+                let common_dir = crate::git::resolve_git_dirs(&top.to_string_lossy())
+                    .map(|dirs| PathBuf::from(dirs.common_dir))
+                    .unwrap_or_else(|| top.join(".git"));
+                let mut builder = GitignoreBuilder::new(top);
+                builder.add(common_dir.join("info/exclude"));
+                let exclude = builder.build().unwrap_or_else(|_| Gitignore::empty());
+                let (global, _) = GitignoreBuilder::new(top).build_global();
+                (exclude, global)
+            }
+            None => (Gitignore::empty(), Gitignore::empty()),
+        };
+        Self {
+            top,
+            excludes: ExcludeMatcher::new(path, scope),
+            exclude,
+            global,
+            dir_rules: Mutex::new(HashMap::new()),
+            dir_ignored: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The ignore rules defined directly in `dir`.
+    fn rules_for(&self, dir: &Path) -> Arc<Gitignore> {
+        if let Some(rules) = self.dir_rules.lock().get(dir) {
+            return rules.clone();
+        }
+        let mut builder = GitignoreBuilder::new(dir);
+        // Later files win in `ignore`, so add in reverse precedence order.
+        for name in IGNORE_FILES.iter().rev() {
+            let file = dir.join(name);
+            if file.is_file() {
+                if let Some(e) = builder.add(&file) {
+                    log::warn!("Invalid ignore rules in {}: {}", file.display(), e);
+                }
+            }
+        }
+        let rules = Arc::new(builder.build().unwrap_or_else(|_| Gitignore::empty()));
+        self.dir_rules
+            .lock()
+            .insert(dir.to_path_buf(), rules.clone());
+        rules
+    }
+
+    /// Whether `path` itself matches a rule, ignoring its ancestors.
+    fn matches_self(&self, top: &Path, path: &Path, is_dir: bool) -> bool {
+        for dir in path.ancestors().skip(1) {
+            if !dir.starts_with(top) {
+                break;
+            }
+            match self.rules_for(dir).matched(path, is_dir) {
+                Match::Ignore(_) => return true,
+                Match::Whitelist(_) => return false,
+                Match::None => {}
+            }
+        }
+        match self.exclude.matched(path, is_dir) {
+            Match::Ignore(_) => return true,
+            Match::Whitelist(_) => return false,
+            Match::None => {}
+        }
+        self.global.matched(path, is_dir).is_ignore()
+    }
+
+    fn is_dir_ignored(&self, dir: &Path) -> bool {
+        if let Some(&ignored) = self.dir_ignored.lock().get(dir) {
+            return ignored;
+        }
+        let ignored = self.is_ignored(dir, true);
+        self.dir_ignored.lock().insert(dir.to_path_buf(), ignored);
+        ignored
+    }
+
+    /// Whether `path` is ignored, either directly or because a parent
+    /// directory is (git never re-includes files under an ignored directory).
+    /// The `.git` directory itself always counts as ignored, and so does
+    /// anything matching an exclude glob.
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        if self.excludes.is_excluded(path, is_dir) {
+            return true;
+        }
+        let Some(top) = self.top.as_deref() else {
+            return false;
+        };
+        let Ok(rel) = path.strip_prefix(top) else {
+            return false;
+        };
+        if rel.as_os_str().is_empty() {
+            return false;
+        }
+        if rel.components().any(|c| c.as_os_str() == ".git") {
+            return true;
+        }
+        match path.parent() {
+            Some(parent) if parent != top && self.is_dir_ignored(parent) => true,
+            _ => self.matches_self(top, path, is_dir),
+        }
+    }
+}
+
+/// A gitignore-aware walker over `root` that skips hidden and ignored
+/// entries using the same rules as [`IgnoreMatcher`]. Symlinked directories
+/// are followed per [`crate::mounts::follow_symlinks`], with cycles reported
+/// as errors instead of walked, and skipped mounts are not entered. Callers
+/// add their own depth limit before building.
+pub fn walk_builder(root: &Path) -> WalkBuilder {
+    walk_builder_with_scope(root, ExcludeScope::Files)
+}
+
+/// [`walk_builder`] that also skips `search_exclude` matches.
+pub fn search_walk_builder(root: &Path) -> WalkBuilder {
+    walk_builder_with_scope(root, ExcludeScope::Search)
+}
+
+fn walk_builder_with_scope(root: &Path, scope: ExcludeScope) -> WalkBuilder {
+    let matcher = Arc::new(IgnoreMatcher::with_scope(root, scope));
+    let skipped_mounts = crate::mounts::skipped_mount_points();
+    let mut builder = WalkBuilder::new(root);
+    builder
+        .hidden(true)
+        .ignore(false)
+        .git_ignore(false)
+        .git_global(false)
+        .git_exclude(false)
+        .parents(false)
+        .follow_links(crate::mounts::follow_symlinks())
+        .filter_entry(move |entry| {
+            let is_dir = entry.file_type().is_some_and(|ft| ft.is_dir());
+            entry.depth() == 0
+                || !(matcher.is_ignored(entry.path(), is_dir)
+                    || is_dir
+                        && crate::mounts::is_skipped(
+                            entry.path(),
+                            entry.path_is_symlink(),
+                            &skipped_mounts,
+                        ))
+        });
+    builder
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo_fixture() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::create_dir(root.join(".git")).unwrap();
+        std::fs::write(root.join(".gitignore"), "target/\n*.log\n!keep.log\n").unwrap();
+        std::fs::create_dir_all(root.join("target/debug")).unwrap();
+        std::fs::create_dir_all(root.join("src/gen")).unwrap();
+        std::fs::write(root.join("src/.gitignore"), "gen/\n").unwrap();
+        for file in [
+            "target/debug/app",
+            "src/main.rs",
+            "src/gen/out.rs",
+            "build.log",
+            "keep.log",
+        ] {
+            std::fs::write(root.join(file), "x").unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn matcher_applies_nested_rules_and_negation() {
+        let dir = repo_fixture();
+        let root = dir.path();
+        let matcher = IgnoreMatcher::new(root);
+        assert!(matcher.is_ignored(&root.join("target"), true));
+        assert!(matcher.is_ignored(&root.join("target/debug/app"), false));
+        assert!(matcher.is_ignored(&root.join("build.log"), false));
+        assert!(!matcher.is_ignored(&root.join("keep.log"), false));
+        assert!(matcher.is_ignored(&root.join("src/gen"), true));
+        assert!(matcher.is_ignored(&root.join("src/gen/out.rs"), false));
+        assert!(!matcher.is_ignored(&root.join("src/main.rs"), false));
+        assert!(matcher.is_ignored(&root.join(".git"), true));
+        assert!(!matcher.is_ignored(root, true));
+    }
+
+    #[test]
+    fn nothing_is_ignored_outside_a_repository() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(".gitignore"), "*.log\n").unwrap();
+        let matcher = IgnoreMatcher::new(dir.path());
+        assert!(!matcher.is_ignored(&dir.path().join("a.log"), false));
+    }
+
+    #[test]
+    fn project_excludes_apply_by_scope() {
+        let dir = repo_fixture();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join(".impulse")).unwrap();
+        std::fs::write(
+            root.join(PROJECT_SETTINGS_FILE),
+            r#"{"files_exclude": ["src/gen/", "*.lock"], "search_exclude": ["/docs"]}"#,
+        )
+        .unwrap();
+        std::fs::create_dir_all(root.join("docs")).unwrap();
+        std::fs::write(root.join("docs/guide.md"), "x").unwrap();
+
+        // Untrusted folders' settings are ignored.
+        let files = ExcludeMatcher::new(root, ExcludeScope::Files);
+        assert!(!files.is_excluded(&root.join("src/gen/out.rs"), false));
+
+        crate::trust::trust_for_test(root);
+        let files = ExcludeMatcher::new(root, ExcludeScope::Files);
+        assert!(files.is_excluded(&root.join("src/gen/out.rs"), false));
+        assert!(files.is_excluded(&root.join("src/Cargo.lock"), false));
+        assert!(!files.is_excluded(&root.join("docs/guide.md"), false));
+        assert!(!files.is_excluded(&root.join("src/main.rs"), false));
+
+        let search = ExcludeMatcher::new(&root.join("src"), ExcludeScope::Search);
+        assert!(search.is_excluded(&root.join("docs/guide.md"), false));
+        let searched: Vec<_> = search_walk_builder(root)
+            .build()
+            .filter_map(Result::ok)
+            .filter(|e| e.file_type().is_some_and(|ft| ft.is_file()))
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .collect();
+        assert!(!searched.contains(&"guide.md".to_string()));
+        assert!(searched.contains(&"main.rs".to_string()));
+    }
+
+    #[test]
+    fn walker_agrees_with_matcher() {
+        let dir = repo_fixture();
+        let root = dir.path();
+        let mut walked: Vec<String> = walk_builder(root)
+            .build()
+            .filter_map(Result::ok)
+            .filter(|e| e.file_type().is_some_and(|ft| ft.is_file()))
+            .map(|e| {
+                e.path()
+                    .strip_prefix(root)
+                    .unwrap()
+                    .to_string_lossy()
+                    .to_string()
+            })
+            .collect();
+        walked.sort();
+        assert_eq!(walked, vec!["keep.log", "src/main.rs"]);
+    }
+}
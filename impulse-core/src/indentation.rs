@@ -0,0 +1,185 @@
+//! Indentation detection: tabs vs. spaces and the indent width of a file,
+//! guessed from its content so the editor matches the file instead of the
+//! global settings.
+//!
+//! The width is taken from how much the indentation grows between
+//! consecutive lines rather than from absolute indent sizes, which can't
+//! tell a 2-space file from a 4-space one (every multiple of 4 is also a
+//! multiple of 2).
+
+use serde::Serialize;
+
+use crate::settings::Settings;
+
+/// How many lines to sample from the top of the file.
+const SAMPLE_LINES: usize = 1000;
+
+/// Indent widths offered when changing a file's indentation.
+pub const INDENT_WIDTHS: &[u32] = &[2, 4, 8];
+
+/// The indentation an editor uses for one file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Indentation {
+    pub use_spaces: bool,
+    pub width: u32,
+}
+
+impl Indentation {
+    /// Status bar label, e.g. `"Spaces: 4"` or `"Tab Size: 8"`.
+    pub fn label(&self) -> String {
+        if self.use_spaces {
+            format!("Spaces: {}", self.width)
+        } else {
+            format!("Tab Size: {}", self.width)
+        }
+    }
+}
+
+/// What could be read from a file's content. Either half is `None` when the
+/// file doesn't say: no indented lines at all, or (for the width) tabs or
+/// only a single level of spaces.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct DetectedIndentation {
+    pub use_spaces: Option<bool>,
+    pub width: Option<u32>,
+}
+
+pub fn detect_indentation(content: &str) -> DetectedIndentation {
+    let mut tab_lines = 0usize;
+    let mut space_lines = 0usize;
+    // Count of each increase in leading spaces, indexed by its size.
+    let mut steps = [0usize; 9];
+    let mut previous = 0usize;
+
+    for line in content.lines().take(SAMPLE_LINES) {
+        if line.trim().is_empty() {
+            continue;
+        }
+        if line.starts_with('\t') {
+            tab_lines += 1;
+            previous = 0;
+            continue;
+        }
+        let spaces = line.len() - line.trim_start_matches(' ').len();
+        if spaces > 0 {
+            space_lines += 1;
+        }
+        // A step of 1 is almost always alignment (` * ` in block comments),
+        // not an indent level.
+        if spaces > previous + 1 && spaces - previous < steps.len() {
+            steps[spaces - previous] += 1;
+        }
+        previous = spaces;
+    }
+
+    if tab_lines == 0 && space_lines == 0 {
+        return DetectedIndentation::default();
+    }
+    let use_spaces = space_lines > tab_lines;
+    // Most common step; ties go to the smaller width.
+    let width = steps
+        .iter()
+        .enumerate()
+        .filter(|&(_, &count)| count > 0)
+        .max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(&a.0)))
+        .map(|(step, _)| step as u32);
+    DetectedIndentation {
+        use_spaces: Some(use_spaces),
+        width: if use_spaces { width } else { None },
+    }
+}
+
+/// The indentation to open `path` with: the global settings, then whatever
+/// the content shows, then the first matching file-type override, which
+/// always wins.
+pub fn resolve_indentation(path: &str, content: &str, settings: &Settings) -> Indentation {
+    let detected = detect_indentation(content);
+    let mut indentation = Indentation {
+        use_spaces: detected.use_spaces.unwrap_or(settings.use_spaces),
+        width: detected.width.unwrap_or(settings.tab_width),
+    };
+    if let Some(ovr) = settings
+        .file_type_overrides
+        .iter()
+        .find(|o| crate::util::matches_file_pattern(path, &o.pattern))
+    {
+        if let Some(width) = ovr.tab_width {
+            indentation.width = width;
+        }
+        if let Some(use_spaces) = ovr.use_spaces {
+            indentation.use_spaces = use_spaces;
+        }
+    }
+    indentation
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::FileTypeOverride;
+
+    #[test]
+    fn detects_space_width_from_indent_steps() {
+        let four = "fn main() {\n    if x {\n        y();\n    }\n    z();\n}\n";
+        assert_eq!(
+            detect_indentation(four),
+            DetectedIndentation {
+                use_spaces: Some(true),
+                width: Some(4)
+            }
+        );
+        let two = "a:\n  b:\n    c: 1\n  d: 2\n";
+        assert_eq!(detect_indentation(two).width, Some(2));
+        // Block comment alignment doesn't count as a level.
+        let comment = "/**\n * doc\n */\nfn f() {\n    g();\n}\n";
+        assert_eq!(detect_indentation(comment).width, Some(4));
+    }
+
+    #[test]
+    fn detects_tabs_and_unknowns() {
+        let tabs = "all:\n\tcc main.c\n\tld main.o\n";
+        assert_eq!(
+            detect_indentation(tabs),
+            DetectedIndentation {
+                use_spaces: Some(false),
+                width: None
+            }
+        );
+        assert_eq!(
+            detect_indentation("no\nindentation\n"),
+            DetectedIndentation::default()
+        );
+    }
+
+    #[test]
+    fn resolve_falls_back_to_settings_and_honors_overrides() {
+        let mut settings = Settings {
+            tab_width: 3,
+            use_spaces: true,
+            ..Settings::default()
+        };
+        assert_eq!(
+            resolve_indentation("a.txt", "plain\n", &settings),
+            Indentation {
+                use_spaces: true,
+                width: 3
+            }
+        );
+        assert_eq!(
+            resolve_indentation("a.go", "func f() {\n\treturn\n}\n", &settings),
+            Indentation {
+                use_spaces: false,
+                width: 3
+            }
+        );
+
+        settings.file_type_overrides.push(FileTypeOverride {
+            pattern: "*.py".to_string(),
+            tab_width: Some(4),
+            use_spaces: Some(true),
+            ..Default::default()
+        });
+        let indentation = resolve_indentation("x.py", "def f():\n\tpass\n", &settings);
+        assert_eq!(indentation.label(), "Spaces: 4");
+    }
+}
@@ -0,0 +1,179 @@
+//! Keymap presets: wholesale remaps of the built-in action shortcuts for
+//! people coming from other editors.
+//!
+//! A preset sits between the frontend's default accelerators and the user's
+//! `keybinding_overrides`: actions a preset leaves out keep the platform
+//! default, and any user override wins over the preset. Shortcuts use the
+//! same `"Ctrl+Shift+P"` form as the overrides.
+
+use std::collections::HashMap;
+
+/// One action remapped by a preset, with the shortcut for each platform.
+/// An empty string keeps the platform default.
+pub struct PresetBinding {
+    pub id: &'static str,
+    pub linux: &'static str,
+    pub macos: &'static str,
+}
+
+/// A selectable keymap preset.
+pub struct KeymapPreset {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub bindings: &'static [PresetBinding],
+}
+
+const fn bind(id: &'static str, linux: &'static str, macos: &'static str) -> PresetBinding {
+    PresetBinding { id, linux, macos }
+}
+
+/// The preset used when none (or an unknown one) is selected.
+pub const DEFAULT_PRESET: &str = "default";
+
+pub const KEYMAP_PRESETS: &[KeymapPreset] = &[
+    KeymapPreset {
+        id: DEFAULT_PRESET,
+        name: "Impulse",
+        bindings: &[],
+    },
+    KeymapPreset {
+        id: "vscode",
+        name: "VS Code",
+        bindings: &[
+            bind("toggle_sidebar", "Ctrl+B", "Cmd+B"),
+            bind("go_to_line", "Ctrl+G", "Ctrl+G"),
+            bind("review_changes", "Ctrl+Shift+G", "Ctrl+Shift+G"),
+        ],
+    },
+    KeymapPreset {
+        id: "jetbrains",
+        name: "JetBrains",
+        bindings: &[
+            bind("command_palette", "Ctrl+Shift+A", "Cmd+Shift+A"),
+            bind("go_to_line", "Ctrl+G", "Cmd+L"),
+            // Cmd+1…9 switch tabs on macOS, so the tool-window shortcuts
+            // only apply on Linux.
+            bind("toggle_sidebar", "Alt+1", ""),
+            bind("review_changes", "Alt+9", ""),
+            bind("new_tab", "Alt+F12", "Alt+F12"),
+            bind("close_tab", "Ctrl+F4", "Cmd+W"),
+            bind("next_tab", "Alt+Right", "Ctrl+Right"),
+            bind("prev_tab", "Alt+Left", "Ctrl+Left"),
+            bind("open_settings", "Ctrl+Alt+S", ""),
+        ],
+    },
+    KeymapPreset {
+        id: "emacs",
+        name: "Emacs",
+        bindings: &[
+            // Chords like `C-x C-s` can't be expressed in macOS menus, so
+            // the two-key Emacs commands get a single-key stand-in.
+            bind("command_palette", "Alt+X", "Alt+X"),
+            bind("find", "Ctrl+S", "Ctrl+S"),
+            bind("save", "Ctrl+Shift+S", "Ctrl+Shift+S"),
+            bind("go_to_line", "Alt+G", "Alt+G"),
+            bind("close_tab", "Ctrl+Shift+K", "Ctrl+Shift+K"),
+            bind("toggle_sidebar", "Ctrl+Shift+D", "Ctrl+Shift+D"),
+            bind("project_search", "Ctrl+Alt+S", "Ctrl+Alt+S"),
+        ],
+    },
+];
+
+/// Look up a preset by ID.
+pub fn preset(id: &str) -> Option<&'static KeymapPreset> {
+    KEYMAP_PRESETS.iter().find(|p| p.id == id)
+}
+
+/// The shortcuts a preset assigns on this platform, keyed by action ID.
+/// Unknown presets assign nothing.
+pub fn preset_bindings(id: &str) -> HashMap<String, String> {
+    let Some(preset) = preset(id) else {
+        return HashMap::new();
+    };
+    preset
+        .bindings
+        .iter()
+        .map(|b| {
+            (
+                b.id,
+                if cfg!(target_os = "macos") {
+                    b.macos
+                } else {
+                    b.linux
+                },
+            )
+        })
+        .filter(|(_, shortcut)| !shortcut.is_empty())
+        .map(|(id, shortcut)| (id.to_string(), shortcut.to_string()))
+        .collect()
+}
+
+/// Layer user overrides on top of a preset. The result has the same shape
+/// as `keybinding_overrides`, so frontends resolve it exactly as before.
+pub fn layered_overrides(
+    preset_id: &str,
+    user_overrides: &HashMap<String, String>,
+) -> HashMap<String, String> {
+    let mut merged = preset_bindings(preset_id);
+    for (id, shortcut) in user_overrides {
+        if !shortcut.is_empty() {
+            merged.insert(id.clone(), shortcut.clone());
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn presets_only_bind_known_actions_without_duplicates() {
+        let known: HashSet<String> = crate::command_palette::builtin_items()
+            .into_iter()
+            .map(|item| item.id)
+            .collect();
+        for preset in KEYMAP_PRESETS {
+            let mut ids = HashSet::new();
+            let mut linux = HashSet::new();
+            let mut macos = HashSet::new();
+            for b in preset.bindings {
+                assert!(
+                    known.contains(b.id),
+                    "{}: unknown action {}",
+                    preset.id,
+                    b.id
+                );
+                assert!(ids.insert(b.id), "{}: {} bound twice", preset.id, b.id);
+                assert!(
+                    b.linux.is_empty() || linux.insert(b.linux.to_lowercase()),
+                    "{}: {} reused on Linux",
+                    preset.id,
+                    b.linux
+                );
+                assert!(
+                    b.macos.is_empty() || macos.insert(b.macos.to_lowercase()),
+                    "{}: {} reused on macOS",
+                    preset.id,
+                    b.macos
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn user_overrides_win_over_preset() {
+        let mut user = HashMap::new();
+        user.insert("command_palette".to_string(), "Ctrl+P".to_string());
+        user.insert("find".to_string(), String::new());
+        let merged = layered_overrides("emacs", &user);
+        assert_eq!(merged["command_palette"], "Ctrl+P");
+        // An empty override doesn't erase the preset's binding.
+        assert_eq!(merged["find"], "Ctrl+S");
+        assert!(merged.contains_key("go_to_line"));
+
+        assert!(layered_overrides("nope", &HashMap::new()).is_empty());
+        assert!(preset_bindings(DEFAULT_PRESET).is_empty());
+    }
+}
@@ -0,0 +1,158 @@
+//! Language overrides: the languages a file can be switched to from the
+//! status bar, and the per-extension choices remembered in the
+//! `language_overrides` setting (e.g. `*.conf` as INI).
+//!
+//! Overrides are process-wide once a frontend calls [`set_overrides`], so
+//! [`crate::util::language_from_uri`] and everything routed by it (language
+//! servers, formatters) follow them.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::RwLock;
+
+use serde::Serialize;
+
+/// A language a file can be switched to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Language {
+    /// Language id sent to language servers and stored in settings.
+    pub id: &'static str,
+    pub label: &'static str,
+    /// The Monaco language that highlights it.
+    pub monaco: &'static str,
+}
+
+const fn lang(id: &'static str, label: &'static str, monaco: &'static str) -> Language {
+    Language { id, label, monaco }
+}
+
+/// Languages offered by the override menu, by label.
+pub const LANGUAGES: &[Language] = &[
+    lang("bat", "Batch", "bat"),
+    lang("c", "C", "cpp"),
+    lang("csharp", "C#", "csharp"),
+    lang("cpp", "C++", "cpp"),
+    lang("clojure", "Clojure", "clojure"),
+    lang("css", "CSS", "css"),
+    lang("dart", "Dart", "dart"),
+    lang("dockerfile", "Dockerfile", "dockerfile"),
+    lang("elixir", "Elixir", "elixir"),
+    lang("fsharp", "F#", "fsharp"),
+    lang("go", "Go", "go"),
+    lang("graphql", "GraphQL", "graphql"),
+    lang("hcl", "HCL", "hcl"),
+    lang("html", "HTML", "html"),
+    lang("ini", "INI", "ini"),
+    lang("java", "Java", "java"),
+    lang("javascript", "JavaScript", "javascript"),
+    lang("javascriptreact", "JavaScript JSX", "javascript"),
+    lang("json", "JSON", "json"),
+    lang("jsonc", "JSON with Comments", "json"),
+    lang("kotlin", "Kotlin", "kotlin"),
+    lang("less", "Less", "less"),
+    lang("lua", "Lua", "lua"),
+    lang("markdown", "Markdown", "markdown"),
+    lang("objective-c", "Objective-C", "objective-c"),
+    lang("perl", "Perl", "perl"),
+    lang("php", "PHP", "php"),
+    lang("plaintext", "Plain Text", "plaintext"),
+    lang("powershell", "PowerShell", "powershell"),
+    lang("python", "Python", "python"),
+    lang("r", "R", "r"),
+    lang("ruby", "Ruby", "ruby"),
+    lang("rust", "Rust", "rust"),
+    lang("scala", "Scala", "scala"),
+    lang("scss", "SCSS", "scss"),
+    lang("shellscript", "Shell Script", "shell"),
+    lang("sql", "SQL", "sql"),
+    lang("swift", "Swift", "swift"),
+    lang("toml", "TOML", "toml"),
+    lang("typescript", "TypeScript", "typescript"),
+    lang("typescriptreact", "TypeScript JSX", "typescript"),
+    lang("xml", "XML", "xml"),
+    lang("yaml", "YAML", "yaml"),
+];
+
+/// The language with `id`.
+pub fn find(id: &str) -> Option<&'static Language> {
+    LANGUAGES.iter().find(|l| l.id == id)
+}
+
+/// The `language_overrides` key for `path`: `*.<ext>` (lowercased) when it
+/// has an extension, otherwise its file name, so it matches like a
+/// file-type override pattern.
+pub fn override_key(path: &Path) -> Option<String> {
+    if let Some(ext) = path.extension() {
+        return Some(format!("*.{}", ext.to_string_lossy().to_lowercase()));
+    }
+    path.file_name().map(|n| n.to_string_lossy().to_string())
+}
+
+/// The override in `overrides` that applies to `path`, if any.
+pub fn override_in(path: &Path, overrides: &HashMap<String, String>) -> Option<&'static Language> {
+    let id = overrides.get(&override_key(path)?)?;
+    let language = find(id);
+    if language.is_none() {
+        log::warn!("Ignoring unknown language override {:?}", id);
+    }
+    language
+}
+
+static OVERRIDES: RwLock<Option<HashMap<String, String>>> = RwLock::new(None);
+
+/// Use `overrides` (the `language_overrides` setting) from now on. Called at
+/// startup and whenever the setting changes.
+pub fn set_overrides(overrides: &HashMap<String, String>) {
+    if let Ok(mut current) = OVERRIDES.write() {
+        *current = Some(overrides.clone());
+    }
+}
+
+/// The language `path` is overridden to, if any.
+pub fn overridden(path: &Path) -> Option<&'static Language> {
+    let overrides = OVERRIDES.read().ok()?;
+    override_in(path, overrides.as_ref()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_by_extension_or_file_name() {
+        assert_eq!(
+            override_key(Path::new("/etc/nginx/site.CONF")).as_deref(),
+            Some("*.conf")
+        );
+        assert_eq!(
+            override_key(Path::new("/src/Jenkinsfile")).as_deref(),
+            Some("Jenkinsfile")
+        );
+
+        let mut overrides = HashMap::new();
+        overrides.insert("*.conf".to_string(), "ini".to_string());
+        overrides.insert("*.txt".to_string(), "no-such-language".to_string());
+        let ini = override_in(Path::new("/etc/app.conf"), &overrides).unwrap();
+        assert_eq!((ini.id, ini.monaco), ("ini", "ini"));
+        assert!(override_in(Path::new("/notes.txt"), &overrides).is_none());
+        assert!(override_in(Path::new("/main.rs"), &overrides).is_none());
+    }
+
+    #[test]
+    fn overrides_apply_to_language_detection() {
+        let uri = "file:///project/build.zzlang";
+        assert_eq!(crate::util::language_from_uri(uri), "zzlang");
+        let mut overrides = HashMap::new();
+        overrides.insert("*.zzlang".to_string(), "shellscript".to_string());
+        set_overrides(&overrides);
+        assert_eq!(crate::util::language_from_uri(uri), "shellscript");
+    }
+
+    #[test]
+    fn every_language_is_listed_once() {
+        let mut ids: Vec<_> = LANGUAGES.iter().map(|l| l.id).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        assert_eq!(ids.len(), LANGUAGES.len());
+    }
+}
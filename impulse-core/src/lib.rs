@@ -1,15 +1,66 @@
+pub mod assistant;
+pub mod cli_open;
 pub mod close_risk;
 pub mod command_palette;
+pub mod commands;
+pub mod commit_message;
 pub mod completion;
+pub mod config_sync;
+pub mod conflict;
+pub mod control;
+pub mod crash_report;
+pub mod editorconfig;
+pub mod environment;
+pub mod file_actions;
+pub mod file_drop;
+pub mod file_properties;
 pub mod file_tree;
 pub mod filesystem;
+pub mod formatter;
 pub mod git;
+pub mod git_hosting;
+pub mod highlight;
+pub mod hot_exit;
+pub mod ignore_rules;
+pub mod indentation;
+pub mod keymap;
+pub mod languages;
+pub mod local_history;
+mod local_socket;
 pub mod lsp;
+mod lsp_diagnostics;
+mod lsp_download;
+pub mod lsp_install;
+mod lsp_watch;
+pub mod mcp;
+pub mod mounts;
+pub mod notifications;
+pub mod paste_guard;
+pub mod privileged_write;
+pub mod process_info;
+pub mod read_only;
+pub mod remote_files;
+pub mod safe_write;
+pub mod save_whitespace;
 pub mod search;
+mod search_index;
+pub mod secrets;
 pub mod session_state;
 pub mod settings;
 pub mod shell;
 pub mod shell_parser;
+pub mod startup_profile;
+pub mod status_bar;
+pub mod symbol_index;
+pub mod tab_order;
+pub mod text_diff;
 pub mod theme;
+pub mod trust;
 pub mod update;
 pub mod util;
+pub mod vscode_import;
+pub mod vscode_theme;
+pub mod watch;
+pub mod window_layouts;
+pub mod workspace_edit;
+pub mod zoom;
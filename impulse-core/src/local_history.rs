@@ -0,0 +1,273 @@
+//! Local history: every saved version of a file is copied into
+//! `<state_dir>/history`, so earlier versions can be viewed, compared with
+//! the file and restored whether or not the project is a git repository.
+//!
+//! Each file gets a directory named after a hash of its path, holding an
+//! `index.json` and one copy per version. A save identical to the newest
+//! version isn't stored again, files over [`MAX_FILE_BYTES`] aren't stored
+//! at all, and only the newest [`MAX_ENTRIES_PER_FILE`] versions are kept.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+/// Versions kept per file; older ones are deleted.
+pub const MAX_ENTRIES_PER_FILE: usize = 50;
+
+/// Larger files are not copied into the history.
+pub const MAX_FILE_BYTES: usize = 1024 * 1024;
+
+const INDEX_FILE: &str = "index.json";
+
+/// One saved version of a file.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub id: String,
+    pub saved_at_ms: u64,
+    pub size: u64,
+    /// SHA-256 of the content, hex encoded.
+    pub digest: String,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct Index {
+    path: String,
+    /// Oldest first.
+    entries: Vec<HistoryEntry>,
+}
+
+/// The history directory.
+pub struct LocalHistory {
+    dir: PathBuf,
+}
+
+impl LocalHistory {
+    /// History lives in `<state_dir>/history`.
+    pub fn new(state_dir: &Path) -> Self {
+        Self {
+            dir: state_dir.join("history"),
+        }
+    }
+
+    /// Store `content` as the newest version of `path`. Returns whether a
+    /// version was added: unchanged and oversized content is skipped.
+    pub fn record(&self, path: &Path, content: &[u8]) -> Result<bool, String> {
+        if content.len() > MAX_FILE_BYTES {
+            return Ok(false);
+        }
+        let digest = hex_digest(content);
+        let file_dir = self.file_dir(path);
+        let mut index = self.read_index(path);
+        if index.entries.last().is_some_and(|e| e.digest == digest) {
+            return Ok(false);
+        }
+
+        std::fs::create_dir_all(&file_dir)
+            .map_err(|e| format!("Failed to create {}: {}", file_dir.display(), e))?;
+        restrict_permissions(&self.dir, 0o700);
+
+        let saved_at_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        // Two saves within a millisecond still get distinct IDs.
+        let mut id = saved_at_ms.to_string();
+        let mut n = 1;
+        while index.entries.iter().any(|e| e.id == id) {
+            id = format!("{}-{}", saved_at_ms, n);
+            n += 1;
+        }
+        let version = file_dir.join(version_name(path, &id));
+        std::fs::write(&version, content)
+            .map_err(|e| format!("Failed to write {}: {}", version.display(), e))?;
+        restrict_permissions(&version, 0o600);
+
+        index.path = path.to_string_lossy().to_string();
+        index.entries.push(HistoryEntry {
+            id,
+            saved_at_ms,
+            size: content.len() as u64,
+            digest,
+        });
+        let excess = index.entries.len().saturating_sub(MAX_ENTRIES_PER_FILE);
+        for old in index.entries.drain(..excess) {
+            let _ = std::fs::remove_file(file_dir.join(version_name(path, &old.id)));
+        }
+        self.write_index(path, &index)?;
+        Ok(true)
+    }
+
+    /// The stored versions of `path`, newest first.
+    pub fn entries(&self, path: &Path) -> Vec<HistoryEntry> {
+        let mut entries = self.read_index(path).entries;
+        entries.reverse();
+        entries
+    }
+
+    /// Where the copy of version `id` of `path` is stored, if it exists.
+    /// The copy keeps the file's extension, so it can be opened or diffed
+    /// with the right language.
+    pub fn version_path(&self, path: &Path, id: &str) -> Option<PathBuf> {
+        // Only IDs from the index, so `id` can't name another file.
+        self.read_index(path).entries.iter().find(|e| e.id == id)?;
+        let version = self.file_dir(path).join(version_name(path, id));
+        version.is_file().then_some(version)
+    }
+
+    /// The content of version `id` of `path`.
+    pub fn read(&self, path: &Path, id: &str) -> Result<String, String> {
+        let version = self
+            .version_path(path, id)
+            .ok_or_else(|| format!("No version {} of {}", id, path.display()))?;
+        let bytes = std::fs::read(&version)
+            .map_err(|e| format!("Failed to read {}: {}", version.display(), e))?;
+        String::from_utf8(bytes).map_err(|_| format!("Version {} is not UTF-8 text", id))
+    }
+
+    fn file_dir(&self, path: &Path) -> PathBuf {
+        let digest = ring::digest::digest(&ring::digest::SHA256, path.to_string_lossy().as_bytes());
+        let name: String = digest.as_ref()[..16]
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        self.dir.join(name)
+    }
+
+    fn read_index(&self, path: &Path) -> Index {
+        let index_path = self.file_dir(path).join(INDEX_FILE);
+        let Ok(json) = std::fs::read_to_string(&index_path) else {
+            return Index::default();
+        };
+        match serde_json::from_str(&json) {
+            Ok(index) => index,
+            Err(e) => {
+                log::warn!("Ignoring unreadable {}: {}", index_path.display(), e);
+                Index::default()
+            }
+        }
+    }
+
+    fn write_index(&self, path: &Path, index: &Index) -> Result<(), String> {
+        let index_path = self.file_dir(path).join(INDEX_FILE);
+        let json = serde_json::to_string(index)
+            .map_err(|e| format!("Failed to serialize history of {}: {}", path.display(), e))?;
+        let tmp = index_path.with_extension("tmp");
+        std::fs::write(&tmp, json)
+            .map_err(|e| format!("Failed to write {}: {}", tmp.display(), e))?;
+        std::fs::rename(&tmp, &index_path)
+            .map_err(|e| format!("Failed to move {} into place: {}", index_path.display(), e))
+    }
+}
+
+/// `<id>.<ext>` for files with an extension, otherwise just `<id>`.
+fn version_name(path: &Path, id: &str) -> String {
+    match path.extension() {
+        Some(ext) => format!("{}.{}", id, ext.to_string_lossy()),
+        None => id.to_string(),
+    }
+}
+
+fn hex_digest(content: &[u8]) -> String {
+    ring::digest::digest(&ring::digest::SHA256, content)
+        .as_ref()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+#[cfg(unix)]
+fn restrict_permissions(path: &Path, mode: u32) {
+    use std::os::unix::fs::PermissionsExt;
+    if let Err(e) = std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode)) {
+        log::warn!("Failed to set permissions on {}: {}", path.display(), e);
+    }
+}
+
+#[cfg(not(unix))]
+fn restrict_permissions(_path: &Path, _mode: u32) {}
+
+static HISTORY: Mutex<Option<LocalHistory>> = Mutex::new(None);
+
+/// Keep local history under `state_dir` from now on. Until this is called
+/// nothing is recorded.
+pub fn set_state_dir(state_dir: &Path) {
+    if let Ok(mut history) = HISTORY.lock() {
+        *history = Some(LocalHistory::new(state_dir));
+    }
+}
+
+fn with_history<T>(f: impl FnOnce(&LocalHistory) -> T) -> Option<T> {
+    HISTORY.lock().ok()?.as_ref().map(f)
+}
+
+/// Record a save of `path`. Frontends call this after every successful
+/// save; failures are only logged.
+pub fn record(path: &Path, content: &[u8]) {
+    if let Some(Err(e)) = with_history(|h| h.record(path, content)) {
+        log::warn!("Local history: {}", e);
+    }
+}
+
+/// See [`LocalHistory::entries`].
+pub fn entries(path: &Path) -> Vec<HistoryEntry> {
+    with_history(|h| h.entries(path)).unwrap_or_default()
+}
+
+/// See [`LocalHistory::version_path`].
+pub fn version_path(path: &Path, id: &str) -> Option<PathBuf> {
+    with_history(|h| h.version_path(path, id)).flatten()
+}
+
+/// See [`LocalHistory::read`].
+pub fn read(path: &Path, id: &str) -> Result<String, String> {
+    with_history(|h| h.read(path, id))
+        .unwrap_or_else(|| Err("Local history is not available".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_versions_newest_first_and_skips_unchanged_saves() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = LocalHistory::new(dir.path());
+        let file = Path::new("/project/src/main.rs");
+
+        assert!(history.record(file, b"one").unwrap());
+        assert!(!history.record(file, b"one").unwrap());
+        assert!(history.record(file, b"two").unwrap());
+
+        let entries = history.entries(file);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(history.read(file, &entries[0].id).unwrap(), "two");
+        assert_eq!(history.read(file, &entries[1].id).unwrap(), "one");
+        let version = history.version_path(file, &entries[0].id).unwrap();
+        assert_eq!(version.extension().unwrap(), "rs");
+
+        assert!(history.entries(Path::new("/project/other.rs")).is_empty());
+        assert!(history.read(file, "../index.json").is_err());
+    }
+
+    #[test]
+    fn keeps_only_the_newest_versions_and_skips_large_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let history = LocalHistory::new(dir.path());
+        let file = Path::new("/project/notes");
+
+        for i in 0..MAX_ENTRIES_PER_FILE + 5 {
+            history.record(file, i.to_string().as_bytes()).unwrap();
+        }
+        let entries = history.entries(file);
+        assert_eq!(entries.len(), MAX_ENTRIES_PER_FILE);
+        let oldest = entries.last().unwrap();
+        assert_eq!(history.read(file, &oldest.id).unwrap(), "5");
+        let stored = std::fs::read_dir(history.file_dir(file)).unwrap().count();
+        assert_eq!(stored, MAX_ENTRIES_PER_FILE + 1);
+
+        let big = vec![b'x'; MAX_FILE_BYTES + 1];
+        assert!(!history.record(Path::new("/project/big.txt"), &big).unwrap());
+    }
+}
@@ -0,0 +1,215 @@
+//! Line-oriented Unix socket servers for local clients (the MCP and control
+//! sockets): one message per line in, at most one reply line out. Sockets
+//! live in an app-owned directory locked down to the owner before they are
+//! bound, and one left behind by a crash is replaced.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Replies to one request line; `None` sends nothing back.
+pub(crate) type LineHandler = Arc<dyn Fn(&str) -> Option<String> + Send + Sync>;
+
+/// A listening socket. Dropping it stops listening, disconnects every
+/// client and removes the socket file.
+pub(crate) struct LineServer {
+    socket: PathBuf,
+    #[cfg(unix)]
+    stopped: Arc<std::sync::atomic::AtomicBool>,
+    #[cfg(unix)]
+    connections: Arc<parking_lot::Mutex<Vec<std::os::unix::net::UnixStream>>>,
+}
+
+impl LineServer {
+    /// Listen on `socket`, answering each line with `handle` on a thread per
+    /// connection. Fails when another process is listening there. The
+    /// socket's directory must belong to the app (not `$XDG_RUNTIME_DIR`
+    /// itself): it is made owner-only before the socket is bound in it.
+    #[cfg(unix)]
+    pub(crate) fn start(socket: PathBuf, name: &str, handle: LineHandler) -> Result<Self, String> {
+        use std::os::unix::fs::PermissionsExt;
+        use std::os::unix::net::UnixListener;
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        let dir = socket
+            .parent()
+            .ok_or_else(|| format!("{} has no parent directory", socket.display()))?;
+        private_dir(dir)?;
+        if socket.exists() {
+            if is_listening(&socket) {
+                return Err(format!("{} is already in use", socket.display()));
+            }
+            // Left behind by a crash.
+            let _ = std::fs::remove_file(&socket);
+        }
+        let listener = UnixListener::bind(&socket)
+            .map_err(|e| format!("Failed to listen on {}: {}", socket.display(), e))?;
+        if let Err(e) = std::fs::set_permissions(&socket, std::fs::Permissions::from_mode(0o600)) {
+            let _ = std::fs::remove_file(&socket);
+            return Err(format!(
+                "Failed to set permissions on {}: {}",
+                socket.display(),
+                e
+            ));
+        }
+
+        let stopped = Arc::new(AtomicBool::new(false));
+        let connections = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let accept_stopped = stopped.clone();
+        let accept_connections = connections.clone();
+        let connection_name = format!("{}-connection", name);
+        std::thread::Builder::new()
+            .name(format!("{}-accept", name))
+            .spawn(move || {
+                for stream in listener.incoming() {
+                    if accept_stopped.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    let stream = match stream {
+                        Ok(stream) => stream,
+                        Err(e) => {
+                            log::warn!("Socket connection failed: {}", e);
+                            continue;
+                        }
+                    };
+                    if let Ok(clone) = stream.try_clone() {
+                        accept_connections.lock().push(clone);
+                    }
+                    let handle = handle.clone();
+                    if let Err(e) = std::thread::Builder::new()
+                        .name(connection_name.clone())
+                        .spawn(move || serve_connection(stream, &*handle))
+                    {
+                        log::warn!("Failed to start socket connection thread: {}", e);
+                    }
+                }
+            })
+            .map_err(|e| format!("Failed to start socket thread: {}", e))?;
+
+        Ok(Self {
+            socket,
+            stopped,
+            connections,
+        })
+    }
+
+    #[cfg(not(unix))]
+    pub(crate) fn start(
+        _socket: PathBuf,
+        _name: &str,
+        _handle: LineHandler,
+    ) -> Result<Self, String> {
+        Err("Local sockets are not supported on this platform".to_string())
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.socket
+    }
+}
+
+impl Drop for LineServer {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        {
+            self.stopped
+                .store(true, std::sync::atomic::Ordering::Relaxed);
+            for stream in self.connections.lock().drain(..) {
+                let _ = stream.shutdown(std::net::Shutdown::Both);
+            }
+            // Wake the accept loop so it sees `stopped`.
+            let _ = std::os::unix::net::UnixStream::connect(&self.socket);
+        }
+        let _ = std::fs::remove_file(&self.socket);
+    }
+}
+
+#[cfg(unix)]
+fn serve_connection(
+    stream: std::os::unix::net::UnixStream,
+    handle: &dyn Fn(&str) -> Option<String>,
+) {
+    use std::io::{BufRead, BufReader, Write};
+
+    let mut writer = match stream.try_clone() {
+        Ok(writer) => writer,
+        Err(e) => {
+            log::warn!("Socket connection failed: {}", e);
+            return;
+        }
+    };
+    for line in BufReader::new(stream).lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Some(reply) = handle(&line) {
+            if writeln!(writer, "{}", reply)
+                .and_then(|()| writer.flush())
+                .is_err()
+            {
+                break;
+            }
+        }
+    }
+}
+
+/// Create `dir` (and any missing parents) owner-only, or lock down an
+/// existing one. Refuses a symlink, and a directory this user can't chmod
+/// because someone else owns it.
+#[cfg(unix)]
+fn private_dir(dir: &Path) -> Result<(), String> {
+    use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+
+    std::fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let meta = std::fs::symlink_metadata(dir)
+        .map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?;
+    if !meta.is_dir() {
+        return Err(format!("{} is not a directory", dir.display()));
+    }
+    if meta.permissions().mode() & 0o077 != 0 {
+        std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))
+            .map_err(|e| format!("Failed to set permissions on {}: {}", dir.display(), e))?;
+    }
+    Ok(())
+}
+
+/// Whether a server is accepting connections on `socket`.
+pub(crate) fn is_listening(socket: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        std::os::unix::net::UnixStream::connect(socket).is_ok()
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = socket;
+        false
+    }
+}
+
+/// Send one request line to the server on `socket` and return its reply.
+#[cfg(unix)]
+pub(crate) fn request(socket: &Path, line: &str) -> Result<String, String> {
+    use std::io::{BufRead, BufReader, Write};
+
+    let mut stream = std::os::unix::net::UnixStream::connect(socket)
+        .map_err(|e| format!("Cannot connect to {}: {}", socket.display(), e))?;
+    writeln!(stream, "{}", line).map_err(|e| format!("Failed to send request: {}", e))?;
+    let mut reply = String::new();
+    BufReader::new(stream)
+        .read_line(&mut reply)
+        .map_err(|e| format!("Failed to read reply: {}", e))?;
+    if reply.is_empty() {
+        return Err("The connection closed without a reply".to_string());
+    }
+    Ok(reply.trim_end().to_string())
+}
+
+#[cfg(not(unix))]
+pub(crate) fn request(_socket: &Path, _line: &str) -> Result<String, String> {
+    Err("Local sockets are not supported on this platform".to_string())
+}
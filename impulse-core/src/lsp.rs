@@ -1,7 +1,9 @@
+use crate::lsp_diagnostics::DiagnosticStore;
+use crate::lsp_watch::WatchedFiles;
+use crate::settings::LspDebounce;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::ffi::OsString;
-use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Command as StdCommand;
 use std::str::FromStr;
@@ -12,27 +14,25 @@ use tokio::process::Command as TokioCommand;
 use tokio::sync::{mpsc, oneshot, Mutex as TokioMutex};
 use url::Url;
 
+type SharedDiagnostics = Arc<parking_lot::Mutex<DiagnosticStore>>;
+
+/// Cap on diagnostics per document to bound memory use with misbehaving
+/// servers.
+const MAX_DIAGNOSTICS: usize = 1000;
+
+/// How long to collect document changes before pulling diagnostics.
+const DIAGNOSTIC_PULL_DELAY: Duration = Duration::from_millis(200);
+
+/// How long a save waits for a server's `willSaveWaitUntil` edits before the
+/// file is written without them.
+pub const WILL_SAVE_TIMEOUT: Duration = Duration::from_millis(1500);
+
 type PendingRequests =
     Arc<TokioMutex<HashMap<i64, oneshot::Sender<Result<serde_json::Value, String>>>>>;
 
 const START_RETRY_COOLDOWN: Duration = Duration::from_secs(15);
 const IMPULSE_INSTALL_HINT: &str =
-    "Run `impulse --install-lsp-servers` (or `cargo run -p impulse-linux -- --install-lsp-servers`) to install managed web LSP servers.";
-
-const RECOMMENDED_WEB_LSP_PACKAGES: &[&str] = &[
-    "typescript",
-    "typescript-language-server",
-    "intelephense",
-    "vscode-langservers-extracted",
-    "@tailwindcss/language-server",
-    "@vue/language-server",
-    "svelte-language-server",
-    "graphql-language-service-cli",
-    "emmet-ls",
-    "yaml-language-server",
-    "dockerfile-language-server-nodejs",
-    "bash-language-server",
-];
+    "Install it from Settings > Language Servers, or run `impulse --install-lsp-servers` (or `cargo run -p impulse-linux -- --install-lsp-servers`).";
 
 const MANAGED_NPM_SERVER_COMMANDS: &[&str] = &[
     "typescript-language-server",
@@ -98,6 +98,114 @@ pub enum LspEvent {
         client_key: String,
         server_id: String,
     },
+    /// A server-initiated `workspace/applyEdit`, typically sent while it
+    /// executes a code action's command. Frontends apply `files` like a
+    /// confirmed rename.
+    ApplyEdit {
+        client_key: String,
+        server_id: String,
+        label: Option<String>,
+        files: Vec<crate::workspace_edit::FileEdits>,
+    },
+    /// A `$/progress` work-done report (indexing, analysis, ...).
+    Progress {
+        client_key: String,
+        server_id: String,
+        token: String,
+        progress: lsp_types::WorkDoneProgress,
+    },
+}
+
+struct ActiveProgress {
+    client_key: String,
+    server_id: String,
+    token: String,
+    title: String,
+    message: Option<String>,
+    percentage: Option<u32>,
+}
+
+/// Work-done progress the language servers are currently reporting, fed from
+/// [`LspEvent::Progress`] for display in a status bar.
+#[derive(Default)]
+pub struct LspProgress {
+    /// In begin order; the last entry is shown.
+    active: Vec<ActiveProgress>,
+}
+
+impl LspProgress {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Apply a progress report. `end` (or a report for an unknown token)
+    /// finishes the work for that token.
+    pub fn update(
+        &mut self,
+        client_key: &str,
+        server_id: &str,
+        token: &str,
+        progress: &lsp_types::WorkDoneProgress,
+    ) {
+        let index = self
+            .active
+            .iter()
+            .position(|p| p.client_key == client_key && p.token == token);
+        match progress {
+            lsp_types::WorkDoneProgress::Begin(begin) => {
+                if let Some(i) = index {
+                    self.active.remove(i);
+                }
+                self.active.push(ActiveProgress {
+                    client_key: client_key.to_string(),
+                    server_id: server_id.to_string(),
+                    token: token.to_string(),
+                    title: begin.title.clone(),
+                    message: begin.message.clone(),
+                    percentage: begin.percentage,
+                });
+            }
+            lsp_types::WorkDoneProgress::Report(report) => {
+                if let Some(active) = index.map(|i| &mut self.active[i]) {
+                    if report.message.is_some() {
+                        active.message = report.message.clone();
+                    }
+                    if report.percentage.is_some() {
+                        active.percentage = report.percentage;
+                    }
+                }
+            }
+            lsp_types::WorkDoneProgress::End(_) => {
+                if let Some(i) = index {
+                    self.active.remove(i);
+                }
+            }
+        }
+    }
+
+    /// Forget all progress from a server that exited.
+    pub fn clear_client(&mut self, client_key: &str) {
+        self.active.retain(|p| p.client_key != client_key);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.active.is_empty()
+    }
+
+    /// The most recently started work, e.g.
+    /// "rust-analyzer: Indexing 3/120 (2%)", or `None` when idle.
+    pub fn status_text(&self) -> Option<String> {
+        let active = self.active.last()?;
+        let mut text = format!("{}: {}", active.server_id, active.title);
+        if let Some(message) = active.message.as_deref().filter(|m| !m.is_empty()) {
+            text.push(' ');
+            text.push_str(message);
+        }
+        if let Some(percentage) = active.percentage {
+            text.push_str(&format!(" ({}%)", percentage));
+        }
+        Some(text)
+    }
 }
 
 fn parse_uri(s: &str) -> Result<lsp_types::Uri, String> {
@@ -116,9 +224,88 @@ fn text_document_sync_kind(
     capabilities: &lsp_types::ServerCapabilities,
 ) -> Option<lsp_types::TextDocumentSyncKind> {
     match capabilities.text_document_sync.as_ref()? {
-        lsp_types::TextDocumentSyncCapability::Kind(kind) => Some(kind.clone()),
-        lsp_types::TextDocumentSyncCapability::Options(options) => options.change.clone(),
+        lsp_types::TextDocumentSyncCapability::Kind(kind) => Some(*kind),
+        lsp_types::TextDocumentSyncCapability::Options(options) => options.change,
+    }
+}
+
+/// Whether the server wants `willSave` and `willSaveWaitUntil`, in that
+/// order. Only the options form of `textDocumentSync` can ask for them.
+fn will_save_support(capabilities: &lsp_types::ServerCapabilities) -> (bool, bool) {
+    match capabilities.text_document_sync.as_ref() {
+        Some(lsp_types::TextDocumentSyncCapability::Options(options)) => (
+            options.will_save == Some(true),
+            options.will_save_wait_until == Some(true),
+        ),
+        _ => (false, false),
+    }
+}
+
+/// Whether a code action of `kind` belongs to the requested `wanted` kind.
+/// Kinds are hierarchical: `source.organizeImports.rust` is a
+/// `source.organizeImports` and a `source` action.
+pub fn code_action_kind_matches(kind: &str, wanted: &str) -> bool {
+    kind == wanted
+        || kind
+            .strip_prefix(wanted)
+            .is_some_and(|rest| rest.starts_with('.'))
+}
+
+/// The position just past the last character of `text`, in LSP terms.
+pub(crate) fn document_end(text: &str) -> lsp_types::Position {
+    let line = text.matches('\n').count() as u32;
+    let last = text.rsplit('\n').next().unwrap_or("");
+    lsp_types::Position::new(line, last.encode_utf16().count() as u32)
+}
+
+/// Whether typing `ch` should ask the server for on-type formatting edits.
+fn triggers_on_type_formatting(capabilities: &lsp_types::ServerCapabilities, ch: &str) -> bool {
+    capabilities
+        .document_on_type_formatting_provider
+        .as_ref()
+        .is_some_and(|provider| {
+            provider.first_trigger_character == ch
+                || provider
+                    .more_trigger_character
+                    .as_ref()
+                    .is_some_and(|more| more.iter().any(|c| c == ch))
+        })
+}
+
+/// Apply `textDocument/didChange` content changes to a host-side copy of a
+/// document, in order, as a server would. Positions are UTF-16 based. A
+/// change without a range replaces the whole text. Fails on the first range
+/// that does not fit the text, which means the copy is out of sync.
+pub fn apply_content_changes(
+    content: &mut String,
+    changes: &[lsp_types::TextDocumentContentChangeEvent],
+) -> Result<(), String> {
+    for change in changes {
+        let Some(range) = change.range else {
+            *content = change.text.clone();
+            continue;
+        };
+        let offset = |position: lsp_types::Position| {
+            crate::workspace_edit::checked_position_to_byte_offset(
+                content,
+                position.line,
+                position.character,
+            )
+        };
+        let (start, end) = (offset(range.start), offset(range.end));
+        match (start, end) {
+            (Some(start), Some(end)) if start <= end => {
+                content.replace_range(start..end, &change.text);
+            }
+            _ => {
+                return Err(format!(
+                    "Change range {}:{}-{}:{} is outside the document",
+                    range.start.line, range.start.character, range.end.line, range.end.character
+                ))
+            }
+        }
     }
+    Ok(())
 }
 
 fn workspace_folder_name(root_uri: &str) -> String {
@@ -141,7 +328,7 @@ pub fn managed_lsp_root_dir() -> Option<PathBuf> {
 
     // Prefer whichever location actually has an installation.
     for candidate in xdg.iter().chain(native.iter()) {
-        if candidate.join("node_modules").is_dir() {
+        if candidate.join("node_modules").is_dir() || candidate.join("bin").is_dir() {
             return Some(candidate.clone());
         }
     }
@@ -157,11 +344,11 @@ pub fn managed_web_lsp_commands() -> &'static [&'static str] {
     MANAGED_NPM_SERVER_COMMANDS
 }
 
-fn command_looks_like_path(command: &str) -> bool {
+pub(crate) fn command_looks_like_path(command: &str) -> bool {
     command.contains(std::path::MAIN_SEPARATOR)
 }
 
-fn is_executable_file(path: &Path) -> bool {
+pub(crate) fn is_executable_file(path: &Path) -> bool {
     #[cfg(unix)]
     {
         use std::os::unix::fs::PermissionsExt;
@@ -176,13 +363,19 @@ fn is_executable_file(path: &Path) -> bool {
     }
 }
 
-fn find_command_in_path(command: &str) -> Option<PathBuf> {
+/// Find `command` in the login shell's PATH (see `environment`).
+pub(crate) fn find_command_in_path(command: &str) -> Option<PathBuf> {
+    find_command_in(command, crate::environment::search_path(None))
+}
+
+/// Find `command` in `path_env`, a PATH-style list.
+pub(crate) fn find_command_in(command: &str, path_env: Option<String>) -> Option<PathBuf> {
     if command_looks_like_path(command) {
         let path = PathBuf::from(command);
         return is_executable_file(&path).then_some(path);
     }
 
-    let path_env: OsString = std::env::var_os("PATH")?;
+    let path_env = OsString::from(path_env?);
     for dir in std::env::split_paths(&path_env) {
         let candidate = dir.join(command);
         if is_executable_file(&candidate) {
@@ -192,21 +385,35 @@ fn find_command_in_path(command: &str) -> Option<PathBuf> {
     None
 }
 
+/// Whether `command` is installed outside Impulse's managed directory.
+pub(crate) fn command_in_path(command: &str) -> bool {
+    find_command_in_path(command).is_some()
+}
+
 fn find_managed_command(command: &str) -> Option<PathBuf> {
-    let managed = managed_lsp_bin_dir()?.join(command);
-    is_executable_file(&managed).then_some(managed)
+    [
+        crate::lsp_install::managed_binary_dir(),
+        managed_lsp_bin_dir(),
+    ]
+    .into_iter()
+    .flatten()
+    .map(|dir| dir.join(command))
+    .find(|path| is_executable_file(path))
 }
 
 pub fn resolve_lsp_command_path(command: &str) -> Option<PathBuf> {
     find_command_in_path(command).or_else(|| find_managed_command(command))
 }
 
-fn is_managed_npm_server_command(command: &str) -> bool {
+fn is_managed_server_command(command: &str) -> bool {
     MANAGED_NPM_SERVER_COMMANDS.contains(&command)
+        || crate::lsp_install::MANAGED_BINARIES
+            .iter()
+            .any(|binary| binary.command == command && binary.is_supported())
 }
 
 fn missing_command_message(server_id: &str, command: &str) -> String {
-    if is_managed_npm_server_command(command) {
+    if is_managed_server_command(command) {
         format!(
             "LSP server '{}' requires '{}' but it is not installed. {}",
             server_id, command, IMPULSE_INSTALL_HINT
@@ -229,53 +436,6 @@ pub fn npm_is_available() -> bool {
         .unwrap_or(false)
 }
 
-pub fn install_managed_web_lsp_servers() -> Result<PathBuf, String> {
-    if !npm_is_available() {
-        return Err(
-            "npm is required but was not found in PATH. Install Node.js + npm first.".to_string(),
-        );
-    }
-
-    let root = managed_lsp_root_dir()
-        .ok_or_else(|| "Unable to determine data directory for managed LSPs".to_string())?;
-    fs::create_dir_all(&root).map_err(|e| format!("Failed to create {}: {}", root.display(), e))?;
-
-    let package_json = root.join("package.json");
-    if !package_json.exists() {
-        let package_doc = serde_json::json!({
-            "name": "impulse-lsp-servers",
-            "private": true,
-            "description": "Managed web LSP dependencies for Impulse",
-            "license": "UNLICENSED"
-        });
-        let content = serde_json::to_string_pretty(&package_doc)
-            .map_err(|e| format!("Failed to serialize package.json: {}", e))?;
-        fs::write(&package_json, content)
-            .map_err(|e| format!("Failed to write {}: {}", package_json.display(), e))?;
-    }
-
-    let status = StdCommand::new("npm")
-        .arg("install")
-        .arg("--prefix")
-        .arg(&root)
-        .arg("--no-audit")
-        .arg("--no-fund")
-        .args(RECOMMENDED_WEB_LSP_PACKAGES)
-        .status()
-        .map_err(|e| format!("Failed to run npm install: {}", e))?;
-
-    if !status.success() {
-        return Err(format!(
-            "npm install failed with status {} while installing managed LSP servers",
-            status
-        ));
-    }
-
-    managed_lsp_bin_dir().ok_or_else(|| {
-        "Installation completed but managed bin directory could not be determined".to_string()
-    })
-}
-
 #[derive(Debug, Clone)]
 pub struct LspCommandStatus {
     pub command: String,
@@ -296,14 +456,18 @@ const SYSTEM_LSP_SERVERS: &[(&str, &str)] = &[
     ("rust-analyzer", "rust-analyzer"),
     ("pyright", "pyright-langserver"),
     ("clangd", "clangd"),
+    ("gopls", "gopls"),
+    ("lua-language-server", "lua-language-server"),
 ];
 
+/// Servers outside the npm set. rust-analyzer, clangd, gopls and
+/// lua-language-server resolve to a managed binary when none is in PATH.
 pub fn system_lsp_status() -> Vec<LspCommandStatus> {
     SYSTEM_LSP_SERVERS
         .iter()
         .map(|(id, cmd)| LspCommandStatus {
             command: (*id).to_string(),
-            resolved_path: find_command_in_path(cmd),
+            resolved_path: resolve_lsp_command_path(cmd),
         })
         .collect()
 }
@@ -342,12 +506,120 @@ fn send_jsonrpc_error(
     }
 }
 
+/// The newest request of one debounced method for one document.
+#[derive(Default)]
+struct LatestRequest {
+    generation: u64,
+    /// Request id once sent, until the response arrives.
+    in_flight: Option<i64>,
+}
+
+/// What a debounced request competes with: the same method for the same
+/// document, so split editors on two files don't cancel each other.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct SupersessionKey {
+    method: String,
+    uri: Option<String>,
+}
+
+impl SupersessionKey {
+    fn new(method: &str, params: &serde_json::Value) -> Self {
+        Self {
+            method: method.to_string(),
+            uri: params
+                .pointer("/textDocument/uri")
+                .and_then(|uri| uri.as_str())
+                .map(str::to_string),
+        }
+    }
+}
+
+/// Tracks the newest request per debounced method and document so an older
+/// one is dropped before it is sent, or cancelled with `$/cancelRequest`
+/// once it is.
+#[derive(Default)]
+struct RequestSupersession {
+    latest: HashMap<SupersessionKey, LatestRequest>,
+}
+
+impl RequestSupersession {
+    /// Register a new request for `key`. Returns its generation and the id
+    /// of the in-flight request it supersedes.
+    fn begin(&mut self, key: &SupersessionKey) -> (u64, Option<i64>) {
+        let latest = self.latest.entry(key.clone()).or_default();
+        latest.generation += 1;
+        (latest.generation, latest.in_flight.take())
+    }
+
+    fn is_current(&self, key: &SupersessionKey, generation: u64) -> bool {
+        self.latest
+            .get(key)
+            .is_some_and(|latest| latest.generation == generation)
+    }
+
+    /// Record that request `generation` was sent as `id`. Returns `false` when
+    /// a newer request arrived in the meantime.
+    fn sent(&mut self, key: &SupersessionKey, generation: u64, id: i64) -> bool {
+        match self.latest.get_mut(key) {
+            Some(latest) if latest.generation == generation => {
+                latest.in_flight = Some(id);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Forget the request sent as `id`; the entry goes once nothing for its
+    /// document is in flight, so closed documents don't accumulate.
+    fn finished(&mut self, key: &SupersessionKey, generation: u64, id: i64) {
+        if let Some(latest) = self.latest.get_mut(key) {
+            if latest.in_flight == Some(id) {
+                latest.in_flight = None;
+            }
+            if latest.generation == generation && latest.in_flight.is_none() {
+                self.latest.remove(key);
+            }
+        }
+    }
+}
+
+/// How long to hold a request of `method` before sending it, or `None` for
+/// methods that are never superseded.
+fn debounce_interval(debounce: &LspDebounce, method: &str) -> Option<Duration> {
+    let ms = match method {
+        "textDocument/completion" => debounce.completion_ms,
+        "textDocument/hover" => debounce.hover_ms,
+        "textDocument/signatureHelp" => debounce.signature_help_ms,
+        "textDocument/codeAction" => debounce.code_action_ms,
+        _ => return None,
+    };
+    Some(Duration::from_millis(ms))
+}
+
+/// Error returned for a request dropped in favor of a newer one.
+pub const REQUEST_SUPERSEDED: &str = "Request superseded";
+
+/// Per-server state the reader task needs to answer server requests and
+/// notifications.
+struct ServerContext {
+    sender: mpsc::UnboundedSender<Vec<u8>>,
+    event_tx: mpsc::UnboundedSender<LspEvent>,
+    client_key: String,
+    server_id: String,
+    root_uri: String,
+    watched_files: Option<Arc<WatchedFiles>>,
+    diagnostics: SharedDiagnostics,
+}
+
 pub struct LspClient {
     sender: mpsc::UnboundedSender<Vec<u8>>,
     pending: PendingRequests,
     next_id: Arc<TokioMutex<i64>>,
     pub capabilities: Arc<TokioMutex<Option<lsp_types::ServerCapabilities>>>,
     change_sync_kind: Arc<StdMutex<Option<lsp_types::TextDocumentSyncKind>>>,
+    debounce: Arc<StdMutex<LspDebounce>>,
+    supersession: StdMutex<RequestSupersession>,
+    diagnostics: SharedDiagnostics,
     event_tx: mpsc::UnboundedSender<LspEvent>,
     client_key: String,
     server_id: String,
@@ -390,8 +662,11 @@ impl LspClient {
             client_key
         );
 
+        let root_path = uri_to_file_path(root_uri);
         let mut child = TokioCommand::new(command)
             .args(args)
+            .env_clear()
+            .envs(crate::environment::resolve(root_path.as_deref()))
             .stdin(std::process::Stdio::piped())
             .stdout(std::process::Stdio::piped())
             .stderr(std::process::Stdio::piped())
@@ -408,24 +683,17 @@ impl LspClient {
 
         tokio::spawn(Self::writer_task(stdin, receiver));
 
-        let pending_clone = pending.clone();
-        let event_tx_clone = event_tx.clone();
-        let sender_clone = sender.clone();
-        let client_key_reader = client_key.to_string();
-        let server_id_reader = server_id.to_string();
-        let root_uri_reader = root_uri.to_string();
-        tokio::spawn(async move {
-            Self::reader_task(
-                stdout,
-                pending_clone,
-                sender_clone,
-                event_tx_clone,
-                &client_key_reader,
-                &server_id_reader,
-                &root_uri_reader,
-            )
-            .await;
-        });
+        let diagnostics = SharedDiagnostics::default();
+        let server = ServerContext {
+            sender: sender.clone(),
+            event_tx: event_tx.clone(),
+            client_key: client_key.to_string(),
+            server_id: server_id.to_string(),
+            root_uri: root_uri.to_string(),
+            watched_files: WatchedFiles::new(root_uri, sender.clone()),
+            diagnostics: diagnostics.clone(),
+        };
+        tokio::spawn(Self::reader_task(stdout, pending.clone(), server));
 
         if let Some(stderr) = stderr {
             let cmd_name = command.to_string();
@@ -504,6 +772,9 @@ impl LspClient {
             next_id,
             capabilities: Arc::new(TokioMutex::new(None)),
             change_sync_kind: Arc::new(StdMutex::new(None)),
+            debounce: Arc::new(StdMutex::new(LspDebounce::default())),
+            supersession: StdMutex::new(RequestSupersession::default()),
+            diagnostics,
             event_tx: event_tx.clone(),
             client_key: client_key.to_string(),
             server_id: server_id.to_string(),
@@ -540,11 +811,7 @@ impl LspClient {
     async fn reader_task(
         stdout: tokio::process::ChildStdout,
         pending: PendingRequests,
-        sender: mpsc::UnboundedSender<Vec<u8>>,
-        event_tx: mpsc::UnboundedSender<LspEvent>,
-        client_key: &str,
-        server_id: &str,
-        root_uri: &str,
+        server: ServerContext,
     ) {
         let mut reader = BufReader::new(stdout);
         const MAX_HEADER_LINE: usize = 8192;
@@ -656,13 +923,11 @@ impl LspClient {
 
             if let Some(method) = &msg.method {
                 if let Some(id) = msg.id {
-                    Self::handle_server_request(method, id, msg.params, &sender, root_uri);
+                    Self::handle_server_request(method, id, msg.params, &server);
                     continue;
                 }
 
-                Self::handle_server_notification(
-                    method, msg.params, &event_tx, client_key, server_id,
-                );
+                Self::handle_server_notification(method, msg.params, &server);
             }
         }
     }
@@ -671,9 +936,10 @@ impl LspClient {
         method: &str,
         id: serde_json::Value,
         params: Option<serde_json::Value>,
-        sender: &mpsc::UnboundedSender<Vec<u8>>,
-        root_uri: &str,
+        server: &ServerContext,
     ) {
+        let sender = &server.sender;
+        let root_uri = server.root_uri.as_str();
         match method {
             "workspace/configuration" => {
                 let items = params
@@ -697,6 +963,35 @@ impl LspClient {
                 };
                 send_jsonrpc_result(sender, id, result);
             }
+            "workspace/applyEdit" => {
+                let Some(params) = params.and_then(|p| {
+                    serde_json::from_value::<lsp_types::ApplyWorkspaceEditParams>(p).ok()
+                }) else {
+                    send_jsonrpc_error(sender, id, -32602, "Invalid params");
+                    return;
+                };
+                // Applied on the frontend's main thread without waiting for
+                // it, so the result is optimistic.
+                let _ = server.event_tx.send(LspEvent::ApplyEdit {
+                    client_key: server.client_key.clone(),
+                    server_id: server.server_id.clone(),
+                    label: params.label,
+                    files: crate::workspace_edit::group_workspace_edit(&params.edit),
+                });
+                send_jsonrpc_result(
+                    sender,
+                    id,
+                    serde_json::json!(lsp_types::ApplyWorkspaceEditResponse {
+                        applied: true,
+                        failure_reason: None,
+                        failed_change: None,
+                    }),
+                );
+            }
+            "workspace/diagnostic/refresh" => {
+                server.diagnostics.lock().schedule_all();
+                send_jsonrpc_result(sender, id, serde_json::Value::Null);
+            }
             "window/workDoneProgress/create" => {
                 send_jsonrpc_result(sender, id, serde_json::Value::Null);
             }
@@ -707,7 +1002,33 @@ impl LspClient {
                 });
                 send_jsonrpc_result(sender, id, serde_json::Value::Array(vec![folder]));
             }
-            "client/registerCapability" | "client/unregisterCapability" => {
+            "client/registerCapability" => {
+                let registrations = params
+                    .and_then(|p| serde_json::from_value::<lsp_types::RegistrationParams>(p).ok())
+                    .map(|p| p.registrations)
+                    .unwrap_or_default();
+                for registration in registrations {
+                    if registration.method != "workspace/didChangeWatchedFiles" {
+                        continue;
+                    }
+                    if let (Some(watched_files), Some(options)) =
+                        (&server.watched_files, registration.register_options)
+                    {
+                        watched_files.register(&registration.id, options);
+                    }
+                }
+                send_jsonrpc_result(sender, id, serde_json::Value::Null);
+            }
+            "client/unregisterCapability" => {
+                let unregistrations = params
+                    .and_then(|p| serde_json::from_value::<lsp_types::UnregistrationParams>(p).ok())
+                    .map(|p| p.unregisterations)
+                    .unwrap_or_default();
+                if let Some(watched_files) = &server.watched_files {
+                    for unregistration in unregistrations {
+                        watched_files.unregister(&unregistration.id);
+                    }
+                }
                 send_jsonrpc_result(sender, id, serde_json::Value::Null);
             }
             _ => {
@@ -719,10 +1040,9 @@ impl LspClient {
     fn handle_server_notification(
         method: &str,
         params: Option<serde_json::Value>,
-        event_tx: &mpsc::UnboundedSender<LspEvent>,
-        _client_key: &str,
-        _server_id: &str,
+        server: &ServerContext,
     ) {
+        let event_tx = &server.event_tx;
         match method {
             "textDocument/publishDiagnostics" => {
                 if let Some(params) = params {
@@ -730,7 +1050,6 @@ impl LspClient {
                         serde_json::from_value::<lsp_types::PublishDiagnosticsParams>(params)
                     {
                         // Cap diagnostics to prevent memory exhaustion from malicious servers
-                        const MAX_DIAGNOSTICS: usize = 1000;
                         if diag_params.diagnostics.len() > MAX_DIAGNOSTICS {
                             log::warn!(
                                 "Truncating diagnostics for {} from {} to {}",
@@ -740,10 +1059,15 @@ impl LspClient {
                             );
                             diag_params.diagnostics.truncate(MAX_DIAGNOSTICS);
                         }
+                        let uri = diag_params.uri.to_string();
+                        let merged = server
+                            .diagnostics
+                            .lock()
+                            .set_pushed(&uri, diag_params.diagnostics);
                         let _ = event_tx.send(LspEvent::Diagnostics {
-                            uri: diag_params.uri.to_string(),
+                            uri,
                             version: diag_params.version,
-                            diagnostics: diag_params.diagnostics,
+                            diagnostics: merged,
                         });
                     }
                 }
@@ -755,17 +1079,95 @@ impl LspClient {
                     }
                 }
             }
-            "$/logTrace" | "$/progress" => {}
+            "$/progress" => {
+                let Some(params) = params
+                    .and_then(|p| serde_json::from_value::<lsp_types::ProgressParams>(p).ok())
+                else {
+                    return;
+                };
+                let lsp_types::ProgressParamsValue::WorkDone(progress) = params.value;
+                let token = match params.token {
+                    lsp_types::NumberOrString::Number(n) => n.to_string(),
+                    lsp_types::NumberOrString::String(s) => s,
+                };
+                let _ = event_tx.send(LspEvent::Progress {
+                    client_key: server.client_key.clone(),
+                    server_id: server.server_id.clone(),
+                    token,
+                    progress,
+                });
+            }
+            "$/logTrace" => {}
             _ => {
                 log::debug!("Unhandled LSP notification: {}", method);
             }
         }
     }
 
+    fn supersession(&self) -> std::sync::MutexGuard<'_, RequestSupersession> {
+        self.supersession
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Drop a request the server no longer needs to answer. Its waiter gets
+    /// "Request cancelled".
+    async fn cancel_request(&self, id: i64) {
+        self.pending.lock().await.remove(&id);
+        let _ = self.notify("$/cancelRequest", serde_json::json!({ "id": id }));
+    }
+
+    /// Send a request and wait for its result. For completion, hover,
+    /// signature help and code actions a newer request of the same method
+    /// for the same document supersedes this one: it is held for the
+    /// method's debounce interval and never sent if superseded meanwhile, and
+    /// cancelled server-side if it was already sent.
     pub async fn request<P: Serialize>(
         &self,
         method: &str,
         params: P,
+    ) -> Result<serde_json::Value, String> {
+        let params = serde_json::to_value(params).map_err(|e| e.to_string())?;
+        let delay = self
+            .debounce
+            .lock()
+            .ok()
+            .and_then(|debounce| debounce_interval(&debounce, method));
+        let Some(delay) = delay else {
+            return self.send_request(method, params, None).await;
+        };
+
+        let key = SupersessionKey::new(method, &params);
+        let (generation, superseded) = self.supersession().begin(&key);
+        if let Some(old_id) = superseded {
+            self.cancel_request(old_id).await;
+        }
+        if !delay.is_zero() {
+            tokio::time::sleep(delay).await;
+        }
+        if !self.supersession().is_current(&key, generation) {
+            return Err(REQUEST_SUPERSEDED.to_string());
+        }
+        self.send_request(method, params, Some((&key, generation)))
+            .await
+    }
+
+    /// Send a request right away, never debounced or superseded, for
+    /// requests the user asked for or a save waits on.
+    pub async fn request_now<P: Serialize>(
+        &self,
+        method: &str,
+        params: P,
+    ) -> Result<serde_json::Value, String> {
+        let params = serde_json::to_value(params).map_err(|e| e.to_string())?;
+        self.send_request(method, params, None).await
+    }
+
+    async fn send_request(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+        superseding: Option<(&SupersessionKey, u64)>,
     ) -> Result<serde_json::Value, String> {
         let id = {
             let mut next = self.next_id.lock().await;
@@ -778,7 +1180,7 @@ impl LspClient {
             jsonrpc: "2.0",
             id,
             method: method.to_string(),
-            params: Some(serde_json::to_value(params).map_err(|e| e.to_string())?),
+            params: Some(params),
         };
 
         let body = serde_json::to_vec(&request).map_err(|e| e.to_string())?;
@@ -791,8 +1193,15 @@ impl LspClient {
 
         self.sender.send(body).map_err(|e| e.to_string())?;
 
+        if let Some((key, generation)) = superseding {
+            // A newer request may have started while this one was being sent.
+            if !self.supersession().sent(key, generation, id) {
+                self.cancel_request(id).await;
+            }
+        }
+
         let timeout = lsp_request_timeout(method);
-        match tokio::time::timeout(timeout, rx).await {
+        let result = match tokio::time::timeout(timeout, rx).await {
             Ok(result) => result.map_err(|_| "Request cancelled".to_string())?,
             Err(_) => {
                 // Remove the pending request so the oneshot sender is dropped
@@ -804,17 +1213,131 @@ impl LspClient {
                     timeout.as_secs()
                 ))
             }
+        };
+        if let Some((key, generation)) = superseding {
+            self.supersession().finished(key, generation, id);
         }
+        result
     }
 
     pub fn notify<P: Serialize>(&self, method: &str, params: P) -> Result<(), String> {
+        let params = serde_json::to_value(params).map_err(|e| e.to_string())?;
+        let document_uri = method
+            .starts_with("textDocument/did")
+            .then(|| {
+                params
+                    .pointer("/textDocument/uri")?
+                    .as_str()
+                    .map(str::to_string)
+            })
+            .flatten();
         let msg = serde_json::json!({
             "jsonrpc": "2.0",
             "method": method,
-            "params": serde_json::to_value(params).map_err(|e| e.to_string())?
+            "params": params,
         });
         let body = serde_json::to_vec(&msg).map_err(|e| e.to_string())?;
-        self.sender.send(body).map_err(|e| e.to_string())
+        self.sender.send(body).map_err(|e| e.to_string())?;
+        if let Some(uri) = document_uri {
+            self.diagnostics.lock().document_notified(method, &uri);
+        }
+        Ok(())
+    }
+
+    /// Pull diagnostics (`textDocument/diagnostic`) after document
+    /// open/change/save notifications if the server supports the pull model.
+    /// Changes are collected briefly so a burst of edits costs one pull.
+    pub async fn start_diagnostic_pulls(self: &Arc<Self>) {
+        let identifier = {
+            let capabilities = self.capabilities.lock().await;
+            match capabilities
+                .as_ref()
+                .and_then(|c| c.diagnostic_provider.as_ref())
+            {
+                Some(lsp_types::DiagnosticServerCapabilities::Options(options)) => {
+                    options.identifier.clone()
+                }
+                Some(lsp_types::DiagnosticServerCapabilities::RegistrationOptions(options)) => {
+                    options.diagnostic_options.identifier.clone()
+                }
+                None => return,
+            }
+        };
+
+        let (tx, mut rx) = mpsc::unbounded_channel::<String>();
+        self.diagnostics.lock().set_pull_sender(tx);
+        // The task ends when the client (and with it the sender) is dropped.
+        let client = Arc::downgrade(self);
+        tokio::spawn(async move {
+            while let Some(first) = rx.recv().await {
+                tokio::time::sleep(DIAGNOSTIC_PULL_DELAY).await;
+                let mut uris = vec![first];
+                while let Ok(uri) = rx.try_recv() {
+                    if !uris.contains(&uri) {
+                        uris.push(uri);
+                    }
+                }
+                let Some(client) = client.upgrade() else {
+                    break;
+                };
+                for uri in uris {
+                    client.pull_diagnostics(&uri, identifier.clone()).await;
+                }
+            }
+        });
+    }
+
+    async fn pull_diagnostics(&self, uri: &str, identifier: Option<String>) {
+        let Ok(text_document_uri) = parse_uri(uri) else {
+            return;
+        };
+        let previous_result_id = self.diagnostics.lock().previous_result_id(uri);
+        let result = self
+            .request(
+                "textDocument/diagnostic",
+                lsp_types::DocumentDiagnosticParams {
+                    text_document: lsp_types::TextDocumentIdentifier {
+                        uri: text_document_uri,
+                    },
+                    identifier,
+                    previous_result_id,
+                    work_done_progress_params: Default::default(),
+                    partial_result_params: Default::default(),
+                },
+            )
+            .await;
+        let report = match result.and_then(|v| serde_json::from_value(v).map_err(|e| e.to_string()))
+        {
+            Ok(lsp_types::DocumentDiagnosticReportResult::Report(report)) => report,
+            Ok(lsp_types::DocumentDiagnosticReportResult::Partial(_)) => return,
+            Err(e) => {
+                log::debug!("Diagnostic pull for {} failed: {}", uri, e);
+                return;
+            }
+        };
+        match report {
+            lsp_types::DocumentDiagnosticReport::Full(full) => {
+                let mut report = full.full_document_diagnostic_report;
+                report.items.truncate(MAX_DIAGNOSTICS);
+                let merged =
+                    self.diagnostics
+                        .lock()
+                        .set_pulled(uri, report.result_id, report.items);
+                if let Some(merged) = merged {
+                    let _ = self.event_tx.send(LspEvent::Diagnostics {
+                        uri: uri.to_string(),
+                        version: None,
+                        diagnostics: merged,
+                    });
+                }
+            }
+            lsp_types::DocumentDiagnosticReport::Unchanged(unchanged) => {
+                self.diagnostics.lock().set_unchanged(
+                    uri,
+                    unchanged.unchanged_document_diagnostic_report.result_id,
+                );
+            }
+        }
     }
 
     #[allow(deprecated)]
@@ -833,15 +1356,28 @@ impl LspClient {
             workspace_folders,
             capabilities: lsp_types::ClientCapabilities {
                 workspace: Some(lsp_types::WorkspaceClientCapabilities {
+                    diagnostic: Some(lsp_types::DiagnosticWorkspaceClientCapabilities {
+                        refresh_support: Some(true),
+                    }),
                     configuration: Some(true),
                     workspace_folders: Some(true),
+                    apply_edit: Some(true),
+                    execute_command: Some(lsp_types::DynamicRegistrationClientCapabilities {
+                        dynamic_registration: Some(false),
+                    }),
+                    did_change_watched_files: Some(
+                        lsp_types::DidChangeWatchedFilesClientCapabilities {
+                            dynamic_registration: Some(true),
+                            relative_pattern_support: Some(true),
+                        },
+                    ),
                     ..Default::default()
                 }),
                 text_document: Some(lsp_types::TextDocumentClientCapabilities {
                     synchronization: Some(lsp_types::TextDocumentSyncClientCapabilities {
                         did_save: Some(true),
-                        will_save: Some(false),
-                        will_save_wait_until: Some(false),
+                        will_save: Some(true),
+                        will_save_wait_until: Some(true),
                         dynamic_registration: Some(false),
                     }),
                     completion: Some(lsp_types::CompletionClientCapabilities {
@@ -863,6 +1399,10 @@ impl LspClient {
                         ]),
                         ..Default::default()
                     }),
+                    diagnostic: Some(lsp_types::DiagnosticClientCapabilities {
+                        dynamic_registration: Some(false),
+                        related_document_support: Some(false),
+                    }),
                     publish_diagnostics: Some(lsp_types::PublishDiagnosticsClientCapabilities {
                         related_information: Some(true),
                         version_support: Some(true),
@@ -889,6 +1429,7 @@ impl LspClient {
                         prepare_support: Some(true),
                         ..Default::default()
                     }),
+                    on_type_formatting: Some(Default::default()),
                     signature_help: Some(lsp_types::SignatureHelpClientCapabilities {
                         signature_information: Some(lsp_types::SignatureInformationSettings {
                             documentation_format: Some(vec![
@@ -906,6 +1447,8 @@ impl LspClient {
                                     "quickfix".into(),
                                     "refactor".into(),
                                     "source".into(),
+                                    "source.organizeImports".into(),
+                                    "source.fixAll".into(),
                                 ],
                             },
                         }),
@@ -913,6 +1456,10 @@ impl LspClient {
                     }),
                     ..Default::default()
                 }),
+                window: Some(lsp_types::WindowClientCapabilities {
+                    work_done_progress: Some(true),
+                    ..Default::default()
+                }),
                 ..Default::default()
             },
             initialization_options,
@@ -978,6 +1525,8 @@ impl LspClient {
         self.did_change_with_changes(uri, version, text, Vec::new())
     }
 
+    /// Send `changes` to a server that advertised incremental sync and the
+    /// whole `text` otherwise. Servers with sync kind `NONE` get nothing.
     pub fn did_change_with_changes(
         &self,
         uri: &str,
@@ -985,13 +1534,12 @@ impl LspClient {
         text: &str,
         changes: Vec<lsp_types::TextDocumentContentChangeEvent>,
     ) -> Result<(), String> {
-        let use_incremental = !changes.is_empty()
-            && self
-                .change_sync_kind
-                .lock()
-                .ok()
-                .and_then(|kind| kind.clone())
-                .is_some_and(|kind| kind == lsp_types::TextDocumentSyncKind::INCREMENTAL);
+        let sync_kind = self.change_sync_kind.lock().ok().and_then(|kind| *kind);
+        if sync_kind == Some(lsp_types::TextDocumentSyncKind::NONE) {
+            return Ok(());
+        }
+        let use_incremental =
+            !changes.is_empty() && sync_kind == Some(lsp_types::TextDocumentSyncKind::INCREMENTAL);
 
         let content_changes = if use_incremental {
             changes
@@ -1027,6 +1575,45 @@ impl LspClient {
         )
     }
 
+    /// Announce an upcoming save (`willSave`) and collect the edits the
+    /// server wants applied first (`willSaveWaitUntil`), such as organizing
+    /// imports. Servers that asked for neither are skipped; one that does not
+    /// answer within [`WILL_SAVE_TIMEOUT`] contributes nothing.
+    pub async fn will_save(
+        &self,
+        uri: &str,
+        reason: lsp_types::TextDocumentSaveReason,
+    ) -> Result<Vec<lsp_types::TextEdit>, String> {
+        let (will_save, wait_until) = match self.capabilities.lock().await.as_ref() {
+            Some(capabilities) => will_save_support(capabilities),
+            None => return Ok(vec![]),
+        };
+        let params = lsp_types::WillSaveTextDocumentParams {
+            text_document: lsp_types::TextDocumentIdentifier {
+                uri: parse_uri(uri)?,
+            },
+            reason,
+        };
+        if will_save {
+            self.notify("textDocument/willSave", &params)?;
+        }
+        if !wait_until {
+            return Ok(vec![]);
+        }
+        let result = tokio::time::timeout(
+            WILL_SAVE_TIMEOUT,
+            self.request("textDocument/willSaveWaitUntil", &params),
+        )
+        .await
+        .map_err(|_| "willSaveWaitUntil timed out".to_string())??;
+
+        if result.is_null() {
+            Ok(vec![])
+        } else {
+            serde_json::from_value(result).map_err(|e| e.to_string())
+        }
+    }
+
     pub fn did_close(&self, uri: &str) -> Result<(), String> {
         self.notify(
             "textDocument/didClose",
@@ -1163,6 +1750,54 @@ impl LspClient {
         }
     }
 
+    /// Formatting edits after typing `ch` at `line`/`character`
+    /// (`textDocument/onTypeFormatting`). Empty unless `ch` is one of the
+    /// server's trigger characters.
+    pub async fn on_type_formatting(
+        &self,
+        uri: &str,
+        line: u32,
+        character: u32,
+        ch: &str,
+        tab_size: u32,
+        insert_spaces: bool,
+    ) -> Result<Vec<lsp_types::TextEdit>, String> {
+        let triggered = self
+            .capabilities
+            .lock()
+            .await
+            .as_ref()
+            .is_some_and(|c| triggers_on_type_formatting(c, ch));
+        if !triggered {
+            return Ok(vec![]);
+        }
+        let result = self
+            .request(
+                "textDocument/onTypeFormatting",
+                lsp_types::DocumentOnTypeFormattingParams {
+                    text_document_position: lsp_types::TextDocumentPositionParams {
+                        text_document: lsp_types::TextDocumentIdentifier {
+                            uri: parse_uri(uri)?,
+                        },
+                        position: lsp_types::Position { line, character },
+                    },
+                    ch: ch.to_string(),
+                    options: lsp_types::FormattingOptions {
+                        tab_size,
+                        insert_spaces,
+                        ..Default::default()
+                    },
+                },
+            )
+            .await?;
+
+        if result.is_null() {
+            Ok(vec![])
+        } else {
+            serde_json::from_value(result).map_err(|e| e.to_string())
+        }
+    }
+
     pub async fn signature_help(
         &self,
         uri: &str,
@@ -1226,42 +1861,36 @@ impl LspClient {
         }
     }
 
+    /// Code actions for `range`. `only` narrows the request to action kinds
+    /// such as `source.organizeImports`. Only the unnarrowed lightbulb
+    /// request is debounced; a narrowed one is a command the user ran or a
+    /// save waits on, so it is sent right away and never superseded.
     pub async fn code_action(
         &self,
         uri: &str,
-        start_line: u32,
-        start_col: u32,
-        end_line: u32,
-        end_col: u32,
+        range: lsp_types::Range,
         diagnostics: Vec<lsp_types::Diagnostic>,
+        only: Option<Vec<lsp_types::CodeActionKind>>,
     ) -> Result<Vec<lsp_types::CodeActionOrCommand>, String> {
-        let result = self
-            .request(
-                "textDocument/codeAction",
-                lsp_types::CodeActionParams {
-                    text_document: lsp_types::TextDocumentIdentifier {
-                        uri: parse_uri(uri)?,
-                    },
-                    range: lsp_types::Range {
-                        start: lsp_types::Position {
-                            line: start_line,
-                            character: start_col,
-                        },
-                        end: lsp_types::Position {
-                            line: end_line,
-                            character: end_col,
-                        },
-                    },
-                    context: lsp_types::CodeActionContext {
-                        diagnostics,
-                        only: None,
-                        ..Default::default()
-                    },
-                    work_done_progress_params: Default::default(),
-                    partial_result_params: Default::default(),
-                },
-            )
-            .await?;
+        let debounced = only.is_none();
+        let params = lsp_types::CodeActionParams {
+            text_document: lsp_types::TextDocumentIdentifier {
+                uri: parse_uri(uri)?,
+            },
+            range,
+            context: lsp_types::CodeActionContext {
+                diagnostics,
+                only,
+                ..Default::default()
+            },
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        let result = if debounced {
+            self.request("textDocument/codeAction", params).await?
+        } else {
+            self.request_now("textDocument/codeAction", params).await?
+        };
 
         if result.is_null() {
             Ok(vec![])
@@ -1274,6 +1903,55 @@ impl LspClient {
         }
     }
 
+    /// Edits for `text` (the document at `uri`) from the first action of
+    /// each kind in `kinds`, in order, for running code actions on save.
+    /// Actions that only carry a command are skipped, since their edits would
+    /// arrive after the file is written, and so is a kind whose edits clash
+    /// with an earlier one.
+    pub async fn code_actions_on_save(
+        &self,
+        uri: &str,
+        text: &str,
+        kinds: &[String],
+    ) -> Vec<crate::workspace_edit::TextEdit> {
+        let mut edits = Vec::new();
+        if kinds.is_empty() {
+            return edits;
+        }
+        let range = lsp_types::Range {
+            start: lsp_types::Position::new(0, 0),
+            end: document_end(text),
+        };
+        for kind in kinds {
+            let only = Some(vec![lsp_types::CodeActionKind::from(kind.clone())]);
+            let actions = match self.code_action(uri, range, Vec::new(), only).await {
+                Ok(actions) => actions,
+                Err(e) => {
+                    log::warn!("Code actions on save ({}) failed for {}: {}", kind, uri, e);
+                    continue;
+                }
+            };
+            let action_edits = actions.into_iter().find_map(|action| match action {
+                lsp_types::CodeActionOrCommand::CodeAction(action)
+                    if action
+                        .kind
+                        .as_ref()
+                        .is_some_and(|k| code_action_kind_matches(k.as_str(), kind)) =>
+                {
+                    let grouped = crate::workspace_edit::group_workspace_edit(&action.edit?);
+                    grouped.into_iter().find(|f| f.uri == uri).map(|f| f.edits)
+                }
+                _ => None,
+            });
+            if let Some(action_edits) = action_edits {
+                if !crate::workspace_edit::merge_text_edits(text, &mut edits, action_edits) {
+                    log::warn!("Skipping {} on save for {}: edits clash", kind, uri);
+                }
+            }
+        }
+        edits
+    }
+
     pub async fn rename(
         &self,
         uri: &str,
@@ -1304,6 +1982,45 @@ impl LspClient {
         }
     }
 
+    /// Whether the server advertised `textDocument/formatting`.
+    pub async fn supports_formatting(&self) -> bool {
+        self.capabilities
+            .lock()
+            .await
+            .as_ref()
+            .and_then(|c| c.document_formatting_provider.as_ref())
+            .is_some_and(|provider| !matches!(provider, lsp_types::OneOf::Left(false)))
+    }
+
+    /// Whether the server advertised `command` in its
+    /// `executeCommandProvider`.
+    pub async fn supports_command(&self, command: &str) -> bool {
+        self.capabilities
+            .lock()
+            .await
+            .as_ref()
+            .and_then(|c| c.execute_command_provider.as_ref())
+            .is_some_and(|provider| provider.commands.iter().any(|c| c == command))
+    }
+
+    /// Run a command from a code action (`workspace/executeCommand`). Edits
+    /// the server makes while running it arrive as [`LspEvent::ApplyEdit`].
+    pub async fn execute_command(
+        &self,
+        command: &str,
+        arguments: Vec<serde_json::Value>,
+    ) -> Result<serde_json::Value, String> {
+        self.request(
+            "workspace/executeCommand",
+            lsp_types::ExecuteCommandParams {
+                command: command.to_string(),
+                arguments,
+                work_done_progress_params: Default::default(),
+            },
+        )
+        .await
+    }
+
     pub async fn prepare_rename(
         &self,
         uri: &str,
@@ -1481,7 +2198,10 @@ impl LspConfig {
             cfg.apply_file(&global_path, /* trusted */ true);
         }
 
-        if let Some(root_path) = uri_to_file_path(fallback_root_uri) {
+        // Restricted folders' project configs are ignored entirely.
+        if let Some(root_path) =
+            uri_to_file_path(fallback_root_uri).filter(|root| crate::trust::is_trusted(root))
+        {
             let project_config_paths = [
                 root_path.join(".impulse").join("lsp.json"),
                 root_path.join(".impulse-lsp.json"),
@@ -1597,6 +2317,22 @@ impl Default for LspConfig {
                 initialization_options: None,
             },
         );
+        servers.insert(
+            "gopls".into(),
+            LspServerConfig {
+                command: "gopls".into(),
+                args: vec![],
+                initialization_options: None,
+            },
+        );
+        servers.insert(
+            "lua-language-server".into(),
+            LspServerConfig {
+                command: "lua-language-server".into(),
+                args: vec![],
+                initialization_options: None,
+            },
+        );
         servers.insert(
             "typescript-language-server".into(),
             LspServerConfig {
@@ -1715,6 +2451,8 @@ impl Default for LspConfig {
         language_servers.insert("python".into(), vec!["pyright".into()]);
         language_servers.insert("c".into(), vec!["clangd".into()]);
         language_servers.insert("cpp".into(), vec!["clangd".into()]);
+        language_servers.insert("go".into(), vec!["gopls".into()]);
+        language_servers.insert("lua".into(), vec!["lua-language-server".into()]);
         language_servers.insert(
             "javascript".into(),
             vec![
@@ -1821,6 +2559,7 @@ impl Default for LspConfig {
             "turbo.json".to_string(),
             "nx.json".to_string(),
             "go.mod".to_string(),
+            ".luarc.json".to_string(),
             "pyproject.toml".to_string(),
             "setup.py".to_string(),
             "composer.json".to_string(),
@@ -1844,6 +2583,10 @@ pub struct LspRegistry {
     config: LspConfig,
     fallback_root_uri: String,
     event_tx: mpsc::UnboundedSender<LspEvent>,
+    /// Shared with every client so changes apply to running servers.
+    debounce: Arc<StdMutex<LspDebounce>>,
+    /// Server ids from the `disabled_lsp_servers` setting.
+    disabled: StdMutex<HashSet<String>>,
 }
 
 fn detect_project_root(file_uri: &str, markers: &[String]) -> Option<String> {
@@ -1884,19 +2627,53 @@ impl LspRegistry {
             config,
             fallback_root_uri: root_uri,
             event_tx,
+            debounce: Arc::new(StdMutex::new(LspDebounce::default())),
+            disabled: StdMutex::new(HashSet::new()),
         }
     }
 
-    fn resolve_server_ids(&self, language_id: &str) -> Vec<String> {
-        if let Some(ids) = self.config.language_servers.get(language_id) {
-            return ids.clone();
+    /// Set the debounce intervals for completion, hover, signature help and
+    /// code action requests.
+    pub fn set_request_debounce(&self, debounce: LspDebounce) {
+        if let Ok(mut current) = self.debounce.lock() {
+            *current = debounce;
+        }
+    }
+
+    /// Stop using the given servers: they are no longer started, and running
+    /// instances are shut down. Replaces the previous set.
+    pub async fn set_disabled_servers(&self, server_ids: Vec<String>) {
+        let disabled: HashSet<String> = server_ids.into_iter().collect();
+        if let Ok(mut current) = self.disabled.lock() {
+            *current = disabled.clone();
         }
 
-        if self.config.servers.contains_key(language_id) {
-            return vec![language_id.to_string()];
+        let stopped: Vec<Arc<LspClient>> = {
+            let mut clients = self.clients.lock().await;
+            let keys: Vec<String> = clients
+                .iter()
+                .filter(|(_, client)| disabled.contains(&client.server_id))
+                .map(|(key, _)| key.clone())
+                .collect();
+            keys.iter().filter_map(|key| clients.remove(key)).collect()
+        };
+        for client in stopped {
+            let _ = client.shutdown().await;
         }
+    }
 
-        Vec::new()
+    fn resolve_server_ids(&self, language_id: &str) -> Vec<String> {
+        let mut ids = if let Some(ids) = self.config.language_servers.get(language_id) {
+            ids.clone()
+        } else if self.config.servers.contains_key(language_id) {
+            vec![language_id.to_string()]
+        } else {
+            Vec::new()
+        };
+        if let Ok(disabled) = self.disabled.lock() {
+            ids.retain(|id| !disabled.contains(id));
+        }
+        ids
     }
 
     fn detect_root_uri(&self, file_uri: &str) -> String {
@@ -1972,8 +2749,24 @@ impl LspRegistry {
             }
         };
 
+        // Restricted folders get no servers; nothing is recorded so the
+        // next request starts one once the folder is trusted.
+        if let Some(root) = uri_to_file_path(root_uri) {
+            if !crate::trust::is_trusted(&root) {
+                log::debug!(
+                    "LSP startup skipped for {}: {} is not trusted",
+                    server_id,
+                    root.display()
+                );
+                return None;
+            }
+        }
+
         let client_key = Self::client_key(server_id, root_uri);
-        let resolved_command = match resolve_lsp_command_path(&server_config.command) {
+        let search_path = crate::environment::search_path(uri_to_file_path(root_uri).as_deref());
+        let resolved_command = match find_command_in(&server_config.command, search_path)
+            .or_else(|| find_managed_command(&server_config.command))
+        {
             Some(path) => path,
             None => {
                 let message = missing_command_message(server_id, &server_config.command);
@@ -2008,8 +2801,10 @@ impl LspRegistry {
         )
         .await
         {
-            Ok(client) => {
+            Ok(mut client) => {
+                client.debounce = self.debounce.clone();
                 let client = Arc::new(client);
+                client.start_diagnostic_pulls().await;
                 self.clients
                     .lock()
                     .await
@@ -2110,3 +2905,252 @@ mod root_detection_tests {
         assert_eq!(root, path_to_file_uri(repo));
     }
 }
+
+#[cfg(test)]
+mod content_change_tests {
+    use super::apply_content_changes;
+    use lsp_types::{Position, Range, TextDocumentContentChangeEvent};
+
+    fn change(start: (u32, u32), end: (u32, u32), text: &str) -> TextDocumentContentChangeEvent {
+        TextDocumentContentChangeEvent {
+            range: Some(Range {
+                start: Position::new(start.0, start.1),
+                end: Position::new(end.0, end.1),
+            }),
+            range_length: None,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn changes_apply_in_order_with_utf16_columns() {
+        let mut doc = String::from("let s = \"😀\";\nfoo();\n");
+        // Multi-cursor edit: later positions first, as Monaco reports them.
+        let changes = [
+            change((1, 0), (1, 3), "bar"),
+            change((0, 11), (0, 11), "!"),
+            change((0, 4), (0, 5), "t"),
+        ];
+        apply_content_changes(&mut doc, &changes).unwrap();
+        assert_eq!(doc, "let t = \"😀!\";\nbar();\n");
+
+        // Columns past the line end clamp to it.
+        apply_content_changes(&mut doc, &[change((1, 50), (1, 50), " // x")]).unwrap();
+        assert_eq!(doc, "let t = \"😀!\";\nbar(); // x\n");
+    }
+
+    #[test]
+    fn full_replacement_and_out_of_range_changes() {
+        let mut doc = String::from("old");
+        let full = TextDocumentContentChangeEvent {
+            range: None,
+            range_length: None,
+            text: "new\n".to_string(),
+        };
+        apply_content_changes(&mut doc, &[full]).unwrap();
+        assert_eq!(doc, "new\n");
+        assert!(apply_content_changes(&mut doc, &[change((5, 0), (5, 0), "x")]).is_err());
+        assert_eq!(doc, "new\n");
+    }
+}
+
+#[cfg(test)]
+mod supersession_tests {
+    use super::{debounce_interval, RequestSupersession, SupersessionKey};
+    use crate::settings::LspDebounce;
+
+    fn key(method: &str, uri: &str) -> SupersessionKey {
+        SupersessionKey::new(
+            method,
+            &serde_json::json!({ "textDocument": { "uri": uri } }),
+        )
+    }
+
+    #[test]
+    fn newer_request_supersedes_waiting_and_in_flight_ones() {
+        let method = key("textDocument/completion", "file:///a.rs");
+        let mut supersession = RequestSupersession::default();
+
+        let (first, superseded) = supersession.begin(&method);
+        assert_eq!(superseded, None);
+        let (second, superseded) = supersession.begin(&method);
+        assert_eq!(superseded, None);
+        // The first request was still waiting out its debounce interval.
+        assert!(!supersession.is_current(&method, first));
+        assert!(!supersession.sent(&method, first, 1));
+
+        assert!(supersession.is_current(&method, second));
+        assert!(supersession.sent(&method, second, 2));
+        let (third, superseded) = supersession.begin(&method);
+        assert_eq!(superseded, Some(2));
+        assert!(supersession.sent(&method, third, 3));
+
+        // Other methods are tracked independently.
+        let hover = key("textDocument/hover", "file:///a.rs");
+        let (generation, _) = supersession.begin(&hover);
+        assert!(supersession.is_current(&hover, generation));
+
+        supersession.finished(&method, third, 3);
+        assert_eq!(supersession.begin(&method).1, None);
+    }
+
+    #[test]
+    fn requests_for_different_documents_do_not_supersede_each_other() {
+        let mut supersession = RequestSupersession::default();
+        let a = key("textDocument/hover", "file:///a.rs");
+        let b = key("textDocument/hover", "file:///b.rs");
+
+        let (a_generation, _) = supersession.begin(&a);
+        assert!(supersession.sent(&a, a_generation, 1));
+        let (b_generation, superseded) = supersession.begin(&b);
+        assert_eq!(superseded, None);
+        assert!(supersession.is_current(&a, a_generation));
+        assert!(supersession.sent(&b, b_generation, 2));
+
+        // A finished request leaves nothing behind for its document.
+        supersession.finished(&a, a_generation, 1);
+        supersession.finished(&b, b_generation, 2);
+        assert!(supersession.latest.is_empty());
+    }
+
+    #[test]
+    fn only_latest_wins_methods_are_debounced() {
+        let debounce = LspDebounce {
+            hover_ms: 0,
+            ..LspDebounce::default()
+        };
+        assert_eq!(
+            debounce_interval(&debounce, "textDocument/completion"),
+            Some(std::time::Duration::from_millis(debounce.completion_ms))
+        );
+        assert_eq!(
+            debounce_interval(&debounce, "textDocument/hover"),
+            Some(std::time::Duration::ZERO)
+        );
+        assert_eq!(
+            debounce_interval(&debounce, "textDocument/definition"),
+            None
+        );
+    }
+}
+
+#[cfg(test)]
+mod progress_tests {
+    use super::LspProgress;
+    use lsp_types::{WorkDoneProgress, WorkDoneProgressBegin, WorkDoneProgressEnd};
+
+    fn begin(title: &str) -> WorkDoneProgress {
+        WorkDoneProgress::Begin(WorkDoneProgressBegin {
+            title: title.to_string(),
+            ..Default::default()
+        })
+    }
+
+    fn report(message: &str, percentage: Option<u32>) -> WorkDoneProgress {
+        WorkDoneProgress::Report(lsp_types::WorkDoneProgressReport {
+            message: Some(message.to_string()),
+            percentage,
+            ..Default::default()
+        })
+    }
+
+    fn end() -> WorkDoneProgress {
+        WorkDoneProgress::End(WorkDoneProgressEnd::default())
+    }
+
+    #[test]
+    fn latest_work_is_shown_until_its_token_ends() {
+        let mut progress = LspProgress::new();
+        assert_eq!(progress.status_text(), None);
+
+        progress.update("rust:/p", "rust-analyzer", "index", &begin("Indexing"));
+        progress.update("rust:/p", "rust-analyzer", "index", &report("3/120", None));
+        assert_eq!(
+            progress.status_text().as_deref(),
+            Some("rust-analyzer: Indexing 3/120")
+        );
+
+        progress.update("ts:/p", "typescript", "1", &begin("Analyzing"));
+        progress.update("ts:/p", "typescript", "1", &report("", Some(40)));
+        assert_eq!(
+            progress.status_text().as_deref(),
+            Some("typescript: Analyzing (40%)")
+        );
+
+        progress.update("ts:/p", "typescript", "1", &end());
+        assert_eq!(
+            progress.status_text().as_deref(),
+            Some("rust-analyzer: Indexing 3/120")
+        );
+
+        progress.clear_client("rust:/p");
+        assert!(progress.is_empty());
+        assert_eq!(progress.status_text(), None);
+    }
+}
+
+#[cfg(test)]
+mod save_and_on_type_tests {
+    use super::{
+        code_action_kind_matches, document_end, triggers_on_type_formatting, will_save_support,
+    };
+    use lsp_types::{
+        DocumentOnTypeFormattingOptions, ServerCapabilities, TextDocumentSyncCapability,
+        TextDocumentSyncKind, TextDocumentSyncOptions,
+    };
+
+    #[test]
+    fn will_save_support_comes_from_sync_options() {
+        let kind_only = ServerCapabilities {
+            text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+            ..Default::default()
+        };
+        assert_eq!(will_save_support(&kind_only), (false, false));
+
+        let options = ServerCapabilities {
+            text_document_sync: Some(TextDocumentSyncCapability::Options(
+                TextDocumentSyncOptions {
+                    will_save_wait_until: Some(true),
+                    ..Default::default()
+                },
+            )),
+            ..Default::default()
+        };
+        assert_eq!(will_save_support(&options), (false, true));
+    }
+
+    #[test]
+    fn code_action_kinds_match_hierarchically() {
+        assert!(code_action_kind_matches("source.organizeImports", "source"));
+        assert!(code_action_kind_matches(
+            "source.organizeImports.rust",
+            "source.organizeImports"
+        ));
+        assert!(code_action_kind_matches("source.fixAll", "source.fixAll"));
+        assert!(!code_action_kind_matches(
+            "source.fixAllEslint",
+            "source.fixAll"
+        ));
+        assert!(!code_action_kind_matches("quickfix", "source"));
+        assert_eq!(document_end("a\nbc😀"), lsp_types::Position::new(1, 4));
+        assert_eq!(document_end("a\n"), lsp_types::Position::new(1, 0));
+    }
+
+    #[test]
+    fn on_type_formatting_only_for_trigger_characters() {
+        let capabilities = ServerCapabilities {
+            document_on_type_formatting_provider: Some(DocumentOnTypeFormattingOptions {
+                first_trigger_character: "}".to_string(),
+                more_trigger_character: Some(vec![";".to_string(), "\n".to_string()]),
+            }),
+            ..Default::default()
+        };
+        assert!(triggers_on_type_formatting(&capabilities, "}"));
+        assert!(triggers_on_type_formatting(&capabilities, "\n"));
+        assert!(!triggers_on_type_formatting(&capabilities, ")"));
+        assert!(!triggers_on_type_formatting(
+            &ServerCapabilities::default(),
+            "}"
+        ));
+    }
+}
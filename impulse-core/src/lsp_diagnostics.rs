@@ -0,0 +1,157 @@
+//! Per-document diagnostics for one language server, merging the push model
+//! (`textDocument/publishDiagnostics`) with the LSP 3.17 pull model
+//! (`textDocument/diagnostic`). A server may use both, so each update emits
+//! the union instead of letting one set replace the other in the
+//! `LspEvent::Diagnostics` stream.
+
+use std::collections::{HashMap, HashSet};
+
+use lsp_types::Diagnostic;
+use tokio::sync::mpsc;
+
+struct PulledDiagnostics {
+    result_id: Option<String>,
+    items: Vec<Diagnostic>,
+}
+
+#[derive(Default)]
+pub(crate) struct DiagnosticStore {
+    open: HashSet<String>,
+    pushed: HashMap<String, Vec<Diagnostic>>,
+    pulled: HashMap<String, PulledDiagnostics>,
+    /// Feeds the pull scheduler; `None` for servers without pull support.
+    pull_tx: Option<mpsc::UnboundedSender<String>>,
+}
+
+impl DiagnosticStore {
+    pub(crate) fn set_pull_sender(&mut self, tx: mpsc::UnboundedSender<String>) {
+        self.pull_tx = Some(tx);
+    }
+
+    /// Track a document notification (`didOpen` / `didChange` / `didSave` /
+    /// `didClose`), scheduling a pull for changes to open documents.
+    pub(crate) fn document_notified(&mut self, method: &str, uri: &str) {
+        match method {
+            "textDocument/didOpen" => {
+                self.open.insert(uri.to_string());
+            }
+            "textDocument/didClose" => {
+                self.open.remove(uri);
+                self.pulled.remove(uri);
+                return;
+            }
+            "textDocument/didChange" | "textDocument/didSave" => {}
+            _ => return,
+        }
+        self.schedule_pull(uri);
+    }
+
+    fn schedule_pull(&self, uri: &str) {
+        if let Some(tx) = &self.pull_tx {
+            if self.open.contains(uri) {
+                let _ = tx.send(uri.to_string());
+            }
+        }
+    }
+
+    /// Re-pull every open document (`workspace/diagnostic/refresh`).
+    pub(crate) fn schedule_all(&self) {
+        for uri in &self.open {
+            self.schedule_pull(uri);
+        }
+    }
+
+    pub(crate) fn previous_result_id(&self, uri: &str) -> Option<String> {
+        self.pulled.get(uri)?.result_id.clone()
+    }
+
+    fn merged(&self, uri: &str) -> Vec<Diagnostic> {
+        let mut merged = self.pushed.get(uri).cloned().unwrap_or_default();
+        if let Some(pulled) = self.pulled.get(uri) {
+            merged.extend(pulled.items.iter().cloned());
+        }
+        merged
+    }
+
+    /// Record pushed diagnostics and return the merged set to publish.
+    pub(crate) fn set_pushed(&mut self, uri: &str, items: Vec<Diagnostic>) -> Vec<Diagnostic> {
+        if items.is_empty() {
+            self.pushed.remove(uri);
+        } else {
+            self.pushed.insert(uri.to_string(), items);
+        }
+        self.merged(uri)
+    }
+
+    /// Record a full pull report and return the merged set to publish, or
+    /// `None` if the document was closed while the pull was in flight.
+    pub(crate) fn set_pulled(
+        &mut self,
+        uri: &str,
+        result_id: Option<String>,
+        items: Vec<Diagnostic>,
+    ) -> Option<Vec<Diagnostic>> {
+        if !self.open.contains(uri) {
+            return None;
+        }
+        self.pulled
+            .insert(uri.to_string(), PulledDiagnostics { result_id, items });
+        Some(self.merged(uri))
+    }
+
+    /// Record an `unchanged` pull report: the items stay, the id moves on.
+    pub(crate) fn set_unchanged(&mut self, uri: &str, result_id: String) {
+        if let Some(pulled) = self.pulled.get_mut(uri) {
+            pulled.result_id = Some(result_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diagnostic(message: &str) -> Diagnostic {
+        Diagnostic {
+            message: message.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn messages(items: &[Diagnostic]) -> Vec<&str> {
+        items.iter().map(|d| d.message.as_str()).collect()
+    }
+
+    #[test]
+    fn push_and_pull_results_are_merged_per_document() {
+        let uri = "file:///p/src/main.rs";
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut store = DiagnosticStore::default();
+        store.set_pull_sender(tx);
+
+        store.document_notified("textDocument/didChange", uri);
+        assert!(rx.try_recv().is_err(), "closed documents are not pulled");
+        store.document_notified("textDocument/didOpen", uri);
+        assert_eq!(rx.try_recv().unwrap(), uri);
+
+        let merged = store.set_pushed(uri, vec![diagnostic("pushed")]);
+        assert_eq!(messages(&merged), ["pushed"]);
+        let merged = store
+            .set_pulled(uri, Some("1".to_string()), vec![diagnostic("pulled")])
+            .unwrap();
+        assert_eq!(messages(&merged), ["pushed", "pulled"]);
+        assert_eq!(store.previous_result_id(uri).as_deref(), Some("1"));
+
+        store.set_unchanged(uri, "2".to_string());
+        assert_eq!(store.previous_result_id(uri).as_deref(), Some("2"));
+        let merged = store.set_pushed(uri, Vec::new());
+        assert_eq!(messages(&merged), ["pulled"]);
+
+        store.schedule_all();
+        assert_eq!(rx.try_recv().unwrap(), uri);
+
+        store.document_notified("textDocument/didClose", uri);
+        assert!(store.set_pulled(uri, None, Vec::new()).is_none());
+        assert_eq!(store.previous_result_id(uri), None);
+    }
+}
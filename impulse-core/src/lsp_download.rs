@@ -0,0 +1,316 @@
+//! Download, verification and unpacking of prebuilt language server
+//! binaries for [`crate::lsp_install`].
+//!
+//! Release assets come from GitHub and are checked against the SHA-256
+//! digest GitHub publishes for each asset before anything is unpacked; an
+//! asset without a published digest is refused. Archives are unpacked into
+//! a staging directory that is renamed into place only once extraction
+//! succeeded, so an interrupted install never leaves a half-written server.
+
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::Deserialize;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Report download progress at most every this many bytes.
+const PROGRESS_STEP: u64 = 4 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveKind {
+    /// A single gzip-compressed executable.
+    Gzip,
+    Zip,
+    TarGz,
+}
+
+#[derive(Deserialize)]
+struct GitHubRelease {
+    assets: Vec<GitHubAsset>,
+}
+
+#[derive(Deserialize)]
+struct GitHubAsset {
+    name: String,
+    browser_download_url: String,
+    /// `sha256:<hex>`; absent for assets uploaded before GitHub started
+    /// recording digests.
+    digest: Option<String>,
+}
+
+fn agent() -> ureq::Agent {
+    ureq::Agent::config_builder()
+        .timeout_connect(Some(CONNECT_TIMEOUT))
+        .build()
+        .new_agent()
+}
+
+fn user_agent() -> String {
+    format!("impulse/{}", crate::update::CURRENT_VERSION)
+}
+
+/// The lowercase hex SHA-256 from a GitHub `digest` field.
+fn sha256_from_digest(digest: &str) -> Option<String> {
+    let hex = digest.strip_prefix("sha256:")?;
+    (hex.len() == 64 && hex.chars().all(|c| c.is_ascii_hexdigit())).then(|| hex.to_lowercase())
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Look up `asset` in release `tag` of `repo` (`owner/name`), returning its
+/// download URL and expected SHA-256.
+fn release_asset(repo: &str, tag: &str, asset: &str) -> Result<(String, String), String> {
+    let url = format!(
+        "https://api.github.com/repos/{}/releases/tags/{}",
+        repo, tag
+    );
+    let body = agent()
+        .get(&url)
+        .header("Accept", "application/vnd.github+json")
+        .header("User-Agent", &user_agent())
+        .call()
+        .map_err(|e| format!("Failed to look up {} release {}: {}", repo, tag, e))?
+        .into_body()
+        .read_to_string()
+        .map_err(|e| format!("Failed to read {} release {}: {}", repo, tag, e))?;
+    let release: GitHubRelease = serde_json::from_str(&body)
+        .map_err(|e| format!("Failed to parse {} release {}: {}", repo, tag, e))?;
+    let found = release
+        .assets
+        .into_iter()
+        .find(|a| a.name == asset)
+        .ok_or_else(|| format!("{} release {} has no asset {}", repo, tag, asset))?;
+    let sha256 = found
+        .digest
+        .as_deref()
+        .and_then(sha256_from_digest)
+        .ok_or_else(|| {
+            format!(
+                "GitHub publishes no checksum for {}; refusing to install it unverified",
+                asset
+            )
+        })?;
+    Ok((found.browser_download_url, sha256))
+}
+
+/// Stream `url` into `dest`, returning the SHA-256 of what was written.
+fn download(url: &str, dest: &Path, on_line: &mut dyn FnMut(String)) -> Result<String, String> {
+    let response = agent()
+        .get(url)
+        .header("User-Agent", &user_agent())
+        .call()
+        .map_err(|e| format!("Failed to download {}: {}", url, e))?;
+    let total = response
+        .headers()
+        .get("content-length")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok());
+    let mut reader = response.into_body().into_reader();
+    let mut file = fs::File::create(dest)
+        .map_err(|e| format!("Failed to create {}: {}", dest.display(), e))?;
+    let mut hasher = ring::digest::Context::new(&ring::digest::SHA256);
+    let mut buf = vec![0u8; 64 * 1024];
+    let mut written: u64 = 0;
+    let mut next_report = PROGRESS_STEP;
+    loop {
+        let n = reader
+            .read(&mut buf)
+            .map_err(|e| format!("Download of {} failed: {}", url, e))?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+        file.write_all(&buf[..n])
+            .map_err(|e| format!("Failed to write {}: {}", dest.display(), e))?;
+        written += n as u64;
+        if written >= next_report {
+            next_report += PROGRESS_STEP;
+            on_line(match total {
+                Some(total) => format!(
+                    "Downloaded {} of {} MB",
+                    written / (1024 * 1024),
+                    total / (1024 * 1024)
+                ),
+                None => format!("Downloaded {} MB", written / (1024 * 1024)),
+            });
+        }
+    }
+    Ok(hex(hasher.finish().as_ref()))
+}
+
+/// Unpack `archive` into `dir`. A gzip file becomes the single executable
+/// `dir/<binary>`.
+fn unpack(archive: &Path, kind: ArchiveKind, dir: &Path, binary: &str) -> Result<(), String> {
+    let open = || {
+        fs::File::open(archive).map_err(|e| format!("Failed to open {}: {}", archive.display(), e))
+    };
+    let failed =
+        |e: &dyn std::fmt::Display| format!("Failed to unpack {}: {}", archive.display(), e);
+    match kind {
+        ArchiveKind::Gzip => {
+            let mut decoder = flate2::read::GzDecoder::new(open()?);
+            let target = dir.join(binary);
+            let mut out = fs::File::create(&target)
+                .map_err(|e| format!("Failed to create {}: {}", target.display(), e))?;
+            std::io::copy(&mut decoder, &mut out).map_err(|e| failed(&e))?;
+        }
+        ArchiveKind::TarGz => {
+            // `unpack` refuses entries that would land outside `dir`.
+            tar::Archive::new(flate2::read::GzDecoder::new(open()?))
+                .unpack(dir)
+                .map_err(|e| failed(&e))?;
+        }
+        ArchiveKind::Zip => {
+            zip::ZipArchive::new(open()?)
+                .and_then(|mut zip| zip.extract(dir))
+                .map_err(|e| failed(&e))?;
+        }
+    }
+    Ok(())
+}
+
+fn make_executable(path: &Path) -> Result<(), String> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mut permissions = fs::metadata(path)
+            .map_err(|e| format!("{} is missing after unpacking: {}", path.display(), e))?
+            .permissions();
+        permissions.set_mode(permissions.mode() | 0o755);
+        fs::set_permissions(path, permissions)
+            .map_err(|e| format!("Failed to make {} executable: {}", path.display(), e))?;
+    }
+    #[cfg(not(unix))]
+    if !path.is_file() {
+        return Err(format!("{} is missing after unpacking", path.display()));
+    }
+    Ok(())
+}
+
+/// Download release asset `asset` of `repo`@`tag`, verify it, and unpack it
+/// into `dir` (replacing it). Returns the path of the executable `binary`
+/// inside `dir`.
+pub(crate) fn install_release_asset(
+    repo: &str,
+    tag: &str,
+    asset: &str,
+    kind: ArchiveKind,
+    binary: &str,
+    dir: &Path,
+    on_line: &mut dyn FnMut(String),
+) -> Result<PathBuf, String> {
+    let parent = dir
+        .parent()
+        .ok_or_else(|| format!("Invalid install directory {}", dir.display()))?;
+    fs::create_dir_all(parent)
+        .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+
+    on_line(format!("Looking up {} {}", repo, tag));
+    let (url, expected) = release_asset(repo, tag, asset)?;
+    let download_path = parent.join(format!(".{}.download", asset));
+    on_line(format!("Downloading {}", asset));
+    let result = download(&url, &download_path, on_line).and_then(|actual| {
+        if actual != expected {
+            return Err(format!(
+                "Checksum mismatch for {}: expected {}, got {}",
+                asset, expected, actual
+            ));
+        }
+        on_line(format!("Verified {} (sha256 {})", asset, &actual[..12]));
+        let staging = parent.join(format!(".{}.staging", asset));
+        let _ = fs::remove_dir_all(&staging);
+        fs::create_dir_all(&staging)
+            .map_err(|e| format!("Failed to create {}: {}", staging.display(), e))?;
+        if let Err(e) = unpack(&download_path, kind, &staging, binary)
+            .and_then(|()| make_executable(&staging.join(binary)))
+        {
+            let _ = fs::remove_dir_all(&staging);
+            return Err(e);
+        }
+        let _ = fs::remove_dir_all(dir);
+        fs::rename(&staging, dir)
+            .map_err(|e| format!("Failed to move {} into place: {}", dir.display(), e))
+    });
+    let _ = fs::remove_file(&download_path);
+    result.map(|()| dir.join(binary))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_well_formed_sha256_digests_are_accepted() {
+        let hex64 = "AB".repeat(32);
+        assert_eq!(
+            sha256_from_digest(&format!("sha256:{}", hex64)),
+            Some("ab".repeat(32))
+        );
+        assert_eq!(sha256_from_digest(&hex64), None);
+        assert_eq!(sha256_from_digest("sha256:abc"), None);
+        assert_eq!(sha256_from_digest(&format!("sha512:{}", hex64)), None);
+        assert_eq!(
+            sha256_from_digest(&format!("sha256:{}", "zz".repeat(32))),
+            None
+        );
+    }
+
+    #[test]
+    fn archives_unpack_into_the_target_directory() {
+        let temp = tempfile::tempdir().unwrap();
+
+        let gz_path = temp.path().join("server.gz");
+        let mut encoder = flate2::write::GzEncoder::new(
+            fs::File::create(&gz_path).unwrap(),
+            flate2::Compression::fast(),
+        );
+        encoder.write_all(b"#!/bin/sh\n").unwrap();
+        encoder.finish().unwrap();
+        let gz_dir = temp.path().join("gz");
+        fs::create_dir(&gz_dir).unwrap();
+        unpack(&gz_path, ArchiveKind::Gzip, &gz_dir, "server").unwrap();
+        make_executable(&gz_dir.join("server")).unwrap();
+        assert_eq!(fs::read(gz_dir.join("server")).unwrap(), b"#!/bin/sh\n");
+
+        let tar_path = temp.path().join("server.tar.gz");
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+            fs::File::create(&tar_path).unwrap(),
+            flate2::Compression::fast(),
+        ));
+        let mut header = tar::Header::new_gnu();
+        header.set_size(4);
+        header.set_mode(0o755);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "bin/server", &b"lua\n"[..])
+            .unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+        let tar_dir = temp.path().join("tar");
+        fs::create_dir(&tar_dir).unwrap();
+        unpack(&tar_path, ArchiveKind::TarGz, &tar_dir, "bin/server").unwrap();
+        assert_eq!(fs::read(tar_dir.join("bin/server")).unwrap(), b"lua\n");
+
+        let zip_path = temp.path().join("server.zip");
+        let mut writer = zip::ZipWriter::new(fs::File::create(&zip_path).unwrap());
+        writer
+            .start_file(
+                "clangd_1/bin/clangd",
+                zip::write::SimpleFileOptions::default(),
+            )
+            .unwrap();
+        writer.write_all(b"elf").unwrap();
+        writer.finish().unwrap();
+        let zip_dir = temp.path().join("zip");
+        fs::create_dir(&zip_dir).unwrap();
+        unpack(&zip_path, ArchiveKind::Zip, &zip_dir, "clangd_1/bin/clangd").unwrap();
+        assert_eq!(
+            fs::read(zip_dir.join("clangd_1/bin/clangd")).unwrap(),
+            b"elf"
+        );
+    }
+}
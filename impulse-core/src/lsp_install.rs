@@ -0,0 +1,968 @@
+//! Managed installation of language servers under [`managed_lsp_root_dir`].
+//!
+//! The web language servers are npm packages, pinned in [`MANAGED_PACKAGES`]
+//! and installed with `--save-exact`. Servers that ship native binaries are
+//! pinned in [`MANAGED_BINARIES`] and downloaded from their GitHub releases
+//! for the current platform, so they work without npm; their executables
+//! are linked into [`managed_binary_dir`]. Either way an Impulse release
+//! runs the server versions it was tested with. When a release moves a pin,
+//! [`check_for_updates`] reports the installed packages that differ and
+//! [`InstallAction::Update`] reinstalls them. Servers listed in the
+//! `disabled_lsp_servers` setting are neither started nor installed.
+//!
+//! Installs run on a background thread ([`spawn`]); npm output, download
+//! progress and the outcome arrive as [`InstallEvent`]s.
+
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use serde::{Deserialize, Serialize};
+
+use crate::lsp::{managed_lsp_root_dir, npm_is_available};
+pub use crate::lsp_download::ArchiveKind;
+
+/// An npm package Impulse installs, at a pinned version.
+pub struct ManagedPackage {
+    pub name: &'static str,
+    pub version: &'static str,
+    /// Server ids (as in lsp.json) the package provides. Empty for support
+    /// packages other servers load at runtime, which are always installed.
+    pub servers: &'static [&'static str],
+}
+
+pub const MANAGED_PACKAGES: &[ManagedPackage] = &[
+    ManagedPackage {
+        name: "typescript",
+        version: "5.7.3",
+        servers: &[],
+    },
+    ManagedPackage {
+        name: "typescript-language-server",
+        version: "4.3.3",
+        servers: &["typescript-language-server"],
+    },
+    ManagedPackage {
+        name: "intelephense",
+        version: "1.12.6",
+        servers: &["intelephense"],
+    },
+    ManagedPackage {
+        name: "vscode-langservers-extracted",
+        version: "4.10.0",
+        servers: &[
+            "vscode-html-language-server",
+            "vscode-css-language-server",
+            "vscode-json-language-server",
+            "vscode-eslint-language-server",
+        ],
+    },
+    ManagedPackage {
+        name: "@tailwindcss/language-server",
+        version: "0.14.14",
+        servers: &["tailwindcss-language-server"],
+    },
+    ManagedPackage {
+        name: "@vue/language-server",
+        version: "2.2.0",
+        servers: &["vue-language-server"],
+    },
+    ManagedPackage {
+        name: "svelte-language-server",
+        version: "0.17.7",
+        servers: &["svelteserver"],
+    },
+    ManagedPackage {
+        name: "graphql-language-service-cli",
+        version: "3.4.1",
+        servers: &["graphql-lsp"],
+    },
+    ManagedPackage {
+        name: "emmet-ls",
+        version: "0.7.1",
+        servers: &["emmet-ls"],
+    },
+    ManagedPackage {
+        name: "yaml-language-server",
+        version: "1.15.0",
+        servers: &["yaml-language-server"],
+    },
+    ManagedPackage {
+        name: "dockerfile-language-server-nodejs",
+        version: "0.13.0",
+        servers: &["docker-langserver"],
+    },
+    ManagedPackage {
+        name: "bash-language-server",
+        version: "5.4.3",
+        servers: &["bash-language-server"],
+    },
+];
+
+impl ManagedPackage {
+    /// Whether the package is wanted: it provides at least one server that
+    /// is not disabled, or it is a support package.
+    pub fn is_enabled(&self, disabled_servers: &[String]) -> bool {
+        self.servers.is_empty()
+            || self
+                .servers
+                .iter()
+                .any(|id| !disabled_servers.iter().any(|d| d == id))
+    }
+
+    fn spec(&self) -> String {
+        format!("{}@{}", self.name, self.version)
+    }
+}
+
+/// A prebuilt archive of a [`ManagedBinary`] for one platform.
+pub struct ReleaseAsset {
+    /// As in `std::env::consts::OS`.
+    pub os: &'static str,
+    /// As in `std::env::consts::ARCH`.
+    pub arch: &'static str,
+    pub name: &'static str,
+    pub archive: ArchiveKind,
+    /// Path of the executable inside the unpacked archive.
+    pub binary: &'static str,
+}
+
+pub enum BinarySource {
+    /// Assets of a GitHub release, verified against the SHA-256 digests
+    /// GitHub publishes for them.
+    GitHubRelease {
+        repo: &'static str,
+        tag: &'static str,
+        assets: &'static [ReleaseAsset],
+    },
+    /// `go install <module>@<version>`. gopls publishes no prebuilt
+    /// binaries, and needs a Go toolchain at runtime anyway.
+    GoInstall { module: &'static str },
+}
+
+/// A language server Impulse installs as a native executable.
+pub struct ManagedBinary {
+    /// Server id, as in lsp.json.
+    pub server: &'static str,
+    pub command: &'static str,
+    pub version: &'static str,
+    pub source: BinarySource,
+}
+
+pub const MANAGED_BINARIES: &[ManagedBinary] = &[
+    ManagedBinary {
+        server: "rust-analyzer",
+        command: "rust-analyzer",
+        version: "2025-08-25",
+        source: BinarySource::GitHubRelease {
+            repo: "rust-lang/rust-analyzer",
+            tag: "2025-08-25",
+            assets: &[
+                ReleaseAsset {
+                    os: "linux",
+                    arch: "x86_64",
+                    name: "rust-analyzer-x86_64-unknown-linux-gnu.gz",
+                    archive: ArchiveKind::Gzip,
+                    binary: "rust-analyzer",
+                },
+                ReleaseAsset {
+                    os: "linux",
+                    arch: "aarch64",
+                    name: "rust-analyzer-aarch64-unknown-linux-gnu.gz",
+                    archive: ArchiveKind::Gzip,
+                    binary: "rust-analyzer",
+                },
+                ReleaseAsset {
+                    os: "macos",
+                    arch: "x86_64",
+                    name: "rust-analyzer-x86_64-apple-darwin.gz",
+                    archive: ArchiveKind::Gzip,
+                    binary: "rust-analyzer",
+                },
+                ReleaseAsset {
+                    os: "macos",
+                    arch: "aarch64",
+                    name: "rust-analyzer-aarch64-apple-darwin.gz",
+                    archive: ArchiveKind::Gzip,
+                    binary: "rust-analyzer",
+                },
+            ],
+        },
+    },
+    ManagedBinary {
+        server: "clangd",
+        command: "clangd",
+        version: "21.1.0",
+        source: BinarySource::GitHubRelease {
+            repo: "clangd/clangd",
+            tag: "21.1.0",
+            // The macOS build is x86_64 only and runs under Rosetta on
+            // Apple silicon. There is no Linux arm64 build.
+            assets: &[
+                ReleaseAsset {
+                    os: "linux",
+                    arch: "x86_64",
+                    name: "clangd-linux-21.1.0.zip",
+                    archive: ArchiveKind::Zip,
+                    binary: "clangd_21.1.0/bin/clangd",
+                },
+                ReleaseAsset {
+                    os: "macos",
+                    arch: "x86_64",
+                    name: "clangd-mac-21.1.0.zip",
+                    archive: ArchiveKind::Zip,
+                    binary: "clangd_21.1.0/bin/clangd",
+                },
+                ReleaseAsset {
+                    os: "macos",
+                    arch: "aarch64",
+                    name: "clangd-mac-21.1.0.zip",
+                    archive: ArchiveKind::Zip,
+                    binary: "clangd_21.1.0/bin/clangd",
+                },
+            ],
+        },
+    },
+    ManagedBinary {
+        server: "lua-language-server",
+        command: "lua-language-server",
+        version: "3.15.0",
+        source: BinarySource::GitHubRelease {
+            repo: "LuaLS/lua-language-server",
+            tag: "3.15.0",
+            assets: &[
+                ReleaseAsset {
+                    os: "linux",
+                    arch: "x86_64",
+                    name: "lua-language-server-3.15.0-linux-x64.tar.gz",
+                    archive: ArchiveKind::TarGz,
+                    binary: "bin/lua-language-server",
+                },
+                ReleaseAsset {
+                    os: "linux",
+                    arch: "aarch64",
+                    name: "lua-language-server-3.15.0-linux-arm64.tar.gz",
+                    archive: ArchiveKind::TarGz,
+                    binary: "bin/lua-language-server",
+                },
+                ReleaseAsset {
+                    os: "macos",
+                    arch: "x86_64",
+                    name: "lua-language-server-3.15.0-darwin-x64.tar.gz",
+                    archive: ArchiveKind::TarGz,
+                    binary: "bin/lua-language-server",
+                },
+                ReleaseAsset {
+                    os: "macos",
+                    arch: "aarch64",
+                    name: "lua-language-server-3.15.0-darwin-arm64.tar.gz",
+                    archive: ArchiveKind::TarGz,
+                    binary: "bin/lua-language-server",
+                },
+            ],
+        },
+    },
+    ManagedBinary {
+        server: "gopls",
+        command: "gopls",
+        version: "v0.20.0",
+        source: BinarySource::GoInstall {
+            module: "golang.org/x/tools/gopls",
+        },
+    },
+];
+
+impl ManagedBinary {
+    pub fn is_enabled(&self, disabled_servers: &[String]) -> bool {
+        !disabled_servers.iter().any(|d| d == self.server)
+    }
+
+    /// The release asset for `os`/`arch`, or `None` if the server has no
+    /// build for it. Always `None` for `go install` servers.
+    pub fn asset_for(&self, os: &str, arch: &str) -> Option<&'static ReleaseAsset> {
+        match &self.source {
+            BinarySource::GitHubRelease { assets, .. } => {
+                assets.iter().find(|a| a.os == os && a.arch == arch)
+            }
+            BinarySource::GoInstall { .. } => None,
+        }
+    }
+
+    /// Whether the server can be installed on this machine.
+    pub fn is_supported(&self) -> bool {
+        match self.source {
+            BinarySource::GitHubRelease { .. } => self
+                .asset_for(std::env::consts::OS, std::env::consts::ARCH)
+                .is_some(),
+            BinarySource::GoInstall { .. } => true,
+        }
+    }
+}
+
+fn find_binary(name: &str) -> Option<&'static ManagedBinary> {
+    MANAGED_BINARIES.iter().find(|b| b.server == name)
+}
+
+/// Where managed native executables are linked, ahead of npm's
+/// `node_modules/.bin` when resolving commands.
+pub fn managed_binary_dir() -> Option<PathBuf> {
+    managed_lsp_root_dir().map(|root| root.join("bin"))
+}
+
+fn binary_root(root: &Path, binary: &ManagedBinary) -> PathBuf {
+    root.join("servers").join(binary.server)
+}
+
+/// Records the installed version next to the unpacked server.
+const VERSION_MARKER: &str = "installed-version";
+
+fn installed_binary_version(root: &Path, binary: &ManagedBinary) -> Option<String> {
+    let marker = binary_root(root, binary).join(VERSION_MARKER);
+    let version = std::fs::read_to_string(marker).ok()?;
+    let version = version.trim();
+    (!version.is_empty()).then(|| version.to_string())
+}
+
+/// One managed package or binary.
+#[derive(Clone, Copy)]
+enum Managed {
+    Npm(&'static ManagedPackage),
+    Binary(&'static ManagedBinary),
+}
+
+impl Managed {
+    fn find(name: &str) -> Option<Self> {
+        find_package(name)
+            .map(Managed::Npm)
+            .or_else(|| find_binary(name).map(Managed::Binary))
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Managed::Npm(package) => package.name,
+            Managed::Binary(binary) => binary.server,
+        }
+    }
+}
+
+fn find_package(name: &str) -> Option<&'static ManagedPackage> {
+    MANAGED_PACKAGES.iter().find(|p| p.name == name)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PackageKind {
+    Npm,
+    Binary,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ManagedPackageStatus {
+    /// npm package name, or server id for binaries.
+    pub name: String,
+    pub kind: PackageKind,
+    pub pinned_version: String,
+    /// `None` when the package is not installed.
+    pub installed_version: Option<String>,
+    pub servers: Vec<String>,
+    pub enabled: bool,
+    /// A binary with no build for this platform.
+    pub unsupported: bool,
+    /// The server's command is already installed outside Impulse (e.g. a
+    /// rustup rust-analyzer), which takes precedence. Always false for npm
+    /// packages.
+    pub on_path: bool,
+}
+
+impl ManagedPackageStatus {
+    /// Installed at a version other than the pin.
+    pub fn needs_update(&self) -> bool {
+        self.installed_version
+            .as_ref()
+            .is_some_and(|v| *v != self.pinned_version)
+    }
+}
+
+/// The version npm installed under `root`, read from the package's own
+/// `package.json`.
+fn installed_version(root: &Path, name: &str) -> Option<String> {
+    let manifest = root.join("node_modules").join(name).join("package.json");
+    let contents = std::fs::read_to_string(manifest).ok()?;
+    let doc: serde_json::Value = serde_json::from_str(&contents).ok()?;
+    doc.get("version")?.as_str().map(str::to_string)
+}
+
+fn status_in(
+    root: Option<&Path>,
+    disabled_servers: &[String],
+    on_path: impl Fn(&str) -> bool,
+) -> Vec<ManagedPackageStatus> {
+    let packages = MANAGED_PACKAGES.iter().map(|package| ManagedPackageStatus {
+        name: package.name.to_string(),
+        kind: PackageKind::Npm,
+        pinned_version: package.version.to_string(),
+        installed_version: root.and_then(|root| installed_version(root, package.name)),
+        servers: package.servers.iter().map(|s| s.to_string()).collect(),
+        enabled: package.is_enabled(disabled_servers),
+        unsupported: false,
+        on_path: false,
+    });
+    let binaries = MANAGED_BINARIES.iter().map(|binary| ManagedPackageStatus {
+        name: binary.server.to_string(),
+        kind: PackageKind::Binary,
+        pinned_version: binary.version.to_string(),
+        installed_version: root.and_then(|root| installed_binary_version(root, binary)),
+        servers: vec![binary.server.to_string()],
+        enabled: binary.is_enabled(disabled_servers),
+        unsupported: !binary.is_supported(),
+        on_path: on_path(binary.command),
+    });
+    packages.chain(binaries).collect()
+}
+
+/// Installed and pinned versions of every managed package and binary.
+pub fn package_status(disabled_servers: &[String]) -> Vec<ManagedPackageStatus> {
+    status_in(
+        managed_lsp_root_dir().as_deref(),
+        disabled_servers,
+        crate::lsp::command_in_path,
+    )
+}
+
+/// Enabled packages whose installed version differs from the pin.
+pub fn check_for_updates(disabled_servers: &[String]) -> Vec<ManagedPackageStatus> {
+    package_status(disabled_servers)
+        .into_iter()
+        .filter(|status| status.enabled && status.needs_update())
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InstallAction {
+    /// Install packages at their pinned versions.
+    Install,
+    /// Reinstall packages whose installed version differs from the pin.
+    Update,
+    /// Remove packages.
+    Uninstall,
+}
+
+impl InstallAction {
+    pub fn verb(self) -> &'static str {
+        match self {
+            InstallAction::Install => "install",
+            InstallAction::Update => "update",
+            InstallAction::Uninstall => "uninstall",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum InstallEvent {
+    /// npm is about to run for `packages`.
+    Started {
+        action: InstallAction,
+        packages: Vec<String>,
+    },
+    /// One line of npm output.
+    Output { line: String },
+    /// The action is over; `message` is a summary or the error.
+    Finished {
+        action: InstallAction,
+        success: bool,
+        message: String,
+    },
+}
+
+/// The packages an action applies to. An explicit `packages` list is used
+/// as given (unknown names are an error); otherwise install covers every
+/// enabled npm package and every enabled, supported binary that is not
+/// already installed outside Impulse, update the outdated ones and
+/// uninstall the installed ones.
+fn select_packages(
+    action: InstallAction,
+    packages: &[String],
+    statuses: &[ManagedPackageStatus],
+) -> Result<Vec<Managed>, String> {
+    if !packages.is_empty() {
+        return packages
+            .iter()
+            .map(|name| {
+                Managed::find(name).ok_or_else(|| format!("'{}' is not a managed package", name))
+            })
+            .collect();
+    }
+    let wanted = |status: &ManagedPackageStatus| match action {
+        InstallAction::Install => {
+            status.enabled
+                && !status.unsupported
+                && (status.kind == PackageKind::Npm || !status.on_path)
+        }
+        InstallAction::Update => status.enabled && status.needs_update(),
+        InstallAction::Uninstall => status.installed_version.is_some(),
+    };
+    Ok(statuses
+        .iter()
+        .filter(|status| wanted(status))
+        .filter_map(|status| Managed::find(&status.name))
+        .collect())
+}
+
+fn ensure_package_json(root: &Path) -> Result<(), String> {
+    std::fs::create_dir_all(root)
+        .map_err(|e| format!("Failed to create {}: {}", root.display(), e))?;
+    let package_json = root.join("package.json");
+    if package_json.exists() {
+        return Ok(());
+    }
+    let package_doc = serde_json::json!({
+        "name": "impulse-lsp-servers",
+        "private": true,
+        "description": "Managed web LSP dependencies for Impulse",
+        "license": "UNLICENSED"
+    });
+    let content = serde_json::to_string_pretty(&package_doc)
+        .map_err(|e| format!("Failed to serialize package.json: {}", e))?;
+    std::fs::write(&package_json, content)
+        .map_err(|e| format!("Failed to write {}: {}", package_json.display(), e))
+}
+
+/// Run `program`, forwarding each line it prints on stdout or stderr.
+fn run_streaming(
+    program: &str,
+    args: &[String],
+    envs: &[(&str, &Path)],
+    on_line: &mut dyn FnMut(String),
+) -> Result<(), String> {
+    let mut child = Command::new(program)
+        .args(args)
+        .envs(envs.iter().copied())
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run {} {}: {}", program, args[0], e))?;
+
+    let (tx, rx) = std::sync::mpsc::channel::<String>();
+    let readers: Vec<_> = [
+        child
+            .stdout
+            .take()
+            .map(|s| Box::new(s) as Box<dyn Read + Send>),
+        child
+            .stderr
+            .take()
+            .map(|s| Box::new(s) as Box<dyn Read + Send>),
+    ]
+    .into_iter()
+    .flatten()
+    .map(|stream| {
+        let tx = tx.clone();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stream).lines().map_while(Result::ok) {
+                if tx.send(line).is_err() {
+                    break;
+                }
+            }
+        })
+    })
+    .collect();
+    drop(tx);
+
+    for line in rx {
+        if !line.trim().is_empty() {
+            on_line(line);
+        }
+    }
+    for reader in readers {
+        let _ = reader.join();
+    }
+
+    let status = child
+        .wait()
+        .map_err(|e| format!("Failed to wait for {}: {}", program, e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} {} failed with status {}",
+            program, args[0], status
+        ))
+    }
+}
+
+fn go_is_available() -> bool {
+    Command::new("go")
+        .arg("version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// Point `bin/<command>` at `target`, replacing an older link.
+fn link_command(root: &Path, command: &str, target: &Path) -> Result<(), String> {
+    let bin_dir = root.join("bin");
+    std::fs::create_dir_all(&bin_dir)
+        .map_err(|e| format!("Failed to create {}: {}", bin_dir.display(), e))?;
+    let link = bin_dir.join(command);
+    let _ = std::fs::remove_file(&link);
+    #[cfg(unix)]
+    let linked = std::os::unix::fs::symlink(target, &link);
+    #[cfg(not(unix))]
+    let linked = std::fs::copy(target, &link).map(|_| ());
+    linked.map_err(|e| format!("Failed to link {}: {}", link.display(), e))
+}
+
+fn install_binary(
+    root: &Path,
+    binary: &ManagedBinary,
+    on_line: &mut dyn FnMut(String),
+) -> Result<(), String> {
+    let server_root = binary_root(root, binary);
+    let dir = server_root.join(binary.version);
+    let executable = match &binary.source {
+        BinarySource::GitHubRelease { repo, tag, .. } => {
+            let asset = binary
+                .asset_for(std::env::consts::OS, std::env::consts::ARCH)
+                .ok_or_else(|| {
+                    format!(
+                        "{} has no prebuilt binary for {}/{}",
+                        binary.server,
+                        std::env::consts::OS,
+                        std::env::consts::ARCH
+                    )
+                })?;
+            crate::lsp_download::install_release_asset(
+                repo,
+                tag,
+                asset.name,
+                asset.archive,
+                asset.binary,
+                &dir,
+                on_line,
+            )?
+        }
+        BinarySource::GoInstall { module } => {
+            if !go_is_available() {
+                return Err(format!(
+                    "{} is installed with `go install`, but go was not found in PATH",
+                    binary.server
+                ));
+            }
+            std::fs::create_dir_all(&dir)
+                .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+            let args = vec![
+                "install".to_string(),
+                format!("{}@{}", module, binary.version),
+            ];
+            run_streaming("go", &args, &[("GOBIN", &dir)], on_line)?;
+            dir.join(binary.command)
+        }
+    };
+    link_command(root, binary.command, &executable)?;
+
+    // Drop the previously installed version once the new one is linked.
+    if let Ok(entries) = std::fs::read_dir(&server_root) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() && path != dir {
+                let _ = std::fs::remove_dir_all(path);
+            }
+        }
+    }
+    std::fs::write(server_root.join(VERSION_MARKER), binary.version)
+        .map_err(|e| format!("Failed to record {} version: {}", binary.server, e))
+}
+
+fn uninstall_binary(root: &Path, binary: &ManagedBinary) -> Result<(), String> {
+    let _ = std::fs::remove_file(root.join("bin").join(binary.command));
+    let server_root = binary_root(root, binary);
+    match std::fs::remove_dir_all(&server_root) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(format!("Failed to remove {}: {}", server_root.display(), e)),
+    }
+}
+
+fn run_action(
+    action: InstallAction,
+    packages: &[String],
+    disabled_servers: &[String],
+    on_event: &mut dyn FnMut(InstallEvent),
+) -> Result<String, String> {
+    let root = managed_lsp_root_dir()
+        .ok_or_else(|| "Unable to determine data directory for managed LSPs".to_string())?;
+    let statuses = status_in(Some(&root), disabled_servers, crate::lsp::command_in_path);
+    let mut selected = select_packages(action, packages, &statuses)?;
+
+    let wants_npm = selected.iter().any(|m| matches!(m, Managed::Npm(_)));
+    let mut skipped_npm = false;
+    if wants_npm && !npm_is_available() {
+        let npm_missing = "npm is required but was not found in PATH. Install Node.js + npm first.";
+        if !packages.is_empty() || selected.iter().all(|m| matches!(m, Managed::Npm(_))) {
+            return Err(npm_missing.to_string());
+        }
+        // Native servers still install; report the web ones as skipped.
+        selected.retain(|m| !matches!(m, Managed::Npm(_)));
+        skipped_npm = true;
+    }
+    if selected.is_empty() {
+        return Ok(match action {
+            InstallAction::Install => "No language servers are enabled".to_string(),
+            InstallAction::Update => "Language servers are up to date".to_string(),
+            InstallAction::Uninstall => "No language servers are installed".to_string(),
+        });
+    }
+
+    on_event(InstallEvent::Started {
+        action,
+        packages: selected.iter().map(|m| m.name().to_string()).collect(),
+    });
+    let mut on_line = |line: String| on_event(InstallEvent::Output { line });
+    if skipped_npm {
+        on_line("npm was not found in PATH; skipping the web language servers".to_string());
+    }
+
+    let npm_packages: Vec<&ManagedPackage> = selected
+        .iter()
+        .filter_map(|m| match m {
+            Managed::Npm(package) => Some(*package),
+            Managed::Binary(_) => None,
+        })
+        .collect();
+    if !npm_packages.is_empty() {
+        ensure_package_json(&root)?;
+        let mut args: Vec<String> = match action {
+            InstallAction::Install | InstallAction::Update => vec!["install".into()],
+            InstallAction::Uninstall => vec!["uninstall".into()],
+        };
+        args.extend([
+            "--prefix".to_string(),
+            root.to_string_lossy().to_string(),
+            "--no-audit".to_string(),
+            "--no-fund".to_string(),
+        ]);
+        match action {
+            InstallAction::Install | InstallAction::Update => {
+                args.push("--save-exact".to_string());
+                args.extend(npm_packages.iter().map(|p| p.spec()));
+            }
+            InstallAction::Uninstall => {
+                args.extend(npm_packages.iter().map(|p| p.name.to_string()));
+            }
+        }
+        run_streaming("npm", &args, &[], &mut on_line)?;
+    }
+
+    for managed in &selected {
+        let Managed::Binary(binary) = managed else {
+            continue;
+        };
+        match action {
+            InstallAction::Install | InstallAction::Update => {
+                on_line(format!("Installing {} {}", binary.server, binary.version));
+                install_binary(&root, binary, &mut on_line)?;
+            }
+            InstallAction::Uninstall => {
+                on_line(format!("Removing {}", binary.server));
+                uninstall_binary(&root, binary)?;
+            }
+        }
+    }
+
+    Ok(match action {
+        InstallAction::Uninstall => format!("Uninstalled {} package(s)", selected.len()),
+        _ => format!(
+            "Installed {} package(s) to {}",
+            selected.len(),
+            root.display()
+        ),
+    })
+}
+
+/// Run `action` on the calling thread, reporting progress to `on_event`.
+/// An empty `packages` list selects packages as described on
+/// [`InstallAction`]. Always ends with an [`InstallEvent::Finished`].
+pub fn run(
+    action: InstallAction,
+    packages: &[String],
+    disabled_servers: &[String],
+    mut on_event: impl FnMut(InstallEvent),
+) -> Result<String, String> {
+    let result = run_action(action, packages, disabled_servers, &mut on_event);
+    let (success, message) = match &result {
+        Ok(message) => (true, message.clone()),
+        Err(e) => (false, e.clone()),
+    };
+    if !success {
+        log::warn!("Managed LSP {} failed: {}", action.verb(), message);
+    }
+    on_event(InstallEvent::Finished {
+        action,
+        success,
+        message,
+    });
+    result
+}
+
+/// Run `action` on a background thread. The receiver yields progress and
+/// disconnects after the final [`InstallEvent::Finished`].
+pub fn spawn(
+    action: InstallAction,
+    packages: Vec<String>,
+    disabled_servers: Vec<String>,
+) -> std::sync::mpsc::Receiver<InstallEvent> {
+    let (tx, rx) = std::sync::mpsc::channel();
+    let spawned = std::thread::Builder::new()
+        .name("impulse-lsp-install".to_string())
+        .spawn({
+            let tx = tx.clone();
+            move || {
+                let _ = run(action, &packages, &disabled_servers, |event| {
+                    let _ = tx.send(event);
+                });
+            }
+        });
+    if let Err(e) = spawned {
+        let _ = tx.send(InstallEvent::Finished {
+            action,
+            success: false,
+            message: format!("Failed to start installer thread: {}", e),
+        });
+    }
+    rx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_installed(root: &Path, name: &str, version: &str) {
+        let dir = root.join("node_modules").join(name);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("package.json"),
+            serde_json::json!({ "name": name, "version": version }).to_string(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn installed_versions_are_compared_with_pins() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        let pinned = find_package("intelephense").unwrap().version;
+        write_installed(root, "intelephense", pinned);
+        write_installed(root, "@vue/language-server", "0.0.1");
+
+        let clangd = find_binary("clangd").unwrap();
+        std::fs::create_dir_all(binary_root(root, clangd)).unwrap();
+        std::fs::write(binary_root(root, clangd).join(VERSION_MARKER), "1.0.0\n").unwrap();
+
+        let statuses = status_in(Some(root), &[], |_| false);
+        let status = |name: &str| statuses.iter().find(|s| s.name == name).unwrap();
+        assert_eq!(
+            status("intelephense").installed_version.as_deref(),
+            Some(pinned)
+        );
+        assert!(!status("intelephense").needs_update());
+        assert!(status("@vue/language-server").needs_update());
+        assert_eq!(status("emmet-ls").installed_version, None);
+        assert!(!status("emmet-ls").needs_update());
+        assert_eq!(status("clangd").kind, PackageKind::Binary);
+        assert_eq!(status("clangd").installed_version.as_deref(), Some("1.0.0"));
+
+        let update = select_packages(InstallAction::Update, &[], &statuses).unwrap();
+        assert_eq!(
+            update.iter().map(|m| m.name()).collect::<Vec<_>>(),
+            ["@vue/language-server", "clangd"]
+        );
+        let uninstall = select_packages(InstallAction::Uninstall, &[], &statuses).unwrap();
+        assert_eq!(uninstall.len(), 3);
+        assert!(
+            select_packages(InstallAction::Install, &["left-pad".to_string()], &statuses).is_err()
+        );
+    }
+
+    #[test]
+    fn disabled_servers_drop_their_packages() {
+        let disabled: Vec<String> =
+            vec!["intelephense".into(), "vscode-html-language-server".into()];
+        let statuses = status_in(None, &disabled, |_| false);
+        let enabled = |name: &str| statuses.iter().find(|s| s.name == name).unwrap().enabled;
+        assert!(!enabled("intelephense"));
+        // The package still provides the CSS, JSON and ESLint servers.
+        assert!(enabled("vscode-langservers-extracted"));
+        assert!(enabled("typescript"));
+
+        let install = select_packages(InstallAction::Install, &[], &statuses).unwrap();
+        let npm = install
+            .iter()
+            .filter(|m| matches!(m, Managed::Npm(_)))
+            .count();
+        assert!(install.iter().all(|m| m.name() != "intelephense"));
+        assert_eq!(npm, MANAGED_PACKAGES.len() - 1);
+    }
+
+    #[test]
+    fn binaries_on_path_or_without_a_build_are_not_installed_by_default() {
+        let statuses = status_in(None, &["gopls".to_string()], |command| {
+            command == "rust-analyzer"
+        });
+        let install = select_packages(InstallAction::Install, &[], &statuses).unwrap();
+        let binaries: Vec<&str> = install
+            .iter()
+            .filter(|m| matches!(m, Managed::Binary(_)))
+            .map(|m| m.name())
+            .collect();
+        assert!(!binaries.contains(&"rust-analyzer"));
+        assert!(!binaries.contains(&"gopls"));
+        let lua = find_binary("lua-language-server").unwrap();
+        assert_eq!(
+            binaries.contains(&"lua-language-server"),
+            lua.is_supported()
+        );
+
+        // An explicit request still installs a binary found on PATH.
+        let explicit = select_packages(
+            InstallAction::Install,
+            &["rust-analyzer".to_string()],
+            &statuses,
+        )
+        .unwrap();
+        assert_eq!(explicit[0].name(), "rust-analyzer");
+    }
+
+    #[test]
+    fn release_assets_are_selected_per_platform() {
+        let clangd = find_binary("clangd").unwrap();
+        assert!(clangd.asset_for("linux", "aarch64").is_none());
+        let asset = clangd.asset_for("linux", "x86_64").unwrap();
+        assert_eq!(asset.archive, ArchiveKind::Zip);
+        assert!(asset.name.contains(clangd.version));
+        let ra = find_binary("rust-analyzer").unwrap();
+        assert_eq!(
+            ra.asset_for("macos", "aarch64").unwrap().name,
+            "rust-analyzer-aarch64-apple-darwin.gz"
+        );
+        assert!(find_binary("gopls")
+            .unwrap()
+            .asset_for("linux", "x86_64")
+            .is_none());
+        assert!(find_binary("gopls").unwrap().is_supported());
+    }
+
+    #[test]
+    fn every_managed_command_belongs_to_a_package() {
+        for command in crate::lsp::managed_web_lsp_commands() {
+            assert!(
+                MANAGED_PACKAGES.iter().any(|p| p.servers.contains(command)),
+                "{} has no managed package",
+                command
+            );
+        }
+    }
+}
@@ -0,0 +1,320 @@
+//! `workspace/didChangeWatchedFiles` support for LSP clients.
+//!
+//! Servers register glob watchers with `client/registerCapability`. The first
+//! registration starts a [`FileWatcher`] on the server's root; changes that
+//! match a registered watcher are batched briefly (a git checkout touches
+//! many files at once) and forwarded as one `didChangeWatchedFiles`
+//! notification. Dropping the last registration stops the watcher.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use globset::{GlobBuilder, GlobMatcher};
+use lsp_types::{FileChangeType, FileEvent, GlobPattern, OneOf, WatchKind};
+use parking_lot::Mutex;
+use tokio::sync::mpsc;
+
+use crate::watch::{FileWatcher, WatchEvent, WatchEventKind};
+
+/// How long to collect filesystem events before notifying the server.
+const WATCH_BATCH_DELAY: Duration = Duration::from_millis(100);
+
+/// One registered `FileSystemWatcher` with its glob compiled.
+struct CompiledWatcher {
+    /// Directory the glob is matched relative to, or `None` for absolute
+    /// patterns, which match the full path.
+    base: Option<PathBuf>,
+    glob: GlobMatcher,
+    kind: WatchKind,
+}
+
+impl CompiledWatcher {
+    fn matches(&self, path: &Path, change: FileChangeType) -> bool {
+        let wanted = match change {
+            FileChangeType::CREATED => WatchKind::Create,
+            FileChangeType::DELETED => WatchKind::Delete,
+            _ => WatchKind::Change,
+        };
+        if !self.kind.contains(wanted) {
+            return false;
+        }
+        match &self.base {
+            Some(base) => path
+                .strip_prefix(base)
+                .is_ok_and(|rel| self.glob.is_match(rel)),
+            None => self.glob.is_match(path),
+        }
+    }
+}
+
+/// Compile the watchers from a `workspace/didChangeWatchedFiles`
+/// registration. String patterns are relative to `root` unless absolute.
+fn compile_watchers(root: &Path, options: serde_json::Value) -> Vec<CompiledWatcher> {
+    let options: lsp_types::DidChangeWatchedFilesRegistrationOptions =
+        match serde_json::from_value(options) {
+            Ok(options) => options,
+            Err(e) => {
+                log::warn!("Invalid didChangeWatchedFiles registration: {}", e);
+                return Vec::new();
+            }
+        };
+    options
+        .watchers
+        .into_iter()
+        .filter_map(|watcher| {
+            let (base, pattern) = match watcher.glob_pattern {
+                GlobPattern::String(pattern) if Path::new(&pattern).is_absolute() => {
+                    (None, pattern)
+                }
+                GlobPattern::String(pattern) => (Some(root.to_path_buf()), pattern),
+                GlobPattern::Relative(relative) => {
+                    let base_uri = match &relative.base_uri {
+                        OneOf::Left(folder) => folder.uri.as_str().to_string(),
+                        OneOf::Right(uri) => uri.as_str().to_string(),
+                    };
+                    let base = url::Url::parse(&base_uri).ok()?.to_file_path().ok()?;
+                    (Some(base), relative.pattern)
+                }
+            };
+            let glob = match GlobBuilder::new(&pattern).literal_separator(true).build() {
+                Ok(glob) => glob.compile_matcher(),
+                Err(e) => {
+                    log::warn!("Invalid watch pattern '{}': {}", pattern, e);
+                    return None;
+                }
+            };
+            Some(CompiledWatcher {
+                base,
+                glob,
+                kind: watcher.kind.unwrap_or(WatchKind::all()),
+            })
+        })
+        .collect()
+}
+
+/// Reduce watch events to the LSP file events some watcher is interested
+/// in, in order and without duplicates. A rename is a delete plus a create.
+fn file_events<'a>(
+    watchers: impl Iterator<Item = &'a CompiledWatcher> + Clone,
+    events: &[WatchEvent],
+) -> Vec<FileEvent> {
+    let mut out: Vec<FileEvent> = Vec::new();
+    for event in events {
+        let changes: Vec<(&String, FileChangeType)> = match event.kind {
+            WatchEventKind::Created => event
+                .paths
+                .iter()
+                .map(|p| (p, FileChangeType::CREATED))
+                .collect(),
+            WatchEventKind::Modified => event
+                .paths
+                .iter()
+                .map(|p| (p, FileChangeType::CHANGED))
+                .collect(),
+            WatchEventKind::Deleted => event
+                .paths
+                .iter()
+                .map(|p| (p, FileChangeType::DELETED))
+                .collect(),
+            WatchEventKind::Renamed => match event.paths.as_slice() {
+                [from, to] => vec![
+                    (from, FileChangeType::DELETED),
+                    (to, FileChangeType::CREATED),
+                ],
+                _ => Vec::new(),
+            },
+        };
+        for (path, change) in changes {
+            let path = Path::new(path);
+            if !watchers.clone().any(|w| w.matches(path, change)) {
+                continue;
+            }
+            let Some(uri) = crate::util::file_path_to_uri(path) else {
+                continue;
+            };
+            let Ok(uri) = uri.parse::<lsp_types::Uri>() else {
+                continue;
+            };
+            if !out.iter().any(|e| e.uri == uri && e.typ == change) {
+                out.push(FileEvent { uri, typ: change });
+            }
+        }
+    }
+    out
+}
+
+#[derive(Default)]
+struct WatchState {
+    registrations: HashMap<String, Vec<CompiledWatcher>>,
+    watcher: Option<FileWatcher>,
+}
+
+/// The file watchers one language server registered.
+pub(crate) struct WatchedFiles {
+    root: PathBuf,
+    sender: mpsc::UnboundedSender<Vec<u8>>,
+    state: Mutex<WatchState>,
+}
+
+impl WatchedFiles {
+    /// Track registrations for the server rooted at `root_uri`, sending
+    /// notifications through `sender`. `None` for non-file roots.
+    pub(crate) fn new(root_uri: &str, sender: mpsc::UnboundedSender<Vec<u8>>) -> Option<Arc<Self>> {
+        let root = url::Url::parse(root_uri).ok()?.to_file_path().ok()?;
+        Some(Arc::new(Self {
+            root,
+            sender,
+            state: Mutex::new(WatchState::default()),
+        }))
+    }
+
+    /// Add a `workspace/didChangeWatchedFiles` registration, starting the
+    /// filesystem watcher if this is the first. Must run inside a Tokio
+    /// runtime.
+    pub(crate) fn register(self: &Arc<Self>, id: &str, options: serde_json::Value) {
+        let watchers = compile_watchers(&self.root, options);
+        let mut state = self.state.lock();
+        state.registrations.insert(id.to_string(), watchers);
+        if state.watcher.is_none() {
+            state.watcher = self.start_watcher();
+        }
+    }
+
+    /// Drop a registration, stopping the watcher once none are left.
+    pub(crate) fn unregister(&self, id: &str) {
+        let mut state = self.state.lock();
+        state.registrations.remove(id);
+        if state.registrations.is_empty() {
+            state.watcher = None;
+        }
+    }
+
+    fn start_watcher(self: &Arc<Self>) -> Option<FileWatcher> {
+        let (tx, mut rx) = mpsc::unbounded_channel::<WatchEvent>();
+        let root = self.root.to_string_lossy().to_string();
+        let watcher = match FileWatcher::new(&root, move |event| {
+            let _ = tx.send(event);
+        }) {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                log::warn!("LSP file watching unavailable: {}", e);
+                return None;
+            }
+        };
+
+        // The task ends once the watcher (and with it `tx`) is dropped.
+        let this: Weak<Self> = Arc::downgrade(self);
+        tokio::spawn(async move {
+            while let Some(first) = rx.recv().await {
+                tokio::time::sleep(WATCH_BATCH_DELAY).await;
+                let mut batch = vec![first];
+                while let Ok(event) = rx.try_recv() {
+                    batch.push(event);
+                }
+                let Some(this) = this.upgrade() else {
+                    break;
+                };
+                this.notify(&batch);
+            }
+        });
+        Some(watcher)
+    }
+
+    fn notify(&self, events: &[WatchEvent]) {
+        let changes = {
+            let state = self.state.lock();
+            file_events(state.registrations.values().flatten(), events)
+        };
+        if changes.is_empty() {
+            return;
+        }
+        let msg = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": "workspace/didChangeWatchedFiles",
+            "params": lsp_types::DidChangeWatchedFilesParams { changes },
+        });
+        if let Ok(body) = serde_json::to_vec(&msg) {
+            let _ = self.sender.send(body);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(kind: WatchEventKind, paths: &[&str]) -> WatchEvent {
+        WatchEvent {
+            kind,
+            paths: paths.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn events_are_filtered_by_registered_globs_and_kinds() {
+        let root = Path::new("/work/project");
+        let mut watchers = compile_watchers(
+            root,
+            serde_json::json!({
+                "watchers": [
+                    { "globPattern": "**/*.rs" },
+                    { "globPattern": "/work/project/Cargo.toml", "kind": 2 },
+                    {
+                        "globPattern": { "baseUri": "file:///work/project/web", "pattern": "*.ts" },
+                        "kind": 1
+                    }
+                ]
+            }),
+        );
+        assert_eq!(watchers.len(), 3);
+        watchers.extend(compile_watchers(root, serde_json::json!({ "bogus": true })));
+
+        let changes = file_events(
+            watchers.iter(),
+            &[
+                event(WatchEventKind::Modified, &["/work/project/src/main.rs"]),
+                event(WatchEventKind::Modified, &["/work/project/src/main.rs"]),
+                event(WatchEventKind::Modified, &["/work/project/README.md"]),
+                event(WatchEventKind::Deleted, &["/work/project/Cargo.toml"]),
+                event(WatchEventKind::Modified, &["/work/project/Cargo.toml"]),
+                event(WatchEventKind::Created, &["/work/project/web/app.ts"]),
+                event(WatchEventKind::Modified, &["/work/project/web/app.ts"]),
+                event(
+                    WatchEventKind::Renamed,
+                    &["/work/project/src/a.rs", "/work/project/src/b.rs"],
+                ),
+            ],
+        );
+        let summary: Vec<(String, FileChangeType)> = changes
+            .into_iter()
+            .map(|e| (e.uri.as_str().to_string(), e.typ))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    "file:///work/project/src/main.rs".to_string(),
+                    FileChangeType::CHANGED
+                ),
+                (
+                    "file:///work/project/Cargo.toml".to_string(),
+                    FileChangeType::CHANGED
+                ),
+                (
+                    "file:///work/project/web/app.ts".to_string(),
+                    FileChangeType::CREATED
+                ),
+                (
+                    "file:///work/project/src/a.rs".to_string(),
+                    FileChangeType::DELETED
+                ),
+                (
+                    "file:///work/project/src/b.rs".to_string(),
+                    FileChangeType::CREATED
+                ),
+            ]
+        );
+    }
+}
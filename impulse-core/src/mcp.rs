@@ -0,0 +1,930 @@
+//! Model Context Protocol server. With the user's consent, a workspace is
+//! shared with coding agents (Claude Code, Codex, ...): they can list the
+//! open files, read buffers with their unsaved changes, see diagnostics,
+//! search, and propose edits, which the frontend previews and applies
+//! through [`crate::workspace_edit`].
+//!
+//! A shared workspace listens on its own Unix socket under the runtime
+//! directory ([`socket_path`]), accessible only to the user. Agents speak
+//! MCP over stdio, so they launch `impulse --mcp-bridge`, which connects its
+//! stdin and stdout to the socket of the workspace it was started in.
+//! Messages are JSON-RPC 2.0, one per line.
+//!
+//! Consent is recorded per workspace folder in
+//! `<config dir>/impulse/agent_access.json`. Unlike workspace trust it
+//! doesn't extend to subfolders: each workspace is allowed on its own.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Arc};
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::local_socket::{self, LineServer};
+use crate::workspace_edit::{FileEdits, TextEdit};
+
+const CONSENT_FILE: &str = "agent_access.json";
+
+/// Newest protocol revision first; an agent asking for another one gets
+/// the newest.
+const PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// How long to wait for the frontend to list its open files.
+const OPEN_FILES_TIMEOUT: Duration = Duration::from_secs(5);
+/// How long an edit may wait for the user to review it.
+const EDIT_TIMEOUT: Duration = Duration::from_secs(600);
+/// Largest file `read_file` returns from disk.
+const MAX_READ_BYTES: u64 = 2 * 1024 * 1024;
+const DEFAULT_SEARCH_LIMIT: usize = 100;
+const MAX_SEARCH_LIMIT: usize = 500;
+
+// ---------------------------------------------------------------------------
+// Consent
+// ---------------------------------------------------------------------------
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Consent {
+    Allowed,
+    /// The user said no; don't ask again.
+    Denied,
+    /// No decision yet. Treated as denied.
+    Unknown,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ConsentStore {
+    pub allowed: Vec<PathBuf>,
+    pub denied: Vec<PathBuf>,
+}
+
+impl ConsentStore {
+    pub fn consent_for(&self, folder: &Path) -> Consent {
+        if self.allowed.iter().any(|f| f == folder) {
+            Consent::Allowed
+        } else if self.denied.iter().any(|f| f == folder) {
+            Consent::Denied
+        } else {
+            Consent::Unknown
+        }
+    }
+
+    /// Record a decision for `folder`, replacing any earlier one for it.
+    pub fn set(&mut self, folder: &Path, allowed: bool) {
+        self.allowed.retain(|f| f != folder);
+        self.denied.retain(|f| f != folder);
+        if allowed {
+            self.allowed.push(folder.to_path_buf());
+        } else {
+            self.denied.push(folder.to_path_buf());
+        }
+    }
+}
+
+fn store_path() -> Option<PathBuf> {
+    Some(crate::settings::settings_path()?.with_file_name(CONSENT_FILE))
+}
+
+fn load_store() -> ConsentStore {
+    let Some(path) = store_path() else {
+        return ConsentStore::default();
+    };
+    match std::fs::read_to_string(&path) {
+        Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+            log::warn!("Invalid agent access store at {}: {}", path.display(), e);
+            ConsentStore::default()
+        }),
+        Err(_) => ConsentStore::default(),
+    }
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Whether the user let agents use the workspace `folder`.
+pub fn consent(folder: &Path) -> Consent {
+    load_store().consent_for(&canonical(folder))
+}
+
+/// Allow or deny agent access to `folder`. Persisted immediately.
+pub fn set_consent(folder: &Path, allowed: bool) -> Result<(), String> {
+    let path = store_path().ok_or("No config directory for the agent access store")?;
+    let mut store = load_store();
+    store.set(&canonical(folder), allowed);
+    let json = serde_json::to_string_pretty(&store)
+        .map_err(|e| format!("Failed to serialize agent access store: {}", e))?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, json)
+        .and_then(|()| std::fs::rename(&tmp, &path))
+        .map_err(|e| format!("Failed to save {}: {}", path.display(), e))
+}
+
+/// The socket a shared `workspace` listens on: a name derived from the
+/// folder, in `$XDG_RUNTIME_DIR/impulse/mcp` (the cache directory where
+/// there is no runtime directory).
+pub fn socket_path(workspace: &Path) -> Option<PathBuf> {
+    let dir = dirs::runtime_dir()
+        .or_else(dirs::cache_dir)?
+        .join("impulse")
+        .join("mcp");
+    let workspace = canonical(workspace);
+    let digest = ring::digest::digest(
+        &ring::digest::SHA256,
+        workspace.as_os_str().as_encoded_bytes(),
+    );
+    let name: String = digest.as_ref()[..8]
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    Some(dir.join(format!("{}.sock", name)))
+}
+
+// ---------------------------------------------------------------------------
+// Frontend requests
+// ---------------------------------------------------------------------------
+
+/// A file open in an editor tab.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OpenFile {
+    pub path: String,
+    pub language: String,
+    pub modified: bool,
+    /// Buffer contents, unsaved changes included.
+    pub content: String,
+}
+
+/// A language server diagnostic, in LSP coordinates.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub line: u32,
+    pub character: u32,
+    pub end_line: u32,
+    pub end_character: u32,
+    /// `error`, `warning`, `information` or `hint`.
+    pub severity: String,
+    pub message: String,
+}
+
+/// What an agent needs from the frontend.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum HostRequestKind {
+    /// Reply with the [`OpenFile`]s of every editor tab.
+    OpenFiles,
+    /// Show the edits to the user and apply the ones they accept. Reply
+    /// with a summary, or an error when they are declined.
+    ApplyEdits { files: Vec<FileEdits> },
+}
+
+/// A request waiting for the frontend, taken from
+/// [`McpServer::next_request`]. The agent waits until it is answered.
+#[derive(Debug)]
+pub struct HostRequest {
+    pub id: u64,
+    pub kind: HostRequestKind,
+    reply: mpsc::Sender<Result<Value, String>>,
+}
+
+impl HostRequest {
+    pub fn respond(self, result: Result<Value, String>) {
+        // The agent may have disconnected.
+        let _ = self.reply.send(result);
+    }
+}
+
+/// What the tools need from the workspace; the server asks the frontend,
+/// tests supply their own.
+trait Workspace {
+    fn root(&self) -> &Path;
+    fn open_files(&self) -> Result<Vec<OpenFile>, String>;
+    fn diagnostics(&self) -> BTreeMap<String, Vec<Diagnostic>>;
+    fn apply_edits(&self, files: Vec<FileEdits>) -> Result<String, String>;
+}
+
+// ---------------------------------------------------------------------------
+// Protocol
+// ---------------------------------------------------------------------------
+
+/// Handle one JSON-RPC message and return the response line, or `None` for
+/// notifications.
+fn handle_message(workspace: &dyn Workspace, line: &str) -> Option<String> {
+    let message: Value = match serde_json::from_str(line) {
+        Ok(message) => message,
+        Err(e) => {
+            return Some(error_response(
+                Value::Null,
+                PARSE_ERROR,
+                &format!("Parse error: {}", e),
+            ))
+        }
+    };
+    let id = message.get("id").cloned()?;
+    let params = message.get("params").cloned().unwrap_or(Value::Null);
+    let result = match message.get("method").and_then(Value::as_str) {
+        Some("initialize") => Ok(initialize_result(&params)),
+        Some("ping") => Ok(json!({})),
+        Some("tools/list") => Ok(json!({ "tools": tool_definitions() })),
+        Some("tools/call") => call_tool(workspace, &params),
+        Some(method) => Err((METHOD_NOT_FOUND, format!("Unknown method: {}", method))),
+        None => Err((INVALID_PARAMS, "Missing method".to_string())),
+    };
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }).to_string(),
+        Err((code, message)) => error_response(id, code, &message),
+    })
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> String {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message },
+    })
+    .to_string()
+}
+
+fn initialize_result(params: &Value) -> Value {
+    let requested = params.get("protocolVersion").and_then(Value::as_str);
+    let version = PROTOCOL_VERSIONS
+        .iter()
+        .find(|v| Some(**v) == requested)
+        .unwrap_or(&PROTOCOL_VERSIONS[0]);
+    json!({
+        "protocolVersion": version,
+        "capabilities": { "tools": {} },
+        "serverInfo": { "name": "impulse", "version": env!("CARGO_PKG_VERSION") },
+        "instructions": "Tools for the workspace open in the Impulse editor. Paths may be \
+            absolute or relative to the workspace root. Edits are shown to the user, who \
+            may decline them or apply only some.",
+    })
+}
+
+fn tool_definitions() -> Value {
+    let path = json!({ "type": "string", "description": "File path, absolute or relative to the workspace root" });
+    json!([
+        {
+            "name": "list_open_files",
+            "description": "List the files open in editor tabs, with their language and whether they have unsaved changes.",
+            "inputSchema": { "type": "object", "properties": {} },
+        },
+        {
+            "name": "read_file",
+            "description": "Read a file. Open files are read from the editor, unsaved changes included.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "path": path },
+                "required": ["path"],
+            },
+        },
+        {
+            "name": "get_diagnostics",
+            "description": "Language server errors and warnings, for one file or every file that has any. Lines and characters are 0-based.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "path": path },
+            },
+        },
+        {
+            "name": "search",
+            "description": "Search file contents in the workspace, respecting .gitignore.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string" },
+                    "case_sensitive": { "type": "boolean" },
+                    "limit": { "type": "integer", "minimum": 1, "maximum": MAX_SEARCH_LIMIT },
+                },
+                "required": ["query"],
+            },
+        },
+        {
+            "name": "apply_edits",
+            "description": "Propose text edits. The user reviews them in Impulse and may apply all, some or none; the result says which. Open files are edited in their buffer and left unsaved. Lines are 0-based; characters are 0-based UTF-16 code units.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "files": {
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "path": path,
+                                "edits": {
+                                    "type": "array",
+                                    "items": {
+                                        "type": "object",
+                                        "properties": {
+                                            "start_line": { "type": "integer" },
+                                            "start_character": { "type": "integer" },
+                                            "end_line": { "type": "integer" },
+                                            "end_character": { "type": "integer" },
+                                            "new_text": { "type": "string" },
+                                        },
+                                        "required": ["start_line", "start_character", "end_line", "end_character", "new_text"],
+                                    },
+                                },
+                            },
+                            "required": ["path", "edits"],
+                        },
+                    },
+                },
+                "required": ["files"],
+            },
+        },
+    ])
+}
+
+/// Run a `tools/call`. Tool failures are results with `isError` so the
+/// agent sees them; only a malformed call is a protocol error.
+fn call_tool(workspace: &dyn Workspace, params: &Value) -> Result<Value, (i64, String)> {
+    let name = params
+        .get("name")
+        .and_then(Value::as_str)
+        .ok_or((INVALID_PARAMS, "Missing tool name".to_string()))?;
+    let args = params.get("arguments").cloned().unwrap_or(json!({}));
+    let output = match name {
+        "list_open_files" => list_open_files(workspace),
+        "read_file" => read_file(workspace, &args),
+        "get_diagnostics" => get_diagnostics(workspace, &args),
+        "search" => search(workspace, &args),
+        "apply_edits" => apply_edits(workspace, &args),
+        _ => return Err((INVALID_PARAMS, format!("Unknown tool: {}", name))),
+    };
+    let (text, is_error) = match output {
+        Ok(text) => (text, false),
+        Err(e) => (e, true),
+    };
+    Ok(json!({
+        "content": [{ "type": "text", "text": text }],
+        "isError": is_error,
+    }))
+}
+
+fn to_json_text<T: Serialize>(value: &T) -> Result<String, String> {
+    serde_json::to_string_pretty(value).map_err(|e| e.to_string())
+}
+
+/// `path` (relative to the root or absolute) as a canonical path inside
+/// the workspace.
+fn resolve_path(workspace: &dyn Workspace, path: &str) -> Result<PathBuf, String> {
+    let path = workspace.root().join(path);
+    crate::util::validate_path_within_root(
+        &path.to_string_lossy(),
+        &workspace.root().to_string_lossy(),
+    )
+}
+
+/// The open files inside the workspace; other windows' files stay private.
+fn workspace_open_files(workspace: &dyn Workspace) -> Result<Vec<OpenFile>, String> {
+    let mut files = workspace.open_files()?;
+    files.retain(|file| canonical(Path::new(&file.path)).starts_with(workspace.root()));
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(files)
+}
+
+fn list_open_files(workspace: &dyn Workspace) -> Result<String, String> {
+    let files: Vec<Value> = workspace_open_files(workspace)?
+        .into_iter()
+        .map(|file| json!({ "path": file.path, "language": file.language, "modified": file.modified }))
+        .collect();
+    to_json_text(&files)
+}
+
+fn string_arg<'a>(args: &'a Value, name: &str) -> Result<&'a str, String> {
+    args.get(name)
+        .and_then(Value::as_str)
+        .ok_or_else(|| format!("Missing \"{}\"", name))
+}
+
+fn read_file(workspace: &dyn Workspace, args: &Value) -> Result<String, String> {
+    let path = resolve_path(workspace, string_arg(args, "path")?)?;
+    let open = workspace_open_files(workspace)?
+        .into_iter()
+        .find(|file| canonical(Path::new(&file.path)) == path);
+    if let Some(file) = open {
+        return Ok(file.content);
+    }
+    let metadata =
+        std::fs::metadata(&path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+    if metadata.len() > MAX_READ_BYTES {
+        return Err(format!(
+            "{} is too large to read ({} bytes)",
+            path.display(),
+            metadata.len()
+        ));
+    }
+    std::fs::read_to_string(&path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))
+}
+
+fn get_diagnostics(workspace: &dyn Workspace, args: &Value) -> Result<String, String> {
+    let only = match args.get("path").and_then(Value::as_str) {
+        Some(path) => Some(resolve_path(workspace, path)?),
+        None => None,
+    };
+    let diagnostics: BTreeMap<String, Vec<Diagnostic>> = workspace
+        .diagnostics()
+        .into_iter()
+        .filter(|(path, diagnostics)| {
+            let path = canonical(Path::new(path));
+            !diagnostics.is_empty()
+                && path.starts_with(workspace.root())
+                && only.as_ref().is_none_or(|only| *only == path)
+        })
+        .collect();
+    if diagnostics.is_empty() {
+        return Ok("No diagnostics.".to_string());
+    }
+    to_json_text(&diagnostics)
+}
+
+fn search(workspace: &dyn Workspace, args: &Value) -> Result<String, String> {
+    let query = string_arg(args, "query")?;
+    let case_sensitive = args
+        .get("case_sensitive")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    let limit = args
+        .get("limit")
+        .and_then(Value::as_u64)
+        .map_or(DEFAULT_SEARCH_LIMIT, |limit| {
+            (limit as usize).clamp(1, MAX_SEARCH_LIMIT)
+        });
+    let results = crate::search::search_contents(
+        &workspace.root().to_string_lossy(),
+        query,
+        limit,
+        case_sensitive,
+        None,
+    )?;
+    let matches: Vec<Value> = results
+        .into_iter()
+        .map(|result| {
+            json!({
+                "path": result.path,
+                "line": result.line_number,
+                "column": result.column_start,
+                "text": result.line_content,
+            })
+        })
+        .collect();
+    to_json_text(&matches)
+}
+
+#[derive(Deserialize)]
+struct PathEdits {
+    path: String,
+    edits: Vec<TextEdit>,
+}
+
+fn apply_edits(workspace: &dyn Workspace, args: &Value) -> Result<String, String> {
+    let files: Vec<PathEdits> =
+        serde_json::from_value(args.get("files").cloned().unwrap_or_default())
+            .map_err(|e| format!("Invalid \"files\": {}", e))?;
+    let mut grouped = Vec::with_capacity(files.len());
+    for file in files {
+        if file.edits.is_empty() {
+            continue;
+        }
+        let path = resolve_path(workspace, &file.path)?;
+        let uri = crate::util::file_path_to_uri(&path)
+            .ok_or_else(|| format!("Invalid path: {}", file.path))?;
+        grouped.push(FileEdits {
+            uri,
+            edits: file.edits,
+        });
+    }
+    if grouped.is_empty() {
+        return Err("No edits to apply".to_string());
+    }
+    workspace.apply_edits(grouped)
+}
+
+// ---------------------------------------------------------------------------
+// Server
+// ---------------------------------------------------------------------------
+
+struct Shared {
+    root: PathBuf,
+    diagnostics: Mutex<BTreeMap<String, Vec<Diagnostic>>>,
+    requests: mpsc::Sender<HostRequest>,
+    next_id: AtomicU64,
+}
+
+impl Shared {
+    fn ask(&self, kind: HostRequestKind, timeout: Duration) -> Result<Value, String> {
+        let (reply, answer) = mpsc::channel();
+        let request = HostRequest {
+            id: self.next_id.fetch_add(1, Ordering::Relaxed),
+            kind,
+            reply,
+        };
+        self.requests
+            .send(request)
+            .map_err(|_| "Impulse stopped sharing this workspace".to_string())?;
+        answer
+            .recv_timeout(timeout)
+            .map_err(|_| "Impulse didn't answer in time".to_string())?
+    }
+}
+
+impl Workspace for Shared {
+    fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn open_files(&self) -> Result<Vec<OpenFile>, String> {
+        let files = self.ask(HostRequestKind::OpenFiles, OPEN_FILES_TIMEOUT)?;
+        serde_json::from_value(files).map_err(|e| format!("Invalid open files: {}", e))
+    }
+
+    fn diagnostics(&self) -> BTreeMap<String, Vec<Diagnostic>> {
+        self.diagnostics.lock().clone()
+    }
+
+    fn apply_edits(&self, files: Vec<FileEdits>) -> Result<String, String> {
+        let summary = self.ask(HostRequestKind::ApplyEdits { files }, EDIT_TIMEOUT)?;
+        Ok(summary.as_str().unwrap_or("Applied").to_string())
+    }
+}
+
+/// A workspace shared with agents. Dropping it stops listening and
+/// disconnects every agent.
+pub struct McpServer {
+    shared: Arc<Shared>,
+    requests: mpsc::Receiver<HostRequest>,
+    server: LineServer,
+}
+
+impl McpServer {
+    /// Share `workspace` on its [`socket_path`]. Fails when another window
+    /// already shares it. Callers check [`consent`] first.
+    pub fn start(workspace: &Path) -> Result<Self, String> {
+        let socket = socket_path(workspace).ok_or("No runtime directory for the agent socket")?;
+        Self::start_at(&canonical(workspace), socket)
+    }
+
+    fn start_at(root: &Path, socket: PathBuf) -> Result<Self, String> {
+        if local_socket::is_listening(&socket) {
+            return Err(format!(
+                "Another Impulse window is already sharing {} with agents",
+                root.display()
+            ));
+        }
+        let (requests_tx, requests) = mpsc::channel();
+        let shared = Arc::new(Shared {
+            root: root.to_path_buf(),
+            diagnostics: Mutex::new(BTreeMap::new()),
+            requests: requests_tx,
+            next_id: AtomicU64::new(1),
+        });
+        let handler_shared = shared.clone();
+        let server = LineServer::start(
+            socket,
+            "mcp",
+            Arc::new(move |line: &str| handle_message(&*handler_shared, line)),
+        )?;
+        Ok(Self {
+            shared,
+            requests,
+            server,
+        })
+    }
+
+    /// The shared workspace folder.
+    pub fn workspace(&self) -> &Path {
+        &self.shared.root
+    }
+
+    pub fn socket_path(&self) -> &Path {
+        self.server.path()
+    }
+
+    /// The next request agents are waiting on, if any. Frontends poll this
+    /// on their main loop and answer with [`HostRequest::respond`].
+    pub fn next_request(&self) -> Option<HostRequest> {
+        self.requests.try_recv().ok()
+    }
+
+    /// Record the latest diagnostics for the file at `path`.
+    pub fn set_diagnostics(&self, path: &str, diagnostics: Vec<Diagnostic>) {
+        let mut all = self.shared.diagnostics.lock();
+        if diagnostics.is_empty() {
+            all.remove(path);
+        } else {
+            all.insert(path.to_string(), diagnostics);
+        }
+    }
+}
+
+/// `impulse --mcp-bridge`: connect stdin and stdout to the socket of the
+/// workspace containing `dir`, until either side closes.
+#[cfg(unix)]
+pub fn run_stdio_bridge(dir: &Path) -> Result<(), String> {
+    use std::os::unix::net::UnixStream;
+
+    let folder = crate::trust::workspace_folder(dir);
+    let socket = socket_path(&folder).ok_or("No runtime directory for the agent socket")?;
+    let stream = UnixStream::connect(&socket).map_err(|_| {
+        format!(
+            "Impulse isn't sharing {} with agents. Open it in Impulse and run \
+             \"Manage Agent Access (MCP)\".",
+            folder.display()
+        )
+    })?;
+    let mut writer = stream
+        .try_clone()
+        .map_err(|e| format!("Failed to connect to Impulse: {}", e))?;
+    std::thread::spawn(move || {
+        let _ = std::io::copy(&mut std::io::stdin().lock(), &mut writer);
+        let _ = writer.shutdown(std::net::Shutdown::Write);
+    });
+    let mut reader = stream;
+    std::io::copy(&mut reader, &mut std::io::stdout().lock())
+        .map(|_| ())
+        .map_err(|e| format!("Connection to Impulse failed: {}", e))
+}
+
+#[cfg(not(unix))]
+pub fn run_stdio_bridge(_dir: &Path) -> Result<(), String> {
+    Err("Agent access is not supported on this platform".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    struct FakeWorkspace {
+        root: PathBuf,
+        open: Vec<OpenFile>,
+        diagnostics: BTreeMap<String, Vec<Diagnostic>>,
+        applied: RefCell<Vec<FileEdits>>,
+    }
+
+    impl FakeWorkspace {
+        fn new(root: &Path) -> Self {
+            Self {
+                root: root.to_path_buf(),
+                open: Vec::new(),
+                diagnostics: BTreeMap::new(),
+                applied: RefCell::new(Vec::new()),
+            }
+        }
+    }
+
+    impl Workspace for FakeWorkspace {
+        fn root(&self) -> &Path {
+            &self.root
+        }
+
+        fn open_files(&self) -> Result<Vec<OpenFile>, String> {
+            Ok(self.open.clone())
+        }
+
+        fn diagnostics(&self) -> BTreeMap<String, Vec<Diagnostic>> {
+            self.diagnostics.clone()
+        }
+
+        fn apply_edits(&self, files: Vec<FileEdits>) -> Result<String, String> {
+            self.applied.borrow_mut().extend(files);
+            Ok("Applied".to_string())
+        }
+    }
+
+    fn call(workspace: &dyn Workspace, tool: &str, arguments: Value) -> (String, bool) {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 7,
+            "method": "tools/call",
+            "params": { "name": tool, "arguments": arguments },
+        });
+        let response: Value =
+            serde_json::from_str(&handle_message(workspace, &request.to_string()).unwrap())
+                .unwrap();
+        let result = &response["result"];
+        (
+            result["content"][0]["text"].as_str().unwrap().to_string(),
+            result["isError"].as_bool().unwrap(),
+        )
+    }
+
+    #[test]
+    fn consent_is_per_folder() {
+        let mut store = ConsentStore::default();
+        let code = Path::new("/home/me/code");
+        assert_eq!(store.consent_for(code), Consent::Unknown);
+        store.set(code, true);
+        assert_eq!(store.consent_for(code), Consent::Allowed);
+        // Subfolders are separate workspaces.
+        assert_eq!(
+            store.consent_for(Path::new("/home/me/code/app")),
+            Consent::Unknown
+        );
+        store.set(code, false);
+        assert!(store.allowed.is_empty());
+        assert_eq!(store.consent_for(code), Consent::Denied);
+    }
+
+    #[test]
+    fn answers_protocol_messages() {
+        let dir = tempfile::tempdir().unwrap();
+        let workspace = FakeWorkspace::new(dir.path());
+        let respond = |message: Value| -> Value {
+            serde_json::from_str(&handle_message(&workspace, &message.to_string()).unwrap())
+                .unwrap()
+        };
+
+        let init = respond(json!({
+            "jsonrpc": "2.0", "id": 1, "method": "initialize",
+            "params": { "protocolVersion": "2025-03-26", "capabilities": {} },
+        }));
+        assert_eq!(init["id"], 1);
+        assert_eq!(init["result"]["protocolVersion"], "2025-03-26");
+        assert_eq!(init["result"]["serverInfo"]["name"], "impulse");
+        let future = respond(json!({
+            "jsonrpc": "2.0", "id": 2, "method": "initialize",
+            "params": { "protocolVersion": "2099-01-01" },
+        }));
+        assert_eq!(future["result"]["protocolVersion"], PROTOCOL_VERSIONS[0]);
+
+        let tools = respond(json!({ "jsonrpc": "2.0", "id": "t", "method": "tools/list" }));
+        let names: Vec<&str> = tools["result"]["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|tool| tool["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "list_open_files",
+                "read_file",
+                "get_diagnostics",
+                "search",
+                "apply_edits"
+            ]
+        );
+
+        let unknown = respond(json!({ "jsonrpc": "2.0", "id": 3, "method": "resources/list" }));
+        assert_eq!(unknown["error"]["code"], METHOD_NOT_FOUND);
+        let bad_tool = respond(json!({
+            "jsonrpc": "2.0", "id": 4, "method": "tools/call", "params": { "name": "rm" },
+        }));
+        assert_eq!(bad_tool["error"]["code"], INVALID_PARAMS);
+        let garbage: Value =
+            serde_json::from_str(&handle_message(&workspace, "{not json").unwrap()).unwrap();
+        assert_eq!(garbage["error"]["code"], PARSE_ERROR);
+        assert_eq!(garbage["id"], Value::Null);
+
+        let notification = json!({ "jsonrpc": "2.0", "method": "notifications/initialized" });
+        assert!(handle_message(&workspace, &notification.to_string()).is_none());
+    }
+
+    #[test]
+    fn tools_stay_inside_the_workspace() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        std::fs::write(root.join("main.rs"), "fn main() {}\n").unwrap();
+        std::fs::write(root.join("lib.rs"), "pub fn saved() {}\n").unwrap();
+        let mut workspace = FakeWorkspace::new(&root);
+        let open = |path: &Path, content: &str| OpenFile {
+            path: path.to_string_lossy().to_string(),
+            language: "rust".to_string(),
+            modified: true,
+            content: content.to_string(),
+        };
+        workspace.open = vec![
+            open(&root.join("lib.rs"), "pub fn unsaved() {}\n"),
+            open(Path::new("/elsewhere/secret.rs"), "hidden"),
+        ];
+        let lib = root.join("lib.rs").to_string_lossy().to_string();
+        let diagnostic = Diagnostic {
+            line: 0,
+            character: 7,
+            end_line: 0,
+            end_character: 14,
+            severity: "warning".to_string(),
+            message: "unused function".to_string(),
+        };
+        workspace
+            .diagnostics
+            .insert(lib.clone(), vec![diagnostic.clone()]);
+        workspace
+            .diagnostics
+            .insert("/elsewhere/secret.rs".to_string(), vec![diagnostic]);
+
+        let (listing, _) = call(&workspace, "list_open_files", json!({}));
+        assert!(listing.contains("lib.rs") && !listing.contains("secret"));
+
+        assert_eq!(
+            call(&workspace, "read_file", json!({ "path": "lib.rs" })),
+            ("pub fn unsaved() {}\n".to_string(), false)
+        );
+        assert_eq!(
+            call(&workspace, "read_file", json!({ "path": "main.rs" })).0,
+            "fn main() {}\n"
+        );
+        let (escape, is_error) = call(
+            &workspace,
+            "read_file",
+            json!({ "path": "../../etc/passwd" }),
+        );
+        assert!(is_error, "{}", escape);
+
+        let (diagnostics, _) = call(&workspace, "get_diagnostics", json!({}));
+        assert!(diagnostics.contains("unused function") && !diagnostics.contains("secret"));
+        assert_eq!(
+            call(&workspace, "get_diagnostics", json!({ "path": "main.rs" })).0,
+            "No diagnostics."
+        );
+
+        let (matches, _) = call(&workspace, "search", json!({ "query": "saved" }));
+        let matches: Vec<Value> = serde_json::from_str(&matches).unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0]["line"], 1);
+
+        let edit = json!({
+            "start_line": 0, "start_character": 3, "end_line": 0, "end_character": 7,
+            "new_text": "start",
+        });
+        let (result, is_error) = call(
+            &workspace,
+            "apply_edits",
+            json!({ "files": [{ "path": "main.rs", "edits": [edit] }] }),
+        );
+        assert_eq!((result.as_str(), is_error), ("Applied", false));
+        let applied = workspace.applied.borrow();
+        assert_eq!(applied.len(), 1);
+        assert!(applied[0].uri.starts_with("file://") && applied[0].uri.ends_with("/main.rs"));
+        assert_eq!(applied[0].edits[0].new_text, "start");
+        drop(applied);
+
+        let (_, is_error) = call(
+            &workspace,
+            "apply_edits",
+            json!({ "files": [{ "path": "/etc/hosts", "edits": [edit] }] }),
+        );
+        assert!(is_error);
+        assert_eq!(workspace.applied.borrow().len(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn serves_agents_over_the_socket() {
+        use std::io::{BufRead, BufReader, Write};
+        use std::os::unix::net::UnixStream;
+
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        std::fs::write(root.join("a.txt"), "old\n").unwrap();
+        let socket = root.join("mcp.sock");
+        let server = McpServer::start_at(&root, socket.clone()).unwrap();
+        assert!(McpServer::start_at(&root, socket.clone()).is_err());
+
+        let stream = UnixStream::connect(&socket).unwrap();
+        let mut writer = stream.try_clone().unwrap();
+        let mut reader = BufReader::new(stream);
+        let request = json!({
+            "jsonrpc": "2.0", "id": 1, "method": "tools/call",
+            "params": { "name": "apply_edits", "arguments": { "files": [{
+                "path": "a.txt",
+                "edits": [{ "start_line": 0, "start_character": 0, "end_line": 0,
+                            "end_character": 3, "new_text": "new" }],
+            }] } },
+        });
+        writeln!(writer, "{}", request).unwrap();
+
+        // The frontend answers on its own loop.
+        let pending = loop {
+            if let Some(request) = server.next_request() {
+                break request;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        };
+        let HostRequestKind::ApplyEdits { files } = &pending.kind else {
+            panic!("expected edits, got {:?}", pending.kind);
+        };
+        assert_eq!(files[0].edits[0].new_text, "new");
+        pending.respond(Ok(json!("Applied 1 edit")));
+
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        let response: Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(response["result"]["content"][0]["text"], "Applied 1 edit");
+
+        drop(server);
+        assert!(!socket.exists());
+        line.clear();
+        assert_eq!(reader.read_line(&mut line).unwrap(), 0);
+    }
+}
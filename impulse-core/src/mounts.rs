@@ -0,0 +1,265 @@
+//! Mount point awareness for the workspace walkers and watchers. Network
+//! mounts (NFS, SMB, SSHFS, ...) under a workspace are skipped unless the
+//! `scan_network_mounts` setting is on, and pseudo file systems such as
+//! `/proc` always are, so opening `/` or a home folder with a share
+//! mounted in it doesn't crawl the network.
+//!
+//! The mount table comes from `/proc/self/mounts` on Linux and the output of
+//! `mount` on macOS, cached for a few seconds.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+
+/// How long a read mount table is reused.
+const MOUNT_TABLE_TTL: Duration = Duration::from_secs(10);
+
+static FOLLOW_SYMLINKS: AtomicBool = AtomicBool::new(false);
+static SCAN_NETWORK_MOUNTS: AtomicBool = AtomicBool::new(false);
+
+/// Apply the `follow_symlinks` and `scan_network_mounts` settings to the
+/// search and index walkers and the file watchers. Content indexes are
+/// dropped so they rebuild with the new rules.
+pub fn set_walk_options(follow_symlinks: bool, scan_network_mounts: bool) {
+    let old_follow = FOLLOW_SYMLINKS.swap(follow_symlinks, Ordering::Relaxed);
+    let old_scan = SCAN_NETWORK_MOUNTS.swap(scan_network_mounts, Ordering::Relaxed);
+    if old_follow != follow_symlinks || old_scan != scan_network_mounts {
+        crate::search_index::clear_indexes();
+    }
+}
+
+/// Whether walkers and watchers descend into symlinked directories.
+pub fn follow_symlinks() -> bool {
+    FOLLOW_SYMLINKS.load(Ordering::Relaxed)
+}
+
+/// Whether walkers and watchers descend into network mounts.
+pub fn scan_network_mounts() -> bool {
+    SCAN_NETWORK_MOUNTS.load(Ordering::Relaxed)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Mount {
+    path: PathBuf,
+    fs_type: String,
+}
+
+/// Whether `fs_type` is a network file system.
+pub fn is_network_fs(fs_type: &str) -> bool {
+    let fs_type = fs_type.strip_prefix("fuse.").unwrap_or(fs_type);
+    matches!(
+        fs_type,
+        "nfs"
+            | "nfs4"
+            | "cifs"
+            | "smb3"
+            | "smbfs"
+            | "afpfs"
+            | "webdav"
+            | "davfs"
+            | "davfs2"
+            | "sshfs"
+            | "rclone"
+            | "9p"
+            | "afs"
+            | "ceph"
+            | "glusterfs"
+            | "lustre"
+            | "gpfs"
+    )
+}
+
+/// Whether `fs_type` is a kernel pseudo file system with nothing to edit.
+fn is_pseudo_fs(fs_type: &str) -> bool {
+    matches!(
+        fs_type,
+        "proc"
+            | "sysfs"
+            | "devtmpfs"
+            | "devpts"
+            | "devfs"
+            | "cgroup"
+            | "cgroup2"
+            | "debugfs"
+            | "tracefs"
+            | "securityfs"
+            | "pstore"
+            | "bpf"
+            | "autofs"
+            | "fusectl"
+            | "configfs"
+            | "mqueue"
+            | "hugetlbfs"
+            | "binfmt_misc"
+    )
+}
+
+/// Mount points walkers and watchers don't descend into: pseudo file
+/// systems, and network mounts unless `scan_network_mounts` is on.
+pub fn skipped_mount_points() -> HashSet<PathBuf> {
+    let scan_network = scan_network_mounts();
+    mount_table()
+        .iter()
+        .filter(|m| is_pseudo_fs(&m.fs_type) || (!scan_network && is_network_fs(&m.fs_type)))
+        .map(|m| m.path.clone())
+        .collect()
+}
+
+/// Whether `path` (a directory under a workspace) is on a skipped mount.
+/// Symlinked directories are resolved first.
+pub fn is_skipped(path: &Path, is_symlink: bool, skipped: &HashSet<PathBuf>) -> bool {
+    if skipped.is_empty() {
+        return false;
+    }
+    if is_symlink {
+        return std::fs::canonicalize(path)
+            .is_ok_and(|real| real.ancestors().any(|ancestor| skipped.contains(ancestor)));
+    }
+    skipped.contains(path)
+}
+
+/// The last mount table read and when.
+type CachedMountTable = Option<(Instant, Arc<Vec<Mount>>)>;
+
+fn mount_table() -> Arc<Vec<Mount>> {
+    static CACHE: OnceLock<Mutex<CachedMountTable>> = OnceLock::new();
+    let mut cache = CACHE.get_or_init(|| Mutex::new(None)).lock();
+    if let Some((read_at, table)) = cache.as_ref() {
+        if read_at.elapsed() < MOUNT_TABLE_TTL {
+            return table.clone();
+        }
+    }
+    let table = Arc::new(read_mount_table());
+    *cache = Some((Instant::now(), table.clone()));
+    table
+}
+
+#[cfg(target_os = "linux")]
+fn read_mount_table() -> Vec<Mount> {
+    std::fs::read_to_string("/proc/self/mounts")
+        .map(|contents| parse_proc_mounts(&contents))
+        .unwrap_or_default()
+}
+
+#[cfg(target_os = "macos")]
+fn read_mount_table() -> Vec<Mount> {
+    std::process::Command::new("/sbin/mount")
+        .output()
+        .ok()
+        .filter(|out| out.status.success())
+        .map(|out| parse_bsd_mount_output(&String::from_utf8_lossy(&out.stdout)))
+        .unwrap_or_default()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn read_mount_table() -> Vec<Mount> {
+    Vec::new()
+}
+
+/// Parse `/proc/self/mounts`: `device mount-point type options 0 0`, with
+/// spaces and other special characters in paths escaped as octal (`\040`).
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_proc_mounts(contents: &str) -> Vec<Mount> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let _device = fields.next()?;
+            let path = unescape_octal(fields.next()?);
+            let fs_type = fields.next()?.to_string();
+            Some(Mount {
+                path: PathBuf::from(path),
+                fs_type,
+            })
+        })
+        .collect()
+}
+
+fn unescape_octal(field: &str) -> String {
+    let bytes = field.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let digits = bytes.get(i + 1..i + 4).unwrap_or_default();
+        if bytes[i] == b'\\'
+            && digits.len() == 3
+            && digits.iter().all(|d| (b'0'..=b'7').contains(d))
+        {
+            out.push(
+                digits
+                    .iter()
+                    .fold(0u8, |code, d| code.wrapping_mul(8) + (d - b'0')),
+            );
+            i += 4;
+            continue;
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+/// Parse the output of BSD `mount`: `device on /mount/point (type, options)`.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_bsd_mount_output(output: &str) -> Vec<Mount> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (_, rest) = line.split_once(" on ")?;
+            let (path, details) = rest.rsplit_once(" (")?;
+            let fs_type = details.split([',', ')']).next()?.trim().to_string();
+            Some(Mount {
+                path: PathBuf::from(path),
+                fs_type,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_mount_tables_and_classifies_file_systems() {
+        let proc = "sysfs /sys sysfs rw 0 0\n\
+                    server:/export /home/me/my\\040share nfs4 rw,relatime 0 0\n\
+                    /dev/sda1 / ext4 rw 0 0\n";
+        let mounts = parse_proc_mounts(proc);
+        assert_eq!(mounts.len(), 3);
+        assert_eq!(mounts[1].path, PathBuf::from("/home/me/my share"));
+        assert_eq!(mounts[1].fs_type, "nfs4");
+
+        let bsd = "/dev/disk3s1s1 on / (apfs, sealed, local, read-only, journaled)\n\
+                   //me@nas/media on /Volumes/media (smbfs, nodev, nosuid, mounted by me)\n";
+        let mounts = parse_bsd_mount_output(bsd);
+        assert_eq!(
+            mounts,
+            vec![
+                Mount {
+                    path: PathBuf::from("/"),
+                    fs_type: "apfs".to_string()
+                },
+                Mount {
+                    path: PathBuf::from("/Volumes/media"),
+                    fs_type: "smbfs".to_string()
+                },
+            ]
+        );
+
+        assert!(is_network_fs("nfs4"));
+        assert!(is_network_fs("fuse.sshfs"));
+        assert!(!is_network_fs("ext4"));
+        assert!(is_pseudo_fs("proc"));
+        assert!(!is_pseudo_fs("tmpfs"));
+
+        let skipped: HashSet<PathBuf> = [PathBuf::from("/mnt/nfs")].into_iter().collect();
+        assert!(is_skipped(Path::new("/mnt/nfs"), false, &skipped));
+        assert!(!is_skipped(Path::new("/mnt/nfs/sub"), false, &skipped));
+        assert!(!is_skipped(Path::new("/mnt/nfs"), false, &HashSet::new()));
+    }
+}
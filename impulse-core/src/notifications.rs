@@ -0,0 +1,227 @@
+//! Notification history. Toasts vanish after a few seconds, so anything the
+//! user may need later (install results, save failures, language server
+//! errors) is also recorded here; frontends show the unread count on a bell
+//! in the status bar and list the history in a panel.
+//!
+//! The center is process-wide and bounded. A notification identical to the
+//! newest unread one bumps its count instead of adding another entry, so a
+//! crashing server doesn't fill the history with copies.
+
+use std::collections::VecDeque;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+/// Entries kept before the oldest are dropped.
+pub const MAX_NOTIFICATIONS: usize = 100;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+/// A button on a notification that runs a command from the core registry
+/// (see `commands`).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotificationAction {
+    pub label: String,
+    pub command: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Notification {
+    #[serde(default)]
+    pub id: u64,
+    pub severity: Severity,
+    pub title: String,
+    #[serde(default)]
+    pub message: String,
+    /// What reported it, e.g. "lsp" or "save".
+    #[serde(default)]
+    pub source: String,
+    #[serde(default)]
+    pub actions: Vec<NotificationAction>,
+    #[serde(default)]
+    pub timestamp_ms: u64,
+    /// How many times it was reported while unread.
+    #[serde(default)]
+    pub count: u32,
+    #[serde(default)]
+    pub read: bool,
+}
+
+impl Notification {
+    pub fn new(severity: Severity, title: impl Into<String>) -> Self {
+        Self {
+            id: 0,
+            severity,
+            title: title.into(),
+            message: String::new(),
+            source: String::new(),
+            actions: Vec::new(),
+            timestamp_ms: 0,
+            count: 1,
+            read: false,
+        }
+    }
+
+    pub fn message(mut self, message: impl Into<String>) -> Self {
+        self.message = message.into();
+        self
+    }
+
+    pub fn source(mut self, source: impl Into<String>) -> Self {
+        self.source = source.into();
+        self
+    }
+
+    pub fn action(mut self, label: impl Into<String>, command: impl Into<String>) -> Self {
+        self.actions.push(NotificationAction {
+            label: label.into(),
+            command: command.into(),
+        });
+        self
+    }
+
+    fn same_as(&self, other: &Notification) -> bool {
+        self.severity == other.severity
+            && self.title == other.title
+            && self.message == other.message
+            && self.source == other.source
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct NotificationCenter {
+    /// Oldest first.
+    entries: VecDeque<Notification>,
+    next_id: u64,
+}
+
+impl NotificationCenter {
+    /// Record `notification` and return its id.
+    pub fn push(&mut self, mut notification: Notification) -> u64 {
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        if let Some(last) = self.entries.back_mut() {
+            if !last.read && last.same_as(&notification) {
+                last.count += 1;
+                last.timestamp_ms = timestamp_ms;
+                return last.id;
+            }
+        }
+        self.next_id += 1;
+        notification.id = self.next_id;
+        notification.timestamp_ms = timestamp_ms;
+        notification.count = 1;
+        notification.read = false;
+        self.entries.push_back(notification);
+        while self.entries.len() > MAX_NOTIFICATIONS {
+            self.entries.pop_front();
+        }
+        self.next_id
+    }
+
+    /// The history, newest first.
+    pub fn list(&self) -> Vec<Notification> {
+        self.entries.iter().rev().cloned().collect()
+    }
+
+    pub fn unread_count(&self) -> usize {
+        self.entries.iter().filter(|n| !n.read).count()
+    }
+
+    pub fn mark_all_read(&mut self) {
+        for n in &mut self.entries {
+            n.read = true;
+        }
+    }
+
+    pub fn dismiss(&mut self, id: u64) {
+        self.entries.retain(|n| n.id != id);
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+static CENTER: Mutex<Option<NotificationCenter>> = Mutex::new(None);
+
+fn with_center<T>(f: impl FnOnce(&mut NotificationCenter) -> T) -> T {
+    f(CENTER
+        .lock()
+        .get_or_insert_with(NotificationCenter::default))
+}
+
+/// Record a notification in the process-wide history and return its id.
+pub fn push(notification: Notification) -> u64 {
+    with_center(|c| c.push(notification))
+}
+
+pub fn list() -> Vec<Notification> {
+    with_center(|c| c.list())
+}
+
+pub fn unread_count() -> usize {
+    with_center(|c| c.unread_count())
+}
+
+pub fn mark_all_read() {
+    with_center(|c| c.mark_all_read())
+}
+
+pub fn dismiss(id: u64) {
+    with_center(|c| c.dismiss(id))
+}
+
+pub fn clear() {
+    with_center(|c| c.clear())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeats_collapse_until_read() {
+        let mut center = NotificationCenter::default();
+        let failed = || Notification::new(Severity::Error, "Server crashed").source("lsp");
+        let id = center.push(failed());
+        assert_eq!(center.push(failed()), id);
+        assert_eq!(center.list().len(), 1);
+        assert_eq!(center.list()[0].count, 2);
+        assert_eq!(center.unread_count(), 1);
+
+        center.mark_all_read();
+        assert_eq!(center.unread_count(), 0);
+        // Once read, a repeat is news again.
+        let again = center.push(failed());
+        assert_ne!(again, id);
+        assert_eq!(center.list()[0].id, again);
+        assert_eq!(center.unread_count(), 1);
+
+        center.dismiss(id);
+        assert_eq!(center.list().len(), 1);
+        center.clear();
+        assert!(center.list().is_empty());
+    }
+
+    #[test]
+    fn history_is_bounded_and_newest_first() {
+        let mut center = NotificationCenter::default();
+        for i in 0..MAX_NOTIFICATIONS + 5 {
+            center.push(Notification::new(Severity::Info, format!("n{}", i)));
+        }
+        let list = center.list();
+        assert_eq!(list.len(), MAX_NOTIFICATIONS);
+        assert_eq!(list[0].title, format!("n{}", MAX_NOTIFICATIONS + 4));
+        assert_eq!(list.last().unwrap().title, "n5");
+    }
+}
@@ -0,0 +1,126 @@
+//! Paste protection for terminals: text that would run more than the user
+//! expects (several lines outside bracketed paste, or a `sudo` command) is
+//! shown in an editable preview before it reaches the shell, when the
+//! `terminal_confirm_paste` setting is on.
+
+use serde::Serialize;
+
+const PASTE_START: &str = "\x1b[200~";
+const PASTE_END: &str = "\x1b[201~";
+
+/// Why a paste is confirmed first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PasteRisk {
+    /// Several lines, each run as soon as it arrives because the program
+    /// hasn't turned on bracketed paste.
+    MultiLine,
+    /// Runs a command with `sudo`.
+    Sudo,
+}
+
+/// The confirmation to show before pasting.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PasteConfirmation {
+    pub risks: Vec<PasteRisk>,
+    /// Explanation for the dialog, e.g. "The text has 3 lines. …".
+    pub message: String,
+}
+
+/// The confirmation pasting `text` needs, or `None` when it can be pasted
+/// right away. `bracketed` is whether the terminal has bracketed paste on.
+/// Trailing line breaks don't count as lines; frontends drop them or they
+/// end the single command.
+pub fn check(text: &str, bracketed: bool) -> Option<PasteConfirmation> {
+    let mut risks = Vec::new();
+    let mut sentences = Vec::new();
+
+    let lines = text
+        .trim_end_matches(['\n', '\r'])
+        .split(['\n', '\r'])
+        .filter(|line| !line.is_empty())
+        .count();
+    if lines > 1 && !bracketed {
+        risks.push(PasteRisk::MultiLine);
+        sentences.push(format!(
+            "The text has {lines} lines, and each one runs as a command as soon as it is pasted."
+        ));
+    }
+    if runs_sudo(text) {
+        sentences.push(if risks.is_empty() {
+            "The text runs a command with sudo.".to_string()
+        } else {
+            "It also runs a command with sudo.".to_string()
+        });
+        risks.push(PasteRisk::Sudo);
+    }
+
+    (!risks.is_empty()).then(|| PasteConfirmation {
+        risks,
+        message: sentences.join(" "),
+    })
+}
+
+/// Whether any command in `text` is `sudo`.
+fn runs_sudo(text: &str) -> bool {
+    text.split(|c: char| c.is_whitespace() || matches!(c, ';' | '|' | '&' | '(' | ')' | '`'))
+        .any(|word| word == "sudo" || word.ends_with("/sudo"))
+}
+
+/// The bytes to write for a paste of `text`: wrapped in bracketed paste
+/// markers when the terminal asked for them, with any markers inside the
+/// text removed so it can't end the paste early and run commands.
+pub fn payload(text: &str, bracketed: bool) -> String {
+    if !bracketed {
+        return text.to_string();
+    }
+    // Removing one marker can join the text around it into another, so
+    // strip until none are left.
+    let mut sanitized = text.to_string();
+    while sanitized.contains(PASTE_END) || sanitized.contains(PASTE_START) {
+        sanitized = sanitized.replace(PASTE_END, "").replace(PASTE_START, "");
+    }
+    format!("{PASTE_START}{sanitized}{PASTE_END}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn confirms_multi_line_and_sudo_pastes() {
+        assert!(check("ls -la", false).is_none());
+        assert!(check("ls -la\n", false).is_none());
+        assert!(check("pseudo-random", false).is_none());
+
+        let lines = check("cd /tmp\r\nrm -rf build\n", false).unwrap();
+        assert_eq!(lines.risks, vec![PasteRisk::MultiLine]);
+        assert!(lines.message.starts_with("The text has 2 lines"));
+        // With bracketed paste the shell waits for Enter.
+        assert!(check("cd /tmp\nrm -rf build\n", true).is_none());
+
+        let sudo = check("make && sudo make install", true).unwrap();
+        assert_eq!(sudo.risks, vec![PasteRisk::Sudo]);
+        assert_eq!(sudo.message, "The text runs a command with sudo.");
+        let both = check("apt update\n/usr/bin/sudo apt upgrade", false).unwrap();
+        assert_eq!(both.risks, vec![PasteRisk::MultiLine, PasteRisk::Sudo]);
+    }
+
+    #[test]
+    fn bracketed_payload_strips_embedded_markers() {
+        assert_eq!(payload("echo hi", false), "echo hi");
+        assert_eq!(
+            payload("echo hi\x1b[201~rm -rf ~\n", true),
+            "\x1b[200~echo hirm -rf ~\n\x1b[201~"
+        );
+        // Nested markers that form a new one once the inner one is gone.
+        assert_eq!(
+            payload("a\x1b[20\x1b[201~1~b", true),
+            "\x1b[200~ab\x1b[201~"
+        );
+        assert_eq!(
+            payload("a\x1b[20\x1b[20\x1b[200~0~1~b", true),
+            "\x1b[200~ab\x1b[201~"
+        );
+    }
+}
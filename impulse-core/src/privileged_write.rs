@@ -0,0 +1,184 @@
+//! Saving files the user can't write ("Retry as Administrator" after a
+//! permission error). On Linux the content is piped to `tee` run through
+//! polkit's `pkexec`; on macOS it is staged in a temporary file and copied
+//! over the target by `osascript` with administrator privileges, which
+//! shows the system authorization prompt.
+//!
+//! The target is overwritten in place, so an existing file keeps its owner
+//! and permissions.
+
+use std::path::Path;
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+use std::process::{Command, Stdio};
+
+/// Whether a failed write can be retried here with [`write`].
+pub fn is_available() -> bool {
+    if cfg!(target_os = "macos") {
+        Path::new("/usr/bin/osascript").is_file()
+    } else if cfg!(target_os = "linux") {
+        crate::lsp::find_command_in_path("pkexec").is_some()
+    } else {
+        false
+    }
+}
+
+/// Whether `error` is one [`write`] may get past.
+pub fn is_permission_error(error: &std::io::Error) -> bool {
+    error.kind() == std::io::ErrorKind::PermissionDenied
+}
+
+/// Write `content` to `path` as the administrator, after the system asks
+/// the user to authenticate.
+#[cfg(target_os = "linux")]
+pub fn write(path: &Path, content: &[u8]) -> Result<(), String> {
+    let pkexec = crate::lsp::find_command_in_path("pkexec")
+        .ok_or_else(|| "pkexec (polkit) isn't installed".to_string())?;
+    let tee = system_tee().ok_or_else(|| "tee isn't installed in /usr/bin or /bin".to_string())?;
+    let mut child = Command::new(pkexec)
+        .arg(tee)
+        .arg("--")
+        .arg(path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run pkexec: {e}"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        std::io::Write::write_all(&mut stdin, content)
+            .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("Failed to run pkexec: {e}"))?;
+    match output.status.code() {
+        Some(0) => Ok(()),
+        // pkexec's exit codes for a dismissed or refused authorization.
+        Some(126) => Err("Authorization was cancelled".to_string()),
+        Some(127) => Err("Not authorized to write the file".to_string()),
+        _ => Err(format!(
+            "Failed to write {}: {}",
+            path.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )),
+    }
+}
+
+#[cfg(target_os = "macos")]
+pub fn write(path: &Path, content: &[u8]) -> Result<(), String> {
+    let staged = stage(content).map_err(|e| format!("Failed to stage {}: {e}", path.display()))?;
+    let output = Command::new("/usr/bin/osascript")
+        .arg("-e")
+        .arg(copy_script(&staged, path))
+        .stdin(Stdio::null())
+        .output();
+    let _ = std::fs::remove_file(&staged);
+    let output = output.map_err(|e| format!("Failed to run osascript: {e}"))?;
+    if output.status.success() {
+        return Ok(());
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    // AppleScript error -128 is "User canceled."
+    if stderr.contains("(-128)") {
+        return Err("Authorization was cancelled".to_string());
+    }
+    Err(format!(
+        "Failed to write {}: {}",
+        path.display(),
+        stderr.trim()
+    ))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub fn write(_path: &Path, _content: &[u8]) -> Result<(), String> {
+    Err("Writing as administrator isn't supported on this platform".to_string())
+}
+
+/// `tee` from the system directories, never the user's PATH: it runs as
+/// root, so an earlier `tee` on PATH would get root.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn system_tee() -> Option<&'static str> {
+    ["/usr/bin/tee", "/bin/tee"]
+        .into_iter()
+        .find(|tee| Path::new(tee).is_file())
+}
+
+/// Write `content` to a new owner-only file with an unpredictable name in
+/// the shared temp directory, for the administrator copy to read.
+#[cfg(unix)]
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn stage(content: &[u8]) -> std::io::Result<std::path::PathBuf> {
+    use std::io::Write;
+    use std::os::unix::fs::OpenOptionsExt;
+
+    let staged = std::env::temp_dir().join(format!(
+        "impulse-admin-save-{}",
+        uuid::Uuid::new_v4().as_simple()
+    ));
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(0o600)
+        .open(&staged)?;
+    if let Err(e) = file.write_all(content).and_then(|()| file.sync_all()) {
+        let _ = std::fs::remove_file(&staged);
+        return Err(e);
+    }
+    Ok(staged)
+}
+
+/// The AppleScript that copies `staged` over `target` as the administrator,
+/// with `cat` so the target keeps its owner and permissions.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn copy_script(staged: &Path, target: &Path) -> String {
+    format!(
+        "do shell script \"/bin/cat \" & quoted form of {} & \" > \" & quoted form of {} \
+         with administrator privileges",
+        applescript_string(&staged.to_string_lossy()),
+        applescript_string(&target.to_string_lossy()),
+    )
+}
+
+/// `s` as an AppleScript string literal.
+fn applescript_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quotes_paths_for_the_macos_helper() {
+        assert_eq!(
+            applescript_string(r#"/etc/a "b"\c"#),
+            r#""/etc/a \"b\"\\c""#
+        );
+        assert_eq!(
+            copy_script(Path::new("/tmp/x"), Path::new("/etc/hosts")),
+            "do shell script \"/bin/cat \" & quoted form of \"/tmp/x\" & \" > \" & \
+             quoted form of \"/etc/hosts\" with administrator privileges"
+        );
+        assert!(is_permission_error(&std::io::Error::from(
+            std::io::ErrorKind::PermissionDenied
+        )));
+        assert!(!is_permission_error(&std::io::Error::from(
+            std::io::ErrorKind::NotFound
+        )));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn stages_content_privately_and_runs_the_system_tee() {
+        use std::os::unix::fs::PermissionsExt;
+        let staged = stage(b"content").unwrap();
+        let mode = std::fs::metadata(&staged).unwrap().permissions().mode();
+        let content = std::fs::read_to_string(&staged).unwrap();
+        std::fs::remove_file(&staged).unwrap();
+        assert_eq!(mode & 0o777, 0o600);
+        assert_eq!(content, "content");
+
+        if let Some(tee) = system_tee() {
+            assert!(tee == "/usr/bin/tee" || tee == "/bin/tee");
+        }
+    }
+}
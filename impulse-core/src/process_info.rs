@@ -0,0 +1,448 @@
+//! Process inspection for terminal tabs: what a terminal's shell is running
+//! (its foreground job and every other descendant) with a CPU time and
+//! memory snapshot of each, for tab tooltips and close warnings.
+//!
+//! The process table comes from `/proc` on Linux and the output of `ps` on
+//! macOS. Reading it scans every process, so callers read one
+//! [`ProcessTable`] and look up all their shells in it.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+
+/// Linux reports CPU times in clock ticks, which are 1/100 s on every
+/// architecture Impulse runs on.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+const CLOCK_TICKS_PER_SECOND: f64 = 100.0;
+
+/// A snapshot of one process.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub ppid: u32,
+    /// Executable name, e.g. `node`.
+    pub name: String,
+    /// Full command line.
+    pub command: String,
+    /// User plus system CPU time used so far.
+    pub cpu_seconds: f64,
+    /// Resident memory.
+    pub memory_bytes: u64,
+}
+
+impl ProcessInfo {
+    /// One-line description, e.g. `node (PID 4242) · CPU 3.2 s · 120 MB`.
+    pub fn summary(&self) -> String {
+        format!(
+            "{} (PID {}) · CPU {} · {}",
+            self.name,
+            self.pid,
+            format_cpu(self.cpu_seconds),
+            format_memory(self.memory_bytes)
+        )
+    }
+}
+
+/// What a terminal's shell is running.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TerminalProcesses {
+    /// The job in the foreground of the terminal, when it isn't the shell.
+    pub foreground: Option<ProcessInfo>,
+    /// Every descendant of the shell, foreground job included, parents
+    /// before their children.
+    pub children: Vec<ProcessInfo>,
+}
+
+impl TerminalProcesses {
+    /// Whether the shell has a running child that closing the terminal
+    /// would kill (a dev server, an editor, a background job).
+    pub fn is_busy(&self) -> bool {
+        !self.children.is_empty()
+    }
+
+    /// How many of the shell's descendants closing the terminal should warn
+    /// about: all of them except `ignored` programs (see
+    /// [`crate::close_risk::is_ignored_command`]) and their own children.
+    pub fn close_risk_count(&self, ignored: &[String]) -> usize {
+        let mut skipped = HashSet::new();
+        self.children
+            .iter()
+            .filter(|process| {
+                if skipped.contains(&process.ppid)
+                    || crate::close_risk::is_ignored_command(&process.name, ignored)
+                {
+                    skipped.insert(process.pid);
+                    return false;
+                }
+                true
+            })
+            .count()
+    }
+
+    /// Tooltip lines describing the foreground job and how many other
+    /// processes run under the shell. `None` while the shell is idle.
+    pub fn describe(&self) -> Option<String> {
+        let main = self.foreground.as_ref().or(self.children.first())?;
+        let mut text = main.summary();
+        let others = self.children.len() - 1;
+        if others == 1 {
+            text.push_str("\n1 other process");
+        } else if others > 1 {
+            text.push_str(&format!("\n{others} other processes"));
+        }
+        Some(text)
+    }
+}
+
+/// One row of the process table.
+#[derive(Debug, Clone, PartialEq)]
+struct ProcessRow {
+    info: ProcessInfo,
+    pgid: u32,
+    /// Foreground process group of the process's controlling terminal.
+    tpgid: i64,
+    /// Memory and command line still have to be read (Linux).
+    needs_details: bool,
+}
+
+/// Every process on the system, read once.
+#[derive(Debug, Default)]
+pub struct ProcessTable {
+    rows: Vec<ProcessRow>,
+}
+
+impl ProcessTable {
+    /// Read the current process table. Empty on platforms without one.
+    pub fn read() -> Self {
+        ProcessTable {
+            rows: read_process_rows(),
+        }
+    }
+
+    /// What the shell with PID `shell_pid` is running.
+    pub fn terminal(&self, shell_pid: u32) -> TerminalProcesses {
+        let Some(shell) = self.rows.iter().find(|r| r.info.pid == shell_pid) else {
+            return TerminalProcesses::default();
+        };
+
+        let mut children_of: HashMap<u32, Vec<&ProcessRow>> = HashMap::new();
+        for row in &self.rows {
+            children_of.entry(row.info.ppid).or_default().push(row);
+        }
+        let mut descendants: Vec<&ProcessRow> = Vec::new();
+        let mut queue = vec![shell_pid];
+        while let Some(pid) = queue.pop() {
+            if let Some(children) = children_of.get(&pid) {
+                for child in children {
+                    // PID reuse can't loop back to the shell.
+                    if child.info.pid != shell_pid {
+                        descendants.push(child);
+                        queue.push(child.info.pid);
+                    }
+                }
+            }
+        }
+
+        let foreground = (shell.tpgid > 0 && shell.tpgid != shell.pgid as i64)
+            .then(|| {
+                let group = shell.tpgid as u32;
+                descendants
+                    .iter()
+                    .find(|r| r.info.pid == group)
+                    .or_else(|| descendants.iter().find(|r| r.pgid == group))
+            })
+            .flatten()
+            .map(|r| with_details(r));
+
+        TerminalProcesses {
+            foreground,
+            children: descendants.iter().map(|r| with_details(r)).collect(),
+        }
+    }
+}
+
+/// What the shell with PID `shell_pid` is running, reading a fresh table.
+pub fn terminal_processes(shell_pid: u32) -> TerminalProcesses {
+    ProcessTable::read().terminal(shell_pid)
+}
+
+fn format_cpu(seconds: f64) -> String {
+    if seconds < 60.0 {
+        format!("{seconds:.1} s")
+    } else if seconds < 3600.0 {
+        format!("{}m {:02}s", seconds as u64 / 60, seconds as u64 % 60)
+    } else {
+        format!(
+            "{}h {:02}m",
+            seconds as u64 / 3600,
+            seconds as u64 % 3600 / 60
+        )
+    }
+}
+
+fn format_memory(bytes: u64) -> String {
+    const MB: f64 = 1024.0 * 1024.0;
+    let mb = bytes as f64 / MB;
+    if mb >= 1024.0 {
+        format!("{:.1} GB", mb / 1024.0)
+    } else if mb >= 10.0 {
+        format!("{mb:.0} MB")
+    } else {
+        format!("{mb:.1} MB")
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn read_process_rows() -> Vec<ProcessRow> {
+    let Ok(entries) = std::fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let pid: u32 = entry.file_name().to_str()?.parse().ok()?;
+            let stat = std::fs::read_to_string(entry.path().join("stat")).ok()?;
+            parse_proc_stat(pid, &stat)
+        })
+        .collect()
+}
+
+#[cfg(target_os = "macos")]
+fn read_process_rows() -> Vec<ProcessRow> {
+    std::process::Command::new("/bin/ps")
+        .args(["-axo", "pid=,ppid=,pgid=,tpgid=,rss=,time=,args="])
+        .output()
+        .ok()
+        .filter(|out| out.status.success())
+        .map(|out| parse_ps_output(&String::from_utf8_lossy(&out.stdout)))
+        .unwrap_or_default()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn read_process_rows() -> Vec<ProcessRow> {
+    Vec::new()
+}
+
+/// `/proc/<pid>/stat` has no memory in bytes or full command line, so
+/// Linux fills them in for the processes a caller asked about.
+#[cfg(target_os = "linux")]
+fn with_details(row: &ProcessRow) -> ProcessInfo {
+    let mut info = row.info.clone();
+    if !row.needs_details {
+        return info;
+    }
+    let dir = std::path::Path::new("/proc").join(info.pid.to_string());
+    if let Some(rss) = std::fs::read_to_string(dir.join("status"))
+        .ok()
+        .and_then(|status| parse_vm_rss(&status))
+    {
+        info.memory_bytes = rss;
+    }
+    if let Ok(cmdline) = std::fs::read(dir.join("cmdline")) {
+        let command = parse_cmdline(&cmdline);
+        if !command.is_empty() {
+            info.command = command;
+        }
+    }
+    info
+}
+
+#[cfg(not(target_os = "linux"))]
+fn with_details(row: &ProcessRow) -> ProcessInfo {
+    row.info.clone()
+}
+
+/// Parse `/proc/<pid>/stat`: `pid (comm) state ppid pgrp session tty_nr
+/// tpgid flags minflt cminflt majflt cmajflt utime stime ...`. The name can
+/// hold spaces and parentheses, so fields are counted from the last `)`.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_proc_stat(pid: u32, stat: &str) -> Option<ProcessRow> {
+    let open = stat.find('(')?;
+    let close = stat.rfind(')')?;
+    let name = stat.get(open + 1..close)?.to_string();
+    let fields: Vec<&str> = stat.get(close + 1..)?.split_whitespace().collect();
+    let ticks = |i: usize| fields.get(i)?.parse::<u64>().ok();
+    let cpu_ticks = ticks(11)? + ticks(12)?;
+    Some(ProcessRow {
+        info: ProcessInfo {
+            pid,
+            ppid: fields.get(1)?.parse().ok()?,
+            command: name.clone(),
+            name,
+            cpu_seconds: cpu_ticks as f64 / CLOCK_TICKS_PER_SECOND,
+            memory_bytes: 0,
+        },
+        pgid: fields.get(2)?.parse().ok()?,
+        tpgid: fields.get(5)?.parse().ok()?,
+        needs_details: true,
+    })
+}
+
+/// Resident memory from `/proc/<pid>/status` (`VmRSS:  1234 kB`).
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_vm_rss(status: &str) -> Option<u64> {
+    let line = status.lines().find(|l| l.starts_with("VmRSS:"))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb * 1024)
+}
+
+/// `/proc/<pid>/cmdline` separates arguments with NULs.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_cmdline(cmdline: &[u8]) -> String {
+    String::from_utf8_lossy(cmdline)
+        .split('\0')
+        .filter(|arg| !arg.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Parse `ps -axo pid=,ppid=,pgid=,tpgid=,rss=,time=,args=`. RSS is in
+/// kilobytes and the command line is the rest of the line.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_ps_output(output: &str) -> Vec<ProcessRow> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut rest = line.trim_start();
+            let mut next = || {
+                let (field, tail) = rest.split_once(char::is_whitespace)?;
+                rest = tail.trim_start();
+                Some(field)
+            };
+            let pid = next()?.parse().ok()?;
+            let ppid = next()?.parse().ok()?;
+            let pgid = next()?.parse().ok()?;
+            let tpgid = next()?.parse().ok()?;
+            let rss_kb: u64 = next()?.parse().ok()?;
+            let cpu_seconds = parse_ps_time(next()?)?;
+            let command = rest.trim_end().to_string();
+            let program = command.split_whitespace().next().unwrap_or("");
+            let name = program.rsplit('/').next().unwrap_or(program);
+            // Login shells show as `-zsh`.
+            let name = name.strip_prefix('-').unwrap_or(name).to_string();
+            Some(ProcessRow {
+                info: ProcessInfo {
+                    pid,
+                    ppid,
+                    name,
+                    command,
+                    cpu_seconds,
+                    memory_bytes: rss_kb * 1024,
+                },
+                pgid,
+                tpgid,
+                needs_details: false,
+            })
+        })
+        .collect()
+}
+
+/// Parse a `ps` CPU time: `[[dd-]hh:]mm:ss[.cc]`.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_ps_time(time: &str) -> Option<f64> {
+    let (days, clock) = match time.split_once('-') {
+        Some((days, clock)) => (days.parse::<f64>().ok()?, clock),
+        None => (0.0, time),
+    };
+    let mut seconds = 0.0;
+    for part in clock.split(':') {
+        seconds = seconds * 60.0 + part.parse::<f64>().ok()?;
+    }
+    Some(days * 86_400.0 + seconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(pid: u32, ppid: u32, pgid: u32, tpgid: i64, name: &str) -> ProcessRow {
+        ProcessRow {
+            info: ProcessInfo {
+                pid,
+                ppid,
+                name: name.to_string(),
+                command: name.to_string(),
+                cpu_seconds: 1.5,
+                memory_bytes: 120 * 1024 * 1024,
+            },
+            pgid,
+            tpgid,
+            needs_details: false,
+        }
+    }
+
+    #[test]
+    fn finds_the_foreground_job_and_all_descendants() {
+        let table = ProcessTable {
+            rows: vec![
+                row(1, 0, 1, -1, "init"),
+                row(100, 1, 100, 200, "zsh"),
+                row(150, 100, 150, 200, "sleep"),
+                row(200, 100, 200, 200, "npm"),
+                row(201, 200, 200, 200, "node"),
+                row(300, 1, 300, 300, "bash"),
+            ],
+        };
+
+        let busy = table.terminal(100);
+        assert_eq!(busy.foreground.as_ref().unwrap().name, "npm");
+        let mut pids: Vec<u32> = busy.children.iter().map(|p| p.pid).collect();
+        pids.sort_unstable();
+        assert_eq!(pids, vec![150, 200, 201]);
+        assert!(busy.is_busy());
+        let ignored = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+        assert_eq!(busy.close_risk_count(&[]), 3);
+        assert_eq!(busy.close_risk_count(&ignored(&["sleep"])), 2);
+        // Ignoring npm also ignores the node it started.
+        assert_eq!(busy.close_risk_count(&ignored(&["npm", "sleep"])), 0);
+        assert_eq!(
+            busy.describe().unwrap(),
+            "npm (PID 200) · CPU 1.5 s · 120 MB\n2 other processes"
+        );
+
+        let idle = table.terminal(300);
+        assert_eq!(idle, TerminalProcesses::default());
+        assert!(!idle.is_busy());
+        assert!(idle.describe().is_none());
+        assert!(!table.terminal(999).is_busy());
+    }
+
+    #[test]
+    fn parses_proc_files() {
+        let stat = "4242 (my (odd) app) S 100 4242 100 34816 4242 4194304 \
+                    1000 0 0 0 250 70 0 0 20 0 1 0 12345 1000000 300 ...";
+        let row = parse_proc_stat(4242, stat).unwrap();
+        assert_eq!(row.info.name, "my (odd) app");
+        assert_eq!((row.info.ppid, row.pgid, row.tpgid), (100, 4242, 4242));
+        assert_eq!(row.info.cpu_seconds, 3.2);
+        assert!(parse_proc_stat(1, "garbage").is_none());
+
+        let status = "Name:\tnode\nVmPeak:\t 200000 kB\nVmRSS:\t  122880 kB\n";
+        assert_eq!(parse_vm_rss(status), Some(122880 * 1024));
+        assert_eq!(
+            parse_cmdline(b"node\0server.js\0--port\x003000\0"),
+            "node server.js --port 3000"
+        );
+    }
+
+    #[test]
+    fn parses_ps_output() {
+        let output = "  100     1   100   200   4096   0:00.05 -zsh\n\
+                      \x20 200   100   200   200 122880   1:02.50 /usr/local/bin/node server.js --watch\n\
+                      \x20 300     1   300     0      0 1-02:00:00 /sbin/launchd\n";
+        let rows = parse_ps_output(output);
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0].info.name, "zsh");
+        assert_eq!(rows[1].info.name, "node");
+        assert_eq!(
+            rows[1].info.command,
+            "/usr/local/bin/node server.js --watch"
+        );
+        assert_eq!(rows[1].info.cpu_seconds, 62.5);
+        assert_eq!(rows[1].info.memory_bytes, 120 * 1024 * 1024);
+        assert_eq!(rows[2].info.cpu_seconds, 93_600.0);
+
+        assert_eq!(format_cpu(62.5), "1m 02s");
+        assert_eq!(format_memory(3 * 1024 * 1024 * 1024), "3.0 GB");
+    }
+}
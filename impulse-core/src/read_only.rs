@@ -0,0 +1,110 @@
+//! Files that open read-only: ones the user can't write, git's own
+//! internals, and generated code that would be overwritten on the next
+//! build. Frontends show a lock on the tab and let the user lift it.
+
+use std::path::Path;
+
+use serde::Serialize;
+
+/// How many lines from the top are searched for a generated-code marker.
+const GENERATED_MARKER_LINES: usize = 5;
+
+/// Markers tools put in the header of files they generate (Go's
+/// `Code generated … DO NOT EDIT.`, Cargo.lock's `@generated`, .NET's
+/// `<auto-generated>`).
+const GENERATED_MARKERS: &[&str] = &["@generated", "DO NOT EDIT", "<auto-generated"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReadOnlyReason {
+    NotWritable,
+    GitInternal,
+    Generated,
+    LargeFile,
+    /// Locked by the user with Toggle Read-Only.
+    Manual,
+}
+
+impl ReadOnlyReason {
+    /// Tooltip text for the lock indicator.
+    pub fn description(self) -> &'static str {
+        match self {
+            ReadOnlyReason::NotWritable => "Read-only: no write permission",
+            ReadOnlyReason::GitInternal => "Read-only: inside the .git directory",
+            ReadOnlyReason::Generated => "Read-only: generated file",
+            ReadOnlyReason::LargeFile => "Read-only: file is too large to edit",
+            ReadOnlyReason::Manual => "Read-only",
+        }
+    }
+}
+
+/// Why `path` (with the given content) should open read-only, if it should.
+pub fn read_only_reason(path: &str, content: &str) -> Option<ReadOnlyReason> {
+    let path = Path::new(path);
+    if path.components().any(|c| c.as_os_str() == ".git") {
+        return Some(ReadOnlyReason::GitInternal);
+    }
+    if std::fs::metadata(path).is_ok_and(|m| m.permissions().readonly()) {
+        return Some(ReadOnlyReason::NotWritable);
+    }
+    if is_generated(content) {
+        return Some(ReadOnlyReason::Generated);
+    }
+    None
+}
+
+/// Whether the file header carries a generated-code marker.
+pub fn is_generated(content: &str) -> bool {
+    content
+        .lines()
+        .take(GENERATED_MARKER_LINES)
+        .any(|line| GENERATED_MARKERS.iter().any(|m| line.contains(m)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_markers_in_header_only() {
+        assert!(is_generated(
+            "// Code generated by protoc-gen-go. DO NOT EDIT.\npackage pb\n"
+        ));
+        assert!(is_generated(
+            "# This file is automatically @generated by Cargo.\nversion = 4\n"
+        ));
+        let late = format!(
+            "{}// DO NOT EDIT\n",
+            "line\n".repeat(GENERATED_MARKER_LINES)
+        );
+        assert!(!is_generated(&late));
+        assert!(!is_generated("fn main() {}\n"));
+    }
+
+    #[test]
+    fn reasons_for_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let plain = dir.path().join("main.rs");
+        std::fs::write(&plain, "fn main() {}\n").unwrap();
+        let plain = plain.to_str().unwrap();
+        assert_eq!(read_only_reason(plain, "fn main() {}\n"), None);
+        assert_eq!(
+            read_only_reason(plain, "// @generated\n"),
+            Some(ReadOnlyReason::Generated)
+        );
+        assert_eq!(
+            read_only_reason("/repo/.git/COMMIT_EDITMSG", ""),
+            Some(ReadOnlyReason::GitInternal)
+        );
+
+        let locked = dir.path().join("locked.txt");
+        std::fs::write(&locked, "x").unwrap();
+        let mut permissions = std::fs::metadata(&locked).unwrap().permissions();
+        permissions.set_readonly(true);
+        std::fs::set_permissions(&locked, permissions).unwrap();
+        assert_eq!(
+            read_only_reason(locked.to_str().unwrap(), "x"),
+            Some(ReadOnlyReason::NotWritable)
+        );
+    }
+}
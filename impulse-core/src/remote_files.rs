@@ -0,0 +1,504 @@
+//! Single remote files opened by URI (`sftp://user@host:port/path`,
+//! `ssh://...`): [`open`] downloads the file into a local cache and the
+//! editor works on the copy; [`upload`] writes it back after a save. A
+//! lighter complement to a remote workspace for editing one config file on
+//! a server.
+//!
+//! Transfers run the system `ssh` (so `~/.ssh/config`, agents and
+//! `ProxyJump` apply) in batch mode: hosts must accept a key without a
+//! prompt. The remote modification time is recorded at download, so a
+//! cached copy is reused only while the server's file is unchanged, and an
+//! upload that would overwrite someone else's change is refused until
+//! forced.
+//!
+//! Cached copies live under `<cache>/impulse/remote/<host>/`, with
+//! `index.json` mapping each one back to its URI.
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::OnceLock;
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+/// Seconds `ssh` waits to connect.
+const CONNECT_TIMEOUT_SECS: u32 = 10;
+
+/// Largest remote file [`open`] downloads.
+const MAX_REMOTE_FILE_SIZE: u64 = 64 * 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteUri {
+    pub user: Option<String>,
+    pub host: String,
+    pub port: Option<u16>,
+    /// Absolute, or starting with `~/` for the remote home directory.
+    pub path: String,
+}
+
+impl RemoteUri {
+    /// `user@host`, as `ssh` takes it.
+    fn destination(&self) -> String {
+        match &self.user {
+            Some(user) => format!("{user}@{}", self.host),
+            None => self.host.clone(),
+        }
+    }
+
+    /// The path as a remote shell word, leaving a leading `~/` unquoted so
+    /// it still expands.
+    fn shell_path(&self) -> String {
+        match self.path.strip_prefix("~/") {
+            Some(rest) => format!("~/{}", shell_quote(rest)),
+            None => shell_quote(&self.path),
+        }
+    }
+
+    /// The cached copy under `cache_root`. Fails rather than leave it.
+    fn cache_path(&self, cache_root: &Path) -> Result<PathBuf, String> {
+        let mut host_dir = self.destination();
+        if let Some(port) = self.port {
+            host_dir.push_str(&format!("_{port}"));
+        }
+        let mut path = cache_root.join(host_dir);
+        for part in self.path.split('/').filter(|p| !p.is_empty()) {
+            path.push(part);
+        }
+        let escapes = path.strip_prefix(cache_root).map_or(true, |rel| {
+            rel.components().any(|c| !matches!(c, Component::Normal(_)))
+        });
+        if escapes {
+            return Err(format!(
+                "{} would be cached outside {}",
+                self.path,
+                cache_root.display()
+            ));
+        }
+        Ok(path)
+    }
+}
+
+/// Whether `uri` names a remote file [`parse_uri`] understands.
+pub fn is_remote_uri(uri: &str) -> bool {
+    uri.starts_with("sftp://") || uri.starts_with("ssh://")
+}
+
+/// Parse `sftp://[user@]host[:port]/path` or the same with `ssh://`. A path
+/// starting with `/~/` is relative to the remote home directory.
+pub fn parse_uri(uri: &str) -> Result<RemoteUri, String> {
+    let rest = uri
+        .trim()
+        .strip_prefix("sftp://")
+        .or_else(|| uri.trim().strip_prefix("ssh://"))
+        .ok_or_else(|| format!("\"{uri}\" isn't an sftp:// or ssh:// URI"))?;
+    let (authority, path) = rest
+        .split_once('/')
+        .ok_or_else(|| format!("\"{uri}\" has no file path"))?;
+    let (user, host_port) = match authority.rsplit_once('@') {
+        Some((user, host_port)) => (Some(percent_decode(user)), host_port),
+        None => (None, authority),
+    };
+    // IPv6 hosts are bracketed: `[::1]:22`.
+    let (host, port) = match host_port.strip_prefix('[') {
+        Some(bracketed) => {
+            let (host, rest) = bracketed
+                .split_once(']')
+                .ok_or_else(|| format!("\"{uri}\" has an unclosed [ in its host"))?;
+            (host, rest.strip_prefix(':'))
+        }
+        None => match host_port.rsplit_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (host_port, None),
+        },
+    };
+    let port = port
+        .map(|port| {
+            port.parse::<u16>()
+                .map_err(|_| format!("\"{port}\" isn't a valid port"))
+        })
+        .transpose()?;
+    if !is_safe_name(host) {
+        return Err(format!("\"{uri}\" has no valid host"));
+    }
+    if user
+        .as_deref()
+        .is_some_and(|user| !user.is_empty() && !is_safe_name(user))
+    {
+        return Err(format!("\"{uri}\" has no valid user"));
+    }
+    let path = percent_decode(path);
+    let path = match path.strip_prefix("~/") {
+        Some(rest) => format!("~/{rest}"),
+        None => format!("/{path}"),
+    };
+    let components = Path::new(&path).components();
+    if components
+        .clone()
+        .any(|c| matches!(c, Component::ParentDir))
+        || path.ends_with('/')
+        || components.count() < 2
+    {
+        return Err(format!("\"{uri}\" doesn't name a file"));
+    }
+    Ok(RemoteUri {
+        user: user.filter(|u| !u.is_empty()),
+        host: host.to_string(),
+        port,
+        path,
+    })
+}
+
+/// Whether `name` can be a user or host: passed to `ssh` without being
+/// read as an option, and a single directory name in the cache.
+fn is_safe_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('-')
+        && name != "."
+        && name != ".."
+        && !name.contains(['/', '\\', '\0'])
+}
+
+/// A cached copy and the remote file it came from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct CacheEntry {
+    uri: String,
+    /// The remote modification time at the last download or upload, in
+    /// seconds since the Unix epoch.
+    remote_modified: i64,
+    /// The copy's modification time then, to tell whether it was edited.
+    local_modified: Option<u64>,
+}
+
+/// The outcome of an [`upload`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UploadOutcome {
+    Uploaded,
+    /// The server's file changed since it was downloaded; nothing was
+    /// written. Upload with `force` to overwrite it.
+    ChangedOnServer,
+}
+
+fn cache_root() -> Result<PathBuf, String> {
+    dirs::cache_dir()
+        .map(|d| d.join("impulse").join("remote"))
+        .ok_or_else(|| "No cache directory".to_string())
+}
+
+fn index() -> &'static Mutex<Option<HashMap<String, CacheEntry>>> {
+    static INDEX: OnceLock<Mutex<Option<HashMap<String, CacheEntry>>>> = OnceLock::new();
+    INDEX.get_or_init(|| Mutex::new(None))
+}
+
+fn load_index(cache_root: &Path) -> HashMap<String, CacheEntry> {
+    std::fs::read_to_string(cache_root.join("index.json"))
+        .ok()
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+fn save_index(cache_root: &Path, entries: &HashMap<String, CacheEntry>) {
+    let result = serde_json::to_string_pretty(entries)
+        .map_err(|e| e.to_string())
+        .and_then(|json| {
+            std::fs::create_dir_all(cache_root).map_err(|e| e.to_string())?;
+            std::fs::write(cache_root.join("index.json"), json).map_err(|e| e.to_string())
+        });
+    if let Err(e) = result {
+        log::warn!("Failed to save the remote file index: {}", e);
+    }
+}
+
+fn cache_entry(local: &str) -> Option<CacheEntry> {
+    let root = cache_root().ok()?;
+    let mut index = index().lock();
+    index
+        .get_or_insert_with(|| load_index(&root))
+        .get(local)
+        .cloned()
+}
+
+fn set_cache_entry(local: &str, entry: CacheEntry) {
+    let Ok(root) = cache_root() else {
+        return;
+    };
+    let mut index = index().lock();
+    let entries = index.get_or_insert_with(|| load_index(&root));
+    entries.insert(local.to_string(), entry);
+    save_index(&root, entries);
+}
+
+/// The URI a cached copy at `local` was downloaded from, if it is one.
+pub fn remote_uri_for(local: &str) -> Option<String> {
+    cache_entry(local).map(|entry| entry.uri)
+}
+
+/// The URIs of cached remote files, sorted, for suggestions.
+pub fn recent_uris() -> Vec<String> {
+    let Ok(root) = cache_root() else {
+        return Vec::new();
+    };
+    let mut index = index().lock();
+    let mut uris: Vec<String> = index
+        .get_or_insert_with(|| load_index(&root))
+        .values()
+        .map(|entry| entry.uri.clone())
+        .collect();
+    uris.sort();
+    uris.dedup();
+    uris
+}
+
+/// Download the file at `uri` into the cache and return the local copy's
+/// path. An existing copy is kept when the server's file hasn't changed
+/// since it was downloaded; a copy with edits that weren't uploaded is an
+/// error when the server's file has changed too.
+pub fn open(uri: &str) -> Result<PathBuf, String> {
+    let remote = parse_uri(uri)?;
+    let local = remote.cache_path(&cache_root()?)?;
+    let local_str = local.to_string_lossy().to_string();
+    let remote_modified = remote_modified(&remote)?;
+
+    if let Some(entry) = cache_entry(&local_str).filter(|_| local.is_file()) {
+        let edited = local_modified(&local) != entry.local_modified;
+        if entry.remote_modified == remote_modified {
+            return Ok(local);
+        }
+        if edited {
+            return Err(format!(
+                "{uri} changed on the server, and the cached copy has edits that weren't uploaded"
+            ));
+        }
+    }
+
+    let content = download(&remote)?;
+    if let Some(parent) = local.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+    }
+    std::fs::write(&local, content)
+        .map_err(|e| format!("Failed to write {}: {e}", local.display()))?;
+    set_cache_entry(
+        &local_str,
+        CacheEntry {
+            uri: uri.trim().to_string(),
+            remote_modified,
+            local_modified: local_modified(&local),
+        },
+    );
+    Ok(local)
+}
+
+/// Write the cached copy at `local` back to its server. Unless `force`, the
+/// upload is refused when the server's file changed since the download.
+pub fn upload(local: &str, force: bool) -> Result<UploadOutcome, String> {
+    let entry = cache_entry(local).ok_or_else(|| format!("{local} isn't a remote file"))?;
+    let remote = parse_uri(&entry.uri)?;
+    if !force && remote_modified(&remote)? != entry.remote_modified {
+        return Ok(UploadOutcome::ChangedOnServer);
+    }
+    let content = std::fs::read(local).map_err(|e| format!("Failed to read {local}: {e}"))?;
+
+    let mut child = ssh(&remote, &format!("cat > {}", remote.shell_path()))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run ssh: {e}"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(&content)
+            .map_err(|e| format!("Failed to upload {}: {e}", entry.uri))?;
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("Failed to run ssh: {e}"))?;
+    if !output.status.success() {
+        return Err(ssh_error(&entry.uri, &output.stderr));
+    }
+
+    set_cache_entry(
+        local,
+        CacheEntry {
+            remote_modified: remote_modified(&remote)?,
+            local_modified: local_modified(Path::new(local)),
+            uri: entry.uri,
+        },
+    );
+    Ok(UploadOutcome::Uploaded)
+}
+
+fn ssh(remote: &RemoteUri, command: &str) -> Command {
+    let mut cmd = Command::new("ssh");
+    cmd.args(["-o", "BatchMode=yes", "-o"])
+        .arg(format!("ConnectTimeout={CONNECT_TIMEOUT_SECS}"));
+    if let Some(port) = remote.port {
+        cmd.arg("-p").arg(port.to_string());
+    }
+    cmd.arg("--").arg(remote.destination()).arg(command);
+    cmd.stdin(Stdio::null());
+    cmd
+}
+
+fn ssh_error(uri: &str, stderr: &[u8]) -> String {
+    let stderr = String::from_utf8_lossy(stderr);
+    let reason = stderr.lines().rev().find(|l| !l.trim().is_empty());
+    match reason {
+        Some(reason) => format!("{uri}: {}", reason.trim()),
+        None => format!("{uri}: ssh failed"),
+    }
+}
+
+/// The remote file's modification time, and that it is a regular file of
+/// a size worth downloading. GNU `stat` first, then BSD.
+fn remote_modified(remote: &RemoteUri) -> Result<i64, String> {
+    let path = remote.shell_path();
+    let command = format!(
+        "test -f {path} || {{ echo 'Not a regular file' >&2; exit 1; }}; \
+         stat -c '%Y %s' -- {path} 2>/dev/null || stat -f '%m %z' -- {path}"
+    );
+    let output = ssh(remote, &command)
+        .output()
+        .map_err(|e| format!("Failed to run ssh: {e}"))?;
+    let uri = format!("{}:{}", remote.destination(), remote.path);
+    if !output.status.success() {
+        return Err(ssh_error(&uri, &output.stderr));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut fields = stdout.split_whitespace();
+    let modified = fields.next().and_then(|m| m.parse::<i64>().ok());
+    let size = fields.next().and_then(|s| s.parse::<u64>().ok());
+    match (modified, size) {
+        (Some(_), Some(size)) if size > MAX_REMOTE_FILE_SIZE => {
+            Err(format!("{uri} is too large to open"))
+        }
+        (Some(modified), Some(_)) => Ok(modified),
+        _ => Err(format!(
+            "{uri}: unexpected stat output \"{}\"",
+            stdout.trim()
+        )),
+    }
+}
+
+fn download(remote: &RemoteUri) -> Result<Vec<u8>, String> {
+    let output = ssh(remote, &format!("cat -- {}", remote.shell_path()))
+        .output()
+        .map_err(|e| format!("Failed to run ssh: {e}"))?;
+    if !output.status.success() {
+        let uri = format!("{}:{}", remote.destination(), remote.path);
+        return Err(ssh_error(&uri, &output.stderr));
+    }
+    Ok(output.stdout)
+}
+
+fn local_modified(path: &Path) -> Option<u64> {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_millis() as u64)
+}
+
+/// `s` single-quoted for a POSIX shell.
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "'\\''"))
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                out.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_remote_uris() {
+        let remote = parse_uri("sftp://deploy@web-1:2222/etc/nginx/my%20site.conf").unwrap();
+        assert_eq!(
+            remote,
+            RemoteUri {
+                user: Some("deploy".to_string()),
+                host: "web-1".to_string(),
+                port: Some(2222),
+                path: "/etc/nginx/my site.conf".to_string(),
+            }
+        );
+        assert_eq!(remote.destination(), "deploy@web-1");
+        assert_eq!(remote.shell_path(), "'/etc/nginx/my site.conf'");
+        assert_eq!(
+            remote.cache_path(Path::new("/cache")).unwrap(),
+            PathBuf::from("/cache/deploy@web-1_2222/etc/nginx/my site.conf")
+        );
+
+        let home = parse_uri("ssh://box/~/notes/it's.md").unwrap();
+        assert_eq!(home.user, None);
+        assert_eq!(home.port, None);
+        assert_eq!(home.path, "~/notes/it's.md");
+        assert_eq!(home.shell_path(), "~/'notes/it'\\''s.md'");
+
+        assert_eq!(parse_uri("sftp://[::1]:22/x.txt").unwrap().host, "::1");
+        assert_eq!(parse_uri("sftp://[::1]/x.txt").unwrap().port, None);
+        assert!(parse_uri("/local/file").is_err());
+        assert!(parse_uri("sftp://host").is_err());
+        assert!(parse_uri("sftp://host/").is_err());
+        assert!(parse_uri("sftp://host/etc/").is_err());
+        assert!(parse_uri("sftp://host/../../etc/passwd").is_err());
+        assert!(parse_uri("sftp://-oProxyCommand=x/file").is_err());
+        assert!(parse_uri("sftp://-oProxyCommand=x@host/file").is_err());
+        // A decoded user or host must not climb out of the cache.
+        assert!(parse_uri("sftp://..%2F..%2F..%2Fx@host/file").is_err());
+        assert!(parse_uri("sftp://..@host/file").is_err());
+        assert!(parse_uri("sftp://a%5Cb@host/file").is_err());
+        assert!(parse_uri("sftp://a%00b@host/file").is_err());
+        assert!(parse_uri("sftp://../file").is_err());
+        let crafted = RemoteUri {
+            user: Some("../../x".to_string()),
+            host: "host".to_string(),
+            port: None,
+            path: "/file".to_string(),
+        };
+        assert!(crafted.cache_path(Path::new("/cache")).is_err());
+        assert!(parse_uri("sftp://host:port/file").is_err());
+        assert!(is_remote_uri("ssh://host/file"));
+        assert!(!is_remote_uri("https://host/file"));
+    }
+
+    #[test]
+    fn cache_index_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let entries: HashMap<String, CacheEntry> = [(
+            "/cache/host/a.txt".to_string(),
+            CacheEntry {
+                uri: "sftp://host/a.txt".to_string(),
+                remote_modified: 1_700_000_000,
+                local_modified: Some(42),
+            },
+        )]
+        .into_iter()
+        .collect();
+        assert!(load_index(dir.path()).is_empty());
+        save_index(dir.path(), &entries);
+        assert_eq!(load_index(dir.path()), entries);
+    }
+}
@@ -0,0 +1,279 @@
+//! Saving files without risking a truncated file on a crash. The default
+//! (`save_strategy: "atomic"`) writes a temporary file next to the target,
+//! syncs it, and renames it over the target, carrying over the permission
+//! bits, owner and extended attributes. The target gets a fresh mtime, as
+//! build tools and watchers expect.
+//!
+//! Hard links (a rename would split them) and files in directories the
+//! user can't create files in are overwritten in place instead, as is
+//! every file with `save_strategy: "in_place"`, for file systems and tools
+//! that track inodes. An in-place save keeps a copy of the old content
+//! next to the file until the new content is on disk.
+//!
+//! Symlinks are written through, so the link stays a link.
+
+use std::fs::{File, Metadata, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// The `save_strategy` values.
+pub const STRATEGY_ATOMIC: &str = "atomic";
+pub const STRATEGY_IN_PLACE: &str = "in_place";
+
+static IN_PLACE: AtomicBool = AtomicBool::new(false);
+
+/// Apply the `save_strategy` setting.
+pub fn set_save_strategy(strategy: &str) {
+    IN_PLACE.store(strategy == STRATEGY_IN_PLACE, Ordering::Relaxed);
+}
+
+/// Save `content` to `path` with the configured strategy. Errors keep their
+/// `io::ErrorKind`, so a permission error can be retried as the
+/// administrator (`privileged_write`).
+pub fn write(path: &Path, content: &[u8]) -> io::Result<()> {
+    write_with(path, content, IN_PLACE.load(Ordering::Relaxed))
+}
+
+fn write_with(path: &Path, content: &[u8], in_place: bool) -> io::Result<()> {
+    let dest = resolve_symlink(path);
+    let meta = std::fs::metadata(&dest).ok();
+    if in_place || meta.as_ref().is_some_and(is_hard_linked) {
+        return write_in_place(&dest, content, meta.is_some());
+    }
+
+    // Only the owner can read the new content until the target's
+    // permissions are applied; a new file gets the umask default.
+    let mode = if meta.is_some() { 0o600 } else { 0o666 };
+    let (tmp, mut file) = match create_sibling(&dest, "impulse-save-tmp", mode) {
+        Ok(created) => created,
+        // The directory is read-only but the file may not be.
+        Err(e) if e.kind() == io::ErrorKind::PermissionDenied && meta.is_some() => {
+            return write_in_place(&dest, content, false);
+        }
+        Err(e) => return Err(e),
+    };
+    if let Some(meta) = &meta {
+        copy_metadata(meta, &dest, &tmp);
+    }
+    let result = file
+        .write_all(content)
+        .and_then(|()| file.sync_all())
+        .and_then(|()| std::fs::rename(&tmp, &dest));
+    if let Err(e) = result {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(e);
+    }
+    sync_parent(&dest);
+    Ok(())
+}
+
+/// Overwrite `dest`, keeping a copy of an existing file beside it until the
+/// new content is synced.
+fn write_in_place(dest: &Path, content: &[u8], back_up: bool) -> io::Result<()> {
+    let backup = back_up.then(|| back_up_file(dest)).flatten();
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(dest)?;
+    file.write_all(content)?;
+    file.sync_all()?;
+    if let Some(backup) = backup {
+        let _ = std::fs::remove_file(&backup);
+    }
+    Ok(())
+}
+
+/// Copy `dest` to a new owner-only file beside it.
+fn back_up_file(dest: &Path) -> Option<PathBuf> {
+    let (backup, mut file) = create_sibling(dest, "impulse-backup", 0o600).ok()?;
+    let copied = std::fs::File::open(dest)
+        .and_then(|mut source| io::copy(&mut source, &mut file))
+        .and_then(|_| file.sync_all());
+    match copied {
+        Ok(()) => Some(backup),
+        Err(_) => {
+            let _ = std::fs::remove_file(&backup);
+            None
+        }
+    }
+}
+
+/// Create a new `.name.<suffix>-<random>` file beside `path` with `mode`
+/// (on Unix, before the umask). It must not exist yet, so a symlink planted
+/// under a guessed name is never followed.
+fn create_sibling(path: &Path, suffix: &str, mode: u32) -> io::Result<(PathBuf, File)> {
+    let tmp = sibling(
+        path,
+        &format!("{}-{}", suffix, uuid::Uuid::new_v4().as_simple()),
+    );
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(mode);
+    }
+    #[cfg(not(unix))]
+    let _ = mode;
+    let file = options.open(&tmp)?;
+    Ok((tmp, file))
+}
+
+/// `path`, or what it links to when it is a symlink to an existing file.
+fn resolve_symlink(path: &Path) -> PathBuf {
+    match std::fs::symlink_metadata(path) {
+        Ok(meta) if meta.file_type().is_symlink() => {
+            std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+        }
+        _ => path.to_path_buf(),
+    }
+}
+
+/// `.name.<suffix>` in the same directory as `path`.
+fn sibling(path: &Path, suffix: &str) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "file".to_string());
+    path.with_file_name(format!(".{name}.{suffix}"))
+}
+
+#[cfg(unix)]
+fn is_hard_linked(meta: &Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    meta.is_file() && meta.nlink() > 1
+}
+
+#[cfg(not(unix))]
+fn is_hard_linked(_meta: &Metadata) -> bool {
+    false
+}
+
+/// Give `tmp` the permissions, owner and extended attributes of the file
+/// it replaces. Best effort: a file the user doesn't own can't be given
+/// its owner back.
+fn copy_metadata(meta: &Metadata, dest: &Path, tmp: &Path) {
+    if let Err(e) = std::fs::set_permissions(tmp, meta.permissions()) {
+        log::warn!("Failed to keep permissions of {}: {}", dest.display(), e);
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        let _ = std::os::unix::fs::chown(tmp, Some(meta.uid()), Some(meta.gid()));
+        if let Ok(names) = xattr::list(dest) {
+            for name in names {
+                if let Ok(Some(value)) = xattr::get(dest, &name) {
+                    if let Err(e) = xattr::set(tmp, &name, &value) {
+                        log::debug!(
+                            "Failed to keep attribute {:?} of {}: {}",
+                            name,
+                            dest.display(),
+                            e
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Make the rename durable.
+fn sync_parent(path: &Path) {
+    #[cfg(unix)]
+    if let Some(parent) = path.parent() {
+        if let Ok(dir) = std::fs::File::open(parent) {
+            let _ = dir.sync_all();
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn atomic_saves_replace_the_file_and_keep_its_mode() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.txt");
+        write_with(&path, b"one", false).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "one");
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::{MetadataExt, PermissionsExt};
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o640)).unwrap();
+            let inode = std::fs::metadata(&path).unwrap().ino();
+            write_with(&path, b"two", false).unwrap();
+            let meta = std::fs::metadata(&path).unwrap();
+            assert_eq!(meta.permissions().mode() & 0o777, 0o640);
+            assert_ne!(meta.ino(), inode);
+        }
+        write_with(&path, b"two", false).unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "two");
+        let leftovers: Vec<_> = std::fs::read_dir(dir.path()).unwrap().flatten().collect();
+        assert_eq!(leftovers.len(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn links_and_in_place_saves_keep_the_inode() {
+        use std::os::unix::fs::MetadataExt;
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("real.conf");
+        std::fs::write(&target, "old").unwrap();
+
+        let link = dir.path().join("link.conf");
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+        write_with(&link, b"via link", false).unwrap();
+        assert!(std::fs::symlink_metadata(&link)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        assert_eq!(std::fs::read_to_string(&target).unwrap(), "via link");
+
+        let hard = dir.path().join("hard.conf");
+        std::fs::hard_link(&target, &hard).unwrap();
+        let inode = std::fs::metadata(&target).unwrap().ino();
+        write_with(&target, b"hard", false).unwrap();
+        assert_eq!(std::fs::read_to_string(&hard).unwrap(), "hard");
+        assert_eq!(std::fs::metadata(&target).unwrap().ino(), inode);
+
+        std::fs::remove_file(&hard).unwrap();
+        write_with(&target, b"in place", true).unwrap();
+        assert_eq!(std::fs::metadata(&target).unwrap().ino(), inode);
+        let leftovers = std::fs::read_dir(dir.path()).unwrap().count();
+        assert_eq!(leftovers, 2);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn temp_files_are_private_until_the_target_mode_applies() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("secret.txt");
+        std::fs::write(&path, "old").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+        let (tmp, _file) = create_sibling(&path, "impulse-save-tmp", 0o600).unwrap();
+        let mode = std::fs::metadata(&tmp).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        // Names are unpredictable, and an existing name is never reused.
+        let (other, _) = create_sibling(&path, "impulse-save-tmp", 0o600).unwrap();
+        assert_ne!(tmp, other);
+
+        // A symlink planted where the old fixed temp name was isn't
+        // followed.
+        let victim = dir.path().join("victim");
+        std::fs::write(&victim, "untouched").unwrap();
+        std::os::unix::fs::symlink(&victim, sibling(&path, "impulse-save-tmp")).unwrap();
+        write_with(&path, b"new", false).unwrap();
+        assert_eq!(std::fs::read_to_string(&victim).unwrap(), "untouched");
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "new");
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o644);
+    }
+}
@@ -0,0 +1,151 @@
+//! Whitespace fixes applied when a file is saved: trimming trailing
+//! whitespace and ending the file with a newline. The settings choose the
+//! defaults and `.editorconfig` files override them per file.
+//!
+//! The fixes come back as minimal edits, so frontends can apply them to the
+//! open buffer as well as to the saved text without moving the cursor.
+
+use std::path::Path;
+
+use crate::settings::Settings;
+use crate::workspace_edit::TextEdit;
+
+/// Which fixes to apply to one file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SaveWhitespace {
+    pub trim_trailing_whitespace: bool,
+    pub insert_final_newline: bool,
+}
+
+impl SaveWhitespace {
+    /// The fixes for `path`: `defaults` overridden by `.editorconfig`.
+    pub fn for_file(path: &Path, defaults: SaveWhitespace) -> Self {
+        let config = crate::editorconfig::for_file(path);
+        SaveWhitespace {
+            trim_trailing_whitespace: config
+                .trim_trailing_whitespace
+                .unwrap_or(defaults.trim_trailing_whitespace),
+            insert_final_newline: config
+                .insert_final_newline
+                .unwrap_or(defaults.insert_final_newline),
+        }
+    }
+
+    /// The fixes `settings` ask for, before `.editorconfig`.
+    pub fn from_settings(settings: &Settings) -> Self {
+        SaveWhitespace {
+            trim_trailing_whitespace: settings.trim_trailing_whitespace,
+            insert_final_newline: settings.insert_final_newline,
+        }
+    }
+}
+
+/// Edits that apply `fixes` to `content`, in LSP coordinates (UTF-16
+/// columns), in document order and never overlapping. Empty when there is
+/// nothing to fix.
+pub fn edits(content: &str, fixes: SaveWhitespace) -> Vec<TextEdit> {
+    let mut edits = Vec::new();
+    if fixes.trim_trailing_whitespace {
+        for (line_no, line) in content.split('\n').enumerate() {
+            let line = line.strip_suffix('\r').unwrap_or(line);
+            let trimmed = line.trim_end_matches([' ', '\t']);
+            if trimmed.len() == line.len() {
+                continue;
+            }
+            edits.push(TextEdit {
+                start_line: line_no as u32,
+                start_character: utf16_len(trimmed),
+                end_line: line_no as u32,
+                end_character: utf16_len(line),
+                new_text: String::new(),
+            });
+        }
+    }
+    if fixes.insert_final_newline && !content.is_empty() && !content.ends_with('\n') {
+        let last_line = content.split('\n').count() - 1;
+        let last = content.rsplit('\n').next().unwrap_or("");
+        let end = if fixes.trim_trailing_whitespace {
+            last.trim_end_matches([' ', '\t'])
+        } else {
+            last
+        };
+        // Match the file's line endings.
+        let newline = if content.contains("\r\n") {
+            "\r\n"
+        } else {
+            "\n"
+        };
+        let position = utf16_len(end);
+        match edits.last_mut() {
+            // The trim of the last line ends where the newline goes.
+            Some(edit) if edit.start_line == last_line as u32 => {
+                edit.new_text = newline.to_string();
+            }
+            _ => edits.push(TextEdit {
+                start_line: last_line as u32,
+                start_character: position,
+                end_line: last_line as u32,
+                end_character: position,
+                new_text: newline.to_string(),
+            }),
+        }
+    }
+    edits
+}
+
+fn utf16_len(text: &str) -> u32 {
+    text.encode_utf16().count() as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workspace_edit::apply_text_edits;
+
+    const ALL: SaveWhitespace = SaveWhitespace {
+        trim_trailing_whitespace: true,
+        insert_final_newline: true,
+    };
+
+    fn fix(content: &str, fixes: SaveWhitespace) -> String {
+        apply_text_edits(content, &edits(content, fixes)).unwrap()
+    }
+
+    #[test]
+    fn trims_trailing_whitespace_and_adds_a_final_newline() {
+        assert_eq!(fix("a  \nb\t\nc", ALL), "a\nb\nc\n");
+        assert_eq!(fix("héllo \r\nwörld \t", ALL), "héllo\r\nwörld\r\n");
+        assert_eq!(fix("  \n", ALL), "\n");
+        assert!(edits("clean\n", ALL).is_empty());
+        assert!(edits("", ALL).is_empty());
+
+        let trim_only = SaveWhitespace {
+            trim_trailing_whitespace: true,
+            insert_final_newline: false,
+        };
+        assert_eq!(fix("x  \ny  ", trim_only), "x\ny");
+        let newline_only = SaveWhitespace {
+            trim_trailing_whitespace: false,
+            insert_final_newline: true,
+        };
+        assert_eq!(fix("x  \ny  ", newline_only), "x  \ny  \n");
+        assert!(edits("x  \ny  ", SaveWhitespace::default()).is_empty());
+    }
+
+    #[test]
+    fn editorconfig_overrides_the_settings() {
+        let temp = tempfile::tempdir().unwrap();
+        std::fs::write(
+            temp.path().join(".editorconfig"),
+            "root = true\n[*.md]\ntrim_trailing_whitespace = false\n",
+        )
+        .unwrap();
+        let md = SaveWhitespace::for_file(&temp.path().join("notes.md"), ALL);
+        assert!(!md.trim_trailing_whitespace);
+        assert!(md.insert_final_newline);
+        assert_eq!(
+            SaveWhitespace::for_file(&temp.path().join("main.rs"), ALL),
+            ALL
+        );
+    }
+}
@@ -1,11 +1,14 @@
-use ignore::WalkBuilder;
+use ignore::WalkState;
+use parking_lot::Mutex;
 use regex::RegexBuilder;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
 use std::fs::File;
-use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::io::{Read, Write};
 use std::path::Path;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+pub use crate::search_index::set_content_index_enabled;
 
 #[derive(Serialize, Clone, Debug)]
 pub struct SearchResult {
@@ -15,9 +18,46 @@ pub struct SearchResult {
     pub line_content: Option<String>,
     pub column_start: Option<u32>,
     pub column_end: Option<u32>,
+    /// Byte range of the match within `line_content`, clamped to its end
+    /// when the line was truncated.
+    pub match_start: Option<u32>,
+    pub match_end: Option<u32>,
+    /// Up to [`CONTEXT_LINES`] lines before and after the matched line.
+    pub context_before: Vec<String>,
+    pub context_after: Vec<String>,
     pub match_type: String,
 }
 
+/// Which files a project content search looks in.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SearchScope {
+    /// Every file under the search root.
+    #[default]
+    Workspace,
+    /// The open files, searched in their editor buffers so unsaved changes
+    /// are found.
+    OpenFiles { files: Vec<OpenFile> },
+    /// Files under one directory.
+    Directory { path: String },
+    /// Files under the root matching comma-separated globs, like
+    /// `src/**/*.rs, !*_test.rs`.
+    Glob { pattern: String },
+}
+
+/// An open file and its buffer contents, for [`SearchScope::OpenFiles`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OpenFile {
+    pub path: String,
+    pub content: String,
+}
+
+/// Lines of context reported on each side of a content match.
+pub const CONTEXT_LINES: usize = 2;
+
+/// Matched and context lines are cut to this many characters.
+const MAX_LINE_CHARS: usize = 500;
+
 /// Search for files by name pattern (substring matching, case-insensitive).
 /// If `cancel` is provided and set to `true`, the search stops early and returns partial results.
 pub fn search_filenames(
@@ -29,13 +69,8 @@ pub fn search_filenames(
     let query_lower = query.to_lowercase();
     let mut results = Vec::new();
 
-    let walker = WalkBuilder::new(root)
-        .hidden(true)
-        .git_ignore(true)
-        .git_global(true)
-        .git_exclude(true)
+    let walker = crate::ignore_rules::search_walk_builder(Path::new(root))
         .max_depth(Some(15))
-        .same_file_system(true)
         .build();
 
     for entry in walker {
@@ -66,6 +101,10 @@ pub fn search_filenames(
                 line_content: None,
                 column_start: None,
                 column_end: None,
+                match_start: None,
+                match_end: None,
+                context_before: Vec::new(),
+                context_after: Vec::new(),
                 match_type: "file".to_string(),
             });
         }
@@ -74,20 +113,132 @@ pub fn search_filenames(
     Ok(results)
 }
 
-/// Check the first 8KB of an already-opened file for null bytes (binary indicator).
-/// Returns `Ok(true)` if the file appears to be binary, `Ok(false)` if it appears to be text.
-/// Returns `Err` on permission or I/O errors so the caller can decide how to handle them.
-/// On success, the file handle is seeked back to the beginning for subsequent reading.
-fn check_binary_and_rewind(file: &mut File) -> Result<bool, std::io::Error> {
-    let mut buffer = [0u8; 8192];
-    let bytes_read = file.read(&mut buffer)?;
-    let is_binary = buffer[..bytes_read].contains(&0);
-    file.seek(SeekFrom::Start(0))?;
-    Ok(is_binary)
+/// Files larger than this are skipped by content search.
+const MAX_SEARCHED_FILE_SIZE: u64 = 1_048_576;
+
+/// Files at least this large are memory-mapped instead of read.
+const MMAP_THRESHOLD: u64 = 64 * 1024;
+
+/// At most this many matches are reported per file, so one generated or
+/// minified file cannot use up the whole result limit.
+const MAX_MATCHES_PER_FILE: usize = 100;
+
+/// Upper bound on content search walker threads.
+const MAX_SEARCH_THREADS: usize = 8;
+
+/// A file's bytes, either read into memory or mapped.
+enum FileBytes {
+    Read(Vec<u8>),
+    Mapped(memmap2::Mmap),
+}
+
+impl std::ops::Deref for FileBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            FileBytes::Read(bytes) => bytes,
+            FileBytes::Mapped(map) => map,
+        }
+    }
+}
+
+/// Load a file for content search, or `None` if it is too large, binary
+/// (a NUL in the first 8KB) or unreadable.
+fn load_text_file(path: &Path) -> Option<FileBytes> {
+    let mut file = match File::open(path) {
+        Ok(f) => f,
+        Err(e) => {
+            log::warn!("Failed to open '{}': {}", path.display(), e);
+            return None;
+        }
+    };
+    let len = file.metadata().ok()?.len();
+    if len > MAX_SEARCHED_FILE_SIZE {
+        return None;
+    }
+    let bytes = if len >= MMAP_THRESHOLD {
+        // SAFETY: the map is read-only and dropped before this search
+        // returns. A file truncated by another process while mapped can
+        // fault on access, which is the accepted trade-off of mapped reads
+        // (ripgrep makes the same one); small files are read instead.
+        match unsafe { memmap2::Mmap::map(&file) } {
+            Ok(map) => FileBytes::Mapped(map),
+            Err(e) => {
+                log::warn!("Failed to map '{}': {}", path.display(), e);
+                return None;
+            }
+        }
+    } else {
+        let mut buf = Vec::with_capacity(len as usize);
+        if let Err(e) = file.read_to_end(&mut buf) {
+            log::warn!("Failed to read '{}': {}", path.display(), e);
+            return None;
+        }
+        FileBytes::Read(buf)
+    };
+    if bytes[..bytes.len().min(8192)].contains(&0) {
+        return None;
+    }
+    Some(bytes)
+}
+
+/// A content search query, prepared once and shared by all walker threads.
+struct ContentQuery {
+    query: String,
+    /// `query`, lowercased unless the search is case sensitive.
+    query_match: String,
+    case_sensitive: bool,
+    /// Rejects files without any match before they are split into lines.
+    /// Case-insensitive prefiltering is only exact for ASCII, so it is
+    /// skipped for non-ASCII queries and files.
+    finder: memchr::memmem::Finder<'static>,
+    ascii_folded: Option<regex::bytes::Regex>,
+}
+
+impl ContentQuery {
+    fn new(query: &str, case_sensitive: bool) -> Self {
+        let query_match = if case_sensitive {
+            query.to_string()
+        } else {
+            query.to_lowercase()
+        };
+        let ascii_folded = (!case_sensitive && query.is_ascii())
+            .then(|| {
+                regex::bytes::RegexBuilder::new(&regex::escape(query))
+                    .case_insensitive(true)
+                    .unicode(false)
+                    .build()
+                    .ok()
+            })
+            .flatten();
+        Self {
+            query: query.to_string(),
+            finder: memchr::memmem::Finder::new(query.as_bytes()).into_owned(),
+            query_match,
+            case_sensitive,
+            ascii_folded,
+        }
+    }
+
+    /// Whether `bytes` might contain a match; `false` only when it surely
+    /// does not.
+    fn may_match(&self, bytes: &[u8]) -> bool {
+        if self.case_sensitive {
+            return self.finder.find(bytes).is_some();
+        }
+        match &self.ascii_folded {
+            Some(re) if bytes.is_ascii() => re.is_match(bytes),
+            _ => true,
+        }
+    }
 }
 
 /// Search file contents for a text pattern.
 /// If `cancel` is provided and set to `true`, the search stops early and returns partial results.
+/// With content indexing enabled, only files the index says can match are
+/// read; otherwise the tree is walked on several threads. Each file reports
+/// at most [`MAX_MATCHES_PER_FILE`] matches, and results are ordered by path.
 pub fn search_contents(
     root: &str,
     query: &str,
@@ -95,132 +246,329 @@ pub fn search_contents(
     case_sensitive: bool,
     cancel: Option<&AtomicBool>,
 ) -> Result<Vec<SearchResult>, String> {
-    let query_match = if case_sensitive {
-        query.to_string()
-    } else {
-        query.to_lowercase()
-    };
+    search_tree(
+        root,
+        Path::new(root),
+        None,
+        query,
+        limit,
+        case_sensitive,
+        cancel,
+    )
+}
 
-    let mut results = Vec::new();
+/// Search file contents in `scope`. Directory and glob scopes narrow the
+/// tree under `root`; the open files scope searches the given buffers.
+pub fn search_contents_in_scope(
+    root: &str,
+    scope: &SearchScope,
+    query: &str,
+    limit: usize,
+    case_sensitive: bool,
+    cancel: Option<&AtomicBool>,
+) -> Result<Vec<SearchResult>, String> {
+    match scope {
+        SearchScope::Workspace => search_contents(root, query, limit, case_sensitive, cancel),
+        SearchScope::OpenFiles { files } => Ok(search_open_files(
+            files,
+            query,
+            limit,
+            case_sensitive,
+            cancel,
+        )),
+        SearchScope::Directory { path } => {
+            if !Path::new(path).is_dir() {
+                return Err(format!("Not a directory: {path}"));
+            }
+            search_tree(
+                root,
+                Path::new(path),
+                None,
+                query,
+                limit,
+                case_sensitive,
+                cancel,
+            )
+        }
+        SearchScope::Glob { pattern } => {
+            let mut builder = ignore::overrides::OverrideBuilder::new(root);
+            let mut any = false;
+            for glob in pattern.split(',').map(str::trim).filter(|g| !g.is_empty()) {
+                builder
+                    .add(glob)
+                    .map_err(|e| format!("Invalid glob \"{glob}\": {e}"))?;
+                any = true;
+            }
+            if !any {
+                return search_contents(root, query, limit, case_sensitive, cancel);
+            }
+            let globs = builder
+                .build()
+                .map_err(|e| format!("Invalid glob pattern: {e}"))?;
+            search_tree(
+                root,
+                Path::new(root),
+                Some(&globs),
+                query,
+                limit,
+                case_sensitive,
+                cancel,
+            )
+        }
+    }
+}
 
-    let walker = WalkBuilder::new(root)
-        .hidden(true)
-        .git_ignore(true)
-        .git_global(true)
-        .git_exclude(true)
-        .max_depth(Some(15))
-        .same_file_system(true)
-        .build();
+/// Search the files under `within`, a directory at or below `root`, that
+/// `globs` don't exclude.
+fn search_tree(
+    root: &str,
+    within: &Path,
+    globs: Option<&ignore::overrides::Override>,
+    query: &str,
+    limit: usize,
+    case_sensitive: bool,
+    cancel: Option<&AtomicBool>,
+) -> Result<Vec<SearchResult>, String> {
+    let content_query = ContentQuery::new(query, case_sensitive);
+    let included = |path: &Path| {
+        path.starts_with(within) && !globs.is_some_and(|g| g.matched(path, false).is_ignore())
+    };
 
-    for entry in walker {
-        if results.len() >= limit {
-            break;
+    // The index covers `root`; a directory outside it is scanned.
+    let candidates = within
+        .starts_with(root)
+        .then(|| crate::search_index::candidate_files(root, query, case_sensitive))
+        .flatten();
+    if let Some(candidates) = candidates {
+        let mut results = Vec::new();
+        for path in candidates {
+            if results.len() >= limit || cancel.is_some_and(|c| c.load(Ordering::Relaxed)) {
+                break;
+            }
+            let path = Path::new(&path);
+            if !included(path) {
+                continue;
+            }
+            let file_name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            results.extend(search_file(
+                path,
+                &file_name,
+                &content_query,
+                limit - results.len(),
+            ));
         }
+        return Ok(results);
+    }
 
-        if cancel.is_some_and(|c| c.load(Ordering::Relaxed)) {
-            break;
-        }
+    let results: Mutex<Vec<SearchResult>> = Mutex::new(Vec::new());
+    let found = AtomicUsize::new(0);
+    let threads = std::thread::available_parallelism()
+        .map_or(1, |n| n.get())
+        .min(MAX_SEARCH_THREADS);
 
-        let entry = match entry {
-            Ok(e) => e,
-            Err(_) => continue,
-        };
+    let mut walk = crate::ignore_rules::search_walk_builder(within);
+    if let Some(globs) = globs {
+        walk.overrides(globs.clone());
+    }
+    walk.max_depth(Some(15))
+        .threads(threads)
+        .build_parallel()
+        .run(|| {
+            Box::new(|entry| {
+                if found.load(Ordering::Relaxed) >= limit
+                    || cancel.is_some_and(|c| c.load(Ordering::Relaxed))
+                {
+                    return WalkState::Quit;
+                }
+                let Ok(entry) = entry else {
+                    return WalkState::Continue;
+                };
+                if !entry.file_type().is_some_and(|ft| ft.is_file()) {
+                    return WalkState::Continue;
+                }
+                let remaining = limit.saturating_sub(found.load(Ordering::Relaxed));
+                let file_name = entry.file_name().to_string_lossy().to_string();
+                let matches = search_file(entry.path(), &file_name, &content_query, remaining);
+                if !matches.is_empty() {
+                    found.fetch_add(matches.len(), Ordering::Relaxed);
+                    results.lock().extend(matches);
+                }
+                WalkState::Continue
+            })
+        });
+
+    let mut results = results.into_inner();
+    results.sort_by(|a, b| {
+        (&a.path, a.line_number, a.column_start).cmp(&(&b.path, b.line_number, b.column_start))
+    });
+    results.truncate(limit);
+    Ok(results)
+}
 
-        if entry.file_type().map(|ft| !ft.is_file()).unwrap_or(true) {
-            continue;
+/// The matches in one file, at most `remaining` (and at most
+/// [`MAX_MATCHES_PER_FILE`]).
+fn search_file(
+    path: &Path,
+    file_name: &str,
+    query: &ContentQuery,
+    remaining: usize,
+) -> Vec<SearchResult> {
+    let limit = remaining.min(MAX_MATCHES_PER_FILE);
+    if limit == 0 {
+        return Vec::new();
+    }
+    let Some(bytes) = load_text_file(path) else {
+        return Vec::new();
+    };
+    search_text(&path.to_string_lossy(), file_name, &bytes, query, limit)
+}
+
+/// Search the open files' buffers, ordered by path like a tree search.
+fn search_open_files(
+    files: &[OpenFile],
+    query: &str,
+    limit: usize,
+    case_sensitive: bool,
+    cancel: Option<&AtomicBool>,
+) -> Vec<SearchResult> {
+    let content_query = ContentQuery::new(query, case_sensitive);
+    let mut files: Vec<&OpenFile> = files.iter().collect();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    files.dedup_by(|a, b| a.path == b.path);
+    let mut results = Vec::new();
+    for file in files {
+        if results.len() >= limit || cancel.is_some_and(|c| c.load(Ordering::Relaxed)) {
+            break;
         }
+        let file_name = Path::new(&file.path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let remaining = (limit - results.len()).min(MAX_MATCHES_PER_FILE);
+        results.extend(search_text(
+            &file.path,
+            &file_name,
+            file.content.as_bytes(),
+            &content_query,
+            remaining,
+        ));
+    }
+    results
+}
 
-        let path = entry.path();
+/// The matches in one file's contents, at most `limit`.
+fn search_text(
+    file_path: &str,
+    file_name: &str,
+    bytes: &[u8],
+    query: &ContentQuery,
+    limit: usize,
+) -> Vec<SearchResult> {
+    let mut results = Vec::new();
+    if limit == 0 || !query.may_match(bytes) {
+        return results;
+    }
 
-        if entry
-            .metadata()
-            .map(|m| m.len() > 1_048_576)
-            .unwrap_or(false)
-        {
-            continue;
+    let match_char_len = query.query.chars().count();
+    // A trailing newline ends the last line rather than starting another,
+    // which would otherwise show up as empty context.
+    let text = bytes.strip_suffix(b"\n").unwrap_or(bytes);
+    let lines: Vec<&[u8]> = text.split(|&b| b == b'\n').collect();
+
+    for (line_idx, line) in lines.iter().enumerate() {
+        if results.len() >= limit {
+            break;
         }
 
-        // Open the file once: check for binary content, then reuse the handle for reading lines.
-        let mut file = match File::open(path) {
-            Ok(f) => f,
-            Err(e) => {
-                log::warn!("Failed to open '{}': {}", path.display(), e);
-                continue;
-            }
+        // Lines that are not valid UTF-8 are skipped, as `BufRead::lines`
+        // would.
+        let Ok(line) = std::str::from_utf8(line) else {
+            continue;
         };
+        let line = line.strip_suffix('\r').unwrap_or(line);
 
-        match check_binary_and_rewind(&mut file) {
-            Ok(true) => continue, // binary file, skip
-            Ok(false) => {}       // text file, proceed
-            Err(e) => {
-                log::warn!("Failed to read '{}': {}", path.display(), e);
-                continue;
-            }
-        }
+        // Avoid allocating a clone for case-sensitive search; borrow directly.
+        let haystack: Cow<str> = if query.case_sensitive {
+            Cow::Borrowed(line)
+        } else {
+            Cow::Owned(line.to_lowercase())
+        };
 
-        let reader = BufReader::new(file);
-        let file_name = entry.file_name().to_string_lossy().to_string();
-        let file_path = path.to_string_lossy().to_string();
+        // Find all matches on this line, not just the first.
+        // Use character-based column positions so that non-ASCII text
+        // (and case-insensitive lowercasing that changes byte lengths)
+        // reports correct columns.
+        let mut line_content: Option<String> = None;
+        let mut context: Option<(Vec<String>, Vec<String>)> = None;
+        let mut prev_byte_pos: usize = 0;
+        let mut prev_char_pos: usize = 0;
 
-        for (line_idx, line) in reader.lines().enumerate() {
+        for (byte_pos, _) in haystack.match_indices(query.query_match.as_str()) {
             if results.len() >= limit {
                 break;
             }
 
-            let line = match line {
-                Ok(l) => l,
-                Err(_) => continue,
-            };
+            // Incrementally compute char offset from the last match position
+            // to avoid O(n) chars().count() from the start on every match.
+            let col_start_chars = prev_char_pos + haystack[prev_byte_pos..byte_pos].chars().count();
+            prev_byte_pos = byte_pos;
+            prev_char_pos = col_start_chars;
+
+            let col_end_chars = col_start_chars + match_char_len;
+
+            // Lazily compute truncated line content and context only when
+            // there's a match.
+            let content = line_content
+                .get_or_insert_with(|| truncate_line(line))
+                .clone();
+            let (context_before, context_after) = context
+                .get_or_insert_with(|| {
+                    let before = line_idx.saturating_sub(CONTEXT_LINES)..line_idx;
+                    let after = line_idx + 1..(line_idx + 1 + CONTEXT_LINES).min(lines.len());
+                    (
+                        lines[before].iter().map(|l| context_line(l)).collect(),
+                        lines[after].iter().map(|l| context_line(l)).collect(),
+                    )
+                })
+                .clone();
+            let match_start = char_to_byte(&content, col_start_chars);
+            let match_end = char_to_byte(&content, col_end_chars);
 
-            // Avoid allocating a clone for case-sensitive search; borrow directly.
-            let haystack: Cow<str> = if case_sensitive {
-                Cow::Borrowed(&line)
-            } else {
-                Cow::Owned(line.to_lowercase())
-            };
-
-            // Find all matches on this line, not just the first.
-            // Use character-based column positions so that non-ASCII text
-            // (and case-insensitive lowercasing that changes byte lengths)
-            // reports correct columns.
-            let match_char_len = query.chars().count();
-            let mut line_content: Option<String> = None;
-            let mut prev_byte_pos: usize = 0;
-            let mut prev_char_pos: usize = 0;
-
-            for (byte_pos, _) in haystack.match_indices(&query_match) {
-                if results.len() >= limit {
-                    break;
-                }
-
-                // Incrementally compute char offset from the last match position
-                // to avoid O(n) chars().count() from the start on every match.
-                let col_start_chars =
-                    prev_char_pos + haystack[prev_byte_pos..byte_pos].chars().count();
-                prev_byte_pos = byte_pos;
-                prev_char_pos = col_start_chars;
-
-                let col_end_chars = col_start_chars + match_char_len;
-
-                // Lazily compute truncated line content only when there's a match.
-                let content = line_content
-                    .get_or_insert_with(|| line.chars().take(500).collect())
-                    .clone();
-
-                results.push(SearchResult {
-                    path: file_path.clone(),
-                    name: file_name.clone(),
-                    line_number: Some((line_idx + 1) as u32),
-                    line_content: Some(content),
-                    column_start: Some(col_start_chars as u32),
-                    column_end: Some(col_end_chars as u32),
-                    match_type: "content".to_string(),
-                });
-            }
+            results.push(SearchResult {
+                path: file_path.to_string(),
+                name: file_name.to_string(),
+                line_number: Some((line_idx + 1) as u32),
+                line_content: Some(content),
+                column_start: Some(col_start_chars as u32),
+                column_end: Some(col_end_chars as u32),
+                match_start: Some(match_start as u32),
+                match_end: Some(match_end as u32),
+                context_before,
+                context_after,
+                match_type: "content".to_string(),
+            });
         }
     }
+    results
+}
 
-    Ok(results)
+fn truncate_line(line: &str) -> String {
+    line.chars().take(MAX_LINE_CHARS).collect()
+}
+
+/// A context line for display; invalid UTF-8 is replaced rather than
+/// skipped so line positions stay right.
+fn context_line(line: &[u8]) -> String {
+    let line = String::from_utf8_lossy(line);
+    truncate_line(line.strip_suffix('\r').unwrap_or(&line))
+}
+
+/// The byte offset of character `chars` in `s`, or `s.len()` past its end.
+fn char_to_byte(s: &str, chars: usize) -> usize {
+    s.char_indices().nth(chars).map_or(s.len(), |(i, _)| i)
 }
 
 /// Search files by name, content, or both.
@@ -392,7 +740,129 @@ pub fn replace_in_files(
 
 #[cfg(test)]
 mod tests {
-    use super::replace_in_file;
+    use super::{
+        replace_in_file, search_contents, search_contents_in_scope, OpenFile, SearchScope,
+        MAX_MATCHES_PER_FILE, MMAP_THRESHOLD,
+    };
+
+    #[test]
+    fn content_matches_carry_context_and_byte_ranges() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        std::fs::write(
+            root.join("notes.txt"),
+            "one\ntwo\r\nthree\nnaïve Needle\nfive\n",
+        )
+        .unwrap();
+
+        let results = search_contents(root.to_str().unwrap(), "needle", 10, false, None).unwrap();
+        assert_eq!(results.len(), 1);
+        let hit = &results[0];
+        assert_eq!(hit.context_before, vec!["two", "three"]);
+        assert_eq!(hit.context_after, vec!["five"]);
+        assert_eq!((hit.column_start, hit.column_end), (Some(6), Some(12)));
+        let content = hit.line_content.as_deref().unwrap();
+        let range = hit.match_start.unwrap() as usize..hit.match_end.unwrap() as usize;
+        assert_eq!(&content[range], "Needle");
+    }
+
+    #[test]
+    fn content_search_is_ordered_capped_and_skips_binaries() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        std::fs::create_dir_all(root.join("src/nested")).unwrap();
+        std::fs::write(root.join("src/b.rs"), "let Needle = 1;\r\nneedle needle\n").unwrap();
+        std::fs::write(root.join("src/nested/a.rs"), "no match\n").unwrap();
+        std::fs::write(root.join("src/blob.bin"), b"needle\0").unwrap();
+        // Large enough to be memory-mapped, with more matches than one file
+        // may report.
+        let mut big = "filler line\n".repeat(MMAP_THRESHOLD as usize / 12);
+        big.push_str(&"NEEDLE\n".repeat(MAX_MATCHES_PER_FILE + 10));
+        std::fs::write(root.join("src/a_big.txt"), big).unwrap();
+
+        let root_str = root.to_str().unwrap();
+        let results = search_contents(root_str, "needle", 500, false, None).unwrap();
+        let big_count = results.iter().filter(|r| r.name == "a_big.txt").count();
+        assert_eq!(big_count, MAX_MATCHES_PER_FILE);
+        assert!(results.iter().all(|r| r.name != "blob.bin"));
+        let b: Vec<_> = results.iter().filter(|r| r.name == "b.rs").collect();
+        assert_eq!(b.len(), 3);
+        assert_eq!(b[0].line_content.as_deref(), Some("let Needle = 1;"));
+        assert_eq!((b[2].line_number, b[2].column_start), (Some(2), Some(7)));
+        assert!(results
+            .windows(2)
+            .all(|w| (&w[0].path, w[0].line_number) <= (&w[1].path, w[1].line_number)));
+
+        let sensitive = search_contents(root_str, "Needle", 500, true, None).unwrap();
+        assert_eq!(sensitive.len(), 1);
+        assert_eq!(
+            search_contents(root_str, "needle", 5, false, None)
+                .unwrap()
+                .len(),
+            5
+        );
+    }
+
+    #[test]
+    fn scoped_search_narrows_files_and_reads_open_buffers() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("src/lib.rs"), "needle\n").unwrap();
+        std::fs::write(root.join("src/lib_test.rs"), "needle\n").unwrap();
+        std::fs::write(root.join("notes.md"), "needle\n").unwrap();
+        let root_str = root.to_str().unwrap();
+        let names = |scope: &SearchScope| -> Vec<String> {
+            search_contents_in_scope(root_str, scope, "needle", 500, false, None)
+                .unwrap()
+                .into_iter()
+                .map(|r| r.name)
+                .collect()
+        };
+
+        assert_eq!(names(&SearchScope::Workspace).len(), 3);
+        let src = root.join("src").to_string_lossy().to_string();
+        assert_eq!(
+            names(&SearchScope::Directory { path: src }),
+            vec!["lib.rs", "lib_test.rs"]
+        );
+        assert_eq!(
+            names(&SearchScope::Glob {
+                pattern: "*.rs, !*_test.rs".to_string()
+            }),
+            vec!["lib.rs"]
+        );
+        assert!(search_contents_in_scope(
+            root_str,
+            &SearchScope::Glob {
+                pattern: "src/[".to_string()
+            },
+            "needle",
+            500,
+            false,
+            None
+        )
+        .is_err());
+
+        // Unsaved buffer contents win over what's on disk.
+        let open = SearchScope::OpenFiles {
+            files: vec![
+                OpenFile {
+                    path: root.join("notes.md").to_string_lossy().to_string(),
+                    content: "no match\n".to_string(),
+                },
+                OpenFile {
+                    path: root.join("new.txt").to_string_lossy().to_string(),
+                    content: "one\nNeedle two\n".to_string(),
+                },
+            ],
+        };
+        let results =
+            search_contents_in_scope(root_str, &open, "needle", 500, false, None).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].name, "new.txt");
+        assert_eq!(results[0].line_number, Some(2));
+    }
 
     #[test]
     fn replace_in_file_rejects_empty_search_string() {
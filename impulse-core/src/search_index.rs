@@ -0,0 +1,524 @@
+//! Trigram index over workspace file contents for [`crate::search`].
+//!
+//! Each indexed file is broken into byte trigrams (ASCII case-folded) with
+//! a posting list of file ids per trigram. A query's trigrams narrow the
+//! search to the files containing all of them, so a repeated search reads a
+//! handful of files instead of walking and reading the whole tree.
+//!
+//! Indexes are built on a background thread when a root is first searched
+//! and kept current by a [`FileWatcher`]. A search only uses an index that
+//! is fully built with no watcher events left to apply; otherwise it falls
+//! back to scanning, so results are never stale. Trees over the size limits
+//! are not indexed at all.
+
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, OnceLock};
+
+use parking_lot::Mutex;
+
+use crate::ignore_rules::{ExcludeScope, IgnoreMatcher, PROJECT_SETTINGS_FILE};
+use crate::watch::{FileWatcher, WatchEvent, WatchEventKind};
+
+/// Same limits as the scanning search.
+const MAX_FILE_SIZE: u64 = 1_048_576;
+const MAX_DEPTH: usize = 15;
+
+/// Give up on indexing trees larger than this.
+const MAX_INDEXED_FILES: usize = 50_000;
+const MAX_INDEXED_BYTES: u64 = 512 * 1024 * 1024;
+
+/// Indexes kept at once; the least recently searched root is dropped.
+const MAX_INDEXED_ROOTS: usize = 4;
+
+/// Rebuild the posting lists once this share of file ids are dead.
+const COMPACT_DEAD_RATIO: usize = 2;
+
+type Trigram = u32;
+
+fn trigram(bytes: &[u8]) -> Trigram {
+    (u32::from(bytes[0].to_ascii_lowercase()) << 16)
+        | (u32::from(bytes[1].to_ascii_lowercase()) << 8)
+        | u32::from(bytes[2].to_ascii_lowercase())
+}
+
+/// The distinct trigrams of `content`, unordered.
+fn content_trigrams(content: &[u8]) -> HashSet<Trigram> {
+    content.windows(3).map(trigram).collect()
+}
+
+/// Trigrams every file matching `query` must contain. Case-insensitive
+/// matching lowercases full Unicode, which ASCII folding cannot mirror, so
+/// trigrams with non-ASCII bytes are then left out.
+fn query_trigrams(query: &str, case_sensitive: bool) -> Vec<Trigram> {
+    let mut trigrams: Vec<Trigram> = query
+        .as_bytes()
+        .windows(3)
+        .filter(|w| case_sensitive || w.is_ascii())
+        .map(trigram)
+        .collect();
+    trigrams.sort_unstable();
+    trigrams.dedup();
+    trigrams
+}
+
+#[derive(Default)]
+struct IndexState {
+    /// Path per file id; `None` once the file changed or went away.
+    files: Vec<Option<String>>,
+    ids: HashMap<String, u32>,
+    /// Sorted file ids per trigram, possibly including dead ids.
+    postings: HashMap<Trigram, Vec<u32>>,
+    dead: usize,
+    bytes: u64,
+    ready: bool,
+    /// The tree exceeded the limits; searches always scan.
+    too_large: bool,
+}
+
+impl IndexState {
+    fn remove(&mut self, path: &str) {
+        if let Some(id) = self.ids.remove(path) {
+            self.files[id as usize] = None;
+            self.dead += 1;
+        }
+    }
+
+    /// Remove every file at or below `path`.
+    fn remove_tree(&mut self, path: &str) {
+        let prefix = format!("{}/", path.trim_end_matches('/'));
+        let doomed: Vec<String> = self
+            .ids
+            .keys()
+            .filter(|p| p.as_str() == path || p.starts_with(&prefix))
+            .cloned()
+            .collect();
+        for path in doomed {
+            self.remove(&path);
+        }
+    }
+
+    fn insert(&mut self, path: String, content: &[u8]) {
+        self.remove(&path);
+        if self.ids.len() >= MAX_INDEXED_FILES
+            || self.bytes + content.len() as u64 > MAX_INDEXED_BYTES
+        {
+            self.too_large = true;
+            return;
+        }
+        let id = self.files.len() as u32;
+        for trigram in content_trigrams(content) {
+            self.postings.entry(trigram).or_default().push(id);
+        }
+        self.bytes += content.len() as u64;
+        self.files.push(Some(path.clone()));
+        self.ids.insert(path, id);
+        if self.dead > 1024 && self.dead * COMPACT_DEAD_RATIO > self.files.len() {
+            self.compact();
+        }
+    }
+
+    /// Drop dead ids from the posting lists.
+    fn compact(&mut self) {
+        let files = &self.files;
+        self.postings.retain(|_, ids| {
+            ids.retain(|&id| files[id as usize].is_some());
+            !ids.is_empty()
+        });
+        self.dead = 0;
+    }
+
+    /// Live files containing every trigram, sorted by path.
+    fn candidates(&self, trigrams: &[Trigram]) -> Vec<String> {
+        let mut lists: Vec<&Vec<u32>> = Vec::with_capacity(trigrams.len());
+        for trigram in trigrams {
+            match self.postings.get(trigram) {
+                Some(ids) => lists.push(ids),
+                None => return Vec::new(),
+            }
+        }
+        lists.sort_by_key(|ids| ids.len());
+        let Some((first, rest)) = lists.split_first() else {
+            return Vec::new();
+        };
+        let mut paths: Vec<String> = first
+            .iter()
+            .filter(|id| rest.iter().all(|ids| ids.binary_search(id).is_ok()))
+            .filter_map(|&id| self.files[id as usize].clone())
+            .collect();
+        paths.sort();
+        paths
+    }
+}
+
+struct Shared {
+    root: PathBuf,
+    state: Mutex<IndexState>,
+    /// Watcher events sent to the worker but not yet applied.
+    pending: AtomicUsize,
+    cancelled: AtomicBool,
+}
+
+impl Shared {
+    /// The contents of `path` if it belongs in the index: a regular,
+    /// non-hidden, non-ignored text file within the size and depth limits.
+    fn indexable(&self, matcher: &IgnoreMatcher, path: &Path) -> Option<Vec<u8>> {
+        let rel = path.strip_prefix(&self.root).ok()?;
+        if rel.components().count() > MAX_DEPTH
+            || rel
+                .components()
+                .any(|c| c.as_os_str().to_string_lossy().starts_with('.'))
+            || matcher.is_ignored(path, false)
+        {
+            return None;
+        }
+        read_text_file(path)
+    }
+
+    fn build(&self) -> IgnoreMatcher {
+        *self.state.lock() = IndexState::default();
+        let walker = crate::ignore_rules::search_walk_builder(&self.root)
+            .max_depth(Some(MAX_DEPTH))
+            .build();
+        for entry in walker {
+            if self.cancelled.load(Ordering::Relaxed) {
+                break;
+            }
+            let Ok(entry) = entry else {
+                continue;
+            };
+            if !entry.file_type().is_some_and(|ft| ft.is_file()) {
+                continue;
+            }
+            let Some(content) = read_text_file(entry.path()) else {
+                continue;
+            };
+            let mut state = self.state.lock();
+            state.insert(entry.path().to_string_lossy().to_string(), &content);
+            if state.too_large {
+                log::info!(
+                    "{} is too large to index; searches will scan it",
+                    self.root.display()
+                );
+                *state = IndexState {
+                    too_large: true,
+                    ..IndexState::default()
+                };
+                break;
+            }
+        }
+        let mut state = self.state.lock();
+        state.ready = !state.too_large && !self.cancelled.load(Ordering::Relaxed);
+        drop(state);
+        IgnoreMatcher::with_scope(&self.root, ExcludeScope::Search)
+    }
+
+    /// Bring the index up to date with one watcher event. Returns a fresh
+    /// matcher when ignore rules changed and the index was rebuilt.
+    fn apply(&self, matcher: &IgnoreMatcher, event: &WatchEvent) -> Option<IgnoreMatcher> {
+        if event.paths.iter().any(|p| {
+            let path = Path::new(p);
+            let name = path.file_name().unwrap_or_default();
+            name == ".gitignore" || name == ".ignore" || path.ends_with(PROJECT_SETTINGS_FILE)
+        }) {
+            return Some(self.build());
+        }
+        if self.state.lock().too_large {
+            return None;
+        }
+        let (removed, updated): (&[String], &[String]) = match event.kind {
+            WatchEventKind::Deleted => (&event.paths, &[]),
+            WatchEventKind::Created | WatchEventKind::Modified => (&[], &event.paths),
+            WatchEventKind::Renamed => event.paths.split_at(1),
+        };
+        for path in removed {
+            self.state.lock().remove_tree(path);
+        }
+        for path in updated {
+            let path = Path::new(path);
+            if path.is_dir() {
+                // A directory moved or checked out in one step reports only
+                // itself; index what is below it.
+                let walker = crate::ignore_rules::search_walk_builder(path).build();
+                for entry in walker.flatten() {
+                    if entry.file_type().is_some_and(|ft| ft.is_file()) {
+                        self.update_file(matcher, entry.path());
+                    }
+                }
+            } else {
+                self.update_file(matcher, path);
+            }
+        }
+        None
+    }
+
+    fn update_file(&self, matcher: &IgnoreMatcher, path: &Path) {
+        let content = self.indexable(matcher, path);
+        let mut state = self.state.lock();
+        let key = path.to_string_lossy().to_string();
+        match content {
+            Some(content) => state.insert(key, &content),
+            None => state.remove(&key),
+        }
+        if state.too_large {
+            *state = IndexState {
+                too_large: true,
+                ..IndexState::default()
+            };
+        }
+    }
+}
+
+/// Read `path` if it is a text file (no NUL in the first 8 KB) of at most
+/// [`MAX_FILE_SIZE`] bytes.
+fn read_text_file(path: &Path) -> Option<Vec<u8>> {
+    let mut file = File::open(path).ok()?;
+    let metadata = file.metadata().ok()?;
+    if !metadata.is_file() || metadata.len() > MAX_FILE_SIZE {
+        return None;
+    }
+    let mut content = Vec::with_capacity(metadata.len() as usize);
+    file.read_to_end(&mut content).ok()?;
+    if content[..content.len().min(8192)].contains(&0) {
+        return None;
+    }
+    Some(content)
+}
+
+/// A live index for one root. Dropping it stops the watcher and worker.
+struct ContentIndex {
+    shared: Arc<Shared>,
+    _watcher: FileWatcher,
+}
+
+impl ContentIndex {
+    fn start(root: &Path) -> Result<Self, String> {
+        let shared = Arc::new(Shared {
+            root: root.to_path_buf(),
+            state: Mutex::new(IndexState::default()),
+            pending: AtomicUsize::new(0),
+            cancelled: AtomicBool::new(false),
+        });
+        let (tx, rx) = mpsc::channel::<WatchEvent>();
+        let watcher_shared = Arc::clone(&shared);
+        let watcher = FileWatcher::new(&root.to_string_lossy(), move |event| {
+            watcher_shared.pending.fetch_add(1, Ordering::SeqCst);
+            if tx.send(event).is_err() {
+                watcher_shared.pending.fetch_sub(1, Ordering::SeqCst);
+            }
+        })?;
+        let worker_shared = Arc::clone(&shared);
+        std::thread::Builder::new()
+            .name("impulse-search-index".to_string())
+            .spawn(move || {
+                let shared = worker_shared;
+                let mut matcher = shared.build();
+                // Ends once the watcher (and with it the sender) is dropped.
+                while let Ok(event) = rx.recv() {
+                    if !shared.cancelled.load(Ordering::Relaxed) {
+                        if let Some(rebuilt) = shared.apply(&matcher, &event) {
+                            matcher = rebuilt;
+                        }
+                    }
+                    shared.pending.fetch_sub(1, Ordering::SeqCst);
+                }
+            })
+            .map_err(|e| format!("Failed to start search indexer: {}", e))?;
+        Ok(Self {
+            shared,
+            _watcher: watcher,
+        })
+    }
+
+    /// Candidate files for `query`, or `None` if the index cannot answer
+    /// right now (still building, behind the watcher, too large) or the
+    /// query is too short to narrow anything down.
+    fn candidates(&self, query: &str, case_sensitive: bool) -> Option<Vec<String>> {
+        let trigrams = query_trigrams(query, case_sensitive);
+        if trigrams.is_empty() || self.shared.pending.load(Ordering::SeqCst) > 0 {
+            return None;
+        }
+        let state = self.shared.state.lock();
+        if !state.ready {
+            return None;
+        }
+        Some(state.candidates(&trigrams))
+    }
+}
+
+impl Drop for ContentIndex {
+    fn drop(&mut self) {
+        self.shared.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+#[derive(Default)]
+struct Registry {
+    enabled: bool,
+    /// Most recently searched last.
+    indexes: Vec<(PathBuf, Arc<ContentIndex>)>,
+}
+
+fn registry() -> &'static Mutex<Registry> {
+    static REGISTRY: OnceLock<Mutex<Registry>> = OnceLock::new();
+    REGISTRY.get_or_init(|| Mutex::new(Registry::default()))
+}
+
+/// Turn content indexing on or off. Off drops every index; on starts
+/// indexing each root the first time it is searched.
+pub fn set_content_index_enabled(enabled: bool) {
+    let mut registry = registry().lock();
+    registry.enabled = enabled;
+    if !enabled {
+        registry.indexes.clear();
+    }
+}
+
+/// Drop every index, e.g. because exclude settings changed; each root is
+/// indexed again the next time it is searched.
+pub(crate) fn clear_indexes() {
+    registry().lock().indexes.clear();
+}
+
+/// Candidate files under `root` for a content search, or `None` to scan.
+/// Starts indexing `root` if indexing is enabled and it has no index yet.
+pub(crate) fn candidate_files(
+    root: &str,
+    query: &str,
+    case_sensitive: bool,
+) -> Option<Vec<String>> {
+    let root = Path::new(root);
+    let index = {
+        let mut registry = registry().lock();
+        if !registry.enabled {
+            return None;
+        }
+        match registry.indexes.iter().position(|(r, _)| r == root) {
+            Some(i) => {
+                let entry = registry.indexes.remove(i);
+                let index = Arc::clone(&entry.1);
+                registry.indexes.push(entry);
+                index
+            }
+            None => {
+                if !root.is_dir() {
+                    return None;
+                }
+                match ContentIndex::start(root) {
+                    Ok(index) => {
+                        if registry.indexes.len() >= MAX_INDEXED_ROOTS {
+                            registry.indexes.remove(0);
+                        }
+                        registry.indexes.push((root.to_path_buf(), Arc::new(index)));
+                    }
+                    Err(e) => log::warn!("Content index unavailable for {}: {}", root.display(), e),
+                }
+                return None;
+            }
+        }
+    };
+    index.candidates(query, case_sensitive)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index_of(files: &[(&str, &str)]) -> IndexState {
+        let mut state = IndexState::default();
+        for (path, content) in files {
+            state.insert(path.to_string(), content.as_bytes());
+        }
+        state
+    }
+
+    #[test]
+    fn candidates_contain_every_query_trigram() {
+        let mut state = index_of(&[
+            ("/r/b.rs", "fn parse_config() {}"),
+            ("/r/a.rs", "let CONFIG = load();"),
+            ("/r/c.rs", "fn main() {}"),
+        ]);
+        let find = |state: &IndexState, query: &str, case_sensitive: bool| {
+            state.candidates(&query_trigrams(query, case_sensitive))
+        };
+        assert_eq!(find(&state, "config", false), ["/r/a.rs", "/r/b.rs"]);
+        assert_eq!(find(&state, "parse_c", false), ["/r/b.rs"]);
+        assert!(find(&state, "missing", false).is_empty());
+
+        state.insert("/r/c.rs".to_string(), b"fn main() { config(); }");
+        state.remove("/r/a.rs");
+        assert_eq!(find(&state, "config", true), ["/r/b.rs", "/r/c.rs"]);
+        state.compact();
+        assert_eq!(state.dead, 0);
+        assert_eq!(find(&state, "config", true), ["/r/b.rs", "/r/c.rs"]);
+
+        state.remove_tree("/r");
+        assert!(find(&state, "config", true).is_empty());
+    }
+
+    #[test]
+    fn case_insensitive_queries_skip_non_ascii_trigrams() {
+        assert_eq!(query_trigrams("ab", false), Vec::<Trigram>::new());
+        assert_eq!(query_trigrams("ÉtÉ", false), Vec::<Trigram>::new());
+        assert_eq!(query_trigrams("ÉtÉ", true).len(), 3);
+        assert_eq!(query_trigrams("ABCabc", false).len(), 3);
+    }
+
+    #[test]
+    fn index_follows_the_watcher_and_ignore_rules() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        std::fs::create_dir(root.join(".git")).unwrap();
+        std::fs::write(root.join(".gitignore"), "target/\n").unwrap();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::create_dir_all(root.join("target")).unwrap();
+        std::fs::write(root.join("src/lib.rs"), "pub fn needle() {}\n").unwrap();
+        std::fs::write(root.join("target/out.rs"), "needle\n").unwrap();
+        std::fs::write(root.join("src/blob.bin"), b"needle\0").unwrap();
+
+        let shared = Shared {
+            root: root.to_path_buf(),
+            state: Mutex::new(IndexState::default()),
+            pending: AtomicUsize::new(0),
+            cancelled: AtomicBool::new(false),
+        };
+        let matcher = shared.build();
+        let trigrams = query_trigrams("needle", false);
+        let lib = root.join("src/lib.rs").to_string_lossy().to_string();
+        assert_eq!(shared.state.lock().candidates(&trigrams), vec![lib.clone()]);
+
+        let added = root.join("src/new.rs").to_string_lossy().to_string();
+        std::fs::write(&added, "needle\n").unwrap();
+        std::fs::write(&lib, "pub fn other() {}\n").unwrap();
+        for path in [&added, &lib] {
+            shared.apply(
+                &matcher,
+                &WatchEvent {
+                    kind: WatchEventKind::Modified,
+                    paths: vec![path.clone()],
+                },
+            );
+        }
+        shared.apply(
+            &matcher,
+            &WatchEvent {
+                kind: WatchEventKind::Created,
+                paths: vec![root.join("target/more.rs").to_string_lossy().to_string()],
+            },
+        );
+        assert_eq!(shared.state.lock().candidates(&trigrams), vec![added]);
+
+        shared.apply(
+            &matcher,
+            &WatchEvent {
+                kind: WatchEventKind::Deleted,
+                paths: vec![root.join("src").to_string_lossy().to_string()],
+            },
+        );
+        assert!(shared.state.lock().candidates(&trigrams).is_empty());
+    }
+}
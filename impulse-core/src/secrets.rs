@@ -0,0 +1,331 @@
+//! Secrets (access tokens, API keys, passwords) in the system keyring: the
+//! Secret Service (GNOME Keyring, KWallet) on Linux and the Keychain on
+//! macOS. Values never touch disk.
+//!
+//! Keyrings can't be listed portably, so `<config dir>/impulse/secrets.json`
+//! records what is stored (ids and when, never values) for Settings to
+//! review and delete.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+/// Keyring service every secret is stored under.
+const KEYRING_SERVICE: &str = "impulse";
+
+const INDEX_FILE: &str = "secrets.json";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SecretKind {
+    /// A token for git over HTTPS, named `user@host`.
+    GitCredential,
+    /// An assistant provider's API key, named by the provider id.
+    AssistantKey,
+    /// A password for a remote host, named `user@host`.
+    RemotePassword,
+    /// The passphrase of an SSH private key, named by the key's path.
+    SshPassphrase,
+}
+
+const KINDS: &[SecretKind] = &[
+    SecretKind::GitCredential,
+    SecretKind::AssistantKey,
+    SecretKind::RemotePassword,
+    SecretKind::SshPassphrase,
+];
+
+impl SecretKind {
+    /// Prefix of the keyring account names; `assistant-` matches the keys
+    /// the assistant stored before this module existed.
+    fn prefix(self) -> &'static str {
+        match self {
+            SecretKind::GitCredential => "git-",
+            SecretKind::AssistantKey => "assistant-",
+            SecretKind::RemotePassword => "remote-",
+            SecretKind::SshPassphrase => "ssh-",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SecretKind::GitCredential => "Git HTTPS Token",
+            SecretKind::AssistantKey => "Assistant API Key",
+            SecretKind::RemotePassword => "Remote Host Password",
+            SecretKind::SshPassphrase => "SSH Key Passphrase",
+        }
+    }
+}
+
+/// The keyring account a secret is stored under.
+pub fn secret_id(kind: SecretKind, name: &str) -> String {
+    format!("{}{}", kind.prefix(), name)
+}
+
+/// The kind and name of the secret stored under `id`.
+fn parse_id(id: &str) -> Option<(SecretKind, &str)> {
+    KINDS.iter().find_map(|&kind| {
+        id.strip_prefix(kind.prefix())
+            .filter(|name| !name.is_empty())
+            .map(|name| (kind, name))
+    })
+}
+
+/// A stored secret, without its value.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SecretInfo {
+    pub id: String,
+    pub kind: SecretKind,
+    /// What the secret is for: a provider id, or `user@host`.
+    pub name: String,
+    /// Unix seconds.
+    pub updated_at: u64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+struct SecretIndex {
+    secrets: Vec<SecretInfo>,
+}
+
+impl SecretIndex {
+    /// Record that `id` was stored at `updated_at`.
+    fn record(&mut self, id: &str, updated_at: u64) {
+        let Some((kind, name)) = parse_id(id) else {
+            return;
+        };
+        self.secrets.retain(|s| s.id != id);
+        self.secrets.push(SecretInfo {
+            id: id.to_string(),
+            kind,
+            name: name.to_string(),
+            updated_at,
+        });
+        self.secrets
+            .sort_by(|a, b| (a.kind, &a.name).cmp(&(b.kind, &b.name)));
+    }
+
+    fn remove(&mut self, id: &str) {
+        self.secrets.retain(|s| s.id != id);
+    }
+
+    fn contains(&self, id: &str) -> bool {
+        self.secrets.iter().any(|s| s.id == id)
+    }
+
+    /// The stored git credential for `host`: the one for `username` when
+    /// given, otherwise the first.
+    fn git_credential_for(&self, host: &str, username: Option<&str>) -> Option<&SecretInfo> {
+        let mut candidates = self.secrets.iter().filter(|s| {
+            s.kind == SecretKind::GitCredential
+                && s.name.rsplit_once('@').is_some_and(|(_, h)| h == host)
+        });
+        match username {
+            Some(user) => candidates.find(|s| s.name == format!("{}@{}", user, host)),
+            None => candidates.next(),
+        }
+    }
+}
+
+fn index_path() -> Option<std::path::PathBuf> {
+    Some(crate::settings::settings_path()?.with_file_name(INDEX_FILE))
+}
+
+fn load_index() -> SecretIndex {
+    let Some(path) = index_path() else {
+        return SecretIndex::default();
+    };
+    match std::fs::read_to_string(&path) {
+        Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+            log::warn!("Invalid secrets index at {}: {}", path.display(), e);
+            SecretIndex::default()
+        }),
+        Err(_) => SecretIndex::default(),
+    }
+}
+
+fn save_index(index: &SecretIndex) -> Result<(), String> {
+    let path = index_path().ok_or("No config directory for the secrets index")?;
+    let json = serde_json::to_string_pretty(index)
+        .map_err(|e| format!("Failed to serialize secrets index: {}", e))?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, json)
+        .and_then(|()| std::fs::rename(&tmp, &path))
+        .map_err(|e| format!("Failed to save {}: {}", path.display(), e))
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn entry(id: &str) -> Result<keyring::Entry, String> {
+    keyring::Entry::new(KEYRING_SERVICE, id).map_err(|e| format!("Keyring unavailable: {}", e))
+}
+
+/// The secret of `kind` named `name`, if one is stored.
+pub fn get(kind: SecretKind, name: &str) -> Option<String> {
+    let id = secret_id(kind, name);
+    let value = match entry(&id).and_then(|entry| {
+        entry
+            .get_password()
+            .map_err(|e| format!("Failed to read {}: {}", id, e))
+    }) {
+        Ok(value) if !value.is_empty() => value,
+        Ok(_) => return None,
+        Err(e) => {
+            log::debug!("No secret {}: {}", id, e);
+            return None;
+        }
+    };
+    // Stored by an older version, before the index.
+    let mut index = load_index();
+    if !index.contains(&id) {
+        index.record(&id, now());
+        if let Err(e) = save_index(&index) {
+            log::warn!("{}", e);
+        }
+    }
+    Some(value)
+}
+
+/// Store `value` as the secret of `kind` named `name`, replacing any
+/// earlier one. An empty value deletes it.
+pub fn set(kind: SecretKind, name: &str, value: &str) -> Result<(), String> {
+    let id = secret_id(kind, name);
+    if value.is_empty() {
+        return delete(&id);
+    }
+    if name.trim().is_empty() {
+        return Err("The secret needs a name".to_string());
+    }
+    entry(&id)?
+        .set_password(value)
+        .map_err(|e| format!("Failed to store the secret: {}", e))?;
+    let mut index = load_index();
+    index.record(&id, now());
+    save_index(&index)
+}
+
+/// Remove the secret stored under `id` (see [`SecretInfo::id`]).
+pub fn delete(id: &str) -> Result<(), String> {
+    match entry(id)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => {}
+        Err(e) => return Err(format!("Failed to remove the secret: {}", e)),
+    }
+    let mut index = load_index();
+    if index.contains(id) {
+        index.remove(id);
+        save_index(&index)?;
+    }
+    Ok(())
+}
+
+/// Every stored secret, by kind and name.
+pub fn list() -> Vec<SecretInfo> {
+    load_index().secrets
+}
+
+/// The host (with any port) of an HTTPS remote URL.
+fn url_host(url: &str) -> Option<String> {
+    let url = url::Url::parse(url).ok()?;
+    let host = url.host_str()?;
+    Some(match url.port() {
+        Some(port) => format!("{}:{}", host, port),
+        None => host.to_string(),
+    })
+}
+
+/// The username and token stored for git over HTTPS to `url`, preferring
+/// the URL's own username.
+pub fn git_credential(url: &str, username: Option<&str>) -> Option<(String, String)> {
+    let host = url_host(url)?;
+    let index = load_index();
+    let info = index.git_credential_for(&host, username)?;
+    let user = info.name.rsplit_once('@')?.0.to_string();
+    let token = get(SecretKind::GitCredential, &info.name)?;
+    Some((user, token))
+}
+
+/// Store `token` for git over HTTPS to `url` as `username`, for
+/// [`git_credential`] to find next time.
+pub fn set_git_credential(url: &str, username: &str, token: &str) -> Result<(), String> {
+    let host = url_host(url).ok_or_else(|| format!("No host in {}", url))?;
+    set(
+        SecretKind::GitCredential,
+        &format!("{}@{}", username, host),
+        token,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ids_round_trip_through_kinds() {
+        let id = secret_id(SecretKind::GitCredential, "me@github.com");
+        assert_eq!(id, "git-me@github.com");
+        assert_eq!(
+            parse_id(&id),
+            Some((SecretKind::GitCredential, "me@github.com"))
+        );
+        // The assistant's keys keep the account names they always had.
+        assert_eq!(
+            secret_id(SecretKind::AssistantKey, "openai"),
+            "assistant-openai"
+        );
+        assert_eq!(parse_id("remote-"), None);
+        assert_eq!(parse_id("other"), None);
+    }
+
+    #[test]
+    fn the_index_tracks_what_is_stored() {
+        let mut index = SecretIndex::default();
+        index.record("remote-root@box", 3);
+        index.record("git-me@github.com", 1);
+        index.record("git-bot@github.com", 2);
+        index.record("git-me@github.com", 4);
+        index.record("bogus", 5);
+        let ids: Vec<&str> = index.secrets.iter().map(|s| s.id.as_str()).collect();
+        assert_eq!(
+            ids,
+            ["git-bot@github.com", "git-me@github.com", "remote-root@box"]
+        );
+        assert_eq!(index.secrets[1].updated_at, 4);
+
+        assert_eq!(
+            index
+                .git_credential_for("github.com", Some("me"))
+                .map(|s| s.name.as_str()),
+            Some("me@github.com")
+        );
+        assert_eq!(
+            index
+                .git_credential_for("github.com", None)
+                .map(|s| s.name.as_str()),
+            Some("bot@github.com")
+        );
+        assert!(index.git_credential_for("gitlab.com", None).is_none());
+        assert!(index.git_credential_for("box", None).is_none());
+
+        index.remove("git-bot@github.com");
+        assert!(!index.contains("git-bot@github.com"));
+        assert_eq!(index.secrets.len(), 2);
+    }
+
+    #[test]
+    fn finds_the_host_of_remote_urls() {
+        assert_eq!(
+            url_host("https://me@github.com/owner/repo.git").as_deref(),
+            Some("github.com")
+        );
+        assert_eq!(
+            url_host("https://git.example.com:8443/repo").as_deref(),
+            Some("git.example.com:8443")
+        );
+        assert_eq!(url_host("not a url"), None);
+    }
+}
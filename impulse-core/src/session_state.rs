@@ -21,6 +21,16 @@ pub struct SessionWindow {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub active_tab_index: Option<usize>,
     pub layout: SessionLayout,
+    /// Sidebar visibility and width, kept by saved window layouts.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sidebar: Option<SessionSidebar>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize, JsonSchema)]
+#[serde(default)]
+pub struct SessionSidebar {
+    pub visible: bool,
+    pub width: i32,
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize, JsonSchema)]
@@ -423,6 +433,10 @@ mod tests {
                     tab_indices: vec![0, 1],
                     active_tab_index: Some(1),
                 }),
+                sidebar: Some(SessionSidebar {
+                    visible: true,
+                    width: 280,
+                }),
             }],
         };
 
@@ -510,6 +524,7 @@ mod tests {
                         active_tab_index: Some(99),
                     })),
                 }),
+                sidebar: None,
             }],
         };
 
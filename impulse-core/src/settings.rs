@@ -1,6 +1,9 @@
+use parking_lot::Mutex;
 use schemars::{schema_for, JsonSchema};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 /// A formatter command that runs on save before the editor reloads the file.
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -10,6 +13,20 @@ pub struct FormatOnSave {
     pub args: Vec<String>,
 }
 
+/// An external formatter that reads a document on stdin and writes the
+/// formatted document to stdout. `{file}` in `args` is replaced with the
+/// document's path.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ExternalFormatter {
+    pub name: String,
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Language ids the formatter handles, e.g. `typescript` or `python`.
+    #[serde(default)]
+    pub languages: Vec<String>,
+}
+
 /// Per-file-type overrides for editor settings (tab width, spaces, formatter).
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct FileTypeOverride {
@@ -22,6 +39,16 @@ pub struct FileTypeOverride {
     pub format_on_save: Option<FormatOnSave>,
 }
 
+/// Starting content for new files matching `pattern`. `${name}` and
+/// `${filename}` in `content` become the new file's name without and with
+/// its extension.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Default)]
+pub struct FileTemplate {
+    pub pattern: String,
+    #[serde(default)]
+    pub content: String,
+}
+
 /// A command that runs automatically when a file matching the pattern is saved.
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
 pub struct CommandOnSave {
@@ -35,7 +62,7 @@ pub struct CommandOnSave {
 }
 
 /// A user-defined keybinding that runs a command.
-#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Default)]
 pub struct CustomKeybinding {
     pub name: String,
     pub key: String,
@@ -44,6 +71,51 @@ pub struct CustomKeybinding {
     pub args: Vec<String>,
 }
 
+/// Debounce intervals in milliseconds for LSP requests where only the latest
+/// one matters. A newer request of the same kind drops an older one that is
+/// still waiting and cancels one already sent; 0 sends immediately but still
+/// cancels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(default)]
+pub struct LspDebounce {
+    pub completion_ms: u64,
+    pub hover_ms: u64,
+    pub signature_help_ms: u64,
+    pub code_action_ms: u64,
+}
+
+impl Default for LspDebounce {
+    fn default() -> Self {
+        Self {
+            completion_ms: 30,
+            hover_ms: 50,
+            signature_help_ms: 30,
+            code_action_ms: 100,
+        }
+    }
+}
+
+impl LspDebounce {
+    /// Cap the intervals at 2s.
+    pub fn validate(&mut self) {
+        for ms in [
+            &mut self.completion_ms,
+            &mut self.hover_ms,
+            &mut self.signature_help_ms,
+            &mut self.code_action_ms,
+        ] {
+            *ms = (*ms).min(2000);
+        }
+    }
+}
+
+/// Current settings schema version. Bump it and append to [`MIGRATIONS`]
+/// whenever existing files need rewriting to keep their meaning.
+pub const SETTINGS_VERSION: u32 = 1;
+
+/// Forward migrations: `MIGRATIONS[n]` upgrades a version `n` file to `n + 1`.
+const MIGRATIONS: &[fn(&mut Settings)] = &[Settings::migrate_v0];
+
 /// Application settings shared across all frontends.
 ///
 /// The `#[serde(default)]` on the struct ensures that any fields missing from
@@ -52,18 +124,44 @@ pub struct CustomKeybinding {
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct Settings {
+    /// Schema version the file was last migrated to. Files written before
+    /// versioning have no key and count as version 0.
+    #[serde(default)]
+    pub version: u32,
+
     // ── Window ───────────────────────────────────────────────────────────
     pub window_width: i32,
     pub window_height: i32,
     pub sidebar_visible: bool,
     pub sidebar_width: i32,
+    /// Maximum width in pixels of the centered content area in Zen mode.
+    pub zen_mode_max_width: i32,
     pub confirm_close_warnings: bool,
     pub restore_session: bool,
+    /// Files given on the command line (`impulse file.rs`) open in a new
+    /// window instead of the active one. `--new-window` forces it per call.
+    pub cli_open_in_new_window: bool,
+    /// Listen on the control socket (see `control`) so scripts and tools
+    /// can open files and run commands in the running editor.
+    pub control_socket_enabled: bool,
     pub last_directory: String,
     pub open_files: Vec<String>,
 
     // ── Editor ───────────────────────────────────────────────────────────
     pub auto_save: bool,
+    /// How files are saved (see `safe_write`): "atomic" writes a temporary
+    /// file and renames it over the original; "in_place" overwrites the
+    /// original, for file systems and tools that track inodes.
+    pub save_strategy: String,
+    /// Back up unsaved buffers every few seconds and offer to restore them
+    /// after a crash (see `hot_exit`).
+    pub hot_exit: bool,
+    /// Strip spaces and tabs at the ends of lines when saving. An
+    /// `.editorconfig` `trim_trailing_whitespace` overrides it per file.
+    pub trim_trailing_whitespace: bool,
+    /// End saved files with a newline. An `.editorconfig`
+    /// `insert_final_newline` overrides it per file.
+    pub insert_final_newline: bool,
     pub font_size: i32,
     pub font_family: String,
     pub tab_width: u32,
@@ -94,11 +192,24 @@ pub struct Settings {
     pub terminal_font_family: String,
     pub terminal_font_size: i32,
     pub terminal_copy_on_select: bool,
+    /// Preview pastes of several lines or `sudo` commands before they reach
+    /// the shell (see `paste_guard`).
+    pub terminal_confirm_paste: bool,
+    /// Where new terminal tabs start: "home", "workspace" (the sidebar's
+    /// folder) or "current" (the active tab's directory). See
+    /// `shell::new_terminal_directory`.
+    pub terminal_new_tab_directory: String,
     pub terminal_scroll_on_output: bool,
     pub terminal_allow_hyperlink: bool,
     pub terminal_allow_notifications: bool,
     pub terminal_attention_on_long_command: bool,
     pub terminal_long_command_seconds: i32,
+    /// Ask before closing a terminal tab or window whose shell is running
+    /// something (needs `confirm_close_warnings` on as well).
+    pub terminal_confirm_close: bool,
+    /// Programs that never make closing a terminal ask, e.g. `less`; see
+    /// `close_risk::is_ignored_command`.
+    pub terminal_close_ignored_commands: Vec<String>,
     pub terminal_bold_is_bright: bool,
     /// Minimum WCAG contrast ratio (1.0–21.0) enforced between terminal cell
     /// foreground and background at render time; 1.0 disables the adjustment.
@@ -109,11 +220,29 @@ pub struct Settings {
     /// Show the context bar (shell, cwd, git branch, last command status)
     /// below the terminal.
     pub terminal_context_bar: bool,
+    /// Overrides for the 16-color ANSI palette, black through white then the
+    /// bright variants. Empty or invalid entries keep the theme's color.
+    pub terminal_palette: Vec<String>,
+    /// Cursor color (`#rrggbb`); empty uses the theme's foreground.
+    pub terminal_cursor_color: String,
+    /// Opacity of the terminal background (0.3–1.0). Below 1.0 whatever is
+    /// behind the terminal shows through.
+    pub terminal_background_opacity: f64,
 
     // ── Tabs ─────────────────────────────────────────────────────────────
     /// Where the tab strip lives: "sidebar" (Warp-style vertical list) or
     /// "top" (classic horizontal bar).
     pub tab_bar_position: String,
+    /// Single-clicking a file in the sidebar opens it in a preview tab that
+    /// the next single-click replaces, until it is edited or double-clicked.
+    pub preview_tabs: bool,
+
+    // ── Status bar ───────────────────────────────────────────────────────
+    /// Segment IDs in display order; segments not listed follow in their
+    /// default order. See `status_bar::STATUS_BAR_SEGMENTS`.
+    pub status_bar_segments: Vec<String>,
+    /// Segment IDs the user turned off.
+    pub status_bar_hidden_segments: Vec<String>,
 
     // ── Editor (additional) ──────────────────────────────────────────────
     pub editor_line_height: u32,
@@ -125,6 +254,11 @@ pub struct Settings {
 
     // ── Sidebar ────────────────────────────────────────────────────────
     pub sidebar_show_hidden: bool,
+    /// Hide gitignored entries in the file tree instead of dimming them.
+    pub sidebar_hide_ignored: bool,
+    /// Switching tabs expands the file tree to the active editor's file,
+    /// selects it and scrolls it into view.
+    pub sidebar_auto_reveal: bool,
     /// Height in px of the sidebar's vertical tab section. 0 = auto (size to
     /// the tab count, capped so the file tree keeps most of the sidebar).
     /// Set by dragging the divider under the tab list.
@@ -133,37 +267,148 @@ pub struct Settings {
 
     // ── Appearance ───────────────────────────────────────────────────────
     pub color_scheme: String,
+    /// Switch between `light_color_scheme` and `dark_color_scheme` with the
+    /// system appearance instead of always using `color_scheme`.
+    pub follow_system_appearance: bool,
+    pub light_color_scheme: String,
+    pub dark_color_scheme: String,
+    /// Use the theme's increased-contrast variant
+    /// (`ResolvedTheme::with_increased_contrast`). Frontends also use it
+    /// when the system's high contrast / increase contrast option is on.
+    pub increase_contrast: bool,
 
     // ── Custom commands ──────────────────────────────────────────────────
     pub commands_on_save: Vec<CommandOnSave>,
+    /// Load a trusted folder's `.envrc` through direnv for the language
+    /// servers, formatters and commands started in it.
+    pub use_direnv: bool,
     pub custom_keybindings: Vec<CustomKeybinding>,
 
     // ── Keybinding overrides ─────────────────────────────────────────────
+    /// Keymap preset (`crate::keymap::KEYMAP_PRESETS`) layered under
+    /// `keybinding_overrides`.
+    pub keymap_preset: String,
     #[serde(default)]
     pub keybinding_overrides: HashMap<String, String>,
 
     // ── Per-file-type overrides ───────────────────────────────────────────
     pub file_type_overrides: Vec<FileTypeOverride>,
+    /// Language id per `*.ext` pattern (or file name for extensionless
+    /// files), chosen from the status bar's language menu. See `languages`.
+    pub language_overrides: HashMap<String, String>,
+    /// Boilerplate for files created from the sidebar, first match wins.
+    pub file_templates: Vec<FileTemplate>,
 
     // ── Updates ──────────────────────────────────────────────────────────
     pub check_for_updates: bool,
+    /// Download and install updates found by the launch check; they take
+    /// effect on the next launch. Only for installs Impulse can update
+    /// itself (a macOS app bundle or a standalone Linux binary).
+    pub auto_install_updates: bool,
+
+    // ── Workspace trust ──────────────────────────────────────────────────
+    /// Open folders in restricted mode until the user trusts them (see
+    /// `trust`). Off, every folder is trusted.
+    pub workspace_trust_enabled: bool,
+
+    // ── Crash reports ────────────────────────────────────────────────────
+    /// Submit crash reports from the previous session without asking. Off
+    /// by default; reports are always kept locally (see `crash_report`).
+    pub send_crash_reports: bool,
+    /// Where crash reports are POSTed as JSON. Empty disables sending.
+    pub crash_report_endpoint: String,
+
+    // ── Settings sync ────────────────────────────────────────────────────
+    /// Git repository the configuration is synced with at launch and on
+    /// "Sync Settings" (see `config_sync`). Empty disables sync.
+    pub settings_sync_repository: String,
+
+    // ── Git hosting ──────────────────────────────────────────────────────
+    /// Show the GitHub/GitLab CI status of HEAD in the status bar.
+    pub show_ci_status: bool,
+
+    // ── Commits ──────────────────────────────────────────────────────────
+    /// Lint commit messages as Conventional Commits (`type(scope): subject`).
+    pub conventional_commits: bool,
+
+    // ── Assistant ────────────────────────────────────────────────────────
+    /// Turn on the assistant (chat, "explain selection", commit messages).
+    /// Off by default; nothing is sent anywhere until it is enabled.
+    pub assistant_enabled: bool,
+    /// Backend: `"openai"` (any OpenAI-compatible API) or `"ollama"`.
+    pub assistant_provider: String,
+    /// Base URL of the API. Empty uses the provider's default.
+    pub assistant_endpoint: String,
+    /// Model name. Empty uses the provider's default.
+    pub assistant_model: String,
+    /// Let coding agents connect over MCP (see `mcp`). Each workspace is
+    /// still shared only after the user allows it.
+    pub agent_access_enabled: bool,
+
+    // ── Language servers ─────────────────────────────────────────────────
+    pub lsp_debounce: LspDebounce,
+    /// Code action kinds applied before a file is saved, in order, e.g.
+    /// `source.organizeImports` or `source.fixAll`.
+    pub code_actions_on_save: Vec<String>,
+    /// Server ids (as in lsp.json) that are never started, and whose managed
+    /// npm packages are not installed.
+    pub disabled_lsp_servers: Vec<String>,
+
+    // ── Search ───────────────────────────────────────────────────────────
+    /// Keep an in-memory trigram index of searched workspaces so repeated
+    /// content searches skip files that cannot match.
+    pub search_index_enabled: bool,
+    /// Gitignore-style globs hidden from the file tree, watchers and
+    /// search. Projects add their own in `.impulse/settings.json`.
+    pub files_exclude: Vec<String>,
+    /// Gitignore-style globs left out of file name and content search only.
+    pub search_exclude: Vec<String>,
+    /// Search, indexing and file watching descend into symlinked folders.
+    /// Symlink cycles are detected and not followed.
+    pub follow_symlinks: bool,
+    /// Search, indexing and file watching descend into network mounts (NFS,
+    /// SMB, SSHFS, ...) inside the workspace.
+    pub scan_network_mounts: bool,
+
+    // ── Formatting ───────────────────────────────────────────────────────
+    /// External formatters added to the built-in ones (prettier, rustfmt,
+    /// black, ...); an entry with a built-in's name replaces it.
+    pub formatters: Vec<ExternalFormatter>,
+    /// Formatter per language id: a formatter name, or `"lsp"` for language
+    /// servers only. Languages without an entry use their language server,
+    /// falling back to an installed external formatter.
+    pub default_formatters: HashMap<String, String>,
+
+    /// Keys this build does not know, e.g. written by a newer version.
+    /// Carried through unchanged so saving never drops them.
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
 }
 
 impl Default for Settings {
     fn default() -> Self {
         Settings {
+            version: SETTINGS_VERSION,
+
             // Window
             window_width: 1200,
             window_height: 800,
             sidebar_visible: false,
             sidebar_width: 250,
+            zen_mode_max_width: 960,
             confirm_close_warnings: true,
             restore_session: false,
+            cli_open_in_new_window: false,
+            control_socket_enabled: true,
             last_directory: String::new(),
             open_files: Vec::new(),
 
             // Editor
             auto_save: false,
+            save_strategy: String::from(crate::safe_write::STRATEGY_ATOMIC),
+            hot_exit: true,
+            trim_trailing_whitespace: false,
+            insert_final_newline: false,
             font_size: 14,
             font_family: String::from("JetBrains Mono"),
             tab_width: 4,
@@ -194,18 +439,33 @@ impl Default for Settings {
             terminal_font_family: String::from("JetBrains Mono"),
             terminal_font_size: 14,
             terminal_copy_on_select: true,
+            terminal_confirm_paste: true,
+            terminal_new_tab_directory: String::from(crate::shell::NEW_TAB_DIRECTORY_HOME),
             terminal_scroll_on_output: false,
             terminal_allow_hyperlink: true,
             terminal_allow_notifications: true,
             terminal_attention_on_long_command: true,
             terminal_long_command_seconds: 30,
+            terminal_confirm_close: true,
+            terminal_close_ignored_commands: crate::close_risk::DEFAULT_IGNORED_COMMANDS
+                .iter()
+                .map(|c| c.to_string())
+                .collect(),
             terminal_bold_is_bright: true,
             terminal_minimum_contrast: 3.0,
             terminal_blocks: true,
             terminal_context_bar: true,
+            terminal_palette: Vec::new(),
+            terminal_cursor_color: String::new(),
+            terminal_background_opacity: 1.0,
 
             // Tabs
             tab_bar_position: String::from("sidebar"),
+            preview_tabs: true,
+
+            // Status bar
+            status_bar_segments: Vec::new(),
+            status_bar_hidden_segments: Vec::new(),
 
             // Editor (additional)
             editor_line_height: 0,
@@ -217,23 +477,75 @@ impl Default for Settings {
 
             // Sidebar
             sidebar_show_hidden: false,
+            sidebar_hide_ignored: false,
+            sidebar_auto_reveal: false,
             sidebar_tab_section_height: 0,
 
             // Appearance
             color_scheme: String::from("nord"),
+            follow_system_appearance: false,
+            light_color_scheme: String::from("github-light"),
+            dark_color_scheme: String::from("nord"),
+            increase_contrast: false,
 
             // Custom commands
             commands_on_save: Vec::new(),
+            use_direnv: false,
             custom_keybindings: Vec::new(),
 
             // Keybinding overrides
+            keymap_preset: String::from(crate::keymap::DEFAULT_PRESET),
             keybinding_overrides: HashMap::new(),
 
             // Per-file-type overrides
             file_type_overrides: Vec::new(),
+            language_overrides: HashMap::new(),
+            file_templates: Vec::new(),
 
             // Updates
             check_for_updates: true,
+            auto_install_updates: false,
+
+            // Workspace trust
+            workspace_trust_enabled: true,
+
+            // Crash reports
+            send_crash_reports: false,
+            crash_report_endpoint: String::new(),
+
+            // Settings sync
+            settings_sync_repository: String::new(),
+
+            // Git hosting
+            show_ci_status: false,
+
+            // Commits
+            conventional_commits: false,
+
+            // Assistant
+            assistant_enabled: false,
+            assistant_provider: String::from(crate::assistant::PROVIDER_OPENAI),
+            assistant_endpoint: String::new(),
+            assistant_model: String::new(),
+            agent_access_enabled: false,
+
+            // Language servers
+            lsp_debounce: LspDebounce::default(),
+            code_actions_on_save: Vec::new(),
+            disabled_lsp_servers: Vec::new(),
+
+            // Search
+            search_index_enabled: true,
+            files_exclude: Vec::new(),
+            search_exclude: Vec::new(),
+            follow_symlinks: false,
+            scan_network_mounts: false,
+
+            // Formatting
+            formatters: Vec::new(),
+            default_formatters: HashMap::new(),
+
+            extra: serde_json::Map::new(),
         }
     }
 }
@@ -265,6 +577,37 @@ impl Settings {
             .expect("settings schema must serialize")
     }
 
+    /// The theme ID to use given the system's current light/dark
+    /// appearance. Only consults `system_dark` when following the system.
+    pub fn active_color_scheme(&self, system_dark: bool) -> &str {
+        if !self.follow_system_appearance {
+            &self.color_scheme
+        } else if system_dark {
+            &self.dark_color_scheme
+        } else {
+            &self.light_color_scheme
+        }
+    }
+
+    /// The shortcut overrides to resolve against the built-in defaults: the
+    /// keymap preset's bindings with the user's overrides on top.
+    pub fn effective_keybinding_overrides(&self) -> HashMap<String, String> {
+        crate::keymap::layered_overrides(&self.keymap_preset, &self.keybinding_overrides)
+    }
+
+    /// The palette override for ANSI color `index` (0–15), if one is set.
+    pub fn terminal_palette_color(&self, index: usize) -> Option<&str> {
+        self.terminal_palette
+            .get(index)
+            .map(String::as_str)
+            .filter(|c| is_hex_color(c))
+    }
+
+    /// The cursor color override, if one is set.
+    pub fn terminal_cursor_color(&self) -> Option<&str> {
+        Some(self.terminal_cursor_color.as_str()).filter(|c| is_hex_color(c))
+    }
+
     /// Clamp settings values to valid ranges.
     pub fn validate(&mut self) {
         self.font_size = self.font_size.clamp(6, 72);
@@ -273,19 +616,84 @@ impl Settings {
         if self.terminal_scrollback > 1_000_000 {
             self.terminal_scrollback = 1_000_000;
         }
+        self.terminal_palette.truncate(16);
+        self.terminal_background_opacity = if self.terminal_background_opacity.is_finite() {
+            self.terminal_background_opacity.clamp(0.3, 1.0)
+        } else {
+            1.0
+        };
         self.tab_width = self.tab_width.clamp(1, 16);
         self.right_margin_position = self.right_margin_position.clamp(1, 500);
         self.sidebar_width = self.sidebar_width.clamp(100, 1000);
+        self.zen_mode_max_width = self.zen_mode_max_width.clamp(400, 4000);
         self.editor_line_height = self.editor_line_height.min(100);
         self.window_width = self.window_width.clamp(400, 10000);
         self.window_height = self.window_height.clamp(300, 10000);
         if self.tab_bar_position != "top" && self.tab_bar_position != "sidebar" {
             self.tab_bar_position = String::from("sidebar");
         }
+        if self.save_strategy != crate::safe_write::STRATEGY_ATOMIC
+            && self.save_strategy != crate::safe_write::STRATEGY_IN_PLACE
+        {
+            self.save_strategy = String::from(crate::safe_write::STRATEGY_ATOMIC);
+        }
+        if ![
+            crate::shell::NEW_TAB_DIRECTORY_HOME,
+            crate::shell::NEW_TAB_DIRECTORY_WORKSPACE,
+            crate::shell::NEW_TAB_DIRECTORY_CURRENT,
+        ]
+        .contains(&self.terminal_new_tab_directory.as_str())
+        {
+            self.terminal_new_tab_directory = String::from(crate::shell::NEW_TAB_DIRECTORY_HOME);
+        }
+        if crate::keymap::preset(&self.keymap_preset).is_none() {
+            self.keymap_preset = String::from(crate::keymap::DEFAULT_PRESET);
+        }
+        if crate::assistant::Provider::from_id(&self.assistant_provider).is_none() {
+            self.assistant_provider = String::from(crate::assistant::PROVIDER_OPENAI);
+        }
+        self.lsp_debounce.validate();
     }
 
-    /// Run all pending migrations.
+    /// Run the migrations between the settings' version and
+    /// [`SETTINGS_VERSION`]. Settings from a newer build keep their version.
     pub fn migrate(&mut self) {
+        for migration in MIGRATIONS.iter().skip(self.version as usize) {
+            migration(self);
+        }
+        self.version = self.version.max(SETTINGS_VERSION);
+    }
+
+    /// Read a setting by its JSON key, including keys kept in `extra`.
+    /// Returns `None` when the key is absent or has a different type.
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let value = match self.extra.get(key) {
+            Some(value) => value.clone(),
+            None => serde_json::to_value(self).ok()?.get_mut(key)?.take(),
+        };
+        serde_json::from_value(value).ok()
+    }
+
+    /// Set a setting by its JSON key and re-validate. Unknown keys are kept
+    /// in `extra`; a value of the wrong type for a known key is an error.
+    pub fn set<T: Serialize>(&mut self, key: &str, value: T) -> Result<(), String> {
+        let value = serde_json::to_value(value)
+            .map_err(|e| format!("Failed to serialize {}: {}", key, e))?;
+        let mut fields = serde_json::to_value(&*self)
+            .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+        if let serde_json::Value::Object(fields) = &mut fields {
+            fields.insert(key.to_string(), value);
+        }
+        let mut updated: Settings = serde_json::from_value(fields)
+            .map_err(|e| format!("Invalid value for {}: {}", key, e))?;
+        updated.validate();
+        *self = updated;
+        Ok(())
+    }
+
+    /// Version 0 → 1: unversioned files from before `commands_on_save` and
+    /// the bundled default font.
+    fn migrate_v0(&mut self) {
         self.migrate_format_on_save();
         self.migrate_default_font();
     }
@@ -318,6 +726,435 @@ impl Settings {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Persistence
+// ---------------------------------------------------------------------------
+
+/// Why the settings file could not be used on the last [`load`]. While a
+/// warning is set, [`save`] refuses to overwrite the file.
+#[derive(Debug, Clone, Serialize)]
+pub struct SettingsLoadWarning {
+    pub settings_path: PathBuf,
+    pub backup_path: Option<PathBuf>,
+    pub message: String,
+}
+
+/// Hash of the file contents as last loaded or written, so saves can detect
+/// edits made on disk in the meantime.
+#[derive(Debug, Clone)]
+struct SettingsFileSnapshot {
+    path: PathBuf,
+    content_hash: String,
+}
+
+static SETTINGS_LOAD_WARNING: Mutex<Option<SettingsLoadWarning>> = Mutex::new(None);
+static SETTINGS_FILE_SNAPSHOT: Mutex<Option<SettingsFileSnapshot>> = Mutex::new(None);
+/// In-app settings whose save was refused because the file changed on disk,
+/// kept until [`check_external_change`] reports the conflict.
+static PENDING_SAVE: Mutex<Option<Settings>> = Mutex::new(None);
+
+/// What [`check_external_change`] found.
+#[derive(Debug, Clone)]
+pub enum ExternalChange {
+    Unchanged,
+    /// The file was edited outside Impulse and has been reloaded.
+    Reloaded(Box<Settings>),
+    /// The edited file doesn't parse; the settings in effect stay.
+    Invalid(String),
+    /// The file was edited while in-app changes couldn't be saved. Nothing
+    /// is applied until [`resolve_conflict`] picks a side.
+    Conflict {
+        disk: Box<Settings>,
+        app: Box<Settings>,
+    },
+}
+
+/// The shared settings file, `<config dir>/impulse/settings.json`
+/// (`~/.config` on Linux, `~/Library/Application Support` on macOS). The
+/// directory is created with owner-only permissions.
+pub fn settings_path() -> Option<PathBuf> {
+    let impulse_dir = dirs::config_dir()?.join("impulse");
+    let _ = std::fs::create_dir_all(&impulse_dir);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        if let Err(e) =
+            std::fs::set_permissions(&impulse_dir, std::fs::Permissions::from_mode(0o700))
+        {
+            log::warn!("Failed to set permissions on {:?}: {}", impulse_dir, e);
+        }
+    }
+    Some(impulse_dir.join("settings.json"))
+}
+
+/// The warning recorded by the last [`load`], if the file was unusable.
+pub fn settings_load_warning() -> Option<SettingsLoadWarning> {
+    SETTINGS_LOAD_WARNING.lock().clone()
+}
+
+/// Load settings from [`settings_path`], falling back to defaults when the
+/// file is missing or invalid. An invalid file is backed up next to the
+/// original and reported through [`settings_load_warning`].
+pub fn load() -> Settings {
+    match settings_path() {
+        Some(path) => load_from(&path),
+        None => {
+            log::warn!("Cannot determine config directory; using default settings");
+            *SETTINGS_LOAD_WARNING.lock() = None;
+            *SETTINGS_FILE_SNAPSHOT.lock() = None;
+            Settings::default()
+        }
+    }
+}
+
+/// Write settings to [`settings_path`]. Refuses to save while a load warning
+/// is set or when the file changed on disk since it was loaded, so neither a
+/// broken file nor an external edit is silently overwritten.
+pub fn save(settings: &Settings) -> Result<(), String> {
+    let path = settings_path()
+        .ok_or_else(|| "Cannot determine config directory; settings not saved".to_string())?;
+    save_to(&path, settings)
+}
+
+/// Apply the top-level keys of the JSON object `json` on top of the settings
+/// currently on disk and save the result. Keys the caller does not know about
+/// keep their stored values, so a frontend with an older settings model does
+/// not reset fields it never read. Returns the saved settings.
+pub fn save_merged(json: &str) -> Result<Settings, String> {
+    let path = settings_path()
+        .ok_or_else(|| "Cannot determine config directory; settings not saved".to_string())?;
+    let current = match std::fs::read_to_string(&path) {
+        Ok(raw) => Settings::from_json(&raw)?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Settings::default(),
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+    let merged = merge_json(&current, json)?;
+    save_to(&path, &merged)?;
+    Ok(merged)
+}
+
+/// Reload the settings file if it changed on disk since it was last loaded
+/// or saved (edited in another window, by hand or by a dotfiles manager).
+/// Frontends call this when [`watch`] fires and apply what it returns.
+pub fn check_external_change() -> ExternalChange {
+    match settings_path() {
+        Some(path) => check_external_change_at(&path),
+        None => ExternalChange::Unchanged,
+    }
+}
+
+/// Settle an [`ExternalChange::Conflict`]: overwrite the file with the
+/// in-app settings (`keep_app`) or reload it. Returns the settings now in
+/// effect.
+pub fn resolve_conflict(keep_app: bool) -> Result<Settings, String> {
+    let path = settings_path()
+        .ok_or_else(|| "Cannot determine config directory; settings not saved".to_string())?;
+    resolve_conflict_at(&path, keep_app)
+}
+
+fn check_external_change_at(path: &Path) -> ExternalChange {
+    if settings_load_warning().is_none() && settings_file_changed_since_load(path).is_none() {
+        return ExternalChange::Unchanged;
+    }
+    let contents = match std::fs::read(path) {
+        Ok(contents) => contents,
+        // Deleted: keep what is in effect; the next save recreates it.
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return ExternalChange::Unchanged,
+        Err(e) => return ExternalChange::Invalid(e.to_string()),
+    };
+    let disk = match std::str::from_utf8(&contents)
+        .map_err(|e| format!("Failed to read settings as UTF-8: {e}"))
+        .and_then(Settings::from_json)
+    {
+        Ok(disk) => disk,
+        Err(message) => return ExternalChange::Invalid(message),
+    };
+    let pending = PENDING_SAVE.lock().clone();
+    match pending {
+        Some(app) => ExternalChange::Conflict {
+            disk: Box::new(disk),
+            app: Box::new(app),
+        },
+        None => ExternalChange::Reloaded(Box::new(load_from(path))),
+    }
+}
+
+fn resolve_conflict_at(path: &Path, keep_app: bool) -> Result<Settings, String> {
+    let pending = PENDING_SAVE.lock().take();
+    match pending {
+        Some(app) if keep_app => {
+            // Accept the file as seen so the save below isn't refused again.
+            *SETTINGS_FILE_SNAPSHOT.lock() =
+                std::fs::read(path)
+                    .ok()
+                    .map(|contents| SettingsFileSnapshot {
+                        path: path.to_path_buf(),
+                        content_hash: stable_content_hash(&contents),
+                    });
+            save_to(path, &app)?;
+            Ok(app)
+        }
+        _ => Ok(load_from(path)),
+    }
+}
+
+/// Watches the settings file until dropped.
+pub struct SettingsWatcher {
+    _watcher: notify::RecommendedWatcher,
+}
+
+/// Call `on_change` (on the watcher's thread) whenever the settings file
+/// may have changed, including Impulse's own saves; [`check_external_change`]
+/// tells them apart. The directory is watched so atomic replaces are seen.
+pub fn watch(on_change: impl Fn() + Send + 'static) -> Result<SettingsWatcher, String> {
+    use notify::Watcher;
+    let path = settings_path().ok_or("Cannot determine config directory")?;
+    let dir = path.parent().ok_or("Settings file has no directory")?;
+    let name = path.file_name().map(|name| name.to_os_string());
+    let mut watcher =
+        notify::recommended_watcher(move |res: Result<notify::Event, notify::Error>| match res {
+            Ok(event) => {
+                if !event.kind.is_access()
+                    && event.paths.iter().any(|p| p.file_name() == name.as_deref())
+                {
+                    on_change();
+                }
+            }
+            Err(e) => log::warn!("Settings watcher error: {}", e),
+        })
+        .map_err(|e| format!("Failed to create settings watcher: {}", e))?;
+    watcher
+        .watch(dir, notify::RecursiveMode::NonRecursive)
+        .map_err(|e| format!("Failed to watch {}: {}", dir.display(), e))?;
+    Ok(SettingsWatcher { _watcher: watcher })
+}
+
+/// Overlay the top-level keys of the JSON object `patch` onto `base`.
+pub fn merge_json(base: &Settings, patch: &str) -> Result<Settings, String> {
+    let patch: serde_json::Value =
+        serde_json::from_str(patch).map_err(|e| format!("Failed to parse settings: {}", e))?;
+    let serde_json::Value::Object(patch) = patch else {
+        return Err("Settings must be a JSON object".to_string());
+    };
+    let mut merged =
+        serde_json::to_value(base).map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    if let serde_json::Value::Object(fields) = &mut merged {
+        fields.extend(patch);
+    }
+    Settings::from_json(&merged.to_string())
+}
+
+fn load_from(path: &Path) -> Settings {
+    let contents = match std::fs::read(path) {
+        Ok(contents) => contents,
+        Err(e) => {
+            *SETTINGS_FILE_SNAPSHOT.lock() = None;
+            if e.kind() == std::io::ErrorKind::NotFound {
+                *SETTINGS_LOAD_WARNING.lock() = None;
+            } else {
+                log::error!(
+                    "Failed to read settings from {}: {}; using defaults",
+                    path.display(),
+                    e
+                );
+                *SETTINGS_LOAD_WARNING.lock() = Some(SettingsLoadWarning {
+                    settings_path: path.to_path_buf(),
+                    backup_path: None,
+                    message: e.to_string(),
+                });
+            }
+            return Settings::default();
+        }
+    };
+    let parsed = std::str::from_utf8(&contents)
+        .map_err(|e| format!("Failed to read settings as UTF-8: {e}"))
+        .and_then(Settings::from_json);
+    let settings = match parsed {
+        Ok(settings) => settings,
+        Err(message) => {
+            // Keep the first backup when the same broken file is reloaded.
+            let existing_warning =
+                settings_load_warning().filter(|warning| warning.settings_path == path);
+            let backup_path = match existing_warning {
+                Some(warning) => warning.backup_path,
+                None => backup_invalid_settings_file(path, &contents),
+            };
+            log::error!(
+                "Failed to load settings from {}: {}; using defaults{}",
+                path.display(),
+                message,
+                backup_path
+                    .as_ref()
+                    .map(|path| format!("; invalid file backed up to {}", path.display()))
+                    .unwrap_or_default()
+            );
+            *SETTINGS_LOAD_WARNING.lock() = Some(SettingsLoadWarning {
+                settings_path: path.to_path_buf(),
+                backup_path,
+                message,
+            });
+            *SETTINGS_FILE_SNAPSHOT.lock() = None;
+            return Settings::default();
+        }
+    };
+    *SETTINGS_LOAD_WARNING.lock() = None;
+    *SETTINGS_FILE_SNAPSHOT.lock() = Some(SettingsFileSnapshot {
+        path: path.to_path_buf(),
+        content_hash: stable_content_hash(&contents),
+    });
+
+    // Persist migrations right away so the file records its new version.
+    let file_version = serde_json::from_slice::<serde_json::Value>(&contents)
+        .ok()
+        .and_then(|raw| raw.get("version").and_then(|v| v.as_u64()))
+        .unwrap_or(0);
+    if file_version < u64::from(SETTINGS_VERSION) {
+        if let Err(e) = save_to(path, &settings) {
+            log::warn!("Failed to save migrated settings: {}", e);
+        }
+    }
+
+    settings
+}
+
+fn save_to(path: &Path, settings: &Settings) -> Result<(), String> {
+    if let Some(warning) = settings_load_warning() {
+        return Err(format!(
+            "Skipping settings save to preserve invalid settings file {}: {}",
+            warning.settings_path.display(),
+            warning.message
+        ));
+    }
+    if let Some(message) = settings_file_changed_since_load(path) {
+        *PENDING_SAVE.lock() = Some(settings.clone());
+        return Err(format!(
+            "Skipping settings save because {}; it will be reloaded or you'll be asked which version to keep",
+            message
+        ));
+    }
+    let json = settings.to_json()?;
+    // Atomic write: write to temp file with restrictive permissions, then rename
+    let tmp_path = path.with_extension("json.tmp");
+    {
+        use std::io::Write;
+        #[cfg(unix)]
+        use std::os::unix::fs::OpenOptionsExt;
+        let mut opts = std::fs::OpenOptions::new();
+        opts.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        opts.mode(0o600);
+        opts.open(&tmp_path)
+            .and_then(|mut file| file.write_all(json.as_bytes()))
+            .map_err(|e| format!("Failed to write settings to {}: {}", tmp_path.display(), e))?;
+    }
+    std::fs::rename(&tmp_path, path)
+        .map_err(|e| format!("Failed to rename settings file: {}", e))?;
+    *SETTINGS_FILE_SNAPSHOT.lock() = Some(SettingsFileSnapshot {
+        path: path.to_path_buf(),
+        content_hash: stable_content_hash(json.as_bytes()),
+    });
+    *PENDING_SAVE.lock() = None;
+    Ok(())
+}
+
+fn settings_file_changed_since_load(path: &Path) -> Option<String> {
+    let snapshot = SETTINGS_FILE_SNAPSHOT.lock().clone();
+    match std::fs::read(path) {
+        Ok(contents) => match snapshot {
+            Some(snapshot) if snapshot.path.as_path() == path => {
+                if snapshot.content_hash == stable_content_hash(&contents) {
+                    None
+                } else {
+                    Some(format!(
+                        "{} changed on disk since it was loaded",
+                        path.display()
+                    ))
+                }
+            }
+            Some(_) => Some(format!(
+                "{} was not the file loaded at startup",
+                path.display()
+            )),
+            None => Some(format!("{} was created after startup", path.display())),
+        },
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            snapshot.map(|_| format!("{} was removed after it was loaded", path.display()))
+        }
+        Err(e) => Some(format!("{} could not be checked: {}", path.display(), e)),
+    }
+}
+
+fn stable_content_hash(contents: &[u8]) -> String {
+    let mut hash = 0xcbf29ce484222325u64;
+    for byte in contents {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("fnv1a64:{}:{hash:016x}", contents.len())
+}
+
+fn backup_invalid_settings_file(path: &Path, contents: &[u8]) -> Option<PathBuf> {
+    let parent = path.parent()?;
+    let stem = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("settings");
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or("json");
+    let timestamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0);
+
+    for attempt in 0..100 {
+        let suffix = if attempt == 0 {
+            String::new()
+        } else {
+            format!("-{}", attempt)
+        };
+        let backup = parent.join(format!("{stem}.invalid-{timestamp}{suffix}.{extension}"));
+        let mut opts = std::fs::OpenOptions::new();
+        opts.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            opts.mode(0o600);
+        }
+        match opts.open(&backup) {
+            Ok(mut file) => {
+                use std::io::Write;
+                if file.write_all(contents).is_ok() {
+                    return Some(backup);
+                }
+                log::error!(
+                    "Failed to write invalid settings backup {}",
+                    backup.display()
+                );
+                return None;
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => {
+                log::error!(
+                    "Failed to create invalid settings backup {}: {}",
+                    backup.display(),
+                    e
+                );
+                return None;
+            }
+        }
+    }
+
+    log::error!("Failed to choose a unique invalid settings backup path");
+    None
+}
+
+/// `#rrggbb`, the form terminal color overrides are stored in.
+fn is_hex_color(color: &str) -> bool {
+    color.len() == 7 && color.starts_with('#') && color[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -339,16 +1176,26 @@ mod tests {
         assert_eq!(settings.color_scheme, "nord");
         assert_eq!(settings.tab_width, 4);
         assert!(settings.confirm_close_warnings);
+        assert!(settings.terminal_confirm_close);
+        assert!(settings
+            .terminal_close_ignored_commands
+            .contains(&"less".to_string()));
         assert!(!settings.restore_session);
     }
 
     #[test]
     fn validation_clamps_values() {
-        let json = r#"{"font_size": 200, "tab_width": 0, "terminal_scrollback": 9999999}"#;
+        let json = r#"{"font_size": 200, "tab_width": 0, "terminal_scrollback": 9999999,
+            "lsp_debounce": {"hover_ms": 60000}}"#;
         let settings = Settings::from_json(json).unwrap();
         assert_eq!(settings.font_size, 72);
         assert_eq!(settings.tab_width, 1);
         assert_eq!(settings.terminal_scrollback, 1_000_000);
+        assert_eq!(settings.lsp_debounce.hover_ms, 2000);
+        assert_eq!(
+            settings.lsp_debounce.completion_ms,
+            LspDebounce::default().completion_ms
+        );
     }
 
     #[test]
@@ -381,6 +1228,156 @@ mod tests {
         assert_eq!(settings.terminal_font_family, "JetBrains Mono");
     }
 
+    #[test]
+    fn migrations_only_run_for_older_versions() {
+        let legacy = Settings::from_json(r#"{"font_family": "monospace"}"#).unwrap();
+        assert_eq!(legacy.font_family, "JetBrains Mono");
+        assert_eq!(legacy.version, SETTINGS_VERSION);
+
+        let current = Settings::from_json(&format!(
+            r#"{{"version": {SETTINGS_VERSION}, "font_family": "monospace"}}"#
+        ))
+        .unwrap();
+        assert_eq!(current.font_family, "monospace");
+
+        let newer = Settings::from_json(r#"{"version": 99}"#).unwrap();
+        assert_eq!(newer.version, 99);
+    }
+
+    #[test]
+    fn unknown_keys_survive_a_roundtrip() {
+        let json = r#"{"font_size": 16, "future_option": {"enabled": true}}"#;
+        let settings = Settings::from_json(json).unwrap();
+        assert_eq!(settings.font_size, 16);
+        let saved: serde_json::Value = serde_json::from_str(&settings.to_json().unwrap()).unwrap();
+        assert_eq!(saved["future_option"]["enabled"], true);
+        assert_eq!(saved["version"], SETTINGS_VERSION);
+    }
+
+    #[test]
+    fn terminal_color_overrides_ignore_invalid_entries() {
+        let json = r##"{"terminal_palette": ["#000000", "", "red", "#12345G"],
+            "terminal_cursor_color": "#ff8800", "terminal_background_opacity": 0.1}"##;
+        let settings = Settings::from_json(json).unwrap();
+        assert_eq!(settings.terminal_palette_color(0), Some("#000000"));
+        assert_eq!(settings.terminal_palette_color(1), None);
+        assert_eq!(settings.terminal_palette_color(2), None);
+        assert_eq!(settings.terminal_palette_color(3), None);
+        assert_eq!(settings.terminal_palette_color(15), None);
+        assert_eq!(settings.terminal_cursor_color(), Some("#ff8800"));
+        assert_eq!(settings.terminal_background_opacity, 0.3);
+    }
+
+    #[test]
+    fn active_color_scheme_follows_system_only_when_enabled() {
+        let mut settings = Settings {
+            color_scheme: "dracula".to_string(),
+            ..Settings::default()
+        };
+        assert_eq!(settings.active_color_scheme(false), "dracula");
+
+        settings.follow_system_appearance = true;
+        assert_eq!(settings.active_color_scheme(true), "nord");
+        assert_eq!(settings.active_color_scheme(false), "github-light");
+    }
+
+    #[test]
+    fn typed_accessors_read_and_write_by_key() {
+        let mut settings = Settings::default();
+        assert_eq!(settings.get::<i32>("font_size"), Some(14));
+        assert_eq!(settings.get::<String>("font_size"), None);
+
+        settings.set("font_size", 300).unwrap();
+        assert_eq!(settings.font_size, 72);
+        assert!(settings.set("font_size", "large").is_err());
+        assert_eq!(settings.font_size, 72);
+
+        settings.set("future_option", vec!["a", "b"]).unwrap();
+        assert_eq!(
+            settings.get::<Vec<String>>("future_option"),
+            Some(vec!["a".to_string(), "b".to_string()])
+        );
+        assert!(settings.get::<bool>("missing").is_none());
+    }
+
+    #[test]
+    fn merge_json_keeps_fields_missing_from_patch() {
+        let base = Settings {
+            font_size: 18,
+            conventional_commits: true,
+            ..Settings::default()
+        };
+        let merged = merge_json(&base, r#"{"color_scheme": "dracula", "tab_width": 0}"#).unwrap();
+        assert_eq!(merged.color_scheme, "dracula");
+        assert_eq!(merged.tab_width, 1);
+        assert_eq!(merged.font_size, 18);
+        assert!(merged.conventional_commits);
+        assert!(merge_json(&base, "[1]").is_err());
+    }
+
+    #[test]
+    fn load_and_save_guard_the_settings_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+
+        // First launch: nothing on disk, saving creates the file.
+        assert_eq!(load_from(&path).font_size, 14);
+        let settings = Settings {
+            font_size: 20,
+            ..Settings::default()
+        };
+        save_to(&path, &settings).unwrap();
+        assert_eq!(load_from(&path).font_size, 20);
+
+        // An external edit blocks saving until the file is reloaded.
+        std::fs::write(&path, r#"{"font_size": 16}"#).unwrap();
+        assert!(save_to(&path, &settings).is_err());
+        assert_eq!(load_from(&path).font_size, 16);
+        save_to(&path, &settings).unwrap();
+
+        // Our own save isn't an external change; an edit is reloaded.
+        assert!(matches!(
+            check_external_change_at(&path),
+            ExternalChange::Unchanged
+        ));
+        std::fs::write(&path, r#"{"font_size": 18}"#).unwrap();
+        match check_external_change_at(&path) {
+            ExternalChange::Reloaded(s) => assert_eq!(s.font_size, 18),
+            other => panic!("expected a reload, got {:?}", other),
+        }
+        std::fs::write(&path, "{broken").unwrap();
+        assert!(matches!(
+            check_external_change_at(&path),
+            ExternalChange::Invalid(_)
+        ));
+
+        // An edit racing a refused in-app save is a conflict.
+        std::fs::write(&path, r#"{"font_size": 12}"#).unwrap();
+        assert!(save_to(&path, &settings).is_err());
+        match check_external_change_at(&path) {
+            ExternalChange::Conflict { disk, app } => {
+                assert_eq!(disk.font_size, 12);
+                assert_eq!(app.font_size, 20);
+            }
+            other => panic!("expected a conflict, got {:?}", other),
+        }
+        assert_eq!(resolve_conflict_at(&path, true).unwrap().font_size, 20);
+        assert_eq!(load_from(&path).font_size, 20);
+        std::fs::write(&path, r#"{"font_size": 12}"#).unwrap();
+        assert!(save_to(&path, &settings).is_err());
+        assert_eq!(resolve_conflict_at(&path, false).unwrap().font_size, 12);
+        save_to(&path, &settings).unwrap();
+
+        // An invalid file is backed up and never overwritten.
+        std::fs::write(&path, "{not json").unwrap();
+        assert_eq!(load_from(&path).font_size, 14);
+        let warning = settings_load_warning().unwrap();
+        let backup = warning.backup_path.unwrap();
+        assert_eq!(std::fs::read_to_string(backup).unwrap(), "{not json");
+        assert!(save_to(&path, &settings).is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "{not json");
+    }
+
     #[test]
     fn default_settings_validate_against_generated_schema() {
         let schema: serde_json::Value = serde_json::from_str(&Settings::schema_json()).unwrap();
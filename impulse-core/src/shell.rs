@@ -114,6 +114,36 @@ pub fn get_home_directory() -> Result<String, String> {
         .ok_or_else(|| "Failed to determine home directory".to_string())
 }
 
+/// `terminal_new_tab_directory`: new terminal tabs start in the home folder.
+pub const NEW_TAB_DIRECTORY_HOME: &str = "home";
+/// New terminal tabs start in the workspace root (the sidebar's folder).
+pub const NEW_TAB_DIRECTORY_WORKSPACE: &str = "workspace";
+/// New terminal tabs start in the active tab's directory.
+pub const NEW_TAB_DIRECTORY_CURRENT: &str = "current";
+
+/// The directory a new terminal tab starts in under the
+/// `terminal_new_tab_directory` setting `mode`. Falls back from the active
+/// tab's directory to the workspace root to `home` whenever one is unknown
+/// or no longer a directory.
+pub fn new_terminal_directory(
+    mode: &str,
+    home: &str,
+    workspace: Option<&str>,
+    current: Option<&str>,
+) -> String {
+    let candidates = match mode {
+        NEW_TAB_DIRECTORY_CURRENT => vec![current, workspace],
+        NEW_TAB_DIRECTORY_WORKSPACE => vec![workspace],
+        _ => Vec::new(),
+    };
+    candidates
+        .into_iter()
+        .flatten()
+        .find(|dir| !dir.is_empty() && std::path::Path::new(dir).is_dir())
+        .unwrap_or(home)
+        .to_string()
+}
+
 /// Write a file with owner-only permissions (0600) to prevent other users
 /// from reading shell integration scripts that may reveal path information.
 #[cfg(unix)]
@@ -326,6 +356,38 @@ pub fn prepare_shell_launch_config() -> Result<ShellLaunchConfig, std::io::Error
 mod tests {
     use super::*;
 
+    #[test]
+    fn new_terminal_directory_follows_the_setting() {
+        let temp = tempfile::tempdir().unwrap();
+        let workspace = temp.path().join("project");
+        let current = workspace.join("src");
+        std::fs::create_dir_all(&current).unwrap();
+        let (workspace, current) = (workspace.to_str().unwrap(), current.to_str().unwrap());
+        let missing = temp.path().join("deleted");
+        let missing = missing.to_str().unwrap();
+
+        let dir =
+            |mode, workspace, current| new_terminal_directory(mode, "/home/me", workspace, current);
+        assert_eq!(
+            dir(NEW_TAB_DIRECTORY_HOME, Some(workspace), Some(current)),
+            "/home/me"
+        );
+        assert_eq!(
+            dir(NEW_TAB_DIRECTORY_WORKSPACE, Some(workspace), Some(current)),
+            workspace
+        );
+        assert_eq!(
+            dir(NEW_TAB_DIRECTORY_CURRENT, Some(workspace), Some(current)),
+            current
+        );
+        assert_eq!(
+            dir(NEW_TAB_DIRECTORY_CURRENT, Some(workspace), Some(missing)),
+            workspace
+        );
+        assert_eq!(dir(NEW_TAB_DIRECTORY_CURRENT, None, None), "/home/me");
+        assert_eq!(dir(NEW_TAB_DIRECTORY_WORKSPACE, Some(""), None), "/home/me");
+    }
+
     #[test]
     fn detect_shell_type_bash() {
         assert_eq!(detect_shell_type("/bin/bash"), ShellType::Bash);
@@ -0,0 +1,122 @@
+//! Startup phase timings. With `--profile-startup` frontends call [`mark`]
+//! at each phase of launch and [`finish`] once the first window is on
+//! screen, which prints how long each phase took to stderr.
+//!
+//! Disabled (the default), every call is a cheap no-op.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// Command-line flag that turns profiling on.
+pub const FLAG: &str = "--profile-startup";
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+struct Profile {
+    origin: Instant,
+    phases: Vec<(String, Duration)>,
+    finished: bool,
+}
+
+fn profile() -> &'static parking_lot::Mutex<Profile> {
+    static PROFILE: OnceLock<parking_lot::Mutex<Profile>> = OnceLock::new();
+    PROFILE.get_or_init(|| {
+        parking_lot::Mutex::new(Profile {
+            origin: Instant::now(),
+            phases: Vec::new(),
+            finished: false,
+        })
+    })
+}
+
+/// Start profiling. Call first thing in `main`: phases are timed from here.
+pub fn enable() {
+    profile();
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Whether `args` (the process arguments) ask for a startup profile.
+pub fn requested<S: AsRef<str>>(args: &[S]) -> bool {
+    args.iter().any(|a| a.as_ref() == FLAG)
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Record that `phase` just finished. Can be called from any thread.
+pub fn mark(phase: &str) {
+    if !is_enabled() {
+        return;
+    }
+    let mut profile = profile().lock();
+    if profile.finished {
+        return;
+    }
+    let elapsed = profile.origin.elapsed();
+    profile.phases.push((phase.to_string(), elapsed));
+}
+
+/// Record the final phase and print the profile. Only the first call
+/// prints; later windows don't repeat it.
+pub fn finish(phase: &str) {
+    if !is_enabled() {
+        return;
+    }
+    mark(phase);
+    let mut profile = profile().lock();
+    if profile.finished {
+        return;
+    }
+    profile.finished = true;
+    eprintln!("{}", format_report(&profile.phases));
+}
+
+/// A table of phases with the time each took and the running total.
+pub fn format_report(phases: &[(String, Duration)]) -> String {
+    let width = phases
+        .iter()
+        .map(|(name, _)| name.len())
+        .max()
+        .unwrap_or(0)
+        .max("phase".len());
+    let mut report = format!(
+        "Startup profile\n{:width$}  {:>9}  {:>9}\n",
+        "phase", "took ms", "total ms"
+    );
+    let mut previous = Duration::ZERO;
+    for (name, at) in phases {
+        let took = at.saturating_sub(previous);
+        report.push_str(&format!(
+            "{:width$}  {:>9.1}  {:>9.1}\n",
+            name,
+            took.as_secs_f64() * 1000.0,
+            at.as_secs_f64() * 1000.0
+        ));
+        previous = *at;
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_shows_each_phase_and_running_total() {
+        let phases = vec![
+            ("settings".to_string(), Duration::from_millis(5)),
+            ("window presented".to_string(), Duration::from_millis(125)),
+        ];
+        let report = format_report(&phases);
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[2].starts_with("settings "));
+        assert!(lines[2].ends_with("5.0        5.0"));
+        assert!(lines[3].starts_with("window presented"));
+        assert!(lines[3].ends_with("120.0      125.0"));
+        assert!(requested(&["impulse", FLAG]));
+        assert!(!requested(&["impulse", "--dev"]));
+    }
+}
@@ -0,0 +1,227 @@
+//! Status bar segments: the registry of the pieces a frontend's status bar
+//! is built from, and how the user's order and hidden list resolve against it.
+//!
+//! Frontends place each segment they provide by its ID and skip the ones
+//! they don't (e.g. the CI badge only exists on Linux). Transient indicators
+//! such as git progress or the update button aren't segments and always
+//! show when active.
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SegmentAlignment {
+    Left,
+    Right,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StatusBarSegment {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub alignment: SegmentAlignment,
+}
+
+const fn segment(
+    id: &'static str,
+    name: &'static str,
+    alignment: SegmentAlignment,
+) -> StatusBarSegment {
+    StatusBarSegment {
+        id,
+        name,
+        alignment,
+    }
+}
+
+/// All segments in their default order.
+pub const STATUS_BAR_SEGMENTS: &[StatusBarSegment] = &[
+    segment("shell", "Shell", SegmentAlignment::Left),
+    segment("branch", "Git Branch", SegmentAlignment::Left),
+    segment("git_sync", "Ahead/Behind", SegmentAlignment::Left),
+    segment("ci", "CI Status", SegmentAlignment::Left),
+    segment("review", "Uncommitted Changes", SegmentAlignment::Left),
+    segment("lsp", "Language Server Status", SegmentAlignment::Left),
+    segment("cwd", "Working Directory", SegmentAlignment::Left),
+    segment("blame", "Git Blame", SegmentAlignment::Right),
+    segment("problems", "Problems", SegmentAlignment::Right),
+    segment("encoding", "Encoding", SegmentAlignment::Right),
+    segment("indent", "Indentation", SegmentAlignment::Right),
+    segment("language", "Language", SegmentAlignment::Right),
+    segment("zoom", "Zoom Level", SegmentAlignment::Right),
+    segment("cursor", "Cursor Position", SegmentAlignment::Right),
+];
+
+pub fn segment_by_id(id: &str) -> Option<&'static StatusBarSegment> {
+    STATUS_BAR_SEGMENTS.iter().find(|s| s.id == id)
+}
+
+/// The visible segments in display order: those named in `order` first (in
+/// that order), then the rest in their default order so segments added later
+/// still appear. Anything in `hidden` is left out; unknown IDs are ignored.
+pub fn resolve_segments(order: &[String], hidden: &[String]) -> Vec<&'static StatusBarSegment> {
+    let mut resolved: Vec<&'static StatusBarSegment> = Vec::new();
+    let ordered = order
+        .iter()
+        .filter_map(|id| segment_by_id(id))
+        .chain(STATUS_BAR_SEGMENTS.iter());
+    for segment in ordered {
+        if resolved.iter().any(|s| s.id == segment.id) || hidden.iter().any(|h| h == segment.id) {
+            continue;
+        }
+        resolved.push(segment);
+    }
+    resolved
+}
+
+/// Short problems summary like `"2 errors, 1 warning"`, or `None` when clean.
+pub fn problems_summary(errors: usize, warnings: usize) -> Option<String> {
+    fn count(n: usize, noun: &str) -> String {
+        format!("{} {}{}", n, noun, if n == 1 { "" } else { "s" })
+    }
+    match (errors, warnings) {
+        (0, 0) => None,
+        (e, 0) => Some(count(e, "error")),
+        (0, w) => Some(count(w, "warning")),
+        (e, w) => Some(format!("{}, {}", count(e, "error"), count(w, "warning"))),
+    }
+}
+
+/// One clickable part of the working directory segment.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PathCrumb {
+    pub label: String,
+    /// The directory the crumb opens.
+    pub path: String,
+}
+
+/// The working directory `path` as breadcrumbs, outermost first. Paths
+/// under `home` start at a `~` crumb, others at `/`.
+pub fn cwd_breadcrumbs(path: &str, home: Option<&str>) -> Vec<PathCrumb> {
+    let path = path.trim_end_matches('/');
+    let home = home
+        .map(|h| h.trim_end_matches('/'))
+        .filter(|h| !h.is_empty());
+    let (mut crumbs, mut current, rest) = match home {
+        Some(home) if path == home || path.starts_with(&format!("{home}/")) => (
+            vec![PathCrumb {
+                label: "~".to_string(),
+                path: home.to_string(),
+            }],
+            home.to_string(),
+            &path[home.len()..],
+        ),
+        _ => (
+            vec![PathCrumb {
+                label: "/".to_string(),
+                path: "/".to_string(),
+            }],
+            String::new(),
+            path,
+        ),
+    };
+    for part in rest.split('/').filter(|p| !p.is_empty()) {
+        current = format!("{current}/{part}");
+        crumbs.push(PathCrumb {
+            label: part.to_string(),
+            path: current.clone(),
+        });
+    }
+    crumbs
+}
+
+/// How many directories a terminal's history keeps.
+pub const DIRECTORY_HISTORY_LIMIT: usize = 20;
+
+/// Record a visit to `dir` in a terminal's directory history, most recent
+/// first and without duplicates.
+pub fn record_directory(history: &mut Vec<String>, dir: &str) {
+    if dir.is_empty() || history.first().is_some_and(|d| d == dir) {
+        return;
+    }
+    history.retain(|d| d != dir);
+    history.insert(0, dir.to_string());
+    history.truncate(DIRECTORY_HISTORY_LIMIT);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(segments: &[&StatusBarSegment]) -> Vec<&'static str> {
+        segments.iter().map(|s| s.id).collect()
+    }
+
+    #[test]
+    fn default_layout_shows_every_segment_in_order() {
+        let resolved = resolve_segments(&[], &[]);
+        assert_eq!(
+            ids(&resolved),
+            STATUS_BAR_SEGMENTS.iter().map(|s| s.id).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn custom_order_and_hidden_segments() {
+        let order = vec![
+            "cursor".to_string(),
+            "bogus".to_string(),
+            "language".to_string(),
+            "cursor".to_string(),
+        ];
+        let hidden = vec!["blame".to_string(), "shell".to_string()];
+        let resolved = ids(&resolve_segments(&order, &hidden));
+        assert_eq!(&resolved[..2], &["cursor", "language"]);
+        assert!(!resolved.contains(&"blame"));
+        assert!(!resolved.contains(&"shell"));
+        // Unlisted segments follow in default order.
+        assert_eq!(resolved[2], "branch");
+        assert_eq!(resolved.len(), STATUS_BAR_SEGMENTS.len() - 2);
+    }
+
+    #[test]
+    fn problems_summary_pluralizes() {
+        assert_eq!(problems_summary(0, 0), None);
+        assert_eq!(problems_summary(1, 0).as_deref(), Some("1 error"));
+        assert_eq!(problems_summary(0, 3).as_deref(), Some("3 warnings"));
+        assert_eq!(
+            problems_summary(2, 1).as_deref(),
+            Some("2 errors, 1 warning")
+        );
+    }
+
+    #[test]
+    fn breadcrumbs_start_at_home_or_root() {
+        let crumbs = cwd_breadcrumbs("/home/me/src/app", Some("/home/me"));
+        let labels: Vec<&str> = crumbs.iter().map(|c| c.label.as_str()).collect();
+        assert_eq!(labels, vec!["~", "src", "app"]);
+        assert_eq!(crumbs[0].path, "/home/me");
+        assert_eq!(crumbs[2].path, "/home/me/src/app");
+
+        let crumbs = cwd_breadcrumbs("/etc/nginx/", Some("/home/me"));
+        let paths: Vec<&str> = crumbs.iter().map(|c| c.path.as_str()).collect();
+        assert_eq!(paths, vec!["/", "/etc", "/etc/nginx"]);
+
+        assert_eq!(cwd_breadcrumbs("/home/me", Some("/home/me/")).len(), 1);
+        assert_eq!(
+            cwd_breadcrumbs("/home/meow", Some("/home/me"))[0].label,
+            "/"
+        );
+        assert_eq!(cwd_breadcrumbs("/", None).len(), 1);
+    }
+
+    #[test]
+    fn directory_history_is_recent_first_without_duplicates() {
+        let mut history = Vec::new();
+        for dir in ["/a", "/b", "/a", "/c", "/c", ""] {
+            record_directory(&mut history, dir);
+        }
+        assert_eq!(history, vec!["/c", "/a", "/b"]);
+
+        for i in 0..30 {
+            record_directory(&mut history, &format!("/d{i}"));
+        }
+        assert_eq!(history.len(), DIRECTORY_HISTORY_LIMIT);
+        assert_eq!(history[0], "/d29");
+    }
+}
@@ -0,0 +1,904 @@
+//! Lightweight workspace symbol index used when no language server is
+//! available.
+//!
+//! Symbols are extracted with per-language regular expressions (in the spirit
+//! of ctags' regex parsers), and an existing ctags `tags` file at the
+//! workspace root is merged in so languages without built-in rules can still
+//! be navigated. Results power go-to-definition and workspace symbol search.
+
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+use crate::util::language_from_uri;
+
+/// Files larger than this are skipped while indexing.
+const MAX_INDEXED_FILE_SIZE: u64 = 1024 * 1024; // 1 MB
+
+/// Stop walking the workspace after this many indexed files.
+const MAX_INDEXED_FILES: usize = 20_000;
+
+/// ctags files that are picked up from the workspace root, in priority order.
+const TAGS_FILE_NAMES: &[&str] = &["tags", ".tags", "TAGS"];
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum SymbolKind {
+    Function,
+    Method,
+    Class,
+    Struct,
+    Enum,
+    Interface,
+    Trait,
+    Type,
+    Constant,
+    Variable,
+    Module,
+    Macro,
+}
+
+impl SymbolKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            SymbolKind::Function => "function",
+            SymbolKind::Method => "method",
+            SymbolKind::Class => "class",
+            SymbolKind::Struct => "struct",
+            SymbolKind::Enum => "enum",
+            SymbolKind::Interface => "interface",
+            SymbolKind::Trait => "trait",
+            SymbolKind::Type => "type",
+            SymbolKind::Constant => "constant",
+            SymbolKind::Variable => "variable",
+            SymbolKind::Module => "module",
+            SymbolKind::Macro => "macro",
+        }
+    }
+
+    /// Map a ctags kind (single letter or long name) to a symbol kind.
+    fn from_ctags_kind(kind: &str) -> Self {
+        match kind {
+            "f" | "function" | "func" => SymbolKind::Function,
+            "m" | "method" | "member" | "P" | "implementation" => SymbolKind::Method,
+            "c" | "class" => SymbolKind::Class,
+            "s" | "struct" => SymbolKind::Struct,
+            "g" | "e" | "enum" | "enumerator" => SymbolKind::Enum,
+            "i" | "interface" => SymbolKind::Interface,
+            "t" | "typedef" | "type" => SymbolKind::Type,
+            "d" | "macro" | "define" => SymbolKind::Macro,
+            "n" | "module" | "namespace" | "package" => SymbolKind::Module,
+            "C" | "constant" | "const" => SymbolKind::Constant,
+            _ => SymbolKind::Variable,
+        }
+    }
+}
+
+/// A symbol definition. `line` is 0-based and `character` is a 0-based UTF-16
+/// column, matching LSP positions.
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct Symbol {
+    pub name: String,
+    pub kind: SymbolKind,
+    pub path: String,
+    pub line: u32,
+    pub character: u32,
+}
+
+/// An entry loaded from a ctags file. Entries addressed by search pattern are
+/// resolved to a line only when they are looked up.
+#[derive(Clone, Debug)]
+struct TagEntry {
+    name: String,
+    kind: SymbolKind,
+    path: String,
+    line: Option<u32>,
+    pattern: Option<String>,
+}
+
+/// In-memory symbol index for one workspace root.
+pub struct SymbolIndex {
+    root: PathBuf,
+    built: bool,
+    files: HashMap<String, Vec<Symbol>>,
+    tags: Vec<TagEntry>,
+}
+
+impl SymbolIndex {
+    /// Create an empty index for `root`. Call [`SymbolIndex::build`] (or
+    /// [`SymbolIndex::ensure_built`]) before querying.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            built: false,
+            files: HashMap::new(),
+            tags: Vec::new(),
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn is_built(&self) -> bool {
+        self.built
+    }
+
+    /// Build the index if it hasn't been built yet.
+    pub fn ensure_built(&mut self) {
+        if !self.built {
+            self.build(None);
+        }
+    }
+
+    /// (Re)index the workspace, respecting `.gitignore`. If `cancel` is set
+    /// the walk stops early and the partial index is kept. The home directory
+    /// and filesystem root are only indexed one level deep.
+    pub fn build(&mut self, cancel: Option<&AtomicBool>) {
+        self.files.clear();
+        self.tags = load_tags_file(&self.root);
+
+        let home = crate::shell::get_home_directory().ok().map(PathBuf::from);
+        let shallow = self.root.parent().is_none() || home.as_deref() == Some(self.root.as_path());
+        let walker = crate::ignore_rules::walk_builder(&self.root)
+            .max_depth(Some(if shallow { 1 } else { 15 }))
+            .build();
+
+        for entry in walker {
+            if self.files.len() >= MAX_INDEXED_FILES {
+                log::warn!(
+                    "Symbol index stopped after {} files in {}",
+                    MAX_INDEXED_FILES,
+                    self.root.display()
+                );
+                break;
+            }
+            if cancel.is_some_and(|c| c.load(Ordering::Relaxed)) {
+                break;
+            }
+            let entry = match entry {
+                Ok(e) => e,
+                Err(_) => continue,
+            };
+            if !entry.file_type().is_some_and(|ft| ft.is_file()) {
+                continue;
+            }
+            let path = entry.path().to_string_lossy().to_string();
+            if language_rules(&language_from_uri(&path)).is_none() {
+                continue;
+            }
+            if entry
+                .metadata()
+                .map(|m| m.len() > MAX_INDEXED_FILE_SIZE)
+                .unwrap_or(true)
+            {
+                continue;
+            }
+            if let Ok(content) = std::fs::read_to_string(entry.path()) {
+                self.update_file(&path, &content);
+            }
+        }
+        self.built = true;
+    }
+
+    /// Re-extract symbols for one file, e.g. after it was saved.
+    pub fn update_file(&mut self, path: &str, content: &str) {
+        let symbols = extract_symbols(path, &language_from_uri(path), content);
+        if symbols.is_empty() {
+            self.files.remove(path);
+        } else {
+            self.files.insert(path.to_string(), symbols);
+        }
+    }
+
+    pub fn remove_file(&mut self, path: &str) {
+        self.files.remove(path);
+    }
+
+    /// All definitions named exactly `name`.
+    pub fn find_definitions(&self, name: &str) -> Vec<Symbol> {
+        let mut result: Vec<Symbol> = self
+            .files
+            .values()
+            .flatten()
+            .filter(|symbol| symbol.name == name)
+            .cloned()
+            .collect();
+        for tag in self.tags.iter().filter(|tag| tag.name == name) {
+            // Files covered by the regex indexer already have precise positions.
+            if self.files.contains_key(&tag.path) {
+                continue;
+            }
+            if let Some(symbol) = resolve_tag(tag) {
+                result.push(symbol);
+            }
+        }
+        result
+    }
+
+    /// Definitions for the identifier at `line`/`character` (0-based, UTF-16)
+    /// in `content`. Matches in the same file come first, then files of the
+    /// same language, then everything else.
+    pub fn definitions_at(
+        &self,
+        path: &str,
+        content: &str,
+        line: u32,
+        character: u32,
+    ) -> Vec<Symbol> {
+        let Some(word) = word_at(content, line, character) else {
+            return Vec::new();
+        };
+        let language = language_from_uri(path);
+        let mut definitions = self.find_definitions(&word);
+        definitions.sort_by_key(|symbol| {
+            let rank = if symbol.path == path {
+                0
+            } else if language_from_uri(&symbol.path) == language {
+                1
+            } else {
+                2
+            };
+            (rank, symbol.path.clone(), symbol.line)
+        });
+        definitions
+    }
+
+    /// Search symbols by name: exact, prefix, substring, then in-order
+    /// character matches, all case-insensitive.
+    pub fn workspace_symbols(&self, query: &str, limit: usize) -> Vec<Symbol> {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return Vec::new();
+        }
+        let mut scored: Vec<(u32, Symbol)> = self
+            .files
+            .values()
+            .flatten()
+            .filter_map(|symbol| {
+                symbol_match_score(&symbol.name, &query).map(|score| (score, symbol.clone()))
+            })
+            .collect();
+        for tag in &self.tags {
+            if self.files.contains_key(&tag.path) {
+                continue;
+            }
+            if let Some(score) = symbol_match_score(&tag.name, &query) {
+                if let Some(symbol) = resolve_tag(tag) {
+                    scored.push((score, symbol));
+                }
+            }
+        }
+        scored.sort_by(|(a_score, a), (b_score, b)| {
+            a_score
+                .cmp(b_score)
+                .then(a.name.len().cmp(&b.name.len()))
+                .then_with(|| a.path.cmp(&b.path))
+                .then(a.line.cmp(&b.line))
+        });
+        scored
+            .into_iter()
+            .take(limit)
+            .map(|(_, symbol)| symbol)
+            .collect()
+    }
+}
+
+/// Symbol indexes for every workspace that has been queried, keyed by root.
+#[derive(Default)]
+pub struct SymbolIndexes {
+    indexes: HashMap<PathBuf, SymbolIndex>,
+}
+
+impl SymbolIndexes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The built index for `root`, indexing the workspace on first use.
+    pub fn for_root(&mut self, root: &Path) -> &mut SymbolIndex {
+        let index = self
+            .indexes
+            .entry(root.to_path_buf())
+            .or_insert_with(|| SymbolIndex::new(root));
+        index.ensure_built();
+        index
+    }
+
+    /// The built index for the workspace containing `path`.
+    pub fn for_file(&mut self, path: &str) -> &mut SymbolIndex {
+        let root = workspace_root_for_file(Path::new(path));
+        self.for_root(&root)
+    }
+
+    /// Refresh `path` in every index that covers it. Workspaces that haven't
+    /// been indexed yet are left alone; they read the file when built.
+    pub fn file_changed(&mut self, path: &str, content: &str) {
+        for index in self.indexes.values_mut() {
+            if Path::new(path).starts_with(index.root()) {
+                index.update_file(path, content);
+            }
+        }
+    }
+}
+
+/// The workspace a file belongs to for symbol indexing: its git working
+/// directory, or the containing directory outside of a repository.
+pub fn workspace_root_for_file(path: &Path) -> PathBuf {
+    let dir = path.parent().unwrap_or(path);
+    crate::git::get_git_root(&dir.to_string_lossy())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| dir.to_path_buf())
+}
+
+/// Lower is better; `None` means no match.
+fn symbol_match_score(name: &str, query_lower: &str) -> Option<u32> {
+    let name_lower = name.to_lowercase();
+    if name_lower == query_lower {
+        Some(0)
+    } else if name_lower.starts_with(query_lower) {
+        Some(1)
+    } else if name_lower.contains(query_lower) {
+        Some(2)
+    } else {
+        let mut chars = name_lower.chars();
+        query_lower
+            .chars()
+            .all(|q| chars.any(|c| c == q))
+            .then_some(3)
+    }
+}
+
+/// Return the identifier touching `line`/`character` (0-based, UTF-16).
+pub fn word_at(content: &str, line: u32, character: u32) -> Option<String> {
+    let text = content.lines().nth(line as usize)?;
+    let is_word = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+
+    let mut byte_at = text.len();
+    let mut units = 0u32;
+    for (i, ch) in text.char_indices() {
+        if units >= character {
+            byte_at = i;
+            break;
+        }
+        units += ch.len_utf16() as u32;
+    }
+
+    let start = text[..byte_at]
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| is_word(*c))
+        .last()
+        .map(|(i, _)| i)
+        .unwrap_or(byte_at);
+    let end = text[byte_at..]
+        .char_indices()
+        .find(|(_, c)| !is_word(*c))
+        .map(|(i, _)| byte_at + i)
+        .unwrap_or(text.len());
+    let word = &text[start..end];
+    if word.is_empty() || word.chars().next().is_some_and(|c| c.is_ascii_digit()) {
+        None
+    } else {
+        Some(word.to_string())
+    }
+}
+
+/// Extract symbol definitions from `content` using the built-in rules for
+/// `language_id`. Unsupported languages yield no symbols.
+pub fn extract_symbols(path: &str, language_id: &str, content: &str) -> Vec<Symbol> {
+    let Some(rules) = language_rules(language_id) else {
+        return Vec::new();
+    };
+
+    let line_starts: Vec<usize> = std::iter::once(0)
+        .chain(content.match_indices('\n').map(|(i, _)| i + 1))
+        .collect();
+    let mut seen = std::collections::HashSet::new();
+    let mut symbols = Vec::new();
+    for (regex, kind) in rules {
+        for captures in regex.captures_iter(content) {
+            let Some(name) = captures.name("name") else {
+                continue;
+            };
+            if !seen.insert(name.start()) {
+                continue;
+            }
+            let line = line_starts.partition_point(|&start| start <= name.start()) - 1;
+            let character = content[line_starts[line]..name.start()]
+                .encode_utf16()
+                .count() as u32;
+            symbols.push(Symbol {
+                name: name.as_str().to_string(),
+                kind: *kind,
+                path: path.to_string(),
+                line: line as u32,
+                character,
+            });
+        }
+    }
+    symbols.sort_by_key(|symbol| (symbol.line, symbol.character));
+    symbols
+}
+
+type LanguageRules = Vec<(Regex, SymbolKind)>;
+
+fn language_rules(language_id: &str) -> Option<&'static LanguageRules> {
+    static RULES: OnceLock<HashMap<&'static str, LanguageRules>> = OnceLock::new();
+    let rules = RULES.get_or_init(|| {
+        use SymbolKind::*;
+        let build = |rules: &[(&str, SymbolKind)]| -> LanguageRules {
+            rules
+                .iter()
+                .map(|(pattern, kind)| {
+                    (
+                        Regex::new(&format!("(?m){}", pattern)).expect("valid symbol regex"),
+                        *kind,
+                    )
+                })
+                .collect()
+        };
+
+        let js_rules: &[(&str, SymbolKind)] = &[
+            (
+                r"^[ \t]*(?:export[ \t]+)?(?:default[ \t]+)?(?:async[ \t]+)?function[ \t]*\*?[ \t]*(?P<name>[A-Za-z_$][\w$]*)",
+                Function,
+            ),
+            (
+                r"^[ \t]*(?:export[ \t]+)?(?:default[ \t]+)?(?:abstract[ \t]+)?class[ \t]+(?P<name>[A-Za-z_$][\w$]*)",
+                Class,
+            ),
+            (
+                r"^[ \t]*(?:export[ \t]+)?(?:const|let|var)[ \t]+(?P<name>[A-Za-z_$][\w$]*)[ \t]*(?::[^=]+)?=[ \t]*(?:async[ \t]*)?(?:\([^)]*\)|[A-Za-z_$][\w$]*)[ \t]*(?::[^=]+)?=>",
+                Function,
+            ),
+            (
+                r"^[ \t]*(?:export[ \t]+)?(?:declare[ \t]+)?interface[ \t]+(?P<name>[A-Za-z_$][\w$]*)",
+                Interface,
+            ),
+            (
+                r"^[ \t]*(?:export[ \t]+)?(?:declare[ \t]+)?type[ \t]+(?P<name>[A-Za-z_$][\w$]*)[ \t]*(?:<[^>]*>)?[ \t]*=",
+                Type,
+            ),
+            (
+                r"^[ \t]*(?:export[ \t]+)?(?:declare[ \t]+)?(?:const[ \t]+)?enum[ \t]+(?P<name>[A-Za-z_$][\w$]*)",
+                Enum,
+            ),
+            (
+                r"^(?:export[ \t]+)?(?:const|let|var)[ \t]+(?P<name>[A-Za-z_$][\w$]*)",
+                Variable,
+            ),
+        ];
+        let c_rules: &[(&str, SymbolKind)] = &[
+            (r"^[ \t]*#[ \t]*define[ \t]+(?P<name>[A-Za-z_]\w*)", Macro),
+            (
+                r"^[ \t]*(?:typedef[ \t]+)?struct[ \t]+(?P<name>[A-Za-z_]\w*)[ \t]*\{?[ \t]*$",
+                Struct,
+            ),
+            (
+                r"^[ \t]*(?:typedef[ \t]+)?enum[ \t]+(?:class[ \t]+)?(?P<name>[A-Za-z_]\w*)",
+                Enum,
+            ),
+            (
+                r"^[ \t]*class[ \t]+(?P<name>[A-Za-z_]\w*)[^;]*$",
+                Class,
+            ),
+            (r"^[ \t]*namespace[ \t]+(?P<name>[A-Za-z_]\w*)", Module),
+            (r"^typedef[^;]*[ \t*](?P<name>[A-Za-z_]\w*)[ \t]*;", Type),
+            (
+                r"^[A-Za-z_][\w \t\*&:<>,]*?[ \t\*&](?:[A-Za-z_]\w*::)*(?P<name>[A-Za-z_]\w*)[ \t]*\([^;]*$",
+                Function,
+            ),
+        ];
+
+        let mut map: HashMap<&'static str, LanguageRules> = HashMap::new();
+        map.insert(
+            "rust",
+            build(&[
+                (
+                    r#"^[ \t]*(?:pub(?:\([^)]*\))?[ \t]+)?(?:(?:const|async|unsafe|extern(?:[ \t]+"[^"]*")?)[ \t]+)*fn[ \t]+(?P<name>[A-Za-z_]\w*)"#,
+                    Function,
+                ),
+                (
+                    r"^[ \t]*(?:pub(?:\([^)]*\))?[ \t]+)?struct[ \t]+(?P<name>[A-Za-z_]\w*)",
+                    Struct,
+                ),
+                (
+                    r"^[ \t]*(?:pub(?:\([^)]*\))?[ \t]+)?enum[ \t]+(?P<name>[A-Za-z_]\w*)",
+                    Enum,
+                ),
+                (
+                    r"^[ \t]*(?:pub(?:\([^)]*\))?[ \t]+)?union[ \t]+(?P<name>[A-Za-z_]\w*)",
+                    Struct,
+                ),
+                (
+                    r"^[ \t]*(?:pub(?:\([^)]*\))?[ \t]+)?(?:unsafe[ \t]+)?trait[ \t]+(?P<name>[A-Za-z_]\w*)",
+                    Trait,
+                ),
+                (
+                    r"^[ \t]*(?:pub(?:\([^)]*\))?[ \t]+)?type[ \t]+(?P<name>[A-Za-z_]\w*)",
+                    Type,
+                ),
+                (
+                    r"^[ \t]*(?:pub(?:\([^)]*\))?[ \t]+)?(?:const|static)[ \t]+(?:mut[ \t]+)?(?P<name>[A-Za-z_]\w*)[ \t]*:",
+                    Constant,
+                ),
+                (
+                    r"^[ \t]*(?:pub(?:\([^)]*\))?[ \t]+)?mod[ \t]+(?P<name>[A-Za-z_]\w*)",
+                    Module,
+                ),
+                (r"^[ \t]*macro_rules![ \t]*(?P<name>[A-Za-z_]\w*)", Macro),
+            ]),
+        );
+        map.insert(
+            "python",
+            build(&[
+                (
+                    r"^[ \t]*(?:async[ \t]+)?def[ \t]+(?P<name>[A-Za-z_]\w*)",
+                    Function,
+                ),
+                (r"^[ \t]*class[ \t]+(?P<name>[A-Za-z_]\w*)", Class),
+                (
+                    r"^(?P<name>[A-Za-z_]\w*)[ \t]*(?::[^=\n]+)?=[^=]",
+                    Variable,
+                ),
+            ]),
+        );
+        for language in [
+            "javascript",
+            "javascriptreact",
+            "typescript",
+            "typescriptreact",
+        ] {
+            map.insert(language, build(js_rules));
+        }
+        map.insert(
+            "go",
+            build(&[
+                (
+                    r"^func[ \t]+(?:\([^)]*\)[ \t]*)?(?P<name>[A-Za-z_]\w*)",
+                    Function,
+                ),
+                (r"^type[ \t]+(?P<name>[A-Za-z_]\w*)[ \t]+struct\b", Struct),
+                (
+                    r"^type[ \t]+(?P<name>[A-Za-z_]\w*)[ \t]+interface\b",
+                    Interface,
+                ),
+                (r"^type[ \t]+(?P<name>[A-Za-z_]\w*)", Type),
+                (r"^const[ \t]+(?P<name>[A-Za-z_]\w*)", Constant),
+                (r"^var[ \t]+(?P<name>[A-Za-z_]\w*)", Variable),
+            ]),
+        );
+        map.insert("c", build(c_rules));
+        map.insert("cpp", build(c_rules));
+        map.insert(
+            "java",
+            build(&[
+                (
+                    r"^[ \t]*(?:(?:public|private|protected|static|final|abstract|sealed)[ \t]+)*class[ \t]+(?P<name>[A-Za-z_]\w*)",
+                    Class,
+                ),
+                (
+                    r"^[ \t]*(?:(?:public|private|protected|static)[ \t]+)*interface[ \t]+(?P<name>[A-Za-z_]\w*)",
+                    Interface,
+                ),
+                (
+                    r"^[ \t]*(?:(?:public|private|protected|static)[ \t]+)*(?:enum|record)[ \t]+(?P<name>[A-Za-z_]\w*)",
+                    Enum,
+                ),
+                (
+                    r"^[ \t]+(?:(?:public|private|protected|static|final|abstract|synchronized|native|default)[ \t]+)+(?:<[^>]*>[ \t]+)?[\w<>\[\],.? \t]+?[ \t]+(?P<name>[A-Za-z_]\w*)[ \t]*\(",
+                    Method,
+                ),
+            ]),
+        );
+        map.insert(
+            "ruby",
+            build(&[
+                (
+                    r"^[ \t]*def[ \t]+(?:self\.)?(?P<name>[A-Za-z_]\w*[?!=]?)",
+                    Method,
+                ),
+                (r"^[ \t]*class[ \t]+(?:\w+::)*(?P<name>[A-Z]\w*)", Class),
+                (r"^[ \t]*module[ \t]+(?:\w+::)*(?P<name>[A-Z]\w*)", Module),
+                (r"^[ \t]*(?P<name>[A-Z][A-Z0-9_]*)[ \t]*=[^=]", Constant),
+            ]),
+        );
+        map.insert(
+            "php",
+            build(&[
+                (
+                    r"^[ \t]*(?:(?:public|private|protected|static|final|abstract)[ \t]+)*function[ \t]+&?(?P<name>[A-Za-z_]\w*)",
+                    Function,
+                ),
+                (
+                    r"^[ \t]*(?:(?:final|abstract|readonly)[ \t]+)*class[ \t]+(?P<name>[A-Za-z_]\w*)",
+                    Class,
+                ),
+                (r"^[ \t]*interface[ \t]+(?P<name>[A-Za-z_]\w*)", Interface),
+                (r"^[ \t]*trait[ \t]+(?P<name>[A-Za-z_]\w*)", Trait),
+                (r"^[ \t]*enum[ \t]+(?P<name>[A-Za-z_]\w*)", Enum),
+            ]),
+        );
+        map.insert(
+            "shellscript",
+            build(&[
+                (r"^[ \t]*function[ \t]+(?P<name>[A-Za-z_][\w-]*)", Function),
+                (r"^[ \t]*(?P<name>[A-Za-z_][\w-]*)[ \t]*\(\)", Function),
+            ]),
+        );
+        map.insert(
+            "lua",
+            build(&[(
+                r"^[ \t]*(?:local[ \t]+)?function[ \t]+(?:[A-Za-z_]\w*[.:])*(?P<name>[A-Za-z_]\w*)",
+                Function,
+            )]),
+        );
+        map.insert(
+            "zig",
+            build(&[
+                (
+                    r"^[ \t]*(?:pub[ \t]+)?(?:export[ \t]+|inline[ \t]+)?fn[ \t]+(?P<name>[A-Za-z_]\w*)",
+                    Function,
+                ),
+                (
+                    r"^[ \t]*(?:pub[ \t]+)?const[ \t]+(?P<name>[A-Za-z_]\w*)[ \t]*=[ \t]*(?:extern[ \t]+|packed[ \t]+)?(?:struct|union|opaque)\b",
+                    Struct,
+                ),
+                (
+                    r"^[ \t]*(?:pub[ \t]+)?const[ \t]+(?P<name>[A-Za-z_]\w*)[ \t]*=[ \t]*enum\b",
+                    Enum,
+                ),
+                (
+                    r"^(?:pub[ \t]+)?(?:const|var)[ \t]+(?P<name>[A-Za-z_]\w*)",
+                    Variable,
+                ),
+            ]),
+        );
+        map
+    });
+    rules.get(language_id)
+}
+
+/// Load the first ctags file found at `root`. Both numeric (`42;"`) and
+/// search-pattern (`/^fn main() {$/;"`) addresses are supported.
+fn load_tags_file(root: &Path) -> Vec<TagEntry> {
+    for name in TAGS_FILE_NAMES {
+        let tags_path = root.join(name);
+        if let Ok(content) = std::fs::read_to_string(&tags_path) {
+            return parse_ctags(&content, root);
+        }
+    }
+    Vec::new()
+}
+
+fn parse_ctags(content: &str, root: &Path) -> Vec<TagEntry> {
+    content
+        .lines()
+        .filter(|line| !line.starts_with("!_TAG_"))
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\t');
+            let name = fields.next()?;
+            let file = fields.next()?;
+            let rest = fields.next()?;
+            let (address, extensions) = match rest.find(";\"") {
+                Some(i) => (&rest[..i], rest[i + 2..].trim_start_matches('\t')),
+                None => (rest, ""),
+            };
+
+            let mut kind = SymbolKind::Variable;
+            let mut line = None;
+            for field in extensions.split('\t').filter(|f| !f.is_empty()) {
+                match field.split_once(':') {
+                    Some(("kind", value)) => kind = SymbolKind::from_ctags_kind(value),
+                    Some(("line", value)) => line = value.parse::<u32>().ok(),
+                    None => kind = SymbolKind::from_ctags_kind(field),
+                    _ => {}
+                }
+            }
+
+            let mut pattern = None;
+            if let Ok(number) = address.trim().parse::<u32>() {
+                line = Some(number);
+            } else if let Some(body) = address
+                .strip_prefix('/')
+                .and_then(|a| a.strip_suffix('/'))
+                .or_else(|| address.strip_prefix('?').and_then(|a| a.strip_suffix('?')))
+            {
+                let body = body.strip_prefix('^').unwrap_or(body);
+                let body = body.strip_suffix('$').unwrap_or(body);
+                pattern = Some(body.replace("\\/", "/").replace("\\\\", "\\"));
+            }
+
+            let path = if Path::new(file).is_absolute() {
+                PathBuf::from(file)
+            } else {
+                root.join(file)
+            };
+            Some(TagEntry {
+                name: name.to_string(),
+                kind,
+                path: path.to_string_lossy().to_string(),
+                // ctags lines are 1-based.
+                line: line.map(|l| l.saturating_sub(1)),
+                pattern,
+            })
+        })
+        .collect()
+}
+
+fn resolve_tag(tag: &TagEntry) -> Option<Symbol> {
+    let (line, text) = match (&tag.line, &tag.pattern) {
+        (Some(line), _) => {
+            let text = std::fs::read_to_string(&tag.path)
+                .ok()
+                .and_then(|content| content.lines().nth(*line as usize).map(str::to_string));
+            (*line, text)
+        }
+        (None, Some(pattern)) => {
+            let content = std::fs::read_to_string(&tag.path).ok()?;
+            let (line, text) = content
+                .lines()
+                .enumerate()
+                .find(|(_, line)| line.contains(pattern.as_str()))?;
+            (line as u32, Some(text.to_string()))
+        }
+        (None, None) => return None,
+    };
+    let character = text
+        .and_then(|text| {
+            text.find(&tag.name)
+                .map(|i| text[..i].encode_utf16().count() as u32)
+        })
+        .unwrap_or(0);
+    Some(Symbol {
+        name: tag.name.clone(),
+        kind: tag.kind,
+        path: tag.path.clone(),
+        line,
+        character,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(symbols: &[Symbol]) -> Vec<(&str, SymbolKind, u32)> {
+        symbols
+            .iter()
+            .map(|s| (s.name.as_str(), s.kind, s.line))
+            .collect()
+    }
+
+    #[test]
+    fn extract_symbols_rust() {
+        let content = "pub struct Config {}\n\nimpl Config {\n    pub(crate) async fn load() {}\n}\n\nconst MAX: usize = 3;\nmacro_rules! log_it { () => {} }\n";
+        let symbols = extract_symbols("/w/lib.rs", "rust", content);
+        assert_eq!(
+            names(&symbols),
+            vec![
+                ("Config", SymbolKind::Struct, 0),
+                ("load", SymbolKind::Function, 3),
+                ("MAX", SymbolKind::Constant, 6),
+                ("log_it", SymbolKind::Macro, 7),
+            ]
+        );
+        assert_eq!(symbols[1].character, 24);
+    }
+
+    #[test]
+    fn extract_symbols_typescript_and_python() {
+        let ts = "export interface Props {}\nexport const render = async (p: Props) => {};\nfunction helper() {}\n";
+        assert_eq!(
+            names(&extract_symbols("/w/a.ts", "typescript", ts)),
+            vec![
+                ("Props", SymbolKind::Interface, 0),
+                ("render", SymbolKind::Function, 1),
+                ("helper", SymbolKind::Function, 2),
+            ]
+        );
+
+        let py = "class Parser:\n    def parse(self):\n        x = 1\nVERSION = '1'\n";
+        assert_eq!(
+            names(&extract_symbols("/w/a.py", "python", py)),
+            vec![
+                ("Parser", SymbolKind::Class, 0),
+                ("parse", SymbolKind::Function, 1),
+                ("VERSION", SymbolKind::Variable, 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn extract_symbols_unknown_language_is_empty() {
+        assert!(extract_symbols("/w/a.txt", "plaintext", "fn main() {}").is_empty());
+    }
+
+    #[test]
+    fn word_at_handles_boundaries() {
+        let content = "let value = other_fn(x);";
+        assert_eq!(word_at(content, 0, 14).as_deref(), Some("other_fn"));
+        assert_eq!(word_at(content, 0, 12).as_deref(), Some("other_fn"));
+        assert_eq!(word_at(content, 0, 20).as_deref(), Some("other_fn"));
+        assert_eq!(word_at(content, 0, 10), None);
+        assert_eq!(word_at(content, 3, 0), None);
+    }
+
+    #[test]
+    fn parse_ctags_supports_line_and_pattern_addresses() {
+        let tags = "!_TAG_FILE_FORMAT\t2\t/extended format/\nmain\tsrc/main.zig\t/^pub fn main() void {$/;\"\tf\nPoint\tsrc/geo.nim\t12;\"\tkind:type\n";
+        let entries = parse_ctags(tags, Path::new("/w"));
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].name, "main");
+        assert_eq!(entries[0].kind, SymbolKind::Function);
+        assert_eq!(entries[0].pattern.as_deref(), Some("pub fn main() void {"));
+        assert_eq!(entries[1].path, "/w/src/geo.nim");
+        assert_eq!(entries[1].line, Some(11));
+        assert_eq!(entries[1].kind, SymbolKind::Type);
+    }
+
+    #[test]
+    fn index_builds_and_finds_definitions() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path();
+        std::fs::write(root.join("a.go"), "package a\n\nfunc Shared() {}\n").unwrap();
+        std::fs::write(root.join("b.rs"), "fn shared_helper() {}\n").unwrap();
+        std::fs::write(root.join("util.nim"), "proc nimProc() =\n  discard\n").unwrap();
+        std::fs::write(
+            root.join("tags"),
+            "nimProc\tutil.nim\t/^proc nimProc() =$/;\"\tf\n",
+        )
+        .unwrap();
+
+        let mut index = SymbolIndex::new(root);
+        index.ensure_built();
+        assert!(index.is_built());
+
+        let caller = root.join("c.go").to_string_lossy().to_string();
+        let defs = index.definitions_at(&caller, "Shared()\n", 0, 2);
+        assert_eq!(defs.len(), 1);
+        assert!(defs[0].path.ends_with("a.go"));
+        assert_eq!((defs[0].line, defs[0].character), (2, 5));
+
+        let nim = index.find_definitions("nimProc");
+        assert_eq!(nim.len(), 1);
+        assert_eq!((nim[0].line, nim[0].character), (0, 5));
+
+        let found = index.workspace_symbols("shared", 10);
+        assert_eq!(found[0].name, "Shared");
+        assert_eq!(found[1].name, "shared_helper");
+    }
+
+    #[test]
+    fn indexes_resolve_workspace_by_file_and_track_changes() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path().join("proj");
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        git2::Repository::init(&root).unwrap();
+        std::fs::write(root.join("src/lib.rs"), "fn first() {}\n").unwrap();
+
+        let file = root.join("src/lib.rs").to_string_lossy().to_string();
+        let mut indexes = SymbolIndexes::new();
+        assert_eq!(indexes.for_file(&file).find_definitions("first").len(), 1);
+
+        indexes.file_changed(&file, "fn second() {}\n");
+        let index = indexes.for_file(&file);
+        assert!(index.find_definitions("first").is_empty());
+        assert_eq!(index.find_definitions("second").len(), 1);
+    }
+
+    #[test]
+    fn update_and_remove_file() {
+        let mut index = SymbolIndex::new("/w");
+        index.update_file("/w/a.rs", "fn one() {}\n");
+        assert_eq!(index.find_definitions("one").len(), 1);
+        index.update_file("/w/a.rs", "fn two() {}\n");
+        assert!(index.find_definitions("one").is_empty());
+        index.remove_file("/w/a.rs");
+        assert!(index.find_definitions("two").is_empty());
+    }
+}
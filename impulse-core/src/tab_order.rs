@@ -0,0 +1,143 @@
+//! Commands that act on many tabs at once: sorting, closing a group of tabs
+//! and filtering the "Show All Tabs" list. Frontends describe their tabs in
+//! order as [`TabSummary`]s and apply the indices these return. Pinned tabs
+//! stay at the front and are never closed as part of a group.
+
+use std::cmp::Ordering;
+
+use serde::{Deserialize, Serialize};
+
+/// One open tab, as the frontend sees it.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default)]
+pub struct TabSummary {
+    pub title: String,
+    /// File behind an editor or image tab; `None` for terminals and
+    /// untitled editors.
+    pub path: Option<String>,
+    pub pinned: bool,
+    /// Has unsaved changes.
+    pub modified: bool,
+    /// When the tab was last selected, in any increasing unit; 0 if never.
+    pub last_used: u64,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TabSort {
+    /// Files by path, then other tabs by title.
+    Path,
+    /// Most recently selected first.
+    RecentUse,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TabGroup {
+    /// File tabs without unsaved changes.
+    Saved,
+    /// Tabs after the anchor tab.
+    Right,
+    /// Tabs before the anchor tab.
+    Left,
+}
+
+/// The new order of `tabs` as indices into it. Pinned tabs keep their
+/// place at the front; the rest are sorted after them.
+pub fn sorted_order(tabs: &[TabSummary], sort: TabSort) -> Vec<usize> {
+    let (mut order, mut unpinned): (Vec<usize>, Vec<usize>) =
+        (0..tabs.len()).partition(|&i| tabs[i].pinned);
+    unpinned.sort_by(|&a, &b| compare(&tabs[a], &tabs[b], sort));
+    order.extend(unpinned);
+    order
+}
+
+fn compare(a: &TabSummary, b: &TabSummary, sort: TabSort) -> Ordering {
+    match sort {
+        TabSort::RecentUse => b.last_used.cmp(&a.last_used),
+        TabSort::Path => match (&a.path, &b.path) {
+            (Some(a), Some(b)) => a.to_lowercase().cmp(&b.to_lowercase()).then(a.cmp(b)),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => a.title.to_lowercase().cmp(&b.title.to_lowercase()),
+        },
+    }
+}
+
+/// Indices of the tabs in `group` relative to the tab at `anchor`, never
+/// including pinned tabs. Modified tabs are included for `Right` and
+/// `Left`; closing them asks about unsaved changes as usual.
+pub fn tabs_to_close(tabs: &[TabSummary], anchor: usize, group: TabGroup) -> Vec<usize> {
+    (0..tabs.len())
+        .filter(|&i| !tabs[i].pinned)
+        .filter(|&i| match group {
+            TabGroup::Saved => tabs[i].path.is_some() && !tabs[i].modified,
+            TabGroup::Right => i > anchor,
+            TabGroup::Left => i < anchor,
+        })
+        .collect()
+}
+
+/// Indices of the tabs whose title or path contains every word of `query`,
+/// ignoring case. An empty query matches every tab.
+pub fn filter(tabs: &[TabSummary], query: &str) -> Vec<usize> {
+    let words: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+    (0..tabs.len())
+        .filter(|&i| {
+            let haystack = format!(
+                "{} {}",
+                tabs[i].title,
+                tabs[i].path.as_deref().unwrap_or_default()
+            )
+            .to_lowercase();
+            words.iter().all(|word| haystack.contains(word.as_str()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tab(title: &str, path: Option<&str>, last_used: u64) -> TabSummary {
+        TabSummary {
+            title: title.to_string(),
+            path: path.map(str::to_string),
+            last_used,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn sorting_keeps_pinned_tabs_first() {
+        let mut tabs = vec![
+            tab("zsh", None, 5),
+            tab("main.rs", Some("/p/src/main.rs"), 1),
+            tab("Cargo.toml", Some("/p/Cargo.toml"), 9),
+            tab("lib.rs", Some("/p/src/lib.rs"), 3),
+        ];
+        tabs[3].pinned = true;
+
+        assert_eq!(sorted_order(&tabs, TabSort::Path), vec![3, 2, 1, 0]);
+        assert_eq!(sorted_order(&tabs, TabSort::RecentUse), vec![3, 2, 0, 1]);
+    }
+
+    #[test]
+    fn groups_skip_pinned_tabs() {
+        let mut tabs = vec![
+            tab("a.rs", Some("/p/a.rs"), 0),
+            tab("b.rs", Some("/p/b.rs"), 0),
+            tab("zsh", None, 0),
+            tab("c.rs", Some("/p/c.rs"), 0),
+            tab("d.rs", Some("/p/d.rs"), 0),
+        ];
+        tabs[0].pinned = true;
+        tabs[3].modified = true;
+
+        assert_eq!(tabs_to_close(&tabs, 2, TabGroup::Left), vec![1]);
+        assert_eq!(tabs_to_close(&tabs, 2, TabGroup::Right), vec![3, 4]);
+        assert_eq!(tabs_to_close(&tabs, 2, TabGroup::Saved), vec![1, 4]);
+        assert_eq!(filter(&tabs, "P  B"), vec![1]);
+        assert_eq!(filter(&tabs, ""), vec![0, 1, 2, 3, 4]);
+    }
+}
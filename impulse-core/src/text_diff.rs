@@ -0,0 +1,123 @@
+//! Plain text diffing outside of git: compares two buffers or two arbitrary
+//! files and returns hunks in the same [`FileHunks`] shape the git review
+//! views render.
+
+use std::path::Path;
+
+use crate::git::{hunks_from_patch, FileHunks, MAX_DIFF_CONTENT_SIZE};
+
+/// Diff `old` against `new`, labelling the hunks with Monaco `language`.
+pub fn diff_texts(old: &str, new: &str, language: &str) -> Result<FileHunks, String> {
+    if old.len() as u64 > MAX_DIFF_CONTENT_SIZE || new.len() as u64 > MAX_DIFF_CONTENT_SIZE {
+        return Ok(FileHunks::blank(language.to_string(), false, true));
+    }
+    let patch = git2::Patch::from_buffers(old.as_bytes(), None, new.as_bytes(), None, None)
+        .map_err(|e| format!("Diff failed: {}", e))?;
+    hunks_from_patch(&patch, language.to_string())
+}
+
+/// Diff the file at `old_path` against the file at `new_path`. The language
+/// is taken from `new_path`. Oversized files and files that are not UTF-8
+/// text come back blanked with `too_large` / `is_binary` set.
+pub fn diff_files(old_path: &Path, new_path: &Path) -> Result<FileHunks, String> {
+    let language = language_for(new_path);
+    let old = match read_text(old_path, &language)? {
+        Ok(text) => text,
+        Err(blank) => return Ok(blank),
+    };
+    let new = match read_text(new_path, &language)? {
+        Ok(text) => text,
+        Err(blank) => return Ok(blank),
+    };
+    diff_texts(&old, &new, &language)
+}
+
+/// Diff the saved file at `path` against `buffer`, the editor's unsaved
+/// content ("Compare with Saved"). The saved side is checked like in
+/// [`diff_files`].
+pub fn diff_saved(path: &Path, buffer: &str) -> Result<FileHunks, String> {
+    let language = language_for(path);
+    match read_text(path, &language)? {
+        Ok(saved) => diff_texts(&saved, buffer, &language),
+        Err(blank) => Ok(blank),
+    }
+}
+
+fn language_for(path: &Path) -> String {
+    crate::util::file_path_to_uri(path)
+        .map(|uri| crate::util::language_from_uri(&uri))
+        .unwrap_or_default()
+}
+
+/// The text of the file at `path`, or blank hunks flagged `too_large` /
+/// `is_binary` when it can't be diffed as text.
+fn read_text(path: &Path, language: &str) -> Result<Result<String, FileHunks>, String> {
+    let metadata =
+        std::fs::metadata(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+    if !metadata.is_file() {
+        return Err(format!("{} is not a file", path.display()));
+    }
+    if metadata.len() > MAX_DIFF_CONTENT_SIZE {
+        return Ok(Err(FileHunks::blank(language.to_string(), false, true)));
+    }
+    let bytes =
+        std::fs::read(path).map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+    match String::from_utf8(bytes) {
+        Ok(text) if !text.contains('\0') => Ok(Ok(text)),
+        _ => Ok(Err(FileHunks::blank(language.to_string(), true, false))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::DiffLineKind;
+
+    #[test]
+    fn diff_texts_reports_changed_lines() {
+        let fh = diff_texts("a\nb\nc\n", "a\nB\nc\nd\n", "plaintext").unwrap();
+        assert_eq!(fh.language, "plaintext");
+        assert_eq!(fh.added, 2);
+        assert_eq!(fh.removed, 1);
+        let removed: Vec<_> = fh.hunks[0]
+            .lines
+            .iter()
+            .filter(|l| l.kind == DiffLineKind::Removed)
+            .map(|l| (l.old_lineno, l.content.as_str()))
+            .collect();
+        assert_eq!(removed, vec![(Some(2), "b")]);
+        assert!(diff_texts("same\n", "same\n", "").unwrap().hunks.is_empty());
+    }
+
+    #[test]
+    fn diff_files_uses_new_path_language_and_flags_binary() {
+        let temp = tempfile::tempdir().unwrap();
+        let old = temp.path().join("old.txt");
+        let new = temp.path().join("new.py");
+        std::fs::write(&old, "x = 1\n").unwrap();
+        std::fs::write(&new, "x = 2\n").unwrap();
+        let fh = diff_files(&old, &new).unwrap();
+        assert_eq!(fh.language, "python");
+        assert_eq!((fh.added, fh.removed), (1, 1));
+
+        let bin = temp.path().join("blob.bin");
+        std::fs::write(&bin, [0u8, 159, 146, 150]).unwrap();
+        assert!(diff_files(&old, &bin).unwrap().is_binary);
+        assert!(diff_files(&old, temp.path()).is_err());
+    }
+
+    #[test]
+    fn diff_saved_compares_the_file_with_the_buffer() {
+        let temp = tempfile::tempdir().unwrap();
+        let file = temp.path().join("main.rs");
+        std::fs::write(&file, "fn main() {}\n").unwrap();
+        let fh = diff_saved(&file, "fn main() {\n    run();\n}\n").unwrap();
+        assert_eq!(fh.language, "rust");
+        assert_eq!((fh.added, fh.removed), (3, 1));
+        assert!(diff_saved(&file, "fn main() {}\n")
+            .unwrap()
+            .hunks
+            .is_empty());
+        assert!(diff_saved(&temp.path().join("gone.rs"), "").is_err());
+    }
+}
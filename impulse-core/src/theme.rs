@@ -191,10 +191,12 @@ struct Hsl {
 
 fn hex_to_rgb(hex: &str) -> (u8, u8, u8) {
     let hex = hex.trim_start_matches('#');
-    let r = u8::from_str_radix(&hex[0..2], 16).unwrap_or(0);
-    let g = u8::from_str_radix(&hex[2..4], 16).unwrap_or(0);
-    let b = u8::from_str_radix(&hex[4..6], 16).unwrap_or(0);
-    (r, g, b)
+    let channel = |range| {
+        hex.get(range)
+            .and_then(|c| u8::from_str_radix(c, 16).ok())
+            .unwrap_or(0)
+    };
+    (channel(0..2), channel(2..4), channel(4..6))
 }
 
 fn rgb_to_hsl(r: u8, g: u8, b: u8) -> Hsl {
@@ -293,6 +295,57 @@ fn mute_color(hex: &str, saturation_factor: f64, lightness_target: f64) -> Strin
     rgb_to_hex(r, g, b)
 }
 
+fn luminance_channel(c: f64) -> f64 {
+    if c <= 0.03928 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// WCAG relative luminance of a `#rrggbb` color.
+fn relative_luminance(hex: &str) -> f64 {
+    let (r, g, b) = hex_to_rgb(hex);
+    0.2126 * luminance_channel(r as f64 / 255.0)
+        + 0.7152 * luminance_channel(g as f64 / 255.0)
+        + 0.0722 * luminance_channel(b as f64 / 255.0)
+}
+
+/// WCAG contrast ratio (1 to 21) of `fg` on `bg`. An alpha suffix on `fg`
+/// is ignored.
+pub fn contrast_ratio(fg: &str, bg: &str) -> f64 {
+    let l1 = relative_luminance(fg.get(..7).unwrap_or(fg));
+    let l2 = relative_luminance(bg);
+    let (hi, lo) = if l1 >= l2 { (l1, l2) } else { (l2, l1) };
+    (hi + 0.05) / (lo + 0.05)
+}
+
+fn is_hex_color(s: &str) -> bool {
+    s.starts_with('#')
+        && s.as_bytes()
+            .get(1..7)
+            .is_some_and(|digits| digits.iter().all(u8::is_ascii_hexdigit))
+}
+
+/// `color` made lighter (on dark themes) or darker (on light ones) until it
+/// reaches `min_ratio` against every one of `surfaces`, keeping its hue and
+/// any alpha suffix. Colors already there are returned unchanged.
+fn with_min_contrast(color: &str, surfaces: &[&str], min_ratio: f64, is_light: bool) -> String {
+    if !is_hex_color(color) || !surfaces.iter().all(|s| is_hex_color(s)) {
+        return color.to_string();
+    }
+    let (mut hex, alpha) = (color[..7].to_string(), &color[7..]);
+    let meets = |hex: &str| surfaces.iter().all(|s| contrast_ratio(hex, s) >= min_ratio);
+    let step = if is_light { -0.02 } else { 0.02 };
+    for _ in 0..50 {
+        if meets(&hex) {
+            break;
+        }
+        hex = shift_lightness(&hex, step);
+    }
+    format!("{hex}{alpha}")
+}
+
 // ---------------------------------------------------------------------------
 // Theme resolution
 // ---------------------------------------------------------------------------
@@ -302,6 +355,11 @@ pub fn parse_theme(toml_str: &str) -> Result<ThemeFile, String> {
     toml::from_str(toml_str).map_err(|e| format!("Failed to parse theme TOML: {e}"))
 }
 
+/// Parse a JSON theme string (the same schema as TOML) into a `ThemeFile`.
+pub fn parse_theme_json(json: &str) -> Result<ThemeFile, String> {
+    serde_json::from_str(json).map_err(|e| format!("Failed to parse theme JSON: {e}"))
+}
+
 /// Resolve a parsed `ThemeFile` into a fully-populated `ResolvedTheme`.
 /// Fills in all defaults and derived values from the palette.
 /// The `id` parameter is the lookup key (e.g. `"rose-pine"`, not the display
@@ -538,6 +596,95 @@ pub fn resolve_theme(id: &str, tf: &ThemeFile) -> ResolvedTheme {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Increased contrast
+// ---------------------------------------------------------------------------
+
+/// Contrast every readable color reaches with `increase_contrast` (WCAG AAA).
+const INCREASED_TEXT_CONTRAST: f64 = 7.0;
+/// Contrast borders reach with `increase_contrast` (WCAG non-text minimum).
+const INCREASED_BORDER_CONTRAST: f64 = 3.0;
+/// Least opacity of the selection color with `increase_contrast`.
+const INCREASED_SELECTION_ALPHA: u8 = 0x70;
+
+impl ResolvedTheme {
+    /// The increase-contrast variant of this theme, used when the
+    /// `increase_contrast` setting is on or the system asks for more
+    /// contrast. Backgrounds stay; text, syntax, git and terminal colors are
+    /// pushed away from them to 7:1, borders to 3:1, and the selection is
+    /// made more opaque.
+    pub fn with_increased_contrast(&self) -> ResolvedTheme {
+        let mut t = self.clone();
+        let is_light = t.is_light;
+        let surfaces = [t.bg.clone(), t.bg_dark.clone(), t.bg_surface.clone()];
+        let surfaces: Vec<&str> = surfaces.iter().map(String::as_str).collect();
+        let text = |color: &mut String| {
+            *color = with_min_contrast(color, &surfaces, INCREASED_TEXT_CONTRAST, is_light);
+        };
+        for color in [
+            &mut t.fg,
+            &mut t.fg_muted,
+            &mut t.fg_comment,
+            &mut t.accent,
+            &mut t.cursor,
+            &mut t.red,
+            &mut t.orange,
+            &mut t.yellow,
+            &mut t.green,
+            &mut t.cyan,
+            &mut t.blue,
+            &mut t.magenta,
+            &mut t.git_added,
+            &mut t.git_modified,
+            &mut t.git_deleted,
+            &mut t.git_renamed,
+            &mut t.git_conflict,
+            &mut t.git_ignored,
+            &mut t.syntax_keyword,
+            &mut t.syntax_function,
+            &mut t.syntax_type,
+            &mut t.syntax_string,
+            &mut t.syntax_number,
+            &mut t.syntax_constant,
+            &mut t.syntax_comment,
+            &mut t.syntax_operator,
+            &mut t.syntax_tag,
+            &mut t.syntax_attribute,
+            &mut t.syntax_variable,
+            &mut t.syntax_delimiter,
+            &mut t.syntax_escape,
+            &mut t.syntax_regexp,
+            &mut t.syntax_link,
+        ] {
+            text(color);
+        }
+        t.border = with_min_contrast(&t.border, &surfaces, INCREASED_BORDER_CONTRAST, is_light);
+        if t.selection.len() == 9 && is_hex_color(&t.selection) {
+            let alpha = u8::from_str_radix(&t.selection[7..], 16).unwrap_or(0);
+            if alpha < INCREASED_SELECTION_ALPHA {
+                t.selection = format!("{}{:02x}", &t.selection[..7], INCREASED_SELECTION_ALPHA);
+            }
+        }
+
+        let terminal_bg = [t.terminal_bg.as_str()];
+        t.terminal_fg = with_min_contrast(
+            &t.terminal_fg,
+            &terminal_bg,
+            INCREASED_TEXT_CONTRAST,
+            is_light,
+        );
+        // The slot matching the background (black on dark themes, white on
+        // light ones) is left alone, as programs draw backgrounds with it.
+        let background_slot = if is_light { 15 } else { 0 };
+        for (i, color) in t.terminal_palette.iter_mut().enumerate() {
+            if i != background_slot {
+                *color = with_min_contrast(color, &terminal_bg, INCREASED_TEXT_CONTRAST, is_light);
+            }
+        }
+        t
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Built-in themes
 // ---------------------------------------------------------------------------
@@ -579,6 +726,18 @@ const BUILTIN_THEMES: &[(&str, &str)] = &[
         include_str!("../themes/catppuccin-latte.toml"),
     ),
     ("github-light", include_str!("../themes/github-light.toml")),
+    (
+        "rose-pine-dawn",
+        include_str!("../themes/rose-pine-dawn.toml"),
+    ),
+    (
+        "gruvbox-light",
+        include_str!("../themes/gruvbox-light.toml"),
+    ),
+    (
+        "tokyo-night-day",
+        include_str!("../themes/tokyo-night-day.toml"),
+    ),
     ("harbor", include_str!("../themes/harbor.toml")),
 ];
 
@@ -602,7 +761,8 @@ pub fn builtin_theme(name: &str) -> Option<ResolvedTheme> {
         .map(|(id, tf)| resolve_theme(id, &tf))
 }
 
-/// Discover user themes from the platform config directory.
+/// Discover user themes (`.toml`, or `.json` in Impulse or VS Code format)
+/// from the platform config directory.
 ///
 /// Returns `(theme_id, file_path)` pairs. The theme ID is the filename stem.
 pub fn discover_user_themes() -> Vec<(String, std::path::PathBuf)> {
@@ -615,7 +775,10 @@ pub fn discover_user_themes() -> Vec<(String, std::path::PathBuf)> {
     if let Ok(entries) = std::fs::read_dir(&dir) {
         for entry in entries.flatten() {
             let path = entry.path();
-            if path.extension().and_then(|e| e.to_str()) == Some("toml") {
+            if matches!(
+                path.extension().and_then(|e| e.to_str()),
+                Some("toml" | "json")
+            ) {
                 if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
                     themes.push((stem.to_string(), path));
                 }
@@ -636,7 +799,15 @@ pub fn load_user_theme(path: &std::path::Path) -> Result<ResolvedTheme, String>
         .to_string();
     let contents =
         std::fs::read_to_string(path).map_err(|e| format!("Failed to read theme file: {e}"))?;
-    let tf = parse_theme(&contents)?;
+    let tf = if path.extension().and_then(|e| e.to_str()) == Some("json") {
+        if crate::vscode_theme::is_vscode_theme(&contents) {
+            crate::vscode_theme::convert_vscode_theme_file(path)?
+        } else {
+            parse_theme_json(&contents)?
+        }
+    } else {
+        parse_theme(&contents)?
+    };
     Ok(resolve_theme(&id, &tf))
 }
 
@@ -690,6 +861,7 @@ pub fn theme_display_name(id: &str) -> String {
         "catppuccin-latte" => "Catppuccin Latte".to_string(),
         "github-dark" => "GitHub Dark".to_string(),
         "github-light" => "GitHub Light".to_string(),
+        "rose-pine-dawn" => "Rosé Pine Dawn".to_string(),
         "monokai-pro" => "Monokai Pro".to_string(),
         _ => id
             .split('-')
@@ -718,6 +890,32 @@ pub fn theme_from_json(json: &str) -> Result<ResolvedTheme, String> {
     serde_json::from_str(json).map_err(|e| format!("Failed to parse theme JSON: {e}"))
 }
 
+/// Path of the user stylesheet layered over the generated UI CSS
+/// (`custom.css` next to the `themes/` directory).
+pub fn custom_css_path() -> Option<std::path::PathBuf> {
+    user_config_dir().map(|d| d.join("custom.css"))
+}
+
+/// Path of the user stylesheet injected into the Monaco editor page
+/// (`editor.css` next to the `themes/` directory).
+pub fn editor_css_path() -> Option<std::path::PathBuf> {
+    user_config_dir().map(|d| d.join("editor.css"))
+}
+
+/// Read a user stylesheet. A missing or blank file yields `None`; read errors
+/// are logged and treated the same way.
+pub fn read_user_css(path: &std::path::Path) -> Option<String> {
+    match std::fs::read_to_string(path) {
+        Ok(css) if !css.trim().is_empty() => Some(css),
+        Ok(_) => None,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => {
+            log::warn!("Failed to read {}: {e}", path.display());
+            None
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Internals
 // ---------------------------------------------------------------------------
@@ -743,21 +941,30 @@ fn normalize_theme_id(name: &str) -> String {
         "solarized_light" | "solarizedlight" => "solarized-light".to_string(),
         "catppuccin_latte" | "catppuccinlatte" => "catppuccin-latte".to_string(),
         "github_light" | "githublight" => "github-light".to_string(),
+        "rose_pine_dawn" | "rosepinedawn" => "rose-pine-dawn".to_string(),
+        "gruvbox_light" | "gruvboxlight" => "gruvbox-light".to_string(),
+        "tokyo_night_day" | "tokyonightday" => "tokyo-night-day".to_string(),
         other => other.to_string(),
     }
 }
 
-fn user_themes_dir() -> Option<std::path::PathBuf> {
+/// Where user theme files and stylesheets live.
+fn user_config_dir() -> Option<std::path::PathBuf> {
     #[cfg(target_os = "macos")]
     {
-        dirs::home_dir().map(|h| h.join("Library/Application Support/impulse/themes"))
+        dirs::home_dir().map(|h| h.join("Library/Application Support/impulse"))
     }
     #[cfg(not(target_os = "macos"))]
     {
-        dirs::config_dir().map(|c| c.join("impulse/themes"))
+        dirs::config_dir().map(|c| c.join("impulse"))
     }
 }
 
+/// Where user theme files live.
+pub(crate) fn user_themes_dir() -> Option<std::path::PathBuf> {
+    user_config_dir().map(|d| d.join("themes"))
+}
+
 // ---------------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------------
@@ -825,6 +1032,9 @@ mod tests {
         assert!(theme.is_light);
         let theme = get_theme("harbor");
         assert!(theme.is_light);
+        for id in ["rose-pine-dawn", "gruvbox-light", "tokyo-night-day"] {
+            assert!(get_theme(id).is_light, "{id} should be a light theme");
+        }
     }
 
     #[test]
@@ -898,29 +1108,6 @@ mod tests {
     // white/bright-white on light themes) are exempt. See
     // `examples/contrast_audit.rs` for the full report + suggester.
 
-    fn lum_channel(c: f64) -> f64 {
-        if c <= 0.03928 {
-            c / 12.92
-        } else {
-            ((c + 0.055) / 1.055).powf(2.4)
-        }
-    }
-
-    fn rel_luminance(hex: &str) -> f64 {
-        let (r, g, b) = hex_to_rgb(hex);
-        0.2126 * lum_channel(r as f64 / 255.0)
-            + 0.7152 * lum_channel(g as f64 / 255.0)
-            + 0.0722 * lum_channel(b as f64 / 255.0)
-    }
-
-    fn contrast_ratio(fg: &str, bg: &str) -> f64 {
-        // fg is opaque for every checked role; ignore any alpha suffix.
-        let l1 = rel_luminance(&fg[..7.min(fg.len())]);
-        let l2 = rel_luminance(bg);
-        let (hi, lo) = if l1 >= l2 { (l1, l2) } else { (l2, l1) };
-        (hi + 0.05) / (lo + 0.05)
-    }
-
     #[test]
     fn all_themes_meet_wcag_aa_for_text() {
         const AA: f64 = 4.5;
@@ -992,6 +1179,68 @@ mod tests {
         );
     }
 
+    #[test]
+    fn increased_contrast_reaches_aaa_and_keeps_backgrounds() {
+        for id in builtin_theme_names() {
+            let t = builtin_theme(id).unwrap();
+            let hc = t.with_increased_contrast();
+            assert_eq!(
+                (&hc.bg, &hc.bg_dark, &hc.terminal_bg),
+                (&t.bg, &t.bg_dark, &t.terminal_bg)
+            );
+            for (name, color) in [
+                ("fg", &hc.fg),
+                ("fg_muted", &hc.fg_muted),
+                ("fg_comment", &hc.fg_comment),
+                ("syntax_comment", &hc.syntax_comment),
+                ("git_modified", &hc.git_modified),
+            ] {
+                for bg in [&hc.bg, &hc.bg_dark, &hc.bg_surface] {
+                    let ratio = contrast_ratio(color, bg);
+                    assert!(ratio >= 7.0, "{id}: {name} {color} on {bg} = {ratio:.2}");
+                }
+            }
+            assert!(contrast_ratio(&hc.border, &hc.bg) >= 3.0, "{id}: border");
+        }
+
+        let nord = builtin_theme("nord").unwrap();
+        let hc = nord.with_increased_contrast();
+        // Already-strong colors are left as they are.
+        assert_eq!(hc.fg, nord.fg);
+        assert_ne!(hc.fg_comment, nord.fg_comment);
+        assert!(hc.selection.ends_with("70"));
+    }
+
+    #[test]
+    fn non_ascii_theme_colors_do_not_panic() {
+        assert!(!is_hex_color("#aaaaaé"));
+        assert!(!is_hex_color("#é"));
+        assert!(is_hex_color("#aabbcc80"));
+        assert_eq!(
+            with_min_contrast("#aaaaaé", &["#000000"], 7.0, false),
+            "#aaaaaé"
+        );
+        assert_eq!(hex_to_rgb("#aé"), (0, 0, 0));
+        assert!(contrast_ratio("#ffffffé", "#00000é") > 1.0);
+
+        let mut theme = builtin_theme("nord").unwrap();
+        theme.fg_comment = "#aaaaaé".to_string();
+        theme.bg = "#2é".to_string();
+        let _ = theme.with_increased_contrast();
+
+        let mut theme = builtin_theme("nord").unwrap();
+        for selection in ["#ab€éx", "#aéaaaa80"] {
+            theme.selection = selection.to_string();
+            assert_eq!(theme.with_increased_contrast().selection, selection);
+        }
+        // A valid color with an unreadable alpha gets the raised one.
+        theme.selection = "#aabbccé".to_string();
+        assert!(theme
+            .with_increased_contrast()
+            .selection
+            .starts_with("#aabbcc"));
+    }
+
     #[test]
     fn hsl_roundtrip() {
         // Test that hex -> HSL -> hex roundtrip is stable
@@ -1055,4 +1304,20 @@ mod tests {
         // Terminal palette should be auto-derived
         assert_eq!(resolved.terminal_palette.len(), 16);
     }
+
+    #[test]
+    fn read_user_css_skips_missing_and_blank_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("custom.css");
+        assert_eq!(read_user_css(&path), None);
+
+        std::fs::write(&path, "  \n").unwrap();
+        assert_eq!(read_user_css(&path), None);
+
+        std::fs::write(&path, ".sidebar { padding: 2px; }").unwrap();
+        assert_eq!(
+            read_user_css(&path).as_deref(),
+            Some(".sidebar { padding: 2px; }")
+        );
+    }
 }
@@ -0,0 +1,216 @@
+//! Workspace trust. Until the user trusts a folder, Impulse runs it in
+//! restricted mode: language servers are not started for it, its
+//! `.impulse/settings.json` is ignored, formatters are not taken from its
+//! `node_modules`, and commands-on-save don't run on its files.
+//!
+//! Decisions are kept in `<config dir>/impulse/trusted_folders.json` and
+//! cover everything below the folder; the deepest decision wins, so a
+//! restricted checkout inside a trusted `~/code` stays restricted.
+//! Frontends ask about a folder's [`workspace_folder`] the first time a
+//! file from it opens, and the `workspace_trust_enabled` setting turns the
+//! whole mechanism off.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+
+const TRUST_FILE: &str = "trusted_folders.json";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TrustState {
+    Trusted,
+    /// The user chose restricted mode; don't ask again.
+    Restricted,
+    /// No decision yet. Treated as restricted.
+    Unknown,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TrustStore {
+    pub trusted: Vec<PathBuf>,
+    pub restricted: Vec<PathBuf>,
+}
+
+impl TrustStore {
+    /// The deepest decision covering `path`.
+    pub fn state_of(&self, path: &Path) -> TrustState {
+        let depth = |folders: &[PathBuf]| {
+            folders
+                .iter()
+                .filter(|folder| path.starts_with(folder))
+                .map(|folder| folder.components().count())
+                .max()
+        };
+        match (depth(&self.trusted), depth(&self.restricted)) {
+            (Some(t), Some(r)) if t > r => TrustState::Trusted,
+            (_, Some(_)) => TrustState::Restricted,
+            (Some(_), None) => TrustState::Trusted,
+            (None, None) => TrustState::Unknown,
+        }
+    }
+
+    /// Record a decision for `folder`, replacing any earlier one for it.
+    pub fn set(&mut self, folder: &Path, trusted: bool) {
+        self.trusted.retain(|f| f != folder);
+        self.restricted.retain(|f| f != folder);
+        if trusted {
+            self.trusted.push(folder.to_path_buf());
+        } else {
+            self.restricted.push(folder.to_path_buf());
+        }
+    }
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(true);
+static STORE: RwLock<Option<TrustStore>> = RwLock::new(None);
+
+/// Apply the `workspace_trust_enabled` setting. Disabled, every folder is
+/// trusted.
+pub fn set_enabled(enabled: bool) {
+    if ENABLED.swap(enabled, Ordering::Relaxed) != enabled {
+        crate::ignore_rules::project_settings_changed();
+    }
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+fn store_path() -> Option<PathBuf> {
+    Some(crate::settings::settings_path()?.with_file_name(TRUST_FILE))
+}
+
+fn load_store() -> TrustStore {
+    let Some(path) = store_path() else {
+        return TrustStore::default();
+    };
+    match std::fs::read_to_string(&path) {
+        Ok(contents) => serde_json::from_str(&contents).unwrap_or_else(|e| {
+            log::warn!("Invalid trust store at {}: {}", path.display(), e);
+            TrustStore::default()
+        }),
+        Err(_) => TrustStore::default(),
+    }
+}
+
+fn with_store<T>(f: impl FnOnce(&TrustStore) -> T) -> T {
+    if let Some(store) = STORE.read().as_ref() {
+        return f(store);
+    }
+    let mut guard = STORE.write();
+    f(guard.get_or_insert_with(load_store))
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// The folder a trust prompt for `path` is about: the nearest ancestor
+/// (inclusive) that is a git work tree or has `.impulse` settings, else
+/// the directory itself.
+pub fn workspace_folder(path: &Path) -> PathBuf {
+    let path = canonical(path);
+    let dir = if path.is_dir() {
+        path.as_path()
+    } else {
+        path.parent().unwrap_or(&path)
+    };
+    dir.ancestors()
+        .find(|d| d.join(".git").exists() || d.join(".impulse").is_dir())
+        .unwrap_or(dir)
+        .to_path_buf()
+}
+
+/// The trust state covering `path` (a file or folder).
+pub fn state(path: &Path) -> TrustState {
+    if !is_enabled() {
+        return TrustState::Trusted;
+    }
+    let path = canonical(path);
+    with_store(|store| store.state_of(&path))
+}
+
+/// Whether restricted-mode features may run for `path`.
+pub fn is_trusted(path: &Path) -> bool {
+    state(path) == TrustState::Trusted
+}
+
+/// Trust `folder` (and everything below it), or keep it restricted
+/// without asking again. Persisted immediately.
+pub fn set_trusted(folder: &Path, trusted: bool) -> Result<(), String> {
+    let path = store_path().ok_or("No config directory for the trust store")?;
+    let folder = canonical(folder);
+    let mut guard = STORE.write();
+    let store = guard.get_or_insert_with(load_store);
+    store.set(&folder, trusted);
+    let json = serde_json::to_string_pretty(store)
+        .map_err(|e| format!("Failed to serialize trust store: {}", e))?;
+    drop(guard);
+    crate::ignore_rules::project_settings_changed();
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, json)
+        .and_then(|()| std::fs::rename(&tmp, &path))
+        .map_err(|e| format!("Failed to save {}: {}", path.display(), e))
+}
+
+/// Trust `folder` for this process only.
+#[cfg(test)]
+pub(crate) fn trust_for_test(folder: &Path) {
+    STORE
+        .write()
+        .get_or_insert_with(TrustStore::default)
+        .set(&canonical(folder), true);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deepest_decision_wins() {
+        let mut store = TrustStore::default();
+        assert_eq!(
+            store.state_of(Path::new("/home/me/code/app")),
+            TrustState::Unknown
+        );
+
+        store.set(Path::new("/home/me/code"), true);
+        store.set(Path::new("/home/me/code/vendor"), false);
+        assert_eq!(
+            store.state_of(Path::new("/home/me/code/app/src/main.rs")),
+            TrustState::Trusted
+        );
+        assert_eq!(
+            store.state_of(Path::new("/home/me/code/vendor/lib")),
+            TrustState::Restricted
+        );
+        // A sibling that merely shares a name prefix isn't covered.
+        assert_eq!(
+            store.state_of(Path::new("/home/me/codex")),
+            TrustState::Unknown
+        );
+
+        store.set(Path::new("/home/me/code/vendor"), true);
+        assert!(store.restricted.is_empty());
+        assert_eq!(
+            store.state_of(Path::new("/home/me/code/vendor/lib")),
+            TrustState::Trusted
+        );
+    }
+
+    #[test]
+    fn workspace_folder_is_the_project_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().canonicalize().unwrap();
+        std::fs::create_dir_all(root.join(".git")).unwrap();
+        std::fs::create_dir_all(root.join("src/nested")).unwrap();
+        std::fs::write(root.join("src/nested/lib.rs"), "").unwrap();
+
+        assert_eq!(workspace_folder(&root.join("src/nested/lib.rs")), root);
+        assert_eq!(workspace_folder(&root.join("src")), root);
+    }
+}
@@ -1,15 +1,46 @@
-use serde::Deserialize;
+//! Update checks and self-update.
+//!
+//! The release feed is a Sparkle-compatible appcast (`appcast.xml` on the
+//! latest GitHub release): one `<item>` per release, with an ed25519-signed
+//! `<enclosure>` per platform (`sparkle:os`) and optional
+//! `<sparkle:deltas>` from earlier versions. Releases without an appcast
+//! fall back to the GitHub Releases API, which can only point at the
+//! release page.
+//!
+//! Only self-contained installs update themselves: a macOS `.app` bundle or
+//! a standalone Linux binary the user can write to. Flatpak and
+//! package-manager installs are told where their updates come from.
+
+use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::io::Read;
+use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 const GITHUB_OWNER: &str = "dowilcox";
 const GITHUB_REPO: &str = "impulse";
 const CHECK_INTERVAL_SECS: u64 = 24 * 60 * 60; // 24 hours
 const REQUEST_TIMEOUT_SECS: u64 = 5;
+const DOWNLOAD_TIMEOUT_SECS: u64 = 10 * 60;
+const MAX_DOWNLOAD_BYTES: u64 = 1024 * 1024 * 1024;
 
 pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+/// The appcast on the latest release.
+pub const APPCAST_URL: &str =
+    "https://github.com/dowilcox/impulse/releases/latest/download/appcast.xml";
+
+/// Base64 ed25519 public key update archives are signed with (Sparkle's
+/// `SUPublicEDKey`), set at build time. Builds without one can't verify an
+/// update, so they never install one.
+pub const UPDATE_PUBLIC_KEY: Option<&str> = option_env!("IMPULSE_UPDATE_PUBLIC_KEY");
+
+const SPARKLE_NS: &str = "http://www.andymatuschak.org/xml-namespaces/sparkle";
+
+/// Paths a delta archive removes from the bundle, one per line, relative to
+/// the bundle root. Everything else in the archive is added or replaced.
+const DELTA_REMOVED_LIST: &str = ".impulse-delta-removed";
+
 #[derive(Deserialize)]
 struct GitHubRelease {
     tag_name: String,
@@ -20,7 +51,94 @@ struct GitHubRelease {
 pub struct UpdateInfo {
     pub version: String,
     pub current_version: String,
+    /// Release notes, or the release page.
     pub url: String,
+    /// The appcast entry; `None` when the release has no appcast, in which
+    /// case it can't be installed in-app.
+    pub item: Option<AppcastItem>,
+}
+
+impl UpdateInfo {
+    /// Whether Impulse can install this update itself for `kind`.
+    pub fn can_install(&self, kind: &InstallKind) -> bool {
+        self.item.is_some() && UPDATE_PUBLIC_KEY.is_some() && kind.update_target().is_some()
+    }
+}
+
+/// A downloadable archive in the appcast.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Enclosure {
+    pub url: String,
+    /// Expected size in bytes; 0 when the appcast doesn't say.
+    pub length: u64,
+    /// Base64 ed25519 signature of the archive (`sparkle:edSignature`).
+    pub ed_signature: String,
+}
+
+/// An archive holding only the files that changed since `from`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DeltaEnclosure {
+    pub from: String,
+    pub enclosure: Enclosure,
+}
+
+/// One release for one platform.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AppcastItem {
+    pub version: String,
+    /// `sparkle:os`: `"macos"` or `"linux"`; `None` applies to every OS.
+    pub os: Option<String>,
+    pub release_notes_url: Option<String>,
+    pub enclosure: Enclosure,
+    pub deltas: Vec<DeltaEnclosure>,
+}
+
+/// How this copy of Impulse was installed, which decides whether it can
+/// update itself.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum InstallKind {
+    /// A macOS `.app` bundle in a folder the user can write to.
+    AppBundle { path: String },
+    /// A standalone binary in a folder the user can write to (a release
+    /// tarball or `cargo install`).
+    Standalone { path: String },
+    /// Flatpak: updates come from the Flatpak remote.
+    Flatpak,
+    /// Installed by a package manager, or anywhere else Impulse can't
+    /// write.
+    Package,
+}
+
+impl InstallKind {
+    /// The file or bundle an update replaces.
+    pub fn update_target(&self) -> Option<&Path> {
+        match self {
+            Self::AppBundle { path } | Self::Standalone { path } => Some(Path::new(path)),
+            Self::Flatpak | Self::Package => None,
+        }
+    }
+
+    /// Where this install's updates come from when Impulse can't install
+    /// them itself.
+    pub fn update_hint(&self) -> &'static str {
+        match self {
+            Self::AppBundle { .. } | Self::Standalone { .. } => {
+                "Download the new version from the release page."
+            }
+            Self::Flatpak => "Update with your software center or `flatpak update`.",
+            Self::Package => "Update with the package manager Impulse was installed with.",
+        }
+    }
+}
+
+/// An update downloaded, verified and unpacked next to the install it
+/// replaces, ready for [`apply_update`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StagedUpdate {
+    pub version: String,
+    pub staged_path: String,
+    pub target_path: String,
 }
 
 fn cache_path() -> Option<PathBuf> {
@@ -76,7 +194,7 @@ fn is_newer(latest: &str, current: &str) -> bool {
     }
 }
 
-/// Check GitHub Releases for a newer version.
+/// Check for a newer version: the appcast first, then GitHub Releases.
 ///
 /// Returns `Ok(Some(UpdateInfo))` if a newer version is available,
 /// `Ok(None)` if already up to date or checked recently,
@@ -87,18 +205,59 @@ pub fn check_for_update() -> Result<Option<UpdateInfo>, String> {
     if !should_check() {
         return Ok(None);
     }
+    check_for_update_now()
+}
+
+/// [`check_for_update`] without the 24-hour interval, for "Check for
+/// Updates".
+pub fn check_for_update_now() -> Result<Option<UpdateInfo>, String> {
+    let result = match fetch_appcast(APPCAST_URL) {
+        Ok(items) => Ok(latest_update(&items, current_os(), CURRENT_VERSION)
+            .cloned()
+            .map(|item| UpdateInfo {
+                version: item.version.clone(),
+                current_version: CURRENT_VERSION.to_string(),
+                url: item
+                    .release_notes_url
+                    .clone()
+                    .unwrap_or_else(release_page_url),
+                item: Some(item),
+            })),
+        Err(e) => {
+            log::info!("{}; falling back to GitHub Releases", e);
+            check_github_release()
+        }
+    }?;
+    write_check_timestamp();
+
+    match &result {
+        Some(info) => log::info!("Update available: {} -> {}", CURRENT_VERSION, info.version),
+        None => log::info!("No update available (current: {})", CURRENT_VERSION),
+    }
+    Ok(result)
+}
+
+fn release_page_url() -> String {
+    format!(
+        "https://github.com/{}/{}/releases/latest",
+        GITHUB_OWNER, GITHUB_REPO
+    )
+}
 
+fn agent(timeout_secs: u64) -> ureq::Agent {
+    ureq::Agent::config_builder()
+        .timeout_global(Some(std::time::Duration::from_secs(timeout_secs)))
+        .build()
+        .new_agent()
+}
+
+fn check_github_release() -> Result<Option<UpdateInfo>, String> {
     let url = format!(
         "https://api.github.com/repos/{}/{}/releases/latest",
         GITHUB_OWNER, GITHUB_REPO
     );
 
-    let agent = ureq::Agent::config_builder()
-        .timeout_global(Some(std::time::Duration::from_secs(REQUEST_TIMEOUT_SECS)))
-        .build()
-        .new_agent();
-
-    let response = agent
+    let response = agent(REQUEST_TIMEOUT_SECS)
         .get(&url)
         .header("Accept", "application/vnd.github.v3+json")
         .header("User-Agent", &format!("impulse/{}", CURRENT_VERSION))
@@ -113,33 +272,340 @@ pub fn check_for_update() -> Result<Option<UpdateInfo>, String> {
     let release: GitHubRelease =
         serde_json::from_str(&body).map_err(|e| format!("Failed to parse release JSON: {}", e))?;
 
-    write_check_timestamp();
+    if !is_newer(&release.tag_name, CURRENT_VERSION) {
+        return Ok(None);
+    }
+    let version = release
+        .tag_name
+        .strip_prefix('v')
+        .unwrap_or(&release.tag_name)
+        .to_string();
+    Ok(Some(UpdateInfo {
+        version,
+        current_version: CURRENT_VERSION.to_string(),
+        url: release.html_url,
+        item: None,
+    }))
+}
 
-    if is_newer(&release.tag_name, CURRENT_VERSION) {
-        let version = release
-            .tag_name
-            .strip_prefix('v')
-            .unwrap_or(&release.tag_name)
-            .to_string();
-        log::info!(
-            "Update available: {} -> {} (current: {})",
-            CURRENT_VERSION,
-            version,
-            CURRENT_VERSION
-        );
-        Ok(Some(UpdateInfo {
+/// The `sparkle:os` value for this build.
+pub fn current_os() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "macos"
+    } else {
+        "linux"
+    }
+}
+
+/// Download and parse an appcast.
+pub fn fetch_appcast(url: &str) -> Result<Vec<AppcastItem>, String> {
+    let body = agent(REQUEST_TIMEOUT_SECS)
+        .get(url)
+        .header("User-Agent", &format!("impulse/{}", CURRENT_VERSION))
+        .call()
+        .map_err(|e| format!("Appcast unavailable: {}", e))?
+        .into_body()
+        .read_to_string()
+        .map_err(|e| format!("Failed to read appcast: {}", e))?;
+    parse_appcast(&body)
+}
+
+/// Parse a Sparkle appcast. Versions come from `sparkle:shortVersionString`
+/// (falling back to `sparkle:version`), as an element of the `<item>` or an
+/// attribute of its `<enclosure>`. Items without a URL or signature are
+/// skipped.
+pub fn parse_appcast(xml: &str) -> Result<Vec<AppcastItem>, String> {
+    let doc = roxmltree::Document::parse(xml).map_err(|e| format!("Invalid appcast: {}", e))?;
+    let mut items = Vec::new();
+    for item in doc.descendants().filter(|n| n.has_tag_name("item")) {
+        let Some(enclosure_node) = item
+            .children()
+            .find(|n| n.has_tag_name("enclosure") && n.tag_name().namespace().is_none())
+        else {
+            continue;
+        };
+        let sparkle_text = |name: &str| {
+            item.children()
+                .find(|n| n.has_tag_name((SPARKLE_NS, name)))
+                .and_then(|n| n.text())
+                .map(|t| t.trim().to_string())
+        };
+        let version = sparkle_text("shortVersionString")
+            .or_else(|| sparkle_text("version"))
+            .or_else(|| {
+                enclosure_node
+                    .attribute((SPARKLE_NS, "shortVersionString"))
+                    .or_else(|| enclosure_node.attribute((SPARKLE_NS, "version")))
+                    .map(str::to_string)
+            });
+        let (Some(version), Some(enclosure)) = (version, parse_enclosure(enclosure_node)) else {
+            log::warn!("Skipping appcast item without a version, URL or signature");
+            continue;
+        };
+        let deltas = item
+            .children()
+            .filter(|n| n.has_tag_name((SPARKLE_NS, "deltas")))
+            .flat_map(|d| d.children().filter(|n| n.has_tag_name("enclosure")))
+            .filter_map(|node| {
+                Some(DeltaEnclosure {
+                    from: node.attribute((SPARKLE_NS, "deltaFrom"))?.to_string(),
+                    enclosure: parse_enclosure(node)?,
+                })
+            })
+            .collect();
+        items.push(AppcastItem {
             version,
-            current_version: CURRENT_VERSION.to_string(),
-            url: release.html_url,
-        }))
+            os: enclosure_node
+                .attribute((SPARKLE_NS, "os"))
+                .map(str::to_string),
+            release_notes_url: sparkle_text("releaseNotesLink").or_else(|| {
+                item.children()
+                    .find(|n| n.has_tag_name("link"))
+                    .and_then(|n| n.text())
+                    .map(|t| t.trim().to_string())
+            }),
+            enclosure,
+            deltas,
+        });
+    }
+    Ok(items)
+}
+
+fn parse_enclosure(node: roxmltree::Node) -> Option<Enclosure> {
+    Some(Enclosure {
+        url: node.attribute("url")?.to_string(),
+        length: node
+            .attribute("length")
+            .and_then(|l| l.parse().ok())
+            .unwrap_or(0),
+        ed_signature: node.attribute((SPARKLE_NS, "edSignature"))?.to_string(),
+    })
+}
+
+/// The newest item for `os` that is newer than `current`.
+pub fn latest_update<'a>(
+    items: &'a [AppcastItem],
+    os: &str,
+    current: &str,
+) -> Option<&'a AppcastItem> {
+    items
+        .iter()
+        .filter(|item| item.os.as_deref().is_none_or(|o| o == os))
+        .filter(|item| is_newer(&item.version, current))
+        .max_by_key(|item| parse_version(&item.version))
+}
+
+/// Check `data` against a base64 ed25519 signature and public key.
+pub fn verify_signature(data: &[u8], signature: &str, public_key: &str) -> Result<(), String> {
+    use base64::Engine;
+    let engine = base64::engine::general_purpose::STANDARD;
+    let signature = engine
+        .decode(signature.trim())
+        .map_err(|e| format!("Invalid update signature: {}", e))?;
+    let public_key = engine
+        .decode(public_key.trim())
+        .map_err(|e| format!("Invalid update public key: {}", e))?;
+    ring::signature::UnparsedPublicKey::new(&ring::signature::ED25519, public_key)
+        .verify(data, &signature)
+        .map_err(|_| "Update signature does not match; not installing it".to_string())
+}
+
+/// How the running executable was installed.
+pub fn install_kind(exe: &Path) -> InstallKind {
+    if std::env::var_os("FLATPAK_ID").is_some() || Path::new("/.flatpak-info").exists() {
+        return InstallKind::Flatpak;
+    }
+    let exe = exe.canonicalize().unwrap_or_else(|_| exe.to_path_buf());
+    let bundle = exe
+        .ancestors()
+        .find(|a| a.extension().is_some_and(|e| e == "app"));
+    let (target, is_bundle) = match bundle {
+        Some(bundle) => (bundle.to_path_buf(), true),
+        None => (exe, false),
+    };
+    let writable = target.parent().is_some_and(is_writable_dir);
+    let path = target.to_string_lossy().to_string();
+    match (writable, is_bundle) {
+        (false, _) => InstallKind::Package,
+        (true, true) => InstallKind::AppBundle { path },
+        (true, false) if path.starts_with("/usr/") => InstallKind::Package,
+        (true, false) => InstallKind::Standalone { path },
+    }
+}
+
+fn is_writable_dir(dir: &Path) -> bool {
+    let probe = dir.join(format!(".impulse-write-test-{}", std::process::id()));
+    let ok = fs::write(&probe, b"").is_ok();
+    let _ = fs::remove_file(&probe);
+    ok
+}
+
+/// Download `item` for `kind`, verify it against [`UPDATE_PUBLIC_KEY`] and
+/// unpack it next to the install. A bundle uses the delta from the running
+/// version when the appcast has one.
+pub fn download_update(item: &AppcastItem, kind: &InstallKind) -> Result<StagedUpdate, String> {
+    let public_key = UPDATE_PUBLIC_KEY
+        .ok_or("This build of Impulse can't verify updates; install it manually")?;
+    let target = kind.update_target().ok_or(kind.update_hint())?;
+    let delta = matches!(kind, InstallKind::AppBundle { .. })
+        .then(|| item.deltas.iter().find(|d| d.from == CURRENT_VERSION))
+        .flatten();
+    let enclosure = delta.map_or(&item.enclosure, |d| &d.enclosure);
+    let archive = download(enclosure)?;
+    stage_update(
+        &item.version,
+        &archive,
+        enclosure,
+        delta.is_some(),
+        target,
+        public_key,
+    )
+}
+
+fn download(enclosure: &Enclosure) -> Result<Vec<u8>, String> {
+    let mut reader = agent(DOWNLOAD_TIMEOUT_SECS)
+        .get(&enclosure.url)
+        .header("User-Agent", &format!("impulse/{}", CURRENT_VERSION))
+        .call()
+        .map_err(|e| format!("Update download failed: {}", e))?
+        .into_body()
+        .into_reader()
+        .take(MAX_DOWNLOAD_BYTES);
+    let mut bytes = Vec::new();
+    reader
+        .read_to_end(&mut bytes)
+        .map_err(|e| format!("Update download failed: {}", e))?;
+    if enclosure.length > 0 && bytes.len() as u64 != enclosure.length {
+        return Err(format!(
+            "Update download is {} bytes, expected {}",
+            bytes.len(),
+            enclosure.length
+        ));
+    }
+    Ok(bytes)
+}
+
+/// The folder updates are unpacked into: beside the target, so applying
+/// is a rename on the same filesystem.
+fn staging_dir(target: &Path) -> PathBuf {
+    target.with_file_name(".impulse-update")
+}
+
+fn stage_update(
+    version: &str,
+    archive: &[u8],
+    enclosure: &Enclosure,
+    is_delta: bool,
+    target: &Path,
+    public_key: &str,
+) -> Result<StagedUpdate, String> {
+    verify_signature(archive, &enclosure.ed_signature, public_key)?;
+
+    let name = target
+        .file_name()
+        .ok_or_else(|| format!("Cannot update {}", target.display()))?;
+    let staging = staging_dir(target);
+    let _ = fs::remove_dir_all(&staging);
+    fs::create_dir_all(&staging)
+        .map_err(|e| format!("Failed to create {}: {}", staging.display(), e))?;
+    let staged = staging.join(name);
+
+    if is_delta {
+        copy_recursively(target, &staged)?;
+        unpack(archive, &enclosure.url, &staged)?;
+        let removed_list = staged.join(DELTA_REMOVED_LIST);
+        if let Ok(removed) = fs::read_to_string(&removed_list) {
+            for rel in removed.lines().map(str::trim).filter(|l| !l.is_empty()) {
+                let path = staged.join(rel);
+                if !path.starts_with(&staged) || rel.contains("..") {
+                    return Err(format!(
+                        "Update delta removes a path outside the bundle: {}",
+                        rel
+                    ));
+                }
+                let _ = fs::remove_dir_all(&path).or_else(|_| fs::remove_file(&path));
+            }
+            let _ = fs::remove_file(&removed_list);
+        }
     } else {
-        log::info!(
-            "No update available (current: {}, latest: {})",
-            CURRENT_VERSION,
-            release.tag_name
-        );
-        Ok(None)
+        unpack(archive, &enclosure.url, &staging)?;
+    }
+
+    if !staged.exists() {
+        return Err(format!(
+            "Update archive does not contain {}",
+            name.to_string_lossy()
+        ));
     }
+    Ok(StagedUpdate {
+        version: version.to_string(),
+        staged_path: staged.to_string_lossy().to_string(),
+        target_path: target.to_string_lossy().to_string(),
+    })
+}
+
+fn unpack(archive: &[u8], url: &str, dest: &Path) -> Result<(), String> {
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    if path.ends_with(".zip") {
+        zip::ZipArchive::new(std::io::Cursor::new(archive))
+            .and_then(|mut zip| zip.extract(dest))
+            .map_err(|e| format!("Failed to unpack update: {}", e))
+    } else if path.ends_with(".tar.gz") || path.ends_with(".tgz") {
+        tar::Archive::new(flate2::read::GzDecoder::new(archive))
+            .unpack(dest)
+            .map_err(|e| format!("Failed to unpack update: {}", e))
+    } else {
+        Err(format!("Unsupported update archive: {}", url))
+    }
+}
+
+fn copy_recursively(from: &Path, to: &Path) -> Result<(), String> {
+    let meta = fs::symlink_metadata(from).map_err(|e| format!("{}: {}", from.display(), e))?;
+    if meta.file_type().is_symlink() {
+        let link = fs::read_link(from).map_err(|e| format!("{}: {}", from.display(), e))?;
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&link, to).map_err(|e| format!("{}: {}", to.display(), e))?;
+        #[cfg(not(unix))]
+        let _ = link;
+    } else if meta.is_dir() {
+        fs::create_dir_all(to).map_err(|e| format!("{}: {}", to.display(), e))?;
+        let entries = fs::read_dir(from).map_err(|e| format!("{}: {}", from.display(), e))?;
+        for entry in entries.flatten() {
+            copy_recursively(&entry.path(), &to.join(entry.file_name()))?;
+        }
+    } else {
+        fs::copy(from, to).map_err(|e| format!("{}: {}", from.display(), e))?;
+    }
+    Ok(())
+}
+
+/// Swap the staged update in for the install. The old copy is restored if
+/// the swap fails. The running process keeps its files until it exits, so
+/// the update takes effect on the next launch.
+pub fn apply_update(staged: &StagedUpdate) -> Result<(), String> {
+    let target = Path::new(&staged.target_path);
+    let staged_path = Path::new(&staged.staged_path);
+    let backup = staging_dir(target).with_file_name(".impulse-update-old");
+    remove_path(&backup);
+
+    fs::rename(target, &backup)
+        .map_err(|e| format!("Failed to move {} aside: {}", target.display(), e))?;
+    if let Err(e) = fs::rename(staged_path, target) {
+        let _ = fs::rename(&backup, target);
+        return Err(format!("Failed to install the update: {}", e));
+    }
+    remove_path(&backup);
+    remove_path(&staging_dir(target));
+    log::info!(
+        "Installed Impulse {} at {}",
+        staged.version,
+        target.display()
+    );
+    Ok(())
+}
+
+fn remove_path(path: &Path) {
+    let _ = fs::remove_dir_all(path).or_else(|_| fs::remove_file(path));
 }
 
 #[cfg(test)]
@@ -161,4 +627,90 @@ mod tests {
         assert!(!is_newer("v0.13.2", "0.13.2"));
         assert!(!is_newer("v0.13.1", "0.13.2"));
     }
+
+    const APPCAST: &str = r#"<?xml version="1.0"?>
+<rss version="2.0" xmlns:sparkle="http://www.andymatuschak.org/xml-namespaces/sparkle">
+  <channel>
+    <item>
+      <sparkle:shortVersionString>0.99.0</sparkle:shortVersionString>
+      <sparkle:releaseNotesLink>https://example.com/notes</sparkle:releaseNotesLink>
+      <enclosure url="https://example.com/Impulse.zip" length="10" sparkle:os="macos" sparkle:edSignature="c2ln"/>
+      <sparkle:deltas>
+        <enclosure url="https://example.com/delta.zip" sparkle:deltaFrom="0.98.0" sparkle:edSignature="ZA=="/>
+      </sparkle:deltas>
+    </item>
+    <item>
+      <enclosure url="https://example.com/impulse.tar.gz" sparkle:version="0.99.0" sparkle:os="linux" sparkle:edSignature="c2ln"/>
+    </item>
+    <item>
+      <enclosure url="https://example.com/old.tar.gz" sparkle:version="0.1.0" sparkle:edSignature="c2ln"/>
+    </item>
+    <item>
+      <enclosure url="https://example.com/unsigned.zip" sparkle:version="1.0.0"/>
+    </item>
+  </channel>
+</rss>"#;
+
+    #[test]
+    fn parses_appcast_and_picks_latest_for_os() {
+        let items = parse_appcast(APPCAST).unwrap();
+        assert_eq!(items.len(), 3, "the unsigned item is skipped");
+        assert_eq!(items[0].version, "0.99.0");
+        assert_eq!(items[0].os.as_deref(), Some("macos"));
+        assert_eq!(items[0].enclosure.length, 10);
+        assert_eq!(
+            items[0].release_notes_url.as_deref(),
+            Some("https://example.com/notes")
+        );
+        assert_eq!(items[0].deltas[0].from, "0.98.0");
+
+        let linux = latest_update(&items, "linux", "0.98.0").unwrap();
+        assert_eq!(linux.enclosure.url, "https://example.com/impulse.tar.gz");
+        assert!(latest_update(&items, "linux", "0.99.0").is_none());
+    }
+
+    #[test]
+    fn stages_and_applies_a_signed_standalone_update() {
+        use base64::Engine;
+        use ring::signature::KeyPair;
+        let engine = base64::engine::general_purpose::STANDARD;
+        let pkcs8 =
+            ring::signature::Ed25519KeyPair::generate_pkcs8(&ring::rand::SystemRandom::new())
+                .unwrap();
+        let keys = ring::signature::Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+        let public_key = engine.encode(keys.public_key().as_ref());
+
+        let mut archive = Vec::new();
+        {
+            let gz = flate2::write::GzEncoder::new(&mut archive, flate2::Compression::default());
+            let mut tar = tar::Builder::new(gz);
+            let mut header = tar::Header::new_gnu();
+            header.set_size(3);
+            header.set_mode(0o755);
+            header.set_cksum();
+            tar.append_data(&mut header, "impulse", &b"new"[..])
+                .unwrap();
+            tar.into_inner().unwrap().finish().unwrap();
+        }
+        let enclosure = Enclosure {
+            url: "https://example.com/impulse.tar.gz".into(),
+            length: archive.len() as u64,
+            ed_signature: engine.encode(keys.sign(&archive).as_ref()),
+        };
+
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("impulse");
+        fs::write(&target, "old").unwrap();
+
+        let mut tampered = archive.clone();
+        tampered[0] ^= 1;
+        assert!(stage_update("9.9.9", &tampered, &enclosure, false, &target, &public_key).is_err());
+
+        let staged =
+            stage_update("9.9.9", &archive, &enclosure, false, &target, &public_key).unwrap();
+        assert_eq!(fs::read_to_string(&target).unwrap(), "old");
+        apply_update(&staged).unwrap();
+        assert_eq!(fs::read_to_string(&target).unwrap(), "new");
+        assert!(!staging_dir(&target).exists());
+    }
 }
@@ -83,6 +83,32 @@ pub fn file_path_to_uri(path: &Path) -> Option<String> {
     }
 }
 
+/// The text the "Copy Path" commands put on the clipboard for `path`, by
+/// command id: `copy_path`, `copy_relative_path` (to `root`, or absolute
+/// outside it), `copy_file_name` and `copy_file_url`. `None` for other ids.
+pub fn path_for_copy(command_id: &str, path: &str, root: &str) -> Option<String> {
+    let text = match command_id {
+        "copy_path" => path.to_string(),
+        "copy_relative_path" => {
+            let root = root.trim_end_matches('/');
+            match path
+                .strip_prefix(root)
+                .and_then(|rest| rest.strip_prefix('/'))
+            {
+                Some(relative) if !root.is_empty() => relative.to_string(),
+                _ => path.to_string(),
+            }
+        }
+        "copy_file_name" => Path::new(path)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.to_string()),
+        "copy_file_url" => file_path_to_uri(Path::new(path)).unwrap_or_else(|| path.to_string()),
+        _ => return None,
+    };
+    Some(text)
+}
+
 /// Convert a `file://` URI to a local file path string.
 pub fn uri_to_file_path(uri: &str) -> String {
     if let Ok(parsed) = Url::parse(uri) {
@@ -139,10 +165,14 @@ fn url_decode(input: &str) -> String {
     String::from_utf8_lossy(&bytes).into_owned()
 }
 
-/// Determine LSP language ID from a file URI based on extension.
+/// Determine LSP language ID from a file URI based on extension, unless the
+/// user overrode the language for files like it.
 pub fn language_from_uri(uri: &str) -> String {
     let path = uri_to_file_path(uri);
     let path_obj = Path::new(&path);
+    if let Some(language) = crate::languages::overridden(path_obj) {
+        return language.id.to_string();
+    }
     if let Some(name) = path_obj.file_name().and_then(|n| n.to_str()) {
         if name.eq_ignore_ascii_case("dockerfile") {
             return "dockerfile".to_string();
@@ -154,6 +184,7 @@ pub fn language_from_uri(uri: &str) -> String {
             "Gemfile" | "Rakefile" => return "ruby".to_string(),
             "Vagrantfile" => return "ruby".to_string(),
             "Jenkinsfile" => return "groovy".to_string(),
+            "justfile" | "Justfile" | ".justfile" => return "just".to_string(),
             _ => {}
         }
     }
@@ -188,6 +219,8 @@ pub fn language_from_uri(uri: &str) -> String {
         "rb" => "ruby".to_string(),
         "lua" => "lua".to_string(),
         "zig" => "zig".to_string(),
+        "nix" => "nix".to_string(),
+        "just" => "just".to_string(),
         "php" => "php".to_string(),
         _ => ext,
     }
@@ -299,6 +332,33 @@ pub fn validate_rel_path_lexically(root: &Path, rel: &Path) -> Result<std::path:
 mod tests {
     use super::*;
 
+    #[test]
+    fn path_for_copy_formats() {
+        let path = "/home/user/my project/src/main.rs";
+        let root = "/home/user/my project";
+        assert_eq!(
+            path_for_copy("copy_path", path, root).as_deref(),
+            Some(path)
+        );
+        assert_eq!(
+            path_for_copy("copy_relative_path", path, root).as_deref(),
+            Some("src/main.rs")
+        );
+        assert_eq!(
+            path_for_copy("copy_relative_path", path, "/home/user/my").as_deref(),
+            Some(path)
+        );
+        assert_eq!(
+            path_for_copy("copy_file_name", path, root).as_deref(),
+            Some("main.rs")
+        );
+        assert_eq!(
+            path_for_copy("copy_file_url", path, root).as_deref(),
+            Some("file:///home/user/my%20project/src/main.rs")
+        );
+        assert_eq!(path_for_copy("copy_remote_link", path, root), None);
+    }
+
     #[test]
     fn uri_to_file_path_basic() {
         assert_eq!(
@@ -443,6 +503,13 @@ mod tests {
         assert_eq!(language_from_uri("file:///foo/Jenkinsfile"), "groovy");
     }
 
+    #[test]
+    fn language_from_uri_justfile_and_nix() {
+        assert_eq!(language_from_uri("file:///foo/justfile"), "just");
+        assert_eq!(language_from_uri("file:///foo/.justfile"), "just");
+        assert_eq!(language_from_uri("file:///foo/flake.nix"), "nix");
+    }
+
     #[test]
     fn language_from_uri_no_extension_returns_empty() {
         assert_eq!(language_from_uri("file:///foo/SomeRandomFile"), "");
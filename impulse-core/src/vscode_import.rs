@@ -0,0 +1,525 @@
+//! Importing a VS Code setup: the user's `settings.json` and
+//! `keybindings.json` from `Code/User` in the platform config directory.
+//!
+//! Known options map onto Impulse settings and keybindings onto
+//! `keybinding_overrides` (over the VS Code keymap preset). Everything
+//! without an equivalent is listed in [`VscodeImport::unmapped`] so the
+//! user can see what didn't carry over. Nothing is saved here; frontends
+//! show the report and save [`VscodeImport::settings`] when confirmed.
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::settings::Settings;
+
+/// VS Code commands for the actions in the core command registry.
+const COMMAND_MAP: &[(&str, &str)] = &[
+    ("workbench.action.terminal.new", "new_tab"),
+    ("workbench.action.closeActiveEditor", "close_tab"),
+    ("workbench.action.reopenClosedEditor", "reopen_tab"),
+    ("workbench.action.nextEditor", "next_tab"),
+    ("workbench.action.previousEditor", "prev_tab"),
+    ("editor.action.clipboardCopyAction", "copy"),
+    ("editor.action.clipboardPasteAction", "paste"),
+    ("workbench.view.scm", "review_changes"),
+    ("workbench.action.files.newUntitledFile", "new_file"),
+    ("workbench.action.files.save", "save"),
+    ("actions.find", "find"),
+    ("workbench.action.gotoLine", "go_to_line"),
+    ("markdown.showPreview", "toggle_markdown_preview"),
+    ("workbench.action.toggleSidebarVisibility", "toggle_sidebar"),
+    ("workbench.action.toggleZenMode", "toggle_zen_mode"),
+    (
+        "workbench.action.toggleStatusbarVisibility",
+        "toggle_status_bar",
+    ),
+    (
+        "workbench.action.toggleCenteredLayout",
+        "toggle_centered_layout",
+    ),
+    ("workbench.action.quickOpen", "quick_open"),
+    ("workbench.action.findInFiles", "project_search"),
+    ("workbench.view.search", "project_search"),
+    ("workbench.action.showCommands", "command_palette"),
+    ("workbench.action.openSettings", "open_settings"),
+    ("workbench.action.zoomIn", "font_increase"),
+    ("editor.action.fontZoomIn", "font_increase"),
+    ("workbench.action.zoomOut", "font_decrease"),
+    ("editor.action.fontZoomOut", "font_decrease"),
+    ("workbench.action.zoomReset", "font_reset"),
+    ("editor.action.fontZoomReset", "font_reset"),
+    ("workbench.action.newWindow", "new_window"),
+    ("workbench.action.toggleFullScreen", "fullscreen"),
+    (
+        "workbench.action.manageTrustedDomain",
+        "manage_workspace_trust",
+    ),
+    ("notifications.showList", "show_notifications"),
+];
+
+/// What [`import`] made of a VS Code setup.
+#[derive(Debug, Clone, Serialize)]
+pub struct VscodeImport {
+    /// The base settings with everything mapped applied.
+    pub settings: Settings,
+    /// VS Code settings and keybindings that were applied.
+    pub imported: Vec<String>,
+    /// VS Code settings and keybindings with no Impulse equivalent.
+    pub unmapped: Vec<String>,
+}
+
+/// VS Code's user settings directory (`~/.config/Code/User` on Linux,
+/// `~/Library/Application Support/Code/User` on macOS).
+pub fn vscode_user_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join("Code").join("User"))
+}
+
+/// Map the VS Code setup in [`vscode_user_dir`] onto `base`.
+/// `editor_commands` adds `(VS Code command, action id)` pairs for actions
+/// outside the core registry, e.g. the editor's rebindable Monaco actions.
+pub fn import(base: &Settings, editor_commands: &[(&str, &str)]) -> Result<VscodeImport, String> {
+    let dir = vscode_user_dir().ok_or("Cannot determine config directory")?;
+    import_from(&dir, base, editor_commands)
+}
+
+fn import_from(
+    dir: &Path,
+    base: &Settings,
+    editor_commands: &[(&str, &str)],
+) -> Result<VscodeImport, String> {
+    let read = |name: &str| -> Result<Option<Value>, String> {
+        let path = dir.join(name);
+        match std::fs::read_to_string(&path) {
+            Ok(text) => serde_json::from_str(&crate::vscode_theme::strip_jsonc(&text))
+                .map(Some)
+                .map_err(|e| format!("Failed to parse {}: {}", path.display(), e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(format!("Failed to read {}: {}", path.display(), e)),
+        }
+    };
+    let settings_json = read("settings.json")?;
+    let keybindings_json = read("keybindings.json")?;
+    if settings_json.is_none() && keybindings_json.is_none() {
+        return Err(format!("No VS Code settings found in {}", dir.display()));
+    }
+
+    let mut result = VscodeImport {
+        settings: base.clone(),
+        imported: Vec::new(),
+        unmapped: Vec::new(),
+    };
+    if let Some(Value::Object(options)) = settings_json {
+        for (key, value) in &options {
+            if let Some(overrides) = key.strip_prefix('[').and_then(|k| k.strip_suffix(']')) {
+                apply_language_overrides(&mut result, key, overrides, value);
+            } else if apply_setting(&mut result.settings, key, value) {
+                result.imported.push(key.clone());
+            } else {
+                result.unmapped.push(key.clone());
+            }
+        }
+    }
+    if let Some(Value::Array(bindings)) = keybindings_json {
+        result.settings.keymap_preset = "vscode".to_string();
+        for binding in &bindings {
+            apply_keybinding(&mut result, binding, editor_commands);
+        }
+    }
+    result.settings.validate();
+    Ok(result)
+}
+
+/// `"[python]": {"editor.tabSize": 4}` becomes a file type override for
+/// the language's usual extensions.
+fn apply_language_overrides(result: &mut VscodeImport, key: &str, languages: &str, value: &Value) {
+    let tab_width = value
+        .get("editor.tabSize")
+        .and_then(Value::as_u64)
+        .map(|n| n as u32);
+    let use_spaces = value.get("editor.insertSpaces").and_then(Value::as_bool);
+    let patterns: Vec<&str> = languages.split("][").filter_map(language_pattern).collect();
+    if patterns.is_empty() || (tab_width.is_none() && use_spaces.is_none()) {
+        result.unmapped.push(key.to_string());
+        return;
+    }
+    for pattern in patterns {
+        let overrides = &mut result.settings.file_type_overrides;
+        let index = match overrides.iter().position(|o| o.pattern == pattern) {
+            Some(index) => index,
+            None => {
+                overrides.push(crate::settings::FileTypeOverride {
+                    pattern: pattern.to_string(),
+                    ..Default::default()
+                });
+                overrides.len() - 1
+            }
+        };
+        overrides[index].tab_width = tab_width.or(overrides[index].tab_width);
+        overrides[index].use_spaces = use_spaces.or(overrides[index].use_spaces);
+    }
+    result.imported.push(key.to_string());
+}
+
+fn language_pattern(language: &str) -> Option<&'static str> {
+    Some(match language {
+        "python" => "*.py",
+        "rust" => "*.rs",
+        "go" => "*.go",
+        "javascript" => "*.js",
+        "javascriptreact" => "*.jsx",
+        "typescript" => "*.ts",
+        "typescriptreact" => "*.tsx",
+        "json" | "jsonc" => "*.json",
+        "yaml" => "*.yaml",
+        "markdown" => "*.md",
+        "html" => "*.html",
+        "css" => "*.css",
+        "scss" => "*.scss",
+        "c" => "*.c",
+        "cpp" => "*.cpp",
+        "java" => "*.java",
+        "ruby" => "*.rb",
+        "php" => "*.php",
+        "shellscript" => "*.sh",
+        "lua" => "*.lua",
+        "makefile" => "Makefile",
+        _ => return None,
+    })
+}
+
+/// Apply one VS Code setting. Returns whether it has an equivalent.
+fn apply_setting(s: &mut Settings, key: &str, value: &Value) -> bool {
+    let text = || value.as_str().map(str::to_string);
+    let flag = || value.as_bool();
+    let int = || value.as_i64();
+    let applied: Option<()> = match key {
+        "editor.fontSize" => int().map(|n| s.font_size = n as i32),
+        "editor.fontFamily" => text()
+            .and_then(|f| first_font(&f))
+            .map(|f| s.font_family = f),
+        "editor.tabSize" => int().map(|n| s.tab_width = n as u32),
+        "editor.insertSpaces" => flag().map(|b| s.use_spaces = b),
+        "editor.lineNumbers" => text().map(|v| s.show_line_numbers = v != "off"),
+        "editor.rulers" => value.as_array().map(|rulers| {
+            let first = rulers.first().and_then(|r| {
+                r.as_u64()
+                    .or_else(|| r.get("column").and_then(Value::as_u64))
+            });
+            s.show_right_margin = first.is_some();
+            if let Some(column) = first {
+                s.right_margin_position = column as u32;
+            }
+        }),
+        "editor.wordWrap" => text().map(|v| s.word_wrap = v != "off"),
+        "editor.renderLineHighlight" => text().map(|v| s.highlight_current_line = v != "none"),
+        "editor.minimap.enabled" => flag().map(|b| s.minimap_enabled = b),
+        "editor.renderWhitespace" => text().map(|v| s.render_whitespace = v),
+        "editor.stickyScroll.enabled" => flag().map(|b| s.sticky_scroll = b),
+        "editor.bracketPairColorization.enabled" => flag().map(|b| s.bracket_pair_colorization = b),
+        "editor.guides.indentation" => flag().map(|b| s.indent_guides = b),
+        // `true`, `false` or a font-feature-settings string.
+        "editor.fontLigatures" => {
+            s.font_ligatures = flag().unwrap_or(value.is_string());
+            Some(())
+        }
+        "editor.folding" => flag().map(|b| s.folding = b),
+        "editor.scrollBeyondLastLine" => flag().map(|b| s.scroll_beyond_last_line = b),
+        "editor.smoothScrolling" => flag().map(|b| s.smooth_scrolling = b),
+        "editor.cursorStyle" => text().map(|v| s.editor_cursor_style = v),
+        "editor.cursorBlinking" => text().map(|v| s.editor_cursor_blinking = v),
+        "editor.lineHeight" => int().map(|n| s.editor_line_height = n.max(0) as u32),
+        "editor.autoClosingBrackets" => text().map(|v| s.editor_auto_closing_brackets = v),
+        "editor.cursorSurroundingLines" => {
+            int().map(|n| s.editor_cursor_surrounding_lines = n.max(0) as u32)
+        }
+        "editor.selectionHighlight" => flag().map(|b| s.editor_selection_highlight = b),
+        // Older versions use a boolean, newer ones "off" / "singleFile" / ...
+        "editor.occurrencesHighlight" => {
+            s.editor_occurrences_highlight = flag().unwrap_or(value.as_str() != Some("off"));
+            Some(())
+        }
+        "editor.wordBasedSuggestions" => match value {
+            Value::Bool(b) => {
+                s.editor_word_based_suggestions =
+                    if *b { "matchingDocuments" } else { "off" }.to_string();
+                Some(())
+            }
+            _ => text().map(|v| s.editor_word_based_suggestions = v),
+        },
+        "editor.codeActionsOnSave" => value.as_object().map(|actions| {
+            s.code_actions_on_save = actions
+                .iter()
+                .filter(|(_, when)| {
+                    matches!(when, Value::Bool(true))
+                        || matches!(when.as_str(), Some("explicit" | "always"))
+                })
+                .map(|(kind, _)| kind.clone())
+                .collect();
+        }),
+        "files.autoSave" => text().map(|v| s.auto_save = v != "off"),
+        "files.hotExit" => text().map(|v| s.hot_exit = v != "off"),
+        "files.trimTrailingWhitespace" => flag().map(|b| s.trim_trailing_whitespace = b),
+        "files.insertFinalNewline" => flag().map(|b| s.insert_final_newline = b),
+        "files.exclude" => value
+            .as_object()
+            .map(|globs| s.files_exclude = enabled_globs(globs)),
+        "search.exclude" => value
+            .as_object()
+            .map(|globs| s.search_exclude = enabled_globs(globs)),
+        "explorer.excludeGitIgnore" => flag().map(|b| s.sidebar_hide_ignored = b),
+        // `true`, `false` or "focusNoScroll".
+        "explorer.autoReveal" => {
+            s.sidebar_auto_reveal = flag().unwrap_or(value.is_string());
+            Some(())
+        }
+        "window.restoreWindows" => text().map(|v| s.restore_session = v != "none"),
+        "security.workspace.trust.enabled" => flag().map(|b| s.workspace_trust_enabled = b),
+        "workbench.colorTheme" => text()
+            .and_then(|name| matching_theme(&name))
+            .map(|id| s.color_scheme = id),
+        "window.autoDetectColorScheme" => flag().map(|b| s.follow_system_appearance = b),
+        "workbench.preferredLightColorTheme" => text()
+            .and_then(|name| matching_theme(&name))
+            .map(|id| s.light_color_scheme = id),
+        "workbench.preferredDarkColorTheme" => text()
+            .and_then(|name| matching_theme(&name))
+            .map(|id| s.dark_color_scheme = id),
+        "terminal.integrated.fontSize" => int().map(|n| s.terminal_font_size = n as i32),
+        "terminal.integrated.fontFamily" => text()
+            .and_then(|f| first_font(&f))
+            .map(|f| s.terminal_font_family = f),
+        "terminal.integrated.scrollback" => int().map(|n| s.terminal_scrollback = n),
+        "terminal.integrated.cursorStyle" => text()
+            .map(|v| s.terminal_cursor_shape = if v == "line" { "ibeam".to_string() } else { v }),
+        "terminal.integrated.cursorBlinking" => flag().map(|b| s.terminal_cursor_blink = b),
+        "terminal.integrated.copyOnSelection" => flag().map(|b| s.terminal_copy_on_select = b),
+        "terminal.integrated.drawBoldTextInBrightColors" => {
+            flag().map(|b| s.terminal_bold_is_bright = b)
+        }
+        "terminal.integrated.minimumContrastRatio" => {
+            value.as_f64().map(|n| s.terminal_minimum_contrast = n)
+        }
+        "terminal.integrated.enableBell" => flag().map(|b| s.terminal_bell = b),
+        _ => None,
+    };
+    applied.is_some()
+}
+
+/// The first family of a CSS font list like `"'Fira Code', Menlo, monospace"`.
+fn first_font(list: &str) -> Option<String> {
+    let first = list
+        .split(',')
+        .next()?
+        .trim()
+        .trim_matches(['\'', '"'])
+        .trim();
+    (!first.is_empty()).then(|| first.to_string())
+}
+
+fn enabled_globs(globs: &serde_json::Map<String, Value>) -> Vec<String> {
+    globs
+        .iter()
+        .filter(|(_, enabled)| enabled.as_bool() == Some(true))
+        .map(|(glob, _)| glob.clone())
+        .collect()
+}
+
+/// The Impulse theme with the same name as a VS Code theme, e.g.
+/// "Tokyo Night Storm" or "GitHub Light".
+fn matching_theme(name: &str) -> Option<String> {
+    let key = |s: &str| -> String {
+        s.chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect()
+    };
+    let wanted = key(name);
+    crate::theme::available_themes()
+        .into_iter()
+        .find(|id| key(id) == wanted || key(&crate::theme::theme_display_name(id)) == wanted)
+}
+
+fn apply_keybinding(result: &mut VscodeImport, binding: &Value, editor_commands: &[(&str, &str)]) {
+    let key = binding
+        .get("key")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let command = binding
+        .get("command")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let label = format!("{} → {}", key, command);
+    // `-command` (removing a default binding) matches nothing and is
+    // reported as unmapped.
+    let id = COMMAND_MAP
+        .iter()
+        .chain(editor_commands)
+        .find(|(vscode, _)| *vscode == command)
+        .map(|(_, id)| *id);
+    match (id, convert_key(key)) {
+        (Some(id), Some(shortcut)) => {
+            result
+                .settings
+                .keybinding_overrides
+                .insert(id.to_string(), shortcut);
+            result.imported.push(label);
+        }
+        _ => result.unmapped.push(label),
+    }
+}
+
+/// Convert a VS Code key like `"ctrl+shift+p"` or the chord
+/// `"ctrl+k ctrl+s"` to the `"Ctrl+Shift+P"` form of the overrides.
+fn convert_key(key: &str) -> Option<String> {
+    let steps: Vec<String> = key
+        .split_whitespace()
+        .map(convert_step)
+        .collect::<Option<_>>()?;
+    if steps.is_empty() || steps.len() > 2 {
+        return None;
+    }
+    Some(steps.join(" "))
+}
+
+fn convert_step(step: &str) -> Option<String> {
+    let mac = cfg!(target_os = "macos");
+    // `ctrl++` binds the plus key.
+    let (modifiers, key) = match step.strip_suffix("++") {
+        Some(modifiers) => (modifiers, "+"),
+        None => step.rsplit_once('+').unwrap_or(("", step)),
+    };
+    let mut parts: Vec<&str> = Vec::new();
+    for modifier in modifiers.split('+').filter(|m| !m.is_empty()) {
+        parts.push(match modifier.to_lowercase().as_str() {
+            "ctrl" => "Ctrl",
+            "shift" => "Shift",
+            "alt" => "Alt",
+            "cmd" | "meta" | "win" if mac => "Cmd",
+            "meta" | "win" | "cmd" => "Super",
+            _ => return None,
+        });
+    }
+    let key = key.to_lowercase();
+    let named = match key.as_str() {
+        "left" => "Left",
+        "right" => "Right",
+        "up" => "Up",
+        "down" => "Down",
+        "tab" => "Tab",
+        "enter" => "Return",
+        "escape" => "Escape",
+        "space" if mac => "Space",
+        "space" => "space",
+        "backspace" if mac => "Delete",
+        "backspace" => "BackSpace",
+        "delete" if !mac => "Delete",
+        "home" => "Home",
+        "end" => "End",
+        "pageup" if !mac => "Page_Up",
+        "pagedown" if !mac => "Page_Down",
+        "," if !mac => "comma",
+        "." if !mac => "period",
+        "/" if !mac => "slash",
+        ";" if !mac => "semicolon",
+        "'" if !mac => "apostrophe",
+        "-" if !mac => "minus",
+        "=" if !mac => "equal",
+        "[" if !mac => "bracketleft",
+        "]" if !mac => "bracketright",
+        "\\" if !mac => "backslash",
+        "`" if !mac => "grave",
+        k if k.len() == 1 && k.chars().all(|c| c.is_ascii_graphic()) => {
+            return Some(join_step(parts, &k.to_uppercase()));
+        }
+        k if k
+            .strip_prefix('f')
+            .and_then(|n| n.parse::<u8>().ok())
+            .is_some_and(|n| (1..=24).contains(&n)) =>
+        {
+            return Some(join_step(parts, &k.to_uppercase()));
+        }
+        _ => return None,
+    };
+    Some(join_step(parts, named))
+}
+
+fn join_step(modifiers: Vec<&str>, key: &str) -> String {
+    modifiers
+        .into_iter()
+        .chain(std::iter::once(key))
+        .collect::<Vec<_>>()
+        .join("+")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_settings_and_keybindings_and_reports_the_rest() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("settings.json"),
+            r#"{
+                // Comments and trailing commas are allowed.
+                "editor.fontSize": 15,
+                "editor.fontFamily": "'Fira Code', Menlo, monospace",
+                "editor.rulers": [100],
+                "files.exclude": {"**/.DS_Store": true, "**/node_modules": false},
+                "workbench.colorTheme": "Tokyo Night Storm",
+                "[python]": {"editor.tabSize": 4},
+                "gitlens.codeLens.enabled": false,
+            }"#,
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("keybindings.json"),
+            r#"[
+                {"key": "ctrl+shift+e", "command": "workbench.action.quickOpen"},
+                {"key": "ctrl+k ctrl+c", "command": "editor.action.commentLine"},
+                {"key": "ctrl+alt+t", "command": "-workbench.action.terminal.new"},
+            ]"#,
+        )
+        .unwrap();
+
+        let result = import_from(
+            dir.path(),
+            &Settings::default(),
+            &[("editor.action.commentLine", "toggle_comment")],
+        )
+        .unwrap();
+        let s = &result.settings;
+        assert_eq!(s.font_size, 15);
+        assert_eq!(s.font_family, "Fira Code");
+        assert!(s.show_right_margin);
+        assert_eq!(s.right_margin_position, 100);
+        assert_eq!(s.files_exclude, vec!["**/.DS_Store".to_string()]);
+        assert_eq!(s.color_scheme, "tokyo-night-storm");
+        assert!(s
+            .file_type_overrides
+            .iter()
+            .any(|o| o.pattern == "*.py" && o.tab_width == Some(4)));
+        assert_eq!(s.keymap_preset, "vscode");
+        assert_eq!(s.keybinding_overrides["quick_open"], "Ctrl+Shift+E");
+        assert_eq!(s.keybinding_overrides["toggle_comment"], "Ctrl+K Ctrl+C");
+        assert!(!s.keybinding_overrides.contains_key("new_tab"));
+
+        assert!(result.imported.contains(&"editor.fontSize".to_string()));
+        assert!(result.imported.contains(&"[python]".to_string()));
+        assert_eq!(
+            result.unmapped,
+            vec![
+                "gitlens.codeLens.enabled".to_string(),
+                "ctrl+alt+t → -workbench.action.terminal.new".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_vscode_setup_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(import_from(dir.path(), &Settings::default(), &[]).is_err());
+    }
+}
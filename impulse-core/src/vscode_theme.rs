@@ -0,0 +1,499 @@
+//! Import of VS Code color themes into Impulse [`ThemeFile`]s.
+//!
+//! UI colors come from the theme's `colors` map (editor, side bar, git
+//! decorations, terminal ANSI colors) and syntax colors from the TextMate
+//! scopes in `tokenColors`. Anything the theme leaves out is filled in from
+//! a neutral dark or light palette, and [`crate::theme::resolve_theme`]
+//! derives the rest as it does for hand-written themes.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::theme::{SemanticSyntax, SemanticUI, TerminalPalette, ThemeFile, ThemePalette};
+
+/// `include` chains deeper than this are cut off.
+const MAX_INCLUDE_DEPTH: usize = 4;
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VsCodeTheme {
+    name: Option<String>,
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    include: Option<String>,
+    #[serde(default)]
+    colors: HashMap<String, Option<String>>,
+    #[serde(default)]
+    token_colors: Vec<TokenColor>,
+}
+
+#[derive(Deserialize)]
+struct TokenColor {
+    scope: Option<Scope>,
+    #[serde(default)]
+    settings: TokenSettings,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Scope {
+    One(String),
+    Many(Vec<String>),
+}
+
+#[derive(Default, Deserialize)]
+struct TokenSettings {
+    foreground: Option<String>,
+}
+
+/// Remove `//` and `/* */` comments and trailing commas, which VS Code
+/// theme files commonly contain.
+pub(crate) fn strip_jsonc(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.char_indices().peekable();
+    let mut in_string = false;
+    while let Some((i, c)) = chars.next() {
+        if in_string {
+            out.push(c);
+            match c {
+                '\\' => out.extend(chars.next().map(|(_, c)| c)),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek().map(|&(_, c)| c)) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+            }
+            ('/', Some('/')) => while chars.next_if(|&(_, c)| c != '\n').is_some() {},
+            ('/', Some('*')) => {
+                chars.next();
+                let mut prev = '\0';
+                for (_, c) in chars.by_ref() {
+                    if prev == '*' && c == '/' {
+                        break;
+                    }
+                    prev = c;
+                }
+            }
+            (',', _) => {
+                // Look past whitespace and comments: `a, // note\n}`.
+                let next = strip_leading_comments(text[i + 1..].trim_start());
+                if !(next.starts_with('}') || next.starts_with(']')) {
+                    out.push(c);
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn strip_leading_comments(mut text: &str) -> &str {
+    loop {
+        if let Some(rest) = text.strip_prefix("//") {
+            text = rest
+                .split_once('\n')
+                .map_or("", |(_, rest)| rest)
+                .trim_start();
+        } else if let Some(rest) = text.strip_prefix("/*") {
+            text = rest
+                .split_once("*/")
+                .map_or("", |(_, rest)| rest)
+                .trim_start();
+        } else {
+            return text;
+        }
+    }
+}
+
+/// `#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa` as lowercase `#rrggbb`, with
+/// the alpha kept as a suffix only when `keep_alpha` is set.
+fn normalize_color(color: &str, keep_alpha: bool) -> Option<String> {
+    let hex = color.trim().strip_prefix('#')?;
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let expanded: String = match hex.len() {
+        3 | 4 => hex.chars().flat_map(|c| [c, c]).collect(),
+        6 | 8 => hex.to_string(),
+        _ => return None,
+    };
+    let expanded = expanded.to_ascii_lowercase();
+    let (rgb, alpha) = expanded.split_at(6);
+    Some(if keep_alpha && alpha != "ff" {
+        format!("#{rgb}{alpha}")
+    } else {
+        format!("#{rgb}")
+    })
+}
+
+impl VsCodeTheme {
+    fn color(&self, keys: &[&str]) -> Option<String> {
+        keys.iter().find_map(|key| {
+            self.colors
+                .get(*key)
+                .and_then(|c| c.as_deref())
+                .and_then(|c| normalize_color(c, false))
+        })
+    }
+
+    fn color_with_alpha(&self, key: &str) -> Option<String> {
+        self.colors
+            .get(key)
+            .and_then(|c| c.as_deref())
+            .and_then(|c| normalize_color(c, true))
+    }
+
+    /// The foreground of the rule that best covers `scope`: a rule naming
+    /// it exactly wins over one naming a parent scope (`keyword` for
+    /// `keyword.control`). Later rules win ties, as in VS Code.
+    fn scope_color(&self, scope: &str) -> Option<String> {
+        let mut best: Option<(usize, String)> = None;
+        for rule in &self.token_colors {
+            let Some(color) = rule
+                .settings
+                .foreground
+                .as_deref()
+                .and_then(|c| normalize_color(c, false))
+            else {
+                continue;
+            };
+            let selectors: Vec<&str> = match &rule.scope {
+                Some(Scope::One(s)) => s.split(',').collect(),
+                Some(Scope::Many(v)) => v.iter().flat_map(|s| s.split(',')).collect(),
+                None => continue,
+            };
+            for selector in selectors {
+                // Only the innermost scope of a descendant selector
+                // (`meta.function entity.name`) is considered.
+                let Some(selector) = selector.split_whitespace().last() else {
+                    continue;
+                };
+                let covers = scope == selector
+                    || scope
+                        .strip_prefix(selector)
+                        .is_some_and(|rest| rest.starts_with('.'));
+                if covers && best.as_ref().is_none_or(|(len, _)| selector.len() >= *len) {
+                    best = Some((selector.len(), color.clone()));
+                }
+            }
+        }
+        best.map(|(_, color)| color)
+    }
+
+    fn first_scope_color(&self, scopes: &[&str]) -> Option<String> {
+        scopes.iter().find_map(|scope| self.scope_color(scope))
+    }
+
+    /// Layer `self` over the theme it includes.
+    fn merged_over(self, mut base: VsCodeTheme) -> VsCodeTheme {
+        base.colors.extend(self.colors);
+        base.token_colors.extend(self.token_colors);
+        VsCodeTheme {
+            name: self.name.or(base.name),
+            kind: self.kind.or(base.kind),
+            include: None,
+            colors: base.colors,
+            token_colors: base.token_colors,
+        }
+    }
+}
+
+fn parse(text: &str) -> Result<VsCodeTheme, String> {
+    serde_json::from_str(&strip_jsonc(text))
+        .map_err(|e| format!("Failed to parse VS Code theme: {e}"))
+}
+
+/// Whether `text` looks like a VS Code theme rather than an Impulse one.
+pub fn is_vscode_theme(text: &str) -> bool {
+    serde_json::from_str::<serde_json::Value>(&strip_jsonc(text)).is_ok_and(|v| {
+        v.get("palette").is_none() && (v.get("colors").is_some() || v.get("tokenColors").is_some())
+    })
+}
+
+/// Convert the text of a VS Code color theme. `fallback_name` is used when
+/// the theme has no `name`. `include`d themes are not followed; see
+/// [`convert_vscode_theme_file`].
+pub fn import_vscode_theme(text: &str, fallback_name: &str) -> Result<ThemeFile, String> {
+    Ok(convert(parse(text)?, fallback_name))
+}
+
+fn load_with_includes(path: &Path, depth: usize) -> Result<VsCodeTheme, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    let mut theme = parse(&text)?;
+    if let Some(include) = theme.include.take() {
+        if depth >= MAX_INCLUDE_DEPTH {
+            log::warn!(
+                "Ignoring nested theme include '{include}' in {}",
+                path.display()
+            );
+        } else {
+            let base_path = path.parent().unwrap_or(Path::new(".")).join(&include);
+            match load_with_includes(&base_path, depth + 1) {
+                Ok(base) => theme = theme.merged_over(base),
+                Err(e) => log::warn!("Theme include '{include}' not loaded: {e}"),
+            }
+        }
+    }
+    Ok(theme)
+}
+
+/// Convert a VS Code theme file, following its `include` chain relative to
+/// the file.
+pub fn convert_vscode_theme_file(path: &Path) -> Result<ThemeFile, String> {
+    let fallback = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("Imported Theme")
+        .trim_end_matches("-color-theme");
+    Ok(convert(load_with_includes(path, 0)?, fallback))
+}
+
+/// Import a VS Code theme file into the user themes directory as an Impulse
+/// JSON theme, returning its theme id.
+pub fn import_vscode_theme_file(path: &Path) -> Result<String, String> {
+    let theme = convert_vscode_theme_file(path)?;
+    let id = theme_id_for_name(&theme.name);
+    if id.is_empty() {
+        return Err(format!("'{}' has no usable theme name", theme.name));
+    }
+    let dir = crate::theme::user_themes_dir()
+        .ok_or_else(|| "Cannot determine the themes directory".to_string())?;
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+    let json = serde_json::to_string_pretty(&theme)
+        .map_err(|e| format!("Failed to serialize theme: {e}"))?;
+    let target = dir.join(format!("{id}.json"));
+    std::fs::write(&target, json)
+        .map_err(|e| format!("Failed to write {}: {e}", target.display()))?;
+    Ok(id)
+}
+
+/// A kebab-case theme id for a display name: `"One Dark Pro"` → `"one-dark-pro"`.
+pub fn theme_id_for_name(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+fn convert(theme: VsCodeTheme, fallback_name: &str) -> ThemeFile {
+    let is_light = theme
+        .kind
+        .as_deref()
+        .is_some_and(|kind| kind == "light" || kind == "hcLight");
+    // Neutral stand-ins (VS Code's own Dark+/Light+ values) for colors the
+    // theme does not define.
+    let default = |dark: &str, light: &str| {
+        if is_light {
+            light.to_string()
+        } else {
+            dark.to_string()
+        }
+    };
+    let ansi = |name: &str| {
+        theme.color(&[
+            &format!("terminal.ansi{name}"),
+            &format!("terminal.ansiBright{name}"),
+        ])
+    };
+
+    let bg = theme
+        .color(&["editor.background"])
+        .unwrap_or_else(|| default("#1e1e1e", "#ffffff"));
+    let fg = theme
+        .color(&["editor.foreground", "foreground"])
+        .unwrap_or_else(|| default("#d4d4d4", "#000000"));
+    let comment = theme.first_scope_color(&["comment"]);
+    let palette = ThemePalette {
+        accent: theme
+            .color(&[
+                "focusBorder",
+                "button.background",
+                "textLink.foreground",
+                "activityBarBadge.background",
+            ])
+            .unwrap_or_else(|| default("#007acc", "#005fb8")),
+        red: ansi("Red")
+            .or_else(|| theme.color(&["errorForeground", "editorError.foreground"]))
+            .unwrap_or_else(|| default("#f14c4c", "#cd3131")),
+        orange: theme
+            .first_scope_color(&["constant.numeric", "constant"])
+            .or_else(|| theme.color(&["editorWarning.foreground"]))
+            .unwrap_or_else(|| default("#ce9178", "#a31515")),
+        yellow: ansi("Yellow").unwrap_or_else(|| default("#dcdcaa", "#795e26")),
+        green: ansi("Green").unwrap_or_else(|| default("#6a9955", "#008000")),
+        cyan: ansi("Cyan").unwrap_or_else(|| default("#4ec9b0", "#267f99")),
+        blue: ansi("Blue").unwrap_or_else(|| default("#569cd6", "#0000ff")),
+        magenta: ansi("Magenta").unwrap_or_else(|| default("#c586c0", "#af00db")),
+        surface: theme.color(&["sideBar.background", "activityBar.background"]),
+        overlay: theme.color(&["editorWidget.background", "dropdown.background"]),
+        muted: theme.color(&["descriptionForeground", "editorLineNumber.foreground"]),
+        subtle: comment.clone(),
+        bg,
+        fg,
+    };
+
+    let ui = SemanticUI {
+        bg_dark: theme.color(&[
+            "sideBar.background",
+            "activityBar.background",
+            "titleBar.activeBackground",
+        ]),
+        bg_highlight: theme.color(&["editor.lineHighlightBackground", "list.hoverBackground"]),
+        bg_surface: theme.color(&["titleBar.activeBackground", "tab.inactiveBackground"]),
+        border: theme.color(&["panel.border", "editorGroup.border", "sideBar.border"]),
+        fg_muted: theme.color(&["descriptionForeground", "sideBar.foreground"]),
+        fg_comment: comment.clone(),
+        selection: theme.color_with_alpha("editor.selectionBackground"),
+        cursor: theme.color(&["editorCursor.foreground"]),
+        git_added: theme.color(&[
+            "gitDecoration.addedResourceForeground",
+            "gitDecoration.untrackedResourceForeground",
+        ]),
+        git_modified: theme.color(&["gitDecoration.modifiedResourceForeground"]),
+        git_deleted: theme.color(&["gitDecoration.deletedResourceForeground"]),
+        git_renamed: theme.color(&["gitDecoration.renamedResourceForeground"]),
+        git_conflict: theme.color(&["gitDecoration.conflictingResourceForeground"]),
+        git_ignored: theme.color(&["gitDecoration.ignoredResourceForeground"]),
+        surface_style: None,
+    };
+
+    let syntax = SemanticSyntax {
+        keyword: theme.first_scope_color(&["keyword.control", "keyword", "storage.type"]),
+        function: theme.first_scope_color(&["entity.name.function", "support.function"]),
+        r#type: theme.first_scope_color(&[
+            "entity.name.type",
+            "support.type",
+            "entity.name.class",
+            "storage.type",
+        ]),
+        string: theme.first_scope_color(&["string.quoted", "string"]),
+        number: theme.first_scope_color(&["constant.numeric"]),
+        constant: theme.first_scope_color(&["constant.language", "support.constant", "constant"]),
+        comment,
+        operator: theme.first_scope_color(&["keyword.operator"]),
+        tag: theme.first_scope_color(&["entity.name.tag"]),
+        attribute: theme.first_scope_color(&["entity.other.attribute-name"]),
+        variable: theme.first_scope_color(&["variable.other", "variable"]),
+        delimiter: theme.first_scope_color(&["punctuation.separator", "punctuation"]),
+        escape: theme.first_scope_color(&["constant.character.escape"]),
+        regexp: theme.first_scope_color(&["string.regexp"]),
+        link: theme.first_scope_color(&["markup.underline.link"]),
+    };
+
+    let terminal = (|| {
+        let color = |key: &str| theme.color(&[&format!("terminal.ansi{key}")]);
+        Some(TerminalPalette {
+            black: color("Black")?,
+            red: color("Red")?,
+            green: color("Green")?,
+            yellow: color("Yellow")?,
+            blue: color("Blue")?,
+            magenta: color("Magenta")?,
+            cyan: color("Cyan")?,
+            white: color("White")?,
+            bright_black: color("BrightBlack")?,
+            bright_red: color("BrightRed")?,
+            bright_green: color("BrightGreen")?,
+            bright_yellow: color("BrightYellow")?,
+            bright_blue: color("BrightBlue")?,
+            bright_magenta: color("BrightMagenta")?,
+            bright_cyan: color("BrightCyan")?,
+            bright_white: color("BrightWhite")?,
+        })
+    })();
+
+    ThemeFile {
+        name: theme
+            .name
+            .clone()
+            .filter(|n| !n.trim().is_empty())
+            .unwrap_or_else(|| fallback_name.to_string()),
+        variant: if is_light { "light" } else { "dark" }.to_string(),
+        palette,
+        ui,
+        syntax,
+        terminal,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = r##"{
+        // A trimmed-down VS Code theme.
+        "name": "Sample Night",
+        "type": "dark",
+        "colors": {
+            "editor.background": "#1A1B26",
+            "editor.foreground": "#c0caf5",
+            "editor.selectionBackground": "#515c7e4d",
+            "terminal.ansiRed": "#f7768e",
+            "sideBar.background": "#16161e", /* inline */
+            "focusBorder": null,
+        },
+        "tokenColors": [
+            { "scope": "comment", "settings": { "foreground": "#565f89", "fontStyle": "italic" } },
+            { "scope": ["keyword", "storage.type"], "settings": { "foreground": "#bb9af7" } },
+            { "scope": "keyword.operator, punctuation", "settings": { "foreground": "#89ddff" } },
+            { "scope": "meta.function entity.name.function", "settings": { "foreground": "#7aa2f7" } },
+            { "scope": "string", "settings": { "foreground": "#9ece6a" } },
+        ],
+    }"##;
+
+    #[test]
+    fn vscode_theme_colors_and_scopes_are_mapped() {
+        assert!(is_vscode_theme(SAMPLE));
+        let theme = import_vscode_theme(SAMPLE, "fallback").unwrap();
+        assert_eq!(theme.name, "Sample Night");
+        assert_eq!(theme.variant, "dark");
+        assert_eq!(theme.palette.bg, "#1a1b26");
+        assert_eq!(theme.palette.red, "#f7768e");
+        assert_eq!(theme.ui.bg_dark.as_deref(), Some("#16161e"));
+        assert_eq!(theme.ui.selection.as_deref(), Some("#515c7e4d"));
+        assert_eq!(theme.syntax.comment.as_deref(), Some("#565f89"));
+        // `keyword.control` falls back to the parent `keyword` rule.
+        assert_eq!(theme.syntax.keyword.as_deref(), Some("#bb9af7"));
+        assert_eq!(theme.syntax.operator.as_deref(), Some("#89ddff"));
+        assert_eq!(theme.syntax.function.as_deref(), Some("#7aa2f7"));
+        assert!(theme.terminal.is_none());
+
+        let resolved = crate::theme::resolve_theme("sample-night", &theme);
+        assert_eq!(resolved.syntax_string, "#9ece6a");
+        assert!(!resolved.is_light);
+    }
+
+    #[test]
+    fn includes_are_layered_under_the_including_theme() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("base.json"),
+            r##"{"type": "light", "colors": {"editor.background": "#fff", "editor.foreground": "#333"}}"##,
+        )
+        .unwrap();
+        let path = dir.path().join("my-color-theme.json");
+        std::fs::write(
+            &path,
+            r##"{"include": "./base.json", "colors": {"editor.foreground": "#111111"}}"##,
+        )
+        .unwrap();
+        let theme = convert_vscode_theme_file(&path).unwrap();
+        assert_eq!(theme.name, "my");
+        assert_eq!(theme.variant, "light");
+        assert_eq!(theme.palette.bg, "#ffffff");
+        assert_eq!(theme.palette.fg, "#111111");
+        assert_eq!(
+            theme_id_for_name("One Dark Pro (Flat)"),
+            "one-dark-pro-flat"
+        );
+    }
+}
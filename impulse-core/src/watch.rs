@@ -0,0 +1,327 @@
+//! Recursive filesystem watching for frontends that need change
+//! notifications without their own platform watcher (e.g. the FFI, so
+//! WKWebView editors can reload files changed on disk).
+//!
+//! Raw `notify` events are reduced to created / modified / deleted / renamed
+//! with absolute paths. Changes inside `.git` are dropped; git state has its
+//! own refresh paths. So are changes to paths hidden by `files_exclude`.
+//! Every change drops the file's cached git diff and blame.
+//!
+//! On macOS FSEvents watches the whole tree from the root and never follows
+//! symlinks. Elsewhere every directory gets its own watch, added by walking
+//! the tree the way the search walkers do: into symlinked directories only
+//! with the `follow_symlinks` setting (stopping at cycles), and never into
+//! skipped mounts (see [`crate::mounts`]). `notify`'s own recursive mode
+//! would follow every link and cross every mount.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Weak};
+
+use parking_lot::Mutex;
+
+use crate::ignore_rules::{ExcludeMatcher, ExcludeScope, LiveExcludes};
+
+use notify::event::{ModifyKind, RenameMode};
+use notify::{EventKind, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WatchEventKind {
+    Created,
+    Modified,
+    Deleted,
+    /// `paths` is `[from, to]`.
+    Renamed,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct WatchEvent {
+    pub kind: WatchEventKind,
+    pub paths: Vec<String>,
+}
+
+/// Whether each directory gets its own watch rather than one recursive
+/// watch on the root.
+const WATCH_EACH_DIRECTORY: bool = cfg!(not(target_os = "macos"));
+
+/// Watches a directory tree until dropped.
+pub struct FileWatcher {
+    _watcher: Arc<Mutex<notify::RecommendedWatcher>>,
+}
+
+impl FileWatcher {
+    /// Start watching `root` recursively. `on_event` runs on the watcher's
+    /// background thread.
+    pub fn new(root: &str, on_event: impl Fn(WatchEvent) + Send + 'static) -> Result<Self, String> {
+        let root_path = Path::new(root);
+        if !root_path.is_dir() {
+            return Err(format!("{} is not a directory", root));
+        }
+        let mut excludes = LiveExcludes::new(root_path);
+        // New directories are watched from another thread: adding a watch
+        // from the event callback would wait on the thread running it.
+        let (new_dirs_tx, new_dirs_rx) = std::sync::mpsc::channel::<PathBuf>();
+        let watcher = notify::recommended_watcher(
+            move |res: Result<notify::Event, notify::Error>| match res {
+                Ok(event) => {
+                    if let Some(event) =
+                        reduce_event(&event).and_then(|e| drop_excluded(e, &mut excludes))
+                    {
+                        if WATCH_EACH_DIRECTORY
+                            && matches!(
+                                event.kind,
+                                WatchEventKind::Created | WatchEventKind::Renamed
+                            )
+                        {
+                            let created = event.paths.last().map(PathBuf::from);
+                            if let Some(dir) = created.filter(|p| p.is_dir()) {
+                                let _ = new_dirs_tx.send(dir);
+                            }
+                        }
+                        crate::git::invalidate_file_caches(&event.paths);
+                        on_event(event);
+                    }
+                }
+                Err(e) => log::warn!("File watcher error: {}", e),
+            },
+        )
+        .map_err(|e| format!("Failed to create watcher: {}", e))?;
+        let watcher = Arc::new(Mutex::new(watcher));
+
+        if !WATCH_EACH_DIRECTORY {
+            watcher
+                .lock()
+                .watch(root_path, RecursiveMode::Recursive)
+                .map_err(|e| format!("Failed to watch {}: {}", root, e))?;
+            return Ok(Self { _watcher: watcher });
+        }
+
+        watcher
+            .lock()
+            .watch(root_path, RecursiveMode::NonRecursive)
+            .map_err(|e| format!("Failed to watch {}: {}", root, e))?;
+        watch_directories(&watcher, root_path, root_path);
+        let weak = Arc::downgrade(&watcher);
+        let root = root_path.to_path_buf();
+        std::thread::Builder::new()
+            .name("file-watcher-dirs".to_string())
+            .spawn(move || watch_new_directories(&weak, &root, &new_dirs_rx))
+            .map_err(|e| format!("Failed to start watcher thread: {}", e))?;
+        Ok(Self { _watcher: watcher })
+    }
+}
+
+/// Add a watch for every directory below `dir` (exclusive) that the search
+/// walkers would enter, except `.git` and `files_exclude` matches.
+fn watch_directories(watcher: &Mutex<notify::RecommendedWatcher>, root: &Path, dir: &Path) {
+    let excludes = ExcludeMatcher::new(root, ExcludeScope::Files);
+    let skipped_mounts = crate::mounts::skipped_mount_points();
+    let walker = ignore::WalkBuilder::new(dir)
+        .standard_filters(false)
+        .follow_links(crate::mounts::follow_symlinks())
+        .filter_entry(move |entry| {
+            entry.depth() == 0
+                || entry.file_type().is_some_and(|ft| ft.is_dir())
+                    && entry.file_name() != ".git"
+                    && !excludes.is_excluded(entry.path(), true)
+                    && !crate::mounts::is_skipped(
+                        entry.path(),
+                        entry.path_is_symlink(),
+                        &skipped_mounts,
+                    )
+        })
+        .build();
+    // Cycles and unreadable directories come back as errors and are skipped.
+    for entry in walker.flatten().filter(|entry| entry.depth() > 0) {
+        if let Err(e) = watcher
+            .lock()
+            .watch(entry.path(), RecursiveMode::NonRecursive)
+        {
+            log::debug!("Not watching {}: {}", entry.path().display(), e);
+        }
+    }
+}
+
+/// Watch directories created or moved into the tree, with everything in
+/// them, until the watcher is dropped.
+fn watch_new_directories(
+    watcher: &Weak<Mutex<notify::RecommendedWatcher>>,
+    root: &Path,
+    new_dirs: &std::sync::mpsc::Receiver<PathBuf>,
+) {
+    for dir in new_dirs {
+        let Some(watcher) = watcher.upgrade() else {
+            break;
+        };
+        if let Err(e) = watcher.lock().watch(&dir, RecursiveMode::NonRecursive) {
+            log::debug!("Not watching {}: {}", dir.display(), e);
+            continue;
+        }
+        watch_directories(&watcher, root, &dir);
+    }
+}
+
+fn in_git_dir(path: &Path) -> bool {
+    path.components().any(|c| c.as_os_str() == ".git")
+}
+
+/// Map a raw `notify` event to a [`WatchEvent`], or `None` for events that
+/// don't change anything (access events, `.git` internals).
+fn reduce_event(event: &notify::Event) -> Option<WatchEvent> {
+    let paths: Vec<&Path> = event
+        .paths
+        .iter()
+        .map(|p| p.as_path())
+        .filter(|p| !in_git_dir(p))
+        .collect();
+    if paths.is_empty() {
+        return None;
+    }
+    let kind = match event.kind {
+        EventKind::Create(_) => WatchEventKind::Created,
+        EventKind::Remove(_) => WatchEventKind::Deleted,
+        EventKind::Modify(ModifyKind::Name(RenameMode::Both)) if paths.len() == 2 => {
+            WatchEventKind::Renamed
+        }
+        EventKind::Modify(ModifyKind::Name(RenameMode::From)) => WatchEventKind::Deleted,
+        EventKind::Modify(ModifyKind::Name(RenameMode::To)) => WatchEventKind::Created,
+        // Platforms that report each side of a rename separately (FSEvents)
+        // don't say which side a path is; existence tells.
+        EventKind::Modify(ModifyKind::Name(_)) => {
+            if paths[0].exists() {
+                WatchEventKind::Created
+            } else {
+                WatchEventKind::Deleted
+            }
+        }
+        EventKind::Modify(_) | EventKind::Any => WatchEventKind::Modified,
+        EventKind::Access(_) | EventKind::Other => return None,
+    };
+    Some(WatchEvent {
+        kind,
+        paths: paths
+            .iter()
+            .map(|p| p.to_string_lossy().to_string())
+            .collect(),
+    })
+}
+
+/// `event` without its excluded paths, or `None` if nothing is left. A
+/// rename across the exclude boundary becomes a creation or deletion.
+fn drop_excluded(event: WatchEvent, excludes: &mut LiveExcludes) -> Option<WatchEvent> {
+    let kept: Vec<bool> = event
+        .paths
+        .iter()
+        .map(|p| !excludes.is_excluded(Path::new(p)))
+        .collect();
+    let kind = match (event.kind, kept.as_slice()) {
+        (WatchEventKind::Renamed, [true, false]) => WatchEventKind::Deleted,
+        (WatchEventKind::Renamed, [false, true]) => WatchEventKind::Created,
+        (kind, _) => kind,
+    };
+    let paths: Vec<String> = event
+        .paths
+        .into_iter()
+        .zip(kept)
+        .filter_map(|(path, kept)| kept.then_some(path))
+        .collect();
+    (!paths.is_empty()).then_some(WatchEvent { kind, paths })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{AccessKind, CreateKind, DataChange};
+    use std::path::PathBuf;
+
+    fn raw(kind: EventKind, paths: &[&str]) -> notify::Event {
+        let mut event = notify::Event::new(kind);
+        event.paths = paths.iter().map(PathBuf::from).collect();
+        event
+    }
+
+    #[test]
+    fn reduce_event_maps_kinds_and_drops_noise() {
+        let created = reduce_event(&raw(EventKind::Create(CreateKind::File), &["/r/a.rs"]));
+        assert_eq!(
+            created,
+            Some(WatchEvent {
+                kind: WatchEventKind::Created,
+                paths: vec!["/r/a.rs".into()],
+            })
+        );
+        let renamed = reduce_event(&raw(
+            EventKind::Modify(ModifyKind::Name(RenameMode::Both)),
+            &["/r/a.rs", "/r/b.rs"],
+        ))
+        .unwrap();
+        assert_eq!(renamed.kind, WatchEventKind::Renamed);
+        assert_eq!(renamed.paths, vec!["/r/a.rs", "/r/b.rs"]);
+        let modified = reduce_event(&raw(
+            EventKind::Modify(ModifyKind::Data(DataChange::Content)),
+            &["/r/a.rs"],
+        ))
+        .unwrap();
+        assert_eq!(modified.kind, WatchEventKind::Modified);
+
+        assert!(reduce_event(&raw(EventKind::Access(AccessKind::Any), &["/r/a.rs"])).is_none());
+        assert!(
+            reduce_event(&raw(EventKind::Modify(ModifyKind::Any), &["/r/.git/index"])).is_none()
+        );
+    }
+
+    #[test]
+    fn watcher_reports_file_creation() {
+        let dir = tempfile::tempdir().unwrap();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let _watcher = FileWatcher::new(dir.path().to_str().unwrap(), move |event| {
+            let _ = tx.send(event);
+        })
+        .unwrap();
+        let file = dir.path().join("new.txt");
+        std::fs::write(&file, "x").unwrap();
+        let file = file.to_string_lossy().to_string();
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        let mut seen = false;
+        while let Some(timeout) = deadline.checked_duration_since(std::time::Instant::now()) {
+            match rx.recv_timeout(timeout) {
+                Ok(event) if event.paths.contains(&file) => {
+                    seen = true;
+                    break;
+                }
+                Ok(_) => {}
+                Err(_) => break,
+            }
+        }
+        assert!(seen, "no event for {}", file);
+        assert!(FileWatcher::new(&file, |_| {}).is_err());
+    }
+
+    #[test]
+    fn watcher_reports_changes_in_new_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        let (tx, rx) = std::sync::mpsc::channel();
+        let _watcher = FileWatcher::new(dir.path().to_str().unwrap(), move |event| {
+            let _ = tx.send(event);
+        })
+        .unwrap();
+        let sub = dir.path().join("sub");
+        std::fs::create_dir(&sub).unwrap();
+        let file = sub.join("new.txt").to_string_lossy().to_string();
+        let deadline = std::time::Instant::now() + std::time::Duration::from_secs(5);
+        let mut seen = false;
+        // The new directory's watch is added asynchronously; keep writing
+        // until it reports.
+        while !seen && std::time::Instant::now() < deadline {
+            std::fs::write(&file, "x").unwrap();
+            while let Ok(event) = rx.recv_timeout(std::time::Duration::from_millis(100)) {
+                if event.paths.contains(&file) {
+                    seen = true;
+                    break;
+                }
+            }
+        }
+        assert!(seen, "no event for {}", file);
+    }
+}
@@ -0,0 +1,120 @@
+//! Named window layouts. "Save Window Layout As…" stores a window's
+//! workspace root, tabs and sidebar under a name, and "Open Layout" opens
+//! one in a new window, for switching between project contexts.
+//!
+//! Each layout is a `<name>.json` file in a `layouts` directory next to the
+//! session state, in the session state format with a single window.
+
+use std::path::{Path, PathBuf};
+
+use crate::session_state::{SessionState, SessionWindow};
+
+/// Longest layout name, in characters.
+const MAX_NAME_LEN: usize = 80;
+
+/// The file for the layout `name`, or why the name can't be used.
+fn layout_path(dir: &Path, name: &str) -> Result<PathBuf, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Layout name is empty".to_string());
+    }
+    if name.chars().count() > MAX_NAME_LEN {
+        return Err(format!(
+            "Layout name is longer than {MAX_NAME_LEN} characters"
+        ));
+    }
+    if name.starts_with('.') || name.contains(['/', '\\']) || name.chars().any(char::is_control) {
+        return Err(format!(
+            "Layout name \"{name}\" can't start with a dot or contain slashes"
+        ));
+    }
+    Ok(dir.join(format!("{name}.json")))
+}
+
+/// The saved layout names in `dir`, sorted case-insensitively.
+pub fn list(dir: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension()? != "json" {
+                return None;
+            }
+            let name = path.file_stem()?.to_str()?.to_string();
+            layout_path(dir, &name).is_ok().then_some(name)
+        })
+        .collect();
+    names.sort_by_key(|name| name.to_lowercase());
+    names
+}
+
+/// Save `window` as the layout `name`, replacing any layout of that name.
+pub fn save(dir: &Path, name: &str, window: &SessionWindow) -> Result<(), String> {
+    let path = layout_path(dir, name)?;
+    let json = SessionState {
+        windows: vec![window.clone()],
+        active_window_index: Some(0),
+        ..Default::default()
+    }
+    .to_json()?;
+    std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, json).map_err(|e| format!("Failed to write {}: {e}", tmp.display()))?;
+    std::fs::rename(&tmp, &path).map_err(|e| format!("Failed to save {}: {e}", path.display()))
+}
+
+/// Load the layout `name`.
+pub fn load(dir: &Path, name: &str) -> Result<SessionWindow, String> {
+    let path = layout_path(dir, name)?;
+    let json = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read layout \"{}\": {e}", name.trim()))?;
+    SessionState::from_json(&json)?
+        .windows
+        .into_iter()
+        .next()
+        .ok_or_else(|| format!("Layout \"{}\" has no window", name.trim()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session_state::{SessionSidebar, SessionTab, SessionTerminalTab};
+
+    #[test]
+    fn saves_lists_and_loads_layouts() {
+        let dir = tempfile::tempdir().unwrap();
+        let layouts = dir.path().join("layouts");
+        let window = SessionWindow {
+            project_root: Some("/repo".to_string()),
+            tabs: vec![SessionTab::Terminal(SessionTerminalTab {
+                cwd: "/repo".to_string(),
+                ..Default::default()
+            })],
+            active_tab_index: Some(0),
+            sidebar: Some(SessionSidebar {
+                visible: false,
+                width: 300,
+            }),
+            ..Default::default()
+        };
+
+        assert!(list(&layouts).is_empty());
+        save(&layouts, " api ", &window).unwrap();
+        save(&layouts, "Web", &window).unwrap();
+        save(&layouts, "docs", &window).unwrap();
+        assert_eq!(list(&layouts), vec!["api", "docs", "Web"]);
+
+        let loaded = load(&layouts, "api").unwrap();
+        assert_eq!(loaded.project_root.as_deref(), Some("/repo"));
+        assert_eq!(loaded.tabs.len(), 1);
+        assert_eq!(loaded.sidebar, window.sidebar);
+
+        assert!(save(&layouts, "", &window).is_err());
+        assert!(save(&layouts, "../escape", &window).is_err());
+        assert!(save(&layouts, ".hidden", &window).is_err());
+        assert!(load(&layouts, "missing").is_err());
+    }
+}
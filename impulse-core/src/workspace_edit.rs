@@ -0,0 +1,390 @@
+//! Workspace-edit engine: flattens LSP `WorkspaceEdit`s into per-file edit
+//! lists, previews them, and applies them to buffers or files on disk.
+//!
+//! Positions follow the LSP convention (0-based lines, UTF-16 code unit
+//! columns) so results from any server can be applied without conversion.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+/// A single replacement in LSP coordinates.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextEdit {
+    pub start_line: u32,
+    pub start_character: u32,
+    pub end_line: u32,
+    pub end_character: u32,
+    pub new_text: String,
+}
+
+impl From<&lsp_types::TextEdit> for TextEdit {
+    fn from(edit: &lsp_types::TextEdit) -> Self {
+        Self {
+            start_line: edit.range.start.line,
+            start_character: edit.range.start.character,
+            end_line: edit.range.end.line,
+            end_character: edit.range.end.character,
+            new_text: edit.new_text.clone(),
+        }
+    }
+}
+
+/// All edits targeting one document.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileEdits {
+    pub uri: String,
+    pub edits: Vec<TextEdit>,
+}
+
+/// A before/after rendering of the line an edit starts on, for confirmation UIs.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EditPreview {
+    /// 0-based line number in the original document.
+    pub line: u32,
+    pub before: String,
+    pub after: String,
+}
+
+/// Flatten a `WorkspaceEdit` into per-file edit lists, sorted by URI.
+///
+/// Both the `changes` map and `document_changes` text edits are collected.
+/// Resource operations (create/rename/delete file) are not supported and are
+/// skipped with a warning.
+pub fn group_workspace_edit(edit: &lsp_types::WorkspaceEdit) -> Vec<FileEdits> {
+    let mut grouped: BTreeMap<String, Vec<TextEdit>> = BTreeMap::new();
+
+    if let Some(changes) = &edit.changes {
+        for (uri, edits) in changes {
+            grouped
+                .entry(uri.to_string())
+                .or_default()
+                .extend(edits.iter().map(TextEdit::from));
+        }
+    }
+
+    let mut push_document_edit = |doc_edit: &lsp_types::TextDocumentEdit| {
+        let entry = grouped
+            .entry(doc_edit.text_document.uri.to_string())
+            .or_default();
+        for edit in &doc_edit.edits {
+            let text_edit = match edit {
+                lsp_types::OneOf::Left(edit) => edit,
+                lsp_types::OneOf::Right(annotated) => &annotated.text_edit,
+            };
+            entry.push(TextEdit::from(text_edit));
+        }
+    };
+
+    match &edit.document_changes {
+        Some(lsp_types::DocumentChanges::Edits(edits)) => {
+            for doc_edit in edits {
+                push_document_edit(doc_edit);
+            }
+        }
+        Some(lsp_types::DocumentChanges::Operations(ops)) => {
+            for op in ops {
+                match op {
+                    lsp_types::DocumentChangeOperation::Edit(doc_edit) => {
+                        push_document_edit(doc_edit)
+                    }
+                    lsp_types::DocumentChangeOperation::Op(op) => {
+                        log::warn!(
+                            "Skipping unsupported workspace resource operation: {:?}",
+                            op
+                        );
+                    }
+                }
+            }
+        }
+        None => {}
+    }
+
+    grouped
+        .into_iter()
+        .filter(|(_, edits)| !edits.is_empty())
+        .map(|(uri, edits)| FileEdits { uri, edits })
+        .collect()
+}
+
+/// Apply `edits` to `content` and return the new text.
+///
+/// Edits are applied back-to-front so earlier ranges stay valid. Overlapping
+/// edits are rejected, matching the LSP requirement that edits in one
+/// document never overlap.
+pub fn apply_text_edits(content: &str, edits: &[TextEdit]) -> Result<String, String> {
+    let mut resolved: Vec<(usize, usize, &str)> = edits
+        .iter()
+        .map(|edit| {
+            let start = position_to_byte_offset(content, edit.start_line, edit.start_character);
+            let end = position_to_byte_offset(content, edit.end_line, edit.end_character);
+            if start > end {
+                return Err(format!(
+                    "Invalid edit range {}:{}-{}:{}",
+                    edit.start_line, edit.start_character, edit.end_line, edit.end_character
+                ));
+            }
+            Ok((start, end, edit.new_text.as_str()))
+        })
+        .collect::<Result<_, _>>()?;
+    // Stable sort keeps insertions at the same offset in their original order.
+    resolved.sort_by_key(|(start, end, _)| (*start, *end));
+
+    for pair in resolved.windows(2) {
+        if pair[0].1 > pair[1].0 {
+            return Err("Overlapping edits cannot be applied".to_string());
+        }
+    }
+
+    let mut result = content.to_string();
+    for (start, end, text) in resolved.into_iter().rev() {
+        result.replace_range(start..end, text);
+    }
+    Ok(result)
+}
+
+/// Add `extra` to `edits` if the combined list still applies cleanly to
+/// `content`, for combining edits that several sources (save participants,
+/// source actions) computed against the same text. Returns whether `extra`
+/// was taken; on a clash `edits` is left as it was.
+pub fn merge_text_edits(content: &str, edits: &mut Vec<TextEdit>, extra: Vec<TextEdit>) -> bool {
+    let before = edits.len();
+    edits.extend(extra);
+    if apply_text_edits(content, edits).is_ok() {
+        return true;
+    }
+    edits.truncate(before);
+    false
+}
+
+/// Render each edit as the line it starts on, before and after applying just
+/// that edit. Multi-line replacements show only their first resulting line.
+pub fn preview_text_edits(content: &str, edits: &[TextEdit]) -> Vec<EditPreview> {
+    edits
+        .iter()
+        .map(|edit| {
+            let line_start = position_to_byte_offset(content, edit.start_line, 0);
+            let line_end = content[line_start..]
+                .find('\n')
+                .map(|i| line_start + i)
+                .unwrap_or(content.len());
+            let edit_end_line_end = {
+                let end = position_to_byte_offset(content, edit.end_line, edit.end_character);
+                content[end..]
+                    .find('\n')
+                    .map(|i| end + i)
+                    .unwrap_or(content.len())
+            };
+            let before = content[line_start..line_end]
+                .trim_end_matches('\r')
+                .to_string();
+
+            let segment = &content[line_start..edit_end_line_end.max(line_end)];
+            let relative = TextEdit {
+                start_line: 0,
+                end_line: edit.end_line - edit.start_line,
+                ..edit.clone()
+            };
+            let after = apply_text_edits(segment, std::slice::from_ref(&relative))
+                .ok()
+                .and_then(|text| text.lines().next().map(|l| l.to_string()))
+                .unwrap_or_default();
+
+            EditPreview {
+                line: edit.start_line,
+                before,
+                after,
+            }
+        })
+        .collect()
+}
+
+/// Apply edits to a file on disk, saving like the editor does
+/// ([`crate::safe_write`]) so a failure never leaves a partially written
+/// document behind and links, permissions and the save strategy are kept.
+pub fn apply_file_edits_on_disk(path: &Path, edits: &[TextEdit]) -> Result<(), String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read '{}': {}", path.display(), e))?;
+    let updated = apply_text_edits(&content, edits)
+        .map_err(|e| format!("Failed to edit '{}': {}", path.display(), e))?;
+    if updated == content {
+        return Ok(());
+    }
+    crate::safe_write::write(path, updated.as_bytes())
+        .map_err(|e| format!("Failed to write '{}': {}", path.display(), e))
+}
+
+/// Convert an LSP position (0-based line, UTF-16 column) into a byte offset,
+/// clamping past-the-end lines and columns to the nearest valid offset.
+pub fn position_to_byte_offset(content: &str, line: u32, character: u32) -> usize {
+    checked_position_to_byte_offset(content, line, character).unwrap_or(content.len())
+}
+
+/// Like [`position_to_byte_offset`], but `None` for a line past the last
+/// one. Columns past the end of a line still clamp to the line end, as the
+/// LSP spec requires.
+pub fn checked_position_to_byte_offset(content: &str, line: u32, character: u32) -> Option<usize> {
+    let mut line_start = 0usize;
+    for _ in 0..line {
+        line_start += content[line_start..].find('\n')? + 1;
+    }
+
+    let mut utf16_units = 0u32;
+    for (relative, ch) in content[line_start..].char_indices() {
+        if ch == '\n' || utf16_units >= character {
+            return Some(line_start + relative);
+        }
+        utf16_units = utf16_units.saturating_add(ch.len_utf16() as u32);
+        if utf16_units > character {
+            return Some(line_start + relative);
+        }
+    }
+    Some(content.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit(sl: u32, sc: u32, el: u32, ec: u32, text: &str) -> TextEdit {
+        TextEdit {
+            start_line: sl,
+            start_character: sc,
+            end_line: el,
+            end_character: ec,
+            new_text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn apply_text_edits_replaces_back_to_front() {
+        let content = "let foo = 1;\nprint(foo);\n";
+        let edits = vec![edit(0, 4, 0, 7, "bar"), edit(1, 6, 1, 9, "bar")];
+        assert_eq!(
+            apply_text_edits(content, &edits).unwrap(),
+            "let bar = 1;\nprint(bar);\n"
+        );
+    }
+
+    #[test]
+    fn apply_text_edits_handles_unsorted_input_and_utf16_columns() {
+        let content = "const é = \"😀\"; é;\n";
+        // "é" is one UTF-16 unit; "😀" is two.
+        let edits = vec![edit(0, 16, 0, 17, "x"), edit(0, 6, 0, 7, "x")];
+        assert_eq!(
+            apply_text_edits(content, &edits).unwrap(),
+            "const x = \"😀\"; x;\n"
+        );
+    }
+
+    #[test]
+    fn apply_text_edits_rejects_overlaps() {
+        let edits = vec![edit(0, 0, 0, 5, "a"), edit(0, 3, 0, 6, "b")];
+        assert!(apply_text_edits("abcdefgh", &edits).is_err());
+    }
+
+    #[test]
+    fn preview_text_edits_shows_line_before_and_after() {
+        let content = "fn old_name() {}\nold_name();\n";
+        let previews = preview_text_edits(content, &[edit(1, 0, 1, 8, "new_name")]);
+        assert_eq!(
+            previews,
+            vec![EditPreview {
+                line: 1,
+                before: "old_name();".to_string(),
+                after: "new_name();".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn group_workspace_edit_merges_changes_and_document_changes() {
+        let uri_a: lsp_types::Uri = "file:///tmp/a.rs".parse().unwrap();
+        let uri_b: lsp_types::Uri = "file:///tmp/b.rs".parse().unwrap();
+        let text_edit = |line| lsp_types::TextEdit {
+            range: lsp_types::Range {
+                start: lsp_types::Position { line, character: 0 },
+                end: lsp_types::Position { line, character: 3 },
+            },
+            new_text: "new".to_string(),
+        };
+        let workspace_edit = lsp_types::WorkspaceEdit {
+            changes: Some([(uri_b.clone(), vec![text_edit(2)])].into_iter().collect()),
+            document_changes: Some(lsp_types::DocumentChanges::Edits(vec![
+                lsp_types::TextDocumentEdit {
+                    text_document: lsp_types::OptionalVersionedTextDocumentIdentifier {
+                        uri: uri_a.clone(),
+                        version: Some(3),
+                    },
+                    edits: vec![lsp_types::OneOf::Left(text_edit(0))],
+                },
+            ])),
+            change_annotations: None,
+        };
+
+        let grouped = group_workspace_edit(&workspace_edit);
+        assert_eq!(grouped.len(), 2);
+        assert_eq!(grouped[0].uri, "file:///tmp/a.rs");
+        assert_eq!(grouped[0].edits[0].start_line, 0);
+        assert_eq!(grouped[1].uri, "file:///tmp/b.rs");
+        assert_eq!(grouped[1].edits[0].start_line, 2);
+    }
+
+    #[test]
+    fn positions_clamp_columns_and_check_lines() {
+        let content = "a😀b\nxy";
+        assert_eq!(position_to_byte_offset(content, 0, 3), 5);
+        assert_eq!(position_to_byte_offset(content, 0, 99), 6);
+        assert_eq!(checked_position_to_byte_offset(content, 1, 1), Some(8));
+        assert_eq!(checked_position_to_byte_offset(content, 2, 0), None);
+        assert_eq!(position_to_byte_offset(content, 2, 0), content.len());
+    }
+
+    #[test]
+    fn apply_file_edits_on_disk_rewrites_file() {
+        let temp = tempfile::tempdir().unwrap();
+        let file = temp.path().join("lib.rs");
+        std::fs::write(&file, "use old;\n").unwrap();
+
+        apply_file_edits_on_disk(&file, &[edit(0, 4, 0, 7, "new")]).unwrap();
+
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "use new;\n");
+        let leftovers = std::fs::read_dir(temp.path()).unwrap().count();
+        assert_eq!(leftovers, 1);
+
+        // Edits through a symlink land in its target, like a save.
+        #[cfg(unix)]
+        {
+            let link = temp.path().join("link.rs");
+            std::os::unix::fs::symlink(&file, &link).unwrap();
+            apply_file_edits_on_disk(&link, &[edit(0, 4, 0, 7, "via")]).unwrap();
+            assert!(std::fs::symlink_metadata(&link)
+                .unwrap()
+                .file_type()
+                .is_symlink());
+            assert_eq!(std::fs::read_to_string(&file).unwrap(), "use via;\n");
+        }
+    }
+
+    #[test]
+    fn merge_text_edits_rejects_clashing_sources() {
+        let content = "use b;\nuse a;\nfn main() {}  \n";
+        let mut edits = vec![edit(2, 12, 2, 14, "")];
+        assert!(merge_text_edits(
+            content,
+            &mut edits,
+            vec![edit(0, 0, 2, 0, "use a;\nuse b;\n")]
+        ));
+        assert!(!merge_text_edits(
+            content,
+            &mut edits,
+            vec![edit(1, 0, 1, 6, "use c;")]
+        ));
+        assert_eq!(edits.len(), 2);
+        assert_eq!(
+            apply_text_edits(content, &edits).unwrap(),
+            "use a;\nuse b;\nfn main() {}\n"
+        );
+    }
+}
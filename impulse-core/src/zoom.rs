@@ -0,0 +1,56 @@
+//! Per-tab and per-pane zoom. Each terminal pane and editor tab keeps a zoom
+//! level: points added to the font size from settings, which stays the
+//! global default. Changing the setting moves every pane; zooming one pane
+//! never touches the setting.
+
+/// Smallest and largest font size, in points, a zoom can reach. Both
+/// renderers draw nothing smaller than 8pt.
+pub const MIN_ZOOMED_FONT_SIZE: i32 = 8;
+pub const MAX_ZOOMED_FONT_SIZE: i32 = 72;
+
+/// The font size to draw with for a pane zoomed by `zoom` over `base`.
+pub fn zoomed_font_size(base: i32, zoom: i32) -> i32 {
+    (base + zoom).clamp(MIN_ZOOMED_FONT_SIZE, MAX_ZOOMED_FONT_SIZE)
+}
+
+/// The zoom after stepping `zoom` by `delta` points, or `zoom` unchanged
+/// when that would leave the allowed font sizes.
+pub fn step(base: i32, zoom: i32, delta: i32) -> i32 {
+    let size = base + zoom + delta;
+    if (MIN_ZOOMED_FONT_SIZE..=MAX_ZOOMED_FONT_SIZE).contains(&size) {
+        zoom + delta
+    } else {
+        zoom
+    }
+}
+
+/// Status bar text for a zoomed pane, like `"120%"`; `None` at the default
+/// size so the segment hides.
+pub fn label(base: i32, zoom: i32) -> Option<String> {
+    let size = zoomed_font_size(base, zoom);
+    if zoom == 0 || base <= 0 || size == base {
+        return None;
+    }
+    Some(format!(
+        "{}%",
+        (f64::from(size) * 100.0 / f64::from(base)).round()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zoom_stays_within_font_limits() {
+        assert_eq!(step(14, 0, 1), 1);
+        assert_eq!(step(14, -6, -1), -6);
+        assert_eq!(step(72, 0, 1), 0);
+        assert_eq!(zoomed_font_size(14, 2), 16);
+        assert_eq!(zoomed_font_size(10, -5), MIN_ZOOMED_FONT_SIZE);
+
+        assert_eq!(label(14, 0), None);
+        assert_eq!(label(10, 2).as_deref(), Some("120%"));
+        assert_eq!(label(14, -7).as_deref(), Some("57%"));
+    }
+}
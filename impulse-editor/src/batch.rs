@@ -0,0 +1,198 @@
+//! Batching for [`EditorCommand`]s sent to the Monaco WebView.
+//!
+//! Hosts queue commands as they are produced and flush once per frame, so a
+//! burst of diagnostics and decoration updates costs one script evaluation
+//! (`impulseReceiveCommands`) instead of one each. Commands that replace
+//! editor state wholesale (diagnostics for a URI, diff decorations, syntax
+//! tokens, blame gutter, conflict regions) drop an earlier queued command of
+//! the same kind, since only the last one would be visible anyway. An
+//! `OpenFile` in between is a barrier: updates for the previous contents are
+//! never merged across it.
+
+use serde_json::Value;
+
+use crate::protocol::EditorCommand;
+
+struct QueuedCommand {
+    /// Commands with equal keys replace each other.
+    coalesce_key: Option<String>,
+    /// `OpenFile`: earlier commands are never coalesced with later ones.
+    barrier: bool,
+    json: String,
+}
+
+/// Commands waiting for the next flush, in send order.
+#[derive(Default)]
+pub struct CommandBatch {
+    queue: Vec<QueuedCommand>,
+}
+
+impl CommandBatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue a command.
+    pub fn push(&mut self, cmd: &EditorCommand) -> Result<(), String> {
+        let value = serde_json::to_value(cmd)
+            .map_err(|e| format!("Failed to serialize EditorCommand: {}", e))?;
+        self.push_value(value);
+        Ok(())
+    }
+
+    /// Queue a command the host serialized itself (the macOS frontend
+    /// mirrors [`EditorCommand`] in Swift).
+    pub fn push_json(&mut self, json: &str) -> Result<(), String> {
+        let value: Value =
+            serde_json::from_str(json).map_err(|e| format!("Invalid editor command: {}", e))?;
+        if value.get("type").and_then(Value::as_str).is_none() {
+            return Err("Editor command has no type".to_string());
+        }
+        self.push_value(value);
+        Ok(())
+    }
+
+    fn push_value(&mut self, value: Value) {
+        let coalesce_key = coalesce_key(&value);
+        let barrier = value.get("type").and_then(Value::as_str) == Some("OpenFile");
+        if let Some(key) = &coalesce_key {
+            let superseded = self
+                .queue
+                .iter()
+                .rposition(|queued| queued.barrier || queued.coalesce_key.as_ref() == Some(key))
+                .filter(|&i| !self.queue[i].barrier);
+            if let Some(i) = superseded {
+                self.queue.remove(i);
+            }
+        }
+        self.queue.push(QueuedCommand {
+            coalesce_key,
+            barrier,
+            json: value.to_string(),
+        });
+    }
+
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    /// Drain the queue into a JSON array for `impulseReceiveCommands`.
+    /// Returns `None` when nothing is queued.
+    pub fn take_json(&mut self) -> Option<String> {
+        if self.queue.is_empty() {
+            return None;
+        }
+        let mut json = String::from("[");
+        for (i, queued) in self.queue.drain(..).enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            json.push_str(&queued.json);
+        }
+        json.push(']');
+        Some(json)
+    }
+}
+
+/// The coalescing key for a serialized command, or `None` for commands that
+/// must all be delivered. Only state-replacing commands have keys.
+fn coalesce_key(value: &Value) -> Option<String> {
+    let kind = value.get("type")?.as_str()?;
+    match kind {
+        "ApplyDiagnostics" => {
+            let uri = value.get("uri")?.as_str()?;
+            Some(format!("{}:{}", kind, uri))
+        }
+        "ApplyDiffDecorations" | "SetSyntaxTokens" | "SetBlameGutter" | "SetConflictRegions" => {
+            Some(kind.to_string())
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::SyntaxToken;
+
+    fn diagnostics(uri: &str) -> EditorCommand {
+        EditorCommand::ApplyDiagnostics {
+            uri: uri.to_string(),
+            markers: Vec::new(),
+        }
+    }
+
+    fn types(json: &str) -> Vec<String> {
+        let values: Vec<Value> = serde_json::from_str(json).unwrap();
+        values
+            .iter()
+            .map(|v| v["type"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn successive_decoration_sets_coalesce() {
+        let mut batch = CommandBatch::new();
+        batch.push(&diagnostics("file:///a.rs")).unwrap();
+        batch.push(&diagnostics("file:///b.rs")).unwrap();
+        batch
+            .push(&EditorCommand::GoToPosition { line: 1, column: 1 })
+            .unwrap();
+        batch.push(&diagnostics("file:///a.rs")).unwrap();
+        batch
+            .push(&EditorCommand::SetSyntaxTokens { tokens: Vec::new() })
+            .unwrap();
+        batch
+            .push(&EditorCommand::SetSyntaxTokens {
+                tokens: vec![SyntaxToken {
+                    line: 0,
+                    start_column: 0,
+                    end_column: 2,
+                    scope: "keyword".to_string(),
+                }],
+            })
+            .unwrap();
+        assert_eq!(batch.len(), 4);
+
+        let json = batch.take_json().unwrap();
+        assert_eq!(
+            types(&json),
+            [
+                "ApplyDiagnostics",
+                "GoToPosition",
+                "ApplyDiagnostics",
+                "SetSyntaxTokens"
+            ]
+        );
+        let values: Vec<Value> = serde_json::from_str(&json).unwrap();
+        assert_eq!(values[0]["uri"], "file:///b.rs");
+        assert_eq!(values[2]["uri"], "file:///a.rs");
+        assert_eq!(values[3]["tokens"].as_array().unwrap().len(), 1);
+        assert!(batch.is_empty());
+        assert!(batch.take_json().is_none());
+    }
+
+    #[test]
+    fn open_file_is_a_coalescing_barrier() {
+        let mut batch = CommandBatch::new();
+        batch
+            .push(&EditorCommand::SetBlameGutter { lines: Vec::new() })
+            .unwrap();
+        batch
+            .push_json(r#"{"type":"OpenFile","file_path":"/b","content":"","language":"rust"}"#)
+            .unwrap();
+        batch
+            .push_json(r#"{"type":"SetBlameGutter","lines":[]}"#)
+            .unwrap();
+        assert_eq!(
+            types(&batch.take_json().unwrap()),
+            ["SetBlameGutter", "OpenFile", "SetBlameGutter"]
+        );
+        assert!(batch.push_json("{}").is_err());
+        assert!(batch.push_json("not json").is_err());
+    }
+}
@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+
+use impulse_core::git::{relative_age, FileBlame};
+
+use crate::protocol::BlameGutterLine;
+
+/// Number of heat levels; `BlameGutterLine::heat` ranges over `0..HEAT_LEVELS`.
+pub const HEAT_LEVELS: u8 = 10;
+
+/// Maximum author name length shown in the gutter before truncation.
+const MAX_AUTHOR_CHARS: usize = 16;
+
+/// Build the blame gutter rows for a file.
+///
+/// Only the first line of each run of lines from the same commit is labeled
+/// ("author, age"); the rest carry just their heat so the gutter stays
+/// readable. Heat ranks the file's commits by age: the newest gets the
+/// highest level and uncommitted lines are always hottest.
+pub fn build_blame_gutter(blame: &FileBlame, now: i64) -> Vec<BlameGutterLine> {
+    let mut timestamps: Vec<i64> = blame
+        .lines
+        .iter()
+        .filter(|l| l.committed)
+        .map(|l| l.timestamp)
+        .collect();
+    timestamps.sort_unstable();
+    timestamps.dedup();
+    let rank: HashMap<i64, usize> = timestamps
+        .iter()
+        .enumerate()
+        .map(|(i, &t)| (t, i))
+        .collect();
+    let max_level = u32::from(HEAT_LEVELS - 1);
+    let heat_for = |committed: bool, timestamp: i64| -> u8 {
+        if !committed {
+            return HEAT_LEVELS - 1;
+        }
+        if timestamps.len() <= 1 {
+            return 0;
+        }
+        let position = rank.get(&timestamp).copied().unwrap_or(0) as u32;
+        let level = position * max_level / (timestamps.len() as u32 - 1);
+        level as u8
+    };
+
+    let mut previous_hash: Option<&str> = None;
+    blame
+        .lines
+        .iter()
+        .enumerate()
+        .map(|(index, line)| {
+            let starts_run = previous_hash != Some(line.commit_hash.as_str());
+            previous_hash = Some(line.commit_hash.as_str());
+            let label = if !starts_run {
+                String::new()
+            } else if !line.committed {
+                "Uncommitted".to_string()
+            } else {
+                format!(
+                    "{}, {}",
+                    truncate_author(&line.author),
+                    relative_age(line.timestamp, now)
+                )
+            };
+            BlameGutterLine {
+                line: index as u32 + 1,
+                label,
+                heat: heat_for(line.committed, line.timestamp),
+            }
+        })
+        .collect()
+}
+
+fn truncate_author(author: &str) -> String {
+    if author.chars().count() <= MAX_AUTHOR_CHARS {
+        return author.to_string();
+    }
+    let mut truncated: String = author.chars().take(MAX_AUTHOR_CHARS - 1).collect();
+    truncated.push('\u{2026}');
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use impulse_core::git::BlameLine;
+
+    fn line(hash: &str, author: &str, timestamp: i64, committed: bool) -> BlameLine {
+        BlameLine {
+            commit_hash: hash.to_string(),
+            author: author.to_string(),
+            timestamp,
+            tz_offset_minutes: 0,
+            summary: String::new(),
+            committed,
+        }
+    }
+
+    #[test]
+    fn labels_only_the_first_line_of_each_commit_run() {
+        let blame = FileBlame {
+            lines: vec![
+                line("aaaaaaa", "Alice", 1_000, true),
+                line("aaaaaaa", "Alice", 1_000, true),
+                line("bbbbbbb", "Bob", 2_000, true),
+                line("0000000", "Not Committed Yet", 3_000, false),
+            ],
+        };
+        let rows = build_blame_gutter(&blame, 1_000 + 2 * 86_400);
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows[0].line, 1);
+        assert_eq!(rows[0].label, "Alice, 2 days ago");
+        assert!(rows[1].label.is_empty());
+        assert!(rows[2].label.starts_with("Bob, "));
+        assert_eq!(rows[3].label, "Uncommitted");
+    }
+
+    #[test]
+    fn heat_ranks_commits_oldest_to_newest() {
+        let blame = FileBlame {
+            lines: vec![
+                line("aaaaaaa", "A", 1_000, true),
+                line("bbbbbbb", "B", 5_000, true),
+                line("ccccccc", "C", 9_000, true),
+                line("0000000", "Not Committed Yet", 0, false),
+            ],
+        };
+        let heat: Vec<u8> = build_blame_gutter(&blame, 10_000)
+            .iter()
+            .map(|row| row.heat)
+            .collect();
+        assert_eq!(heat, vec![0, 4, HEAT_LEVELS - 1, HEAT_LEVELS - 1]);
+    }
+
+    #[test]
+    fn long_author_names_are_truncated() {
+        assert_eq!(truncate_author("Alice"), "Alice");
+        let long = truncate_author("Bartholomew Montgomery-Smythe");
+        assert_eq!(long.chars().count(), MAX_AUTHOR_CHARS);
+        assert!(long.ends_with('\u{2026}'));
+    }
+}
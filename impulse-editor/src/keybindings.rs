@@ -0,0 +1,213 @@
+use std::collections::HashMap;
+
+use crate::protocol::EditorKeybinding;
+
+/// A Monaco-internal editor action that can be rebound from settings.
+///
+/// Overrides live in the same `keybinding_overrides` map as the native
+/// shortcuts, keyed by `id`. Actions without an override keep Monaco's own
+/// default, which `default_linux` / `default_macos` describe for display.
+pub struct EditorAction {
+    pub id: &'static str,
+    pub description: &'static str,
+    /// Monaco command ID, e.g. `editor.action.commentLine`.
+    pub monaco_action: &'static str,
+    pub default_linux: &'static str,
+    pub default_macos: &'static str,
+}
+
+const fn action(
+    id: &'static str,
+    description: &'static str,
+    monaco_action: &'static str,
+    default_linux: &'static str,
+    default_macos: &'static str,
+) -> EditorAction {
+    EditorAction {
+        id,
+        description,
+        monaco_action,
+        default_linux,
+        default_macos,
+    }
+}
+
+pub const EDITOR_ACTIONS: &[EditorAction] = &[
+    action(
+        "toggle_comment",
+        "Toggle Line Comment",
+        "editor.action.commentLine",
+        "Ctrl+/",
+        "Cmd+/",
+    ),
+    action(
+        "toggle_block_comment",
+        "Toggle Block Comment",
+        "editor.action.blockComment",
+        "Ctrl+Shift+A",
+        "Shift+Alt+A",
+    ),
+    action(
+        "duplicate_line",
+        "Duplicate Line",
+        "editor.action.copyLinesDownAction",
+        "Shift+Alt+Down",
+        "Shift+Alt+Down",
+    ),
+    action(
+        "move_line_up",
+        "Move Line Up",
+        "editor.action.moveLinesUpAction",
+        "Alt+Up",
+        "Alt+Up",
+    ),
+    action(
+        "move_line_down",
+        "Move Line Down",
+        "editor.action.moveLinesDownAction",
+        "Alt+Down",
+        "Alt+Down",
+    ),
+    action(
+        "delete_line",
+        "Delete Line",
+        "editor.action.deleteLines",
+        "Ctrl+Shift+K",
+        "Cmd+Shift+K",
+    ),
+    action(
+        "expand_selection",
+        "Expand Selection",
+        "editor.action.smartSelect.expand",
+        "Shift+Alt+Right",
+        "Ctrl+Shift+Cmd+Right",
+    ),
+    action(
+        "shrink_selection",
+        "Shrink Selection",
+        "editor.action.smartSelect.shrink",
+        "Shift+Alt+Left",
+        "Ctrl+Shift+Cmd+Left",
+    ),
+    action(
+        "select_next_occurrence",
+        "Add Selection to Next Match",
+        "editor.action.addSelectionToNextFindMatch",
+        "Ctrl+D",
+        "Cmd+D",
+    ),
+    action(
+        "add_cursor_below",
+        "Add Cursor Below",
+        "editor.action.insertCursorBelow",
+        "Ctrl+Alt+Down",
+        "Cmd+Alt+Down",
+    ),
+    action(
+        "add_cursor_above",
+        "Add Cursor Above",
+        "editor.action.insertCursorAbove",
+        "Ctrl+Alt+Up",
+        "Cmd+Alt+Up",
+    ),
+    action(
+        "indent_lines",
+        "Indent Line",
+        "editor.action.indentLines",
+        "Ctrl+]",
+        "Cmd+]",
+    ),
+    action(
+        "outdent_lines",
+        "Outdent Line",
+        "editor.action.outdentLines",
+        "Ctrl+[",
+        "Cmd+[",
+    ),
+    action(
+        "format_document",
+        "Format Document",
+        "editor.action.formatDocument",
+        "Ctrl+Shift+I",
+        "Shift+Alt+F",
+    ),
+    action(
+        "rename_symbol",
+        "Rename Symbol",
+        "editor.action.rename",
+        "F2",
+        "F2",
+    ),
+    action(
+        "go_to_definition",
+        "Go to Definition",
+        "editor.action.revealDefinition",
+        "F12",
+        "F12",
+    ),
+];
+
+impl EditorAction {
+    /// Monaco's default shortcut on this platform, in settings notation.
+    pub fn default_shortcut(&self) -> &'static str {
+        if cfg!(target_os = "macos") {
+            self.default_macos
+        } else {
+            self.default_linux
+        }
+    }
+}
+
+/// The keybindings to push into Monaco for the given overrides. Only
+/// overridden actions are included; the rest keep Monaco's defaults.
+pub fn editor_keybindings(overrides: &HashMap<String, String>) -> Vec<EditorKeybinding> {
+    EDITOR_ACTIONS
+        .iter()
+        .filter_map(|action| {
+            let key = overrides.get(action.id)?.trim();
+            (!key.is_empty()).then(|| EditorKeybinding {
+                command: action.monaco_action.to_string(),
+                key: key.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// `(VS Code command, action id)` for every action, for
+/// `impulse_core::vscode_import` (Monaco's command IDs are VS Code's).
+pub fn vscode_commands() -> Vec<(&'static str, &'static str)> {
+    EDITOR_ACTIONS
+        .iter()
+        .map(|action| (action.monaco_action, action.id))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_overridden_actions_are_pushed() {
+        let mut overrides = HashMap::new();
+        overrides.insert("toggle_comment".to_string(), "Ctrl+Shift+7".to_string());
+        overrides.insert("move_line_up".to_string(), "  ".to_string());
+        overrides.insert("save".to_string(), "Ctrl+Alt+S".to_string());
+
+        let bindings = editor_keybindings(&overrides);
+        assert_eq!(
+            bindings,
+            vec![EditorKeybinding {
+                command: "editor.action.commentLine".to_string(),
+                key: "Ctrl+Shift+7".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn action_ids_are_unique() {
+        let mut ids: Vec<&str> = EDITOR_ACTIONS.iter().map(|a| a.id).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        assert_eq!(ids.len(), EDITOR_ACTIONS.len());
+    }
+}
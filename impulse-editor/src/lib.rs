@@ -1,6 +1,10 @@
 pub mod assets;
+pub mod batch;
+pub mod blame;
 pub mod css;
+pub mod keybindings;
 pub mod markdown;
+pub mod peek;
 pub mod protocol;
 pub mod svg;
 
@@ -0,0 +1,192 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::protocol::{MonacoLocation, MonacoPeekLocation};
+
+/// Lines of context shown above and below the peeked range.
+pub const PEEK_CONTEXT_LINES: u32 = 6;
+
+/// Maximum number of lines of the peeked range itself that are included in a
+/// preview. Long ranges (e.g. a whole class body) are cut off after this.
+const MAX_RANGE_LINES: u32 = 40;
+
+/// Maximum number of results sent to the WebView. Huge reference lists are
+/// truncated rather than serialized in full.
+pub const MAX_PEEK_LOCATIONS: usize = 500;
+
+/// Files larger than this are not read for previews.
+const MAX_PREVIEW_SOURCE_SIZE: u64 = 4 * 1024 * 1024; // 4 MB
+
+/// Build the peek widget rows for a set of LSP locations.
+///
+/// `read_source` is called at most once per distinct URI and should return the
+/// current text of that document — frontends pass unsaved buffer contents for
+/// open tabs and fall back to [`read_source_from_disk`] otherwise. Locations
+/// whose source cannot be read are still listed, just with an empty preview.
+/// Duplicate locations are dropped and the LSP's ordering is preserved.
+pub fn build_peek_locations<F>(
+    locations: &[MonacoLocation],
+    mut read_source: F,
+) -> Vec<MonacoPeekLocation>
+where
+    F: FnMut(&str) -> Option<String>,
+{
+    let mut sources: HashMap<String, Option<String>> = HashMap::new();
+    let mut seen = HashSet::new();
+    let mut result = Vec::new();
+
+    for location in locations {
+        if result.len() >= MAX_PEEK_LOCATIONS {
+            break;
+        }
+        let key = (
+            location.uri.clone(),
+            location.range.start_line,
+            location.range.start_column,
+            location.range.end_line,
+            location.range.end_column,
+        );
+        if !seen.insert(key) {
+            continue;
+        }
+
+        let source = sources
+            .entry(location.uri.clone())
+            .or_insert_with(|| read_source(&location.uri));
+        let (preview_start_line, preview_lines) = match source {
+            Some(text) => preview_window(
+                text,
+                location.range.start_line,
+                location.range.end_line,
+                PEEK_CONTEXT_LINES,
+            ),
+            None => (location.range.start_line, Vec::new()),
+        };
+
+        result.push(MonacoPeekLocation {
+            uri: location.uri.clone(),
+            range: location.range.clone(),
+            language: impulse_core::util::language_from_uri(&location.uri),
+            preview_start_line,
+            preview_lines,
+        });
+    }
+    result
+}
+
+/// Return the 0-based first line and the lines of `content` surrounding the
+/// 0-based `start_line..=end_line` range, padded with `context` lines on
+/// either side and clamped to the document.
+pub fn preview_window(
+    content: &str,
+    start_line: u32,
+    end_line: u32,
+    context: u32,
+) -> (u32, Vec<String>) {
+    let end_line = end_line
+        .max(start_line)
+        .min(start_line.saturating_add(MAX_RANGE_LINES));
+    let first = start_line.saturating_sub(context);
+    let last = end_line.saturating_add(context);
+    let lines: Vec<String> = content
+        .lines()
+        .skip(first as usize)
+        .take((last - first) as usize + 1)
+        .map(|line| line.to_string())
+        .collect();
+    (first, lines)
+}
+
+/// Read a `file://` URI from disk for a peek preview. Returns `None` for
+/// non-file URIs, unreadable or oversized files, and non-UTF-8 content.
+pub fn read_source_from_disk(uri: &str) -> Option<String> {
+    if !uri.starts_with("file://") {
+        return None;
+    }
+    let path = impulse_core::util::uri_to_file_path(uri);
+    let metadata = std::fs::metadata(&path).ok()?;
+    if !metadata.is_file() || metadata.len() > MAX_PREVIEW_SOURCE_SIZE {
+        return None;
+    }
+    std::fs::read_to_string(&path).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::MonacoRange;
+
+    fn location(uri: &str, line: u32) -> MonacoLocation {
+        MonacoLocation {
+            uri: uri.to_string(),
+            range: MonacoRange {
+                start_line: line,
+                start_column: 0,
+                end_line: line,
+                end_column: 3,
+            },
+        }
+    }
+
+    #[test]
+    fn preview_window_clamps_to_document_start() {
+        let content = "a\nb\nc\nd\ne";
+        let (first, lines) = preview_window(content, 1, 1, 3);
+        assert_eq!(first, 0);
+        assert_eq!(lines, vec!["a", "b", "c", "d", "e"]);
+    }
+
+    #[test]
+    fn preview_window_pads_both_sides() {
+        let content = (0..20)
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let (first, lines) = preview_window(&content, 10, 11, 2);
+        assert_eq!(first, 8);
+        assert_eq!(lines, vec!["8", "9", "10", "11", "12", "13"]);
+    }
+
+    #[test]
+    fn preview_window_caps_long_ranges() {
+        let content = (0..200)
+            .map(|i| i.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        let (first, lines) = preview_window(&content, 0, 199, 0);
+        assert_eq!(first, 0);
+        assert_eq!(lines.len() as u32, MAX_RANGE_LINES + 1);
+    }
+
+    #[test]
+    fn build_peek_locations_reads_each_uri_once_and_dedupes() {
+        let mut reads = Vec::new();
+        let locations = vec![
+            location("file:///a.rs", 0),
+            location("file:///a.rs", 2),
+            location("file:///a.rs", 0),
+            location("file:///b.py", 1),
+        ];
+        let result = build_peek_locations(&locations, |uri| {
+            reads.push(uri.to_string());
+            Some("zero\none\ntwo".to_string())
+        });
+        assert_eq!(reads, vec!["file:///a.rs", "file:///b.py"]);
+        assert_eq!(result.len(), 3);
+        assert_eq!(result[0].language, "rust");
+        assert_eq!(result[2].language, "python");
+        assert_eq!(result[1].preview_lines, vec!["zero", "one", "two"]);
+    }
+
+    #[test]
+    fn build_peek_locations_keeps_unreadable_locations() {
+        let result = build_peek_locations(&[location("file:///missing.rs", 5)], |_| None);
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].preview_start_line, 5);
+        assert!(result[0].preview_lines.is_empty());
+    }
+
+    #[test]
+    fn read_source_from_disk_rejects_non_file_uris() {
+        assert!(read_source_from_disk("https://example.com/a.rs").is_none());
+    }
+}
@@ -1,4 +1,41 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+// ---------------------------------------------------------------------------
+// Handshake
+// ---------------------------------------------------------------------------
+
+/// Version of the editor protocol spoken by this crate and the bundled
+/// `editor.js`. Bump it whenever a command or event changes shape.
+///
+/// The editor reports its version in [`EditorEvent::Ready`] and the host
+/// answers with [`EditorCommand::Handshake`]. Bundles from before the
+/// handshake report version 0. Either side ignores messages it does not
+/// know, so a mismatch only disables features rather than breaking the
+/// editor.
+pub const PROTOCOL_VERSION: u32 = 9;
+
+/// Optional host capabilities the editor gates its UI on. Actions whose
+/// feature the host did not list in [`EditorCommand::Handshake`] are hidden.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HostFeature {
+    /// The host answers `PeekRequested` with `ShowPeek`.
+    Peek,
+    /// The host handles `StageHunkRequested`.
+    StageHunk,
+    /// The host handles `OpenMergeView`.
+    MergeView,
+    /// The host answers `OnTypeFormattingRequested` with `ResolveFormatting`.
+    OnTypeFormatting,
+}
+
+/// Whether an editor reporting `editor_version` speaks the same protocol as
+/// this host. Hosts log a mismatch and carry on.
+pub fn protocol_compatible(editor_version: u32) -> bool {
+    editor_version == PROTOCOL_VERSION
+}
 
 // ---------------------------------------------------------------------------
 // Commands: Rust → Monaco (sent via evaluate_javascript)
@@ -7,6 +44,12 @@ use serde::{Deserialize, Serialize};
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum EditorCommand {
+    /// Reply to [`EditorEvent::Ready`] with the host's protocol version and
+    /// the optional features it handles.
+    Handshake {
+        protocol_version: u32,
+        features: Vec<HostFeature>,
+    },
     OpenFile {
         file_path: String,
         content: String,
@@ -46,6 +89,11 @@ pub enum EditorCommand {
     },
     ApplyDiffDecorations {
         decorations: Vec<DiffDecoration>,
+        /// Changed regions backing next/previous-change navigation, the
+        /// old-text gutter hover and per-hunk revert/stage. Hosts that only
+        /// send line markers may omit it.
+        #[serde(default)]
+        hunks: Vec<DiffHunkMarker>,
     },
     ResolveFormatting {
         request_id: u64,
@@ -72,6 +120,85 @@ pub enum EditorCommand {
         range: Option<MonacoRange>,
         placeholder: Option<String>,
     },
+    /// Open the inline peek widget for a `PeekRequested` event. An empty
+    /// `locations` list shows a "no results" message instead.
+    ShowPeek {
+        request_id: u64,
+        kind: PeekKind,
+        locations: Vec<MonacoPeekLocation>,
+    },
+    /// Run a Monaco editor action by id, e.g. `editor.action.organizeImports`
+    /// from the host's command palette.
+    RunAction {
+        id: String,
+    },
+    /// Apply edits to the current model as a single undoable operation.
+    /// Used for workspace edits that the host confirmed outside Monaco.
+    ApplyEdits {
+        edits: Vec<MonacoTextEdit>,
+    },
+    /// Replace the whole buffer with `content` as one undoable edit, keeping
+    /// the undo history (unlike `OpenFile`). Used to revert to the saved file
+    /// or restore an earlier version.
+    ReplaceContent {
+        content: String,
+    },
+    /// Highlight the current model as the Monaco language `language`, when
+    /// the user overrides the detected language.
+    SetLanguage {
+        language: String,
+    },
+    /// Replace the tree-sitter highlighting of the current model. Sent only
+    /// for languages Monaco has no grammar for; an empty list clears it.
+    SetSyntaxTokens {
+        tokens: Vec<SyntaxToken>,
+    },
+    /// Show per-line blame annotations in a gutter column before the text.
+    /// An empty list hides the gutter.
+    SetBlameGutter {
+        lines: Vec<BlameGutterLine>,
+    },
+    /// Mark merge-conflict regions with "Accept Current / Incoming / Both"
+    /// actions. An empty list clears them.
+    SetConflictRegions {
+        regions: Vec<ConflictRegionMarker>,
+    },
+    /// Replace the user stylesheet (`editor.css`) injected after the page's
+    /// own styles. An empty string removes it.
+    SetCustomCss {
+        css: String,
+    },
+    /// Replace the user's rebindings of Monaco actions. Each entry drops the
+    /// action's default shortcuts; actions not listed keep theirs.
+    SetKeybindings {
+        bindings: Vec<EditorKeybinding>,
+    },
+    /// Ask for the current buffer. Answered with an [`EditorEvent::Response`]
+    /// carrying `content`.
+    GetContent {
+        request_id: u64,
+    },
+    /// Ask for the selected text (empty when nothing is selected). Answered
+    /// with an [`EditorEvent::Response`] carrying `content`.
+    GetSelection {
+        request_id: u64,
+    },
+    /// Run `command` and answer with an [`EditorEvent::Response`] once it
+    /// has been applied, with `error` set if it failed. Lets the host notice
+    /// an editor that stopped responding (see [`PendingRequests`]).
+    Request {
+        request_id: u64,
+        command: Box<EditorCommand>,
+    },
+    /// Set the current file's indentation. With `convert`, existing leading
+    /// whitespace is rewritten to match (as one undoable edit); otherwise
+    /// only new indentation follows it.
+    SetIndentation {
+        tab_size: u32,
+        insert_spaces: bool,
+        #[serde(default)]
+        convert: bool,
+    },
 }
 
 // ---------------------------------------------------------------------------
@@ -81,7 +208,13 @@ pub enum EditorCommand {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum EditorEvent {
-    Ready,
+    /// Monaco finished loading. The host should answer with
+    /// [`EditorCommand::Handshake`].
+    Ready {
+        /// 0 for editor bundles that predate the handshake.
+        #[serde(default)]
+        protocol_version: u32,
+    },
     FileOpened,
     ContentChanged {
         #[serde(default)]
@@ -123,6 +256,16 @@ pub enum EditorEvent {
         tab_size: u32,
         insert_spaces: bool,
     },
+    /// `ch` was just typed at `line`/`character` (the position after it).
+    /// The host answers with [`EditorCommand::ResolveFormatting`].
+    OnTypeFormattingRequested {
+        request_id: u64,
+        line: u32,
+        character: u32,
+        ch: String,
+        tab_size: u32,
+        insert_spaces: bool,
+    },
     SignatureHelpRequested {
         request_id: u64,
         line: u32,
@@ -140,6 +283,16 @@ pub enum EditorEvent {
         end_line: u32,
         end_column: u32,
         diagnostics: Vec<MonacoDiagnostic>,
+        /// Action kind to narrow the request to, e.g. `source.organizeImports`
+        /// for the Organize Imports action.
+        #[serde(default)]
+        only: Option<String>,
+    },
+    /// The user picked a code action carrying a server command.
+    ExecuteCommand {
+        command: String,
+        #[serde(default)]
+        arguments: Vec<serde_json::Value>,
     },
     RenameRequested {
         request_id: u64,
@@ -152,9 +305,153 @@ pub enum EditorEvent {
         line: u32,
         character: u32,
     },
+    /// Fired by the Peek Definition / Peek References actions. The host
+    /// answers with [`EditorCommand::ShowPeek`] instead of navigating.
+    PeekRequested {
+        request_id: u64,
+        kind: PeekKind,
+        line: u32,
+        character: u32,
+    },
     FocusChanged {
         focused: bool,
     },
+    /// The user asked to stage one gutter hunk. Fields echo the
+    /// [`DiffHunkMarker`] the action was invoked on.
+    StageHunkRequested {
+        new_start: u32,
+        new_lines: u32,
+    },
+    /// The user asked to compare the sides of a merge conflict in a
+    /// three-way view.
+    OpenMergeView,
+    /// Answer to [`EditorCommand::GetContent`], [`EditorCommand::GetSelection`]
+    /// or [`EditorCommand::Request`].
+    Response {
+        request_id: u64,
+        /// The buffer, for `GetContent`, or the selected text, for
+        /// `GetSelection`.
+        #[serde(default)]
+        content: Option<String>,
+        /// Why the request failed, if it did.
+        #[serde(default)]
+        error: Option<String>,
+    },
+}
+
+// ---------------------------------------------------------------------------
+// Request/response correlation
+// ---------------------------------------------------------------------------
+
+/// How long a host waits for an [`EditorEvent::Response`] before giving up
+/// on the editor.
+pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Why a correlated editor request produced no result.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RequestError {
+    /// The editor didn't answer within the timeout.
+    TimedOut,
+    /// The editor answered with an error.
+    Failed(String),
+}
+
+impl std::fmt::Display for RequestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RequestError::TimedOut => write!(f, "The editor did not respond"),
+            RequestError::Failed(e) => write!(f, "The editor failed: {}", e),
+        }
+    }
+}
+
+/// The outcome of an [`EditorEvent::Response`]: the content it carried, if
+/// any, or the editor's error.
+pub fn response_result(
+    content: Option<String>,
+    error: Option<String>,
+) -> Result<Option<String>, RequestError> {
+    match error {
+        Some(e) => Err(RequestError::Failed(e)),
+        None => Ok(content),
+    }
+}
+
+/// Requests sent to one editor and waiting for their
+/// [`EditorEvent::Response`]. `T` is whatever the host runs with the result,
+/// usually a callback. The host calls [`PendingRequests::expire`] when the
+/// timeout passes, so a hung WebView fails its requests instead of leaving
+/// them (and e.g. a save waiting on the buffer) pending forever.
+pub struct PendingRequests<T> {
+    next_id: u64,
+    timeout: Duration,
+    pending: HashMap<u64, (Instant, T)>,
+}
+
+impl<T> PendingRequests<T> {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            next_id: 1,
+            timeout,
+            pending: HashMap::new(),
+        }
+    }
+
+    /// Track a new request and return its id.
+    pub fn register(&mut self, on_response: T) -> u64 {
+        self.register_at(on_response, Instant::now())
+    }
+
+    fn register_at(&mut self, on_response: T, now: Instant) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.pending.insert(id, (now + self.timeout, on_response));
+        id
+    }
+
+    /// The request `request_id` answered; `None` if it is unknown or
+    /// already timed out.
+    pub fn resolve(&mut self, request_id: u64) -> Option<T> {
+        self.pending.remove(&request_id).map(|(_, t)| t)
+    }
+
+    /// Remove and return the requests whose deadline has passed, oldest
+    /// first.
+    pub fn expire(&mut self) -> Vec<T> {
+        self.expire_at(Instant::now())
+    }
+
+    fn expire_at(&mut self, now: Instant) -> Vec<T> {
+        let mut expired: Vec<u64> = self
+            .pending
+            .iter()
+            .filter(|(_, (deadline, _))| *deadline <= now)
+            .map(|(&id, _)| id)
+            .collect();
+        expired.sort_unstable();
+        expired
+            .into_iter()
+            .filter_map(|id| self.resolve(id))
+            .collect()
+    }
+
+    /// Remove and return every pending request, e.g. when the editor goes
+    /// away.
+    pub fn drain(&mut self) -> Vec<T> {
+        let mut pending: Vec<(u64, T)> = self.pending.drain().map(|(id, (_, t))| (id, t)).collect();
+        pending.sort_unstable_by_key(|(id, _)| *id);
+        pending.into_iter().map(|(_, t)| t).collect()
+    }
+
+    /// How long to wait before the next [`PendingRequests::expire`] call
+    /// can find anything.
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -171,7 +468,12 @@ pub enum EditorEvent {
 #[serde(tag = "type")]
 pub enum ReviewCommand {
     /// Rebuild the review list with one collapsible section per changed file.
-    Render { files: Vec<ReviewFileEntry> },
+    /// `read_only` hides the per-file discard buttons (ref/file comparisons).
+    Render {
+        files: Vec<ReviewFileEntry>,
+        #[serde(default)]
+        read_only: bool,
+    },
     /// Provide the unified-diff hunks for a single file's section.
     SetHunks {
         path: String,
@@ -362,6 +664,26 @@ pub struct MonacoLocation {
     pub range: MonacoRange,
 }
 
+/// Which LSP query a peek widget is showing results for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PeekKind {
+    Definition,
+    References,
+}
+
+/// One result row in the peek widget, with enough surrounding source for the
+/// embedded preview so the WebView never has to read files itself.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonacoPeekLocation {
+    pub uri: String,
+    pub range: MonacoRange,
+    pub language: String,
+    /// 0-based line number of `preview_lines[0]` in the target file.
+    pub preview_start_line: u32,
+    pub preview_lines: Vec<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MonacoCodeAction {
     pub title: String,
@@ -369,6 +691,19 @@ pub struct MonacoCodeAction {
     pub kind: Option<String>,
     pub edits: Vec<MonacoWorkspaceTextEdit>,
     pub is_preferred: bool,
+    /// Server command to run after `edits` are applied; the editor sends it
+    /// back as [`EditorEvent::ExecuteCommand`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<MonacoCommand>,
+}
+
+/// An LSP `Command` attached to a code action.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MonacoCommand {
+    pub title: String,
+    pub command: String,
+    #[serde(default)]
+    pub arguments: Vec<serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -378,6 +713,33 @@ pub struct MonacoWorkspaceTextEdit {
     pub text: String,
 }
 
+// ---------------------------------------------------------------------------
+// Syntax tokens
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyntaxToken {
+    /// 0-based line number
+    pub line: u32,
+    /// 0-based start column (UTF-16 code units)
+    pub start_column: u32,
+    /// 0-based exclusive end column (UTF-16 code units)
+    pub end_column: u32,
+    /// Monaco theme token name, e.g. `keyword` or `string.escape`
+    pub scope: String,
+}
+
+impl From<impulse_core::highlight::HighlightToken> for SyntaxToken {
+    fn from(token: impulse_core::highlight::HighlightToken) -> Self {
+        Self {
+            line: token.line,
+            start_column: token.start_column,
+            end_column: token.end_column,
+            scope: token.scope,
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Diff decorations
 // ---------------------------------------------------------------------------
@@ -397,6 +759,81 @@ pub struct DiffDecoration {
     pub status: DiffStatus,
 }
 
+/// One row of the blame gutter (see [`crate::blame::build_blame_gutter`]).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlameGutterLine {
+    /// 1-based line number
+    pub line: u32,
+    /// "author, age" on the first line of each commit run, empty otherwise.
+    pub label: String,
+    /// Age heat level, 0 (oldest) to `blame::HEAT_LEVELS - 1` (newest).
+    pub heat: u8,
+}
+
+/// One changed region relative to HEAD, with line numbers from the diff's
+/// `@@` header (see [`impulse_core::git::GutterHunk`]).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiffHunkMarker {
+    /// 1-based first HEAD line.
+    pub old_start: u32,
+    pub old_lines: u32,
+    /// 1-based first line in the editor; for pure deletions, the line the
+    /// deletion follows (0 at the top of the file).
+    pub new_start: u32,
+    pub new_lines: u32,
+    /// The HEAD lines the hunk replaced, shown on hover and restored on revert.
+    pub old_text: Vec<String>,
+}
+
+impl From<impulse_core::git::GutterHunk> for DiffHunkMarker {
+    fn from(hunk: impulse_core::git::GutterHunk) -> Self {
+        Self {
+            old_start: hunk.old_start,
+            old_lines: hunk.old_lines,
+            new_start: hunk.new_start,
+            new_lines: hunk.new_lines,
+            old_text: hunk.old_text,
+        }
+    }
+}
+
+/// A Monaco action bound to a shortcut in settings notation (`"Ctrl+Shift+K"`,
+/// `"Cmd+/"`). See [`crate::keybindings::EDITOR_ACTIONS`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EditorKeybinding {
+    pub command: String,
+    pub key: String,
+}
+
+/// A merge-conflict region (see [`impulse_core::conflict::ConflictRegion`]).
+/// Line numbers are 1-based and point at the marker lines.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConflictRegionMarker {
+    /// The `<<<<<<<` line.
+    pub start_line: u32,
+    /// The `|||||||` line of a diff3-style conflict.
+    pub base_line: Option<u32>,
+    /// The `=======` line.
+    pub separator_line: u32,
+    /// The `>>>>>>>` line.
+    pub end_line: u32,
+    pub current_label: String,
+    pub incoming_label: String,
+}
+
+impl From<impulse_core::conflict::ConflictRegion> for ConflictRegionMarker {
+    fn from(region: impulse_core::conflict::ConflictRegion) -> Self {
+        Self {
+            start_line: region.start_line + 1,
+            base_line: region.base_line.map(|line| line + 1),
+            separator_line: region.separator_line + 1,
+            end_line: region.end_line + 1,
+            current_label: region.current_label,
+            incoming_label: region.incoming_label,
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Theme
 // ---------------------------------------------------------------------------
@@ -866,6 +1303,57 @@ mod tests {
         }
     }
 
+    #[test]
+    fn editor_requests_roundtrip_and_correlate() {
+        let cmd = EditorCommand::Request {
+            request_id: 7,
+            command: Box::new(EditorCommand::GoToPosition { line: 3, column: 1 }),
+        };
+        let json = serde_json::to_value(&cmd).unwrap();
+        assert_eq!(json["type"], "Request");
+        assert_eq!(json["command"]["type"], "GoToPosition");
+        let json = serde_json::to_string(&EditorCommand::GetContent { request_id: 8 }).unwrap();
+        assert_eq!(json, r#"{"type":"GetContent","request_id":8}"#);
+        let json = serde_json::to_string(&EditorCommand::GetSelection { request_id: 9 }).unwrap();
+        assert_eq!(json, r#"{"type":"GetSelection","request_id":9}"#);
+
+        let event: EditorEvent =
+            serde_json::from_str(r#"{"type":"Response","request_id":8,"content":"x"}"#).unwrap();
+        let EditorEvent::Response {
+            request_id,
+            content,
+            error,
+        } = event
+        else {
+            panic!("Wrong variant");
+        };
+        assert_eq!(request_id, 8);
+        assert_eq!(response_result(content, error), Ok(Some("x".to_string())));
+        assert_eq!(
+            response_result(None, Some("boom".to_string())),
+            Err(RequestError::Failed("boom".to_string()))
+        );
+
+        let mut pending = PendingRequests::new(Duration::from_secs(5));
+        let start = Instant::now();
+        let first = pending.register_at("first", start);
+        let second = pending.register_at("second", start + Duration::from_secs(2));
+        assert_ne!(first, second);
+        assert!(pending.expire_at(start + Duration::from_secs(4)).is_empty());
+        assert_eq!(pending.resolve(second), Some("second"));
+        assert_eq!(pending.resolve(second), None);
+        assert_eq!(
+            pending.expire_at(start + Duration::from_secs(5)),
+            vec!["first"]
+        );
+        assert_eq!(pending.resolve(first), None);
+        assert!(pending.is_empty());
+
+        pending.register("a");
+        pending.register("b");
+        assert_eq!(pending.drain(), vec!["a", "b"]);
+    }
+
     #[test]
     fn editor_event_roundtrip_content_changed() {
         let event = EditorEvent::ContentChanged {
@@ -928,11 +1416,34 @@ mod tests {
 
     #[test]
     fn editor_event_tagged_serialization() {
-        let event = EditorEvent::Ready;
+        let event = EditorEvent::Ready {
+            protocol_version: PROTOCOL_VERSION,
+        };
         let json = serde_json::to_string(&event).unwrap();
         assert!(json.contains("\"type\":\"Ready\""));
     }
 
+    #[test]
+    fn handshake_tolerates_editors_without_a_version() {
+        let legacy: EditorEvent = serde_json::from_str(r#"{"type":"Ready"}"#).unwrap();
+        match legacy {
+            EditorEvent::Ready { protocol_version } => {
+                assert_eq!(protocol_version, 0);
+                assert!(!protocol_compatible(protocol_version));
+            }
+            _ => panic!("Wrong variant"),
+        }
+        assert!(protocol_compatible(PROTOCOL_VERSION));
+
+        let cmd = EditorCommand::Handshake {
+            protocol_version: PROTOCOL_VERSION,
+            features: vec![HostFeature::Peek, HostFeature::StageHunk],
+        };
+        let json: serde_json::Value = serde_json::to_value(&cmd).unwrap();
+        assert_eq!(json["type"], "Handshake");
+        assert_eq!(json["features"], serde_json::json!(["peek", "stage_hunk"]));
+    }
+
     #[test]
     fn editor_command_tagged_serialization() {
         let cmd = EditorCommand::SetReadOnly { read_only: true };
@@ -1155,11 +1666,20 @@ mod tests {
                     status: DiffStatus::Deleted,
                 },
             ],
+            hunks: vec![DiffHunkMarker {
+                old_start: 5,
+                old_lines: 1,
+                new_start: 5,
+                new_lines: 1,
+                old_text: vec!["old line".to_string()],
+            }],
         };
         let json = serde_json::to_string(&cmd).unwrap();
         let parsed: EditorCommand = serde_json::from_str(&json).unwrap();
         match parsed {
-            EditorCommand::ApplyDiffDecorations { decorations } => {
+            EditorCommand::ApplyDiffDecorations { decorations, hunks } => {
+                assert_eq!(hunks.len(), 1);
+                assert_eq!(hunks[0].old_text, vec!["old line"]);
                 assert_eq!(decorations.len(), 3);
                 assert_eq!(decorations[0].line, 1);
                 assert_eq!(decorations[0].status, DiffStatus::Added);
@@ -1242,6 +1762,35 @@ mod tests {
         }
     }
 
+    #[test]
+    fn editor_command_apply_diff_decorations_without_hunks() {
+        let json = r#"{"type":"ApplyDiffDecorations","decorations":[{"line":2,"status":"added"}]}"#;
+        let parsed: EditorCommand = serde_json::from_str(json).unwrap();
+        match parsed {
+            EditorCommand::ApplyDiffDecorations { decorations, hunks } => {
+                assert_eq!(decorations.len(), 1);
+                assert!(hunks.is_empty());
+            }
+            _ => panic!("Wrong variant"),
+        }
+    }
+
+    #[test]
+    fn editor_event_roundtrip_stage_hunk_requested() {
+        let json = r#"{"type":"StageHunkRequested","new_start":12,"new_lines":3}"#;
+        let parsed: EditorEvent = serde_json::from_str(json).unwrap();
+        match parsed {
+            EditorEvent::StageHunkRequested {
+                new_start,
+                new_lines,
+            } => {
+                assert_eq!(new_start, 12);
+                assert_eq!(new_lines, 3);
+            }
+            _ => panic!("Wrong variant"),
+        }
+    }
+
     #[test]
     fn editor_event_roundtrip_focus_changed() {
         let event = EditorEvent::FocusChanged { focused: true };
@@ -1288,6 +1837,31 @@ mod tests {
         }
     }
 
+    #[test]
+    fn editor_event_on_type_formatting_requested_from_json() {
+        let json = r#"{"type":"OnTypeFormattingRequested","request_id":7,"line":3,"character":1,"ch":"}","tab_size":2,"insert_spaces":true}"#;
+        match serde_json::from_str::<EditorEvent>(json).unwrap() {
+            EditorEvent::OnTypeFormattingRequested {
+                request_id,
+                line,
+                character,
+                ch,
+                tab_size,
+                insert_spaces,
+            } => {
+                assert_eq!((request_id, line, character), (7, 3, 1));
+                assert_eq!(ch, "}");
+                assert_eq!(tab_size, 2);
+                assert!(insert_spaces);
+            }
+            _ => panic!("Wrong variant"),
+        }
+        assert_eq!(
+            serde_json::to_string(&HostFeature::OnTypeFormatting).unwrap(),
+            r#""on_type_formatting""#
+        );
+    }
+
     #[test]
     fn editor_event_roundtrip_signature_help_requested() {
         let event = EditorEvent::SignatureHelpRequested {
@@ -1334,6 +1908,19 @@ mod tests {
         }
     }
 
+    #[test]
+    fn editor_event_execute_command_without_arguments() {
+        let parsed: EditorEvent =
+            serde_json::from_str(r#"{"type":"ExecuteCommand","command":"tsserver.fix"}"#).unwrap();
+        match parsed {
+            EditorEvent::ExecuteCommand { command, arguments } => {
+                assert_eq!(command, "tsserver.fix");
+                assert!(arguments.is_empty());
+            }
+            _ => panic!("Wrong variant"),
+        }
+    }
+
     #[test]
     fn editor_event_roundtrip_code_action_requested() {
         let event = EditorEvent::CodeActionRequested {
@@ -1351,6 +1938,7 @@ mod tests {
                 message: "unused variable".to_string(),
                 source: Some("rustc".to_string()),
             }],
+            only: None,
         };
         let json = serde_json::to_string(&event).unwrap();
         let parsed: EditorEvent = serde_json::from_str(&json).unwrap();
@@ -1362,6 +1950,7 @@ mod tests {
                 end_line,
                 end_column,
                 diagnostics,
+                only,
             } => {
                 assert_eq!(request_id, 53);
                 assert_eq!(start_line, 5);
@@ -1370,9 +1959,41 @@ mod tests {
                 assert_eq!(end_column, 10);
                 assert_eq!(diagnostics.len(), 1);
                 assert_eq!(diagnostics[0].message, "unused variable");
+                assert_eq!(only, None);
+            }
+            _ => panic!("Wrong variant"),
+        }
+    }
+
+    #[test]
+    fn code_action_requested_with_only_kind_and_run_action() {
+        let json = r#"{"type":"CodeActionRequested","request_id":1,"start_line":0,"start_column":0,"end_line":9,"end_column":0,"diagnostics":[],"only":"source.organizeImports"}"#;
+        match serde_json::from_str::<EditorEvent>(json).unwrap() {
+            EditorEvent::CodeActionRequested { only, .. } => {
+                assert_eq!(only.as_deref(), Some("source.organizeImports"));
             }
             _ => panic!("Wrong variant"),
         }
+        let cmd = EditorCommand::RunAction {
+            id: "editor.action.fixAll".to_string(),
+        };
+        assert_eq!(
+            serde_json::to_string(&cmd).unwrap(),
+            r#"{"type":"RunAction","id":"editor.action.fixAll"}"#
+        );
+    }
+
+    #[test]
+    fn set_indentation_serializes() {
+        let cmd = EditorCommand::SetIndentation {
+            tab_size: 2,
+            insert_spaces: true,
+            convert: true,
+        };
+        assert_eq!(
+            serde_json::to_string(&cmd).unwrap(),
+            r#"{"type":"SetIndentation","tab_size":2,"insert_spaces":true,"convert":true}"#
+        );
     }
 
     #[test]
@@ -1575,6 +2196,11 @@ mod tests {
                     text: "".to_string(),
                 }],
                 is_preferred: true,
+                command: Some(MonacoCommand {
+                    title: "Organize imports".to_string(),
+                    command: "rust-analyzer.organizeImports".to_string(),
+                    arguments: vec![serde_json::json!({"uri": "file:///tmp/test.rs"})],
+                }),
             }],
         };
         let json = serde_json::to_string(&cmd).unwrap();
@@ -1592,6 +2218,9 @@ mod tests {
                 assert_eq!(actions[0].edits.len(), 1);
                 assert_eq!(actions[0].edits[0].uri, "file:///tmp/test.rs");
                 assert_eq!(actions[0].edits[0].text, "");
+                let command = actions[0].command.as_ref().unwrap();
+                assert_eq!(command.command, "rust-analyzer.organizeImports");
+                assert_eq!(command.arguments.len(), 1);
             }
             _ => panic!("Wrong variant"),
         }
@@ -1681,13 +2310,15 @@ mod tests {
                 removed: 3,
                 is_binary: false,
             }],
+            read_only: false,
         };
         let json = serde_json::to_string(&cmd).unwrap();
         assert!(json.contains("\"type\":\"Render\""));
         assert!(json.contains("\"status\":\"M\""));
         let parsed: ReviewCommand = serde_json::from_str(&json).unwrap();
         match parsed {
-            ReviewCommand::Render { files } => {
+            ReviewCommand::Render { files, read_only } => {
+                assert!(!read_only);
                 assert_eq!(files.len(), 1);
                 assert_eq!(files[0].path, "src/main.rs");
                 assert_eq!(files[0].status, "M");
@@ -1775,4 +2406,223 @@ mod tests {
             _ => panic!("Wrong variant"),
         }
     }
+
+    #[test]
+    fn editor_event_roundtrip_peek_requested() {
+        let json =
+            r#"{"type":"PeekRequested","request_id":8,"kind":"references","line":4,"character":2}"#;
+        let parsed: EditorEvent = serde_json::from_str(json).unwrap();
+        match parsed {
+            EditorEvent::PeekRequested {
+                request_id,
+                kind,
+                line,
+                character,
+            } => {
+                assert_eq!(request_id, 8);
+                assert_eq!(kind, PeekKind::References);
+                assert_eq!(line, 4);
+                assert_eq!(character, 2);
+            }
+            _ => panic!("Wrong variant"),
+        }
+    }
+
+    #[test]
+    fn editor_command_roundtrip_show_peek() {
+        let cmd = EditorCommand::ShowPeek {
+            request_id: 12,
+            kind: PeekKind::Definition,
+            locations: vec![MonacoPeekLocation {
+                uri: "file:///tmp/lib.rs".to_string(),
+                range: MonacoRange {
+                    start_line: 9,
+                    start_column: 4,
+                    end_line: 9,
+                    end_column: 7,
+                },
+                language: "rust".to_string(),
+                preview_start_line: 7,
+                preview_lines: vec!["a".into(), "b".into(), "fn foo() {}".into()],
+            }],
+        };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert!(json.contains(r#""kind":"definition""#));
+        let parsed: EditorCommand = serde_json::from_str(&json).unwrap();
+        match parsed {
+            EditorCommand::ShowPeek {
+                request_id,
+                kind,
+                locations,
+            } => {
+                assert_eq!(request_id, 12);
+                assert_eq!(kind, PeekKind::Definition);
+                assert_eq!(locations.len(), 1);
+                assert_eq!(locations[0].preview_start_line, 7);
+                assert_eq!(locations[0].preview_lines[2], "fn foo() {}");
+            }
+            _ => panic!("Wrong variant"),
+        }
+    }
+
+    #[test]
+    fn editor_command_roundtrip_apply_edits() {
+        let cmd = EditorCommand::ApplyEdits {
+            edits: vec![MonacoTextEdit {
+                range: MonacoRange {
+                    start_line: 3,
+                    start_column: 8,
+                    end_line: 3,
+                    end_column: 11,
+                },
+                text: "renamed".to_string(),
+            }],
+        };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert!(json.contains(r#""type":"ApplyEdits""#));
+        let parsed: EditorCommand = serde_json::from_str(&json).unwrap();
+        match parsed {
+            EditorCommand::ApplyEdits { edits } => {
+                assert_eq!(edits.len(), 1);
+                assert_eq!(edits[0].range.start_column, 8);
+                assert_eq!(edits[0].text, "renamed");
+            }
+            _ => panic!("Wrong variant"),
+        }
+    }
+
+    #[test]
+    fn editor_command_roundtrip_replace_content() {
+        let cmd = EditorCommand::ReplaceContent {
+            content: "fn main() {}\n".to_string(),
+        };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert!(json.contains(r#""type":"ReplaceContent""#));
+        let parsed: EditorCommand = serde_json::from_str(&json).unwrap();
+        match parsed {
+            EditorCommand::ReplaceContent { content } => assert_eq!(content, "fn main() {}\n"),
+            _ => panic!("Wrong variant"),
+        }
+    }
+
+    #[test]
+    fn editor_command_roundtrip_set_language() {
+        let cmd = EditorCommand::SetLanguage {
+            language: "ini".to_string(),
+        };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert_eq!(json, r#"{"type":"SetLanguage","language":"ini"}"#);
+        let parsed: EditorCommand = serde_json::from_str(&json).unwrap();
+        match parsed {
+            EditorCommand::SetLanguage { language } => assert_eq!(language, "ini"),
+            _ => panic!("Wrong variant"),
+        }
+    }
+
+    #[test]
+    fn editor_command_roundtrip_set_syntax_tokens() {
+        let cmd = EditorCommand::SetSyntaxTokens {
+            tokens: vec![SyntaxToken {
+                line: 2,
+                start_column: 4,
+                end_column: 9,
+                scope: "keyword".to_string(),
+            }],
+        };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert!(json.contains(r#""type":"SetSyntaxTokens""#));
+        let parsed: EditorCommand = serde_json::from_str(&json).unwrap();
+        match parsed {
+            EditorCommand::SetSyntaxTokens { tokens } => {
+                assert_eq!(tokens.len(), 1);
+                assert_eq!(tokens[0].end_column, 9);
+                assert_eq!(tokens[0].scope, "keyword");
+            }
+            _ => panic!("Wrong variant"),
+        }
+    }
+
+    #[test]
+    fn editor_command_roundtrip_set_blame_gutter() {
+        let cmd = EditorCommand::SetBlameGutter {
+            lines: vec![BlameGutterLine {
+                line: 1,
+                label: "Alice, 2 days ago".to_string(),
+                heat: 7,
+            }],
+        };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert!(json.contains(r#""type":"SetBlameGutter""#));
+        let parsed: EditorCommand = serde_json::from_str(&json).unwrap();
+        match parsed {
+            EditorCommand::SetBlameGutter { lines } => {
+                assert_eq!(lines.len(), 1);
+                assert_eq!(lines[0].label, "Alice, 2 days ago");
+                assert_eq!(lines[0].heat, 7);
+            }
+            _ => panic!("Wrong variant"),
+        }
+    }
+
+    #[test]
+    fn conflict_region_marker_is_one_based() {
+        let content = "<<<<<<< HEAD\na\n=======\nb\n>>>>>>> topic\n";
+        let regions: Vec<ConflictRegionMarker> =
+            impulse_core::conflict::find_conflict_regions(content)
+                .into_iter()
+                .map(ConflictRegionMarker::from)
+                .collect();
+        let cmd = EditorCommand::SetConflictRegions { regions };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert!(json.contains(r#""type":"SetConflictRegions""#));
+        match serde_json::from_str::<EditorCommand>(&json).unwrap() {
+            EditorCommand::SetConflictRegions { regions } => {
+                assert_eq!(regions.len(), 1);
+                assert_eq!(regions[0].start_line, 1);
+                assert_eq!(regions[0].base_line, None);
+                assert_eq!(regions[0].separator_line, 3);
+                assert_eq!(regions[0].end_line, 5);
+                assert_eq!(regions[0].incoming_label, "topic");
+            }
+            _ => panic!("Wrong variant"),
+        }
+
+        let event: EditorEvent = serde_json::from_str(r#"{"type":"OpenMergeView"}"#).unwrap();
+        assert!(matches!(event, EditorEvent::OpenMergeView));
+    }
+
+    #[test]
+    fn editor_command_set_custom_css_roundtrip() {
+        let cmd = EditorCommand::SetCustomCss {
+            css: ".monaco-editor { padding: 4px; }".to_string(),
+        };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert!(json.contains(r#""type":"SetCustomCss""#));
+        match serde_json::from_str::<EditorCommand>(&json).unwrap() {
+            EditorCommand::SetCustomCss { css } => {
+                assert_eq!(css, ".monaco-editor { padding: 4px; }");
+            }
+            _ => panic!("Wrong variant"),
+        }
+    }
+
+    #[test]
+    fn editor_command_set_keybindings_roundtrip() {
+        let cmd = EditorCommand::SetKeybindings {
+            bindings: vec![EditorKeybinding {
+                command: "editor.action.commentLine".to_string(),
+                key: "Ctrl+Shift+7".to_string(),
+            }],
+        };
+        let json = serde_json::to_string(&cmd).unwrap();
+        assert!(json.contains(r#""type":"SetKeybindings""#));
+        assert!(json.contains(r#""command":"editor.action.commentLine""#));
+        match serde_json::from_str::<EditorCommand>(&json).unwrap() {
+            EditorCommand::SetKeybindings { bindings } => {
+                assert_eq!(bindings.len(), 1);
+                assert_eq!(bindings[0].key, "Ctrl+Shift+7");
+            }
+            _ => panic!("Wrong variant"),
+        }
+    }
 }
@@ -50,6 +50,22 @@ function normalizeFontFamily(raw) {
   return parts.join(", ");
 }
 
+// ---------------------------------------------------------------------------
+// Protocol handshake
+// ---------------------------------------------------------------------------
+// Must match impulse_editor::protocol::PROTOCOL_VERSION.
+const PROTOCOL_VERSION = 9;
+// Optional host features from the Handshake command. Null until the host
+// replies; hosts that predate the handshake are assumed to support all of
+// them, as before.
+let hostFeatures = null;
+// Context keys that hide actions the host cannot handle.
+let hostFeatureKeys = {};
+
+function hostSupports(feature) {
+  return hostFeatures === null || hostFeatures.has(feature);
+}
+
 // ---------------------------------------------------------------------------
 // State
 // ---------------------------------------------------------------------------
@@ -67,8 +83,23 @@ const pendingCodeActions = new Map();
 const pendingRename = new Map();
 const pendingPrepareRename = new Map();
 let contentVersion = 0;
+// Indentation the host chose for this file. Applied to every new model so
+// Monaco's own guess doesn't replace it when the file is (re)opened.
+let hostIndentation = null;
 let currentDiffDecorations = [];
+// Gutter hunks paired with the decoration that tracks each one's lines.
+let currentDiffHunks = [];
+let currentSyntaxDecorations = [];
+let currentBlameDecorations = [];
+// Merge-conflict regions paired with the decoration tracking each one.
+let currentConflicts = [];
+let currentConflictDecorations = [];
+let conflictLensEmitter = null;
 let pendingCommands = [];
+// Inline peek widget (definition / references)
+let pendingPeek = null;
+let peekWidget = null;
+let peekVisibleKey = null;
 
 // ---------------------------------------------------------------------------
 // Monaco initialization
@@ -211,7 +242,7 @@ require(["vs/editor/editor.main"], function () {
     tabSize: 4,
     insertSpaces: true,
     formatOnPaste: false,
-    formatOnType: false,
+    formatOnType: true,
     // Auto-rename matching HTML/JSX tags
     linkedEditing: true,
     // Hide line highlight when editor is not focused
@@ -268,6 +299,9 @@ require(["vs/editor/editor.main"], function () {
     sendToHost({ type: "SaveRequested" });
   });
 
+  // --- Languages highlighted by the host (tree-sitter fallback) ---
+  registerFallbackLanguages();
+
   // --- Register LSP Completion Provider ---
   monaco.languages.registerCompletionItemProvider("*", {
     triggerCharacters: [".", ":", "<", '"', "/", "@", "\\", " "],
@@ -357,6 +391,35 @@ require(["vs/editor/editor.main"], function () {
     },
   });
 
+  // --- Register LSP On-Type Formatting Provider ---
+  // Monaco needs the trigger characters up front; this covers the ones
+  // common servers use and the host drops characters its server ignores.
+  monaco.languages.registerOnTypeFormattingEditProvider("*", {
+    autoFormatTriggerCharacters: ["}", ";", ")", "]", ">", ":", "\n"],
+    provideOnTypeFormattingEdits: function (model, position, ch, options) {
+      if (!hostSupports("on_type_formatting")) return [];
+      var id = ++requestSeq;
+      sendToHost({
+        type: "OnTypeFormattingRequested",
+        request_id: id,
+        line: position.lineNumber - 1,
+        character: position.column - 1,
+        ch: ch,
+        tab_size: options.tabSize,
+        insert_spaces: options.insertSpaces,
+      });
+      return new Promise(function (resolve) {
+        pendingFormatting.set(id, resolve);
+        setTimeout(function () {
+          if (pendingFormatting.has(id)) {
+            pendingFormatting.delete(id);
+            resolve([]);
+          }
+        }, 2000);
+      });
+    },
+  });
+
   // --- Register LSP Signature Help Provider ---
   monaco.languages.registerSignatureHelpProvider("*", {
     signatureHelpTriggerCharacters: ["(", ","],
@@ -403,7 +466,19 @@ require(["vs/editor/editor.main"], function () {
   });
 
   // --- Register LSP Code Action Provider ---
-  monaco.languages.registerCodeActionProvider("*", {
+  // Actions that carry a server command run it through the host
+  // (`workspace/executeCommand`) after any inline edit is applied.
+  monaco.editor.registerCommand(
+    "impulse.executeLspCommand",
+    function (_accessor, command, args) {
+      sendToHost({
+        type: "ExecuteCommand",
+        command: command,
+        arguments: args || [],
+      });
+    },
+  );
+  var codeActionProvider = {
     provideCodeActions: function (model, range, context) {
       var id = ++requestSeq;
       var diagnostics = (context.markers || []).map(function (m) {
@@ -425,6 +500,7 @@ require(["vs/editor/editor.main"], function () {
         end_line: range.endLineNumber - 1,
         end_column: range.endColumn - 1,
         diagnostics: diagnostics,
+        only: context.only || null,
       });
       return new Promise(function (resolve) {
         pendingCodeActions.set(id, resolve);
@@ -436,7 +512,34 @@ require(["vs/editor/editor.main"], function () {
         }, 10000);
       });
     },
+  };
+  // Listing the source kinds enables Monaco's Organize Imports, Fix All and
+  // Source Action... commands, which request them via `context.only`.
+  monaco.languages.registerCodeActionProvider("*", codeActionProvider, {
+    providedCodeActionKinds: [
+      "quickfix",
+      "refactor",
+      "source",
+      "source.organizeImports",
+      "source.fixAll",
+    ],
   });
+  // Organize Imports keeps Monaco's Shift+Alt+O; the other two have no
+  // default binding.
+  monaco.editor.addKeybindingRules([
+    {
+      keybinding:
+        monaco.KeyMod.Shift | monaco.KeyMod.Alt | monaco.KeyCode.Period,
+      command: "editor.action.fixAll",
+      when: "textInputFocus",
+    },
+    {
+      keybinding:
+        monaco.KeyMod.CtrlCmd | monaco.KeyMod.Shift | monaco.KeyCode.Period,
+      command: "editor.action.sourceAction",
+      when: "textInputFocus",
+    },
+  ]);
 
   // --- Register LSP Rename Provider ---
   monaco.languages.registerRenameProvider("*", {
@@ -479,6 +582,125 @@ require(["vs/editor/editor.main"], function () {
     },
   });
 
+  // --- Peek Definition / Peek References ---
+  // Results are shown in an inline widget below the cursor line instead of
+  // navigating away. The host answers PeekRequested with ShowPeek, including
+  // preview snippets for files that aren't open in this editor.
+  peekVisibleKey = editor.createContextKey("impulsePeekVisible", false);
+  hostFeatureKeys = {
+    peek: editor.createContextKey("impulseHostPeek", true),
+    stage_hunk: editor.createContextKey("impulseHostStageHunk", true),
+  };
+  editor.addAction({
+    id: "impulse.peekDefinition",
+    label: "Peek Definition",
+    keybindings: [monaco.KeyMod.Alt | monaco.KeyCode.F12],
+    precondition: "impulseHostPeek",
+    contextMenuGroupId: "navigation",
+    contextMenuOrder: 1.2,
+    run: function () {
+      requestPeek("definition");
+    },
+  });
+  editor.addAction({
+    id: "impulse.peekReferences",
+    label: "Peek References",
+    keybindings: [monaco.KeyMod.Shift | monaco.KeyCode.F12],
+    precondition: "impulseHostPeek",
+    contextMenuGroupId: "navigation",
+    contextMenuOrder: 1.3,
+    run: function () {
+      requestPeek("references");
+    },
+  });
+  editor.addCommand(monaco.KeyCode.Escape, closePeek, "impulsePeekVisible");
+  editor.addCommand(
+    monaco.KeyCode.F4,
+    function () {
+      movePeekSelection(1);
+    },
+    "impulsePeekVisible",
+  );
+  editor.addCommand(
+    monaco.KeyMod.Shift | monaco.KeyCode.F4,
+    function () {
+      movePeekSelection(-1);
+    },
+    "impulsePeekVisible",
+  );
+  editor.onDidLayoutChange(layoutPeek);
+
+  // --- Git gutter hunks ---
+  // Navigate between changed regions and revert or stage the one under the
+  // cursor. The line-number hover offers the same actions as links.
+  editor.addAction({
+    id: "impulse.nextChange",
+    label: "Go to Next Change",
+    keybindings: [monaco.KeyMod.Alt | monaco.KeyCode.F5],
+    run: function () {
+      goToHunk(1);
+    },
+  });
+  editor.addAction({
+    id: "impulse.previousChange",
+    label: "Go to Previous Change",
+    keybindings: [monaco.KeyMod.Shift | monaco.KeyMod.Alt | monaco.KeyCode.F5],
+    run: function () {
+      goToHunk(-1);
+    },
+  });
+  editor.addAction({
+    id: "impulse.revertChange",
+    label: "Revert Change",
+    contextMenuGroupId: "9_git",
+    contextMenuOrder: 1,
+    run: function () {
+      revertHunk(hunkAtCursor());
+    },
+  });
+  editor.addAction({
+    id: "impulse.stageChange",
+    label: "Stage Change",
+    precondition: "impulseHostStageHunk",
+    contextMenuGroupId: "9_git",
+    contextMenuOrder: 2,
+    run: function () {
+      stageHunk(hunkAtCursor());
+    },
+  });
+  monaco.editor.registerCommand(
+    "impulse.revertHunk",
+    function (_accessor, index) {
+      revertHunk(currentDiffHunks[index]);
+    },
+  );
+  monaco.editor.registerCommand(
+    "impulse.stageHunk",
+    function (_accessor, index) {
+      stageHunk(currentDiffHunks[index]);
+    },
+  );
+
+  // --- Merge conflicts ---
+  // Conflict regions found by the host get code lenses to keep one side or
+  // both, or to compare the sides in the host's three-way view.
+  monaco.editor.registerCommand(
+    "impulse.acceptConflict",
+    function (_accessor, index, choice) {
+      resolveConflict(currentConflicts[index], choice);
+    },
+  );
+  monaco.editor.registerCommand("impulse.compareConflict", function () {
+    sendToHost({ type: "OpenMergeView" });
+  });
+  conflictLensEmitter = new monaco.Emitter();
+  monaco.languages.registerCodeLensProvider("*", {
+    onDidChange: conflictLensEmitter.event,
+    provideCodeLenses: function (model) {
+      return { lenses: conflictLenses(model), dispose: function () {} };
+    },
+  });
+
   // --- Cross-file go-to-definition ---
   // Monaco calls this when Cmd+click resolves to a definition in a different
   // file URI. We forward the request to the host to open the target file.
@@ -516,7 +738,7 @@ require(["vs/editor/editor.main"], function () {
   });
 
   // Signal ready
-  sendToHost({ type: "Ready" });
+  sendToHost({ type: "Ready", protocol_version: PROTOCOL_VERSION });
 });
 
 // ---------------------------------------------------------------------------
@@ -524,69 +746,172 @@ require(["vs/editor/editor.main"], function () {
 // ---------------------------------------------------------------------------
 function handleCommand(cmd) {
   try {
-    switch (cmd.type) {
-      case "OpenFile":
-        handleOpenFile(cmd);
-        break;
-      case "SetTheme":
-        handleSetTheme(cmd);
-        break;
-      case "UpdateSettings":
-        handleUpdateSettings(cmd);
-        break;
-      case "ApplyDiagnostics":
-        handleApplyDiagnostics(cmd);
-        break;
-      case "ResolveCompletions":
-        handleResolveCompletions(cmd);
-        break;
-      case "ResolveHover":
-        handleResolveHover(cmd);
-        break;
-      case "ResolveDefinition":
-        handleResolveDefinition(cmd);
-        break;
-      case "GoToPosition":
-        handleGoToPosition(cmd);
-        break;
-      case "SetReadOnly":
-        editor.updateOptions({ readOnly: cmd.read_only });
-        break;
-      case "ApplyDiffDecorations":
-        handleApplyDiffDecorations(cmd);
-        break;
-      case "ResolveFormatting":
-        handleResolveFormatting(cmd);
-        break;
-      case "ResolveSignatureHelp":
-        handleResolveSignatureHelp(cmd);
-        break;
-      case "ResolveReferences":
-        handleResolveReferences(cmd);
-        break;
-      case "ResolveCodeActions":
-        handleResolveCodeActions(cmd);
-        break;
-      case "ResolveRename":
-        handleResolveRename(cmd);
-        break;
-      case "ResolvePrepareRename":
-        handleResolvePrepareRename(cmd);
-        break;
-      default:
-        console.warn("Unknown command:", cmd.type);
-    }
+    runCommand(cmd);
   } catch (e) {
     console.error("Command handler error for", cmd.type, ":", e);
   }
 }
 
+// Apply one command, throwing if it fails or is unknown.
+function runCommand(cmd) {
+  switch (cmd.type) {
+    case "Handshake":
+      handleHandshake(cmd);
+      break;
+    case "OpenFile":
+      handleOpenFile(cmd);
+      break;
+    case "SetTheme":
+      handleSetTheme(cmd);
+      break;
+    case "UpdateSettings":
+      handleUpdateSettings(cmd);
+      break;
+    case "ApplyDiagnostics":
+      handleApplyDiagnostics(cmd);
+      break;
+    case "ResolveCompletions":
+      handleResolveCompletions(cmd);
+      break;
+    case "ResolveHover":
+      handleResolveHover(cmd);
+      break;
+    case "ResolveDefinition":
+      handleResolveDefinition(cmd);
+      break;
+    case "GoToPosition":
+      handleGoToPosition(cmd);
+      break;
+    case "SetReadOnly":
+      editor.updateOptions({ readOnly: cmd.read_only });
+      break;
+    case "ApplyDiffDecorations":
+      handleApplyDiffDecorations(cmd);
+      break;
+    case "ResolveFormatting":
+      handleResolveFormatting(cmd);
+      break;
+    case "ResolveSignatureHelp":
+      handleResolveSignatureHelp(cmd);
+      break;
+    case "ResolveReferences":
+      handleResolveReferences(cmd);
+      break;
+    case "ResolveCodeActions":
+      handleResolveCodeActions(cmd);
+      break;
+    case "ResolveRename":
+      handleResolveRename(cmd);
+      break;
+    case "ResolvePrepareRename":
+      handleResolvePrepareRename(cmd);
+      break;
+    case "ShowPeek":
+      handleShowPeek(cmd);
+      break;
+    case "RunAction":
+      handleRunAction(cmd);
+      break;
+    case "ApplyEdits":
+      handleApplyEdits(cmd);
+      break;
+    case "ReplaceContent":
+      handleReplaceContent(cmd);
+      break;
+    case "SetLanguage":
+      handleSetLanguage(cmd);
+      break;
+    case "SetSyntaxTokens":
+      handleSetSyntaxTokens(cmd);
+      break;
+    case "SetBlameGutter":
+      handleSetBlameGutter(cmd);
+      break;
+    case "SetConflictRegions":
+      handleSetConflictRegions(cmd);
+      break;
+    case "SetCustomCss":
+      handleSetCustomCss(cmd);
+      break;
+    case "SetKeybindings":
+      handleSetKeybindings(cmd);
+      break;
+    case "SetIndentation":
+      handleSetIndentation(cmd);
+      break;
+    case "GetContent":
+      sendToHost({
+        type: "Response",
+        request_id: cmd.request_id,
+        content: editor.getValue(),
+      });
+      break;
+    case "GetSelection": {
+      const model = editor.getModel();
+      const selection = editor.getSelection();
+      sendToHost({
+        type: "Response",
+        request_id: cmd.request_id,
+        content: model && selection ? model.getValueInRange(selection) : "",
+      });
+      break;
+    }
+    case "Request":
+      handleRequest(cmd);
+      break;
+    default:
+      throw new Error("Unknown command: " + cmd.type);
+  }
+}
+
 // Expose handleCommand globally for Qt WebEngine (QML calls window.handleCommand directly)
 window.handleCommand = handleCommand;
 
+// A command the host is waiting on: acknowledge it once applied, so the
+// host can time out on an editor that stopped responding.
+function handleRequest(cmd) {
+  try {
+    runCommand(cmd.command);
+    sendToHost({ type: "Response", request_id: cmd.request_id });
+  } catch (e) {
+    sendToHost({
+      type: "Response",
+      request_id: cmd.request_id,
+      error: String((e && e.message) || e),
+    });
+  }
+}
+
+function handleHandshake(cmd) {
+  if (cmd.protocol_version !== PROTOCOL_VERSION) {
+    console.warn(
+      "Editor protocol " +
+        PROTOCOL_VERSION +
+        " talking to host protocol " +
+        cmd.protocol_version +
+        "; unknown messages will be ignored",
+    );
+  }
+  hostFeatures = new Set(cmd.features || []);
+  Object.keys(hostFeatureKeys).forEach(function (feature) {
+    hostFeatureKeys[feature].set(hostFeatures.has(feature));
+  });
+  // Hunk hovers and conflict lenses offer host actions too.
+  if (conflictLensEmitter) conflictLensEmitter.fire();
+}
+
 // ---------------------------------------------------------------------------
 // Command handler: called from Rust via evaluate_javascript
 // ---------------------------------------------------------------------------
+function receiveCommand(cmd) {
+  if (!editor) {
+    pendingCommands.push(cmd);
+    return;
+  }
+
+  handleCommand(cmd);
+}
+
 window.impulseReceiveCommand = function (jsonString) {
   let cmd;
   try {
@@ -595,13 +920,20 @@ window.impulseReceiveCommand = function (jsonString) {
     console.error("Failed to parse command:", e);
     return;
   }
+  receiveCommand(cmd);
+};
 
-  if (!editor) {
-    pendingCommands.push(cmd);
+// A per-frame batch from the host (impulse_editor::batch): a JSON array of
+// commands, applied in order.
+window.impulseReceiveCommands = function (jsonString) {
+  let cmds;
+  try {
+    cmds = JSON.parse(jsonString);
+  } catch (e) {
+    console.error("Failed to parse command batch:", e);
     return;
   }
-
-  handleCommand(cmd);
+  cmds.forEach(receiveCommand);
 };
 
 // ---------------------------------------------------------------------------
@@ -614,6 +946,20 @@ function handleOpenFile(cmd) {
 
   // Clear diff decorations from previous file
   currentDiffDecorations = editor.deltaDecorations(currentDiffDecorations, []);
+  currentDiffHunks = [];
+  currentSyntaxDecorations = editor.deltaDecorations(
+    currentSyntaxDecorations,
+    [],
+  );
+  currentBlameDecorations = editor.deltaDecorations(
+    currentBlameDecorations,
+    [],
+  );
+  handleSetConflictRegions({ regions: [] });
+
+  // A peek belongs to the previous file's positions
+  closePeek();
+  pendingPeek = null;
 
   // Dispose old model if it exists
   if (currentModel) {
@@ -633,6 +979,7 @@ function handleOpenFile(cmd) {
 
   const uri = monaco.Uri.file(currentFilePath);
   currentModel = monaco.editor.createModel(cmd.content || "", language, uri);
+  if (hostIndentation) currentModel.updateOptions(hostIndentation);
   editor.setModel(currentModel);
   contentVersion = 0;
 
@@ -658,6 +1005,152 @@ function handleSetTheme(cmd) {
   });
   monaco.editor.setTheme("impulse-theme");
   if (theme.colors) updateDiffGutterColors(theme.colors);
+  updateSyntaxTokenColors(theme.rules || []);
+}
+
+// The user stylesheet goes in its own <style> element at the end of <head>
+// so it overrides the page's styles and can be replaced on reload.
+function handleSetCustomCss(cmd) {
+  let style = document.getElementById("impulse-custom-css");
+  if (!cmd.css) {
+    if (style) style.remove();
+    return;
+  }
+  if (!style) {
+    style = document.createElement("style");
+    style.id = "impulse-custom-css";
+  }
+  style.textContent = cmd.css;
+  document.head.appendChild(style);
+}
+
+// --- User keybindings for Monaco actions ---
+// Each rebinding also registers a "-command" rule with no key, which drops
+// Monaco's default shortcuts for that command. Replacing the set disposes
+// the previous rules, restoring defaults for actions no longer overridden.
+
+const IS_MAC = /Mac/.test(navigator.platform);
+let userKeybindingRules = null;
+
+const KEY_NAME_CODES = {
+  "/": "Slash",
+  slash: "Slash",
+  ",": "Comma",
+  comma: "Comma",
+  ".": "Period",
+  period: "Period",
+  ";": "Semicolon",
+  semicolon: "Semicolon",
+  "'": "Quote",
+  apostrophe: "Quote",
+  "`": "Backquote",
+  grave: "Backquote",
+  "[": "BracketLeft",
+  bracketleft: "BracketLeft",
+  "]": "BracketRight",
+  bracketright: "BracketRight",
+  "\\": "Backslash",
+  backslash: "Backslash",
+  "-": "Minus",
+  minus: "Minus",
+  "=": "Equal",
+  equal: "Equal",
+  up: "UpArrow",
+  down: "DownArrow",
+  left: "LeftArrow",
+  right: "RightArrow",
+  tab: "Tab",
+  return: "Enter",
+  enter: "Enter",
+  space: "Space",
+  escape: "Escape",
+  esc: "Escape",
+  backspace: "Backspace",
+  delete: "Delete",
+  home: "Home",
+  end: "End",
+  page_up: "PageUp",
+  pageup: "PageUp",
+  page_down: "PageDown",
+  pagedown: "PageDown",
+};
+
+// Parse "Ctrl+Shift+K" / "Cmd+/" (or a two-step chord like "Ctrl+K Ctrl+C")
+// into a Monaco keybinding number, or 0 when the shortcut can't be expressed.
+function parseKeybinding(shortcut) {
+  const steps = String(shortcut).trim().split(/\s+/);
+  if (steps.length === 1) return parseKeyStep(steps[0]);
+  if (steps.length !== 2) return 0;
+  const first = parseKeyStep(steps[0]);
+  const second = parseKeyStep(steps[1]);
+  if (!first || !second) return 0;
+  return monaco.KeyMod.chord(first, second);
+}
+
+function parseKeyStep(shortcut) {
+  const parts = shortcut.split("+");
+  let keyName = parts.pop();
+  // "Ctrl++" splits into a trailing empty part.
+  if (keyName === "" && parts.length > 0 && parts[parts.length - 1] === "") {
+    parts.pop();
+    keyName = "+";
+  }
+  let mods = 0;
+  for (const part of parts) {
+    switch (part.trim().toLowerCase()) {
+      case "cmd":
+      case "command":
+        mods |= monaco.KeyMod.CtrlCmd;
+        break;
+      case "ctrl":
+      case "control":
+        mods |= IS_MAC ? monaco.KeyMod.WinCtrl : monaco.KeyMod.CtrlCmd;
+        break;
+      case "shift":
+        mods |= monaco.KeyMod.Shift;
+        break;
+      case "alt":
+      case "option":
+      case "opt":
+        mods |= monaco.KeyMod.Alt;
+        break;
+      case "super":
+      case "meta":
+        mods |= monaco.KeyMod.WinCtrl;
+        break;
+      default:
+        return 0;
+    }
+  }
+  const key = keyName.trim();
+  const lower = key.toLowerCase();
+  let code = null;
+  if (/^[a-z]$/.test(lower)) code = "Key" + lower.toUpperCase();
+  else if (/^[0-9]$/.test(lower)) code = "Digit" + lower;
+  else if (/^f([1-9]|1[0-9])$/.test(lower)) code = lower.toUpperCase();
+  else code = KEY_NAME_CODES[lower] || KEY_NAME_CODES[key] || null;
+  if (!code || monaco.KeyCode[code] === undefined) return 0;
+  return mods | monaco.KeyCode[code];
+}
+
+function handleSetKeybindings(cmd) {
+  if (userKeybindingRules) {
+    userKeybindingRules.dispose();
+    userKeybindingRules = null;
+  }
+  const rules = [];
+  for (const binding of cmd.bindings || []) {
+    const keybinding = parseKeybinding(binding.key);
+    if (!keybinding) {
+      console.warn("Unsupported keybinding:", binding.key);
+      continue;
+    }
+    rules.push({ keybinding: 0, command: "-" + binding.command });
+    rules.push({ keybinding: keybinding, command: binding.command });
+  }
+  if (rules.length > 0) {
+    userKeybindingRules = monaco.editor.addKeybindingRules(rules);
+  }
 }
 
 function handleUpdateSettings(cmd) {
@@ -707,6 +1200,14 @@ function handleUpdateSettings(cmd) {
     update.wordBasedSuggestions = opts.word_based_suggestions;
   editor.updateOptions(update);
 
+  if (opts.tab_size != null && opts.insert_spaces != null) {
+    hostIndentation = {
+      tabSize: opts.tab_size,
+      indentSize: opts.tab_size,
+      insertSpaces: opts.insert_spaces,
+    };
+  }
+
   // Also update model options if tab settings changed
   if (currentModel && (opts.tab_size != null || opts.insert_spaces != null)) {
     currentModel.updateOptions({
@@ -850,10 +1351,479 @@ function handleApplyDiffDecorations(cmd) {
       },
     };
   });
+  // One extra decoration per hunk tracks its lines through edits and carries
+  // the old-text hover.
+  const hunks = cmd.hunks || [];
+  hunks.forEach(function (h, index) {
+    decorations.push({
+      range: hunkRange(h),
+      options: {
+        isWholeLine: true,
+        lineNumberHoverMessage: hunkHoverMessage(h, index),
+        stickiness:
+          monaco.editor.TrackedRangeStickiness.NeverGrowsWhenTypingAtEdges,
+      },
+    });
+  });
   currentDiffDecorations = editor.deltaDecorations(
     currentDiffDecorations,
     decorations,
   );
+  const hunkIds = currentDiffDecorations.slice(
+    currentDiffDecorations.length - hunks.length,
+  );
+  currentDiffHunks = hunks.map(function (h, i) {
+    return { hunk: h, decorationId: hunkIds[i] };
+  });
+}
+
+// Lines a hunk occupies in the editor. Pure deletions have no lines of their
+// own and are anchored to the line they follow.
+function hunkRange(h) {
+  if (h.new_lines > 0) {
+    return new monaco.Range(h.new_start, 1, h.new_start + h.new_lines - 1, 1);
+  }
+  const line = Math.max(h.new_start, 1);
+  return new monaco.Range(line, 1, line, 1);
+}
+
+// Hover links may only run these commands.
+var HUNK_HOVER_TRUST = {
+  enabledCommands: ["impulse.revertHunk", "impulse.stageHunk"],
+};
+
+function hunkHoverMessage(h, index) {
+  const args = encodeURIComponent(JSON.stringify([index]));
+  let actions = "[Revert](command:impulse.revertHunk?" + args + ")";
+  if (hostSupports("stage_hunk")) {
+    actions += " \u00b7 [Stage](command:impulse.stageHunk?" + args + ")";
+  }
+  if (h.old_text.length === 0) {
+    return {
+      value: "*Added lines*\n\n" + actions,
+      isTrusted: HUNK_HOVER_TRUST,
+    };
+  }
+  const oldText = h.old_text.join("\n");
+  let fence = "```";
+  while (oldText.indexOf(fence) !== -1) fence += "`";
+  const language = currentModel ? currentModel.getLanguageId() : "";
+  return {
+    value:
+      fence + language + "\n" + oldText + "\n" + fence + "\n\n" + actions,
+    isTrusted: HUNK_HOVER_TRUST,
+  };
+}
+
+function hunkCurrentRange(entry) {
+  if (!entry || !currentModel) return null;
+  return currentModel.getDecorationRange(entry.decorationId);
+}
+
+function hunkAtCursor() {
+  const position = editor.getPosition();
+  if (!position) return null;
+  for (let i = 0; i < currentDiffHunks.length; i++) {
+    const range = hunkCurrentRange(currentDiffHunks[i]);
+    if (
+      range &&
+      position.lineNumber >= range.startLineNumber &&
+      position.lineNumber <= range.endLineNumber
+    ) {
+      return currentDiffHunks[i];
+    }
+  }
+  return null;
+}
+
+// Move the cursor to the next (direction 1) or previous (-1) hunk, wrapping
+// around the ends of the file.
+function goToHunk(direction) {
+  const position = editor.getPosition();
+  if (!position) return;
+  const starts = currentDiffHunks
+    .map(function (entry) {
+      const range = hunkCurrentRange(entry);
+      return range ? range.startLineNumber : null;
+    })
+    .filter(function (line) {
+      return line !== null;
+    })
+    .sort(function (a, b) {
+      return a - b;
+    });
+  if (starts.length === 0) return;
+
+  const current = position.lineNumber;
+  let target;
+  if (direction > 0) {
+    target = starts.find(function (line) {
+      return line > current;
+    });
+    if (target === undefined) target = starts[0];
+  } else {
+    const before = starts.filter(function (line) {
+      return line < current;
+    });
+    target = before.length
+      ? before[before.length - 1]
+      : starts[starts.length - 1];
+  }
+  editor.setPosition({ lineNumber: target, column: 1 });
+  editor.revealLineInCenterIfOutsideViewport(target);
+}
+
+// Restore a hunk's HEAD text in the buffer as one undoable edit, then drop
+// its gutter markers. Remaining markers refresh on the next save.
+function revertHunk(entry) {
+  const range = hunkCurrentRange(entry);
+  if (!range) return;
+  const h = entry.hunk;
+  const model = currentModel;
+  const eol = model.getEOL();
+  const oldText = h.old_text.join(eol);
+  const start = range.startLineNumber;
+  const end = range.endLineNumber;
+  let edit;
+  if (h.new_lines === 0) {
+    if (h.new_start === 0) {
+      edit = { range: new monaco.Range(1, 1, 1, 1), text: oldText + eol };
+    } else {
+      const column = model.getLineMaxColumn(start);
+      edit = {
+        range: new monaco.Range(start, column, start, column),
+        text: eol + oldText,
+      };
+    }
+  } else if (h.old_text.length > 0) {
+    edit = {
+      range: new monaco.Range(start, 1, end, model.getLineMaxColumn(end)),
+      text: oldText,
+    };
+  } else if (end < model.getLineCount()) {
+    edit = { range: new monaco.Range(start, 1, end + 1, 1), text: "" };
+  } else if (start > 1) {
+    edit = {
+      range: new monaco.Range(
+        start - 1,
+        model.getLineMaxColumn(start - 1),
+        end,
+        model.getLineMaxColumn(end),
+      ),
+      text: "",
+    };
+  } else {
+    edit = { range: model.getFullModelRange(), text: "" };
+  }
+
+  const stale = currentDiffDecorations.filter(function (id) {
+    const r = model.getDecorationRange(id);
+    return r && r.startLineNumber <= end && r.endLineNumber >= start;
+  });
+  editor.pushUndoStop();
+  editor.executeEdits("impulse-revert-hunk", [edit]);
+  editor.pushUndoStop();
+
+  editor.deltaDecorations(stale, []);
+  currentDiffDecorations = currentDiffDecorations.filter(function (id) {
+    return stale.indexOf(id) === -1;
+  });
+  // Keep indices stable: hover links of the remaining hunks refer to them.
+  currentDiffHunks = currentDiffHunks.map(function (other) {
+    return other === entry ? null : other;
+  });
+}
+
+function stageHunk(entry) {
+  if (!entry || !hostSupports("stage_hunk")) return;
+  sendToHost({
+    type: "StageHunkRequested",
+    new_start: entry.hunk.new_start,
+    new_lines: entry.hunk.new_lines,
+  });
+}
+
+var CONFLICT_LENS_ACTIONS = [
+  { title: "Accept Current Change", choice: "current" },
+  { title: "Accept Incoming Change", choice: "incoming" },
+  { title: "Accept Both Changes", choice: "both" },
+];
+
+function conflictBlock(start, end, className) {
+  if (end < start) return null;
+  return {
+    range: new monaco.Range(start, 1, end, 1),
+    options: { isWholeLine: true, className: className },
+  };
+}
+
+// Each region gets a tracking decoration spanning its markers, followed by
+// the highlighting of its marker lines and sides.
+function handleSetConflictRegions(cmd) {
+  var regions = cmd.regions || [];
+  var decorations = [];
+  var trackerIndices = [];
+  regions.forEach(function (r) {
+    trackerIndices.push(decorations.length);
+    decorations.push({
+      range: new monaco.Range(r.start_line, 1, r.end_line, 1),
+      options: {
+        stickiness:
+          monaco.editor.TrackedRangeStickiness.NeverGrowsWhenTypingAtEdges,
+      },
+    });
+    var markers = [r.start_line, r.separator_line, r.end_line];
+    if (r.base_line) markers.push(r.base_line);
+    markers.forEach(function (line) {
+      decorations.push(conflictBlock(line, line, "impulse-conflict-marker"));
+    });
+    var currentEnd = (r.base_line || r.separator_line) - 1;
+    [
+      conflictBlock(r.start_line + 1, currentEnd, "impulse-conflict-current"),
+      r.base_line
+        ? conflictBlock(
+            r.base_line + 1,
+            r.separator_line - 1,
+            "impulse-conflict-base",
+          )
+        : null,
+      conflictBlock(
+        r.separator_line + 1,
+        r.end_line - 1,
+        "impulse-conflict-incoming",
+      ),
+    ].forEach(function (block) {
+      if (block) decorations.push(block);
+    });
+  });
+  currentConflictDecorations = editor.deltaDecorations(
+    currentConflictDecorations,
+    decorations,
+  );
+  currentConflicts = regions.map(function (r, i) {
+    var first = trackerIndices[i];
+    var last =
+      i + 1 < trackerIndices.length
+        ? trackerIndices[i + 1]
+        : decorations.length;
+    return {
+      region: r,
+      decorationId: currentConflictDecorations[first],
+      decorationIds: currentConflictDecorations.slice(first, last),
+    };
+  });
+  if (conflictLensEmitter) conflictLensEmitter.fire();
+}
+
+function conflictLenses(model) {
+  if (model !== currentModel) return [];
+  var lenses = [];
+  currentConflicts.forEach(function (entry, index) {
+    if (!entry) return;
+    var range = model.getDecorationRange(entry.decorationId);
+    if (!range) return;
+    var at = new monaco.Range(
+      range.startLineNumber,
+      1,
+      range.startLineNumber,
+      1,
+    );
+    CONFLICT_LENS_ACTIONS.forEach(function (action) {
+      lenses.push({
+        range: at,
+        command: {
+          id: "impulse.acceptConflict",
+          title: action.title,
+          arguments: [index, action.choice],
+        },
+      });
+    });
+    if (hostSupports("merge_view")) {
+      lenses.push({
+        range: at,
+        command: { id: "impulse.compareConflict", title: "Compare Changes" },
+      });
+    }
+  });
+  return lenses;
+}
+
+// Re-read a region's sides from the buffer so edits made inside it since the
+// host last reported it are kept. Returns null if its markers were removed.
+function conflictSides(model, start, end) {
+  var current = [];
+  var incoming = [];
+  var section = "current";
+  if (!model.getLineContent(start).startsWith("<<<<<<<")) return null;
+  if (!model.getLineContent(end).startsWith(">>>>>>>")) return null;
+  for (var line = start + 1; line < end; line++) {
+    var text = model.getLineContent(line);
+    if (section === "current" && text.startsWith("|||||||")) {
+      section = "base";
+    } else if (section !== "incoming" && text === "=======") {
+      section = "incoming";
+    } else if (section === "current") {
+      current.push(text);
+    } else if (section === "incoming") {
+      incoming.push(text);
+    }
+  }
+  if (section !== "incoming") return null;
+  return { current: current, incoming: incoming };
+}
+
+// Replace a conflict region with the chosen side(s) as one undoable edit.
+// Remaining regions refresh when the host re-parses the buffer.
+function resolveConflict(entry, choice) {
+  if (!entry) return;
+  var model = currentModel;
+  var range = model.getDecorationRange(entry.decorationId);
+  if (!range) return;
+  var start = range.startLineNumber;
+  var end = range.endLineNumber;
+  var sides = conflictSides(model, start, end);
+  if (!sides) return;
+  var lines =
+    choice === "current"
+      ? sides.current
+      : choice === "incoming"
+        ? sides.incoming
+        : sides.current.concat(sides.incoming);
+
+  var edit;
+  if (lines.length > 0) {
+    edit = {
+      range: new monaco.Range(start, 1, end, model.getLineMaxColumn(end)),
+      text: lines.join(model.getEOL()),
+    };
+  } else if (end < model.getLineCount()) {
+    edit = { range: new monaco.Range(start, 1, end + 1, 1), text: "" };
+  } else if (start > 1) {
+    edit = {
+      range: new monaco.Range(
+        start - 1,
+        model.getLineMaxColumn(start - 1),
+        end,
+        model.getLineMaxColumn(end),
+      ),
+      text: "",
+    };
+  } else {
+    edit = { range: model.getFullModelRange(), text: "" };
+  }
+
+  editor.deltaDecorations(entry.decorationIds, []);
+  currentConflictDecorations = currentConflictDecorations.filter(
+    function (id) {
+      return entry.decorationIds.indexOf(id) === -1;
+    },
+  );
+  // Keep indices stable: lens arguments of the remaining regions refer to them.
+  currentConflicts = currentConflicts.map(function (other) {
+    return other === entry ? null : other;
+  });
+  editor.pushUndoStop();
+  editor.executeEdits("impulse-resolve-conflict", [edit]);
+  editor.pushUndoStop();
+  conflictLensEmitter.fire();
+}
+
+// Languages Monaco has no grammar for. The host highlights them with
+// tree-sitter and sends the result as SetSyntaxTokens.
+var FALLBACK_LANGUAGES = {
+  zig: {
+    comments: { lineComment: "//" },
+    brackets: [
+      ["{", "}"],
+      ["[", "]"],
+      ["(", ")"],
+    ],
+  },
+  nix: {
+    comments: { lineComment: "#", blockComment: ["/*", "*/"] },
+    brackets: [
+      ["{", "}"],
+      ["[", "]"],
+      ["(", ")"],
+    ],
+  },
+  just: {
+    comments: { lineComment: "#" },
+    brackets: [
+      ["{", "}"],
+      ["[", "]"],
+      ["(", ")"],
+    ],
+  },
+};
+
+function registerFallbackLanguages() {
+  var known = monaco.languages.getLanguages().map(function (l) {
+    return l.id;
+  });
+  Object.keys(FALLBACK_LANGUAGES).forEach(function (id) {
+    if (known.indexOf(id) !== -1) return;
+    monaco.languages.register({ id: id });
+    monaco.languages.setLanguageConfiguration(id, FALLBACK_LANGUAGES[id]);
+  });
+}
+
+// "keyword.flow" -> "impulse-syn-keyword impulse-syn-keyword-flow", so a
+// scope without its own theme rule inherits its parent's color.
+function syntaxClassName(scope) {
+  var parts = String(scope).split(".");
+  var classes = [];
+  for (var i = 1; i <= parts.length; i++) {
+    classes.push("impulse-syn-" + parts.slice(0, i).join("-"));
+  }
+  return classes.join(" ");
+}
+
+function handleSetSyntaxTokens(cmd) {
+  var decorations = (cmd.tokens || []).map(function (t) {
+    return {
+      range: new monaco.Range(
+        t.line + 1,
+        t.start_column + 1,
+        t.line + 1,
+        t.end_column + 1,
+      ),
+      options: {
+        inlineClassName: syntaxClassName(t.scope),
+        stickiness:
+          monaco.editor.TrackedRangeStickiness.NeverGrowsWhenTypingAtEdges,
+      },
+    };
+  });
+  currentSyntaxDecorations = editor.deltaDecorations(
+    currentSyntaxDecorations,
+    decorations,
+  );
+}
+
+// Blame annotations are injected before each line's text in a fixed-width
+// column. Every line gets one (blank for continuation lines) so text stays
+// aligned.
+function handleSetBlameGutter(cmd) {
+  const decorations = (cmd.lines || []).map(function (l) {
+    return {
+      range: new monaco.Range(l.line, 1, l.line, 1),
+      options: {
+        before: {
+          content: l.label || "\u00a0",
+          inlineClassName:
+            "impulse-blame-annotation impulse-blame-heat-" + (l.heat || 0),
+          cursorStops: monaco.editor.InjectedTextCursorStops.None,
+        },
+        stickiness:
+          monaco.editor.TrackedRangeStickiness.NeverGrowsWhenTypingAtEdges,
+      },
+    };
+  });
+  currentBlameDecorations = editor.deltaDecorations(
+    currentBlameDecorations,
+    decorations,
+  );
 }
 
 function handleResolveFormatting(cmd) {
@@ -953,12 +1923,20 @@ function handleResolveCodeActions(cmd) {
         },
       };
     });
-    return {
+    var result = {
       title: action.title,
       kind: action.kind || undefined,
       isPreferred: action.is_preferred,
       edit: { edits: workspaceEdits },
     };
+    if (action.command) {
+      result.command = {
+        id: "impulse.executeLspCommand",
+        title: action.command.title,
+        arguments: [action.command.command, action.command.arguments || []],
+      };
+    }
+    return result;
   });
 
   resolve({ actions: actions, dispose: function () {} });
@@ -969,7 +1947,14 @@ function handleResolveRename(cmd) {
   if (!pending) return;
   pendingRename.delete(cmd.request_id);
 
-  var edits = (cmd.edits || []).map(function (e) {
+  // The host may confirm and apply the rename itself (e.g. via a multi-file
+  // preview dialog). Resolving with null ends Monaco's rename silently.
+  if (!cmd.edits || cmd.edits.length === 0) {
+    pending.resolve(null);
+    return;
+  }
+
+  var edits = cmd.edits.map(function (e) {
     return {
       resource: monaco.Uri.parse(e.uri),
       textEdit: {
@@ -986,6 +1971,78 @@ function handleResolveRename(cmd) {
   pending.resolve({ edits: edits });
 }
 
+function handleRunAction(cmd) {
+  var action = editor.getAction(cmd.id);
+  if (!action) {
+    console.warn("Unknown editor action:", cmd.id);
+    return;
+  }
+  editor.focus();
+  action.run();
+}
+
+// Converting goes through tabs at the old width and back out to spaces at
+// the new one, so changing e.g. 2 spaces to 4 re-indents every level.
+function handleSetIndentation(cmd) {
+  hostIndentation = {
+    tabSize: cmd.tab_size,
+    indentSize: cmd.tab_size,
+    insertSpaces: cmd.insert_spaces,
+  };
+  if (!currentModel) return;
+  if (cmd.convert) {
+    editor.getAction("editor.action.indentationToTabs").run();
+  }
+  currentModel.updateOptions(hostIndentation);
+  if (cmd.convert && cmd.insert_spaces) {
+    editor.getAction("editor.action.indentationToSpaces").run();
+  }
+}
+
+function handleApplyEdits(cmd) {
+  if (!currentModel || !cmd.edits || cmd.edits.length === 0) return;
+  var operations = cmd.edits.map(function (e) {
+    return {
+      range: new monaco.Range(
+        e.range.start_line + 1,
+        e.range.start_column + 1,
+        e.range.end_line + 1,
+        e.range.end_column + 1,
+      ),
+      text: e.text,
+    };
+  });
+  currentModel.pushStackElement();
+  currentModel.pushEditOperations(
+    editor.getSelections(),
+    operations,
+    function () {
+      return null;
+    },
+  );
+  currentModel.pushStackElement();
+}
+
+// Replace the whole buffer as a single undo step, so the previous content
+// stays reachable with Undo (OpenFile would reset the history).
+function handleReplaceContent(cmd) {
+  if (!currentModel || currentModel.getValue() === cmd.content) return;
+  currentModel.pushStackElement();
+  currentModel.pushEditOperations(
+    editor.getSelections(),
+    [{ range: currentModel.getFullModelRange(), text: cmd.content }],
+    function () {
+      return null;
+    },
+  );
+  currentModel.pushStackElement();
+}
+
+function handleSetLanguage(cmd) {
+  if (!currentModel) return;
+  monaco.editor.setModelLanguage(currentModel, cmd.language || "plaintext");
+}
+
 function handleResolvePrepareRename(cmd) {
   var pending = pendingPrepareRename.get(cmd.request_id);
   if (!pending) return;
@@ -1006,6 +2063,326 @@ function handleResolvePrepareRename(cmd) {
   }
 }
 
+// ---------------------------------------------------------------------------
+// Peek widget
+// ---------------------------------------------------------------------------
+// A view zone reserves vertical space below the requesting line; an overlay
+// widget positioned over that zone holds the actual UI (result list on the
+// right, read-only preview editor on the left). This mirrors how Monaco's own
+// ZoneWidget works, but previews come from host-supplied snippets rather than
+// models Monaco would need to load itself.
+
+var PEEK_HEIGHT_PX = 260;
+var PEEK_EMPTY_HEIGHT_PX = 64;
+
+function requestPeek(kind) {
+  if (!hostSupports("peek")) return;
+  var position = editor.getPosition();
+  if (!position) return;
+  var id = ++requestSeq;
+  pendingPeek = { id: id, lineNumber: position.lineNumber };
+  sendToHost({
+    type: "PeekRequested",
+    request_id: id,
+    kind: kind,
+    line: position.lineNumber - 1,
+    character: position.column - 1,
+  });
+}
+
+function handleShowPeek(cmd) {
+  if (!pendingPeek || pendingPeek.id !== cmd.request_id) return;
+  var lineNumber = pendingPeek.lineNumber;
+  pendingPeek = null;
+  showPeek(lineNumber, cmd.kind, cmd.locations || []);
+}
+
+function peekFileLabel(uri) {
+  var path = monaco.Uri.parse(uri).path;
+  var slash = path.lastIndexOf("/");
+  return {
+    name: slash >= 0 ? path.substring(slash + 1) : path,
+    dir: slash > 0 ? path.substring(0, slash) : "",
+  };
+}
+
+function showPeek(afterLineNumber, kind, locations) {
+  closePeek();
+
+  var root = document.createElement("div");
+  root.className = "impulse-peek";
+  root.setAttribute("role", "dialog");
+
+  var header = document.createElement("div");
+  header.className = "impulse-peek-header";
+  var title = document.createElement("span");
+  title.className = "impulse-peek-title";
+  title.textContent = kind === "references" ? "References" : "Definition";
+  var count = document.createElement("span");
+  count.className = "impulse-peek-count";
+  count.textContent =
+    locations.length === 1 ? "1 result" : locations.length + " results";
+  var close = document.createElement("button");
+  close.className = "impulse-peek-close";
+  close.title = "Close (Escape)";
+  close.textContent = "\u00d7";
+  close.addEventListener("click", function () {
+    closePeek();
+    editor.focus();
+  });
+  header.appendChild(title);
+  header.appendChild(count);
+  header.appendChild(close);
+  root.appendChild(header);
+
+  var body = document.createElement("div");
+  body.className = "impulse-peek-body";
+  root.appendChild(body);
+
+  var widget = {
+    root: root,
+    zoneId: null,
+    items: [],
+    selected: -1,
+    preview: null,
+    previewModel: null,
+    previewDecorations: [],
+    list: null,
+  };
+
+  if (locations.length === 0) {
+    var empty = document.createElement("div");
+    empty.className = "impulse-peek-empty";
+    empty.textContent =
+      kind === "references" ? "No references found" : "No definition found";
+    body.appendChild(empty);
+  } else {
+    var previewNode = document.createElement("div");
+    previewNode.className = "impulse-peek-preview";
+    var list = document.createElement("div");
+    list.className = "impulse-peek-list";
+    list.tabIndex = 0;
+    list.setAttribute("role", "listbox");
+    body.appendChild(previewNode);
+    body.appendChild(list);
+    widget.list = list;
+
+    var lastUri = null;
+    locations.forEach(function (loc) {
+      if (loc.uri !== lastUri) {
+        lastUri = loc.uri;
+        var label = peekFileLabel(loc.uri);
+        var fileRow = document.createElement("div");
+        fileRow.className = "impulse-peek-file";
+        var name = document.createElement("span");
+        name.textContent = label.name;
+        var dir = document.createElement("span");
+        dir.className = "impulse-peek-dir";
+        dir.textContent = label.dir;
+        fileRow.appendChild(name);
+        fileRow.appendChild(dir);
+        list.appendChild(fileRow);
+      }
+      var row = document.createElement("div");
+      row.className = "impulse-peek-item";
+      row.setAttribute("role", "option");
+      var lineIndex = loc.range.start_line - loc.preview_start_line;
+      var text = (loc.preview_lines || [])[lineIndex];
+      row.textContent =
+        loc.range.start_line +
+        1 +
+        ": " +
+        (text != null ? text.trim() : peekFileLabel(loc.uri).name);
+      var index = widget.items.length;
+      row.addEventListener("click", function () {
+        selectPeekItem(index);
+      });
+      row.addEventListener("dblclick", function () {
+        openPeekLocation(widget.items[index].location);
+      });
+      list.appendChild(row);
+      widget.items.push({ location: loc, row: row });
+    });
+
+    list.addEventListener("keydown", function (e) {
+      if (e.key === "ArrowDown" || e.key === "F4") {
+        movePeekSelection(e.shiftKey && e.key === "F4" ? -1 : 1);
+      } else if (e.key === "ArrowUp") {
+        movePeekSelection(-1);
+      } else if (e.key === "Enter") {
+        if (widget.selected >= 0) {
+          openPeekLocation(widget.items[widget.selected].location);
+        }
+      } else if (e.key === "Escape") {
+        closePeek();
+        editor.focus();
+      } else {
+        return;
+      }
+      e.preventDefault();
+      e.stopPropagation();
+    });
+
+    widget.previewModel = monaco.editor.createModel("", "plaintext");
+    widget.preview = monaco.editor.create(previewNode, {
+      model: widget.previewModel,
+      readOnly: true,
+      automaticLayout: true,
+      minimap: { enabled: false },
+      scrollBeyondLastLine: false,
+      glyphMargin: false,
+      folding: false,
+      lineDecorationsWidth: 6,
+      renderLineHighlight: "none",
+      contextmenu: false,
+      fontSize: editor.getOption(monaco.editor.EditorOption.fontSize),
+      fontFamily: editor.getOption(monaco.editor.EditorOption.fontFamily),
+      scrollbar: { useShadows: false, verticalScrollbarSize: 8 },
+    });
+  }
+
+  var zoneNode = document.createElement("div");
+  var height = locations.length === 0 ? PEEK_EMPTY_HEIGHT_PX : PEEK_HEIGHT_PX;
+  editor.changeViewZones(function (accessor) {
+    widget.zoneId = accessor.addZone({
+      afterLineNumber: afterLineNumber,
+      heightInPx: height,
+      domNode: zoneNode,
+      onDomNodeTop: function (top) {
+        root.style.top = top + "px";
+      },
+      onComputedHeight: function (h) {
+        root.style.height = h + "px";
+      },
+    });
+  });
+  editor.addOverlayWidget({
+    getId: function () {
+      return "impulse.peek";
+    },
+    getDomNode: function () {
+      return root;
+    },
+    getPosition: function () {
+      return null;
+    },
+  });
+
+  peekWidget = widget;
+  peekVisibleKey.set(true);
+  layoutPeek();
+  var lineHeight = editor.getOption(monaco.editor.EditorOption.lineHeight);
+  editor.revealLinesInCenterIfOutsideViewport(
+    afterLineNumber,
+    afterLineNumber + Math.ceil(height / lineHeight),
+  );
+
+  if (widget.items.length > 0) {
+    selectPeekItem(0);
+    widget.list.focus();
+  }
+}
+
+function layoutPeek() {
+  if (!peekWidget) return;
+  var layout = editor.getLayoutInfo();
+  peekWidget.root.style.left = layout.contentLeft + "px";
+  peekWidget.root.style.width =
+    Math.max(
+      0,
+      layout.width - layout.contentLeft - layout.verticalScrollbarWidth,
+    ) + "px";
+}
+
+function selectPeekItem(index) {
+  var widget = peekWidget;
+  if (!widget || index < 0 || index >= widget.items.length) return;
+  if (widget.selected >= 0) {
+    widget.items[widget.selected].row.classList.remove("selected");
+    widget.items[widget.selected].row.setAttribute("aria-selected", "false");
+  }
+  widget.selected = index;
+  var item = widget.items[index];
+  item.row.classList.add("selected");
+  item.row.setAttribute("aria-selected", "true");
+  item.row.scrollIntoView({ block: "nearest" });
+
+  var loc = item.location;
+  var start = loc.preview_start_line;
+  widget.previewModel.setValue((loc.preview_lines || []).join("\n"));
+  monaco.editor.setModelLanguage(
+    widget.previewModel,
+    loc.language || "plaintext",
+  );
+  widget.preview.updateOptions({
+    lineNumbers: function (n) {
+      return String(n + start);
+    },
+  });
+  var range = new monaco.Range(
+    loc.range.start_line - start + 1,
+    loc.range.start_column + 1,
+    loc.range.end_line - start + 1,
+    loc.range.end_column + 1,
+  );
+  widget.previewDecorations = widget.preview.deltaDecorations(
+    widget.previewDecorations,
+    [
+      {
+        range: range,
+        options: { className: "impulse-peek-match" },
+      },
+    ],
+  );
+  widget.preview.revealRangeInCenter(range);
+}
+
+function movePeekSelection(delta) {
+  if (!peekWidget || peekWidget.items.length === 0) return;
+  var count = peekWidget.items.length;
+  selectPeekItem((peekWidget.selected + delta + count) % count);
+}
+
+function openPeekLocation(loc) {
+  var target = monaco.Uri.parse(loc.uri);
+  closePeek();
+  if (currentModel && target.toString() === currentModel.uri.toString()) {
+    var position = {
+      lineNumber: loc.range.start_line + 1,
+      column: loc.range.start_column + 1,
+    };
+    editor.setPosition(position);
+    editor.revealPositionInCenter(position);
+    editor.focus();
+  } else {
+    sendToHost({
+      type: "OpenFileRequested",
+      uri: loc.uri,
+      line: loc.range.start_line,
+      character: loc.range.start_column,
+    });
+  }
+}
+
+function closePeek() {
+  var widget = peekWidget;
+  if (!widget) return;
+  peekWidget = null;
+  if (peekVisibleKey) peekVisibleKey.set(false);
+  if (widget.zoneId != null) {
+    editor.changeViewZones(function (accessor) {
+      accessor.removeZone(widget.zoneId);
+    });
+  }
+  editor.removeOverlayWidget({
+    getId: function () {
+      return "impulse.peek";
+    },
+  });
+  if (widget.preview) widget.preview.dispose();
+  if (widget.previewModel) widget.previewModel.dispose();
+}
+
 function isValidCssColor(c) {
   return (
     typeof c === "string" &&
@@ -1013,6 +2390,47 @@ function isValidCssColor(c) {
   );
 }
 
+// Mirror the theme's token rules as CSS classes for host-provided syntax
+// tokens. Rules are emitted shallowest first so more specific scopes win.
+function updateSyntaxTokenColors(rules) {
+  var sorted = rules
+    .filter(function (r) {
+      return r.token && /^[a-zA-Z0-9.]+$/.test(r.token);
+    })
+    .sort(function (a, b) {
+      return a.token.split(".").length - b.token.split(".").length;
+    });
+
+  var css = "";
+  sorted.forEach(function (r) {
+    var declarations = "";
+    var color = r.foreground ? "#" + r.foreground : null;
+    if (isValidCssColor(color)) declarations += "color: " + color + ";";
+    var fontStyle = r.font_style || "";
+    if (fontStyle.indexOf("italic") !== -1)
+      declarations += "font-style: italic;";
+    if (fontStyle.indexOf("bold") !== -1) declarations += "font-weight: bold;";
+    if (fontStyle.indexOf("underline") !== -1)
+      declarations += "text-decoration: underline;";
+    if (!declarations) return;
+    css +=
+      ".monaco-editor .impulse-syn-" +
+      r.token.replace(/\./g, "-") +
+      " { " +
+      declarations +
+      " }";
+  });
+
+  var styleId = "impulse-syntax-token-style";
+  var existing = document.getElementById(styleId);
+  if (existing) existing.remove();
+
+  var style = document.createElement("style");
+  style.id = styleId;
+  style.textContent = css;
+  document.head.appendChild(style);
+}
+
 function updateDiffGutterColors(colors) {
   var addedColor = colors["impulse.diffAddedColor"];
   var modifiedColor = colors["impulse.diffModifiedColor"];
@@ -1029,6 +2447,14 @@ function updateDiffGutterColors(colors) {
 
   var style = document.createElement("style");
   style.id = styleId;
+  document.documentElement.style.setProperty(
+    "--impulse-blame-heat",
+    safeModified,
+  );
+  document.documentElement.style.setProperty(
+    "--impulse-conflict-current",
+    safeAdded,
+  );
   style.textContent =
     ".diff-gutter-added { background: " +
     safeAdded +
@@ -231,6 +231,8 @@ function reconcileSection(rec) {
 // ---------------------------------------------------------------------------
 function handleRender(cmd) {
   const files = cmd.files || [];
+  // Comparisons between refs or files have nothing to discard.
+  const readOnly = !!cmd.read_only;
   const root = document.getElementById("review-root");
   if (!root) return;
 
@@ -251,7 +253,7 @@ function handleRender(cmd) {
   if (files.length === 0) {
     const empty = document.createElement("div");
     empty.className = "review-empty";
-    empty.textContent = "No changes to review.";
+    empty.textContent = readOnly ? "No differences." : "No changes to review.";
     root.appendChild(empty);
     return;
   }
@@ -260,7 +262,7 @@ function handleRender(cmd) {
     // All sections start collapsed; the diff is requested and the Monaco editor
     // mounted lazily the first time the user expands a section (and it is near
     // the viewport — see reconcileSection / the IntersectionObserver).
-    const rec = buildSection(f, false);
+    const rec = buildSection(f, false, readOnly);
     sections.set(f.path, rec);
     root.appendChild(rec.el);
     observeSection(rec);
@@ -288,7 +290,7 @@ function statusGlyphText(status) {
   return status === "?" ? "?" : status || "M";
 }
 
-function buildSection(f, expanded) {
+function buildSection(f, expanded, readOnly) {
   const rec = {
     path: f.path,
     status: f.status,
@@ -355,15 +357,17 @@ function buildSection(f, expanded) {
   badge.appendChild(removedEl);
   header.appendChild(badge);
 
-  const discard = document.createElement("button");
-  discard.className = "review-discard";
-  discard.title = "Discard changes to this file";
-  discard.textContent = "Discard";
-  discard.addEventListener("click", function (ev) {
-    ev.stopPropagation();
-    sendToHost({ type: "Discard", path: rec.path });
-  });
-  header.appendChild(discard);
+  if (!readOnly) {
+    const discard = document.createElement("button");
+    discard.className = "review-discard";
+    discard.title = "Discard changes to this file";
+    discard.textContent = "Discard";
+    discard.addEventListener("click", function (ev) {
+      ev.stopPropagation();
+      sendToHost({ type: "Discard", path: rec.path });
+    });
+    header.appendChild(discard);
+  }
 
   header.addEventListener("click", function () {
     toggleSection(rec);
@@ -69,6 +69,14 @@ fn to_c_string(s: &str) -> *mut c_char {
     }
 }
 
+/// Serialized JSON, or an `{"error": ...}` object.
+fn json_or_error(result: Result<String, String>) -> String {
+    match result {
+        Ok(json) => json,
+        Err(e) => serde_json::json!({"error": e}).to_string(),
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Memory management
 // ---------------------------------------------------------------------------
@@ -133,6 +141,93 @@ pub extern "C" fn impulse_get_editor_html() -> *const c_char {
     )
 }
 
+// ---------------------------------------------------------------------------
+// Editor command batching
+// ---------------------------------------------------------------------------
+//
+// Hosts push serialized `EditorCommand`s as they are produced and flush once
+// per frame with a single `impulseReceiveCommands(json)` call. Successive
+// decoration sets for the same file are coalesced (see
+// `impulse_editor::batch`). A batch belongs to one editor and is not
+// thread-safe; use it from the thread that drives the WebView.
+
+/// Opaque handle to one editor's command queue.
+pub struct EditorBatchHandle {
+    batch: impulse_editor::batch::CommandBatch,
+}
+
+#[no_mangle]
+pub extern "C" fn impulse_editor_batch_new() -> *mut EditorBatchHandle {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            Box::into_raw(Box::new(EditorBatchHandle {
+                batch: impulse_editor::batch::CommandBatch::new(),
+            }))
+        }),
+    )
+}
+
+/// Queue one serialized command. Returns false if `command_json` is not a
+/// command object.
+#[no_mangle]
+pub extern "C" fn impulse_editor_batch_push(
+    handle: *mut EditorBatchHandle,
+    command_json: *const c_char,
+) -> bool {
+    ffi_catch(
+        false,
+        AssertUnwindSafe(|| {
+            if handle.is_null() {
+                return false;
+            }
+            let Some(json) = to_rust_str(command_json) else {
+                return false;
+            };
+            let h = unsafe { &mut *handle };
+            match h.batch.push_json(&json) {
+                Ok(()) => true,
+                Err(e) => {
+                    log::warn!("{}", e);
+                    false
+                }
+            }
+        }),
+    )
+}
+
+/// Drain the queue as a JSON array for `impulseReceiveCommands`. Returns
+/// NULL when nothing is queued. The caller must free the returned string
+/// with `impulse_free_string`.
+#[no_mangle]
+pub extern "C" fn impulse_editor_batch_take(handle: *mut EditorBatchHandle) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            if handle.is_null() {
+                return std::ptr::null_mut();
+            }
+            let h = unsafe { &mut *handle };
+            match h.batch.take_json() {
+                Some(json) => to_c_string(&json),
+                None => std::ptr::null_mut(),
+            }
+        }),
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn impulse_editor_batch_free(handle: *mut EditorBatchHandle) {
+    ffi_catch(
+        (),
+        AssertUnwindSafe(|| {
+            if !handle.is_null() {
+                drop(unsafe { Box::from_raw(handle) });
+            }
+        }),
+    )
+}
+
 // ---------------------------------------------------------------------------
 // Shell integration
 // ---------------------------------------------------------------------------
@@ -182,6 +277,133 @@ pub extern "C" fn impulse_get_user_login_shell_name() -> *mut c_char {
     )
 }
 
+/// The directory a new terminal tab starts in under the
+/// `terminal_new_tab_directory` setting `mode`. `workspace` and `current`
+/// (the active tab's directory) may be NULL.
+///
+/// The caller must free the returned string with `impulse_free_string`.
+#[no_mangle]
+pub extern "C" fn impulse_new_terminal_directory(
+    mode: *const c_char,
+    home: *const c_char,
+    workspace: *const c_char,
+    current: *const c_char,
+) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let mode = to_rust_str(mode).unwrap_or_default();
+            let home = to_rust_str(home).unwrap_or_default();
+            let workspace = to_rust_str(workspace);
+            let current = to_rust_str(current);
+            to_c_string(&impulse_core::shell::new_terminal_directory(
+                &mode,
+                &home,
+                workspace.as_deref(),
+                current.as_deref(),
+            ))
+        }),
+    )
+}
+
+/// What each terminal shell in `shell_pids_json` (a JSON array of PIDs) is
+/// running. Returns a JSON array, in the same order, of objects with
+/// `shell_pid`, `description` (the tab tooltip text, or null when the shell
+/// is idle), `foreground` and `children`. Reads the process table once.
+///
+/// The caller must free the returned string with `impulse_free_string`.
+#[no_mangle]
+pub extern "C" fn impulse_terminal_processes(shell_pids_json: *const c_char) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let pids: Vec<u32> = match to_rust_str(shell_pids_json)
+                .and_then(|json| serde_json::from_str(&json).ok())
+            {
+                Some(pids) => pids,
+                None => return std::ptr::null_mut(),
+            };
+            let table = impulse_core::process_info::ProcessTable::read();
+            let result: Vec<serde_json::Value> = pids
+                .iter()
+                .map(|&pid| {
+                    let processes = table.terminal(pid);
+                    serde_json::json!({
+                        "shell_pid": pid,
+                        "description": processes.describe(),
+                        "foreground": processes.foreground,
+                        "children": processes.children,
+                    })
+                })
+                .collect();
+            to_c_string(&serde_json::to_string(&result).unwrap_or_else(|_| "[]".to_string()))
+        }),
+    )
+}
+
+/// Why pasting `text` into a terminal should be confirmed, as a message for
+/// the preview dialog, or NULL when it can be pasted right away. `bracketed`
+/// is whether the terminal has bracketed paste on.
+///
+/// The caller must free the returned string with `impulse_free_string`.
+#[no_mangle]
+pub extern "C" fn impulse_paste_confirmation(text: *const c_char, bracketed: bool) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let Some(text) = to_rust_str(text) else {
+                return std::ptr::null_mut();
+            };
+            match impulse_core::paste_guard::check(&text, bracketed) {
+                Some(confirmation) => to_c_string(&confirmation.message),
+                None => std::ptr::null_mut(),
+            }
+        }),
+    )
+}
+
+/// The local paths in dropped `text/uri-list` data (or bare absolute paths
+/// sent as text), as a JSON array of strings.
+///
+/// The caller must free the returned string with `impulse_free_string`.
+#[no_mangle]
+pub extern "C" fn impulse_parse_uri_list(text: *const c_char) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let text = to_rust_str(text).unwrap_or_default();
+            let paths: Vec<String> = impulse_core::file_drop::parse_uri_list(&text)
+                .iter()
+                .map(|p| p.to_string_lossy().to_string())
+                .collect();
+            to_c_string(&serde_json::to_string(&paths).unwrap_or_else(|_| "[]".to_string()))
+        }),
+    )
+}
+
+/// How many processes under the terminal shell `shell_pid` closing it should
+/// warn about, leaving out the programs in `ignored_json` (a JSON array of
+/// names, the `terminal_close_ignored_commands` setting) and their children.
+#[no_mangle]
+pub extern "C" fn impulse_terminal_close_risk_process_count(
+    shell_pid: i32,
+    ignored_json: *const c_char,
+) -> i32 {
+    ffi_catch(
+        0,
+        AssertUnwindSafe(|| {
+            if shell_pid <= 0 {
+                return 0;
+            }
+            let ignored: Vec<String> = to_rust_str(ignored_json)
+                .and_then(|json| serde_json::from_str(&json).ok())
+                .unwrap_or_default();
+            impulse_core::process_info::terminal_processes(shell_pid as u32)
+                .close_risk_count(&ignored) as i32
+        }),
+    )
+}
+
 // ---------------------------------------------------------------------------
 // Search
 // ---------------------------------------------------------------------------
@@ -268,6 +490,252 @@ pub extern "C" fn impulse_search_content(
     )
 }
 
+/// Search file contents for `query` in a scope: `scope_json` is a
+/// `SearchScope` object such as `{"kind": "glob", "pattern": "*.rs"}` or
+/// `{"kind": "open_files", "files": [{"path", "content"}]}`.
+///
+/// Returns a JSON array of `SearchResult` objects, or `{"error": ...}`.
+/// The caller must free the returned string with `impulse_free_string`.
+#[no_mangle]
+pub extern "C" fn impulse_search_content_in_scope(
+    root: *const c_char,
+    scope_json: *const c_char,
+    query: *const c_char,
+    case_sensitive: bool,
+) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let (Some(root), Some(query)) = (to_rust_str(root), to_rust_str(query)) else {
+                return std::ptr::null_mut();
+            };
+            let result = to_rust_str(scope_json)
+                .ok_or_else(|| "Missing search scope".to_string())
+                .and_then(|json| {
+                    serde_json::from_str::<impulse_core::search::SearchScope>(&json)
+                        .map_err(|e| format!("Invalid search scope: {e}"))
+                })
+                .and_then(|scope| {
+                    impulse_core::search::search_contents_in_scope(
+                        &root,
+                        &scope,
+                        &query,
+                        500,
+                        case_sensitive,
+                        None,
+                    )
+                });
+            let json = match result {
+                Ok(results) => serde_json::to_string(&results)
+                    .unwrap_or_else(|e| serde_json::json!({"error": e.to_string()}).to_string()),
+                Err(e) => serde_json::json!({"error": e}).to_string(),
+            };
+            to_c_string(&json)
+        }),
+    )
+}
+
+/// Turn the content search index on or off. With it on, each root searched
+/// with `impulse_search_content` is indexed in the background and later
+/// searches read only files that can match.
+#[no_mangle]
+pub extern "C" fn impulse_search_set_index_enabled(enabled: bool) {
+    ffi_catch(
+        (),
+        AssertUnwindSafe(|| impulse_core::search::set_content_index_enabled(enabled)),
+    )
+}
+
+/// Set the `follow_symlinks` and `scan_network_mounts` settings: whether
+/// search, indexing and file watching follow symlinked directories and
+/// enter network mounts.
+#[no_mangle]
+pub extern "C" fn impulse_set_walk_options(follow_symlinks: bool, scan_network_mounts: bool) {
+    ffi_catch(
+        (),
+        AssertUnwindSafe(|| {
+            impulse_core::mounts::set_walk_options(follow_symlinks, scan_network_mounts)
+        }),
+    )
+}
+
+/// Set the `save_strategy` setting (`"atomic"` or `"in_place"`).
+#[no_mangle]
+pub extern "C" fn impulse_set_save_strategy(strategy: *const c_char) {
+    ffi_catch(
+        (),
+        AssertUnwindSafe(|| {
+            let strategy = to_rust_str(strategy).unwrap_or_default();
+            impulse_core::safe_write::set_save_strategy(&strategy)
+        }),
+    )
+}
+
+/// Set the `files_exclude` and `search_exclude` settings, each a JSON array
+/// of gitignore-style globs. Returns 0 on success, -1 on error.
+#[no_mangle]
+pub extern "C" fn impulse_set_exclude_globs(
+    files_exclude_json: *const c_char,
+    search_exclude_json: *const c_char,
+) -> i32 {
+    ffi_catch(
+        -1,
+        AssertUnwindSafe(|| {
+            let (Some(files_exclude), Some(search_exclude)) = (
+                string_list_arg(files_exclude_json),
+                string_list_arg(search_exclude_json),
+            ) else {
+                return -1;
+            };
+            impulse_core::ignore_rules::set_exclude_globs(files_exclude, search_exclude);
+            0
+        }),
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Event callbacks
+// ---------------------------------------------------------------------------
+//
+// Handles that produce asynchronous events (LSP today; watchers, search, and
+// git later) can deliver them through a C callback instead of a poll
+// function. Threading contract, for every `*_set_event_callback`:
+//
+// - The callback runs on a dedicated background thread owned by the handle,
+//   never on the caller's thread and never concurrently with itself. Events
+//   arrive in order. Hop to the main thread before touching UI state.
+// - `event_json` is a NUL-terminated JSON object that is only valid for the
+//   duration of the call. Copy it; do not free it.
+// - The callback should return quickly; slow callbacks delay later events.
+// - After `*_clear_event_callback` (or freeing the handle) returns, the
+//   callback is never invoked again, so `context` may be released. Clearing
+//   from inside the callback is allowed and takes effect once it returns.
+// - While a callback is set, events are not queued for the poll function.
+
+use parking_lot::ReentrantMutex;
+use std::cell::Cell;
+use std::ffi::c_void;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// C callback receiving one event: `context` as registered, and a borrowed
+/// JSON string.
+pub type ImpulseEventCallback = extern "C" fn(context: *mut c_void, event_json: *const c_char);
+
+#[derive(Clone, Copy)]
+struct EventSubscriber {
+    callback: ImpulseEventCallback,
+    context: *mut c_void,
+}
+
+// SAFETY: the context pointer is opaque to Rust and only handed back to the
+// callback; the caller promises it may be used from the dispatcher thread.
+unsafe impl Send for EventSubscriber {}
+
+/// Delivers JSON events to at most one C subscriber on a dedicated thread.
+struct EventDispatcher {
+    /// Held (reentrantly) for the duration of every callback invocation so
+    /// that unsubscribing waits for an in-flight call to finish.
+    subscriber: Arc<ReentrantMutex<Cell<Option<EventSubscriber>>>>,
+    /// Mirrors whether `subscriber` is set, readable without waiting for a
+    /// callback in progress.
+    active: AtomicBool,
+    tx: parking_lot::Mutex<Option<crossbeam_channel::Sender<String>>>,
+    thread: parking_lot::Mutex<Option<std::thread::JoinHandle<()>>>,
+}
+
+// SAFETY: the `Cell` is only accessed while holding the reentrant mutex, and
+// the mutex serializes access across threads.
+unsafe impl Sync for EventDispatcher {}
+unsafe impl Send for EventDispatcher {}
+
+impl EventDispatcher {
+    fn new(thread_name: &str) -> Self {
+        let (tx, rx) = crossbeam_channel::unbounded::<String>();
+        let subscriber: Arc<ReentrantMutex<Cell<Option<EventSubscriber>>>> =
+            Arc::new(ReentrantMutex::new(Cell::new(None)));
+        let thread_subscriber = Arc::clone(&subscriber);
+        let thread = std::thread::Builder::new()
+            .name(thread_name.to_string())
+            .spawn(move || {
+                for json in rx {
+                    let guard = thread_subscriber.lock();
+                    let Some(subscriber) = guard.get() else {
+                        // Unsubscribed after the event was queued.
+                        continue;
+                    };
+                    let json = match CString::new(json) {
+                        Ok(json) => json,
+                        Err(_) => continue,
+                    };
+                    let call = AssertUnwindSafe(|| {
+                        (subscriber.callback)(subscriber.context, json.as_ptr())
+                    });
+                    if catch_unwind(call).is_err() {
+                        log::error!("Event callback panicked");
+                    }
+                    drop(guard);
+                }
+            })
+            .map_err(|e| log::error!("Failed to spawn event dispatcher thread: {}", e))
+            .ok();
+        Self {
+            subscriber,
+            active: AtomicBool::new(false),
+            tx: parking_lot::Mutex::new(Some(tx)),
+            thread: parking_lot::Mutex::new(thread),
+        }
+    }
+
+    fn subscribe(&self, callback: ImpulseEventCallback, context: *mut c_void) {
+        let guard = self.subscriber.lock();
+        guard.set(Some(EventSubscriber { callback, context }));
+        self.active.store(true, Ordering::SeqCst);
+    }
+
+    /// Remove the subscriber, waiting for an in-flight callback on another
+    /// thread to return.
+    fn unsubscribe(&self) {
+        let guard = self.subscriber.lock();
+        guard.set(None);
+        self.active.store(false, Ordering::SeqCst);
+    }
+
+    fn is_subscribed(&self) -> bool {
+        self.active.load(Ordering::SeqCst)
+    }
+
+    /// Queue `json` for the subscriber. Returns false when nobody is
+    /// subscribed, so the caller can fall back to its poll queue.
+    fn send(&self, json: String) -> bool {
+        if !self.is_subscribed() {
+            return false;
+        }
+        match self.tx.lock().as_ref() {
+            Some(tx) => tx.send(json).is_ok(),
+            None => false,
+        }
+    }
+
+    /// Unsubscribe and stop the dispatcher thread.
+    fn shutdown(&self) {
+        self.unsubscribe();
+        self.tx.lock().take();
+        let thread = self.thread.lock().take();
+        if let Some(thread) = thread {
+            // Joining ourselves would deadlock when freed from a callback.
+            if thread.thread().id() != std::thread::current().id() {
+                let _ = thread.join();
+            }
+        }
+    }
+}
+
+impl Drop for EventDispatcher {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
 // ---------------------------------------------------------------------------
 // LSP management
 // ---------------------------------------------------------------------------
@@ -283,6 +751,8 @@ struct LspRegistryInner {
     registry: Arc<impulse_core::lsp::LspRegistry>,
     runtime: Arc<Runtime>,
     event_rx: parking_lot::Mutex<mpsc::Receiver<impulse_core::lsp::LspEvent>>,
+    /// Callback delivery; bypasses `event_rx` while subscribed.
+    events: Arc<EventDispatcher>,
     documents: parking_lot::Mutex<HashMap<String, String>>,
 }
 
@@ -330,52 +800,6 @@ fn update_lsp_document_cache_for_notify(
     }
 }
 
-fn apply_lsp_content_changes_to_string(
-    content: &mut String,
-    changes: &[lsp_types::TextDocumentContentChangeEvent],
-) {
-    for change in changes.iter().rev() {
-        let Some(range) = change.range else {
-            *content = change.text.clone();
-            continue;
-        };
-        let start = lsp_position_to_byte_offset(content, range.start);
-        let end = lsp_position_to_byte_offset(content, range.end);
-        if start <= end && end <= content.len() {
-            content.replace_range(start..end, &change.text);
-        }
-    }
-}
-
-fn lsp_position_to_byte_offset(content: &str, position: lsp_types::Position) -> usize {
-    let mut line = 0u32;
-    let mut line_start = 0usize;
-    for (byte_index, ch) in content.char_indices() {
-        if line == position.line {
-            break;
-        }
-        if ch == '\n' {
-            line = line.saturating_add(1);
-            line_start = byte_index + ch.len_utf8();
-        }
-    }
-    if line != position.line {
-        return content.len();
-    }
-
-    let mut utf16_units = 0u32;
-    for (relative, ch) in content[line_start..].char_indices() {
-        if ch == '\n' || utf16_units >= position.character {
-            return line_start + relative;
-        }
-        utf16_units = utf16_units.saturating_add(ch.len_utf16() as u32);
-        if utf16_units > position.character {
-            return line_start + relative;
-        }
-    }
-    content.len()
-}
-
 /// Look up a handle in the global registry and run `f` with the inner data.
 /// Returns `default` if the handle is null or freed.
 fn with_lsp_handle<T>(
@@ -434,9 +858,17 @@ pub extern "C" fn impulse_lsp_registry_new(root_uri: *const c_char) -> *mut LspR
             // Create a bounded channel and spawn a forwarding task that bridges
             // the unbounded channel (required by LspRegistry) to a bounded one.
             // Events are dropped with a warning if the bounded channel is full.
+            // With an event callback set, events go to its dispatcher instead.
             let (bounded_tx, bounded_rx) = mpsc::channel(LSP_EVENT_CHANNEL_CAPACITY);
+            let events = Arc::new(EventDispatcher::new("impulse-lsp-events"));
+            let forward_events = Arc::clone(&events);
             runtime.spawn(async move {
                 while let Some(event) = unbounded_rx.recv().await {
+                    if forward_events.is_subscribed()
+                        && forward_events.send(lsp_event_json(&event).to_string())
+                    {
+                        continue;
+                    }
                     match bounded_tx.try_send(event) {
                         Ok(()) => {}
                         Err(mpsc::error::TrySendError::Full(_)) => {
@@ -457,6 +889,7 @@ pub extern "C" fn impulse_lsp_registry_new(root_uri: *const c_char) -> *mut LspR
                 registry,
                 runtime,
                 event_rx: parking_lot::Mutex::new(bounded_rx),
+                events,
                 documents: parking_lot::Mutex::new(HashMap::new()),
             });
 
@@ -538,36 +971,45 @@ pub extern "C" fn impulse_lsp_request(
                 handle,
                 to_c_string("{\"error\":\"invalid handle\"}"),
                 |inner| {
-                    inner.runtime.block_on(async {
-                    let clients = inner.registry.get_clients(&language_id, &file_uri).await;
-                    if let Some(client) = clients.first() {
-                        match client.request(&method, params).await {
-                            Ok(value) => {
-                                let json = match serde_json::to_string(&value) {
-                                    Ok(j) => j,
-                                    Err(e) => {
-                                        log::error!("JSON serialization failed: {}", e);
-                                        serde_json::json!({"error": format!("serialization failed: {}", e)})
-                                            .to_string()
-                                    }
-                                };
-                                to_c_string(&json)
-                            }
-                            Err(e) => {
-                                let json = serde_json::json!({"error": e.to_string()});
-                                to_c_string(&json.to_string())
-                            }
-                        }
-                    } else {
-                        to_c_string("{\"error\":\"no LSP client available\"}")
-                    }
-                })
+                    let json = inner.runtime.block_on(lsp_request_json(
+                        &inner.registry,
+                        &language_id,
+                        &file_uri,
+                        &method,
+                        params,
+                    ));
+                    to_c_string(&json)
                 },
             )
         }),
     )
 }
 
+/// Send `method` to the first client serving `file_uri` and return the
+/// response (or an `{"error": ...}` object) as JSON.
+async fn lsp_request_json(
+    registry: &impulse_core::lsp::LspRegistry,
+    language_id: &str,
+    file_uri: &str,
+    method: &str,
+    params: Option<serde_json::Value>,
+) -> String {
+    let clients = registry.get_clients(language_id, file_uri).await;
+    let Some(client) = clients.first() else {
+        return "{\"error\":\"no LSP client available\"}".to_string();
+    };
+    match client.request(method, params).await {
+        Ok(value) => match serde_json::to_string(&value) {
+            Ok(j) => j,
+            Err(e) => {
+                log::error!("JSON serialization failed: {}", e);
+                serde_json::json!({"error": format!("serialization failed: {}", e)}).to_string()
+            }
+        },
+        Err(e) => serde_json::json!({"error": e.to_string()}).to_string(),
+    }
+}
+
 /// Send an LSP notification (no response expected).
 ///
 /// `method` is the LSP method name (e.g. "textDocument/didOpen").
@@ -623,7 +1065,9 @@ pub extern "C" fn impulse_lsp_notify(
 ///
 /// `changes_json` should encode an array of LSP TextDocumentContentChangeEvent
 /// objects. Servers that did not advertise incremental sync receive `full_text`
-/// as a full-document change instead.
+/// as a full-document change instead. Returns -2, sending nothing, when the
+/// changes don't apply to the host copy of the document; the caller should
+/// resend with `full_text` set.
 #[no_mangle]
 pub extern "C" fn impulse_lsp_did_change(
     handle: *mut LspRegistryHandle,
@@ -660,7 +1104,14 @@ pub extern "C" fn impulse_lsp_did_change(
                     if let Some(full_text) = full_text {
                         *document = full_text;
                     } else {
-                        apply_lsp_content_changes_to_string(document, &changes);
+                        let mut updated = document.clone();
+                        if let Err(e) =
+                            impulse_core::lsp::apply_content_changes(&mut updated, &changes)
+                        {
+                            log::warn!("LSP document {} out of sync: {}", file_uri, e);
+                            return -2;
+                        }
+                        *document = updated;
                     }
                     let mut ok = false;
                     for client in clients {
@@ -679,7 +1130,106 @@ pub extern "C" fn impulse_lsp_did_change(
     )
 }
 
-/// Poll for LSP events (diagnostics, server lifecycle).
+/// JSON shape of an LSP event, shared by the poll function and callbacks.
+fn lsp_event_json(event: &impulse_core::lsp::LspEvent) -> serde_json::Value {
+    match event {
+        impulse_core::lsp::LspEvent::Diagnostics {
+            uri,
+            version,
+            diagnostics,
+        } => {
+            let diag_json: Vec<serde_json::Value> = diagnostics
+                .iter()
+                .map(|d| {
+                    serde_json::json!({
+                        "severity": d.severity.map(|s| match s {
+                            lsp_types::DiagnosticSeverity::ERROR => 1u8,
+                            lsp_types::DiagnosticSeverity::WARNING => 2,
+                            lsp_types::DiagnosticSeverity::INFORMATION => 3,
+                            lsp_types::DiagnosticSeverity::HINT => 4,
+                            _ => 1,
+                        }).unwrap_or(1),
+                        "startLine": d.range.start.line,
+                        "startColumn": d.range.start.character,
+                        "endLine": d.range.end.line,
+                        "endColumn": d.range.end.character,
+                        "message": d.message,
+                        "source": d.source,
+                    })
+                })
+                .collect();
+            serde_json::json!({
+                "type": "diagnostics",
+                "uri": uri,
+                "version": version,
+                "diagnostics": diag_json,
+            })
+        }
+        impulse_core::lsp::LspEvent::Initialized {
+            client_key,
+            server_id,
+        } => {
+            serde_json::json!({
+                "type": "initialized",
+                "clientKey": client_key,
+                "serverId": server_id,
+            })
+        }
+        impulse_core::lsp::LspEvent::ServerError {
+            client_key,
+            server_id,
+            message,
+        } => {
+            serde_json::json!({
+                "type": "serverError",
+                "clientKey": client_key,
+                "serverId": server_id,
+                "message": message,
+            })
+        }
+        impulse_core::lsp::LspEvent::ServerExited {
+            client_key,
+            server_id,
+        } => {
+            serde_json::json!({
+                "type": "serverExited",
+                "clientKey": client_key,
+                "serverId": server_id,
+            })
+        }
+        impulse_core::lsp::LspEvent::Progress {
+            client_key,
+            server_id,
+            token,
+            progress,
+        } => {
+            serde_json::json!({
+                "type": "progress",
+                "clientKey": client_key,
+                "serverId": server_id,
+                "token": token,
+                "progress": progress,
+            })
+        }
+        impulse_core::lsp::LspEvent::ApplyEdit {
+            client_key,
+            server_id,
+            label,
+            files,
+        } => {
+            serde_json::json!({
+                "type": "applyEdit",
+                "clientKey": client_key,
+                "serverId": server_id,
+                "label": label,
+                "files": files,
+            })
+        }
+    }
+}
+
+/// Poll for LSP events (diagnostics, server lifecycle, work-done progress,
+/// server-initiated workspace edits).
 ///
 /// Returns a JSON string describing the event, or null if no events are pending.
 /// The caller must free the returned string with `impulse_free_string`.
@@ -692,75 +1242,7 @@ pub extern "C" fn impulse_lsp_poll_event(handle: *mut LspRegistryHandle) -> *mut
                 let mut rx = inner.event_rx.lock();
 
                 match rx.try_recv() {
-                    Ok(event) => {
-                        let json = match event {
-                            impulse_core::lsp::LspEvent::Diagnostics {
-                                uri,
-                                version,
-                                diagnostics,
-                            } => {
-                                let diag_json: Vec<serde_json::Value> = diagnostics
-                                    .iter()
-                                    .map(|d| {
-                                        serde_json::json!({
-                                            "severity": d.severity.map(|s| match s {
-                                                lsp_types::DiagnosticSeverity::ERROR => 1u8,
-                                                lsp_types::DiagnosticSeverity::WARNING => 2,
-                                                lsp_types::DiagnosticSeverity::INFORMATION => 3,
-                                                lsp_types::DiagnosticSeverity::HINT => 4,
-                                                _ => 1,
-                                            }).unwrap_or(1),
-                                            "startLine": d.range.start.line,
-                                            "startColumn": d.range.start.character,
-                                            "endLine": d.range.end.line,
-                                            "endColumn": d.range.end.character,
-                                            "message": d.message,
-                                            "source": d.source,
-                                        })
-                                    })
-                                    .collect();
-                                serde_json::json!({
-                                    "type": "diagnostics",
-                                    "uri": uri,
-                                    "version": version,
-                                    "diagnostics": diag_json,
-                                })
-                            }
-                            impulse_core::lsp::LspEvent::Initialized {
-                                client_key,
-                                server_id,
-                            } => {
-                                serde_json::json!({
-                                    "type": "initialized",
-                                    "clientKey": client_key,
-                                    "serverId": server_id,
-                                })
-                            }
-                            impulse_core::lsp::LspEvent::ServerError {
-                                client_key,
-                                server_id,
-                                message,
-                            } => {
-                                serde_json::json!({
-                                    "type": "serverError",
-                                    "clientKey": client_key,
-                                    "serverId": server_id,
-                                    "message": message,
-                                })
-                            }
-                            impulse_core::lsp::LspEvent::ServerExited {
-                                client_key,
-                                server_id,
-                            } => {
-                                serde_json::json!({
-                                    "type": "serverExited",
-                                    "clientKey": client_key,
-                                    "serverId": server_id,
-                                })
-                            }
-                        };
-                        to_c_string(&json.to_string())
-                    }
+                    Ok(event) => to_c_string(&lsp_event_json(&event).to_string()),
                     Err(_) => std::ptr::null_mut(),
                 }
             })
@@ -768,9 +1250,101 @@ pub extern "C" fn impulse_lsp_poll_event(handle: *mut LspRegistryHandle) -> *mut
     )
 }
 
-/// Shut down all LSP servers managed by this registry.
+/// Deliver LSP events to `callback` instead of `impulse_lsp_poll_event`.
+/// See "Event callbacks" above for the threading contract. Passing a null
+/// callback unsubscribes. Replaces any previous callback. Returns 0 on
+/// success, -1 on an invalid handle.
 #[no_mangle]
-pub extern "C" fn impulse_lsp_shutdown_all(handle: *mut LspRegistryHandle) {
+pub extern "C" fn impulse_lsp_set_event_callback(
+    handle: *mut LspRegistryHandle,
+    callback: Option<ImpulseEventCallback>,
+    context: *mut c_void,
+) -> i32 {
+    ffi_catch(
+        -1,
+        AssertUnwindSafe(|| {
+            with_lsp_handle(handle, -1, |inner| {
+                match callback {
+                    Some(callback) => inner.events.subscribe(callback, context),
+                    None => inner.events.unsubscribe(),
+                }
+                0
+            })
+        }),
+    )
+}
+
+/// Remove the LSP event callback. Once this returns the callback will not be
+/// invoked again and its context may be released; events are queued for
+/// `impulse_lsp_poll_event` again.
+#[no_mangle]
+pub extern "C" fn impulse_lsp_clear_event_callback(handle: *mut LspRegistryHandle) {
+    ffi_catch(
+        (),
+        AssertUnwindSafe(|| {
+            with_lsp_handle(handle, (), |inner| inner.events.unsubscribe());
+        }),
+    );
+}
+
+/// Set the debounce intervals for completion, hover, signature help and code
+/// action requests. `debounce_json` is the `lsp_debounce` settings object;
+/// missing keys use their defaults. Returns 0 on success, -1 on error.
+#[no_mangle]
+pub extern "C" fn impulse_lsp_set_request_debounce(
+    handle: *mut LspRegistryHandle,
+    debounce_json: *const c_char,
+) -> i32 {
+    ffi_catch(
+        -1,
+        AssertUnwindSafe(|| {
+            let Some(json) = to_rust_str(debounce_json) else {
+                return -1;
+            };
+            let mut debounce: impulse_core::settings::LspDebounce =
+                match serde_json::from_str(&json) {
+                    Ok(debounce) => debounce,
+                    Err(e) => {
+                        log::warn!("Invalid LSP debounce settings: {}", e);
+                        return -1;
+                    }
+                };
+            debounce.validate();
+            with_lsp_handle(handle, -1, |inner| {
+                inner.registry.set_request_debounce(debounce);
+                0
+            })
+        }),
+    )
+}
+
+/// Stop using the servers in `server_ids_json` (a JSON array of server ids,
+/// the `disabled_lsp_servers` setting): they are no longer started and
+/// running instances are shut down. Returns 0 on success, -1 on error.
+#[no_mangle]
+pub extern "C" fn impulse_lsp_set_disabled_servers(
+    handle: *mut LspRegistryHandle,
+    server_ids_json: *const c_char,
+) -> i32 {
+    ffi_catch(
+        -1,
+        AssertUnwindSafe(|| {
+            let Some(server_ids) = string_list_arg(server_ids_json) else {
+                return -1;
+            };
+            with_lsp_handle(handle, -1, |inner| {
+                inner.runtime.block_on(async {
+                    inner.registry.set_disabled_servers(server_ids).await;
+                });
+                0
+            })
+        }),
+    )
+}
+
+/// Shut down all LSP servers managed by this registry.
+#[no_mangle]
+pub extern "C" fn impulse_lsp_shutdown_all(handle: *mut LspRegistryHandle) {
     ffi_catch(
         (),
         AssertUnwindSafe(|| {
@@ -800,6 +1374,7 @@ pub extern "C" fn impulse_lsp_registry_free(handle: *mut LspRegistryHandle) {
                 reg.remove(&key)
             };
             if let Some(inner) = inner {
+                inner.events.shutdown();
                 inner.runtime.block_on(async {
                     inner.registry.shutdown_all().await;
                 });
@@ -853,7 +1428,8 @@ pub extern "C" fn impulse_lsp_check_status() -> *mut c_char {
     )
 }
 
-/// Install managed web LSP servers.
+/// Install managed web LSP servers, blocking until npm finishes. Prefer
+/// `impulse_lsp_install_start`, which reports progress.
 ///
 /// Returns the installation root path on success, or an error string prefixed
 /// with "ERROR:" on failure.
@@ -862,15 +1438,199 @@ pub extern "C" fn impulse_lsp_check_status() -> *mut c_char {
 pub extern "C" fn impulse_lsp_install() -> *mut c_char {
     ffi_catch(
         std::ptr::null_mut(),
-        AssertUnwindSafe(
-            || match impulse_core::lsp::install_managed_web_lsp_servers() {
-                Ok(path) => to_c_string(&path.to_string_lossy()),
+        AssertUnwindSafe(|| {
+            let result = impulse_core::lsp_install::run(
+                impulse_core::lsp_install::InstallAction::Install,
+                &[],
+                &[],
+                |_| {},
+            );
+            match result {
+                Ok(_) => match impulse_core::lsp::managed_lsp_bin_dir() {
+                    Some(path) => to_c_string(&path.to_string_lossy()),
+                    None => to_c_string("ERROR:Managed bin directory could not be determined"),
+                },
                 Err(e) => to_c_string(&format!("ERROR:{}", e)),
-            },
-        ),
+            }
+        }),
+    )
+}
+
+/// Parse an optional JSON array of strings; null means an empty list.
+fn string_list_arg(json: *const c_char) -> Option<Vec<String>> {
+    if json.is_null() {
+        return Some(Vec::new());
+    }
+    let json = to_rust_str(json)?;
+    serde_json::from_str(&json)
+        .map_err(|e| log::warn!("Expected a JSON array of strings: {}", e))
+        .ok()
+}
+
+/// Installed and pinned versions of the managed npm packages, as a JSON
+/// array of objects with `name`, `pinned_version`, `installed_version`
+/// (null when not installed), `servers` and `enabled`. `disabled_json` is
+/// the `disabled_lsp_servers` setting (a JSON array, or null).
+/// The caller must free the returned string with `impulse_free_string`.
+#[no_mangle]
+pub extern "C" fn impulse_lsp_managed_status(disabled_json: *const c_char) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let disabled = string_list_arg(disabled_json).unwrap_or_default();
+            let statuses = impulse_core::lsp_install::package_status(&disabled);
+            to_c_string(&json_or_error(
+                serde_json::to_string(&statuses).map_err(|e| e.to_string()),
+            ))
+        }),
+    )
+}
+
+/// Like `impulse_lsp_managed_status`, but only the enabled packages whose
+/// installed version differs from the pin.
+/// The caller must free the returned string with `impulse_free_string`.
+#[no_mangle]
+pub extern "C" fn impulse_lsp_check_for_updates(disabled_json: *const c_char) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let disabled = string_list_arg(disabled_json).unwrap_or_default();
+            let updates = impulse_core::lsp_install::check_for_updates(&disabled);
+            to_c_string(&json_or_error(
+                serde_json::to_string(&updates).map_err(|e| e.to_string()),
+            ))
+        }),
+    )
+}
+
+// Installer progress: `impulse_lsp_install_start` runs npm in the
+// background and reports `{"type": "started" | "output" | "finished", ...}`
+// events (see `impulse_core::lsp_install::InstallEvent`) to the callback set
+// with `impulse_lsp_install_set_callback` (same threading contract as "Event
+// callbacks" above) or, with no callback, through
+// `impulse_lsp_install_poll_event`.
+
+/// Maximum number of installer events queued for polling; npm output beyond
+/// this is dropped, `finished` events never are.
+const LSP_INSTALL_EVENT_QUEUE_CAPACITY: usize = 1_000;
+
+fn lsp_install_events() -> &'static EventDispatcher {
+    static EVENTS: OnceLock<EventDispatcher> = OnceLock::new();
+    EVENTS.get_or_init(|| EventDispatcher::new("impulse-lsp-install-events"))
+}
+
+fn lsp_install_queue() -> &'static parking_lot::Mutex<std::collections::VecDeque<String>> {
+    static QUEUE: OnceLock<parking_lot::Mutex<std::collections::VecDeque<String>>> =
+        OnceLock::new();
+    QUEUE.get_or_init(|| parking_lot::Mutex::new(std::collections::VecDeque::new()))
+}
+
+static LSP_INSTALL_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// Start an installer action in the background. `action` is `"install"`,
+/// `"update"` or `"uninstall"`; `packages_json` is a JSON array of package
+/// names, or null for every applicable package; `disabled_json` is the
+/// `disabled_lsp_servers` setting. Returns false if the arguments are
+/// invalid or another action is still running.
+#[no_mangle]
+pub extern "C" fn impulse_lsp_install_start(
+    action: *const c_char,
+    packages_json: *const c_char,
+    disabled_json: *const c_char,
+) -> bool {
+    ffi_catch(
+        false,
+        AssertUnwindSafe(|| {
+            let Some(action) = to_rust_str(action).and_then(|action| {
+                serde_json::from_value::<impulse_core::lsp_install::InstallAction>(
+                    serde_json::Value::String(action),
+                )
+                .ok()
+            }) else {
+                return false;
+            };
+            let (Some(packages), Some(disabled)) = (
+                string_list_arg(packages_json),
+                string_list_arg(disabled_json),
+            ) else {
+                return false;
+            };
+            if LSP_INSTALL_RUNNING.swap(true, Ordering::SeqCst) {
+                return false;
+            }
+
+            let rx = impulse_core::lsp_install::spawn(action, packages, disabled);
+            let spawned = std::thread::Builder::new()
+                .name("impulse-lsp-install-forward".to_string())
+                .spawn(move || {
+                    for event in rx {
+                        let finished = matches!(
+                            event,
+                            impulse_core::lsp_install::InstallEvent::Finished { .. }
+                        );
+                        if finished {
+                            LSP_INSTALL_RUNNING.store(false, Ordering::SeqCst);
+                        }
+                        let json = match serde_json::to_string(&event) {
+                            Ok(json) => json,
+                            Err(_) => continue,
+                        };
+                        if lsp_install_events().send(json.clone()) {
+                            continue;
+                        }
+                        let mut queue = lsp_install_queue().lock();
+                        if finished || queue.len() < LSP_INSTALL_EVENT_QUEUE_CAPACITY {
+                            queue.push_back(json);
+                        }
+                    }
+                    LSP_INSTALL_RUNNING.store(false, Ordering::SeqCst);
+                });
+            if let Err(e) = spawned {
+                log::error!("Failed to spawn LSP installer thread: {}", e);
+                LSP_INSTALL_RUNNING.store(false, Ordering::SeqCst);
+                return false;
+            }
+            true
+        }),
+    )
+}
+
+/// Take the next queued installer event, or null if there is none.
+/// The caller must free the returned string with `impulse_free_string`.
+#[no_mangle]
+pub extern "C" fn impulse_lsp_install_poll_event() -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| match lsp_install_queue().lock().pop_front() {
+            Some(json) => to_c_string(&json),
+            None => std::ptr::null_mut(),
+        }),
     )
 }
 
+/// Deliver installer events to `callback` instead of
+/// `impulse_lsp_install_poll_event`. A null callback unsubscribes.
+#[no_mangle]
+pub extern "C" fn impulse_lsp_install_set_callback(
+    callback: Option<ImpulseEventCallback>,
+    context: *mut c_void,
+) {
+    ffi_catch(
+        (),
+        AssertUnwindSafe(|| match callback {
+            Some(callback) => lsp_install_events().subscribe(callback, context),
+            None => lsp_install_events().unsubscribe(),
+        }),
+    );
+}
+
+/// Remove the installer callback. Once this returns the callback will not be
+/// invoked again and its context may be released.
+#[no_mangle]
+pub extern "C" fn impulse_lsp_install_clear_callback() {
+    ffi_catch((), AssertUnwindSafe(|| lsp_install_events().unsubscribe()));
+}
+
 /// Check whether npm is available on the system PATH.
 #[no_mangle]
 pub extern "C" fn impulse_npm_is_available() -> bool {
@@ -994,6 +1754,20 @@ pub extern "C" fn impulse_git_blame(file_path: *const c_char, line: u32) -> *mut
     )
 }
 
+/// Recompute the cached git diff and blame of a file in the background,
+/// e.g. after saving it.
+#[no_mangle]
+pub extern "C" fn impulse_git_refresh_file_caches(file_path: *const c_char) {
+    ffi_catch(
+        (),
+        AssertUnwindSafe(|| {
+            if let Some(file_path) = to_rust_str(file_path) {
+                impulse_core::git::refresh_file_caches(&file_path);
+            }
+        }),
+    )
+}
+
 /// Discard working-tree changes for a single file, restoring it to the HEAD version.
 /// `workspace_root` is used to validate that the file is within the workspace.
 ///
@@ -1094,6 +1868,36 @@ pub extern "C" fn impulse_get_all_git_statuses(path: *const c_char) -> *mut c_ch
     )
 }
 
+/// Read directory contents as a JSON array of `FileEntry` objects, without
+/// git status (frontends fetch that for the whole repo in one batch).
+/// Honors `files_exclude` and marks gitignored entries with `is_ignored`.
+/// Returns null on error.
+/// The caller must free the returned string with `impulse_free_string`.
+#[no_mangle]
+pub extern "C" fn impulse_read_directory_entries(
+    path: *const c_char,
+    show_hidden: bool,
+) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let Some(path) = to_rust_str(path) else {
+                return std::ptr::null_mut();
+            };
+            match impulse_core::filesystem::read_directory_entries(&path, show_hidden) {
+                Ok(entries) => match serde_json::to_string(&entries) {
+                    Ok(json) => to_c_string(&json),
+                    Err(e) => {
+                        log::error!("JSON serialization failed: {}", e);
+                        std::ptr::null_mut()
+                    }
+                },
+                Err(_) => std::ptr::null_mut(),
+            }
+        }),
+    )
+}
+
 /// Read directory contents with git status enrichment as a JSON array.
 ///
 /// Returns a JSON array of `FileEntry` objects, each with `name`, `path`,
@@ -1375,475 +2179,3177 @@ pub extern "C" fn impulse_git_commit_all(
                 None => return std::ptr::null_mut(),
             };
 
-            let result = match impulse_core::git::commit_all(&repo_path, &message) {
-                Ok(oid) => serde_json::json!({
-                    "ok": true,
-                    "oid": oid,
-                    "error": serde_json::Value::Null,
-                }),
-                Err(e) => serde_json::json!({
-                    "ok": false,
-                    "oid": serde_json::Value::Null,
-                    "error": e,
-                }),
-            };
-            to_c_string(&result.to_string())
+            commit_result(impulse_core::git::commit_all(&repo_path, &message))
         }),
     )
 }
 
-/// Discards changes for a single REPO-RELATIVE path, reverting it to a clean
-/// state: tracked modified/deleted files are checked out from HEAD, while
-/// untracked/new files are deleted from disk.
+/// `{ok, oid, error}` JSON for a commit result.
+fn commit_result(result: Result<String, String>) -> *mut c_char {
+    let json = match result {
+        Ok(oid) => serde_json::json!({
+            "ok": true,
+            "oid": oid,
+            "error": serde_json::Value::Null,
+        }),
+        Err(e) => serde_json::json!({
+            "ok": false,
+            "oid": serde_json::Value::Null,
+            "error": e,
+        }),
+    };
+    to_c_string(&json.to_string())
+}
+
+/// `{ok, error}` JSON for an operation without a result value.
+fn unit_result(result: Result<(), String>) -> *mut c_char {
+    let json = match result {
+        Ok(()) => serde_json::json!({"ok": true, "error": serde_json::Value::Null}),
+        Err(e) => serde_json::json!({"ok": false, "error": e}),
+    };
+    to_c_string(&json.to_string())
+}
+
+/// Returns the repository status for `repo_path` as JSON:
+/// `{ "repo_root", "branch", "ahead_behind": {ahead, behind, upstream}|null,
+/// "entries": [{ "path", "index", "worktree", "old_path" }] }`, where
+/// `index` / `worktree` are status letters or null. On failure returns
+/// `{"error": ...}`.
 ///
-/// Returns 0 on success or -1 on error.
+/// The caller must free the returned string with `impulse_free_string`.
 #[no_mangle]
-pub extern "C" fn impulse_git_discard_path(
-    repo_path: *const c_char,
-    file_path: *const c_char,
-) -> i32 {
+pub extern "C" fn impulse_git_status(repo_path: *const c_char) -> *mut c_char {
     ffi_catch(
-        -1,
+        std::ptr::null_mut(),
         AssertUnwindSafe(|| {
-            let repo_path = match to_rust_str(repo_path) {
-                Some(s) => s,
-                None => return -1,
-            };
-            let file_path = match to_rust_str(file_path) {
-                Some(s) => s,
-                None => return -1,
+            let Some(repo_path) = to_rust_str(repo_path) else {
+                return std::ptr::null_mut();
             };
-
-            match impulse_core::git::discard_path(&repo_path, &file_path) {
-                Ok(()) => 0,
-                Err(_) => -1,
-            }
+            let json = json_or_error(
+                impulse_core::git::repo_status(&repo_path)
+                    .and_then(|r| serde_json::to_string(&r).map_err(|e| e.to_string())),
+            );
+            to_c_string(&json)
         }),
     )
 }
 
-// ---------------------------------------------------------------------------
-// Markdown preview
-// ---------------------------------------------------------------------------
+/// Parse a JSON array of repo-relative paths.
+fn paths_from_json(paths_json: *const c_char) -> Result<Vec<String>, String> {
+    let json = to_rust_str(paths_json).ok_or("invalid paths")?;
+    serde_json::from_str(&json).map_err(|e| format!("invalid paths: {}", e))
+}
 
-/// Render markdown source to a full HTML document with themed CSS and highlight.js.
+/// Stages the REPO-RELATIVE paths in the JSON array `paths_json` (missing
+/// files are staged as deletions). Returns `{ok, error}` JSON.
 ///
-/// `source` — the markdown text to render.
-/// `theme_json` — JSON-serialized `MarkdownThemeColors`.
-/// `highlight_js_path` — absolute file:// path or URL to highlight.min.js.
-///
-/// Returns a newly allocated HTML string (caller must free with `impulse_free_string`),
-/// or null on failure.
+/// The caller must free the returned string with `impulse_free_string`.
 #[no_mangle]
-pub extern "C" fn impulse_render_markdown_preview(
-    source: *const c_char,
-    theme_json: *const c_char,
-    highlight_js_path: *const c_char,
+pub extern "C" fn impulse_git_stage(
+    repo_path: *const c_char,
+    paths_json: *const c_char,
 ) -> *mut c_char {
     ffi_catch(
         std::ptr::null_mut(),
         AssertUnwindSafe(|| {
-            let source = match to_rust_str(source) {
-                Some(s) => s,
-                None => return std::ptr::null_mut(),
-            };
-            let theme_json = match to_rust_str(theme_json) {
-                Some(s) => s,
-                None => return std::ptr::null_mut(),
-            };
-            let hljs_path = match to_rust_str(highlight_js_path) {
-                Some(s) => s,
-                None => return std::ptr::null_mut(),
-            };
-            let theme: impulse_editor::markdown::MarkdownThemeColors =
-                match serde_json::from_str(&theme_json) {
-                    Ok(t) => t,
-                    Err(e) => {
-                        log::error!("Failed to parse MarkdownThemeColors: {}", e);
-                        return std::ptr::null_mut();
-                    }
-                };
-            let html = match impulse_editor::markdown::render_markdown_preview(
-                &source, &theme, &hljs_path,
-            ) {
-                Some(h) => h,
-                None => return std::ptr::null_mut(),
+            let Some(repo_path) = to_rust_str(repo_path) else {
+                return std::ptr::null_mut();
             };
-            to_c_string(&html)
+            unit_result(
+                paths_from_json(paths_json)
+                    .and_then(|paths| impulse_core::git::stage_paths(&repo_path, &paths)),
+            )
+        }),
+    )
+}
+
+/// Unstages the REPO-RELATIVE paths in the JSON array `paths_json`, leaving
+/// the working tree untouched. Returns `{ok, error}` JSON.
+///
+/// The caller must free the returned string with `impulse_free_string`.
+#[no_mangle]
+pub extern "C" fn impulse_git_unstage(
+    repo_path: *const c_char,
+    paths_json: *const c_char,
+) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let Some(repo_path) = to_rust_str(repo_path) else {
+                return std::ptr::null_mut();
+            };
+            unit_result(
+                paths_from_json(paths_json)
+                    .and_then(|paths| impulse_core::git::unstage_paths(&repo_path, &paths)),
+            )
+        }),
+    )
+}
+
+/// Commits only the staged changes with `message`. Returns the same
+/// `{ok, oid, error}` JSON as `impulse_git_commit_all`.
+///
+/// The caller must free the returned string with `impulse_free_string`.
+#[no_mangle]
+pub extern "C" fn impulse_git_commit(
+    repo_path: *const c_char,
+    message: *const c_char,
+) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let (Some(repo_path), Some(message)) = (to_rust_str(repo_path), to_rust_str(message))
+            else {
+                return std::ptr::null_mut();
+            };
+            commit_result(impulse_core::git::commit_staged(&repo_path, &message))
+        }),
+    )
+}
+
+/// Returns up to `limit` commits reachable from HEAD, newest first, as a JSON
+/// array of `{ "commit_hash", "short_hash", "author", "timestamp", "summary" }`.
+/// On failure returns `{"error": ...}`. See `impulse_git_log_async` for a
+/// non-blocking variant.
+///
+/// The caller must free the returned string with `impulse_free_string`.
+#[no_mangle]
+pub extern "C" fn impulse_git_log(repo_path: *const c_char, limit: u32) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let Some(repo_path) = to_rust_str(repo_path) else {
+                return std::ptr::null_mut();
+            };
+            let json = json_or_error(
+                impulse_core::git::commit_log(&repo_path, limit as usize, None)
+                    .and_then(|r| serde_json::to_string(&r).map_err(|e| e.to_string())),
+            );
+            to_c_string(&json)
+        }),
+    )
+}
+
+/// Switches to branch `name`; a remote branch such as `origin/feature` is
+/// checked out as a new tracking branch. Fails without touching files if
+/// local changes would be overwritten. Returns `{ok, error}` JSON.
+///
+/// The caller must free the returned string with `impulse_free_string`.
+#[no_mangle]
+pub extern "C" fn impulse_git_checkout(
+    repo_path: *const c_char,
+    name: *const c_char,
+) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let (Some(repo_path), Some(name)) = (to_rust_str(repo_path), to_rust_str(name)) else {
+                return std::ptr::null_mut();
+            };
+            unit_result(impulse_core::git::checkout_branch(&repo_path, &name))
+        }),
+    )
+}
+
+/// Discards changes for a single REPO-RELATIVE path, reverting it to a clean
+/// state: tracked modified/deleted files are checked out from HEAD, while
+/// untracked/new files are deleted from disk.
+///
+/// Returns 0 on success or -1 on error.
+#[no_mangle]
+pub extern "C" fn impulse_git_discard_path(
+    repo_path: *const c_char,
+    file_path: *const c_char,
+) -> i32 {
+    ffi_catch(
+        -1,
+        AssertUnwindSafe(|| {
+            let repo_path = match to_rust_str(repo_path) {
+                Some(s) => s,
+                None => return -1,
+            };
+            let file_path = match to_rust_str(file_path) {
+                Some(s) => s,
+                None => return -1,
+            };
+
+            match impulse_core::git::discard_path(&repo_path, &file_path) {
+                Ok(()) => 0,
+                Err(_) => -1,
+            }
+        }),
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Async operations
+// ---------------------------------------------------------------------------
+//
+// Slow calls (content search, LSP requests, git log) also come in `_async`
+// variants that return a nonzero operation id immediately instead of
+// blocking the caller's thread; 0 means the operation could not be started.
+// When the operation finishes, its result is delivered as
+// `{"op_id": <id>, "result": <the sync variant's JSON>}` either to the
+// callback set with `impulse_operations_set_callback` (same threading
+// contract as "Event callbacks" above) or, with no callback set, through
+// `impulse_operation_poll`. `impulse_cancel` aborts an operation; a cancelled
+// operation never delivers a result.
+
+use std::sync::atomic::AtomicU64;
+
+/// A started operation that has not yet been collected or cancelled.
+struct Operation {
+    /// Stops blocking work (search, git walks) that cannot be aborted.
+    cancel: Arc<AtomicBool>,
+    abort: Option<tokio::task::AbortHandle>,
+    /// Completed result awaiting `impulse_operation_poll`.
+    result: Option<String>,
+}
+
+fn operations() -> &'static parking_lot::Mutex<HashMap<u64, Operation>> {
+    static OPERATIONS: OnceLock<parking_lot::Mutex<HashMap<u64, Operation>>> = OnceLock::new();
+    OPERATIONS.get_or_init(|| parking_lot::Mutex::new(HashMap::new()))
+}
+
+fn operation_events() -> &'static EventDispatcher {
+    static EVENTS: OnceLock<EventDispatcher> = OnceLock::new();
+    EVENTS.get_or_init(|| EventDispatcher::new("impulse-operation-events"))
+}
+
+/// Runtime for operations not tied to an LSP registry.
+fn operation_runtime() -> Option<&'static Runtime> {
+    static RUNTIME: OnceLock<Option<Runtime>> = OnceLock::new();
+    RUNTIME
+        .get_or_init(|| {
+            tokio::runtime::Builder::new_multi_thread()
+                .worker_threads(2)
+                .thread_name("impulse-operations")
+                .enable_all()
+                .build()
+                .map_err(|e| log::error!("Failed to create operation runtime: {}", e))
+                .ok()
+        })
+        .as_ref()
+}
+
+/// Register a new operation and spawn `work` for it on `runtime`. `work`
+/// receives the operation's cancel flag and returns the result JSON.
+fn start_operation<F, Fut>(runtime: &tokio::runtime::Handle, work: F) -> u64
+where
+    F: FnOnce(Arc<AtomicBool>) -> Fut,
+    Fut: std::future::Future<Output = String> + Send + 'static,
+{
+    static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    let cancel = Arc::new(AtomicBool::new(false));
+    operations().lock().insert(
+        id,
+        Operation {
+            cancel: Arc::clone(&cancel),
+            abort: None,
+            result: None,
+        },
+    );
+    let future = work(cancel);
+    let task = runtime.spawn(async move {
+        let result = future.await;
+        finish_operation(id, result);
+    });
+    // The task may already have finished (and removed the entry).
+    if let Some(op) = operations().lock().get_mut(&id) {
+        op.abort = Some(task.abort_handle());
+    }
+    id
+}
+
+/// Deliver a finished operation's result, unless it was cancelled.
+fn finish_operation(id: u64, result: String) {
+    let mut ops = operations().lock();
+    let Some(op) = ops.get_mut(&id) else {
+        return;
+    };
+    let json = format!("{{\"op_id\":{},\"result\":{}}}", id, result);
+    if operation_events().send(json.clone()) {
+        ops.remove(&id);
+    } else {
+        op.result = Some(json);
+    }
+}
+
+/// Run blocking `work` on the operation runtime's blocking pool.
+fn start_blocking_operation(work: impl FnOnce(&AtomicBool) -> String + Send + 'static) -> u64 {
+    let Some(runtime) = operation_runtime() else {
+        return 0;
+    };
+    start_operation(runtime.handle(), |cancel| async move {
+        tokio::task::spawn_blocking(move || work(&cancel))
+            .await
+            .unwrap_or_else(|e| serde_json::json!({"error": e.to_string()}).to_string())
+    })
+}
+
+/// Deliver completed operation results to `callback` instead of
+/// `impulse_operation_poll`. A null callback unsubscribes.
+#[no_mangle]
+pub extern "C" fn impulse_operations_set_callback(
+    callback: Option<ImpulseEventCallback>,
+    context: *mut c_void,
+) {
+    ffi_catch(
+        (),
+        AssertUnwindSafe(|| match callback {
+            Some(callback) => operation_events().subscribe(callback, context),
+            None => operation_events().unsubscribe(),
+        }),
+    );
+}
+
+/// Remove the operation callback. Once this returns the callback will not be
+/// invoked again and its context may be released.
+#[no_mangle]
+pub extern "C" fn impulse_operations_clear_callback() {
+    ffi_catch((), AssertUnwindSafe(|| operation_events().unsubscribe()));
+}
+
+/// Collect the result of operation `op_id` if it has finished. Returns the
+/// `{"op_id", "result"}` JSON once, then forgets the operation; returns null
+/// while it is still running or if the id is unknown.
+/// The caller must free the returned string with `impulse_free_string`.
+#[no_mangle]
+pub extern "C" fn impulse_operation_poll(op_id: u64) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let mut ops = operations().lock();
+            match ops.get(&op_id).and_then(|op| op.result.clone()) {
+                Some(json) => {
+                    ops.remove(&op_id);
+                    to_c_string(&json)
+                }
+                None => std::ptr::null_mut(),
+            }
+        }),
+    )
+}
+
+/// Cancel operation `op_id`: aborts its task and drops any pending result.
+/// Returns 0 if the operation was pending, -1 if the id is unknown (already
+/// collected, delivered, or cancelled).
+#[no_mangle]
+pub extern "C" fn impulse_cancel(op_id: u64) -> i32 {
+    ffi_catch(
+        -1,
+        AssertUnwindSafe(|| match operations().lock().remove(&op_id) {
+            Some(op) => {
+                op.cancel.store(true, Ordering::Relaxed);
+                if let Some(abort) = op.abort {
+                    abort.abort();
+                }
+                0
+            }
+            None => -1,
+        }),
+    )
+}
+
+/// Async variant of `impulse_search_content`.
+#[no_mangle]
+pub extern "C" fn impulse_search_content_async(
+    root: *const c_char,
+    query: *const c_char,
+    case_sensitive: bool,
+) -> u64 {
+    ffi_catch(
+        0,
+        AssertUnwindSafe(|| {
+            let (Some(root), Some(query)) = (to_rust_str(root), to_rust_str(query)) else {
+                return 0;
+            };
+            start_blocking_operation(move |cancel| {
+                json_or_error(
+                    impulse_core::search::search_contents(
+                        &root,
+                        &query,
+                        500,
+                        case_sensitive,
+                        Some(cancel),
+                    )
+                    .and_then(|r| serde_json::to_string(&r).map_err(|e| e.to_string())),
+                )
+            })
+        }),
+    )
+}
+
+/// List up to `limit` commits reachable from HEAD, newest first, as a JSON
+/// array of `CommitSummary` objects (delivered as an operation result).
+#[no_mangle]
+pub extern "C" fn impulse_git_log_async(repo_path: *const c_char, limit: u32) -> u64 {
+    ffi_catch(
+        0,
+        AssertUnwindSafe(|| {
+            let Some(repo_path) = to_rust_str(repo_path) else {
+                return 0;
+            };
+            start_blocking_operation(move |cancel| {
+                json_or_error(
+                    impulse_core::git::commit_log(&repo_path, limit as usize, Some(cancel))
+                        .and_then(|r| serde_json::to_string(&r).map_err(|e| e.to_string())),
+                )
+            })
+        }),
+    )
+}
+
+/// Async variant of `impulse_lsp_request`. Runs on the registry's runtime,
+/// so freeing the registry also stops its pending requests (cancel their ids
+/// to forget them).
+#[no_mangle]
+pub extern "C" fn impulse_lsp_request_async(
+    handle: *mut LspRegistryHandle,
+    language_id: *const c_char,
+    file_uri: *const c_char,
+    method: *const c_char,
+    params_json: *const c_char,
+) -> u64 {
+    ffi_catch(
+        0,
+        AssertUnwindSafe(|| {
+            let (Some(language_id), Some(file_uri), Some(method)) = (
+                to_rust_str(language_id),
+                to_rust_str(file_uri),
+                to_rust_str(method),
+            ) else {
+                return 0;
+            };
+            let params: Option<serde_json::Value> =
+                to_rust_str(params_json).and_then(|s| serde_json::from_str(&s).ok());
+
+            with_lsp_handle(handle, 0, |inner| {
+                let registry = Arc::clone(&inner.registry);
+                start_operation(inner.runtime.handle(), move |_| async move {
+                    lsp_request_json(&registry, &language_id, &file_uri, &method, params).await
+                })
+            })
+        }),
+    )
+}
+
+// ---------------------------------------------------------------------------
+// File watching
+// ---------------------------------------------------------------------------
+
+/// Opaque handle type for a file watcher. Never dereferenced — only used as a
+/// registry key, like `LspRegistryHandle`.
+pub struct WatchHandle {
+    _private: u8,
+}
+
+/// Maximum number of watch events queued for `impulse_watch_poll_event`.
+const WATCH_EVENT_CHANNEL_CAPACITY: usize = 10_000;
+
+struct WatchInner {
+    _watcher: impulse_core::watch::FileWatcher,
+    event_rx: crossbeam_channel::Receiver<String>,
+    events: Arc<EventDispatcher>,
+}
+
+fn watch_handle_registry() -> &'static parking_lot::Mutex<HashMap<usize, Arc<WatchInner>>> {
+    static REGISTRY: OnceLock<parking_lot::Mutex<HashMap<usize, Arc<WatchInner>>>> =
+        OnceLock::new();
+    REGISTRY.get_or_init(|| parking_lot::Mutex::new(HashMap::new()))
+}
+
+fn with_watch_handle<T>(
+    handle: *mut WatchHandle,
+    default: T,
+    f: impl FnOnce(&WatchInner) -> T,
+) -> T {
+    if handle.is_null() {
+        return default;
+    }
+    let inner = watch_handle_registry()
+        .lock()
+        .get(&(handle as usize))
+        .cloned();
+    match inner {
+        Some(inner) => f(&inner),
+        None => {
+            log::warn!("Attempted to use invalid or freed watch handle");
+            default
+        }
+    }
+}
+
+/// Start watching `root` recursively. Events are JSON objects
+/// `{"kind": "created"|"modified"|"deleted"|"renamed", "paths": [..]}` with
+/// absolute paths (`[from, to]` for renames); changes inside `.git` are not
+/// reported. Returns null if `root` is not a directory or cannot be watched.
+/// Free with `impulse_watch_free`.
+#[no_mangle]
+pub extern "C" fn impulse_watch_create(root: *const c_char) -> *mut WatchHandle {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let Some(root) = to_rust_str(root) else {
+                return std::ptr::null_mut();
+            };
+            let (tx, rx) = crossbeam_channel::bounded(WATCH_EVENT_CHANNEL_CAPACITY);
+            let events = Arc::new(EventDispatcher::new("impulse-watch-events"));
+            let forward_events = Arc::clone(&events);
+            let watcher = impulse_core::watch::FileWatcher::new(&root, move |event| {
+                let json = match serde_json::to_string(&event) {
+                    Ok(json) => json,
+                    Err(e) => {
+                        log::error!("JSON serialization failed: {}", e);
+                        return;
+                    }
+                };
+                if forward_events.is_subscribed() && forward_events.send(json.clone()) {
+                    return;
+                }
+                if tx.try_send(json).is_err() {
+                    log::warn!("Watch event channel full, dropping event");
+                }
+            });
+            let watcher = match watcher {
+                Ok(watcher) => watcher,
+                Err(e) => {
+                    log::warn!("{}", e);
+                    return std::ptr::null_mut();
+                }
+            };
+            let handle = Box::into_raw(Box::new(WatchHandle { _private: 0 }));
+            watch_handle_registry().lock().insert(
+                handle as usize,
+                Arc::new(WatchInner {
+                    _watcher: watcher,
+                    event_rx: rx,
+                    events,
+                }),
+            );
+            handle
+        }),
+    )
+}
+
+/// Poll for the next watch event. Returns null when none is queued.
+/// The caller must free the returned string with `impulse_free_string`.
+#[no_mangle]
+pub extern "C" fn impulse_watch_poll_event(handle: *mut WatchHandle) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            with_watch_handle(handle, std::ptr::null_mut(), |inner| {
+                match inner.event_rx.try_recv() {
+                    Ok(json) => to_c_string(&json),
+                    Err(_) => std::ptr::null_mut(),
+                }
+            })
+        }),
+    )
+}
+
+/// Deliver watch events to `callback` instead of `impulse_watch_poll_event`.
+/// See "Event callbacks" above for the threading contract. Passing a null
+/// callback unsubscribes. Returns 0 on success, -1 on an invalid handle.
+#[no_mangle]
+pub extern "C" fn impulse_watch_set_event_callback(
+    handle: *mut WatchHandle,
+    callback: Option<ImpulseEventCallback>,
+    context: *mut c_void,
+) -> i32 {
+    ffi_catch(
+        -1,
+        AssertUnwindSafe(|| {
+            with_watch_handle(handle, -1, |inner| {
+                match callback {
+                    Some(callback) => inner.events.subscribe(callback, context),
+                    None => inner.events.unsubscribe(),
+                }
+                0
+            })
+        }),
+    )
+}
+
+/// Remove the watch event callback. Once this returns the callback will not
+/// be invoked again and its context may be released.
+#[no_mangle]
+pub extern "C" fn impulse_watch_clear_event_callback(handle: *mut WatchHandle) {
+    ffi_catch(
+        (),
+        AssertUnwindSafe(|| {
+            with_watch_handle(handle, (), |inner| inner.events.unsubscribe());
+        }),
+    );
+}
+
+/// Stop watching and free the handle. After this returns no callback for
+/// the handle is invoked again.
+#[no_mangle]
+pub extern "C" fn impulse_watch_free(handle: *mut WatchHandle) {
+    ffi_catch(
+        (),
+        AssertUnwindSafe(|| {
+            if handle.is_null() {
+                return;
+            }
+            let Some(inner) = watch_handle_registry().lock().remove(&(handle as usize)) else {
+                log::warn!("impulse_watch_free called on already-freed handle");
+                return;
+            };
+            inner.events.shutdown();
+            // SAFETY: `handle` was allocated by `Box::into_raw` in
+            // `impulse_watch_create`; the registry removal above ensures this
+            // only happens once per handle.
+            unsafe {
+                drop(Box::from_raw(handle));
+            }
+        }),
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Markdown preview
+// ---------------------------------------------------------------------------
+
+/// Render markdown source to a full HTML document with themed CSS and highlight.js.
+///
+/// `source` — the markdown text to render.
+/// `theme_json` — JSON-serialized `MarkdownThemeColors`.
+/// `highlight_js_path` — absolute file:// path or URL to highlight.min.js.
+///
+/// Returns a newly allocated HTML string (caller must free with `impulse_free_string`),
+/// or null on failure.
+#[no_mangle]
+pub extern "C" fn impulse_render_markdown_preview(
+    source: *const c_char,
+    theme_json: *const c_char,
+    highlight_js_path: *const c_char,
+) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let source = match to_rust_str(source) {
+                Some(s) => s,
+                None => return std::ptr::null_mut(),
+            };
+            let theme_json = match to_rust_str(theme_json) {
+                Some(s) => s,
+                None => return std::ptr::null_mut(),
+            };
+            let hljs_path = match to_rust_str(highlight_js_path) {
+                Some(s) => s,
+                None => return std::ptr::null_mut(),
+            };
+            let theme: impulse_editor::markdown::MarkdownThemeColors =
+                match serde_json::from_str(&theme_json) {
+                    Ok(t) => t,
+                    Err(e) => {
+                        log::error!("Failed to parse MarkdownThemeColors: {}", e);
+                        return std::ptr::null_mut();
+                    }
+                };
+            let html = match impulse_editor::markdown::render_markdown_preview(
+                &source, &theme, &hljs_path,
+            ) {
+                Some(h) => h,
+                None => return std::ptr::null_mut(),
+            };
+            to_c_string(&html)
         }),
     )
 }
 
 /// Check whether a file path has a markdown extension.
 #[no_mangle]
-pub extern "C" fn impulse_is_markdown_file(path: *const c_char) -> bool {
+pub extern "C" fn impulse_is_markdown_file(path: *const c_char) -> bool {
+    ffi_catch(
+        false,
+        AssertUnwindSafe(|| {
+            let path = match to_rust_str(path) {
+                Some(s) => s,
+                None => return false,
+            };
+            impulse_editor::markdown::is_markdown_file(&path)
+        }),
+    )
+}
+
+/// Render an SVG source string to a themed HTML preview document.
+///
+/// Returns a newly allocated HTML string (caller must free with `impulse_free_string`),
+/// or null on failure.
+#[no_mangle]
+pub extern "C" fn impulse_render_svg_preview(
+    source: *const c_char,
+    bg_color: *const c_char,
+) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let source = match to_rust_str(source) {
+                Some(s) => s,
+                None => return std::ptr::null_mut(),
+            };
+            let bg_color = match to_rust_str(bg_color) {
+                Some(s) => s,
+                None => return std::ptr::null_mut(),
+            };
+            let html = match impulse_editor::svg::render_svg_preview(&source, &bg_color) {
+                Some(h) => h,
+                None => return std::ptr::null_mut(),
+            };
+            to_c_string(&html)
+        }),
+    )
+}
+
+/// Check whether a file path has an SVG extension.
+#[no_mangle]
+pub extern "C" fn impulse_is_svg_file(path: *const c_char) -> bool {
+    ffi_catch(
+        false,
+        AssertUnwindSafe(|| {
+            let path = match to_rust_str(path) {
+                Some(s) => s,
+                None => return false,
+            };
+            impulse_editor::svg::is_svg_file(&path)
+        }),
+    )
+}
+
+/// Check whether a file path is a previewable type (markdown or SVG).
+#[no_mangle]
+pub extern "C" fn impulse_is_previewable_file(path: *const c_char) -> bool {
+    ffi_catch(
+        false,
+        AssertUnwindSafe(|| {
+            let path = match to_rust_str(path) {
+                Some(s) => s,
+                None => return false,
+            };
+            impulse_editor::is_previewable_file(&path)
+        }),
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Settings
+// ---------------------------------------------------------------------------
+
+/// Return default settings as a JSON string.
+#[no_mangle]
+pub extern "C" fn impulse_settings_default_json() -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| to_c_string(&impulse_core::settings::Settings::default_json())),
+    )
+}
+
+/// Return the generated JSON Schema for settings.
+#[no_mangle]
+pub extern "C" fn impulse_settings_schema_json() -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| to_c_string(&impulse_core::settings::Settings::schema_json())),
+    )
+}
+
+/// Parse, migrate, and validate a raw settings JSON string.
+/// Returns the cleaned JSON. If the input is null or invalid, returns default settings.
+#[no_mangle]
+pub extern "C" fn impulse_settings_load_json(json: *const c_char) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let raw = to_rust_str(json).unwrap_or_default();
+            let settings = impulse_core::settings::Settings::from_json(&raw).unwrap_or_default();
+            let result = settings
+                .to_json()
+                .unwrap_or_else(|_| impulse_core::settings::Settings::default_json());
+            to_c_string(&result)
+        }),
+    )
+}
+
+/// Validate/clamp a settings JSON string and return the cleaned version.
+#[no_mangle]
+pub extern "C" fn impulse_settings_validate_json(json: *const c_char) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let raw = match to_rust_str(json) {
+                Some(s) => s,
+                None => return to_c_string(&impulse_core::settings::Settings::default_json()),
+            };
+            let mut settings: impulse_core::settings::Settings =
+                serde_json::from_str(&raw).unwrap_or_default();
+            settings.validate();
+            let result = settings
+                .to_json()
+                .unwrap_or_else(|_| impulse_core::settings::Settings::default_json());
+            to_c_string(&result)
+        }),
+    )
+}
+
+/// Load settings from the shared settings file (the same file the Linux
+/// frontend uses), applying migrations and validation.
+/// Returns JSON: `{"path":"...","settings":{...},"warning":null}`. When the
+/// file could not be used, `settings` holds defaults and `warning` is
+/// `{"settings_path","backup_path","message"}`; saves are refused until the
+/// file is fixed and loaded again.
+#[no_mangle]
+pub extern "C" fn impulse_settings_load() -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let settings = impulse_core::settings::load();
+            let result = serde_json::json!({
+                "path": impulse_core::settings::settings_path(),
+                "settings": settings,
+                "warning": impulse_core::settings::settings_load_warning(),
+            });
+            to_c_string(&result.to_string())
+        }),
+    )
+}
+
+/// Save settings to the shared settings file. `json` is a settings object
+/// whose top-level keys are merged over the stored settings, so keys the
+/// caller omits keep their current values. The save is refused if the file
+/// failed to load or was changed on disk since it was last loaded.
+/// Returns JSON: `{"ok":true,"error":null}` or `{"ok":false,"error":"..."}`.
+#[no_mangle]
+pub extern "C" fn impulse_settings_save(json: *const c_char) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let result = match to_rust_str(json) {
+                Some(raw) => impulse_core::settings::save_merged(&raw).map(|_| ()),
+                None => Err("Settings JSON is required".to_string()),
+            };
+            unit_result(result)
+        }),
+    )
+}
+
+/// The settings file watcher started by `impulse_settings_watch`.
+static SETTINGS_WATCHER: parking_lot::Mutex<Option<impulse_core::settings::SettingsWatcher>> =
+    parking_lot::Mutex::new(None);
+
+/// Call `callback` (on a watcher thread, with `{}`) whenever the settings
+/// file may have changed, including the app's own saves; follow up with
+/// `impulse_settings_check_external_change`. A null callback stops watching.
+#[no_mangle]
+pub extern "C" fn impulse_settings_watch(
+    callback: Option<ImpulseEventCallback>,
+    context: *mut c_void,
+) {
+    ffi_catch(
+        (),
+        AssertUnwindSafe(|| {
+            let mut watcher = SETTINGS_WATCHER.lock();
+            *watcher = None;
+            let Some(callback) = callback else {
+                return;
+            };
+            let subscriber = EventSubscriber { callback, context };
+            match impulse_core::settings::watch(move || {
+                let subscriber = subscriber;
+                (subscriber.callback)(subscriber.context, c"{}".as_ptr());
+            }) {
+                Ok(w) => *watcher = Some(w),
+                Err(e) => log::warn!("{}", e),
+            }
+        }),
+    )
+}
+
+/// Reload the settings file if it changed outside the app. Returns JSON
+/// `{"status","settings","message"}`: status is "unchanged", "reloaded"
+/// (settings holds the new settings), "invalid" (message says why) or
+/// "conflict" (resolve with `impulse_settings_resolve_conflict`).
+#[no_mangle]
+pub extern "C" fn impulse_settings_check_external_change() -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            use impulse_core::settings::ExternalChange;
+            let result = match impulse_core::settings::check_external_change() {
+                ExternalChange::Unchanged => serde_json::json!({"status": "unchanged"}),
+                ExternalChange::Reloaded(settings) => {
+                    serde_json::json!({"status": "reloaded", "settings": settings})
+                }
+                ExternalChange::Invalid(message) => {
+                    serde_json::json!({"status": "invalid", "message": message})
+                }
+                ExternalChange::Conflict { .. } => serde_json::json!({"status": "conflict"}),
+            };
+            to_c_string(&result.to_string())
+        }),
+    )
+}
+
+/// Settle a settings conflict by writing the app's settings (`keep_app`) or
+/// reloading the file. Returns JSON `{"ok","settings","error"}`.
+#[no_mangle]
+pub extern "C" fn impulse_settings_resolve_conflict(keep_app: bool) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let result = match impulse_core::settings::resolve_conflict(keep_app) {
+                Ok(settings) => serde_json::json!({"ok": true, "settings": settings}),
+                Err(e) => serde_json::json!({"ok": false, "error": e}),
+            };
+            to_c_string(&result.to_string())
+        }),
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Settings export/import and sync
+// ---------------------------------------------------------------------------
+
+/// Write the configuration to the zip archive at `path`. Returns JSON
+/// `{"ok","count","error"}`.
+#[no_mangle]
+pub extern "C" fn impulse_config_export(path: *const c_char) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let path = to_rust_str(path).unwrap_or_default();
+            let result =
+                match impulse_core::config_sync::export_archive(std::path::Path::new(&path)) {
+                    Ok(count) => serde_json::json!({"ok": true, "count": count}),
+                    Err(e) => serde_json::json!({"ok": false, "error": e}),
+                };
+            to_c_string(&result.to_string())
+        }),
+    )
+}
+
+/// Restore the configuration from the zip archive at `path`; apply it with
+/// `impulse_settings_check_external_change`. Returns JSON
+/// `{"ok","count","error"}`.
+#[no_mangle]
+pub extern "C" fn impulse_config_import(path: *const c_char) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let path = to_rust_str(path).unwrap_or_default();
+            let result =
+                match impulse_core::config_sync::import_archive(std::path::Path::new(&path)) {
+                    Ok(count) => serde_json::json!({"ok": true, "count": count}),
+                    Err(e) => serde_json::json!({"ok": false, "error": e}),
+                };
+            to_c_string(&result.to_string())
+        }),
+    )
+}
+
+/// Sync the configuration with the git repository `url` (blocking; call
+/// off the main thread), or with `keep_local` >= 0 settle a conflict: 1
+/// pushes this machine's configuration, 0 takes the repository's. Returns
+/// JSON `{"ok","outcome","error"}` where outcome is "up_to_date", "pushed",
+/// "pulled" or "conflict".
+#[no_mangle]
+pub extern "C" fn impulse_config_sync(url: *const c_char, keep_local: i32) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let url = to_rust_str(url).unwrap_or_default();
+            let outcome = match keep_local {
+                k if k < 0 => impulse_core::config_sync::sync(&url),
+                k => impulse_core::config_sync::resolve_sync_conflict(&url, k > 0),
+            };
+            let result = match outcome {
+                Ok(outcome) => serde_json::json!({"ok": true, "outcome": outcome}),
+                Err(e) => serde_json::json!({"ok": false, "error": e}),
+            };
+            to_c_string(&result.to_string())
+        }),
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Window layouts
+// ---------------------------------------------------------------------------
+
+/// The names of the window layouts saved in `dir`, as a JSON array.
+#[no_mangle]
+pub extern "C" fn impulse_window_layout_list(dir: *const c_char) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let dir = to_rust_str(dir).unwrap_or_default();
+            let names = impulse_core::window_layouts::list(std::path::Path::new(&dir));
+            to_c_string(&serde_json::json!(names).to_string())
+        }),
+    )
+}
+
+/// Save the session window JSON `window_json` in `dir` as the layout
+/// `name`. Returns JSON `{"ok","error"}`.
+#[no_mangle]
+pub extern "C" fn impulse_window_layout_save(
+    dir: *const c_char,
+    name: *const c_char,
+    window_json: *const c_char,
+) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let dir = to_rust_str(dir).unwrap_or_default();
+            let name = to_rust_str(name).unwrap_or_default();
+            let json = to_rust_str(window_json).unwrap_or_default();
+            let result = serde_json::from_str::<impulse_core::session_state::SessionWindow>(&json)
+                .map_err(|e| format!("Invalid window layout: {e}"))
+                .and_then(|window| {
+                    impulse_core::window_layouts::save(std::path::Path::new(&dir), &name, &window)
+                });
+            let result = match result {
+                Ok(()) => serde_json::json!({"ok": true}),
+                Err(e) => serde_json::json!({"ok": false, "error": e}),
+            };
+            to_c_string(&result.to_string())
+        }),
+    )
+}
+
+/// Load the layout `name` from `dir`. Returns JSON `{"ok","window","error"}`
+/// with the session window.
+#[no_mangle]
+pub extern "C" fn impulse_window_layout_load(
+    dir: *const c_char,
+    name: *const c_char,
+) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let dir = to_rust_str(dir).unwrap_or_default();
+            let name = to_rust_str(name).unwrap_or_default();
+            let result = match impulse_core::window_layouts::load(std::path::Path::new(&dir), &name)
+            {
+                Ok(window) => serde_json::json!({"ok": true, "window": window}),
+                Err(e) => serde_json::json!({"ok": false, "error": e}),
+            };
+            to_c_string(&result.to_string())
+        }),
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Sidebar file actions
+// ---------------------------------------------------------------------------
+
+fn file_action_result(result: Result<std::path::PathBuf, String>) -> *mut c_char {
+    let result = match result {
+        Ok(path) => serde_json::json!({"ok": true, "path": path.to_string_lossy()}),
+        Err(e) => serde_json::json!({"ok": false, "error": e}),
+    };
+    to_c_string(&result.to_string())
+}
+
+/// Create the file or folder `name` in `dir`. New files start from the
+/// matching template in `templates_json`. Returns JSON `{"ok","path","error"}`.
+#[no_mangle]
+pub extern "C" fn impulse_file_create(
+    dir: *const c_char,
+    name: *const c_char,
+    is_dir: bool,
+    templates_json: *const c_char,
+) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let dir = to_rust_str(dir).unwrap_or_default();
+            let name = to_rust_str(name).unwrap_or_default();
+            let dir = std::path::Path::new(&dir);
+            let result = if is_dir {
+                impulse_core::file_actions::create_dir(dir, &name)
+            } else {
+                let templates: Vec<impulse_core::settings::FileTemplate> =
+                    to_rust_str(templates_json)
+                        .and_then(|json| serde_json::from_str(&json).ok())
+                        .unwrap_or_default();
+                impulse_core::file_actions::create_file(dir, &name, &templates)
+            };
+            file_action_result(result)
+        }),
+    )
+}
+
+/// Rename `path` to `new_name` in the same folder. Returns JSON
+/// `{"ok","path","error"}`.
+#[no_mangle]
+pub extern "C" fn impulse_file_rename(path: *const c_char, new_name: *const c_char) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let path = to_rust_str(path).unwrap_or_default();
+            let new_name = to_rust_str(new_name).unwrap_or_default();
+            file_action_result(impulse_core::file_actions::rename(
+                std::path::Path::new(&path),
+                &new_name,
+            ))
+        }),
+    )
+}
+
+/// Copy the file or folder at `path` next to it as "name copy". Returns
+/// JSON `{"ok","path","error"}`.
+#[no_mangle]
+pub extern "C" fn impulse_file_duplicate(path: *const c_char) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let path = to_rust_str(path).unwrap_or_default();
+            file_action_result(impulse_core::file_actions::duplicate(std::path::Path::new(
+                &path,
+            )))
+        }),
+    )
+}
+
+/// Properties of the file or folder at `path` for the Properties dialog.
+/// Returns JSON `{"ok","properties","size_label","error"}`.
+#[no_mangle]
+pub extern "C" fn impulse_file_properties(path: *const c_char) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            use impulse_core::file_properties;
+            let path = to_rust_str(path).unwrap_or_default();
+            let result = match file_properties::read(std::path::Path::new(&path)) {
+                Ok(props) => serde_json::json!({
+                    "ok": true,
+                    "size_label": file_properties::size_label(props.size),
+                    "properties": props,
+                }),
+                Err(e) => serde_json::json!({"ok": false, "error": e}),
+            };
+            to_c_string(&result.to_string())
+        }),
+    )
+}
+
+/// Set the permission bits of `path` to `mode`. Returns JSON `{"ok","error"}`.
+#[no_mangle]
+pub extern "C" fn impulse_file_set_mode(path: *const c_char, mode: u32) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let path = to_rust_str(path).unwrap_or_default();
+            let result =
+                match impulse_core::file_properties::set_mode(std::path::Path::new(&path), mode) {
+                    Ok(()) => serde_json::json!({"ok": true}),
+                    Err(e) => serde_json::json!({"ok": false, "error": e}),
+                };
+            to_c_string(&result.to_string())
+        }),
+    )
+}
+
+/// Whether a save that failed for lack of permission can be retried as the
+/// administrator.
+#[no_mangle]
+pub extern "C" fn impulse_privileged_write_available() -> bool {
+    ffi_catch(
+        false,
+        AssertUnwindSafe(impulse_core::privileged_write::is_available),
+    )
+}
+
+/// Whitespace fixes to make before saving `content` to `path`: trailing
+/// whitespace and the final newline as the settings (`trim`,
+/// `final_newline`) and any `.editorconfig` ask. Returns JSON
+/// `{"content","edits"}` with the fixed text and Monaco-shaped edits
+/// (`{range,text}`) for the buffer, or NULL when nothing needs fixing.
+#[no_mangle]
+pub extern "C" fn impulse_save_whitespace(
+    path: *const c_char,
+    content: *const c_char,
+    trim: bool,
+    final_newline: bool,
+) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            use impulse_core::save_whitespace::{self, SaveWhitespace};
+            let path = to_rust_str(path).unwrap_or_default();
+            let content = to_rust_str(content).unwrap_or_default();
+            let fixes = SaveWhitespace::for_file(
+                std::path::Path::new(&path),
+                SaveWhitespace {
+                    trim_trailing_whitespace: trim,
+                    insert_final_newline: final_newline,
+                },
+            );
+            let edits = save_whitespace::edits(&content, fixes);
+            if edits.is_empty() {
+                return std::ptr::null_mut();
+            }
+            let fixed = match impulse_core::workspace_edit::apply_text_edits(&content, &edits) {
+                Ok(fixed) => fixed,
+                Err(e) => {
+                    log::warn!("Skipping whitespace fixes for {}: {}", path, e);
+                    return std::ptr::null_mut();
+                }
+            };
+            let edits: Vec<serde_json::Value> = edits
+                .iter()
+                .map(|e| {
+                    serde_json::json!({
+                        "range": {
+                            "start_line": e.start_line,
+                            "start_column": e.start_character,
+                            "end_line": e.end_line,
+                            "end_column": e.end_character,
+                        },
+                        "text": e.new_text,
+                    })
+                })
+                .collect();
+            to_c_string(&serde_json::json!({"content": fixed, "edits": edits}).to_string())
+        }),
+    )
+}
+
+/// Save `content` to `path` with the configured `save_strategy` and record
+/// the saved version in local history. Returns
+/// JSON `{"ok","error","permission_denied"}`.
+#[no_mangle]
+pub extern "C" fn impulse_safe_write(path: *const c_char, content: *const c_char) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let path = to_rust_str(path).unwrap_or_default();
+            let content = to_rust_str(content).unwrap_or_default();
+            let result = match impulse_core::safe_write::write(
+                std::path::Path::new(&path),
+                content.as_bytes(),
+            ) {
+                Ok(()) => {
+                    impulse_core::local_history::record(
+                        std::path::Path::new(&path),
+                        content.as_bytes(),
+                    );
+                    serde_json::json!({"ok": true})
+                }
+                Err(e) => serde_json::json!({
+                    "ok": false,
+                    "error": e.to_string(),
+                    "permission_denied": impulse_core::privileged_write::is_permission_error(&e),
+                }),
+            };
+            to_c_string(&result.to_string())
+        }),
+    )
+}
+
+/// Write `content` to `path` as the administrator, after the system
+/// authorization prompt. Returns JSON `{"ok","error"}`.
+#[no_mangle]
+pub extern "C" fn impulse_privileged_write(
+    path: *const c_char,
+    content: *const c_char,
+) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let path = to_rust_str(path).unwrap_or_default();
+            let content = to_rust_str(content).unwrap_or_default();
+            let result = match impulse_core::privileged_write::write(
+                std::path::Path::new(&path),
+                content.as_bytes(),
+            ) {
+                Ok(()) => serde_json::json!({"ok": true}),
+                Err(e) => serde_json::json!({"ok": false, "error": e}),
+            };
+            to_c_string(&result.to_string())
+        }),
+    )
+}
+
+/// Download the remote file at `uri` (`sftp://` or `ssh://`) into the cache.
+/// Returns JSON `{"ok","path","error"}` with the local copy's path.
+#[no_mangle]
+pub extern "C" fn impulse_remote_file_open(uri: *const c_char) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let uri = to_rust_str(uri).unwrap_or_default();
+            let result = match impulse_core::remote_files::open(&uri) {
+                Ok(path) => serde_json::json!({"ok": true, "path": path.to_string_lossy()}),
+                Err(e) => serde_json::json!({"ok": false, "error": e}),
+            };
+            to_c_string(&result.to_string())
+        }),
+    )
+}
+
+/// Upload the cached copy at `path` to its server; unless `force`, refuse
+/// when the server's file changed since the download. Returns JSON
+/// `{"ok","changed_on_server","error"}`.
+#[no_mangle]
+pub extern "C" fn impulse_remote_file_upload(path: *const c_char, force: bool) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let path = to_rust_str(path).unwrap_or_default();
+            let result = match impulse_core::remote_files::upload(&path, force) {
+                Ok(outcome) => serde_json::json!({
+                    "ok": true,
+                    "changed_on_server":
+                        outcome == impulse_core::remote_files::UploadOutcome::ChangedOnServer,
+                }),
+                Err(e) => serde_json::json!({"ok": false, "error": e}),
+            };
+            to_c_string(&result.to_string())
+        }),
+    )
+}
+
+/// The URI the cached copy at `path` came from, or null when it isn't one.
+#[no_mangle]
+pub extern "C" fn impulse_remote_file_uri(path: *const c_char) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let path = to_rust_str(path).unwrap_or_default();
+            match impulse_core::remote_files::remote_uri_for(&path) {
+                Some(uri) => to_c_string(&uri),
+                None => std::ptr::null_mut(),
+            }
+        }),
+    )
+}
+
+/// The URIs of remote files opened before, as a JSON array.
+#[no_mangle]
+pub extern "C" fn impulse_remote_file_recent_uris() -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let uris = impulse_core::remote_files::recent_uris();
+            to_c_string(&serde_json::to_string(&uris).unwrap_or_else(|_| "[]".to_string()))
+        }),
+    )
+}
+
+/// Map the user's VS Code settings and keybindings onto the settings JSON
+/// `base_json` without saving. Returns JSON `{"ok","settings","imported",
+/// "unmapped","error"}`.
+#[no_mangle]
+pub extern "C" fn impulse_vscode_import(base_json: *const c_char) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let base = to_rust_str(base_json)
+                .and_then(|json| impulse_core::settings::Settings::from_json(&json).ok())
+                .unwrap_or_default();
+            let commands = impulse_editor::keybindings::vscode_commands();
+            let result = match impulse_core::vscode_import::import(&base, &commands) {
+                Ok(import) => serde_json::json!({
+                    "ok": true,
+                    "settings": import.settings,
+                    "imported": import.imported,
+                    "unmapped": import.unmapped,
+                }),
+                Err(e) => serde_json::json!({"ok": false, "error": e}),
+            };
+            to_c_string(&result.to_string())
+        }),
+    )
+}
+
+// ---------------------------------------------------------------------------
+// External formatters
+// ---------------------------------------------------------------------------
+
+/// The formatter settings pick for `language` (file at `file_path`).
+/// Returns JSON: `{"mode":"lsp"|"lsp_or_fallback"|"external","formatter":name|null}`.
+/// With `lsp_or_fallback`, call `impulse_format_external` when no language
+/// server formats the document.
+#[no_mangle]
+pub extern "C" fn impulse_formatter_choice(
+    language: *const c_char,
+    file_path: *const c_char,
+) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            use impulse_core::formatter::FormatterChoice;
+            let language = to_rust_str(language).unwrap_or_default();
+            let file_path = to_rust_str(file_path).unwrap_or_default();
+            let settings = impulse_core::settings::load();
+            let choice = impulse_core::formatter::choose(&settings, &language, &file_path);
+            let mode = match &choice {
+                FormatterChoice::Lsp => "lsp",
+                FormatterChoice::LspOrFallback(_) => "lsp_or_fallback",
+                FormatterChoice::External(_) => "external",
+            };
+            let result = serde_json::json!({
+                "mode": mode,
+                "formatter": choice.external().map(|f| f.name.as_str()),
+            });
+            to_c_string(&result.to_string())
+        }),
+    )
+}
+
+/// Format `text` (the contents of `file_path`) with the external formatter
+/// settings pick for `language`. Blocks until the formatter exits (at most
+/// 10s). Returns a JSON array of LSP `TextEdit`s (empty when already
+/// formatted) or `{"error": "..."}`.
+#[no_mangle]
+pub extern "C" fn impulse_format_external(
+    language: *const c_char,
+    file_path: *const c_char,
+    text: *const c_char,
+) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let (Some(language), Some(file_path), Some(text)) = (
+                to_rust_str(language),
+                to_rust_str(file_path),
+                to_rust_str(text),
+            ) else {
+                return to_c_string("{\"error\":\"invalid argument\"}");
+            };
+            let settings = impulse_core::settings::load();
+            let choice = impulse_core::formatter::choose(&settings, &language, &file_path);
+            let result = choice
+                .external()
+                .ok_or_else(|| format!("No external formatter for {}", language))
+                .and_then(|f| impulse_core::formatter::format_edits(f, &file_path, &text))
+                .and_then(|edits| serde_json::to_string(&edits).map_err(|e| e.to_string()));
+            to_c_string(&json_or_error(result))
+        }),
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Keymap presets
+// ---------------------------------------------------------------------------
+
+/// Return a JSON array of `{"id", "name"}` for the selectable keymap presets.
+#[no_mangle]
+pub extern "C" fn impulse_keymap_presets() -> *mut c_char {
+    ffi_catch(
+        to_c_string("[]"),
+        AssertUnwindSafe(|| {
+            let presets: Vec<serde_json::Value> = impulse_core::keymap::KEYMAP_PRESETS
+                .iter()
+                .map(|p| serde_json::json!({ "id": p.id, "name": p.name }))
+                .collect();
+            to_c_string(&serde_json::Value::Array(presets).to_string())
+        }),
+    )
+}
+
+/// Return the shortcuts a keymap preset assigns on this platform as a JSON
+/// object mapping action ID to shortcut string.
+#[no_mangle]
+pub extern "C" fn impulse_keymap_preset_bindings(preset: *const c_char) -> *mut c_char {
+    ffi_catch(
+        to_c_string("{}"),
+        AssertUnwindSafe(|| {
+            let preset = to_rust_str(preset).unwrap_or_default();
+            let bindings = impulse_core::keymap::preset_bindings(&preset);
+            let json = serde_json::to_string(&bindings).unwrap_or_else(|_| "{}".to_string());
+            to_c_string(&json)
+        }),
+    )
+}
+
+/// Return a JSON array describing the rebindable Monaco editor actions:
+/// `{"id", "description", "monaco_action", "default_shortcut"}`.
+#[no_mangle]
+pub extern "C" fn impulse_editor_actions_json() -> *mut c_char {
+    ffi_catch(
+        to_c_string("[]"),
+        AssertUnwindSafe(|| {
+            let actions: Vec<serde_json::Value> = impulse_editor::keybindings::EDITOR_ACTIONS
+                .iter()
+                .map(|a| {
+                    serde_json::json!({
+                        "id": a.id,
+                        "description": a.description,
+                        "monaco_action": a.monaco_action,
+                        "default_shortcut": a.default_shortcut(),
+                    })
+                })
+                .collect();
+            to_c_string(&serde_json::Value::Array(actions).to_string())
+        }),
+    )
+}
+
+/// Resolve the status bar layout. Takes the `status_bar_segments` and
+/// `status_bar_hidden_segments` settings as JSON string arrays and returns
+/// the visible segments in order as `{"id", "name", "alignment"}` objects.
+#[no_mangle]
+pub extern "C" fn impulse_status_bar_segments(
+    order_json: *const c_char,
+    hidden_json: *const c_char,
+) -> *mut c_char {
+    ffi_catch(
+        to_c_string("[]"),
+        AssertUnwindSafe(|| {
+            let parse = |ptr| -> Vec<String> {
+                to_rust_str(ptr)
+                    .and_then(|s| serde_json::from_str(&s).ok())
+                    .unwrap_or_default()
+            };
+            let segments =
+                impulse_core::status_bar::resolve_segments(&parse(order_json), &parse(hidden_json));
+            let json = serde_json::to_string(&segments).unwrap_or_else(|_| "[]".to_string());
+            to_c_string(&json)
+        }),
+    )
+}
+
+/// The working directory `path` as status bar breadcrumbs, outermost first,
+/// as `{"label", "path"}` objects. Paths under home start at `~`.
+#[no_mangle]
+pub extern "C" fn impulse_status_bar_cwd_breadcrumbs(path: *const c_char) -> *mut c_char {
+    ffi_catch(
+        to_c_string("[]"),
+        AssertUnwindSafe(|| {
+            let path = to_rust_str(path).unwrap_or_default();
+            let home = impulse_core::shell::get_home_directory().ok();
+            let crumbs = impulse_core::status_bar::cwd_breadcrumbs(&path, home.as_deref());
+            let json = serde_json::to_string(&crumbs).unwrap_or_else(|_| "[]".to_string());
+            to_c_string(&json)
+        }),
+    )
+}
+
+/// Guess a file's indentation from its content. Returns
+/// `{"use_spaces": bool|null, "width": number|null}`; null halves mean the
+/// content doesn't say and the settings apply.
+#[no_mangle]
+pub extern "C" fn impulse_detect_indentation(content: *const c_char) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let content = to_rust_str(content).unwrap_or_default();
+            let detected = impulse_core::indentation::detect_indentation(&content);
+            let json = serde_json::to_string(&detected).unwrap_or_else(|_| "{}".to_string());
+            to_c_string(&json)
+        }),
+    )
+}
+
+/// Why a file should open read-only, as the lock tooltip text (e.g.
+/// `"Read-only: generated file"`), or null when it's editable.
+#[no_mangle]
+pub extern "C" fn impulse_read_only_reason(
+    path: *const c_char,
+    content: *const c_char,
+) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let path = to_rust_str(path).unwrap_or_default();
+            let content = to_rust_str(content).unwrap_or_default();
+            match impulse_core::read_only::read_only_reason(&path, &content) {
+                Some(reason) => to_c_string(reason.description()),
+                None => std::ptr::null_mut(),
+            }
+        }),
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Hot exit
+// ---------------------------------------------------------------------------
+
+/// The app-wide backup store, opened by `impulse_hot_exit_start`.
+fn hot_exit() -> &'static parking_lot::Mutex<Option<impulse_core::hot_exit::HotExit>> {
+    static HOT_EXIT: OnceLock<parking_lot::Mutex<Option<impulse_core::hot_exit::HotExit>>> =
+        OnceLock::new();
+    HOT_EXIT.get_or_init(|| parking_lot::Mutex::new(None))
+}
+
+/// Open the backup store under `state_dir` and mark the app as running.
+/// Returns the backups a crashed previous run left as a JSON array of
+/// `{"path", "content", "saved_at_ms"}` (empty after a clean exit).
+#[no_mangle]
+pub extern "C" fn impulse_hot_exit_start(state_dir: *const c_char) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let state_dir = to_rust_str(state_dir).unwrap_or_default();
+            let mut store = impulse_core::hot_exit::HotExit::new(std::path::Path::new(&state_dir));
+            let recovered = store.start().unwrap_or_else(|e| {
+                log::warn!("{}", e);
+                Vec::new()
+            });
+            *hot_exit().lock() = Some(store);
+            let json = serde_json::to_string(&recovered).unwrap_or_else(|_| "[]".to_string());
+            to_c_string(&json)
+        }),
+    )
+}
+
+/// Back up the modified buffers in `buffers_json` (`[{"path", "content"}]`)
+/// and drop the backups of every other buffer.
+#[no_mangle]
+pub extern "C" fn impulse_hot_exit_sync(buffers_json: *const c_char) {
+    ffi_catch(
+        (),
+        AssertUnwindSafe(|| {
+            let json = to_rust_str(buffers_json).unwrap_or_default();
+            let buffers: Vec<serde_json::Value> = serde_json::from_str(&json).unwrap_or_default();
+            let buffers = buffers
+                .iter()
+                .filter_map(|b| Some((b.get("path")?.as_str()?, b.get("content")?.as_str()?)));
+            if let Some(store) = hot_exit().lock().as_mut() {
+                store.sync(buffers);
+            }
+        }),
+    )
+}
+
+/// Remove every backup, after the user declined to restore them.
+#[no_mangle]
+pub extern "C" fn impulse_hot_exit_discard() {
+    ffi_catch(
+        (),
+        AssertUnwindSafe(|| {
+            if let Some(store) = hot_exit().lock().as_mut() {
+                store.clear();
+            }
+        }),
+    )
+}
+
+/// Clean exit: drop the backups and the running marker.
+#[no_mangle]
+pub extern "C" fn impulse_hot_exit_finish() {
+    ffi_catch(
+        (),
+        AssertUnwindSafe(|| {
+            if let Some(store) = hot_exit().lock().as_mut() {
+                store.finish();
+            }
+        }),
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Local history
+// ---------------------------------------------------------------------------
+
+/// Keep saved versions of files under `<state_dir>/history` from now on.
+#[no_mangle]
+pub extern "C" fn impulse_local_history_set_state_dir(state_dir: *const c_char) {
+    ffi_catch(
+        (),
+        AssertUnwindSafe(|| {
+            let state_dir = to_rust_str(state_dir).unwrap_or_default();
+            if !state_dir.is_empty() {
+                impulse_core::local_history::set_state_dir(std::path::Path::new(&state_dir));
+            }
+        }),
+    )
+}
+
+/// The saved versions of `path`, newest first, as a JSON array of
+/// `{"id", "saved_at_ms", "size", "digest"}`.
+#[no_mangle]
+pub extern "C" fn impulse_local_history_entries(path: *const c_char) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let path = to_rust_str(path).unwrap_or_default();
+            let entries = impulse_core::local_history::entries(std::path::Path::new(&path));
+            let json = serde_json::to_string(&entries).unwrap_or_else(|_| "[]".to_string());
+            to_c_string(&json)
+        }),
+    )
+}
+
+/// Where version `id` of `path` is stored, or NULL.
+#[no_mangle]
+pub extern "C" fn impulse_local_history_version_path(
+    path: *const c_char,
+    id: *const c_char,
+) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let path = to_rust_str(path).unwrap_or_default();
+            let id = to_rust_str(id).unwrap_or_default();
+            match impulse_core::local_history::version_path(std::path::Path::new(&path), &id) {
+                Some(version) => to_c_string(&version.to_string_lossy()),
+                None => std::ptr::null_mut(),
+            }
+        }),
+    )
+}
+
+/// The content of version `id` of `path`. Returns JSON `{"content"}` or
+/// `{"error"}`.
+#[no_mangle]
+pub extern "C" fn impulse_local_history_read(
+    path: *const c_char,
+    id: *const c_char,
+) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let path = to_rust_str(path).unwrap_or_default();
+            let id = to_rust_str(id).unwrap_or_default();
+            let result = match impulse_core::local_history::read(std::path::Path::new(&path), &id) {
+                Ok(content) => serde_json::json!({ "content": content }),
+                Err(e) => serde_json::json!({ "error": e }),
+            };
+            to_c_string(&result.to_string())
+        }),
+    )
+}
+
+/// Diff the file at `old_path` against the file at `new_path`. Returns
+/// `FileHunks` JSON (as `impulse_git_file_hunks`), or NULL on error.
+#[no_mangle]
+pub extern "C" fn impulse_diff_files(
+    old_path: *const c_char,
+    new_path: *const c_char,
+) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let old_path = to_rust_str(old_path).unwrap_or_default();
+            let new_path = to_rust_str(new_path).unwrap_or_default();
+            match impulse_core::text_diff::diff_files(
+                std::path::Path::new(&old_path),
+                std::path::Path::new(&new_path),
+            ) {
+                Ok(hunks) => match serde_json::to_string(&hunks) {
+                    Ok(json) => to_c_string(&json),
+                    Err(e) => {
+                        log::error!("JSON serialization failed: {}", e);
+                        std::ptr::null_mut()
+                    }
+                },
+                Err(e) => {
+                    log::warn!("File comparison failed: {}", e);
+                    std::ptr::null_mut()
+                }
+            }
+        }),
+    )
+}
+
+/// Diff the saved file at `path` against `buffer`, the editor's unsaved
+/// content. Returns `FileHunks` JSON, or NULL on error.
+#[no_mangle]
+pub extern "C" fn impulse_diff_saved(path: *const c_char, buffer: *const c_char) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let path = to_rust_str(path).unwrap_or_default();
+            let buffer = to_rust_str(buffer).unwrap_or_default();
+            match impulse_core::text_diff::diff_saved(std::path::Path::new(&path), &buffer) {
+                Ok(hunks) => match serde_json::to_string(&hunks) {
+                    Ok(json) => to_c_string(&json),
+                    Err(e) => {
+                        log::error!("JSON serialization failed: {}", e);
+                        std::ptr::null_mut()
+                    }
+                },
+                Err(e) => {
+                    log::warn!("Comparison with saved file failed: {}", e);
+                    std::ptr::null_mut()
+                }
+            }
+        }),
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Language overrides
+// ---------------------------------------------------------------------------
+
+/// The languages a file can be switched to, as a JSON array of
+/// `{"id", "label", "monaco"}`.
+#[no_mangle]
+pub extern "C" fn impulse_languages() -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let json = serde_json::to_string(impulse_core::languages::LANGUAGES)
+                .unwrap_or_else(|_| "[]".to_string());
+            to_c_string(&json)
+        }),
+    )
+}
+
+/// Apply the `language_overrides` setting, given as a JSON object.
+#[no_mangle]
+pub extern "C" fn impulse_languages_set_overrides(overrides_json: *const c_char) {
+    ffi_catch(
+        (),
+        AssertUnwindSafe(|| {
+            let json = to_rust_str(overrides_json).unwrap_or_default();
+            match serde_json::from_str::<HashMap<String, String>>(&json) {
+                Ok(overrides) => impulse_core::languages::set_overrides(&overrides),
+                Err(e) => log::warn!("Invalid language overrides: {}", e),
+            }
+        }),
+    )
+}
+
+/// The `language_overrides` key for `path` (`*.ext` or the file name), or
+/// NULL.
+#[no_mangle]
+pub extern "C" fn impulse_language_override_key(path: *const c_char) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let path = to_rust_str(path).unwrap_or_default();
+            match impulse_core::languages::override_key(std::path::Path::new(&path)) {
+                Some(key) => to_c_string(&key),
+                None => std::ptr::null_mut(),
+            }
+        }),
+    )
+}
+
+/// The language `path` is overridden to as `{"id", "label", "monaco"}`, or
+/// NULL when it uses the detected one.
+#[no_mangle]
+pub extern "C" fn impulse_language_override(path: *const c_char) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let path = to_rust_str(path).unwrap_or_default();
+            match impulse_core::languages::overridden(std::path::Path::new(&path)) {
+                Some(language) => to_c_string(&serde_json::to_string(language).unwrap_or_default()),
+                None => std::ptr::null_mut(),
+            }
+        }),
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Command-line open
+// ---------------------------------------------------------------------------
+
+/// Parse `impulse [--new-window] [--wait] <path[:line[:column]]>...`
+/// arguments (a JSON array, without the program name) into
+/// `{"targets": [{"path", "line", "column", "is_dir"}], "new_window", "wait"}`.
+/// Relative paths resolve against `cwd`.
+#[no_mangle]
+pub extern "C" fn impulse_cli_parse_open_request(
+    args_json: *const c_char,
+    cwd: *const c_char,
+) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let json = to_rust_str(args_json).unwrap_or_default();
+            let args: Vec<String> = serde_json::from_str(&json).unwrap_or_default();
+            let cwd = to_rust_str(cwd).unwrap_or_else(|| "/".to_string());
+            let request = impulse_core::cli_open::parse_args(&args, std::path::Path::new(&cwd));
+            let json = serde_json::to_string(&request).unwrap_or_else(|_| "{}".to_string());
+            to_c_string(&json)
+        }),
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Startup profile
+// ---------------------------------------------------------------------------
+
+/// Start timing launch phases (`--profile-startup`).
+#[no_mangle]
+pub extern "C" fn impulse_startup_profile_enable() {
+    ffi_catch((), impulse_core::startup_profile::enable)
+}
+
+/// Record that a launch phase just finished.
+#[no_mangle]
+pub extern "C" fn impulse_startup_profile_mark(phase: *const c_char) {
+    ffi_catch(
+        (),
+        AssertUnwindSafe(|| {
+            if let Some(phase) = to_rust_str(phase) {
+                impulse_core::startup_profile::mark(&phase);
+            }
+        }),
+    )
+}
+
+/// Record the last phase and print the profile to stderr (once).
+#[no_mangle]
+pub extern "C" fn impulse_startup_profile_finish(phase: *const c_char) {
+    ffi_catch(
+        (),
+        AssertUnwindSafe(|| {
+            if let Some(phase) = to_rust_str(phase) {
+                impulse_core::startup_profile::finish(&phase);
+            }
+        }),
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Crash reports
+// ---------------------------------------------------------------------------
+
+/// `<state_dir>/crashes`, set by `impulse_crash_reporter_install`.
+fn crash_reports_dir() -> &'static OnceLock<std::path::PathBuf> {
+    static DIR: OnceLock<std::path::PathBuf> = OnceLock::new();
+    &DIR
+}
+
+/// Start keeping recent log lines and write a crash report for every Rust
+/// panic, including ones `ffi_catch` recovers from. Call once at launch.
+#[no_mangle]
+pub extern "C" fn impulse_crash_reporter_install(state_dir: *const c_char) {
+    ffi_catch(
+        (),
+        AssertUnwindSafe(|| {
+            let state_dir = to_rust_str(state_dir).unwrap_or_default();
+            let dir = impulse_core::crash_report::reports_dir(std::path::Path::new(&state_dir));
+            if crash_reports_dir().set(dir).is_err() {
+                return;
+            }
+            impulse_core::crash_report::RecentLogger::install(None, log::LevelFilter::Info);
+            let default_hook = std::panic::take_hook();
+            std::panic::set_hook(Box::new(move |panic_info| {
+                let backtrace = std::backtrace::Backtrace::force_capture();
+                write_crash_report(&panic_info.to_string(), &backtrace.to_string());
+                default_hook(panic_info);
+            }));
+        }),
+    )
+}
+
+/// Record a crash the Swift side caught (an uncaught exception or a fatal
+/// signal) with its call stack.
+#[no_mangle]
+pub extern "C" fn impulse_crash_report_write(message: *const c_char, backtrace: *const c_char) {
+    ffi_catch(
+        (),
+        AssertUnwindSafe(|| {
+            let message = to_rust_str(message).unwrap_or_default();
+            let backtrace = to_rust_str(backtrace).unwrap_or_default();
+            write_crash_report(&message, &backtrace);
+        }),
+    )
+}
+
+fn write_crash_report(message: &str, backtrace: &str) {
+    if let Some(dir) = crash_reports_dir().get() {
+        let report = impulse_core::crash_report::CrashReport::new(message, backtrace);
+        let _ = impulse_core::crash_report::write_report(dir, &report);
+    }
+}
+
+/// Reports from earlier sessions not yet shown, newest first, as a JSON array
+/// of report objects with an extra `text` rendering for the viewer. Marks
+/// them seen.
+#[no_mangle]
+pub extern "C" fn impulse_crash_report_take_unseen() -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let reports = crash_reports_dir()
+                .get()
+                .map(|dir| impulse_core::crash_report::take_unseen(dir))
+                .unwrap_or_default();
+            let json: Vec<serde_json::Value> = reports
+                .iter()
+                .filter_map(|report| {
+                    let mut value = serde_json::to_value(report).ok()?;
+                    value["text"] = serde_json::Value::String(report.to_text());
+                    Some(value)
+                })
+                .collect();
+            to_c_string(&serde_json::Value::Array(json).to_string())
+        }),
+    )
+}
+
+/// POST a report (as returned by `impulse_crash_report_take_unseen`) to
+/// `endpoint`. Blocks; returns null on success or an error message.
+#[no_mangle]
+pub extern "C" fn impulse_crash_report_submit(
+    report_json: *const c_char,
+    endpoint: *const c_char,
+) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let json = to_rust_str(report_json).unwrap_or_default();
+            let endpoint = to_rust_str(endpoint).unwrap_or_default();
+            let result = serde_json::from_str::<impulse_core::crash_report::CrashReport>(&json)
+                .map_err(|e| format!("Invalid crash report: {}", e))
+                .and_then(|report| impulse_core::crash_report::submit(&report, endpoint.trim()));
+            match result {
+                Ok(()) => std::ptr::null_mut(),
+                Err(e) => to_c_string(&e),
+            }
+        }),
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Command palette
+// ---------------------------------------------------------------------------
+
+#[no_mangle]
+pub extern "C" fn impulse_command_palette_builtin_items_json() -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            match serde_json::to_string(&impulse_core::command_palette::builtin_items()) {
+                Ok(json) => to_c_string(&json),
+                Err(e) => to_c_string(&serde_json::json!({"error": e.to_string()}).to_string()),
+            }
+        }),
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn impulse_command_palette_custom_item_json(
+    name: *const c_char,
+    shortcut: *const c_char,
+    command: *const c_char,
+    args_json: *const c_char,
+) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let name = to_rust_str(name).unwrap_or_default();
+            let shortcut = to_rust_str(shortcut);
+            let command = to_rust_str(command).unwrap_or_default();
+            let args: Vec<String> = to_rust_str(args_json)
+                .and_then(|json| serde_json::from_str(&json).ok())
+                .unwrap_or_default();
+            let item = impulse_core::command_palette::custom_command_item(
+                &name,
+                shortcut.as_deref(),
+                &command,
+                &args,
+            );
+            match serde_json::to_string(&item) {
+                Ok(json) => to_c_string(&json),
+                Err(e) => to_c_string(&serde_json::json!({"error": e.to_string()}).to_string()),
+            }
+        }),
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn impulse_command_palette_filter_json(
+    items_json: *const c_char,
+    recents_json: *const c_char,
+    query: *const c_char,
+) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let items: Vec<impulse_core::command_palette::CommandPaletteItem> =
+                to_rust_str(items_json)
+                    .and_then(|json| serde_json::from_str(&json).ok())
+                    .unwrap_or_default();
+            let recents: impulse_core::command_palette::RecentCommandStore =
+                to_rust_str(recents_json)
+                    .and_then(|json| serde_json::from_str(&json).ok())
+                    .unwrap_or_default();
+            let query = to_rust_str(query).unwrap_or_default();
+            let filtered = impulse_core::command_palette::filter_items(&items, &recents, &query);
+            match serde_json::to_string(&filtered) {
+                Ok(json) => to_c_string(&json),
+                Err(e) => to_c_string(&serde_json::json!({"error": e.to_string()}).to_string()),
+            }
+        }),
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn impulse_command_palette_record_recent_json(
+    recents_json: *const c_char,
+    item_json: *const c_char,
+    now_ms: u64,
+    max_items: usize,
+) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let mut recents: impulse_core::command_palette::RecentCommandStore =
+                to_rust_str(recents_json)
+                    .and_then(|json| serde_json::from_str(&json).ok())
+                    .unwrap_or_default();
+            let Some(item_json) = to_rust_str(item_json) else {
+                return std::ptr::null_mut();
+            };
+            let Ok(item) = serde_json::from_str(&item_json) else {
+                return std::ptr::null_mut();
+            };
+            recents.record(&item, now_ms, max_items);
+            match serde_json::to_string(&recents) {
+                Ok(json) => to_c_string(&json),
+                Err(e) => to_c_string(&serde_json::json!({"error": e.to_string()}).to_string()),
+            }
+        }),
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn impulse_command_palette_search_items_json(
+    root: *const c_char,
+    query: *const c_char,
+    limit: usize,
+) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let root = match to_rust_str(root) {
+                Some(root) => root,
+                None => return std::ptr::null_mut(),
+            };
+            let query = match to_rust_str(query) {
+                Some(query) => query,
+                None => return std::ptr::null_mut(),
+            };
+            match impulse_core::command_palette::search_items(&root, &query, limit) {
+                Ok(items) => match serde_json::to_string(&items) {
+                    Ok(json) => to_c_string(&json),
+                    Err(e) => to_c_string(&serde_json::json!({"error": e.to_string()}).to_string()),
+                },
+                Err(e) => to_c_string(&serde_json::json!({"error": e}).to_string()),
+            }
+        }),
+    )
+}
+
+/// Check for a newer version (the appcast, then GitHub Releases), at most
+/// once a day.
+///
+/// Returns a JSON string `{"version","current_version","url","item",
+/// "can_install","hint"}` if an update is available, an empty string if
+/// up-to-date or checked recently, or an `"ERROR:..."` string on failure.
+/// `item` is passed back to `impulse_update_install`. Caller must free with
+/// `impulse_free_string`.
+#[no_mangle]
+pub extern "C" fn impulse_check_for_update() -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| update_check_result(impulse_core::update::check_for_update())),
+    )
+}
+
+/// `impulse_check_for_update` without the once-a-day limit, for "Check for
+/// Updates".
+#[no_mangle]
+pub extern "C" fn impulse_check_for_update_now() -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| update_check_result(impulse_core::update::check_for_update_now())),
+    )
+}
+
+fn update_check_result(
+    result: Result<Option<impulse_core::update::UpdateInfo>, String>,
+) -> *mut c_char {
+    match result {
+        Ok(Some(info)) => {
+            let kind = current_install_kind();
+            let json = serde_json::json!({
+                "version": info.version,
+                "current_version": info.current_version,
+                "url": info.url,
+                "item": info.item,
+                "can_install": info.can_install(&kind),
+                "hint": kind.update_hint(),
+            });
+            to_c_string(&json.to_string())
+        }
+        Ok(None) => to_c_string(""),
+        Err(e) => to_c_string(&format!("ERROR:{}", e)),
+    }
+}
+
+fn current_install_kind() -> impulse_core::update::InstallKind {
+    match std::env::current_exe() {
+        Ok(exe) => impulse_core::update::install_kind(&exe),
+        Err(_) => impulse_core::update::InstallKind::Package,
+    }
+}
+
+/// Download, verify and install the update `item_json` (the `item` from
+/// `impulse_check_for_update`) over the running app bundle. It takes
+/// effect on the next launch.
+///
+/// Returns null on success or an error message. Caller must free with
+/// `impulse_free_string`.
+#[no_mangle]
+pub extern "C" fn impulse_update_install(item_json: *const c_char) -> *mut c_char {
+    ffi_catch(
+        to_c_string("Update failed"),
+        AssertUnwindSafe(|| {
+            let item: impulse_core::update::AppcastItem =
+                match to_rust_str(item_json).and_then(|json| serde_json::from_str(&json).ok()) {
+                    Some(item) => item,
+                    None => return to_c_string("Invalid update"),
+                };
+            let result = impulse_core::update::download_update(&item, &current_install_kind())
+                .and_then(|staged| impulse_core::update::apply_update(&staged));
+            match result {
+                Ok(()) => std::ptr::null_mut(),
+                Err(e) => {
+                    log::warn!("Update failed: {}", e);
+                    to_c_string(&e)
+                }
+            }
+        }),
+    )
+}
+
+/// Return the current application version string.
+///
+/// The returned pointer is valid for the lifetime of the process and must
+/// NOT be freed.
+#[no_mangle]
+pub extern "C" fn impulse_get_version() -> *const c_char {
+    ffi_catch(
+        std::ptr::null(),
+        AssertUnwindSafe(|| {
+            static CACHED: std::sync::OnceLock<CString> = std::sync::OnceLock::new();
+            CACHED
+                .get_or_init(|| {
+                    CString::new(impulse_core::update::CURRENT_VERSION).unwrap_or_default()
+                })
+                .as_ptr()
+        }),
+    )
+}
+
+/// The text the Copy Path command `command_id` (`copy_path`,
+/// `copy_relative_path`, `copy_file_name` or `copy_file_url`) copies for
+/// `path`, relative paths being relative to `root`. Null for other ids.
+#[no_mangle]
+pub extern "C" fn impulse_path_for_copy(
+    command_id: *const c_char,
+    path: *const c_char,
+    root: *const c_char,
+) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let command_id = to_rust_str(command_id).unwrap_or_default();
+            let path = to_rust_str(path).unwrap_or_default();
+            let root = to_rust_str(root).unwrap_or_default();
+            match impulse_core::util::path_for_copy(&command_id, &path, &root) {
+                Some(text) => to_c_string(&text),
+                None => std::ptr::null_mut(),
+            }
+        }),
+    )
+}
+
+/// Check whether a file path matches a glob-style pattern.
+#[no_mangle]
+pub extern "C" fn impulse_matches_file_pattern(
+    path: *const c_char,
+    pattern: *const c_char,
+) -> bool {
+    ffi_catch(
+        false,
+        AssertUnwindSafe(|| {
+            let path = match to_rust_str(path) {
+                Some(s) => s,
+                None => return false,
+            };
+            let pattern = match to_rust_str(pattern) {
+                Some(s) => s,
+                None => return false,
+            };
+            impulse_core::util::matches_file_pattern(&path, &pattern)
+        }),
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn impulse_close_risk_summary(input_json: *const c_char) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let Some(raw) = to_rust_str(input_json) else {
+                return to_c_string(
+                    &serde_json::json!({
+                        "has_risk": false,
+                        "error": "missing close risk input"
+                    })
+                    .to_string(),
+                );
+            };
+            let input: impulse_core::close_risk::CloseRiskInput = match serde_json::from_str(&raw) {
+                Ok(input) => input,
+                Err(err) => {
+                    return to_c_string(
+                        &serde_json::json!({
+                            "has_risk": false,
+                            "error": format!("invalid close risk input: {err}")
+                        })
+                        .to_string(),
+                    );
+                }
+            };
+            match serde_json::to_string(&input.summarize()) {
+                Ok(json) => to_c_string(&json),
+                Err(err) => to_c_string(
+                    &serde_json::json!({
+                        "has_risk": false,
+                        "error": format!("close risk serialization failed: {err}")
+                    })
+                    .to_string(),
+                ),
+            }
+        }),
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Tab sorting and groups
+// ---------------------------------------------------------------------------
+
+fn tab_summaries(tabs_json: *const c_char) -> Vec<impulse_core::tab_order::TabSummary> {
+    to_rust_str(tabs_json)
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default()
+}
+
+/// A snake_case enum name like `"recent_use"` as JSON, for parsing into a
+/// `tab_order` enum.
+fn tab_order_enum(name: *const c_char) -> serde_json::Value {
+    serde_json::Value::String(to_rust_str(name).unwrap_or_default())
+}
+
+/// The new order of the tabs in `tabs_json` (`[{"title","path","pinned",
+/// "modified","last_used"}]`) for `sort` (`"path"` or `"recent_use"`), as
+/// a JSON array of indices.
+#[no_mangle]
+pub extern "C" fn impulse_tab_sorted_order(
+    tabs_json: *const c_char,
+    sort: *const c_char,
+) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let order = serde_json::from_value(tab_order_enum(sort))
+                .map(|sort| impulse_core::tab_order::sorted_order(&tab_summaries(tabs_json), sort))
+                .unwrap_or_default();
+            to_c_string(&serde_json::json!(order).to_string())
+        }),
+    )
+}
+
+/// Indices of the tabs in `group` (`"saved"`, `"right"` or `"left"`) of the
+/// tab at `anchor`, as a JSON array.
+#[no_mangle]
+pub extern "C" fn impulse_tabs_to_close(
+    tabs_json: *const c_char,
+    anchor: u32,
+    group: *const c_char,
+) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let indices = serde_json::from_value(tab_order_enum(group))
+                .map(|group| {
+                    impulse_core::tab_order::tabs_to_close(
+                        &tab_summaries(tabs_json),
+                        anchor as usize,
+                        group,
+                    )
+                })
+                .unwrap_or_default();
+            to_c_string(&serde_json::json!(indices).to_string())
+        }),
+    )
+}
+
+/// Indices of the tabs matching the "Show All Tabs" search `query`, as a
+/// JSON array.
+#[no_mangle]
+pub extern "C" fn impulse_tab_filter(
+    tabs_json: *const c_char,
+    query: *const c_char,
+) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let query = to_rust_str(query).unwrap_or_default();
+            let indices = impulse_core::tab_order::filter(&tab_summaries(tabs_json), &query);
+            to_c_string(&serde_json::json!(indices).to_string())
+        }),
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Zoom
+// ---------------------------------------------------------------------------
+
+/// The zoom after stepping `zoom` by `delta` points over the `base` font
+/// size, unchanged when that would leave the allowed sizes.
+#[no_mangle]
+pub extern "C" fn impulse_zoom_step(base: i32, zoom: i32, delta: i32) -> i32 {
+    ffi_catch(
+        zoom,
+        AssertUnwindSafe(|| impulse_core::zoom::step(base, zoom, delta)),
+    )
+}
+
+/// The font size to draw with for a pane zoomed by `zoom` over `base`.
+#[no_mangle]
+pub extern "C" fn impulse_zoom_font_size(base: i32, zoom: i32) -> i32 {
+    ffi_catch(
+        base,
+        AssertUnwindSafe(|| impulse_core::zoom::zoomed_font_size(base, zoom)),
+    )
+}
+
+/// Status bar text for a zoomed pane like `"120%"`, or null at the default size.
+#[no_mangle]
+pub extern "C" fn impulse_zoom_label(base: i32, zoom: i32) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| match impulse_core::zoom::label(base, zoom) {
+            Some(label) => to_c_string(&label),
+            None => std::ptr::null_mut(),
+        }),
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Command registry
+// ---------------------------------------------------------------------------
+
+/// The frontend's command handler, invoked synchronously on the caller's
+/// thread by `impulse_execute_command`.
+static COMMAND_HANDLER: parking_lot::Mutex<Option<EventSubscriber>> = parking_lot::Mutex::new(None);
+
+/// Every command in the registry with the shortcuts the saved settings put
+/// in effect. `context_json` is `{"editor","terminal","file"}` describing
+/// the active tab (all optional) and decides each entry's `enabled`.
+/// Returns a JSON array of `{id, title, category, keywords, source,
+/// shortcut, default_shortcut, when, enabled}`.
+#[no_mangle]
+pub extern "C" fn impulse_command_registry_json(context_json: *const c_char) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let context: impulse_core::commands::CommandContext = to_rust_str(context_json)
+                .and_then(|json| serde_json::from_str(&json).ok())
+                .unwrap_or_default();
+            let registry =
+                impulse_core::commands::CommandRegistry::new(&impulse_core::settings::load());
+            match serde_json::to_string(&registry.entries(&context)) {
+                Ok(json) => to_c_string(&json),
+                Err(e) => to_c_string(&serde_json::json!({"error": e.to_string()}).to_string()),
+            }
+        }),
+    )
+}
+
+/// Route `impulse_execute_command` to `callback`, which receives
+/// `{"id","args"}`. A null callback unregisters.
+#[no_mangle]
+pub extern "C" fn impulse_set_command_handler(
+    callback: Option<ImpulseEventCallback>,
+    context: *mut c_void,
+) {
+    ffi_catch(
+        (),
+        AssertUnwindSafe(|| {
+            *COMMAND_HANDLER.lock() =
+                callback.map(|callback| EventSubscriber { callback, context });
+        }),
+    );
+}
+
+/// Run registry command `id` through the frontend's handler. `args_json` is
+/// any JSON value (or null) passed through to the handler. Returns null on
+/// success or an error message (caller frees).
+#[no_mangle]
+pub extern "C" fn impulse_execute_command(
+    id: *const c_char,
+    args_json: *const c_char,
+) -> *mut c_char {
+    ffi_catch(
+        to_c_string("Command failed"),
+        AssertUnwindSafe(|| {
+            let id = to_rust_str(id).unwrap_or_default();
+            let registry =
+                impulse_core::commands::CommandRegistry::new(&impulse_core::settings::load());
+            if !registry.contains(&id) {
+                return to_c_string(&format!("Unknown command {}", id));
+            }
+            let args = match to_rust_str(args_json).filter(|json| !json.trim().is_empty()) {
+                Some(json) => match serde_json::from_str::<serde_json::Value>(&json) {
+                    Ok(args) => args,
+                    Err(e) => return to_c_string(&format!("Invalid arguments: {}", e)),
+                },
+                None => serde_json::Value::Null,
+            };
+            // Copy the handler out so it may re-enter this function.
+            let Some(handler) = *COMMAND_HANDLER.lock() else {
+                return to_c_string("No command handler registered");
+            };
+            let Ok(event) = CString::new(serde_json::json!({"id": id, "args": args}).to_string())
+            else {
+                return to_c_string("Invalid command");
+            };
+            (handler.callback)(handler.context, event.as_ptr());
+            std::ptr::null_mut()
+        }),
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Workspace trust
+// ---------------------------------------------------------------------------
+
+/// Apply the `workspace_trust_enabled` setting.
+#[no_mangle]
+pub extern "C" fn impulse_trust_set_enabled(enabled: bool) {
+    ffi_catch(
+        (),
+        AssertUnwindSafe(|| impulse_core::trust::set_enabled(enabled)),
+    )
+}
+
+/// Trust state covering `path`: "trusted", "restricted" (the user chose
+/// restricted mode) or "unknown" (not asked yet; also restricted). Caller
+/// frees.
+#[no_mangle]
+pub extern "C" fn impulse_trust_state(path: *const c_char) -> *mut c_char {
+    ffi_catch(
+        to_c_string("unknown"),
+        AssertUnwindSafe(|| {
+            let path = to_rust_str(path).unwrap_or_default();
+            let state = impulse_core::trust::state(std::path::Path::new(&path));
+            let json = serde_json::to_value(state).unwrap_or_default();
+            to_c_string(json.as_str().unwrap_or("unknown"))
+        }),
+    )
+}
+
+/// The folder a trust decision for `path` applies to. Caller frees.
+#[no_mangle]
+pub extern "C" fn impulse_trust_workspace_folder(path: *const c_char) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let path = to_rust_str(path).unwrap_or_default();
+            let folder = impulse_core::trust::workspace_folder(std::path::Path::new(&path));
+            to_c_string(&folder.to_string_lossy())
+        }),
+    )
+}
+
+/// Trust `folder` or keep it restricted. Returns null on success or an
+/// error message (caller frees).
+#[no_mangle]
+pub extern "C" fn impulse_trust_set(folder: *const c_char, trusted: bool) -> *mut c_char {
+    ffi_catch(
+        to_c_string("Failed to save workspace trust"),
+        AssertUnwindSafe(|| {
+            let folder = to_rust_str(folder).unwrap_or_default();
+            match impulse_core::trust::set_trusted(std::path::Path::new(&folder), trusted) {
+                Ok(()) => std::ptr::null_mut(),
+                Err(e) => to_c_string(&e),
+            }
+        }),
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Assistant
+// ---------------------------------------------------------------------------
+//
+// Requests go to the provider in the current settings and fail while
+// `assistant_enabled` is off. They block until the model answers (up to two
+// minutes), so call them off the main thread. Results are
+// `{"reply": "..."}` or `{"error": "..."}`.
+
+fn assistant_reply(result: Result<String, String>) -> *mut c_char {
+    let json = match result {
+        Ok(reply) => serde_json::json!({ "reply": reply }),
+        Err(e) => serde_json::json!({ "error": e }),
+    };
+    to_c_string(&json.to_string())
+}
+
+/// Continue a conversation given as a JSON array of `{"role", "content"}`
+/// (`role` is `user` or `assistant`). The system prompt is added here.
+#[no_mangle]
+pub extern "C" fn impulse_assistant_chat(messages_json: *const c_char) -> *mut c_char {
     ffi_catch(
-        false,
+        std::ptr::null_mut(),
         AssertUnwindSafe(|| {
-            let path = match to_rust_str(path) {
-                Some(s) => s,
-                None => return false,
-            };
-            impulse_editor::markdown::is_markdown_file(&path)
+            let json = to_rust_str(messages_json).unwrap_or_default();
+            let result = serde_json::from_str::<Vec<impulse_core::assistant::ChatMessage>>(&json)
+                .map_err(|e| format!("Invalid messages JSON: {}", e))
+                .and_then(|messages| {
+                    let settings = impulse_core::settings::load();
+                    let config =
+                        impulse_core::assistant::AssistantConfig::from_settings(&settings)?;
+                    impulse_core::assistant::chat(
+                        &config,
+                        &impulse_core::assistant::with_system_prompt(&messages),
+                    )
+                });
+            assistant_reply(result)
         }),
     )
 }
 
-/// Render an SVG source string to a themed HTML preview document.
-///
-/// Returns a newly allocated HTML string (caller must free with `impulse_free_string`),
-/// or null on failure.
+/// Explain `code`, selected in `file_path` (may be null or empty for an
+/// unsaved buffer) written in `language`.
 #[no_mangle]
-pub extern "C" fn impulse_render_svg_preview(
-    source: *const c_char,
-    bg_color: *const c_char,
+pub extern "C" fn impulse_assistant_explain_selection(
+    code: *const c_char,
+    language: *const c_char,
+    file_path: *const c_char,
 ) -> *mut c_char {
     ffi_catch(
         std::ptr::null_mut(),
         AssertUnwindSafe(|| {
-            let source = match to_rust_str(source) {
-                Some(s) => s,
-                None => return std::ptr::null_mut(),
-            };
-            let bg_color = match to_rust_str(bg_color) {
-                Some(s) => s,
-                None => return std::ptr::null_mut(),
-            };
-            let html = match impulse_editor::svg::render_svg_preview(&source, &bg_color) {
-                Some(h) => h,
-                None => return std::ptr::null_mut(),
-            };
-            to_c_string(&html)
+            let code = to_rust_str(code).unwrap_or_default();
+            let language = to_rust_str(language).unwrap_or_default();
+            let file_path = to_rust_str(file_path).filter(|p| !p.is_empty());
+            let settings = impulse_core::settings::load();
+            assistant_reply(impulse_core::assistant::explain_selection(
+                &settings,
+                &code,
+                &language,
+                file_path.as_deref(),
+            ))
         }),
     )
 }
 
-/// Check whether a file path has an SVG extension.
+/// A commit message for the uncommitted changes in the repository
+/// containing `repo_path`: the staged ones if any, otherwise all of them.
 #[no_mangle]
-pub extern "C" fn impulse_is_svg_file(path: *const c_char) -> bool {
+pub extern "C" fn impulse_assistant_commit_message(repo_path: *const c_char) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let repo_path = to_rust_str(repo_path).unwrap_or_default();
+            let settings = impulse_core::settings::load();
+            assistant_reply(impulse_core::assistant::generate_commit_message(
+                &settings, &repo_path,
+            ))
+        }),
+    )
+}
+
+/// Store the API key for `provider` (`openai` or `ollama`) in the system
+/// keyring; an empty key removes it. Returns false on failure.
+#[no_mangle]
+pub extern "C" fn impulse_assistant_set_api_key(
+    provider: *const c_char,
+    key: *const c_char,
+) -> bool {
     ffi_catch(
         false,
         AssertUnwindSafe(|| {
-            let path = match to_rust_str(path) {
-                Some(s) => s,
-                None => return false,
+            let provider = to_rust_str(provider).unwrap_or_default();
+            let Some(provider) = impulse_core::assistant::Provider::from_id(&provider) else {
+                return false;
             };
-            impulse_editor::svg::is_svg_file(&path)
+            let key = to_rust_str(key).unwrap_or_default();
+            match impulse_core::assistant::set_api_key(provider, &key) {
+                Ok(()) => true,
+                Err(e) => {
+                    log::warn!("{}", e);
+                    false
+                }
+            }
         }),
     )
 }
 
-/// Check whether a file path is a previewable type (markdown or SVG).
+/// Whether an API key is stored for `provider`.
 #[no_mangle]
-pub extern "C" fn impulse_is_previewable_file(path: *const c_char) -> bool {
+pub extern "C" fn impulse_assistant_has_api_key(provider: *const c_char) -> bool {
     ffi_catch(
         false,
         AssertUnwindSafe(|| {
-            let path = match to_rust_str(path) {
-                Some(s) => s,
-                None => return false,
-            };
-            impulse_editor::is_previewable_file(&path)
+            let provider = to_rust_str(provider).unwrap_or_default();
+            impulse_core::assistant::Provider::from_id(&provider)
+                .and_then(impulse_core::assistant::api_key)
+                .is_some()
         }),
     )
 }
 
 // ---------------------------------------------------------------------------
-// Settings
+// Secrets
 // ---------------------------------------------------------------------------
+//
+// Tokens and passwords in the system keyring (see `impulse_core::secrets`).
+// Kinds are `git_credential`, `assistant_key`, `remote_password` and
+// `ssh_passphrase`.
+
+fn secret_kind(kind: *const c_char) -> Option<impulse_core::secrets::SecretKind> {
+    let kind = to_rust_str(kind)?;
+    serde_json::from_value(serde_json::Value::String(kind)).ok()
+}
 
-/// Return default settings as a JSON string.
+/// The stored secrets as a JSON array of `{"id", "kind", "label", "name",
+/// "updated_at"}`, without their values.
 #[no_mangle]
-pub extern "C" fn impulse_settings_default_json() -> *mut c_char {
+pub extern "C" fn impulse_secrets_list() -> *mut c_char {
     ffi_catch(
         std::ptr::null_mut(),
-        AssertUnwindSafe(|| to_c_string(&impulse_core::settings::Settings::default_json())),
+        AssertUnwindSafe(|| {
+            let secrets: Vec<serde_json::Value> = impulse_core::secrets::list()
+                .into_iter()
+                .map(|info| {
+                    let mut json = serde_json::to_value(&info).unwrap_or_default();
+                    json["label"] = serde_json::json!(info.kind.label());
+                    json
+                })
+                .collect();
+            to_c_string(&serde_json::Value::Array(secrets).to_string())
+        }),
     )
 }
 
-/// Return the generated JSON Schema for settings.
+/// The secret of `kind` named `name`, or null when none is stored.
 #[no_mangle]
-pub extern "C" fn impulse_settings_schema_json() -> *mut c_char {
+pub extern "C" fn impulse_secret_get(kind: *const c_char, name: *const c_char) -> *mut c_char {
     ffi_catch(
         std::ptr::null_mut(),
-        AssertUnwindSafe(|| to_c_string(&impulse_core::settings::Settings::schema_json())),
+        AssertUnwindSafe(|| {
+            let (Some(kind), Some(name)) = (secret_kind(kind), to_rust_str(name)) else {
+                return std::ptr::null_mut();
+            };
+            match impulse_core::secrets::get(kind, &name) {
+                Some(value) => to_c_string(&value),
+                None => std::ptr::null_mut(),
+            }
+        }),
     )
 }
 
-/// Parse, migrate, and validate a raw settings JSON string.
-/// Returns the cleaned JSON. If the input is null or invalid, returns default settings.
+/// Store `value` as the secret of `kind` named `name`; an empty value
+/// removes it. Returns false on failure.
 #[no_mangle]
-pub extern "C" fn impulse_settings_load_json(json: *const c_char) -> *mut c_char {
+pub extern "C" fn impulse_secret_set(
+    kind: *const c_char,
+    name: *const c_char,
+    value: *const c_char,
+) -> bool {
     ffi_catch(
-        std::ptr::null_mut(),
+        false,
         AssertUnwindSafe(|| {
-            let raw = to_rust_str(json).unwrap_or_default();
-            let settings = impulse_core::settings::Settings::from_json(&raw).unwrap_or_default();
-            let result = settings
-                .to_json()
-                .unwrap_or_else(|_| impulse_core::settings::Settings::default_json());
-            to_c_string(&result)
+            let (Some(kind), Some(name)) = (secret_kind(kind), to_rust_str(name)) else {
+                return false;
+            };
+            let value = to_rust_str(value).unwrap_or_default();
+            match impulse_core::secrets::set(kind, &name, &value) {
+                Ok(()) => true,
+                Err(e) => {
+                    log::warn!("{}", e);
+                    false
+                }
+            }
         }),
     )
 }
 
-/// Validate/clamp a settings JSON string and return the cleaned version.
+/// Remove the secret with `id` (from the list). Returns false on failure.
 #[no_mangle]
-pub extern "C" fn impulse_settings_validate_json(json: *const c_char) -> *mut c_char {
+pub extern "C" fn impulse_secret_delete(id: *const c_char) -> bool {
     ffi_catch(
-        std::ptr::null_mut(),
+        false,
         AssertUnwindSafe(|| {
-            let raw = match to_rust_str(json) {
-                Some(s) => s,
-                None => return to_c_string(&impulse_core::settings::Settings::default_json()),
+            let Some(id) = to_rust_str(id) else {
+                return false;
             };
-            let mut settings: impulse_core::settings::Settings =
-                serde_json::from_str(&raw).unwrap_or_default();
-            settings.validate();
-            let result = settings
-                .to_json()
-                .unwrap_or_else(|_| impulse_core::settings::Settings::default_json());
-            to_c_string(&result)
+            match impulse_core::secrets::delete(&id) {
+                Ok(()) => true,
+                Err(e) => {
+                    log::warn!("{}", e);
+                    false
+                }
+            }
         }),
     )
 }
 
 // ---------------------------------------------------------------------------
-// Command palette
+// Agent access (MCP)
 // ---------------------------------------------------------------------------
+//
+// One workspace at a time is shared with agents (see `impulse_core::mcp`).
+// The frontend asks for consent, starts the server, then polls
+// `impulse_mcp_next_request` on its main loop and answers each request with
+// `impulse_mcp_respond`.
+
+struct AgentAccess {
+    server: impulse_core::mcp::McpServer,
+    /// Requests handed to the frontend and not yet answered, by id.
+    pending: HashMap<u64, impulse_core::mcp::HostRequest>,
+}
+
+fn agent_access() -> &'static parking_lot::Mutex<Option<AgentAccess>> {
+    static ACCESS: OnceLock<parking_lot::Mutex<Option<AgentAccess>>> = OnceLock::new();
+    ACCESS.get_or_init(|| parking_lot::Mutex::new(None))
+}
 
+/// The consent recorded for `folder`: `"allowed"`, `"denied"` or `"unknown"`.
 #[no_mangle]
-pub extern "C" fn impulse_command_palette_builtin_items_json() -> *mut c_char {
+pub extern "C" fn impulse_mcp_consent(folder: *const c_char) -> *mut c_char {
     ffi_catch(
         std::ptr::null_mut(),
         AssertUnwindSafe(|| {
-            match serde_json::to_string(&impulse_core::command_palette::builtin_items()) {
-                Ok(json) => to_c_string(&json),
-                Err(e) => to_c_string(&serde_json::json!({"error": e.to_string()}).to_string()),
-            }
+            let folder = to_rust_str(folder).unwrap_or_default();
+            let consent = impulse_core::mcp::consent(std::path::Path::new(&folder));
+            let json = serde_json::to_value(consent).unwrap_or_default();
+            to_c_string(json.as_str().unwrap_or("unknown"))
         }),
     )
 }
 
+/// Allow or deny agent access to `folder`. Denying also stops sharing it.
 #[no_mangle]
-pub extern "C" fn impulse_command_palette_custom_item_json(
-    name: *const c_char,
-    shortcut: *const c_char,
-    command: *const c_char,
-    args_json: *const c_char,
-) -> *mut c_char {
+pub extern "C" fn impulse_mcp_set_consent(folder: *const c_char, allowed: bool) -> bool {
     ffi_catch(
-        std::ptr::null_mut(),
+        false,
         AssertUnwindSafe(|| {
-            let name = to_rust_str(name).unwrap_or_default();
-            let shortcut = to_rust_str(shortcut);
-            let command = to_rust_str(command).unwrap_or_default();
-            let args: Vec<String> = to_rust_str(args_json)
-                .and_then(|json| serde_json::from_str(&json).ok())
-                .unwrap_or_default();
-            let item = impulse_core::command_palette::custom_command_item(
-                &name,
-                shortcut.as_deref(),
-                &command,
-                &args,
-            );
-            match serde_json::to_string(&item) {
-                Ok(json) => to_c_string(&json),
-                Err(e) => to_c_string(&serde_json::json!({"error": e.to_string()}).to_string()),
+            let Some(folder) = to_rust_str(folder) else {
+                return false;
+            };
+            let folder = std::path::Path::new(&folder);
+            if let Err(e) = impulse_core::mcp::set_consent(folder, allowed) {
+                log::warn!("{}", e);
+                return false;
+            }
+            if !allowed {
+                let mut access = agent_access().lock();
+                if access.as_ref().is_some_and(|a| {
+                    a.server.workspace() == impulse_core::trust::workspace_folder(folder)
+                }) {
+                    *access = None;
+                }
             }
+            true
         }),
     )
 }
 
+/// Share the workspace containing `path` with agents, replacing any shared
+/// workspace. Fails unless the user allowed it. Returns `{"socket": "..."}`
+/// or `{"error": "..."}`.
 #[no_mangle]
-pub extern "C" fn impulse_command_palette_filter_json(
-    items_json: *const c_char,
-    recents_json: *const c_char,
-    query: *const c_char,
-) -> *mut c_char {
+pub extern "C" fn impulse_mcp_start(path: *const c_char) -> *mut c_char {
     ffi_catch(
         std::ptr::null_mut(),
         AssertUnwindSafe(|| {
-            let items: Vec<impulse_core::command_palette::CommandPaletteItem> =
-                to_rust_str(items_json)
-                    .and_then(|json| serde_json::from_str(&json).ok())
-                    .unwrap_or_default();
-            let recents: impulse_core::command_palette::RecentCommandStore =
-                to_rust_str(recents_json)
-                    .and_then(|json| serde_json::from_str(&json).ok())
-                    .unwrap_or_default();
-            let query = to_rust_str(query).unwrap_or_default();
-            let filtered = impulse_core::command_palette::filter_items(&items, &recents, &query);
-            match serde_json::to_string(&filtered) {
-                Ok(json) => to_c_string(&json),
-                Err(e) => to_c_string(&serde_json::json!({"error": e.to_string()}).to_string()),
-            }
+            let path = to_rust_str(path).unwrap_or_default();
+            let folder = impulse_core::trust::workspace_folder(std::path::Path::new(&path));
+            let mut access = agent_access().lock();
+            *access = None;
+            let result = if impulse_core::mcp::consent(&folder)
+                != impulse_core::mcp::Consent::Allowed
+            {
+                Err(format!(
+                    "Agent access to {} is not allowed",
+                    folder.display()
+                ))
+            } else {
+                impulse_core::mcp::McpServer::start(&folder).map(|server| {
+                    let json = serde_json::json!({ "socket": server.socket_path() }).to_string();
+                    *access = Some(AgentAccess {
+                        server,
+                        pending: HashMap::new(),
+                    });
+                    json
+                })
+            };
+            to_c_string(&json_or_error(result))
         }),
     )
 }
 
+/// Stop sharing and disconnect every agent. Pending requests fail.
 #[no_mangle]
-pub extern "C" fn impulse_command_palette_record_recent_json(
-    recents_json: *const c_char,
-    item_json: *const c_char,
-    now_ms: u64,
-    max_items: usize,
-) -> *mut c_char {
+pub extern "C" fn impulse_mcp_stop() {
+    ffi_catch(
+        (),
+        AssertUnwindSafe(|| {
+            *agent_access().lock() = None;
+        }),
+    )
+}
+
+/// The next request an agent is waiting on, as JSON: `{"id", "kind":
+/// "open_files"}` or `{"id", "kind": "apply_edits", "files": [...]}`.
+/// Null when there is none.
+#[no_mangle]
+pub extern "C" fn impulse_mcp_next_request() -> *mut c_char {
     ffi_catch(
         std::ptr::null_mut(),
         AssertUnwindSafe(|| {
-            let mut recents: impulse_core::command_palette::RecentCommandStore =
-                to_rust_str(recents_json)
-                    .and_then(|json| serde_json::from_str(&json).ok())
-                    .unwrap_or_default();
-            let Some(item_json) = to_rust_str(item_json) else {
+            let mut guard = agent_access().lock();
+            let Some(access) = guard.as_mut() else {
                 return std::ptr::null_mut();
             };
-            let Ok(item) = serde_json::from_str(&item_json) else {
+            let Some(request) = access.server.next_request() else {
                 return std::ptr::null_mut();
             };
-            recents.record(&item, now_ms, max_items);
-            match serde_json::to_string(&recents) {
-                Ok(json) => to_c_string(&json),
-                Err(e) => to_c_string(&serde_json::json!({"error": e.to_string()}).to_string()),
-            }
+            let mut json = serde_json::to_value(&request.kind).unwrap_or_default();
+            json["id"] = serde_json::json!(request.id);
+            access.pending.insert(request.id, request);
+            to_c_string(&json.to_string())
         }),
     )
 }
 
+/// Answer request `id`: `result_json` is the open files array or the edit
+/// summary string; a non-null `error` fails the request instead.
 #[no_mangle]
-pub extern "C" fn impulse_command_palette_search_items_json(
-    root: *const c_char,
-    query: *const c_char,
-    limit: usize,
-) -> *mut c_char {
+pub extern "C" fn impulse_mcp_respond(
+    id: u64,
+    result_json: *const c_char,
+    error: *const c_char,
+) -> bool {
     ffi_catch(
-        std::ptr::null_mut(),
+        false,
         AssertUnwindSafe(|| {
-            let root = match to_rust_str(root) {
-                Some(root) => root,
-                None => return std::ptr::null_mut(),
+            let Some(request) = agent_access()
+                .lock()
+                .as_mut()
+                .and_then(|access| access.pending.remove(&id))
+            else {
+                return false;
             };
-            let query = match to_rust_str(query) {
-                Some(query) => query,
-                None => return std::ptr::null_mut(),
+            let result = match to_rust_str(error) {
+                Some(error) => Err(error),
+                None => serde_json::from_str(&to_rust_str(result_json).unwrap_or_default())
+                    .map_err(|e| format!("Invalid response from Impulse: {}", e)),
             };
-            match impulse_core::command_palette::search_items(&root, &query, limit) {
-                Ok(items) => match serde_json::to_string(&items) {
-                    Ok(json) => to_c_string(&json),
-                    Err(e) => to_c_string(&serde_json::json!({"error": e.to_string()}).to_string()),
-                },
-                Err(e) => to_c_string(&serde_json::json!({"error": e}).to_string()),
+            request.respond(result);
+            true
+        }),
+    )
+}
+
+/// Record the diagnostics of the file at `path` for agents: a JSON array of
+/// `{"line", "character", "end_line", "end_character", "severity",
+/// "message"}`.
+#[no_mangle]
+pub extern "C" fn impulse_mcp_set_diagnostics(
+    path: *const c_char,
+    diagnostics_json: *const c_char,
+) {
+    ffi_catch(
+        (),
+        AssertUnwindSafe(|| {
+            let Some(path) = to_rust_str(path) else {
+                return;
+            };
+            let json = to_rust_str(diagnostics_json).unwrap_or_default();
+            let diagnostics = match serde_json::from_str(&json) {
+                Ok(diagnostics) => diagnostics,
+                Err(e) => {
+                    log::warn!("Invalid diagnostics JSON: {}", e);
+                    return;
+                }
+            };
+            if let Some(access) = agent_access().lock().as_ref() {
+                access.server.set_diagnostics(&path, diagnostics);
             }
         }),
     )
 }
 
-/// Check for a newer version on GitHub Releases.
-///
-/// Returns a JSON string `{"version":"X.Y.Z","url":"..."}` if an update is
-/// available, an empty string if up-to-date or checked recently, or an
-/// `"ERROR:..."` string on failure. Caller must free with `impulse_free_string`.
+// ---------------------------------------------------------------------------
+// Control socket
+// ---------------------------------------------------------------------------
+//
+// Scripts and tools drive the app over `impulse_core::control`. The frontend
+// starts the server while `control_socket_enabled` is on, polls
+// `impulse_control_next_call` on its main loop and answers each call with
+// `impulse_control_respond`.
+
+struct ControlSocket {
+    server: impulse_core::control::ControlServer,
+    /// Calls handed to the frontend and not yet answered, by id.
+    pending: HashMap<u64, impulse_core::control::ControlCall>,
+}
+
+fn control_socket() -> &'static parking_lot::Mutex<Option<ControlSocket>> {
+    static SOCKET: OnceLock<parking_lot::Mutex<Option<ControlSocket>>> = OnceLock::new();
+    SOCKET.get_or_init(|| parking_lot::Mutex::new(None))
+}
+
+/// Start listening on the control socket unless already listening. Returns
+/// `{"socket": "..."}` or `{"error": "..."}`.
 #[no_mangle]
-pub extern "C" fn impulse_check_for_update() -> *mut c_char {
+pub extern "C" fn impulse_control_start() -> *mut c_char {
     ffi_catch(
         std::ptr::null_mut(),
-        AssertUnwindSafe(|| match impulse_core::update::check_for_update() {
-            Ok(Some(info)) => {
-                let json = serde_json::json!({
-                    "version": info.version,
-                    "current_version": info.current_version,
-                    "url": info.url,
-                });
-                to_c_string(&json.to_string())
-            }
-            Ok(None) => to_c_string(""),
-            Err(e) => to_c_string(&format!("ERROR:{}", e)),
+        AssertUnwindSafe(|| {
+            let mut socket = control_socket().lock();
+            let result = match socket.as_ref() {
+                Some(running) => {
+                    Ok(serde_json::json!({ "socket": running.server.socket_path() }).to_string())
+                }
+                None => impulse_core::control::ControlServer::start().map(|server| {
+                    let json = serde_json::json!({ "socket": server.socket_path() }).to_string();
+                    *socket = Some(ControlSocket {
+                        server,
+                        pending: HashMap::new(),
+                    });
+                    json
+                }),
+            };
+            to_c_string(&json_or_error(result))
+        }),
+    )
+}
+
+/// Stop listening. Pending calls fail.
+#[no_mangle]
+pub extern "C" fn impulse_control_stop() {
+    ffi_catch(
+        (),
+        AssertUnwindSafe(|| {
+            *control_socket().lock() = None;
         }),
     )
 }
 
-/// Return the current application version string.
-///
-/// The returned pointer is valid for the lifetime of the process and must
-/// NOT be freed.
+/// The next call a client is waiting on, as JSON: `{"id", "method",
+/// "params"}`, with params already validated. Null when there is none.
 #[no_mangle]
-pub extern "C" fn impulse_get_version() -> *const c_char {
+pub extern "C" fn impulse_control_next_call() -> *mut c_char {
     ffi_catch(
-        std::ptr::null(),
+        std::ptr::null_mut(),
         AssertUnwindSafe(|| {
-            static CACHED: std::sync::OnceLock<CString> = std::sync::OnceLock::new();
-            CACHED
-                .get_or_init(|| {
-                    CString::new(impulse_core::update::CURRENT_VERSION).unwrap_or_default()
-                })
-                .as_ptr()
+            let mut guard = control_socket().lock();
+            let Some(socket) = guard.as_mut() else {
+                return std::ptr::null_mut();
+            };
+            let Some(call) = socket.server.next_call() else {
+                return std::ptr::null_mut();
+            };
+            let mut json = serde_json::to_value(&call.request).unwrap_or_default();
+            json["id"] = serde_json::json!(call.id);
+            socket.pending.insert(call.id, call);
+            to_c_string(&json.to_string())
         }),
     )
 }
 
-/// Check whether a file path matches a glob-style pattern.
+/// Answer call `id` with `result_json` (`null` when there is nothing to
+/// return, a `ControlState` for `query-state`); a non-null `error` fails the
+/// call instead.
 #[no_mangle]
-pub extern "C" fn impulse_matches_file_pattern(
-    path: *const c_char,
-    pattern: *const c_char,
+pub extern "C" fn impulse_control_respond(
+    id: u64,
+    result_json: *const c_char,
+    error: *const c_char,
 ) -> bool {
     ffi_catch(
         false,
         AssertUnwindSafe(|| {
-            let path = match to_rust_str(path) {
-                Some(s) => s,
-                None => return false,
+            let Some(call) = control_socket()
+                .lock()
+                .as_mut()
+                .and_then(|socket| socket.pending.remove(&id))
+            else {
+                return false;
             };
-            let pattern = match to_rust_str(pattern) {
-                Some(s) => s,
-                None => return false,
+            let result = match to_rust_str(error) {
+                Some(error) => Err(error),
+                None => serde_json::from_str(&to_rust_str(result_json).unwrap_or_default())
+                    .map_err(|e| format!("Invalid response from Impulse: {}", e)),
             };
-            impulse_core::util::matches_file_pattern(&path, &pattern)
+            call.respond(result);
+            true
         }),
     )
 }
 
+// ---------------------------------------------------------------------------
+// Notifications
+// ---------------------------------------------------------------------------
+
+/// Record a notification given as JSON (`severity`, `title` and optionally
+/// `message`, `source` and `actions`). Returns its id, or 0 if the JSON is
+/// invalid.
 #[no_mangle]
-pub extern "C" fn impulse_close_risk_summary(input_json: *const c_char) -> *mut c_char {
+pub extern "C" fn impulse_notifications_push(json: *const c_char) -> u64 {
     ffi_catch(
-        std::ptr::null_mut(),
+        0,
         AssertUnwindSafe(|| {
-            let Some(raw) = to_rust_str(input_json) else {
-                return to_c_string(
-                    &serde_json::json!({
-                        "has_risk": false,
-                        "error": "missing close risk input"
-                    })
-                    .to_string(),
-                );
-            };
-            let input: impulse_core::close_risk::CloseRiskInput = match serde_json::from_str(&raw) {
-                Ok(input) => input,
-                Err(err) => {
-                    return to_c_string(
-                        &serde_json::json!({
-                            "has_risk": false,
-                            "error": format!("invalid close risk input: {err}")
-                        })
-                        .to_string(),
-                    );
+            let json = to_rust_str(json).unwrap_or_default();
+            match serde_json::from_str(&json) {
+                Ok(notification) => impulse_core::notifications::push(notification),
+                Err(e) => {
+                    log::warn!("Invalid notification JSON: {}", e);
+                    0
                 }
-            };
-            match serde_json::to_string(&input.summarize()) {
-                Ok(json) => to_c_string(&json),
-                Err(err) => to_c_string(
-                    &serde_json::json!({
-                        "has_risk": false,
-                        "error": format!("close risk serialization failed: {err}")
-                    })
-                    .to_string(),
-                ),
             }
         }),
     )
 }
 
+/// The notification history as a JSON array, newest first. Caller frees.
+#[no_mangle]
+pub extern "C" fn impulse_notifications_json() -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(
+            || match serde_json::to_string(&impulse_core::notifications::list()) {
+                Ok(json) => to_c_string(&json),
+                Err(_) => std::ptr::null_mut(),
+            },
+        ),
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn impulse_notifications_mark_all_read() {
+    ffi_catch(
+        (),
+        AssertUnwindSafe(impulse_core::notifications::mark_all_read),
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn impulse_notifications_dismiss(id: u64) {
+    ffi_catch(
+        (),
+        AssertUnwindSafe(|| impulse_core::notifications::dismiss(id)),
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn impulse_notifications_clear() {
+    ffi_catch((), AssertUnwindSafe(impulse_core::notifications::clear))
+}
+
 // ---------------------------------------------------------------------------
 // Theme API
 // ---------------------------------------------------------------------------
@@ -1876,6 +5382,25 @@ pub extern "C" fn impulse_theme_display_name(id: *const c_char) -> *mut c_char {
     )
 }
 
+/// Whether the theme getters below return the increased-contrast variant.
+static INCREASE_CONTRAST: AtomicBool = AtomicBool::new(false);
+
+/// Use the increased-contrast variant of every theme from now on, when the
+/// `increase_contrast` setting or the system's Increase Contrast option is on.
+#[no_mangle]
+pub extern "C" fn impulse_theme_set_increase_contrast(enabled: bool) {
+    INCREASE_CONTRAST.store(enabled, Ordering::Relaxed);
+}
+
+fn resolve_theme(name: &str) -> impulse_core::theme::ResolvedTheme {
+    let theme = impulse_core::theme::get_theme(name);
+    if INCREASE_CONTRAST.load(Ordering::Relaxed) {
+        theme.with_increased_contrast()
+    } else {
+        theme
+    }
+}
+
 /// Resolve a theme by name and return the full `ResolvedTheme` as JSON.
 #[no_mangle]
 pub extern "C" fn impulse_get_theme(name: *const c_char) -> *mut c_char {
@@ -1886,7 +5411,7 @@ pub extern "C" fn impulse_get_theme(name: *const c_char) -> *mut c_char {
                 Some(s) => s,
                 None => "nord".to_string(),
             };
-            let theme = impulse_core::theme::get_theme(&name);
+            let theme = resolve_theme(&name);
             to_c_string(&impulse_core::theme::theme_to_json(&theme))
         }),
     )
@@ -1902,7 +5427,7 @@ pub extern "C" fn impulse_get_monaco_theme(name: *const c_char) -> *mut c_char {
                 Some(s) => s,
                 None => "nord".to_string(),
             };
-            let theme = impulse_core::theme::get_theme(&name);
+            let theme = resolve_theme(&name);
             let monaco = impulse_editor::protocol::theme_to_monaco(&theme);
             let json = serde_json::to_string(&monaco).unwrap_or_else(|_| "{}".to_string());
             to_c_string(&json)
@@ -1920,7 +5445,7 @@ pub extern "C" fn impulse_get_markdown_theme(name: *const c_char) -> *mut c_char
                 Some(s) => s,
                 None => "nord".to_string(),
             };
-            let theme = impulse_core::theme::get_theme(&name);
+            let theme = resolve_theme(&name);
             let md_colors = impulse_editor::markdown::theme_to_markdown_colors(&theme);
             let json = serde_json::to_string(&md_colors).unwrap_or_else(|_| "{}".to_string());
             to_c_string(&json)
@@ -1928,6 +5453,24 @@ pub extern "C" fn impulse_get_markdown_theme(name: *const c_char) -> *mut c_char
     )
 }
 
+/// Import the VS Code color theme file at `path` as a user theme. Returns
+/// `{"id": "<theme id>"}` or `{"error": "..."}`.
+#[no_mangle]
+pub extern "C" fn impulse_import_vscode_theme(path: *const c_char) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let Some(path) = to_rust_str(path) else {
+                return std::ptr::null_mut();
+            };
+            let result =
+                impulse_core::vscode_theme::import_vscode_theme_file(std::path::Path::new(&path))
+                    .map(|id| serde_json::json!({ "id": id }).to_string());
+            to_c_string(&json_or_error(result))
+        }),
+    )
+}
+
 // ---------------------------------------------------------------------------
 // Terminal backend API
 // ---------------------------------------------------------------------------
@@ -1951,13 +5494,20 @@ pub extern "C" fn impulse_terminal_create(
         std::ptr::null_mut(),
         AssertUnwindSafe(|| {
             let json = to_rust_str(config_json).unwrap_or_default();
-            let config: impulse_terminal::TerminalConfig = match serde_json::from_str(&json) {
+            let mut config: impulse_terminal::TerminalConfig = match serde_json::from_str(&json) {
                 Ok(c) => c,
                 Err(e) => {
                     log::error!("Failed to parse terminal config: {e}");
                     return std::ptr::null_mut();
                 }
             };
+            if let Some(dir) = &config.working_directory {
+                config
+                    .env_vars
+                    .extend(impulse_core::environment::project_env(
+                        std::path::Path::new(dir),
+                    ));
+            }
             match TerminalBackend::new(config, cols, rows, cell_width, cell_height) {
                 Ok(backend) => {
                     let handle = TerminalHandle { backend };
@@ -2199,6 +5749,38 @@ pub extern "C" fn impulse_completion_warm_cache() {
     ffi_catch((), AssertUnwindSafe(impulse_core::completion::warm_cache));
 }
 
+/// Start reading the login shell's environment in the background.
+#[no_mangle]
+pub extern "C" fn impulse_environment_warm() {
+    ffi_catch((), AssertUnwindSafe(impulse_core::environment::warm));
+}
+
+/// Apply the `use_direnv` setting.
+#[no_mangle]
+pub extern "C" fn impulse_environment_set_direnv_enabled(enabled: bool) {
+    ffi_catch(
+        (),
+        AssertUnwindSafe(|| impulse_core::environment::set_direnv_enabled(enabled)),
+    )
+}
+
+/// The environment for a process started in `dir` (may be NULL) as a JSON
+/// object. Blocks until the login environment has been read. Caller frees.
+#[no_mangle]
+pub extern "C" fn impulse_environment_json(dir: *const c_char) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let dir = to_rust_str(dir);
+            let env = impulse_core::environment::resolve(dir.as_deref().map(std::path::Path::new));
+            match serde_json::to_string(&env) {
+                Ok(json) => to_c_string(&json),
+                Err(_) => std::ptr::null_mut(),
+            }
+        }),
+    )
+}
+
 /// Best inline completion for the input bar. `input` is the current text,
 /// `cwd` the terminal's working directory (may be NULL). Returns the full
 /// completed line (always starts with `input`), or NULL when there's none.
@@ -2362,6 +5944,21 @@ pub extern "C" fn impulse_terminal_selected_text(handle: *mut TerminalHandle) ->
     )
 }
 
+/// The terminal's whole buffer as text, scrollback included.
+#[no_mangle]
+pub extern "C" fn impulse_terminal_scrollback_text(handle: *mut TerminalHandle) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            if handle.is_null() {
+                return std::ptr::null_mut();
+            }
+            let h = unsafe { &*handle };
+            to_c_string(&h.backend.scrollback_text())
+        }),
+    )
+}
+
 #[no_mangle]
 pub extern "C" fn impulse_terminal_scroll(handle: *mut TerminalHandle, delta: i32) {
     ffi_catch(
@@ -2571,3 +6168,199 @@ pub extern "C" fn impulse_terminal_search_clear(handle: *mut TerminalHandle) {
         }),
     )
 }
+
+// ---------------------------------------------------------------------------
+// Raw PTY API
+// ---------------------------------------------------------------------------
+//
+// Process management without terminal emulation, for hosts that bring their
+// own emulator. Output is pushed to a callback on the session's dedicated
+// reader thread (never concurrently with itself): `data`/`len` is a borrowed
+// chunk valid only during the call. When the child exits the callback is
+// called once more with `data == NULL`, `len == 0`, and the exit code (-1 if
+// unknown). After `impulse_pty_free` returns the callback is never invoked
+// again; freeing from inside the callback is allowed.
+
+use impulse_terminal::pty::{PtyEvent, PtyProfile, PtySession};
+
+/// C callback receiving PTY output chunks, or the exit code when `data` is null.
+pub type ImpulsePtyCallback =
+    extern "C" fn(context: *mut c_void, data: *const u8, len: usize, exit_code: i32);
+
+#[derive(Clone, Copy)]
+struct PtySubscriber {
+    callback: ImpulsePtyCallback,
+    context: *mut c_void,
+}
+
+// SAFETY: see `EventSubscriber`.
+unsafe impl Send for PtySubscriber {}
+
+/// Held (reentrantly) for every callback invocation; cleared on free.
+type PtySubscriberSlot = Arc<ReentrantMutex<Cell<Option<PtySubscriber>>>>;
+
+/// Opaque handle passed across FFI — never constructed by external code.
+struct PtyHandle {
+    session: PtySession,
+    subscriber: PtySubscriberSlot,
+}
+
+/// Spawn a process in a new PTY. `profile_json` is
+/// `{"shell_path", "shell_args", "working_directory", "env_vars", "cols",
+/// "rows"}` (all optional; an empty `shell_path` runs the login shell).
+/// Returns null on failure. Free with `impulse_pty_free`.
+#[no_mangle]
+pub extern "C" fn impulse_pty_spawn(
+    profile_json: *const c_char,
+    callback: Option<ImpulsePtyCallback>,
+    context: *mut c_void,
+) -> *mut PtyHandle {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            let json = to_rust_str(profile_json).unwrap_or_else(|| "{}".to_string());
+            let mut profile: PtyProfile = match serde_json::from_str(&json) {
+                Ok(p) => p,
+                Err(e) => {
+                    log::error!("Failed to parse PTY profile: {e}");
+                    return std::ptr::null_mut();
+                }
+            };
+            if let Some(dir) = &profile.working_directory {
+                profile
+                    .env_vars
+                    .extend(impulse_core::environment::project_env(
+                        std::path::Path::new(dir),
+                    ));
+            }
+            let subscriber: PtySubscriberSlot = Arc::new(ReentrantMutex::new(Cell::new(
+                callback.map(|callback| PtySubscriber { callback, context }),
+            )));
+            let thread_subscriber = Arc::clone(&subscriber);
+            let session = PtySession::spawn(&profile, move |event| {
+                let guard = thread_subscriber.lock();
+                let Some(sub) = guard.get() else {
+                    return;
+                };
+                match event {
+                    PtyEvent::Output(bytes) => {
+                        (sub.callback)(sub.context, bytes.as_ptr(), bytes.len(), 0)
+                    }
+                    PtyEvent::Exited(code) => {
+                        (sub.callback)(sub.context, std::ptr::null(), 0, code)
+                    }
+                }
+            });
+            match session {
+                Ok(session) => Box::into_raw(Box::new(PtyHandle {
+                    session,
+                    subscriber,
+                })),
+                Err(e) => {
+                    log::error!("Failed to spawn PTY: {e}");
+                    std::ptr::null_mut()
+                }
+            }
+        }),
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn impulse_pty_write(handle: *mut PtyHandle, data: *const u8, len: usize) {
+    ffi_catch(
+        (),
+        AssertUnwindSafe(|| {
+            if handle.is_null() || data.is_null() || len == 0 {
+                return;
+            }
+            let h = unsafe { &*handle };
+            let bytes = unsafe { std::slice::from_raw_parts(data, len) };
+            h.session.write(bytes);
+        }),
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn impulse_pty_resize(handle: *mut PtyHandle, cols: u16, rows: u16) {
+    ffi_catch(
+        (),
+        AssertUnwindSafe(|| {
+            if handle.is_null() {
+                return;
+            }
+            let h = unsafe { &*handle };
+            h.session.resize(cols, rows);
+        }),
+    )
+}
+
+/// Send `signal` (e.g. SIGTERM, SIGKILL) to the child. Returns 0 on success.
+#[no_mangle]
+pub extern "C" fn impulse_pty_kill(handle: *mut PtyHandle, signal: i32) -> i32 {
+    ffi_catch(
+        -1,
+        AssertUnwindSafe(|| {
+            if handle.is_null() {
+                return -1;
+            }
+            let h = unsafe { &*handle };
+            match h.session.kill(signal) {
+                Ok(()) => 0,
+                Err(e) => {
+                    log::warn!("{e}");
+                    -1
+                }
+            }
+        }),
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn impulse_pty_child_pid(handle: *mut PtyHandle) -> u32 {
+    ffi_catch(
+        0,
+        AssertUnwindSafe(|| {
+            if handle.is_null() {
+                return 0;
+            }
+            let h = unsafe { &*handle };
+            h.session.child_pid()
+        }),
+    )
+}
+
+/// The child's current working directory, or null if unavailable.
+/// The caller must free the returned string with `impulse_free_string`.
+#[no_mangle]
+pub extern "C" fn impulse_pty_cwd(handle: *mut PtyHandle) -> *mut c_char {
+    ffi_catch(
+        std::ptr::null_mut(),
+        AssertUnwindSafe(|| {
+            if handle.is_null() {
+                return std::ptr::null_mut();
+            }
+            let h = unsafe { &*handle };
+            match h.session.cwd() {
+                Some(cwd) => to_c_string(&cwd),
+                None => std::ptr::null_mut(),
+            }
+        }),
+    )
+}
+
+/// Close the PTY (hanging up the child) and free the handle.
+#[no_mangle]
+pub extern "C" fn impulse_pty_free(handle: *mut PtyHandle) {
+    ffi_catch(
+        (),
+        AssertUnwindSafe(|| {
+            if handle.is_null() {
+                return;
+            }
+            let h = unsafe { Box::from_raw(handle) };
+            h.subscriber.lock().set(None);
+            h.session.shutdown();
+            drop(h);
+        }),
+    )
+}
@@ -0,0 +1,289 @@
+//! Assistant chat panel docked on the right of the workspace. Talks to the
+//! provider configured in settings through `impulse_core::assistant`; the
+//! conversation lasts until it is cleared or the window closes.
+
+use gtk4::prelude::*;
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use impulse_core::assistant::{self, ChatMessage, Role};
+
+use crate::settings::Settings;
+
+const PANEL_WIDTH: i32 = 360;
+
+pub struct AssistantPanel {
+    pub widget: gtk4::Box,
+    messages_box: gtk4::Box,
+    scroll: gtk4::ScrolledWindow,
+    input: gtk4::TextView,
+    send_btn: gtk4::Button,
+    spinner: gtk4::Spinner,
+    settings: Rc<RefCell<Settings>>,
+    conversation: RefCell<Vec<ChatMessage>>,
+    busy: Cell<bool>,
+    /// Bumped by Clear so a reply to the old conversation is dropped.
+    generation: Cell<u64>,
+    /// Mirrors `settings.assistant_enabled`; kept here because settings
+    /// callbacks run while the settings are mutably borrowed.
+    enabled: Cell<bool>,
+}
+
+impl AssistantPanel {
+    pub fn new(settings: &Rc<RefCell<Settings>>) -> Rc<Self> {
+        let widget = gtk4::Box::new(gtk4::Orientation::Vertical, 0);
+        widget.add_css_class("assistant-panel");
+        widget.set_width_request(PANEL_WIDTH);
+        widget.set_visible(false);
+        widget.update_property(&[gtk4::accessible::Property::Label("Assistant")]);
+
+        // --- Header: title, spinner, clear, close ---
+        let header = gtk4::Box::new(gtk4::Orientation::Horizontal, 4);
+        header.add_css_class("assistant-panel-header");
+        let title = gtk4::Label::new(Some("Assistant"));
+        title.add_css_class("heading");
+        title.set_halign(gtk4::Align::Start);
+        title.set_hexpand(true);
+        header.append(&title);
+        let spinner = gtk4::Spinner::new();
+        spinner.set_visible(false);
+        header.append(&spinner);
+        let clear_btn = gtk4::Button::from_icon_name("edit-clear-all-symbolic");
+        clear_btn.set_tooltip_text(Some("Clear Conversation"));
+        clear_btn.add_css_class("flat");
+        header.append(&clear_btn);
+        let close_btn = gtk4::Button::from_icon_name("window-close-symbolic");
+        close_btn.set_tooltip_text(Some("Close"));
+        close_btn.add_css_class("flat");
+        header.append(&close_btn);
+        widget.append(&header);
+
+        // --- Conversation ---
+        let messages_box = gtk4::Box::new(gtk4::Orientation::Vertical, 8);
+        messages_box.set_margin_top(8);
+        messages_box.set_margin_bottom(8);
+        messages_box.set_margin_start(8);
+        messages_box.set_margin_end(8);
+        let scroll = gtk4::ScrolledWindow::builder()
+            .hscrollbar_policy(gtk4::PolicyType::Never)
+            .vexpand(true)
+            .child(&messages_box)
+            .build();
+        widget.append(&scroll);
+
+        // --- Input: Enter sends, Shift+Enter adds a line ---
+        let input_row = gtk4::Box::new(gtk4::Orientation::Horizontal, 6);
+        input_row.add_css_class("assistant-input");
+        let input = gtk4::TextView::new();
+        input.set_wrap_mode(gtk4::WrapMode::WordChar);
+        input.set_top_margin(6);
+        input.set_bottom_margin(6);
+        input.set_left_margin(8);
+        input.set_right_margin(8);
+        input.update_property(&[gtk4::accessible::Property::Label("Message")]);
+        let input_scroll = gtk4::ScrolledWindow::builder()
+            .hscrollbar_policy(gtk4::PolicyType::Never)
+            .min_content_height(40)
+            .max_content_height(160)
+            .propagate_natural_height(true)
+            .hexpand(true)
+            .child(&input)
+            .build();
+        input_row.append(&input_scroll);
+        let send_btn = gtk4::Button::from_icon_name("mail-send-symbolic");
+        send_btn.set_tooltip_text(Some("Send (Enter)"));
+        send_btn.set_valign(gtk4::Align::End);
+        send_btn.set_margin_bottom(6);
+        send_btn.set_margin_end(6);
+        input_row.append(&send_btn);
+        widget.append(&input_row);
+
+        let panel = Rc::new(Self {
+            widget,
+            messages_box,
+            scroll,
+            input: input.clone(),
+            send_btn: send_btn.clone(),
+            spinner,
+            settings: settings.clone(),
+            conversation: RefCell::new(Vec::new()),
+            busy: Cell::new(false),
+            generation: Cell::new(0),
+            enabled: Cell::new(settings.borrow().assistant_enabled),
+        });
+
+        {
+            let panel = Rc::downgrade(&panel);
+            send_btn.connect_clicked(move |_| {
+                if let Some(panel) = panel.upgrade() {
+                    panel.send_input();
+                }
+            });
+        }
+        {
+            let key_controller = gtk4::EventControllerKey::new();
+            let panel = Rc::downgrade(&panel);
+            key_controller.connect_key_pressed(move |_, key, _, modifiers| {
+                let is_enter = matches!(key, gtk4::gdk::Key::Return | gtk4::gdk::Key::KP_Enter);
+                if is_enter && !modifiers.contains(gtk4::gdk::ModifierType::SHIFT_MASK) {
+                    if let Some(panel) = panel.upgrade() {
+                        panel.send_input();
+                    }
+                    return gtk4::glib::Propagation::Stop;
+                }
+                gtk4::glib::Propagation::Proceed
+            });
+            input.add_controller(key_controller);
+        }
+        {
+            let panel = Rc::downgrade(&panel);
+            clear_btn.connect_clicked(move |_| {
+                if let Some(panel) = panel.upgrade() {
+                    panel.clear();
+                }
+            });
+        }
+        {
+            let panel = Rc::downgrade(&panel);
+            close_btn.connect_clicked(move |_| {
+                if let Some(panel) = panel.upgrade() {
+                    panel.widget.set_visible(false);
+                }
+            });
+        }
+
+        panel
+    }
+
+    /// Show the panel with the input focused, or hide it.
+    pub fn toggle(&self) {
+        if self.widget.is_visible() {
+            self.widget.set_visible(false);
+        } else {
+            self.present();
+        }
+    }
+
+    /// Show the panel with the input focused.
+    pub fn present(&self) {
+        if !self.enabled.get() {
+            return;
+        }
+        self.widget.set_visible(true);
+        self.input.grab_focus();
+    }
+
+    /// Follow `settings.assistant_enabled`; turning it off hides the panel.
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.set(enabled);
+        if !enabled {
+            self.widget.set_visible(false);
+        }
+    }
+
+    /// Ask the assistant to explain `code` from the file at `path`.
+    pub fn explain(self: &Rc<Self>, code: &str, language: &str, path: Option<&str>) {
+        self.present();
+        self.ask(assistant::explain_selection_prompt(code, language, path));
+    }
+
+    fn send_input(self: &Rc<Self>) {
+        let buffer = self.input.buffer();
+        let text = buffer
+            .text(&buffer.start_iter(), &buffer.end_iter(), false)
+            .trim()
+            .to_string();
+        if text.is_empty() || self.busy.get() {
+            return;
+        }
+        buffer.set_text("");
+        self.ask(text);
+    }
+
+    /// Add `prompt` to the conversation and wait for the reply.
+    fn ask(self: &Rc<Self>, prompt: String) {
+        if self.busy.get() {
+            return;
+        }
+        self.append_message(Role::User, &prompt, false);
+        self.conversation
+            .borrow_mut()
+            .push(ChatMessage::user(prompt));
+        self.set_busy(true);
+
+        let messages = assistant::with_system_prompt(&self.conversation.borrow());
+        let settings = self.settings.borrow().clone();
+        let generation = self.generation.get();
+        let panel = Rc::downgrade(self);
+        gtk4::glib::spawn_future_local(async move {
+            let result = gtk4::gio::spawn_blocking(move || {
+                let config = assistant::AssistantConfig::from_settings(&settings)?;
+                assistant::chat(&config, &messages)
+            })
+            .await
+            .unwrap_or_else(|_| Err("The assistant request failed".to_string()));
+            let Some(panel) = panel.upgrade() else {
+                return;
+            };
+            if panel.generation.get() != generation {
+                return;
+            }
+            panel.set_busy(false);
+            match result {
+                Ok(reply) => {
+                    panel.append_message(Role::Assistant, &reply, false);
+                    panel
+                        .conversation
+                        .borrow_mut()
+                        .push(ChatMessage::assistant(reply));
+                }
+                Err(e) => {
+                    // Drop the unanswered question so a retry doesn't repeat it.
+                    panel.conversation.borrow_mut().pop();
+                    panel.append_message(Role::Assistant, &e, true);
+                }
+            }
+        });
+    }
+
+    fn set_busy(&self, busy: bool) {
+        self.busy.set(busy);
+        self.spinner.set_visible(busy);
+        self.spinner.set_spinning(busy);
+        self.send_btn.set_sensitive(!busy);
+    }
+
+    fn clear(&self) {
+        self.generation.set(self.generation.get() + 1);
+        self.conversation.borrow_mut().clear();
+        while let Some(child) = self.messages_box.first_child() {
+            self.messages_box.remove(&child);
+        }
+        self.set_busy(false);
+        self.input.grab_focus();
+    }
+
+    fn append_message(&self, role: Role, text: &str, is_error: bool) {
+        let label = gtk4::Label::new(Some(text));
+        label.set_wrap(true);
+        label.set_wrap_mode(gtk4::pango::WrapMode::WordChar);
+        label.set_selectable(true);
+        label.set_xalign(0.0);
+        label.add_css_class("assistant-message");
+        label.add_css_class(match role {
+            Role::User => "user",
+            _ => "assistant",
+        });
+        if is_error {
+            label.add_css_class("error");
+        }
+        self.messages_box.append(&label);
+
+        // Scroll to the new message once it has been laid out.
+        let adjustment = self.scroll.vadjustment();
+        gtk4::glib::idle_add_local_once(move || {
+            adjustment.set_value(adjustment.upper() - adjustment.page_size());
+        });
+    }
+}
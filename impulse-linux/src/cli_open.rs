@@ -0,0 +1,111 @@
+//! Single instance: running `impulse <path[:line[:column]]>` while Impulse is
+//! already open makes GApplication forward the command line over D-Bus, and
+//! the running instance opens the targets here.
+//!
+//! `--wait` works because the launching process doesn't exit until the
+//! running instance drops its `ApplicationCommandLine`; it is held until
+//! every file it opened has its tab closed.
+
+use std::cell::RefCell;
+use std::path::PathBuf;
+
+use gtk4::gio;
+use gtk4::glib;
+use gtk4::prelude::*;
+use impulse_core::cli_open::OpenRequest;
+use libadwaita as adw;
+
+use crate::{editor, window};
+
+thread_local! {
+    /// Remote `--wait` invocations and the files they are waiting on.
+    static WAITERS: RefCell<Vec<(gio::ApplicationCommandLine, Vec<String>)>> =
+        const { RefCell::new(Vec::new()) };
+}
+
+/// `command-line` handler for the primary instance, for both its own launch
+/// and forwarded ones.
+pub fn handle_command_line(
+    app: &adw::Application,
+    command_line: &gio::ApplicationCommandLine,
+) -> glib::ExitCode {
+    let args: Vec<String> = command_line
+        .arguments()
+        .iter()
+        .skip(1)
+        .map(|a| a.to_string_lossy().to_string())
+        .collect();
+    let cwd = command_line.cwd().unwrap_or_else(|| PathBuf::from("/"));
+    let request = impulse_core::cli_open::parse_args(&args, &cwd);
+
+    let (targets, missing): (Vec<_>, Vec<_>) = request
+        .targets
+        .iter()
+        .partition(|t| std::path::Path::new(&t.path).exists());
+    for target in &missing {
+        log::warn!("Cannot open {}: no such file or directory", target.path);
+    }
+
+    open(app, &request);
+
+    let files: Vec<String> = targets
+        .iter()
+        .filter(|t| !t.is_dir)
+        .map(|t| t.path.clone())
+        .collect();
+    if request.wait && command_line.is_remote() && !files.is_empty() {
+        WAITERS.with(|w| w.borrow_mut().push((command_line.clone(), files)));
+    }
+
+    if missing.is_empty() {
+        glib::ExitCode::SUCCESS
+    } else {
+        glib::ExitCode::FAILURE
+    }
+}
+
+/// Open the request's targets: in the active window, or a new one when
+/// asked for, configured, when a directory is given, or when no window is
+/// open yet.
+pub(crate) fn open(app: &adw::Application, request: &OpenRequest) {
+    let targets: Vec<_> = request
+        .targets
+        .iter()
+        .filter(|t| std::path::Path::new(&t.path).exists())
+        .collect();
+    let paths: Vec<String> = targets.iter().map(|t| t.path.clone()).collect();
+
+    let new_window = app.active_window().is_none()
+        || request.new_window
+        || crate::settings::load().cli_open_in_new_window
+        || targets.iter().any(|t| t.is_dir);
+    if new_window {
+        window::build_window(app, (!paths.is_empty()).then_some(paths));
+    } else {
+        window::open_files_in_active_window(app, &paths);
+    }
+
+    for target in &targets {
+        if let Some(line) = target.line {
+            if let Some(handle) = editor::get_handle(&target.path) {
+                handle.go_to_position(line, target.column.unwrap_or(1));
+            }
+        }
+    }
+    if let Some(window) = app.active_window() {
+        window.present();
+    }
+}
+
+/// An editor tab closed: release `--wait` invocations that have no open
+/// files left.
+pub fn file_closed(path: &str) {
+    WAITERS.with(|w| {
+        let mut waiters = w.borrow_mut();
+        for (_, files) in waiters.iter_mut() {
+            files.retain(|f| f != path);
+        }
+        // Dropping the command line lets the launching process exit.
+        waiters.retain(|(_, files)| !files.is_empty());
+    });
+}
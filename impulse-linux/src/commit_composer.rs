@@ -0,0 +1,450 @@
+//! Commit message composer for the Review Changes tab: a subject entry with
+//! a Conventional Commits type picker and 50/72 length counter, a body view
+//! that highlights text past column 72, co-author insertion from recent
+//! history, live lint feedback from `impulse_core::commit_message`, and a
+//! Generate button that asks the assistant for a message when it is on.
+
+use gtk4::prelude::*;
+
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+use impulse_core::commit_message::{
+    self, LintSeverity, CONVENTIONAL_TYPES, LINE_HARD_LIMIT, SUBJECT_SOFT_LIMIT,
+};
+
+/// How many commits to scan for co-author suggestions.
+const CO_AUTHOR_HISTORY: usize = 300;
+
+/// Receives whether the message can currently be committed.
+type ChangedCallback = RefCell<Option<Rc<dyn Fn(bool)>>>;
+
+pub struct CommitComposer {
+    pub widget: gtk4::Box,
+    repo_root: String,
+    type_dropdown: gtk4::DropDown,
+    subject_entry: gtk4::Entry,
+    subject_count: gtk4::Label,
+    body_view: gtk4::TextView,
+    lint_label: gtk4::Label,
+    generate_btn: gtk4::Button,
+    conventional: Cell<bool>,
+    /// Set while the composer itself edits the subject or picker, so the
+    /// two don't update each other in a loop.
+    syncing: Cell<bool>,
+    on_changed: ChangedCallback,
+    on_submit: RefCell<Option<Rc<dyn Fn()>>>,
+}
+
+impl CommitComposer {
+    pub fn new(repo_root: &str) -> Rc<Self> {
+        let widget = gtk4::Box::new(gtk4::Orientation::Vertical, 6);
+        widget.add_css_class("commit-composer");
+        widget.set_hexpand(true);
+
+        // --- Subject row: type picker, subject, counter, co-authors ---
+        let subject_row = gtk4::Box::new(gtk4::Orientation::Horizontal, 6);
+        let mut type_names = vec!["type"];
+        type_names.extend(CONVENTIONAL_TYPES.iter().map(|(name, _)| *name));
+        let type_dropdown = gtk4::DropDown::from_strings(&type_names);
+        type_dropdown.set_tooltip_text(Some("Conventional Commits type"));
+        subject_row.append(&type_dropdown);
+
+        let subject_entry = gtk4::Entry::new();
+        subject_entry.set_placeholder_text(Some("Subject"));
+        subject_entry.set_hexpand(true);
+        subject_row.append(&subject_entry);
+
+        let subject_count = gtk4::Label::new(Some("0"));
+        subject_count.add_css_class("commit-subject-count");
+        subject_count.set_tooltip_text(Some(&format!(
+            "Keep the subject under {} characters ({} at most)",
+            SUBJECT_SOFT_LIMIT, LINE_HARD_LIMIT
+        )));
+        subject_row.append(&subject_count);
+
+        let co_author_list = gtk4::ListBox::new();
+        co_author_list.set_selection_mode(gtk4::SelectionMode::None);
+        let co_author_entry = gtk4::Entry::new();
+        co_author_entry.set_placeholder_text(Some("Name <email>"));
+        let co_author_box = gtk4::Box::new(gtk4::Orientation::Vertical, 6);
+        co_author_box.append(&co_author_entry);
+        co_author_box.append(
+            &gtk4::ScrolledWindow::builder()
+                .hscrollbar_policy(gtk4::PolicyType::Never)
+                .propagate_natural_height(true)
+                .max_content_height(280)
+                .child(&co_author_list)
+                .build(),
+        );
+        let co_author_popover = gtk4::Popover::new();
+        co_author_popover.set_child(Some(&co_author_box));
+        let co_author_btn = gtk4::MenuButton::builder()
+            .icon_name("contact-new-symbolic")
+            .tooltip_text("Add Co-Author")
+            .popover(&co_author_popover)
+            .build();
+        co_author_btn.add_css_class("flat");
+        subject_row.append(&co_author_btn);
+
+        let generate_btn = gtk4::Button::from_icon_name("document-edit-symbolic");
+        generate_btn.set_tooltip_text(Some("Generate Commit Message"));
+        generate_btn.add_css_class("flat");
+        generate_btn.set_visible(false);
+        subject_row.append(&generate_btn);
+        widget.append(&subject_row);
+
+        // --- Body with a column-72 guide ---
+        let body_view = gtk4::TextView::new();
+        body_view.set_monospace(true);
+        body_view.set_wrap_mode(gtk4::WrapMode::None);
+        body_view.set_top_margin(4);
+        body_view.set_bottom_margin(4);
+        body_view.set_left_margin(6);
+        body_view.add_css_class("commit-body");
+        body_view.buffer().create_tag(
+            Some("overlong"),
+            &[("underline", &gtk4::pango::Underline::Error)],
+        );
+        let body_scroll = gtk4::ScrolledWindow::builder()
+            .hscrollbar_policy(gtk4::PolicyType::Automatic)
+            .min_content_height(56)
+            .max_content_height(160)
+            .propagate_natural_height(true)
+            .child(&body_view)
+            .build();
+        body_scroll.add_css_class("commit-body-scroll");
+        body_scroll.set_tooltip_text(Some(&format!(
+            "Body (optional). Wrap lines at {} characters. Ctrl+Enter commits.",
+            LINE_HARD_LIMIT
+        )));
+        widget.append(&body_scroll);
+
+        let lint_label = gtk4::Label::new(None);
+        lint_label.add_css_class("commit-lint");
+        lint_label.set_halign(gtk4::Align::Start);
+        lint_label.set_wrap(true);
+        lint_label.set_visible(false);
+        widget.append(&lint_label);
+
+        let composer = Rc::new(Self {
+            widget,
+            repo_root: repo_root.to_string(),
+            type_dropdown: type_dropdown.clone(),
+            subject_entry: subject_entry.clone(),
+            subject_count,
+            body_view: body_view.clone(),
+            lint_label,
+            generate_btn: generate_btn.clone(),
+            conventional: Cell::new(false),
+            syncing: Cell::new(false),
+            on_changed: RefCell::new(None),
+            on_submit: RefCell::new(None),
+        });
+
+        {
+            let composer = Rc::downgrade(&composer);
+            subject_entry.connect_changed(move |_| {
+                if let Some(composer) = composer.upgrade() {
+                    composer.sync_type_from_subject();
+                    composer.revalidate();
+                }
+            });
+        }
+        {
+            let composer = Rc::downgrade(&composer);
+            subject_entry.connect_activate(move |_| {
+                if let Some(composer) = composer.upgrade() {
+                    composer.submit();
+                }
+            });
+        }
+        {
+            let composer = Rc::downgrade(&composer);
+            type_dropdown.connect_selected_notify(move |dropdown| {
+                let Some(composer) = composer.upgrade() else {
+                    return;
+                };
+                if composer.syncing.get() {
+                    return;
+                }
+                let kind = match dropdown.selected() {
+                    0 | gtk4::INVALID_LIST_POSITION => "",
+                    index => CONVENTIONAL_TYPES[index as usize - 1].0,
+                };
+                let subject =
+                    commit_message::set_conventional_type(&composer.subject_entry.text(), kind);
+                composer.syncing.set(true);
+                composer.subject_entry.set_text(&subject);
+                composer.syncing.set(false);
+                composer.subject_entry.grab_focus();
+                composer.subject_entry.set_position(-1);
+            });
+        }
+        {
+            let composer = Rc::downgrade(&composer);
+            body_view.buffer().connect_changed(move |_| {
+                if let Some(composer) = composer.upgrade() {
+                    composer.mark_overlong_lines();
+                    composer.revalidate();
+                }
+            });
+        }
+        {
+            let key_controller = gtk4::EventControllerKey::new();
+            let composer = Rc::downgrade(&composer);
+            key_controller.connect_key_pressed(move |_, key, _, modifiers| {
+                let is_enter = matches!(key, gtk4::gdk::Key::Return | gtk4::gdk::Key::KP_Enter);
+                if is_enter && modifiers.contains(gtk4::gdk::ModifierType::CONTROL_MASK) {
+                    if let Some(composer) = composer.upgrade() {
+                        composer.submit();
+                    }
+                    return gtk4::glib::Propagation::Stop;
+                }
+                gtk4::glib::Propagation::Proceed
+            });
+            body_view.add_controller(key_controller);
+        }
+
+        // Co-authors: suggestions from recent history, refreshed on open.
+        {
+            let composer = Rc::downgrade(&composer);
+            let list = co_author_list.clone();
+            co_author_popover.connect_show(move |_| {
+                let Some(composer) = composer.upgrade() else {
+                    return;
+                };
+                while let Some(row) = list.row_at_index(0) {
+                    list.remove(&row);
+                }
+                let root = composer.repo_root.clone();
+                let authors =
+                    commit_message::recent_authors(&root, CO_AUTHOR_HISTORY).unwrap_or_default();
+                for author in authors {
+                    let label = gtk4::Label::new(Some(&author));
+                    label.set_halign(gtk4::Align::Start);
+                    label.set_margin_start(6);
+                    label.set_margin_end(6);
+                    list.append(&label);
+                }
+            });
+        }
+        {
+            let composer = Rc::downgrade(&composer);
+            let popover = co_author_popover.clone();
+            co_author_list.connect_row_activated(move |_, row| {
+                let author = row
+                    .child()
+                    .and_downcast::<gtk4::Label>()
+                    .map(|label| label.text().to_string());
+                if let (Some(composer), Some(author)) = (composer.upgrade(), author) {
+                    composer.add_co_author(&author);
+                }
+                popover.popdown();
+            });
+        }
+        {
+            let composer = Rc::downgrade(&composer);
+            let popover = co_author_popover.clone();
+            co_author_entry.connect_activate(move |entry| {
+                let author = entry.text().trim().to_string();
+                if author.contains('<') && author.ends_with('>') {
+                    if let Some(composer) = composer.upgrade() {
+                        composer.add_co_author(&author);
+                    }
+                    entry.set_text("");
+                    popover.popdown();
+                }
+            });
+        }
+
+        {
+            let composer = Rc::downgrade(&composer);
+            generate_btn.connect_clicked(move |_| {
+                if let Some(composer) = composer.upgrade() {
+                    composer.generate();
+                }
+            });
+        }
+
+        composer.reset();
+        composer
+    }
+
+    /// Called with whether the message can be committed whenever it changes.
+    pub fn connect_changed(&self, f: impl Fn(bool) + 'static) {
+        *self.on_changed.borrow_mut() = Some(Rc::new(f));
+        self.revalidate();
+    }
+
+    /// Called on Enter in the subject or Ctrl+Enter in the body.
+    pub fn connect_submit(&self, f: impl Fn() + 'static) {
+        *self.on_submit.borrow_mut() = Some(Rc::new(f));
+    }
+
+    /// Lint subjects as Conventional Commits headers.
+    pub fn set_conventional(&self, conventional: bool) {
+        self.conventional.set(conventional);
+        self.revalidate();
+    }
+
+    /// Show the Generate button, for when the assistant is turned on.
+    pub fn set_assistant_enabled(&self, enabled: bool) {
+        self.generate_btn.set_visible(enabled);
+    }
+
+    /// The full message, or `None` (with the subject focused) if it has
+    /// errors that block committing.
+    pub fn message(&self) -> Option<String> {
+        let message = self.compose();
+        if self.is_committable(&message) {
+            Some(message)
+        } else {
+            self.subject_entry.grab_focus();
+            None
+        }
+    }
+
+    /// Clear the composer, pre-filling the repository's commit template.
+    pub fn reset(&self) {
+        let template = commit_message::load_template(&self.repo_root).unwrap_or_default();
+        let (subject, body) = commit_message::split(&template);
+        self.subject_entry.set_text(&subject);
+        self.body_view.buffer().set_text(&body);
+        self.revalidate();
+    }
+
+    fn compose(&self) -> String {
+        let buffer = self.body_view.buffer();
+        let body = buffer.text(&buffer.start_iter(), &buffer.end_iter(), false);
+        commit_message::compose(&self.subject_entry.text(), &body)
+    }
+
+    fn is_committable(&self, message: &str) -> bool {
+        !commit_message::lint(message, self.conventional.get())
+            .iter()
+            .any(|lint| lint.severity == LintSeverity::Error)
+    }
+
+    fn submit(&self) {
+        let callback = self.on_submit.borrow().clone();
+        if let Some(callback) = callback {
+            callback();
+        }
+    }
+
+    /// Replace the message with one the assistant writes for the changes.
+    fn generate(self: &Rc<Self>) {
+        self.generate_btn.set_sensitive(false);
+        self.lint_label.remove_css_class("error");
+        self.lint_label
+            .set_text("Generating commit message\u{2026}");
+        self.lint_label.set_visible(true);
+        let root = self.repo_root.clone();
+        let composer = Rc::downgrade(self);
+        gtk4::glib::spawn_future_local(async move {
+            let result = gtk4::gio::spawn_blocking(move || {
+                let settings = crate::settings::load();
+                impulse_core::assistant::generate_commit_message(&settings, &root)
+            })
+            .await
+            .unwrap_or_else(|_| Err("The assistant request failed".to_string()));
+            let Some(composer) = composer.upgrade() else {
+                return;
+            };
+            composer.generate_btn.set_sensitive(true);
+            match result {
+                Ok(message) => {
+                    let (subject, body) = commit_message::split(&message);
+                    composer.subject_entry.set_text(&subject);
+                    composer.body_view.buffer().set_text(&body);
+                    composer.revalidate();
+                }
+                Err(e) => {
+                    composer.revalidate();
+                    composer.lint_label.set_text(&e);
+                    composer.lint_label.add_css_class("error");
+                    composer.lint_label.set_visible(true);
+                }
+            }
+        });
+    }
+
+    fn add_co_author(&self, author: &str) {
+        let buffer = self.body_view.buffer();
+        let body = buffer.text(&buffer.start_iter(), &buffer.end_iter(), false);
+        buffer.set_text(&commit_message::add_co_author(&body, author));
+    }
+
+    fn sync_type_from_subject(&self) {
+        if self.syncing.get() {
+            return;
+        }
+        let index = commit_message::parse_conventional(&self.subject_entry.text())
+            .and_then(|header| {
+                CONVENTIONAL_TYPES
+                    .iter()
+                    .position(|(name, _)| *name == header.kind)
+            })
+            .map_or(0, |i| i as u32 + 1);
+        if self.type_dropdown.selected() != index {
+            self.syncing.set(true);
+            self.type_dropdown.set_selected(index);
+            self.syncing.set(false);
+        }
+    }
+
+    /// Underline everything past column 72 in the body.
+    fn mark_overlong_lines(&self) {
+        let buffer = self.body_view.buffer();
+        buffer.remove_tag_by_name("overlong", &buffer.start_iter(), &buffer.end_iter());
+        for line in 0..buffer.line_count() {
+            let Some(mut start) = buffer.iter_at_line(line) else {
+                continue;
+            };
+            let mut end = start;
+            if !end.ends_line() {
+                end.forward_to_line_end();
+            }
+            if end.line_offset() as usize <= LINE_HARD_LIMIT {
+                continue;
+            }
+            start.set_line_offset(LINE_HARD_LIMIT as i32);
+            buffer.apply_tag_by_name("overlong", &start, &end);
+        }
+    }
+
+    fn revalidate(&self) {
+        let subject_len = self.subject_entry.text().chars().count();
+        self.subject_count.set_text(&subject_len.to_string());
+        for css in ["warning", "error"] {
+            self.subject_count.remove_css_class(css);
+        }
+        if subject_len > LINE_HARD_LIMIT {
+            self.subject_count.add_css_class("error");
+        } else if subject_len > SUBJECT_SOFT_LIMIT {
+            self.subject_count.add_css_class("warning");
+        }
+
+        let message = self.compose();
+        let lints = commit_message::lint(&message, self.conventional.get());
+        // An empty subject is obvious from the disabled Commit button.
+        let shown: Vec<String> = lints
+            .iter()
+            .filter(|lint| !(lint.line == 0 && self.subject_entry.text().trim().is_empty()))
+            .map(|lint| lint.message.clone())
+            .collect();
+        self.lint_label.set_text(&shown.join(" \u{b7} "));
+        self.lint_label.set_visible(!shown.is_empty());
+        let has_error = lints.iter().any(|l| l.severity == LintSeverity::Error);
+        self.lint_label.remove_css_class("error");
+        if has_error {
+            self.lint_label.add_css_class("error");
+        }
+
+        let callback = self.on_changed.borrow().clone();
+        if let Some(callback) = callback {
+            callback(!has_error);
+        }
+    }
+}
@@ -0,0 +1,234 @@
+//! Export, import and git sync of the configuration (see
+//! `impulse_core::config_sync`), and the VS Code importer. Imported and
+//! pulled settings are applied to every window through `settings_watch`.
+
+use gtk4::gio;
+use gtk4::prelude::*;
+use impulse_core::config_sync::SyncOutcome;
+use impulse_core::notifications::{Notification, Severity};
+use libadwaita as adw;
+use libadwaita::prelude::*;
+
+use crate::settings::Settings;
+
+/// "Export Settings": write the configuration to an archive the user picks.
+pub fn export(window: &adw::ApplicationWindow, toast_overlay: &adw::ToastOverlay) {
+    let dialog = gtk4::FileDialog::new();
+    dialog.set_title("Export Settings");
+    dialog.set_initial_name(Some("impulse-settings.zip"));
+    let toast_overlay = toast_overlay.clone();
+    dialog.save(Some(window), gio::Cancellable::NONE, move |result| {
+        let Some(path) = result.ok().and_then(|file| file.path()) else {
+            return;
+        };
+        let notification =
+            match impulse_core::config_sync::export_archive(&path) {
+                Ok(count) => Notification::new(Severity::Info, "Settings exported")
+                    .message(format!("{} file(s) saved to {}", count, path.display())),
+                Err(e) => Notification::new(Severity::Error, "Settings export failed").message(e),
+            };
+        crate::notifications::notify(&toast_overlay, notification.source("settings"));
+    });
+}
+
+/// "Import Settings": restore an exported archive and apply it.
+pub fn import(window: &adw::ApplicationWindow, toast_overlay: &adw::ToastOverlay) {
+    let filter = gtk4::FileFilter::new();
+    filter.set_name(Some("Settings archives"));
+    filter.add_pattern("*.zip");
+    let filters = gio::ListStore::new::<gtk4::FileFilter>();
+    filters.append(&filter);
+    let dialog = gtk4::FileDialog::new();
+    dialog.set_title("Import Settings");
+    dialog.set_filters(Some(&filters));
+    let toast_overlay = toast_overlay.clone();
+    dialog.open(Some(window), gio::Cancellable::NONE, move |result| {
+        let Some(path) = result.ok().and_then(|file| file.path()) else {
+            return;
+        };
+        let notification = match impulse_core::config_sync::import_archive(&path) {
+            Ok(count) => {
+                crate::settings_watch::check(false);
+                Notification::new(Severity::Info, "Settings imported")
+                    .message(format!("{} file(s) restored", count))
+            }
+            Err(e) => Notification::new(Severity::Error, "Settings import failed").message(e),
+        };
+        crate::notifications::notify(&toast_overlay, notification.source("settings"));
+    });
+}
+
+/// "Import VS Code Settings": show what carries over from the user's VS Code
+/// setup on top of `current` and apply it when confirmed.
+pub fn import_vscode(
+    window: &adw::ApplicationWindow,
+    toast_overlay: &adw::ToastOverlay,
+    current: &Settings,
+) {
+    let commands = impulse_editor::keybindings::vscode_commands();
+    let import = match impulse_core::vscode_import::import(current, &commands) {
+        Ok(import) => import,
+        Err(e) => {
+            crate::notifications::notify(
+                toast_overlay,
+                Notification::new(Severity::Error, "VS Code import failed")
+                    .message(e)
+                    .source("settings"),
+            );
+            return;
+        }
+    };
+    let mut body = format!(
+        "{} setting(s) and keybinding(s) will replace yours.",
+        import.imported.len()
+    );
+    if !import.unmapped.is_empty() {
+        body.push_str("\n\nNo Impulse equivalent: ");
+        body.push_str(&import.unmapped.join(", "));
+    }
+    let dialog = adw::AlertDialog::builder()
+        .heading("Import VS Code Settings?")
+        .body(body)
+        .build();
+    dialog.add_response("cancel", "Cancel");
+    dialog.add_response("import", "Import");
+    dialog.set_response_appearance("import", adw::ResponseAppearance::Suggested);
+    dialog.set_default_response(Some("import"));
+    dialog.set_close_response("cancel");
+    let toast_overlay = toast_overlay.clone();
+    dialog.connect_response(None, move |_dialog, response| {
+        if response != "import" {
+            return;
+        }
+        crate::settings::save(&import.settings);
+        crate::settings_watch::apply_all(&import.settings);
+        let mut message = format!("{} item(s) imported.", import.imported.len());
+        if !import.unmapped.is_empty() {
+            message.push_str(" Not mapped: ");
+            message.push_str(&import.unmapped.join(", "));
+        }
+        crate::notifications::notify(
+            &toast_overlay,
+            Notification::new(Severity::Info, "VS Code settings imported")
+                .message(message)
+                .source("settings"),
+        );
+    });
+    dialog.present(Some(window));
+}
+
+/// "Sync Settings": sync with `settings_sync_repository`, asking which side
+/// to keep on a conflict.
+pub fn sync_now(window: &adw::ApplicationWindow, toast_overlay: &adw::ToastOverlay, url: &str) {
+    if url.trim().is_empty() {
+        crate::notifications::notify(
+            toast_overlay,
+            Notification::new(Severity::Warning, "Settings sync is off")
+                .message("Set a sync repository under Settings → General.")
+                .action("Open Settings", "open_settings")
+                .source("settings"),
+        );
+        return;
+    }
+    let window = window.clone();
+    let toast_overlay = toast_overlay.clone();
+    run(url.to_string(), None, move |url, result| match result {
+        Ok(SyncOutcome::Conflict) => ask_about_conflict(&window, &toast_overlay, url),
+        result => report(Some(&toast_overlay), result),
+    });
+}
+
+/// Sync at launch when a repository is set. Conflicts are left for
+/// "Sync Settings" to ask about.
+pub fn sync_at_startup(url: &str) {
+    if url.trim().is_empty() {
+        return;
+    }
+    run(url.to_string(), None, |_, result| match result {
+        Ok(SyncOutcome::Conflict) => crate::notifications::record(
+            Notification::new(Severity::Warning, "Settings sync needs a decision")
+                .message("Settings changed both here and in the sync repository.")
+                .action("Sync Settings", "sync_settings")
+                .source("settings"),
+        ),
+        Ok(SyncOutcome::UpToDate) => {}
+        result => report(None, result),
+    });
+}
+
+fn ask_about_conflict(
+    window: &adw::ApplicationWindow,
+    toast_overlay: &adw::ToastOverlay,
+    url: String,
+) {
+    let dialog = adw::AlertDialog::builder()
+        .heading("Settings Changed in Both Places")
+        .body(
+            "Settings changed both on this machine and in the sync repository since the \
+             last sync. Which version do you want to keep?",
+        )
+        .build();
+    dialog.add_response("cancel", "Cancel");
+    dialog.add_response("remote", "Use Repository Version");
+    dialog.add_response("local", "Keep This Machine's");
+    dialog.set_default_response(Some("cancel"));
+    dialog.set_close_response("cancel");
+    let toast_overlay = toast_overlay.clone();
+    dialog.connect_response(None, move |_dialog, response| {
+        if response == "cancel" {
+            return;
+        }
+        let toast_overlay = toast_overlay.clone();
+        run(url.clone(), Some(response == "local"), move |_, result| {
+            report(Some(&toast_overlay), result)
+        });
+    });
+    dialog.present(Some(window));
+}
+
+/// Run a sync (or with `keep_local`, a conflict resolution) off the main
+/// thread and hand the result to `done` on it.
+fn run(
+    url: String,
+    keep_local: Option<bool>,
+    done: impl FnOnce(String, Result<SyncOutcome, String>) + 'static,
+) {
+    gtk4::glib::spawn_future_local(async move {
+        let thread_url = url.clone();
+        let result = gio::spawn_blocking(move || match keep_local {
+            Some(keep_local) => {
+                impulse_core::config_sync::resolve_sync_conflict(&thread_url, keep_local)
+            }
+            None => impulse_core::config_sync::sync(&thread_url),
+        })
+        .await
+        .unwrap_or_else(|_| Err("Settings sync failed unexpectedly".to_string()));
+        if let Err(e) = &result {
+            log::warn!("Settings sync with {} failed: {}", url, e);
+        }
+        done(url, result);
+    });
+}
+
+fn report(toast_overlay: Option<&adw::ToastOverlay>, result: Result<SyncOutcome, String>) {
+    let notification = match result {
+        Ok(SyncOutcome::Pulled) => {
+            crate::settings_watch::check(false);
+            Notification::new(Severity::Info, "Settings synced")
+                .message("Applied settings from the sync repository.")
+        }
+        Ok(SyncOutcome::Pushed) => Notification::new(Severity::Info, "Settings synced")
+            .message("Pushed settings to the sync repository."),
+        Ok(SyncOutcome::UpToDate | SyncOutcome::Conflict) => {
+            Notification::new(Severity::Info, "Settings are up to date")
+        }
+        Err(e) => Notification::new(Severity::Error, "Settings sync failed")
+            .message(e)
+            .action("Retry", "sync_settings"),
+    }
+    .source("settings");
+    match toast_overlay {
+        Some(toast_overlay) => crate::notifications::notify(toast_overlay, notification),
+        None => crate::notifications::record(notification),
+    }
+}
@@ -9,6 +9,8 @@ use webkit6::prelude::*;
 use crate::editor_webview::{self, MonacoEditorHandle};
 use crate::settings::Settings;
 use crate::theme::ThemeColors;
+use impulse_core::indentation::Indentation;
+use impulse_core::read_only::{read_only_reason, ReadOnlyReason};
 use impulse_editor::markdown;
 use impulse_editor::protocol::EditorEvent;
 
@@ -38,12 +40,24 @@ pub fn unregister_handle(file_path: &str) {
     if let Some(handle) = HANDLES.with(|h| h.borrow_mut().remove(file_path)) {
         handle.cleanup();
     }
+    crate::cli_open::file_closed(file_path);
 }
 
 pub fn get_handle(file_path: &str) -> Option<Rc<MonacoEditorHandle>> {
     HANDLES.with(|h| h.borrow().get(file_path).cloned())
 }
 
+/// Path and content of every editor with unsaved changes, in all windows.
+pub fn modified_buffers() -> Vec<(String, String)> {
+    HANDLES.with(|h| {
+        h.borrow()
+            .iter()
+            .filter(|(_, handle)| handle.is_modified.get())
+            .map(|(path, handle)| (path.clone(), handle.get_content()))
+            .collect()
+    })
+}
+
 pub fn get_handle_for_widget(widget: &gtk4::Widget) -> Option<Rc<MonacoEditorHandle>> {
     if !is_editor(widget) {
         return None;
@@ -91,7 +105,9 @@ where
             file_path,
             LARGE_FILE_THRESHOLD / (1024 * 1024)
         );
-        handle.set_read_only(true);
+        handle.set_read_only(Some(ReadOnlyReason::LargeFile));
+    } else if let Some(reason) = read_only_reason(file_path, &contents) {
+        handle.set_read_only(Some(reason));
     }
 
     register_handle(file_path, handle.clone());
@@ -104,19 +120,27 @@ where
 ///
 /// Unlike `create_editor`, this skips reading from disk, file watching,
 /// and large-file checks. The `cwd` is stored on the handle for use as the
-/// default directory in the save-as dialog.
+/// default directory in the save-as dialog. `content` is the initial text,
+/// empty for a new file.
 pub fn create_untitled_editor<F>(
     settings: &Settings,
     theme: &ThemeColors,
     cwd: Option<String>,
+    content: &str,
     on_event: F,
 ) -> (gtk4::Box, Rc<MonacoEditorHandle>)
 where
     F: Fn(&MonacoEditorHandle, EditorEvent) + 'static,
 {
     let sentinel = next_untitled_path();
-    let (container, handle) =
-        editor_webview::create_monaco_editor(&sentinel, "", "plaintext", settings, theme, on_event);
+    let (container, handle) = editor_webview::create_monaco_editor(
+        &sentinel,
+        content,
+        "plaintext",
+        settings,
+        theme,
+        on_event,
+    );
     *handle.untitled_cwd.borrow_mut() = cwd;
     register_handle(&sentinel, handle.clone());
     (container, handle)
@@ -176,11 +200,33 @@ pub fn get_editor_language(widget: &gtk4::Widget) -> Option<String> {
     }
 }
 
-/// Get indentation info for status bar display.
-pub fn get_editor_indent_info(widget: &gtk4::Widget) -> Option<String> {
+/// Get the editor's indentation for status bar display.
+pub fn get_editor_indentation(widget: &gtk4::Widget) -> Option<Indentation> {
+    let handle = get_handle_for_widget(widget)?;
+    Some(handle.indentation.get())
+}
+
+/// Why the editor is read-only, if it is.
+pub fn get_read_only_reason(widget: &gtk4::Widget) -> Option<ReadOnlyReason> {
+    get_handle_for_widget(widget)?.read_only.get()
+}
+
+/// Lock or unlock the editor. Unlocking clears whatever made it read-only.
+pub fn toggle_read_only(widget: &gtk4::Widget) -> Option<ReadOnlyReason> {
     let handle = get_handle_for_widget(widget)?;
-    let info = handle.indent_info.borrow().clone();
-    Some(info)
+    let reason = match handle.read_only.get() {
+        Some(_) => None,
+        None => Some(ReadOnlyReason::Manual),
+    };
+    handle.set_read_only(reason);
+    reason
+}
+
+/// Change the editor's indentation, re-indenting existing lines when `convert`.
+pub fn set_editor_indentation(widget: &gtk4::Widget, indentation: Indentation, convert: bool) {
+    if let Some(handle) = get_handle_for_widget(widget) {
+        handle.set_indentation(indentation, convert);
+    }
 }
 
 /// Apply settings changes to an existing Monaco editor.
@@ -197,6 +243,13 @@ pub fn apply_theme(widget: &gtk4::Widget, theme: &ThemeColors) {
     }
 }
 
+/// Replace the user `editor.css` in an existing Monaco editor.
+pub fn apply_custom_css(widget: &gtk4::Widget, css: &str) {
+    if let Some(handle) = get_handle_for_widget(widget) {
+        handle.set_custom_css(css);
+    }
+}
+
 /// Navigate to a specific position in the editor.
 pub fn go_to_position(widget: &gtk4::Widget, line: u32, column: u32) {
     if let Some(handle) = get_handle_for_widget(widget) {
@@ -220,6 +273,9 @@ pub fn is_previewable_file(path: &str) -> bool {
 
 /// Map the application theme to markdown preview colors.
 pub fn theme_to_markdown_colors(theme: &ThemeColors) -> markdown::MarkdownThemeColors {
+    if let Some(resolved) = theme.resolved {
+        return markdown::theme_to_markdown_colors(resolved);
+    }
     markdown::MarkdownThemeColors {
         bg: theme.bg.to_string(),
         fg: theme.fg.to_string(),
@@ -462,7 +518,11 @@ pub fn is_binary_file(path: &str) -> bool {
 // Language detection
 // ---------------------------------------------------------------------------
 
-fn guess_language(file_path: &str) -> String {
+/// The Monaco language for `file_path`, honoring language overrides.
+pub fn guess_language(file_path: &str) -> String {
+    if let Some(language) = impulse_core::languages::overridden(std::path::Path::new(file_path)) {
+        return language.monaco.to_string();
+    }
     let ext = file_path.rsplit('.').next().unwrap_or("").to_lowercase();
     match ext.as_str() {
         "rs" => "rust",
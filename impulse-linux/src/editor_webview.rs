@@ -1,5 +1,4 @@
 use std::cell::{Cell, RefCell};
-use std::collections::HashMap;
 use std::rc::Rc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
@@ -9,12 +8,16 @@ use gtk4::glib;
 use gtk4::prelude::*;
 use webkit6::prelude::*;
 
+use impulse_core::indentation::{resolve_indentation, Indentation};
+use impulse_core::read_only::ReadOnlyReason;
+use impulse_editor::batch::CommandBatch;
 use impulse_editor::protocol::{
-    self, DiffDecoration, EditorCommand, EditorEvent, EditorOptions, MonacoCodeAction,
-    MonacoCompletionItem, MonacoContentChange, MonacoDiagnostic, MonacoHoverContent,
-    MonacoLocation, MonacoParameterInfo, MonacoRange, MonacoSignatureHelp, MonacoSignatureInfo,
-    MonacoTextEdit, MonacoThemeColors, MonacoThemeDefinition, MonacoTokenRule,
-    MonacoWorkspaceTextEdit,
+    self, BlameGutterLine, ConflictRegionMarker, DiffDecoration, DiffHunkMarker, EditorCommand,
+    EditorEvent, EditorOptions, HostFeature, MonacoCodeAction, MonacoCommand, MonacoCompletionItem,
+    MonacoContentChange, MonacoDiagnostic, MonacoHoverContent, MonacoLocation, MonacoParameterInfo,
+    MonacoPeekLocation, MonacoRange, MonacoSignatureHelp, MonacoSignatureInfo, MonacoTextEdit,
+    MonacoThemeColors, MonacoThemeDefinition, MonacoTokenRule, MonacoWorkspaceTextEdit,
+    PendingRequests, RequestError, SyntaxToken,
 };
 
 use crate::lsp_completion::{
@@ -24,36 +27,121 @@ use crate::lsp_completion::{
 use crate::settings::Settings;
 use crate::theme::ThemeColors;
 
+/// Commands waiting for the next frame, sent to Monaco in one
+/// `impulseReceiveCommands` call (see `impulse_editor::batch`).
+struct CommandQueue {
+    webview: webkit6::WebView,
+    batch: RefCell<CommandBatch>,
+    flush_scheduled: Cell<bool>,
+}
+
+impl CommandQueue {
+    fn new(webview: webkit6::WebView) -> Rc<Self> {
+        Rc::new(Self {
+            webview,
+            batch: RefCell::new(CommandBatch::new()),
+            flush_scheduled: Cell::new(false),
+        })
+    }
+
+    fn push(self: &Rc<Self>, cmd: &EditorCommand) {
+        if let Err(e) = self.batch.borrow_mut().push(cmd) {
+            log::error!("{}", e);
+            return;
+        }
+        if self.flush_scheduled.replace(true) {
+            return;
+        }
+        let queue = Rc::downgrade(self);
+        let flush = move || {
+            if let Some(queue) = queue.upgrade() {
+                queue.flush();
+            }
+        };
+        if self.webview.is_mapped() {
+            self.webview.add_tick_callback(move |_, _| {
+                flush();
+                glib::ControlFlow::Break
+            });
+        } else {
+            // Hidden editors get no frame clock ticks.
+            glib::idle_add_local_once(flush);
+        }
+    }
+
+    fn flush(&self) {
+        self.flush_scheduled.set(false);
+        let Some(json) = self.batch.borrow_mut().take_json() else {
+            return;
+        };
+        let script = format!("impulseReceiveCommands('{}')", js_string_escape(&json));
+        self.webview.evaluate_javascript(
+            &script,
+            None,
+            None,
+            None::<&gtk4::gio::Cancellable>,
+            |_| {},
+        );
+    }
+}
+
 /// Handle for communicating with a Monaco editor running inside a WebView.
 pub struct MonacoEditorHandle {
     webview: webkit6::WebView,
+    /// Outgoing commands, flushed once per frame.
+    commands: Rc<CommandQueue>,
     pub file_path: RefCell<String>,
     pub cached_content: Rc<RefCell<String>>,
     pub is_modified: Rc<Cell<bool>>,
     pub is_ready: Rc<Cell<bool>>,
     pub language: RefCell<String>,
     pub version: Rc<Cell<u32>>,
-    pub indent_info: RefCell<String>,
+    /// This file's indentation: detected on open, changed from the status bar.
+    pub indentation: Cell<Indentation>,
     /// When true, the next ContentChanged event will not mark the file as modified.
     /// Used when reloading file content externally (e.g. discard changes).
     pub suppress_next_modify: Rc<Cell<bool>>,
     /// Position to navigate to once the editor becomes ready (for cross-file go-to-definition).
     pending_position: Cell<Option<(u32, u32)>>,
-    /// When set, the editor will be put into read-only mode once it becomes ready.
-    pending_read_only: Cell<bool>,
+    /// Whether Monaco has reported `FileOpened` for this editor.
+    file_opened: Cell<bool>,
+    /// Recovered unsaved content to put in the buffer once the file opens.
+    pending_restore: RefCell<Option<String>>,
+    /// Why the editor is read-only, if it is. Applied once the editor
+    /// becomes ready when set before that.
+    pub read_only: Cell<Option<ReadOnlyReason>>,
     /// Keeps the file watcher alive. Dropping this stops watching.
     _file_watcher: Rc<RefCell<Option<notify::RecommendedWatcher>>>,
     /// Source ID for the file watcher's polling timer.
     _file_watcher_timer: RefCell<Option<glib::SourceId>>,
     /// Whether this editor is currently showing markdown preview.
     pub is_previewing: Cell<bool>,
+    /// Whether the blame gutter is shown for this editor.
+    pub blame_visible: Cell<bool>,
     /// The gtk4::Stack wrapping editor and preview children (set during create).
     pub stack: RefCell<Option<gtk4::Stack>>,
     /// CWD captured at the time of Ctrl+N for untitled editors; used as default directory
     /// in the save-as dialog.
     pub untitled_cwd: RefCell<Option<String>>,
+    /// Source ID for the debounced tree-sitter highlighting pass.
+    syntax_highlight_timer: RefCell<Option<glib::SourceId>>,
+    /// Whether merge-conflict regions are currently shown, so they are
+    /// cleared once the last marker is removed.
+    has_conflict_regions: Cell<bool>,
+    /// 1-based line of the cursor as last reported by Monaco.
+    pub cursor_line: Cell<u32>,
+    /// Points added to the settings font size by zooming this tab.
+    pub zoom: Cell<i32>,
+    /// Receives the editor's events; replaced when the tab moves to
+    /// another window.
+    event_handler: RefCell<EventHandler>,
+    /// Correlated requests waiting for the editor's `Response`.
+    requests: Rc<RefCell<PendingRequests<ResponseCallback>>>,
 }
 
+type EventHandler = Rc<dyn Fn(&MonacoEditorHandle, EditorEvent)>;
+type ResponseCallback = Box<dyn FnOnce(Result<Option<String>, RequestError>)>;
+
 impl MonacoEditorHandle {
     fn send_command(&self, cmd: &EditorCommand) {
         if !self.is_ready.get() {
@@ -63,23 +151,23 @@ impl MonacoEditorHandle {
             );
             return;
         }
-        let json = match serde_json::to_string(cmd) {
-            Ok(j) => j,
-            Err(e) => {
-                log::error!("Failed to serialize EditorCommand: {}", e);
-                return;
-            }
-        };
-        // Escape for embedding in JS string literal
-        let escaped = js_string_escape(&json);
-        let script = format!("impulseReceiveCommand('{}')", escaped);
-        self.webview.evaluate_javascript(
-            &script,
-            None,
-            None,
-            None::<&gtk4::gio::Cancellable>,
-            |_| {},
-        );
+        self.commands.push(cmd);
+    }
+
+    /// Answer the editor's `Ready` with our protocol version and the
+    /// optional features this host handles.
+    fn send_handshake(&self, editor_version: u32) {
+        if !protocol::protocol_compatible(editor_version) {
+            log::warn!(
+                "Editor protocol version {} does not match host version {}",
+                editor_version,
+                protocol::PROTOCOL_VERSION
+            );
+        }
+        self.send_command(&EditorCommand::Handshake {
+            protocol_version: protocol::PROTOCOL_VERSION,
+            features: HOST_FEATURES.to_vec(),
+        });
     }
 
     pub fn open_file(&self, file_path: &str, content: &str, language: &str) {
@@ -114,16 +202,113 @@ impl MonacoEditorHandle {
             self.pending_position.set(Some((line, column)));
             return;
         }
-        self.send_command(&EditorCommand::GoToPosition { line, column });
+        self.send_go_to_position(line, column);
     }
 
     /// Sends any queued go-to-position command (set while the editor wasn't ready).
     pub fn flush_pending_position(&self) {
         if let Some((line, column)) = self.pending_position.take() {
-            self.send_command(&EditorCommand::GoToPosition { line, column });
+            self.send_go_to_position(line, column);
         }
     }
 
+    fn send_go_to_position(&self, line: u32, column: u32) {
+        let file_path = self.file_path.borrow().clone();
+        self.send_request(
+            |request_id| EditorCommand::Request {
+                request_id,
+                command: Box::new(EditorCommand::GoToPosition { line, column }),
+            },
+            move |result| {
+                if let Err(e) = result {
+                    log::warn!(
+                        "Go to {}:{} in {} failed: {}",
+                        line + 1,
+                        column + 1,
+                        file_path,
+                        e
+                    );
+                }
+            },
+        );
+    }
+
+    /// Fetch the selected text (empty when nothing is selected) and pass it
+    /// to `on_text`.
+    pub fn get_selection(&self, on_text: impl FnOnce(Result<String, RequestError>) + 'static) {
+        self.send_request(
+            |request_id| EditorCommand::GetSelection { request_id },
+            move |result| on_text(result.map(Option::unwrap_or_default)),
+        );
+    }
+
+    /// Send the command `make_command` builds for a new request id and pass
+    /// the editor's `Response` to `on_response`, or
+    /// `RequestError::TimedOut` when none arrives in time.
+    fn send_request(
+        &self,
+        make_command: impl FnOnce(u64) -> EditorCommand,
+        on_response: impl FnOnce(Result<Option<String>, RequestError>) + 'static,
+    ) {
+        let request_id = self.requests.borrow_mut().register(Box::new(on_response));
+        self.send_command(&make_command(request_id));
+        let requests = Rc::downgrade(&self.requests);
+        glib::timeout_add_local_once(self.requests.borrow().timeout(), move || {
+            let Some(requests) = requests.upgrade() else {
+                return;
+            };
+            let expired = requests.borrow_mut().expire();
+            for on_response in expired {
+                on_response(Err(RequestError::TimedOut));
+            }
+        });
+    }
+
+    fn resolve_request(&self, request_id: u64, content: Option<String>, error: Option<String>) {
+        let on_response = self.requests.borrow_mut().resolve(request_id);
+        match on_response {
+            Some(on_response) => on_response(protocol::response_result(content, error)),
+            None => log::debug!("Late or unknown editor response {}", request_id),
+        }
+    }
+
+    /// Replace the buffer with unsaved content recovered after a crash,
+    /// leaving the file modified. Waits for the file to open if needed.
+    pub fn restore_content(&self, text: String) {
+        if self.file_opened.get() {
+            self.replace_content(&text);
+        } else {
+            *self.pending_restore.borrow_mut() = Some(text);
+        }
+    }
+
+    /// Send this editor's events to `on_event` from now on, e.g. after its
+    /// tab was dragged to another window. An already open file is announced
+    /// with a `FileOpened` so the new handler can sync it with its own
+    /// language servers.
+    pub fn set_event_handler(&self, on_event: impl Fn(&MonacoEditorHandle, EditorEvent) + 'static) {
+        let on_event: EventHandler = Rc::new(on_event);
+        *self.event_handler.borrow_mut() = on_event.clone();
+        if self.file_opened.get() {
+            on_event(self, EditorEvent::FileOpened);
+        }
+    }
+
+    fn dispatch_event(&self, event: EditorEvent) {
+        let on_event = self.event_handler.borrow().clone();
+        on_event(self, event);
+    }
+
+    fn on_file_opened(self: &Rc<Self>) {
+        self.file_opened.set(true);
+        if let Some(text) = self.pending_restore.take() {
+            self.replace_content(&text);
+        }
+        self.has_conflict_regions.set(false);
+        self.refresh_syntax_highlight();
+        self.refresh_conflict_regions();
+    }
+
     pub fn apply_diagnostics(&self, diagnostics: &[DiagnosticInfo]) {
         let markers: Vec<MonacoDiagnostic> = diagnostics
             .iter()
@@ -219,10 +404,62 @@ impl MonacoEditorHandle {
         });
     }
 
+    /// Indentation stays per-file: it was resolved on open and may since
+    /// have been changed from the status bar.
     pub fn apply_settings(&self, settings: &Settings) {
-        let options = settings_to_editor_options(settings);
         self.send_command(&EditorCommand::UpdateSettings {
-            options: Box::new(options),
+            options: Box::new(self.editor_options(settings)),
+        });
+        self.send_keybindings(settings);
+    }
+
+    /// `settings` as Monaco options, with this file's indentation and zoom.
+    fn editor_options(&self, settings: &Settings) -> EditorOptions {
+        let mut options = settings_to_editor_options(settings);
+        let indentation = self.indentation.get();
+        options.tab_size = Some(indentation.width);
+        options.insert_spaces = Some(indentation.use_spaces);
+        options.font_size =
+            Some(impulse_core::zoom::zoomed_font_size(settings.font_size, self.zoom.get()) as u32);
+        options
+    }
+
+    /// Zoom this tab by `zoom` points over the `font_size` setting.
+    pub fn set_zoom(&self, zoom: i32, settings: &Settings) {
+        self.zoom.set(zoom);
+        self.send_command(&EditorCommand::UpdateSettings {
+            options: Box::new(EditorOptions {
+                font_size: Some(
+                    impulse_core::zoom::zoomed_font_size(settings.font_size, zoom) as u32,
+                ),
+                ..Default::default()
+            }),
+        });
+    }
+
+    /// Switch this file to `indentation`; with `convert`, existing lines are
+    /// re-indented to match.
+    pub fn set_indentation(&self, indentation: Indentation, convert: bool) {
+        self.indentation.set(indentation);
+        self.send_command(&EditorCommand::SetIndentation {
+            tab_size: indentation.width,
+            insert_spaces: indentation.use_spaces,
+            convert,
+        });
+    }
+
+    /// Push the user's rebindings of Monaco actions.
+    fn send_keybindings(&self, settings: &Settings) {
+        self.send_command(&EditorCommand::SetKeybindings {
+            bindings: impulse_editor::keybindings::editor_keybindings(
+                &settings.effective_keybinding_overrides(),
+            ),
+        });
+    }
+
+    pub fn set_custom_css(&self, css: &str) {
+        self.send_command(&EditorCommand::SetCustomCss {
+            css: css.to_string(),
         });
     }
 
@@ -233,15 +470,24 @@ impl MonacoEditorHandle {
         });
     }
 
-    pub fn apply_diff_decorations(&self, decorations: Vec<DiffDecoration>) {
-        self.send_command(&EditorCommand::ApplyDiffDecorations { decorations });
+    pub fn apply_diff_decorations(
+        &self,
+        decorations: Vec<DiffDecoration>,
+        hunks: Vec<DiffHunkMarker>,
+    ) {
+        self.send_command(&EditorCommand::ApplyDiffDecorations { decorations, hunks });
+    }
+
+    pub fn set_blame_gutter(&self, lines: Vec<BlameGutterLine>) {
+        self.send_command(&EditorCommand::SetBlameGutter { lines });
     }
 
-    pub fn set_read_only(&self, read_only: bool) {
+    pub fn set_read_only(&self, reason: Option<ReadOnlyReason>) {
+        self.read_only.set(reason);
         if self.is_ready.get() {
-            self.send_command(&EditorCommand::SetReadOnly { read_only });
-        } else {
-            self.pending_read_only.set(read_only);
+            self.send_command(&EditorCommand::SetReadOnly {
+                read_only: reason.is_some(),
+            });
         }
     }
 
@@ -348,6 +594,11 @@ impl MonacoEditorHandle {
                     })
                     .collect(),
                 is_preferred: a.is_preferred,
+                command: a.command.as_ref().map(|c| MonacoCommand {
+                    title: c.title.clone(),
+                    command: c.command.clone(),
+                    arguments: c.arguments.clone().unwrap_or_default(),
+                }),
             })
             .collect();
         self.send_command(&EditorCommand::ResolveCodeActions {
@@ -356,6 +607,11 @@ impl MonacoEditorHandle {
         });
     }
 
+    /// Run a Monaco action such as `editor.action.organizeImports`.
+    pub fn run_action(&self, id: &str) {
+        self.send_command(&EditorCommand::RunAction { id: id.to_string() });
+    }
+
     pub fn resolve_rename(&self, request_id: u64, edits: &[WorkspaceTextEditInfo]) {
         let monaco_edits: Vec<MonacoWorkspaceTextEdit> = edits
             .iter()
@@ -376,6 +632,42 @@ impl MonacoEditorHandle {
         });
     }
 
+    /// Apply confirmed workspace edits to this editor's buffer as one undoable
+    /// step. The buffer becomes modified; saving is left to the user.
+    pub fn apply_edits(&self, edits: &[impulse_core::workspace_edit::TextEdit]) {
+        let monaco_edits: Vec<MonacoTextEdit> = edits
+            .iter()
+            .map(|e| MonacoTextEdit {
+                range: MonacoRange {
+                    start_line: e.start_line,
+                    start_column: e.start_character,
+                    end_line: e.end_line,
+                    end_column: e.end_character,
+                },
+                text: e.new_text.clone(),
+            })
+            .collect();
+        self.send_command(&EditorCommand::ApplyEdits {
+            edits: monaco_edits,
+        });
+    }
+
+    /// Replace the whole buffer as one undoable edit, e.g. to take one side
+    /// of a merge conflict or revert to the saved file. Undo history is kept.
+    pub fn replace_content(&self, text: &str) {
+        self.send_command(&EditorCommand::ReplaceContent {
+            content: text.to_string(),
+        });
+    }
+
+    /// Highlight the buffer as the Monaco language `language`.
+    pub fn set_language(&self, language: &str) {
+        *self.language.borrow_mut() = language.to_string();
+        self.send_command(&EditorCommand::SetLanguage {
+            language: language.to_string(),
+        });
+    }
+
     pub fn resolve_prepare_rename(
         &self,
         request_id: u64,
@@ -395,6 +687,113 @@ impl MonacoEditorHandle {
         });
     }
 
+    /// Show the inline peek widget for a `PeekRequested` event. Previews for
+    /// each location are read from open editor buffers first and from disk
+    /// otherwise, so unsaved edits are reflected.
+    pub fn show_peek(&self, request_id: u64, kind: protocol::PeekKind, locations: &[LocationInfo]) {
+        let monaco_locations: Vec<MonacoLocation> = locations
+            .iter()
+            .map(|l| MonacoLocation {
+                uri: l.uri.clone(),
+                range: MonacoRange {
+                    start_line: l.start_line,
+                    start_column: l.start_character,
+                    end_line: l.end_line,
+                    end_column: l.end_character,
+                },
+            })
+            .collect();
+        let peek_locations: Vec<MonacoPeekLocation> =
+            impulse_editor::peek::build_peek_locations(&monaco_locations, |uri| {
+                let path = impulse_core::util::uri_to_file_path(uri);
+                if path == *self.file_path.borrow() {
+                    return Some(self.get_content());
+                }
+                match crate::editor::get_handle(&path) {
+                    Some(handle) => Some(handle.get_content()),
+                    None => impulse_editor::peek::read_source_from_disk(uri),
+                }
+            });
+        self.send_command(&EditorCommand::ShowPeek {
+            request_id,
+            kind,
+            locations: peek_locations,
+        });
+    }
+
+    /// Highlight the buffer with tree-sitter when Monaco has no grammar for its
+    /// language. Runs off the main thread; results for a stale buffer version
+    /// are dropped since a newer pass is already scheduled.
+    fn refresh_syntax_highlight(self: &Rc<Self>) {
+        let language = self.language.borrow().clone();
+        if !impulse_core::highlight::supports_language(&language) {
+            return;
+        }
+        let content = self.get_content();
+        let file_path = self.file_path.borrow().clone();
+        let version = self.version.get();
+        let weak = Rc::downgrade(self);
+        glib::spawn_future_local(async move {
+            let result = gtk4::gio::spawn_blocking(move || {
+                impulse_core::highlight::highlight(&language, &content)
+            })
+            .await;
+            let Some(handle) = weak.upgrade() else {
+                return;
+            };
+            if handle.version.get() != version || *handle.file_path.borrow() != file_path {
+                return;
+            }
+            match result {
+                Ok(Ok(tokens)) => handle.send_command(&EditorCommand::SetSyntaxTokens {
+                    tokens: tokens.into_iter().map(SyntaxToken::from).collect(),
+                }),
+                Ok(Err(e)) => log::warn!("Syntax highlighting failed for {}: {}", file_path, e),
+                Err(_) => log::warn!("Syntax highlighting task panicked for {}", file_path),
+            }
+        });
+    }
+
+    /// Send the buffer's merge-conflict regions to Monaco. Buffers without
+    /// markers are skipped unless regions are showing and must be cleared.
+    fn refresh_conflict_regions(&self) {
+        let content = self.cached_content.borrow();
+        let has_markers = impulse_core::conflict::has_conflict_markers(&content);
+        if !has_markers && !self.has_conflict_regions.get() {
+            return;
+        }
+        let regions: Vec<ConflictRegionMarker> = if has_markers {
+            impulse_core::conflict::find_conflict_regions(&content)
+                .into_iter()
+                .map(ConflictRegionMarker::from)
+                .collect()
+        } else {
+            Vec::new()
+        };
+        drop(content);
+        self.has_conflict_regions.set(!regions.is_empty());
+        self.send_command(&EditorCommand::SetConflictRegions { regions });
+    }
+
+    /// Debounced [`Self::refresh_syntax_highlight`] so a burst of keystrokes
+    /// triggers a single pass.
+    fn schedule_syntax_highlight(self: &Rc<Self>) {
+        if !impulse_core::highlight::supports_language(&self.language.borrow()) {
+            return;
+        }
+        if let Some(id) = self.syntax_highlight_timer.borrow_mut().take() {
+            id.remove();
+        }
+        let weak = Rc::downgrade(self);
+        let id = glib::timeout_add_local_once(Duration::from_millis(150), move || {
+            if let Some(handle) = weak.upgrade() {
+                handle.syntax_highlight_timer.borrow_mut().take();
+                handle.refresh_syntax_highlight();
+            }
+        });
+        *self.syntax_highlight_timer.borrow_mut() = Some(id);
+    }
+
     /// Release resources held by this editor handle. Must be called before the
     /// tab is removed to break the reference cycle between the GLib timer, the
     /// WebView, and the signal closures that hold `Rc<MonacoEditorHandle>`.
@@ -405,6 +804,9 @@ impl MonacoEditorHandle {
         if let Some(id) = self._file_watcher_timer.borrow_mut().take() {
             id.remove();
         }
+        if let Some(id) = self.syntax_highlight_timer.borrow_mut().take() {
+            id.remove();
+        }
         // Drop the filesystem watcher (closes the inotify fd).
         self._file_watcher.borrow_mut().take();
         // Unregister the JS→Rust message handler so the UCM signal closure
@@ -469,7 +871,7 @@ impl MonacoEditorHandle {
         let cached_content = self.cached_content.clone();
         let file_path_cell = self.file_path.clone();
         let language = self.language.clone();
-        let webview = self.webview.clone();
+        let commands = self.commands.clone();
         let is_ready = self.is_ready.clone();
         let watcher_cell = self._file_watcher.clone();
 
@@ -491,17 +893,7 @@ impl MonacoEditorHandle {
                         content: new_content,
                         language: lang,
                     };
-                    if let Ok(json) = serde_json::to_string(&cmd) {
-                        let escaped = js_string_escape(&json);
-                        let script = format!("impulseReceiveCommand('{}')", escaped);
-                        webview.evaluate_javascript(
-                            &script,
-                            None,
-                            None,
-                            None::<&gtk4::gio::Cancellable>,
-                            |_| {},
-                        );
-                    }
+                    commands.push(&cmd);
                 }
                 // Restart watcher: after an atomic write the inotify watch may
                 // be on a stale inode. Create a new watcher writing to the same
@@ -546,13 +938,40 @@ struct WarmWebView {
     webview: webkit6::WebView,
     user_content_manager: webkit6::UserContentManager,
     is_ready: Rc<Cell<bool>>,
+    /// Protocol version the editor reported on `Ready`.
+    protocol_version: Rc<Cell<u32>>,
     signal_handler_id: glib::SignalHandlerId,
 }
 
+/// Optional editor features the Linux host handles.
+const HOST_FEATURES: &[HostFeature] = &[
+    HostFeature::Peek,
+    HostFeature::StageHunk,
+    HostFeature::MergeView,
+    HostFeature::OnTypeFormatting,
+];
+
 thread_local! {
     static WARM_POOL: RefCell<Option<WarmWebView>> = const { RefCell::new(None) };
 }
 
+/// Extract Monaco on a worker thread, then pre-warm a WebView. Keeps the
+/// extraction (slow on first launch after an update) off the critical path
+/// to the first window.
+pub fn warm_up_editor_in_background() {
+    glib::spawn_future_local(async {
+        impulse_core::startup_profile::mark("monaco extraction started");
+        match gtk4::gio::spawn_blocking(impulse_editor::assets::ensure_monaco_extracted).await {
+            Ok(Ok(_)) => {
+                impulse_core::startup_profile::mark("monaco extracted");
+                warm_up_editor();
+            }
+            Ok(Err(e)) => log::warn!("Failed to extract Monaco for pre-warm: {}", e),
+            Err(_) => log::warn!("Monaco extraction panicked"),
+        }
+    });
+}
+
 /// Pre-extract Monaco assets and start loading a WebView in the background.
 /// When the WebView is ready, it can be claimed by `create_monaco_editor` for
 /// an instant editor open. Call this once at app startup.
@@ -592,15 +1011,18 @@ pub fn warm_up_editor() {
 
         let is_ready = Rc::new(Cell::new(false));
         let is_ready_clone = is_ready.clone();
+        let protocol_version = Rc::new(Cell::new(0));
+        let protocol_version_clone = protocol_version.clone();
 
         ucm.register_script_message_handler("impulse", None);
         let signal_id = ucm.connect_script_message_received(Some("impulse"), move |_ucm, value| {
             let json_str = value.to_str().to_string();
-            if let Ok(event) = serde_json::from_str::<EditorEvent>(&json_str) {
-                if matches!(event, EditorEvent::Ready) {
-                    is_ready_clone.set(true);
-                    log::info!("Pre-warmed editor WebView is ready");
-                }
+            if let Ok(EditorEvent::Ready { protocol_version }) =
+                serde_json::from_str::<EditorEvent>(&json_str)
+            {
+                protocol_version_clone.set(protocol_version);
+                is_ready_clone.set(true);
+                log::info!("Pre-warmed editor WebView is ready");
             }
         });
 
@@ -637,6 +1059,7 @@ pub fn warm_up_editor() {
             webview,
             user_content_manager: ucm,
             is_ready,
+            protocol_version,
             signal_handler_id: signal_id,
         });
     });
@@ -656,6 +1079,34 @@ fn claim_warm_editor() -> Option<WarmWebView> {
 
 // ---------------------------------------------------------------------------
 
+/// Files dropped on the editor open in tabs, through the window's
+/// `open-in-tab` action. The target runs in the capture phase so the WebView
+/// never sees the drop.
+fn connect_file_drops(container: &gtk4::Box) {
+    let drop_target = gtk4::DropTarget::new(
+        gtk4::gdk::FileList::static_type(),
+        gtk4::gdk::DragAction::COPY,
+    );
+    drop_target.set_propagation_phase(gtk4::PropagationPhase::Capture);
+    let widget = container.clone();
+    drop_target.connect_drop(move |_target, value, _x, _y| {
+        let Ok(file_list) = value.get::<gtk4::gdk::FileList>() else {
+            return false;
+        };
+        let mut opened = false;
+        for path in file_list.files().iter().filter_map(|f| f.path()) {
+            if path.is_file() {
+                let path = path.to_string_lossy().to_variant();
+                opened |= widget
+                    .activate_action("win.open-in-tab", Some(&path))
+                    .is_ok();
+            }
+        }
+        opened
+    });
+    container.add_controller(drop_target);
+}
+
 /// Create a Monaco editor widget inside a WebView.
 ///
 /// Returns the container `gtk4::Box` (with `widget_name` set to `file_path`)
@@ -679,25 +1130,10 @@ where
     container.set_hexpand(true);
     container.set_vexpand(true);
     container.set_widget_name(file_path);
+    connect_file_drops(&container);
 
     // Detect indentation from content, then apply per-file-type overrides
-    let (mut use_spaces, mut indent_width) = detect_indentation(content);
-    for ovr in &settings.file_type_overrides {
-        if crate::settings::matches_file_pattern(file_path, &ovr.pattern) {
-            if let Some(tw) = ovr.tab_width {
-                indent_width = tw;
-            }
-            if let Some(us) = ovr.use_spaces {
-                use_spaces = us;
-            }
-            break;
-        }
-    }
-    let indent_info = if use_spaces {
-        format!("Spaces: {}", indent_width)
-    } else {
-        format!("Tab Size: {}", indent_width)
-    };
+    let indentation = resolve_indentation(file_path, content, settings);
 
     // Try to claim a pre-warmed WebView for instant editor opening.
     if let Some(warm) = claim_warm_editor() {
@@ -715,6 +1151,7 @@ where
         // Create the handle with is_ready already set — Monaco is loaded.
         let handle = Rc::new(MonacoEditorHandle {
             webview: webview.clone(),
+            commands: CommandQueue::new(webview.clone()),
             file_path: RefCell::new(file_path.to_string()),
             cached_content: Rc::new(RefCell::new(content.to_string())),
             is_modified: Rc::new(Cell::new(false)),
@@ -723,13 +1160,24 @@ where
             version: Rc::new(Cell::new(0)),
             suppress_next_modify: Rc::new(Cell::new(false)),
             pending_position: Cell::new(None),
-            pending_read_only: Cell::new(false),
-            indent_info: RefCell::new(indent_info),
+            file_opened: Cell::new(false),
+            pending_restore: RefCell::new(None),
+            read_only: Cell::new(None),
+            indentation: Cell::new(indentation),
             _file_watcher: Rc::new(RefCell::new(None)),
             _file_watcher_timer: RefCell::new(None),
             is_previewing: Cell::new(false),
+            blame_visible: Cell::new(false),
             stack: RefCell::new(None),
             untitled_cwd: RefCell::new(None),
+            syntax_highlight_timer: RefCell::new(None),
+            has_conflict_regions: Cell::new(false),
+            cursor_line: Cell::new(1),
+            zoom: Cell::new(0),
+            event_handler: RefCell::new(Rc::new(on_event)),
+            requests: Rc::new(RefCell::new(PendingRequests::new(
+                protocol::REQUEST_TIMEOUT,
+            ))),
         });
 
         // Connect the real signal handler for ongoing events.
@@ -761,20 +1209,40 @@ where
                 }
             }
 
-            on_event(&handle_for_signal, event);
+            if let EditorEvent::Response {
+                request_id,
+                content,
+                error,
+            } = event
+            {
+                handle_for_signal.resolve_request(request_id, content, error);
+                return;
+            }
+
+            match &event {
+                EditorEvent::FileOpened => handle_for_signal.on_file_opened(),
+                EditorEvent::ContentChanged { .. } => {
+                    handle_for_signal.schedule_syntax_highlight();
+                    handle_for_signal.refresh_conflict_regions();
+                }
+                EditorEvent::CursorMoved { line, .. } => handle_for_signal.cursor_line.set(*line),
+                _ => {}
+            }
+
+            handle_for_signal.dispatch_event(event);
         });
 
-        // Immediately send theme, settings, and file content.
+        // Immediately send the handshake, theme, settings, and file content.
+        handle.send_handshake(warm.protocol_version.get());
         handle.send_command(&EditorCommand::SetTheme {
             theme: Box::new(theme_to_monaco(theme)),
         });
+        handle.set_custom_css(&crate::theme::editor_custom_css());
 
-        let mut options = settings_to_editor_options(settings);
-        options.tab_size = Some(indent_width);
-        options.insert_spaces = Some(use_spaces);
         handle.send_command(&EditorCommand::UpdateSettings {
-            options: Box::new(options),
+            options: Box::new(handle.editor_options(settings)),
         });
+        handle.send_keybindings(settings);
 
         handle.send_command(&EditorCommand::OpenFile {
             file_path: file_path.to_string(),
@@ -853,6 +1321,7 @@ where
     // Create the handle
     let handle = Rc::new(MonacoEditorHandle {
         webview: webview.clone(),
+        commands: CommandQueue::new(webview.clone()),
         file_path: RefCell::new(file_path.to_string()),
         cached_content: Rc::new(RefCell::new(content.to_string())),
         is_modified: Rc::new(Cell::new(false)),
@@ -861,13 +1330,24 @@ where
         version: Rc::new(Cell::new(0)),
         suppress_next_modify: Rc::new(Cell::new(false)),
         pending_position: Cell::new(None),
-        pending_read_only: Cell::new(false),
-        indent_info: RefCell::new(indent_info),
+        file_opened: Cell::new(false),
+        pending_restore: RefCell::new(None),
+        read_only: Cell::new(None),
+        indentation: Cell::new(indentation),
         _file_watcher: Rc::new(RefCell::new(None)),
         _file_watcher_timer: RefCell::new(None),
         is_previewing: Cell::new(false),
+        blame_visible: Cell::new(false),
         stack: RefCell::new(None),
         untitled_cwd: RefCell::new(None),
+        syntax_highlight_timer: RefCell::new(None),
+        has_conflict_regions: Cell::new(false),
+        cursor_line: Cell::new(1),
+        zoom: Cell::new(0),
+        event_handler: RefCell::new(Rc::new(on_event)),
+        requests: Rc::new(RefCell::new(PendingRequests::new(
+            protocol::REQUEST_TIMEOUT,
+        ))),
     });
 
     // Store initial content, language, settings, and theme to send after Ready
@@ -876,8 +1356,6 @@ where
     let initial_language = language.to_string();
     let initial_settings = settings.clone();
     let initial_theme = theme_to_monaco(theme);
-    let initial_indent_width = indent_width;
-    let initial_use_spaces = use_spaces;
 
     // Connect JS→Rust message handler
     let handle_for_signal = handle.clone();
@@ -894,9 +1372,10 @@ where
             }
         };
 
-        // Handle Ready specially: send initial content, theme, settings
-        if matches!(event, EditorEvent::Ready) {
+        // Handle Ready specially: send handshake, initial content, theme, settings
+        if let EditorEvent::Ready { protocol_version } = event {
             handle_for_signal.is_ready.set(true);
+            handle_for_signal.send_handshake(protocol_version);
 
             if !sent_initial.get() {
                 sent_initial.set(true);
@@ -905,14 +1384,13 @@ where
                 handle_for_signal.send_command(&EditorCommand::SetTheme {
                     theme: Box::new(initial_theme.clone()),
                 });
+                handle_for_signal.set_custom_css(&crate::theme::editor_custom_css());
 
                 // Set settings (including indent from file detection)
-                let mut options = settings_to_editor_options(&initial_settings);
-                options.tab_size = Some(initial_indent_width);
-                options.insert_spaces = Some(initial_use_spaces);
                 handle_for_signal.send_command(&EditorCommand::UpdateSettings {
-                    options: Box::new(options),
+                    options: Box::new(handle_for_signal.editor_options(&initial_settings)),
                 });
+                handle_for_signal.send_keybindings(&initial_settings);
 
                 // Open the file
                 handle_for_signal.send_command(&EditorCommand::OpenFile {
@@ -922,7 +1400,7 @@ where
                 });
 
                 // Apply deferred read-only mode (e.g. for large files)
-                if handle_for_signal.pending_read_only.get() {
+                if handle_for_signal.read_only.get().is_some() {
                     handle_for_signal.send_command(&EditorCommand::SetReadOnly { read_only: true });
                 }
             }
@@ -944,8 +1422,29 @@ where
             }
         }
 
+        if let EditorEvent::Response {
+            request_id,
+            content,
+            error,
+        } = event
+        {
+            handle_for_signal.resolve_request(request_id, content, error);
+            return;
+        }
+
+        // Highlight languages Monaco has no grammar for
+        match &event {
+            EditorEvent::FileOpened => handle_for_signal.on_file_opened(),
+            EditorEvent::ContentChanged { .. } => {
+                handle_for_signal.schedule_syntax_highlight();
+                handle_for_signal.refresh_conflict_regions();
+            }
+            EditorEvent::CursorMoved { line, .. } => handle_for_signal.cursor_line.set(*line),
+            _ => {}
+        }
+
         // Forward to caller's event handler
-        on_event(&handle_for_signal, event);
+        handle_for_signal.dispatch_event(event);
     });
 
     // Extract Monaco assets and load editor from local filesystem
@@ -1107,6 +1606,9 @@ fn settings_to_editor_options(settings: &Settings) -> EditorOptions {
 }
 
 pub fn theme_to_monaco(theme: &ThemeColors) -> MonacoThemeDefinition {
+    if let Some(resolved) = theme.resolved {
+        return impulse_editor::protocol::theme_to_monaco(resolved);
+    }
     // Strip '#' prefix from colors for Monaco (which expects bare hex)
     let strip = |c: &str| c.trim_start_matches('#').to_string();
 
@@ -1409,38 +1911,3 @@ pub fn theme_to_monaco(theme: &ThemeColors) -> MonacoThemeDefinition {
         },
     }
 }
-
-fn detect_indentation(content: &str) -> (bool, u32) {
-    let mut space_lines = 0;
-    let mut tab_lines = 0;
-    let mut indent_widths = HashMap::new();
-
-    for line in content.lines().take(100) {
-        if line.starts_with('\t') {
-            tab_lines += 1;
-        } else if line.starts_with(' ') {
-            space_lines += 1;
-            let spaces = line.len() - line.trim_start_matches(' ').len();
-            if spaces >= 2 {
-                for width in &[2u32, 4, 8] {
-                    if spaces % (*width as usize) == 0 {
-                        *indent_widths.entry(*width).or_insert(0) += 1;
-                    }
-                }
-            }
-        }
-    }
-
-    let use_spaces = space_lines >= tab_lines;
-    let indent_width = if use_spaces {
-        indent_widths
-            .into_iter()
-            .max_by_key(|&(_, count)| count)
-            .map(|(width, _)| width)
-            .unwrap_or(4)
-    } else {
-        4
-    };
-
-    (use_spaces, indent_width)
-}
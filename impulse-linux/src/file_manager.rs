@@ -0,0 +1,53 @@
+//! Handing files to the desktop's file manager.
+
+use gtk4::gio;
+use gtk4::prelude::*;
+
+const FILE_MANAGER_BUS_NAME: &str = "org.freedesktop.FileManager1";
+const FILE_MANAGER_OBJECT_PATH: &str = "/org/freedesktop/FileManager1";
+
+/// Open the folder containing `path` with `path` selected, through the
+/// `org.freedesktop.FileManager1` D-Bus interface that Nautilus, Dolphin,
+/// Nemo and Thunar implement. Without one, the parent folder is opened with
+/// the default handler instead (nothing selected).
+pub fn show_item(path: &str) {
+    let uri = gio::File::for_path(path).uri().to_string();
+    let connection = match gio::bus_get_sync(gio::BusType::Session, gio::Cancellable::NONE) {
+        Ok(connection) => connection,
+        Err(e) => {
+            log::warn!("No session bus to reach the file manager: {}", e);
+            open_parent(path);
+            return;
+        }
+    };
+    let path = path.to_string();
+    connection.call(
+        Some(FILE_MANAGER_BUS_NAME),
+        FILE_MANAGER_OBJECT_PATH,
+        FILE_MANAGER_BUS_NAME,
+        "ShowItems",
+        Some(&(vec![uri], String::new()).to_variant()),
+        None,
+        gio::DBusCallFlags::NONE,
+        5000,
+        gio::Cancellable::NONE,
+        move |result| {
+            if let Err(e) = result {
+                log::warn!("FileManager1.ShowItems failed: {}", e);
+                open_parent(&path);
+            }
+        },
+    );
+}
+
+fn open_parent(path: &str) {
+    let Some(parent) = std::path::Path::new(path).parent() else {
+        return;
+    };
+    let uri = gio::File::for_path(parent).uri();
+    if let Err(e) =
+        gio::AppInfo::launch_default_for_uri(uri.as_str(), None::<&gio::AppLaunchContext>)
+    {
+        log::warn!("Failed to open {}: {}", parent.display(), e);
+    }
+}
@@ -0,0 +1,80 @@
+//! Crash recovery for unsaved buffers: one app-wide `impulse_core::hot_exit`
+//! store, synced with every open editor on a timer. Backups left by a run
+//! that didn't exit cleanly are kept until a window asks for them with
+//! [`take_recovered`].
+
+use std::cell::{Cell, RefCell};
+
+use gtk4::glib;
+use impulse_core::hot_exit::{Backup, HotExit, BACKUP_INTERVAL_SECS};
+
+thread_local! {
+    static HOT_EXIT: RefCell<Option<HotExit>> = const { RefCell::new(None) };
+    static ENABLED: Cell<bool> = const { Cell::new(true) };
+    static RECOVERED: RefCell<Vec<Backup>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Open the backup store and start backing up modified editors.
+pub fn start(enabled: bool) {
+    let Some(dir) = crate::session_state::state_dir() else {
+        log::warn!("Cannot determine state directory; unsaved changes will not be backed up");
+        return;
+    };
+    let mut hot_exit = HotExit::new(&dir);
+    match hot_exit.start() {
+        Ok(recovered) => RECOVERED.with(|r| *r.borrow_mut() = recovered),
+        Err(e) => {
+            log::warn!("{}", e);
+            return;
+        }
+    }
+    HOT_EXIT.with(|h| *h.borrow_mut() = Some(hot_exit));
+    ENABLED.with(|e| e.set(enabled));
+
+    glib::timeout_add_seconds_local(BACKUP_INTERVAL_SECS, || {
+        sync();
+        glib::ControlFlow::Continue
+    });
+}
+
+/// Turning backups off drops the existing ones on the next tick.
+pub fn set_enabled(enabled: bool) {
+    ENABLED.with(|e| e.set(enabled));
+}
+
+/// Backups from a crashed previous run, handed out once.
+pub fn take_recovered() -> Vec<Backup> {
+    RECOVERED.with(|r| std::mem::take(&mut *r.borrow_mut()))
+}
+
+/// The user declined to restore: remove the recovered backups.
+pub fn discard_recovered() {
+    HOT_EXIT.with(|h| {
+        if let Some(hot_exit) = h.borrow_mut().as_mut() {
+            hot_exit.clear();
+        }
+    });
+}
+
+/// Clean exit. Every window has already confirmed saving or discarding its
+/// changes, so nothing needs recovering.
+pub fn finish() {
+    HOT_EXIT.with(|h| {
+        if let Some(hot_exit) = h.borrow_mut().as_mut() {
+            hot_exit.finish();
+        }
+    });
+}
+
+fn sync() {
+    let buffers = if ENABLED.with(|e| e.get()) {
+        crate::editor::modified_buffers()
+    } else {
+        Vec::new()
+    };
+    HOT_EXIT.with(|h| {
+        if let Some(hot_exit) = h.borrow_mut().as_mut() {
+            hot_exit.sync(buffers.iter().map(|(p, c)| (p.as_str(), c.as_str())));
+        }
+    });
+}
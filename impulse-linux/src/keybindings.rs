@@ -1,153 +1,31 @@
 use std::collections::HashMap;
+use std::sync::OnceLock;
 
 /// A built-in keyboard shortcut with its default accelerator.
 pub struct BuiltinKeybinding {
     pub id: &'static str,
     pub description: &'static str,
     pub category: &'static str,
-    pub default_accel: &'static str,
+    pub default_accel: String,
 }
 
-pub const BUILTIN_KEYBINDINGS: &[BuiltinKeybinding] = &[
-    // Tabs
-    BuiltinKeybinding {
-        id: "new_tab",
-        description: "New Terminal Tab",
-        category: "Tabs",
-        default_accel: "<Ctrl>t",
-    },
-    BuiltinKeybinding {
-        id: "close_tab",
-        description: "Close Tab",
-        category: "Tabs",
-        default_accel: "<Ctrl>w",
-    },
-    BuiltinKeybinding {
-        id: "reopen_tab",
-        description: "Reopen Closed Tab",
-        category: "Tabs",
-        default_accel: "<Ctrl><Shift>t",
-    },
-    BuiltinKeybinding {
-        id: "next_tab",
-        description: "Next Tab",
-        category: "Tabs",
-        default_accel: "<Ctrl>Tab",
-    },
-    BuiltinKeybinding {
-        id: "prev_tab",
-        description: "Previous Tab",
-        category: "Tabs",
-        default_accel: "<Ctrl><Shift>Tab",
-    },
-    // Terminal
-    BuiltinKeybinding {
-        id: "paste",
-        description: "Paste",
-        category: "Terminal",
-        default_accel: "<Ctrl><Shift>v",
-    },
-    BuiltinKeybinding {
-        id: "copy",
-        description: "Copy",
-        category: "Terminal",
-        default_accel: "<Ctrl><Shift>c",
-    },
-    // Editor
-    BuiltinKeybinding {
-        id: "new_file",
-        description: "New File",
-        category: "Editor",
-        default_accel: "<Ctrl>n",
-    },
-    BuiltinKeybinding {
-        id: "save",
-        description: "Save File",
-        category: "Editor",
-        default_accel: "<Ctrl>s",
-    },
-    BuiltinKeybinding {
-        id: "find",
-        description: "Find",
-        category: "Editor",
-        default_accel: "<Ctrl>f",
-    },
-    BuiltinKeybinding {
-        id: "go_to_line",
-        description: "Go to Line",
-        category: "Editor",
-        default_accel: "<Ctrl>g",
-    },
-    BuiltinKeybinding {
-        id: "toggle_markdown_preview",
-        description: "Toggle Preview",
-        category: "Editor",
-        default_accel: "<Ctrl><Shift>m",
-    },
-    // Navigation
-    BuiltinKeybinding {
-        id: "toggle_sidebar",
-        description: "Toggle Sidebar",
-        category: "Navigation",
-        default_accel: "<Ctrl><Shift>b",
-    },
-    BuiltinKeybinding {
-        id: "project_search",
-        description: "Find in Project",
-        category: "Navigation",
-        default_accel: "<Ctrl><Shift>f",
-    },
-    BuiltinKeybinding {
-        id: "command_palette",
-        description: "Command Palette",
-        category: "Navigation",
-        default_accel: "<Ctrl><Shift>p",
-    },
-    BuiltinKeybinding {
-        id: "review_changes",
-        description: "Review Changes",
-        category: "Navigation",
-        default_accel: "<Ctrl><Shift>g",
-    },
-    BuiltinKeybinding {
-        id: "open_settings",
-        description: "Open Settings",
-        category: "Navigation",
-        default_accel: "<Ctrl>comma",
-    },
-    // Font
-    BuiltinKeybinding {
-        id: "font_increase",
-        description: "Increase Font Size",
-        category: "Font",
-        default_accel: "<Ctrl>equal",
-    },
-    BuiltinKeybinding {
-        id: "font_decrease",
-        description: "Decrease Font Size",
-        category: "Font",
-        default_accel: "<Ctrl>minus",
-    },
-    BuiltinKeybinding {
-        id: "font_reset",
-        description: "Reset Font Size",
-        category: "Font",
-        default_accel: "<Ctrl>0",
-    },
-    // App
-    BuiltinKeybinding {
-        id: "new_window",
-        description: "New Window",
-        category: "App",
-        default_accel: "<Ctrl><Shift>n",
-    },
-    BuiltinKeybinding {
-        id: "fullscreen",
-        description: "Toggle Fullscreen",
-        category: "App",
-        default_accel: "F11",
-    },
-];
+/// The core command registry's commands that have a default shortcut.
+pub fn builtin_keybindings() -> &'static [BuiltinKeybinding] {
+    static BUILTINS: OnceLock<Vec<BuiltinKeybinding>> = OnceLock::new();
+    BUILTINS.get_or_init(|| {
+        impulse_core::commands::COMMANDS
+            .iter()
+            .filter_map(|command| {
+                Some(BuiltinKeybinding {
+                    id: command.id,
+                    description: command.title,
+                    category: command.category,
+                    default_accel: parse_keybinding_to_accel(command.default_shortcut()?),
+                })
+            })
+            .collect()
+    })
+}
 
 /// Returns the GTK accel string for a given keybinding ID, using the override
 /// if present, otherwise the built-in default.
@@ -158,16 +36,31 @@ pub fn get_accel(id: &str, overrides: &HashMap<String, String>) -> String {
             return accel;
         }
     }
-    BUILTIN_KEYBINDINGS
+    builtin_keybindings()
         .iter()
         .find(|kb| kb.id == id)
-        .map(|kb| kb.default_accel.to_string())
+        .map(|kb| kb.default_accel.clone())
         .unwrap_or_default()
 }
 
+/// Returns the GTK accel string `id` falls back to without a user override:
+/// the keymap preset's binding if it has one, otherwise the built-in default.
+pub fn preset_accel(id: &str, preset: &str) -> String {
+    get_accel(id, &impulse_core::keymap::preset_bindings(preset))
+}
+
 /// Converts a GTK accel string like `"<Ctrl><Shift>b"` to a human-readable
-/// display string like `"Ctrl+Shift+B"`.
+/// display string like `"Ctrl+Shift+B"`. Chords (`"<Ctrl>k <Ctrl>s"`) are
+/// shown step by step, e.g. `"Ctrl+K Ctrl+S"`.
 pub fn accel_to_display(accel: &str) -> String {
+    accel
+        .split_whitespace()
+        .map(step_to_display)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn step_to_display(accel: &str) -> String {
     let mut parts = Vec::new();
     let mut remaining = accel;
 
@@ -207,8 +100,23 @@ pub fn accel_to_display(accel: &str) -> String {
 }
 
 /// Converts a human-readable keybinding string like `"Ctrl+Shift+B"` into a
-/// GTK accelerator string like `"<Ctrl><Shift>b"`.
+/// GTK accelerator string like `"<Ctrl><Shift>b"`. A two-step chord such as
+/// `"Ctrl+K Ctrl+S"` becomes `"<Ctrl>k <Ctrl>s"`; anything invalid, including
+/// chords longer than two steps, yields an empty string.
 pub fn parse_keybinding_to_accel(key: &str) -> String {
+    let steps: Vec<String> = key.split_whitespace().map(parse_step_to_accel).collect();
+    if steps.is_empty() || steps.len() > 2 || steps.iter().any(|s| s.is_empty()) {
+        return String::new();
+    }
+    steps.join(" ")
+}
+
+/// Whether a GTK accel string from [`get_accel`] is a two-step chord.
+pub fn is_chord(accel: &str) -> bool {
+    accel.split_whitespace().count() > 1
+}
+
+fn parse_step_to_accel(key: &str) -> String {
     let parts: Vec<&str> = key.split('+').collect();
     if parts.is_empty() {
         return String::new();
@@ -230,7 +138,11 @@ pub fn parse_keybinding_to_accel(key: &str) -> String {
     if key_part.is_empty() {
         return String::new();
     }
-    accel.push_str(key_part);
+    if key_part.chars().count() == 1 {
+        accel.push_str(&key_part.to_lowercase());
+    } else {
+        accel.push_str(key_part);
+    }
     accel
 }
 
@@ -310,3 +222,155 @@ pub fn matches_key(
         .unwrap_or_default();
     key_name == parsed.key_lower
 }
+
+/// How long a chord waits for its second key before giving up.
+const CHORD_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(1500);
+
+type PendingCallback = dyn Fn(Option<&str>);
+
+struct Chord {
+    first: ParsedAccel,
+    first_display: String,
+    second: ParsedAccel,
+    action: std::rc::Rc<dyn Fn()>,
+}
+
+/// Dispatches two-step chord shortcuts like `Ctrl+K Ctrl+S`, which a
+/// `ShortcutController` can't express.
+///
+/// The first key of a chord is swallowed and starts a pending state; the
+/// next key press either completes a chord or cancels it. The pending state
+/// also ends after [`CHORD_TIMEOUT`]. `on_pending` receives the display
+/// string of the pending first key, or `None` when the chord is resolved.
+pub struct ChordDispatcher {
+    chords: std::cell::RefCell<Vec<Chord>>,
+    /// Indices into `chords` whose first key was just pressed.
+    pending: std::cell::RefCell<Vec<usize>>,
+    /// Bumped on every state change so stale timeouts do nothing.
+    generation: std::cell::Cell<u64>,
+    on_pending: Box<PendingCallback>,
+}
+
+impl ChordDispatcher {
+    pub fn new(on_pending: impl Fn(Option<&str>) + 'static) -> std::rc::Rc<Self> {
+        std::rc::Rc::new(Self {
+            chords: std::cell::RefCell::new(Vec::new()),
+            pending: std::cell::RefCell::new(Vec::new()),
+            generation: std::cell::Cell::new(0),
+            on_pending: Box::new(on_pending),
+        })
+    }
+
+    /// Register a chord accel (`"<Ctrl>k <Ctrl>s"`). Anything that isn't a
+    /// two-step chord is ignored.
+    pub fn add(&self, accel: &str, action: impl Fn() + 'static) {
+        let steps: Vec<&str> = accel.split_whitespace().collect();
+        let [first, second] = steps[..] else {
+            log::warn!("Ignoring non-chord accel {:?}", accel);
+            return;
+        };
+        let first_display = step_to_display(first);
+        let (Some(first), Some(second)) = (parse_accel(first), parse_accel(second)) else {
+            log::warn!("Failed to parse chord {:?}", accel);
+            return;
+        };
+        self.chords.borrow_mut().push(Chord {
+            first,
+            first_display,
+            second,
+            action: std::rc::Rc::new(action),
+        });
+    }
+
+    /// Whether a chord is waiting for its second key.
+    pub fn is_pending(&self) -> bool {
+        !self.pending.borrow().is_empty()
+    }
+
+    /// Cancel any pending chord.
+    pub fn cancel(&self) {
+        if self.is_pending() {
+            self.pending.borrow_mut().clear();
+            self.generation.set(self.generation.get() + 1);
+            (self.on_pending)(None);
+        }
+    }
+
+    /// Feed a key press. Returns `Stop` when the key was consumed as part
+    /// of a chord.
+    pub fn handle_key(
+        self: &std::rc::Rc<Self>,
+        key: gtk4::gdk::Key,
+        modifiers: gtk4::gdk::ModifierType,
+    ) -> gtk4::glib::Propagation {
+        use gtk4::gdk::Key;
+        use gtk4::glib::Propagation;
+
+        // Holding a modifier down for the next step must not cancel the chord.
+        if matches!(
+            key,
+            Key::Control_L
+                | Key::Control_R
+                | Key::Shift_L
+                | Key::Shift_R
+                | Key::Alt_L
+                | Key::Alt_R
+                | Key::Super_L
+                | Key::Super_R
+                | Key::Meta_L
+                | Key::Meta_R
+                | Key::ISO_Level3_Shift
+        ) {
+            return Propagation::Proceed;
+        }
+
+        if self.is_pending() {
+            let pending = std::mem::take(&mut *self.pending.borrow_mut());
+            self.generation.set(self.generation.get() + 1);
+            (self.on_pending)(None);
+            let action = {
+                let chords = self.chords.borrow();
+                pending
+                    .iter()
+                    .map(|&i| &chords[i])
+                    .find(|c| matches_key(&c.second, key, modifiers))
+                    .map(|c| c.action.clone())
+            };
+            if let Some(action) = action {
+                action();
+            }
+            // An unmatched second key only cancels the chord.
+            return Propagation::Stop;
+        }
+
+        let (matched, display) = {
+            let chords = self.chords.borrow();
+            let matched: Vec<usize> = chords
+                .iter()
+                .enumerate()
+                .filter(|(_, c)| matches_key(&c.first, key, modifiers))
+                .map(|(i, _)| i)
+                .collect();
+            let display = matched.first().map(|&i| chords[i].first_display.clone());
+            (matched, display)
+        };
+        let Some(display) = display else {
+            return Propagation::Proceed;
+        };
+
+        *self.pending.borrow_mut() = matched;
+        let generation = self.generation.get() + 1;
+        self.generation.set(generation);
+        (self.on_pending)(Some(&display));
+
+        let weak = std::rc::Rc::downgrade(self);
+        gtk4::glib::timeout_add_local_once(CHORD_TIMEOUT, move || {
+            if let Some(this) = weak.upgrade() {
+                if this.generation.get() == generation {
+                    this.cancel();
+                }
+            }
+        });
+        Propagation::Stop
+    }
+}
@@ -1,4 +1,4 @@
-use impulse_editor::protocol::MonacoContentChange;
+use impulse_editor::protocol::{MonacoContentChange, PeekKind};
 
 #[derive(Debug)]
 pub enum LspRequest {
@@ -35,18 +35,49 @@ pub enum LspRequest {
         text: Option<String>,
         changes: Vec<lsp_types::TextDocumentContentChangeEvent>,
     },
+    /// Pre-save hook: the `code_actions` kinds plus `willSave` and
+    /// `willSaveWaitUntil`, whose edits are sent back on `reply` before the
+    /// file is written.
+    WillSave {
+        uri: String,
+        reason: lsp_types::TextDocumentSaveReason,
+        code_actions: Vec<String>,
+        reply: std::sync::mpsc::Sender<Vec<impulse_core::workspace_edit::TextEdit>>,
+    },
     DidSave {
         uri: String,
     },
     DidClose {
         uri: String,
     },
+    /// The user changed the file's language: close it with the servers for
+    /// `old_language_id` and open it with those for `language_id`.
+    ChangeLanguage {
+        uri: String,
+        old_language_id: String,
+        language_id: String,
+        version: i32,
+        text: String,
+    },
     Formatting {
         request_id: u64,
         uri: String,
         version: i32,
         tab_size: u32,
         insert_spaces: bool,
+        formatter: impulse_core::formatter::FormatterChoice,
+        /// Document contents, when `formatter` may run an external tool.
+        text: Option<String>,
+    },
+    OnTypeFormatting {
+        request_id: u64,
+        uri: String,
+        version: i32,
+        line: u32,
+        character: u32,
+        ch: String,
+        tab_size: u32,
+        insert_spaces: bool,
     },
     SignatureHelp {
         request_id: u64,
@@ -71,6 +102,13 @@ pub enum LspRequest {
         end_line: u32,
         end_column: u32,
         diagnostics: Vec<DiagnosticInfo>,
+        only: Option<String>,
+    },
+    /// Run a code action's server command (`workspace/executeCommand`).
+    ExecuteCommand {
+        uri: String,
+        command: String,
+        arguments: Vec<serde_json::Value>,
     },
     Rename {
         request_id: u64,
@@ -87,14 +125,33 @@ pub enum LspRequest {
         line: u32,
         character: u32,
     },
+    /// Definition or references lookup whose results are shown in the inline
+    /// peek widget. Unlike `Definition`, all returned locations are kept.
+    Peek {
+        request_id: u64,
+        uri: String,
+        version: i32,
+        line: u32,
+        character: u32,
+        kind: PeekKind,
+    },
+    /// Replace the set of servers that must not run (`disabled_lsp_servers`).
+    SetDisabledServers {
+        server_ids: Vec<String>,
+    },
     Shutdown,
 }
 
+/// Convert one Monaco content-change event into `didChange` content changes.
+/// Monaco's ranges all refer to the text before the event, so they are
+/// ordered last-to-first, which keeps them valid when applied in sequence.
 pub fn lsp_content_changes(
     changes: &[MonacoContentChange],
 ) -> Vec<lsp_types::TextDocumentContentChangeEvent> {
-    changes
-        .iter()
+    let mut ordered: Vec<_> = changes.iter().collect();
+    ordered.sort_by_key(|change| std::cmp::Reverse(change.range_offset));
+    ordered
+        .into_iter()
         .map(|change| lsp_types::TextDocumentContentChangeEvent {
             range: Some(lsp_types::Range {
                 start: lsp_types::Position {
@@ -112,57 +169,6 @@ pub fn lsp_content_changes(
         .collect()
 }
 
-pub fn apply_lsp_content_changes(
-    content: &mut String,
-    changes: &[lsp_types::TextDocumentContentChangeEvent],
-) {
-    if changes.is_empty() {
-        return;
-    }
-
-    for change in changes.iter().rev() {
-        let Some(range) = change.range else {
-            *content = change.text.clone();
-            continue;
-        };
-        let start = lsp_position_to_byte_offset(content, range.start);
-        let end = lsp_position_to_byte_offset(content, range.end);
-        if start <= end && end <= content.len() {
-            content.replace_range(start..end, &change.text);
-        }
-    }
-}
-
-fn lsp_position_to_byte_offset(content: &str, position: lsp_types::Position) -> usize {
-    let mut line = 0u32;
-    let mut line_start = 0usize;
-    for (byte_index, ch) in content.char_indices() {
-        if line == position.line {
-            break;
-        }
-        if ch == '\n' {
-            line = line.saturating_add(1);
-            line_start = byte_index + ch.len_utf8();
-        }
-    }
-
-    if line != position.line {
-        return content.len();
-    }
-
-    let mut utf16_units = 0u32;
-    for (relative, ch) in content[line_start..].char_indices() {
-        if ch == '\n' || utf16_units >= position.character {
-            return line_start + relative;
-        }
-        utf16_units = utf16_units.saturating_add(ch.len_utf16() as u32);
-        if utf16_units > position.character {
-            return line_start + relative;
-        }
-    }
-    content.len()
-}
-
 #[derive(Debug)]
 pub enum LspResponse {
     Diagnostics {
@@ -203,6 +209,16 @@ pub enum LspResponse {
         client_key: String,
         server_id: String,
     },
+    /// Edits a server asked to apply (`workspace/applyEdit`).
+    ApplyEdit {
+        label: Option<String>,
+        files: Vec<impulse_core::workspace_edit::FileEdits>,
+    },
+    /// Status bar text for the servers' work-done progress; `None` when idle.
+    Progress { text: Option<String> },
+    /// The host copy of a document no longer matched the editor, so the
+    /// editor should resend its full text.
+    Resync { uri: String },
     FormattingResult {
         request_id: u64,
         uri: String,
@@ -227,11 +243,14 @@ pub enum LspResponse {
         version: i32,
         actions: Vec<CodeActionInfo>,
     },
+    /// Rename edits grouped per file, confirmed by the user in the rename
+    /// preview dialog before being applied.
     RenameResult {
         request_id: u64,
         uri: String,
         version: i32,
-        edits: Vec<WorkspaceTextEditInfo>,
+        new_name: String,
+        files: Vec<impulse_core::workspace_edit::FileEdits>,
     },
     PrepareRenameResult {
         request_id: u64,
@@ -240,6 +259,13 @@ pub enum LspResponse {
         range: Option<RangeInfo>,
         placeholder: Option<String>,
     },
+    PeekResult {
+        request_id: u64,
+        uri: String,
+        version: i32,
+        kind: PeekKind,
+        locations: Vec<LocationInfo>,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -315,6 +341,8 @@ pub struct CodeActionInfo {
     pub kind: Option<String>,
     pub edits: Vec<WorkspaceTextEditInfo>,
     pub is_preferred: bool,
+    /// Server command to run after the edits.
+    pub command: Option<lsp_types::Command>,
 }
 
 #[derive(Debug, Clone)]
@@ -1,24 +1,35 @@
+mod assistant_panel;
+mod cli_open;
+mod commit_composer;
+mod config_sync;
 mod context_bar;
 mod editor;
 mod editor_webview;
 mod file_icons;
+mod file_manager;
+mod hot_exit;
 mod keybindings;
 mod lsp_completion;
 mod lsp_hover;
+mod notifications;
 mod project_search;
+mod remote_files;
 mod review_tab;
 mod session_state;
 mod settings;
 mod settings_page;
+mod settings_watch;
 mod sidebar;
 mod status_bar;
 mod terminal;
 mod terminal_container;
 mod theme;
+mod updater;
 mod vertical_tabs;
 mod window;
 
 use gtk4::gio;
+use impulse_core::{crash_report, startup_profile};
 use libadwaita as adw;
 use libadwaita::prelude::*;
 use std::backtrace::Backtrace;
@@ -35,7 +46,12 @@ const APP_ID_DEVEL: &str = "dev.impulse.Impulse.Devel";
 enum StartupMode {
     RunGui,
     InstallLspServers,
+    UpdateLspServers,
     CheckLspServers,
+    /// Connect stdio to the MCP socket of the current directory's workspace.
+    McpBridge,
+    /// Send one request to the running instance's control socket.
+    Control,
 }
 
 fn is_devel_mode() -> bool {
@@ -46,17 +62,29 @@ fn parse_startup_mode() -> StartupMode {
     let args: Vec<String> = std::env::args().skip(1).collect();
     if args.iter().any(|a| a == "--install-lsp-servers") {
         StartupMode::InstallLspServers
+    } else if args.iter().any(|a| a == "--update-lsp-servers") {
+        StartupMode::UpdateLspServers
     } else if args.iter().any(|a| a == "--check-lsp-servers") {
         StartupMode::CheckLspServers
+    } else if args.iter().any(|a| a == "--mcp-bridge") {
+        StartupMode::McpBridge
+    } else if args.iter().any(|a| a == "--control") {
+        StartupMode::Control
     } else {
         StartupMode::RunGui
     }
 }
 
-fn run_lsp_install() -> i32 {
-    match impulse_core::lsp::install_managed_web_lsp_servers() {
-        Ok(bin_dir) => {
-            println!("Installed managed web LSP servers to {}", bin_dir.display());
+fn run_lsp_install(action: impulse_core::lsp_install::InstallAction) -> i32 {
+    let disabled = impulse_core::settings::load().disabled_lsp_servers;
+    let result = impulse_core::lsp_install::run(action, &[], &disabled, |event| {
+        if let impulse_core::lsp_install::InstallEvent::Output { line } = event {
+            println!("{}", line);
+        }
+    });
+    match result {
+        Ok(message) => {
+            println!("{}", message);
             let statuses = impulse_core::lsp::managed_web_lsp_status();
             let installed = statuses
                 .iter()
@@ -84,6 +112,15 @@ fn run_lsp_check() -> i32 {
             None => println!("MISS {:32} not found", status.command),
         }
     }
+    let disabled = impulse_core::settings::load().disabled_lsp_servers;
+    for package in impulse_core::lsp_install::check_for_updates(&disabled) {
+        println!(
+            "OLD  {:32} {} installed, {} pinned",
+            package.name,
+            package.installed_version.as_deref().unwrap_or("?"),
+            package.pinned_version
+        );
+    }
     let missing = statuses
         .iter()
         .filter(|s| s.resolved_path.is_none())
@@ -100,6 +137,64 @@ fn run_lsp_check() -> i32 {
     }
 }
 
+fn run_mcp_bridge() -> i32 {
+    let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+    match impulse_core::mcp::run_stdio_bridge(&cwd) {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    }
+}
+
+/// `impulse --control <method> [params-json]`: print the result as JSON.
+/// A relative `path` in the params resolves against the current directory.
+fn run_control() -> i32 {
+    let args: Vec<String> = std::env::args()
+        .skip_while(|a| a != "--control")
+        .skip(1)
+        .collect();
+    let Some(method) = args.first() else {
+        eprintln!(
+            "Usage: impulse --control <method> [params]\nMethods: {}",
+            impulse_core::control::METHODS.join(", ")
+        );
+        return 2;
+    };
+    let mut params = match args.get(1) {
+        Some(json) => match serde_json::from_str::<serde_json::Value>(json) {
+            Ok(params) => params,
+            Err(e) => {
+                eprintln!("Invalid params: {}", e);
+                return 2;
+            }
+        },
+        None => serde_json::json!({}),
+    };
+    if let Some(path) = params.get("path").and_then(|p| p.as_str()) {
+        if !std::path::Path::new(path).is_absolute() {
+            let cwd = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("/"));
+            params["path"] = cwd.join(path).to_string_lossy().to_string().into();
+        }
+    }
+    match impulse_core::control::call(method, params) {
+        Ok(result) => {
+            if !result.is_null() {
+                println!(
+                    "{}",
+                    serde_json::to_string_pretty(&result).unwrap_or_default()
+                );
+            }
+            0
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    }
+}
+
 fn state_dir() -> Option<PathBuf> {
     if let Ok(xdg_state_home) = std::env::var("XDG_STATE_HOME") {
         if !xdg_state_home.is_empty() {
@@ -145,6 +240,10 @@ fn install_panic_hook() {
             {
                 let _ = f.write_all(msg.as_bytes());
             }
+
+            let report =
+                crash_report::CrashReport::new(&panic_info.to_string(), &backtrace.to_string());
+            let _ = crash_report::write_report(&crash_report::reports_dir(&dir), &report);
         }
 
         eprintln!("{}", msg);
@@ -176,16 +275,38 @@ fn install_app_icon() {
 }
 
 fn main() {
-    env_logger::init();
+    let args: Vec<String> = std::env::args().collect();
+    if startup_profile::requested(&args) {
+        startup_profile::enable();
+    }
+
+    // Keep the recent log in memory so crash reports can include it.
+    let logger = env_logger::Builder::from_default_env().build();
+    let max_level = logger.filter();
+    crash_report::RecentLogger::install(Some(Box::new(logger)), max_level);
     install_panic_hook();
+    startup_profile::mark("logging");
 
     match parse_startup_mode() {
         StartupMode::InstallLspServers => {
-            std::process::exit(run_lsp_install());
+            std::process::exit(run_lsp_install(
+                impulse_core::lsp_install::InstallAction::Install,
+            ));
+        }
+        StartupMode::UpdateLspServers => {
+            std::process::exit(run_lsp_install(
+                impulse_core::lsp_install::InstallAction::Update,
+            ));
         }
         StartupMode::CheckLspServers => {
             std::process::exit(run_lsp_check());
         }
+        StartupMode::McpBridge => {
+            std::process::exit(run_mcp_bridge());
+        }
+        StartupMode::Control => {
+            std::process::exit(run_control());
+        }
         StartupMode::RunGui => {}
     }
 
@@ -197,7 +318,7 @@ fn main() {
     let app_id = if devel { APP_ID_DEVEL } else { APP_ID };
     let app = adw::Application::builder()
         .application_id(app_id)
-        .flags(gio::ApplicationFlags::HANDLES_OPEN)
+        .flags(gio::ApplicationFlags::HANDLES_OPEN | gio::ApplicationFlags::HANDLES_COMMAND_LINE)
         .build();
 
     // Shared storage for file paths received via GIO open (from file managers
@@ -205,13 +326,19 @@ fn main() {
     let pending_files: Rc<RefCell<Vec<String>>> = Rc::new(RefCell::new(Vec::new()));
 
     app.connect_startup(move |_app| {
-        let initial_theme = theme::get_theme(&settings::load().color_scheme);
-        let style_manager = adw::StyleManager::default();
-        if initial_theme.base == "vs" {
-            style_manager.set_color_scheme(adw::ColorScheme::ForceLight);
-        } else {
-            style_manager.set_color_scheme(adw::ColorScheme::ForceDark);
+        theme::apply_color_scheme(&settings::load());
+        theme::reload_custom_css();
+        hot_exit::start(settings::load().hot_exit);
+        if let Some(dir) = session_state::state_dir() {
+            impulse_core::local_history::set_state_dir(&dir);
         }
+        impulse_core::trust::set_enabled(settings::load().workspace_trust_enabled);
+        impulse_core::languages::set_overrides(&settings::load().language_overrides);
+        impulse_core::environment::set_direnv_enabled(settings::load().use_direnv);
+        impulse_core::environment::warm();
+        settings_watch::start();
+        window::control::set_enabled(settings::load().control_socket_enabled);
+        config_sync::sync_at_startup(&settings::load().settings_sync_repository);
 
         // Install application icon into user icon theme and set as default
         install_app_icon();
@@ -220,6 +347,7 @@ fn main() {
         if devel {
             log::info!("Running in development mode (app-id: {})", APP_ID_DEVEL);
         }
+        startup_profile::mark("gtk startup");
     });
 
     {
@@ -236,6 +364,12 @@ fn main() {
         });
     }
 
+    app.connect_shutdown(|_app| hot_exit::finish());
+
+    // `impulse <path[:line]>` from a shell, including launches forwarded
+    // from a second process while this one is running.
+    app.connect_command_line(cli_open::handle_command_line);
+
     {
         let pending_files = pending_files.clone();
         app.connect_activate(move |app| {
@@ -257,9 +391,10 @@ fn main() {
         .filter(|a| {
             !matches!(
                 a.as_str(),
-                "--dev" | "--install-lsp-servers" | "--check-lsp-servers"
+                "--dev" | "--install-lsp-servers" | "--check-lsp-servers" | startup_profile::FLAG
             )
         })
         .collect();
     app.run_with_args(&gtk_args);
+    updater::relaunch_if_requested();
 }
@@ -0,0 +1,74 @@
+//! Notifications. Each one is recorded in the core history
+//! (`impulse_core::notifications`), shown as a toast, and counted on the
+//! bell in every window's status bar, whose panel lists the history.
+
+use std::cell::RefCell;
+use std::rc::Weak;
+
+use impulse_core::notifications::{Notification, Severity};
+use libadwaita as adw;
+
+use crate::status_bar::{SharedStatusBar, StatusBar};
+
+thread_local! {
+    static STATUS_BARS: RefCell<Vec<Weak<RefCell<StatusBar>>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Keep `status_bar`'s bell in sync with the history.
+pub fn register_status_bar(status_bar: &SharedStatusBar) {
+    STATUS_BARS.with(|bars| {
+        let mut bars = bars.borrow_mut();
+        bars.retain(|bar| bar.strong_count() > 0);
+        bars.push(std::rc::Rc::downgrade(status_bar));
+    });
+    status_bar.borrow().refresh_notifications();
+}
+
+/// Update the unread count on every window's bell.
+pub fn refresh_status_bars() {
+    let bars: Vec<SharedStatusBar> =
+        STATUS_BARS.with(|bars| bars.borrow().iter().filter_map(Weak::upgrade).collect());
+    for bar in bars {
+        if let Ok(bar) = bar.try_borrow() {
+            bar.refresh_notifications();
+        }
+    }
+}
+
+/// Record `notification` and show it as a toast on `toast_overlay`.
+pub fn notify(toast_overlay: &adw::ToastOverlay, notification: Notification) {
+    let text = if notification.message.is_empty() {
+        notification.title.clone()
+    } else {
+        format!("{}: {}", notification.title, notification.message)
+    };
+    let toast = adw::Toast::new(&text);
+    toast.set_timeout(match notification.severity {
+        Severity::Info => 3,
+        Severity::Warning => 5,
+        Severity::Error => 7,
+    });
+    impulse_core::notifications::push(notification);
+    toast_overlay.add_toast(toast);
+    refresh_status_bars();
+}
+
+/// Record `notification` without a toast, e.g. when no window is at hand.
+pub fn record(notification: Notification) {
+    impulse_core::notifications::push(notification);
+    refresh_status_bars();
+}
+
+/// Report a failed save of `path`.
+pub fn save_failed(toast_overlay: &adw::ToastOverlay, path: &str, error: &str) {
+    let name = std::path::Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string());
+    notify(
+        toast_overlay,
+        Notification::new(Severity::Error, format!("Couldn't save {}", name))
+            .message(error)
+            .source("save"),
+    );
+}
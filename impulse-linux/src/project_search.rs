@@ -6,11 +6,21 @@ use std::rc::Rc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
-use impulse_core::search::SearchResult;
+use impulse_core::search::{OpenFile, SearchResult, SearchScope};
 
 use crate::window::run_guarded_ui;
 
 type ResultActivatedCallback = Rc<RefCell<Option<Box<dyn Fn(&str, u32)>>>>;
+type OpenFilesProvider = Rc<RefCell<Option<Box<dyn Fn() -> Vec<OpenFile>>>>>;
+type DirectoryProvider = Rc<RefCell<Option<Box<dyn Fn() -> Option<String>>>>>;
+
+/// Scope dropdown entries, in [`scope_for`] order.
+const SCOPE_LABELS: [&str; 4] = [
+    "Entire Workspace",
+    "Open Files",
+    "Current Directory",
+    "Custom Glob",
+];
 
 /// State for the project-wide search panel, used to wire callbacks from window.rs.
 #[allow(dead_code)]
@@ -21,6 +31,10 @@ pub struct ProjectSearchState {
     pub result_count_label: gtk4::Label,
     pub case_sensitive: Rc<RefCell<bool>>,
     pub on_result_activated: ResultActivatedCallback,
+    /// Open files with their buffer contents, for the open files scope.
+    pub open_files: OpenFilesProvider,
+    /// The selected tab's directory, for the current directory scope.
+    pub current_directory: DirectoryProvider,
     pub current_results: Rc<RefCell<Vec<SearchResult>>>,
     pub current_root: Rc<RefCell<String>>,
 }
@@ -45,6 +59,18 @@ pub fn build_project_search_panel() -> ProjectSearchState {
     search_row.append(&search_entry);
     search_row.append(&case_btn);
 
+    // Scope row: where to search, plus the globs for the custom scope
+    let scope_row = gtk4::Box::new(gtk4::Orientation::Horizontal, 4);
+    scope_row.add_css_class("project-search-row");
+    let scope_dropdown = gtk4::DropDown::from_strings(&SCOPE_LABELS);
+    scope_dropdown.set_tooltip_text(Some("Search Scope"));
+    let glob_entry = gtk4::Entry::new();
+    glob_entry.set_placeholder_text(Some("e.g. src/**/*.rs, !*_test.rs"));
+    glob_entry.set_hexpand(true);
+    glob_entry.set_visible(false);
+    scope_row.append(&scope_dropdown);
+    scope_row.append(&glob_entry);
+
     // Result count label
     let result_count_label = gtk4::Label::new(None);
     result_count_label.add_css_class("project-search-count");
@@ -62,11 +88,14 @@ pub fn build_project_search_panel() -> ProjectSearchState {
     scroll.set_child(Some(&result_list));
 
     panel.append(&search_row);
+    panel.append(&scope_row);
     panel.append(&result_count_label);
     panel.append(&scroll);
 
     let case_sensitive: Rc<RefCell<bool>> = Rc::new(RefCell::new(false));
     let on_result_activated: ResultActivatedCallback = Rc::new(RefCell::new(None));
+    let open_files: OpenFilesProvider = Rc::new(RefCell::new(None));
+    let current_directory: DirectoryProvider = Rc::new(RefCell::new(None));
     let current_results: Rc<RefCell<Vec<SearchResult>>> = Rc::new(RefCell::new(Vec::new()));
     let current_root: Rc<RefCell<String>> = Rc::new(RefCell::new(String::new()));
 
@@ -90,6 +119,10 @@ pub fn build_project_search_panel() -> ProjectSearchState {
         let current_root = current_root.clone();
         let active_cancel = active_cancel.clone();
         let search_generation = search_generation.clone();
+        let scope_dropdown = scope_dropdown.clone();
+        let glob_entry = glob_entry.clone();
+        let open_files = open_files.clone();
+        let current_directory = current_directory.clone();
         search_entry.connect_search_changed(move |entry| {
             run_guarded_ui("project-search-changed", || {
                 // Cancel any pending search
@@ -122,6 +155,10 @@ pub fn build_project_search_panel() -> ProjectSearchState {
                 let pending_clear = pending_search.clone();
                 let active_cancel = active_cancel.clone();
                 let search_generation = search_generation.clone();
+                let scope_index = scope_dropdown.selected();
+                let glob = glob_entry.text().to_string();
+                let open_files = open_files.clone();
+                let current_directory = current_directory.clone();
                 let cancel = Arc::new(AtomicBool::new(false));
                 *active_cancel.borrow_mut() = Some(cancel.clone());
 
@@ -133,14 +170,18 @@ pub fn build_project_search_panel() -> ProjectSearchState {
                         let result_list = result_list.clone();
                         let result_count_label = result_count_label.clone();
                         let current_results = current_results.clone();
+                        // Buffers are read here, on the main thread, so the
+                        // search sees what the editors show right now.
+                        let scope = scope_for(scope_index, &glob, &open_files, &current_directory);
                         glib::spawn_future_local(async move {
                             let q = query.clone();
                             let r = root.clone();
                             let cs = case_sensitive;
                             let cancel_for_search = cancel.clone();
                             let results = gio::spawn_blocking(move || {
-                                impulse_core::search::search_contents(
+                                impulse_core::search::search_contents_in_scope(
                                     &r,
+                                    &scope,
                                     &q,
                                     500,
                                     cs,
@@ -180,7 +221,12 @@ pub fn build_project_search_panel() -> ProjectSearchState {
                                         ));
                                     }
                                 }
-                                _ => {
+                                Ok(Err(e)) => {
+                                    clear_list(&result_list);
+                                    result_count_label.set_text(&e);
+                                    current_results.borrow_mut().clear();
+                                }
+                                Err(_) => {
                                     clear_list(&result_list);
                                     result_count_label.set_text("Search error");
                                     current_results.borrow_mut().clear();
@@ -203,6 +249,22 @@ pub fn build_project_search_panel() -> ProjectSearchState {
         });
     }
 
+    // And when the scope or its globs change
+    {
+        let search_entry = search_entry.clone();
+        let glob_entry = glob_entry.clone();
+        scope_dropdown.connect_selected_notify(move |dropdown| {
+            glob_entry.set_visible(dropdown.selected() == 3);
+            search_entry.emit_by_name::<()>("search-changed", &[]);
+        });
+    }
+    {
+        let search_entry = search_entry.clone();
+        glob_entry.connect_changed(move |_| {
+            search_entry.emit_by_name::<()>("search-changed", &[]);
+        });
+    }
+
     // Wire result list activation
     {
         let on_result_activated = on_result_activated.clone();
@@ -230,11 +292,42 @@ pub fn build_project_search_panel() -> ProjectSearchState {
         result_count_label,
         case_sensitive,
         on_result_activated,
+        open_files,
+        current_directory,
         current_results,
         current_root,
     }
 }
 
+/// The scope for the dropdown entry at `index`. The current directory scope
+/// falls back to the whole workspace when the selected tab has none.
+fn scope_for(
+    index: u32,
+    glob: &str,
+    open_files: &OpenFilesProvider,
+    current_directory: &DirectoryProvider,
+) -> SearchScope {
+    match index {
+        1 => SearchScope::OpenFiles {
+            files: open_files
+                .borrow()
+                .as_ref()
+                .map(|f| f())
+                .unwrap_or_default(),
+        },
+        2 => current_directory
+            .borrow()
+            .as_ref()
+            .and_then(|f| f())
+            .map(|path| SearchScope::Directory { path })
+            .unwrap_or_default(),
+        3 => SearchScope::Glob {
+            pattern: glob.to_string(),
+        },
+        _ => SearchScope::Workspace,
+    }
+}
+
 /// Populate the result list grouped by file.
 fn populate_project_results(list: &gtk4::ListBox, results: &[SearchResult]) {
     clear_list(list);
@@ -278,38 +371,118 @@ fn populate_project_results(list: &gtk4::ListBox, results: &[SearchResult]) {
 
         list.append(&header);
 
-        // Match rows
-        for m in matches {
-            let match_box = gtk4::Box::new(gtk4::Orientation::Horizontal, 4);
-            match_box.add_css_class("project-search-match");
-            match_box.set_widget_name(file_path);
-            match_box.set_cursor_from_name(Some("pointer"));
-
-            // Store line number in tooltip for retrieval on activation
-            if let Some(line_num) = m.line_number {
-                match_box.set_tooltip_text(Some(&format!("{}", line_num)));
+        // Match rows, with context lines around them. Matches on the same
+        // line share a row, and context shared by nearby matches is shown
+        // once.
+        let mut shown_through = 0u32;
+        let mut i = 0;
+        while i < matches.len() {
+            let m = matches[i];
+            let Some(line_num) = m.line_number else {
+                i += 1;
+                continue;
+            };
+            let mut same_line = 1;
+            while matches
+                .get(i + same_line)
+                .is_some_and(|n| n.line_number == Some(line_num))
+            {
+                same_line += 1;
             }
-
-            if let Some(line_num) = m.line_number {
-                let line_label = gtk4::Label::new(Some(&format!("{}", line_num)));
-                line_label.add_css_class("project-search-line-num");
-                line_label.set_width_chars(5);
-                line_label.set_xalign(1.0);
-                match_box.append(&line_label);
+            let line_matches = &matches[i..i + same_line];
+            i += same_line;
+
+            let first_before = line_num - m.context_before.len() as u32;
+            for (offset, context) in m.context_before.iter().enumerate() {
+                let context_line = first_before + offset as u32;
+                if context_line > shown_through {
+                    append_line_row(list, file_path, context_line, context, &[], true);
+                }
             }
 
-            if let Some(ref content) = m.line_content {
-                let content_label = gtk4::Label::new(Some(content.trim()));
-                content_label.add_css_class("project-search-line-content");
-                content_label.set_halign(gtk4::Align::Start);
-                content_label.set_hexpand(true);
-                content_label.set_ellipsize(gtk4::pango::EllipsizeMode::End);
-                match_box.append(&content_label);
+            let content = m.line_content.as_deref().unwrap_or("");
+            let ranges: Vec<(usize, usize)> = line_matches
+                .iter()
+                .filter_map(|m| Some((m.match_start? as usize, m.match_end? as usize)))
+                .collect();
+            append_line_row(list, file_path, line_num, content, &ranges, false);
+            shown_through = line_num;
+
+            let next_line = matches
+                .get(i)
+                .and_then(|n| n.line_number)
+                .unwrap_or(u32::MAX);
+            for (offset, context) in m.context_after.iter().enumerate() {
+                let context_line = line_num + 1 + offset as u32;
+                if context_line >= next_line {
+                    break;
+                }
+                append_line_row(list, file_path, context_line, context, &[], true);
+                shown_through = context_line;
             }
+        }
+    }
+}
+
+/// Append a result row for line `line_num` of `file_path`. `ranges` are the
+/// byte ranges of `content` to highlight; context rows are dimmed.
+fn append_line_row(
+    list: &gtk4::ListBox,
+    file_path: &str,
+    line_num: u32,
+    content: &str,
+    ranges: &[(usize, usize)],
+    is_context: bool,
+) {
+    let match_box = gtk4::Box::new(gtk4::Orientation::Horizontal, 4);
+    match_box.add_css_class("project-search-match");
+    if is_context {
+        match_box.add_css_class("project-search-context");
+    }
+    match_box.set_widget_name(file_path);
+    match_box.set_cursor_from_name(Some("pointer"));
+
+    // Store line number in tooltip for retrieval on activation
+    match_box.set_tooltip_text(Some(&format!("{}", line_num)));
+
+    let line_label = gtk4::Label::new(Some(&format!("{}", line_num)));
+    line_label.add_css_class("project-search-line-num");
+    line_label.set_width_chars(5);
+    line_label.set_xalign(1.0);
+    match_box.append(&line_label);
+
+    let content_label = gtk4::Label::new(None);
+    content_label.set_markup(&highlight_markup(content, ranges));
+    content_label.add_css_class("project-search-line-content");
+    content_label.set_halign(gtk4::Align::Start);
+    content_label.set_hexpand(true);
+    content_label.set_ellipsize(gtk4::pango::EllipsizeMode::End);
+    match_box.append(&content_label);
+
+    list.append(&match_box);
+}
 
-            list.append(&match_box);
+/// Pango markup for `content` with surrounding whitespace trimmed and the
+/// byte `ranges` (relative to the untrimmed line) in bold.
+fn highlight_markup(content: &str, ranges: &[(usize, usize)]) -> String {
+    let lead = content.len() - content.trim_start().len();
+    let shown = content.trim();
+    let mut markup = String::new();
+    let mut pos = 0;
+    for &(start, end) in ranges {
+        let start = start.saturating_sub(lead).clamp(pos, shown.len());
+        let end = end.saturating_sub(lead).clamp(start, shown.len());
+        if start == end || !shown.is_char_boundary(start) || !shown.is_char_boundary(end) {
+            continue;
         }
+        markup.push_str(&gtk4::glib::markup_escape_text(&shown[pos..start]));
+        markup.push_str("<b>");
+        markup.push_str(&gtk4::glib::markup_escape_text(&shown[start..end]));
+        markup.push_str("</b>");
+        pos = end;
     }
+    markup.push_str(&gtk4::glib::markup_escape_text(&shown[pos..]));
+    markup
 }
 
 fn clear_list(list: &gtk4::ListBox) {
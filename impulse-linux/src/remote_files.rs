@@ -0,0 +1,117 @@
+//! Remote files opened by URI (see `impulse_core::remote_files`): "Open
+//! Remote File…" downloads into the cache and opens the copy, and saving
+//! the copy uploads it, asking before overwriting a file that changed on
+//! the server.
+
+use gtk4::gio;
+use gtk4::prelude::*;
+use impulse_core::notifications::{Notification, Severity};
+use impulse_core::remote_files::UploadOutcome;
+use libadwaita as adw;
+use libadwaita::prelude::*;
+
+/// Download `uri` off the main thread and pass the local copy's path to
+/// `on_opened`.
+pub fn open(
+    toast_overlay: &adw::ToastOverlay,
+    uri: String,
+    on_opened: impl FnOnce(String) + 'static,
+) {
+    let toast_overlay = toast_overlay.clone();
+    gtk4::glib::spawn_future_local(async move {
+        let thread_uri = uri.clone();
+        let result = gio::spawn_blocking(move || impulse_core::remote_files::open(&thread_uri))
+            .await
+            .unwrap_or_else(|_| Err("Download failed unexpectedly".to_string()));
+        match result {
+            Ok(path) => on_opened(path.to_string_lossy().to_string()),
+            Err(e) => {
+                log::warn!("Failed to open {}: {}", uri, e);
+                crate::notifications::notify(
+                    &toast_overlay,
+                    Notification::new(Severity::Error, "Couldn't open remote file")
+                        .message(e)
+                        .source("remote"),
+                );
+            }
+        }
+    });
+}
+
+/// Upload `path` if it is the cached copy of a remote file. Called after
+/// every save.
+pub fn upload_after_save(path: &str) {
+    if impulse_core::remote_files::remote_uri_for(path).is_some() {
+        upload(path.to_string(), false);
+    }
+}
+
+fn upload(path: String, force: bool) {
+    gtk4::glib::spawn_future_local(async move {
+        let thread_path = path.clone();
+        let result =
+            gio::spawn_blocking(move || impulse_core::remote_files::upload(&thread_path, force))
+                .await
+                .unwrap_or_else(|_| Err("Upload failed unexpectedly".to_string()));
+        let uri = impulse_core::remote_files::remote_uri_for(&path).unwrap_or_default();
+        match result {
+            Ok(UploadOutcome::Uploaded) => {}
+            Ok(UploadOutcome::ChangedOnServer) => ask_to_overwrite(path, uri),
+            Err(e) => {
+                log::warn!("Failed to upload {}: {}", uri, e);
+                report(
+                    Notification::new(Severity::Error, "Couldn't upload remote file")
+                        .message(e)
+                        .source("remote"),
+                );
+            }
+        }
+    });
+}
+
+fn ask_to_overwrite(path: String, uri: String) {
+    let Some(window) = active_window() else {
+        report(
+            Notification::new(Severity::Warning, "Remote file not uploaded")
+                .message(format!("{uri} changed on the server since it was opened."))
+                .source("remote"),
+        );
+        return;
+    };
+    let dialog = adw::AlertDialog::builder()
+        .heading("File Changed on the Server")
+        .body(format!(
+            "{uri} changed on the server since it was opened. Overwrite it with your \
+             version? The save is kept locally either way."
+        ))
+        .build();
+    dialog.add_response("cancel", "Don't Upload");
+    dialog.add_response("overwrite", "Overwrite");
+    dialog.set_response_appearance("overwrite", adw::ResponseAppearance::Destructive);
+    dialog.set_default_response(Some("cancel"));
+    dialog.set_close_response("cancel");
+    dialog.connect_response(None, move |_, response| {
+        if response == "overwrite" {
+            upload(path.clone(), true);
+        }
+    });
+    dialog.present(Some(&window));
+}
+
+fn active_window() -> Option<gtk4::Window> {
+    gio::Application::default()
+        .and_downcast::<gtk4::Application>()
+        .and_then(|app| app.active_window())
+}
+
+/// Toast on the active window, or just record when there is none.
+fn report(notification: Notification) {
+    match active_window()
+        .and_then(|window| window.downcast::<adw::ApplicationWindow>().ok())
+        .and_then(|window| window.content())
+        .and_downcast::<adw::ToastOverlay>()
+    {
+        Some(toast_overlay) => crate::notifications::notify(&toast_overlay, notification),
+        None => crate::notifications::record(notification),
+    }
+}
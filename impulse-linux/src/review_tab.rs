@@ -1,7 +1,11 @@
 //! "Review Changes" tab: a WebKitGTK WebView hosting impulse-editor's
 //! review.html stacked-diff page, with a native header (repo, branch, file
-//! count, +/- totals, refresh) above it and a commit bar (message entry +
-//! Commit button) below. Mirrors the macOS `DiffReviewTab`.
+//! count, +/- totals, refresh) above it and a commit bar (message composer
+//! + Commit button) below. Mirrors the macOS `DiffReviewTab`.
+//!
+//! The same tab also serves read-only comparisons between two git revisions,
+//! two arbitrary files, or a file and its unsaved editor buffer; those hide
+//! the commit bar and discard buttons.
 
 use gtk4::prelude::*;
 use libadwaita as adw;
@@ -13,6 +17,7 @@ use std::rc::Rc;
 
 use impulse_editor::protocol::{ReviewCommand, ReviewEvent, ReviewFileEntry};
 
+use crate::commit_composer::CommitComposer;
 use crate::theme::ThemeColors;
 
 /// Widget name identifying review tabs in the tab view.
@@ -22,9 +27,23 @@ thread_local! {
     static HANDLES: RefCell<Vec<(gtk4::Box, Rc<ReviewTabHandle>)>> = const { RefCell::new(Vec::new()) };
 }
 
+/// What a review tab diffs.
+#[derive(Clone)]
+enum ReviewSource {
+    /// Uncommitted working-tree changes (the "Review Changes" tab).
+    WorkingTree,
+    /// Committed differences between two revisions.
+    Refs { base: String, target: String },
+    /// Two files on disk, possibly outside any repository.
+    Files { old: String, new: String },
+    /// The saved file at `path` against its open editor's buffer.
+    Saved { path: String },
+}
+
 pub struct ReviewTabHandle {
     webview: webkit6::WebView,
     repo_root: RefCell<String>,
+    source: ReviewSource,
     is_ready: Cell<bool>,
     /// Bumped per reload so a stale async result is dropped.
     load_generation: Cell<u64>,
@@ -35,7 +54,7 @@ pub struct ReviewTabHandle {
     count_label: gtk4::Label,
     added_label: gtk4::Label,
     removed_label: gtk4::Label,
-    commit_entry: gtk4::Entry,
+    composer: Rc<CommitComposer>,
     commit_btn: gtk4::Button,
     confirmation_label: gtk4::Label,
 }
@@ -72,8 +91,78 @@ pub fn refresh(widget: &gtk4::Widget) {
     }
 }
 
+/// Apply settings that affect an open review tab.
+pub fn apply_settings(widget: &gtk4::Widget, settings: &crate::settings::Settings) {
+    if let Some(handle) = handle_for_widget(widget) {
+        handle
+            .composer
+            .set_conventional(settings.conventional_commits);
+        handle
+            .composer
+            .set_assistant_enabled(settings.assistant_enabled);
+    }
+}
+
+/// Check if a review tab shows a ref or file comparison rather than the
+/// working-tree changes.
+pub fn is_compare_tab(widget: &gtk4::Widget) -> bool {
+    handle_for_widget(widget).is_some_and(|h| !matches!(h.source, ReviewSource::WorkingTree))
+}
+
 /// Build a review tab for the repository containing `repo_root`.
 pub fn create_review_tab(repo_root: &str, theme: &'static ThemeColors) -> gtk4::Box {
+    build_tab(repo_root, ReviewSource::WorkingTree, theme)
+}
+
+/// Build a read-only tab comparing revision `base` to `target` (branches,
+/// tags, or commits) in the repository at `repo_root`.
+pub fn create_ref_compare_tab(
+    repo_root: &str,
+    base: &str,
+    target: &str,
+    theme: &'static ThemeColors,
+) -> gtk4::Box {
+    let source = ReviewSource::Refs {
+        base: base.to_string(),
+        target: target.to_string(),
+    };
+    build_tab(repo_root, source, theme)
+}
+
+/// Build a read-only tab comparing the file at `old` to the file at `new`.
+pub fn create_file_compare_tab(old: &str, new: &str, theme: &'static ThemeColors) -> gtk4::Box {
+    let dir = std::path::Path::new(new)
+        .parent()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let source = ReviewSource::Files {
+        old: old.to_string(),
+        new: new.to_string(),
+    };
+    build_tab(&dir, source, theme)
+}
+
+/// Build a read-only tab comparing the saved file at `path` to the unsaved
+/// content of its open editor. Refreshing picks up new edits.
+pub fn create_saved_compare_tab(path: &str, theme: &'static ThemeColors) -> gtk4::Box {
+    let dir = std::path::Path::new(path)
+        .parent()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let source = ReviewSource::Saved {
+        path: path.to_string(),
+    };
+    build_tab(&dir, source, theme)
+}
+
+/// The buffer a `Saved` comparison diffs against, read on the main thread.
+fn editor_buffer(path: &str) -> Result<String, String> {
+    crate::editor::get_handle(path)
+        .map(|handle| handle.get_content())
+        .ok_or_else(|| format!("{} is no longer open", path))
+}
+
+fn build_tab(repo_root: &str, source: ReviewSource, theme: &'static ThemeColors) -> gtk4::Box {
     let container = gtk4::Box::new(gtk4::Orientation::Vertical, 0);
     container.set_widget_name(REVIEW_TAB_NAME);
     container.set_hexpand(true);
@@ -137,30 +226,42 @@ pub fn create_review_tab(repo_root: &str, theme: &'static ThemeColors) -> gtk4::
     }
     container.append(&webview);
 
-    // --- Commit bar: message entry + Commit button ---
+    // --- Commit bar: message composer + Commit button ---
     container.append(&gtk4::Separator::new(gtk4::Orientation::Horizontal));
     let commit_bar = gtk4::Box::new(gtk4::Orientation::Horizontal, 10);
     commit_bar.add_css_class("review-commit-bar");
 
-    let commit_entry = gtk4::Entry::new();
-    commit_entry.set_placeholder_text(Some("Commit message"));
-    commit_entry.set_hexpand(true);
-    commit_bar.append(&commit_entry);
-
-    let confirmation_label = gtk4::Label::new(None);
-    confirmation_label.add_css_class("review-confirmation");
-    confirmation_label.set_visible(false);
-    commit_bar.append(&confirmation_label);
+    let is_working_tree = matches!(source, ReviewSource::WorkingTree);
+    // Compare tabs never commit; skip reading the repo's template for them.
+    let composer = CommitComposer::new(if is_working_tree { repo_root } else { "" });
+    commit_bar.append(&composer.widget);
 
+    let commit_side = gtk4::Box::new(gtk4::Orientation::Vertical, 6);
+    commit_side.set_valign(gtk4::Align::Start);
     let commit_btn = gtk4::Button::with_label("Commit");
     commit_btn.add_css_class("suggested-action");
     commit_btn.set_sensitive(false);
-    commit_bar.append(&commit_btn);
+    commit_side.append(&commit_btn);
+
+    let confirmation_label = gtk4::Label::new(None);
+    confirmation_label.add_css_class("review-confirmation");
+    confirmation_label.set_visible(false);
+    confirmation_label.set_wrap(true);
+    confirmation_label.set_max_width_chars(28);
+    commit_side.append(&confirmation_label);
+    commit_bar.append(&commit_side);
     container.append(&commit_bar);
+    if !is_working_tree {
+        commit_bar.set_visible(false);
+        if let Some(separator) = commit_bar.prev_sibling() {
+            separator.set_visible(false);
+        }
+    }
 
     let handle = Rc::new(ReviewTabHandle {
         webview: webview.clone(),
         repo_root: RefCell::new(repo_root.to_string()),
+        source,
         is_ready: Cell::new(false),
         load_generation: Cell::new(0),
         known_paths: RefCell::new(std::collections::HashSet::new()),
@@ -169,7 +270,7 @@ pub fn create_review_tab(repo_root: &str, theme: &'static ThemeColors) -> gtk4::
         count_label,
         added_label,
         removed_label,
-        commit_entry: commit_entry.clone(),
+        composer: composer.clone(),
         commit_btn: commit_btn.clone(),
         confirmation_label,
     });
@@ -253,13 +354,15 @@ pub fn create_review_tab(repo_root: &str, theme: &'static ThemeColors) -> gtk4::
     }
     {
         let commit_btn = commit_btn.clone();
-        commit_entry.connect_changed(move |entry| {
-            commit_btn.set_sensitive(!entry.text().trim().is_empty());
-        });
+        composer.connect_changed(move |committable| commit_btn.set_sensitive(committable));
     }
     {
-        let handle = handle.clone();
-        commit_entry.connect_activate(move |_| handle.perform_commit());
+        let handle = Rc::downgrade(&handle);
+        composer.connect_submit(move || {
+            if let Some(handle) = handle.upgrade() {
+                handle.perform_commit();
+            }
+        });
     }
     {
         let handle = handle.clone();
@@ -326,10 +429,35 @@ impl ReviewTabHandle {
         self.repo_label.set_text(&name);
     }
 
+    fn set_totals(&self, files: usize, added: u32, removed: u32) {
+        self.count_label.set_text(&if files == 1 {
+            "1 file".to_string()
+        } else {
+            format!("{} files", files)
+        });
+        self.added_label.set_text(&format!("+{}", added));
+        self.removed_label.set_text(&format!("-{}", removed));
+    }
+
     /// Reload the changed-file list off the main thread and push a Render.
     fn reload_and_render(self: &Rc<Self>) {
         let generation = self.load_generation.get() + 1;
         self.load_generation.set(generation);
+        match self.source.clone() {
+            ReviewSource::WorkingTree => {}
+            ReviewSource::Refs { base, target } => {
+                self.reload_ref_comparison(generation, base, target);
+                return;
+            }
+            ReviewSource::Files { old, new } => {
+                self.reload_file_comparison(generation, old, new);
+                return;
+            }
+            ReviewSource::Saved { path } => {
+                self.reload_saved_comparison(generation, path);
+                return;
+            }
+        }
         let repo = self.repo_root.borrow().clone();
         let handle = self.clone();
         gtk4::glib::spawn_future_local(async move {
@@ -347,13 +475,139 @@ impl ReviewTabHandle {
                     // Repo disappeared / error — render an empty set.
                     handle.known_paths.borrow_mut().clear();
                     handle.branch_label.set_text("");
-                    handle.count_label.set_text("0 files");
-                    handle.added_label.set_text("+0");
-                    handle.removed_label.set_text("-0");
-                    handle.send_command(&ReviewCommand::Render { files: Vec::new() });
+                    handle.set_totals(0, 0, 0);
+                    handle.send_command(&ReviewCommand::Render {
+                        files: Vec::new(),
+                        read_only: false,
+                    });
+                }
+            }
+        });
+    }
+
+    fn reload_ref_comparison(self: &Rc<Self>, generation: u64, base: String, target: String) {
+        let repo = self.repo_root.borrow().clone();
+        self.branch_label.set_text(&format!("{base}..{target}"));
+        let handle = self.clone();
+        gtk4::glib::spawn_future_local(async move {
+            let result = gtk4::gio::spawn_blocking(move || {
+                impulse_core::git::diff_refs(&repo, &base, &target)
+            })
+            .await;
+            if handle.load_generation.get() != generation {
+                return;
+            }
+            let (files, added, removed) = match result {
+                Ok(Ok(comparison)) => (
+                    comparison.files,
+                    comparison.total_added,
+                    comparison.total_removed,
+                ),
+                Ok(Err(e)) => {
+                    log::warn!("Ref comparison failed: {}", e);
+                    (Vec::new(), 0, 0)
                 }
+                Err(_) => (Vec::new(), 0, 0),
+            };
+            handle.set_totals(files.len(), added, removed);
+            let entries = files
+                .into_iter()
+                .map(|f| ReviewFileEntry {
+                    path: f.path,
+                    status: f.status,
+                    old_path: f.old_path,
+                    added: f.added,
+                    removed: f.removed,
+                    is_binary: f.is_binary,
+                })
+                .collect();
+            handle.send_command(&ReviewCommand::Render {
+                files: entries,
+                read_only: true,
+            });
+        });
+    }
+
+    fn reload_file_comparison(self: &Rc<Self>, generation: u64, old: String, new: String) {
+        let file_name = |path: &str| {
+            std::path::Path::new(path)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| path.to_string())
+        };
+        self.repo_label
+            .set_text(&format!("{} ↔ {}", file_name(&old), file_name(&new)));
+        self.branch_label.set_text("");
+        let handle = self.clone();
+        gtk4::glib::spawn_future_local(async move {
+            let (old_for_task, new_for_task) = (old.clone(), new.clone());
+            let result = gtk4::gio::spawn_blocking(move || {
+                impulse_core::text_diff::diff_files(
+                    std::path::Path::new(&old_for_task),
+                    std::path::Path::new(&new_for_task),
+                )
+            })
+            .await;
+            if handle.load_generation.get() != generation {
+                return;
+            }
+            handle.render_single_file(new, Some(old), result.ok());
+        });
+    }
+
+    fn reload_saved_comparison(self: &Rc<Self>, generation: u64, path: String) {
+        let file_name = std::path::Path::new(&path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.clone());
+        self.repo_label
+            .set_text(&format!("{} (saved) ↔ {}", file_name, file_name));
+        self.branch_label.set_text("");
+        let buffer = editor_buffer(&path);
+        let handle = self.clone();
+        gtk4::glib::spawn_future_local(async move {
+            let path_for_task = path.clone();
+            let result = gtk4::gio::spawn_blocking(move || {
+                impulse_core::text_diff::diff_saved(std::path::Path::new(&path_for_task), &buffer?)
+            })
+            .await;
+            if handle.load_generation.get() != generation {
+                return;
+            }
+            handle.render_single_file(path, None, result.ok());
+        });
+    }
+
+    /// Render the one-file list of a file or saved comparison along with its
+    /// hunks, which are already computed.
+    fn render_single_file(
+        &self,
+        path: String,
+        old_path: Option<String>,
+        result: Option<Result<impulse_core::git::FileHunks, String>>,
+    ) {
+        let hunks = match result {
+            Some(Ok(hunks)) => hunks,
+            Some(Err(e)) => {
+                log::warn!("File comparison failed: {}", e);
+                impulse_core::git::FileHunks::blank("plaintext".to_string(), false, false)
             }
+            None => impulse_core::git::FileHunks::blank("plaintext".to_string(), false, false),
+        };
+        self.set_totals(1, hunks.added, hunks.removed);
+        let entry = ReviewFileEntry {
+            path: path.clone(),
+            status: "M".to_string(),
+            old_path,
+            added: hunks.added,
+            removed: hunks.removed,
+            is_binary: hunks.is_binary,
+        };
+        self.send_command(&ReviewCommand::Render {
+            files: vec![entry],
+            read_only: true,
         });
+        self.send_command(&ReviewCommand::SetHunks { path, hunks });
     }
 
     fn apply_change_set(&self, change_set: impulse_core::git::ChangeSet) {
@@ -372,16 +626,15 @@ impl ReviewTabHandle {
         *self.known_paths.borrow_mut() = change_set.files.iter().map(|f| f.path.clone()).collect();
         self.branch_label
             .set_text(change_set.branch.as_deref().unwrap_or(""));
-        self.count_label.set_text(&if change_set.files.len() == 1 {
-            "1 file".to_string()
-        } else {
-            format!("{} files", change_set.files.len())
+        self.set_totals(
+            change_set.files.len(),
+            change_set.total_added,
+            change_set.total_removed,
+        );
+        self.send_command(&ReviewCommand::Render {
+            files: entries,
+            read_only: false,
         });
-        self.added_label
-            .set_text(&format!("+{}", change_set.total_added));
-        self.removed_label
-            .set_text(&format!("-{}", change_set.total_removed));
-        self.send_command(&ReviewCommand::Render { files: entries });
     }
 
     /// Fetch unified-diff hunks for a repo-relative path off the main thread
@@ -390,12 +643,26 @@ impl ReviewTabHandle {
         let repo = self.repo_root.borrow().clone();
         let path = path.to_string();
         let generation = self.load_generation.get();
+        let source = self.source.clone();
+        let buffer = match &source {
+            ReviewSource::Saved { path } => editor_buffer(path),
+            _ => Ok(String::new()),
+        };
         let handle = self.clone();
         gtk4::glib::spawn_future_local(async move {
-            let repo_for_task = repo.clone();
             let path_for_task = path.clone();
-            let result = gtk4::gio::spawn_blocking(move || {
-                impulse_core::git::file_hunks(&repo_for_task, &path_for_task)
+            let result = gtk4::gio::spawn_blocking(move || match source {
+                ReviewSource::WorkingTree => impulse_core::git::file_hunks(&repo, &path_for_task),
+                ReviewSource::Refs { base, target } => {
+                    impulse_core::git::diff_refs_file(&repo, &base, &target, &path_for_task)
+                }
+                ReviewSource::Files { old, new } => impulse_core::text_diff::diff_files(
+                    std::path::Path::new(&old),
+                    std::path::Path::new(&new),
+                ),
+                ReviewSource::Saved { path } => {
+                    impulse_core::text_diff::diff_saved(std::path::Path::new(&path), &buffer?)
+                }
             })
             .await;
             if handle.load_generation.get() != generation {
@@ -404,15 +671,7 @@ impl ReviewTabHandle {
             let hunks = match result {
                 Ok(Ok(hunks)) => hunks,
                 // Send an empty result so review.js stops showing the spinner.
-                _ => impulse_core::git::FileHunks {
-                    language: "plaintext".to_string(),
-                    is_binary: false,
-                    too_large: false,
-                    truncated: false,
-                    added: 0,
-                    removed: 0,
-                    hunks: Vec::new(),
-                },
+                _ => impulse_core::git::FileHunks::blank("plaintext".to_string(), false, false),
             };
             handle.send_command(&ReviewCommand::SetHunks { path, hunks });
         });
@@ -457,11 +716,9 @@ impl ReviewTabHandle {
     }
 
     fn perform_commit(self: &Rc<Self>) {
-        let message = self.commit_entry.text().trim().to_string();
-        if message.is_empty() {
-            self.commit_entry.grab_focus();
+        let Some(message) = self.composer.message() else {
             return;
-        }
+        };
         let repo = self.repo_root.borrow().clone();
         let handle = self.clone();
         self.commit_btn.set_sensitive(false);
@@ -471,7 +728,7 @@ impl ReviewTabHandle {
                     .await;
             match result {
                 Ok(Ok(oid)) => {
-                    handle.commit_entry.set_text("");
+                    handle.composer.reset();
                     let short: String = oid.chars().take(7).collect();
                     handle
                         .confirmation_label
@@ -102,11 +102,16 @@ pub fn save(state: &impulse_core::session_state::SessionState) {
     }
 }
 
+/// Where named window layouts are saved.
+pub fn layouts_dir() -> Option<PathBuf> {
+    state_dir().map(|dir| dir.join("layouts"))
+}
+
 fn session_state_path() -> Option<PathBuf> {
     state_dir().map(|dir| dir.join("session-state.json"))
 }
 
-fn state_dir() -> Option<PathBuf> {
+pub fn state_dir() -> Option<PathBuf> {
     if let Ok(xdg_state_home) = std::env::var("XDG_STATE_HOME") {
         if !xdg_state_home.is_empty() {
             return Some(PathBuf::from(xdg_state_home).join("impulse"));
@@ -7,9 +7,99 @@ use libadwaita as adw;
 use libadwaita::prelude::*;
 
 use crate::keybindings;
-use crate::settings::{self, CommandOnSave, CustomKeybinding, FileTypeOverride, Settings};
+use crate::settings::{
+    self, CommandOnSave, CustomKeybinding, FileTemplate, FileTypeOverride, Settings,
+};
 use crate::theme;
 
+/// Picks one of the theme-name fields out of the settings.
+type ThemeField = fn(&mut Settings) -> &mut String;
+
+const ANSI_COLOR_NAMES: [&str; 16] = [
+    "Black",
+    "Red",
+    "Green",
+    "Yellow",
+    "Blue",
+    "Magenta",
+    "Cyan",
+    "White",
+    "Bright Black",
+    "Bright Red",
+    "Bright Green",
+    "Bright Yellow",
+    "Bright Blue",
+    "Bright Magenta",
+    "Bright Cyan",
+    "Bright White",
+];
+
+fn rgba_to_hex(rgba: &gtk4::gdk::RGBA) -> String {
+    let channel = |v: f32| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+    format!(
+        "#{:02x}{:02x}{:02x}",
+        channel(rgba.red()),
+        channel(rgba.green()),
+        channel(rgba.blue())
+    )
+}
+
+/// A row with a color picker for an optional color override. The picker
+/// shows `current`, or the theme's `fallback` when there is no override;
+/// the reset button drops the override. `on_set` gets the new override.
+fn color_override_row(
+    title: &str,
+    current: Option<&str>,
+    fallback: &str,
+    on_set: impl Fn(Option<String>) + 'static,
+) -> adw::ActionRow {
+    let row = adw::ActionRow::new();
+    row.set_title(title);
+    let dialog = gtk4::ColorDialog::new();
+    dialog.set_with_alpha(false);
+    let button = gtk4::ColorDialogButton::new(Some(dialog));
+    button.set_valign(gtk4::Align::Center);
+    let fallback = gtk4::gdk::RGBA::parse(fallback).unwrap_or(gtk4::gdk::RGBA::BLACK);
+    button.set_rgba(
+        &current
+            .and_then(|c| gtk4::gdk::RGBA::parse(c).ok())
+            .unwrap_or(fallback),
+    );
+    let reset = gtk4::Button::from_icon_name("edit-undo-symbolic");
+    reset.set_valign(gtk4::Align::Center);
+    reset.add_css_class("flat");
+    reset.set_tooltip_text(Some("Use the theme color"));
+    reset.set_sensitive(current.is_some());
+
+    let on_set = Rc::new(on_set);
+    let resetting = Rc::new(Cell::new(false));
+    {
+        let on_set = Rc::clone(&on_set);
+        let resetting = Rc::clone(&resetting);
+        let reset = reset.clone();
+        button.connect_rgba_notify(move |button| {
+            if resetting.get() {
+                return;
+            }
+            reset.set_sensitive(true);
+            on_set(Some(rgba_to_hex(&button.rgba())));
+        });
+    }
+    {
+        let button = button.clone();
+        reset.connect_clicked(move |reset| {
+            resetting.set(true);
+            button.set_rgba(&fallback);
+            resetting.set(false);
+            reset.set_sensitive(false);
+            on_set(None);
+        });
+    }
+    row.add_suffix(&reset);
+    row.add_suffix(&button);
+    row
+}
+
 fn override_summary(o: &FileTypeOverride) -> String {
     let mut parts = Vec::new();
     if let Some(tw) = o.tab_width {
@@ -25,6 +115,13 @@ fn override_summary(o: &FileTypeOverride) -> String {
     }
 }
 
+fn template_summary(content: &str) -> String {
+    match content.lines().find(|line| !line.trim().is_empty()) {
+        Some(line) => line.trim().to_string(),
+        None => "Empty file".to_string(),
+    }
+}
+
 fn command_summary(c: &CommandOnSave) -> String {
     let suffix = if c.reload_file { " (formatter)" } else { "" };
     if c.command.is_empty() {
@@ -34,6 +131,15 @@ fn command_summary(c: &CommandOnSave) -> String {
     }
 }
 
+/// Parse a comma-separated list of globs or names, dropping empty entries.
+fn split_list(text: &str) -> Vec<String> {
+    text.split(',')
+        .map(str::trim)
+        .filter(|glob| !glob.is_empty())
+        .map(String::from)
+        .collect()
+}
+
 fn rebuild_overrides_group(
     group: &adw::PreferencesGroup,
     tracked: &Rc<RefCell<Vec<gtk4::Widget>>>,
@@ -199,6 +305,333 @@ fn rebuild_overrides_group(
     tracked.borrow_mut().push(add_row.upcast());
 }
 
+fn rebuild_templates_group(
+    group: &adw::PreferencesGroup,
+    tracked: &Rc<RefCell<Vec<gtk4::Widget>>>,
+    settings: &Rc<RefCell<Settings>>,
+    on_changed: &Rc<dyn Fn(&Settings)>,
+    generation: &Rc<Cell<u64>>,
+) {
+    generation.set(generation.get() + 1);
+    let gen = generation.get();
+
+    for row in tracked.borrow().iter() {
+        group.remove(row);
+    }
+    tracked.borrow_mut().clear();
+
+    let count = settings.borrow().file_templates.len();
+    for i in 0..count {
+        let (pattern, content) = {
+            let s = settings.borrow();
+            let t = &s.file_templates[i];
+            (t.pattern.clone(), t.content.clone())
+        };
+
+        let expander = adw::ExpanderRow::new();
+        expander.set_title(&pattern);
+        expander.set_subtitle(&template_summary(&content));
+
+        let delete_btn = gtk4::Button::from_icon_name("user-trash-symbolic");
+        delete_btn.set_valign(gtk4::Align::Center);
+        delete_btn.add_css_class("flat");
+        {
+            let group = group.clone();
+            let tracked = Rc::clone(tracked);
+            let settings = Rc::clone(settings);
+            let on_changed = Rc::clone(on_changed);
+            let generation = Rc::clone(generation);
+            delete_btn.connect_clicked(move |_| {
+                {
+                    let mut s = settings.borrow_mut();
+                    if i >= s.file_templates.len() {
+                        return;
+                    }
+                    s.file_templates.remove(i);
+                    settings::save(&s);
+                    on_changed(&s);
+                }
+                rebuild_templates_group(&group, &tracked, &settings, &on_changed, &generation);
+            });
+        }
+        expander.add_suffix(&delete_btn);
+
+        let pattern_row = adw::EntryRow::new();
+        pattern_row.set_title("Pattern");
+        pattern_row.set_text(&pattern);
+        {
+            let settings = Rc::clone(settings);
+            let on_changed = Rc::clone(on_changed);
+            let expander = expander.clone();
+            let generation = Rc::clone(generation);
+            pattern_row.connect_changed(move |row| {
+                if generation.get() != gen {
+                    return;
+                }
+                let mut s = settings.borrow_mut();
+                if i >= s.file_templates.len() {
+                    return;
+                }
+                s.file_templates[i].pattern = row.text().to_string();
+                expander.set_title(&row.text());
+                settings::save(&s);
+                on_changed(&s);
+            });
+        }
+        expander.add_row(&pattern_row);
+
+        let content_view = gtk4::TextView::new();
+        content_view.set_monospace(true);
+        content_view.set_top_margin(8);
+        content_view.set_bottom_margin(8);
+        content_view.set_left_margin(12);
+        content_view.set_right_margin(12);
+        content_view.buffer().set_text(&content);
+        {
+            let settings = Rc::clone(settings);
+            let on_changed = Rc::clone(on_changed);
+            let expander = expander.clone();
+            let generation = Rc::clone(generation);
+            content_view.buffer().connect_changed(move |buffer| {
+                if generation.get() != gen {
+                    return;
+                }
+                let text = buffer
+                    .text(&buffer.start_iter(), &buffer.end_iter(), false)
+                    .to_string();
+                let mut s = settings.borrow_mut();
+                if i >= s.file_templates.len() {
+                    return;
+                }
+                expander.set_subtitle(&template_summary(&text));
+                s.file_templates[i].content = text;
+                settings::save(&s);
+                on_changed(&s);
+            });
+        }
+        let content_scroll = gtk4::ScrolledWindow::new();
+        content_scroll.set_min_content_height(120);
+        content_scroll.set_child(Some(&content_view));
+        expander.add_row(&content_scroll);
+
+        group.add(&expander);
+        tracked.borrow_mut().push(expander.upcast());
+    }
+
+    let add_row = adw::ActionRow::new();
+    add_row.set_title("Add File Template");
+    add_row.set_activatable(true);
+    add_row.add_prefix(&gtk4::Image::from_icon_name("list-add-symbolic"));
+    {
+        let group = group.clone();
+        let tracked = Rc::clone(tracked);
+        let settings = Rc::clone(settings);
+        let on_changed = Rc::clone(on_changed);
+        let generation = Rc::clone(generation);
+        add_row.connect_activated(move |_| {
+            {
+                let mut s = settings.borrow_mut();
+                s.file_templates.push(FileTemplate {
+                    pattern: "*.ext".to_string(),
+                    content: String::new(),
+                });
+                settings::save(&s);
+                on_changed(&s);
+            }
+            rebuild_templates_group(&group, &tracked, &settings, &on_changed, &generation);
+        });
+    }
+    group.add(&add_row);
+    tracked.borrow_mut().push(add_row.upcast());
+}
+
+/// One row per secret in the keyring, with a button to delete it, and a
+/// form to add a git HTTPS token. Secrets are read and written off the UI
+/// thread since the keyring may prompt to unlock.
+fn rebuild_secrets_group(group: &adw::PreferencesGroup, tracked: &Rc<RefCell<Vec<gtk4::Widget>>>) {
+    use impulse_core::secrets::{self, SecretKind};
+
+    for row in tracked.borrow().iter() {
+        group.remove(row);
+    }
+    tracked.borrow_mut().clear();
+
+    for info in secrets::list() {
+        let row = adw::ActionRow::new();
+        row.set_title(&info.name);
+        row.set_subtitle(info.kind.label());
+        let delete_btn = gtk4::Button::from_icon_name("user-trash-symbolic");
+        delete_btn.set_valign(gtk4::Align::Center);
+        delete_btn.add_css_class("flat");
+        delete_btn.set_tooltip_text(Some("Delete from Keyring"));
+        delete_btn.update_property(&[gtk4::accessible::Property::Label(&format!(
+            "Delete {} {}",
+            info.kind.label(),
+            info.name
+        ))]);
+        {
+            let group = group.clone();
+            let tracked = Rc::clone(tracked);
+            let id = info.id.clone();
+            delete_btn.connect_clicked(move |_| {
+                let group = group.clone();
+                let tracked = Rc::clone(&tracked);
+                let id = id.clone();
+                gtk4::glib::spawn_future_local(async move {
+                    let result = gtk4::gio::spawn_blocking(move || secrets::delete(&id))
+                        .await
+                        .unwrap_or_else(|_| Err("Failed to remove the secret".to_string()));
+                    if let Err(e) = result {
+                        log::warn!("{}", e);
+                    }
+                    rebuild_secrets_group(&group, &tracked);
+                });
+            });
+        }
+        row.add_suffix(&delete_btn);
+        group.add(&row);
+        tracked.borrow_mut().push(row.upcast());
+    }
+
+    let add_row = adw::ExpanderRow::new();
+    add_row.set_title("Add Git HTTPS Token");
+    add_row.set_subtitle("Used to push and pull over HTTPS before git credential helpers");
+    let host_row = adw::EntryRow::new();
+    host_row.set_title("Host (e.g. github.com)");
+    add_row.add_row(&host_row);
+    let user_row = adw::EntryRow::new();
+    user_row.set_title("Username");
+    add_row.add_row(&user_row);
+    let token_row = adw::PasswordEntryRow::new();
+    token_row.set_title("Token");
+    token_row.set_show_apply_button(true);
+    add_row.add_row(&token_row);
+    {
+        let group = group.clone();
+        let tracked = Rc::clone(tracked);
+        token_row.connect_apply(move |row| {
+            let host = host_row.text().trim().to_string();
+            let user = user_row.text().trim().to_string();
+            let token = row.text().to_string();
+            if host.is_empty() || user.is_empty() || token.is_empty() {
+                return;
+            }
+            row.set_text("");
+            let group = group.clone();
+            let tracked = Rc::clone(&tracked);
+            gtk4::glib::spawn_future_local(async move {
+                let name = format!("{}@{}", user, host);
+                let result = gtk4::gio::spawn_blocking(move || {
+                    secrets::set(SecretKind::GitCredential, &name, &token)
+                })
+                .await
+                .unwrap_or_else(|_| Err("Failed to store the secret".to_string()));
+                if let Err(e) = result {
+                    log::warn!("{}", e);
+                }
+                rebuild_secrets_group(&group, &tracked);
+            });
+        });
+    }
+    group.add(&add_row);
+    tracked.borrow_mut().push(add_row.upcast());
+}
+
+/// One switch row per status bar segment, in the current order, with
+/// buttons to move it within its side of the bar.
+fn rebuild_status_bar_group(
+    group: &adw::PreferencesGroup,
+    tracked: &Rc<RefCell<Vec<gtk4::Widget>>>,
+    settings: &Rc<RefCell<Settings>>,
+    on_changed: &Rc<dyn Fn(&Settings)>,
+) {
+    for row in tracked.borrow().iter() {
+        group.remove(row);
+    }
+    tracked.borrow_mut().clear();
+
+    // The full order, hidden segments included.
+    let order: Vec<&'static impulse_core::status_bar::StatusBarSegment> =
+        impulse_core::status_bar::resolve_segments(&settings.borrow().status_bar_segments, &[]);
+
+    for (i, segment) in order.iter().enumerate() {
+        let row = adw::SwitchRow::new();
+        row.set_title(segment.name);
+        row.set_subtitle(match segment.alignment {
+            impulse_core::status_bar::SegmentAlignment::Left => "Left",
+            impulse_core::status_bar::SegmentAlignment::Right => "Right",
+        });
+        row.set_active(
+            !settings
+                .borrow()
+                .status_bar_hidden_segments
+                .iter()
+                .any(|id| id == segment.id),
+        );
+        {
+            let settings = Rc::clone(settings);
+            let on_changed = Rc::clone(on_changed);
+            let id = segment.id;
+            row.connect_active_notify(move |row| {
+                let mut s = settings.borrow_mut();
+                s.status_bar_hidden_segments.retain(|h| h != id);
+                if !row.is_active() {
+                    s.status_bar_hidden_segments.push(id.to_string());
+                }
+                settings::save(&s);
+                on_changed(&s);
+            });
+        }
+
+        // Moving only makes sense among segments on the same side.
+        let neighbor = |forward: bool| {
+            let mut j = i;
+            loop {
+                j = if forward { j + 1 } else { j.checked_sub(1)? };
+                let other = order.get(j)?;
+                if other.alignment == segment.alignment {
+                    return Some(j);
+                }
+            }
+        };
+        for (icon, tooltip, target) in [
+            ("go-up-symbolic", "Move Earlier", neighbor(false)),
+            ("go-down-symbolic", "Move Later", neighbor(true)),
+        ] {
+            let btn = gtk4::Button::from_icon_name(icon);
+            btn.set_valign(gtk4::Align::Center);
+            btn.add_css_class("flat");
+            btn.set_tooltip_text(Some(tooltip));
+            btn.set_sensitive(target.is_some());
+            let Some(target) = target else {
+                row.add_suffix(&btn);
+                continue;
+            };
+            {
+                let group = group.clone();
+                let tracked = Rc::clone(tracked);
+                let settings = Rc::clone(settings);
+                let on_changed = Rc::clone(on_changed);
+                let mut ids: Vec<String> = order.iter().map(|s| s.id.to_string()).collect();
+                ids.swap(i, target);
+                btn.connect_clicked(move |_| {
+                    {
+                        let mut s = settings.borrow_mut();
+                        s.status_bar_segments = ids.clone();
+                        settings::save(&s);
+                        on_changed(&s);
+                    }
+                    rebuild_status_bar_group(&group, &tracked, &settings, &on_changed);
+                });
+            }
+            row.add_suffix(&btn);
+        }
+
+        group.add(&row);
+        tracked.borrow_mut().push(row.upcast());
+    }
+}
+
 fn rebuild_commands_group(
     group: &adw::PreferencesGroup,
     tracked: &Rc<RefCell<Vec<gtk4::Widget>>>,
@@ -473,8 +906,113 @@ pub fn show_settings_window(
         });
     }
     startup_group.add(&updates_row);
+
+    let auto_install_row = adw::SwitchRow::new();
+    auto_install_row.set_title("Install Updates Automatically");
+    auto_install_row.set_subtitle(
+        "Standalone installs only; Flatpak and packages update through their own tools",
+    );
+    auto_install_row.set_active(settings.borrow().auto_install_updates);
+    {
+        let settings = Rc::clone(settings);
+        let on_changed = Rc::clone(&on_changed);
+        auto_install_row.connect_active_notify(move |row| {
+            let mut s = settings.borrow_mut();
+            s.auto_install_updates = row.is_active();
+            settings::save(&s);
+            on_changed(&s);
+        });
+    }
+    startup_group.add(&auto_install_row);
     general_page.add(&startup_group);
 
+    // -- Workspace trust group --
+    let trust_group = adw::PreferencesGroup::new();
+    trust_group.set_title("Workspace Trust");
+    trust_group.set_description(Some(
+        "Restricted folders don't start language servers, run commands on save, or read .impulse settings",
+    ));
+
+    let trust_row = adw::SwitchRow::new();
+    trust_row.set_title("Restricted Mode for New Folders");
+    trust_row.set_subtitle("Ask before trusting a folder opened for the first time");
+    trust_row.set_active(settings.borrow().workspace_trust_enabled);
+    {
+        let settings = Rc::clone(settings);
+        let on_changed = Rc::clone(&on_changed);
+        trust_row.connect_active_notify(move |row| {
+            let mut s = settings.borrow_mut();
+            s.workspace_trust_enabled = row.is_active();
+            impulse_core::trust::set_enabled(s.workspace_trust_enabled);
+            settings::save(&s);
+            on_changed(&s);
+        });
+    }
+    trust_group.add(&trust_row);
+    general_page.add(&trust_group);
+
+    // -- Crash reports group --
+    let crash_group = adw::PreferencesGroup::new();
+    crash_group.set_title("Crash Reports");
+    crash_group.set_description(Some(
+        "Reports are always saved locally and shown after a crash",
+    ));
+
+    let send_crashes_row = adw::SwitchRow::new();
+    send_crashes_row.set_title("Send Crash Reports Automatically");
+    send_crashes_row.set_subtitle("Submit new reports to the endpoint below without asking");
+    send_crashes_row.set_active(settings.borrow().send_crash_reports);
+    {
+        let settings = Rc::clone(settings);
+        let on_changed = Rc::clone(&on_changed);
+        send_crashes_row.connect_active_notify(move |row| {
+            let mut s = settings.borrow_mut();
+            s.send_crash_reports = row.is_active();
+            settings::save(&s);
+            on_changed(&s);
+        });
+    }
+    crash_group.add(&send_crashes_row);
+
+    let crash_endpoint_row = adw::EntryRow::new();
+    crash_endpoint_row.set_title("Report Endpoint URL");
+    crash_endpoint_row.set_text(&settings.borrow().crash_report_endpoint);
+    {
+        let settings = Rc::clone(settings);
+        let on_changed = Rc::clone(&on_changed);
+        crash_endpoint_row.connect_changed(move |row| {
+            let mut s = settings.borrow_mut();
+            s.crash_report_endpoint = row.text().to_string();
+            settings::save(&s);
+            on_changed(&s);
+        });
+    }
+    crash_group.add(&crash_endpoint_row);
+    general_page.add(&crash_group);
+
+    // -- Settings sync group --
+    let sync_group = adw::PreferencesGroup::new();
+    sync_group.set_title("Settings Sync");
+    sync_group.set_description(Some(
+        "Keep settings, keybindings, custom CSS and themes in a git repository; synced at launch and with Sync Settings",
+    ));
+
+    let sync_repo_row = adw::EntryRow::new();
+    sync_repo_row.set_title("Repository URL");
+    sync_repo_row.set_text(&settings.borrow().settings_sync_repository);
+    {
+        let settings = Rc::clone(settings);
+        let on_changed = Rc::clone(&on_changed);
+        sync_repo_row.connect_changed(move |row| {
+            let mut s = settings.borrow_mut();
+            s.settings_sync_repository = row.text().trim().to_string();
+            settings::save(&s);
+            on_changed(&s);
+        });
+    }
+    sync_group.add(&sync_repo_row);
+    general_page.add(&sync_group);
+
     // -- Window group --
     let window_group = adw::PreferencesGroup::new();
     window_group.set_title("Window");
@@ -495,8 +1033,352 @@ pub fn show_settings_window(
         });
     }
     window_group.add(&close_warnings_row);
+
+    let cli_new_window_row = adw::SwitchRow::new();
+    cli_new_window_row.set_title("Open Command-Line Files in New Window");
+    cli_new_window_row
+        .set_subtitle("impulse <file> opens a new window instead of a tab in the active one");
+    cli_new_window_row.set_active(settings.borrow().cli_open_in_new_window);
+    {
+        let settings = Rc::clone(settings);
+        let on_changed = Rc::clone(&on_changed);
+        cli_new_window_row.connect_active_notify(move |row| {
+            let mut s = settings.borrow_mut();
+            s.cli_open_in_new_window = row.is_active();
+            settings::save(&s);
+            on_changed(&s);
+        });
+    }
+    window_group.add(&cli_new_window_row);
+
+    let control_socket_row = adw::SwitchRow::new();
+    control_socket_row.set_title("Control Socket");
+    control_socket_row
+        .set_subtitle("Scripts and tools can open files and run commands with impulse --control");
+    control_socket_row.set_active(settings.borrow().control_socket_enabled);
+    {
+        let settings = Rc::clone(settings);
+        let on_changed = Rc::clone(&on_changed);
+        control_socket_row.connect_active_notify(move |row| {
+            let mut s = settings.borrow_mut();
+            s.control_socket_enabled = row.is_active();
+            settings::save(&s);
+            on_changed(&s);
+        });
+    }
+    window_group.add(&control_socket_row);
+
+    let zen_width_adj = gtk4::Adjustment::new(
+        settings.borrow().zen_mode_max_width as f64,
+        400.0,
+        4000.0,
+        20.0,
+        100.0,
+        0.0,
+    );
+    let zen_width_row = adw::SpinRow::new(Some(&zen_width_adj), 20.0, 0);
+    zen_width_row.set_title("Zen Mode Width");
+    zen_width_row.set_subtitle("Maximum width in pixels of the centered content");
+    {
+        let settings = Rc::clone(settings);
+        let on_changed = Rc::clone(&on_changed);
+        zen_width_row.connect_value_notify(move |row| {
+            let mut s = settings.borrow_mut();
+            s.zen_mode_max_width = row.value() as i32;
+            settings::save(&s);
+            on_changed(&s);
+        });
+    }
+    window_group.add(&zen_width_row);
     general_page.add(&window_group);
 
+    // -- Git group --
+    let git_group = adw::PreferencesGroup::new();
+    git_group.set_title("Git");
+
+    let ci_status_row = adw::SwitchRow::new();
+    ci_status_row.set_title("Show CI Status in Status Bar");
+    ci_status_row.set_subtitle(
+        "Query GitHub or GitLab for the current commit's checks (uses GITHUB_TOKEN / GITLAB_TOKEN)",
+    );
+    ci_status_row.set_active(settings.borrow().show_ci_status);
+    {
+        let settings = Rc::clone(settings);
+        let on_changed = Rc::clone(&on_changed);
+        ci_status_row.connect_active_notify(move |row| {
+            let mut s = settings.borrow_mut();
+            s.show_ci_status = row.is_active();
+            settings::save(&s);
+            on_changed(&s);
+        });
+    }
+    git_group.add(&ci_status_row);
+
+    let conventional_row = adw::SwitchRow::new();
+    conventional_row.set_title("Conventional Commits");
+    conventional_row
+        .set_subtitle("Warn when commit subjects don't follow type(scope): description");
+    conventional_row.set_active(settings.borrow().conventional_commits);
+    {
+        let settings = Rc::clone(settings);
+        let on_changed = Rc::clone(&on_changed);
+        conventional_row.connect_active_notify(move |row| {
+            let mut s = settings.borrow_mut();
+            s.conventional_commits = row.is_active();
+            settings::save(&s);
+            on_changed(&s);
+        });
+    }
+    git_group.add(&conventional_row);
+    general_page.add(&git_group);
+
+    // -- Assistant group --
+    let assistant_group = adw::PreferencesGroup::new();
+    assistant_group.set_title("Assistant");
+    assistant_group.set_description(Some(
+        "Chat, explain selections and write commit messages with an OpenAI-compatible API or a local Ollama server. Code is only sent when you ask.",
+    ));
+
+    let assistant_enabled_row = adw::SwitchRow::new();
+    assistant_enabled_row.set_title("Enable Assistant");
+    assistant_enabled_row.set_active(settings.borrow().assistant_enabled);
+    {
+        let settings = Rc::clone(settings);
+        let on_changed = Rc::clone(&on_changed);
+        assistant_enabled_row.connect_active_notify(move |row| {
+            let mut s = settings.borrow_mut();
+            s.assistant_enabled = row.is_active();
+            settings::save(&s);
+            on_changed(&s);
+        });
+    }
+    assistant_group.add(&assistant_enabled_row);
+
+    let providers = impulse_core::assistant::PROVIDERS;
+    let provider_labels: Vec<&str> = providers.iter().map(|p| p.label()).collect();
+    let provider_row = adw::ComboRow::new();
+    provider_row.set_title("Provider");
+    provider_row.set_model(Some(&gtk4::StringList::new(&provider_labels)));
+    provider_row.set_selected(
+        providers
+            .iter()
+            .position(|p| p.id() == settings.borrow().assistant_provider)
+            .unwrap_or(0) as u32,
+    );
+    assistant_group.add(&provider_row);
+
+    let endpoint_row = adw::EntryRow::new();
+    endpoint_row.set_text(&settings.borrow().assistant_endpoint);
+    {
+        let settings = Rc::clone(settings);
+        let on_changed = Rc::clone(&on_changed);
+        endpoint_row.connect_changed(move |row| {
+            let mut s = settings.borrow_mut();
+            s.assistant_endpoint = row.text().trim().to_string();
+            settings::save(&s);
+            on_changed(&s);
+        });
+    }
+    assistant_group.add(&endpoint_row);
+
+    let model_row = adw::EntryRow::new();
+    model_row.set_text(&settings.borrow().assistant_model);
+    {
+        let settings = Rc::clone(settings);
+        let on_changed = Rc::clone(&on_changed);
+        model_row.connect_changed(move |row| {
+            let mut s = settings.borrow_mut();
+            s.assistant_model = row.text().trim().to_string();
+            settings::save(&s);
+            on_changed(&s);
+        });
+    }
+    assistant_group.add(&model_row);
+
+    // The key goes to the system keyring on Apply, never to settings.json.
+    let api_key_row = adw::PasswordEntryRow::new();
+    api_key_row.set_show_apply_button(true);
+    assistant_group.add(&api_key_row);
+
+    // Titles show the provider's defaults and whether a key is stored.
+    let show_provider: Rc<dyn Fn(impulse_core::assistant::Provider)> = {
+        let endpoint_row = endpoint_row.clone();
+        let model_row = model_row.clone();
+        let api_key_row = api_key_row.clone();
+        Rc::new(move |provider| {
+            endpoint_row.set_title(&format!(
+                "Endpoint (default {})",
+                provider.default_endpoint()
+            ));
+            model_row.set_title(&format!("Model (default {})", provider.default_model()));
+            api_key_row.set_title("API Key");
+            let api_key_row = api_key_row.clone();
+            gtk4::glib::spawn_future_local(async move {
+                let stored = gtk4::gio::spawn_blocking(move || {
+                    impulse_core::assistant::api_key(provider).is_some()
+                })
+                .await
+                .unwrap_or(false);
+                if stored {
+                    api_key_row.set_title("API Key (stored in keyring)");
+                }
+            });
+        })
+    };
+    show_provider(providers[provider_row.selected() as usize]);
+    {
+        let settings = Rc::clone(settings);
+        let on_changed = Rc::clone(&on_changed);
+        let show_provider = Rc::clone(&show_provider);
+        provider_row.connect_selected_notify(move |row| {
+            if let Some(&provider) = providers.get(row.selected() as usize) {
+                {
+                    let mut s = settings.borrow_mut();
+                    s.assistant_provider = provider.id().to_string();
+                    settings::save(&s);
+                    on_changed(&s);
+                }
+                show_provider(provider);
+            }
+        });
+    }
+    // -- Secrets group --
+    let secrets_group = adw::PreferencesGroup::new();
+    secrets_group.set_title("Stored Secrets");
+    secrets_group.set_description(Some(
+        "Tokens, API keys and passwords kept in the system keyring, never in settings.json",
+    ));
+    let tracked_secrets: Rc<RefCell<Vec<gtk4::Widget>>> = Rc::new(RefCell::new(Vec::new()));
+    rebuild_secrets_group(&secrets_group, &tracked_secrets);
+
+    {
+        let provider_row = provider_row.clone();
+        let show_provider = Rc::clone(&show_provider);
+        let secrets_group = secrets_group.clone();
+        let tracked_secrets = Rc::clone(&tracked_secrets);
+        api_key_row.connect_apply(move |row| {
+            let Some(&provider) = providers.get(provider_row.selected() as usize) else {
+                return;
+            };
+            if let Err(e) = impulse_core::assistant::set_api_key(provider, &row.text()) {
+                log::warn!("{}", e);
+            }
+            row.set_text("");
+            show_provider(provider);
+            rebuild_secrets_group(&secrets_group, &tracked_secrets);
+        });
+    }
+
+    let agent_access_row = adw::SwitchRow::new();
+    agent_access_row.set_title("Allow Agent Access (MCP)");
+    agent_access_row.set_subtitle(
+        "Coding agents can read open files, diagnostics and search results, and propose edits, in workspaces you share with \u{201c}Manage Agent Access\u{201d}",
+    );
+    agent_access_row.set_active(settings.borrow().agent_access_enabled);
+    {
+        let settings = Rc::clone(settings);
+        let on_changed = Rc::clone(&on_changed);
+        agent_access_row.connect_active_notify(move |row| {
+            let mut s = settings.borrow_mut();
+            s.agent_access_enabled = row.is_active();
+            settings::save(&s);
+            on_changed(&s);
+        });
+    }
+    assistant_group.add(&agent_access_row);
+    general_page.add(&assistant_group);
+    general_page.add(&secrets_group);
+
+    // -- Search group --
+    let search_group = adw::PreferencesGroup::new();
+    search_group.set_title("Search");
+
+    let search_index_row = adw::SwitchRow::new();
+    search_index_row.set_title("Index Workspace Contents");
+    search_index_row
+        .set_subtitle("Keep an in-memory index so repeated project searches return instantly");
+    search_index_row.set_active(settings.borrow().search_index_enabled);
+    {
+        let settings = Rc::clone(settings);
+        let on_changed = Rc::clone(&on_changed);
+        search_index_row.connect_active_notify(move |row| {
+            let mut s = settings.borrow_mut();
+            s.search_index_enabled = row.is_active();
+            settings::save(&s);
+            on_changed(&s);
+        });
+    }
+    search_group.add(&search_index_row);
+
+    let files_exclude_row = adw::EntryRow::new();
+    files_exclude_row.set_title("Files Exclude (comma-separated globs)");
+    files_exclude_row.set_tooltip_text(Some(
+        "Hidden from the file tree, file watching and search. Projects can add more in .impulse/settings.json",
+    ));
+    files_exclude_row.set_text(&settings.borrow().files_exclude.join(", "));
+    {
+        let settings = Rc::clone(settings);
+        let on_changed = Rc::clone(&on_changed);
+        files_exclude_row.connect_changed(move |row| {
+            let mut s = settings.borrow_mut();
+            s.files_exclude = split_list(&row.text());
+            settings::save(&s);
+            on_changed(&s);
+        });
+    }
+    search_group.add(&files_exclude_row);
+
+    let search_exclude_row = adw::EntryRow::new();
+    search_exclude_row.set_title("Search Exclude (comma-separated globs)");
+    search_exclude_row.set_tooltip_text(Some("Left out of quick open and project search only"));
+    search_exclude_row.set_text(&settings.borrow().search_exclude.join(", "));
+    {
+        let settings = Rc::clone(settings);
+        let on_changed = Rc::clone(&on_changed);
+        search_exclude_row.connect_changed(move |row| {
+            let mut s = settings.borrow_mut();
+            s.search_exclude = split_list(&row.text());
+            settings::save(&s);
+            on_changed(&s);
+        });
+    }
+    search_group.add(&search_exclude_row);
+
+    let follow_symlinks_row = adw::SwitchRow::new();
+    follow_symlinks_row.set_title("Follow Symbolic Links");
+    follow_symlinks_row
+        .set_subtitle("Search, index and watch inside symlinked folders, stopping at cycles");
+    follow_symlinks_row.set_active(settings.borrow().follow_symlinks);
+    {
+        let settings = Rc::clone(settings);
+        let on_changed = Rc::clone(&on_changed);
+        follow_symlinks_row.connect_active_notify(move |row| {
+            let mut s = settings.borrow_mut();
+            s.follow_symlinks = row.is_active();
+            settings::save(&s);
+            on_changed(&s);
+        });
+    }
+    search_group.add(&follow_symlinks_row);
+
+    let network_mounts_row = adw::SwitchRow::new();
+    network_mounts_row.set_title("Scan Network Mounts");
+    network_mounts_row
+        .set_subtitle("Search, index and watch inside NFS, SMB and SSHFS mounts in the workspace");
+    network_mounts_row.set_active(settings.borrow().scan_network_mounts);
+    {
+        let settings = Rc::clone(settings);
+        let on_changed = Rc::clone(&on_changed);
+        network_mounts_row.connect_active_notify(move |row| {
+            let mut s = settings.borrow_mut();
+            s.scan_network_mounts = row.is_active();
+            settings::save(&s);
+            on_changed(&s);
+        });
+    }
+    search_group.add(&network_mounts_row);
+    general_page.add(&search_group);
+
     preferences_window.add(&general_page);
 
     // ── Page 2: Editor ───────────────────────────────────────────────────
@@ -936,6 +1818,83 @@ pub fn show_settings_window(
     }
     behavior_group.add(&auto_save_row);
 
+    let save_strategy_labels = ["Atomic (write a copy, then replace)", "In place"];
+    let save_strategy_values = [
+        impulse_core::safe_write::STRATEGY_ATOMIC,
+        impulse_core::safe_write::STRATEGY_IN_PLACE,
+    ];
+    let save_strategy_row = adw::ComboRow::new();
+    save_strategy_row.set_title("Save Strategy");
+    save_strategy_row.set_subtitle("Use in place for file systems or tools that track inodes");
+    save_strategy_row.set_model(Some(&gtk4::StringList::new(&save_strategy_labels)));
+    save_strategy_row.set_selected(
+        save_strategy_values
+            .iter()
+            .position(|v| *v == settings.borrow().save_strategy)
+            .unwrap_or(0) as u32,
+    );
+    {
+        let settings = Rc::clone(settings);
+        let on_changed = Rc::clone(&on_changed);
+        save_strategy_row.connect_selected_notify(move |row| {
+            if let Some(&val) = save_strategy_values.get(row.selected() as usize) {
+                let mut s = settings.borrow_mut();
+                s.save_strategy = val.to_string();
+                settings::save(&s);
+                on_changed(&s);
+            }
+        });
+    }
+    behavior_group.add(&save_strategy_row);
+
+    let hot_exit_row = adw::SwitchRow::new();
+    hot_exit_row.set_title("Back Up Unsaved Changes");
+    hot_exit_row.set_subtitle("Offer to restore unsaved edits after a crash");
+    hot_exit_row.set_active(settings.borrow().hot_exit);
+    {
+        let settings = Rc::clone(settings);
+        let on_changed = Rc::clone(&on_changed);
+        hot_exit_row.connect_active_notify(move |row| {
+            let mut s = settings.borrow_mut();
+            s.hot_exit = row.is_active();
+            settings::save(&s);
+            on_changed(&s);
+        });
+    }
+    behavior_group.add(&hot_exit_row);
+
+    let trim_row = adw::SwitchRow::new();
+    trim_row.set_title("Trim Trailing Whitespace");
+    trim_row.set_subtitle("Remove spaces and tabs at line ends on save; .editorconfig overrides");
+    trim_row.set_active(settings.borrow().trim_trailing_whitespace);
+    {
+        let settings = Rc::clone(settings);
+        let on_changed = Rc::clone(&on_changed);
+        trim_row.connect_active_notify(move |row| {
+            let mut s = settings.borrow_mut();
+            s.trim_trailing_whitespace = row.is_active();
+            settings::save(&s);
+            on_changed(&s);
+        });
+    }
+    behavior_group.add(&trim_row);
+
+    let final_newline_row = adw::SwitchRow::new();
+    final_newline_row.set_title("Insert Final Newline");
+    final_newline_row.set_subtitle("End files with a newline on save; .editorconfig overrides");
+    final_newline_row.set_active(settings.borrow().insert_final_newline);
+    {
+        let settings = Rc::clone(settings);
+        let on_changed = Rc::clone(&on_changed);
+        final_newline_row.connect_active_notify(move |row| {
+            let mut s = settings.borrow_mut();
+            s.insert_final_newline = row.is_active();
+            settings::save(&s);
+            on_changed(&s);
+        });
+    }
+    behavior_group.add(&final_newline_row);
+
     let auto_close_labels = ["Always", "Language Defined", "Before Whitespace", "Never"];
     let auto_close_values = ["always", "languageDefined", "beforeWhitespace", "never"];
     let auto_close_model = gtk4::StringList::new(&auto_close_labels);
@@ -1078,8 +2037,91 @@ pub fn show_settings_window(
         });
     }
     term_cursor_group.add(&cursor_blink_row);
+
+    let term_theme = theme::active_theme(&settings.borrow());
+    let cursor_color = settings
+        .borrow()
+        .terminal_cursor_color()
+        .map(str::to_string);
+    let cursor_color_row = {
+        let settings = Rc::clone(settings);
+        let on_changed = Rc::clone(&on_changed);
+        color_override_row(
+            "Cursor Color",
+            cursor_color.as_deref(),
+            term_theme
+                .resolved
+                .map_or(term_theme.fg, |r| r.cursor.as_str()),
+            move |color| {
+                let mut s = settings.borrow_mut();
+                s.terminal_cursor_color = color.unwrap_or_default();
+                settings::save(&s);
+                on_changed(&s);
+            },
+        )
+    };
+    term_cursor_group.add(&cursor_color_row);
     terminal_page.add(&term_cursor_group);
 
+    // -- Colors group --
+    let term_colors_group = adw::PreferencesGroup::new();
+    term_colors_group.set_title("Colors");
+    term_colors_group.set_description(Some("Override the theme's terminal colors"));
+
+    let opacity_adj = gtk4::Adjustment::new(
+        settings.borrow().terminal_background_opacity,
+        0.3,
+        1.0,
+        0.05,
+        0.1,
+        0.0,
+    );
+    let opacity_row = adw::SpinRow::new(Some(&opacity_adj), 0.05, 2);
+    opacity_row.set_title("Background Opacity");
+    opacity_row.set_subtitle("Below 1.0 the terminal background is translucent");
+    {
+        let settings = Rc::clone(settings);
+        let on_changed = Rc::clone(&on_changed);
+        opacity_row.connect_value_notify(move |row| {
+            let mut s = settings.borrow_mut();
+            s.terminal_background_opacity = row.value();
+            settings::save(&s);
+            on_changed(&s);
+        });
+    }
+    term_colors_group.add(&opacity_row);
+
+    let palette_row = adw::ExpanderRow::new();
+    palette_row.set_title("ANSI Palette");
+    palette_row.set_subtitle("The 16 colors programs pick from");
+    for (index, name) in ANSI_COLOR_NAMES.iter().enumerate() {
+        let current = settings
+            .borrow()
+            .terminal_palette_color(index)
+            .map(str::to_string);
+        let settings = Rc::clone(settings);
+        let on_changed = Rc::clone(&on_changed);
+        palette_row.add_row(&color_override_row(
+            name,
+            current.as_deref(),
+            term_theme.terminal_palette[index],
+            move |color| {
+                let mut s = settings.borrow_mut();
+                if s.terminal_palette.len() < 16 {
+                    s.terminal_palette.resize(16, String::new());
+                }
+                s.terminal_palette[index] = color.unwrap_or_default();
+                if s.terminal_palette.iter().all(String::is_empty) {
+                    s.terminal_palette.clear();
+                }
+                settings::save(&s);
+                on_changed(&s);
+            },
+        ));
+    }
+    term_colors_group.add(&palette_row);
+    terminal_page.add(&term_colors_group);
+
     // -- Behavior group --
     let term_behavior_group = adw::PreferencesGroup::new();
     term_behavior_group.set_title("Behavior");
@@ -1091,14 +2133,95 @@ pub fn show_settings_window(
     {
         let settings = Rc::clone(settings);
         let on_changed = Rc::clone(&on_changed);
-        copy_on_select_row.connect_active_notify(move |row| {
+        copy_on_select_row.connect_active_notify(move |row| {
+            let mut s = settings.borrow_mut();
+            s.terminal_copy_on_select = row.is_active();
+            settings::save(&s);
+            on_changed(&s);
+        });
+    }
+    term_behavior_group.add(&copy_on_select_row);
+
+    let confirm_paste_row = adw::SwitchRow::new();
+    confirm_paste_row.set_title("Confirm Risky Pastes");
+    confirm_paste_row
+        .set_subtitle("Preview multi-line and sudo pastes before they reach the shell");
+    confirm_paste_row.set_active(settings.borrow().terminal_confirm_paste);
+    {
+        let settings = Rc::clone(settings);
+        let on_changed = Rc::clone(&on_changed);
+        confirm_paste_row.connect_active_notify(move |row| {
+            let mut s = settings.borrow_mut();
+            s.terminal_confirm_paste = row.is_active();
+            settings::save(&s);
+            on_changed(&s);
+        });
+    }
+    term_behavior_group.add(&confirm_paste_row);
+
+    let new_tab_dir_labels = ["Home Folder", "Workspace Folder", "Active Tab's Folder"];
+    let new_tab_dir_values = [
+        impulse_core::shell::NEW_TAB_DIRECTORY_HOME,
+        impulse_core::shell::NEW_TAB_DIRECTORY_WORKSPACE,
+        impulse_core::shell::NEW_TAB_DIRECTORY_CURRENT,
+    ];
+    let new_tab_dir_row = adw::ComboRow::new();
+    new_tab_dir_row.set_title("New Tabs Start In");
+    new_tab_dir_row.set_model(Some(&gtk4::StringList::new(&new_tab_dir_labels)));
+    new_tab_dir_row.set_selected(
+        new_tab_dir_values
+            .iter()
+            .position(|v| *v == settings.borrow().terminal_new_tab_directory)
+            .unwrap_or(0) as u32,
+    );
+    {
+        let settings = Rc::clone(settings);
+        let on_changed = Rc::clone(&on_changed);
+        new_tab_dir_row.connect_selected_notify(move |row| {
+            if let Some(&val) = new_tab_dir_values.get(row.selected() as usize) {
+                let mut s = settings.borrow_mut();
+                s.terminal_new_tab_directory = val.to_string();
+                settings::save(&s);
+                on_changed(&s);
+            }
+        });
+    }
+    term_behavior_group.add(&new_tab_dir_row);
+
+    let confirm_close_row = adw::SwitchRow::new();
+    confirm_close_row.set_title("Confirm Close with Running Processes");
+    confirm_close_row
+        .set_subtitle("Ask before closing a terminal whose shell is running something");
+    confirm_close_row.set_active(settings.borrow().terminal_confirm_close);
+    {
+        let settings = Rc::clone(settings);
+        let on_changed = Rc::clone(&on_changed);
+        confirm_close_row.connect_active_notify(move |row| {
+            let mut s = settings.borrow_mut();
+            s.terminal_confirm_close = row.is_active();
+            settings::save(&s);
+            on_changed(&s);
+        });
+    }
+    term_behavior_group.add(&confirm_close_row);
+
+    let close_ignored_row = adw::EntryRow::new();
+    close_ignored_row.set_title("Never Ask For (comma-separated programs)");
+    close_ignored_row.set_tooltip_text(Some(
+        "Running these (e.g. less, vim) never makes closing a terminal ask first",
+    ));
+    close_ignored_row.set_text(&settings.borrow().terminal_close_ignored_commands.join(", "));
+    {
+        let settings = Rc::clone(settings);
+        let on_changed = Rc::clone(&on_changed);
+        close_ignored_row.connect_changed(move |row| {
             let mut s = settings.borrow_mut();
-            s.terminal_copy_on_select = row.is_active();
+            s.terminal_close_ignored_commands = split_list(&row.text());
             settings::save(&s);
             on_changed(&s);
         });
     }
-    term_behavior_group.add(&copy_on_select_row);
+    term_behavior_group.add(&close_ignored_row);
 
     let scroll_on_output_row = adw::SwitchRow::new();
     scroll_on_output_row.set_title("Scroll on Output");
@@ -1238,16 +2361,17 @@ pub fn show_settings_window(
     let theme_group = adw::PreferencesGroup::new();
     theme_group.set_title("Theme");
 
-    let available_themes = theme::get_available_themes();
-    let theme_labels: Vec<String> = available_themes
-        .iter()
-        .map(|id| theme::theme_display_name(id))
-        .collect();
-    let theme_label_refs: Vec<&str> = theme_labels.iter().map(|s| s.as_str()).collect();
-    let theme_model = gtk4::StringList::new(&theme_label_refs);
+    // Built-in themes followed by user themes; selecting one applies it
+    // right away, so browsing the list previews each theme live.
+    let available_themes = Rc::new(RefCell::new(theme::get_available_themes()));
+    let theme_model = gtk4::StringList::new(&[]);
+    for id in available_themes.borrow().iter() {
+        theme_model.append(&theme::theme_display_name(id));
+    }
 
     let current_theme = settings.borrow().color_scheme.clone();
     let theme_index = available_themes
+        .borrow()
         .iter()
         .position(|t| *t == current_theme)
         .unwrap_or(0) as u32;
@@ -1259,17 +2383,164 @@ pub fn show_settings_window(
     {
         let settings = Rc::clone(settings);
         let on_changed = Rc::clone(&on_changed);
+        let available_themes = Rc::clone(&available_themes);
         theme_row.connect_selected_notify(move |row| {
             let idx = row.selected() as usize;
-            if let Some(&val) = available_themes.get(idx) {
+            if let Some(val) = available_themes.borrow().get(idx) {
                 let mut s = settings.borrow_mut();
-                s.color_scheme = val.to_string();
+                s.color_scheme = val.clone();
                 settings::save(&s);
                 on_changed(&s);
             }
         });
     }
     theme_group.add(&theme_row);
+
+    // Follow the desktop's light/dark preference, switching between a
+    // light and a dark theme instead of always using the color scheme.
+    let follow_system = settings.borrow().follow_system_appearance;
+    let follow_row = adw::SwitchRow::new();
+    follow_row.set_title("Follow System Appearance");
+    follow_row.set_subtitle("Switch between the light and dark theme with the desktop");
+    follow_row.set_active(follow_system);
+    theme_row.set_sensitive(!follow_system);
+    theme_group.add(&follow_row);
+
+    let variant_rows: [(&str, ThemeField); 2] = [
+        ("Light Theme", |s| &mut s.light_color_scheme),
+        ("Dark Theme", |s| &mut s.dark_color_scheme),
+    ];
+    let mut variant_combos = Vec::new();
+    for (title, field) in variant_rows {
+        let current = field(&mut settings.borrow_mut()).clone();
+        let row = adw::ComboRow::new();
+        row.set_title(title);
+        row.set_model(Some(&theme_model));
+        row.set_selected(
+            available_themes
+                .borrow()
+                .iter()
+                .position(|t| *t == current)
+                .unwrap_or(0) as u32,
+        );
+        row.set_visible(follow_system);
+        {
+            let settings = Rc::clone(settings);
+            let on_changed = Rc::clone(&on_changed);
+            let available_themes = Rc::clone(&available_themes);
+            row.connect_selected_notify(move |row| {
+                let idx = row.selected() as usize;
+                if let Some(val) = available_themes.borrow().get(idx) {
+                    let mut s = settings.borrow_mut();
+                    *field(&mut s) = val.clone();
+                    settings::save(&s);
+                    on_changed(&s);
+                }
+            });
+        }
+        theme_group.add(&row);
+        variant_combos.push(row);
+    }
+    {
+        let settings = Rc::clone(settings);
+        let on_changed = Rc::clone(&on_changed);
+        let theme_row = theme_row.clone();
+        follow_row.connect_active_notify(move |row| {
+            let active = row.is_active();
+            theme_row.set_sensitive(!active);
+            for combo in &variant_combos {
+                combo.set_visible(active);
+            }
+            let mut s = settings.borrow_mut();
+            s.follow_system_appearance = active;
+            settings::save(&s);
+            on_changed(&s);
+        });
+    }
+
+    let contrast_row = adw::SwitchRow::new();
+    contrast_row.set_title("Increase Contrast");
+    contrast_row.set_subtitle(
+        "Strengthen text, syntax and border colors (also on with the desktop's high contrast option)",
+    );
+    contrast_row.set_active(settings.borrow().increase_contrast);
+    {
+        let settings = Rc::clone(settings);
+        let on_changed = Rc::clone(&on_changed);
+        contrast_row.connect_active_notify(move |row| {
+            let mut s = settings.borrow_mut();
+            s.increase_contrast = row.is_active();
+            settings::save(&s);
+            on_changed(&s);
+        });
+    }
+    theme_group.add(&contrast_row);
+
+    let import_row = adw::ActionRow::new();
+    import_row.set_title("Import VS Code Theme");
+    import_row.set_subtitle("Convert a VS Code color theme (.json) into a user theme");
+    let import_btn = gtk4::Button::with_label("Import…");
+    import_btn.set_valign(gtk4::Align::Center);
+    {
+        let window = preferences_window.downgrade();
+        let theme_row = theme_row.clone();
+        import_btn.connect_clicked(move |_| {
+            let Some(preferences_window) = window.upgrade() else {
+                return;
+            };
+            let filter = gtk4::FileFilter::new();
+            filter.set_name(Some("VS Code themes"));
+            filter.add_pattern("*.json");
+            let filters = gtk4::gio::ListStore::new::<gtk4::FileFilter>();
+            filters.append(&filter);
+            let dialog = gtk4::FileDialog::new();
+            dialog.set_title("Import VS Code Theme");
+            dialog.set_filters(Some(&filters));
+            let available_themes = Rc::clone(&available_themes);
+            let theme_model = theme_model.clone();
+            let theme_row = theme_row.clone();
+            let window = preferences_window.downgrade();
+            dialog.open(
+                Some(&preferences_window),
+                gtk4::gio::Cancellable::NONE,
+                move |result| {
+                    let Some(preferences_window) = window.upgrade() else {
+                        return;
+                    };
+                    let Some(path) = result.ok().and_then(|file| file.path()) else {
+                        return;
+                    };
+                    match impulse_core::vscode_theme::import_vscode_theme_file(&path) {
+                        Ok(id) => {
+                            let themes = theme::get_available_themes();
+                            let labels: Vec<String> = themes
+                                .iter()
+                                .map(|t| theme::theme_display_name(t))
+                                .collect();
+                            let label_refs: Vec<&str> = labels.iter().map(String::as_str).collect();
+                            let index = themes.iter().position(|t| *t == id);
+                            *available_themes.borrow_mut() = themes;
+                            theme_model.splice(0, theme_model.n_items(), &label_refs);
+                            if let Some(index) = index {
+                                theme_row.set_selected(index as u32);
+                            }
+                            preferences_window.add_toast(adw::Toast::new(&format!(
+                                "Imported theme \"{}\"",
+                                theme::theme_display_name(&id)
+                            )));
+                        }
+                        Err(e) => {
+                            log::warn!("Theme import failed: {}", e);
+                            preferences_window
+                                .add_toast(adw::Toast::new(&format!("Import failed: {}", e)));
+                        }
+                    }
+                },
+            );
+        });
+    }
+    import_row.add_suffix(&import_btn);
+    theme_group.add(&import_row);
     appearance_page.add(&theme_group);
 
     // -- Layout group --
@@ -1305,8 +2576,53 @@ pub fn show_settings_window(
         });
     }
     layout_group.add(&tab_bar_row);
+
+    let preview_tabs_row = adw::SwitchRow::new();
+    preview_tabs_row.set_title("Preview Tabs");
+    preview_tabs_row.set_subtitle(
+        "A single click in the file tree opens a temporary tab that the next file replaces",
+    );
+    preview_tabs_row.set_active(settings.borrow().preview_tabs);
+    {
+        let settings = Rc::clone(settings);
+        let on_changed = Rc::clone(&on_changed);
+        preview_tabs_row.connect_active_notify(move |row| {
+            let mut s = settings.borrow_mut();
+            s.preview_tabs = row.is_active();
+            settings::save(&s);
+            on_changed(&s);
+        });
+    }
+    layout_group.add(&preview_tabs_row);
+
+    let auto_reveal_row = adw::SwitchRow::new();
+    auto_reveal_row.set_title("Auto Reveal Active File");
+    auto_reveal_row.set_subtitle(
+        "Switching to an editor tab expands the file tree to its file and selects it",
+    );
+    auto_reveal_row.set_active(settings.borrow().sidebar_auto_reveal);
+    {
+        let settings = Rc::clone(settings);
+        let on_changed = Rc::clone(&on_changed);
+        auto_reveal_row.connect_active_notify(move |row| {
+            let mut s = settings.borrow_mut();
+            s.sidebar_auto_reveal = row.is_active();
+            settings::save(&s);
+            on_changed(&s);
+        });
+    }
+    layout_group.add(&auto_reveal_row);
     appearance_page.add(&layout_group);
 
+    let status_bar_group = adw::PreferencesGroup::new();
+    status_bar_group.set_title("Status Bar");
+    status_bar_group.set_description(Some(
+        "Choose which segments the status bar shows and in what order",
+    ));
+    let tracked_segments: Rc<RefCell<Vec<gtk4::Widget>>> = Rc::new(RefCell::new(Vec::new()));
+    rebuild_status_bar_group(&status_bar_group, &tracked_segments, settings, &on_changed);
+    appearance_page.add(&status_bar_group);
+
     preferences_window.add(&appearance_page);
 
     // ── Page 5: Automation ──────────────────────────────────────────────
@@ -1314,6 +2630,29 @@ pub fn show_settings_window(
     automation_page.set_title("Automation");
     automation_page.set_icon_name(Some("system-run-symbolic"));
 
+    let environment_group = adw::PreferencesGroup::new();
+    environment_group.set_title("Environment");
+    environment_group.set_description(Some(
+        "Language servers, formatters and commands on save get your login shell's environment (nvm, asdf, pyenv)",
+    ));
+    let direnv_row = adw::SwitchRow::new();
+    direnv_row.set_title("Load .envrc Files with direnv");
+    direnv_row.set_subtitle("Only in trusted folders, and only files allowed with direnv allow");
+    direnv_row.set_active(settings.borrow().use_direnv);
+    {
+        let settings = Rc::clone(settings);
+        let on_changed = Rc::clone(&on_changed);
+        direnv_row.connect_active_notify(move |row| {
+            let mut s = settings.borrow_mut();
+            s.use_direnv = row.is_active();
+            impulse_core::environment::set_direnv_enabled(s.use_direnv);
+            settings::save(&s);
+            on_changed(&s);
+        });
+    }
+    environment_group.add(&direnv_row);
+    automation_page.add(&environment_group);
+
     let overrides_group = adw::PreferencesGroup::new();
     overrides_group.set_title("File Type Overrides");
     overrides_group.set_description(Some("Per-file-type indentation settings"));
@@ -1328,6 +2667,85 @@ pub fn show_settings_window(
     );
     automation_page.add(&overrides_group);
 
+    let templates_group = adw::PreferencesGroup::new();
+    templates_group.set_title("File Templates");
+    templates_group.set_description(Some(
+        "Starting content for files created from the sidebar, first matching pattern wins. ${name} and ${filename} become the new file's name without and with its extension.",
+    ));
+    let tracked_templates: Rc<RefCell<Vec<gtk4::Widget>>> = Rc::new(RefCell::new(Vec::new()));
+    let templates_generation: Rc<Cell<u64>> = Rc::new(Cell::new(0));
+    rebuild_templates_group(
+        &templates_group,
+        &tracked_templates,
+        settings,
+        &on_changed,
+        &templates_generation,
+    );
+    automation_page.add(&templates_group);
+
+    let formatters_group = adw::PreferencesGroup::new();
+    formatters_group.set_title("Formatters");
+    formatters_group.set_description(Some(
+        "Formatter used by Format Document. Automatic uses the language server, falling back to an installed formatter. Add custom formatters under \"formatters\" in settings.json.",
+    ));
+    let formatter_languages: Vec<String> = {
+        let mut languages: Vec<String> = Vec::new();
+        for formatter in impulse_core::formatter::registry(&settings.borrow().formatters) {
+            for language in formatter.languages {
+                if !languages.contains(&language) {
+                    languages.push(language);
+                }
+            }
+        }
+        languages
+    };
+    for language in formatter_languages {
+        // Index 0 is "Automatic" (no entry), 1 is language servers only.
+        let mut values = vec![
+            String::new(),
+            impulse_core::formatter::LSP_FORMATTER.to_string(),
+        ];
+        values.extend(impulse_core::formatter::formatters_for_language(
+            &settings.borrow(),
+            &language,
+        ));
+        let mut labels = vec!["Automatic", "Language Server Only"];
+        labels.extend(values[2..].iter().map(String::as_str));
+        let model = gtk4::StringList::new(&labels);
+
+        let current = settings
+            .borrow()
+            .default_formatters
+            .get(&language)
+            .cloned()
+            .unwrap_or_default();
+        let index = values.iter().position(|v| *v == current).unwrap_or(0) as u32;
+
+        let row = adw::ComboRow::new();
+        row.set_title(&language);
+        row.set_model(Some(&model));
+        row.set_selected(index);
+        {
+            let settings = Rc::clone(settings);
+            let on_changed = Rc::clone(&on_changed);
+            row.connect_selected_notify(move |row| {
+                let Some(value) = values.get(row.selected() as usize) else {
+                    return;
+                };
+                let mut s = settings.borrow_mut();
+                if value.is_empty() {
+                    s.default_formatters.remove(&language);
+                } else {
+                    s.default_formatters.insert(language.clone(), value.clone());
+                }
+                settings::save(&s);
+                on_changed(&s);
+            });
+        }
+        formatters_group.add(&row);
+    }
+    automation_page.add(&formatters_group);
+
     let commands_group = adw::PreferencesGroup::new();
     commands_group.set_title("Commands on Save");
     commands_group.set_description(Some("Shell commands that run after saving matching files"));
@@ -1349,6 +2767,72 @@ pub fn show_settings_window(
     keybindings_page.set_title("Keybindings");
     keybindings_page.set_icon_name(Some("preferences-desktop-keyboard-symbolic"));
 
+    // Built-in shortcut rows, refreshed when the keymap preset changes.
+    type ShortcutRow = (&'static str, gtk4::Button, adw::ActionRow);
+    let shortcut_rows: Rc<RefCell<Vec<ShortcutRow>>> = Rc::new(RefCell::new(Vec::new()));
+    let refresh_shortcut_rows: Rc<dyn Fn()> = {
+        let settings = Rc::clone(settings);
+        let shortcut_rows = Rc::clone(&shortcut_rows);
+        Rc::new(move || {
+            let s = settings.borrow();
+            let overrides = s.effective_keybinding_overrides();
+            for (id, btn, row) in shortcut_rows.borrow().iter() {
+                btn.set_label(&keybindings::accel_to_display(&keybindings::get_accel(
+                    id, &overrides,
+                )));
+                if s.keybinding_overrides.contains_key(*id) {
+                    row.set_subtitle(&format!(
+                        "Default: {}",
+                        keybindings::accel_to_display(&keybindings::preset_accel(
+                            id,
+                            &s.keymap_preset
+                        ))
+                    ));
+                } else {
+                    row.set_subtitle("");
+                }
+            }
+        })
+    };
+
+    let keymap_group = adw::PreferencesGroup::new();
+    keymap_group.set_title("Keymap");
+    let preset_labels: Vec<&str> = impulse_core::keymap::KEYMAP_PRESETS
+        .iter()
+        .map(|p| p.name)
+        .collect();
+    let preset_model = gtk4::StringList::new(&preset_labels);
+    let current_preset = settings.borrow().keymap_preset.clone();
+    let preset_index = impulse_core::keymap::KEYMAP_PRESETS
+        .iter()
+        .position(|p| p.id == current_preset)
+        .unwrap_or(0) as u32;
+
+    let preset_row = adw::ComboRow::new();
+    preset_row.set_title("Preset");
+    preset_row.set_subtitle("Shortcuts from another editor; your own changes still win");
+    preset_row.set_model(Some(&preset_model));
+    preset_row.set_selected(preset_index);
+    {
+        let settings = Rc::clone(settings);
+        let on_changed = Rc::clone(&on_changed);
+        let refresh_shortcut_rows = Rc::clone(&refresh_shortcut_rows);
+        preset_row.connect_selected_notify(move |row| {
+            let idx = row.selected() as usize;
+            if let Some(preset) = impulse_core::keymap::KEYMAP_PRESETS.get(idx) {
+                {
+                    let mut s = settings.borrow_mut();
+                    s.keymap_preset = preset.id.to_string();
+                    settings::save(&s);
+                    on_changed(&s);
+                }
+                refresh_shortcut_rows();
+            }
+        });
+    }
+    keymap_group.add(&preset_row);
+    keybindings_page.add(&keymap_group);
+
     // Built-in shortcuts group
     let builtin_group = adw::PreferencesGroup::new();
     builtin_group.set_title("Built-in Shortcuts");
@@ -1364,38 +2848,31 @@ pub fn show_settings_window(
         header.add_css_class("heading");
         builtin_group.add(&header);
 
-        for kb in keybindings::BUILTIN_KEYBINDINGS
+        for kb in keybindings::builtin_keybindings()
             .iter()
             .filter(|kb| kb.category == *category)
         {
-            let overrides = settings.borrow().keybinding_overrides.clone();
-            let current_accel = keybindings::get_accel(kb.id, &overrides);
-            let display_text = keybindings::accel_to_display(&current_accel);
-            let is_overridden = overrides.contains_key(kb.id);
-
             let row = adw::ActionRow::new();
             row.set_title(kb.description);
-            if is_overridden {
-                row.set_subtitle(&format!(
-                    "Default: {}",
-                    keybindings::accel_to_display(kb.default_accel)
-                ));
-            }
 
-            let btn = gtk4::Button::with_label(&display_text);
+            let btn = gtk4::Button::new();
             btn.set_valign(gtk4::Align::Center);
             btn.add_css_class("flat");
             row.add_suffix(&btn);
+            shortcut_rows
+                .borrow_mut()
+                .push((kb.id, btn.clone(), row.clone()));
 
             {
                 let settings = Rc::clone(settings);
                 let on_changed = Rc::clone(&on_changed);
                 let kb_id = kb.id.to_string();
-                let kb_default = kb.default_accel.to_string();
                 let kb_desc = kb.description.to_string();
                 let row = row.clone();
                 let preferences_window_ref = preferences_window.clone();
                 btn.connect_clicked(move |btn| {
+                    let kb_default =
+                        keybindings::preset_accel(&kb_id, &settings.borrow().keymap_preset);
                     show_key_capture_dialog(
                         &preferences_window_ref,
                         &kb_desc,
@@ -1412,9 +2889,59 @@ pub fn show_settings_window(
             builtin_group.add(&row);
         }
     }
+    refresh_shortcut_rows();
 
     keybindings_page.add(&builtin_group);
 
+    // Monaco editor actions, rebound through the same overrides map
+    let editor_actions_group = adw::PreferencesGroup::new();
+    editor_actions_group.set_title("Editor Actions");
+    editor_actions_group.set_description(Some(
+        "Shortcuts handled inside the code editor. Changes apply immediately.",
+    ));
+    for action in impulse_editor::keybindings::EDITOR_ACTIONS {
+        let default = action.default_shortcut();
+        let current = settings
+            .borrow()
+            .keybinding_overrides
+            .get(action.id)
+            .cloned()
+            .filter(|k| !k.is_empty());
+
+        let row = adw::ActionRow::new();
+        row.set_title(action.description);
+        if current.is_some() {
+            row.set_subtitle(&format!("Default: {default}"));
+        }
+
+        let btn = gtk4::Button::with_label(current.as_deref().unwrap_or(default));
+        btn.set_valign(gtk4::Align::Center);
+        btn.add_css_class("flat");
+        row.add_suffix(&btn);
+
+        {
+            let settings = Rc::clone(settings);
+            let on_changed = Rc::clone(&on_changed);
+            let row = row.clone();
+            let preferences_window_ref = preferences_window.clone();
+            btn.connect_clicked(move |btn| {
+                show_key_capture_dialog(
+                    &preferences_window_ref,
+                    action.description,
+                    action.id,
+                    default,
+                    btn,
+                    &row,
+                    &settings,
+                    &on_changed,
+                );
+            });
+        }
+
+        editor_actions_group.add(&row);
+    }
+    keybindings_page.add(&editor_actions_group);
+
     // Custom keybindings group
     let custom_kb_group = adw::PreferencesGroup::new();
     custom_kb_group.set_title("Custom Keybindings");
@@ -1457,123 +2984,107 @@ pub fn show_settings_window(
     npm_group.add(&npm_row);
     lsp_page.add(&npm_group);
 
-    // -- Managed Web Language Servers group --
+    // -- Managed Language Servers group --
     let managed_group = adw::PreferencesGroup::new();
-    managed_group.set_title("Managed Web Language Servers");
-    managed_group.set_description(Some("Installed and managed by Impulse via npm."));
-
-    // Helper: rebuild managed server rows from fresh data
-    fn rebuild_managed_lsp_rows(
-        group: &adw::PreferencesGroup,
-        tracked: &Rc<RefCell<Vec<gtk4::Widget>>>,
-    ) {
-        let mut rows = tracked.borrow_mut();
-        for row in rows.drain(..) {
-            group.remove(&row);
-        }
-        let statuses = impulse_core::lsp::managed_web_lsp_status();
-        for status in &statuses {
-            let row = adw::ActionRow::new();
-            row.set_title(&status.command);
-            if let Some(ref path) = status.resolved_path {
-                row.set_subtitle(&path.to_string_lossy());
-                let icon = gtk4::Image::from_icon_name("emblem-ok-symbolic");
-                icon.set_valign(gtk4::Align::Center);
-                row.add_suffix(&icon);
-            } else {
-                row.set_subtitle("Not installed");
-                let icon = gtk4::Image::from_icon_name("window-close-symbolic");
-                icon.set_valign(gtk4::Align::Center);
-                row.add_suffix(&icon);
-            }
-            group.add(&row);
-            rows.push(row.upcast());
-        }
-    }
-
-    let tracked_managed: Rc<RefCell<Vec<gtk4::Widget>>> = Rc::new(RefCell::new(Vec::new()));
-    rebuild_managed_lsp_rows(&managed_group, &tracked_managed);
+    managed_group.set_title("Managed Language Servers");
+    managed_group.set_description(Some(
+        "Installed and managed by Impulse at versions pinned by this release: web servers via npm, native servers as verified release binaries.",
+    ));
 
-    // Install button row
+    let npm_available = impulse_core::lsp::npm_is_available();
     let install_row = adw::ActionRow::new();
-    install_row.set_title("Install All Web Language Servers");
-    install_row.set_subtitle("Downloads and installs via npm");
+    install_row.set_title("Install All Language Servers");
+    install_row.set_subtitle(MANAGED_LSP_INSTALL_SUBTITLE);
 
     let install_spinner = gtk4::Spinner::new();
     install_spinner.set_visible(false);
     install_spinner.set_valign(gtk4::Align::Center);
 
+    let update_button = gtk4::Button::with_label("Update");
+    update_button.set_valign(gtk4::Align::Center);
     let install_button = gtk4::Button::with_label("Install");
     install_button.set_valign(gtk4::Align::Center);
     install_button.add_css_class("suggested-action");
-    if !impulse_core::lsp::npm_is_available() {
-        install_button.set_sensitive(false);
-        install_button.set_tooltip_text(Some("npm is not available"));
+    if !npm_available {
+        install_button.set_tooltip_text(Some(
+            "npm is not available; only native servers will be installed",
+        ));
     }
 
     install_row.add_suffix(&install_spinner);
+    install_row.add_suffix(&update_button);
     install_row.add_suffix(&install_button);
     managed_group.add(&install_row);
 
+    let managed_section = Rc::new(ManagedLspSection {
+        group: managed_group.clone(),
+        rows: RefCell::new(Vec::new()),
+        install_row: install_row.clone(),
+        spinner: install_spinner,
+        install_button: install_button.clone(),
+        update_button: update_button.clone(),
+        busy: Cell::new(false),
+        npm_available,
+        settings: Rc::clone(settings),
+        window: preferences_window.downgrade(),
+    });
+    managed_section.rebuild();
     {
-        let managed_group = managed_group.clone();
-        let tracked_managed = Rc::clone(&tracked_managed);
-        let install_spinner = install_spinner.clone();
-        let install_button = install_button.clone();
-        let preferences_window_weak = preferences_window.downgrade();
-        install_button.connect_clicked(move |btn| {
-            btn.set_sensitive(false);
-            install_spinner.set_visible(true);
-            install_spinner.set_spinning(true);
-
-            let (tx, rx) = std::sync::mpsc::channel::<Result<std::path::PathBuf, String>>();
+        let section = Rc::clone(&managed_section);
+        install_button.connect_clicked(move |_| {
+            section.run(
+                impulse_core::lsp_install::InstallAction::Install,
+                Vec::new(),
+            );
+        });
+    }
+    {
+        let section = Rc::clone(&managed_section);
+        update_button.connect_clicked(move |_| {
+            section.run(impulse_core::lsp_install::InstallAction::Update, Vec::new());
+        });
+    }
 
-            std::thread::spawn(move || {
-                let result = impulse_core::lsp::install_managed_web_lsp_servers();
-                let _ = tx.send(result);
-            });
+    lsp_page.add(&managed_group);
 
-            let managed_group = managed_group.clone();
-            let tracked_managed = Rc::clone(&tracked_managed);
-            let install_spinner = install_spinner.clone();
-            let btn = btn.clone();
-            let preferences_window_weak = preferences_window_weak.clone();
-            glib::timeout_add_local(std::time::Duration::from_millis(200), move || {
-                match rx.try_recv() {
-                    Ok(result) => {
-                        install_spinner.set_spinning(false);
-                        install_spinner.set_visible(false);
-                        btn.set_sensitive(true);
-
-                        rebuild_managed_lsp_rows(&managed_group, &tracked_managed);
-
-                        if let Some(win) = preferences_window_weak.upgrade() {
-                            let toast = match result {
-                                Ok(_) => adw::Toast::new("Language servers installed successfully"),
-                                Err(ref e) => adw::Toast::new(&format!("Install failed: {e}")),
-                            };
-                            win.add_toast(toast);
-                        }
-                        glib::ControlFlow::Break
-                    }
-                    Err(std::sync::mpsc::TryRecvError::Empty) => {
-                        if preferences_window_weak.upgrade().is_none() {
-                            return glib::ControlFlow::Break;
-                        }
-                        glib::ControlFlow::Continue
-                    }
-                    Err(std::sync::mpsc::TryRecvError::Disconnected) => {
-                        install_spinner.set_spinning(false);
-                        install_spinner.set_visible(false);
-                        btn.set_sensitive(true);
-                        glib::ControlFlow::Break
-                    }
+    // -- Enabled servers group --
+    let enabled_group = adw::PreferencesGroup::new();
+    enabled_group.set_title("Enabled Servers");
+    enabled_group.set_description(Some(
+        "Disabled servers are never started, and their managed packages are not installed.",
+    ));
+    let server_ids: Vec<String> = impulse_core::lsp::system_lsp_status()
+        .into_iter()
+        .map(|status| status.command)
+        .chain(
+            impulse_core::lsp_install::MANAGED_PACKAGES
+                .iter()
+                .flat_map(|package| package.servers.iter().map(|id| id.to_string())),
+        )
+        .collect();
+    for server_id in server_ids {
+        let row = adw::SwitchRow::new();
+        row.set_title(&server_id);
+        row.set_active(!settings.borrow().disabled_lsp_servers.contains(&server_id));
+        let settings = Rc::clone(settings);
+        let on_changed = Rc::clone(&on_changed);
+        let section = Rc::clone(&managed_section);
+        row.connect_active_notify(move |row| {
+            {
+                let mut s = settings.borrow_mut();
+                s.disabled_lsp_servers.retain(|id| *id != server_id);
+                if !row.is_active() {
+                    s.disabled_lsp_servers.push(server_id.clone());
                 }
-            });
+                settings::save(&s);
+                on_changed(&s);
+            }
+            section.rebuild();
         });
+        enabled_group.add(&row);
     }
 
-    lsp_page.add(&managed_group);
+    lsp_page.add(&enabled_group);
 
     // -- System Language Servers group --
     let system_group = adw::PreferencesGroup::new();
@@ -1605,6 +3116,174 @@ pub fn show_settings_window(
     preferences_window.present();
 }
 
+const MANAGED_LSP_INSTALL_SUBTITLE: &str = "Downloads and installs enabled servers";
+
+/// The managed language server rows of the Language Servers page:
+/// installed and pinned versions, per-package install, update and
+/// uninstall, and installer progress while an action runs.
+struct ManagedLspSection {
+    group: adw::PreferencesGroup,
+    rows: RefCell<Vec<gtk4::Widget>>,
+    install_row: adw::ActionRow,
+    spinner: gtk4::Spinner,
+    install_button: gtk4::Button,
+    update_button: gtk4::Button,
+    busy: Cell<bool>,
+    npm_available: bool,
+    settings: Rc<RefCell<Settings>>,
+    window: glib::WeakRef<adw::PreferencesWindow>,
+}
+
+impl ManagedLspSection {
+    fn rebuild(self: &Rc<Self>) {
+        let mut rows = self.rows.borrow_mut();
+        for row in rows.drain(..) {
+            self.group.remove(&row);
+        }
+        let disabled = self.settings.borrow().disabled_lsp_servers.clone();
+        let statuses = impulse_core::lsp_install::package_status(&disabled);
+        let idle = !self.busy.get();
+        self.install_button.set_sensitive(idle);
+        self.update_button
+            .set_sensitive(idle && statuses.iter().any(|s| s.enabled && s.needs_update()));
+
+        for status in statuses {
+            let row = adw::ActionRow::new();
+            row.set_title(&status.name);
+            // Per-row actions on npm packages need npm; binaries do not.
+            let idle = idle
+                && (self.npm_available
+                    || status.kind == impulse_core::lsp_install::PackageKind::Binary);
+            let mut subtitle = match &status.installed_version {
+                Some(version) if status.needs_update() => {
+                    format!(
+                        "{} installed — update to {}",
+                        version, status.pinned_version
+                    )
+                }
+                Some(version) => version.clone(),
+                None if status.unsupported => "No prebuilt binary for this platform".to_string(),
+                None if status.on_path => "Using the version found in PATH".to_string(),
+                None => format!("Not installed — pinned {}", status.pinned_version),
+            };
+            if !status.enabled {
+                subtitle.push_str(" (disabled)");
+            }
+            row.set_subtitle(&subtitle);
+
+            if status.enabled && status.needs_update() {
+                let button = gtk4::Button::with_label("Update");
+                button.set_valign(gtk4::Align::Center);
+                button.set_sensitive(idle);
+                let section = Rc::clone(self);
+                let name = status.name.clone();
+                button.connect_clicked(move |_| {
+                    section.run(
+                        impulse_core::lsp_install::InstallAction::Install,
+                        vec![name.clone()],
+                    );
+                });
+                row.add_suffix(&button);
+            }
+            if status.installed_version.is_some() {
+                let button = gtk4::Button::with_label("Uninstall");
+                button.set_valign(gtk4::Align::Center);
+                button.add_css_class("flat");
+                button.set_sensitive(idle);
+                let section = Rc::clone(self);
+                let name = status.name.clone();
+                button.connect_clicked(move |_| {
+                    section.run(
+                        impulse_core::lsp_install::InstallAction::Uninstall,
+                        vec![name.clone()],
+                    );
+                });
+                row.add_suffix(&button);
+            } else if status.enabled && !status.unsupported {
+                let button = gtk4::Button::with_label("Install");
+                button.set_valign(gtk4::Align::Center);
+                button.add_css_class("flat");
+                button.set_sensitive(idle);
+                let section = Rc::clone(self);
+                let name = status.name.clone();
+                button.connect_clicked(move |_| {
+                    section.run(
+                        impulse_core::lsp_install::InstallAction::Install,
+                        vec![name.clone()],
+                    );
+                });
+                row.add_suffix(&button);
+            } else {
+                let icon = gtk4::Image::from_icon_name("window-close-symbolic");
+                icon.set_valign(gtk4::Align::Center);
+                row.add_suffix(&icon);
+            }
+            self.group.add(&row);
+            rows.push(row.upcast());
+        }
+    }
+
+    /// Run an installer action in the background, showing npm output and
+    /// download progress in the install row until it finishes.
+    fn run(
+        self: &Rc<Self>,
+        action: impulse_core::lsp_install::InstallAction,
+        packages: Vec<String>,
+    ) {
+        if self.busy.replace(true) {
+            return;
+        }
+        self.spinner.set_visible(true);
+        self.spinner.set_spinning(true);
+        self.rebuild();
+
+        let disabled = self.settings.borrow().disabled_lsp_servers.clone();
+        let rx = impulse_core::lsp_install::spawn(action, packages, disabled);
+        let section = Rc::clone(self);
+        glib::timeout_add_local(std::time::Duration::from_millis(200), move || {
+            if section.window.upgrade().is_none() {
+                return glib::ControlFlow::Break;
+            }
+            loop {
+                match rx.try_recv() {
+                    Ok(impulse_core::lsp_install::InstallEvent::Started { packages, .. }) => {
+                        section.install_row.set_subtitle(&packages.join(", "));
+                    }
+                    Ok(impulse_core::lsp_install::InstallEvent::Output { line }) => {
+                        section.install_row.set_subtitle(&line);
+                    }
+                    Ok(impulse_core::lsp_install::InstallEvent::Finished {
+                        success,
+                        message,
+                        ..
+                    }) => {
+                        let text = if success {
+                            message
+                        } else {
+                            format!("Language server {} failed: {}", action.verb(), message)
+                        };
+                        if let Some(win) = section.window.upgrade() {
+                            win.add_toast(adw::Toast::new(&text));
+                        }
+                    }
+                    Err(std::sync::mpsc::TryRecvError::Empty) => {
+                        return glib::ControlFlow::Continue;
+                    }
+                    Err(std::sync::mpsc::TryRecvError::Disconnected) => break,
+                }
+            }
+            section.busy.set(false);
+            section.spinner.set_spinning(false);
+            section.spinner.set_visible(false);
+            section
+                .install_row
+                .set_subtitle(MANAGED_LSP_INSTALL_SUBTITLE);
+            section.rebuild();
+            glib::ControlFlow::Break
+        });
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 fn show_key_capture_dialog(
     parent: &adw::PreferencesWindow,
@@ -1632,7 +3311,7 @@ fn show_key_capture_dialog(
     vbox.set_margin_end(24);
 
     let label = gtk4::Label::new(Some(&format!(
-        "Press a key combination for \"{}\"\nPress Escape to cancel, Backspace to reset",
+        "Press a key combination for \"{}\"\nFollow it with a second combination for a chord\nPress Escape to cancel, Backspace to reset",
         description
     )));
     label.set_halign(gtk4::Align::Center);
@@ -1640,9 +3319,40 @@ fn show_key_capture_dialog(
 
     dialog.set_child(Some(&vbox));
 
+    // Store the captured shortcut (one combination or a two-step chord).
+    let commit: Rc<dyn Fn(&str)> = {
+        let dialog = dialog.clone();
+        let kb_id = kb_id.to_string();
+        let kb_default = kb_default.to_string();
+        let btn = btn.clone();
+        let row = row.clone();
+        let settings = Rc::clone(settings);
+        let on_changed = Rc::clone(on_changed);
+        Rc::new(move |display_str: &str| {
+            {
+                let mut s = settings.borrow_mut();
+                s.keybinding_overrides
+                    .insert(kb_id.clone(), display_str.to_string());
+                settings::save(&s);
+                on_changed(&s);
+            }
+            btn.set_label(display_str);
+            row.set_subtitle(&format!(
+                "Default: {}",
+                keybindings::accel_to_display(&kb_default)
+            ));
+            dialog.close();
+        })
+    };
+    // The first combination waits briefly for a second one before being
+    // committed on its own; the generation invalidates stale timeouts.
+    let first_step: Rc<RefCell<Option<String>>> = Rc::new(RefCell::new(None));
+    let generation = Rc::new(std::cell::Cell::new(0u64));
+
     let key_controller = gtk4::EventControllerKey::new();
     {
         let dialog = dialog.clone();
+        let label = label.clone();
         let kb_id = kb_id.to_string();
         let kb_default = kb_default.to_string();
         let btn = btn.clone();
@@ -1652,12 +3362,14 @@ fn show_key_capture_dialog(
         key_controller.connect_key_pressed(move |_, key, _keycode, modifiers| {
             // Escape cancels
             if key == gtk4::gdk::Key::Escape {
+                generation.set(generation.get() + 1);
                 dialog.close();
                 return gtk4::glib::Propagation::Stop;
             }
 
             // Backspace/Delete resets to default
             if key == gtk4::gdk::Key::BackSpace || key == gtk4::gdk::Key::Delete {
+                generation.set(generation.get() + 1);
                 {
                     let mut s = settings.borrow_mut();
                     s.keybinding_overrides.remove(&kb_id);
@@ -1710,20 +3422,27 @@ fn show_key_capture_dialog(
 
             let display_str = parts.join("+");
 
-            // Store the override
-            {
-                let mut s = settings.borrow_mut();
-                s.keybinding_overrides
-                    .insert(kb_id.clone(), display_str.clone());
-                settings::save(&s);
-                on_changed(&s);
+            generation.set(generation.get() + 1);
+            if let Some(first) = first_step.borrow_mut().take() {
+                commit(&format!("{} {}", first, display_str));
+                return gtk4::glib::Propagation::Stop;
             }
-            btn.set_label(&display_str);
-            row.set_subtitle(&format!(
-                "Default: {}",
-                keybindings::accel_to_display(&kb_default)
+
+            label.set_text(&format!(
+                "{}\nPress a second combination for a chord",
+                display_str
             ));
-            dialog.close();
+            *first_step.borrow_mut() = Some(display_str.clone());
+            let expected = generation.get();
+            let generation = generation.clone();
+            let first_step = first_step.clone();
+            let commit = commit.clone();
+            gtk4::glib::timeout_add_local_once(std::time::Duration::from_millis(1000), move || {
+                if generation.get() == expected {
+                    first_step.borrow_mut().take();
+                    commit(&display_str);
+                }
+            });
             gtk4::glib::Propagation::Stop
         });
     }
@@ -0,0 +1,173 @@
+//! Live reload of `settings.json` when it changes outside Impulse (by hand,
+//! in another editor, or by a dotfiles manager). Each window registers how
+//! it applies settings; one watcher per process checks the file and applies
+//! the result to every window (see `impulse_core::settings::check_external_change`).
+
+use std::cell::RefCell;
+use std::rc::{Rc, Weak};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use gtk4::glib;
+use gtk4::prelude::*;
+use impulse_core::notifications::{Notification, Severity};
+use impulse_core::settings::ExternalChange;
+use libadwaita as adw;
+use libadwaita::prelude::*;
+
+use crate::settings::Settings;
+
+type ApplySettings = Rc<dyn Fn(&Settings)>;
+
+struct WindowSettings {
+    window: glib::WeakRef<adw::ApplicationWindow>,
+    settings: Weak<RefCell<Settings>>,
+    apply: ApplySettings,
+}
+
+thread_local! {
+    static WINDOWS: RefCell<Vec<WindowSettings>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Apply reloaded settings to `window` through `apply`.
+pub fn register(
+    window: &adw::ApplicationWindow,
+    settings: &Rc<RefCell<Settings>>,
+    apply: ApplySettings,
+) {
+    WINDOWS.with(|windows| {
+        let mut windows = windows.borrow_mut();
+        windows.retain(|w| w.window.upgrade().is_some());
+        windows.push(WindowSettings {
+            window: window.downgrade(),
+            settings: Rc::downgrade(settings),
+            apply,
+        });
+    });
+}
+
+/// Start watching the settings file for the life of the process. Events
+/// are coalesced and checked on the main loop.
+pub fn start() {
+    let dirty = Arc::new(AtomicBool::new(false));
+    let watcher = {
+        let dirty = dirty.clone();
+        match impulse_core::settings::watch(move || dirty.store(true, Ordering::Relaxed)) {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                log::warn!("{}", e);
+                return;
+            }
+        }
+    };
+    glib::timeout_add_local(std::time::Duration::from_millis(500), move || {
+        let _keep_alive = &watcher;
+        if dirty.swap(false, Ordering::Relaxed) {
+            check(true);
+        }
+        glib::ControlFlow::Continue
+    });
+}
+
+/// Apply the settings file if it changed outside Impulse. `announce`
+/// records a notification for a reload; callers that wrote the file
+/// themselves (import, sync) report it their own way.
+pub fn check(announce: bool) {
+    match impulse_core::settings::check_external_change() {
+        ExternalChange::Unchanged => {}
+        ExternalChange::Reloaded(settings) => {
+            apply_all(&settings);
+            if announce {
+                crate::notifications::record(
+                    Notification::new(Severity::Info, "Settings reloaded")
+                        .message("settings.json changed on disk.")
+                        .source("settings"),
+                );
+            }
+        }
+        ExternalChange::Invalid(message) => crate::notifications::record(
+            Notification::new(Severity::Warning, "settings.json has errors")
+                .message(format!(
+                    "{}. The previous settings stay in effect.",
+                    message
+                ))
+                .action("Open Settings", "open_settings")
+                .source("settings"),
+        ),
+        ExternalChange::Conflict { .. } => show_conflict_dialog(),
+    }
+}
+
+/// Both the file and unsaved in-app changes exist: ask which to keep.
+fn show_conflict_dialog() {
+    let windows: Vec<adw::ApplicationWindow> = WINDOWS.with(|w| {
+        w.borrow()
+            .iter()
+            .filter_map(|w| w.window.upgrade())
+            .collect()
+    });
+    let Some(parent) = windows
+        .iter()
+        .find(|w| w.is_active())
+        .or_else(|| windows.first())
+    else {
+        return;
+    };
+    let dialog = adw::AlertDialog::builder()
+        .heading("Settings Changed on Disk")
+        .body(
+            "settings.json was changed outside Impulse while Impulse had settings changes \
+             it couldn't save. Which version do you want to keep?",
+        )
+        .build();
+    dialog.add_response("file", "Use File Version");
+    dialog.add_response("app", "Keep Impulse Settings");
+    dialog.set_response_appearance("app", adw::ResponseAppearance::Suggested);
+    dialog.set_default_response(Some("file"));
+    dialog.set_close_response("file");
+    dialog.connect_response(
+        None,
+        |_dialog, response| match impulse_core::settings::resolve_conflict(response == "app") {
+            Ok(settings) => apply_all(&settings),
+            Err(e) => crate::notifications::record(
+                Notification::new(Severity::Error, "Couldn't save settings")
+                    .message(e)
+                    .source("settings"),
+            ),
+        },
+    );
+    dialog.present(Some(parent));
+}
+
+/// Replace every window's settings with `settings` and apply them.
+pub fn apply_all(settings: &Settings) {
+    let windows: Vec<(Rc<RefCell<Settings>>, ApplySettings)> = WINDOWS.with(|windows| {
+        windows
+            .borrow()
+            .iter()
+            .filter(|w| w.window.upgrade().is_some())
+            .filter_map(|w| Some((w.settings.upgrade()?, w.apply.clone())))
+            .collect()
+    });
+    let keybindings_changed = windows.first().is_some_and(|(current, _)| {
+        let current = current.borrow();
+        current.effective_keybinding_overrides() != settings.effective_keybinding_overrides()
+            || current.custom_keybindings != settings.custom_keybindings
+    });
+
+    impulse_core::trust::set_enabled(settings.workspace_trust_enabled);
+    impulse_core::languages::set_overrides(&settings.language_overrides);
+    impulse_core::environment::set_direnv_enabled(settings.use_direnv);
+    for (current, apply) in windows {
+        *current.borrow_mut() = settings.clone();
+        apply(settings);
+    }
+
+    if keybindings_changed {
+        crate::notifications::record(
+            Notification::new(Severity::Info, "Keybindings changed")
+                .message("Keybinding changes take effect after restarting Impulse.")
+                .source("settings"),
+        );
+    }
+}
@@ -16,7 +16,16 @@ use crate::project_search;
 use crate::settings;
 use crate::theme::ThemeColors;
 
+/// The path-copying context menu actions and the command each runs.
+pub(crate) const COPY_PATH_ACTIONS: [(&str, &str); 4] = [
+    ("copy-path", "copy_path"),
+    ("copy-relative-path", "copy_relative_path"),
+    ("copy-file-name", "copy_file_name"),
+    ("copy-file-url", "copy_file_url"),
+];
+
 type EventCallback = Rc<RefCell<Option<Box<dyn Fn(&str)>>>>;
+type CompareCallback = Rc<RefCell<Option<Box<dyn Fn(&str, &str)>>>>;
 type PendingFileTreeEvents = Arc<Mutex<Vec<impulse_core::file_tree::FileTreeWatchEvent>>>;
 use impulse_core::filesystem::FileEntry;
 
@@ -115,6 +124,14 @@ pub fn build_sidebar(
         }
     }
 
+    let ignored_btn = gtk4::ToggleButton::new();
+    ignored_btn.set_tooltip_text(Some("Hide Gitignored Files"));
+    ignored_btn.set_active(settings.borrow().sidebar_hide_ignored);
+    ignored_btn.set_icon_name("view-conceal-symbolic");
+    ignored_btn.set_cursor_from_name(Some("pointer"));
+    ignored_btn.add_css_class("flat");
+    ignored_btn.add_css_class("sidebar-toolbar-btn");
+
     let refresh_btn = gtk4::Button::new();
     refresh_btn.set_tooltip_text(Some("Refresh File Tree"));
     refresh_btn.set_cursor_from_name(Some("pointer"));
@@ -138,6 +155,7 @@ pub fn build_sidebar(
     toolbar_box.append(&new_folder_btn);
     toolbar_box.append(&search_btn);
     toolbar_box.append(&hidden_btn);
+    toolbar_box.append(&ignored_btn);
     toolbar_box.append(&refresh_btn);
     toolbar_box.append(&collapse_btn);
     header_box.append(&toolbar_box);
@@ -148,6 +166,29 @@ pub fn build_sidebar(
     let file_tree_list = gtk4::ListBox::new();
     file_tree_list.set_selection_mode(gtk4::SelectionMode::Single);
     file_tree_list.add_css_class("file-tree");
+    file_tree_list.update_property(&[gtk4::accessible::Property::Label("Project files")]);
+    // Gitignored rows stay in the list (row indices mirror `tree_nodes`) and
+    // are filtered out of view when the toggle is on.
+    let hide_ignored = Rc::new(Cell::new(settings.borrow().sidebar_hide_ignored));
+    {
+        let hide_ignored = hide_ignored.clone();
+        file_tree_list.set_filter_func(move |row| {
+            !hide_ignored.get()
+                || !row
+                    .child()
+                    .is_some_and(|child| child.has_css_class("file-entry-ignored"))
+        });
+    }
+    {
+        let settings = settings.clone();
+        let file_tree_list = file_tree_list.clone();
+        ignored_btn.connect_toggled(move |btn| {
+            let active = btn.is_active();
+            hide_ignored.set(active);
+            settings.borrow_mut().sidebar_hide_ignored = active;
+            file_tree_list.invalidate_filter();
+        });
+    }
     file_tree_scroll.set_child(Some(&file_tree_list));
     stack.add_named(&file_tree_scroll, Some("files"));
 
@@ -161,44 +202,90 @@ pub fn build_sidebar(
     // --- Right-click context menu for file tree ---
     let clicked_path: Rc<RefCell<String>> = Rc::new(RefCell::new(String::new()));
     let on_open_terminal: EventCallback = Rc::new(RefCell::new(None));
+    let on_file_activated: EventCallback = Rc::new(RefCell::new(None));
+    let on_compare_files: CompareCallback = Rc::new(RefCell::new(None));
+    let on_show_properties: EventCallback = Rc::new(RefCell::new(None));
+    // The file picked with "Select for Compare", the left side of the next
+    // "Compare with Selected".
+    let compare_selection: Rc<RefCell<Option<String>>> = Rc::new(RefCell::new(None));
 
     // Menu models: one for files, one for directories
     let file_menu = gio::Menu::new();
     file_menu.append(Some("Open in Default App"), Some("filetree.open"));
+    file_menu.append(
+        Some("Open Containing Folder"),
+        Some("filetree.open-containing-folder"),
+    );
     file_menu.append(Some("Copy Path"), Some("filetree.copy-path"));
     file_menu.append(
         Some("Copy Relative Path"),
         Some("filetree.copy-relative-path"),
     );
+    file_menu.append(Some("Copy File Name"), Some("filetree.copy-file-name"));
+    file_menu.append(Some("Copy File URL"), Some("filetree.copy-file-url"));
+    file_menu.append(
+        Some("Select for Compare"),
+        Some("filetree.select-for-compare"),
+    );
+    file_menu.append(
+        Some("Compare with Selected"),
+        Some("filetree.compare-with-selected"),
+    );
     file_menu.append(Some("New File"), Some("filetree.new-file"));
     file_menu.append(Some("New Folder"), Some("filetree.new-folder"));
     file_menu.append(Some("Rename"), Some("filetree.rename"));
+    file_menu.append(Some("Duplicate"), Some("filetree.duplicate"));
     file_menu.append(Some("Delete"), Some("filetree.delete"));
+    file_menu.append(Some("Properties"), Some("filetree.properties"));
 
     let file_menu_git = gio::Menu::new();
     file_menu_git.append(Some("Open in Default App"), Some("filetree.open"));
+    file_menu_git.append(
+        Some("Open Containing Folder"),
+        Some("filetree.open-containing-folder"),
+    );
     file_menu_git.append(Some("Copy Path"), Some("filetree.copy-path"));
     file_menu_git.append(
         Some("Copy Relative Path"),
         Some("filetree.copy-relative-path"),
     );
+    file_menu_git.append(Some("Copy File Name"), Some("filetree.copy-file-name"));
+    file_menu_git.append(Some("Copy File URL"), Some("filetree.copy-file-url"));
+    file_menu_git.append(
+        Some("Select for Compare"),
+        Some("filetree.select-for-compare"),
+    );
+    file_menu_git.append(
+        Some("Compare with Selected"),
+        Some("filetree.compare-with-selected"),
+    );
     file_menu_git.append(Some("New File"), Some("filetree.new-file"));
     file_menu_git.append(Some("New Folder"), Some("filetree.new-folder"));
     file_menu_git.append(Some("Rename"), Some("filetree.rename"));
+    file_menu_git.append(Some("Duplicate"), Some("filetree.duplicate"));
     file_menu_git.append(Some("Delete"), Some("filetree.delete"));
+    file_menu_git.append(Some("Properties"), Some("filetree.properties"));
     file_menu_git.append(Some("Discard Changes"), Some("filetree.discard-changes"));
 
     let dir_menu = gio::Menu::new();
     dir_menu.append(Some("Open in Terminal"), Some("filetree.open-terminal"));
+    dir_menu.append(
+        Some("Open Containing Folder"),
+        Some("filetree.open-containing-folder"),
+    );
     dir_menu.append(Some("Copy Path"), Some("filetree.copy-path"));
     dir_menu.append(
         Some("Copy Relative Path"),
         Some("filetree.copy-relative-path"),
     );
+    dir_menu.append(Some("Copy File Name"), Some("filetree.copy-file-name"));
+    dir_menu.append(Some("Copy File URL"), Some("filetree.copy-file-url"));
     dir_menu.append(Some("New File"), Some("filetree.new-file"));
     dir_menu.append(Some("New Folder"), Some("filetree.new-folder"));
     dir_menu.append(Some("Rename"), Some("filetree.rename"));
+    dir_menu.append(Some("Duplicate"), Some("filetree.duplicate"));
     dir_menu.append(Some("Delete"), Some("filetree.delete"));
+    dir_menu.append(Some("Properties"), Some("filetree.properties"));
 
     // Create popover menu
     let popover = gtk4::PopoverMenu::from_model(Some(&file_menu));
@@ -226,48 +313,88 @@ pub fn build_sidebar(
     }
     action_group.add_action(&open_action);
 
-    // "copy-path" action - copies path to clipboard
-    let copy_action = gio::SimpleAction::new("copy-path", None);
+    // "open-containing-folder" action - shows the item in the file manager
+    let show_action = gio::SimpleAction::new("open-containing-folder", None);
     {
         let clicked_path = clicked_path.clone();
-        let list_ref = file_tree_list.clone();
-        copy_action.connect_activate(move |_, _| {
+        show_action.connect_activate(move |_, _| {
             let path = clicked_path.borrow().clone();
             if !path.is_empty() {
-                list_ref.clipboard().set_text(&path);
+                crate::file_manager::show_item(&path);
             }
         });
     }
-    action_group.add_action(&copy_action);
+    action_group.add_action(&show_action);
 
-    // "copy-relative-path" action - copies relative path to clipboard
-    let copy_rel_action = gio::SimpleAction::new("copy-relative-path", None);
-    {
+    // "copy-path", "copy-relative-path", "copy-file-name" and
+    // "copy-file-url" actions - copy the path in that form to the clipboard
+    for (name, command_id) in COPY_PATH_ACTIONS {
+        let action = gio::SimpleAction::new(name, None);
         let clicked_path = clicked_path.clone();
         let current_path = current_path.clone();
         let list_ref = file_tree_list.clone();
-        copy_rel_action.connect_activate(move |_, _| {
-            let abs_path = clicked_path.borrow().clone();
-            if !abs_path.is_empty() {
-                let root = current_path.borrow().clone();
-                let relative = if !root.is_empty() {
-                    let root_with_sep = if root.ends_with('/') {
-                        root.clone()
-                    } else {
-                        format!("{}/", root)
-                    };
-                    abs_path
-                        .strip_prefix(&root_with_sep)
-                        .unwrap_or(&abs_path)
-                        .to_string()
-                } else {
-                    abs_path.clone()
-                };
-                list_ref.clipboard().set_text(&relative);
+        action.connect_activate(move |_, _| {
+            let path = clicked_path.borrow().clone();
+            if path.is_empty() {
+                return;
+            }
+            let root = current_path.borrow().clone();
+            if let Some(text) = impulse_core::util::path_for_copy(command_id, &path, &root) {
+                list_ref.clipboard().set_text(&text);
+            }
+        });
+        action_group.add_action(&action);
+    }
+
+    // "select-for-compare" / "compare-with-selected" - diff two files
+    let select_for_compare_action = gio::SimpleAction::new("select-for-compare", None);
+    {
+        let clicked_path = clicked_path.clone();
+        let compare_selection = compare_selection.clone();
+        select_for_compare_action.connect_activate(move |_, _| {
+            let path = clicked_path.borrow().clone();
+            if !path.is_empty() {
+                *compare_selection.borrow_mut() = Some(path);
             }
         });
     }
-    action_group.add_action(&copy_rel_action);
+    action_group.add_action(&select_for_compare_action);
+
+    let compare_with_selected_action = gio::SimpleAction::new("compare-with-selected", None);
+    compare_with_selected_action.set_enabled(false);
+    {
+        let clicked_path = clicked_path.clone();
+        let compare_selection = compare_selection.clone();
+        let on_compare_files = on_compare_files.clone();
+        compare_with_selected_action.connect_activate(move |_, _| {
+            let path = clicked_path.borrow().clone();
+            let Some(selected) = compare_selection.borrow().clone() else {
+                return;
+            };
+            if !path.is_empty() && path != selected {
+                if let Some(ref callback) = *on_compare_files.borrow() {
+                    callback(&selected, &path);
+                }
+            }
+        });
+    }
+    action_group.add_action(&compare_with_selected_action);
+
+    // "properties" action - size, permissions and so on of the item
+    let properties_action = gio::SimpleAction::new("properties", None);
+    {
+        let clicked_path = clicked_path.clone();
+        let on_show_properties = on_show_properties.clone();
+        properties_action.connect_activate(move |_, _| {
+            let path = clicked_path.borrow().clone();
+            if !path.is_empty() {
+                if let Some(ref callback) = *on_show_properties.borrow() {
+                    callback(&path);
+                }
+            }
+        });
+    }
+    action_group.add_action(&properties_action);
 
     // "open-terminal" action - opens directory in a new terminal tab
     let open_terminal_action = gio::SimpleAction::new("open-terminal", None);
@@ -285,7 +412,7 @@ pub fn build_sidebar(
     }
     action_group.add_action(&open_terminal_action);
 
-    // "rename" action - rename file or directory via inline dialog
+    // "rename" action - edit the name in place in the tree
     let rename_action = gio::SimpleAction::new("rename", None);
     {
         let clicked_path = clicked_path.clone();
@@ -294,100 +421,7 @@ pub fn build_sidebar(
         let icon_cache = icon_cache.clone();
         rename_action.connect_activate(move |_, _| {
             let path = clicked_path.borrow().clone();
-            if path.is_empty() {
-                return;
-            }
-
-            let old_name = std::path::Path::new(&path)
-                .file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("")
-                .to_string();
-            let parent = std::path::Path::new(&path)
-                .parent()
-                .map(|p| p.to_string_lossy().to_string())
-                .unwrap_or_default();
-
-            let dialog = gtk4::Window::builder()
-                .modal(true)
-                .decorated(false)
-                .default_width(300)
-                .default_height(50)
-                .build();
-            if let Some(root) = file_tree_list.root() {
-                if let Some(window) = root.downcast_ref::<gtk4::Window>() {
-                    dialog.set_transient_for(Some(window));
-                }
-            }
-            dialog.add_css_class("quick-open");
-
-            let entry = gtk4::Entry::new();
-            entry.set_text(&old_name);
-            entry.set_margin_start(12);
-            entry.set_margin_end(12);
-            entry.set_margin_top(12);
-            entry.set_margin_bottom(12);
-            dialog.set_child(Some(&entry));
-
-            // Select the name without extension
-            if let Some(dot_idx) = old_name.rfind('.') {
-                entry.select_region(0, dot_idx as i32);
-            } else {
-                entry.select_region(0, -1);
-            }
-
-            let tree_nodes = tree_nodes.clone();
-            let file_tree_list = file_tree_list.clone();
-            let icon_cache = icon_cache.clone();
-            {
-                let dialog = dialog.clone();
-                let parent = parent.clone();
-                entry.connect_activate(move |entry| {
-                    let new_name = entry.text().to_string();
-                    if !new_name.is_empty() && new_name != old_name {
-                        // Validate filename is a single valid component
-                        let path_check = Path::new(&new_name);
-                        if path_check.file_name() != Some(path_check.as_os_str())
-                            || new_name.contains('\0')
-                        {
-                            log::error!("Invalid filename: must be a single filename component");
-                            dialog.close();
-                            return;
-                        }
-                        let new_path = std::path::Path::new(&parent).join(&new_name);
-                        if let Err(e) = std::fs::rename(&path, &new_path) {
-                            log::error!("Failed to rename: {}", e);
-                        } else {
-                            let mut nodes = tree_nodes.borrow_mut();
-                            if let Some(node) = nodes.iter_mut().find(|n| n.entry.path == path) {
-                                node.entry.path = new_path.to_string_lossy().to_string();
-                                node.entry.name = new_name;
-                            }
-                            let snapshot: Vec<_> = nodes.clone();
-                            drop(nodes);
-                            render_tree(&file_tree_list, &snapshot, &icon_cache.borrow());
-                        }
-                    }
-                    dialog.close();
-                });
-            }
-
-            // Escape to cancel
-            let key_ctrl = gtk4::EventControllerKey::new();
-            {
-                let dialog = dialog.clone();
-                key_ctrl.connect_key_pressed(move |_, key, _, _| {
-                    if key == gtk4::gdk::Key::Escape {
-                        dialog.close();
-                        return gtk4::glib::Propagation::Stop;
-                    }
-                    gtk4::glib::Propagation::Proceed
-                });
-            }
-            entry.add_controller(key_ctrl);
-
-            dialog.present();
-            entry.grab_focus();
+            begin_rename(&tree_nodes, &file_tree_list, &icon_cache, &path);
         });
     }
     action_group.add_action(&rename_action);
@@ -574,7 +608,9 @@ pub fn build_sidebar(
         let tree_nodes = tree_nodes.clone();
         let file_tree_list = file_tree_list.clone();
         let current_path = current_path.clone();
+        let settings = settings.clone();
         let icon_cache = icon_cache.clone();
+        let on_file_activated = on_file_activated.clone();
         move |_: &gio::SimpleAction, _: Option<&glib::Variant>| {
             let clicked = clicked_path.borrow().clone();
             if clicked.is_empty() {
@@ -588,31 +624,63 @@ pub fn build_sidebar(
                     .map(|p| p.to_string_lossy().to_string())
                     .unwrap_or_default()
             };
-            let tree_nodes = tree_nodes.clone();
-            let file_tree_list2 = file_tree_list.clone();
-            let current_path = current_path.clone();
-            let icon_cache = icon_cache.clone();
-            let dir_path2 = dir_path.clone();
-            show_new_entry_dialog(
-                is_folder,
-                &dir_path,
+            begin_new_entry(
+                &tree_nodes,
                 &file_tree_list,
-                move |name, new_path| {
-                    insert_new_entry_into_tree(
-                        &tree_nodes,
-                        &file_tree_list2,
-                        &current_path,
-                        &dir_path2,
-                        &name,
-                        &new_path,
-                        is_folder,
-                        &icon_cache.borrow(),
-                    );
-                },
+                &current_path,
+                &settings,
+                &icon_cache,
+                &on_file_activated,
+                &dir_path,
+                is_folder,
             );
         }
     };
 
+    // "duplicate" action - copy next to the original, then rename the copy
+    let duplicate_action = gio::SimpleAction::new("duplicate", None);
+    {
+        let clicked_path = clicked_path.clone();
+        let tree_nodes = tree_nodes.clone();
+        let file_tree_list = file_tree_list.clone();
+        let current_path = current_path.clone();
+        let icon_cache = icon_cache.clone();
+        duplicate_action.connect_activate(move |_, _| {
+            let path = clicked_path.borrow().clone();
+            if path.is_empty() || path == *current_path.borrow() {
+                return;
+            }
+            let copy = match impulse_core::file_actions::duplicate(Path::new(&path)) {
+                Ok(copy) => copy,
+                Err(e) => {
+                    log::warn!("{e}");
+                    return;
+                }
+            };
+            let dir_path = Path::new(&path)
+                .parent()
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let name = copy
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let copy = copy.to_string_lossy().to_string();
+            insert_new_entry_into_tree(
+                &tree_nodes,
+                &file_tree_list,
+                &current_path,
+                &dir_path,
+                &name,
+                &copy,
+                Path::new(&copy).is_dir(),
+                &icon_cache.borrow(),
+            );
+            begin_rename(&tree_nodes, &file_tree_list, &icon_cache, &copy);
+        });
+    }
+    action_group.add_action(&duplicate_action);
+
     let new_file_action = gio::SimpleAction::new("new-file", None);
     new_file_action.connect_activate(make_new_entry_action(false));
     action_group.add_action(&new_file_action);
@@ -623,24 +691,30 @@ pub fn build_sidebar(
 
     file_tree_list.insert_action_group("filetree", Some(&action_group));
 
-    // Right-click gesture
-    let gesture = gtk4::GestureClick::new();
-    gesture.set_button(3); // right click
-    {
+    // Context menu for a row, or for the root folder when `row` is None,
+    // pointing at `rect`. Opened by right-click and by the Menu key.
+    let show_context_menu: Rc<dyn Fn(Option<gtk4::ListBoxRow>, gtk4::gdk::Rectangle)> = {
         let popover = popover.clone();
-        let file_tree_list_ref = file_tree_list.clone();
         let clicked_path = clicked_path.clone();
         let file_menu = file_menu.clone();
         let file_menu_git = file_menu_git.clone();
         let dir_menu = dir_menu.clone();
         let current_path = current_path.clone();
         let tree_nodes_for_menu = tree_nodes.clone();
-        gesture.connect_pressed(move |_gesture, _n_press, x, y| {
-            if let Some(row) = file_tree_list_ref.row_at_y(y as i32) {
+        let compare_selection = compare_selection.clone();
+        let compare_with_selected_action = compare_with_selected_action.clone();
+        Rc::new(move |row, rect| {
+            if let Some(row) = row {
                 if let Some(child) = row.child() {
                     let path = child.widget_name().to_string();
                     let is_dir = std::path::Path::new(&path).is_dir();
                     *clicked_path.borrow_mut() = path.clone();
+                    // A file selected for compare that was since deleted
+                    // can't be compared any more.
+                    let can_compare = compare_selection.borrow().as_ref().is_some_and(|selected| {
+                        *selected != path && std::path::Path::new(selected).is_file()
+                    });
+                    compare_with_selected_action.set_enabled(can_compare);
 
                     if is_dir {
                         popover.set_menu_model(Some(&dir_menu));
@@ -657,25 +731,98 @@ pub fn build_sidebar(
                         }
                     }
 
-                    let rect = gtk4::gdk::Rectangle::new(x as i32, y as i32, 1, 1);
                     popover.set_pointing_to(Some(&rect));
                     popover.popup();
                     return;
                 }
             }
-            // Right-clicked on empty space — show dir menu for current directory
+            // Empty space — show dir menu for current directory
             let cur = current_path.borrow().clone();
             if !cur.is_empty() {
                 *clicked_path.borrow_mut() = cur;
                 popover.set_menu_model(Some(&dir_menu));
-                let rect = gtk4::gdk::Rectangle::new(x as i32, y as i32, 1, 1);
                 popover.set_pointing_to(Some(&rect));
                 popover.popup();
             }
+        })
+    };
+
+    // Right-click gesture
+    let gesture = gtk4::GestureClick::new();
+    gesture.set_button(3); // right click
+    {
+        let file_tree_list_ref = file_tree_list.clone();
+        let show_context_menu = show_context_menu.clone();
+        gesture.connect_pressed(move |_gesture, _n_press, x, y| {
+            let rect = gtk4::gdk::Rectangle::new(x as i32, y as i32, 1, 1);
+            show_context_menu(file_tree_list_ref.row_at_y(y as i32), rect);
         });
     }
     file_tree_list.add_controller(gesture);
 
+    // Keyboard: the Menu key (or Shift+F10) opens the selected row's context
+    // menu; Right expands a folder, Left collapses it or moves to the parent.
+    let key_ctrl = gtk4::EventControllerKey::new();
+    {
+        let file_tree_list_ref = file_tree_list.clone();
+        let tree_nodes = tree_nodes.clone();
+        key_ctrl.connect_key_pressed(move |_, key, _, modifiers| {
+            let selected = file_tree_list_ref.selected_row();
+            let is_menu_key = key == gtk4::gdk::Key::Menu
+                || (key == gtk4::gdk::Key::F10
+                    && modifiers.contains(gtk4::gdk::ModifierType::SHIFT_MASK));
+            if is_menu_key {
+                let rect = selected
+                    .as_ref()
+                    .and_then(|row| row.compute_bounds(&file_tree_list_ref))
+                    .map(|b| {
+                        gtk4::gdk::Rectangle::new(
+                            b.x() as i32 + 24,
+                            (b.y() + b.height()) as i32,
+                            1,
+                            1,
+                        )
+                    })
+                    .unwrap_or_else(|| gtk4::gdk::Rectangle::new(24, 0, 1, 1));
+                show_context_menu(selected, rect);
+                return glib::Propagation::Stop;
+            }
+            let Some(row) = selected else {
+                return glib::Propagation::Proceed;
+            };
+            let index = row.index() as usize;
+            let (is_dir, expanded, parent) = {
+                let nodes = tree_nodes.borrow();
+                let Some(node) = nodes.get(index) else {
+                    return glib::Propagation::Proceed;
+                };
+                let parent = nodes[..index]
+                    .iter()
+                    .rposition(|n| n.depth + 1 == node.depth);
+                (node.entry.is_dir, node.expanded, parent)
+            };
+            match key {
+                gtk4::gdk::Key::Right if is_dir && !expanded => {
+                    row.activate();
+                }
+                gtk4::gdk::Key::Left if is_dir && expanded => {
+                    row.activate();
+                }
+                gtk4::gdk::Key::Left => {
+                    if let Some(parent) =
+                        parent.and_then(|i| file_tree_list_ref.row_at_index(i as i32))
+                    {
+                        file_tree_list_ref.select_row(Some(&parent));
+                        parent.grab_focus();
+                    }
+                }
+                _ => return glib::Propagation::Proceed,
+            };
+            glib::Propagation::Stop
+        });
+    }
+    file_tree_list.add_controller(key_ctrl);
+
     // Enable dragging file paths from the tree
     let drag_source = gtk4::DragSource::new();
     drag_source.set_actions(gtk4::gdk::DragAction::COPY | gtk4::gdk::DragAction::MOVE);
@@ -791,6 +938,17 @@ pub fn build_sidebar(
         drop_target_external.connect_drop(move |_target, value, _x, y| {
             remove_drop_highlight(&file_tree_list, &highlight);
             if let Ok(file_list) = value.get::<gtk4::gdk::FileList>() {
+                // A folder dropped outside the rows opens as the workspace;
+                // drops on a row copy into it.
+                if file_tree_list.row_at_y(y as i32).is_none() {
+                    let paths: Vec<_> = file_list.files().iter().filter_map(|f| f.path()).collect();
+                    if let Some(dir) = impulse_core::file_drop::workspace_folder(&paths) {
+                        let dir = dir.to_string_lossy().to_variant();
+                        return file_tree_list
+                            .activate_action("win.open-folder", Some(&dir))
+                            .is_ok();
+                    }
+                }
                 let cur = current_path.borrow().clone();
                 if let Some(target_dir) = resolve_drop_target_dir(&file_tree_list, y, &cur) {
                     let mut any_success = false;
@@ -861,7 +1019,7 @@ pub fn build_sidebar(
     sidebar.append(&header_box);
     sidebar.append(&stack);
 
-    let on_file_activated: EventCallback = Rc::new(RefCell::new(None));
+    let on_file_previewed: EventCallback = Rc::new(RefCell::new(None));
 
     let state = SidebarState {
         file_tree_list,
@@ -871,12 +1029,18 @@ pub fn build_sidebar(
         project_search: project_search_state,
         current_path: current_path.clone(),
         on_file_activated: on_file_activated.clone(),
+        on_file_previewed: on_file_previewed.clone(),
         on_open_terminal: on_open_terminal.clone(),
+        on_compare_files: on_compare_files.clone(),
+        on_show_properties: on_show_properties.clone(),
         tree_nodes: tree_nodes.clone(),
         tab_tree_states: Rc::new(RefCell::new(HashMap::new())),
         active_tab: Rc::new(RefCell::new(None)),
         show_hidden: show_hidden.clone(),
         icon_cache: icon_cache.clone(),
+        loaded: Cell::new(false),
+        loading: Rc::new(Cell::new(false)),
+        pending_reveal: Rc::new(RefCell::new(None)),
         _watcher: watcher_rc.clone(),
         #[allow(clippy::arc_with_non_send_sync)]
         _git_index_watcher: Rc::new(RefCell::new(None)),
@@ -894,29 +1058,24 @@ pub fn build_sidebar(
         let tree_nodes = state.tree_nodes.clone();
         let file_tree_list = state.file_tree_list.clone();
         let current_path = state.current_path.clone();
+        let settings = settings.clone();
         let icon_cache = icon_cache.clone();
-        btn.connect_clicked(move |btn| {
+        let on_file_activated = on_file_activated.clone();
+        btn.connect_clicked(move |_| {
             let dir_path = selected_directory(&file_tree_list, &tree_nodes, &current_path);
             if dir_path.is_empty() {
                 return;
             }
-            let tree_nodes = tree_nodes.clone();
-            let file_tree_list2 = file_tree_list.clone();
-            let current_path = current_path.clone();
-            let icon_cache = icon_cache.clone();
-            let dir_path2 = dir_path.clone();
-            show_new_entry_dialog(is_folder, &dir_path, btn, move |name, new_path| {
-                insert_new_entry_into_tree(
-                    &tree_nodes,
-                    &file_tree_list2,
-                    &current_path,
-                    &dir_path2,
-                    &name,
-                    &new_path,
-                    is_folder,
-                    &icon_cache.borrow(),
-                );
-            });
+            begin_new_entry(
+                &tree_nodes,
+                &file_tree_list,
+                &current_path,
+                &settings,
+                &icon_cache,
+                &on_file_activated,
+                &dir_path,
+                is_folder,
+            );
         });
     }
 
@@ -995,6 +1154,7 @@ pub fn build_sidebar(
         let tree_nodes = tree_nodes.clone();
         let file_tree_list = state.file_tree_list.clone();
         let on_file_activated = on_file_activated.clone();
+        let on_file_previewed = on_file_previewed.clone();
         let show_hidden = state.show_hidden.clone();
         let icon_cache = icon_cache.clone();
         let watcher_rc = state._watcher.clone();
@@ -1073,7 +1233,6 @@ pub fn build_sidebar(
                             }
                         }
 
-                        let child_depth = node.depth + 1;
                         let path = node.entry.path.clone();
                         let tree_nodes_ref2 = tree_nodes_ref.clone();
                         let list2 = list.clone();
@@ -1090,46 +1249,47 @@ pub fn build_sidebar(
                             .await;
 
                             if let Ok(Ok(entries)) = result {
-                                let mut nodes = tree_nodes_ref2.borrow_mut();
-                                let insert_idx = nodes
-                                    .iter()
-                                    .position(|n| n.entry.path == path)
-                                    .map(|i| i + 1);
-                                if let Some(insert_idx) = insert_idx {
-                                    let new_nodes: Vec<TreeNode> = entries
-                                        .into_iter()
-                                        .map(|e| TreeNode {
-                                            entry: e,
-                                            depth: child_depth,
-                                            expanded: false,
-                                        })
-                                        .collect();
-                                    // Insert into data model
-                                    let nodes_to_insert: Vec<TreeNode> = new_nodes.clone();
-                                    for (i, child_node) in new_nodes.into_iter().enumerate() {
-                                        nodes.insert(insert_idx + i, child_node);
-                                    }
-                                    drop(nodes);
-                                    // Insert rows into the ListBox at the right position
-                                    insert_rows_at(
-                                        &list2,
-                                        insert_idx,
-                                        &nodes_to_insert,
-                                        &icon_cache2.borrow(),
-                                    );
-                                }
+                                insert_child_nodes(
+                                    &tree_nodes_ref2,
+                                    &list2,
+                                    &path,
+                                    entries,
+                                    &icon_cache2.borrow(),
+                                );
                             }
                         });
                     }
-                } else {
-                    // File activated -- invoke callback
-                    if let Some(cb) = on_file_activated.borrow().as_ref() {
-                        cb(&node.entry.path);
-                    }
+                } else if let Some(cb) = on_file_previewed.borrow().as_ref() {
+                    cb(&node.entry.path);
+                } else if let Some(cb) = on_file_activated.borrow().as_ref() {
+                    cb(&node.entry.path);
                 }
             });
     }
 
+    // Double-clicking a file opens it for keeps (the first click already
+    // opened it as a preview).
+    {
+        let gesture = gtk4::GestureClick::new();
+        let file_tree_list = state.file_tree_list.clone();
+        let on_file_activated = on_file_activated.clone();
+        gesture.connect_pressed(move |_gesture, n_press, _x, y| {
+            if n_press != 2 {
+                return;
+            }
+            let Some(child) = file_tree_list.row_at_y(y as i32).and_then(|r| r.child()) else {
+                return;
+            };
+            let path = child.widget_name().to_string();
+            if std::path::Path::new(&path).is_file() {
+                if let Some(cb) = on_file_activated.borrow().as_ref() {
+                    cb(&path);
+                }
+            }
+        });
+        state.file_tree_list.add_controller(gesture);
+    }
+
     (sidebar, state)
 }
 
@@ -1141,12 +1301,27 @@ pub struct SidebarState {
     pub project_search: project_search::ProjectSearchState,
     pub current_path: Rc<RefCell<String>>,
     pub on_file_activated: EventCallback,
+    /// A single click on a file: opens it in the preview tab when set,
+    /// otherwise falls back to `on_file_activated`.
+    pub on_file_previewed: EventCallback,
     pub on_open_terminal: EventCallback,
+    /// "Compare with Selected": diff the file selected for compare (first)
+    /// against the clicked one.
+    pub on_compare_files: CompareCallback,
+    /// "Properties": show the clicked item's properties dialog.
+    pub on_show_properties: EventCallback,
     pub tree_nodes: Rc<RefCell<Vec<TreeNode>>>,
     pub tab_tree_states: Rc<RefCell<HashMap<gtk4::Widget, TabTreeState>>>,
     pub active_tab: Rc<RefCell<Option<gtk4::Widget>>>,
     pub show_hidden: Rc<RefCell<bool>>,
     pub icon_cache: Rc<RefCell<IconCache>>,
+    /// Set by the first `load_directory`. A new window defers its initial
+    /// load until it is on screen, and skips it if something loaded first.
+    pub loaded: Cell<bool>,
+    /// Set while `load_directory` reads the root; a reveal asked for
+    /// meanwhile waits in `pending_reveal` until the tree is there.
+    loading: Rc<Cell<bool>>,
+    pending_reveal: Rc<RefCell<Option<String>>>,
     /// Keeps the filesystem watcher alive. Dropping this stops watching.
     _watcher: Rc<RefCell<Option<notify::RecommendedWatcher>>>,
     /// Keeps the .git/index watcher alive.
@@ -1173,12 +1348,17 @@ impl SidebarState {
     /// Load directory contents into the file tree as root-level (depth 0) nodes.
     pub fn load_directory(&self, path: &str) {
         *self.current_path.borrow_mut() = path.to_string();
+        self.loaded.set(true);
+        self.loading.set(true);
 
         let list = self.file_tree_list.clone();
         let path = path.to_string();
         let tree_nodes = self.tree_nodes.clone();
         let show_hidden = *self.show_hidden.borrow();
         let icon_cache = self.icon_cache.clone();
+        let loading = self.loading.clone();
+        let pending_reveal = self.pending_reveal.clone();
+        let tree_reveal = self.tree_reveal();
 
         // Set up filesystem watcher for this directory
         self.setup_watcher(&path);
@@ -1208,9 +1388,42 @@ impl SidebarState {
                     clear_list(&list);
                 }
             }
+            loading.set(false);
+            let pending = pending_reveal.borrow_mut().take();
+            if let Some(pending) = pending {
+                tree_reveal.reveal(pending).await;
+            }
         });
     }
 
+    /// Expand the directories leading to `path`, then select its row and
+    /// scroll it into view, after any directory load in progress. Returns
+    /// false, doing nothing, when `path` is outside the tree's root.
+    pub fn reveal_path(&self, path: &str) -> bool {
+        let root = self.current_path.borrow().clone();
+        if impulse_core::file_tree::reveal_ancestors(&root, path).is_none() {
+            return false;
+        }
+        if self.loading.get() {
+            *self.pending_reveal.borrow_mut() = Some(path.to_string());
+        } else {
+            glib::spawn_future_local(self.tree_reveal().reveal(path.to_string()));
+        }
+        true
+    }
+
+    fn tree_reveal(&self) -> TreeReveal {
+        TreeReveal {
+            list: self.file_tree_list.clone(),
+            scroll: self.file_tree_scroll.clone(),
+            tree_nodes: self.tree_nodes.clone(),
+            current_path: self.current_path.clone(),
+            show_hidden: self.show_hidden.clone(),
+            icon_cache: self.icon_cache.clone(),
+            watcher: self._watcher.clone(),
+        }
+    }
+
     /// Set up a filesystem watcher for the given directory.
     /// Both the FS watcher and .git watcher set a shared dirty flag;
     /// a single 300ms timer checks the flag and applies a patch batch.
@@ -1232,6 +1445,7 @@ impl SidebarState {
 
         let dirty = self._refresh_dirty.clone();
         let pending_events = self._pending_file_tree_events.clone();
+        let mut excludes = impulse_core::ignore_rules::LiveExcludes::new(Path::new(path));
         let mut watcher =
             match notify::recommended_watcher(move |res: Result<notify::Event, notify::Error>| {
                 if let Ok(event) = res {
@@ -1239,9 +1453,11 @@ impl SidebarState {
                         let paths: Vec<String> = event
                             .paths
                             .iter()
+                            .filter(|path| !excludes.is_excluded(path))
                             .map(|path| path.to_string_lossy().to_string())
                             .collect();
                         if !paths.is_empty() {
+                            impulse_core::git::invalidate_file_caches(&paths);
                             if let Ok(mut events) = pending_events.lock() {
                                 events.push(impulse_core::file_tree::FileTreeWatchEvent {
                                     kind,
@@ -1339,21 +1555,20 @@ impl SidebarState {
     /// Sets the shared dirty flag instead of running its own timer.
     /// Watching `.git/` (NonRecursive) catches `.git/index`, `.git/HEAD`,
     /// and handles atomic file replacement (where the old file is removed
-    /// and a new one is created) without losing the watch.
+    /// and a new one is created) without losing the watch. In linked
+    /// worktrees and submodules `.git` is a file, so the resolved git dir
+    /// (plus the shared one, for refs) is watched instead.
     fn setup_git_watcher(&self, path: &str) {
         use notify::{RecursiveMode, Watcher};
 
         // Stop any previous git watcher.
         *self._git_index_watcher.borrow_mut() = None;
 
-        // Find the git repo root via libgit2 (no subprocess).
-        let git_root = match impulse_core::git::get_git_root(path) {
-            Some(root) => root,
-            None => return,
+        // Find the git dirs via libgit2 (no subprocess).
+        let Some(dirs) = impulse_core::git::resolve_git_dirs(path) else {
+            return;
         };
-
-        let git_dir = format!("{}/.git", git_root);
-        if !Path::new(&git_dir).is_dir() {
+        if !Path::new(&dirs.git_dir).is_dir() {
             return;
         }
 
@@ -1404,10 +1619,16 @@ impl SidebarState {
                 }
             };
 
-        if let Err(e) = watcher.watch(Path::new(&git_dir), RecursiveMode::NonRecursive) {
+        if let Err(e) = watcher.watch(Path::new(&dirs.git_dir), RecursiveMode::NonRecursive) {
             log::warn!("Failed to watch .git/ directory: {}", e);
             return;
         }
+        if dirs.common_dir != dirs.git_dir {
+            if let Err(e) = watcher.watch(Path::new(&dirs.common_dir), RecursiveMode::NonRecursive)
+            {
+                log::warn!("Failed to watch shared git directory: {}", e);
+            }
+        }
 
         *self._git_index_watcher.borrow_mut() = Some(watcher);
     }
@@ -1568,7 +1789,6 @@ impl SidebarState {
     }
 
     /// Refresh the file tree to pick up git status changes (e.g. after saving a file).
-    #[allow(dead_code)]
     pub fn refresh(&self) {
         refresh_tree(
             &self.tree_nodes,
@@ -1613,6 +1833,139 @@ impl SidebarState {
     }
 }
 
+/// The parts of the sidebar state a reveal works on, owned so it can run
+/// across awaits.
+struct TreeReveal {
+    list: gtk4::ListBox,
+    scroll: gtk4::ScrolledWindow,
+    tree_nodes: Rc<RefCell<Vec<TreeNode>>>,
+    current_path: Rc<RefCell<String>>,
+    show_hidden: Rc<RefCell<bool>>,
+    icon_cache: Rc<RefCell<IconCache>>,
+    watcher: Rc<RefCell<Option<notify::RecommendedWatcher>>>,
+}
+
+impl TreeReveal {
+    async fn reveal(self, path: String) {
+        let root = self.current_path.borrow().clone();
+        let Some(ancestors) = impulse_core::file_tree::reveal_ancestors(&root, &path) else {
+            return;
+        };
+        for dir in ancestors {
+            let expanded = self
+                .tree_nodes
+                .borrow()
+                .iter()
+                .find(|n| n.entry.path == dir)
+                .map(|n| n.expanded);
+            match expanded {
+                Some(true) => continue,
+                Some(false) => {}
+                None => return,
+            }
+
+            let show_hidden = *self.show_hidden.borrow();
+            let dir_clone = dir.clone();
+            let result = gio::spawn_blocking(move || {
+                impulse_core::filesystem::read_directory_with_git_status(&dir_clone, show_hidden)
+            })
+            .await;
+            let Ok(Ok(entries)) = result else {
+                return;
+            };
+
+            // The tree may have changed while the directory was read.
+            let index = {
+                let mut nodes = self.tree_nodes.borrow_mut();
+                let Some(index) = nodes.iter().position(|n| n.entry.path == dir) else {
+                    return;
+                };
+                if nodes[index].expanded {
+                    continue;
+                }
+                nodes[index].expanded = true;
+                index
+            };
+            update_dir_row_expanded(&self.list, index, true, &self.icon_cache.borrow());
+            {
+                use notify::{RecursiveMode, Watcher};
+                if let Some(ref mut w) = *self.watcher.borrow_mut() {
+                    let _ = w.watch(Path::new(&dir), RecursiveMode::NonRecursive);
+                }
+            }
+            insert_child_nodes(
+                &self.tree_nodes,
+                &self.list,
+                &dir,
+                entries,
+                &self.icon_cache.borrow(),
+            );
+        }
+
+        let index = self
+            .tree_nodes
+            .borrow()
+            .iter()
+            .position(|n| n.entry.path == path);
+        let Some(row) = index.and_then(|index| self.list.row_at_index(index as i32)) else {
+            return;
+        };
+        self.list.select_row(Some(&row));
+        // Rows inserted above it are only laid out by the next frame.
+        let list = self.list.clone();
+        let scroll = self.scroll.clone();
+        glib::idle_add_local_once(move || scroll_row_into_view(&list, &scroll, &row));
+    }
+}
+
+/// Scroll `row` into view without moving keyboard focus.
+fn scroll_row_into_view(
+    list: &gtk4::ListBox,
+    scroll: &gtk4::ScrolledWindow,
+    row: &gtk4::ListBoxRow,
+) {
+    let Some(point) = row.compute_point(list, &gtk4::graphene::Point::zero()) else {
+        return;
+    };
+    let y = point.y() as f64;
+    let height = row.height() as f64;
+    let adj = scroll.vadjustment();
+    if y < adj.value() {
+        adj.set_value(y);
+    } else if y + height > adj.value() + adj.page_size() {
+        adj.set_value(y + height - adj.page_size());
+    }
+}
+
+/// Insert the `entries` of the expanded directory at `parent_path` below
+/// its node, in the model and the list. Does nothing if the directory has
+/// left the tree.
+fn insert_child_nodes(
+    tree_nodes: &Rc<RefCell<Vec<TreeNode>>>,
+    list: &gtk4::ListBox,
+    parent_path: &str,
+    entries: Vec<FileEntry>,
+    icon_cache: &IconCache,
+) {
+    let mut nodes = tree_nodes.borrow_mut();
+    let Some(parent) = nodes.iter().position(|n| n.entry.path == parent_path) else {
+        return;
+    };
+    let child_depth = nodes[parent].depth + 1;
+    let new_nodes: Vec<TreeNode> = entries
+        .into_iter()
+        .map(|e| TreeNode {
+            entry: e,
+            depth: child_depth,
+            expanded: false,
+        })
+        .collect();
+    let insert_idx = parent + 1;
+    nodes.splice(insert_idx..insert_idx, new_nodes.iter().cloned());
+    drop(nodes);
+    insert_rows_at(list, insert_idx, &new_nodes, icon_cache);
+}
+
 /// Insert a newly created file or folder into the tree at the correct position.
 /// Return the best target directory for new file/folder creation: the selected
 /// node's directory (or its parent if a file is selected), falling back to
@@ -1657,9 +2010,11 @@ fn insert_new_entry_into_tree(
         path: full_path.to_string(),
         is_dir,
         is_symlink: false,
+        symlink_target: None,
         size: 0,
         modified: 0,
         git_status: None,
+        is_ignored: false,
     };
 
     let mut nodes = tree_nodes.borrow_mut();
@@ -1935,9 +2290,11 @@ fn build_upsert_subtree(
             path: node.path.clone(),
             is_dir: node.is_dir,
             is_symlink: node.is_symlink,
+            symlink_target: node.symlink_target.clone(),
             size: node.size,
             modified: node.modified,
             git_status: node.git_status.clone(),
+            is_ignored: node.is_ignored,
         },
         depth: target_depth,
         expanded: false,
@@ -2233,7 +2590,7 @@ fn refresh_git_status(
 
     // Walk tree nodes and update git_status, tracking which row indices changed.
     let mut nodes = tree_nodes.borrow_mut();
-    let mut changed_rows: Vec<(usize, Option<String>)> = Vec::new();
+    let mut changed_rows: Vec<(usize, Option<String>, String)> = Vec::new();
     for (i, node) in nodes.iter_mut().enumerate() {
         let parent_dir = Path::new(&node.entry.path)
             .parent()
@@ -2245,17 +2602,18 @@ fn refresh_git_status(
             .cloned();
         if node.entry.git_status != new_status {
             node.entry.git_status = new_status.clone();
-            changed_rows.push((i, new_status));
+            changed_rows.push((i, new_status, node.entry.accessible_label()));
         }
     }
     drop(nodes);
 
     // Update only the rows that actually changed, avoiding a full tree rebuild.
-    for (row_idx, new_status) in changed_rows {
+    for (row_idx, new_status, label) in changed_rows {
         let row = match file_tree_list.row_at_index(row_idx as i32) {
             Some(r) => r,
             None => continue,
         };
+        row.update_property(&[gtk4::accessible::Property::Label(&label)]);
         let content_box = match row.child().and_then(|c| c.downcast::<gtk4::Box>().ok()) {
             Some(b) => b,
             None => continue,
@@ -2386,78 +2744,145 @@ fn file_icon_name(filename: &str) -> &'static str {
     }
 }
 
-/// Show a modal dialog for creating a new file or folder.
-/// `is_dir` determines whether to create a directory (true) or file (false).
-/// `parent_widget` is used to resolve the transient-for window.
-/// On success, calls `on_created(name, new_path)`.
-fn show_new_entry_dialog(
-    is_dir: bool,
-    dir_path: &str,
-    parent_widget: &impl IsA<gtk4::Widget>,
-    on_created: impl Fn(String, String) + 'static,
+/// Where an inline name entry goes in the file tree.
+enum InlineEntrySlot {
+    /// In place of the name in the row at this index.
+    Rename(usize),
+    /// A new row at `depth`, shown as the header of the row at `before` or
+    /// after the last row, so row indices keep matching `tree_nodes`.
+    New {
+        before: Option<usize>,
+        depth: usize,
+        is_dir: bool,
+    },
+}
+
+/// Edit a name in place in the file tree. Enter passes the text to
+/// `on_commit`, keeping the entry open with the error if it fails; Escape
+/// cancels, and so does moving focus away without a change.
+fn show_inline_name_entry(
+    list: &gtk4::ListBox,
+    slot: InlineEntrySlot,
+    initial: &str,
+    icon_cache: &IconCache,
+    on_commit: impl Fn(&str) -> Result<(), String> + 'static,
 ) {
-    let dialog = gtk4::Window::builder()
-        .modal(true)
-        .decorated(false)
-        .default_width(300)
-        .default_height(50)
-        .build();
-    if let Some(root) = parent_widget.root() {
-        if let Some(window) = root.downcast_ref::<gtk4::Window>() {
-            dialog.set_transient_for(Some(window));
+    let entry = gtk4::Entry::new();
+    entry.set_text(initial);
+    entry.set_hexpand(true);
+    entry.add_css_class("file-tree-inline-entry");
+
+    let cleanup: Box<dyn Fn()> = match slot {
+        InlineEntrySlot::Rename(index) => {
+            let Some(content) = list
+                .row_at_index(index as i32)
+                .and_then(|row| row.child())
+                .and_downcast::<gtk4::Box>()
+            else {
+                return;
+            };
+            let Some(label) = swap_label_for_entry(&content, &entry) else {
+                return;
+            };
+            let entry = entry.clone();
+            Box::new(move || {
+                if entry.parent().is_some() {
+                    content.remove(&entry);
+                }
+                label.set_visible(true);
+            })
         }
-    }
-    dialog.add_css_class("quick-open");
+        InlineEntrySlot::New {
+            before,
+            depth,
+            is_dir,
+        } => {
+            let placeholder = TreeNode {
+                entry: FileEntry {
+                    name: String::new(),
+                    path: String::new(),
+                    is_dir,
+                    is_symlink: false,
+                    symlink_target: None,
+                    size: 0,
+                    modified: 0,
+                    git_status: None,
+                    is_ignored: false,
+                },
+                depth,
+                expanded: false,
+            };
+            let content = build_tree_row(&placeholder, icon_cache);
+            if swap_label_for_entry(&content, &entry).is_none() {
+                return;
+            }
+            match before.and_then(|index| list.row_at_index(index as i32)) {
+                Some(row) => {
+                    row.set_header(Some(&content));
+                    Box::new(move || {
+                        if row.header().as_ref() == Some(content.upcast_ref()) {
+                            row.set_header(None::<&gtk4::Widget>);
+                        }
+                    })
+                }
+                None => {
+                    let row = gtk4::ListBoxRow::new();
+                    row.set_activatable(false);
+                    row.set_selectable(false);
+                    row.set_child(Some(&content));
+                    list.append(&row);
+                    let list = list.clone();
+                    Box::new(move || {
+                        if row.parent().is_some() {
+                            list.remove(&row);
+                        }
+                    })
+                }
+            }
+        }
+    };
 
-    let entry = gtk4::Entry::new();
-    entry.set_placeholder_text(Some(if is_dir {
-        "New folder name..."
-    } else {
-        "New file name..."
-    }));
-    entry.set_margin_start(12);
-    entry.set_margin_end(12);
-    entry.set_margin_top(12);
-    entry.set_margin_bottom(12);
-    dialog.set_child(Some(&entry));
+    let done = Cell::new(false);
+    let initial = initial.to_string();
+    let finish = Rc::new(move |commit: Option<String>| -> Result<(), String> {
+        // Committing re-renders the tree, which takes focus from the
+        // entry and comes back here.
+        if done.replace(true) {
+            return Ok(());
+        }
+        if let Some(name) = commit {
+            if let Err(e) = on_commit(&name) {
+                done.set(false);
+                return Err(e);
+            }
+        }
+        cleanup();
+        Ok(())
+    });
 
-    let dir_path = dir_path.to_string();
     {
-        let dialog = dialog.clone();
+        let finish = finish.clone();
+        let initial = initial.clone();
         entry.connect_activate(move |entry| {
-            let name = entry.text().to_string();
-            if !name.is_empty() {
-                let path_check = Path::new(&name);
-                if path_check.file_name() != Some(path_check.as_os_str()) || name.contains('\0') {
-                    log::error!("Invalid name: must be a single filename component");
-                    dialog.close();
-                    return;
-                }
-                let new_path = std::path::Path::new(&dir_path).join(&name);
-                let result = if is_dir {
-                    std::fs::create_dir(&new_path).map(|_| ())
-                } else {
-                    std::fs::write(&new_path, "").map(|_| ())
-                };
-                match result {
-                    Ok(()) => on_created(name, new_path.to_string_lossy().to_string()),
-                    Err(e) => log::error!(
-                        "Failed to create {}: {}",
-                        if is_dir { "folder" } else { "file" },
-                        e
-                    ),
-                }
+            let text = entry.text().to_string();
+            let commit = (!text.trim().is_empty() && text != initial).then_some(text);
+            if let Err(e) = finish(commit) {
+                entry.add_css_class("error");
+                entry.set_tooltip_text(Some(&e));
             }
-            dialog.close();
         });
     }
+    entry.connect_changed(|entry| {
+        entry.remove_css_class("error");
+        entry.set_tooltip_text(None);
+    });
 
     let key_ctrl = gtk4::EventControllerKey::new();
     {
-        let dialog = dialog.clone();
+        let finish = finish.clone();
         key_ctrl.connect_key_pressed(move |_, key, _, _| {
             if key == gtk4::gdk::Key::Escape {
-                dialog.close();
+                let _ = finish(None);
                 return gtk4::glib::Propagation::Stop;
             }
             gtk4::glib::Propagation::Proceed
@@ -2465,14 +2890,182 @@ fn show_new_entry_dialog(
     }
     entry.add_controller(key_ctrl);
 
-    dialog.present();
-    entry.grab_focus();
+    // Leaving the entry commits a changed name, like clicking away in a
+    // file manager; a name that fails is dropped.
+    let focus_ctrl = gtk4::EventControllerFocus::new();
+    {
+        let entry = entry.clone();
+        focus_ctrl.connect_leave(move |_| {
+            let text = entry.text().to_string();
+            let commit = (!text.trim().is_empty() && text != initial).then_some(text);
+            if let Err(e) = finish(commit) {
+                log::warn!("{e}");
+                let _ = finish(None);
+            }
+        });
+    }
+    entry.add_controller(focus_ctrl);
+
+    // Focus once the context menu that started the edit has closed and
+    // handed focus back.
+    glib::idle_add_local_once(move || {
+        entry.grab_focus();
+        let stem_len = if entry.text().starts_with('.') {
+            None
+        } else {
+            entry
+                .text()
+                .rfind('.')
+                .map(|dot| entry.text()[..dot].chars().count())
+        };
+        entry.select_region(0, stem_len.map_or(-1, |len| len as i32));
+    });
+}
+
+/// Hide the name label in a tree row's `content` and put `entry` in its
+/// place. Returns the hidden label.
+fn swap_label_for_entry(content: &gtk4::Box, entry: &gtk4::Entry) -> Option<gtk4::Label> {
+    let mut child = content.first_child();
+    while let Some(widget) = child {
+        if let Ok(label) = widget.clone().downcast::<gtk4::Label>() {
+            if !label.has_css_class("git-badge") {
+                label.set_visible(false);
+                content.insert_child_after(entry, Some(&label));
+                return Some(label);
+            }
+        }
+        child = widget.next_sibling();
+    }
+    None
+}
+
+/// Start an inline New File or New Folder row in `dir_path`. New files get
+/// their template content and open in the editor.
+#[allow(clippy::too_many_arguments)]
+fn begin_new_entry(
+    tree_nodes: &Rc<RefCell<Vec<TreeNode>>>,
+    list: &gtk4::ListBox,
+    current_path: &Rc<RefCell<String>>,
+    settings: &Rc<RefCell<settings::Settings>>,
+    icon_cache: &Rc<RefCell<IconCache>>,
+    on_file_activated: &EventCallback,
+    dir_path: &str,
+    is_dir: bool,
+) {
+    let (before, depth) = {
+        let nodes = tree_nodes.borrow();
+        match nodes.iter().position(|n| n.entry.path == dir_path) {
+            Some(index) => (index + 1, nodes[index].depth + 1),
+            None if dir_path == *current_path.borrow() => (0, 0),
+            None => return,
+        }
+    };
+    let slot = InlineEntrySlot::New {
+        before: list.row_at_index(before as i32).map(|_| before),
+        depth,
+        is_dir,
+    };
+
+    let tree_nodes = tree_nodes.clone();
+    let list_ref = list.clone();
+    let current_path = current_path.clone();
+    let settings = settings.clone();
+    let icon_cache_ref = icon_cache.clone();
+    let on_file_activated = on_file_activated.clone();
+    let dir_path = dir_path.to_string();
+    show_inline_name_entry(list, slot, "", &icon_cache.borrow(), move |name| {
+        let dir = Path::new(&dir_path);
+        let created = if is_dir {
+            impulse_core::file_actions::create_dir(dir, name)?
+        } else {
+            let templates = settings.borrow().file_templates.clone();
+            impulse_core::file_actions::create_file(dir, name, &templates)?
+        };
+        let name = created
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let created = created.to_string_lossy().to_string();
+        let collapsed_parent = tree_nodes
+            .borrow()
+            .iter()
+            .position(|n| n.entry.path == dir_path && !n.expanded);
+        insert_new_entry_into_tree(
+            &tree_nodes,
+            &list_ref,
+            &current_path,
+            &dir_path,
+            &name,
+            &created,
+            is_dir,
+            &icon_cache_ref.borrow(),
+        );
+        // Expanding the folder reads it again, new entry included.
+        if let Some(row) = collapsed_parent.and_then(|i| list_ref.row_at_index(i as i32)) {
+            list_ref.emit_by_name::<()>("row-activated", &[&row]);
+        }
+        if !is_dir {
+            if let Some(cb) = on_file_activated.borrow().as_ref() {
+                cb(&created);
+            }
+        }
+        Ok(())
+    });
+}
+
+/// Start an inline rename of the tree row for `path`.
+fn begin_rename(
+    tree_nodes: &Rc<RefCell<Vec<TreeNode>>>,
+    list: &gtk4::ListBox,
+    icon_cache: &Rc<RefCell<IconCache>>,
+    path: &str,
+) {
+    let Some((index, name)) = tree_nodes
+        .borrow()
+        .iter()
+        .enumerate()
+        .find(|(_, n)| n.entry.path == path)
+        .map(|(i, n)| (i, n.entry.name.clone()))
+    else {
+        return;
+    };
+    let tree_nodes = tree_nodes.clone();
+    let list_ref = list.clone();
+    let icon_cache_ref = icon_cache.clone();
+    let path = path.to_string();
+    show_inline_name_entry(
+        list,
+        InlineEntrySlot::Rename(index),
+        &name,
+        &icon_cache.borrow(),
+        move |new_name| {
+            let new_path = impulse_core::file_actions::rename(Path::new(&path), new_name)?;
+            let new_path = new_path.to_string_lossy().to_string();
+            let mut nodes = tree_nodes.borrow_mut();
+            let old_prefix = format!("{path}/");
+            for node in nodes.iter_mut() {
+                if node.entry.path == path {
+                    node.entry.path = new_path.clone();
+                    node.entry.name = new_name.trim().to_string();
+                } else if let Some(rest) = node.entry.path.strip_prefix(&old_prefix) {
+                    node.entry.path = format!("{new_path}/{rest}");
+                }
+            }
+            let snapshot: Vec<_> = nodes.clone();
+            drop(nodes);
+            render_tree(&list_ref, &snapshot, &icon_cache_ref.borrow());
+            Ok(())
+        },
+    );
 }
 
 /// Build a single row widget for a tree node.
 fn build_tree_row(node: &TreeNode, icon_cache: &IconCache) -> gtk4::Box {
     let row = gtk4::Box::new(gtk4::Orientation::Horizontal, 4);
     row.add_css_class("file-entry");
+    if node.entry.is_ignored {
+        row.add_css_class("file-entry-ignored");
+    }
     row.set_widget_name(&node.entry.path);
     row.set_cursor_from_name(Some("pointer"));
 
@@ -2564,6 +3157,18 @@ fn build_tree_row(node: &TreeNode, icon_cache: &IconCache) -> gtk4::Box {
     }
     row.append(&label);
 
+    // Symlink indicator: a dim arrow with the link text, which also shows
+    // as the row tooltip.
+    if node.entry.is_symlink {
+        let target = node.entry.symlink_target.as_deref().unwrap_or("?");
+        let link_label = gtk4::Label::new(Some(&format!("\u{2192} {target}")));
+        link_label.add_css_class("file-entry-symlink");
+        link_label.set_ellipsize(gtk4::pango::EllipsizeMode::Start);
+        link_label.set_max_width_chars(24);
+        row.append(&link_label);
+        row.set_tooltip_text(Some(&format!("Symbolic link to {target}")));
+    }
+
     // Git status indicator badge (right-aligned letter) — skip for ignored files
     if let Some(ref status) = node.entry.git_status {
         if status != "I" {
@@ -2623,6 +3228,7 @@ fn update_dir_row_expanded(
             "pan-end-symbolic"
         }));
     }
+    row.update_state(&[gtk4::accessible::State::Expanded(Some(expanded))]);
     if let Some(icon) = images.get(1) {
         if let Some(texture) = icon_cache.get("", true, expanded) {
             icon.set_paintable(Some(texture));
@@ -2646,6 +3252,7 @@ fn insert_rows_at(
     for (i, node) in nodes.iter().enumerate() {
         let row = build_tree_row(node, icon_cache);
         list.insert(&row, (position + i) as i32);
+        set_row_accessibility(&row, node);
     }
 }
 
@@ -2666,6 +3273,24 @@ fn render_tree(list: &gtk4::ListBox, nodes: &[TreeNode], icon_cache: &IconCache)
     for node in nodes {
         let row = build_tree_row(node, icon_cache);
         list.append(&row);
+        set_row_accessibility(&row, node);
+    }
+}
+
+/// Give the ListBoxRow wrapping `content` a screen reader label (name, kind,
+/// git status) and, for folders, its expanded state.
+fn set_row_accessibility(content: &gtk4::Box, node: &TreeNode) {
+    let Some(row) = content
+        .parent()
+        .and_then(|p| p.downcast::<gtk4::ListBoxRow>().ok())
+    else {
+        return;
+    };
+    row.update_property(&[gtk4::accessible::Property::Label(
+        &node.entry.accessible_label(),
+    )]);
+    if node.entry.is_dir {
+        row.update_state(&[gtk4::accessible::State::Expanded(Some(node.expanded))]);
     }
 }
 
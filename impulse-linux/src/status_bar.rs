@@ -1,27 +1,84 @@
 use gtk4::prelude::*;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
 use std::rc::Rc;
 
+use impulse_core::git_hosting::CiState;
+use impulse_core::indentation::{Indentation, INDENT_WIDTHS};
+use impulse_core::notifications::Severity;
+use impulse_core::status_bar::SegmentAlignment;
+
 /// Status bar at the bottom of the window showing CWD, git branch, shell name, and cursor position.
+///
+/// It is built from segments (see `impulse_core::status_bar`) registered
+/// with [`StatusBar::register_segment`]; the user's order and hidden list
+/// decide where each one goes. Transient indicators (git progress, pending
+/// chord, update, preview, the notifications bell) sit outside the segments.
 pub struct StatusBar {
     pub widget: gtk4::Box,
-    cwd_label: gtk4::Label,
+    left_box: gtk4::Box,
+    right_box: gtk4::Box,
+    /// Registered segment widgets by ID, in registration order.
+    segments: RefCell<Vec<(String, gtk4::Widget)>>,
+    /// The user's segment order and hidden list.
+    layout: RefCell<(Vec<String>, Vec<String>)>,
+    problems_label: gtk4::Label,
+    /// Error and warning counts per open file, summed for the problems segment.
+    problem_counts: RefCell<HashMap<String, (usize, usize)>>,
+    /// The working directory as breadcrumb buttons.
+    cwd_box: gtk4::Box,
+    cwd_actions: Rc<RefCell<Option<Rc<CwdActions>>>>,
     branch_label: gtk4::Label,
+    sync_label: gtk4::Label,
+    git_progress_label: gtk4::Label,
+    chord_label: gtk4::Label,
+    lsp_progress_box: gtk4::Box,
+    lsp_progress_spinner: gtk4::Spinner,
+    lsp_progress_label: gtk4::Label,
+    ci_button: gtk4::Button,
+    ci_url: Rc<RefCell<String>>,
+    ci_enabled: Cell<bool>,
+    /// Bumped per CI lookup so a stale response for another repo is dropped.
+    ci_generation: Rc<Cell<u64>>,
     #[allow(dead_code)] // Kept alive to maintain widget hierarchy
     shell_label: gtk4::Label,
     cursor_label: gtk4::Label,
-    language_label: gtk4::Label,
+    language_button: gtk4::MenuButton,
     encoding_label: gtk4::Label,
-    indent_label: gtk4::Label,
+    indent_button: gtk4::MenuButton,
+    /// The selected pane's zoom; clicking resets it.
+    zoom_button: gtk4::Button,
     blame_label: gtk4::Label,
     pub preview_button: gtk4::Button,
     update_button: gtk4::Button,
+    notifications_button: gtk4::MenuButton,
+    notifications_count: gtk4::Label,
+    /// Runs a registry command by ID for notification actions; set by the
+    /// window once its commands exist.
+    command_runner: Rc<RefCell<Option<CommandRunner>>>,
+}
+
+pub type CommandRunner = Rc<dyn Fn(&str)>;
+
+/// What the working directory breadcrumb does; set by the window.
+pub struct CwdActions {
+    /// Open a directory in the sidebar.
+    pub open_directory: Rc<dyn Fn(&str)>,
+    /// The selected terminal's directory history, most recent first.
+    pub history: Rc<dyn Fn() -> Vec<String>>,
+    /// Change the selected terminal to a directory.
+    pub change_directory: Rc<dyn Fn(&str)>,
 }
 
 impl StatusBar {
     pub fn new() -> Self {
-        let widget = gtk4::Box::new(gtk4::Orientation::Horizontal, 12);
+        let widget = gtk4::Box::builder()
+            .orientation(gtk4::Orientation::Horizontal)
+            .spacing(12)
+            .accessible_role(gtk4::AccessibleRole::Group)
+            .build();
         widget.add_css_class("status-bar");
+        widget.update_property(&[gtk4::accessible::Property::Label("Status bar")]);
 
         let shell_label = gtk4::Label::new(Some(&impulse_core::shell::get_default_shell_name()));
         shell_label.add_css_class("shell-name");
@@ -29,33 +86,100 @@ impl StatusBar {
         let branch_label = gtk4::Label::new(None);
         branch_label.add_css_class("git-branch");
 
-        let cwd_label = gtk4::Label::new(None);
-        cwd_label.add_css_class("cwd");
-        cwd_label.set_hexpand(true);
-        cwd_label.set_halign(gtk4::Align::Start);
-        cwd_label.set_ellipsize(gtk4::pango::EllipsizeMode::Start);
+        let sync_label = gtk4::Label::new(None);
+        sync_label.add_css_class("git-sync");
+        sync_label.set_visible(false);
+
+        let git_progress_label = gtk4::Label::new(None);
+        git_progress_label.add_css_class("git-progress");
+        git_progress_label.set_visible(false);
+        git_progress_label.set_ellipsize(gtk4::pango::EllipsizeMode::End);
+        git_progress_label.set_max_width_chars(48);
+
+        let chord_label = gtk4::Label::new(None);
+        chord_label.add_css_class("pending-chord");
+        chord_label.set_visible(false);
+
+        let lsp_progress_box = gtk4::Box::new(gtk4::Orientation::Horizontal, 4);
+        lsp_progress_box.add_css_class("lsp-progress");
+        lsp_progress_box.set_visible(false);
+        let lsp_progress_spinner = gtk4::Spinner::new();
+        let lsp_progress_label = gtk4::Label::new(None);
+        lsp_progress_label.set_ellipsize(gtk4::pango::EllipsizeMode::End);
+        lsp_progress_label.set_max_width_chars(48);
+        lsp_progress_box.append(&lsp_progress_spinner);
+        lsp_progress_box.append(&lsp_progress_label);
+
+        let ci_button = gtk4::Button::new();
+        ci_button.add_css_class("ci-status");
+        ci_button.set_visible(false);
+        ci_button.set_cursor_from_name(Some("pointer"));
+        let ci_url = Rc::new(RefCell::new(String::new()));
+        {
+            let ci_url = ci_url.clone();
+            ci_button.connect_clicked(move |_| {
+                let url = ci_url.borrow().clone();
+                if !url.is_empty() {
+                    let _ = open::that(&url);
+                }
+            });
+        }
+
+        let cwd_box = gtk4::Box::new(gtk4::Orientation::Horizontal, 0);
+        cwd_box.add_css_class("cwd");
+        cwd_box.set_halign(gtk4::Align::Start);
 
         let cursor_label = gtk4::Label::new(None);
         cursor_label.add_css_class("cursor-pos");
         cursor_label.set_visible(false); // hidden by default, shown for editor tabs
 
-        let language_label = gtk4::Label::new(None);
-        language_label.add_css_class("language-name");
-        language_label.set_visible(false);
+        let language_button = gtk4::MenuButton::new();
+        language_button.add_css_class("language-name");
+        language_button.set_has_frame(false);
+        language_button.set_visible(false);
+        language_button.set_cursor_from_name(Some("pointer"));
+        language_button.set_tooltip_text(Some("Change Language"));
+        language_button.set_menu_model(Some(&language_menu()));
 
         let encoding_label = gtk4::Label::new(Some("UTF-8"));
         encoding_label.add_css_class("encoding");
         encoding_label.set_visible(false);
 
-        let indent_label = gtk4::Label::new(None);
-        indent_label.add_css_class("indent-info");
-        indent_label.set_visible(false);
+        let indent_button = gtk4::MenuButton::new();
+        indent_button.add_css_class("indent-info");
+        indent_button.set_has_frame(false);
+        indent_button.set_visible(false);
+        indent_button.set_cursor_from_name(Some("pointer"));
+        indent_button.set_tooltip_text(Some("Change Indentation"));
+        indent_button.set_menu_model(Some(&indent_menu()));
+
+        let command_runner: Rc<RefCell<Option<CommandRunner>>> = Rc::new(RefCell::new(None));
+
+        let zoom_button = gtk4::Button::new();
+        zoom_button.add_css_class("zoom-level");
+        zoom_button.set_has_frame(false);
+        zoom_button.set_visible(false);
+        zoom_button.set_cursor_from_name(Some("pointer"));
+        zoom_button.set_tooltip_text(Some("Reset Zoom"));
+        {
+            let command_runner = command_runner.clone();
+            zoom_button.connect_clicked(move |_| {
+                let runner = command_runner.borrow().clone();
+                if let Some(runner) = runner {
+                    runner("font_reset");
+                }
+            });
+        }
 
         let blame_label = gtk4::Label::new(None);
         blame_label.add_css_class("blame-info");
         blame_label.set_visible(false);
         blame_label.set_ellipsize(gtk4::pango::EllipsizeMode::End);
 
+        let problems_label = gtk4::Label::new(None);
+        problems_label.add_css_class("problems");
+        problems_label.set_visible(false);
+
         let preview_button = gtk4::Button::with_label("Preview");
         preview_button.add_css_class("status-bar-preview-btn");
         preview_button.set_tooltip_text(Some("Toggle Preview (Ctrl+Shift+M)"));
@@ -67,45 +191,135 @@ impl StatusBar {
         update_button.set_visible(false);
         update_button.set_cursor_from_name(Some("pointer"));
 
-        widget.append(&shell_label);
-        widget.append(&branch_label);
-        widget.append(&cwd_label);
-        widget.append(&blame_label);
+        let notifications_count = gtk4::Label::new(None);
+        let bell = gtk4::Box::new(gtk4::Orientation::Horizontal, 4);
+        bell.append(&gtk4::Image::from_icon_name(
+            "preferences-system-notifications-symbolic",
+        ));
+        bell.append(&notifications_count);
+        let notifications_button = gtk4::MenuButton::new();
+        notifications_button.add_css_class("notifications-bell");
+        notifications_button.set_has_frame(false);
+        notifications_button.set_cursor_from_name(Some("pointer"));
+        notifications_button.set_tooltip_text(Some("Notifications"));
+        notifications_button.set_child(Some(&bell));
+        notifications_button.set_popover(Some(&notifications_popover(command_runner.clone())));
+
+        let left_box = gtk4::Box::new(gtk4::Orientation::Horizontal, 12);
+        let right_box = gtk4::Box::new(gtk4::Orientation::Horizontal, 12);
+        let spacer = gtk4::Box::new(gtk4::Orientation::Horizontal, 0);
+        spacer.set_hexpand(true);
+
+        widget.append(&left_box);
+        widget.append(&git_progress_label);
+        widget.append(&chord_label);
+        widget.append(&spacer);
         widget.append(&update_button);
-        widget.append(&encoding_label);
-        widget.append(&indent_label);
-        widget.append(&language_label);
-        widget.append(&cursor_label);
+        widget.append(&right_box);
         widget.append(&preview_button);
+        widget.append(&notifications_button);
 
-        StatusBar {
+        let status_bar = StatusBar {
             widget,
-            cwd_label,
+            left_box,
+            right_box,
+            segments: RefCell::new(Vec::new()),
+            layout: RefCell::new((Vec::new(), Vec::new())),
+            problems_label,
+            problem_counts: RefCell::new(HashMap::new()),
+            cwd_box,
+            cwd_actions: Rc::new(RefCell::new(None)),
             branch_label,
+            sync_label,
+            git_progress_label,
+            chord_label,
+            lsp_progress_box,
+            lsp_progress_spinner,
+            lsp_progress_label,
+            ci_button,
+            ci_url,
+            ci_enabled: Cell::new(false),
+            ci_generation: Rc::new(Cell::new(0)),
             shell_label,
             cursor_label,
-            language_label,
+            language_button,
             encoding_label,
-            indent_label,
+            indent_button,
+            zoom_button,
             blame_label,
             preview_button,
             update_button,
+            notifications_button,
+            notifications_count,
+            command_runner,
+        };
+        status_bar.register_segment("shell", &status_bar.shell_label);
+        status_bar.register_segment("branch", &status_bar.branch_label);
+        status_bar.register_segment("git_sync", &status_bar.sync_label);
+        status_bar.register_segment("ci", &status_bar.ci_button);
+        status_bar.register_segment("lsp", &status_bar.lsp_progress_box);
+        status_bar.register_segment("cwd", &status_bar.cwd_box);
+        status_bar.register_segment("blame", &status_bar.blame_label);
+        status_bar.register_segment("problems", &status_bar.problems_label);
+        status_bar.register_segment("encoding", &status_bar.encoding_label);
+        status_bar.register_segment("indent", &status_bar.indent_button);
+        status_bar.register_segment("language", &status_bar.language_button);
+        status_bar.register_segment("zoom", &status_bar.zoom_button);
+        status_bar.register_segment("cursor", &status_bar.cursor_label);
+        status_bar
+    }
+
+    /// Add a segment under `id`. Known IDs are placed by the user's layout;
+    /// others go at the end of the right side. The owner keeps toggling the
+    /// widget's own visibility — a hidden segment is just never attached.
+    pub fn register_segment(&self, id: &str, widget: &impl IsA<gtk4::Widget>) {
+        let widget: gtk4::Widget = widget.clone().upcast();
+        // Screen readers announce what the segment is along with its text.
+        if let Some(segment) = impulse_core::status_bar::segment_by_id(id) {
+            widget.update_property(&[gtk4::accessible::Property::Description(segment.name)]);
         }
+        self.segments.borrow_mut().push((id.to_string(), widget));
+        self.relayout();
     }
 
-    pub fn update_cwd(&self, path: &str) {
-        // Shorten home directory to ~
-        let display_path = match impulse_core::shell::get_home_directory() {
-            Ok(home) => {
-                if path.starts_with(&home) {
-                    format!("~{}", &path[home.len()..])
-                } else {
-                    path.to_string()
+    /// Apply the `status_bar_segments` / `status_bar_hidden_segments` settings.
+    pub fn set_layout(&self, order: &[String], hidden: &[String]) {
+        {
+            let layout = self.layout.borrow();
+            if layout.0 == order && layout.1 == hidden {
+                return;
+            }
+        }
+        *self.layout.borrow_mut() = (order.to_vec(), hidden.to_vec());
+        self.relayout();
+    }
+
+    fn relayout(&self) {
+        let segments = self.segments.borrow();
+        for (_, widget) in segments.iter() {
+            if let Some(parent) = widget.parent().and_then(|p| p.downcast::<gtk4::Box>().ok()) {
+                parent.remove(widget);
+            }
+        }
+        let (order, hidden) = &*self.layout.borrow();
+        for info in impulse_core::status_bar::resolve_segments(order, hidden) {
+            if let Some((_, widget)) = segments.iter().find(|(id, _)| id == info.id) {
+                match info.alignment {
+                    SegmentAlignment::Left => self.left_box.append(widget),
+                    SegmentAlignment::Right => self.right_box.append(widget),
                 }
             }
-            Err(_) => path.to_string(),
-        };
-        self.cwd_label.set_text(&display_path);
+        }
+        for (id, widget) in segments.iter() {
+            let hidden = hidden.iter().any(|h| h == id);
+            if impulse_core::status_bar::segment_by_id(id).is_none() && !hidden {
+                self.right_box.append(widget);
+            }
+        }
+    }
+
+    pub fn update_cwd(&self, path: &str) {
+        self.fill_cwd_breadcrumbs(path);
 
         // Update git branch
         match impulse_core::filesystem::get_git_branch(path) {
@@ -117,6 +331,154 @@ impl StatusBar {
                 self.branch_label.set_visible(false);
             }
         }
+        self.update_ahead_behind(path);
+        self.update_ci_status(path);
+    }
+
+    /// Turn the CI status segment on or off (`show_ci_status` setting).
+    pub fn set_ci_status_enabled(&self, enabled: bool) {
+        self.ci_enabled.set(enabled);
+        if !enabled {
+            self.ci_generation.set(self.ci_generation.get() + 1);
+            self.ci_button.set_visible(false);
+        }
+    }
+
+    /// Look up the GitHub/GitLab CI status of HEAD in the background and
+    /// show it as a clickable segment. Hidden outside hosted repositories.
+    pub fn update_ci_status(&self, path: &str) {
+        if !self.ci_enabled.get() {
+            return;
+        }
+        let generation = self.ci_generation.get() + 1;
+        self.ci_generation.set(generation);
+        let path = path.to_string();
+        let button = self.ci_button.clone();
+        let ci_url = self.ci_url.clone();
+        let ci_generation = self.ci_generation.clone();
+        gtk4::glib::spawn_future_local(async move {
+            let result =
+                gtk4::gio::spawn_blocking(move || impulse_core::git_hosting::head_ci_status(&path))
+                    .await;
+            if ci_generation.get() != generation {
+                return;
+            }
+            let Ok(Ok(Some(status))) = result else {
+                button.set_visible(false);
+                return;
+            };
+            let (text, class) = match status.state {
+                CiState::Success => ("● CI", "ci-success"),
+                CiState::Failure => ("● CI", "ci-failure"),
+                CiState::Pending => ("◌ CI", "ci-pending"),
+            };
+            for css in ["ci-success", "ci-failure", "ci-pending"] {
+                button.remove_css_class(css);
+            }
+            button.add_css_class(class);
+            button.set_label(text);
+            button.set_tooltip_text(Some(&format!(
+                "{} passed, {} failed, {} running. Click to open.",
+                status.passed, status.failed, status.pending
+            )));
+            *ci_url.borrow_mut() = status.url;
+            button.set_visible(true);
+        });
+    }
+
+    /// Show how far the branch containing `path` is ahead of/behind its
+    /// upstream as of the last fetch. Hidden when there is no upstream.
+    pub fn update_ahead_behind(&self, path: &str) {
+        match impulse_core::git::ahead_behind(path) {
+            Ok(Some(status)) => {
+                let text = match (status.ahead, status.behind) {
+                    (0, 0) => "✓".to_string(),
+                    (ahead, 0) => format!("↑{}", ahead),
+                    (0, behind) => format!("↓{}", behind),
+                    (ahead, behind) => format!("↑{} ↓{}", ahead, behind),
+                };
+                self.sync_label.set_text(&text);
+                self.sync_label.set_tooltip_text(Some(&format!(
+                    "{} ahead, {} behind {}",
+                    status.ahead, status.behind, status.upstream
+                )));
+                self.sync_label.set_visible(true);
+            }
+            _ => self.sync_label.set_visible(false),
+        }
+    }
+
+    pub fn show_git_progress(&self, text: &str) {
+        self.git_progress_label.set_text(text);
+        self.git_progress_label.set_visible(true);
+    }
+
+    pub fn clear_git_progress(&self) {
+        self.git_progress_label.set_visible(false);
+    }
+
+    /// Show the first key of a pending chord, or hide it for `None`.
+    pub fn set_pending_chord(&self, first: Option<&str>) {
+        match first {
+            Some(first) => {
+                self.chord_label
+                    .set_text(&format!("({}) was pressed. Waiting for second key…", first));
+                self.chord_label.set_visible(true);
+            }
+            None => self.chord_label.set_visible(false),
+        }
+    }
+
+    /// Show language server progress with a spinner, or hide it for `None`.
+    pub fn set_lsp_progress(&self, text: Option<&str>) {
+        match text {
+            Some(text) => {
+                self.lsp_progress_label.set_text(text);
+                self.lsp_progress_spinner.start();
+                self.lsp_progress_box.set_visible(true);
+            }
+            None => {
+                self.lsp_progress_spinner.stop();
+                self.lsp_progress_box.set_visible(false);
+            }
+        }
+    }
+
+    /// Record the diagnostics for one file and refresh the problems total.
+    pub fn set_file_problems(&self, path: &str, errors: usize, warnings: usize) {
+        {
+            let mut counts = self.problem_counts.borrow_mut();
+            if errors == 0 && warnings == 0 {
+                counts.remove(path);
+            } else {
+                counts.insert(path.to_string(), (errors, warnings));
+            }
+        }
+        self.refresh_problems();
+    }
+
+    /// Drop a closed file from the problems total.
+    pub fn clear_file_problems(&self, path: &str) {
+        if self.problem_counts.borrow_mut().remove(path).is_some() {
+            self.refresh_problems();
+        }
+    }
+
+    fn refresh_problems(&self) {
+        let (errors, warnings) = self
+            .problem_counts
+            .borrow()
+            .values()
+            .fold((0, 0), |(e, w), (fe, fw)| (e + fe, w + fw));
+        match impulse_core::status_bar::problems_summary(errors, warnings) {
+            Some(text) => {
+                self.problems_label
+                    .set_text(&format!("⊗ {}  ⚠ {}", errors, warnings));
+                self.problems_label.set_tooltip_text(Some(&text));
+                self.problems_label.set_visible(true);
+            }
+            None => self.problems_label.set_visible(false),
+        }
     }
 
     pub fn update_cursor_position(&self, line: i32, col: i32) {
@@ -126,8 +488,8 @@ impl StatusBar {
     }
 
     pub fn update_language(&self, lang: &str) {
-        self.language_label.set_text(lang);
-        self.language_label.set_visible(true);
+        self.language_button.set_label(lang);
+        self.language_button.set_visible(true);
     }
 
     pub fn update_encoding(&self, enc: &str) {
@@ -135,9 +497,20 @@ impl StatusBar {
         self.encoding_label.set_visible(true);
     }
 
-    pub fn update_indent_info(&self, info: &str) {
-        self.indent_label.set_text(info);
-        self.indent_label.set_visible(true);
+    pub fn update_indentation(&self, indentation: Indentation) {
+        self.indent_button.set_label(&indentation.label());
+        self.indent_button.set_visible(true);
+    }
+
+    /// Show the selected pane's zoom, or hide the segment at the default size.
+    pub fn update_zoom(&self, label: Option<&str>) {
+        match label {
+            Some(label) => {
+                self.zoom_button.set_label(label);
+                self.zoom_button.set_visible(true);
+            }
+            None => self.zoom_button.set_visible(false),
+        }
     }
 
     pub fn update_blame(&self, info: &str) {
@@ -163,31 +536,379 @@ impl StatusBar {
         self.preview_button.remove_css_class("previewing");
     }
 
-    pub fn show_update(&self, version: &str, url: &str) {
-        self.update_button
-            .set_label(&format!("⬆ Update v{}", version));
-        self.update_button
-            .set_tooltip_text(Some("Click to open release page"));
+    pub fn show_update(&self, label: &str, tooltip: &str, on_click: impl Fn() + 'static) {
+        self.update_button.set_label(label);
+        self.update_button.set_tooltip_text(Some(tooltip));
         self.update_button.set_visible(true);
-        let url = url.to_string();
-        self.update_button.connect_clicked(move |_| {
-            let _ = open::that(&url);
-        });
+        self.update_button.connect_clicked(move |_| on_click());
+    }
+
+    pub fn set_command_runner(&self, runner: CommandRunner) {
+        *self.command_runner.borrow_mut() = Some(runner);
+    }
+
+    pub fn set_cwd_actions(&self, actions: CwdActions) {
+        *self.cwd_actions.borrow_mut() = Some(Rc::new(actions));
+    }
+
+    /// Rebuild the breadcrumb: each directory opens in the sidebar, and the
+    /// last one opens a menu of the terminal's directory history.
+    fn fill_cwd_breadcrumbs(&self, path: &str) {
+        while let Some(child) = self.cwd_box.first_child() {
+            self.cwd_box.remove(&child);
+        }
+        let home = impulse_core::shell::get_home_directory().ok();
+        let crumbs = impulse_core::status_bar::cwd_breadcrumbs(path, home.as_deref());
+        let starts_at_root = crumbs
+            .first()
+            .is_some_and(|c| c.path == "/" && c.label == "/");
+        let last = crumbs.len().saturating_sub(1);
+        for (i, crumb) in crumbs.into_iter().enumerate() {
+            if i > 0 && !(i == 1 && starts_at_root) {
+                let separator = gtk4::Label::new(Some("/"));
+                separator.add_css_class("dim-label");
+                self.cwd_box.append(&separator);
+            }
+            if i == last {
+                let button = gtk4::MenuButton::new();
+                button.set_label(&crumb.label);
+                button.set_has_frame(false);
+                button.set_always_show_arrow(false);
+                button.set_cursor_from_name(Some("pointer"));
+                button.set_tooltip_text(Some("Directory History"));
+                button.set_popover(Some(&cwd_history_popover(
+                    crumb.path,
+                    self.cwd_actions.clone(),
+                )));
+                self.cwd_box.append(&button);
+            } else {
+                let button = gtk4::Button::with_label(&crumb.label);
+                button.set_has_frame(false);
+                button.set_cursor_from_name(Some("pointer"));
+                button.set_tooltip_text(Some(&format!(
+                    "Open {} in Sidebar",
+                    display_path(&crumb.path)
+                )));
+                let actions = self.cwd_actions.clone();
+                button.connect_clicked(move |_| {
+                    let actions = actions.borrow().clone();
+                    if let Some(actions) = actions {
+                        (actions.open_directory)(&crumb.path);
+                    }
+                });
+                self.cwd_box.append(&button);
+            }
+        }
+    }
+
+    /// Show the unread count from the notification history on the bell.
+    pub fn refresh_notifications(&self) {
+        let unread = impulse_core::notifications::list()
+            .into_iter()
+            .filter(|n| !n.read)
+            .collect::<Vec<_>>();
+        self.notifications_count.set_visible(!unread.is_empty());
+        self.notifications_count
+            .set_label(&unread.len().to_string());
+        if unread.iter().any(|n| n.severity == Severity::Error) {
+            self.notifications_button.add_css_class("has-errors");
+        } else {
+            self.notifications_button.remove_css_class("has-errors");
+        }
+    }
+
+    /// Open the notification history panel.
+    pub fn show_notifications(&self) {
+        self.notifications_button.popup();
     }
 
     pub fn hide_editor_info(&self) {
-        self.language_label.set_visible(false);
+        self.language_button.set_visible(false);
         self.encoding_label.set_visible(false);
         self.cursor_label.set_visible(false);
-        self.indent_label.set_visible(false);
+        self.indent_button.set_visible(false);
         self.blame_label.set_visible(false);
         self.preview_button.set_visible(false);
     }
 }
 
+/// `path` with the home directory shortened to `~`.
+fn display_path(path: &str) -> String {
+    match impulse_core::shell::get_home_directory() {
+        Ok(home) if path.starts_with(&home) => format!("~{}", &path[home.len()..]),
+        _ => path.to_string(),
+    }
+}
+
+/// The last breadcrumb's menu: open `dir` in the sidebar, or change the
+/// terminal to a directory it was in before. Rebuilt each time it opens.
+fn cwd_history_popover(dir: String, actions: Rc<RefCell<Option<Rc<CwdActions>>>>) -> gtk4::Popover {
+    let list = gtk4::Box::new(gtk4::Orientation::Vertical, 0);
+    let popover = gtk4::Popover::new();
+    popover.add_css_class("menu");
+    popover.set_child(Some(&list));
+    let popover_ref = popover.downgrade();
+    popover.connect_show(move |_| {
+        let (Some(popover), Some(actions)) = (popover_ref.upgrade(), actions.borrow().clone())
+        else {
+            return;
+        };
+        while let Some(child) = list.first_child() {
+            list.remove(&child);
+        }
+        let item = |label: &str, on_click: Rc<dyn Fn()>| {
+            let button = gtk4::Button::with_label(label);
+            button.add_css_class("flat");
+            if let Some(label) = button.child().and_downcast::<gtk4::Label>() {
+                label.set_xalign(0.0);
+            }
+            let popover = popover.downgrade();
+            button.connect_clicked(move |_| {
+                if let Some(popover) = popover.upgrade() {
+                    popover.popdown();
+                }
+                on_click();
+            });
+            list.append(&button);
+        };
+
+        item(
+            "Open in Sidebar",
+            Rc::new({
+                let actions = actions.clone();
+                let dir = dir.clone();
+                move || (actions.open_directory)(&dir)
+            }),
+        );
+        let history: Vec<String> = (actions.history)()
+            .into_iter()
+            .filter(|d| *d != dir)
+            .collect();
+        if history.is_empty() {
+            return;
+        }
+        list.append(&gtk4::Separator::new(gtk4::Orientation::Horizontal));
+        for recent in history {
+            item(
+                &display_path(&recent),
+                Rc::new({
+                    let actions = actions.clone();
+                    move || (actions.change_directory)(&recent)
+                }),
+            );
+        }
+    });
+    popover
+}
+
+/// The bell's panel. Its rows are rebuilt from the history each time it
+/// opens, which marks everything read.
+fn notifications_popover(command_runner: Rc<RefCell<Option<CommandRunner>>>) -> gtk4::Popover {
+    let list = gtk4::Box::new(gtk4::Orientation::Vertical, 0);
+    let scroll = gtk4::ScrolledWindow::new();
+    scroll.set_hscrollbar_policy(gtk4::PolicyType::Never);
+    scroll.set_propagate_natural_height(true);
+    scroll.set_max_content_height(420);
+    scroll.set_min_content_width(360);
+    scroll.set_child(Some(&list));
+
+    let heading = gtk4::Label::new(Some("Notifications"));
+    heading.add_css_class("heading");
+    heading.set_hexpand(true);
+    heading.set_halign(gtk4::Align::Start);
+    let clear_button = gtk4::Button::with_label("Clear All");
+    clear_button.add_css_class("flat");
+    let header = gtk4::Box::new(gtk4::Orientation::Horizontal, 6);
+    header.set_margin_start(6);
+    header.set_margin_end(6);
+    header.append(&heading);
+    header.append(&clear_button);
+
+    let content = gtk4::Box::new(gtk4::Orientation::Vertical, 6);
+    content.append(&header);
+    content.append(&scroll);
+
+    let popover = gtk4::Popover::new();
+    popover.add_css_class("notifications-panel");
+    popover.set_child(Some(&content));
+
+    let fill: Rc<dyn Fn()> = {
+        let list = list.clone();
+        let popover = popover.downgrade();
+        Rc::new(move || {
+            let Some(popover) = popover.upgrade() else {
+                return;
+            };
+            fill_notification_list(&list, &popover, &command_runner);
+        })
+    };
+    {
+        let fill = fill.clone();
+        popover.connect_show(move |_| {
+            fill();
+            impulse_core::notifications::mark_all_read();
+            crate::notifications::refresh_status_bars();
+        });
+    }
+    clear_button.connect_clicked(move |_| {
+        impulse_core::notifications::clear();
+        fill();
+        crate::notifications::refresh_status_bars();
+    });
+    popover
+}
+
+fn fill_notification_list(
+    list: &gtk4::Box,
+    popover: &gtk4::Popover,
+    command_runner: &Rc<RefCell<Option<CommandRunner>>>,
+) {
+    while let Some(child) = list.first_child() {
+        list.remove(&child);
+    }
+    let notifications = impulse_core::notifications::list();
+    if notifications.is_empty() {
+        let empty = gtk4::Label::new(Some("No notifications"));
+        empty.add_css_class("dim-label");
+        empty.set_margin_top(12);
+        empty.set_margin_bottom(12);
+        list.append(&empty);
+        return;
+    }
+    for notification in notifications {
+        let row = gtk4::Box::new(gtk4::Orientation::Vertical, 2);
+        row.add_css_class("notification-row");
+        row.add_css_class(match notification.severity {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        });
+
+        let title_text = if notification.count > 1 {
+            format!("{} ({}×)", notification.title, notification.count)
+        } else {
+            notification.title.clone()
+        };
+        let title = gtk4::Label::new(Some(&title_text));
+        title.add_css_class("notification-title");
+        title.set_hexpand(true);
+        title.set_halign(gtk4::Align::Start);
+        title.set_wrap(true);
+        title.set_xalign(0.0);
+        let dismiss = gtk4::Button::from_icon_name("window-close-symbolic");
+        dismiss.add_css_class("flat");
+        dismiss.set_tooltip_text(Some("Dismiss"));
+        dismiss.set_valign(gtk4::Align::Start);
+        let title_row = gtk4::Box::new(gtk4::Orientation::Horizontal, 6);
+        title_row.append(&title);
+        title_row.append(&dismiss);
+        row.append(&title_row);
+
+        if !notification.message.is_empty() {
+            let message = gtk4::Label::new(Some(&notification.message));
+            message.add_css_class("dim-label");
+            message.set_wrap(true);
+            message.set_xalign(0.0);
+            message.set_selectable(true);
+            message.set_max_width_chars(60);
+            row.append(&message);
+        }
+
+        if !notification.actions.is_empty() {
+            let actions = gtk4::Box::new(gtk4::Orientation::Horizontal, 6);
+            actions.set_margin_top(4);
+            for action in &notification.actions {
+                let button = gtk4::Button::with_label(&action.label);
+                let command = action.command.clone();
+                let command_runner = command_runner.clone();
+                let popover = popover.downgrade();
+                button.connect_clicked(move |_| {
+                    if let Some(popover) = popover.upgrade() {
+                        popover.popdown();
+                    }
+                    let runner = command_runner.borrow().clone();
+                    match runner {
+                        Some(run) => run(&command),
+                        None => log::warn!("No command runner for notification action {}", command),
+                    }
+                });
+                actions.append(&button);
+            }
+            row.append(&actions);
+        }
+
+        let id = notification.id;
+        let row_ref = row.downgrade();
+        let list_ref = list.downgrade();
+        dismiss.connect_clicked(move |_| {
+            impulse_core::notifications::dismiss(id);
+            if let (Some(row), Some(list)) = (row_ref.upgrade(), list_ref.upgrade()) {
+                list.remove(&row);
+            }
+            crate::notifications::refresh_status_bars();
+        });
+        list.append(&row);
+    }
+}
+
+/// Menu for the indentation segment. The `win.convert-indentation` and
+/// `win.indent-using` actions are registered by the window.
+fn indent_menu() -> gtk4::gio::Menu {
+    let menu = gtk4::gio::Menu::new();
+    let convert = gtk4::gio::Menu::new();
+    convert.append(
+        Some("Convert Indentation to Spaces"),
+        Some("win.convert-indentation::spaces"),
+    );
+    convert.append(
+        Some("Convert Indentation to Tabs"),
+        Some("win.convert-indentation::tabs"),
+    );
+    menu.append_section(None, &convert);
+
+    let using = gtk4::gio::Menu::new();
+    for (label, kind) in [
+        ("Indent Using Spaces", "spaces"),
+        ("Indent Using Tabs", "tabs"),
+    ] {
+        let widths = gtk4::gio::Menu::new();
+        for width in INDENT_WIDTHS {
+            widths.append(
+                Some(&width.to_string()),
+                Some(&format!("win.indent-using::{}-{}", kind, width)),
+            );
+        }
+        using.append_submenu(Some(label), &widths);
+    }
+    menu.append_section(None, &using);
+    menu
+}
+
+/// Menu for the language segment: automatic detection, then every language
+/// a file can be switched to. The `win.set-language` action is registered
+/// by the window.
+fn language_menu() -> gtk4::gio::Menu {
+    let menu = gtk4::gio::Menu::new();
+    let auto = gtk4::gio::Menu::new();
+    auto.append(Some("Auto Detect"), Some("win.set-language::auto"));
+    menu.append_section(None, &auto);
+
+    let languages = gtk4::gio::Menu::new();
+    for language in impulse_core::languages::LANGUAGES {
+        languages.append(
+            Some(language.label),
+            Some(&format!("win.set-language::{}", language.id)),
+        );
+    }
+    menu.append_section(None, &languages);
+    menu
+}
+
 /// Shared status bar state that can be updated from terminal CWD change signals.
 pub type SharedStatusBar = Rc<RefCell<StatusBar>>;
 
 pub fn new_shared() -> SharedStatusBar {
-    Rc::new(RefCell::new(StatusBar::new()))
+    let status_bar = Rc::new(RefCell::new(StatusBar::new()));
+    crate::notifications::register_status_bar(&status_bar);
+    status_bar
 }
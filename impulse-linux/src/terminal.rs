@@ -12,6 +12,8 @@ use impulse_terminal::{
     TerminalCommandBlock, TerminalConfig, TerminalEvent, TerminalMode, CELL_STRIDE,
     FIXED_HEADER_SIZE, RANGE_ENTRY_SIZE,
 };
+use libadwaita as adw;
+use libadwaita::prelude::*;
 
 use crate::theme::ThemeColors;
 
@@ -53,7 +55,9 @@ type InputRedirect = Box<dyn Fn(Option<char>)>;
 pub struct ShellSpawnCache {
     shell_name: String,
     launch: impulse_core::shell::ShellLaunchConfig,
-    working_dir: String,
+    /// Where new shells start when no directory is given: the home
+    /// directory, or the folder a window was opened on.
+    working_dir: RefCell<String>,
 }
 
 impl ShellSpawnCache {
@@ -81,13 +85,23 @@ impl ShellSpawnCache {
         Self {
             shell_name,
             launch,
-            working_dir,
+            working_dir: RefCell::new(working_dir),
         }
     }
 
     pub fn shell_name(&self) -> &str {
         &self.shell_name
     }
+
+    /// Where shells start when no directory is given: the home directory, or
+    /// the folder the window was opened on.
+    pub fn working_dir(&self) -> String {
+        self.working_dir.borrow().clone()
+    }
+
+    pub fn set_working_dir(&self, dir: &str) {
+        *self.working_dir.borrow_mut() = dir.to_string();
+    }
 }
 
 impl Drop for ShellSpawnCache {
@@ -102,6 +116,10 @@ struct TerminalState {
     grid_buffer: RefCell<Vec<u8>>,
     font_family: RefCell<String>,
     font_size: Cell<i32>,
+    /// `terminal_font_size` from settings, before this pane's zoom.
+    base_font_size: Cell<i32>,
+    /// Points added to `base_font_size` by zooming this pane.
+    zoom: Cell<i32>,
     cell_width: Cell<u16>,
     cell_height: Cell<u16>,
     scrollback_lines: Cell<usize>,
@@ -111,10 +129,18 @@ struct TerminalState {
     cols: Cell<u16>,
     rows: Cell<u16>,
     current_directory: RefCell<Option<String>>,
+    /// Directories the shell has been in, most recent first.
+    directory_history: RefCell<Vec<String>>,
     title: RefCell<String>,
     mode_bits: Cell<u16>,
     colors: RefCell<impulse_terminal::TerminalColors>,
+    cursor_color: Cell<RgbColor>,
+    background_opacity: Cell<f64>,
+    /// Draw bold text in the normal ANSI colors with their bright variants.
+    bold_is_bright: Cell<bool>,
     copy_on_select: Rc<Cell<bool>>,
+    /// Preview risky pastes first (`terminal_confirm_paste`).
+    confirm_paste: Cell<bool>,
     scroll_on_output: Cell<bool>,
     terminal_bell: Cell<bool>,
     selected_command_block_id: Cell<Option<u64>>,
@@ -154,6 +180,8 @@ impl TerminalState {
             grid_buffer: RefCell::new(Vec::new()),
             font_family: RefCell::new("monospace".to_string()),
             font_size: Cell::new(14),
+            base_font_size: Cell::new(14),
+            zoom: Cell::new(0),
             cell_width: Cell::new(DEFAULT_CELL_WIDTH),
             cell_height: Cell::new(DEFAULT_CELL_HEIGHT),
             scrollback_lines: Cell::new(10_000),
@@ -163,10 +191,15 @@ impl TerminalState {
             cols: Cell::new(DEFAULT_COLS),
             rows: Cell::new(DEFAULT_ROWS),
             current_directory: RefCell::new(None),
+            directory_history: RefCell::new(Vec::new()),
             title: RefCell::new("Terminal".to_string()),
             mode_bits: Cell::new(0),
             colors: RefCell::new(impulse_terminal::TerminalColors::default()),
+            cursor_color: Cell::new(RgbColor::new(220, 215, 186)),
+            background_opacity: Cell::new(1.0),
+            bold_is_bright: Cell::new(true),
             copy_on_select,
+            confirm_paste: Cell::new(true),
             scroll_on_output: Cell::new(true),
             terminal_bell: Cell::new(false),
             selected_command_block_id: Cell::new(None),
@@ -282,7 +315,11 @@ pub fn apply_settings(
         &settings.terminal_font_family
     };
     *state.font_family.borrow_mut() = family.to_string();
-    state.font_size.set(settings.terminal_font_size.max(8));
+    state.base_font_size.set(settings.terminal_font_size);
+    state.font_size.set(impulse_core::zoom::zoomed_font_size(
+        settings.terminal_font_size,
+        state.zoom.get(),
+    ));
     state
         .scrollback_lines
         .set(settings.terminal_scrollback.max(100) as usize);
@@ -298,10 +335,27 @@ pub fn apply_settings(
         .set(settings.terminal_scroll_on_output);
     state.terminal_bell.set(settings.terminal_bell);
     copy_on_select_flag.set(settings.terminal_copy_on_select);
+    state.confirm_paste.set(settings.terminal_confirm_paste);
     state.blocks_enabled.set(settings.terminal_blocks);
     state.input_bar_managed.set(settings.terminal_context_bar);
     state.block_style.set(block_style_from_theme(theme));
-    *state.colors.borrow_mut() = terminal_colors(theme);
+    *state.colors.borrow_mut() = terminal_colors(settings, theme);
+    state.cursor_color.set(hex_to_rgb(
+        settings
+            .terminal_cursor_color()
+            .or(theme.resolved.map(|r| r.cursor.as_str()))
+            .unwrap_or(theme.fg),
+    ));
+    state.bold_is_bright.set(settings.terminal_bold_is_bright);
+    let opacity = settings.terminal_background_opacity.clamp(0.3, 1.0);
+    state.background_opacity.set(opacity);
+    // The root paints the theme background behind the grid; drop it so a
+    // translucent terminal shows what lies behind the terminal view.
+    if opacity < 1.0 {
+        terminal.add_css_class("translucent");
+    } else {
+        terminal.remove_css_class("translucent");
+    }
 
     if let Some(backend) = state.backend.borrow_mut().as_mut() {
         backend.set_colors(&terminal_config(settings, theme, None, None));
@@ -313,7 +367,9 @@ pub fn spawn_shell(terminal: &Terminal, cache: &Rc<ShellSpawnCache>, working_dir
     let Some(state) = state(terminal) else {
         return;
     };
-    let dir = working_dir.unwrap_or(&cache.working_dir).to_string();
+    let dir = working_dir
+        .map(str::to_string)
+        .unwrap_or_else(|| cache.working_dir.borrow().clone());
     let config = backend_config_from_launch(&cache.launch, Some(dir), &state);
     start_backend(terminal, &state, config);
 }
@@ -437,10 +493,36 @@ pub fn selected_text(terminal: &Terminal) -> Option<String> {
         .and_then(TerminalBackend::selected_text)
 }
 
+/// The terminal's whole buffer as text, scrollback included.
+pub fn scrollback_text(terminal: &Terminal) -> Option<String> {
+    state(terminal)?
+        .backend
+        .borrow()
+        .as_ref()
+        .map(TerminalBackend::scrollback_text)
+}
+
+/// PID of the terminal's shell, while it runs.
+pub fn shell_pid(terminal: &Terminal) -> Option<u32> {
+    state(terminal)?
+        .backend
+        .borrow()
+        .as_ref()
+        .map(TerminalBackend::child_pid)
+        .filter(|&pid| pid > 0)
+}
+
 pub fn current_directory(terminal: &Terminal) -> Option<String> {
     state(terminal)?.current_directory.borrow().clone()
 }
 
+/// Directories the terminal's shell has been in, most recent first.
+pub fn directory_history(terminal: &Terminal) -> Vec<String> {
+    state(terminal)
+        .map(|state| state.directory_history.borrow().clone())
+        .unwrap_or_default()
+}
+
 pub fn title(terminal: &Terminal) -> String {
     state(terminal)
         .map(|s| s.title.borrow().clone())
@@ -537,6 +619,18 @@ pub fn connect_child_exited(terminal: &Terminal, f: impl Fn(&Terminal) + 'static
     }
 }
 
+/// Drop every callback connected above and the input redirect, so a
+/// terminal moved to another window can be wired up to that one instead.
+pub fn disconnect_signals(terminal: &Terminal) {
+    if let Some(state) = state(terminal) {
+        state.cwd_callbacks.borrow_mut().clear();
+        state.command_block_callbacks.borrow_mut().clear();
+        state.title_callbacks.borrow_mut().clear();
+        state.child_exited_callbacks.borrow_mut().clear();
+        state.input_redirect.borrow_mut().take();
+    }
+}
+
 pub fn search(terminal: &Terminal, pattern: &str) {
     if let Some(state) = state(terminal) {
         if let Some(backend) = state.backend.borrow().as_ref() {
@@ -830,10 +924,19 @@ fn scroll_to_command_block(state: &Rc<TerminalState>, block_id: u64) -> bool {
     scrolled
 }
 
-pub fn set_font(terminal: &Terminal, family: &str, size: i32) {
+/// This pane's font size from settings and its zoom over it.
+pub fn zoom(terminal: &Terminal) -> Option<(i32, i32)> {
+    let state = state(terminal)?;
+    Some((state.base_font_size.get(), state.zoom.get()))
+}
+
+pub fn set_zoom(terminal: &Terminal, zoom: i32) {
     if let Some(state) = state(terminal) {
-        *state.font_family.borrow_mut() = family.to_string();
-        state.font_size.set(size.max(8));
+        state.zoom.set(zoom);
+        state.font_size.set(impulse_core::zoom::zoomed_font_size(
+            state.base_font_size.get(),
+            zoom,
+        ));
         state.drawing.queue_draw();
     }
 }
@@ -871,7 +974,7 @@ fn terminal_config(
         cursor_shape: parse_cursor_shape(&settings.terminal_cursor_shape),
         cursor_blink: settings.terminal_cursor_blink,
         working_directory,
-        colors: terminal_colors(theme),
+        colors: terminal_colors(settings, theme),
         minimum_contrast: settings.terminal_minimum_contrast.clamp(1.0, 21.0) as f32,
         ..TerminalConfig::default()
     };
@@ -908,7 +1011,14 @@ fn backend_config_from_launch(
     config
 }
 
-fn start_backend(terminal: &Terminal, state: &Rc<TerminalState>, config: TerminalConfig) {
+fn start_backend(terminal: &Terminal, state: &Rc<TerminalState>, mut config: TerminalConfig) {
+    if let Some(dir) = &config.working_directory {
+        config
+            .env_vars
+            .extend(impulse_core::environment::project_env(
+                std::path::Path::new(dir),
+            ));
+    }
     if let Some(backend) = state.backend.borrow_mut().take() {
         backend.shutdown();
     }
@@ -1058,10 +1168,16 @@ fn install_input_handlers(terminal: &Terminal) {
         let term = terminal.clone();
         drop_target_text.connect_drop(move |_target, value, _x, _y| {
             if let Ok(text) = value.get::<String>() {
-                write_text(
-                    &term,
-                    &shell_escape(&text.replace('\n', " ").replace('\r', "")),
-                );
+                // Apps that drag files as text send a URI list.
+                let paths = impulse_core::file_drop::parse_uri_list(&text);
+                if !paths.is_empty() {
+                    write_text(&term, &impulse_core::file_drop::shell_quoted_paths(&paths));
+                } else {
+                    write_text(
+                        &term,
+                        &shell_escape(&text.replace('\n', " ").replace('\r', "")),
+                    );
+                }
                 return true;
             }
             false
@@ -1077,14 +1193,9 @@ fn install_input_handlers(terminal: &Terminal) {
         let term = terminal.clone();
         drop_target_files.connect_drop(move |_target, value, _x, _y| {
             if let Ok(file_list) = value.get::<gtk4::gdk::FileList>() {
-                let paths: Vec<String> = file_list
-                    .files()
-                    .iter()
-                    .filter_map(|f| f.path())
-                    .map(|p| shell_escape(&p.to_string_lossy()))
-                    .collect();
+                let paths: Vec<_> = file_list.files().iter().filter_map(|f| f.path()).collect();
                 if !paths.is_empty() {
-                    write_text(&term, &paths.join(" "));
+                    write_text(&term, &impulse_core::file_drop::shell_quoted_paths(&paths));
                     return true;
                 }
             }
@@ -1785,6 +1896,10 @@ fn poll_events(terminal: &Terminal) -> bool {
                 }
             }
             TerminalEvent::CwdChanged(path) => {
+                impulse_core::status_bar::record_directory(
+                    &mut state.directory_history.borrow_mut(),
+                    &path,
+                );
                 *state.current_directory.borrow_mut() = Some(path);
                 for callback in state.cwd_callbacks.borrow().iter() {
                     callback(terminal);
@@ -1885,7 +2000,7 @@ fn draw_terminal(cr: &Context, width: i32, height: i32, state: &Rc<TerminalState
     resize_backend_if_needed(state, cols, rows);
 
     let bg = current_background(state);
-    set_rgb(cr, bg);
+    set_rgba(cr, bg, state.background_opacity.get());
     cr.rectangle(0.0, 0.0, width as f64, height as f64);
     let _ = cr.fill();
 
@@ -1965,6 +2080,10 @@ fn draw_terminal(cr: &Context, width: i32, height: i32, state: &Rc<TerminalState
     }
 
     let default_bg = current_background(state);
+    let bold_palette = state
+        .bold_is_bright
+        .get()
+        .then(|| state.colors.borrow().palette);
     let mut active_font: Option<(FontSlant, FontWeight)> =
         Some((FontSlant::Normal, FontWeight::Normal));
 
@@ -1982,6 +2101,11 @@ fn draw_terminal(cr: &Context, width: i32, height: i32, state: &Rc<TerminalState
             let searched = searched_cells[cell_index];
             let mut fg = RgbColor::new(buf[offset + 4], buf[offset + 5], buf[offset + 6]);
             let mut bg = RgbColor::new(buf[offset + 7], buf[offset + 8], buf[offset + 9]);
+            if let Some(palette) = bold_palette.as_ref() {
+                if flags.contains(CellFlags::BOLD) {
+                    fg = bright_variant(palette, fg);
+                }
+            }
             if flags.contains(CellFlags::INVERSE) || selected {
                 std::mem::swap(&mut fg, &mut bg);
             }
@@ -2076,7 +2200,7 @@ fn draw_terminal(cr: &Context, width: i32, height: i32, state: &Rc<TerminalState
     {
         let x = TERMINAL_PADDING + cursor_col as f64 * cell_width;
         let y = TERMINAL_PADDING + cursor_row as f64 * cell_height;
-        set_rgb(cr, RgbColor::new(220, 215, 186));
+        set_rgb(cr, state.cursor_color.get());
         match cursor_shape {
             1 => cr.rectangle(x, y, 1.5, cell_height),
             2 => cr.rectangle(x, y + cell_height - 2.0, cell_width, 2.0),
@@ -2651,16 +2775,69 @@ fn paste_text(terminal: &Terminal, text: &str) {
     if text.is_empty() {
         return;
     }
-    let bracketed = state(terminal)
-        .map(|s| s.mode_bits.get() & TerminalMode::BRACKETED_PASTE.bits() != 0)
-        .unwrap_or(false);
-    if bracketed {
-        write(terminal, b"\x1b[200~");
-    }
-    write_text(terminal, text);
-    if bracketed {
-        write(terminal, b"\x1b[201~");
+    let Some(state) = state(terminal) else {
+        return;
+    };
+    if state.confirm_paste.get() {
+        if let Some(confirmation) = impulse_core::paste_guard::check(text, is_bracketed(terminal)) {
+            confirm_paste(terminal, text, &confirmation.message);
+            return;
+        }
     }
+    write_paste(terminal, text);
+}
+
+fn is_bracketed(terminal: &Terminal) -> bool {
+    state(terminal)
+        .map(|s| s.mode_bits.get() & TerminalMode::BRACKETED_PASTE.bits() != 0)
+        .unwrap_or(false)
+}
+
+fn write_paste(terminal: &Terminal, text: &str) {
+    write_text(
+        terminal,
+        &impulse_core::paste_guard::payload(text, is_bracketed(terminal)),
+    );
+}
+
+/// Show `text` in an editable preview and paste what the user confirms.
+fn confirm_paste(terminal: &Terminal, text: &str, message: &str) {
+    let dialog = adw::AlertDialog::builder()
+        .heading("Paste into Terminal?")
+        .body(message)
+        .build();
+    dialog.add_response("cancel", "Cancel");
+    dialog.add_response("paste", "Paste");
+    dialog.set_response_appearance("paste", adw::ResponseAppearance::Destructive);
+    dialog.set_default_response(Some("cancel"));
+    dialog.set_close_response("cancel");
+
+    let buffer = gtk4::TextBuffer::new(None);
+    buffer.set_text(text);
+    let view = gtk4::TextView::with_buffer(&buffer);
+    view.set_monospace(true);
+    view.set_wrap_mode(gtk4::WrapMode::WordChar);
+    let scrolled = gtk4::ScrolledWindow::builder()
+        .hscrollbar_policy(gtk4::PolicyType::Never)
+        .min_content_height(120)
+        .max_content_height(360)
+        .propagate_natural_height(true)
+        .child(&view)
+        .build();
+    dialog.set_extra_child(Some(&scrolled));
+
+    let term = terminal.clone();
+    dialog.connect_response(None, move |_dialog, response| {
+        if response == "paste" {
+            let (start, end) = buffer.bounds();
+            let text = buffer.text(&start, &end, false);
+            if !text.is_empty() {
+                write_paste(&term, &text);
+            }
+        }
+        term.grab_focus();
+    });
+    dialog.present(Some(terminal));
 }
 
 fn coords_to_cell(terminal: &Terminal, x: f64, y: f64) -> Option<(usize, usize)> {
@@ -2673,10 +2850,14 @@ fn coords_to_cell(terminal: &Terminal, x: f64, y: f64) -> Option<(usize, usize)>
     Some((col as usize, row as usize))
 }
 
-fn terminal_colors(theme: &ThemeColors) -> impulse_terminal::TerminalColors {
+/// The theme's terminal colors with the settings' palette overrides applied.
+fn terminal_colors(
+    settings: &crate::settings::Settings,
+    theme: &ThemeColors,
+) -> impulse_terminal::TerminalColors {
     let mut palette = [RgbColor::new(0, 0, 0); 16];
     for (idx, hex) in theme.terminal_palette.iter().enumerate().take(16) {
-        palette[idx] = hex_to_rgb(hex);
+        palette[idx] = hex_to_rgb(settings.terminal_palette_color(idx).unwrap_or(hex));
     }
     impulse_terminal::TerminalColors {
         foreground: hex_to_rgb(theme.fg),
@@ -2685,6 +2866,15 @@ fn terminal_colors(theme: &ThemeColors) -> impulse_terminal::TerminalColors {
     }
 }
 
+/// Swap one of the eight normal ANSI colors for its bright variant. The grid
+/// carries resolved colors, so this matches by value like the macOS renderer.
+fn bright_variant(palette: &[RgbColor; 16], fg: RgbColor) -> RgbColor {
+    palette[..8]
+        .iter()
+        .position(|c| *c == fg)
+        .map_or(fg, |i| palette[i + 8])
+}
+
 fn parse_cursor_shape(shape: &str) -> CursorShape {
     match shape {
         "ibeam" | "beam" | "line" => CursorShape::Beam,
@@ -1,3 +1,10 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::SystemTime;
+
+use impulse_core::theme::ResolvedTheme;
+
 /// A color theme definition for the entire application.
 pub struct ThemeColors {
     pub bg: &'static str,
@@ -23,6 +30,9 @@ pub struct ThemeColors {
     /// Editor selection background — a hex color with alpha (e.g. `"#7E9CD850"`).
     pub selection: &'static str,
     pub terminal_palette: [&'static str; 16],
+    /// The core theme this was built from, for user themes. The editor
+    /// then takes syntax colors from it instead of the palette hues.
+    pub resolved: Option<&'static ResolvedTheme>,
 }
 
 // ---------------------------------------------------------------------------
@@ -47,6 +57,7 @@ pub static KANAGAWA: ThemeColors = ThemeColors {
     base: "vs-dark",
     surface_style: "flat",
     selection: "#7E9CD850",
+    resolved: None,
     terminal_palette: [
         "#090618", "#C34043", "#76946A", "#C0A36E", "#7E9CD8", "#957FB8", "#6A9589", "#C8C093",
         "#727169", "#E82424", "#98BB6C", "#E6C384", "#7FB4CA", "#938AA9", "#7AA89F", "#DCD7BA",
@@ -71,6 +82,7 @@ pub static ROSE_PINE: ThemeColors = ThemeColors {
     base: "vs-dark",
     surface_style: "flat",
     selection: "#c4a7e740",
+    resolved: None,
     terminal_palette: [
         "#26233a", "#eb6f92", "#31748f", "#f6c177", "#9ccfd8", "#c4a7e7", "#ebbcba", "#e0def4",
         "#6e6a86", "#eb6f92", "#31748f", "#f6c177", "#9ccfd8", "#c4a7e7", "#ebbcba", "#e0def4",
@@ -95,6 +107,7 @@ pub static NORD: ThemeColors = ThemeColors {
     base: "vs-dark",
     surface_style: "flat",
     selection: "#81A1C150",
+    resolved: None,
     terminal_palette: [
         "#3B4252", "#BF616A", "#A3BE8C", "#EBCB8B", "#81A1C1", "#B48EAD", "#88C0D0", "#E5E9F0",
         "#4C566A", "#BF616A", "#A3BE8C", "#EBCB8B", "#81A1C1", "#B48EAD", "#8FBCBB", "#ECEFF4",
@@ -119,6 +132,7 @@ pub static GRUVBOX: ThemeColors = ThemeColors {
     base: "vs-dark",
     surface_style: "flat",
     selection: "#83a59850",
+    resolved: None,
     terminal_palette: [
         "#282828", "#cc241d", "#98971a", "#d79921", "#458588", "#b16286", "#689d6a", "#a89984",
         "#928374", "#fb4934", "#b8bb26", "#fabd2f", "#83a598", "#d3869b", "#8ec07c", "#ebdbb2",
@@ -143,6 +157,7 @@ pub static TOKYO_NIGHT: ThemeColors = ThemeColors {
     base: "vs-dark",
     surface_style: "flat",
     selection: "#7aa2f740",
+    resolved: None,
     terminal_palette: [
         "#15161e", "#f7768e", "#9ece6a", "#e0af68", "#7aa2f7", "#bb9af7", "#7dcfff", "#a9b1d6",
         "#414868", "#f7768e", "#9ece6a", "#e0af68", "#7aa2f7", "#bb9af7", "#7dcfff", "#c0caf5",
@@ -167,6 +182,7 @@ pub static TOKYO_NIGHT_STORM: ThemeColors = ThemeColors {
     base: "vs-dark",
     surface_style: "flat",
     selection: "#7aa2f740",
+    resolved: None,
     terminal_palette: [
         "#1d202f", "#f7768e", "#9ece6a", "#e0af68", "#7aa2f7", "#bb9af7", "#7dcfff", "#a9b1d6",
         "#414868", "#f7768e", "#9ece6a", "#e0af68", "#7aa2f7", "#bb9af7", "#7dcfff", "#c0caf5",
@@ -191,6 +207,7 @@ pub static CATPPUCCIN_MOCHA: ThemeColors = ThemeColors {
     base: "vs-dark",
     surface_style: "flat",
     selection: "#89b4fa40",
+    resolved: None,
     terminal_palette: [
         "#45475a", "#f38ba8", "#a6e3a1", "#f9e2af", "#89b4fa", "#cba6f7", "#94e2d5", "#bac2de",
         "#585b70", "#f38ba8", "#a6e3a1", "#f9e2af", "#89b4fa", "#cba6f7", "#94e2d5", "#cdd6f4",
@@ -215,6 +232,7 @@ pub static DRACULA: ThemeColors = ThemeColors {
     base: "vs-dark",
     surface_style: "flat",
     selection: "#bd93f940",
+    resolved: None,
     terminal_palette: [
         "#21222c", "#ff5555", "#50fa7b", "#f1fa8c", "#bd93f9", "#ff79c6", "#8be9fd", "#f8f8f2",
         "#6272a4", "#ff6e6e", "#69ff94", "#ffffa5", "#d6acff", "#ff92df", "#a4ffff", "#ffffff",
@@ -239,6 +257,7 @@ pub static SOLARIZED_DARK: ThemeColors = ThemeColors {
     base: "vs-dark",
     surface_style: "flat",
     selection: "#268bd240",
+    resolved: None,
     terminal_palette: [
         "#073642", "#dc322f", "#859900", "#b58900", "#268bd2", "#d33682", "#2aa198", "#eee8d5",
         "#002b36", "#cb4b16", "#586e75", "#657b83", "#839496", "#6c71c4", "#93a1a1", "#fdf6e3",
@@ -263,6 +282,7 @@ pub static ONE_DARK: ThemeColors = ThemeColors {
     base: "vs-dark",
     surface_style: "flat",
     selection: "#61afef40",
+    resolved: None,
     terminal_palette: [
         "#21252b", "#e06c75", "#98c379", "#e5c07b", "#61afef", "#c678dd", "#56b6c2", "#abb2bf",
         "#5c6370", "#e06c75", "#98c379", "#e5c07b", "#61afef", "#c678dd", "#56b6c2", "#ffffff",
@@ -287,6 +307,7 @@ pub static AYU_DARK: ThemeColors = ThemeColors {
     base: "vs-dark",
     surface_style: "flat",
     selection: "#59c2ff30",
+    resolved: None,
     terminal_palette: [
         "#07090d", "#f07178", "#aad94c", "#ffb454", "#59c2ff", "#d2a6ff", "#73b8ff", "#bfbdb6",
         "#565b66", "#f07178", "#aad94c", "#ffb454", "#59c2ff", "#d2a6ff", "#73b8ff", "#ffffff",
@@ -311,6 +332,7 @@ pub static EVERFOREST_DARK: ThemeColors = ThemeColors {
     base: "vs-dark",
     surface_style: "flat",
     selection: "#7fbbb340",
+    resolved: None,
     terminal_palette: [
         "#272e33", "#e67e80", "#a7c080", "#dbbc7f", "#7fbbb3", "#d699b6", "#83c092", "#9da9a0",
         "#7a8478", "#e67e80", "#a7c080", "#dbbc7f", "#7fbbb3", "#d699b6", "#83c092", "#d3c6aa",
@@ -335,6 +357,7 @@ pub static GITHUB_DARK: ThemeColors = ThemeColors {
     base: "vs-dark",
     surface_style: "flat",
     selection: "#79c0ff30",
+    resolved: None,
     terminal_palette: [
         "#010409", "#ff7b72", "#7ee787", "#ffa657", "#79c0ff", "#d2a8ff", "#a5d6ff", "#8b949e",
         "#6e7681", "#ffa198", "#7ee787", "#ffa657", "#79c0ff", "#d2a8ff", "#a5d6ff", "#e6edf3",
@@ -359,6 +382,7 @@ pub static MONOKAI_PRO: ThemeColors = ThemeColors {
     base: "vs-dark",
     surface_style: "flat",
     selection: "#ab9df240",
+    resolved: None,
     terminal_palette: [
         "#221f22", "#ff6188", "#a9dc76", "#ffd866", "#78dce8", "#ab9df2", "#78dce8", "#939293",
         "#727072", "#ff6188", "#a9dc76", "#ffd866", "#78dce8", "#ab9df2", "#78dce8", "#fcfcfa",
@@ -383,6 +407,7 @@ pub static PALENIGHT: ThemeColors = ThemeColors {
     base: "vs-dark",
     surface_style: "flat",
     selection: "#82aaff35",
+    resolved: None,
     terminal_palette: [
         "#1b1e2b", "#f07178", "#c3e88d", "#ffcb6b", "#82aaff", "#c792ea", "#89ddff", "#676e95",
         "#676e95", "#f07178", "#c3e88d", "#ffcb6b", "#82aaff", "#c792ea", "#89ddff", "#a6accd",
@@ -407,6 +432,7 @@ pub static SOLARIZED_LIGHT: ThemeColors = ThemeColors {
     base: "vs",
     surface_style: "flat",
     selection: "#268bd230",
+    resolved: None,
     terminal_palette: [
         "#073642", "#dc322f", "#859900", "#b58900", "#268bd2", "#d33682", "#2aa198", "#eee8d5",
         "#002b36", "#cb4b16", "#586e75", "#657b83", "#839496", "#6c71c4", "#93a1a1", "#fdf6e3",
@@ -431,6 +457,7 @@ pub static CATPPUCCIN_LATTE: ThemeColors = ThemeColors {
     base: "vs",
     surface_style: "flat",
     selection: "#1e66f525",
+    resolved: None,
     terminal_palette: [
         "#5c5f77", "#d20f39", "#40a02b", "#df8e1d", "#1e66f5", "#8839ef", "#179299", "#acb0be",
         "#6c6f85", "#d20f39", "#40a02b", "#df8e1d", "#1e66f5", "#8839ef", "#179299", "#4c4f69",
@@ -455,12 +482,88 @@ pub static GITHUB_LIGHT: ThemeColors = ThemeColors {
     base: "vs",
     surface_style: "flat",
     selection: "#0969da25",
+    resolved: None,
     terminal_palette: [
         "#24292f", "#cf222e", "#1a7f37", "#9a6700", "#0969da", "#8250df", "#0a3069", "#6e7781",
         "#57606a", "#a40e26", "#2da44e", "#bf8700", "#218bff", "#a475f9", "#0a3069", "#1f2328",
     ],
 };
 
+/// Rosé Pine Dawn — the light variant of Rosé Pine, on warm parchment.
+pub static ROSE_PINE_DAWN: ThemeColors = ThemeColors {
+    bg: "#faf4ed",
+    bg_dark: "#f2e9e1",
+    bg_highlight: "#f4ede8",
+    fg: "#575279",
+    fg_dark: "#686480",
+    cyan: "#416f77",
+    blue: "#286983",
+    green: "#286983",
+    magenta: "#765e92",
+    red: "#a14d65",
+    yellow: "#945c0f",
+    orange: "#b63e39",
+    comment: "#6a6478",
+    base: "vs",
+    surface_style: "flat",
+    selection: "#907aa930",
+    resolved: None,
+    terminal_palette: [
+        "#575279", "#a14d65", "#286983", "#945c0f", "#416f77", "#765e92", "#b63e39", "#f2e9e1",
+        "#6a6478", "#a14d65", "#286983", "#945c0f", "#416f77", "#765e92", "#b63e39", "#f2e9e1",
+    ],
+};
+
+/// Gruvbox Light — retro groove colors on a soft cream background.
+pub static GRUVBOX_LIGHT: ThemeColors = ThemeColors {
+    bg: "#fbf1c7",
+    bg_dark: "#f2e5bc",
+    bg_highlight: "#ebdbb2",
+    fg: "#3c3836",
+    fg_dark: "#6e6259",
+    cyan: "#3b6e4f",
+    blue: "#076678",
+    green: "#6b670c",
+    magenta: "#8f3f71",
+    red: "#9d0006",
+    yellow: "#8c5b0f",
+    orange: "#af3a03",
+    comment: "#6e6256",
+    base: "vs",
+    surface_style: "flat",
+    selection: "#d5c4a180",
+    resolved: None,
+    terminal_palette: [
+        "#3c3836", "#c3221c", "#686712", "#835d14", "#376b6d", "#994c6f", "#476d48", "#fbf1c7",
+        "#6e6256", "#9d0006", "#6b670c", "#8c5b0f", "#076678", "#8f3f71", "#3b6e4f", "#fbf1c7",
+    ],
+};
+
+/// Tokyo Night Day — the daylight companion to Tokyo Night.
+pub static TOKYO_NIGHT_DAY: ThemeColors = ThemeColors {
+    bg: "#e1e2e7",
+    bg_dark: "#d0d5e3",
+    bg_highlight: "#c4c8da",
+    fg: "#3156ab",
+    fg_dark: "#485891",
+    cyan: "#006283",
+    blue: "#1356b3",
+    green: "#49602f",
+    magenta: "#7317ec",
+    red: "#b6093b",
+    yellow: "#705631",
+    orange: "#8d4900",
+    comment: "#4f5884",
+    base: "vs",
+    surface_style: "flat",
+    selection: "#99a7df60",
+    resolved: None,
+    terminal_palette: [
+        "#3156ab", "#b6093b", "#49602f", "#705631", "#1356b3", "#7317ec", "#006283", "#e9e9ed",
+        "#505782", "#b6093b", "#49602f", "#705631", "#1356b3", "#7317ec", "#006283", "#e9e9ed",
+    ],
+};
+
 /// Harbor — mid-tone light theme from the Impulse Harbor design handoff.
 /// Cream content card floating on a blue-slate canvas; copper accent,
 /// indigo for interactive/syntax, red only for danger, green only for success.
@@ -481,6 +584,7 @@ pub static HARBOR: ThemeColors = ThemeColors {
     base: "vs",
     surface_style: "card",
     selection: "#3959a640",
+    resolved: None,
     terminal_palette: [
         "#222935", "#a5292b", "#095c34", "#af5a21", "#3959a6", "#503975", "#005366", "#6c7278",
         "#565f69", "#ba3535", "#267b4c", "#af5a21", "#4e71c0", "#674f8d", "#006a7d", "#222935",
@@ -491,7 +595,8 @@ pub static HARBOR: ThemeColors = ThemeColors {
 // Theme lookup helpers
 // ---------------------------------------------------------------------------
 
-/// Return the theme matching `name` (case-insensitive). Falls back to `KANAGAWA`.
+/// Return the theme matching `name` (case-insensitive): a built-in theme,
+/// else a user theme from the themes directory. Falls back to `NORD`.
 pub fn get_theme(name: &str) -> &'static ThemeColors {
     match name.to_ascii_lowercase().as_str() {
         "kanagawa" => &KANAGAWA,
@@ -512,8 +617,127 @@ pub fn get_theme(name: &str) -> &'static ThemeColors {
         "solarized-light" | "solarized_light" | "solarizedlight" => &SOLARIZED_LIGHT,
         "catppuccin-latte" | "catppuccin_latte" | "catppuccinlatte" => &CATPPUCCIN_LATTE,
         "github-light" | "github_light" | "githublight" => &GITHUB_LIGHT,
+        "rose-pine-dawn" | "rose_pine_dawn" | "rosepinedawn" => &ROSE_PINE_DAWN,
+        "gruvbox-light" | "gruvbox_light" | "gruvboxlight" => &GRUVBOX_LIGHT,
+        "tokyo-night-day" | "tokyo_night_day" | "tokyonightday" => &TOKYO_NIGHT_DAY,
         "harbor" => &HARBOR,
-        _ => &NORD,
+        other => user_theme(other).unwrap_or(&NORD),
+    }
+}
+
+/// The theme the settings call for right now. When following the system
+/// appearance this reads libadwaita's dark state, which tracks the desktop
+/// preference while [`apply_color_scheme`] leaves the scheme at `Default`.
+/// The increase-contrast variant is used when the settings or the desktop's
+/// high contrast option ask for it.
+pub fn active_theme(settings: &crate::settings::Settings) -> &'static ThemeColors {
+    let style_manager = libadwaita::StyleManager::default();
+    let name = settings.active_color_scheme(style_manager.is_dark());
+    let theme = get_theme(name);
+    if settings.increase_contrast || style_manager.is_high_contrast() {
+        increased_contrast(name, theme)
+    } else {
+        theme
+    }
+}
+
+/// The increase-contrast variant of `theme` (named `name`), converted once
+/// per theme, and again after a user theme's file changes since that gives a
+/// new `theme`.
+fn increased_contrast(name: &str, theme: &'static ThemeColors) -> &'static ThemeColors {
+    static CACHE: OnceLock<Mutex<HashMap<usize, &'static ThemeColors>>> = OnceLock::new();
+    let key = theme as *const ThemeColors as usize;
+    let mut cache = CACHE
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    if let Some(&variant) = cache.get(&key) {
+        return variant;
+    }
+    let resolved = match theme.resolved {
+        Some(resolved) => resolved.clone(),
+        None => impulse_core::theme::get_theme(name),
+    };
+    let variant: &'static ThemeColors =
+        Box::leak(Box::new(from_resolved(resolved.with_increased_contrast())));
+    cache.insert(key, variant);
+    variant
+}
+
+/// Set the light/dark window chrome: follow the desktop when the settings
+/// ask for it, otherwise force it to match the chosen theme's base. Call
+/// this before [`active_theme`] so the dark state it reads is current.
+pub fn apply_color_scheme(settings: &crate::settings::Settings) {
+    let scheme = if settings.follow_system_appearance {
+        libadwaita::ColorScheme::Default
+    } else if get_theme(&settings.color_scheme).base == "vs" {
+        libadwaita::ColorScheme::ForceLight
+    } else {
+        libadwaita::ColorScheme::ForceDark
+    };
+    libadwaita::StyleManager::default().set_color_scheme(scheme);
+}
+
+/// A user theme by id. Converted themes are leaked so they can be handed
+/// out like the built-in statics; each is cached until its file changes,
+/// so only editing a theme while using it allocates again.
+fn user_theme(id: &str) -> Option<&'static ThemeColors> {
+    static CACHE: OnceLock<Mutex<HashMap<String, (SystemTime, &'static ThemeColors)>>> =
+        OnceLock::new();
+    let (_, path) = impulse_core::theme::discover_user_themes()
+        .into_iter()
+        .find(|(theme_id, _)| theme_id == id)?;
+    let modified = std::fs::metadata(&path)
+        .and_then(|m| m.modified())
+        .unwrap_or(SystemTime::UNIX_EPOCH);
+    let mut cache = CACHE
+        .get_or_init(|| Mutex::new(HashMap::new()))
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    if let Some(&(cached_at, theme)) = cache.get(id) {
+        if cached_at == modified {
+            return Some(theme);
+        }
+    }
+    let resolved = match impulse_core::theme::load_user_theme(&path) {
+        Ok(resolved) => resolved,
+        Err(e) => {
+            log::warn!("Failed to load user theme '{}': {}", id, e);
+            return None;
+        }
+    };
+    let theme: &'static ThemeColors = Box::leak(Box::new(from_resolved(resolved)));
+    cache.insert(id.to_string(), (modified, theme));
+    Some(theme)
+}
+
+/// Build GTK theme colors from a core theme.
+fn from_resolved(resolved: ResolvedTheme) -> ThemeColors {
+    let resolved: &'static ResolvedTheme = Box::leak(Box::new(resolved));
+    let palette = &resolved.terminal_palette;
+    ThemeColors {
+        bg: &resolved.bg,
+        bg_dark: &resolved.bg_dark,
+        bg_highlight: &resolved.bg_highlight,
+        fg: &resolved.fg,
+        fg_dark: &resolved.fg_muted,
+        cyan: &resolved.cyan,
+        blue: &resolved.blue,
+        green: &resolved.green,
+        magenta: &resolved.magenta,
+        red: &resolved.red,
+        yellow: &resolved.yellow,
+        orange: &resolved.orange,
+        comment: &resolved.fg_comment,
+        base: if resolved.is_light { "vs" } else { "vs-dark" },
+        surface_style: if resolved.surface_style == "card" {
+            "card"
+        } else {
+            "flat"
+        },
+        selection: &resolved.selection,
+        terminal_palette: std::array::from_fn(|i| palette[i].as_str()),
+        resolved: Some(resolved),
     }
 }
 
@@ -525,6 +749,7 @@ pub fn theme_display_name(id: &str) -> String {
         "catppuccin-latte" => "Catppuccin Latte".to_string(),
         "github-dark" => "GitHub Dark".to_string(),
         "github-light" => "GitHub Light".to_string(),
+        "rose-pine-dawn" => "Rosé Pine Dawn".to_string(),
         "monokai-pro" => "Monokai Pro".to_string(),
         _ => id
             .split('-')
@@ -543,31 +768,42 @@ pub fn theme_display_name(id: &str) -> String {
     }
 }
 
-/// Return the list of built-in theme names.
-pub fn get_available_themes() -> Vec<&'static str> {
-    vec![
-        "kanagawa",
-        "rose-pine",
-        "nord",
-        "gruvbox",
-        "tokyo-night",
-        "tokyo-night-storm",
-        "catppuccin-mocha",
-        "dracula",
-        "solarized-dark",
-        "one-dark",
-        "ayu-dark",
-        "everforest-dark",
-        "github-dark",
-        "monokai-pro",
-        "palenight",
-        "solarized-light",
-        "catppuccin-latte",
-        "github-light",
-        "harbor",
-    ]
+/// Return the built-in theme names followed by user themes.
+pub fn get_available_themes() -> Vec<String> {
+    let mut themes: Vec<String> = BUILTIN_THEME_IDS.iter().map(|id| id.to_string()).collect();
+    for (id, _) in impulse_core::theme::discover_user_themes() {
+        if !themes.contains(&id) {
+            themes.push(id);
+        }
+    }
+    themes
 }
 
+const BUILTIN_THEME_IDS: &[&str] = &[
+    "kanagawa",
+    "rose-pine",
+    "nord",
+    "gruvbox",
+    "tokyo-night",
+    "tokyo-night-storm",
+    "catppuccin-mocha",
+    "dracula",
+    "solarized-dark",
+    "one-dark",
+    "ayu-dark",
+    "everforest-dark",
+    "github-dark",
+    "monokai-pro",
+    "palenight",
+    "solarized-light",
+    "catppuccin-latte",
+    "github-light",
+    "rose-pine-dawn",
+    "gruvbox-light",
+    "tokyo-night-day",
+    "harbor",
+];
+
 // ---------------------------------------------------------------------------
 // CSS loading
 // ---------------------------------------------------------------------------
@@ -659,6 +895,14 @@ pub fn load_css(theme: &ThemeColors) -> gtk4::CssProvider {
         .file-entry-file {{
             color: {fg};
         }}
+        .file-entry-symlink {{
+            color: {comment};
+            font-size: 11px;
+        }}
+        .file-tree-inline-entry {{
+            min-height: 22px;
+            padding: 0px 4px;
+        }}
         .git-badge {{
             font-size: 11px;
             font-weight: 600;
@@ -709,6 +953,9 @@ pub fn load_css(theme: &ThemeColors) -> gtk4::CssProvider {
         .file-entry-git-ignored {{
             color: {fg_dark};
         }}
+        .file-entry-ignored {{
+            opacity: 0.55;
+        }}
         .drop-target {{
             background-color: alpha({cyan}, 0.10);
             outline: 1px dashed {cyan};
@@ -756,28 +1003,104 @@ pub fn load_css(theme: &ThemeColors) -> gtk4::CssProvider {
         .status-bar .git-branch {{
             color: {magenta};
         }}
+        .status-bar .git-sync {{
+            color: {fg_dark};
+        }}
+        .status-bar .git-progress,
+        .status-bar .pending-chord,
+        .status-bar .lsp-progress {{
+            color: {fg_dark};
+            font-size: 11px;
+        }}
+        .status-bar .ci-status {{
+            min-height: 16px;
+            min-width: 0;
+            padding: 0 4px;
+            border: none;
+            background: none;
+            box-shadow: none;
+        }}
+        .status-bar .ci-status label {{
+            font-size: 11px;
+        }}
+        .status-bar .ci-success label {{
+            color: {green};
+        }}
+        .status-bar .ci-failure label {{
+            color: {red};
+        }}
+        .status-bar .ci-pending label {{
+            color: {yellow};
+        }}
         .status-bar .shell-name {{
             color: {cyan};
         }}
         .status-bar .cwd {{
             color: {fg};
         }}
+        .status-bar .cwd > button,
+        .status-bar .cwd > menubutton > button {{
+            min-height: 16px;
+            min-width: 0;
+            padding: 0 2px;
+            border: none;
+            background: none;
+            box-shadow: none;
+            color: inherit;
+        }}
+        .status-bar .cwd > button:hover,
+        .status-bar .cwd > menubutton > button:hover {{
+            text-decoration: underline;
+        }}
         .status-bar .cursor-pos {{
             color: {fg_dark};
             padding-left: 12px;
         }}
         .status-bar .language-name {{
-            color: {blue};
             padding-left: 12px;
         }}
+        .status-bar .language-name > button {{
+            min-height: 16px;
+            min-width: 0;
+            padding: 0 4px;
+            border: none;
+            background: none;
+            box-shadow: none;
+        }}
+        .status-bar .language-name label {{
+            color: {blue};
+        }}
         .status-bar .encoding {{
             color: {fg_dark};
             padding-left: 12px;
         }}
         .status-bar .indent-info {{
-            color: {fg_dark};
             padding-left: 12px;
         }}
+        .status-bar .indent-info > button {{
+            min-height: 16px;
+            min-width: 0;
+            padding: 0 4px;
+            border: none;
+            background: none;
+            box-shadow: none;
+        }}
+        .status-bar .indent-info label {{
+            color: {fg_dark};
+        }}
+        .status-bar .zoom-level {{
+            min-height: 16px;
+            min-width: 0;
+            padding: 0 4px;
+            margin-left: 8px;
+            border: none;
+            background: none;
+            box-shadow: none;
+            color: {fg_dark};
+        }}
+        .status-bar .problems {{
+            color: {yellow};
+        }}
         .status-bar .blame-info {{
             color: {fg_dark};
             font-size: 11px;
@@ -825,10 +1148,38 @@ pub fn load_css(theme: &ThemeColors) -> gtk4::CssProvider {
         .status-bar .status-bar-update-btn:hover {{
             background: alpha({yellow}, 0.1);
         }}
+        .status-bar .notifications-bell > button {{
+            min-height: 16px;
+            min-width: 0;
+            padding: 0 6px;
+            border: none;
+            background: none;
+            box-shadow: none;
+            color: {fg_dark};
+        }}
+        .status-bar .notifications-bell.has-errors > button {{
+            color: {red};
+        }}
+        .notifications-panel .notification-row {{
+            padding: 6px;
+            border-left: 3px solid transparent;
+        }}
+        .notifications-panel .notification-row.warning {{
+            border-left-color: {yellow};
+        }}
+        .notifications-panel .notification-row.error {{
+            border-left-color: {red};
+        }}
+        .notifications-panel .notification-title {{
+            font-weight: bold;
+        }}
         /* --- Terminal --- */
         .terminal-view {{
             background-color: {bg};
         }}
+        .terminal-view.translucent {{
+            background-color: transparent;
+        }}
         /* --- Header bar --- */
         headerbar {{
             background-color: {bg_dark};
@@ -1049,6 +1400,9 @@ pub fn load_css(theme: &ThemeColors) -> gtk4::CssProvider {
             font-size: 12px;
             font-family: 'JetBrains Mono', monospace;
         }}
+        .project-search-context .project-search-line-content {{
+            color: {fg_dark};
+        }}
         /* --- Vertical tab list (sidebar) --- */
         .vertical-tabs-list {{
             background-color: transparent;
@@ -1077,6 +1431,12 @@ pub fn load_css(theme: &ThemeColors) -> gtk4::CssProvider {
             font-size: 12px;
             color: {fg};
         }}
+        .vertical-tab-title.preview {{
+            font-style: italic;
+        }}
+        .vertical-tab-lock {{
+            color: {fg_dark};
+        }}
         .vertical-tab-subtitle {{
             font-size: 10px;
             color: {fg_dark};
@@ -1213,6 +1573,27 @@ pub fn load_css(theme: &ThemeColors) -> gtk4::CssProvider {
             font-size: 12px;
             color: {green};
         }}
+        .commit-body-scroll {{
+            border: 1px solid alpha({fg}, 0.12);
+            border-radius: 6px;
+        }}
+        .commit-body {{
+            background-color: {bg};
+            font-size: 12px;
+        }}
+        .commit-subject-count,
+        .commit-lint {{
+            font-size: 11px;
+            color: {fg_dark};
+        }}
+        .commit-subject-count.warning,
+        .commit-lint {{
+            color: {yellow};
+        }}
+        .commit-subject-count.error,
+        .commit-lint.error {{
+            color: {red};
+        }}
         .context-bar button.flat {{
             min-width: 24px;
             min-height: 24px;
@@ -1224,6 +1605,31 @@ pub fn load_css(theme: &ThemeColors) -> gtk4::CssProvider {
             color: {fg};
             background-color: alpha({fg}, 0.08);
         }}
+        /* --- Assistant panel --- */
+        .assistant-panel {{
+            background-color: {bg_dark};
+            border-left: 1px solid alpha({fg}, 0.10);
+        }}
+        .assistant-panel-header {{
+            padding: 4px 8px;
+        }}
+        .assistant-message {{
+            padding: 6px 10px;
+            border-radius: 8px;
+        }}
+        .assistant-message.user {{
+            background-color: {bg_highlight};
+        }}
+        .assistant-message.assistant {{
+            background-color: {bg};
+        }}
+        .assistant-message.error {{
+            color: {red};
+        }}
+        .assistant-input {{
+            background-color: {bg};
+            border-top: 1px solid alpha({fg}, 0.10);
+        }}
         "#,
         bg_dark = theme.bg_dark,
         bg = theme.bg,
@@ -1317,3 +1723,57 @@ pub fn load_css(theme: &ThemeColors) -> gtk4::CssProvider {
     );
     provider
 }
+
+thread_local! {
+    static CUSTOM_CSS_PROVIDER: RefCell<Option<gtk4::CssProvider>> = const { RefCell::new(None) };
+    static EDITOR_CUSTOM_CSS: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// (Re)load the user's `custom.css` and `editor.css`.
+///
+/// `custom.css` is installed one priority level above the theme provider so
+/// it wins even after a theme switch re-adds that provider. Returns the
+/// editor stylesheet so callers can push it to open editors.
+pub fn reload_custom_css() -> String {
+    let display = gtk4::gdk::Display::default().expect("Could not get default display");
+    CUSTOM_CSS_PROVIDER.with(|cell| {
+        if let Some(old) = cell.borrow_mut().take() {
+            gtk4::style_context_remove_provider_for_display(&display, &old);
+        }
+        let css = impulse_core::theme::custom_css_path()
+            .and_then(|p| impulse_core::theme::read_user_css(&p));
+        if let Some(css) = css {
+            let provider = gtk4::CssProvider::new();
+            provider.connect_parsing_error(|_, section, error| {
+                log::warn!(
+                    "custom.css:{}: {error}",
+                    section.start_location().lines() + 1
+                );
+            });
+            provider.load_from_string(&css);
+            gtk4::style_context_add_provider_for_display(
+                &display,
+                &provider,
+                gtk4::STYLE_PROVIDER_PRIORITY_USER + 1,
+            );
+            *cell.borrow_mut() = Some(provider);
+        }
+    });
+
+    read_editor_css()
+}
+
+/// The user's `editor.css`, read once and cached until [`reload_custom_css`].
+pub fn editor_custom_css() -> String {
+    EDITOR_CUSTOM_CSS
+        .with(|cell| cell.borrow().clone())
+        .unwrap_or_else(read_editor_css)
+}
+
+fn read_editor_css() -> String {
+    let css = impulse_core::theme::editor_css_path()
+        .and_then(|p| impulse_core::theme::read_user_css(&p))
+        .unwrap_or_default();
+    EDITOR_CUSTOM_CSS.with(|cell| *cell.borrow_mut() = Some(css.clone()));
+    css
+}
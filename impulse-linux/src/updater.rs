@@ -0,0 +1,151 @@
+//! "Check for Updates" and in-app updates. Only a standalone binary the
+//! user can write to updates itself; Flatpak and package-manager installs
+//! are told where their updates come from.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+use gtk4::prelude::*;
+use gtk4::{gio, glib};
+use impulse_core::update::{InstallKind, UpdateInfo};
+use libadwaita as adw;
+use libadwaita::prelude::*;
+
+/// Set once an update is installed and the user asked to restart; `main`
+/// starts the new binary after the application exits.
+static RELAUNCH: AtomicBool = AtomicBool::new(false);
+
+/// The executable's path, read before an update replaces it (afterwards
+/// `/proc/self/exe` names the deleted file).
+fn exe_path() -> Option<&'static PathBuf> {
+    static EXE: OnceLock<Option<PathBuf>> = OnceLock::new();
+    EXE.get_or_init(|| std::env::current_exe().ok()).as_ref()
+}
+
+pub fn install_kind() -> InstallKind {
+    match exe_path() {
+        Some(exe) => impulse_core::update::install_kind(exe),
+        None => InstallKind::Package,
+    }
+}
+
+/// Download, verify and install `info` over the running binary. Blocking.
+pub fn install(info: &UpdateInfo) -> Result<(), String> {
+    let item = info
+        .item
+        .as_ref()
+        .ok_or("This release can't be installed in-app")?;
+    let staged = impulse_core::update::download_update(item, &install_kind())?;
+    impulse_core::update::apply_update(&staged)
+}
+
+/// Check now (ignoring the once-a-day limit) and report the result in a
+/// dialog, offering to install the update when possible.
+pub fn check_now(window: &adw::ApplicationWindow) {
+    let window = window.clone();
+    glib::spawn_future_local(async move {
+        let result = gio::spawn_blocking(impulse_core::update::check_for_update_now)
+            .await
+            .unwrap_or_else(|_| Err("Update check panicked".to_string()));
+        match result {
+            Ok(Some(info)) => offer_update(&window, info),
+            Ok(None) => show_message(
+                &window,
+                "Impulse Is Up to Date",
+                &format!(
+                    "Version {} is the latest version.",
+                    impulse_core::update::CURRENT_VERSION
+                ),
+            ),
+            Err(e) => show_message(&window, "Couldn't Check for Updates", &e),
+        }
+    });
+}
+
+fn offer_update(window: &adw::ApplicationWindow, info: UpdateInfo) {
+    let kind = install_kind();
+    let can_install = info.can_install(&kind);
+    let body = if can_install {
+        format!("You have version {}.", info.current_version)
+    } else {
+        format!(
+            "You have version {}. {}",
+            info.current_version,
+            kind.update_hint()
+        )
+    };
+    let dialog = adw::AlertDialog::builder()
+        .heading(format!("Impulse {} Is Available", info.version))
+        .body(body)
+        .build();
+    dialog.add_response("later", "Later");
+    dialog.add_response("notes", "Release Notes");
+    if can_install {
+        dialog.add_response("install", "Install and Restart");
+        dialog.set_response_appearance("install", adw::ResponseAppearance::Suggested);
+    }
+    dialog.set_default_response(Some(if can_install { "install" } else { "notes" }));
+    dialog.set_close_response("later");
+
+    let window_ref = window.clone();
+    dialog.connect_response(None, move |_dialog, response| match response {
+        "notes" => {
+            let _ = open::that(&info.url);
+        }
+        "install" => install_and_restart(&window_ref, info.clone()),
+        _ => {}
+    });
+    dialog.present(Some(window));
+}
+
+fn install_and_restart(window: &adw::ApplicationWindow, info: UpdateInfo) {
+    let window = window.clone();
+    glib::spawn_future_local(async move {
+        let result = gio::spawn_blocking(move || install(&info))
+            .await
+            .unwrap_or_else(|_| Err("Update panicked".to_string()));
+        match result {
+            Ok(()) => restart(&window),
+            Err(e) => show_message(&window, "Update Failed", &e),
+        }
+    });
+}
+
+/// Close every window (each still asks about unsaved work) and start the
+/// installed binary once the application has exited.
+pub fn restart(window: &adw::ApplicationWindow) {
+    RELAUNCH.store(true, Ordering::SeqCst);
+    match window.application() {
+        Some(app) => {
+            for w in app.windows() {
+                w.close();
+            }
+        }
+        None => window.close(),
+    }
+}
+
+/// Called by `main` after the application exits.
+pub fn relaunch_if_requested() {
+    if !RELAUNCH.load(Ordering::SeqCst) {
+        return;
+    }
+    match exe_path() {
+        Some(exe) => {
+            if let Err(e) = std::process::Command::new(exe).spawn() {
+                log::warn!("Failed to restart {}: {}", exe.display(), e);
+            }
+        }
+        None => log::warn!("Failed to restart Impulse: executable path unknown"),
+    }
+}
+
+fn show_message(window: &adw::ApplicationWindow, heading: &str, body: &str) {
+    let dialog = adw::AlertDialog::builder()
+        .heading(heading)
+        .body(body)
+        .build();
+    dialog.add_response("close", "Close");
+    dialog.present(Some(window));
+}
@@ -27,6 +27,11 @@ const MIN_TAB_HEIGHT: i32 = 48;
 /// Always leave at least this much sidebar height for the file tree.
 const MIN_TREE_HEIGHT: i32 = 140;
 
+/// CSS class on a page's child widget while the tab is a preview tab (opened
+/// with a single click and replaced by the next one). Rows for such pages
+/// show their title in italics.
+pub const PREVIEW_TAB_CLASS: &str = "preview-tab";
+
 /// Build the vertical tab list widget.
 ///
 /// The returned box contains the scrollable list and a draggable divider,
@@ -40,6 +45,7 @@ pub fn build_vertical_tabs(tab_view: &adw::TabView, settings: &Rc<RefCell<Settin
     let list = gtk4::ListBox::new();
     list.set_selection_mode(gtk4::SelectionMode::Single);
     list.add_css_class("vertical-tabs-list");
+    list.update_property(&[gtk4::accessible::Property::Label("Tabs")]);
 
     let scrolled = gtk4::ScrolledWindow::new();
     scrolled.set_policy(gtk4::PolicyType::Never, gtk4::PolicyType::Automatic);
@@ -153,11 +159,7 @@ pub fn build_vertical_tabs(tab_view: &adw::TabView, settings: &Rc<RefCell<Settin
     {
         let rebuild = rebuild.clone();
         tab_view.connect_page_attached(move |_, page, _| {
-            // Rebuild when the page title changes (terminal CWD or file name).
-            {
-                let rebuild = rebuild.clone();
-                page.connect_title_notify(move |_| rebuild());
-            }
+            connect_row_notifies(page, &rebuild);
             rebuild();
         });
     }
@@ -174,19 +176,33 @@ pub fn build_vertical_tabs(tab_view: &adw::TabView, settings: &Rc<RefCell<Settin
         tab_view.connect_selected_page_notify(move |_| rebuild());
     }
 
-    // Pages attached before this widget existed need their title-notify
+    // Pages attached before this widget existed need their notify
     // connections too (e.g. tabs restored from the previous session).
     let n = tab_view.n_pages();
     for i in 0..n {
-        let page = tab_view.nth_page(i);
-        let rebuild = rebuild.clone();
-        page.connect_title_notify(move |_| rebuild());
+        connect_row_notifies(&tab_view.nth_page(i), &rebuild);
     }
 
     rebuild();
     container
 }
 
+/// Rebuild when anything a row shows changes: the title (terminal CWD or file
+/// name), the read-only lock, or the preview state.
+fn connect_row_notifies(page: &adw::TabPage, rebuild: &Rc<dyn Fn()>) {
+    {
+        let rebuild = rebuild.clone();
+        page.connect_title_notify(move |_| rebuild());
+    }
+    {
+        let rebuild = rebuild.clone();
+        page.connect_indicator_icon_notify(move |_| rebuild());
+    }
+    let rebuild = rebuild.clone();
+    page.child()
+        .connect_notify_local(Some("css-classes"), move |_, _| rebuild());
+}
+
 /// Build one row: title, dimmed subtitle, and a hover-revealed close button.
 fn build_tab_row(tab_view: &adw::TabView, page: &adw::TabPage) -> gtk4::ListBoxRow {
     let row_box = gtk4::Box::new(gtk4::Orientation::Horizontal, 8);
@@ -199,6 +215,9 @@ fn build_tab_row(tab_view: &adw::TabView, page: &adw::TabPage) -> gtk4::ListBoxR
     let title = page.title().to_string();
     let title_label = gtk4::Label::new(Some(&title));
     title_label.add_css_class("vertical-tab-title");
+    if page.child().has_css_class(PREVIEW_TAB_CLASS) {
+        title_label.add_css_class("preview");
+    }
     title_label.set_halign(gtk4::Align::Start);
     title_label.set_ellipsize(gtk4::pango::EllipsizeMode::Middle);
     text_box.append(&title_label);
@@ -212,6 +231,14 @@ fn build_tab_row(tab_view: &adw::TabView, page: &adw::TabPage) -> gtk4::ListBoxR
     }
     row_box.append(&text_box);
 
+    if let Some(icon) = page.indicator_icon() {
+        let lock = gtk4::Image::from_gicon(&icon);
+        lock.add_css_class("vertical-tab-lock");
+        lock.set_valign(gtk4::Align::Center);
+        lock.set_tooltip_text(Some(page.indicator_tooltip().as_str()));
+        row_box.append(&lock);
+    }
+
     let close_btn = gtk4::Button::from_icon_name("window-close-symbolic");
     close_btn.add_css_class("flat");
     close_btn.add_css_class("vertical-tab-close");
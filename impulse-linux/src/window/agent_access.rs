@@ -0,0 +1,253 @@
+//! Agent access over MCP (see `impulse_core::mcp`). "Manage Agent Access"
+//! asks before sharing the file tree's workspace with coding agents; once a
+//! workspace is allowed, the window shares it again whenever it opens while
+//! `agent_access_enabled` is on. Edits agents propose go through the same
+//! preview as a rename, and only the ones the user keeps are applied.
+
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::Duration;
+
+use gtk4::prelude::*;
+use impulse_core::mcp::{self, Consent, HostRequest, HostRequestKind, McpServer};
+use libadwaita as adw;
+use libadwaita::prelude::*;
+
+use crate::editor;
+use crate::lsp_completion::{DiagnosticInfo, DiagnosticSeverity};
+
+use super::context::WindowContext;
+use super::uri_to_file_path;
+
+/// How often requests from agents are picked up.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// What agents run to connect, from inside the workspace.
+const BRIDGE_COMMAND: &str = "impulse --mcp-bridge";
+
+/// Share the workspace at startup if it is allowed, and stop sharing when
+/// the window closes.
+pub(super) fn setup(ctx: &WindowContext) {
+    set_enabled(ctx, ctx.settings.borrow().agent_access_enabled);
+    let agent_server = ctx.agent_server.clone();
+    ctx.window.connect_destroy(move |_| {
+        agent_server.borrow_mut().take();
+    });
+}
+
+/// Follow the `agent_access_enabled` setting. Doesn't borrow the settings,
+/// so it can run from a settings callback.
+pub(super) fn set_enabled(ctx: &WindowContext, enabled: bool) {
+    if !enabled {
+        ctx.agent_server.borrow_mut().take();
+        return;
+    }
+    if ctx.agent_server.borrow().is_some() {
+        return;
+    }
+    if let Some(folder) = current_workspace(ctx) {
+        if mcp::consent(&folder) == Consent::Allowed {
+            start(ctx, &folder);
+        }
+    }
+}
+
+/// Record diagnostics for agents when the workspace is shared.
+pub(super) fn diagnostics_changed(ctx: &WindowContext, path: &str, diagnostics: &[DiagnosticInfo]) {
+    let server = ctx.agent_server.borrow();
+    let Some(server) = server.as_ref() else {
+        return;
+    };
+    let diagnostics = diagnostics
+        .iter()
+        .map(|d| mcp::Diagnostic {
+            line: d.line,
+            character: d.character,
+            end_line: d.end_line,
+            end_character: d.end_character,
+            severity: match d.severity {
+                DiagnosticSeverity::Error => "error",
+                DiagnosticSeverity::Warning => "warning",
+                DiagnosticSeverity::Information => "information",
+                DiagnosticSeverity::Hint => "hint",
+            }
+            .to_string(),
+            message: d.message.clone(),
+        })
+        .collect();
+    server.set_diagnostics(path, diagnostics);
+}
+
+/// "Manage Agent Access (MCP)": share the file tree's workspace with
+/// agents after asking, or stop sharing it.
+pub(super) fn manage(ctx: &WindowContext) {
+    let Some(folder) = current_workspace(ctx) else {
+        return;
+    };
+    let sharing = ctx
+        .agent_server
+        .borrow()
+        .as_ref()
+        .is_some_and(|server| server.workspace() == folder);
+
+    let dialog = if sharing {
+        let dialog = adw::AlertDialog::builder()
+            .heading("Agent Access")
+            .body(format!(
+                "{}\n\nAgents can use this workspace. To connect one, add \u{201c}{}\u{201d} \
+                 as an MCP server and start the agent in this folder, e.g. \
+                 \u{201c}claude mcp add impulse -- {}\u{201d}.",
+                folder.display(),
+                BRIDGE_COMMAND,
+                BRIDGE_COMMAND
+            ))
+            .build();
+        dialog.add_response("close", "Close");
+        dialog.add_response("stop", "Stop Sharing");
+        dialog.set_response_appearance("stop", adw::ResponseAppearance::Destructive);
+        dialog.set_default_response(Some("close"));
+        dialog.set_close_response("close");
+        dialog
+    } else {
+        let dialog = adw::AlertDialog::builder()
+            .heading("Share This Workspace with Agents?")
+            .body(format!(
+                "{}\n\nAgents connected with \u{201c}{}\u{201d} will be able to read open \
+                 files (including unsaved changes), see diagnostics, search the workspace \
+                 and propose edits. You review every edit before it is applied.",
+                folder.display(),
+                BRIDGE_COMMAND
+            ))
+            .build();
+        dialog.add_response("deny", "Don't Allow");
+        dialog.add_response("allow", "Allow");
+        dialog.set_response_appearance("allow", adw::ResponseAppearance::Suggested);
+        dialog.set_default_response(Some("deny"));
+        dialog.set_close_response("deny");
+        dialog
+    };
+
+    let ctx_ref = ctx.clone();
+    dialog.connect_response(None, move |_dialog, response| {
+        let allowed = match response {
+            "allow" => true,
+            "deny" | "stop" => false,
+            _ => return,
+        };
+        if let Err(e) = mcp::set_consent(&folder, allowed) {
+            log::warn!("{}", e);
+            ctx_ref.toast_overlay.add_toast(adw::Toast::new(&e));
+            return;
+        }
+        if allowed {
+            start(&ctx_ref, &folder);
+        } else if response == "stop" {
+            ctx_ref.agent_server.borrow_mut().take();
+            ctx_ref
+                .toast_overlay
+                .add_toast(adw::Toast::new("Stopped sharing the workspace with agents"));
+        }
+    });
+    dialog.present(Some(&ctx.window));
+}
+
+fn current_workspace(ctx: &WindowContext) -> Option<PathBuf> {
+    let path = ctx.sidebar_state.current_path.borrow().clone();
+    (!path.is_empty()).then(|| impulse_core::trust::workspace_folder(Path::new(&path)))
+}
+
+/// Share `folder`, replacing any workspace this window already shares, and
+/// answer agent requests until the server is replaced or stopped.
+fn start(ctx: &WindowContext, folder: &Path) {
+    // Stop first so re-sharing the same folder can take over its socket.
+    ctx.agent_server.borrow_mut().take();
+    let server = match McpServer::start(folder) {
+        Ok(server) => server,
+        Err(e) => {
+            log::warn!("{}", e);
+            ctx.toast_overlay.add_toast(adw::Toast::new(&e));
+            return;
+        }
+    };
+    log::info!(
+        "Sharing {} with agents on {}",
+        folder.display(),
+        server.socket_path().display()
+    );
+    let server = Rc::new(server);
+    let weak_server = Rc::downgrade(&server);
+    *ctx.agent_server.borrow_mut() = Some(server);
+
+    let ctx = ctx.clone();
+    gtk4::glib::timeout_add_local(POLL_INTERVAL, move || {
+        let Some(server) = weak_server.upgrade() else {
+            return gtk4::glib::ControlFlow::Break;
+        };
+        while let Some(request) = server.next_request() {
+            handle_request(&ctx, request);
+        }
+        gtk4::glib::ControlFlow::Continue
+    });
+}
+
+fn handle_request(ctx: &WindowContext, request: HostRequest) {
+    match &request.kind {
+        HostRequestKind::OpenFiles => {
+            let files: Vec<mcp::OpenFile> = ctx
+                .editor_tab_pages
+                .borrow()
+                .keys()
+                .filter_map(|path| {
+                    let handle = editor::get_handle(path)?;
+                    let language = handle.language.borrow().clone();
+                    Some(mcp::OpenFile {
+                        path: path.clone(),
+                        language,
+                        modified: handle.is_modified.get(),
+                        content: handle.get_content(),
+                    })
+                })
+                .collect();
+            request.respond(serde_json::to_value(files).map_err(|e| e.to_string()));
+        }
+        HostRequestKind::ApplyEdits { files } => {
+            let files = files.clone();
+            let total: usize = files.iter().map(|f| f.edits.len()).sum();
+            let toast_overlay = ctx.toast_overlay.clone();
+            // The dialog answers once; the agent waits until then.
+            let request = std::cell::RefCell::new(Some(request));
+            super::dialogs::show_edit_preview_dialog(
+                &ctx.window,
+                "Apply Edits from Agent?",
+                "Apply Edits",
+                files,
+                |uri| match editor::get_handle(&uri_to_file_path(uri)) {
+                    Some(handle) => Some(handle.get_content()),
+                    None => impulse_editor::peek::read_source_from_disk(uri),
+                },
+                move |selected| {
+                    let Some(request) = request.borrow_mut().take() else {
+                        return;
+                    };
+                    let Some(selected) = selected else {
+                        request.respond(Err("The user declined the edits".to_string()));
+                        return;
+                    };
+                    let applied: usize = selected.iter().map(|f| f.edits.len()).sum();
+                    super::tab_management::apply_workspace_file_edits(
+                        &selected,
+                        "Agent edit",
+                        &toast_overlay,
+                    );
+                    request.respond(Ok(serde_json::Value::String(format!(
+                        "The user applied {} of {} edits, in {} files. Open files were \
+                         edited in their buffer and are unsaved.",
+                        applied,
+                        total,
+                        selected.len()
+                    ))));
+                },
+            );
+        }
+    }
+}
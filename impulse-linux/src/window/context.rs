@@ -1,7 +1,9 @@
 use std::cell::{Cell, RefCell};
 use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
+use std::sync::{Arc, Mutex};
 
+use impulse_core::symbol_index::SymbolIndexes;
 use libadwaita as adw;
 
 use crate::lsp_completion::LspRequest;
@@ -26,6 +28,15 @@ pub(crate) struct LspState {
     pub latest_references_req: Rc<RefCell<HashMap<String, u64>>>,
     pub latest_code_action_req: Rc<RefCell<HashMap<String, u64>>>,
     pub latest_rename_req: Rc<RefCell<HashMap<String, u64>>>,
+    pub latest_peek_req: Rc<RefCell<HashMap<String, u64>>>,
+    /// Maps internal LSP seq → Monaco's request_id for peek requests.
+    pub peek_monaco_ids: Rc<RefCell<HashMap<u64, u64>>>,
+    /// Maps internal LSP seq → Monaco's request_id for rename and
+    /// prepare-rename requests.
+    pub rename_monaco_ids: Rc<RefCell<HashMap<u64, u64>>>,
+    /// Shared with the LSP bridge thread, which falls back to it for files
+    /// without a language server.
+    pub symbol_indexes: Arc<Mutex<SymbolIndexes>>,
 }
 
 /// Terminal session state shared across keybinding closures.
@@ -35,7 +46,6 @@ pub(crate) struct LspState {
 #[derive(Clone)]
 pub(crate) struct TerminalContext {
     pub copy_on_select: Rc<Cell<bool>>,
-    pub font_size: Rc<Cell<i32>>,
 }
 
 /// Shared window state passed between window module functions.
@@ -51,10 +61,16 @@ pub(crate) struct WindowContext {
     pub lsp: LspState,
     pub toast_overlay: adw::ToastOverlay,
     pub status_bar: Rc<RefCell<crate::status_bar::StatusBar>>,
+    /// Zen mode and the individual chrome toggles.
+    pub layout: Rc<super::layout::ChromeLayout>,
+    /// Two-step chord shortcuts, fed from the capture-phase key handler.
+    pub chords: Rc<crate::keybindings::ChordDispatcher>,
     /// Set of file paths currently open in editor/image tabs for O(1) deduplication.
     pub open_editor_paths: Rc<RefCell<HashSet<String>>>,
     /// Maps file paths to their TabPage for O(1) lookup in LSP responses and navigation.
     pub editor_tab_pages: Rc<RefCell<HashMap<String, adw::TabPage>>>,
     /// Maps a tab widget to the widget that should be selected when it closes.
     pub tab_close_return_targets: Rc<RefCell<HashMap<usize, usize>>>,
+    /// The workspace this window shares with agents over MCP, if any.
+    pub agent_server: Rc<RefCell<Option<Rc<impulse_core::mcp::McpServer>>>>,
 }
@@ -0,0 +1,185 @@
+//! The control socket (see `impulse_core::control`): one per app, started
+//! at startup while `control_socket_enabled` is on. Requests are answered on
+//! the main loop against the registered windows; `run-command` goes through
+//! each window's "win.run-command" action.
+
+use std::cell::RefCell;
+use std::path::Path;
+use std::rc::Rc;
+use std::time::Duration;
+
+use gtk4::prelude::*;
+use impulse_core::cli_open::{OpenRequest, OpenTarget};
+use impulse_core::control::{ControlRequest, ControlServer, ControlState, WindowState};
+use libadwaita as adw;
+use serde_json::Value;
+
+use crate::editor;
+
+use super::context::WindowContext;
+use super::tab_management;
+
+/// How often requests from clients are picked up.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+thread_local! {
+    static SERVER: RefCell<Option<Rc<ControlServer>>> = const { RefCell::new(None) };
+    /// Open windows, in the order they were opened.
+    static WINDOWS: RefCell<Vec<WindowContext>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Make `ctx`'s window reachable from the socket until it closes.
+pub(super) fn register(ctx: &WindowContext) {
+    WINDOWS.with(|w| w.borrow_mut().push(ctx.clone()));
+    ctx.window.connect_destroy(|window| {
+        WINDOWS.with(|w| w.borrow_mut().retain(|ctx| &ctx.window != window));
+    });
+}
+
+/// Follow the `control_socket_enabled` setting. Doesn't borrow the
+/// settings, so it can run from a settings callback.
+pub(crate) fn set_enabled(enabled: bool) {
+    if !enabled {
+        SERVER.with(|s| s.borrow_mut().take());
+        return;
+    }
+    if SERVER.with(|s| s.borrow().is_some()) {
+        return;
+    }
+    let server = match ControlServer::start() {
+        Ok(server) => Rc::new(server),
+        Err(e) => {
+            log::warn!("Control socket unavailable: {}", e);
+            return;
+        }
+    };
+    log::info!(
+        "Control socket listening on {}",
+        server.socket_path().display()
+    );
+    let weak_server = Rc::downgrade(&server);
+    SERVER.with(|s| *s.borrow_mut() = Some(server));
+
+    gtk4::glib::timeout_add_local(POLL_INTERVAL, move || {
+        let Some(server) = weak_server.upgrade() else {
+            return gtk4::glib::ControlFlow::Break;
+        };
+        while let Some(call) = server.next_call() {
+            let result = perform(&call.request);
+            call.respond(result);
+        }
+        gtk4::glib::ControlFlow::Continue
+    });
+}
+
+fn perform(request: &ControlRequest) -> Result<Value, String> {
+    match request {
+        ControlRequest::OpenFile {
+            path,
+            line,
+            column,
+            new_window,
+        } => {
+            open(path, *line, *column, *new_window)?;
+            Ok(Value::Null)
+        }
+        ControlRequest::GotoPosition {
+            path: Some(path),
+            line,
+            column,
+        } => {
+            open(path, Some(*line), *column, false)?;
+            Ok(Value::Null)
+        }
+        ControlRequest::GotoPosition {
+            path: None,
+            line,
+            column,
+        } => {
+            let ctx = active_window().ok_or("No window is open")?;
+            let handle = ctx
+                .tab_view
+                .selected_page()
+                .and_then(|page| tab_management::file_path_for_page(&page))
+                .and_then(|path| editor::get_handle(&path))
+                .ok_or("No file is open in the active tab")?;
+            handle.go_to_position(*line, column.unwrap_or(1));
+            Ok(Value::Null)
+        }
+        ControlRequest::RunCommand { id } => {
+            let ctx = active_window().ok_or("No window is open")?;
+            let enabled = {
+                let settings = ctx.settings.borrow();
+                let registry = impulse_core::commands::CommandRegistry::new(&settings);
+                if !registry.contains(id) {
+                    return Err(format!("Unknown command: {}", id));
+                }
+                registry.is_enabled(
+                    id,
+                    &tab_management::command_context(&ctx.tab_view, &settings),
+                )
+            };
+            if !enabled {
+                return Err(format!("{} isn't available in the active tab", id));
+            }
+            WidgetExt::activate_action(&ctx.window, "win.run-command", Some(&id.to_variant()))
+                .map_err(|e| e.to_string())?;
+            Ok(Value::Null)
+        }
+        ControlRequest::QueryState => {
+            let windows = WINDOWS.with(|w| w.borrow().iter().map(window_state).collect());
+            serde_json::to_value(ControlState { windows }).map_err(|e| e.to_string())
+        }
+    }
+}
+
+/// Open `path` the way `impulse <path>` would.
+fn open(
+    path: &str,
+    line: Option<u32>,
+    column: Option<u32>,
+    new_window: bool,
+) -> Result<(), String> {
+    let app = gtk4::gio::Application::default()
+        .and_downcast::<adw::Application>()
+        .ok_or("Impulse is shutting down")?;
+    let request = OpenRequest {
+        targets: vec![OpenTarget {
+            path: path.to_string(),
+            line,
+            column,
+            is_dir: Path::new(path).is_dir(),
+        }],
+        new_window,
+        wait: false,
+    };
+    crate::cli_open::open(&app, &request);
+    Ok(())
+}
+
+/// The focused window, or the most recently opened one.
+fn active_window() -> Option<WindowContext> {
+    WINDOWS.with(|w| {
+        let windows = w.borrow();
+        windows
+            .iter()
+            .find(|ctx| ctx.window.is_active())
+            .or_else(|| windows.last())
+            .cloned()
+    })
+}
+
+fn window_state(ctx: &WindowContext) -> WindowState {
+    let workspace = ctx.sidebar_state.current_path.borrow().clone();
+    let mut open_files: Vec<String> = ctx.editor_tab_pages.borrow().keys().cloned().collect();
+    open_files.sort();
+    WindowState {
+        focused: ctx.window.is_active(),
+        workspace: (!workspace.is_empty()).then_some(workspace),
+        active_file: ctx
+            .tab_view
+            .selected_page()
+            .and_then(|page| tab_management::file_path_for_page(&page)),
+        open_files,
+    }
+}
@@ -1,9 +1,13 @@
 use gtk4::prelude::*;
 use impulse_core::command_palette::{filter_items, RecentCommandStore};
+use impulse_core::commands::CommandContext;
+use impulse_core::symbol_index::{Symbol, SymbolIndexes};
 use libadwaita as adw;
+use libadwaita::prelude::*;
 
 use std::cell::{Cell, RefCell};
 use std::rc::Rc;
+use std::sync::{Arc, Mutex};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::editor;
@@ -36,6 +40,7 @@ pub(super) fn show_quick_open(
     list.set_selection_mode(gtk4::SelectionMode::Single);
     scroll.set_child(Some(&list));
     vbox.append(&scroll);
+    label_palette(&dialog, &entry, &list, "Quick Open", "Files");
 
     dialog.set_child(Some(&vbox));
 
@@ -161,9 +166,180 @@ pub(super) fn show_quick_open(
     entry.grab_focus();
 }
 
+/// Search symbol definitions across the sidebar's workspace using the
+/// built-in symbol index. `on_open` receives the file path and 0-based
+/// line/character of the chosen symbol.
+pub(super) fn show_workspace_symbols(
+    window: &adw::ApplicationWindow,
+    sidebar_state: &Rc<sidebar::SidebarState>,
+    symbol_indexes: &Arc<Mutex<SymbolIndexes>>,
+    on_open: impl Fn(&str, u32, u32) + 'static,
+) {
+    let dialog = gtk4::Window::builder()
+        .transient_for(window)
+        .modal(true)
+        .decorated(false)
+        .default_width(600)
+        .default_height(400)
+        .build();
+    dialog.add_css_class("quick-open");
+
+    let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 0);
+    let entry = gtk4::SearchEntry::new();
+    entry.set_placeholder_text(Some("Go to symbol in workspace..."));
+    vbox.append(&entry);
+
+    let scroll = gtk4::ScrolledWindow::new();
+    scroll.set_vexpand(true);
+    let list = gtk4::ListBox::new();
+    list.set_selection_mode(gtk4::SelectionMode::Single);
+    scroll.set_child(Some(&list));
+    vbox.append(&scroll);
+    label_palette(&dialog, &entry, &list, "Go to Symbol", "Symbols");
+    dialog.set_child(Some(&vbox));
+
+    let root = {
+        let current = sidebar_state.current_path.borrow().clone();
+        impulse_core::git::get_git_root(&current).unwrap_or(current)
+    };
+    let results: Rc<RefCell<Vec<Symbol>>> = Rc::new(RefCell::new(Vec::new()));
+    // Discard results of queries superseded while the index was searched.
+    let generation = Rc::new(Cell::new(0u64));
+
+    {
+        let list = list.clone();
+        let results = results.clone();
+        let symbol_indexes = symbol_indexes.clone();
+        entry.connect_search_changed(move |entry| {
+            run_guarded_ui("workspace-symbols-search-changed", || {
+                let query = entry.text().to_string();
+                generation.set(generation.get() + 1);
+                let this_generation = generation.get();
+                if query.trim().is_empty() || root.is_empty() {
+                    while let Some(row) = list.row_at_index(0) {
+                        list.remove(&row);
+                    }
+                    results.borrow_mut().clear();
+                    return;
+                }
+                let list = list.clone();
+                let results = results.clone();
+                let generation = generation.clone();
+                let symbol_indexes = symbol_indexes.clone();
+                let root = std::path::PathBuf::from(&root);
+                gtk4::glib::spawn_future_local(async move {
+                    let search_root = root.clone();
+                    let found = gtk4::gio::spawn_blocking(move || {
+                        symbol_indexes
+                            .lock()
+                            .map(|mut indexes| {
+                                indexes.for_root(&search_root).workspace_symbols(&query, 50)
+                            })
+                            .unwrap_or_default()
+                    })
+                    .await
+                    .unwrap_or_default();
+                    if generation.get() != this_generation {
+                        return;
+                    }
+                    while let Some(row) = list.row_at_index(0) {
+                        list.remove(&row);
+                    }
+                    for symbol in &found {
+                        let relative = std::path::Path::new(&symbol.path)
+                            .strip_prefix(&root)
+                            .map(|p| p.display().to_string())
+                            .unwrap_or_else(|_| symbol.path.clone());
+                        let row = gtk4::Box::new(gtk4::Orientation::Horizontal, 8);
+                        let name = gtk4::Label::new(Some(&symbol.name));
+                        name.set_halign(gtk4::Align::Start);
+                        let kind = gtk4::Label::new(Some(symbol.kind.label()));
+                        kind.add_css_class("dim-label");
+                        let location =
+                            gtk4::Label::new(Some(&format!("{}:{}", relative, symbol.line + 1)));
+                        location.set_hexpand(true);
+                        location.set_halign(gtk4::Align::End);
+                        location.set_ellipsize(gtk4::pango::EllipsizeMode::Start);
+                        location.add_css_class("dim-label");
+                        row.append(&name);
+                        row.append(&kind);
+                        row.append(&location);
+                        list.append(&row);
+                    }
+                    *results.borrow_mut() = found;
+                    if let Some(first_row) = list.row_at_index(0) {
+                        list.select_row(Some(&first_row));
+                    }
+                });
+            });
+        });
+    }
+
+    let activate: Rc<dyn Fn(i32)> = {
+        let dialog = dialog.clone();
+        let results = results.clone();
+        Rc::new(move |index: i32| {
+            let symbol = usize::try_from(index)
+                .ok()
+                .and_then(|i| results.borrow().get(i).cloned());
+            dialog.close();
+            if let Some(symbol) = symbol {
+                on_open(&symbol.path, symbol.line, symbol.character);
+            }
+        })
+    };
+
+    {
+        let activate = activate.clone();
+        list.connect_row_activated(move |_list, row| activate(row.index()));
+    }
+
+    let key_controller = gtk4::EventControllerKey::new();
+    {
+        let list = list.clone();
+        let dialog = dialog.clone();
+        key_controller.connect_key_pressed(move |_, key, _, _| {
+            if key == gtk4::gdk::Key::Escape {
+                dialog.close();
+                return gtk4::glib::Propagation::Stop;
+            }
+            if key == gtk4::gdk::Key::Return || key == gtk4::gdk::Key::KP_Enter {
+                if let Some(row) = list.selected_row() {
+                    activate(row.index());
+                    return gtk4::glib::Propagation::Stop;
+                }
+            }
+            if key == gtk4::gdk::Key::Down {
+                let next = match list.selected_row() {
+                    Some(row) => list.row_at_index(row.index() + 1),
+                    None => list.row_at_index(0),
+                };
+                if let Some(next) = next {
+                    list.select_row(Some(&next));
+                }
+                return gtk4::glib::Propagation::Stop;
+            }
+            if key == gtk4::gdk::Key::Up {
+                if let Some(row) = list.selected_row().filter(|row| row.index() > 0) {
+                    if let Some(prev) = list.row_at_index(row.index() - 1) {
+                        list.select_row(Some(&prev));
+                    }
+                }
+                return gtk4::glib::Propagation::Stop;
+            }
+            gtk4::glib::Propagation::Proceed
+        });
+    }
+    entry.add_controller(key_controller);
+
+    dialog.present();
+    entry.grab_focus();
+}
+
 pub(super) fn show_command_palette(
     window: &adw::ApplicationWindow,
     commands: &[Command],
+    context: &CommandContext,
     recents: &Rc<RefCell<RecentCommandStore>>,
     sidebar_state: &Rc<sidebar::SidebarState>,
 ) {
@@ -188,11 +364,19 @@ pub(super) fn show_command_palette(
     list.set_selection_mode(gtk4::SelectionMode::Single);
     scroll.set_child(Some(&list));
     vbox.append(&scroll);
+    label_palette(&dialog, &entry, &list, "Command Palette", "Commands");
 
     dialog.set_child(Some(&vbox));
 
-    // Populate with all commands
-    let commands: Vec<Command> = commands.to_vec();
+    // Populate with the commands that apply to the active tab
+    let commands: Vec<Command> = commands
+        .iter()
+        .filter(|command| {
+            impulse_core::commands::get(&command.item.id)
+                .is_none_or(|registered| registered.when.matches(context))
+        })
+        .cloned()
+        .collect();
     let dynamic_commands: Rc<RefCell<Vec<Command>>> = Rc::new(RefCell::new(Vec::new()));
     let search_generation = Rc::new(Cell::new(0u64));
     populate_command_list(&list, &commands, &dynamic_commands.borrow(), "", recents);
@@ -319,6 +503,7 @@ pub(super) fn show_go_to_line_dialog(
         .default_height(60)
         .build();
     dialog.add_css_class("quick-open"); // reuse quick-open styling
+    dialog.set_title(Some("Go to Line"));
 
     let hbox = gtk4::Box::new(gtk4::Orientation::Horizontal, 8);
     hbox.set_margin_start(12);
@@ -330,6 +515,9 @@ pub(super) fn show_go_to_line_dialog(
     let entry = gtk4::Entry::new();
     entry.set_hexpand(true);
     entry.set_input_purpose(gtk4::InputPurpose::Digits);
+    entry.update_relation(&[gtk4::accessible::Relation::LabelledBy(
+        &[label.upcast_ref()],
+    )]);
 
     hbox.append(&label);
     hbox.append(&entry);
@@ -374,6 +562,21 @@ pub(super) fn show_go_to_line_dialog(
     entry.grab_focus();
 }
 
+/// Name a palette-style dialog and its parts for screen readers: the
+/// undecorated window has no visible title, and the search entry and result
+/// list have only a placeholder to go by.
+pub(super) fn label_palette(
+    dialog: &gtk4::Window,
+    entry: &gtk4::SearchEntry,
+    list: &gtk4::ListBox,
+    title: &str,
+    results: &str,
+) {
+    dialog.set_title(Some(title));
+    entry.update_property(&[gtk4::accessible::Property::Label(title)]);
+    list.update_property(&[gtk4::accessible::Property::Label(results)]);
+}
+
 fn execute_command_for_row(
     row: &gtk4::ListBoxRow,
     commands: &[Command],
@@ -479,3 +682,1044 @@ fn find_command<'a>(
         .chain(dynamic_commands.iter())
         .find(|command| command.item.id == id)
 }
+
+/// Show a confirmation dialog for an LSP rename. Edits are grouped per file,
+/// each file and each edit has a checkbox, and `on_apply` receives only the
+/// edits the user left checked. `read_source` supplies the current text of a
+/// document (for before/after previews).
+pub(super) fn show_rename_preview_dialog(
+    window: &adw::ApplicationWindow,
+    new_name: &str,
+    files: Vec<impulse_core::workspace_edit::FileEdits>,
+    read_source: impl Fn(&str) -> Option<String>,
+    on_apply: impl Fn(Vec<impulse_core::workspace_edit::FileEdits>) + 'static,
+) {
+    show_edit_preview_dialog(
+        window,
+        &format!("Rename to \u{201c}{}\u{201d}", new_name),
+        "Apply Rename",
+        files,
+        read_source,
+        move |selected| {
+            if let Some(selected) = selected {
+                on_apply(selected);
+            }
+        },
+    );
+}
+
+/// The checkbox preview behind [`show_rename_preview_dialog`], for any set
+/// of workspace edits. `on_response` gets the checked edits, or `None` when
+/// the dialog is cancelled or every edit is unchecked.
+pub(super) fn show_edit_preview_dialog(
+    window: &adw::ApplicationWindow,
+    heading: &str,
+    apply_label: &str,
+    files: Vec<impulse_core::workspace_edit::FileEdits>,
+    read_source: impl Fn(&str) -> Option<String>,
+    on_response: impl Fn(Option<Vec<impulse_core::workspace_edit::FileEdits>>) + 'static,
+) {
+    let edit_count: usize = files.iter().map(|f| f.edits.len()).sum();
+    let dialog = adw::AlertDialog::builder()
+        .heading(heading)
+        .body(format!(
+            "{} {} in {} {}. Uncheck any changes you want to skip.",
+            edit_count,
+            if edit_count == 1 { "change" } else { "changes" },
+            files.len(),
+            if files.len() == 1 { "file" } else { "files" },
+        ))
+        .build();
+    dialog.add_response("cancel", "Cancel");
+    dialog.add_response("apply", apply_label);
+    dialog.set_response_appearance("apply", adw::ResponseAppearance::Suggested);
+    dialog.set_default_response(Some("apply"));
+    dialog.set_close_response("cancel");
+
+    let list = gtk4::ListBox::new();
+    list.set_selection_mode(gtk4::SelectionMode::None);
+    list.add_css_class("boxed-list");
+
+    // Per file: the file-level checkbox plus one checkbox per edit.
+    let mut checks: Vec<(gtk4::CheckButton, Vec<gtk4::CheckButton>)> = Vec::new();
+    for file in &files {
+        let path = impulse_core::util::uri_to_file_path(&file.uri);
+        let path_ref = std::path::Path::new(&path);
+        let file_row = adw::ExpanderRow::new();
+        file_row.set_use_markup(false);
+        file_row.set_title(
+            &path_ref
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| path.clone()),
+        );
+        file_row.set_subtitle(
+            &path_ref
+                .parent()
+                .map(|p| p.display().to_string())
+                .unwrap_or_default(),
+        );
+        file_row.set_expanded(files.len() == 1);
+        let file_check = gtk4::CheckButton::new();
+        file_check.set_active(true);
+        file_check.set_valign(gtk4::Align::Center);
+        file_row.add_prefix(&file_check);
+
+        let previews = read_source(&file.uri)
+            .map(|content| impulse_core::workspace_edit::preview_text_edits(&content, &file.edits))
+            .unwrap_or_default();
+        let mut edit_checks = Vec::with_capacity(file.edits.len());
+        for (i, edit) in file.edits.iter().enumerate() {
+            let row = adw::ActionRow::new();
+            row.set_use_markup(false);
+            match previews.get(i) {
+                Some(preview) => {
+                    row.set_title(preview.after.trim());
+                    row.set_subtitle(&format!(
+                        "Line {}: {}",
+                        preview.line + 1,
+                        preview.before.trim()
+                    ));
+                }
+                None => {
+                    row.set_title(&edit.new_text);
+                    row.set_subtitle(&format!("Line {}", edit.start_line + 1));
+                }
+            }
+            row.add_css_class("monospace");
+            let check = gtk4::CheckButton::new();
+            check.set_active(true);
+            check.set_valign(gtk4::Align::Center);
+            row.add_prefix(&check);
+            row.set_activatable_widget(Some(&check));
+            file_row.add_row(&row);
+            edit_checks.push(check);
+        }
+
+        {
+            let edit_checks = edit_checks.clone();
+            file_check.connect_toggled(move |file_check| {
+                for check in &edit_checks {
+                    check.set_active(file_check.is_active());
+                }
+            });
+        }
+        list.append(&file_row);
+        checks.push((file_check, edit_checks));
+    }
+
+    let scrolled = gtk4::ScrolledWindow::builder()
+        .hscrollbar_policy(gtk4::PolicyType::Never)
+        .min_content_height(120)
+        .max_content_height(360)
+        .propagate_natural_height(true)
+        .child(&list)
+        .build();
+    dialog.set_extra_child(Some(&scrolled));
+
+    dialog.connect_response(None, move |_dialog, response| {
+        if response != "apply" {
+            on_response(None);
+            return;
+        }
+        let selected: Vec<impulse_core::workspace_edit::FileEdits> = files
+            .iter()
+            .zip(checks.iter())
+            .filter_map(|(file, (_file_check, edit_checks))| {
+                let edits: Vec<_> = file
+                    .edits
+                    .iter()
+                    .zip(edit_checks.iter())
+                    .filter(|(_, check)| check.is_active())
+                    .map(|(edit, _)| edit.clone())
+                    .collect();
+                (!edits.is_empty()).then(|| impulse_core::workspace_edit::FileEdits {
+                    uri: file.uri.clone(),
+                    edits,
+                })
+            })
+            .collect();
+        on_response((!selected.is_empty()).then_some(selected));
+    });
+    dialog.present(Some(window));
+}
+
+/// Ask what to do with each modified editor before the window closes.
+/// `on_close` gets the paths the user chose to save; it is not called when
+/// the close is cancelled. Untitled buffers can't be saved from here.
+pub(super) fn show_unsaved_changes_dialog(
+    window: &adw::ApplicationWindow,
+    summary: &impulse_core::close_risk::CloseRiskSummary,
+    on_close: impl Fn(Vec<String>) + 'static,
+) {
+    let dialog = adw::AlertDialog::builder()
+        .heading(&summary.title)
+        .body(format!(
+            "{} Uncheck any files you don't want to save.",
+            summary.informative_text
+        ))
+        .build();
+    dialog.add_response("cancel", &summary.cancel_title);
+    dialog.add_response("discard", "Don't Save");
+    dialog.add_response("save", "Save & Close");
+    dialog.set_response_appearance("discard", adw::ResponseAppearance::Destructive);
+    dialog.set_response_appearance("save", adw::ResponseAppearance::Suggested);
+    dialog.set_default_response(Some("save"));
+    dialog.set_close_response("cancel");
+
+    let list = gtk4::ListBox::new();
+    list.set_selection_mode(gtk4::SelectionMode::None);
+    list.add_css_class("boxed-list");
+
+    let mut checks: Vec<(String, gtk4::CheckButton)> = Vec::new();
+    for file in &summary.unsaved_files {
+        let row = adw::ActionRow::new();
+        row.set_use_markup(false);
+        row.set_title(&file.display_name);
+        let check = gtk4::CheckButton::new();
+        check.set_valign(gtk4::Align::Center);
+        if editor::is_untitled_path(&file.path) {
+            row.set_subtitle("New file \u{2014} use Save As to keep it");
+            check.set_sensitive(false);
+        } else {
+            row.set_subtitle(
+                &std::path::Path::new(&file.path)
+                    .parent()
+                    .map(|p| p.display().to_string())
+                    .unwrap_or_default(),
+            );
+            check.set_active(true);
+            row.set_activatable_widget(Some(&check));
+        }
+        row.add_prefix(&check);
+        list.append(&row);
+        checks.push((file.path.clone(), check));
+    }
+
+    let scrolled = gtk4::ScrolledWindow::builder()
+        .hscrollbar_policy(gtk4::PolicyType::Never)
+        .max_content_height(360)
+        .propagate_natural_height(true)
+        .child(&list)
+        .build();
+    dialog.set_extra_child(Some(&scrolled));
+
+    dialog.connect_response(None, move |_dialog, response| match response {
+        "save" => on_close(
+            checks
+                .iter()
+                .filter(|(_, check)| check.is_active())
+                .map(|(path, _)| path.clone())
+                .collect(),
+        ),
+        "discard" => on_close(Vec::new()),
+        _ => {}
+    });
+    dialog.present(Some(window));
+}
+
+/// List the versions of `path` in local history, newest first, each with
+/// Compare and Restore buttons. Both close the dialog.
+pub(super) fn show_local_history_dialog(
+    window: &adw::ApplicationWindow,
+    path: &str,
+    entries: Vec<impulse_core::local_history::HistoryEntry>,
+    on_compare: impl Fn(&impulse_core::local_history::HistoryEntry) + 'static,
+    on_restore: impl Fn(&impulse_core::local_history::HistoryEntry) + 'static,
+) {
+    let filename = std::path::Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string());
+    let dialog = adw::AlertDialog::builder()
+        .heading("Local History")
+        .body(if entries.is_empty() {
+            format!("No saved versions of \u{201c}{}\u{201d} yet.", filename)
+        } else {
+            format!(
+                "Saved versions of \u{201c}{}\u{201d}, newest first. Restoring replaces the editor's text; save to keep it.",
+                filename
+            )
+        })
+        .build();
+    dialog.add_response("close", "Close");
+    dialog.set_close_response("close");
+
+    if !entries.is_empty() {
+        let list = gtk4::ListBox::new();
+        list.set_selection_mode(gtk4::SelectionMode::None);
+        list.add_css_class("boxed-list");
+        let on_compare = Rc::new(on_compare);
+        let on_restore = Rc::new(on_restore);
+        for entry in entries {
+            let row = adw::ActionRow::new();
+            row.set_use_markup(false);
+            row.set_title(&history_entry_time(entry.saved_at_ms));
+            row.set_subtitle(&impulse_core::file_properties::size_label(entry.size));
+
+            let compare_btn = gtk4::Button::with_label("Compare");
+            compare_btn.add_css_class("flat");
+            compare_btn.set_valign(gtk4::Align::Center);
+            let restore_btn = gtk4::Button::with_label("Restore");
+            restore_btn.add_css_class("flat");
+            restore_btn.set_valign(gtk4::Align::Center);
+            row.add_suffix(&compare_btn);
+            row.add_suffix(&restore_btn);
+            {
+                let dialog = dialog.clone();
+                let on_compare = on_compare.clone();
+                let entry = entry.clone();
+                compare_btn.connect_clicked(move |_| {
+                    dialog.close();
+                    on_compare(&entry);
+                });
+            }
+            {
+                let dialog = dialog.clone();
+                let on_restore = on_restore.clone();
+                restore_btn.connect_clicked(move |_| {
+                    dialog.close();
+                    on_restore(&entry);
+                });
+            }
+            list.append(&row);
+        }
+
+        let scrolled = gtk4::ScrolledWindow::builder()
+            .hscrollbar_policy(gtk4::PolicyType::Never)
+            .min_content_height(120)
+            .max_content_height(360)
+            .propagate_natural_height(true)
+            .child(&list)
+            .build();
+        dialog.set_extra_child(Some(&scrolled));
+    }
+    dialog.present(Some(window));
+}
+
+/// A history entry's save time in the local time zone.
+pub(super) fn history_entry_time(saved_at_ms: u64) -> String {
+    gtk4::glib::DateTime::from_unix_local((saved_at_ms / 1000) as i64)
+        .and_then(|time| time.format("%Y-%m-%d %H:%M:%S"))
+        .map(|s| s.to_string())
+        .unwrap_or_else(|_| saved_at_ms.to_string())
+}
+
+/// What the user chose in the three-way merge view.
+#[derive(Clone, Copy)]
+pub(super) enum MergeViewAction {
+    UseCurrent,
+    UseIncoming,
+    MarkResolved,
+}
+
+/// Show the base, current, and incoming versions of a conflicted file side
+/// by side. Sides missing from the merge (e.g. a file added on one branch)
+/// are shown as absent and cannot be taken.
+pub(super) fn show_merge_view_dialog(
+    parent: &impl IsA<gtk4::Widget>,
+    file_name: &str,
+    versions: impulse_core::git::ConflictVersions,
+    on_action: impl Fn(MergeViewAction) + 'static,
+) {
+    let dialog = adw::Dialog::builder()
+        .title(format!("Merge \u{201c}{}\u{201d}", file_name))
+        .content_width(1100)
+        .content_height(640)
+        .build();
+
+    let header = adw::HeaderBar::new();
+    let use_current = gtk4::Button::with_label("Use Current");
+    let use_incoming = gtk4::Button::with_label("Use Incoming");
+    let mark_resolved = gtk4::Button::with_label("Mark Resolved");
+    mark_resolved.add_css_class("suggested-action");
+    mark_resolved.set_tooltip_text(Some("Stage the saved file as resolved"));
+    use_current.set_sensitive(versions.current.is_some());
+    use_incoming.set_sensitive(versions.incoming.is_some());
+    header.pack_start(&use_current);
+    header.pack_start(&use_incoming);
+    header.pack_end(&mark_resolved);
+
+    let panes = gtk4::Box::new(gtk4::Orientation::Horizontal, 6);
+    panes.set_homogeneous(true);
+    panes.set_margin_start(6);
+    panes.set_margin_end(6);
+    panes.set_margin_bottom(6);
+    for (title, text) in [
+        ("Current", &versions.current),
+        ("Base", &versions.base),
+        ("Incoming", &versions.incoming),
+    ] {
+        let column = gtk4::Box::new(gtk4::Orientation::Vertical, 4);
+        let label = gtk4::Label::new(Some(title));
+        label.add_css_class("heading");
+        label.set_halign(gtk4::Align::Start);
+        column.append(&label);
+
+        let view = gtk4::TextView::new();
+        view.set_editable(false);
+        view.set_cursor_visible(false);
+        view.set_monospace(true);
+        view.set_left_margin(6);
+        view.buffer().set_text(
+            text.as_deref()
+                .unwrap_or("(file does not exist in this version)"),
+        );
+        let scrolled = gtk4::ScrolledWindow::builder()
+            .vexpand(true)
+            .child(&view)
+            .build();
+        column.append(&scrolled);
+        panes.append(&column);
+    }
+
+    let toolbar = adw::ToolbarView::new();
+    toolbar.add_top_bar(&header);
+    toolbar.set_content(Some(&panes));
+    dialog.set_child(Some(&toolbar));
+
+    let on_action = Rc::new(on_action);
+    for (button, action) in [
+        (&use_current, MergeViewAction::UseCurrent),
+        (&use_incoming, MergeViewAction::UseIncoming),
+        (&mark_resolved, MergeViewAction::MarkResolved),
+    ] {
+        let dialog = dialog.clone();
+        let on_action = on_action.clone();
+        button.connect_clicked(move |_| {
+            dialog.close();
+            on_action(action);
+        });
+    }
+    dialog.present(Some(parent));
+}
+
+/// An entry row for a git revision with a suffix menu of known `refs`.
+/// Any `git rev-parse` expression can also be typed. Also used for layout
+/// names, with the saved layouts as `refs`.
+fn revision_row(title: &str, initial: &str, refs: &[String]) -> adw::EntryRow {
+    let row = adw::EntryRow::new();
+    row.set_title(title);
+    row.set_text(initial);
+
+    let list = gtk4::ListBox::new();
+    list.set_selection_mode(gtk4::SelectionMode::None);
+    for name in refs {
+        let label = gtk4::Label::new(Some(name));
+        label.set_halign(gtk4::Align::Start);
+        label.set_margin_start(6);
+        label.set_margin_end(6);
+        list.append(&label);
+    }
+    let scrolled = gtk4::ScrolledWindow::builder()
+        .hscrollbar_policy(gtk4::PolicyType::Never)
+        .propagate_natural_height(true)
+        .max_content_height(320)
+        .child(&list)
+        .build();
+    let popover = gtk4::Popover::new();
+    popover.set_child(Some(&scrolled));
+    let menu = gtk4::MenuButton::builder()
+        .icon_name("pan-down-symbolic")
+        .valign(gtk4::Align::Center)
+        .tooltip_text("Branches and tags")
+        .popover(&popover)
+        .build();
+    menu.add_css_class("flat");
+    menu.set_sensitive(!refs.is_empty());
+    row.add_suffix(&menu);
+
+    let refs = refs.to_vec();
+    let entry = row.clone();
+    list.connect_row_activated(move |_, list_row| {
+        if let Some(name) = refs.get(list_row.index() as usize) {
+            entry.set_text(name);
+        }
+        popover.popdown();
+    });
+    row
+}
+
+/// Ask for two revisions to compare. `refs` seeds the pickers; `on_compare`
+/// receives the trimmed base and target.
+pub(super) fn show_compare_refs_dialog(
+    parent: &impl IsA<gtk4::Widget>,
+    refs: Vec<String>,
+    on_compare: impl Fn(String, String) + 'static,
+) {
+    let dialog = adw::AlertDialog::builder()
+        .heading("Compare Revisions")
+        .body("Pick branches or tags, or enter any commit or revision expression.")
+        .build();
+    dialog.add_response("cancel", "Cancel");
+    dialog.add_response("compare", "Compare");
+    dialog.set_response_appearance("compare", adw::ResponseAppearance::Suggested);
+    dialog.set_default_response(Some("compare"));
+    dialog.set_close_response("cancel");
+
+    let list = gtk4::ListBox::new();
+    list.set_selection_mode(gtk4::SelectionMode::None);
+    list.add_css_class("boxed-list");
+    let base_row = revision_row("Base", refs.first().map_or("", String::as_str), &refs);
+    let target_row = revision_row("Target", "HEAD", &refs);
+    list.append(&base_row);
+    list.append(&target_row);
+    dialog.set_extra_child(Some(&list));
+
+    dialog.connect_response(None, move |_, response| {
+        if response != "compare" {
+            return;
+        }
+        let base = base_row.text().trim().to_string();
+        let target = target_row.text().trim().to_string();
+        if !base.is_empty() && !target.is_empty() {
+            on_compare(base, target);
+        }
+    });
+    dialog.present(Some(parent));
+}
+
+/// Ask for a name to save the window layout under. `existing` are the saved
+/// layouts, offered to overwrite; `on_save` receives the name.
+pub(super) fn show_save_layout_dialog(
+    parent: &impl IsA<gtk4::Widget>,
+    existing: Vec<String>,
+    on_save: impl Fn(String) + 'static,
+) {
+    let dialog = adw::AlertDialog::builder()
+        .heading("Save Window Layout")
+        .body("Saves the workspace folder, open tabs and sidebar under a name.")
+        .build();
+    dialog.add_response("cancel", "Cancel");
+    dialog.add_response("save", "Save");
+    dialog.set_response_appearance("save", adw::ResponseAppearance::Suggested);
+    dialog.set_default_response(Some("save"));
+    dialog.set_close_response("cancel");
+
+    let list = gtk4::ListBox::new();
+    list.set_selection_mode(gtk4::SelectionMode::None);
+    list.add_css_class("boxed-list");
+    let name_row = revision_row("Name", "", &existing);
+    list.append(&name_row);
+    dialog.set_extra_child(Some(&list));
+
+    dialog.connect_response(None, move |_, response| {
+        let name = name_row.text().trim().to_string();
+        if response == "save" && !name.is_empty() {
+            on_save(name);
+        }
+    });
+    dialog.present(Some(parent));
+}
+
+/// Ask for an `sftp://` or `ssh://` URI to open, offering the remote files
+/// opened before.
+pub(super) fn show_open_remote_file_dialog(
+    parent: &impl IsA<gtk4::Widget>,
+    recent: Vec<String>,
+    on_open: impl Fn(String) + 'static,
+) {
+    let dialog = adw::AlertDialog::builder()
+        .heading("Open Remote File")
+        .body(
+            "Downloads the file over SSH and uploads it again when you save. \
+             The host must accept your SSH key without a prompt.",
+        )
+        .build();
+    dialog.add_response("cancel", "Cancel");
+    dialog.add_response("open", "Open");
+    dialog.set_response_appearance("open", adw::ResponseAppearance::Suggested);
+    dialog.set_default_response(Some("open"));
+    dialog.set_close_response("cancel");
+    dialog.set_response_enabled("open", false);
+
+    let list = gtk4::ListBox::new();
+    list.set_selection_mode(gtk4::SelectionMode::None);
+    list.add_css_class("boxed-list");
+    let uri_row = adw::EntryRow::new();
+    uri_row.set_title("sftp://user@host/path/to/file");
+    list.append(&uri_row);
+    for uri in &recent {
+        let row = adw::ActionRow::new();
+        row.set_title(&gtk4::glib::markup_escape_text(uri));
+        row.set_activatable(true);
+        list.append(&row);
+    }
+    dialog.set_extra_child(Some(&list));
+
+    {
+        let dialog = dialog.clone();
+        uri_row.connect_changed(move |row| {
+            let valid = impulse_core::remote_files::parse_uri(&row.text()).is_ok();
+            dialog.set_response_enabled("open", valid);
+        });
+    }
+    {
+        let uri_row = uri_row.clone();
+        list.connect_row_activated(move |_, row| {
+            if let Some(uri) = row
+                .index()
+                .checked_sub(1)
+                .and_then(|i| recent.get(i as usize))
+            {
+                uri_row.set_text(uri);
+            }
+        });
+    }
+    dialog.connect_response(None, move |_, response| {
+        if response == "open" {
+            on_open(uri_row.text().trim().to_string());
+        }
+    });
+    dialog.present(Some(parent));
+}
+
+/// Ask for the password, token or SSH key passphrase a git remote needs.
+/// `on_answer` gets `None` when the dialog is cancelled.
+pub(super) fn show_git_credentials_dialog(
+    parent: &impl IsA<gtk4::Widget>,
+    request: &impulse_core::git::CredentialRequest,
+    on_answer: impl Fn(Option<impulse_core::git::CredentialReply>) + 'static,
+) {
+    use impulse_core::git::{CredentialReply, CredentialRequest};
+
+    let (heading, body, username) = match request {
+        CredentialRequest::UserPass {
+            url,
+            username,
+            retry,
+        } => {
+            let mut body = format!("Enter a username and password or access token for {}.", url);
+            if *retry {
+                body.insert_str(0, "Those credentials were rejected. ");
+            }
+            (
+                "Git Sign In",
+                body,
+                Some(username.clone().unwrap_or_default()),
+            )
+        }
+        CredentialRequest::SshPassphrase { url, key_path } => (
+            "Unlock SSH Key",
+            format!(
+                "Enter the passphrase of {} to connect to {}.",
+                key_path, url
+            ),
+            None,
+        ),
+    };
+    let dialog = adw::AlertDialog::builder()
+        .heading(heading)
+        .body(body)
+        .build();
+    dialog.add_response("cancel", "Cancel");
+    dialog.add_response("connect", "Connect");
+    dialog.set_response_appearance("connect", adw::ResponseAppearance::Suggested);
+    dialog.set_default_response(Some("connect"));
+    dialog.set_close_response("cancel");
+
+    let list = gtk4::ListBox::new();
+    list.set_selection_mode(gtk4::SelectionMode::None);
+    list.add_css_class("boxed-list");
+    let username_row = username.map(|username| {
+        let row = adw::EntryRow::new();
+        row.set_title("Username");
+        row.set_text(&username);
+        list.append(&row);
+        row
+    });
+    let secret_row = adw::PasswordEntryRow::new();
+    secret_row.set_title(if username_row.is_some() {
+        "Password or Token"
+    } else {
+        "Passphrase"
+    });
+    secret_row.set_activates_default(true);
+    list.append(&secret_row);
+    let remember_row = adw::SwitchRow::new();
+    remember_row.set_title("Remember in Keyring");
+    remember_row.set_subtitle("Kept once the remote accepts it; remove it in Settings");
+    list.append(&remember_row);
+    dialog.set_extra_child(Some(&list));
+
+    {
+        let secret_row = secret_row.clone();
+        let username_row = username_row.clone();
+        dialog.connect_map(move |_| match &username_row {
+            Some(row) if row.text().is_empty() => {
+                row.grab_focus();
+            }
+            _ => {
+                secret_row.grab_focus();
+            }
+        });
+    }
+    dialog.connect_response(None, move |_, response| {
+        let reply = (response == "connect").then(|| CredentialReply {
+            username: username_row
+                .as_ref()
+                .map(|row| row.text().trim().to_string())
+                .unwrap_or_default(),
+            secret: secret_row.text().to_string(),
+            remember: remember_row.is_active(),
+        });
+        on_answer(reply);
+    });
+    dialog.present(Some(parent));
+}
+
+/// List the saved window layouts. Activating a row passes its name to
+/// `on_open`.
+pub(super) fn show_layouts_dialog(
+    parent: &impl IsA<gtk4::Widget>,
+    names: Vec<String>,
+    on_open: impl Fn(String) + 'static,
+) {
+    let dialog = adw::AlertDialog::builder()
+        .heading("Open Layout")
+        .body("Each layout opens in a new window.")
+        .build();
+    dialog.add_response("close", "Close");
+    dialog.set_close_response("close");
+
+    let list = gtk4::ListBox::new();
+    list.set_selection_mode(gtk4::SelectionMode::None);
+    list.add_css_class("boxed-list");
+    for name in &names {
+        let row = adw::ActionRow::new();
+        row.set_title(&gtk4::glib::markup_escape_text(name));
+        row.set_activatable(true);
+        list.append(&row);
+    }
+    let scrolled = gtk4::ScrolledWindow::builder()
+        .hscrollbar_policy(gtk4::PolicyType::Never)
+        .propagate_natural_height(true)
+        .max_content_height(360)
+        .child(&list)
+        .build();
+    dialog.set_extra_child(Some(&scrolled));
+
+    let dialog_ref = dialog.clone();
+    list.connect_row_activated(move |_, row| {
+        if let Some(name) = names.get(row.index() as usize) {
+            dialog_ref.close();
+            on_open(name.clone());
+        }
+    });
+    dialog.present(Some(parent));
+}
+
+/// List the repository's worktrees. Activating a row passes its path to
+/// `on_open`; the "New Worktree" response calls `on_create`.
+pub(super) fn show_worktrees_dialog(
+    parent: &impl IsA<gtk4::Widget>,
+    worktrees: Vec<impulse_core::git::WorktreeInfo>,
+    on_open: impl Fn(String) + 'static,
+    on_create: impl Fn() + 'static,
+) {
+    let dialog = adw::AlertDialog::builder()
+        .heading("Worktrees")
+        .body("Each worktree opens in a new window.")
+        .build();
+    dialog.add_response("close", "Close");
+    dialog.add_response("create", "New Worktree\u{2026}");
+    dialog.set_close_response("close");
+
+    let list = gtk4::ListBox::new();
+    list.set_selection_mode(gtk4::SelectionMode::None);
+    list.add_css_class("boxed-list");
+    for worktree in &worktrees {
+        let row = adw::ActionRow::new();
+        row.set_title(&gtk4::glib::markup_escape_text(&worktree.name));
+        row.set_subtitle(&gtk4::glib::markup_escape_text(&worktree.path));
+        row.set_activatable(!worktree.is_current);
+        let mut tags = Vec::new();
+        if let Some(branch) = &worktree.branch {
+            tags.push(branch.clone());
+        }
+        if worktree.is_main {
+            tags.push("main".to_string());
+        }
+        if worktree.is_locked {
+            tags.push("locked".to_string());
+        }
+        if worktree.is_current {
+            tags.push("current".to_string());
+        }
+        let label = gtk4::Label::new(Some(&tags.join(" \u{b7} ")));
+        label.add_css_class("dim-label");
+        row.add_suffix(&label);
+        list.append(&row);
+    }
+    let scrolled = gtk4::ScrolledWindow::builder()
+        .hscrollbar_policy(gtk4::PolicyType::Never)
+        .propagate_natural_height(true)
+        .max_content_height(360)
+        .child(&list)
+        .build();
+    dialog.set_extra_child(Some(&scrolled));
+
+    {
+        let dialog = dialog.clone();
+        list.connect_row_activated(move |_, row| {
+            if let Some(worktree) = worktrees.get(row.index() as usize) {
+                dialog.close();
+                on_open(worktree.path.clone());
+            }
+        });
+    }
+    dialog.connect_response(None, move |_, response| {
+        if response == "create" {
+            on_create();
+        }
+    });
+    dialog.present(Some(parent));
+}
+
+/// Ask for a branch and location for a new worktree of the repository at
+/// `repo_root`. The location follows the branch name (as a sibling of the
+/// repository) until edited. `on_create` receives the branch and location.
+pub(super) fn show_create_worktree_dialog(
+    parent: &impl IsA<gtk4::Widget>,
+    repo_root: &str,
+    branches: Vec<String>,
+    on_create: impl Fn(String, String) + 'static,
+) {
+    let dialog = adw::AlertDialog::builder()
+        .heading("New Worktree")
+        .body("Check out an existing branch, or name a new one to create it from HEAD.")
+        .build();
+    dialog.add_response("cancel", "Cancel");
+    dialog.add_response("create", "Create");
+    dialog.set_response_appearance("create", adw::ResponseAppearance::Suggested);
+    dialog.set_default_response(Some("create"));
+    dialog.set_close_response("cancel");
+
+    let list = gtk4::ListBox::new();
+    list.set_selection_mode(gtk4::SelectionMode::None);
+    list.add_css_class("boxed-list");
+    let branch_row = revision_row("Branch", "", &branches);
+    let location_row = adw::EntryRow::new();
+    location_row.set_title("Location");
+    list.append(&branch_row);
+    list.append(&location_row);
+    dialog.set_extra_child(Some(&list));
+
+    // Suggest `<parent>/<repo>-<branch>` while the location is untouched.
+    let repo_root = std::path::Path::new(repo_root);
+    let base_dir = repo_root
+        .parent()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let repo_name = repo_root
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let suggested = Rc::new(RefCell::new(String::new()));
+    {
+        let location_row = location_row.clone();
+        let suggested = suggested.clone();
+        branch_row.connect_changed(move |row| {
+            if location_row.text() != *suggested.borrow() {
+                return;
+            }
+            let branch = row.text().trim().replace('/', "-");
+            let path = if branch.is_empty() {
+                String::new()
+            } else {
+                format!("{}/{}-{}", base_dir, repo_name, branch)
+            };
+            *suggested.borrow_mut() = path.clone();
+            location_row.set_text(&path);
+        });
+    }
+
+    dialog.connect_response(None, move |_, response| {
+        if response != "create" {
+            return;
+        }
+        let branch = branch_row.text().trim().to_string();
+        let location = location_row.text().trim().to_string();
+        if !branch.is_empty() && !location.is_empty() {
+            on_create(branch, location);
+        }
+    });
+    dialog.present(Some(parent));
+}
+
+/// Show the properties of the file or folder at `path`. On Unix the
+/// permission bits can be changed from the dialog; read and chmod errors
+/// go to `toast_overlay`.
+pub(super) fn show_properties_dialog(
+    parent: &impl IsA<gtk4::Widget>,
+    path: &str,
+    toast_overlay: &adw::ToastOverlay,
+) {
+    use impulse_core::file_properties::{self, FileKind};
+
+    let toast = {
+        let toast_overlay = toast_overlay.clone();
+        move |message: &str| {
+            let toast = adw::Toast::new(message);
+            toast.set_timeout(4);
+            toast_overlay.add_toast(toast);
+        }
+    };
+    let props = match file_properties::read(std::path::Path::new(path)) {
+        Ok(props) => props,
+        Err(e) => {
+            toast(&e);
+            return;
+        }
+    };
+
+    let dialog = adw::AlertDialog::builder().heading(&props.name).build();
+    dialog.add_response("close", "Close");
+    dialog.set_close_response("close");
+    dialog.set_default_response(Some("close"));
+
+    let content = gtk4::Box::new(gtk4::Orientation::Vertical, 12);
+    let list = gtk4::ListBox::new();
+    list.set_selection_mode(gtk4::SelectionMode::None);
+    list.add_css_class("boxed-list");
+    let add_row = |title: &str, value: &str| {
+        let row = adw::ActionRow::builder()
+            .title(title)
+            .subtitle(value)
+            .subtitle_selectable(true)
+            .build();
+        row.add_css_class("property");
+        list.append(&row);
+    };
+
+    let location = std::path::Path::new(&props.path)
+        .parent()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_default();
+    add_row("Location", &location);
+    add_row(
+        "Kind",
+        match props.kind {
+            FileKind::File => "File",
+            FileKind::Directory => "Folder",
+            FileKind::Symlink => "Symbolic link",
+            FileKind::Other => "Special file",
+        },
+    );
+    if let Some(target) = &props.symlink_target {
+        add_row("Link Target", target);
+    }
+    if props.kind != FileKind::Directory && std::path::Path::new(&props.path).is_file() {
+        add_row("Size", &file_properties::size_label(props.size));
+    }
+    if let Some(lines) = props.line_count {
+        add_row("Lines", &lines.to_string());
+    }
+    if let Some(encoding) = &props.encoding {
+        add_row("Encoding", encoding);
+    }
+    let modified = props
+        .modified
+        .and_then(|secs| gtk4::glib::DateTime::from_unix_local(secs as i64).ok())
+        .and_then(|date| date.format("%Y-%m-%d %H:%M:%S").ok());
+    if let Some(modified) = modified {
+        add_row("Modified", &modified);
+    }
+    if let Some(owner) = &props.owner {
+        add_row("Owner", owner);
+    }
+    if let Some(group) = &props.group {
+        add_row("Group", group);
+    }
+    content.append(&list);
+
+    if let Some(mode) = props.mode {
+        let grid = gtk4::Grid::builder()
+            .row_spacing(4)
+            .column_spacing(18)
+            .halign(gtk4::Align::Center)
+            .build();
+        for (col, title) in ["Read", "Write", "Execute"].into_iter().enumerate() {
+            grid.attach(&gtk4::Label::new(Some(title)), col as i32 + 1, 0, 1, 1);
+        }
+        let summary = gtk4::Label::new(None);
+        summary.add_css_class("monospace");
+        summary.add_css_class("dim-label");
+        let describe = |mode: u32| {
+            format!(
+                "{} ({:03o})",
+                file_properties::mode_string(mode),
+                mode & 0o777
+            )
+        };
+        summary.set_text(&describe(mode));
+
+        // Bits in `ls -l` order: owner, group, others; read, write, execute.
+        let checks: Rc<Vec<(u32, gtk4::CheckButton)>> = Rc::new(
+            ["Owner", "Group", "Others"]
+                .into_iter()
+                .enumerate()
+                .flat_map(|(row, who)| {
+                    let label = gtk4::Label::new(Some(who));
+                    label.set_halign(gtk4::Align::Start);
+                    grid.attach(&label, 0, row as i32 + 1, 1, 1);
+                    (0..3).map(move |col| {
+                        let bit = 0o400 >> (row * 3 + col);
+                        let check = gtk4::CheckButton::new();
+                        check.set_active(mode & bit != 0);
+                        check.set_halign(gtk4::Align::Center);
+                        (bit, check)
+                    })
+                })
+                .collect(),
+        );
+        for (i, (_, check)) in checks.iter().enumerate() {
+            grid.attach(check, (i % 3) as i32 + 1, (i / 3) as i32 + 1, 1, 1);
+        }
+        let chosen_mode = {
+            let checks = checks.clone();
+            move || {
+                checks
+                    .iter()
+                    .filter(|(_, check)| check.is_active())
+                    .fold(mode & !0o777, |mode, (bit, _)| mode | bit)
+            }
+        };
+        for (_, check) in checks.iter() {
+            let dialog = dialog.clone();
+            let summary = summary.clone();
+            let chosen_mode = chosen_mode.clone();
+            check.connect_toggled(move |_| {
+                let new_mode = chosen_mode();
+                summary.set_text(&describe(new_mode));
+                dialog.set_response_enabled("apply", new_mode != mode);
+            });
+        }
+
+        let heading = gtk4::Label::new(Some("Permissions"));
+        heading.add_css_class("heading");
+        heading.set_halign(gtk4::Align::Start);
+        content.append(&heading);
+        content.append(&grid);
+        content.append(&summary);
+
+        dialog.add_response("apply", "Apply Permissions");
+        dialog.set_response_appearance("apply", adw::ResponseAppearance::Suggested);
+        dialog.set_response_enabled("apply", false);
+        let path = props.path.clone();
+        dialog.connect_response(Some("apply"), move |_, _| {
+            if let Err(e) = file_properties::set_mode(std::path::Path::new(&path), chosen_mode()) {
+                toast(&e);
+            }
+        });
+    }
+
+    let scroll = gtk4::ScrolledWindow::builder()
+        .hscrollbar_policy(gtk4::PolicyType::Never)
+        .propagate_natural_height(true)
+        .max_content_height(520)
+        .child(&content)
+        .build();
+    dialog.set_extra_child(Some(&scroll));
+    dialog.present(Some(parent));
+}
@@ -12,13 +12,14 @@ use crate::keybindings;
 use crate::lsp_completion::{lsp_content_changes, LspRequest};
 use crate::terminal;
 use crate::terminal_container;
+use impulse_editor::protocol::EditorEvent;
 
 use super::{
     add_shortcut, build_window, ensure_file_uri, get_active_cwd, language_from_uri,
-    send_diff_decorations, show_go_to_line_dialog, uri_to_file_path, Command,
+    send_diff_decorations, show_go_to_line_dialog, uri_to_file_path, Command, ShortcutRegistry,
 };
 
-use super::sidebar_signals::dispatch_lsp_request;
+use super::sidebar_signals::{dispatch_lsp_request, formatter_for_request};
 
 /// Install the capture-phase EventControllerKey on the window.
 ///
@@ -55,7 +56,8 @@ pub(super) fn setup_capture_phase_keys(
         let custom_keybindings = settings.borrow().custom_keybindings.clone();
         for kb in custom_keybindings {
             let accel = keybindings::parse_keybinding_to_accel(&kb.key);
-            if accel.is_empty() {
+            // Chords are dispatched through `ctx.chords` instead.
+            if accel.is_empty() || keybindings::is_chord(&accel) {
                 continue;
             }
             if let Some(parsed) = keybindings::parse_accel(&accel) {
@@ -73,7 +75,7 @@ pub(super) fn setup_capture_phase_keys(
                         // Get CWD from the active terminal or editor tab
                         let cwd = get_active_cwd(&tab_view);
 
-                        let theme = crate::theme::get_theme(&settings.borrow().color_scheme);
+                        let theme = crate::theme::active_theme(&settings.borrow());
                         let term = terminal::create_terminal(
                             &settings.borrow(),
                             theme,
@@ -96,7 +98,7 @@ pub(super) fn setup_capture_phase_keys(
         }
     }
 
-    let capture_kb_overrides = settings.borrow().keybinding_overrides.clone();
+    let capture_kb_overrides = settings.borrow().effective_keybinding_overrides();
 
     let new_file_accel =
         keybindings::parse_accel(&keybindings::get_accel("new_file", &capture_kb_overrides));
@@ -110,9 +112,16 @@ pub(super) fn setup_capture_phase_keys(
     let md_preview_tab_view = tab_view.clone();
     let md_preview_status_bar = ctx.status_bar.clone();
 
+    let chords = ctx.chords.clone();
+
     let capture_key_ctrl = gtk4::EventControllerKey::new();
     capture_key_ctrl.set_propagation_phase(gtk4::PropagationPhase::Capture);
     capture_key_ctrl.connect_key_pressed(move |_, key, _keycode, modifiers| {
+        // Pending chords take the next key before anything else sees it.
+        if chords.handle_key(key, modifiers) == gtk4::glib::Propagation::Stop {
+            return gtk4::glib::Propagation::Stop;
+        }
+
         let ctrl = modifiers.contains(gtk4::gdk::ModifierType::CONTROL_MASK);
         let shift = modifiers.contains(gtk4::gdk::ModifierType::SHIFT_MASK);
 
@@ -138,7 +147,7 @@ pub(super) fn setup_capture_phase_keys(
                     let child = page.child();
                     if editor::is_editor(&child) {
                         let s = md_preview_settings.borrow();
-                        let theme = crate::theme::get_theme(&s.color_scheme);
+                        let theme = crate::theme::active_theme(&s);
                         if let Some(is_previewing) =
                             editor::toggle_preview(child.upcast_ref(), theme)
                         {
@@ -251,9 +260,14 @@ pub(super) fn setup_shortcut_controller(
     let settings = &ctx.settings;
     let toast_overlay = &ctx.toast_overlay;
     let lsp_request_tx = &ctx.lsp.request_tx;
-    let shortcut_controller = gtk4::ShortcutController::new();
-    shortcut_controller.set_scope(gtk4::ShortcutScope::Global);
-    let kb_overrides = settings.borrow().keybinding_overrides.clone();
+    let shortcut_controller = ShortcutRegistry {
+        controller: gtk4::ShortcutController::new(),
+        chords: ctx.chords.clone(),
+    };
+    shortcut_controller
+        .controller
+        .set_scope(gtk4::ShortcutScope::Global);
+    let kb_overrides = settings.borrow().effective_keybinding_overrides();
 
     // Ctrl+T: New tab
     {
@@ -271,294 +285,19 @@ pub(super) fn setup_shortcut_controller(
     // both the shortcut controller and the capture-phase handler can invoke it.
     {
         let tab_view = tab_view.clone();
-        let settings = settings.clone();
-        let status_bar = ctx.status_bar.clone();
-        let icon_cache = sidebar_state.icon_cache.clone();
-        let toast_overlay = toast_overlay.clone();
-        let lsp_tx = lsp_request_tx.clone();
-        let doc_versions = ctx.lsp.doc_versions.clone();
-        let lsp_request_seq = ctx.lsp.request_seq.clone();
-        let latest_completion_req = ctx.lsp.latest_completion_req.clone();
-        let latest_hover_req = ctx.lsp.latest_hover_req.clone();
-        let latest_definition_req = ctx.lsp.latest_definition_req.clone();
-        let definition_monaco_ids = ctx.lsp.definition_monaco_ids.clone();
-        let latest_formatting_req = ctx.lsp.latest_formatting_req.clone();
-        let latest_signature_help_req = ctx.lsp.latest_signature_help_req.clone();
-        let latest_references_req = ctx.lsp.latest_references_req.clone();
-        let latest_code_action_req = ctx.lsp.latest_code_action_req.clone();
-        let latest_rename_req = ctx.lsp.latest_rename_req.clone();
         let sidebar_state_for_new = sidebar_state.clone();
-        let open_editor_paths = ctx.open_editor_paths.clone();
-        let editor_tab_pages = ctx.editor_tab_pages.clone();
-        let close_return_targets = ctx.tab_close_return_targets.clone();
-        let window_for_new = window.clone();
+        let editor_ctx = ctx.clone();
         let new_file_action = gtk4::gio::SimpleAction::new("new-file", None);
         new_file_action.connect_activate(move |_, _| {
-                let cwd = get_active_cwd(&tab_view)
-                    .or_else(|| {
-                        let p = sidebar_state_for_new.current_path.borrow().clone();
-                        if p.is_empty() { None } else { Some(p) }
-                    });
-                let theme = crate::theme::get_theme(&settings.borrow().color_scheme);
-                let (editor_widget, _handle) = editor::create_untitled_editor(
-                    &settings.borrow(),
-                    theme,
-                    cwd.clone(),
-                    {
-                        let lsp_tx = lsp_tx.clone();
-                        let doc_versions = doc_versions.clone();
-                        let status_bar = status_bar.clone();
-                        let tab_view = tab_view.clone();
-                        let settings = settings.clone();
-                        let lsp_request_seq = lsp_request_seq.clone();
-                        let latest_completion_req = latest_completion_req.clone();
-                        let latest_hover_req = latest_hover_req.clone();
-                        let latest_definition_req = latest_definition_req.clone();
-                        let definition_monaco_ids = definition_monaco_ids.clone();
-                        let latest_formatting_req = latest_formatting_req.clone();
-                        let latest_signature_help_req = latest_signature_help_req.clone();
-                        let latest_references_req = latest_references_req.clone();
-                        let latest_code_action_req = latest_code_action_req.clone();
-                        let latest_rename_req = latest_rename_req.clone();
-                        let sidebar_state = sidebar_state_for_new.clone();
-                        let toast_overlay = toast_overlay.clone();
-                        let editor_tab_pages = editor_tab_pages.clone();
-                        let open_editor_paths = open_editor_paths.clone();
-                        let window = window_for_new.clone();
-                        let icon_cache = icon_cache.clone();
-                        move |handle, event| {
-                            let path = handle.file_path.borrow().clone();
-                            let is_untitled = editor::is_untitled_path(&path);
-                            match event {
-                                impulse_editor::protocol::EditorEvent::Ready => {}
-                                impulse_editor::protocol::EditorEvent::FileOpened => {
-                                    handle.flush_pending_position();
-                                    if !is_untitled {
-                                        let uri = ensure_file_uri(&path);
-                                        let language_id = language_from_uri(&uri);
-                                        let content = handle.get_content();
-                                        let mut versions = doc_versions.borrow_mut();
-                                        let version = versions.entry(path.clone()).or_insert(0);
-                                        *version += 1;
-                                        if let Err(e) = lsp_tx.try_send(LspRequest::DidOpen {
-                                            uri, language_id, version: *version, text: content,
-                                        }) {
-                                            log::warn!("LSP request channel full: {}", e);
-                                        }
-                                        send_diff_decorations(&path);
-                                    }
-                                }
-                                impulse_editor::protocol::EditorEvent::ContentChanged {
-                                    content,
-                                    changes,
-                                    ..
-                                } => {
-                                    if let Some(page) = editor_tab_pages.borrow().get(&path) {
-                                        if is_untitled {
-                                            if handle.is_modified.get() {
-                                                page.set_title("Untitled *");
-                                            } else {
-                                                page.set_title("Untitled");
-                                            }
-                                        } else {
-                                            let filename = std::path::Path::new(&path)
-                                                .file_name()
-                                                .and_then(|n| n.to_str())
-                                                .unwrap_or(&path);
-                                            if handle.is_modified.get() {
-                                                page.set_title(&format!("{} *", filename));
-                                            } else {
-                                                page.set_title(filename);
-                                            }
-                                        }
-                                    }
-                                    if !is_untitled {
-                                        let uri = ensure_file_uri(&path);
-                                        let mut versions = doc_versions.borrow_mut();
-                                        let version = versions.entry(path.clone()).or_insert(0);
-                                        *version += 1;
-                                        let changes = lsp_content_changes(&changes);
-                                        if let Err(e) = lsp_tx.try_send(LspRequest::DidChange {
-                                            uri,
-                                            version: *version,
-                                            text: content,
-                                            changes,
-                                        }) {
-                                            log::warn!("LSP request channel full: {}", e);
-                                        }
-                                    }
-                                }
-                                impulse_editor::protocol::EditorEvent::CursorMoved { line, column } => {
-                                    status_bar.borrow().update_cursor_position(line as i32 - 1, column as i32 - 1);
-                                    if !is_untitled {
-                                        // Git blame (same debounce pattern as sidebar_signals.rs)
-                                        // Omitted for untitled files — no file to blame.
-                                    }
-                                }
-                                impulse_editor::protocol::EditorEvent::SaveRequested => {
-                                    if is_untitled {
-                                        if let Some(rc_handle) = editor::get_handle(&path) {
-                                            show_save_dialog_for_untitled(
-                                                &window, &rc_handle, &tab_view,
-                                                &editor_tab_pages, &open_editor_paths,
-                                                &lsp_tx, &doc_versions,
-                                                &sidebar_state, &toast_overlay,
-                                                &icon_cache, &settings,
-                                            );
-                                        }
-                                    } else {
-                                        let content = handle.get_content();
-                                        if let Err(e) = super::atomic_write(&path, &content) {
-                                            log::error!("Failed to save {}: {}", path, e);
-                                            let toast = adw::Toast::new(&format!("Error saving: {}", e));
-                                            toast.set_timeout(4);
-                                            toast_overlay.add_toast(toast);
-                                        } else {
-                                            handle.is_modified.set(false);
-                                            if let Some(page) = editor_tab_pages.borrow().get(&path) {
-                                                let filename = std::path::Path::new(&path)
-                                                    .file_name()
-                                                    .and_then(|n| n.to_str())
-                                                    .unwrap_or(&path);
-                                                page.set_title(filename);
-                                            }
-                                            let uri = ensure_file_uri(&path);
-                                            if let Err(e) = lsp_tx.try_send(LspRequest::DidSave { uri }) {
-                                                log::warn!("LSP request channel full: {}", e);
-                                            }
-                                            send_diff_decorations(&path);
-                                            sidebar_state.refresh_git_only();
-                                            let commands = settings.borrow().commands_on_save.clone();
-                                            super::spawn_commands_on_save(path.clone(), commands);
-                                        }
-                                    }
-                                }
-                                impulse_editor::protocol::EditorEvent::FocusChanged { focused } => {
-                                    if !is_untitled && !focused && settings.borrow().auto_save && handle.is_modified.get() {
-                                        let content = handle.get_content();
-                                        if let Err(e) = super::atomic_write(&path, &content) {
-                                            log::error!("Auto-save failed for {}: {}", path, e);
-                                        } else {
-                                            handle.is_modified.set(false);
-                                            if let Some(page) = editor_tab_pages.borrow().get(&path) {
-                                                let filename = std::path::Path::new(&path)
-                                                    .file_name()
-                                                    .and_then(|n| n.to_str())
-                                                    .unwrap_or(&path);
-                                                page.set_title(filename);
-                                            }
-                                            let uri = ensure_file_uri(&path);
-                                            if let Err(e) = lsp_tx.try_send(LspRequest::DidSave { uri }) {
-                                                log::warn!("LSP request channel full: {}", e);
-                                            }
-                                            send_diff_decorations(&path);
-                                            sidebar_state.refresh_git_only();
-                                        }
-                                    }
-                                }
-                                impulse_editor::protocol::EditorEvent::CompletionRequested { request_id: _, line, character } => {
-                                    if !is_untitled {
-                                        dispatch_lsp_request(&path, &lsp_request_seq, &doc_versions, &latest_completion_req, &lsp_tx,
-                                            |seq, uri, version| LspRequest::Completion { request_id: seq, uri, version, line, character });
-                                    }
-                                }
-                                impulse_editor::protocol::EditorEvent::HoverRequested { request_id: _, line, character } => {
-                                    if !is_untitled {
-                                        dispatch_lsp_request(&path, &lsp_request_seq, &doc_versions, &latest_hover_req, &lsp_tx,
-                                            |seq, uri, version| LspRequest::Hover { request_id: seq, uri, version, line, character });
-                                    }
-                                }
-                                impulse_editor::protocol::EditorEvent::DefinitionRequested { request_id: monaco_id, line, character } => {
-                                    if !is_untitled {
-                                        let seq = dispatch_lsp_request(&path, &lsp_request_seq, &doc_versions, &latest_definition_req, &lsp_tx,
-                                            |seq, uri, version| LspRequest::Definition { request_id: seq, uri, version, line, character });
-                                        definition_monaco_ids.borrow_mut().insert(seq, monaco_id);
-                                    }
-                                }
-                                impulse_editor::protocol::EditorEvent::OpenFileRequested { uri, line, character } => {
-                                    if !uri.starts_with("file://") && uri.contains("://") {
-                                        log::warn!("Blocked opening non-file URI: {}", uri);
-                                    } else {
-                                        let file_path = uri_to_file_path(&uri);
-                                        if let Some(cb) = sidebar_state.on_file_activated.borrow().as_ref() {
-                                            cb(&file_path);
-                                        }
-                                        if let Some(page) = editor_tab_pages.borrow().get(&file_path) {
-                                            editor::go_to_position(&page.child(), line + 1, character + 1);
-                                            tab_view.set_selected_page(page);
-                                        }
-                                    }
-                                }
-                                impulse_editor::protocol::EditorEvent::FormattingRequested { request_id: _, tab_size, insert_spaces } => {
-                                    if !is_untitled {
-                                        dispatch_lsp_request(&path, &lsp_request_seq, &doc_versions, &latest_formatting_req, &lsp_tx,
-                                            |seq, uri, version| LspRequest::Formatting { request_id: seq, uri, version, tab_size, insert_spaces });
-                                    }
-                                }
-                                impulse_editor::protocol::EditorEvent::SignatureHelpRequested { request_id: _, line, character } => {
-                                    if !is_untitled {
-                                        dispatch_lsp_request(&path, &lsp_request_seq, &doc_versions, &latest_signature_help_req, &lsp_tx,
-                                            |seq, uri, version| LspRequest::SignatureHelp { request_id: seq, uri, version, line, character });
-                                    }
-                                }
-                                impulse_editor::protocol::EditorEvent::ReferencesRequested { request_id: _, line, character } => {
-                                    if !is_untitled {
-                                        dispatch_lsp_request(&path, &lsp_request_seq, &doc_versions, &latest_references_req, &lsp_tx,
-                                            |seq, uri, version| LspRequest::References { request_id: seq, uri, version, line, character });
-                                    }
-                                }
-                                impulse_editor::protocol::EditorEvent::CodeActionRequested { request_id: _, start_line, start_column, end_line, end_column, diagnostics } => {
-                                    if !is_untitled {
-                                        let diag_infos: Vec<crate::lsp_completion::DiagnosticInfo> = diagnostics.into_iter().map(|d| {
-                                            crate::lsp_completion::DiagnosticInfo {
-                                                line: d.start_line, character: d.start_column,
-                                                end_line: d.end_line, end_character: d.end_column,
-                                                severity: match d.severity {
-                                                    8 => crate::lsp_completion::DiagnosticSeverity::Error,
-                                                    4 => crate::lsp_completion::DiagnosticSeverity::Warning,
-                                                    2 => crate::lsp_completion::DiagnosticSeverity::Information,
-                                                    _ => crate::lsp_completion::DiagnosticSeverity::Hint,
-                                                },
-                                                message: d.message,
-                                            }
-                                        }).collect();
-                                        dispatch_lsp_request(&path, &lsp_request_seq, &doc_versions, &latest_code_action_req, &lsp_tx,
-                                            |seq, uri, version| LspRequest::CodeAction {
-                                                request_id: seq, uri, version, start_line, start_column, end_line, end_column, diagnostics: diag_infos,
-                                            });
-                                    }
-                                }
-                                impulse_editor::protocol::EditorEvent::RenameRequested { request_id: _, line, character, new_name } => {
-                                    if !is_untitled {
-                                        dispatch_lsp_request(&path, &lsp_request_seq, &doc_versions, &latest_rename_req, &lsp_tx,
-                                            |seq, uri, version| LspRequest::Rename { request_id: seq, uri, version, line, character, new_name });
-                                    }
-                                }
-                                impulse_editor::protocol::EditorEvent::PrepareRenameRequested { request_id: _, line, character } => {
-                                    if !is_untitled {
-                                        dispatch_lsp_request(&path, &lsp_request_seq, &doc_versions, &latest_rename_req, &lsp_tx,
-                                            |seq, uri, version| LspRequest::PrepareRename { request_id: seq, uri, version, line, character });
-                                    }
-                                }
-                            }
-                        }
-                    },
-                );
-                let close_return_target = tab_management::selected_page_child_key(&tab_view);
-                let page = tab_management::insert_after_selected(&tab_view, &editor_widget);
-                page.set_title("Untitled");
-                if let Some(texture) = icon_cache.borrow().get_toolbar_icon("console") {
-                    page.set_icon(Some(texture));
+            let cwd = get_active_cwd(&tab_view).or_else(|| {
+                let p = sidebar_state_for_new.current_path.borrow().clone();
+                if p.is_empty() {
+                    None
+                } else {
+                    Some(p)
                 }
-                tab_management::set_close_return_target(
-                    &close_return_targets,
-                    &page,
-                    close_return_target,
-                );
-                // Track the sentinel path in the dedup/page maps so Ctrl+S can find the page.
-                let sentinel = editor_widget.widget_name().to_string();
-                editor_tab_pages.borrow_mut().insert(sentinel.clone(), page.clone());
-                tab_view.set_selected_page(&page);
+            });
+            open_untitled_tab(&editor_ctx, cwd, "", "Untitled");
         });
         window.add_action(&new_file_action);
         let window_for_shortcut = window.clone();
@@ -616,16 +355,20 @@ pub(super) fn setup_shortcut_controller(
     // Ctrl+Shift+P: Command palette
     {
         let window_ref = window.clone();
+        let tab_view = tab_view.clone();
         let commands = commands.to_vec();
         let command_recents = command_recents.clone();
         let sidebar_state = sidebar_state.clone();
+        let settings = settings.clone();
         add_shortcut(
             &shortcut_controller,
             &keybindings::get_accel("command_palette", &kb_overrides),
             move || {
+                let context = tab_management::command_context(&tab_view, &settings.borrow());
                 super::show_command_palette(
                     &window_ref,
                     &commands,
+                    &context,
                     &command_recents,
                     &sidebar_state,
                 );
@@ -724,60 +467,19 @@ pub(super) fn setup_shortcut_controller(
     // on the window (see setup_capture_phase_keys), which runs before terminal's
     // internal handler.
 
-    // Ctrl+Equal / Ctrl+plus: Increase font size
-    {
-        let tab_view = tab_view.clone();
-        let font_size = term_ctx.font_size.clone();
-        let settings = settings.clone();
-        add_shortcut(
-            &shortcut_controller,
-            &keybindings::get_accel("font_increase", &kb_overrides),
-            move || {
-                let new_size = font_size.get() + 1;
-                if (6..=72).contains(&new_size) {
-                    font_size.set(new_size);
-                    let family = settings.borrow().terminal_font_family.clone();
-                    super::apply_font_size_to_all_terminals(&tab_view, new_size, &family);
-                }
-            },
-        );
-    }
-
-    // Ctrl+minus: Decrease font size
-    {
-        let tab_view = tab_view.clone();
-        let font_size = term_ctx.font_size.clone();
-        let settings = settings.clone();
-        add_shortcut(
-            &shortcut_controller,
-            &keybindings::get_accel("font_decrease", &kb_overrides),
-            move || {
-                let new_size = font_size.get() - 1;
-                if (6..=72).contains(&new_size) {
-                    font_size.set(new_size);
-                    let family = settings.borrow().terminal_font_family.clone();
-                    super::apply_font_size_to_all_terminals(&tab_view, new_size, &family);
-                }
-            },
-        );
-    }
-
-    // Ctrl+0: Reset font size to default (from settings)
-    {
+    // Ctrl+Equal / Ctrl+minus / Ctrl+0: Zoom the focused pane in, out or back
+    for (id, change) in [
+        ("font_increase", super::zoom::ZoomChange::In),
+        ("font_decrease", super::zoom::ZoomChange::Out),
+        ("font_reset", super::zoom::ZoomChange::Reset),
+    ] {
         let tab_view = tab_view.clone();
-        let font_size = term_ctx.font_size.clone();
         let settings = settings.clone();
+        let status_bar = ctx.status_bar.clone();
         add_shortcut(
             &shortcut_controller,
-            &keybindings::get_accel("font_reset", &kb_overrides),
-            move || {
-                let s = settings.borrow();
-                let default_size = s.terminal_font_size;
-                let family = s.terminal_font_family.clone();
-                drop(s);
-                font_size.set(default_size);
-                super::apply_font_size_to_all_terminals(&tab_view, default_size, &family);
-            },
+            &keybindings::get_accel(id, &kb_overrides),
+            move || super::zoom::zoom_selected(&tab_view, &settings, &status_bar, change),
         );
     }
 
@@ -865,38 +567,16 @@ pub(super) fn setup_shortcut_controller(
                             }
                             return;
                         }
-                        if let Some(text) = editor::get_editor_text(&child) {
-                            match super::atomic_write(&path, &text) {
-                                Ok(()) => {
-                                    editor::set_unmodified(&child);
-                                    // Revert tab title
-                                    let filename = std::path::Path::new(&path)
-                                        .file_name()
-                                        .and_then(|n| n.to_str())
-                                        .unwrap_or(&path);
-                                    page.set_title(filename);
-                                    // LSP: send didSave
-                                    if let Err(e) = lsp_tx.try_send(LspRequest::DidSave {
-                                        uri: ensure_file_uri(&path),
-                                    }) {
-                                        log::warn!(
-                                            "LSP request channel full, dropping request: {}",
-                                            e
-                                        );
-                                    }
-                                    let toast = adw::Toast::new(&format!("Saved {}", filename));
-                                    toast.set_timeout(2);
-                                    toast_overlay.add_toast(toast);
-                                    // Run commands-on-save in a background thread
-                                    let commands = settings.borrow().commands_on_save.clone();
-                                    super::spawn_commands_on_save(path.clone(), commands);
-                                }
-                                Err(e) => {
-                                    let toast = adw::Toast::new(&format!("Error saving: {}", e));
-                                    toast.set_timeout(4);
-                                    toast_overlay.add_toast(toast);
-                                }
-                            }
+                        if super::save_editor_page(
+                            &page,
+                            &lsp_tx,
+                            &settings.borrow(),
+                            &toast_overlay,
+                            &editor_tab_pages_save,
+                        ) {
+                            let toast = adw::Toast::new(&format!("Saved {}", page.title()));
+                            toast.set_timeout(2);
+                            toast_overlay.add_toast(toast);
                         }
                     }
                 }
@@ -904,6 +584,28 @@ pub(super) fn setup_shortcut_controller(
         );
     }
 
+    // Save All: every modified editor (no default shortcut on Linux)
+    {
+        let tab_view = tab_view.clone();
+        let toast_overlay = toast_overlay.clone();
+        let lsp_tx = lsp_request_tx.clone();
+        let settings = settings.clone();
+        let editor_tab_pages = ctx.editor_tab_pages.clone();
+        add_shortcut(
+            &shortcut_controller,
+            &keybindings::get_accel("save_all", &kb_overrides),
+            move || {
+                super::save_all_editors(
+                    &tab_view,
+                    &lsp_tx,
+                    &settings.borrow(),
+                    &toast_overlay,
+                    &editor_tab_pages,
+                );
+            },
+        );
+    }
+
     // Ctrl+G: Go to line (editor tabs only)
     {
         let tab_view = tab_view.clone();
@@ -935,7 +637,7 @@ pub(super) fn setup_shortcut_controller(
                     let child = page.child();
                     if editor::is_editor(&child) {
                         let s = settings.borrow();
-                        let theme = crate::theme::get_theme(&s.color_scheme);
+                        let theme = crate::theme::active_theme(&s);
                         if let Some(is_previewing) =
                             editor::toggle_preview(child.upcast_ref(), theme)
                         {
@@ -979,6 +681,17 @@ pub(super) fn setup_shortcut_controller(
         );
     }
 
+    // Ctrl+Alt+Z: Toggle Zen mode
+    {
+        let layout = ctx.layout.clone();
+        let settings = settings.clone();
+        add_shortcut(
+            &shortcut_controller,
+            &keybindings::get_accel("toggle_zen_mode", &kb_overrides),
+            move || layout.toggle_zen(settings.borrow().zen_mode_max_width),
+        );
+    }
+
     // Register custom keybindings from settings
     {
         let custom_keybindings = settings.borrow().custom_keybindings.clone();
@@ -1000,7 +713,7 @@ pub(super) fn setup_shortcut_controller(
                 // Open a new terminal tab running the command in the active CWD
                 let cwd = get_active_cwd(&tab_view);
 
-                let theme = crate::theme::get_theme(&settings.borrow().color_scheme);
+                let theme = crate::theme::active_theme(&settings.borrow());
                 let term = terminal::create_terminal(
                     &settings.borrow(),
                     theme,
@@ -1021,11 +734,584 @@ pub(super) fn setup_shortcut_controller(
         }
     }
 
-    window.add_controller(shortcut_controller);
+    window.add_controller(shortcut_controller.controller);
+}
+
+/// Open an untitled editor tab holding `content` after the selected tab
+/// and select it.
+pub(super) fn open_untitled_tab(
+    ctx: &super::context::WindowContext,
+    cwd: Option<String>,
+    content: &str,
+    title: &str,
+) -> Rc<MonacoEditorHandle> {
+    let tab_view = &ctx.tab_view;
+    let (editor_widget, handle) = {
+        let settings = ctx.settings.borrow();
+        editor::create_untitled_editor(
+            &settings,
+            crate::theme::active_theme(&settings),
+            cwd,
+            content,
+            untitled_editor_events(ctx),
+        )
+    };
+    let close_return_target = tab_management::selected_page_child_key(tab_view);
+    let page = tab_management::insert_after_selected(tab_view, &editor_widget);
+    page.set_title(title);
+    if let Some(texture) = ctx
+        .sidebar_state
+        .icon_cache
+        .borrow()
+        .get_toolbar_icon("console")
+    {
+        page.set_icon(Some(texture));
+    }
+    tab_management::set_close_return_target(
+        &ctx.tab_close_return_targets,
+        &page,
+        close_return_target,
+    );
+    // Track the sentinel path in the dedup/page maps so Ctrl+S can find the page.
+    let sentinel = editor_widget.widget_name().to_string();
+    ctx.editor_tab_pages
+        .borrow_mut()
+        .insert(sentinel, page.clone());
+    tab_view.set_selected_page(&page);
+    handle
 }
 
 /// Show a save-as dialog for an untitled editor, then transition it to a
 /// file-backed editor on successful save.
+/// Editor event handling for an untitled tab in the window of `ctx`. The
+/// path is read from the handle on every event, since saving the tab gives
+/// it a real one.
+pub(super) fn untitled_editor_events(
+    ctx: &super::context::WindowContext,
+) -> impl Fn(&MonacoEditorHandle, EditorEvent) + 'static {
+    let lsp_tx = ctx.lsp.request_tx.clone();
+    let doc_versions = ctx.lsp.doc_versions.clone();
+    let status_bar = ctx.status_bar.clone();
+    let tab_view = ctx.tab_view.clone();
+    let settings = ctx.settings.clone();
+    let lsp_request_seq = ctx.lsp.request_seq.clone();
+    let latest_completion_req = ctx.lsp.latest_completion_req.clone();
+    let latest_hover_req = ctx.lsp.latest_hover_req.clone();
+    let latest_definition_req = ctx.lsp.latest_definition_req.clone();
+    let definition_monaco_ids = ctx.lsp.definition_monaco_ids.clone();
+    let latest_formatting_req = ctx.lsp.latest_formatting_req.clone();
+    let latest_signature_help_req = ctx.lsp.latest_signature_help_req.clone();
+    let latest_references_req = ctx.lsp.latest_references_req.clone();
+    let latest_code_action_req = ctx.lsp.latest_code_action_req.clone();
+    let latest_rename_req = ctx.lsp.latest_rename_req.clone();
+    let latest_peek_req = ctx.lsp.latest_peek_req.clone();
+    let peek_monaco_ids = ctx.lsp.peek_monaco_ids.clone();
+    let rename_monaco_ids = ctx.lsp.rename_monaco_ids.clone();
+    let sidebar_state = ctx.sidebar_state.clone();
+    let toast_overlay = ctx.toast_overlay.clone();
+    let editor_tab_pages = ctx.editor_tab_pages.clone();
+    let open_editor_paths = ctx.open_editor_paths.clone();
+    let window = ctx.window.clone();
+    let icon_cache = ctx.sidebar_state.icon_cache.clone();
+    move |handle, event| {
+        let path = handle.file_path.borrow().clone();
+        let is_untitled = editor::is_untitled_path(&path);
+        match event {
+            // Answered inside the editor handle.
+            impulse_editor::protocol::EditorEvent::Ready { .. }
+            | impulse_editor::protocol::EditorEvent::Response { .. } => {}
+            impulse_editor::protocol::EditorEvent::FileOpened => {
+                handle.flush_pending_position();
+                if !is_untitled {
+                    let uri = ensure_file_uri(&path);
+                    let language_id = language_from_uri(&uri);
+                    let content = handle.get_content();
+                    let mut versions = doc_versions.borrow_mut();
+                    let version = versions.entry(path.clone()).or_insert(0);
+                    *version += 1;
+                    if let Err(e) = lsp_tx.try_send(LspRequest::DidOpen {
+                        uri,
+                        language_id,
+                        version: *version,
+                        text: content,
+                    }) {
+                        log::warn!("LSP request channel full: {}", e);
+                    }
+                    send_diff_decorations(&path);
+                }
+            }
+            impulse_editor::protocol::EditorEvent::ContentChanged {
+                content, changes, ..
+            } => {
+                if let Some(page) = editor_tab_pages.borrow().get(&path) {
+                    if is_untitled {
+                        // Keep the tab's own name, like a scrollback's.
+                        let title = page.title();
+                        let name = title.trim_end_matches(" *");
+                        if handle.is_modified.get() {
+                            page.set_title(&format!("{name} *"));
+                        } else {
+                            page.set_title(name);
+                        }
+                    } else {
+                        let filename = std::path::Path::new(&path)
+                            .file_name()
+                            .and_then(|n| n.to_str())
+                            .unwrap_or(&path);
+                        if handle.is_modified.get() {
+                            page.set_title(&format!("{} *", filename));
+                        } else {
+                            page.set_title(filename);
+                        }
+                    }
+                }
+                if !is_untitled {
+                    let uri = ensure_file_uri(&path);
+                    let mut versions = doc_versions.borrow_mut();
+                    let version = versions.entry(path.clone()).or_insert(0);
+                    *version += 1;
+                    let changes = lsp_content_changes(&changes);
+                    if let Err(e) = lsp_tx.try_send(LspRequest::DidChange {
+                        uri,
+                        version: *version,
+                        text: content,
+                        changes,
+                    }) {
+                        log::warn!("LSP request channel full: {}", e);
+                    }
+                }
+            }
+            impulse_editor::protocol::EditorEvent::CursorMoved { line, column } => {
+                status_bar
+                    .borrow()
+                    .update_cursor_position(line as i32 - 1, column as i32 - 1);
+                if !is_untitled {
+                    // Git blame (same debounce pattern as sidebar_signals.rs)
+                    // Omitted for untitled files — no file to blame.
+                }
+            }
+            impulse_editor::protocol::EditorEvent::SaveRequested => {
+                if is_untitled {
+                    if let Some(rc_handle) = editor::get_handle(&path) {
+                        show_save_dialog_for_untitled(
+                            &window,
+                            &rc_handle,
+                            &tab_view,
+                            &editor_tab_pages,
+                            &open_editor_paths,
+                            &lsp_tx,
+                            &doc_versions,
+                            &sidebar_state,
+                            &toast_overlay,
+                            &icon_cache,
+                            &settings,
+                        );
+                    }
+                } else {
+                    let content = super::apply_will_save_edits(
+                        &lsp_tx,
+                        handle,
+                        &path,
+                        handle.get_content(),
+                        lsp_types::TextDocumentSaveReason::MANUAL,
+                        &settings.borrow(),
+                    );
+                    if let Err(e) = super::atomic_write(&path, &content) {
+                        log::error!("Failed to save {}: {}", path, e);
+                        super::report_save_error(
+                            &toast_overlay,
+                            &editor_tab_pages,
+                            &path,
+                            content,
+                            &e,
+                        );
+                    } else {
+                        handle.is_modified.set(false);
+                        if let Some(page) = editor_tab_pages.borrow().get(&path) {
+                            let filename = std::path::Path::new(&path)
+                                .file_name()
+                                .and_then(|n| n.to_str())
+                                .unwrap_or(&path);
+                            page.set_title(filename);
+                        }
+                        let uri = ensure_file_uri(&path);
+                        if let Err(e) = lsp_tx.try_send(LspRequest::DidSave { uri }) {
+                            log::warn!("LSP request channel full: {}", e);
+                        }
+                        send_diff_decorations(&path);
+                        sidebar_state.refresh_git_only();
+                        let commands = settings.borrow().commands_on_save.clone();
+                        super::spawn_commands_on_save(path.clone(), commands);
+                    }
+                }
+            }
+            impulse_editor::protocol::EditorEvent::FocusChanged { focused } => {
+                if !is_untitled
+                    && !focused
+                    && settings.borrow().auto_save
+                    && handle.is_modified.get()
+                {
+                    let content = super::apply_will_save_edits(
+                        &lsp_tx,
+                        handle,
+                        &path,
+                        handle.get_content(),
+                        lsp_types::TextDocumentSaveReason::FOCUS_OUT,
+                        &settings.borrow(),
+                    );
+                    if let Err(e) = super::atomic_write(&path, &content) {
+                        log::error!("Auto-save failed for {}: {}", path, e);
+                    } else {
+                        handle.is_modified.set(false);
+                        if let Some(page) = editor_tab_pages.borrow().get(&path) {
+                            let filename = std::path::Path::new(&path)
+                                .file_name()
+                                .and_then(|n| n.to_str())
+                                .unwrap_or(&path);
+                            page.set_title(filename);
+                        }
+                        let uri = ensure_file_uri(&path);
+                        if let Err(e) = lsp_tx.try_send(LspRequest::DidSave { uri }) {
+                            log::warn!("LSP request channel full: {}", e);
+                        }
+                        send_diff_decorations(&path);
+                        sidebar_state.refresh_git_only();
+                    }
+                }
+            }
+            impulse_editor::protocol::EditorEvent::CompletionRequested {
+                request_id: _,
+                line,
+                character,
+            } => {
+                if !is_untitled {
+                    dispatch_lsp_request(
+                        &path,
+                        &lsp_request_seq,
+                        &doc_versions,
+                        &latest_completion_req,
+                        &lsp_tx,
+                        |seq, uri, version| LspRequest::Completion {
+                            request_id: seq,
+                            uri,
+                            version,
+                            line,
+                            character,
+                        },
+                    );
+                }
+            }
+            impulse_editor::protocol::EditorEvent::HoverRequested {
+                request_id: _,
+                line,
+                character,
+            } => {
+                if !is_untitled {
+                    dispatch_lsp_request(
+                        &path,
+                        &lsp_request_seq,
+                        &doc_versions,
+                        &latest_hover_req,
+                        &lsp_tx,
+                        |seq, uri, version| LspRequest::Hover {
+                            request_id: seq,
+                            uri,
+                            version,
+                            line,
+                            character,
+                        },
+                    );
+                }
+            }
+            impulse_editor::protocol::EditorEvent::DefinitionRequested {
+                request_id: monaco_id,
+                line,
+                character,
+            } => {
+                if !is_untitled {
+                    let seq = dispatch_lsp_request(
+                        &path,
+                        &lsp_request_seq,
+                        &doc_versions,
+                        &latest_definition_req,
+                        &lsp_tx,
+                        |seq, uri, version| LspRequest::Definition {
+                            request_id: seq,
+                            uri,
+                            version,
+                            line,
+                            character,
+                        },
+                    );
+                    definition_monaco_ids.borrow_mut().insert(seq, monaco_id);
+                }
+            }
+            impulse_editor::protocol::EditorEvent::OpenFileRequested {
+                uri,
+                line,
+                character,
+            } => {
+                if !uri.starts_with("file://") && uri.contains("://") {
+                    log::warn!("Blocked opening non-file URI: {}", uri);
+                } else {
+                    let file_path = uri_to_file_path(&uri);
+                    if let Some(cb) = sidebar_state.on_file_activated.borrow().as_ref() {
+                        cb(&file_path);
+                    }
+                    if let Some(page) = editor_tab_pages.borrow().get(&file_path) {
+                        editor::go_to_position(&page.child(), line + 1, character + 1);
+                        tab_view.set_selected_page(page);
+                    }
+                }
+            }
+            impulse_editor::protocol::EditorEvent::FormattingRequested {
+                request_id: _,
+                tab_size,
+                insert_spaces,
+            } => {
+                if !is_untitled {
+                    let (formatter, text) =
+                        formatter_for_request(&settings.borrow(), &path, || handle.get_content());
+                    dispatch_lsp_request(
+                        &path,
+                        &lsp_request_seq,
+                        &doc_versions,
+                        &latest_formatting_req,
+                        &lsp_tx,
+                        |seq, uri, version| LspRequest::Formatting {
+                            request_id: seq,
+                            uri,
+                            version,
+                            tab_size,
+                            insert_spaces,
+                            formatter,
+                            text,
+                        },
+                    );
+                }
+            }
+            impulse_editor::protocol::EditorEvent::OnTypeFormattingRequested {
+                request_id: _,
+                line,
+                character,
+                ch,
+                tab_size,
+                insert_spaces,
+            } => {
+                if !is_untitled {
+                    dispatch_lsp_request(
+                        &path,
+                        &lsp_request_seq,
+                        &doc_versions,
+                        &latest_formatting_req,
+                        &lsp_tx,
+                        |seq, uri, version| LspRequest::OnTypeFormatting {
+                            request_id: seq,
+                            uri,
+                            version,
+                            line,
+                            character,
+                            ch,
+                            tab_size,
+                            insert_spaces,
+                        },
+                    );
+                }
+            }
+            impulse_editor::protocol::EditorEvent::SignatureHelpRequested {
+                request_id: _,
+                line,
+                character,
+            } => {
+                if !is_untitled {
+                    dispatch_lsp_request(
+                        &path,
+                        &lsp_request_seq,
+                        &doc_versions,
+                        &latest_signature_help_req,
+                        &lsp_tx,
+                        |seq, uri, version| LspRequest::SignatureHelp {
+                            request_id: seq,
+                            uri,
+                            version,
+                            line,
+                            character,
+                        },
+                    );
+                }
+            }
+            impulse_editor::protocol::EditorEvent::ReferencesRequested {
+                request_id: _,
+                line,
+                character,
+            } => {
+                if !is_untitled {
+                    dispatch_lsp_request(
+                        &path,
+                        &lsp_request_seq,
+                        &doc_versions,
+                        &latest_references_req,
+                        &lsp_tx,
+                        |seq, uri, version| LspRequest::References {
+                            request_id: seq,
+                            uri,
+                            version,
+                            line,
+                            character,
+                        },
+                    );
+                }
+            }
+            impulse_editor::protocol::EditorEvent::CodeActionRequested {
+                request_id: _,
+                start_line,
+                start_column,
+                end_line,
+                end_column,
+                diagnostics,
+                only,
+            } => {
+                if !is_untitled {
+                    let diag_infos: Vec<crate::lsp_completion::DiagnosticInfo> = diagnostics
+                        .into_iter()
+                        .map(|d| crate::lsp_completion::DiagnosticInfo {
+                            line: d.start_line,
+                            character: d.start_column,
+                            end_line: d.end_line,
+                            end_character: d.end_column,
+                            severity: match d.severity {
+                                8 => crate::lsp_completion::DiagnosticSeverity::Error,
+                                4 => crate::lsp_completion::DiagnosticSeverity::Warning,
+                                2 => crate::lsp_completion::DiagnosticSeverity::Information,
+                                _ => crate::lsp_completion::DiagnosticSeverity::Hint,
+                            },
+                            message: d.message,
+                        })
+                        .collect();
+                    dispatch_lsp_request(
+                        &path,
+                        &lsp_request_seq,
+                        &doc_versions,
+                        &latest_code_action_req,
+                        &lsp_tx,
+                        |seq, uri, version| LspRequest::CodeAction {
+                            request_id: seq,
+                            uri,
+                            version,
+                            start_line,
+                            start_column,
+                            end_line,
+                            end_column,
+                            diagnostics: diag_infos,
+                            only,
+                        },
+                    );
+                }
+            }
+            impulse_editor::protocol::EditorEvent::ExecuteCommand { command, arguments } => {
+                if !is_untitled {
+                    let uri = ensure_file_uri(&path);
+                    if let Err(e) = lsp_tx.try_send(LspRequest::ExecuteCommand {
+                        uri,
+                        command,
+                        arguments,
+                    }) {
+                        log::warn!("LSP request channel full: {}", e);
+                    }
+                }
+            }
+            impulse_editor::protocol::EditorEvent::RenameRequested {
+                request_id: monaco_id,
+                line,
+                character,
+                new_name,
+            } => {
+                if !is_untitled {
+                    let seq = dispatch_lsp_request(
+                        &path,
+                        &lsp_request_seq,
+                        &doc_versions,
+                        &latest_rename_req,
+                        &lsp_tx,
+                        |seq, uri, version| LspRequest::Rename {
+                            request_id: seq,
+                            uri,
+                            version,
+                            line,
+                            character,
+                            new_name,
+                        },
+                    );
+                    rename_monaco_ids.borrow_mut().insert(seq, monaco_id);
+                }
+            }
+            impulse_editor::protocol::EditorEvent::PrepareRenameRequested {
+                request_id: monaco_id,
+                line,
+                character,
+            } => {
+                if !is_untitled {
+                    let seq = dispatch_lsp_request(
+                        &path,
+                        &lsp_request_seq,
+                        &doc_versions,
+                        &latest_rename_req,
+                        &lsp_tx,
+                        |seq, uri, version| LspRequest::PrepareRename {
+                            request_id: seq,
+                            uri,
+                            version,
+                            line,
+                            character,
+                        },
+                    );
+                    rename_monaco_ids.borrow_mut().insert(seq, monaco_id);
+                }
+            }
+            impulse_editor::protocol::EditorEvent::OpenMergeView => {
+                if !is_untitled {
+                    super::open_merge_view(&path, &toast_overlay);
+                }
+            }
+            impulse_editor::protocol::EditorEvent::StageHunkRequested {
+                new_start,
+                new_lines,
+            } => {
+                if !is_untitled {
+                    super::stage_diff_hunk(&path, new_start, new_lines, &toast_overlay);
+                }
+            }
+            impulse_editor::protocol::EditorEvent::PeekRequested {
+                request_id: monaco_id,
+                kind,
+                line,
+                character,
+            } => {
+                if is_untitled {
+                    handle.show_peek(monaco_id, kind, &[]);
+                } else {
+                    let seq = dispatch_lsp_request(
+                        &path,
+                        &lsp_request_seq,
+                        &doc_versions,
+                        &latest_peek_req,
+                        &lsp_tx,
+                        |seq, uri, version| LspRequest::Peek {
+                            request_id: seq,
+                            uri,
+                            version,
+                            line,
+                            character,
+                            kind,
+                        },
+                    );
+                    peek_monaco_ids.borrow_mut().insert(seq, monaco_id);
+                }
+            }
+        }
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 fn show_save_dialog_for_untitled(
     window: &adw::ApplicationWindow,
@@ -1071,9 +1357,7 @@ fn show_save_dialog_for_untitled(
         // Write content to disk
         let content = handle.get_content();
         if let Err(e) = super::atomic_write(&chosen_path, &content) {
-            let toast = adw::Toast::new(&format!("Error saving: {}", e));
-            toast.set_timeout(4);
-            toast_overlay.add_toast(toast);
+            crate::notifications::save_failed(&toast_overlay, &chosen_path, &e.to_string());
             return;
         }
 
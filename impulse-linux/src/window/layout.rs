@@ -0,0 +1,127 @@
+use gtk4::prelude::*;
+use libadwaita as adw;
+
+use std::cell::{Cell, RefCell};
+
+/// Visibility of the window chrome, captured on entering Zen mode so that
+/// leaving it puts everything back the way it was.
+#[derive(Clone, Copy)]
+struct SavedLayout {
+    header: bool,
+    sidebar: bool,
+    tab_bar: bool,
+    status_bar: bool,
+    centered: bool,
+}
+
+/// Toggles for the window chrome: header/tab bar, sidebar, status bar, and
+/// the centered content layout, plus Zen mode which hides all of them.
+///
+/// The tab bar and status bar live in slots whose visibility is owned here,
+/// so the tab-switch and settings handlers can keep toggling the widgets
+/// themselves without undoing a hidden slot.
+pub(crate) struct ChromeLayout {
+    header: adw::HeaderBar,
+    tab_bar_slot: gtk4::Box,
+    status_bar_slot: gtk4::Box,
+    sidebar_btn: gtk4::ToggleButton,
+    content_clamp: adw::Clamp,
+    centered: Cell<bool>,
+    zen: RefCell<Option<SavedLayout>>,
+}
+
+impl ChromeLayout {
+    pub fn new(
+        header: &adw::HeaderBar,
+        tab_bar_slot: &gtk4::Box,
+        status_bar_slot: &gtk4::Box,
+        sidebar_btn: &gtk4::ToggleButton,
+        content_clamp: &adw::Clamp,
+    ) -> Self {
+        let layout = ChromeLayout {
+            header: header.clone(),
+            tab_bar_slot: tab_bar_slot.clone(),
+            status_bar_slot: status_bar_slot.clone(),
+            sidebar_btn: sidebar_btn.clone(),
+            content_clamp: content_clamp.clone(),
+            centered: Cell::new(false),
+            zen: RefCell::new(None),
+        };
+        layout.apply_centered(0);
+        layout
+    }
+
+    /// Enter or leave Zen mode. Entering hides the header, sidebar and status
+    /// bar and centers the content at `max_width`; leaving restores the
+    /// layout from before.
+    pub fn toggle_zen(&self, max_width: i32) {
+        let saved = self.zen.borrow_mut().take();
+        match saved {
+            Some(saved) => {
+                self.header.set_visible(saved.header);
+                self.sidebar_btn.set_active(saved.sidebar);
+                self.tab_bar_slot.set_visible(saved.tab_bar);
+                self.status_bar_slot.set_visible(saved.status_bar);
+                self.centered.set(saved.centered);
+                self.apply_centered(max_width);
+            }
+            None => {
+                *self.zen.borrow_mut() = Some(SavedLayout {
+                    header: self.header.is_visible(),
+                    sidebar: self.sidebar_btn.is_active(),
+                    tab_bar: self.tab_bar_slot.is_visible(),
+                    status_bar: self.status_bar_slot.is_visible(),
+                    centered: self.centered.get(),
+                });
+                self.header.set_visible(false);
+                self.sidebar_btn.set_active(false);
+                self.status_bar_slot.set_visible(false);
+                self.centered.set(true);
+                self.apply_centered(max_width);
+            }
+        }
+    }
+
+    pub fn toggle_tab_bar(&self) {
+        self.tab_bar_slot
+            .set_visible(!self.tab_bar_slot.is_visible());
+        // The tab bar lives in the header, which Zen mode hides.
+        if self.tab_bar_slot.is_visible() {
+            self.header.set_visible(true);
+        }
+    }
+
+    pub fn toggle_status_bar(&self) {
+        self.status_bar_slot
+            .set_visible(!self.status_bar_slot.is_visible());
+    }
+
+    pub fn toggle_centered(&self, max_width: i32) {
+        self.centered.set(!self.centered.get());
+        self.apply_centered(max_width);
+    }
+
+    /// Re-apply the centered width after the setting changed.
+    pub fn set_max_width(&self, max_width: i32) {
+        self.apply_centered(max_width);
+    }
+
+    /// Sidebar visibility to persist: the pre-Zen state while in Zen mode,
+    /// so quitting in Zen mode doesn't keep the sidebar hidden.
+    pub fn sidebar_visible_to_save(&self) -> bool {
+        match *self.zen.borrow() {
+            Some(saved) => saved.sidebar,
+            None => self.sidebar_btn.is_active(),
+        }
+    }
+
+    fn apply_centered(&self, max_width: i32) {
+        let width = if self.centered.get() {
+            max_width
+        } else {
+            i32::MAX
+        };
+        self.content_clamp.set_maximum_size(width);
+        self.content_clamp.set_tightening_threshold(width);
+    }
+}
@@ -1,16 +1,27 @@
+mod agent_access;
 pub(crate) mod context;
+pub(crate) mod control;
 mod dialogs;
 mod keybinding_setup;
+mod layout;
 mod sidebar_signals;
+mod tab_groups;
 mod tab_management;
+mod tab_transfer;
+mod workspace_trust;
+mod zoom;
 
-use dialogs::{show_command_palette, show_go_to_line_dialog, show_quick_open};
+use dialogs::{
+    show_command_palette, show_go_to_line_dialog, show_quick_open, show_workspace_symbols,
+};
 
 use gtk4::gio;
 use gtk4::prelude::*;
 use impulse_core::command_palette::{CommandPaletteItem, CommandPaletteSource, RecentCommandStore};
+use impulse_core::tab_order::{TabGroup, TabSort};
 use libadwaita as adw;
 use libadwaita::prelude::*;
+use zoom::ZoomChange;
 
 use std::cell::{Cell, RefCell};
 use std::collections::{HashMap, HashSet, VecDeque};
@@ -18,7 +29,7 @@ use std::rc::Rc;
 
 use crate::editor;
 use crate::keybindings;
-use crate::lsp_completion::{apply_lsp_content_changes, LspRequest, LspResponse};
+use crate::lsp_completion::{LspRequest, LspResponse};
 use crate::sidebar;
 use crate::status_bar;
 use crate::terminal;
@@ -44,10 +55,42 @@ enum ClosedTab {
 const MAX_CLOSED_TABS: usize = 20;
 
 pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>) {
+    build_window_with_tabs(app, initial_files, true, None);
+}
+
+/// Build an empty window for a tab dragged out of another one and return
+/// the tab view it lands in. Skips the session restore and first tab.
+fn build_window_for_transfer(app: &adw::Application) -> adw::TabView {
+    build_window_with_tabs(app, None, false, None)
+}
+
+/// Open a saved window layout in a new window, in place of the session.
+fn build_window_with_layout(
+    app: &adw::Application,
+    window_layout: impulse_core::session_state::SessionWindow,
+) {
+    build_window_with_tabs(app, None, true, Some(window_layout));
+}
+
+fn build_window_with_tabs(
+    app: &adw::Application,
+    initial_files: Option<Vec<String>>,
+    open_initial_tabs: bool,
+    window_layout: Option<impulse_core::session_state::SessionWindow>,
+) -> adw::TabView {
     // Pre-warm a WebView with Monaco so the first editor tab opens instantly.
-    crate::editor_webview::warm_up_editor();
+    crate::editor_webview::warm_up_editor_in_background();
 
     let settings = Rc::new(RefCell::new(crate::settings::load()));
+    let (sidebar_visible, sidebar_width) = window_layout
+        .as_ref()
+        .and_then(|w| w.sidebar.as_ref())
+        .filter(|sidebar| sidebar.width > 0)
+        .map(|sidebar| (sidebar.visible, sidebar.width))
+        .unwrap_or_else(|| {
+            let s = settings.borrow();
+            (s.sidebar_visible, s.sidebar_width)
+        });
 
     let window = adw::ApplicationWindow::builder()
         .application(app)
@@ -57,9 +100,6 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
         .build();
     window.add_css_class("impulse-window");
 
-    // Shared font size state (user-facing size in points, e.g. 11)
-    let font_size: Rc<Cell<i32>> = Rc::new(Cell::new(settings.borrow().font_size));
-
     // Shared copy-on-select flag checked by terminal selection-changed signal handlers
     let copy_on_select_flag: Rc<Cell<bool>> =
         Rc::new(Cell::new(settings.borrow().terminal_copy_on_select));
@@ -81,6 +121,12 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
     let (lsp_gtk_tx, lsp_gtk_rx) = std::sync::mpsc::channel::<LspResponse>();
     let lsp_gtk_rx = Rc::new(RefCell::new(lsp_gtk_rx));
 
+    // Workspace symbol indexes: the go-to-definition fallback for files no
+    // language server handles, and the source for "Go to Symbol in Workspace".
+    let symbol_indexes = std::sync::Arc::new(std::sync::Mutex::new(
+        impulse_core::symbol_index::SymbolIndexes::new(),
+    ));
+
     // Spawn the tokio runtime in a background thread
     {
         let initial_dir = if !settings.borrow().last_directory.is_empty()
@@ -91,7 +137,20 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
             impulse_core::shell::get_home_directory().unwrap_or_else(|_| "/".to_string())
         };
         let root_uri = ensure_file_uri(&initial_dir);
+        let lsp_debounce = settings.borrow().lsp_debounce;
+        let disabled_lsp_servers = settings.borrow().disabled_lsp_servers.clone();
+        impulse_core::search::set_content_index_enabled(settings.borrow().search_index_enabled);
+        impulse_core::ignore_rules::set_exclude_globs(
+            settings.borrow().files_exclude.clone(),
+            settings.borrow().search_exclude.clone(),
+        );
+        impulse_core::mounts::set_walk_options(
+            settings.borrow().follow_symlinks,
+            settings.borrow().scan_network_mounts,
+        );
+        impulse_core::safe_write::set_save_strategy(&settings.borrow().save_strategy);
         let gtk_tx = lsp_gtk_tx.clone();
+        let symbol_indexes = symbol_indexes.clone();
 
         std::thread::spawn(move || {
             let rt = tokio::runtime::Builder::new_multi_thread()
@@ -107,11 +166,15 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
                 let registry = std::sync::Arc::new(
                     impulse_core::lsp::LspRegistry::new(root_uri, event_tx),
                 );
+                registry.set_request_debounce(lsp_debounce);
+                registry.set_disabled_servers(disabled_lsp_servers).await;
+                impulse_core::startup_profile::mark("lsp registry ready");
 
                 // Task to forward LspEvents to the GTK main loop
                 let gtk_tx_events = gtk_tx.clone();
                 let registry_for_exit = registry.clone();
                 tokio::spawn(async move {
+                    let mut work_progress = impulse_core::lsp::LspProgress::new();
                     while let Some(event) = event_rx.recv().await {
                         let response = match event {
                             impulse_core::lsp::LspEvent::Diagnostics {
@@ -172,11 +235,31 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
                                 server_id,
                             } => {
                                 registry_for_exit.remove_client(&client_key).await;
+                                if !work_progress.is_empty() {
+                                    work_progress.clear_client(&client_key);
+                                    let _ = gtk_tx_events.send(LspResponse::Progress {
+                                        text: work_progress.status_text(),
+                                    });
+                                }
                                 LspResponse::ServerExited {
                                     client_key,
                                     server_id,
                                 }
                             }
+                            impulse_core::lsp::LspEvent::ApplyEdit { label, files, .. } => {
+                                LspResponse::ApplyEdit { label, files }
+                            }
+                            impulse_core::lsp::LspEvent::Progress {
+                                client_key,
+                                server_id,
+                                token,
+                                progress,
+                            } => {
+                                work_progress.update(&client_key, &server_id, &token, &progress);
+                                LspResponse::Progress {
+                                    text: work_progress.status_text(),
+                                }
+                            }
                         };
                         if gtk_tx_events.send(response).is_err() {
                             break;
@@ -216,7 +299,17 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
                             if let Some(text) = text {
                                 *document = text;
                             } else {
-                                apply_lsp_content_changes(document, &changes);
+                                let mut updated = document.clone();
+                                if let Err(e) =
+                                    impulse_core::lsp::apply_content_changes(&mut updated, &changes)
+                                {
+                                    // Send nothing; the editor resends its
+                                    // buffer as a full-text change.
+                                    log::warn!("LSP document {} out of sync: {}", uri, e);
+                                    let _ = gtk_tx.send(LspResponse::Resync { uri });
+                                    continue;
+                                }
+                                *document = updated;
                             }
                             for client in clients {
                                 let _ = client.did_change_with_changes(
@@ -227,7 +320,61 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
                                 );
                             }
                         }
+                        LspRequest::WillSave {
+                            uri,
+                            reason,
+                            code_actions,
+                            reply,
+                        } => {
+                            let lang = language_from_uri(&uri);
+                            let clients = registry.get_clients(&lang, &uri).await;
+                            // All edits are computed against the same text, so
+                            // each source is only taken if it does not clash with
+                            // the ones before it. Without a host copy of the
+                            // text, source actions are skipped and the first
+                            // server's willSaveWaitUntil edits win.
+                            let text = lsp_documents.get(&uri).cloned();
+                            let mut edits: Vec<impulse_core::workspace_edit::TextEdit> = Vec::new();
+                            for client in clients {
+                                let mut sources = Vec::new();
+                                if let Some(text) = &text {
+                                    sources.push(client.code_actions_on_save(&uri, text, &code_actions).await);
+                                }
+                                match client.will_save(&uri, reason).await {
+                                    Ok(server_edits) => sources.push(
+                                        server_edits
+                                            .iter()
+                                            .map(impulse_core::workspace_edit::TextEdit::from)
+                                            .collect(),
+                                    ),
+                                    Err(e) => log::warn!("willSaveWaitUntil failed for {}: {}", uri, e),
+                                }
+                                for source in sources.into_iter().filter(|s| !s.is_empty()) {
+                                    let merged = match &text {
+                                        Some(text) => {
+                                            impulse_core::workspace_edit::merge_text_edits(text, &mut edits, source)
+                                        }
+                                        None if edits.is_empty() => {
+                                            edits = source;
+                                            true
+                                        }
+                                        None => false,
+                                    };
+                                    if !merged {
+                                        log::warn!("Dropping clashing save edits for {}", uri);
+                                    }
+                                }
+                            }
+                            let _ = reply.send(edits);
+                        }
                         LspRequest::DidSave { uri } => {
+                            // Skip while an index is being built; the build
+                            // reads the saved file from disk anyway.
+                            if let (Ok(mut indexes), Some(text)) =
+                                (symbol_indexes.try_lock(), lsp_documents.get(&uri))
+                            {
+                                indexes.file_changed(&uri_to_file_path(&uri), text);
+                            }
                             let lang = language_from_uri(&uri);
                             let clients = registry.get_clients(&lang, &uri).await;
                             for client in clients {
@@ -242,6 +389,21 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
                                 let _ = client.did_close(&uri);
                             }
                         }
+                        LspRequest::ChangeLanguage {
+                            uri,
+                            old_language_id,
+                            language_id,
+                            version,
+                            text,
+                        } => {
+                            lsp_documents.insert(uri.clone(), text.clone());
+                            for client in registry.get_clients(&old_language_id, &uri).await {
+                                let _ = client.did_close(&uri);
+                            }
+                            for client in registry.get_clients(&language_id, &uri).await {
+                                let _ = client.did_open(&uri, &language_id, version, &text);
+                            }
+                        }
                         LspRequest::Completion {
                             request_id,
                             uri,
@@ -306,6 +468,29 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
                         } => {
                             let lang = language_from_uri(&uri);
                             let clients = registry.get_clients(&lang, &uri).await;
+                            if clients.is_empty() {
+                                // No language server: answer from the symbol
+                                // index off the request loop, since the first
+                                // lookup indexes the workspace.
+                                let symbol_indexes = symbol_indexes.clone();
+                                let content = lsp_documents.get(&uri).cloned();
+                                tokio::task::spawn_blocking(move || {
+                                    let found = symbol_index_definitions(
+                                        &symbol_indexes, &uri, content, line, character,
+                                    );
+                                    if let Some(loc) = found.into_iter().next() {
+                                        let _ = gtk_tx.send(LspResponse::DefinitionResult {
+                                            request_id,
+                                            source_uri: uri,
+                                            source_version: version,
+                                            uri: loc.uri,
+                                            line: loc.start_line,
+                                            character: loc.start_character,
+                                        });
+                                    }
+                                });
+                                continue;
+                            }
                             for client in clients {
                                 if let Ok(Some(def)) = client.definition(&uri, line, character).await {
                                     let location = match def {
@@ -341,30 +526,106 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
                             version,
                             tab_size,
                             insert_spaces,
+                            formatter,
+                            text,
+                        } => {
+                            use impulse_core::formatter::FormatterChoice;
+                            let mut lsp_edits = None;
+                            if !matches!(formatter, FormatterChoice::External(_)) {
+                                let lang = language_from_uri(&uri);
+                                for client in registry.get_clients(&lang, &uri).await {
+                                    if !client.supports_formatting().await {
+                                        continue;
+                                    }
+                                    if let Ok(edits) = client.formatting(&uri, tab_size, insert_spaces).await {
+                                        lsp_edits = Some(edits);
+                                        break;
+                                    }
+                                }
+                            }
+                            // No server formats this document: use the
+                            // external formatter, if settings chose one.
+                            let edits = match (lsp_edits, formatter.external().cloned(), text) {
+                                (Some(edits), _, _) => edits,
+                                (None, Some(external), Some(text)) => {
+                                    let path = uri_to_file_path(&uri);
+                                    let result = tokio::task::spawn_blocking(move || {
+                                        impulse_core::formatter::format_edits(&external, &path, &text)
+                                    })
+                                    .await;
+                                    match result {
+                                        Ok(Ok(edits)) => edits,
+                                        Ok(Err(e)) => {
+                                            log::warn!("External formatting failed: {}", e);
+                                            Vec::new()
+                                        }
+                                        Err(e) => {
+                                            log::warn!("External formatter task failed: {}", e);
+                                            Vec::new()
+                                        }
+                                    }
+                                }
+                                _ => Vec::new(),
+                            };
+                            let infos = edits
+                                .into_iter()
+                                .map(|e| crate::lsp_completion::TextEditInfo {
+                                    start_line: e.range.start.line,
+                                    start_character: e.range.start.character,
+                                    end_line: e.range.end.line,
+                                    end_character: e.range.end.character,
+                                    new_text: e.new_text,
+                                })
+                                .collect();
+                            let _ = gtk_tx.send(LspResponse::FormattingResult {
+                                request_id,
+                                uri: uri.clone(),
+                                version,
+                                edits: infos,
+                            });
+                        }
+                        LspRequest::OnTypeFormatting {
+                            request_id,
+                            uri,
+                            version,
+                            line,
+                            character,
+                            ch,
+                            tab_size,
+                            insert_spaces,
                         } => {
                             let lang = language_from_uri(&uri);
                             let clients = registry.get_clients(&lang, &uri).await;
+                            // Servers that do not trigger on `ch` answer with
+                            // no edits; the first non-empty answer wins.
+                            let mut edits = Vec::new();
                             for client in clients {
-                                if let Ok(edits) = client.formatting(&uri, tab_size, insert_spaces).await {
-                                    let infos = edits
-                                        .into_iter()
-                                        .map(|e| crate::lsp_completion::TextEditInfo {
-                                            start_line: e.range.start.line,
-                                            start_character: e.range.start.character,
-                                            end_line: e.range.end.line,
-                                            end_character: e.range.end.character,
-                                            new_text: e.new_text,
-                                        })
-                                        .collect();
-                                    let _ = gtk_tx.send(LspResponse::FormattingResult {
-                                        request_id,
-                                        uri: uri.clone(),
-                                        version,
-                                        edits: infos,
-                                    });
-                                    break;
+                                if let Ok(server_edits) = client
+                                    .on_type_formatting(&uri, line, character, &ch, tab_size, insert_spaces)
+                                    .await
+                                {
+                                    if !server_edits.is_empty() {
+                                        edits = server_edits;
+                                        break;
+                                    }
                                 }
                             }
+                            let infos = edits
+                                .into_iter()
+                                .map(|e| crate::lsp_completion::TextEditInfo {
+                                    start_line: e.range.start.line,
+                                    start_character: e.range.start.character,
+                                    end_line: e.range.end.line,
+                                    end_character: e.range.end.character,
+                                    new_text: e.new_text,
+                                })
+                                .collect();
+                            let _ = gtk_tx.send(LspResponse::FormattingResult {
+                                request_id,
+                                uri: uri.clone(),
+                                version,
+                                edits: infos,
+                            });
                         }
                         LspRequest::SignatureHelp {
                             request_id,
@@ -459,6 +720,7 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
                             end_line,
                             end_column,
                             diagnostics,
+                            only,
                         } => {
                             let lang = language_from_uri(&uri);
                             let clients = registry.get_clients(&lang, &uri).await;
@@ -497,43 +759,57 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
                             for client in clients {
                                 if let Ok(actions) = client
                                     .code_action(
-                                        &uri, start_line, start_column, end_line, end_column,
+                                        &uri,
+                                        lsp_types::Range::new(
+                                            lsp_types::Position::new(start_line, start_column),
+                                            lsp_types::Position::new(end_line, end_column),
+                                        ),
                                         lsp_diags.clone(),
+                                        only.clone().map(|kind| vec![kind.into()]),
                                     )
                                     .await
                                 {
                                     let infos = actions
                                         .into_iter()
-                                        .filter_map(|a| match a {
+                                        .map(|a| match a {
                                             lsp_types::CodeActionOrCommand::CodeAction(ca) => {
                                                 let edits = ca
                                                     .edit
-                                                    .and_then(|we| we.changes)
+                                                    .as_ref()
+                                                    .map(impulse_core::workspace_edit::group_workspace_edit)
+                                                    .unwrap_or_default()
                                                     .into_iter()
-                                                    .flat_map(|changes| {
-                                                        changes.into_iter().flat_map(|(u, edits)| {
-                                                            let uri_str = u.to_string();
-                                                            edits.into_iter().map(move |e| {
-                                                                crate::lsp_completion::WorkspaceTextEditInfo {
-                                                                    uri: uri_str.clone(),
-                                                                    start_line: e.range.start.line,
-                                                                    start_character: e.range.start.character,
-                                                                    end_line: e.range.end.line,
-                                                                    end_character: e.range.end.character,
-                                                                    new_text: e.new_text,
-                                                                }
-                                                            })
+                                                    .flat_map(|file| {
+                                                        let uri = file.uri;
+                                                        file.edits.into_iter().map(move |e| {
+                                                            crate::lsp_completion::WorkspaceTextEditInfo {
+                                                                uri: uri.clone(),
+                                                                start_line: e.start_line,
+                                                                start_character: e.start_character,
+                                                                end_line: e.end_line,
+                                                                end_character: e.end_character,
+                                                                new_text: e.new_text,
+                                                            }
                                                         })
                                                     })
                                                     .collect();
-                                                Some(crate::lsp_completion::CodeActionInfo {
+                                                crate::lsp_completion::CodeActionInfo {
                                                     title: ca.title,
                                                     kind: ca.kind.map(|k| k.as_str().to_string()),
                                                     edits,
                                                     is_preferred: ca.is_preferred.unwrap_or(false),
-                                                })
+                                                    command: ca.command,
+                                                }
+                                            }
+                                            lsp_types::CodeActionOrCommand::Command(command) => {
+                                                crate::lsp_completion::CodeActionInfo {
+                                                    title: command.title.clone(),
+                                                    kind: None,
+                                                    edits: Vec::new(),
+                                                    is_preferred: false,
+                                                    command: Some(command),
+                                                }
                                             }
-                                            _ => None,
                                         })
                                         .collect();
                                     let _ = gtk_tx.send(LspResponse::CodeActionResult {
@@ -546,6 +822,26 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
                                 }
                             }
                         }
+                        LspRequest::ExecuteCommand {
+                            uri,
+                            command,
+                            arguments,
+                        } => {
+                            let lang = language_from_uri(&uri);
+                            let clients = registry.get_clients(&lang, &uri).await;
+                            let mut target = None;
+                            for client in &clients {
+                                if client.supports_command(&command).await {
+                                    target = Some(client);
+                                    break;
+                                }
+                            }
+                            if let Some(client) = target.or(clients.first()) {
+                                if let Err(e) = client.execute_command(&command, arguments).await {
+                                    log::warn!("LSP command '{}' failed: {}", command, e);
+                                }
+                            }
+                        }
                         LspRequest::Rename {
                             request_id,
                             uri,
@@ -558,30 +854,13 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
                             let clients = registry.get_clients(&lang, &uri).await;
                             for client in clients {
                                 if let Ok(Some(we)) = client.rename(&uri, line, character, &new_name).await {
-                                    let edits: Vec<crate::lsp_completion::WorkspaceTextEditInfo> = we
-                                        .changes
-                                        .into_iter()
-                                        .flat_map(|changes| {
-                                            changes.into_iter().flat_map(|(u, edits)| {
-                                                let uri_str = u.to_string();
-                                                edits.into_iter().map(move |e| {
-                                                    crate::lsp_completion::WorkspaceTextEditInfo {
-                                                        uri: uri_str.clone(),
-                                                        start_line: e.range.start.line,
-                                                        start_character: e.range.start.character,
-                                                        end_line: e.range.end.line,
-                                                        end_character: e.range.end.character,
-                                                        new_text: e.new_text,
-                                                    }
-                                                })
-                                            })
-                                        })
-                                        .collect();
+                                    let files = impulse_core::workspace_edit::group_workspace_edit(&we);
                                     let _ = gtk_tx.send(LspResponse::RenameResult {
                                         request_id,
                                         uri: uri.clone(),
                                         version,
-                                        edits,
+                                        new_name: new_name.clone(),
+                                        files,
                                     });
                                     break;
                                 }
@@ -633,6 +912,85 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
                                 }
                             }
                         }
+                        LspRequest::Peek {
+                            request_id,
+                            uri,
+                            version,
+                            line,
+                            character,
+                            kind,
+                        } => {
+                            let lang = language_from_uri(&uri);
+                            let clients = registry.get_clients(&lang, &uri).await;
+                            if clients.is_empty()
+                                && kind == impulse_editor::protocol::PeekKind::Definition
+                            {
+                                let symbol_indexes = symbol_indexes.clone();
+                                let content = lsp_documents.get(&uri).cloned();
+                                tokio::task::spawn_blocking(move || {
+                                    let locations = symbol_index_definitions(
+                                        &symbol_indexes, &uri, content, line, character,
+                                    );
+                                    let _ = gtk_tx.send(LspResponse::PeekResult {
+                                        request_id,
+                                        uri,
+                                        version,
+                                        kind,
+                                        locations,
+                                    });
+                                });
+                                continue;
+                            }
+                            let mut locations = Vec::new();
+                            for client in clients {
+                                let found: Vec<lsp_types::Location> = match kind {
+                                    impulse_editor::protocol::PeekKind::Definition => {
+                                        match client.definition(&uri, line, character).await {
+                                            Ok(Some(lsp_types::GotoDefinitionResponse::Scalar(loc))) => {
+                                                vec![loc]
+                                            }
+                                            Ok(Some(lsp_types::GotoDefinitionResponse::Array(locs))) => locs,
+                                            Ok(Some(lsp_types::GotoDefinitionResponse::Link(links))) => links
+                                                .into_iter()
+                                                .map(|l| lsp_types::Location {
+                                                    uri: l.target_uri,
+                                                    range: l.target_selection_range,
+                                                })
+                                                .collect(),
+                                            _ => Vec::new(),
+                                        }
+                                    }
+                                    impulse_editor::protocol::PeekKind::References => client
+                                        .references(&uri, line, character)
+                                        .await
+                                        .unwrap_or_default(),
+                                };
+                                if !found.is_empty() {
+                                    locations = found
+                                        .into_iter()
+                                        .map(|l| crate::lsp_completion::LocationInfo {
+                                            uri: l.uri.to_string(),
+                                            start_line: l.range.start.line,
+                                            start_character: l.range.start.character,
+                                            end_line: l.range.end.line,
+                                            end_character: l.range.end.character,
+                                        })
+                                        .collect();
+                                    break;
+                                }
+                            }
+                            // Always answer so the widget can show "no results".
+                            let _ = gtk_tx.send(LspResponse::PeekResult {
+                                request_id,
+                                uri,
+                                version,
+                                kind,
+                                locations,
+                            });
+                        }
+                        LspRequest::SetDisabledServers { server_ids } => {
+                            registry.set_disabled_servers(server_ids).await;
+                        }
                         LspRequest::Shutdown => {
                             registry.shutdown_all().await;
                         }
@@ -666,6 +1024,12 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
         Rc::new(RefCell::new(std::collections::HashMap::new()));
     let latest_rename_req: Rc<RefCell<std::collections::HashMap<String, u64>>> =
         Rc::new(RefCell::new(std::collections::HashMap::new()));
+    let latest_peek_req: Rc<RefCell<std::collections::HashMap<String, u64>>> =
+        Rc::new(RefCell::new(std::collections::HashMap::new()));
+    let peek_monaco_ids: Rc<RefCell<std::collections::HashMap<u64, u64>>> =
+        Rc::new(RefCell::new(std::collections::HashMap::new()));
+    let rename_monaco_ids: Rc<RefCell<std::collections::HashMap<u64, u64>>> =
+        Rc::new(RefCell::new(std::collections::HashMap::new()));
     let lsp_error_toast_dedupe: Rc<RefCell<HashSet<String>>> =
         Rc::new(RefCell::new(HashSet::new()));
     let (lsp_install_result_tx, lsp_install_result_rx) =
@@ -674,7 +1038,7 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
 
     // Track the current CSS provider so we can swap themes at runtime
     let css_provider: Rc<RefCell<gtk4::CssProvider>> = {
-        let theme = crate::theme::get_theme(&settings.borrow().color_scheme);
+        let theme = crate::theme::active_theme(&settings.borrow());
         Rc::new(RefCell::new(crate::theme::load_css(theme)))
     };
 
@@ -687,6 +1051,7 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
     header.add_css_class("impulse-header");
     let tab_bar = adw::TabBar::new();
     tab_bar.add_css_class("impulse-tab-bar");
+    tab_bar.update_property(&[gtk4::accessible::Property::Label("Tabs")]);
     let tab_view = adw::TabView::new();
     tab_view.add_css_class("impulse-tab-view");
     tab_bar.set_view(Some(&tab_view));
@@ -698,14 +1063,39 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
     tab_menu.append(Some("Pin/Unpin Tab"), Some("tab.pin"));
     tab_menu.append(Some("Close Tab"), Some("tab.close"));
     tab_menu.append(Some("Close Other Tabs"), Some("tab.close-others"));
+    tab_menu.append(Some("Close Tabs to the Right"), Some("tab.close-right"));
+    tab_menu.append(Some("Close Tabs to the Left"), Some("tab.close-left"));
+    tab_menu.append(Some("Close Saved Tabs"), Some("tab.close-saved"));
+    tab_menu.append(
+        Some("Open Containing Folder"),
+        Some("tab.open-containing-folder"),
+    );
+    tab_menu.append(Some("Copy Path"), Some("tab.copy-path"));
+    tab_menu.append(Some("Copy Relative Path"), Some("tab.copy-relative-path"));
+    tab_menu.append(Some("Copy File Name"), Some("tab.copy-file-name"));
+    tab_menu.append(Some("Copy File URL"), Some("tab.copy-file-url"));
     tab_view.set_menu_model(Some(&tab_menu));
 
-    header.set_title_widget(Some(&tab_bar));
+    // With many tabs open the titles get too narrow to read; this lists
+    // them all with a search.
+    let all_tabs_btn = gtk4::Button::from_icon_name("view-list-symbolic");
+    all_tabs_btn.set_tooltip_text(Some("Show All Tabs"));
+    all_tabs_btn.add_css_class("flat");
+    all_tabs_btn.set_cursor_from_name(Some("pointer"));
+    tab_bar.set_end_action_widget(Some(&all_tabs_btn));
+
+    // The slot lets "Toggle Tab Bar" hide the bar independently of the
+    // tab_bar_position setting, which toggles the bar itself.
+    let tab_bar_slot = gtk4::Box::new(gtk4::Orientation::Horizontal, 0);
+    tab_bar_slot.set_hexpand(true);
+    tab_bar.set_hexpand(true);
+    tab_bar_slot.append(&tab_bar);
+    header.set_title_widget(Some(&tab_bar_slot));
 
     // Toggle sidebar button (leftmost)
     let sidebar_btn = gtk4::ToggleButton::builder()
         .tooltip_text("Toggle Sidebar (Ctrl+Shift+B)")
-        .active(settings.borrow().sidebar_visible)
+        .active(sidebar_visible)
         .build();
     sidebar_btn.set_cursor_from_name(Some("pointer"));
     sidebar_btn.add_css_class("impulse-header-button");
@@ -729,19 +1119,29 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
     if let Some(warning) = crate::settings::settings_load_warning() {
         main_box.append(&settings_load_warning_banner(warning));
     }
+    // Only the first window of a session sees the reports; taking them marks
+    // them seen.
+    let crashes = crate::session_state::state_dir()
+        .map(|dir| {
+            impulse_core::crash_report::take_unseen(&impulse_core::crash_report::reports_dir(&dir))
+        })
+        .unwrap_or_default();
+    if let Some(report) = crashes.into_iter().next() {
+        main_box.append(&crash_report_banner(&window, report, &settings.borrow()));
+    }
 
     // Horizontal pane: sidebar + tab view
     let paned = gtk4::Paned::new(gtk4::Orientation::Horizontal);
     paned.add_css_class("workspace-paned");
     paned.set_vexpand(true);
-    paned.set_position(settings.borrow().sidebar_width);
+    paned.set_position(sidebar_width);
     paned.set_shrink_start_child(false);
     paned.set_shrink_end_child(false);
 
     // Sidebar
-    let initial_theme = crate::theme::get_theme(&settings.borrow().color_scheme);
+    let initial_theme = crate::theme::active_theme(&settings.borrow());
     let (sidebar_widget, sidebar_state) = sidebar::build_sidebar(&settings, initial_theme);
-    sidebar_widget.set_visible(settings.borrow().sidebar_visible);
+    sidebar_widget.set_visible(sidebar_visible);
     paned.set_start_child(Some(&sidebar_widget));
 
     // Set header button icons from shared SVG icon cache
@@ -802,14 +1202,36 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
     let context_bar = crate::context_bar::build_context_bar(&tab_view, &settings);
     right_box.append(&context_bar.widget);
 
-    paned.set_end_child(Some(&right_box));
+    // Clamps the content width in Zen mode / centered layout; unbounded
+    // otherwise.
+    let content_clamp = adw::Clamp::new();
+    content_clamp.set_child(Some(&right_box));
+
+    // Assistant chat docked to the right of the content; hidden until
+    // "Toggle Assistant Panel".
+    let assistant_panel = crate::assistant_panel::AssistantPanel::new(&settings);
+    let content_paned = gtk4::Paned::new(gtk4::Orientation::Horizontal);
+    content_paned.add_css_class("workspace-paned");
+    content_paned.set_start_child(Some(&content_clamp));
+    content_paned.set_end_child(Some(&assistant_panel.widget));
+    content_paned.set_resize_end_child(false);
+    content_paned.set_shrink_start_child(false);
+    content_paned.set_shrink_end_child(false);
+    paned.set_end_child(Some(&content_paned));
 
     main_box.append(&paned);
 
     // Status bar. Hidden on terminal tabs while the context bar shows the
     // shell/cwd/branch pills; the tab-switch handler keeps it in sync.
+    // "Toggle Status Bar" and Zen mode hide the slot rather than the bar.
     let status_bar = status_bar::new_shared();
-    main_box.append(&status_bar.borrow().widget);
+    status_bar.borrow().set_layout(
+        &settings.borrow().status_bar_segments,
+        &settings.borrow().status_bar_hidden_segments,
+    );
+    let status_bar_slot = gtk4::Box::new(gtk4::Orientation::Vertical, 0);
+    status_bar_slot.append(&status_bar.borrow().widget);
+    main_box.append(&status_bar_slot);
     status_bar
         .borrow()
         .widget
@@ -827,7 +1249,12 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
     } else {
         impulse_core::shell::get_home_directory().unwrap_or_else(|_| "/".to_string())
     };
-    sidebar_state.load_directory(&initial_dir);
+    // The listing and its file watchers wait until the window is on screen
+    // (see below); a directory or session restored before then replaces it.
+    *sidebar_state.current_path.borrow_mut() = initial_dir.clone();
+    status_bar
+        .borrow()
+        .set_ci_status_enabled(settings.borrow().show_ci_status);
     status_bar.borrow().update_cwd(&initial_dir);
 
     // Initialize project search root to current directory
@@ -850,6 +1277,10 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
         latest_references_req: latest_references_req.clone(),
         latest_code_action_req: latest_code_action_req.clone(),
         latest_rename_req: latest_rename_req.clone(),
+        latest_peek_req: latest_peek_req.clone(),
+        peek_monaco_ids: peek_monaco_ids.clone(),
+        rename_monaco_ids: rename_monaco_ids.clone(),
+        symbol_indexes: symbol_indexes.clone(),
     };
 
     let open_editor_paths: Rc<RefCell<HashSet<String>>> = Rc::new(RefCell::new(HashSet::new()));
@@ -866,12 +1297,26 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
         lsp: lsp_state,
         toast_overlay: toast_overlay.clone(),
         status_bar: status_bar.clone(),
+        layout: Rc::new(layout::ChromeLayout::new(
+            &header,
+            &tab_bar_slot,
+            &status_bar_slot,
+            &sidebar_btn,
+            &content_clamp,
+        )),
+        chords: keybindings::ChordDispatcher::new({
+            let status_bar = status_bar.clone();
+            move |first| status_bar.borrow().set_pending_chord(first)
+        }),
         open_editor_paths,
         editor_tab_pages,
         tab_close_return_targets,
+        agent_server: Rc::new(RefCell::new(None)),
     };
 
     sidebar_signals::wire_sidebar_signals(&ctx);
+    agent_access::setup(&ctx);
+    control::register(&ctx);
 
     let setup_terminal_signals = tab_management::make_setup_terminal_signals(
         &tab_view,
@@ -887,6 +1332,7 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
         &copy_on_select_flag,
         &shell_cache,
         &sidebar_state.icon_cache,
+        &sidebar_state.current_path,
     );
 
     // Open (or focus) the "Review Changes" tab for the active repository.
@@ -899,7 +1345,9 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
             // Reuse an already-open review tab.
             for i in 0..tab_view.n_pages() {
                 let page = tab_view.nth_page(i);
-                if crate::review_tab::is_review_tab(&page.child()) {
+                if crate::review_tab::is_review_tab(&page.child())
+                    && !crate::review_tab::is_compare_tab(&page.child())
+                {
                     tab_view.set_selected_page(&page);
                     crate::review_tab::refresh(&page.child());
                     return;
@@ -921,8 +1369,9 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
                 return;
             };
 
-            let theme = crate::theme::get_theme(&settings.borrow().color_scheme);
+            let theme = crate::theme::active_theme(&settings.borrow());
             let child = crate::review_tab::create_review_tab(&repo_root, theme);
+            crate::review_tab::apply_settings(child.upcast_ref(), &settings.borrow());
             let page = tab_management::insert_after_selected(&tab_view, &child);
             page.set_title("Review Changes");
             tab_view.set_selected_page(&page);
@@ -949,11 +1398,27 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
         tab_bar.set_visible(!sidebar_tabs);
     }
 
+    // A directory passed in (CLI, or a worktree opened in a new window)
+    // becomes the window's root: the sidebar shows it and new terminals
+    // start there.
+    let (initial_dirs, initial_files): (Vec<String>, Vec<String>) = initial_files
+        .unwrap_or_default()
+        .into_iter()
+        .partition(|path| std::path::Path::new(path).is_dir());
+    let opened_directory = initial_dirs.first().cloned();
+    if let Some(dir) = &opened_directory {
+        sidebar_state.load_directory(dir);
+        status_bar.borrow().update_cwd(dir);
+        *sidebar_state.project_search.current_root.borrow_mut() = dir.clone();
+        shell_cache.set_working_dir(dir);
+    }
+
     // Open files passed via CLI / file manager "Open With"
-    let has_initial_files = initial_files.as_ref().is_some_and(|f| !f.is_empty());
-    if let Some(files) = initial_files {
+    let has_initial_files = !initial_files.is_empty();
+    if has_initial_files {
+        let files = initial_files;
         // Switch sidebar to the first file's parent directory.
-        if let Some(first) = files.first() {
+        if let Some(first) = files.first().filter(|_| opened_directory.is_none()) {
             if let Some(parent) = std::path::Path::new(first).parent() {
                 let dir = parent.to_string_lossy().to_string();
                 sidebar_state.load_directory(&dir);
@@ -970,7 +1435,14 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
         }
     }
 
-    let restored_window = if !has_initial_files && settings.borrow().restore_session {
+    let from_layout = window_layout.is_some();
+    let restored_window = if from_layout {
+        window_layout
+    } else if open_initial_tabs
+        && !has_initial_files
+        && opened_directory.is_none()
+        && settings.borrow().restore_session
+    {
         crate::session_state::load().and_then(|state| {
             let index = state.active_window_index.unwrap_or(0);
             state
@@ -997,11 +1469,11 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
         )
     });
 
-    if !has_initial_files && !restored_session {
+    if open_initial_tabs && !has_initial_files && !restored_session {
         // Create initial terminal tab, then restore legacy open-file state.
         (create_tab.clone())();
 
-        if settings.borrow().restore_session {
+        if opened_directory.is_none() && !from_layout && settings.borrow().restore_session {
             for file_path in &settings.borrow().open_files.clone() {
                 if std::path::Path::new(file_path).exists() {
                     if let Some(cb) = sidebar_state.on_file_activated.borrow().as_ref() {
@@ -1043,6 +1515,170 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
         window.add_action(&action);
     }
 
+    // Files dropped on an editor open in tabs without moving the sidebar.
+    {
+        let sidebar_state = sidebar_state.clone();
+        let action = gio::SimpleAction::new("open-in-tab", Some(gtk4::glib::VariantTy::STRING));
+        action.connect_activate(move |_, param| {
+            if let Some(path) = param.and_then(|v| v.get::<String>()) {
+                if let Some(cb) = sidebar_state.on_file_activated.borrow().as_ref() {
+                    cb(&path);
+                }
+            }
+        });
+        window.add_action(&action);
+    }
+
+    // A folder dropped on the file tree becomes the window's root, as if it
+    // had been opened from the command line.
+    {
+        let sidebar_state = sidebar_state.clone();
+        let sidebar_btn = sidebar_btn.clone();
+        let status_bar = status_bar.clone();
+        let shell_cache = shell_cache.clone();
+        let action = gio::SimpleAction::new("open-folder", Some(gtk4::glib::VariantTy::STRING));
+        action.connect_activate(move |_, param| {
+            if let Some(dir) = param.and_then(|v| v.get::<String>()) {
+                sidebar_btn.set_active(true);
+                sidebar_state.search_btn.set_active(false);
+                sidebar_state.load_directory(&dir);
+                status_bar.borrow().update_cwd(&dir);
+                *sidebar_state.project_search.current_root.borrow_mut() = dir.clone();
+                shell_cache.set_working_dir(&dir);
+            }
+        });
+        window.add_action(&action);
+    }
+
+    // Indentation menu in the status bar: convert the file's existing
+    // indentation, or just change what new lines use.
+    {
+        let tab_view = tab_view.clone();
+        let status_bar = status_bar.clone();
+        let action =
+            gio::SimpleAction::new("convert-indentation", Some(gtk4::glib::VariantTy::STRING));
+        action.connect_activate(move |_, param| {
+            let Some(kind) = param.and_then(|v| v.get::<String>()) else {
+                return;
+            };
+            let Some(child) = tab_view.selected_page().map(|p| p.child()) else {
+                return;
+            };
+            let Some(mut indentation) = editor::get_editor_indentation(&child) else {
+                return;
+            };
+            indentation.use_spaces = kind == "spaces";
+            editor::set_editor_indentation(&child, indentation, true);
+            status_bar.borrow().update_indentation(indentation);
+        });
+        window.add_action(&action);
+    }
+    {
+        let tab_view = tab_view.clone();
+        let status_bar = status_bar.clone();
+        let action = gio::SimpleAction::new("indent-using", Some(gtk4::glib::VariantTy::STRING));
+        action.connect_activate(move |_, param| {
+            // Targets look like "spaces-4" or "tabs-8".
+            let Some(target) = param.and_then(|v| v.get::<String>()) else {
+                return;
+            };
+            let Some((kind, width)) = target.split_once('-') else {
+                return;
+            };
+            let Ok(width) = width.parse::<u32>() else {
+                return;
+            };
+            let Some(child) = tab_view.selected_page().map(|p| p.child()) else {
+                return;
+            };
+            if !editor::is_editor(&child) {
+                return;
+            }
+            let indentation = impulse_core::indentation::Indentation {
+                use_spaces: kind == "spaces",
+                width,
+            };
+            editor::set_editor_indentation(&child, indentation, false);
+            status_bar.borrow().update_indentation(indentation);
+        });
+        window.add_action(&action);
+    }
+
+    // Language menu in the status bar: override the detected language of
+    // every file with the active file's extension, and remember it.
+    {
+        let tab_view = tab_view.clone();
+        let status_bar = status_bar.clone();
+        let settings = settings.clone();
+        let editor_tab_pages = ctx.editor_tab_pages.clone();
+        let lsp_tx = lsp_request_tx.clone();
+        let doc_versions = lsp_doc_versions.clone();
+        let action = gio::SimpleAction::new("set-language", Some(gtk4::glib::VariantTy::STRING));
+        action.connect_activate(move |_, param| {
+            // "auto" removes the override.
+            let Some(id) = param.and_then(|v| v.get::<String>()) else {
+                return;
+            };
+            let Some(child) = tab_view.selected_page().map(|p| p.child()) else {
+                return;
+            };
+            let Some(handle) = editor::get_handle_for_widget(&child) else {
+                return;
+            };
+            let key_for =
+                |path: &str| impulse_core::languages::override_key(std::path::Path::new(path));
+            let Some(key) = key_for(&handle.file_path.borrow()) else {
+                return;
+            };
+
+            // Servers are picked by language, so note each file's language
+            // before the change to close it with the right ones.
+            let affected: Vec<(String, String)> = editor_tab_pages
+                .borrow()
+                .keys()
+                .filter(|path| !editor::is_untitled_path(path))
+                .filter(|path| key_for(path).as_ref() == Some(&key))
+                .map(|path| (path.clone(), language_from_uri(&ensure_file_uri(path))))
+                .collect();
+            {
+                let mut s = settings.borrow_mut();
+                if id == "auto" {
+                    s.language_overrides.remove(&key);
+                } else {
+                    s.language_overrides.insert(key, id);
+                }
+                impulse_core::languages::set_overrides(&s.language_overrides);
+                crate::settings::save(&s);
+            }
+
+            for (path, old_language_id) in affected {
+                let Some(handle) = editor::get_handle(&path) else {
+                    continue;
+                };
+                handle.set_language(&editor::guess_language(&path));
+                let uri = ensure_file_uri(&path);
+                let language_id = language_from_uri(&uri);
+                let mut versions = doc_versions.borrow_mut();
+                let version = versions.entry(path.clone()).or_insert(0);
+                *version += 1;
+                if let Err(e) = lsp_tx.try_send(LspRequest::ChangeLanguage {
+                    uri,
+                    old_language_id,
+                    language_id,
+                    version: *version,
+                    text: handle.get_content(),
+                }) {
+                    log::warn!("LSP request channel full: {}", e);
+                }
+            }
+            let language = editor::get_editor_language(&child);
+            status_bar
+                .borrow()
+                .update_language(language.as_deref().unwrap_or("Plain Text"));
+        });
+        window.add_action(&action);
+    }
+
     // New tab button
     {
         let create_tab = create_tab.clone();
@@ -1059,13 +1695,37 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
         });
     }
 
-    tab_management::setup_tab_context_menu(&window, &tab_view, &create_tab);
-
-    tab_management::setup_lsp_response_polling(&ctx, &lsp_gtk_rx, &lsp_install_result_rx);
-
-    // Shared closure for reopening the most recently closed editor/image tab.
-    // Defined early so it can be used by both the capture-phase key handler and
-    // the shortcut controller.
+    let tab_usage = Rc::new(tab_groups::TabUsage::default());
+    tab_groups::track_usage(&tab_view, &tab_usage);
+    tab_management::setup_tab_context_menu(
+        &window,
+        &tab_view,
+        &tab_usage,
+        &sidebar_state.current_path,
+        &create_tab,
+    );
+    {
+        let window = window.clone();
+        let tab_view = tab_view.clone();
+        let tab_usage = tab_usage.clone();
+        all_tabs_btn.connect_clicked(move |_| {
+            tab_groups::show_all_tabs(&window, &tab_view, &tab_usage);
+        });
+    }
+
+    // Clicking a tab's lock unlocks the file.
+    tab_view.connect_indicator_activated(|_, page| {
+        editor::toggle_read_only(&page.child());
+        tab_management::update_read_only_indicator(page);
+    });
+
+    tab_management::watch_terminal_processes(&tab_view);
+
+    tab_management::setup_lsp_response_polling(&ctx, &lsp_gtk_rx, &lsp_install_result_rx);
+
+    // Shared closure for reopening the most recently closed editor/image tab.
+    // Defined early so it can be used by both the capture-phase key handler and
+    // the shortcut controller.
     let reopen_tab: Rc<dyn Fn()> = {
         let closed_tabs = closed_tabs.clone();
         let sidebar_state = sidebar_state.clone();
@@ -1087,7 +1747,6 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
 
     let term_ctx = context::TerminalContext {
         copy_on_select: copy_on_select_flag.clone(),
-        font_size: font_size.clone(),
     };
 
     keybinding_setup::setup_capture_phase_keys(
@@ -1110,7 +1769,7 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
                 let child = page.child();
                 if editor::is_editor(&child) {
                     let s = settings.borrow();
-                    let theme = crate::theme::get_theme(&s.color_scheme);
+                    let theme = crate::theme::active_theme(&s);
                     if let Some(is_previewing) = editor::toggle_preview(child.upcast_ref(), theme) {
                         status_bar_for_click
                             .borrow()
@@ -1121,94 +1780,177 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
         });
     }
 
-    let kb_overrides = settings.borrow().keybinding_overrides.clone();
+    let kb_overrides = settings.borrow().effective_keybinding_overrides();
 
-    // Shared closure to open settings and apply changes live
-    let open_settings: Rc<dyn Fn()> = {
-        let window_ref = window.clone();
-        let settings = settings.clone();
+    // Swap the whole window to the theme the settings call for: GTK CSS,
+    // window chrome, file icons, terminal palettes, Monaco and markdown
+    // previews. Runs on settings changes and when the desktop switches
+    // between light and dark while following the system appearance.
+    let apply_theme: Rc<dyn Fn(&crate::settings::Settings)> = {
         let tab_view = tab_view.clone();
         let css_provider = css_provider.clone();
         let copy_on_select_flag = copy_on_select_flag.clone();
-        let font_size = font_size.clone();
+        let sidebar_state = sidebar_state.clone();
+        Rc::new(move |s| {
+            crate::theme::apply_color_scheme(s);
+            let new_theme = crate::theme::active_theme(s);
+            let display = gtk4::gdk::Display::default().expect("No display");
+            gtk4::style_context_remove_provider_for_display(&display, &*css_provider.borrow());
+            *css_provider.borrow_mut() = crate::theme::load_css(new_theme);
+
+            sidebar_state.update_theme(new_theme);
+
+            for i in 0..tab_view.n_pages() {
+                let child = tab_view.nth_page(i).child();
+                if let Some(term) = crate::terminal_container::get_active_terminal(&child) {
+                    crate::terminal::apply_settings(&term, s, new_theme, &copy_on_select_flag);
+                } else if crate::editor::is_editor(&child) {
+                    crate::editor::apply_theme(child.upcast_ref::<gtk4::Widget>(), new_theme);
+                    // Re-render preview if currently previewing
+                    crate::editor::refresh_preview(child.upcast_ref::<gtk4::Widget>(), new_theme);
+                } else if crate::review_tab::is_review_tab(&child) {
+                    crate::review_tab::apply_theme(child.upcast_ref::<gtk4::Widget>(), new_theme);
+                }
+            }
+        })
+    };
+
+    // Follow the desktop's light/dark switch and high contrast option at
+    // runtime. The handlers are dropped with the window so closed windows
+    // don't keep reapplying.
+    {
+        let style_manager = adw::StyleManager::default();
+        let dark_handler = {
+            let settings = settings.clone();
+            let apply_theme = apply_theme.clone();
+            style_manager.connect_dark_notify(move |_| {
+                // try_borrow: the notify can fire from inside the settings
+                // callback, which applies the theme itself.
+                if let Ok(s) = settings.try_borrow() {
+                    if s.follow_system_appearance {
+                        apply_theme(&s);
+                    }
+                }
+            })
+        };
+        let contrast_handler = {
+            let settings = settings.clone();
+            let apply_theme = apply_theme.clone();
+            style_manager.connect_high_contrast_notify(move |_| {
+                if let Ok(s) = settings.try_borrow() {
+                    if !s.increase_contrast {
+                        apply_theme(&s);
+                    }
+                }
+            })
+        };
+        let handlers = RefCell::new(vec![dark_handler, contrast_handler]);
+        window.connect_destroy(move |_| {
+            for handler in handlers.borrow_mut().drain(..) {
+                style_manager.disconnect(handler);
+            }
+        });
+    }
+
+    // Apply changed settings to this window live (settings page, or the
+    // settings file edited outside Impulse).
+    let apply_settings: Rc<dyn Fn(&crate::settings::Settings)> = {
+        let apply_theme = apply_theme.clone();
+        let tab_view = tab_view.clone();
         let sidebar_state = sidebar_state.clone();
         let vertical_tabs = vertical_tabs.clone();
         let tab_bar = tab_bar.clone();
         let context_bar = context_bar.clone();
+        let assistant_panel = assistant_panel.clone();
         let status_bar = status_bar.clone();
-        Rc::new(move || {
-            let tab_view = tab_view.clone();
-            let css_provider = css_provider.clone();
-            let copy_on_select_flag = copy_on_select_flag.clone();
-            let font_size = font_size.clone();
-            let sidebar_state = sidebar_state.clone();
-            let vertical_tabs = vertical_tabs.clone();
-            let tab_bar = tab_bar.clone();
-            let context_bar = context_bar.clone();
-            let status_bar = status_bar.clone();
-            crate::settings_page::show_settings_window(&window_ref, &settings, move |s| {
-                // Keep the font_size Cell in sync so the close handler
-                // doesn't overwrite the user's settings-page changes.
-                font_size.set(s.font_size);
-                // Swap theme CSS
-                let new_theme = crate::theme::get_theme(&s.color_scheme);
-                let display = gtk4::gdk::Display::default().expect("No display");
-                gtk4::style_context_remove_provider_for_display(&display, &*css_provider.borrow());
-                let new_provider = crate::theme::load_css(new_theme);
-                *css_provider.borrow_mut() = new_provider;
-
-                // Switch light/dark window chrome based on theme base
-                let style_manager = libadwaita::StyleManager::default();
-                if new_theme.base == "vs" {
-                    style_manager.set_color_scheme(libadwaita::ColorScheme::ForceLight);
-                } else {
-                    style_manager.set_color_scheme(libadwaita::ColorScheme::ForceDark);
-                }
+        let ctx = ctx.clone();
+        let layout = ctx.layout.clone();
+        let lsp_tx = lsp_request_tx.clone();
+        let disabled_lsp_servers =
+            Rc::new(RefCell::new(settings.borrow().disabled_lsp_servers.clone()));
+        let exclude_globs = Rc::new(RefCell::new((
+            settings.borrow().files_exclude.clone(),
+            settings.borrow().search_exclude.clone(),
+        )));
+        Rc::new(move |s: &crate::settings::Settings| {
+            // Theme, terminal palettes and terminal settings
+            apply_theme(s);
+
+            // Re-evaluate tab bar position and context bar visibility.
+            // NOTE: set_enabled (not refresh) — this callback may run
+            // while the settings RefCell is mutably borrowed.
+            let sidebar_tabs = s.tab_bar_position == "sidebar";
+            vertical_tabs.set_visible(sidebar_tabs);
+            tab_bar.set_visible(!sidebar_tabs);
+            context_bar.set_enabled(s.terminal_context_bar);
+            assistant_panel.set_enabled(s.assistant_enabled);
+            agent_access::set_enabled(&ctx, s.agent_access_enabled);
+            control::set_enabled(s.control_socket_enabled);
+
+            layout.set_max_width(s.zen_mode_max_width);
+            status_bar
+                .borrow()
+                .set_layout(&s.status_bar_segments, &s.status_bar_hidden_segments);
+            status_bar.borrow().set_ci_status_enabled(s.show_ci_status);
+            impulse_core::search::set_content_index_enabled(s.search_index_enabled);
+            impulse_core::mounts::set_walk_options(s.follow_symlinks, s.scan_network_mounts);
+            impulse_core::safe_write::set_save_strategy(&s.save_strategy);
+            crate::hot_exit::set_enabled(s.hot_exit);
+            if *exclude_globs.borrow() != (s.files_exclude.clone(), s.search_exclude.clone()) {
+                *exclude_globs.borrow_mut() = (s.files_exclude.clone(), s.search_exclude.clone());
+                impulse_core::ignore_rules::set_exclude_globs(
+                    s.files_exclude.clone(),
+                    s.search_exclude.clone(),
+                );
+                sidebar_state.refresh();
+            }
 
-                // Update sidebar file icons for the new theme
-                sidebar_state.update_theme(new_theme);
+            if *disabled_lsp_servers.borrow() != s.disabled_lsp_servers {
+                *disabled_lsp_servers.borrow_mut() = s.disabled_lsp_servers.clone();
+                if let Err(e) = lsp_tx.try_send(LspRequest::SetDisabledServers {
+                    server_ids: s.disabled_lsp_servers.clone(),
+                }) {
+                    log::warn!("Failed to send LSP disabled servers: {}", e);
+                }
+            }
+            status_bar
+                .borrow()
+                .update_ci_status(&sidebar_state.current_path.borrow());
 
-                // Re-evaluate tab bar position and context bar visibility.
-                // NOTE: set_enabled (not refresh) — this callback may run
-                // while the settings RefCell is mutably borrowed.
-                let sidebar_tabs = s.tab_bar_position == "sidebar";
-                vertical_tabs.set_visible(sidebar_tabs);
-                tab_bar.set_visible(!sidebar_tabs);
-                context_bar.set_enabled(s.terminal_context_bar);
+            // Status bar: redundant on terminal tabs while the context
+            // bar is enabled; always shown for editor tabs.
+            if let Some(page) = tab_view.selected_page() {
+                let child = page.child();
+                let show = if crate::terminal_container::get_active_terminal(&child).is_some() {
+                    !s.terminal_context_bar
+                } else {
+                    crate::editor::is_editor(&child) || crate::editor::is_image_preview(&child)
+                };
+                status_bar.borrow().widget.set_visible(show);
+            }
 
-                // Status bar: redundant on terminal tabs while the context
-                // bar is enabled; always shown for editor tabs.
-                if let Some(page) = tab_view.selected_page() {
-                    let child = page.child();
-                    let show = if crate::terminal_container::get_active_terminal(&child).is_some() {
-                        !s.terminal_context_bar
-                    } else {
-                        crate::editor::is_editor(&child) || crate::editor::is_image_preview(&child)
-                    };
-                    status_bar.borrow().widget.set_visible(show);
+            // Apply the remaining settings to open editors and reviews
+            for i in 0..tab_view.n_pages() {
+                let child = tab_view.nth_page(i).child();
+                if crate::editor::is_editor(&child) {
+                    crate::editor::apply_settings(child.upcast_ref::<gtk4::Widget>(), s);
+                } else if crate::review_tab::is_review_tab(&child) {
+                    crate::review_tab::apply_settings(child.upcast_ref::<gtk4::Widget>(), s);
                 }
+            }
+        })
+    };
+    crate::settings_watch::register(&window, &settings, apply_settings.clone());
 
-                // Apply to all open tabs
-                for i in 0..tab_view.n_pages() {
-                    let page = tab_view.nth_page(i);
-                    let child = page.child();
-                    if let Some(term) = crate::terminal_container::get_active_terminal(&child) {
-                        crate::terminal::apply_settings(&term, s, new_theme, &copy_on_select_flag);
-                    } else if crate::editor::is_editor(&child) {
-                        crate::editor::apply_settings(child.upcast_ref::<gtk4::Widget>(), s);
-                        crate::editor::apply_theme(child.upcast_ref::<gtk4::Widget>(), new_theme);
-                        // Re-render preview if currently previewing
-                        crate::editor::refresh_preview(
-                            child.upcast_ref::<gtk4::Widget>(),
-                            new_theme,
-                        );
-                    } else if crate::review_tab::is_review_tab(&child) {
-                        crate::review_tab::apply_theme(
-                            child.upcast_ref::<gtk4::Widget>(),
-                            new_theme,
-                        );
-                    }
-                }
+    // Shared closure to open settings
+    let open_settings: Rc<dyn Fn()> = {
+        let apply_settings = apply_settings.clone();
+        let window_ref = window.clone();
+        let settings = settings.clone();
+        Rc::new(move || {
+            let apply_settings = apply_settings.clone();
+            crate::settings_page::show_settings_window(&window_ref, &settings, move |s| {
+                apply_settings(s)
             });
         })
     };
@@ -1223,6 +1965,7 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
 
     // Build command list for the command palette
     let commands = {
+        let git_remote_busy = Rc::new(Cell::new(false));
         let create_tab = create_tab.clone();
         let tab_view = tab_view.clone();
         let sidebar_btn = sidebar_btn.clone();
@@ -1271,6 +2014,114 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
                     move || reopen_tab()
                 }),
             ),
+            make_palette_builtin_command(
+                &builtin_items_by_id,
+                "show_all_tabs",
+                shortcut_for("show_all_tabs"),
+                Rc::new({
+                    let window = window.clone();
+                    let tab_view = tab_view.clone();
+                    let tab_usage = tab_usage.clone();
+                    move || tab_groups::show_all_tabs(&window, &tab_view, &tab_usage)
+                }),
+            ),
+            make_palette_builtin_command(
+                &builtin_items_by_id,
+                "sort_tabs_by_path",
+                shortcut_for("sort_tabs_by_path"),
+                Rc::new({
+                    let tab_view = tab_view.clone();
+                    let tab_usage = tab_usage.clone();
+                    move || tab_groups::sort(&tab_view, &tab_usage, TabSort::Path)
+                }),
+            ),
+            make_palette_builtin_command(
+                &builtin_items_by_id,
+                "sort_tabs_by_recent_use",
+                shortcut_for("sort_tabs_by_recent_use"),
+                Rc::new({
+                    let tab_view = tab_view.clone();
+                    let tab_usage = tab_usage.clone();
+                    move || tab_groups::sort(&tab_view, &tab_usage, TabSort::RecentUse)
+                }),
+            ),
+            make_palette_builtin_command(
+                &builtin_items_by_id,
+                "close_saved_tabs",
+                shortcut_for("close_saved_tabs"),
+                Rc::new({
+                    let tab_view = tab_view.clone();
+                    let tab_usage = tab_usage.clone();
+                    move || {
+                        if let Some(page) = tab_view.selected_page() {
+                            tab_groups::close_group(&tab_view, &tab_usage, &page, TabGroup::Saved);
+                        }
+                    }
+                }),
+            ),
+            make_palette_builtin_command(
+                &builtin_items_by_id,
+                "close_tabs_to_right",
+                shortcut_for("close_tabs_to_right"),
+                Rc::new({
+                    let tab_view = tab_view.clone();
+                    let tab_usage = tab_usage.clone();
+                    move || {
+                        if let Some(page) = tab_view.selected_page() {
+                            tab_groups::close_group(&tab_view, &tab_usage, &page, TabGroup::Right);
+                        }
+                    }
+                }),
+            ),
+            make_palette_builtin_command(
+                &builtin_items_by_id,
+                "close_tabs_to_left",
+                shortcut_for("close_tabs_to_left"),
+                Rc::new({
+                    let tab_view = tab_view.clone();
+                    let tab_usage = tab_usage.clone();
+                    move || {
+                        if let Some(page) = tab_view.selected_page() {
+                            tab_groups::close_group(&tab_view, &tab_usage, &page, TabGroup::Left);
+                        }
+                    }
+                }),
+            ),
+            make_palette_builtin_command(
+                &builtin_items_by_id,
+                "font_increase",
+                shortcut_for("font_increase"),
+                Rc::new({
+                    let tab_view = tab_view.clone();
+                    let settings = settings.clone();
+                    let status_bar = status_bar.clone();
+                    move || zoom::zoom_selected(&tab_view, &settings, &status_bar, ZoomChange::In)
+                }),
+            ),
+            make_palette_builtin_command(
+                &builtin_items_by_id,
+                "font_decrease",
+                shortcut_for("font_decrease"),
+                Rc::new({
+                    let tab_view = tab_view.clone();
+                    let settings = settings.clone();
+                    let status_bar = status_bar.clone();
+                    move || zoom::zoom_selected(&tab_view, &settings, &status_bar, ZoomChange::Out)
+                }),
+            ),
+            make_palette_builtin_command(
+                &builtin_items_by_id,
+                "font_reset",
+                shortcut_for("font_reset"),
+                Rc::new({
+                    let tab_view = tab_view.clone();
+                    let settings = settings.clone();
+                    let status_bar = status_bar.clone();
+                    move || {
+                        zoom::zoom_selected(&tab_view, &settings, &status_bar, ZoomChange::Reset)
+                    }
+                }),
+            ),
             make_palette_builtin_command(
                 &builtin_items_by_id,
                 "toggle_sidebar",
@@ -1280,6 +2131,44 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
                     move || sidebar_btn.set_active(!sidebar_btn.is_active())
                 }),
             ),
+            make_palette_builtin_command(
+                &builtin_items_by_id,
+                "toggle_zen_mode",
+                shortcut_for("toggle_zen_mode"),
+                Rc::new({
+                    let layout = ctx.layout.clone();
+                    let settings = settings.clone();
+                    move || layout.toggle_zen(settings.borrow().zen_mode_max_width)
+                }),
+            ),
+            make_palette_builtin_command(
+                &builtin_items_by_id,
+                "toggle_tab_bar",
+                shortcut_for("toggle_tab_bar"),
+                Rc::new({
+                    let layout = ctx.layout.clone();
+                    move || layout.toggle_tab_bar()
+                }),
+            ),
+            make_palette_builtin_command(
+                &builtin_items_by_id,
+                "toggle_status_bar",
+                shortcut_for("toggle_status_bar"),
+                Rc::new({
+                    let layout = ctx.layout.clone();
+                    move || layout.toggle_status_bar()
+                }),
+            ),
+            make_palette_builtin_command(
+                &builtin_items_by_id,
+                "toggle_centered_layout",
+                shortcut_for("toggle_centered_layout"),
+                Rc::new({
+                    let layout = ctx.layout.clone();
+                    let settings = settings.clone();
+                    move || layout.toggle_centered(settings.borrow().zen_mode_max_width)
+                }),
+            ),
             make_palette_builtin_command(
                 &builtin_items_by_id,
                 "quick_open",
@@ -1290,6 +2179,45 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
                     move || show_quick_open(&window_ref, &sidebar_state)
                 }),
             ),
+            Command {
+                item: CommandPaletteItem {
+                    id: "go_to_symbol".to_string(),
+                    title: "Go to Symbol in Workspace".to_string(),
+                    category: "Navigation".to_string(),
+                    keywords: vec!["symbol".into(), "definition".into(), "tags".into()],
+                    source: CommandPaletteSource::Builtin,
+                    shortcut: None,
+                    payload: Default::default(),
+                },
+                shortcut: String::new(),
+                action: Rc::new({
+                    let window_ref = window_ref.clone();
+                    let sidebar_state = sidebar_state.clone();
+                    let symbol_indexes = ctx.lsp.symbol_indexes.clone();
+                    let on_open = {
+                        let sidebar_state = sidebar_state.clone();
+                        let editor_tab_pages = ctx.editor_tab_pages.clone();
+                        let tab_view = tab_view.clone();
+                        move |path: &str, line: u32, character: u32| {
+                            if let Some(cb) = sidebar_state.on_file_activated.borrow().as_ref() {
+                                cb(path);
+                            }
+                            if let Some(page) = editor_tab_pages.borrow().get(path) {
+                                editor::go_to_position(&page.child(), line + 1, character + 1);
+                                tab_view.set_selected_page(page);
+                            }
+                        }
+                    };
+                    move || {
+                        show_workspace_symbols(
+                            &window_ref,
+                            &sidebar_state,
+                            &symbol_indexes,
+                            on_open.clone(),
+                        )
+                    }
+                }),
+            },
             make_palette_builtin_command(
                 &builtin_items_by_id,
                 "project_search",
@@ -1330,6 +2258,116 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
                     move || build_window(&app, None)
                 }),
             ),
+            make_palette_builtin_command(
+                &builtin_items_by_id,
+                "save_window_layout",
+                String::new(),
+                Rc::new({
+                    let tab_view = tab_view.clone();
+                    let sidebar_state = sidebar_state.clone();
+                    let layout = ctx.layout.clone();
+                    let paned = paned.clone();
+                    let toast_overlay = toast_overlay.clone();
+                    move || {
+                        let Some(dir) = crate::session_state::layouts_dir() else {
+                            return;
+                        };
+                        let state = session_state_for_tab_view(
+                            &tab_view,
+                            Some(sidebar_state.current_path.borrow().clone()),
+                        );
+                        let Some(mut window_state) = state.windows.into_iter().next() else {
+                            return;
+                        };
+                        window_state.sidebar = Some(impulse_core::session_state::SessionSidebar {
+                            visible: layout.sidebar_visible_to_save(),
+                            width: paned.position(),
+                        });
+                        let overlay = toast_overlay.clone();
+                        dialogs::show_save_layout_dialog(
+                            &toast_overlay,
+                            impulse_core::window_layouts::list(&dir),
+                            move |name| {
+                                let message = match impulse_core::window_layouts::save(
+                                    &dir,
+                                    &name,
+                                    &window_state,
+                                ) {
+                                    Ok(()) => format!("Saved layout \"{}\"", name.trim()),
+                                    Err(e) => {
+                                        log::warn!("Failed to save window layout: {}", e);
+                                        e
+                                    }
+                                };
+                                let toast = adw::Toast::new(&message);
+                                toast.set_timeout(3);
+                                overlay.add_toast(toast);
+                            },
+                        );
+                    }
+                }),
+            ),
+            make_palette_builtin_command(
+                &builtin_items_by_id,
+                "open_window_layout",
+                String::new(),
+                Rc::new({
+                    let app = app.clone();
+                    let toast_overlay = toast_overlay.clone();
+                    move || {
+                        let Some(dir) = crate::session_state::layouts_dir() else {
+                            return;
+                        };
+                        let names = impulse_core::window_layouts::list(&dir);
+                        if names.is_empty() {
+                            let toast = adw::Toast::new("No saved window layouts");
+                            toast.set_timeout(3);
+                            toast_overlay.add_toast(toast);
+                            return;
+                        }
+                        let app = app.clone();
+                        let overlay = toast_overlay.clone();
+                        dialogs::show_layouts_dialog(&toast_overlay, names, move |name| {
+                            match impulse_core::window_layouts::load(&dir, &name) {
+                                Ok(window_state) => build_window_with_layout(&app, window_state),
+                                Err(e) => {
+                                    log::warn!("Failed to open window layout: {}", e);
+                                    let toast = adw::Toast::new(&e);
+                                    toast.set_timeout(3);
+                                    overlay.add_toast(toast);
+                                }
+                            }
+                        });
+                    }
+                }),
+            ),
+            make_palette_builtin_command(
+                &builtin_items_by_id,
+                "open_remote_file",
+                shortcut_for("open_remote_file"),
+                Rc::new({
+                    let sidebar_state = sidebar_state.clone();
+                    let toast_overlay = toast_overlay.clone();
+                    move || {
+                        let sidebar_state = sidebar_state.clone();
+                        let overlay = toast_overlay.clone();
+                        dialogs::show_open_remote_file_dialog(
+                            &toast_overlay,
+                            impulse_core::remote_files::recent_uris(),
+                            move |uri| {
+                                let sidebar_state = sidebar_state.clone();
+                                crate::remote_files::open(&overlay, uri, move |path| {
+                                    if let Some(cb) =
+                                        sidebar_state.on_file_activated.borrow().as_ref()
+                                    {
+                                        cb(&path);
+                                    }
+                                });
+                            },
+                        );
+                    }
+                }),
+            ),
             make_palette_builtin_command(
                 &builtin_items_by_id,
                 "review_changes",
@@ -1341,12 +2379,264 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
             ),
             make_palette_builtin_command(
                 &builtin_items_by_id,
-                "open_settings",
-                shortcut_for("open_settings"),
+                "open_settings",
+                shortcut_for("open_settings"),
+                Rc::new({
+                    let open_settings = open_settings.clone();
+                    move || {
+                        open_settings();
+                    }
+                }),
+            ),
+            make_palette_builtin_command(
+                &builtin_items_by_id,
+                "reload_custom_css",
+                shortcut_for("reload_custom_css"),
+                Rc::new({
+                    let tab_view = tab_view.clone();
+                    let toast_overlay = toast_overlay.clone();
+                    move || {
+                        let editor_css = crate::theme::reload_custom_css();
+                        for i in 0..tab_view.n_pages() {
+                            let child = tab_view.nth_page(i).child();
+                            if editor::is_editor(&child) {
+                                editor::apply_custom_css(child.upcast_ref(), &editor_css);
+                            }
+                        }
+                        let toast = adw::Toast::new("Reloaded custom CSS");
+                        toast.set_timeout(2);
+                        toast_overlay.add_toast(toast);
+                    }
+                }),
+            ),
+            make_palette_builtin_command(
+                &builtin_items_by_id,
+                "save_all",
+                shortcut_for("save_all"),
+                Rc::new({
+                    let tab_view = tab_view.clone();
+                    let lsp_tx = lsp_request_tx.clone();
+                    let settings = settings.clone();
+                    let toast_overlay = toast_overlay.clone();
+                    let editor_tab_pages = ctx.editor_tab_pages.clone();
+                    move || {
+                        save_all_editors(
+                            &tab_view,
+                            &lsp_tx,
+                            &settings.borrow(),
+                            &toast_overlay,
+                            &editor_tab_pages,
+                        )
+                    }
+                }),
+            ),
+            make_palette_builtin_command(
+                &builtin_items_by_id,
+                "local_history",
+                shortcut_for("local_history"),
+                Rc::new({
+                    let window = window.clone();
+                    let tab_view = tab_view.clone();
+                    let settings = settings.clone();
+                    let toast_overlay = toast_overlay.clone();
+                    move || show_local_history(&window, &tab_view, &settings, &toast_overlay)
+                }),
+            ),
+            make_palette_builtin_command(
+                &builtin_items_by_id,
+                "compare_with_saved",
+                shortcut_for("compare_with_saved"),
+                Rc::new({
+                    let tab_view = tab_view.clone();
+                    let settings = settings.clone();
+                    move || {
+                        let Some(page) = tab_view.selected_page() else {
+                            return;
+                        };
+                        let child = page.child();
+                        let path = child.widget_name().to_string();
+                        if editor::is_editor(&child) && !editor::is_untitled_path(&path) {
+                            tab_management::open_saved_compare(&tab_view, &settings, &path);
+                        }
+                    }
+                }),
+            ),
+            make_palette_builtin_command(
+                &builtin_items_by_id,
+                "revert_file",
+                shortcut_for("revert_file"),
+                Rc::new({
+                    let tab_view = tab_view.clone();
+                    let toast_overlay = toast_overlay.clone();
+                    move || revert_active_file(&tab_view, &toast_overlay)
+                }),
+            ),
+            make_palette_builtin_command(
+                &builtin_items_by_id,
+                "toggle_read_only",
+                String::new(),
+                Rc::new({
+                    let tab_view = tab_view.clone();
+                    move || {
+                        if let Some(page) = tab_view.selected_page() {
+                            editor::toggle_read_only(&page.child());
+                            tab_management::update_read_only_indicator(&page);
+                        }
+                    }
+                }),
+            ),
+            make_palette_builtin_command(
+                &builtin_items_by_id,
+                "check_for_updates",
+                String::new(),
+                Rc::new({
+                    let window = window.clone();
+                    move || crate::updater::check_now(&window)
+                }),
+            ),
+            make_palette_builtin_command(
+                &builtin_items_by_id,
+                "manage_workspace_trust",
+                String::new(),
+                Rc::new({
+                    let ctx = ctx.clone();
+                    move || workspace_trust::manage(&ctx)
+                }),
+            ),
+            make_palette_builtin_command(
+                &builtin_items_by_id,
+                "manage_agent_access",
+                String::new(),
+                Rc::new({
+                    let ctx = ctx.clone();
+                    move || agent_access::manage(&ctx)
+                }),
+            ),
+            make_palette_builtin_command(
+                &builtin_items_by_id,
+                "show_notifications",
+                String::new(),
+                Rc::new({
+                    let status_bar = status_bar.clone();
+                    move || status_bar.borrow().show_notifications()
+                }),
+            ),
+            make_palette_builtin_command(
+                &builtin_items_by_id,
+                "export_settings",
+                String::new(),
+                Rc::new({
+                    let window = window.clone();
+                    let toast_overlay = toast_overlay.clone();
+                    move || crate::config_sync::export(&window, &toast_overlay)
+                }),
+            ),
+            make_palette_builtin_command(
+                &builtin_items_by_id,
+                "import_settings",
+                String::new(),
+                Rc::new({
+                    let window = window.clone();
+                    let toast_overlay = toast_overlay.clone();
+                    move || crate::config_sync::import(&window, &toast_overlay)
+                }),
+            ),
+            make_palette_builtin_command(
+                &builtin_items_by_id,
+                "import_vscode_settings",
+                String::new(),
+                Rc::new({
+                    let window = window.clone();
+                    let toast_overlay = toast_overlay.clone();
+                    let settings = settings.clone();
+                    move || {
+                        let current = settings.borrow().clone();
+                        crate::config_sync::import_vscode(&window, &toast_overlay, &current)
+                    }
+                }),
+            ),
+            make_palette_builtin_command(
+                &builtin_items_by_id,
+                "sync_settings",
+                String::new(),
+                Rc::new({
+                    let window = window.clone();
+                    let toast_overlay = toast_overlay.clone();
+                    let settings = settings.clone();
+                    move || {
+                        let url = settings.borrow().settings_sync_repository.clone();
+                        crate::config_sync::sync_now(&window, &toast_overlay, &url)
+                    }
+                }),
+            ),
+            make_palette_builtin_command(
+                &builtin_items_by_id,
+                "open_containing_folder",
+                String::new(),
+                Rc::new({
+                    let tab_view = tab_view.clone();
+                    move || {
+                        if let Some(path) = tab_view
+                            .selected_page()
+                            .and_then(|page| tab_management::file_path_for_page(&page))
+                        {
+                            crate::file_manager::show_item(&path);
+                        }
+                    }
+                }),
+            ),
+            make_palette_builtin_command(
+                &builtin_items_by_id,
+                "reveal_in_sidebar",
+                String::new(),
+                Rc::new({
+                    let tab_view = tab_view.clone();
+                    let sidebar_state = sidebar_state.clone();
+                    let sidebar_btn = sidebar_btn.clone();
+                    move || {
+                        let Some(path) = tab_view
+                            .selected_page()
+                            .and_then(|page| tab_management::file_path_for_page(&page))
+                        else {
+                            return;
+                        };
+                        sidebar_btn.set_active(true);
+                        sidebar_state.search_btn.set_active(false);
+                        // A file outside the tree shows in its own folder.
+                        if !sidebar_state.reveal_path(&path) {
+                            if let Some(parent) = std::path::Path::new(&path).parent() {
+                                sidebar_state.load_directory(&parent.to_string_lossy());
+                                sidebar_state.reveal_path(&path);
+                            }
+                        }
+                    }
+                }),
+            ),
+            make_palette_builtin_command(
+                &builtin_items_by_id,
+                "open_scrollback_in_editor",
+                String::new(),
                 Rc::new({
-                    let open_settings = open_settings.clone();
+                    let ctx = ctx.clone();
                     move || {
-                        open_settings();
+                        let Some(term) = ctx.tab_view.selected_page().and_then(|page| {
+                            terminal_container::get_active_terminal(&page.child())
+                        }) else {
+                            return;
+                        };
+                        let text = terminal::scrollback_text(&term).unwrap_or_default();
+                        let lines = text.lines().count().max(1) as u32;
+                        let title = format!("Scrollback: {}", terminal::title(&term));
+                        let handle = keybinding_setup::open_untitled_tab(
+                            &ctx,
+                            terminal::current_directory(&term),
+                            &text,
+                            &title,
+                        );
+                        // Read-only so it can't be mistaken for the live
+                        // terminal; Toggle Read-Only lifts it.
+                        handle.set_read_only(Some(impulse_core::read_only::ReadOnlyReason::Manual));
+                        handle.go_to_position(lines, 1);
                     }
                 }),
             ),
@@ -1363,7 +2653,7 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
                             let child = page.child();
                             if editor::is_editor(&child) {
                                 let s = settings.borrow();
-                                let theme = crate::theme::get_theme(&s.color_scheme);
+                                let theme = crate::theme::active_theme(&s);
                                 if let Some(is_previewing) =
                                     editor::toggle_preview(child.upcast_ref(), theme)
                                 {
@@ -1374,6 +2664,417 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
                     }
                 }),
             ),
+            Command {
+                item: CommandPaletteItem {
+                    id: "toggle_blame".to_string(),
+                    title: "Toggle Blame Gutter".to_string(),
+                    category: "Editor".to_string(),
+                    keywords: vec!["blame".into(), "annotate".into(), "author".into()],
+                    source: CommandPaletteSource::Builtin,
+                    shortcut: None,
+                    payload: Default::default(),
+                },
+                shortcut: String::new(),
+                action: Rc::new({
+                    let tab_view = tab_view.clone();
+                    let toast_overlay = toast_overlay.clone();
+                    move || {
+                        if let Some(page) = tab_view.selected_page() {
+                            let child = page.child();
+                            if let Some(handle) = editor::get_handle_for_widget(&child) {
+                                toggle_blame_gutter(&handle, &toast_overlay);
+                            }
+                        }
+                    }
+                }),
+            },
+            source_action_command(
+                "organize_imports",
+                "Organize Imports",
+                &["imports", "sort"],
+                "Shift+Alt+O",
+                "editor.action.organizeImports",
+                &tab_view,
+            ),
+            source_action_command(
+                "fix_all",
+                "Fix All",
+                &["lint", "autofix"],
+                "Shift+Alt+.",
+                "editor.action.fixAll",
+                &tab_view,
+            ),
+            source_action_command(
+                "source_action",
+                "Source Action…",
+                &["code action", "refactor"],
+                "Ctrl+Shift+.",
+                "editor.action.sourceAction",
+                &tab_view,
+            ),
+            git_remote_command(
+                GitRemoteOp::Fetch,
+                &sidebar_state.current_path,
+                &status_bar,
+                &toast_overlay,
+                &git_remote_busy,
+            ),
+            git_remote_command(
+                GitRemoteOp::Pull,
+                &sidebar_state.current_path,
+                &status_bar,
+                &toast_overlay,
+                &git_remote_busy,
+            ),
+            git_remote_command(
+                GitRemoteOp::Push,
+                &sidebar_state.current_path,
+                &status_bar,
+                &toast_overlay,
+                &git_remote_busy,
+            ),
+            Command {
+                item: CommandPaletteItem {
+                    id: "open_conflicted_files".to_string(),
+                    title: "Git: Open Conflicted Files".to_string(),
+                    category: "Editor".to_string(),
+                    keywords: vec!["merge".into(), "conflict".into(), "resolve".into()],
+                    source: CommandPaletteSource::Builtin,
+                    shortcut: None,
+                    payload: Default::default(),
+                },
+                shortcut: String::new(),
+                action: Rc::new({
+                    let sidebar_state = sidebar_state.clone();
+                    let toast_overlay = toast_overlay.clone();
+                    move || {
+                        let root = sidebar_state.current_path.borrow().clone();
+                        let files =
+                            impulse_core::git::list_conflicted_files(&root).unwrap_or_default();
+                        if files.is_empty() {
+                            let toast = adw::Toast::new("No merge conflicts");
+                            toast.set_timeout(3);
+                            toast_overlay.add_toast(toast);
+                            return;
+                        }
+                        if let Some(cb) = sidebar_state.on_file_activated.borrow().as_ref() {
+                            for file in &files {
+                                cb(file);
+                            }
+                        }
+                    }
+                }),
+            },
+            Command {
+                item: CommandPaletteItem {
+                    id: "compare_refs".to_string(),
+                    title: "Git: Compare Revisions...".to_string(),
+                    category: "Editor".to_string(),
+                    keywords: vec![
+                        "diff".into(),
+                        "branch".into(),
+                        "commit".into(),
+                        "tag".into(),
+                    ],
+                    source: CommandPaletteSource::Builtin,
+                    shortcut: None,
+                    payload: Default::default(),
+                },
+                shortcut: String::new(),
+                action: Rc::new({
+                    let sidebar_state = sidebar_state.clone();
+                    let toast_overlay = toast_overlay.clone();
+                    let tab_view = tab_view.clone();
+                    let settings = settings.clone();
+                    move || {
+                        let root = sidebar_state.current_path.borrow().clone();
+                        let Some(repo_root) = impulse_core::git::get_git_root(&root) else {
+                            let toast = adw::Toast::new("Not in a git repository");
+                            toast.set_timeout(3);
+                            toast_overlay.add_toast(toast);
+                            return;
+                        };
+                        let refs = impulse_core::git::list_git_refs(&repo_root).unwrap_or_default();
+                        let tab_view = tab_view.clone();
+                        let settings = settings.clone();
+                        dialogs::show_compare_refs_dialog(
+                            &toast_overlay,
+                            refs,
+                            move |base, target| {
+                                let theme = crate::theme::active_theme(&settings.borrow());
+                                let child = crate::review_tab::create_ref_compare_tab(
+                                    &repo_root, &base, &target, theme,
+                                );
+                                let page = tab_management::insert_after_selected(&tab_view, &child);
+                                page.set_title(&format!("{base}..{target}"));
+                                tab_view.set_selected_page(&page);
+                            },
+                        );
+                    }
+                }),
+            },
+            Command {
+                item: CommandPaletteItem {
+                    id: "worktrees".to_string(),
+                    title: "Git: Worktrees...".to_string(),
+                    category: "Editor".to_string(),
+                    keywords: vec!["worktree".into(), "switch".into(), "checkout".into()],
+                    source: CommandPaletteSource::Builtin,
+                    shortcut: None,
+                    payload: Default::default(),
+                },
+                shortcut: String::new(),
+                action: Rc::new({
+                    let app = app.clone();
+                    let sidebar_state = sidebar_state.clone();
+                    let toast_overlay = toast_overlay.clone();
+                    move || {
+                        let root = sidebar_state.current_path.borrow().clone();
+                        let Some(repo_root) = impulse_core::git::get_git_root(&root) else {
+                            let toast = adw::Toast::new("Not in a git repository");
+                            toast.set_timeout(3);
+                            toast_overlay.add_toast(toast);
+                            return;
+                        };
+                        let worktrees = match impulse_core::git::list_worktrees(&repo_root) {
+                            Ok(worktrees) => worktrees,
+                            Err(e) => {
+                                let toast = adw::Toast::new(&e);
+                                toast.set_timeout(3);
+                                toast_overlay.add_toast(toast);
+                                return;
+                            }
+                        };
+                        let open_app = app.clone();
+                        let app = app.clone();
+                        let overlay = toast_overlay.clone();
+                        dialogs::show_worktrees_dialog(
+                            &toast_overlay,
+                            worktrees,
+                            move |path| build_window(&open_app, Some(vec![path])),
+                            move || prompt_create_worktree(&app, &overlay, &repo_root),
+                        );
+                    }
+                }),
+            },
+            Command {
+                item: CommandPaletteItem {
+                    id: "create_worktree".to_string(),
+                    title: "Git: Create Worktree...".to_string(),
+                    category: "Editor".to_string(),
+                    keywords: vec!["worktree".into(), "branch".into()],
+                    source: CommandPaletteSource::Builtin,
+                    shortcut: None,
+                    payload: Default::default(),
+                },
+                shortcut: String::new(),
+                action: Rc::new({
+                    let app = app.clone();
+                    let sidebar_state = sidebar_state.clone();
+                    let toast_overlay = toast_overlay.clone();
+                    move || {
+                        let root = sidebar_state.current_path.borrow().clone();
+                        match impulse_core::git::get_git_root(&root) {
+                            Some(repo_root) => {
+                                prompt_create_worktree(&app, &toast_overlay, &repo_root)
+                            }
+                            None => {
+                                let toast = adw::Toast::new("Not in a git repository");
+                                toast.set_timeout(3);
+                                toast_overlay.add_toast(toast);
+                            }
+                        }
+                    }
+                }),
+            },
+            Command {
+                item: CommandPaletteItem {
+                    id: "copy_remote_link".to_string(),
+                    title: "Git: Copy Remote Link".to_string(),
+                    category: "Editor".to_string(),
+                    keywords: vec![
+                        "github".into(),
+                        "gitlab".into(),
+                        "permalink".into(),
+                        "url".into(),
+                    ],
+                    source: CommandPaletteSource::Builtin,
+                    shortcut: None,
+                    payload: Default::default(),
+                },
+                shortcut: String::new(),
+                action: Rc::new({
+                    let window_ref = window_ref.clone();
+                    let tab_view = tab_view.clone();
+                    let sidebar_state = sidebar_state.clone();
+                    let toast_overlay = toast_overlay.clone();
+                    move || {
+                        let root = sidebar_state.current_path.borrow().clone();
+                        let message = match remote_link(&tab_view, &root) {
+                            Ok(url) => {
+                                window_ref.clipboard().set_text(&url);
+                                "Copied remote link".to_string()
+                            }
+                            Err(e) => e,
+                        };
+                        let toast = adw::Toast::new(&message);
+                        toast.set_timeout(3);
+                        toast_overlay.add_toast(toast);
+                    }
+                }),
+            },
+            Command {
+                item: CommandPaletteItem {
+                    id: "open_on_remote".to_string(),
+                    title: "Git: Open on Remote".to_string(),
+                    category: "Editor".to_string(),
+                    keywords: vec![
+                        "github".into(),
+                        "gitlab".into(),
+                        "browser".into(),
+                        "permalink".into(),
+                    ],
+                    source: CommandPaletteSource::Builtin,
+                    shortcut: None,
+                    payload: Default::default(),
+                },
+                shortcut: String::new(),
+                action: Rc::new({
+                    let tab_view = tab_view.clone();
+                    let sidebar_state = sidebar_state.clone();
+                    let toast_overlay = toast_overlay.clone();
+                    move || {
+                        let root = sidebar_state.current_path.borrow().clone();
+                        let result = remote_link(&tab_view, &root)
+                            .and_then(|url| open::that(&url).map_err(|e| e.to_string()));
+                        if let Err(e) = result {
+                            let toast = adw::Toast::new(&e);
+                            toast.set_timeout(3);
+                            toast_overlay.add_toast(toast);
+                        }
+                    }
+                }),
+            },
+            Command {
+                item: CommandPaletteItem {
+                    id: "compare_files".to_string(),
+                    title: "Compare Files...".to_string(),
+                    category: "Editor".to_string(),
+                    keywords: vec!["diff".into(), "compare".into()],
+                    source: CommandPaletteSource::Builtin,
+                    shortcut: None,
+                    payload: Default::default(),
+                },
+                shortcut: String::new(),
+                action: Rc::new({
+                    let window_ref = window_ref.clone();
+                    let tab_view = tab_view.clone();
+                    let settings = settings.clone();
+                    let sidebar_state = sidebar_state.clone();
+                    let toast_overlay = toast_overlay.clone();
+                    move || {
+                        // Compare the active editor's file against a chosen
+                        // file, or pick both when no file is open.
+                        let active = tab_view
+                            .selected_page()
+                            .map(|page| page.child())
+                            .filter(editor::is_editor)
+                            .map(|child| child.widget_name().to_string())
+                            .filter(|path| std::path::Path::new(path).is_file());
+                        let dialog = gtk4::FileDialog::new();
+                        dialog.set_title(if active.is_some() {
+                            "Compare With"
+                        } else {
+                            "Select Two Files to Compare"
+                        });
+                        let folder = sidebar_state.current_path.borrow().clone();
+                        if !folder.is_empty() {
+                            dialog.set_initial_folder(Some(&gtk4::gio::File::for_path(&folder)));
+                        }
+                        let tab_view = tab_view.clone();
+                        let settings = settings.clone();
+                        let toast_overlay = toast_overlay.clone();
+                        let open_compare = move |old: String, new: String| {
+                            tab_management::open_file_compare(&tab_view, &settings, &old, &new);
+                        };
+                        match active {
+                            Some(active) => dialog.open(
+                                Some(&window_ref),
+                                gtk4::gio::Cancellable::NONE,
+                                move |result| {
+                                    if let Some(path) = result.ok().and_then(|f| f.path()) {
+                                        open_compare(active, path.to_string_lossy().to_string());
+                                    }
+                                },
+                            ),
+                            None => dialog.open_multiple(
+                                Some(&window_ref),
+                                gtk4::gio::Cancellable::NONE,
+                                move |result| {
+                                    let Ok(files) = result else {
+                                        return;
+                                    };
+                                    let paths: Vec<String> = (0..files.n_items())
+                                        .filter_map(|i| files.item(i))
+                                        .filter_map(|f| f.downcast::<gtk4::gio::File>().ok())
+                                        .filter_map(|f| f.path())
+                                        .map(|p| p.to_string_lossy().to_string())
+                                        .collect();
+                                    if let [old, new] = paths.as_slice() {
+                                        open_compare(old.clone(), new.clone());
+                                    } else {
+                                        let toast = adw::Toast::new("Select exactly two files");
+                                        toast.set_timeout(3);
+                                        toast_overlay.add_toast(toast);
+                                    }
+                                },
+                            ),
+                        }
+                    }
+                }),
+            },
+            make_palette_builtin_command(
+                &builtin_items_by_id,
+                "toggle_assistant",
+                String::new(),
+                Rc::new({
+                    let assistant_panel = assistant_panel.clone();
+                    move || assistant_panel.toggle()
+                }),
+            ),
+            make_palette_builtin_command(
+                &builtin_items_by_id,
+                "assistant_explain_selection",
+                String::new(),
+                Rc::new({
+                    let tab_view = tab_view.clone();
+                    let assistant_panel = assistant_panel.clone();
+                    let toast_overlay = toast_overlay.clone();
+                    move || {
+                        let Some(page) = tab_view.selected_page() else {
+                            return;
+                        };
+                        let Some(handle) = editor::get_handle_for_widget(&page.child()) else {
+                            return;
+                        };
+                        let language = handle.language.borrow().clone();
+                        let path = tab_management::file_path_for_page(&page);
+                        let assistant_panel = assistant_panel.clone();
+                        let toast_overlay = toast_overlay.clone();
+                        handle.get_selection(move |result| match result {
+                            Ok(code) if !code.trim().is_empty() => {
+                                assistant_panel.explain(&code, &language, path.as_deref());
+                            }
+                            Ok(_) => {
+                                let toast = adw::Toast::new("Select some code to explain");
+                                toast.set_timeout(2);
+                                toast_overlay.add_toast(toast);
+                            }
+                            Err(e) => log::warn!("Failed to read the selection: {}", e),
+                        });
+                    }
+                }),
+            ),
             make_palette_builtin_command(
                 &builtin_items_by_id,
                 "install_lsp",
@@ -1381,6 +3082,7 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
                 Rc::new({
                     let toast_overlay = toast_overlay.clone();
                     let lsp_install_result_tx = lsp_install_result_tx.clone();
+                    let settings = settings.clone();
                     move || {
                         let start_toast = adw::Toast::new(
                             "Installing web LSP servers (TypeScript, PHP, HTML/CSS, etc.)...",
@@ -1389,18 +3091,16 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
                         toast_overlay.add_toast(start_toast);
 
                         let tx = lsp_install_result_tx.clone();
+                        let disabled = settings.borrow().disabled_lsp_servers.clone();
                         std::thread::spawn(move || {
                             if let Err(e) =
                                 std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
-                                    let result =
-                                        impulse_core::lsp::install_managed_web_lsp_servers().map(
-                                            |bin_dir| {
-                                                format!(
-                                                    "Installed managed LSP servers to {}",
-                                                    bin_dir.display()
-                                                )
-                                            },
-                                        );
+                                    let result = impulse_core::lsp_install::run(
+                                        impulse_core::lsp_install::InstallAction::Install,
+                                        &[],
+                                        &disabled,
+                                        |_| {},
+                                    );
                                     let _ = tx.send(result);
                                 }))
                             {
@@ -1412,6 +3112,30 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
             ),
         ];
 
+        // Copy Path and its variants, for the active editor's file.
+        for (_, id) in sidebar::COPY_PATH_ACTIONS {
+            let tab_view = tab_view.clone();
+            let sidebar_state = sidebar_state.clone();
+            let window_ref = window_ref.clone();
+            result.push(make_palette_builtin_command(
+                &builtin_items_by_id,
+                id,
+                String::new(),
+                Rc::new(move || {
+                    let Some(path) = tab_view
+                        .selected_page()
+                        .and_then(|page| tab_management::file_path_for_page(&page))
+                    else {
+                        return;
+                    };
+                    let root = sidebar_state.current_path.borrow().clone();
+                    if let Some(text) = impulse_core::util::path_for_copy(id, &path, &root) {
+                        window_ref.clipboard().set_text(&text);
+                    }
+                }),
+            ));
+        }
+
         for kb in settings.borrow().custom_keybindings.clone() {
             if kb.name.trim().is_empty() || kb.command.trim().is_empty() {
                 continue;
@@ -1436,7 +3160,7 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
                 shortcut,
                 action: Rc::new(move || {
                     let cwd = get_active_cwd(&tab_view);
-                    let theme = crate::theme::get_theme(&settings.borrow().color_scheme);
+                    let theme = crate::theme::active_theme(&settings.borrow());
                     let term = terminal::create_terminal(
                         &settings.borrow(),
                         theme,
@@ -1461,6 +3185,106 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
     };
     let command_recents = Rc::new(RefCell::new(RecentCommandStore::default()));
 
+    // Notification actions and the control socket's `run-command` name
+    // registry commands.
+    {
+        let actions: Rc<HashMap<String, Rc<dyn Fn()>>> = Rc::new(
+            commands
+                .iter()
+                .map(|c| (c.item.id.clone(), c.action.clone()))
+                .collect(),
+        );
+        {
+            let actions = actions.clone();
+            status_bar
+                .borrow()
+                .set_command_runner(Rc::new(move |id: &str| match actions.get(id) {
+                    Some(action) => action(),
+                    None => log::warn!("Unknown command in notification action: {}", id),
+                }));
+        }
+        let action = gio::SimpleAction::new("run-command", Some(gtk4::glib::VariantTy::STRING));
+        action.connect_activate(move |_, param| {
+            let Some(id) = param.and_then(|v| v.get::<String>()) else {
+                return;
+            };
+            match actions.get(&id) {
+                Some(action) => action(),
+                None => log::warn!("Unknown command: {}", id),
+            }
+        });
+        window.add_action(&action);
+    }
+
+    // The working directory breadcrumb opens directories in the sidebar and
+    // moves the selected terminal through its directory history.
+    {
+        let selected_terminal = {
+            let tab_view = tab_view.clone();
+            move || {
+                tab_view
+                    .selected_page()
+                    .and_then(|page| terminal_container::get_active_terminal(&page.child()))
+            }
+        };
+        let sidebar_state = sidebar_state.clone();
+        let sidebar_btn = sidebar_btn.clone();
+        let history_terminal = selected_terminal.clone();
+        status_bar.borrow().set_cwd_actions(status_bar::CwdActions {
+            open_directory: Rc::new(move |dir: &str| {
+                sidebar_btn.set_active(true);
+                sidebar_state.search_btn.set_active(false);
+                sidebar_state.load_directory(dir);
+            }),
+            history: Rc::new(move || {
+                history_terminal()
+                    .map(|term| terminal::directory_history(&term))
+                    .unwrap_or_default()
+            }),
+            change_directory: Rc::new(move |dir: &str| {
+                if let Some(term) = selected_terminal() {
+                    terminal::write_text(&term, &format!("cd '{}'\n", dir.replace('\'', "'\\''")));
+                    term.grab_focus();
+                }
+            }),
+        });
+    }
+
+    // Project search scopes: open files are searched in their buffers, and
+    // the current directory is the selected terminal's or file's.
+    {
+        let tab_view = tab_view.clone();
+        *sidebar_state.project_search.open_files.borrow_mut() = Some(Box::new(move || {
+            (0..tab_view.n_pages())
+                .filter_map(|i| {
+                    let page = tab_view.nth_page(i);
+                    let path = tab_management::file_path_for_page(&page)?;
+                    let handle = editor::get_handle_for_widget(&page.child())?;
+                    Some(impulse_core::search::OpenFile {
+                        path,
+                        content: handle.get_content(),
+                    })
+                })
+                .collect()
+        }));
+    }
+    {
+        let tab_view = tab_view.clone();
+        *sidebar_state.project_search.current_directory.borrow_mut() = Some(Box::new(move || {
+            let page = tab_view.selected_page()?;
+            if let Some(term) = terminal_container::get_active_terminal(&page.child()) {
+                return terminal::current_directory(&term);
+            }
+            let path = tab_management::file_path_for_page(&page)?;
+            Some(
+                std::path::Path::new(&path)
+                    .parent()?
+                    .to_string_lossy()
+                    .to_string(),
+            )
+        }));
+    }
+
     keybinding_setup::setup_shortcut_controller(
         &ctx,
         &term_ctx,
@@ -1589,8 +3413,10 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
     }
 
     tab_management::setup_tab_switch_handler(&tab_view, &status_bar, &sidebar_state, &settings);
+    zoom::setup_scroll_zoom(&tab_view, &settings, &status_bar);
 
     tab_management::setup_tab_close_handler(&ctx, &create_tab, &closed_tabs);
+    tab_transfer::setup_tab_transfer(&ctx, &setup_terminal_signals);
 
     // Initial tabs were created before the context bar's tab-switch handler
     // was connected, so evaluate its state once now.
@@ -1599,16 +3425,49 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
     // Save settings when window is closed
     {
         let paned = paned.clone();
-        let sidebar_btn = sidebar_btn.clone();
+        let layout = ctx.layout.clone();
         let sidebar_state = sidebar_state.clone();
         let tab_view_ref = tab_view.clone();
-        let font_size = font_size.clone();
         let settings = settings.clone();
         let lsp_tx = lsp_request_tx.clone();
+        let toast_overlay = toast_overlay.clone();
+        let editor_tab_pages = ctx.editor_tab_pages.clone();
         let close_confirmed = Rc::new(Cell::new(false));
         window.connect_close_request(move |window| {
-            if !close_confirmed.get() && settings.borrow().confirm_close_warnings {
+            if !close_confirmed.get() {
                 let summary = close_risk_summary_for_tab_view(&tab_view_ref, &settings.borrow());
+                if !summary.unsaved_files.is_empty() {
+                    let window_for_response = window.clone();
+                    let tab_view = tab_view_ref.clone();
+                    let lsp_tx = lsp_tx.clone();
+                    let settings = settings.clone();
+                    let toast_overlay = toast_overlay.clone();
+                    let editor_tab_pages = editor_tab_pages.clone();
+                    let close_confirmed = close_confirmed.clone();
+                    dialogs::show_unsaved_changes_dialog(window, &summary, move |to_save| {
+                        // Keep the window open if any chosen file fails to
+                        // save; the failure has been reported.
+                        let all_saved = modified_editor_pages(&tab_view)
+                            .iter()
+                            .filter(|page| {
+                                to_save.contains(&page.child().widget_name().to_string())
+                            })
+                            .all(|page| {
+                                save_editor_page(
+                                    page,
+                                    &lsp_tx,
+                                    &settings.borrow(),
+                                    &toast_overlay,
+                                    &editor_tab_pages,
+                                )
+                            });
+                        if all_saved {
+                            close_confirmed.set(true);
+                            window_for_response.close();
+                        }
+                    });
+                    return gtk4::glib::Propagation::Stop;
+                }
                 if summary.has_risk {
                     let dialog = adw::AlertDialog::builder()
                         .heading(&summary.title)
@@ -1638,6 +3497,11 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
             if let Err(e) = lsp_tx.try_send(LspRequest::Shutdown) {
                 log::warn!("LSP request channel full, dropping shutdown request: {}", e);
             }
+            // A window whose last tab was dragged away has nothing to save,
+            // and saving would overwrite the state of the window it went to.
+            if tab_view_ref.n_pages() == 0 {
+                return gtk4::glib::Propagation::Proceed;
+            }
             // Collect open editor file paths
             let mut open_files = Vec::new();
             let n = tab_view_ref.n_pages();
@@ -1657,10 +3521,9 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
                 let mut s = settings.borrow_mut();
                 s.window_width = window.width();
                 s.window_height = window.height();
-                s.sidebar_visible = sidebar_btn.is_active();
+                s.sidebar_visible = layout.sidebar_visible_to_save();
                 s.sidebar_width = paned.position();
                 s.last_directory = sidebar_state.current_path.borrow().clone();
-                s.font_size = font_size.get();
                 s.open_files = open_files;
             }
             crate::settings::save(&settings.borrow());
@@ -1672,26 +3535,141 @@ pub fn build_window(app: &adw::Application, initial_files: Option<Vec<String>>)
         });
     }
 
-    // Check for updates in background if enabled.
+    // Check for updates in background if enabled, installing them when
+    // asked to and this copy can update itself.
     if settings.borrow().check_for_updates {
-        let result = std::sync::Arc::new(std::sync::Mutex::new(None::<(String, String)>));
+        let auto_install = settings.borrow().auto_install_updates;
+        let result = std::sync::Arc::new(std::sync::Mutex::new(None::<(String, bool)>));
         let result_writer = std::sync::Arc::clone(&result);
         std::thread::spawn(move || {
             if let Ok(Some(info)) = impulse_core::update::check_for_update() {
-                *result_writer.lock().unwrap() = Some((info.version, info.url));
+                let installed = auto_install
+                    && info.can_install(&crate::updater::install_kind())
+                    && crate::updater::install(&info)
+                        .inspect_err(|e| log::warn!("Update failed: {}", e))
+                        .is_ok();
+                *result_writer.lock().unwrap() = Some((info.version, installed));
             }
         });
         let status_bar_for_update = Rc::clone(&status_bar);
+        let window_for_update = window.clone();
         gtk4::glib::timeout_add_local(std::time::Duration::from_secs(2), move || {
-            if let Some((version, url)) = result.lock().unwrap().take() {
-                status_bar_for_update.borrow().show_update(&version, &url);
+            if let Some((version, installed)) = result.lock().unwrap().take() {
+                let window = window_for_update.clone();
+                if installed {
+                    status_bar_for_update.borrow().show_update(
+                        "⟳ Restart to Update",
+                        &format!(
+                            "Impulse {} is installed. Restart to start using it.",
+                            version
+                        ),
+                        move || crate::updater::restart(&window),
+                    );
+                } else {
+                    status_bar_for_update.borrow().show_update(
+                        &format!("⬆ Update v{}", version),
+                        &format!("Impulse {} is available", version),
+                        move || crate::updater::check_now(&window),
+                    );
+                }
                 return gtk4::glib::ControlFlow::Break;
             }
             gtk4::glib::ControlFlow::Continue
         });
     }
 
+    impulse_core::startup_profile::mark("window built");
     window.present();
+    impulse_core::startup_profile::mark("window presented");
+    window.add_tick_callback(|_, _| {
+        impulse_core::startup_profile::finish("first frame");
+        gtk4::glib::ControlFlow::Break
+    });
+
+    {
+        let sidebar_state = sidebar_state.clone();
+        gtk4::glib::idle_add_local_once(move || {
+            if !sidebar_state.loaded.get() {
+                let path = sidebar_state.current_path.borrow().clone();
+                sidebar_state.load_directory(&path);
+            }
+        });
+    }
+
+    let recovered = crate::hot_exit::take_recovered();
+    if !recovered.is_empty() {
+        offer_hot_exit_restore(&window, &tab_view, &sidebar_state, recovered);
+    }
+
+    tab_view
+}
+
+/// After a crash, ask whether to reopen the unsaved changes the last run
+/// backed up. Files reopen with the recovered content as unsaved edits;
+/// buffers that were never saved (or whose file is gone) come back as
+/// untitled tabs.
+fn offer_hot_exit_restore(
+    window: &adw::ApplicationWindow,
+    tab_view: &adw::TabView,
+    sidebar_state: &Rc<sidebar::SidebarState>,
+    recovered: Vec<impulse_core::hot_exit::Backup>,
+) {
+    let names: Vec<String> = recovered
+        .iter()
+        .map(|b| {
+            if editor::is_untitled_path(&b.path) {
+                "Untitled".to_string()
+            } else {
+                std::path::Path::new(&b.path)
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_else(|| b.path.clone())
+            }
+        })
+        .collect();
+    let dialog = adw::AlertDialog::builder()
+        .heading("Restore Unsaved Changes?")
+        .body(format!(
+            "Impulse didn't exit cleanly. Unsaved changes were recovered for:\n\n{}",
+            names.join("\n")
+        ))
+        .build();
+    dialog.add_response("discard", "Discard");
+    dialog.add_response("restore", "Restore");
+    dialog.set_response_appearance("discard", adw::ResponseAppearance::Destructive);
+    dialog.set_response_appearance("restore", adw::ResponseAppearance::Suggested);
+    dialog.set_default_response(Some("restore"));
+    dialog.set_close_response("discard");
+
+    let window_ref = window.clone();
+    let tab_view = tab_view.clone();
+    let sidebar_state = sidebar_state.clone();
+    dialog.connect_response(None, move |_dialog, response| {
+        if response != "restore" {
+            crate::hot_exit::discard_recovered();
+            return;
+        }
+        for backup in &recovered {
+            let handle = if !editor::is_untitled_path(&backup.path)
+                && std::path::Path::new(&backup.path).is_file()
+            {
+                if let Some(cb) = sidebar_state.on_file_activated.borrow().as_ref() {
+                    cb(&backup.path);
+                }
+                editor::get_handle(&backup.path)
+            } else {
+                let _ = WidgetExt::activate_action(&window_ref, "win.new-file", None);
+                tab_view
+                    .selected_page()
+                    .and_then(|page| editor::get_handle_for_widget(&page.child()))
+            };
+            match handle {
+                Some(handle) => handle.restore_content(backup.content.clone()),
+                None => log::warn!("Could not reopen {} to restore it", backup.path),
+            }
+        }
+    });
+    dialog.present(Some(window));
 }
 
 fn settings_load_warning_banner(warning: crate::settings::SettingsLoadWarning) -> gtk4::Revealer {
@@ -1742,7 +3720,78 @@ fn settings_load_warning_banner(warning: crate::settings::SettingsLoadWarning) -
             );
         });
     }
-    row.append(&open_button);
+    row.append(&open_button);
+
+    let dismiss_button = gtk4::Button::from_icon_name("window-close-symbolic");
+    dismiss_button.set_tooltip_text(Some("Dismiss"));
+    dismiss_button.add_css_class("settings-error-dismiss");
+    {
+        let revealer = revealer.clone();
+        dismiss_button.connect_clicked(move |_| {
+            revealer.set_reveal_child(false);
+        });
+    }
+    row.append(&dismiss_button);
+
+    revealer.set_child(Some(&row));
+    revealer
+}
+
+/// "Impulse quit unexpectedly" banner for the newest crash report of the
+/// previous session. The report is sent right away when the user opted in;
+/// otherwise the viewer offers to send it.
+fn crash_report_banner(
+    window: &adw::ApplicationWindow,
+    report: impulse_core::crash_report::CrashReport,
+    settings: &crate::settings::Settings,
+) -> gtk4::Revealer {
+    let endpoint = settings.crash_report_endpoint.trim().to_string();
+    let auto_sent = settings.send_crash_reports && !endpoint.is_empty();
+    if auto_sent {
+        submit_crash_report(report.clone(), endpoint.clone());
+    }
+
+    let revealer = gtk4::Revealer::new();
+    revealer.set_transition_type(gtk4::RevealerTransitionType::SlideDown);
+    revealer.set_reveal_child(true);
+
+    let row = gtk4::Box::new(gtk4::Orientation::Horizontal, 10);
+    row.add_css_class("settings-error-banner");
+
+    let icon = gtk4::Image::from_icon_name("dialog-warning-symbolic");
+    row.append(&icon);
+
+    let text_box = gtk4::Box::new(gtk4::Orientation::Vertical, 2);
+    text_box.set_hexpand(true);
+
+    let title = gtk4::Label::new(Some("Impulse quit unexpectedly last time"));
+    title.set_xalign(0.0);
+    title.add_css_class("settings-error-title");
+    text_box.append(&title);
+
+    let detail_text = if auto_sent {
+        "A crash report was saved and sent."
+    } else {
+        "A crash report was saved. Nothing is sent unless you choose to."
+    };
+    let detail = gtk4::Label::new(Some(detail_text));
+    detail.set_xalign(0.0);
+    detail.set_wrap(true);
+    detail.add_css_class("settings-error-detail");
+    text_box.append(&detail);
+
+    row.append(&text_box);
+
+    let view_button = gtk4::Button::with_label("View Report");
+    view_button.add_css_class("settings-error-action");
+    {
+        let window = window.clone();
+        let can_send = !auto_sent && !endpoint.is_empty();
+        view_button.connect_clicked(move |_| {
+            show_crash_report(&window, &report, can_send.then(|| endpoint.clone()));
+        });
+    }
+    row.append(&view_button);
 
     let dismiss_button = gtk4::Button::from_icon_name("window-close-symbolic");
     dismiss_button.set_tooltip_text(Some("Dismiss"));
@@ -1759,32 +3808,91 @@ fn settings_load_warning_banner(warning: crate::settings::SettingsLoadWarning) -
     revealer
 }
 
+/// Crash report viewer. `endpoint` is set when the report can still be sent.
+fn show_crash_report(
+    window: &adw::ApplicationWindow,
+    report: &impulse_core::crash_report::CrashReport,
+    endpoint: Option<String>,
+) {
+    let text = report.to_text();
+
+    let buffer = gtk4::TextBuffer::new(None);
+    buffer.set_text(&text);
+    let text_view = gtk4::TextView::with_buffer(&buffer);
+    text_view.set_editable(false);
+    text_view.set_monospace(true);
+    text_view.set_wrap_mode(gtk4::WrapMode::WordChar);
+    let scrolled = gtk4::ScrolledWindow::new();
+    scrolled.set_child(Some(&text_view));
+    scrolled.set_min_content_height(320);
+    scrolled.set_min_content_width(560);
+
+    let dialog = adw::AlertDialog::builder()
+        .heading("Crash Report")
+        .body("This is everything the report contains.")
+        .extra_child(&scrolled)
+        .build();
+    dialog.add_response("copy", "Copy");
+    dialog.add_response("close", "Close");
+    if endpoint.is_some() {
+        dialog.add_response("send", "Send Report");
+        dialog.set_response_appearance("send", adw::ResponseAppearance::Suggested);
+    }
+    dialog.set_default_response(Some("close"));
+    dialog.set_close_response("close");
+
+    let window_ref = window.clone();
+    let report = report.clone();
+    dialog.connect_response(None, move |_dialog, response| match response {
+        "copy" => window_ref.clipboard().set_text(&text),
+        "send" => {
+            if let Some(endpoint) = endpoint.clone() {
+                submit_crash_report(report.clone(), endpoint);
+            }
+        }
+        _ => {}
+    });
+    dialog.present(Some(window));
+}
+
+fn submit_crash_report(report: impulse_core::crash_report::CrashReport, endpoint: String) {
+    std::thread::spawn(
+        move || match impulse_core::crash_report::submit(&report, &endpoint) {
+            Ok(()) => log::info!("Crash report sent to {}", endpoint),
+            Err(e) => log::warn!("{}", e),
+        },
+    );
+}
+
+/// What closing the window would lose. Running terminal work is only
+/// included when `confirm_close_warnings` is on; unsaved editors always are.
 fn close_risk_summary_for_tab_view(
     tab_view: &adw::TabView,
     settings: &crate::settings::Settings,
 ) -> impulse_core::close_risk::CloseRiskSummary {
-    let mut unsaved_editor_count = 0usize;
+    let unsaved_files = modified_editor_pages(tab_view)
+        .iter()
+        .map(|page| page.child().widget_name().to_string())
+        .collect();
     let mut running_commands = Vec::new();
-
-    let n = tab_view.n_pages();
-    for i in 0..n {
-        let page = tab_view.nth_page(i);
-        let child = page.child();
-        if editor::is_editor(&child) && editor::is_modified(&child) {
-            unsaved_editor_count += 1;
-        }
-        for term in terminal_container::collect_terminals(&child) {
-            if let Some(command) = terminal::running_close_risk_command(&term) {
-                running_commands.push(command);
-            }
+    let mut running_terminal_process_count = 0;
+
+    if settings.confirm_close_warnings {
+        for i in 0..tab_view.n_pages() {
+            let child = tab_view.nth_page(i).child();
+            let (process_count, commands) = tab_management::terminal_close_risk(&child, settings);
+            running_terminal_process_count += process_count;
+            running_commands.extend(commands);
         }
     }
 
     impulse_core::close_risk::summarize_close_risk(&impulse_core::close_risk::CloseRiskInput {
         action: impulse_core::close_risk::CloseRiskAction::CloseWindow,
-        unsaved_editor_count,
-        running_terminal_process_count: 0,
+        unsaved_editor_count: 0,
+        unsaved_files,
+        running_terminal_process_count,
         running_commands,
+        ignored_commands: settings.terminal_close_ignored_commands.clone(),
         now_ms: current_unix_time_ms(),
         long_command_threshold_seconds: settings.terminal_long_command_seconds.max(1) as u64,
     })
@@ -1839,11 +3947,20 @@ fn restore_session_window(
                         cb(&editor_tab.path);
                         restored_any = true;
                     }
+                    // Opening selects the file's tab.
+                    let page = tab_view.selected_page().filter(|page| {
+                        tab_management::file_path_for_page(page).as_deref()
+                            == Some(editor_tab.path.as_str())
+                    });
+                    if let Some(page) = page.filter(|_| editor_tab.pinned) {
+                        tab_management::set_preview(&page, false);
+                        tab_view.set_page_pinned(&page, true);
+                    }
                 }
             }
             impulse_core::session_state::SessionTab::Terminal(terminal_tab) => {
                 let s = settings.borrow();
-                let theme = crate::theme::get_theme(&s.color_scheme);
+                let theme = crate::theme::active_theme(&s);
                 let container = terminal_container::TerminalContainer::from_session_tab(
                     terminal_tab,
                     setup_terminal_signals.as_ref(),
@@ -1910,7 +4027,7 @@ fn session_state_for_tab_view(
                         cursor_line: None,
                         cursor_column: None,
                         scroll_line: None,
-                        pinned: false,
+                        pinned: page.is_pinned(),
                     },
                 ))
             } else {
@@ -1923,7 +4040,7 @@ fn session_state_for_tab_view(
                         cwd: pane.cwd,
                         title: pane.title,
                         shell: pane.shell,
-                        pinned: false,
+                        pinned: page.is_pinned(),
                         panes: Vec::new(),
                         active_pane_index: None,
                         pane_layout: None,
@@ -1948,6 +4065,7 @@ fn session_state_for_tab_view(
             project_root: project_root.and_then(non_empty_string),
             tabs,
             active_tab_index,
+            sidebar: None,
             layout: impulse_core::session_state::SessionLayout::TabGroup(
                 impulse_core::session_state::SessionTabGroupLayout {
                     tab_indices,
@@ -1984,6 +4102,39 @@ pub fn open_files_in_active_window(app: &adw::Application, files: &[String]) {
     }
 }
 
+/// A palette entry that runs one of Monaco's source-action commands in the
+/// active editor. The keybinding lives in the editor, so `shortcut` is only
+/// displayed.
+fn source_action_command(
+    id: &str,
+    title: &str,
+    keywords: &[&str],
+    shortcut: &str,
+    action_id: &'static str,
+    tab_view: &adw::TabView,
+) -> Command {
+    let tab_view = tab_view.clone();
+    Command {
+        item: CommandPaletteItem {
+            id: id.to_string(),
+            title: title.to_string(),
+            category: "Editor".to_string(),
+            keywords: keywords.iter().map(|k| k.to_string()).collect(),
+            source: CommandPaletteSource::Builtin,
+            shortcut: Some(shortcut.to_string()),
+            payload: Default::default(),
+        },
+        shortcut: shortcut.to_string(),
+        action: Rc::new(move || {
+            if let Some(page) = tab_view.selected_page() {
+                if let Some(handle) = editor::get_handle_for_widget(&page.child()) {
+                    handle.run_action(action_id);
+                }
+            }
+        }),
+    }
+}
+
 fn make_palette_builtin_command(
     items_by_id: &HashMap<String, CommandPaletteItem>,
     id: &str,
@@ -2022,27 +4173,14 @@ fn palette_builtin_item(
     item
 }
 
-fn apply_font_size_to_all_terminals(tab_view: &adw::TabView, size: i32, font_family: &str) {
-    let family = if font_family.is_empty() {
-        "JetBrains Mono"
-    } else {
-        font_family
-    };
-    let n = tab_view.n_pages();
-    for i in 0..n {
-        let page = tab_view.nth_page(i);
-        for term in terminal_container::collect_terminals(&page.child()) {
-            terminal::set_font(&term, family, size);
-        }
-    }
-}
-
 pub fn send_diff_decorations(file_path: &str) {
+    // Blame shifts with the same saves that change the diff.
+    refresh_blame_gutter(file_path, None);
     let file_path_owned = file_path.to_string();
     gtk4::glib::spawn_future_local(async move {
         let fp = file_path_owned.clone();
         let result = gtk4::gio::spawn_blocking(move || impulse_core::git::get_file_diff(&fp)).await;
-        let decorations = match result {
+        let (decorations, hunks) = match result {
             Ok(Ok(diff)) => {
                 let mut decos: Vec<impulse_editor::protocol::DiffDecoration> = diff
                     .changed_lines
@@ -2069,17 +4207,382 @@ pub fn send_diff_decorations(file_path: &str) {
                         status: impulse_editor::protocol::DiffStatus::Deleted,
                     });
                 }
-                decos
+                let hunks = diff.hunks.into_iter().map(Into::into).collect();
+                (decos, hunks)
             }
-            _ => vec![],
+            _ => (vec![], vec![]),
         };
         // Re-lookup the handle on the main thread (the Rc may have been dropped during async)
         if let Some(handle) = crate::editor::get_handle(&file_path_owned) {
-            handle.apply_diff_decorations(decorations);
+            handle.apply_diff_decorations(decorations, hunks);
+        }
+    });
+}
+
+/// Show or hide the blame gutter of an editor. Failures (e.g. an untracked
+/// file) are reported as a toast and leave the gutter hidden.
+pub fn toggle_blame_gutter(
+    handle: &crate::editor_webview::MonacoEditorHandle,
+    toast_overlay: &adw::ToastOverlay,
+) {
+    let visible = !handle.blame_visible.get();
+    handle.blame_visible.set(visible);
+    if visible {
+        let path = handle.file_path.borrow().clone();
+        refresh_blame_gutter(&path, Some(toast_overlay.clone()));
+    } else {
+        handle.set_blame_gutter(Vec::new());
+    }
+}
+
+/// Recompute the blame gutter of `file_path` if it is visible. Blame runs off
+/// the main thread and is cached in impulse-core until the file or HEAD changes.
+fn refresh_blame_gutter(file_path: &str, toast_overlay: Option<adw::ToastOverlay>) {
+    let visible = crate::editor::get_handle(file_path).is_some_and(|h| h.blame_visible.get());
+    if !visible {
+        return;
+    }
+    let file_path = file_path.to_string();
+    gtk4::glib::spawn_future_local(async move {
+        let fp = file_path.clone();
+        let result = gtk4::gio::spawn_blocking(move || {
+            let now = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or(0);
+            impulse_core::git::blame_file(&fp)
+                .map(|blame| impulse_editor::blame::build_blame_gutter(&blame, now))
+        })
+        .await;
+        let Some(handle) = crate::editor::get_handle(&file_path) else {
+            return;
+        };
+        if !handle.blame_visible.get() {
+            return;
+        }
+        match result {
+            Ok(Ok(lines)) => handle.set_blame_gutter(lines),
+            Ok(Err(e)) => {
+                log::warn!("Blame failed for {}: {}", file_path, e);
+                handle.blame_visible.set(false);
+                handle.set_blame_gutter(Vec::new());
+                if let Some(toast_overlay) = toast_overlay {
+                    let toast = adw::Toast::new(&format!("Blame unavailable: {}", e));
+                    toast.set_timeout(4);
+                    toast_overlay.add_toast(toast);
+                }
+            }
+            Err(_) => log::warn!("Blame task panicked for {}", file_path),
+        }
+    });
+}
+
+/// Stage a single gutter hunk of `file_path` and report the outcome as a toast.
+pub fn stage_diff_hunk(
+    file_path: &str,
+    new_start: u32,
+    new_lines: u32,
+    toast_overlay: &adw::ToastOverlay,
+) {
+    let file_path = file_path.to_string();
+    let toast_overlay = toast_overlay.clone();
+    gtk4::glib::spawn_future_local(async move {
+        let fp = file_path.clone();
+        let result = gtk4::gio::spawn_blocking(move || {
+            impulse_core::git::stage_hunk(&fp, new_start, new_lines)
+        })
+        .await;
+        let message = match result {
+            Ok(Ok(())) => "Change staged".to_string(),
+            Ok(Err(e)) => {
+                log::warn!("Failed to stage hunk in {}: {}", file_path, e);
+                e
+            }
+            Err(_) => "Failed to stage change".to_string(),
+        };
+        let toast = adw::Toast::new(&message);
+        toast.set_timeout(3);
+        toast_overlay.add_toast(toast);
+    });
+}
+
+/// A git operation that talks to the current branch's remote.
+#[derive(Clone, Copy)]
+enum GitRemoteOp {
+    Fetch,
+    Pull,
+    Push,
+}
+
+impl GitRemoteOp {
+    fn id(self) -> &'static str {
+        match self {
+            GitRemoteOp::Fetch => "git_fetch",
+            GitRemoteOp::Pull => "git_pull",
+            GitRemoteOp::Push => "git_push",
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            GitRemoteOp::Fetch => "Git: Fetch",
+            GitRemoteOp::Pull => "Git: Pull",
+            GitRemoteOp::Push => "Git: Push",
+        }
+    }
+
+    fn verb(self) -> &'static str {
+        match self {
+            GitRemoteOp::Fetch => "Fetching",
+            GitRemoteOp::Pull => "Pulling",
+            GitRemoteOp::Push => "Pushing",
+        }
+    }
+}
+
+fn git_remote_command(
+    op: GitRemoteOp,
+    current_path: &Rc<RefCell<String>>,
+    status_bar: &status_bar::SharedStatusBar,
+    toast_overlay: &adw::ToastOverlay,
+    busy: &Rc<Cell<bool>>,
+) -> Command {
+    Command {
+        item: CommandPaletteItem {
+            id: op.id().to_string(),
+            title: op.title().to_string(),
+            category: "Editor".to_string(),
+            keywords: vec!["git".into(), "remote".into(), "sync".into()],
+            source: CommandPaletteSource::Builtin,
+            shortcut: None,
+            payload: Default::default(),
+        },
+        shortcut: String::new(),
+        action: Rc::new({
+            let current_path = current_path.clone();
+            let status_bar = status_bar.clone();
+            let toast_overlay = toast_overlay.clone();
+            let busy = busy.clone();
+            move || {
+                let repo_path = current_path.borrow().clone();
+                run_git_remote_op(op, repo_path, &status_bar, &toast_overlay, &busy);
+            }
+        }),
+    }
+}
+
+/// Run `op` on a background thread, mirroring its progress in the status
+/// bar. Only one remote operation runs at a time per window.
+fn run_git_remote_op(
+    op: GitRemoteOp,
+    repo_path: String,
+    status_bar: &status_bar::SharedStatusBar,
+    toast_overlay: &adw::ToastOverlay,
+    busy: &Rc<Cell<bool>>,
+) {
+    if busy.get() {
+        let toast = adw::Toast::new("A git remote operation is already running");
+        toast.set_timeout(3);
+        toast_overlay.add_toast(toast);
+        return;
+    }
+    if impulse_core::git::get_git_root(&repo_path).is_none() {
+        let toast = adw::Toast::new("Not a git repository");
+        toast.set_timeout(3);
+        toast_overlay.add_toast(toast);
+        return;
+    }
+    busy.set(true);
+    status_bar
+        .borrow()
+        .show_git_progress(&format!("{}…", op.verb()));
+
+    let (progress_tx, progress_rx) = std::sync::mpsc::channel();
+    let (prompt_tx, prompt_rx) = std::sync::mpsc::channel::<impulse_core::git::CredentialPrompt>();
+    let (result_tx, result_rx) = std::sync::mpsc::channel::<Result<String, String>>();
+    let thread_path = repo_path.clone();
+    std::thread::spawn(move || {
+        let prompter = Some(&prompt_tx);
+        let result = match op {
+            GitRemoteOp::Fetch => impulse_core::git::fetch(&thread_path, &progress_tx, prompter)
+                .map(|()| "Fetched".into()),
+            GitRemoteOp::Pull => {
+                impulse_core::git::pull(&thread_path, &progress_tx, prompter).map(|outcome| {
+                    match outcome {
+                        impulse_core::git::PullOutcome::UpToDate => "Already up to date".into(),
+                        impulse_core::git::PullOutcome::FastForwarded { commits } => {
+                            format!("Pulled {} commit(s)", commits)
+                        }
+                    }
+                })
+            }
+            GitRemoteOp::Push => impulse_core::git::push(&thread_path, &progress_tx, prompter)
+                .map(|upstream| format!("Pushed to {}", upstream)),
+        };
+        let _ = result_tx.send(result);
+    });
+
+    let status_bar = status_bar.clone();
+    let toast_overlay = toast_overlay.clone();
+    let busy = busy.clone();
+    gtk4::glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
+        // The operation waits on the answer; closing the dialog cancels.
+        if let Ok(prompt) = prompt_rx.try_recv() {
+            let reply = prompt.reply;
+            dialogs::show_git_credentials_dialog(&toast_overlay, &prompt.request, move |answer| {
+                let _ = reply.send(answer);
+            });
+        }
+        if let Some(progress) = progress_rx.try_iter().last() {
+            status_bar.borrow().show_git_progress(&format!(
+                "{}: {}",
+                op.verb(),
+                progress.describe()
+            ));
         }
+        let result = match result_rx.try_recv() {
+            Ok(result) => result,
+            Err(std::sync::mpsc::TryRecvError::Empty) => {
+                return gtk4::glib::ControlFlow::Continue;
+            }
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                Err(format!("{} failed unexpectedly", op.verb()))
+            }
+        };
+        busy.set(false);
+        let status = status_bar.borrow();
+        status.clear_git_progress();
+        status.update_ahead_behind(&repo_path);
+        let message = match result {
+            Ok(message) => message,
+            Err(e) => {
+                log::warn!("{} failed for {}: {}", op.title(), repo_path, e);
+                e
+            }
+        };
+        let toast = adw::Toast::new(&message);
+        toast.set_timeout(4);
+        toast_overlay.add_toast(toast);
+        gtk4::glib::ControlFlow::Break
+    });
+}
+
+/// Open the three-way merge view for a conflicted file. Taking a side
+/// replaces the editor buffer (undoably); marking resolved stages the saved
+/// file.
+pub fn open_merge_view(file_path: &str, toast_overlay: &adw::ToastOverlay) {
+    let file_path = file_path.to_string();
+    let toast_overlay = toast_overlay.clone();
+    gtk4::glib::spawn_future_local(async move {
+        let fp = file_path.clone();
+        let result =
+            gtk4::gio::spawn_blocking(move || impulse_core::git::conflict_versions(&fp)).await;
+        let versions = match result {
+            Ok(Ok(versions)) => versions,
+            Ok(Err(e)) => {
+                let toast = adw::Toast::new(&format!("Cannot open merge view: {}", e));
+                toast.set_timeout(4);
+                toast_overlay.add_toast(toast);
+                return;
+            }
+            Err(_) => return,
+        };
+        let file_name = std::path::Path::new(&file_path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| file_path.clone());
+        let current = versions.current.clone();
+        let incoming = versions.incoming.clone();
+        let parent = toast_overlay.clone();
+        let title = file_name.clone();
+        dialogs::show_merge_view_dialog(&parent, &title, versions, move |action| {
+            let Some(handle) = crate::editor::get_handle(&file_path) else {
+                return;
+            };
+            match action {
+                dialogs::MergeViewAction::UseCurrent => {
+                    if let Some(text) = &current {
+                        handle.replace_content(text);
+                    }
+                }
+                dialogs::MergeViewAction::UseIncoming => {
+                    if let Some(text) = &incoming {
+                        handle.replace_content(text);
+                    }
+                }
+                dialogs::MergeViewAction::MarkResolved => {
+                    let message = if handle.is_modified.get() {
+                        "Save the file before marking it resolved".to_string()
+                    } else {
+                        match impulse_core::git::mark_conflict_resolved(&file_path) {
+                            Ok(()) => format!("Marked {} as resolved", file_name),
+                            Err(e) => e,
+                        }
+                    };
+                    let toast = adw::Toast::new(&message);
+                    toast.set_timeout(3);
+                    toast_overlay.add_toast(toast);
+                }
+            }
+        });
     });
 }
 
+/// Ask for a branch and location, create the worktree off the UI thread,
+/// and open it in a new window.
+fn prompt_create_worktree(
+    app: &adw::Application,
+    toast_overlay: &adw::ToastOverlay,
+    repo_root: &str,
+) {
+    let branches = impulse_core::git::list_git_branches(repo_root).unwrap_or_default();
+    let app = app.clone();
+    let overlay = toast_overlay.clone();
+    let root = repo_root.to_string();
+    dialogs::show_create_worktree_dialog(
+        toast_overlay,
+        repo_root,
+        branches,
+        move |branch, dest| {
+            let app = app.clone();
+            let overlay = overlay.clone();
+            let root = root.clone();
+            gtk4::glib::spawn_future_local(async move {
+                let result = gtk4::gio::spawn_blocking(move || {
+                    impulse_core::git::create_worktree(&root, &dest, &branch)
+                })
+                .await;
+                match result {
+                    Ok(Ok(path)) => build_window(&app, Some(vec![path])),
+                    Ok(Err(e)) => {
+                        let toast = adw::Toast::new(&e);
+                        toast.set_timeout(4);
+                        overlay.add_toast(toast);
+                    }
+                    Err(_) => {}
+                }
+            });
+        },
+    );
+}
+
+/// The hosting-provider URL for the active editor's file at the cursor line,
+/// or for the repository containing `root` when no file is open.
+fn remote_link(tab_view: &adw::TabView, root: &str) -> Result<String, String> {
+    let active = tab_view.selected_page().map(|page| page.child());
+    if let Some(handle) = active
+        .as_ref()
+        .and_then(crate::editor::get_handle_for_widget)
+    {
+        let path = active.map(|child| child.widget_name().to_string());
+        if let Some(path) = path.filter(|p| std::path::Path::new(p).is_file()) {
+            let line = handle.cursor_line.get();
+            return impulse_core::git_hosting::permalink(&path, Some((line, line)));
+        }
+    }
+    impulse_core::git_hosting::hosted_repo_for_path(root).map(|hosted| hosted.web_url())
+}
+
 /// Runs all matching commands-on-save for the given file path.
 /// Returns `true` if any successful command had `reload_file` set.
 fn run_commands_on_save(path: &str, commands: &[crate::settings::CommandOnSave]) -> bool {
@@ -2087,6 +4590,7 @@ fn run_commands_on_save(path: &str, commands: &[crate::settings::CommandOnSave])
     for cmd in commands {
         if crate::settings::matches_file_pattern(path, &cmd.file_pattern) {
             let mut command = std::process::Command::new(&cmd.command);
+            impulse_core::environment::apply(&mut command, Some(std::path::Path::new(path)));
             command.args(&cmd.args);
             command.arg("--").arg(path);
             match command.output() {
@@ -2112,34 +4616,406 @@ fn run_commands_on_save(path: &str, commands: &[crate::settings::CommandOnSave])
     needs_reload
 }
 
-/// Atomically write content to a file via temp file + rename to prevent
-/// data loss on crash or power failure.
+/// Run the pre-save steps for an open file and return the content to write.
+/// Edits from the language servers (the `code_actions_on_save` kinds and
+/// `willSaveWaitUntil`) and then the whitespace fixes (trailing whitespace,
+/// final newline; see `impulse_core::save_whitespace`) are applied to
+/// `content` and to the buffer, which stays unmodified. The save waits at
+/// most a little longer than the core's own timeout, so a busy or hung
+/// server never holds it up for long.
+pub(super) fn apply_will_save_edits(
+    lsp_tx: &tokio::sync::mpsc::Sender<LspRequest>,
+    handle: &crate::editor_webview::MonacoEditorHandle,
+    path: &str,
+    content: String,
+    reason: lsp_types::TextDocumentSaveReason,
+    settings: &crate::settings::Settings,
+) -> String {
+    let server_edits = will_save_wait_until(lsp_tx, path, reason, settings);
+    let (content, server_edits) = if server_edits.is_empty() {
+        (content, server_edits)
+    } else {
+        match impulse_core::workspace_edit::apply_text_edits(&content, &server_edits) {
+            Ok(updated) => (updated, server_edits),
+            Err(e) => {
+                log::warn!("Ignoring willSaveWaitUntil edits for {}: {}", path, e);
+                (content, Vec::new())
+            }
+        }
+    };
+
+    let fixes = impulse_core::save_whitespace::SaveWhitespace::for_file(
+        std::path::Path::new(path),
+        impulse_core::save_whitespace::SaveWhitespace::from_settings(settings),
+    );
+    let whitespace_edits = impulse_core::save_whitespace::edits(&content, fixes);
+    let content = if whitespace_edits.is_empty() {
+        content
+    } else {
+        match impulse_core::workspace_edit::apply_text_edits(&content, &whitespace_edits) {
+            Ok(updated) => updated,
+            Err(e) => {
+                log::warn!("Skipping whitespace fixes for {}: {}", path, e);
+                return finish_will_save(handle, content, &server_edits, &[]);
+            }
+        }
+    };
+    finish_will_save(handle, content, &server_edits, &whitespace_edits)
+}
+
+/// Put the pre-save edits into the buffer as a single change, since only
+/// one change event can be kept from marking the file modified. Edits from
+/// both steps can't be combined (the second set is relative to the first's
+/// result), so the whole buffer is replaced instead.
+fn finish_will_save(
+    handle: &crate::editor_webview::MonacoEditorHandle,
+    content: String,
+    server_edits: &[impulse_core::workspace_edit::TextEdit],
+    whitespace_edits: &[impulse_core::workspace_edit::TextEdit],
+) -> String {
+    match (server_edits.is_empty(), whitespace_edits.is_empty()) {
+        (true, true) => return content,
+        (false, true) => handle.apply_edits(server_edits),
+        (true, false) => handle.apply_edits(whitespace_edits),
+        (false, false) => handle.replace_content(&content),
+    }
+    handle.suppress_next_modify.set(true);
+    content
+}
+
+/// Ask the language servers for `willSaveWaitUntil` and code-action edits.
+fn will_save_wait_until(
+    lsp_tx: &tokio::sync::mpsc::Sender<LspRequest>,
+    path: &str,
+    reason: lsp_types::TextDocumentSaveReason,
+    settings: &crate::settings::Settings,
+) -> Vec<impulse_core::workspace_edit::TextEdit> {
+    let (reply_tx, reply_rx) = std::sync::mpsc::channel();
+    let request = LspRequest::WillSave {
+        uri: ensure_file_uri(path),
+        reason,
+        code_actions: settings.code_actions_on_save.clone(),
+        reply: reply_tx,
+    };
+    if let Err(e) = lsp_tx.try_send(request) {
+        log::warn!("LSP request channel full, saving without willSave: {}", e);
+        return Vec::new();
+    }
+    let timeout = impulse_core::lsp::WILL_SAVE_TIMEOUT + std::time::Duration::from_millis(250);
+    reply_rx.recv_timeout(timeout).unwrap_or_default()
+}
+
+/// Save content to a file with the configured strategy (see
+/// `impulse_core::safe_write`: temp file + rename by default, so a crash
+/// can't leave it truncated). The saved version goes into local history,
+/// then the file's git diff and blame are recomputed and cached copies of
+/// remote files uploaded in the background.
 pub(super) fn atomic_write(path: &str, content: &str) -> std::io::Result<()> {
-    use std::io::Write;
-    let dest = std::path::Path::new(path);
-    let parent = dest.parent().unwrap_or(std::path::Path::new("."));
-    let tmp_path = parent.join(format!(
-        ".{}.impulse-save-tmp",
-        dest.file_name().and_then(|n| n.to_str()).unwrap_or("file")
-    ));
-    {
-        let mut file = std::fs::OpenOptions::new()
-            .write(true)
-            .create(true)
-            .truncate(true)
-            .open(&tmp_path)?;
-        file.write_all(content.as_bytes())?;
-        file.sync_all()?;
-    }
-    // Preserve original permissions if the file already exists
-    if let Ok(meta) = std::fs::metadata(dest) {
-        let _ = std::fs::set_permissions(&tmp_path, meta.permissions());
-    }
-    std::fs::rename(&tmp_path, dest)?;
+    impulse_core::safe_write::write(std::path::Path::new(path), content.as_bytes())?;
+    impulse_core::local_history::record(std::path::Path::new(path), content.as_bytes());
+    impulse_core::git::refresh_file_caches(path);
+    crate::remote_files::upload_after_save(path);
     Ok(())
 }
 
+/// Show the local history of the active editor's file. Compare opens the
+/// version against the file on disk; Restore puts it into the editor as
+/// one undoable edit, leaving the tab modified.
+fn show_local_history(
+    window: &adw::ApplicationWindow,
+    tab_view: &adw::TabView,
+    settings: &Rc<RefCell<crate::settings::Settings>>,
+    toast_overlay: &adw::ToastOverlay,
+) {
+    let Some(page) = tab_view.selected_page() else {
+        return;
+    };
+    let child = page.child();
+    let path = child.widget_name().to_string();
+    if !editor::is_editor(&child) || editor::is_untitled_path(&path) {
+        return;
+    }
+    let entries = impulse_core::local_history::entries(std::path::Path::new(&path));
+
+    let on_compare = {
+        let tab_view = tab_view.clone();
+        let settings = settings.clone();
+        let toast_overlay = toast_overlay.clone();
+        let path = path.clone();
+        move |entry: &impulse_core::local_history::HistoryEntry| {
+            let Some(version) =
+                impulse_core::local_history::version_path(std::path::Path::new(&path), &entry.id)
+            else {
+                toast_overlay.add_toast(adw::Toast::new("That version is no longer available"));
+                return;
+            };
+            tab_management::open_file_compare(
+                &tab_view,
+                &settings,
+                &version.to_string_lossy(),
+                &path,
+            );
+            if let Some(page) = tab_view.selected_page() {
+                let name = std::path::Path::new(&path)
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                page.set_title(&format!(
+                    "{} ({}) ↔ {}",
+                    name,
+                    dialogs::history_entry_time(entry.saved_at_ms),
+                    name
+                ));
+            }
+        }
+    };
+    let on_restore = {
+        let toast_overlay = toast_overlay.clone();
+        let path = path.clone();
+        move |entry: &impulse_core::local_history::HistoryEntry| {
+            match impulse_core::local_history::read(std::path::Path::new(&path), &entry.id) {
+                Ok(text) => {
+                    if let Some(handle) = editor::get_handle(&path) {
+                        handle.replace_content(&text);
+                        let toast = adw::Toast::new(&format!(
+                            "Restored the version from {}",
+                            dialogs::history_entry_time(entry.saved_at_ms)
+                        ));
+                        toast.set_timeout(3);
+                        toast_overlay.add_toast(toast);
+                    }
+                }
+                Err(e) => {
+                    log::warn!("{}", e);
+                    toast_overlay.add_toast(adw::Toast::new(&e));
+                }
+            }
+        }
+    };
+    dialogs::show_local_history_dialog(window, &path, entries, on_compare, on_restore);
+}
+
+/// Replace the active editor's buffer with the saved file as one undoable
+/// edit, so Undo brings the discarded changes back, and mark it unmodified.
+fn revert_active_file(tab_view: &adw::TabView, toast_overlay: &adw::ToastOverlay) {
+    let Some(page) = tab_view.selected_page() else {
+        return;
+    };
+    let child = page.child();
+    let path = child.widget_name().to_string();
+    if !editor::is_editor(&child) || editor::is_untitled_path(&path) {
+        return;
+    }
+    let Some(handle) = editor::get_handle(&path) else {
+        return;
+    };
+    let saved = match std::fs::read_to_string(&path) {
+        Ok(saved) => saved,
+        Err(e) => {
+            log::warn!("Failed to revert {}: {}", path, e);
+            toast_overlay.add_toast(adw::Toast::new(&format!("Cannot read {}: {}", path, e)));
+            return;
+        }
+    };
+    let filename = std::path::Path::new(&path)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or(&path)
+        .to_string();
+    // An identical buffer produces no ContentChanged, which would leave the
+    // suppression armed for the user's next edit.
+    if handle.get_content() != saved {
+        handle.suppress_next_modify.set(true);
+        handle.replace_content(&saved);
+    }
+    editor::set_unmodified(&child);
+    page.set_title(&filename);
+    let toast = adw::Toast::new(&format!("Reverted {}", filename));
+    toast.set_timeout(2);
+    toast_overlay.add_toast(toast);
+}
+
+/// Editor tabs with unsaved changes, in tab order.
+pub(super) fn modified_editor_pages(tab_view: &adw::TabView) -> Vec<adw::TabPage> {
+    (0..tab_view.n_pages())
+        .map(|i| tab_view.nth_page(i))
+        .filter(|page| {
+            let child = page.child();
+            editor::is_editor(&child) && editor::is_modified(&child)
+        })
+        .collect()
+}
+
+/// Save the editor in `page` the way Ctrl+S does, without the toast:
+/// willSave edits, the write, didSave and commands-on-save. Failures are
+/// reported on `toast_overlay`. Returns whether the file was written;
+/// untitled buffers need Save As and are never written here.
+pub(super) fn save_editor_page(
+    page: &adw::TabPage,
+    lsp_tx: &tokio::sync::mpsc::Sender<LspRequest>,
+    settings: &crate::settings::Settings,
+    toast_overlay: &adw::ToastOverlay,
+    editor_tab_pages: &Rc<RefCell<HashMap<String, adw::TabPage>>>,
+) -> bool {
+    let child = page.child();
+    let path = child.widget_name().to_string();
+    if !editor::is_editor(&child) || editor::is_untitled_path(&path) {
+        return false;
+    }
+    let Some(text) = editor::get_editor_text(&child) else {
+        return false;
+    };
+    let text = match editor::get_handle_for_widget(&child) {
+        Some(handle) => apply_will_save_edits(
+            lsp_tx,
+            &handle,
+            &path,
+            text,
+            lsp_types::TextDocumentSaveReason::MANUAL,
+            settings,
+        ),
+        None => text,
+    };
+    if let Err(e) = atomic_write(&path, &text) {
+        report_save_error(toast_overlay, editor_tab_pages, &path, text, &e);
+        return false;
+    }
+    editor::set_unmodified(&child);
+    let filename = std::path::Path::new(&path)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or(&path);
+    page.set_title(filename);
+    if let Err(e) = lsp_tx.try_send(LspRequest::DidSave {
+        uri: ensure_file_uri(&path),
+    }) {
+        log::warn!("LSP request channel full, dropping request: {}", e);
+    }
+    spawn_commands_on_save(path.clone(), settings.commands_on_save.clone());
+    true
+}
+
+/// Save every modified editor in `tab_view`. Untitled buffers are left
+/// alone and counted in the toast, since each needs a name first.
+pub(super) fn save_all_editors(
+    tab_view: &adw::TabView,
+    lsp_tx: &tokio::sync::mpsc::Sender<LspRequest>,
+    settings: &crate::settings::Settings,
+    toast_overlay: &adw::ToastOverlay,
+    editor_tab_pages: &Rc<RefCell<HashMap<String, adw::TabPage>>>,
+) {
+    let mut saved = 0usize;
+    let mut untitled = 0usize;
+    for page in modified_editor_pages(tab_view) {
+        if editor::is_untitled_path(&page.child().widget_name()) {
+            untitled += 1;
+        } else if save_editor_page(&page, lsp_tx, settings, toast_overlay, editor_tab_pages) {
+            saved += 1;
+        }
+    }
+    let mut message = match saved {
+        0 => "No files saved".to_string(),
+        1 => "Saved 1 file".to_string(),
+        n => format!("Saved {} files", n),
+    };
+    if untitled > 0 {
+        message.push_str(&format!(
+            "; {} untitled {} Save As",
+            untitled,
+            if untitled == 1 {
+                "file needs"
+            } else {
+                "files need"
+            }
+        ));
+    }
+    let toast = adw::Toast::new(&message);
+    toast.set_timeout(2);
+    toast_overlay.add_toast(toast);
+}
+
+/// Report a failed save of `path`. When the file isn't writable, offer to
+/// write `content` as the administrator (`impulse_core::privileged_write`)
+/// and mark the tab saved if that works.
+pub(super) fn report_save_error(
+    toast_overlay: &adw::ToastOverlay,
+    editor_tab_pages: &Rc<RefCell<HashMap<String, adw::TabPage>>>,
+    path: &str,
+    content: String,
+    error: &std::io::Error,
+) {
+    if !impulse_core::privileged_write::is_permission_error(error)
+        || !impulse_core::privileged_write::is_available()
+    {
+        crate::notifications::save_failed(toast_overlay, path, &error.to_string());
+        return;
+    }
+    let filename = std::path::Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string());
+    let dialog = adw::AlertDialog::builder()
+        .heading("Permission Denied")
+        .body(format!(
+            "You don't have permission to write \u{201c}{}\u{201d}. Save it as an administrator?",
+            filename
+        ))
+        .build();
+    dialog.add_response("cancel", "Cancel");
+    dialog.add_response("admin", "Retry as Administrator");
+    dialog.set_response_appearance("admin", adw::ResponseAppearance::Suggested);
+    dialog.set_default_response(Some("admin"));
+    dialog.set_close_response("cancel");
+
+    let toast_overlay_ref = toast_overlay.clone();
+    let editor_tab_pages = editor_tab_pages.clone();
+    let path = path.to_string();
+    dialog.connect_response(None, move |_, response| {
+        if response != "admin" {
+            return;
+        }
+        let toast_overlay = toast_overlay_ref.clone();
+        let editor_tab_pages = editor_tab_pages.clone();
+        let path = path.clone();
+        let content = content.clone();
+        let filename = filename.clone();
+        gtk4::glib::spawn_future_local(async move {
+            let thread_path = path.clone();
+            let result = gio::spawn_blocking(move || {
+                impulse_core::privileged_write::write(
+                    std::path::Path::new(&thread_path),
+                    content.as_bytes(),
+                )
+            })
+            .await
+            .unwrap_or_else(|_| Err("Saving as administrator failed unexpectedly".to_string()));
+            match result {
+                Ok(()) => {
+                    if let Some(handle) = editor::get_handle(&path) {
+                        handle.is_modified.set(false);
+                    }
+                    if let Some(page) = editor_tab_pages.borrow().get(&path) {
+                        page.set_title(&filename);
+                    }
+                    send_diff_decorations(&path);
+                    let toast = adw::Toast::new(&format!("Saved {} as administrator", filename));
+                    toast.set_timeout(2);
+                    toast_overlay.add_toast(toast);
+                }
+                Err(e) => {
+                    log::warn!("Failed to save {} as administrator: {}", path, e);
+                    crate::notifications::save_failed(&toast_overlay, &path, &e);
+                }
+            }
+        });
+    });
+    dialog.present(Some(toast_overlay));
+}
+
 pub(super) fn spawn_commands_on_save(path: String, commands: Vec<crate::settings::CommandOnSave>) {
+    if commands.is_empty() || !impulse_core::trust::is_trusted(std::path::Path::new(&path)) {
+        return;
+    }
     std::thread::spawn(move || {
         if let Err(e) = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
             let needs_reload = run_commands_on_save(&path, &commands);
@@ -2162,7 +5038,18 @@ pub(super) fn spawn_commands_on_save(path: String, commands: Vec<crate::settings
     });
 }
 
-fn add_shortcut(controller: &gtk4::ShortcutController, accel: &str, callback: impl Fn() + 'static) {
+/// Where [`add_shortcut`] registers shortcuts: single-step accels go to the
+/// window's `ShortcutController`, chords to the window's `ChordDispatcher`.
+struct ShortcutRegistry {
+    controller: gtk4::ShortcutController,
+    chords: Rc<keybindings::ChordDispatcher>,
+}
+
+fn add_shortcut(registry: &ShortcutRegistry, accel: &str, callback: impl Fn() + 'static) {
+    if keybindings::is_chord(accel) {
+        registry.chords.add(accel, callback);
+        return;
+    }
     let trigger = gtk4::ShortcutTrigger::parse_string(accel);
     let action = gtk4::CallbackAction::new(move |_widget, _args| {
         callback();
@@ -2170,7 +5057,7 @@ fn add_shortcut(controller: &gtk4::ShortcutController, accel: &str, callback: im
     });
     if let Some(trigger) = trigger {
         let shortcut = gtk4::Shortcut::new(Some(trigger), Some(action));
-        controller.add_shortcut(shortcut);
+        registry.controller.add_shortcut(shortcut);
     }
 }
 
@@ -2279,6 +5166,41 @@ fn uri_to_file_path(uri: &str) -> String {
     impulse_core::util::uri_to_file_path(uri)
 }
 
+/// Definitions of the identifier at `line`/`character` in `uri`, looked up in
+/// the workspace symbol index. `content` is the open buffer, if any; the file
+/// is read from disk otherwise.
+fn symbol_index_definitions(
+    symbol_indexes: &std::sync::Mutex<impulse_core::symbol_index::SymbolIndexes>,
+    uri: &str,
+    content: Option<String>,
+    line: u32,
+    character: u32,
+) -> Vec<crate::lsp_completion::LocationInfo> {
+    let path = uri_to_file_path(uri);
+    let Some(content) = content.or_else(|| std::fs::read_to_string(&path).ok()) else {
+        return Vec::new();
+    };
+    let Ok(mut indexes) = symbol_indexes.lock() else {
+        return Vec::new();
+    };
+    indexes
+        .for_file(&path)
+        .definitions_at(&path, &content, line, character)
+        .into_iter()
+        .filter_map(|symbol| {
+            let uri = file_path_to_uri(std::path::Path::new(&symbol.path))?;
+            let end_character = symbol.character + symbol.name.encode_utf16().count() as u32;
+            Some(crate::lsp_completion::LocationInfo {
+                uri,
+                start_line: symbol.line,
+                start_character: symbol.character,
+                end_line: symbol.line,
+                end_character,
+            })
+        })
+        .collect()
+}
+
 fn language_from_uri(uri: &str) -> String {
     impulse_core::util::language_from_uri(uri)
 }
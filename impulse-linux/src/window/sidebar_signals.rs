@@ -1,10 +1,11 @@
 use super::tab_management;
 use gtk4::prelude::*;
-use libadwaita as adw;
 
 use crate::editor;
+use crate::editor_webview::MonacoEditorHandle;
 use crate::lsp_completion::{lsp_content_changes, LspRequest};
 use crate::terminal_container;
+use impulse_editor::protocol::EditorEvent;
 
 use super::{
     ensure_file_uri, language_from_uri, run_guarded_ui, send_diff_decorations, uri_to_file_path,
@@ -29,54 +30,39 @@ pub(super) fn dispatch_lsp_request(
     seq
 }
 
+/// The formatter for `path` from settings, plus the document contents when
+/// an external formatter may need them.
+pub(super) fn formatter_for_request(
+    settings: &impulse_core::settings::Settings,
+    path: &str,
+    content: impl FnOnce() -> String,
+) -> (impulse_core::formatter::FormatterChoice, Option<String>) {
+    let language = language_from_uri(&ensure_file_uri(path));
+    let formatter = impulse_core::formatter::choose(settings, &language, path);
+    let text = formatter.external().map(|_| content());
+    (formatter, text)
+}
+
 /// Wire up sidebar file activation, project search result activation,
 /// and "Open in Terminal" context menu callbacks.
 pub(super) fn wire_sidebar_signals(ctx: &super::context::WindowContext) {
     let sidebar_state = &ctx.sidebar_state;
     let tab_view = &ctx.tab_view;
-    let status_bar = &ctx.status_bar;
     let settings = &ctx.settings;
-    let lsp_request_tx = &ctx.lsp.request_tx;
-    let lsp_doc_versions = &ctx.lsp.doc_versions;
-    let lsp_request_seq = &ctx.lsp.request_seq;
-    let latest_completion_req = &ctx.lsp.latest_completion_req;
-    let latest_hover_req = &ctx.lsp.latest_hover_req;
-    let latest_definition_req = &ctx.lsp.latest_definition_req;
-    let definition_monaco_ids = &ctx.lsp.definition_monaco_ids;
-    let latest_formatting_req = &ctx.lsp.latest_formatting_req;
-    let latest_signature_help_req = &ctx.lsp.latest_signature_help_req;
-    let latest_references_req = &ctx.lsp.latest_references_req;
-    let latest_code_action_req = &ctx.lsp.latest_code_action_req;
-    let latest_rename_req = &ctx.lsp.latest_rename_req;
-    let toast_overlay = &ctx.toast_overlay;
 
     // Wire up file activation to open in editor tab
     {
         let tab_view = tab_view.clone();
-        let status_bar = status_bar.clone();
         let settings = settings.clone();
-        let lsp_tx = lsp_request_tx.clone();
-        let doc_versions = lsp_doc_versions.clone();
-        let sidebar_state_for_editor = sidebar_state.clone();
         let tree_states = sidebar_state.tab_tree_states.clone();
         let tree_nodes = sidebar_state.tree_nodes.clone();
         let tree_current_path = sidebar_state.current_path.clone();
         let tree_scroll = sidebar_state.file_tree_scroll.clone();
-        let lsp_request_seq = lsp_request_seq.clone();
-        let latest_completion_req = latest_completion_req.clone();
-        let latest_hover_req = latest_hover_req.clone();
-        let latest_definition_req = latest_definition_req.clone();
-        let definition_monaco_ids = definition_monaco_ids.clone();
-        let latest_formatting_req = latest_formatting_req.clone();
-        let latest_signature_help_req = latest_signature_help_req.clone();
-        let latest_references_req = latest_references_req.clone();
-        let latest_code_action_req = latest_code_action_req.clone();
-        let latest_rename_req = latest_rename_req.clone();
         let icon_cache = sidebar_state.icon_cache.clone();
-        let toast_overlay_for_editor = toast_overlay.clone();
         let open_editor_paths = ctx.open_editor_paths.clone();
         let editor_tab_pages = ctx.editor_tab_pages.clone();
         let close_return_targets = ctx.tab_close_return_targets.clone();
+        let editor_ctx = ctx.clone();
         *sidebar_state.on_file_activated.borrow_mut() = Some(Box::new(move |path: &str| {
             run_guarded_ui("on-file-activated", || {
                 let close_return_target = tab_management::selected_page_child_key(&tab_view);
@@ -89,6 +75,7 @@ pub(super) fn wire_sidebar_signals(ctx: &super::context::WindowContext) {
                             page,
                             close_return_target,
                         );
+                        tab_management::set_preview(page, false);
                         tab_view.set_selected_page(page);
                     }
                     return;
@@ -129,263 +116,21 @@ pub(super) fn wire_sidebar_signals(ctx: &super::context::WindowContext) {
                     );
                     tab_view.set_selected_page(&page);
                 } else if !editor::is_binary_file(path) {
+                    super::workspace_trust::prompt_if_undecided(&editor_ctx, path);
                     // Open file in new editor tab
-                    let theme = crate::theme::get_theme(&settings.borrow().color_scheme);
+                    let theme = crate::theme::active_theme(&settings.borrow());
                     let (editor_widget, _handle) = editor::create_editor(
                         path,
                         &settings.borrow(),
                         theme,
-                        {
-                            let lsp_tx = lsp_tx.clone();
-                            let doc_versions = doc_versions.clone();
-                            let status_bar = status_bar.clone();
-                            let tab_view = tab_view.clone();
-                            let settings = settings.clone();
-                            let lsp_request_seq = lsp_request_seq.clone();
-                            let latest_completion_req = latest_completion_req.clone();
-                            let latest_hover_req = latest_hover_req.clone();
-                            let latest_definition_req = latest_definition_req.clone();
-                            let definition_monaco_ids = definition_monaco_ids.clone();
-                            let latest_formatting_req = latest_formatting_req.clone();
-                            let latest_signature_help_req = latest_signature_help_req.clone();
-                            let latest_references_req = latest_references_req.clone();
-                            let latest_code_action_req = latest_code_action_req.clone();
-                            let latest_rename_req = latest_rename_req.clone();
-                            let sidebar_state = sidebar_state_for_editor.clone();
-                            let toast_overlay = toast_overlay_for_editor.clone();
-                            let editor_tab_pages = editor_tab_pages.clone();
-                            let path = path.to_string();
-                            let blame_timer_id: std::rc::Rc<
-                                std::cell::RefCell<Option<gtk4::glib::SourceId>>,
-                            > = std::rc::Rc::new(std::cell::RefCell::new(None));
-                            move |handle, event| {
-                                match event {
-                                    impulse_editor::protocol::EditorEvent::Ready => {
-                                        // No-op: initialization now happens on FileOpened
-                                    }
-                                    impulse_editor::protocol::EditorEvent::FileOpened => {
-                                        // Flush any pending go-to-position from cross-file navigation.
-                                        handle.flush_pending_position();
-                                        // Send LSP didOpen
-                                        let uri = ensure_file_uri(&path);
-                                        let language_id = language_from_uri(&uri);
-                                        let content = handle.get_content();
-                                        let mut versions = doc_versions.borrow_mut();
-                                        let version = versions.entry(path.clone()).or_insert(0);
-                                        *version += 1;
-                                        if let Err(e) = lsp_tx.try_send(LspRequest::DidOpen {
-                                            uri,
-                                            language_id,
-                                            version: *version,
-                                            text: content,
-                                        }) {
-                                            log::warn!("LSP request channel full, dropping request: {}", e);
-                                        }
-                                        // Send initial diff decorations
-                                        send_diff_decorations(&path);
-                                    }
-                                    impulse_editor::protocol::EditorEvent::ContentChanged {
-                                        content,
-                                        changes,
-                                        ..
-                                    } => {
-                                        // Update tab title based on modified state (O(1) lookup)
-                                        if let Some(page) = editor_tab_pages.borrow().get(&path) {
-                                            let filename = std::path::Path::new(&path)
-                                                .file_name()
-                                                .and_then(|n| n.to_str())
-                                                .unwrap_or(&path);
-                                            if handle.is_modified.get() {
-                                                page.set_title(&format!("{} *", filename));
-                                            } else {
-                                                page.set_title(filename);
-                                            }
-                                        }
-                                        // Send LSP didChange
-                                        let uri = ensure_file_uri(&path);
-                                        let mut versions = doc_versions.borrow_mut();
-                                        let version = versions.entry(path.clone()).or_insert(0);
-                                        *version += 1;
-                                        let changes = lsp_content_changes(&changes);
-                                        if let Err(e) = lsp_tx.try_send(LspRequest::DidChange {
-                                            uri,
-                                            version: *version,
-                                            text: content,
-                                            changes,
-                                        }) {
-                                            log::warn!("LSP request channel full, dropping request: {}", e);
-                                        }
-                                    }
-                                    impulse_editor::protocol::EditorEvent::CursorMoved { line, column } => {
-                                        status_bar.borrow().update_cursor_position(line as i32 - 1, column as i32 - 1);
-                                        // Git blame — debounced (300ms) + off main thread
-                                        {
-                                            if let Some(prev) = blame_timer_id.borrow_mut().take() {
-                                                prev.remove();
-                                            }
-                                            let path = path.clone();
-                                            let status_bar = status_bar.clone();
-                                            let timer_id = blame_timer_id.clone();
-                                            let id = gtk4::glib::timeout_add_local_once(
-                                                std::time::Duration::from_millis(300),
-                                                move || {
-                                                    timer_id.borrow_mut().take();
-                                                    gtk4::glib::spawn_future_local(async move {
-                                                        let result = gtk4::gio::spawn_blocking(move || {
-                                                            impulse_core::git::get_line_blame(&path, line)
-                                                        }).await;
-                                                        match result {
-                                                            Ok(Ok(blame)) => {
-                                                                let text = format!(
-                                                                    "{} \u{2022} {} \u{2022} {}",
-                                                                    blame.author, blame.date, blame.summary
-                                                                );
-                                                                status_bar.borrow().update_blame(&text);
-                                                            }
-                                                            _ => {
-                                                                status_bar.borrow().clear_blame();
-                                                            }
-                                                        }
-                                                    });
-                                                },
-                                            );
-                                            *blame_timer_id.borrow_mut() = Some(id);
-                                        }
-                                    }
-                                    impulse_editor::protocol::EditorEvent::SaveRequested => {
-                                        let content = handle.get_content();
-                                        if let Err(e) = super::atomic_write(&path, &content) {
-                                            log::error!("Failed to save {}: {}", path, e);
-                                            let toast = adw::Toast::new(&format!("Error saving: {}", e));
-                                            toast.set_timeout(4);
-                                            toast_overlay.add_toast(toast);
-                                        } else {
-                                            handle.is_modified.set(false);
-                                            // Revert tab title (O(1) lookup)
-                                            if let Some(page) = editor_tab_pages.borrow().get(&path) {
-                                                let filename = std::path::Path::new(&path)
-                                                    .file_name()
-                                                    .and_then(|n| n.to_str())
-                                                    .unwrap_or(&path);
-                                                page.set_title(filename);
-                                            }
-                                            let uri = ensure_file_uri(&path);
-                                            if let Err(e) = lsp_tx.try_send(LspRequest::DidSave { uri }) {
-                                                log::warn!("LSP request channel full, dropping request: {}", e);
-                                            }
-                                            // Refresh diff decorations after save
-                                            send_diff_decorations(&path);
-                                            // Refresh sidebar git badges without rebuilding tree (preserves scroll)
-                                            sidebar_state.refresh_git_only();
-                                            // Run commands-on-save in a background thread
-                                            let commands = settings.borrow().commands_on_save.clone();
-                                            super::spawn_commands_on_save(path.clone(), commands);
-                                        }
-                                    }
-                                    impulse_editor::protocol::EditorEvent::CompletionRequested { request_id: _, line, character } => {
-                                        dispatch_lsp_request(&path, &lsp_request_seq, &doc_versions, &latest_completion_req, &lsp_tx,
-                                            |seq, uri, version| LspRequest::Completion { request_id: seq, uri, version, line, character });
-                                    }
-                                    impulse_editor::protocol::EditorEvent::HoverRequested { request_id: _, line, character } => {
-                                        dispatch_lsp_request(&path, &lsp_request_seq, &doc_versions, &latest_hover_req, &lsp_tx,
-                                            |seq, uri, version| LspRequest::Hover { request_id: seq, uri, version, line, character });
-                                    }
-                                    impulse_editor::protocol::EditorEvent::DefinitionRequested { request_id: monaco_id, line, character } => {
-                                        let seq = dispatch_lsp_request(&path, &lsp_request_seq, &doc_versions, &latest_definition_req, &lsp_tx,
-                                            |seq, uri, version| LspRequest::Definition { request_id: seq, uri, version, line, character });
-                                        definition_monaco_ids.borrow_mut().insert(seq, monaco_id);
-                                    }
-                                    impulse_editor::protocol::EditorEvent::OpenFileRequested { uri, line, character } => {
-                                        // Block non-file URIs (e.g. from malicious LSP responses)
-                                        if !uri.starts_with("file://") && uri.contains("://") {
-                                            log::warn!("Blocked opening non-file URI: {}", uri);
-                                        } else {
-                                            let file_path = uri_to_file_path(&uri);
-                                            if let Some(cb) = sidebar_state.on_file_activated.borrow().as_ref() {
-                                                cb(&file_path);
-                                            }
-                                            // Navigate to position once the tab is created (O(1) lookup)
-                                            if let Some(page) = editor_tab_pages.borrow().get(&file_path) {
-                                                editor::go_to_position(&page.child(), line + 1, character + 1);
-                                                tab_view.set_selected_page(page);
-                                            }
-                                        }
-                                    }
-                                    impulse_editor::protocol::EditorEvent::FocusChanged { focused } => {
-                                        // Auto-save on focus loss
-                                        if !focused && settings.borrow().auto_save && handle.is_modified.get() {
-                                            let content = handle.get_content();
-                                            if let Err(e) = super::atomic_write(&path, &content) {
-                                                log::error!("Auto-save failed for {}: {}", path, e);
-                                            } else {
-                                                handle.is_modified.set(false);
-                                                // Revert tab title (O(1) lookup)
-                                                if let Some(page) = editor_tab_pages.borrow().get(&path) {
-                                                    let filename = std::path::Path::new(&path)
-                                                        .file_name()
-                                                        .and_then(|n| n.to_str())
-                                                        .unwrap_or(&path);
-                                                    page.set_title(filename);
-                                                }
-                                                let uri = ensure_file_uri(&path);
-                                                if let Err(e) = lsp_tx.try_send(LspRequest::DidSave { uri }) {
-                                                    log::warn!("LSP request channel full, dropping request: {}", e);
-                                                }
-                                                send_diff_decorations(&path);
-                                                sidebar_state.refresh_git_only();
-                                            }
-                                        }
-                                    }
-                                    impulse_editor::protocol::EditorEvent::FormattingRequested { request_id: _, tab_size, insert_spaces } => {
-                                        dispatch_lsp_request(&path, &lsp_request_seq, &doc_versions, &latest_formatting_req, &lsp_tx,
-                                            |seq, uri, version| LspRequest::Formatting { request_id: seq, uri, version, tab_size, insert_spaces });
-                                    }
-                                    impulse_editor::protocol::EditorEvent::SignatureHelpRequested { request_id: _, line, character } => {
-                                        dispatch_lsp_request(&path, &lsp_request_seq, &doc_versions, &latest_signature_help_req, &lsp_tx,
-                                            |seq, uri, version| LspRequest::SignatureHelp { request_id: seq, uri, version, line, character });
-                                    }
-                                    impulse_editor::protocol::EditorEvent::ReferencesRequested { request_id: _, line, character } => {
-                                        dispatch_lsp_request(&path, &lsp_request_seq, &doc_versions, &latest_references_req, &lsp_tx,
-                                            |seq, uri, version| LspRequest::References { request_id: seq, uri, version, line, character });
-                                    }
-                                    impulse_editor::protocol::EditorEvent::CodeActionRequested { request_id: _, start_line, start_column, end_line, end_column, diagnostics } => {
-                                        let diag_infos: Vec<crate::lsp_completion::DiagnosticInfo> = diagnostics.into_iter().map(|d| {
-                                            crate::lsp_completion::DiagnosticInfo {
-                                                line: d.start_line,
-                                                character: d.start_column,
-                                                end_line: d.end_line,
-                                                end_character: d.end_column,
-                                                severity: match d.severity {
-                                                    8 => crate::lsp_completion::DiagnosticSeverity::Error,
-                                                    4 => crate::lsp_completion::DiagnosticSeverity::Warning,
-                                                    2 => crate::lsp_completion::DiagnosticSeverity::Information,
-                                                    _ => crate::lsp_completion::DiagnosticSeverity::Hint,
-                                                },
-                                                message: d.message,
-                                            }
-                                        }).collect();
-                                        dispatch_lsp_request(&path, &lsp_request_seq, &doc_versions, &latest_code_action_req, &lsp_tx,
-                                            |seq, uri, version| LspRequest::CodeAction {
-                                                request_id: seq, uri, version, start_line, start_column, end_line, end_column, diagnostics: diag_infos,
-                                            });
-                                    }
-                                    impulse_editor::protocol::EditorEvent::RenameRequested { request_id: _, line, character, new_name } => {
-                                        dispatch_lsp_request(&path, &lsp_request_seq, &doc_versions, &latest_rename_req, &lsp_tx,
-                                            |seq, uri, version| LspRequest::Rename { request_id: seq, uri, version, line, character, new_name });
-                                    }
-                                    impulse_editor::protocol::EditorEvent::PrepareRenameRequested { request_id: _, line, character } => {
-                                        dispatch_lsp_request(&path, &lsp_request_seq, &doc_versions, &latest_rename_req, &lsp_tx,
-                                            |seq, uri, version| LspRequest::PrepareRename { request_id: seq, uri, version, line, character });
-                                    }
-                                }
-                            }
-                        },
+                        file_editor_events(&editor_ctx, path),
                     );
                     let page = tab_management::insert_after_selected(&tab_view, &editor_widget);
                     page.set_title(&filename);
                     if let Some(texture) = icon_cache.borrow().get(&filename, false, false) {
                         page.set_icon(Some(texture));
                     }
+                    tab_management::update_read_only_indicator(&page);
                     tab_management::set_close_return_target(
                         &close_return_targets,
                         &page,
@@ -412,6 +157,47 @@ pub(super) fn wire_sidebar_signals(ctx: &super::context::WindowContext) {
         }));
     }
 
+    // Single clicks in the file tree open a preview tab, which the next
+    // single click replaces unless it was edited or kept in the meantime.
+    {
+        let tab_view = tab_view.clone();
+        let settings = settings.clone();
+        let on_file_activated = sidebar_state.on_file_activated.clone();
+        let open_editor_paths = ctx.open_editor_paths.clone();
+        let editor_tab_pages = ctx.editor_tab_pages.clone();
+        *sidebar_state.on_file_previewed.borrow_mut() = Some(Box::new(move |path: &str| {
+            run_guarded_ui("on-file-previewed", || {
+                let activate = |path: &str| {
+                    if let Some(cb) = on_file_activated.borrow().as_ref() {
+                        cb(path);
+                    }
+                };
+                if !settings.borrow().preview_tabs {
+                    activate(path);
+                    return;
+                }
+                if open_editor_paths.borrow().contains(path) {
+                    if let Some(page) = editor_tab_pages.borrow().get(path) {
+                        tab_view.set_selected_page(page);
+                    }
+                    return;
+                }
+
+                let previous = tab_management::preview_page(&tab_view);
+                activate(path);
+                let Some(page) = editor_tab_pages.borrow().get(path).cloned() else {
+                    return;
+                };
+                tab_management::set_preview(&page, true);
+                if let Some(previous) = previous.filter(|p| *p != page) {
+                    if !editor::is_modified(&previous.child()) {
+                        tab_view.close_page(&previous);
+                    }
+                }
+            });
+        }));
+    }
+
     // Wire up project search result activation to open file at line
     {
         let sidebar_on_file = sidebar_state.on_file_activated.clone();
@@ -446,4 +232,523 @@ pub(super) fn wire_sidebar_signals(ctx: &super::context::WindowContext) {
             }
         }));
     }
+
+    // Wire up "Properties" to the properties dialog
+    {
+        let window = ctx.window.clone();
+        let toast_overlay = ctx.toast_overlay.clone();
+        *sidebar_state.on_show_properties.borrow_mut() = Some(Box::new(move |path: &str| {
+            super::dialogs::show_properties_dialog(&window, path, &toast_overlay);
+        }));
+    }
+
+    // Wire up "Compare with Selected" to open a file compare tab
+    {
+        let tab_view = tab_view.clone();
+        let settings = settings.clone();
+        *sidebar_state.on_compare_files.borrow_mut() = Some(Box::new(move |old, new| {
+            tab_management::open_file_compare(&tab_view, &settings, old, new);
+        }));
+    }
+}
+
+/// Editor event handling for a file tab in the window of `ctx`: LSP sync,
+/// saving, blame and navigation. Also installed on an editor dragged in
+/// from another window.
+pub(super) fn file_editor_events(
+    ctx: &super::context::WindowContext,
+    path: &str,
+) -> impl Fn(&MonacoEditorHandle, EditorEvent) + 'static {
+    let lsp_tx = ctx.lsp.request_tx.clone();
+    let doc_versions = ctx.lsp.doc_versions.clone();
+    let status_bar = ctx.status_bar.clone();
+    let tab_view = ctx.tab_view.clone();
+    let settings = ctx.settings.clone();
+    let lsp_request_seq = ctx.lsp.request_seq.clone();
+    let latest_completion_req = ctx.lsp.latest_completion_req.clone();
+    let latest_hover_req = ctx.lsp.latest_hover_req.clone();
+    let latest_definition_req = ctx.lsp.latest_definition_req.clone();
+    let definition_monaco_ids = ctx.lsp.definition_monaco_ids.clone();
+    let latest_formatting_req = ctx.lsp.latest_formatting_req.clone();
+    let latest_signature_help_req = ctx.lsp.latest_signature_help_req.clone();
+    let latest_references_req = ctx.lsp.latest_references_req.clone();
+    let latest_code_action_req = ctx.lsp.latest_code_action_req.clone();
+    let latest_rename_req = ctx.lsp.latest_rename_req.clone();
+    let latest_peek_req = ctx.lsp.latest_peek_req.clone();
+    let peek_monaco_ids = ctx.lsp.peek_monaco_ids.clone();
+    let rename_monaco_ids = ctx.lsp.rename_monaco_ids.clone();
+    let sidebar_state = ctx.sidebar_state.clone();
+    let toast_overlay = ctx.toast_overlay.clone();
+    let editor_tab_pages = ctx.editor_tab_pages.clone();
+    let path = path.to_string();
+    let blame_timer_id: std::rc::Rc<std::cell::RefCell<Option<gtk4::glib::SourceId>>> =
+        std::rc::Rc::new(std::cell::RefCell::new(None));
+    move |handle, event| {
+        match event {
+            impulse_editor::protocol::EditorEvent::Ready { .. } => {
+                // No-op: initialization now happens on FileOpened
+            }
+            // Answered inside the editor handle.
+            impulse_editor::protocol::EditorEvent::Response { .. } => {}
+            impulse_editor::protocol::EditorEvent::FileOpened => {
+                // Flush any pending go-to-position from cross-file navigation.
+                handle.flush_pending_position();
+                // Send LSP didOpen
+                let uri = ensure_file_uri(&path);
+                let language_id = language_from_uri(&uri);
+                let content = handle.get_content();
+                let mut versions = doc_versions.borrow_mut();
+                let version = versions.entry(path.clone()).or_insert(0);
+                *version += 1;
+                if let Err(e) = lsp_tx.try_send(LspRequest::DidOpen {
+                    uri,
+                    language_id,
+                    version: *version,
+                    text: content,
+                }) {
+                    log::warn!("LSP request channel full, dropping request: {}", e);
+                }
+                // Send initial diff decorations
+                send_diff_decorations(&path);
+            }
+            impulse_editor::protocol::EditorEvent::ContentChanged {
+                content, changes, ..
+            } => {
+                // Update tab title based on modified state (O(1) lookup)
+                if let Some(page) = editor_tab_pages.borrow().get(&path) {
+                    let filename = std::path::Path::new(&path)
+                        .file_name()
+                        .and_then(|n| n.to_str())
+                        .unwrap_or(&path);
+                    if handle.is_modified.get() {
+                        page.set_title(&format!("{} *", filename));
+                        tab_management::set_preview(page, false);
+                    } else {
+                        page.set_title(filename);
+                    }
+                }
+                // Send LSP didChange
+                let uri = ensure_file_uri(&path);
+                let mut versions = doc_versions.borrow_mut();
+                let version = versions.entry(path.clone()).or_insert(0);
+                *version += 1;
+                let changes = lsp_content_changes(&changes);
+                if let Err(e) = lsp_tx.try_send(LspRequest::DidChange {
+                    uri,
+                    version: *version,
+                    text: content,
+                    changes,
+                }) {
+                    log::warn!("LSP request channel full, dropping request: {}", e);
+                }
+            }
+            impulse_editor::protocol::EditorEvent::CursorMoved { line, column } => {
+                status_bar
+                    .borrow()
+                    .update_cursor_position(line as i32 - 1, column as i32 - 1);
+                // Git blame — debounced (300ms) + off main thread
+                {
+                    if let Some(prev) = blame_timer_id.borrow_mut().take() {
+                        prev.remove();
+                    }
+                    let path = path.clone();
+                    let status_bar = status_bar.clone();
+                    let timer_id = blame_timer_id.clone();
+                    let id = gtk4::glib::timeout_add_local_once(
+                        std::time::Duration::from_millis(300),
+                        move || {
+                            timer_id.borrow_mut().take();
+                            gtk4::glib::spawn_future_local(async move {
+                                let result = gtk4::gio::spawn_blocking(move || {
+                                    impulse_core::git::get_line_blame(&path, line)
+                                })
+                                .await;
+                                match result {
+                                    Ok(Ok(blame)) => {
+                                        let text = format!(
+                                            "{} \u{2022} {} \u{2022} {}",
+                                            blame.author, blame.date, blame.summary
+                                        );
+                                        status_bar.borrow().update_blame(&text);
+                                    }
+                                    _ => {
+                                        status_bar.borrow().clear_blame();
+                                    }
+                                }
+                            });
+                        },
+                    );
+                    *blame_timer_id.borrow_mut() = Some(id);
+                }
+            }
+            impulse_editor::protocol::EditorEvent::SaveRequested => {
+                let content = super::apply_will_save_edits(
+                    &lsp_tx,
+                    handle,
+                    &path,
+                    handle.get_content(),
+                    lsp_types::TextDocumentSaveReason::MANUAL,
+                    &settings.borrow(),
+                );
+                if let Err(e) = super::atomic_write(&path, &content) {
+                    log::error!("Failed to save {}: {}", path, e);
+                    super::report_save_error(&toast_overlay, &editor_tab_pages, &path, content, &e);
+                } else {
+                    handle.is_modified.set(false);
+                    // Revert tab title (O(1) lookup)
+                    if let Some(page) = editor_tab_pages.borrow().get(&path) {
+                        let filename = std::path::Path::new(&path)
+                            .file_name()
+                            .and_then(|n| n.to_str())
+                            .unwrap_or(&path);
+                        page.set_title(filename);
+                    }
+                    let uri = ensure_file_uri(&path);
+                    if let Err(e) = lsp_tx.try_send(LspRequest::DidSave { uri }) {
+                        log::warn!("LSP request channel full, dropping request: {}", e);
+                    }
+                    // Refresh diff decorations after save
+                    send_diff_decorations(&path);
+                    // Refresh sidebar git badges without rebuilding tree (preserves scroll)
+                    sidebar_state.refresh_git_only();
+                    // Run commands-on-save in a background thread
+                    let commands = settings.borrow().commands_on_save.clone();
+                    super::spawn_commands_on_save(path.clone(), commands);
+                }
+            }
+            impulse_editor::protocol::EditorEvent::CompletionRequested {
+                request_id: _,
+                line,
+                character,
+            } => {
+                dispatch_lsp_request(
+                    &path,
+                    &lsp_request_seq,
+                    &doc_versions,
+                    &latest_completion_req,
+                    &lsp_tx,
+                    |seq, uri, version| LspRequest::Completion {
+                        request_id: seq,
+                        uri,
+                        version,
+                        line,
+                        character,
+                    },
+                );
+            }
+            impulse_editor::protocol::EditorEvent::HoverRequested {
+                request_id: _,
+                line,
+                character,
+            } => {
+                dispatch_lsp_request(
+                    &path,
+                    &lsp_request_seq,
+                    &doc_versions,
+                    &latest_hover_req,
+                    &lsp_tx,
+                    |seq, uri, version| LspRequest::Hover {
+                        request_id: seq,
+                        uri,
+                        version,
+                        line,
+                        character,
+                    },
+                );
+            }
+            impulse_editor::protocol::EditorEvent::DefinitionRequested {
+                request_id: monaco_id,
+                line,
+                character,
+            } => {
+                let seq = dispatch_lsp_request(
+                    &path,
+                    &lsp_request_seq,
+                    &doc_versions,
+                    &latest_definition_req,
+                    &lsp_tx,
+                    |seq, uri, version| LspRequest::Definition {
+                        request_id: seq,
+                        uri,
+                        version,
+                        line,
+                        character,
+                    },
+                );
+                definition_monaco_ids.borrow_mut().insert(seq, monaco_id);
+            }
+            impulse_editor::protocol::EditorEvent::OpenFileRequested {
+                uri,
+                line,
+                character,
+            } => {
+                // Block non-file URIs (e.g. from malicious LSP responses)
+                if !uri.starts_with("file://") && uri.contains("://") {
+                    log::warn!("Blocked opening non-file URI: {}", uri);
+                } else {
+                    let file_path = uri_to_file_path(&uri);
+                    if let Some(cb) = sidebar_state.on_file_activated.borrow().as_ref() {
+                        cb(&file_path);
+                    }
+                    // Navigate to position once the tab is created (O(1) lookup)
+                    if let Some(page) = editor_tab_pages.borrow().get(&file_path) {
+                        editor::go_to_position(&page.child(), line + 1, character + 1);
+                        tab_view.set_selected_page(page);
+                    }
+                }
+            }
+            impulse_editor::protocol::EditorEvent::FocusChanged { focused } => {
+                // Auto-save on focus loss
+                if !focused && settings.borrow().auto_save && handle.is_modified.get() {
+                    let content = super::apply_will_save_edits(
+                        &lsp_tx,
+                        handle,
+                        &path,
+                        handle.get_content(),
+                        lsp_types::TextDocumentSaveReason::FOCUS_OUT,
+                        &settings.borrow(),
+                    );
+                    if let Err(e) = super::atomic_write(&path, &content) {
+                        log::error!("Auto-save failed for {}: {}", path, e);
+                    } else {
+                        handle.is_modified.set(false);
+                        // Revert tab title (O(1) lookup)
+                        if let Some(page) = editor_tab_pages.borrow().get(&path) {
+                            let filename = std::path::Path::new(&path)
+                                .file_name()
+                                .and_then(|n| n.to_str())
+                                .unwrap_or(&path);
+                            page.set_title(filename);
+                        }
+                        let uri = ensure_file_uri(&path);
+                        if let Err(e) = lsp_tx.try_send(LspRequest::DidSave { uri }) {
+                            log::warn!("LSP request channel full, dropping request: {}", e);
+                        }
+                        send_diff_decorations(&path);
+                        sidebar_state.refresh_git_only();
+                    }
+                }
+            }
+            impulse_editor::protocol::EditorEvent::FormattingRequested {
+                request_id: _,
+                tab_size,
+                insert_spaces,
+            } => {
+                let (formatter, text) =
+                    formatter_for_request(&settings.borrow(), &path, || handle.get_content());
+                dispatch_lsp_request(
+                    &path,
+                    &lsp_request_seq,
+                    &doc_versions,
+                    &latest_formatting_req,
+                    &lsp_tx,
+                    |seq, uri, version| LspRequest::Formatting {
+                        request_id: seq,
+                        uri,
+                        version,
+                        tab_size,
+                        insert_spaces,
+                        formatter,
+                        text,
+                    },
+                );
+            }
+            impulse_editor::protocol::EditorEvent::OnTypeFormattingRequested {
+                request_id: _,
+                line,
+                character,
+                ch,
+                tab_size,
+                insert_spaces,
+            } => {
+                dispatch_lsp_request(
+                    &path,
+                    &lsp_request_seq,
+                    &doc_versions,
+                    &latest_formatting_req,
+                    &lsp_tx,
+                    |seq, uri, version| LspRequest::OnTypeFormatting {
+                        request_id: seq,
+                        uri,
+                        version,
+                        line,
+                        character,
+                        ch,
+                        tab_size,
+                        insert_spaces,
+                    },
+                );
+            }
+            impulse_editor::protocol::EditorEvent::SignatureHelpRequested {
+                request_id: _,
+                line,
+                character,
+            } => {
+                dispatch_lsp_request(
+                    &path,
+                    &lsp_request_seq,
+                    &doc_versions,
+                    &latest_signature_help_req,
+                    &lsp_tx,
+                    |seq, uri, version| LspRequest::SignatureHelp {
+                        request_id: seq,
+                        uri,
+                        version,
+                        line,
+                        character,
+                    },
+                );
+            }
+            impulse_editor::protocol::EditorEvent::ReferencesRequested {
+                request_id: _,
+                line,
+                character,
+            } => {
+                dispatch_lsp_request(
+                    &path,
+                    &lsp_request_seq,
+                    &doc_versions,
+                    &latest_references_req,
+                    &lsp_tx,
+                    |seq, uri, version| LspRequest::References {
+                        request_id: seq,
+                        uri,
+                        version,
+                        line,
+                        character,
+                    },
+                );
+            }
+            impulse_editor::protocol::EditorEvent::CodeActionRequested {
+                request_id: _,
+                start_line,
+                start_column,
+                end_line,
+                end_column,
+                diagnostics,
+                only,
+            } => {
+                let diag_infos: Vec<crate::lsp_completion::DiagnosticInfo> = diagnostics
+                    .into_iter()
+                    .map(|d| crate::lsp_completion::DiagnosticInfo {
+                        line: d.start_line,
+                        character: d.start_column,
+                        end_line: d.end_line,
+                        end_character: d.end_column,
+                        severity: match d.severity {
+                            8 => crate::lsp_completion::DiagnosticSeverity::Error,
+                            4 => crate::lsp_completion::DiagnosticSeverity::Warning,
+                            2 => crate::lsp_completion::DiagnosticSeverity::Information,
+                            _ => crate::lsp_completion::DiagnosticSeverity::Hint,
+                        },
+                        message: d.message,
+                    })
+                    .collect();
+                dispatch_lsp_request(
+                    &path,
+                    &lsp_request_seq,
+                    &doc_versions,
+                    &latest_code_action_req,
+                    &lsp_tx,
+                    |seq, uri, version| LspRequest::CodeAction {
+                        request_id: seq,
+                        uri,
+                        version,
+                        start_line,
+                        start_column,
+                        end_line,
+                        end_column,
+                        diagnostics: diag_infos,
+                        only,
+                    },
+                );
+            }
+            impulse_editor::protocol::EditorEvent::ExecuteCommand { command, arguments } => {
+                let uri = ensure_file_uri(&path);
+                if let Err(e) = lsp_tx.try_send(LspRequest::ExecuteCommand {
+                    uri,
+                    command,
+                    arguments,
+                }) {
+                    log::warn!("LSP request channel full: {}", e);
+                }
+            }
+            impulse_editor::protocol::EditorEvent::RenameRequested {
+                request_id: monaco_id,
+                line,
+                character,
+                new_name,
+            } => {
+                let seq = dispatch_lsp_request(
+                    &path,
+                    &lsp_request_seq,
+                    &doc_versions,
+                    &latest_rename_req,
+                    &lsp_tx,
+                    |seq, uri, version| LspRequest::Rename {
+                        request_id: seq,
+                        uri,
+                        version,
+                        line,
+                        character,
+                        new_name,
+                    },
+                );
+                rename_monaco_ids.borrow_mut().insert(seq, monaco_id);
+            }
+            impulse_editor::protocol::EditorEvent::PrepareRenameRequested {
+                request_id: monaco_id,
+                line,
+                character,
+            } => {
+                let seq = dispatch_lsp_request(
+                    &path,
+                    &lsp_request_seq,
+                    &doc_versions,
+                    &latest_rename_req,
+                    &lsp_tx,
+                    |seq, uri, version| LspRequest::PrepareRename {
+                        request_id: seq,
+                        uri,
+                        version,
+                        line,
+                        character,
+                    },
+                );
+                rename_monaco_ids.borrow_mut().insert(seq, monaco_id);
+            }
+            impulse_editor::protocol::EditorEvent::OpenMergeView => {
+                super::open_merge_view(&path, &toast_overlay);
+            }
+            impulse_editor::protocol::EditorEvent::StageHunkRequested {
+                new_start,
+                new_lines,
+            } => {
+                super::stage_diff_hunk(&path, new_start, new_lines, &toast_overlay);
+            }
+            impulse_editor::protocol::EditorEvent::PeekRequested {
+                request_id: monaco_id,
+                kind,
+                line,
+                character,
+            } => {
+                let seq = dispatch_lsp_request(
+                    &path,
+                    &lsp_request_seq,
+                    &doc_versions,
+                    &latest_peek_req,
+                    &lsp_tx,
+                    |seq, uri, version| LspRequest::Peek {
+                        request_id: seq,
+                        uri,
+                        version,
+                        line,
+                        character,
+                        kind,
+                    },
+                );
+                peek_monaco_ids.borrow_mut().insert(seq, monaco_id);
+            }
+        }
+    }
 }
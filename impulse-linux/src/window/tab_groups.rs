@@ -0,0 +1,226 @@
+//! Commands that act on many tabs at once: "Show All Tabs", sorting and
+//! closing a group of tabs. The orders and groups come from
+//! `impulse_core::tab_order`; this module maps pages to and from it.
+
+use gtk4::prelude::*;
+use impulse_core::tab_order::{TabGroup, TabSort, TabSummary};
+use libadwaita as adw;
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use crate::editor;
+
+use super::run_guarded_ui;
+
+/// When each tab (by child widget) was last selected, for sorting by recent use.
+#[derive(Default)]
+pub(crate) struct TabUsage {
+    last_used: RefCell<HashMap<usize, u64>>,
+    clock: Cell<u64>,
+}
+
+fn child_key(page: &adw::TabPage) -> usize {
+    page.child().as_ptr() as usize
+}
+
+/// Record tab selections in `usage` for the life of `tab_view`.
+pub(super) fn track_usage(tab_view: &adw::TabView, usage: &Rc<TabUsage>) {
+    {
+        let usage = usage.clone();
+        tab_view.connect_selected_page_notify(move |tab_view| {
+            if let Some(page) = tab_view.selected_page() {
+                usage.clock.set(usage.clock.get() + 1);
+                usage
+                    .last_used
+                    .borrow_mut()
+                    .insert(child_key(&page), usage.clock.get());
+            }
+        });
+    }
+    let usage = usage.clone();
+    tab_view.connect_page_detached(move |_, page, _| {
+        usage.last_used.borrow_mut().remove(&child_key(page));
+    });
+}
+
+fn pages(tab_view: &adw::TabView) -> Vec<adw::TabPage> {
+    (0..tab_view.n_pages())
+        .map(|i| tab_view.nth_page(i))
+        .collect()
+}
+
+fn summary(page: &adw::TabPage, usage: &TabUsage) -> TabSummary {
+    let child = page.child();
+    TabSummary {
+        title: page.title().to_string(),
+        path: super::tab_management::file_path_for_page(page),
+        pinned: page.is_pinned(),
+        modified: editor::is_editor(&child) && editor::is_modified(&child),
+        last_used: usage
+            .last_used
+            .borrow()
+            .get(&child_key(page))
+            .copied()
+            .unwrap_or_default(),
+    }
+}
+
+fn summaries(pages: &[adw::TabPage], usage: &TabUsage) -> Vec<TabSummary> {
+    pages.iter().map(|page| summary(page, usage)).collect()
+}
+
+/// Reorder the tabs by `sort`, keeping pinned tabs first.
+pub(super) fn sort(tab_view: &adw::TabView, usage: &TabUsage, sort: TabSort) {
+    let pages = pages(tab_view);
+    let order = impulse_core::tab_order::sorted_order(&summaries(&pages, usage), sort);
+    for (position, index) in order.into_iter().enumerate() {
+        tab_view.reorder_page(&pages[index], position as i32);
+    }
+}
+
+/// Close the tabs in `group` relative to `anchor`. Unsaved editors and
+/// running commands still ask before closing.
+pub(super) fn close_group(
+    tab_view: &adw::TabView,
+    usage: &TabUsage,
+    anchor: &adw::TabPage,
+    group: TabGroup,
+) {
+    let pages = pages(tab_view);
+    let anchor = tab_view.page_position(anchor).max(0) as usize;
+    for index in impulse_core::tab_order::tabs_to_close(&summaries(&pages, usage), anchor, group) {
+        tab_view.close_page(&pages[index]);
+    }
+}
+
+/// A searchable list of every open tab; choosing one selects it.
+pub(super) fn show_all_tabs(
+    window: &adw::ApplicationWindow,
+    tab_view: &adw::TabView,
+    usage: &TabUsage,
+) {
+    let dialog = gtk4::Window::builder()
+        .transient_for(window)
+        .modal(true)
+        .decorated(false)
+        .default_width(500)
+        .default_height(400)
+        .build();
+    dialog.add_css_class("quick-open");
+
+    let vbox = gtk4::Box::new(gtk4::Orientation::Vertical, 0);
+    let entry = gtk4::SearchEntry::new();
+    entry.set_placeholder_text(Some("Search open tabs..."));
+    vbox.append(&entry);
+
+    let scroll = gtk4::ScrolledWindow::new();
+    scroll.set_vexpand(true);
+    let list = gtk4::ListBox::new();
+    list.set_selection_mode(gtk4::SelectionMode::Single);
+    scroll.set_child(Some(&list));
+    vbox.append(&scroll);
+    dialog.set_child(Some(&vbox));
+    super::dialogs::label_palette(&dialog, &entry, &list, "All Tabs", "Open tabs");
+
+    let pages = pages(tab_view);
+    let tabs = summaries(&pages, usage);
+    // The pages behind the rows currently shown, in row order.
+    let shown: Rc<RefCell<Vec<adw::TabPage>>> = Rc::new(RefCell::new(Vec::new()));
+    let populate = {
+        let list = list.clone();
+        let shown = shown.clone();
+        let selected = tab_view.selected_page();
+        move |query: &str| {
+            while let Some(row) = list.row_at_index(0) {
+                list.remove(&row);
+            }
+            let mut shown = shown.borrow_mut();
+            shown.clear();
+            for index in impulse_core::tab_order::filter(&tabs, query) {
+                list.append(&tab_row(&tabs[index]));
+                if selected.as_ref() == Some(&pages[index]) {
+                    list.select_row(list.row_at_index(shown.len() as i32).as_ref());
+                }
+                shown.push(pages[index].clone());
+            }
+            if list.selected_row().is_none() {
+                list.select_row(list.row_at_index(0).as_ref());
+            }
+        }
+    };
+    populate("");
+    entry.connect_search_changed(move |entry| {
+        run_guarded_ui("all-tabs-search-changed", || populate(&entry.text()));
+    });
+
+    let activate = {
+        let dialog = dialog.clone();
+        let tab_view = tab_view.clone();
+        move |row: &gtk4::ListBoxRow| {
+            if let Some(page) = shown.borrow().get(row.index() as usize) {
+                tab_view.set_selected_page(page);
+            }
+            dialog.close();
+        }
+    };
+    {
+        let activate = activate.clone();
+        list.connect_row_activated(move |_, row| activate(row));
+    }
+
+    let key_controller = gtk4::EventControllerKey::new();
+    {
+        let list = list.clone();
+        let dialog = dialog.clone();
+        key_controller.connect_key_pressed(move |_, key, _, _| {
+            let step = match key {
+                gtk4::gdk::Key::Escape => {
+                    dialog.close();
+                    return gtk4::glib::Propagation::Stop;
+                }
+                gtk4::gdk::Key::Return | gtk4::gdk::Key::KP_Enter => {
+                    if let Some(row) = list.selected_row() {
+                        activate(&row);
+                    }
+                    return gtk4::glib::Propagation::Stop;
+                }
+                gtk4::gdk::Key::Down => 1,
+                gtk4::gdk::Key::Up => -1,
+                _ => return gtk4::glib::Propagation::Proceed,
+            };
+            let index = list.selected_row().map_or(0, |row| row.index() + step);
+            if let Some(row) = list.row_at_index(index.max(0)) {
+                list.select_row(Some(&row));
+            }
+            gtk4::glib::Propagation::Stop
+        });
+    }
+    entry.add_controller(key_controller);
+
+    dialog.present();
+    entry.grab_focus();
+}
+
+fn tab_row(tab: &TabSummary) -> gtk4::Box {
+    let row = gtk4::Box::new(gtk4::Orientation::Vertical, 2);
+    let title = if tab.pinned {
+        format!("{} (pinned)", tab.title)
+    } else {
+        tab.title.clone()
+    };
+    let title = gtk4::Label::new(Some(&title));
+    title.set_halign(gtk4::Align::Start);
+    title.set_ellipsize(gtk4::pango::EllipsizeMode::End);
+    row.append(&title);
+    if let Some(path) = &tab.path {
+        let path = gtk4::Label::new(Some(path));
+        path.set_halign(gtk4::Align::Start);
+        path.set_ellipsize(gtk4::pango::EllipsizeMode::Start);
+        path.add_css_class("dim-label");
+        path.add_css_class("caption");
+        row.append(&path);
+    }
+    row
+}
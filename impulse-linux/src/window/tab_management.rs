@@ -7,8 +7,12 @@ use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
 use std::rc::Rc;
 
+use impulse_core::notifications::{Notification, Severity};
+use impulse_core::tab_order::TabGroup;
+
 use crate::editor;
-use crate::lsp_completion::{LspRequest, LspResponse};
+use crate::lsp_completion::{DiagnosticSeverity, LspRequest, LspResponse};
+use crate::notifications;
 use crate::sidebar;
 use crate::terminal;
 use crate::terminal_container;
@@ -157,6 +161,44 @@ pub(super) fn make_setup_terminal_signals(
     })
 }
 
+/// Open a tab diffing the file at `old` against the file at `new`.
+pub(super) fn open_file_compare(
+    tab_view: &adw::TabView,
+    settings: &Rc<RefCell<crate::settings::Settings>>,
+    old: &str,
+    new: &str,
+) {
+    let theme = crate::theme::active_theme(&settings.borrow());
+    let child = crate::review_tab::create_file_compare_tab(old, new, theme);
+    let page = insert_after_selected(tab_view, &child);
+    let name = |path: &str| {
+        std::path::Path::new(path)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default()
+    };
+    page.set_title(&format!("{} ↔ {}", name(old), name(new)));
+    tab_view.set_selected_page(&page);
+}
+
+/// Open a read-only tab comparing the saved file at `path` with its editor
+/// buffer.
+pub(super) fn open_saved_compare(
+    tab_view: &adw::TabView,
+    settings: &Rc<RefCell<crate::settings::Settings>>,
+    path: &str,
+) {
+    let theme = crate::theme::active_theme(&settings.borrow());
+    let child = crate::review_tab::create_saved_compare_tab(path, theme);
+    let page = insert_after_selected(tab_view, &child);
+    let name = std::path::Path::new(path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    page.set_title(&format!("{} (saved) ↔ {}", name, name));
+    tab_view.set_selected_page(&page);
+}
+
 /// Insert a widget into the tab view immediately after the currently selected tab.
 /// Falls back to `append()` if no tab is selected.
 pub(super) fn insert_after_selected(
@@ -179,6 +221,151 @@ pub(super) fn insert_after_selected(
     }
 }
 
+/// The preview tab, if any: the one opened by a single click in the file
+/// tree, which the next single click replaces.
+pub(super) fn preview_page(tab_view: &adw::TabView) -> Option<adw::TabPage> {
+    (0..tab_view.n_pages())
+        .map(|i| tab_view.nth_page(i))
+        .find(|page| {
+            page.child()
+                .has_css_class(crate::vertical_tabs::PREVIEW_TAB_CLASS)
+        })
+}
+
+/// Mark or unmark `page` as the preview tab. Editing, double-clicking,
+/// reopening or pinning a preview tab keeps it.
+pub(super) fn set_preview(page: &adw::TabPage, preview: bool) {
+    let child = page.child();
+    if preview {
+        child.add_css_class(crate::vertical_tabs::PREVIEW_TAB_CLASS);
+        page.set_tooltip(
+            "Preview: replaced by the next file you open. Edit or double-click to keep it.",
+        );
+    } else if child.has_css_class(crate::vertical_tabs::PREVIEW_TAB_CLASS) {
+        child.remove_css_class(crate::vertical_tabs::PREVIEW_TAB_CLASS);
+        page.set_tooltip("");
+    }
+}
+
+/// Show a lock on the tab while its editor is read-only. Clicking the lock
+/// makes the file editable.
+pub(super) fn update_read_only_indicator(page: &adw::TabPage) {
+    match editor::get_read_only_reason(&page.child()) {
+        Some(reason) => {
+            page.set_indicator_icon(Some(&gio::ThemedIcon::new("changes-prevent-symbolic")));
+            page.set_indicator_tooltip(reason.description());
+            page.set_indicator_activatable(true);
+        }
+        None => {
+            page.set_indicator_icon(gio::Icon::NONE);
+            page.set_indicator_tooltip("");
+            page.set_indicator_activatable(false);
+        }
+    }
+}
+
+/// Show what each terminal tab's shell is running (foreground job, CPU
+/// time, memory) in the tab tooltip, refreshed every few seconds while the
+/// window is open.
+pub(super) fn watch_terminal_processes(tab_view: &adw::TabView) {
+    let tab_view = tab_view.downgrade();
+    gtk4::glib::timeout_add_seconds_local(3, move || {
+        let Some(tab_view) = tab_view.upgrade() else {
+            return gtk4::glib::ControlFlow::Break;
+        };
+        let pages: Vec<(adw::TabPage, Vec<u32>)> = (0..tab_view.n_pages())
+            .map(|i| tab_view.nth_page(i))
+            .filter_map(|page| {
+                let pids: Vec<u32> = terminal_container::collect_terminals(&page.child())
+                    .iter()
+                    .filter_map(terminal::shell_pid)
+                    .collect();
+                (!pids.is_empty()).then_some((page, pids))
+            })
+            .collect();
+        if pages.is_empty() {
+            return gtk4::glib::ControlFlow::Continue;
+        }
+        let table = impulse_core::process_info::ProcessTable::read();
+        for (page, pids) in pages {
+            let tooltip = pids
+                .iter()
+                .filter_map(|&pid| table.terminal(pid).describe())
+                .collect::<Vec<_>>()
+                .join("\n");
+            if page.tooltip().as_deref() != Some(tooltip.as_str()) {
+                page.set_tooltip(&tooltip);
+            }
+        }
+        gtk4::glib::ControlFlow::Continue
+    });
+}
+
+/// Running terminal work in `child` that closing it would stop: how many
+/// processes run under its shells, leaving out the
+/// `terminal_close_ignored_commands` and their children, and the commands
+/// the shells report as running. Nothing when `terminal_confirm_close` is off.
+pub(super) fn terminal_close_risk(
+    child: &gtk4::Widget,
+    settings: &crate::settings::Settings,
+) -> (usize, Vec<impulse_core::close_risk::RunningCommandRisk>) {
+    if !settings.terminal_confirm_close {
+        return (0, Vec::new());
+    }
+    let terminals = terminal_container::collect_terminals(child);
+    let running_commands = terminals
+        .iter()
+        .filter_map(terminal::running_close_risk_command)
+        .collect();
+    let pids: Vec<u32> = terminals.iter().filter_map(terminal::shell_pid).collect();
+    if pids.is_empty() {
+        return (0, running_commands);
+    }
+    let table = impulse_core::process_info::ProcessTable::read();
+    let process_count = pids
+        .iter()
+        .map(|&pid| {
+            table
+                .terminal(pid)
+                .close_risk_count(&settings.terminal_close_ignored_commands)
+        })
+        .sum();
+    (process_count, running_commands)
+}
+
+/// The file behind an editor or image tab.
+pub(super) fn file_path_for_page(page: &adw::TabPage) -> Option<String> {
+    let child = page.child();
+    (editor::is_editor(&child) || editor::is_image_preview(&child))
+        .then(|| child.widget_name().to_string())
+        .filter(|path| std::path::Path::new(path).is_absolute())
+}
+
+/// What the selected tab is and whether the assistant is on, for the
+/// command registry's `when`.
+pub(super) fn command_context(
+    tab_view: &adw::TabView,
+    settings: &crate::settings::Settings,
+) -> impulse_core::commands::CommandContext {
+    let assistant = settings.assistant_enabled;
+    let agent_access = settings.agent_access_enabled;
+    let Some(page) = tab_view.selected_page() else {
+        return impulse_core::commands::CommandContext {
+            assistant,
+            agent_access,
+            ..Default::default()
+        };
+    };
+    let child = page.child();
+    impulse_core::commands::CommandContext {
+        editor: editor::is_editor(&child),
+        terminal: crate::terminal_container::get_active_terminal(&child).is_some(),
+        file: file_path_for_page(&page).is_some(),
+        assistant,
+        agent_access,
+    }
+}
+
 pub(super) fn selected_page_child_key(tab_view: &adw::TabView) -> Option<usize> {
     tab_view
         .selected_page()
@@ -240,7 +427,27 @@ fn close_page_finish_with_return_target(
     }
 }
 
-/// Create the closure that spawns a new terminal tab.
+/// The directory of the tab showing `child`: a terminal's working directory,
+/// or the folder of an editor's file.
+fn tab_directory(child: &gtk4::Widget) -> Option<String> {
+    if let Some(term) = terminal_container::get_active_terminal(child) {
+        return terminal::current_directory(&term);
+    }
+    let path = editor::get_handle_for_widget(child)?
+        .file_path
+        .borrow()
+        .clone();
+    if editor::is_untitled_path(&path) {
+        return None;
+    }
+    std::path::Path::new(&path)
+        .parent()
+        .map(|dir| dir.to_string_lossy().to_string())
+}
+
+/// Create the closure that spawns a new terminal tab, starting where the
+/// `terminal_new_tab_directory` setting says: the home directory, `root`
+/// (the sidebar's folder) or the active tab's directory.
 pub(super) fn make_create_tab(
     tab_view: &adw::TabView,
     setup_terminal_signals: &Rc<dyn Fn(&terminal::Terminal)>,
@@ -248,6 +455,7 @@ pub(super) fn make_create_tab(
     copy_on_select_flag: &Rc<Cell<bool>>,
     shell_cache: &Rc<terminal::ShellSpawnCache>,
     icon_cache: &Rc<RefCell<crate::file_icons::IconCache>>,
+    root: &Rc<RefCell<String>>,
 ) -> impl Fn() + Clone {
     let tab_view = tab_view.clone();
     let setup_terminal_signals = setup_terminal_signals.clone();
@@ -255,12 +463,23 @@ pub(super) fn make_create_tab(
     let copy_on_select_flag = copy_on_select_flag.clone();
     let shell_cache = shell_cache.clone();
     let icon_cache = icon_cache.clone();
+    let root = root.clone();
     move || {
-        let theme = crate::theme::get_theme(&settings.borrow().color_scheme);
+        let current = tab_view
+            .selected_page()
+            .and_then(|page| tab_directory(&page.child()));
+        let dir = impulse_core::shell::new_terminal_directory(
+            &settings.borrow().terminal_new_tab_directory,
+            &shell_cache.working_dir(),
+            Some(root.borrow().as_str()),
+            current.as_deref(),
+        );
+
+        let theme = crate::theme::active_theme(&settings.borrow());
         let term =
             terminal::create_terminal(&settings.borrow(), theme, copy_on_select_flag.clone());
         setup_terminal_signals(&term);
-        terminal::spawn_shell(&term, &shell_cache, None);
+        terminal::spawn_shell(&term, &shell_cache, Some(&dir));
 
         let container = terminal_container::TerminalContainer::new(&term);
         let page = insert_after_selected(&tab_view, &container.widget);
@@ -273,10 +492,13 @@ pub(super) fn make_create_tab(
     }
 }
 
-/// Set up tab context menu actions (new, close, close-others, pin).
+/// Set up tab context menu actions (new, close, close-others, close a
+/// group, pin).
 pub(super) fn setup_tab_context_menu(
     window: &adw::ApplicationWindow,
     tab_view: &adw::TabView,
+    tab_usage: &Rc<super::tab_groups::TabUsage>,
+    root: &Rc<RefCell<String>>,
     create_tab: &(impl Fn() + Clone + 'static),
 ) {
     let menu_page: Rc<RefCell<Option<adw::TabPage>>> = Rc::new(RefCell::new(None));
@@ -327,6 +549,24 @@ pub(super) fn setup_tab_context_menu(
         tab_actions.add_action(&action);
     }
 
+    // tab.close-right, tab.close-left and tab.close-saved actions
+    for (name, group) in [
+        ("close-right", TabGroup::Right),
+        ("close-left", TabGroup::Left),
+        ("close-saved", TabGroup::Saved),
+    ] {
+        let action = gio::SimpleAction::new(name, None);
+        let tab_view = tab_view.clone();
+        let tab_usage = tab_usage.clone();
+        let menu_page = menu_page.clone();
+        action.connect_activate(move |_, _| {
+            if let Some(page) = menu_page.borrow().as_ref() {
+                super::tab_groups::close_group(&tab_view, &tab_usage, page, group);
+            }
+        });
+        tab_actions.add_action(&action);
+    }
+
     // tab.pin action - toggle pin state
     {
         let action = gio::SimpleAction::new("pin", None);
@@ -334,12 +574,44 @@ pub(super) fn setup_tab_context_menu(
         let menu_page = menu_page.clone();
         action.connect_activate(move |_, _| {
             if let Some(page) = menu_page.borrow().as_ref() {
+                set_preview(page, false);
                 tab_view.set_page_pinned(page, !page.is_pinned());
             }
         });
         tab_actions.add_action(&action);
     }
 
+    // tab.open-containing-folder action
+    {
+        let action = gio::SimpleAction::new("open-containing-folder", None);
+        let menu_page = menu_page.clone();
+        action.connect_activate(move |_, _| {
+            if let Some(path) = menu_page.borrow().as_ref().and_then(file_path_for_page) {
+                crate::file_manager::show_item(&path);
+            }
+        });
+        tab_actions.add_action(&action);
+    }
+
+    // tab.copy-path, tab.copy-relative-path, tab.copy-file-name and
+    // tab.copy-file-url actions
+    for (name, command_id) in crate::sidebar::COPY_PATH_ACTIONS {
+        let action = gio::SimpleAction::new(name, None);
+        let menu_page = menu_page.clone();
+        let root = root.clone();
+        let window = window.clone();
+        action.connect_activate(move |_, _| {
+            let Some(path) = menu_page.borrow().as_ref().and_then(file_path_for_page) else {
+                return;
+            };
+            if let Some(text) = impulse_core::util::path_for_copy(command_id, &path, &root.borrow())
+            {
+                window.clipboard().set_text(&text);
+            }
+        });
+        tab_actions.add_action(&action);
+    }
+
     // tab.new action
     {
         let action = gio::SimpleAction::new("new", None);
@@ -381,6 +653,48 @@ fn validate_lsp_response(
     None
 }
 
+/// Apply confirmed workspace edits. Files open in an editor are edited in
+/// their buffer (undoable, left unsaved); all others are rewritten on disk.
+/// `what` names the operation in failure messages.
+pub(super) fn apply_workspace_file_edits(
+    files: &[impulse_core::workspace_edit::FileEdits],
+    what: &str,
+    toast_overlay: &adw::ToastOverlay,
+) {
+    let mut failures = Vec::new();
+    for file in files {
+        if !file.uri.starts_with("file://") {
+            failures.push(format!("Unsupported location '{}'", file.uri));
+            continue;
+        }
+        let path = uri_to_file_path(&file.uri);
+        match editor::get_handle(&path) {
+            Some(handle) => handle.apply_edits(&file.edits),
+            None => {
+                if let Err(e) = impulse_core::workspace_edit::apply_file_edits_on_disk(
+                    std::path::Path::new(&path),
+                    &file.edits,
+                ) {
+                    failures.push(e);
+                }
+            }
+        }
+    }
+    if !failures.is_empty() {
+        for failure in &failures {
+            log::warn!("{}: {}", what, failure);
+        }
+        let toast = adw::Toast::new(&format!(
+            "{} could not update {} of {} files",
+            what,
+            failures.len(),
+            files.len()
+        ));
+        toast.set_timeout(5);
+        toast_overlay.add_toast(toast);
+    }
+}
+
 /// Poll LSP responses on the GTK main loop and dispatch them.
 pub(super) fn setup_lsp_response_polling(
     ctx: &super::context::WindowContext,
@@ -399,22 +713,31 @@ pub(super) fn setup_lsp_response_polling(
     let latest_references_req = ctx.lsp.latest_references_req.clone();
     let latest_code_action_req = ctx.lsp.latest_code_action_req.clone();
     let latest_rename_req = ctx.lsp.latest_rename_req.clone();
+    let latest_peek_req = ctx.lsp.latest_peek_req.clone();
+    let peek_monaco_ids = ctx.lsp.peek_monaco_ids.clone();
+    let rename_monaco_ids = ctx.lsp.rename_monaco_ids.clone();
+    let window = ctx.window.clone();
     let toast_overlay = ctx.toast_overlay.clone();
     let lsp_error_toast_dedupe = ctx.lsp.error_toast_dedupe.clone();
     let lsp_install_result_rx = lsp_install_result_rx.clone();
     let editor_tab_pages = ctx.editor_tab_pages.clone();
+    let agent_ctx = ctx.clone();
+    let status_bar = ctx.status_bar.clone();
+    let lsp_tx = ctx.lsp.request_tx.clone();
     gtk4::glib::timeout_add_local(std::time::Duration::from_millis(100), move || {
         run_guarded_ui("lsp-gtk-poll", || {
             {
                 let install_rx = lsp_install_result_rx.borrow();
                 while let Ok(result) = install_rx.try_recv() {
-                    let text = match result {
-                        Ok(msg) => msg,
-                        Err(err) => format!("Failed to install web LSP servers: {}", err),
+                    let notification = match result {
+                        Ok(msg) => Notification::new(Severity::Info, msg),
+                        Err(err) => {
+                            Notification::new(Severity::Error, "Failed to install web LSP servers")
+                                .message(err)
+                                .action("Retry", "install_lsp")
+                        }
                     };
-                    let toast = adw::Toast::new(&text);
-                    toast.set_timeout(5);
-                    toast_overlay.add_toast(toast);
+                    notifications::notify(&toast_overlay, notification.source("lsp"));
                 }
             }
 
@@ -434,11 +757,27 @@ pub(super) fn setup_lsp_response_polling(
                                 continue;
                             }
                         }
+                        super::agent_access::diagnostics_changed(
+                            &agent_ctx,
+                            &file_path,
+                            &diagnostics,
+                        );
                         if let Some(page) = editor_tab_pages.borrow().get(&file_path) {
                             let child = page.child();
                             if let Some(handle) = editor::get_handle_for_widget(&child) {
                                 handle.apply_diagnostics(&diagnostics);
                             }
+                            let count = |severity| {
+                                diagnostics
+                                    .iter()
+                                    .filter(|d| d.severity == severity)
+                                    .count()
+                            };
+                            status_bar.borrow().set_file_problems(
+                                &file_path,
+                                count(DiagnosticSeverity::Error),
+                                count(DiagnosticSeverity::Warning),
+                            );
                         }
                     }
                     LspResponse::DefinitionResult {
@@ -501,17 +840,20 @@ pub(super) fn setup_lsp_response_polling(
 
                         let dedupe_key = format!("{}|{}", server_id, message);
                         if lsp_error_toast_dedupe.borrow_mut().insert(dedupe_key) {
-                            let toast_message = if message.contains("install-lsp-servers") {
-                                format!(
-                                "LSP '{}' missing. Open Command Palette and run 'Install Web LSP Servers'.",
-                                server_id
-                            )
+                            let notification = if message.contains("install-lsp-servers") {
+                                Notification::new(
+                                    Severity::Warning,
+                                    format!("LSP '{}' is not installed", server_id),
+                                )
+                                .action("Install Web LSP Servers", "install_lsp")
                             } else {
-                                format!("LSP '{}' failed to start: {}", server_id, message)
+                                Notification::new(
+                                    Severity::Error,
+                                    format!("LSP '{}' failed to start", server_id),
+                                )
+                                .message(message)
                             };
-                            let toast = adw::Toast::new(&toast_message);
-                            toast.set_timeout(7);
-                            toast_overlay.add_toast(toast);
+                            notifications::notify(&toast_overlay, notification.source("lsp"));
                         }
                     }
                     LspResponse::ServerExited {
@@ -524,6 +866,36 @@ pub(super) fn setup_lsp_response_polling(
                             client_key
                         );
                     }
+                    LspResponse::ApplyEdit { label, files } => {
+                        apply_workspace_file_edits(
+                            &files,
+                            label.as_deref().unwrap_or("Code action"),
+                            &toast_overlay,
+                        );
+                    }
+                    LspResponse::Progress { text } => {
+                        status_bar.borrow().set_lsp_progress(text.as_deref());
+                    }
+                    LspResponse::Resync { uri } => {
+                        let path = uri_to_file_path(&uri);
+                        let Some(handle) = editor::get_handle(&path) else {
+                            continue;
+                        };
+                        let version = {
+                            let mut versions = doc_versions.borrow_mut();
+                            let version = versions.entry(path).or_insert(0);
+                            *version += 1;
+                            *version
+                        };
+                        if let Err(e) = lsp_tx.try_send(LspRequest::DidChange {
+                            uri,
+                            version,
+                            text: Some(handle.get_content()),
+                            changes: Vec::new(),
+                        }) {
+                            log::warn!("LSP request channel full, dropping resync: {}", e);
+                        }
+                    }
                     LspResponse::CompletionResult {
                         request_id,
                         uri,
@@ -631,17 +1003,47 @@ pub(super) fn setup_lsp_response_polling(
                         request_id,
                         uri,
                         version,
-                        edits,
+                        new_name,
+                        files,
                     } => {
-                        if let Some((_path, handle)) = validate_lsp_response(
-                            &uri,
-                            request_id,
-                            version,
-                            &latest_rename_req,
-                            &doc_versions,
-                            &tab_view,
+                        let monaco_id = rename_monaco_ids.borrow_mut().remove(&request_id);
+                        if let (Some(monaco_id), Some((_path, handle))) = (
+                            monaco_id,
+                            validate_lsp_response(
+                                &uri,
+                                request_id,
+                                version,
+                                &latest_rename_req,
+                                &doc_versions,
+                                &tab_view,
+                            ),
                         ) {
-                            handle.resolve_rename(request_id, &edits);
+                            // End Monaco's rename session; the edits are
+                            // confirmed and applied through the preview dialog.
+                            handle.resolve_rename(monaco_id, &[]);
+                            if files.is_empty() {
+                                let toast = adw::Toast::new("Nothing to rename");
+                                toast.set_timeout(3);
+                                toast_overlay.add_toast(toast);
+                            } else {
+                                let toast_overlay = toast_overlay.clone();
+                                super::dialogs::show_rename_preview_dialog(
+                                    &window,
+                                    &new_name,
+                                    files,
+                                    |uri| match editor::get_handle(&uri_to_file_path(uri)) {
+                                        Some(handle) => Some(handle.get_content()),
+                                        None => impulse_editor::peek::read_source_from_disk(uri),
+                                    },
+                                    move |selected| {
+                                        apply_workspace_file_edits(
+                                            &selected,
+                                            "Rename",
+                                            &toast_overlay,
+                                        );
+                                    },
+                                );
+                            }
                         }
                     }
                     LspResponse::PrepareRenameResult {
@@ -651,21 +1053,47 @@ pub(super) fn setup_lsp_response_polling(
                         range,
                         placeholder,
                     } => {
-                        if let Some((_path, handle)) = validate_lsp_response(
-                            &uri,
-                            request_id,
-                            version,
-                            &latest_rename_req,
-                            &doc_versions,
-                            &tab_view,
+                        let monaco_id = rename_monaco_ids.borrow_mut().remove(&request_id);
+                        if let (Some(monaco_id), Some((_path, handle))) = (
+                            monaco_id,
+                            validate_lsp_response(
+                                &uri,
+                                request_id,
+                                version,
+                                &latest_rename_req,
+                                &doc_versions,
+                                &tab_view,
+                            ),
                         ) {
                             handle.resolve_prepare_rename(
-                                request_id,
+                                monaco_id,
                                 range.as_ref(),
                                 placeholder.as_deref(),
                             );
                         }
                     }
+                    LspResponse::PeekResult {
+                        request_id,
+                        uri,
+                        version,
+                        kind,
+                        locations,
+                    } => {
+                        let monaco_id = peek_monaco_ids.borrow_mut().remove(&request_id);
+                        if let (Some(monaco_id), Some((_path, handle))) = (
+                            monaco_id,
+                            validate_lsp_response(
+                                &uri,
+                                request_id,
+                                version,
+                                &latest_peek_req,
+                                &doc_versions,
+                                &tab_view,
+                            ),
+                        ) {
+                            handle.show_peek(monaco_id, kind, &locations);
+                        }
+                    }
                 }
             }
         });
@@ -731,6 +1159,9 @@ pub(super) fn setup_tab_switch_handler(
                     } else if let Some(parent) = std::path::Path::new(&file_path).parent() {
                         let dir = parent.to_string_lossy().to_string();
                         sidebar_state.switch_to_tab(&child, &dir);
+                        if settings.borrow().sidebar_auto_reveal {
+                            sidebar_state.reveal_path(&file_path);
+                        }
                         // Use the sidebar's restored current_path for the status bar,
                         // which preserves the project root rather than the file's parent dir.
                         let cwd = sidebar_state.current_path.borrow().clone();
@@ -750,8 +1181,8 @@ pub(super) fn setup_tab_switch_handler(
                     }
                     status_bar.borrow().update_encoding("UTF-8");
                     // Show indent info for editor tabs
-                    if let Some(indent) = editor::get_editor_indent_info(&child) {
-                        status_bar.borrow().update_indent_info(&indent);
+                    if let Some(indentation) = editor::get_editor_indentation(&child) {
+                        status_bar.borrow().update_indentation(indentation);
                     }
                     // Show/hide preview button based on file type
                     if editor::is_previewable_file(&file_path) {
@@ -766,6 +1197,7 @@ pub(super) fn setup_tab_switch_handler(
                     child.grab_focus();
                     status_bar.borrow().hide_editor_info();
                 }
+                super::zoom::refresh_status(tv, &settings, &status_bar);
             }
         });
     });
@@ -787,11 +1219,16 @@ pub(super) fn setup_tab_close_handler(
     let hover_req_for_close = ctx.lsp.latest_hover_req.clone();
     let definition_req_for_close = ctx.lsp.latest_definition_req.clone();
     let definition_monaco_ids = ctx.lsp.definition_monaco_ids.clone();
+    let peek_req_for_close = ctx.lsp.latest_peek_req.clone();
+    let peek_monaco_ids = ctx.lsp.peek_monaco_ids.clone();
+    let rename_req_for_close = ctx.lsp.latest_rename_req.clone();
+    let rename_monaco_ids = ctx.lsp.rename_monaco_ids.clone();
     let closed_tabs_for_close = closed_tabs.clone();
     let open_editor_paths = ctx.open_editor_paths.clone();
     let editor_tab_pages = ctx.editor_tab_pages.clone();
     let close_return_targets = ctx.tab_close_return_targets.clone();
     let settings_for_close = ctx.settings.clone();
+    let status_bar = ctx.status_bar.clone();
     ctx.tab_view.connect_close_page(move |tv, page| {
         // Confirm before closing pinned tabs
         if page.is_pinned() {
@@ -828,16 +1265,19 @@ pub(super) fn setup_tab_close_handler(
             None
         };
         if settings_for_close.borrow().confirm_close_warnings && !editor::is_editor(&child) {
-            let running_commands = terminal_container::collect_terminals(&child)
-                .into_iter()
-                .filter_map(|term| terminal::running_close_risk_command(&term))
-                .collect::<Vec<_>>();
+            let (running_terminal_process_count, running_commands) =
+                terminal_close_risk(&child, &settings_for_close.borrow());
             let summary = impulse_core::close_risk::summarize_close_risk(
                 &impulse_core::close_risk::CloseRiskInput {
                     action: impulse_core::close_risk::CloseRiskAction::CloseTab,
                     unsaved_editor_count: 0,
-                    running_terminal_process_count: 0,
+                    unsaved_files: Vec::new(),
+                    running_terminal_process_count,
                     running_commands,
+                    ignored_commands: settings_for_close
+                        .borrow()
+                        .terminal_close_ignored_commands
+                        .clone(),
                     now_ms: current_unix_time_ms(),
                     long_command_threshold_seconds: settings_for_close
                         .borrow()
@@ -918,6 +1358,7 @@ pub(super) fn setup_tab_close_handler(
         // Clean up LSP tracking state for editor tabs
         if editor::is_editor(&child) {
             let path = child.widget_name().to_string();
+            status_bar.borrow().clear_file_problems(&path);
             doc_versions_for_close.borrow_mut().remove(&path);
             completion_req_for_close.borrow_mut().remove(&path);
             hover_req_for_close.borrow_mut().remove(&path);
@@ -925,6 +1366,12 @@ pub(super) fn setup_tab_close_handler(
             if let Some(seq) = definition_req_for_close.borrow_mut().remove(&path) {
                 definition_monaco_ids.borrow_mut().remove(&seq);
             }
+            if let Some(seq) = peek_req_for_close.borrow_mut().remove(&path) {
+                peek_monaco_ids.borrow_mut().remove(&seq);
+            }
+            if let Some(seq) = rename_req_for_close.borrow_mut().remove(&path) {
+                rename_monaco_ids.borrow_mut().remove(&seq);
+            }
         }
 
         // Remove from dedup set and page map
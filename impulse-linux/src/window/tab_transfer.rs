@@ -0,0 +1,161 @@
+//! Dragging tabs between windows. libadwaita moves the page itself, into
+//! another window's tab bar or a new window when dropped outside one; these
+//! handlers hand its state over. Terminals keep their shell and rewire
+//! their signals to the new window, editors keep their buffer and move
+//! their document to the new window's language servers.
+
+use gtk4::prelude::*;
+use libadwaita as adw;
+
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::rc::Rc;
+
+use crate::editor;
+use crate::lsp_completion::LspRequest;
+use crate::terminal;
+use crate::terminal_container;
+
+use super::context::WindowContext;
+use super::{ensure_file_uri, run_guarded_ui};
+
+thread_local! {
+    /// Tab children detached from a tab view in the last main loop
+    /// iteration. A page attached with one of these came from another
+    /// window; the rest were just created here.
+    static DETACHED: RefCell<HashSet<usize>> = RefCell::new(HashSet::new());
+}
+
+fn child_key(page: &adw::TabPage) -> usize {
+    page.child().as_ptr() as usize
+}
+
+pub(super) fn setup_tab_transfer(
+    ctx: &WindowContext,
+    setup_terminal_signals: &Rc<dyn Fn(&terminal::Terminal)>,
+) {
+    // Dropped outside every tab bar: open a new window for it.
+    {
+        let window = ctx.window.clone();
+        ctx.tab_view.connect_create_window(move |_| {
+            let app = window.application().and_downcast::<adw::Application>()?;
+            Some(super::build_window_for_transfer(&app))
+        });
+    }
+
+    {
+        let ctx = ctx.clone();
+        let setup_terminal_signals = setup_terminal_signals.clone();
+        ctx.tab_view
+            .clone()
+            .connect_page_attached(move |_, page, _| {
+                let moved = DETACHED.with(|d| d.borrow_mut().remove(&child_key(page)));
+                if moved {
+                    run_guarded_ui("tab-transfer-adopt", || {
+                        adopt_page(&ctx, &setup_terminal_signals, page)
+                    });
+                }
+            });
+    }
+
+    {
+        let ctx = ctx.clone();
+        ctx.tab_view
+            .clone()
+            .connect_page_detached(move |_, page, _| {
+                DETACHED.with(|d| d.borrow_mut().insert(child_key(page)));
+                // Closing a tab detaches it too; only a page that ended up
+                // in another window was moved.
+                let ctx = ctx.clone();
+                let page = page.clone();
+                gtk4::glib::idle_add_local_once(move || {
+                    DETACHED.with(|d| d.borrow_mut().remove(&child_key(&page)));
+                    let child = page.child();
+                    let moved = child
+                        .root()
+                        .is_some_and(|root| root != ctx.window.clone().upcast::<gtk4::Root>());
+                    if moved {
+                        run_guarded_ui("tab-transfer-release", || release_page(&ctx, &page));
+                    }
+                });
+            });
+    }
+}
+
+/// Take over a page that arrived from another window.
+fn adopt_page(
+    ctx: &WindowContext,
+    setup_terminal_signals: &Rc<dyn Fn(&terminal::Terminal)>,
+    page: &adw::TabPage,
+) {
+    let child = page.child();
+    for term in terminal_container::collect_terminals(&child) {
+        terminal::disconnect_signals(&term);
+        setup_terminal_signals(&term);
+    }
+
+    let path = child.widget_name().to_string();
+    if editor::is_image_preview(&child) {
+        ctx.open_editor_paths.borrow_mut().insert(path.clone());
+        ctx.editor_tab_pages.borrow_mut().insert(path, page.clone());
+    } else if let Some(handle) = editor::get_handle_for_widget(&child) {
+        if editor::is_untitled_path(&path) {
+            ctx.editor_tab_pages.borrow_mut().insert(path, page.clone());
+            handle.set_event_handler(super::keybinding_setup::untitled_editor_events(ctx));
+        } else {
+            ctx.open_editor_paths.borrow_mut().insert(path.clone());
+            ctx.editor_tab_pages
+                .borrow_mut()
+                .insert(path.clone(), page.clone());
+            handle.set_event_handler(super::sidebar_signals::file_editor_events(ctx, &path));
+        }
+    }
+}
+
+/// Forget a page that moved to another window, without closing it.
+fn release_page(ctx: &WindowContext, page: &adw::TabPage) {
+    let child = page.child();
+    let key = child_key(page);
+    ctx.sidebar_state.remove_tab_state(&child);
+    {
+        let mut targets = ctx.tab_close_return_targets.borrow_mut();
+        targets.remove(&key);
+        targets.retain(|_, target| *target != key);
+    }
+
+    let path = child.widget_name().to_string();
+    let owned = ctx
+        .editor_tab_pages
+        .borrow()
+        .get(&path)
+        .is_some_and(|p| p == page);
+    if owned {
+        ctx.open_editor_paths.borrow_mut().remove(&path);
+        ctx.editor_tab_pages.borrow_mut().remove(&path);
+        if editor::is_editor(&child) && !editor::is_untitled_path(&path) {
+            ctx.status_bar.borrow().clear_file_problems(&path);
+            ctx.lsp.doc_versions.borrow_mut().remove(&path);
+            ctx.lsp.latest_completion_req.borrow_mut().remove(&path);
+            ctx.lsp.latest_hover_req.borrow_mut().remove(&path);
+            if let Some(seq) = ctx.lsp.latest_definition_req.borrow_mut().remove(&path) {
+                ctx.lsp.definition_monaco_ids.borrow_mut().remove(&seq);
+            }
+            if let Some(seq) = ctx.lsp.latest_peek_req.borrow_mut().remove(&path) {
+                ctx.lsp.peek_monaco_ids.borrow_mut().remove(&seq);
+            }
+            if let Some(seq) = ctx.lsp.latest_rename_req.borrow_mut().remove(&path) {
+                ctx.lsp.rename_monaco_ids.borrow_mut().remove(&seq);
+            }
+            if let Err(e) = ctx.lsp.request_tx.try_send(LspRequest::DidClose {
+                uri: ensure_file_uri(&path),
+            }) {
+                log::warn!("LSP request channel full, dropping request: {}", e);
+            }
+        }
+    }
+
+    // The last tab left: the window goes with it, like a browser's.
+    if ctx.tab_view.n_pages() == 0 {
+        ctx.window.close();
+    }
+}
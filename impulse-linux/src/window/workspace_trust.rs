@@ -0,0 +1,124 @@
+//! Workspace trust prompts. The first file opened from a folder without a
+//! trust decision asks whether to trust it; until then the folder stays in
+//! restricted mode (see `impulse_core::trust`).
+
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use impulse_core::trust::{self, TrustState};
+use libadwaita as adw;
+use libadwaita::prelude::*;
+
+use crate::editor;
+use crate::lsp_completion::LspRequest;
+
+use super::context::WindowContext;
+use super::{ensure_file_uri, language_from_uri};
+
+thread_local! {
+    /// Folders already asked about this session, so dismissing the prompt
+    /// doesn't bring it back for every file.
+    static ASKED: RefCell<HashSet<PathBuf>> = RefCell::new(HashSet::new());
+}
+
+/// Ask about `path`'s workspace folder if the user hasn't decided on it.
+pub(super) fn prompt_if_undecided(ctx: &WindowContext, path: &str) {
+    if !trust::is_enabled() {
+        return;
+    }
+    let folder = trust::workspace_folder(Path::new(path));
+    if trust::state(&folder) != TrustState::Unknown
+        || !ASKED.with(|asked| asked.borrow_mut().insert(folder.clone()))
+    {
+        return;
+    }
+    show_dialog(
+        ctx,
+        folder,
+        "Trust This Folder?",
+        "Until you trust it, language servers don't start, commands on save don't run, \
+         and its .impulse settings are ignored. Only trust folders whose contents you trust.",
+    );
+}
+
+/// "Manage Workspace Trust": change the decision for the current file's
+/// folder, or the file tree's folder when no editor is selected.
+pub(super) fn manage(ctx: &WindowContext) {
+    let path = ctx
+        .tab_view
+        .selected_page()
+        .and_then(|page| editor::get_handle_for_widget(&page.child()))
+        .map(|handle| handle.file_path.borrow().clone())
+        .unwrap_or_else(|| ctx.sidebar_state.current_path.borrow().clone());
+    if path.is_empty() {
+        return;
+    }
+    let folder = trust::workspace_folder(Path::new(&path));
+    let state = if !trust::is_enabled() {
+        "Workspace trust is turned off in Settings, so every folder is trusted."
+    } else {
+        match trust::state(&folder) {
+            TrustState::Trusted => "This folder is trusted.",
+            TrustState::Restricted | TrustState::Unknown => "This folder is in restricted mode.",
+        }
+    };
+    show_dialog(ctx, folder, "Workspace Trust", state);
+}
+
+fn show_dialog(ctx: &WindowContext, folder: PathBuf, heading: &str, body: &str) {
+    let dialog = adw::AlertDialog::builder()
+        .heading(heading)
+        .body(format!("{}\n\n{}", folder.display(), body))
+        .build();
+    dialog.add_response("restrict", "Stay in Restricted Mode");
+    dialog.add_response("trust", "Trust Folder");
+    dialog.set_response_appearance("trust", adw::ResponseAppearance::Suggested);
+    dialog.set_default_response(Some("restrict"));
+    dialog.set_close_response("restrict");
+
+    let ctx_ref = ctx.clone();
+    dialog.connect_response(None, move |_dialog, response| {
+        let trusted = response == "trust";
+        let restricted: Vec<String> = ctx_ref
+            .editor_tab_pages
+            .borrow()
+            .keys()
+            .filter(|path| !trust::is_trusted(Path::new(path)))
+            .cloned()
+            .collect();
+        if let Err(e) = trust::set_trusted(&folder, trusted) {
+            log::warn!("{}", e);
+            ctx_ref.toast_overlay.add_toast(adw::Toast::new(&e));
+            return;
+        }
+        reopen_documents(&ctx_ref, restricted);
+        ctx_ref.sidebar_state.refresh();
+    });
+    dialog.present(Some(&ctx.window));
+}
+
+/// Re-send didOpen for the files among `restricted` that are now trusted,
+/// so language servers start for them.
+fn reopen_documents(ctx: &WindowContext, restricted: Vec<String>) {
+    for path in restricted {
+        if !trust::is_trusted(Path::new(&path)) {
+            continue;
+        }
+        let Some(handle) = editor::get_handle(&path) else {
+            continue;
+        };
+        let uri = ensure_file_uri(&path);
+        let mut versions = ctx.lsp.doc_versions.borrow_mut();
+        let version = versions.entry(path).or_insert(0);
+        *version += 1;
+        if let Err(e) = ctx.lsp.request_tx.try_send(LspRequest::DidOpen {
+            language_id: language_from_uri(&uri),
+            uri,
+            version: *version,
+            text: handle.get_content(),
+        }) {
+            log::warn!("LSP request channel full, dropping request: {}", e);
+        }
+    }
+}
@@ -0,0 +1,106 @@
+//! Per-tab and per-pane zoom: Ctrl+=/-/0 and Ctrl+scroll change the font
+//! size of the focused terminal pane or the selected editor only. The
+//! font size settings stay the default every pane is zoomed from.
+
+use gtk4::prelude::*;
+use libadwaita as adw;
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::editor;
+use crate::settings::Settings;
+use crate::status_bar::StatusBar;
+use crate::terminal;
+use crate::terminal_container;
+
+use super::run_guarded_ui;
+
+/// How a zoom command changes the zoom level.
+#[derive(Clone, Copy)]
+pub(super) enum ZoomChange {
+    In,
+    Out,
+    Reset,
+}
+
+/// Apply `change` to the focused pane of the selected tab.
+pub(super) fn zoom_selected(
+    tab_view: &adw::TabView,
+    settings: &Rc<RefCell<Settings>>,
+    status_bar: &Rc<RefCell<StatusBar>>,
+    change: ZoomChange,
+) {
+    let Some(page) = tab_view.selected_page() else {
+        return;
+    };
+    let child = page.child();
+    let next = |base: i32, zoom: i32| match change {
+        ZoomChange::In => impulse_core::zoom::step(base, zoom, 1),
+        ZoomChange::Out => impulse_core::zoom::step(base, zoom, -1),
+        ZoomChange::Reset => 0,
+    };
+    if let Some(term) = terminal_container::get_active_terminal(&child) {
+        if let Some((base, zoom)) = terminal::zoom(&term) {
+            terminal::set_zoom(&term, next(base, zoom));
+        }
+    } else if let Some(handle) = editor::get_handle_for_widget(&child) {
+        let s = settings.borrow();
+        handle.set_zoom(next(s.font_size, handle.zoom.get()), &s);
+    } else {
+        return;
+    }
+    refresh_status(tab_view, settings, status_bar);
+}
+
+/// Show the selected pane's zoom in the status bar.
+pub(super) fn refresh_status(
+    tab_view: &adw::TabView,
+    settings: &Rc<RefCell<Settings>>,
+    status_bar: &Rc<RefCell<StatusBar>>,
+) {
+    let label = tab_view.selected_page().and_then(|page| {
+        let child = page.child();
+        if let Some(term) = terminal_container::get_active_terminal(&child) {
+            let (base, zoom) = terminal::zoom(&term)?;
+            impulse_core::zoom::label(base, zoom)
+        } else {
+            let handle = editor::get_handle_for_widget(&child)?;
+            impulse_core::zoom::label(settings.borrow().font_size, handle.zoom.get())
+        }
+    });
+    status_bar.borrow().update_zoom(label.as_deref());
+}
+
+/// Ctrl+scroll over the tabs zooms the focused pane. Runs in the capture
+/// phase so terminals don't scroll back and editors don't zoom the page.
+pub(super) fn setup_scroll_zoom(
+    tab_view: &adw::TabView,
+    settings: &Rc<RefCell<Settings>>,
+    status_bar: &Rc<RefCell<StatusBar>>,
+) {
+    let controller = gtk4::EventControllerScroll::new(gtk4::EventControllerScrollFlags::VERTICAL);
+    controller.set_propagation_phase(gtk4::PropagationPhase::Capture);
+    let tab_view_ref = tab_view.clone();
+    let settings = settings.clone();
+    let status_bar = status_bar.clone();
+    controller.connect_scroll(move |controller, _, dy| {
+        if !controller
+            .current_event_state()
+            .contains(gtk4::gdk::ModifierType::CONTROL_MASK)
+            || dy == 0.0
+        {
+            return gtk4::glib::Propagation::Proceed;
+        }
+        let change = if dy < 0.0 {
+            ZoomChange::In
+        } else {
+            ZoomChange::Out
+        };
+        run_guarded_ui("scroll-zoom", || {
+            zoom_selected(&tab_view_ref, &settings, &status_bar, change)
+        });
+        gtk4::glib::Propagation::Stop
+    });
+    tab_view.add_controller(controller);
+}
@@ -13,33 +13,119 @@ void impulse_free_string(char *s);
 
 // Input completion
 void impulse_completion_warm_cache(void);
+// Environment for spawned tools: the login shell's, plus direnv when
+// enabled. environment_json returns a JSON object (dir may be NULL).
+void impulse_environment_warm(void);
+void impulse_environment_set_direnv_enabled(bool enabled);
+char *impulse_environment_json(const char *dir);
 
 // Monaco assets
 char *impulse_ensure_monaco_extracted(void);
 const char *impulse_get_editor_html(void);
 
+// Editor command batching. Push serialized EditorCommands and flush once per
+// frame: impulse_editor_batch_take returns a JSON array for
+// window.impulseReceiveCommands (NULL when empty; free with
+// impulse_free_string). Successive decoration sets are coalesced. Not
+// thread-safe; use from the WebView's thread.
+typedef struct EditorBatchHandle EditorBatchHandle;
+EditorBatchHandle *impulse_editor_batch_new(void);
+bool impulse_editor_batch_push(EditorBatchHandle *handle, const char *command_json);
+char *impulse_editor_batch_take(EditorBatchHandle *handle);
+void impulse_editor_batch_free(EditorBatchHandle *handle);
+
 // Shell integration
 char *impulse_get_shell_integration_script(const char *shell);
 char *impulse_get_user_login_shell(void);
 char *impulse_get_user_login_shell_name(void);
+// Where a new terminal tab starts for the terminal_new_tab_directory setting
+// mode ("home", "workspace", "current"). workspace and current may be NULL.
+char *impulse_new_terminal_directory(const char *mode, const char *home,
+                                     const char *workspace, const char *current);
+// What each terminal shell in a JSON array of PIDs is running: a JSON array of
+// {shell_pid, description, foreground, children}; description is null when idle.
+char *impulse_terminal_processes(const char *shell_pids_json);
+// Processes under a terminal shell that closing it should warn about, leaving
+// out the programs in ignored_json (a JSON array of names) and their children.
+int32_t impulse_terminal_close_risk_process_count(int32_t shell_pid, const char *ignored_json);
+// Why pasting text into a terminal should be confirmed (several lines outside
+// bracketed paste, or a sudo command), or NULL when it can be pasted directly.
+char *impulse_paste_confirmation(const char *text, bool bracketed);
+// Local paths in dropped text/uri-list data as a JSON array of strings.
+char *impulse_parse_uri_list(const char *text);
 
 // Search
 char *impulse_search_files(const char *root, const char *query);
 char *impulse_search_content(const char *root, const char *query, bool case_sensitive);
+// Content search in a scope; scope_json is {"kind": "workspace" | "open_files"
+// (+ "files": [{"path", "content"}]) | "directory" (+ "path") | "glob" (+ "pattern")}.
+char *impulse_search_content_in_scope(const char *root, const char *scope_json, const char *query, bool case_sensitive);
+// Index searched roots in memory (kept current by a watcher) so repeated
+// content searches only read files that can match. Off drops all indexes.
+void impulse_search_set_index_enabled(bool enabled);
+// Set the files_exclude / search_exclude settings (JSON arrays of
+// gitignore-style globs). Returns 0 on success, -1 on error.
+int32_t impulse_set_exclude_globs(const char *files_exclude_json, const char *search_exclude_json);
+// The follow_symlinks / scan_network_mounts settings for search, indexing
+// and file watching.
+void impulse_set_walk_options(bool follow_symlinks, bool scan_network_mounts);
+// The save_strategy setting: "atomic" (temp file + rename) or "in_place".
+void impulse_set_save_strategy(const char *strategy);
 
 // LSP management
 LspRegistryHandle *impulse_lsp_registry_new(const char *root_uri);
 int32_t impulse_lsp_ensure_servers(LspRegistryHandle *handle, const char *language_id, const char *file_uri);
 char *impulse_lsp_request(LspRegistryHandle *handle, const char *language_id, const char *file_uri, const char *method, const char *params_json);
 int32_t impulse_lsp_notify(LspRegistryHandle *handle, const char *language_id, const char *file_uri, const char *method, const char *params_json);
+// Returns -2 when `changes_json` no longer applies; resend with `full_text`.
 int32_t impulse_lsp_did_change(LspRegistryHandle *handle, const char *language_id, const char *file_uri, int32_t version, const char *full_text, const char *changes_json);
 char *impulse_lsp_poll_event(LspRegistryHandle *handle);
+
+// Event callbacks. The callback runs on a dedicated background thread, one
+// event at a time; `event_json` is borrowed and only valid during the call.
+// After the clear function (or the handle's free) returns, the callback is
+// never invoked again. A NULL callback unsubscribes.
+typedef void (*ImpulseEventCallback)(void *context, const char *event_json);
+int32_t impulse_lsp_set_event_callback(LspRegistryHandle *handle, ImpulseEventCallback callback, void *context);
+void impulse_lsp_clear_event_callback(LspRegistryHandle *handle);
+// Debounce intervals for completion/hover/signatureHelp/codeAction requests,
+// as the `lsp_debounce` settings object. Returns 0 on success, -1 on error.
+int32_t impulse_lsp_set_request_debounce(LspRegistryHandle *handle, const char *debounce_json);
+// server_ids_json: the disabled_lsp_servers setting, a JSON array of server ids.
+int32_t impulse_lsp_set_disabled_servers(LspRegistryHandle *handle, const char *server_ids_json);
 void impulse_lsp_shutdown_all(LspRegistryHandle *handle);
 void impulse_lsp_registry_free(LspRegistryHandle *handle);
 
-// Managed LSP installation
+// File watching. Events: { "kind": "created"|"modified"|"deleted"|"renamed",
+// "paths": [absolute path, ...] } ([from, to] for renames); changes inside
+// .git are not reported. impulse_watch_create returns NULL if root cannot be
+// watched. Callbacks follow the event callback rules above.
+typedef struct WatchHandle WatchHandle;
+WatchHandle *impulse_watch_create(const char *root);
+char *impulse_watch_poll_event(WatchHandle *handle);
+int32_t impulse_watch_set_event_callback(WatchHandle *handle, ImpulseEventCallback callback, void *context);
+void impulse_watch_clear_event_callback(WatchHandle *handle);
+void impulse_watch_free(WatchHandle *handle);
+
+// Managed LSP installation. disabled_json is the disabled_lsp_servers setting
+// (JSON array, or NULL). Status: [{ "name", "pinned_version",
+// "installed_version" (null if missing), "servers": [id], "enabled",
+// "kind": "npm" | "binary", "unsupported" (no binary for this platform),
+// "on_path" (binary already found in PATH) }].
+// impulse_lsp_install_start takes "install" | "update" | "uninstall" and a
+// JSON array of package names (NULL for all applicable), returning false if
+// another action is running. Progress events:
+//   { "type": "started", "action", "packages" } | { "type": "output", "line" }
+//   | { "type": "finished", "action", "success", "message" }
+// via the install callback (event callback rules) or impulse_lsp_install_poll_event.
 char *impulse_lsp_check_status(void);
 char *impulse_lsp_install(void);
+char *impulse_lsp_managed_status(const char *disabled_json);
+char *impulse_lsp_check_for_updates(const char *disabled_json);
+bool impulse_lsp_install_start(const char *action, const char *packages_json, const char *disabled_json);
+char *impulse_lsp_install_poll_event(void);
+void impulse_lsp_install_set_callback(ImpulseEventCallback callback, void *context);
+void impulse_lsp_install_clear_callback(void);
 bool impulse_npm_is_available(void);
 char *impulse_system_lsp_status(void);
 
@@ -59,10 +145,13 @@ char *impulse_git_branch(const char *path);
 char *impulse_git_branches(const char *path);
 char *impulse_git_status_for_directory(const char *path);
 char *impulse_get_all_git_statuses(const char *path);
+char *impulse_read_directory_entries(const char *path, bool show_hidden);
 char *impulse_read_directory_with_git_status(const char *path, bool show_hidden);
 char *impulse_build_file_tree_patch_batch(const char *root_path, const char *events_json, const char *before_by_parent_json, bool show_hidden);
 char *impulse_git_diff_markers(const char *file_path);
 char *impulse_git_blame(const char *file_path, uint32_t line);
+// Recompute a file's cached diff and blame in the background (after a save).
+void impulse_git_refresh_file_caches(const char *file_path);
 int32_t impulse_git_discard_changes(const char *file_path, const char *workspace_root);
 
 // Review Changes (git diff review)
@@ -94,15 +183,187 @@ char *impulse_git_commit_all(const char *repo_path, const char *message);
 // Discards changes for one REPO-RELATIVE file_path (checkout HEAD for tracked,
 // delete for untracked/new). Returns 0 on success or -1 on error.
 int32_t impulse_git_discard_path(const char *repo_path, const char *file_path);
+// Repository status as JSON:
+//   { "repo_root": string, "branch": string|null,
+//     "ahead_behind": { "ahead", "behind", "upstream" }|null,
+//     "entries": [{ "path": string, "index": string|null,
+//                   "worktree": string|null, "old_path": string|null }] }
+// index letters: "A"|"M"|"D"|"R"|"U"; worktree letters: "M"|"D"|"R"|"?"|"U".
+// On failure returns { "error": string }.
+char *impulse_git_status(const char *repo_path);
+// Stage / unstage a JSON array of REPO-RELATIVE paths. Return
+// { "ok": bool, "error": string|null }.
+char *impulse_git_stage(const char *repo_path, const char *paths_json);
+char *impulse_git_unstage(const char *repo_path, const char *paths_json);
+// Commits only staged changes. Same JSON as impulse_git_commit_all.
+char *impulse_git_commit(const char *repo_path, const char *message);
+// Up to `limit` commits from HEAD, newest first:
+//   [{ "commit_hash", "short_hash", "author", "timestamp": i64, "summary" }]
+// On failure returns { "error": string }.
+char *impulse_git_log(const char *repo_path, uint32_t limit);
+// Switches branch (remote names like "origin/x" create a tracking branch).
+// Returns { "ok": bool, "error": string|null }.
+char *impulse_git_checkout(const char *repo_path, const char *name);
+
+// Async operations. Each *_async call returns a nonzero operation id at once
+// (0 if it could not start). The finished result arrives as
+//   { "op_id": u64, "result": <the sync variant's JSON> }
+// via the operations callback (same threading rules as event callbacks) or,
+// with no callback set, from impulse_operation_poll (NULL while running;
+// returns the result once). impulse_cancel aborts an operation and returns 0,
+// or -1 for an unknown id; cancelled operations never deliver a result.
+void impulse_operations_set_callback(ImpulseEventCallback callback, void *context);
+void impulse_operations_clear_callback(void);
+char *impulse_operation_poll(uint64_t op_id);
+int32_t impulse_cancel(uint64_t op_id);
+uint64_t impulse_search_content_async(const char *root, const char *query, bool case_sensitive);
+// result: [{ "commit_hash", "short_hash", "author", "timestamp": i64, "summary" }]
+uint64_t impulse_git_log_async(const char *repo_path, uint32_t limit);
+uint64_t impulse_lsp_request_async(LspRegistryHandle *handle, const char *language_id, const char *file_uri, const char *method, const char *params_json);
 
 // Settings
 char *impulse_settings_default_json(void);
 char *impulse_settings_schema_json(void);
 char *impulse_settings_load_json(const char *json);
 char *impulse_settings_validate_json(const char *json);
+// Shared settings file; result: { "path", "settings": {...}, "warning": { "settings_path", "backup_path", "message" }|null }
+char *impulse_settings_load(void);
+// Merges json's top-level keys over the stored settings; result: { "ok", "error" }
+char *impulse_settings_save(const char *json);
+// Settings file watching. The callback fires (with "{}") on a watcher
+// thread when the file may have changed; check then returns
+// { "status": "unchanged"|"reloaded"|"invalid"|"conflict", "settings", "message" }
+// and resolve returns { "ok", "settings", "error" }.
+void impulse_settings_watch(ImpulseEventCallback callback, void *context);
+char *impulse_settings_check_external_change(void);
+char *impulse_settings_resolve_conflict(bool keep_app);
+char *impulse_config_export(const char *path);
+char *impulse_config_import(const char *path);
+char *impulse_config_sync(const char *url, int32_t keep_local);
+// Named window layouts: session windows (the session state's window JSON)
+// saved under a name in `dir`. list returns a JSON array of names; save
+// returns { "ok", "error" }; load returns { "ok", "window", "error" }.
+char *impulse_window_layout_list(const char *dir);
+char *impulse_window_layout_save(const char *dir, const char *name, const char *window_json);
+char *impulse_window_layout_load(const char *dir, const char *name);
+// Sidebar file actions. create makes the file or folder `name` in `dir`,
+// new files starting from the matching template in `templates_json` (the
+// settings' file_templates array); rename renames in place; duplicate
+// copies next to the original. All return { "ok", "path", "error" }.
+char *impulse_file_create(const char *dir, const char *name, bool is_dir, const char *templates_json);
+char *impulse_file_rename(const char *path, const char *new_name);
+char *impulse_file_duplicate(const char *path);
+// Properties dialog: { "ok", "properties", "size_label", "error" } for
+// `path`, and chmod returning { "ok", "error" }.
+char *impulse_file_properties(const char *path);
+char *impulse_file_set_mode(const char *path, uint32_t mode);
+// Trailing-whitespace and final-newline fixes before a save, per the
+// settings and .editorconfig. Returns { "content", "edits": [{range, text}] }
+// or NULL when nothing needs fixing.
+char *impulse_save_whitespace(const char *path, const char *content, bool trim,
+                              bool final_newline);
+// Saving with the save_strategy setting; successful saves go into local
+// history. Returns
+// { "ok", "error", "permission_denied" }.
+char *impulse_safe_write(const char *path, const char *content);
+// Saving as the administrator after a permission error (osascript with
+// administrator privileges). write returns { "ok", "error" }.
+bool impulse_privileged_write_available(void);
+char *impulse_privileged_write(const char *path, const char *content);
+// Remote files by sftp:// or ssh:// URI. open downloads into the cache and
+// returns { "ok", "path", "error" }; upload writes the copy back, returning
+// { "ok", "changed_on_server", "error" } (nothing is written when the
+// server's file changed, unless forced); uri returns the URI of a cached
+// copy or NULL; recent_uris returns a JSON array.
+char *impulse_remote_file_open(const char *uri);
+char *impulse_remote_file_upload(const char *path, bool force);
+char *impulse_remote_file_uri(const char *path);
+char *impulse_remote_file_recent_uris(void);
+char *impulse_vscode_import(const char *base_json);
 bool impulse_matches_file_pattern(const char *path, const char *pattern);
+// Copy Path commands: the text `command_id` (copy_path, copy_relative_path,
+// copy_file_name, copy_file_url) copies for `path`; NULL for other ids.
+char *impulse_path_for_copy(const char *command_id, const char *path, const char *root);
 char *impulse_close_risk_summary(const char *input_json);
 
+// Tab sorting and groups. `tabs_json` is `[{"title","path","pinned","modified",
+// "last_used"}]`; each returns a JSON array of tab indices.
+char *impulse_tab_sorted_order(const char *tabs_json, const char *sort);
+char *impulse_tabs_to_close(const char *tabs_json, uint32_t anchor, const char *group);
+char *impulse_tab_filter(const char *tabs_json, const char *query);
+
+// Per-tab and per-pane zoom: points added to the font size from settings.
+int32_t impulse_zoom_step(int32_t base, int32_t zoom, int32_t delta);
+int32_t impulse_zoom_font_size(int32_t base, int32_t zoom);
+char *impulse_zoom_label(int32_t base, int32_t zoom); // null at the default size
+
+// External formatters (settings "formatters" / "default_formatters").
+// Choice: { "mode": "lsp" | "lsp_or_fallback" | "external", "formatter": name|null }.
+// impulse_format_external blocks (max 10s); result: [LSP TextEdit] or { "error" }.
+char *impulse_formatter_choice(const char *language, const char *file_path);
+char *impulse_format_external(const char *language, const char *file_path, const char *text);
+
+// Keymap presets
+char *impulse_keymap_presets(void);
+char *impulse_keymap_preset_bindings(const char *preset);
+// Rebindable Monaco editor actions as a JSON array.
+char *impulse_editor_actions_json(void);
+// Visible status bar segments in order, from the order/hidden JSON arrays.
+char *impulse_status_bar_segments(const char *order_json, const char *hidden_json);
+// Working directory breadcrumbs as a JSON array of {"label", "path"}.
+char *impulse_status_bar_cwd_breadcrumbs(const char *path);
+// Detected indentation as JSON {"use_spaces": bool|null, "width": number|null}.
+char *impulse_detect_indentation(const char *content);
+// Lock tooltip text when the file should open read-only, or NULL.
+char *impulse_read_only_reason(const char *path, const char *content);
+
+// Hot exit: unsaved buffers backed up for restore after a crash.
+// Returns the previous run's backups as JSON [{"path","content","saved_at_ms"}].
+char *impulse_hot_exit_start(const char *state_dir);
+// buffers_json: [{"path","content"}] for every modified buffer.
+void impulse_hot_exit_sync(const char *buffers_json);
+void impulse_hot_exit_discard(void);
+void impulse_hot_exit_finish(void);
+
+// Local history: every save is also copied under <state_dir>/history.
+void impulse_local_history_set_state_dir(const char *state_dir);
+// The versions of path, newest first: [{"id","saved_at_ms","size","digest"}].
+char *impulse_local_history_entries(const char *path);
+// Where version id of path is stored, or NULL.
+char *impulse_local_history_version_path(const char *path, const char *id);
+// Returns {"content"} or {"error"}.
+char *impulse_local_history_read(const char *path, const char *id);
+// Diff two files on disk. Returns FileHunks JSON or NULL.
+char *impulse_diff_files(const char *old_path, const char *new_path);
+// Diff the saved file at path against an editor buffer. Returns FileHunks
+// JSON or NULL.
+char *impulse_diff_saved(const char *path, const char *buffer);
+
+// Language overrides. Languages: [{"id","label","monaco"}].
+char *impulse_languages(void);
+// overrides_json: the language_overrides setting, {"*.conf": "ini"}.
+void impulse_languages_set_overrides(const char *overrides_json);
+// The language_overrides key for path ("*.ext" or the file name), or NULL.
+char *impulse_language_override_key(const char *path);
+// The language path is overridden to, {"id","label","monaco"}, or NULL.
+char *impulse_language_override(const char *path);
+
+// Command-line open: args_json is a JSON array of arguments (no program name).
+// Returns {"targets":[{"path","line","column","is_dir"}],"new_window","wait"}.
+char *impulse_cli_parse_open_request(const char *args_json, const char *cwd);
+
+// Startup profile (--profile-startup): phase timings printed to stderr.
+void impulse_startup_profile_enable(void);
+void impulse_startup_profile_mark(const char *phase);
+void impulse_startup_profile_finish(const char *phase);
+
+// Crash reports. Install once at launch; take_unseen returns a JSON array of
+// reports (caller frees); submit returns NULL on success or an error message.
+void impulse_crash_reporter_install(const char *state_dir);
+void impulse_crash_report_write(const char *message, const char *backtrace);
+char *impulse_crash_report_take_unseen(void);
+char *impulse_crash_report_submit(const char *report_json, const char *endpoint);
+
 // Command palette
 char *impulse_command_palette_builtin_items_json(void);
 char *impulse_command_palette_custom_item_json(const char *name, const char *shortcut, const char *command, const char *args_json);
@@ -110,15 +371,81 @@ char *impulse_command_palette_filter_json(const char *items_json, const char *re
 char *impulse_command_palette_record_recent_json(const char *recents_json, const char *item_json, uint64_t now_ms, unsigned long max_items);
 char *impulse_command_palette_search_items_json(const char *root, const char *query, unsigned long limit);
 
+// Command registry. The handler receives {"id","args"} on the calling
+// thread; execute returns NULL on success or an error message.
+char *impulse_command_registry_json(const char *context_json);
+void impulse_set_command_handler(ImpulseEventCallback callback, void *context);
+char *impulse_execute_command(const char *id, const char *args_json);
+
+// Workspace trust. State is "trusted", "restricted" or "unknown"; set
+// returns NULL on success or an error message.
+void impulse_trust_set_enabled(bool enabled);
+char *impulse_trust_state(const char *path);
+char *impulse_trust_workspace_folder(const char *path);
+char *impulse_trust_set(const char *folder, bool trusted);
+
+// Assistant. Requests block until the model answers; call them off the main
+// thread. They return {"reply"} or {"error"} and fail while the assistant is
+// turned off in settings. Keys are stored in the system keyring.
+char *impulse_assistant_chat(const char *messages_json);
+char *impulse_assistant_explain_selection(const char *code, const char *language, const char *file_path);
+char *impulse_assistant_commit_message(const char *repo_path);
+bool impulse_assistant_set_api_key(const char *provider, const char *key);
+bool impulse_assistant_has_api_key(const char *provider);
+
+// Secrets in the system keyring. Kinds: "git_credential", "assistant_key",
+// "remote_password", "ssh_passphrase". The list is a JSON array without
+// values; get returns NULL when nothing is stored; set with an empty value
+// removes.
+char *impulse_secrets_list(void);
+char *impulse_secret_get(const char *kind, const char *name);
+bool impulse_secret_set(const char *kind, const char *name, const char *value);
+bool impulse_secret_delete(const char *id);
+
+// Agent access over MCP. Consent is per workspace folder ("allowed",
+// "denied" or "unknown"); start returns {"socket"} or {"error"}. Poll
+// next_request on the main loop ({"id", "kind", ...} or NULL) and answer
+// each with respond: a JSON result, or a non-NULL error.
+char *impulse_mcp_consent(const char *folder);
+bool impulse_mcp_set_consent(const char *folder, bool allowed);
+char *impulse_mcp_start(const char *path);
+void impulse_mcp_stop(void);
+char *impulse_mcp_next_request(void);
+bool impulse_mcp_respond(uint64_t id, const char *result_json, const char *error);
+void impulse_mcp_set_diagnostics(const char *path, const char *diagnostics_json);
+
+// Control socket for scripts and tools. start returns {"socket"} or
+// {"error"}. Poll next_call on the main loop ({"id", "method", "params"} or
+// NULL) and answer each with respond: a JSON result, or a non-NULL error.
+char *impulse_control_start(void);
+void impulse_control_stop(void);
+char *impulse_control_next_call(void);
+bool impulse_control_respond(uint64_t id, const char *result_json, const char *error);
+
+// Notification history. Push takes a JSON object and returns its id (0 if
+// invalid); the list is a JSON array, newest first.
+uint64_t impulse_notifications_push(const char *json);
+char *impulse_notifications_json(void);
+void impulse_notifications_mark_all_read(void);
+void impulse_notifications_dismiss(uint64_t id);
+void impulse_notifications_clear(void);
+
 // Theme API
 char *impulse_available_themes(void);
 char *impulse_theme_display_name(const char *id);
 char *impulse_get_theme(const char *name);
 char *impulse_get_monaco_theme(const char *name);
 char *impulse_get_markdown_theme(const char *name);
+// Make the theme getters above return the increased-contrast variant.
+void impulse_theme_set_increase_contrast(bool enabled);
+// Import a VS Code color theme file as a user theme. Returns {"id": ...} or
+// {"error": ...}.
+char *impulse_import_vscode_theme(const char *path);
 
 // Update checking
 char *impulse_check_for_update(void);
+char *impulse_check_for_update_now(void);
+char *impulse_update_install(const char *item_json);
 const char *impulse_get_version(void);
 
 // Terminal backend API
@@ -146,6 +473,8 @@ void impulse_terminal_start_selection(void *handle, unsigned short col, unsigned
 void impulse_terminal_update_selection(void *handle, unsigned short col, unsigned short row);
 void impulse_terminal_clear_selection(void *handle);
 char *impulse_terminal_selected_text(void *handle);
+// The whole buffer as text, scrollback included.
+char *impulse_terminal_scrollback_text(void *handle);
 void impulse_terminal_scroll(void *handle, int delta);
 void impulse_terminal_scroll_to_bottom(void *handle);
 _Bool impulse_terminal_scroll_to_command_block(void *handle, unsigned long long block_id);
@@ -159,4 +488,18 @@ void impulse_terminal_search_clear(void *handle);
 void impulse_terminal_set_colors(void *handle, const char *config_json);
 char *impulse_terminal_hyperlink_at(void *handle, unsigned int col, unsigned int row);
 
+// Raw PTY API (no terminal emulation). profile_json:
+//   { "shell_path", "shell_args", "working_directory", "env_vars", "cols", "rows" }
+// all optional. The callback runs on the session's reader thread with borrowed
+// output chunks; when the child exits it is called once with data == NULL and
+// the exit code. No calls happen after impulse_pty_free returns.
+typedef void (*ImpulsePtyCallback)(void *context, const uint8_t *data, size_t len, int32_t exit_code);
+void *impulse_pty_spawn(const char *profile_json, ImpulsePtyCallback callback, void *context);
+void impulse_pty_write(void *handle, const uint8_t *data, size_t len);
+void impulse_pty_resize(void *handle, unsigned short cols, unsigned short rows);
+int32_t impulse_pty_kill(void *handle, int32_t signal);
+unsigned int impulse_pty_child_pid(void *handle);
+char *impulse_pty_cwd(void *handle);
+void impulse_pty_free(void *handle);
+
 #endif
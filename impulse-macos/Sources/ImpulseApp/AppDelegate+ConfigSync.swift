@@ -0,0 +1,170 @@
+import AppKit
+import UniformTypeIdentifiers
+
+// MARK: - Settings Export, Import and Sync
+
+/// Moves settings, keybindings, custom CSS and user themes between machines
+/// as an archive or through the git repository in `settingsSyncRepository`.
+/// Imported and pulled settings apply like an outside edit of the file.
+extension AppDelegate {
+
+  /// "Export Settings": writes the configuration to an archive.
+  @objc func exportSettings(_ sender: Any?) {
+    let panel = NSSavePanel()
+    panel.nameFieldStringValue = "impulse-settings.zip"
+    panel.allowedContentTypes = [.zip]
+    panel.canCreateDirectories = true
+    guard panel.runModal() == .OK, let url = panel.url else { return }
+    switch ImpulseCore.exportConfig(to: url.path) {
+    case .success(let count):
+      ImpulseCore.notify(
+        .info, title: "Settings exported", message: "\(count) file(s) saved to \(url.path)",
+        source: "settings")
+    case .failure(let error):
+      ImpulseCore.notify(
+        .error, title: "Settings export failed", message: error.message, source: "settings")
+    }
+  }
+
+  /// "Import Settings": restores an exported archive and applies it.
+  @objc func importSettings(_ sender: Any?) {
+    let panel = NSOpenPanel()
+    panel.allowedContentTypes = [.zip]
+    panel.allowsMultipleSelection = false
+    guard panel.runModal() == .OK, let url = panel.url else { return }
+    switch ImpulseCore.importConfig(from: url.path) {
+    case .success(let count):
+      checkSettingsFile(announce: false)
+      ImpulseCore.notify(
+        .info, title: "Settings imported", message: "\(count) file(s) restored",
+        source: "settings")
+    case .failure(let error):
+      ImpulseCore.notify(
+        .error, title: "Settings import failed", message: error.message, source: "settings")
+    }
+  }
+
+  /// "Sync Settings": syncs with the configured repository, asking which
+  /// side to keep on a conflict.
+  @objc func syncSettings(_ sender: Any?) {
+    let url = settings.settingsSyncRepository
+    guard !url.isEmpty else {
+      ImpulseCore.notify(
+        .warning, title: "Settings sync is off",
+        message: "Set a sync repository under Settings → General.", source: "settings",
+        actions: [.init(label: "Open Settings", command: "open_settings")])
+      return
+    }
+    runConfigSync(url: url) { [weak self] result in
+      if case .success(.conflict) = result {
+        self?.askAboutConfigSyncConflict(url: url)
+      } else {
+        self?.reportConfigSync(result)
+      }
+    }
+  }
+
+  /// Syncs at launch when a repository is set. Conflicts are left for
+  /// "Sync Settings" to ask about.
+  func syncSettingsAtLaunch() {
+    let url = settings.settingsSyncRepository
+    guard !url.isEmpty else { return }
+    runConfigSync(url: url) { [weak self] result in
+      switch result {
+      case .success(.conflict):
+        ImpulseCore.notify(
+          .warning, title: "Settings sync needs a decision",
+          message: "Settings changed both here and in the sync repository.", source: "settings",
+          actions: [.init(label: "Sync Settings", command: "sync_settings")])
+      case .success(.upToDate):
+        break
+      default:
+        self?.reportConfigSync(result)
+      }
+    }
+  }
+
+  /// "Import VS Code Settings": shows what carries over from VS Code and
+  /// applies it when confirmed.
+  @objc func importVscodeSettings(_ sender: Any?) {
+    let result: ImpulseCore.VscodeImport
+    switch ImpulseCore.importVscode(over: settings) {
+    case .success(let imported):
+      result = imported
+    case .failure(let error):
+      ImpulseCore.notify(
+        .error, title: "VS Code import failed", message: error.message, source: "settings")
+      return
+    }
+    let alert = NSAlert()
+    alert.messageText = "Import VS Code Settings?"
+    var summary = "\(result.imported.count) setting(s) and keybinding(s) will replace yours."
+    if !result.unmapped.isEmpty {
+      summary += "\n\nNo Impulse equivalent: " + result.unmapped.joined(separator: ", ")
+    }
+    alert.informativeText = summary
+    alert.addButton(withTitle: "Import")
+    alert.addButton(withTitle: "Cancel")
+    guard alert.runModal() == .alertFirstButtonReturn else { return }
+
+    result.settings.save()
+    SettingsWindowController.settingsReloaded(result.settings)
+    NotificationCenter.default.post(name: .impulseSettingsDidChange, object: result.settings)
+    ImpulseCore.notify(
+      .info, title: "VS Code settings imported",
+      message: result.unmapped.isEmpty
+        ? "\(result.imported.count) item(s) imported."
+        : "\(result.imported.count) item(s) imported. Not mapped: "
+          + result.unmapped.joined(separator: ", "),
+      source: "settings")
+  }
+
+  private func askAboutConfigSyncConflict(url: String) {
+    let alert = NSAlert()
+    alert.messageText = "Settings Changed in Both Places"
+    alert.informativeText =
+      "Settings changed both on this machine and in the sync repository since the last sync. Which version do you want to keep?"
+    alert.addButton(withTitle: "Use Repository Version")
+    alert.addButton(withTitle: "Keep This Mac’s")
+    alert.addButton(withTitle: "Cancel")
+    let response = alert.runModal()
+    guard response != .alertThirdButtonReturn else { return }
+    let keepLocal = response == .alertSecondButtonReturn
+    runConfigSync(url: url, keepLocal: keepLocal) { [weak self] result in
+      self?.reportConfigSync(result)
+    }
+  }
+
+  private func runConfigSync(
+    url: String, keepLocal: Bool? = nil,
+    completion: @escaping (Result<ImpulseCore.ConfigSyncOutcome, ImpulseError>) -> Void
+  ) {
+    DispatchQueue.global(qos: .utility).async {
+      let result = ImpulseCore.syncConfig(url: url, keepLocal: keepLocal)
+      if case .failure(let error) = result {
+        NSLog("Settings sync with %@ failed: %@", url, error.message)
+      }
+      DispatchQueue.main.async { completion(result) }
+    }
+  }
+
+  private func reportConfigSync(_ result: Result<ImpulseCore.ConfigSyncOutcome, ImpulseError>) {
+    switch result {
+    case .success(.pulled):
+      checkSettingsFile(announce: false)
+      ImpulseCore.notify(
+        .info, title: "Settings synced", message: "Applied settings from the sync repository.",
+        source: "settings")
+    case .success(.pushed):
+      ImpulseCore.notify(
+        .info, title: "Settings synced", message: "Pushed settings to the sync repository.",
+        source: "settings")
+    case .success(.upToDate), .success(.conflict):
+      ImpulseCore.notify(.info, title: "Settings are up to date", source: "settings")
+    case .failure(let error):
+      ImpulseCore.notify(
+        .error, title: "Settings sync failed", message: error.message, source: "settings",
+        actions: [.init(label: "Retry", command: "sync_settings")])
+    }
+  }
+}
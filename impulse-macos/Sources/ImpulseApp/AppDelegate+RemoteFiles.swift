@@ -0,0 +1,88 @@
+import AppKit
+
+// MARK: - Remote Files
+
+/// "Open Remote File…": downloads an `sftp://` or `ssh://` file into the
+/// core's cache and opens the copy in the key window. Saving the copy
+/// uploads it again (`RemoteFiles.uploadAfterSave`).
+extension AppDelegate {
+
+  @objc func openRemoteFile(_ sender: Any?) {
+    guard let controller = NSApp.keyWindow?.windowController as? MainWindowController
+    else { return }
+
+    let alert = NSAlert()
+    alert.messageText = "Open Remote File"
+    alert.informativeText =
+      "Downloads the file over SSH and uploads it again when you save. "
+      + "The host must accept your SSH key without a prompt."
+    alert.addButton(withTitle: "Open")
+    alert.addButton(withTitle: "Cancel")
+    let field = NSComboBox(frame: NSRect(x: 0, y: 0, width: 360, height: 26))
+    field.addItems(withObjectValues: ImpulseCore.recentRemoteFileURIs())
+    field.placeholderString = "sftp://user@host/path/to/file"
+    alert.accessoryView = field
+    alert.window.initialFirstResponder = field
+    guard alert.runModal() == .alertFirstButtonReturn else { return }
+
+    let uri = field.stringValue.trimmingCharacters(in: .whitespaces)
+    guard !uri.isEmpty else { return }
+    DispatchQueue.global(qos: .userInitiated).async { [weak controller] in
+      let result = ImpulseCore.openRemoteFile(uri: uri)
+      DispatchQueue.main.async {
+        switch result {
+        case .success(let path):
+          controller?.openFile(path: path, revealInTree: false)
+        case .failure(let error):
+          ImpulseCore.notify(
+            .error, title: "Couldn't open remote file", message: error.message, source: "remote")
+        }
+      }
+    }
+  }
+}
+
+/// Uploads saved copies of remote files, asking before overwriting a file
+/// that changed on the server.
+enum RemoteFiles {
+
+  /// Upload `path` in the background if it is a remote file's cached copy.
+  static func uploadAfterSave(path: String) {
+    guard ImpulseCore.remoteFileURI(for: path) != nil else { return }
+    upload(path: path, force: false)
+  }
+
+  private static func upload(path: String, force: Bool) {
+    DispatchQueue.global(qos: .utility).async {
+      let result = ImpulseCore.uploadRemoteFile(at: path, force: force)
+      DispatchQueue.main.async {
+        switch result {
+        case .success(true):
+          break
+        case .success(false):
+          askToOverwrite(path: path)
+        case .failure(let error):
+          ImpulseCore.notify(
+            .error, title: "Couldn't upload remote file", message: error.message,
+            source: "remote")
+        }
+      }
+    }
+  }
+
+  private static func askToOverwrite(path: String) {
+    let uri = ImpulseCore.remoteFileURI(for: path) ?? path
+    let alert = NSAlert()
+    alert.alertStyle = .warning
+    alert.messageText = "File Changed on the Server"
+    alert.informativeText =
+      "\(uri) changed on the server since it was opened. Overwrite it with your version? "
+      + "The save is kept locally either way."
+    alert.addButton(withTitle: "Don't Upload")
+    let overwrite = alert.addButton(withTitle: "Overwrite")
+    overwrite.hasDestructiveAction = true
+    if alert.runModal() == .alertSecondButtonReturn {
+      upload(path: path, force: true)
+    }
+  }
+}
@@ -0,0 +1,85 @@
+import AppKit
+
+// MARK: - Window Layouts
+
+/// Named window layouts: "Save Window Layout As…" stores the key window's
+/// workspace folder, tabs and sidebar under a name, and "Open Layout" opens
+/// one in a new window. Layouts live in a `layouts` folder next to the
+/// session state.
+extension AppDelegate {
+
+  static func windowLayoutsDirectory() -> String {
+    SessionState.filePath()
+      .deletingLastPathComponent()
+      .appendingPathComponent("layouts")
+      .path
+  }
+
+  /// "Save Window Layout As…": asks for a name and saves the key window.
+  @objc func saveWindowLayout(_ sender: Any?) {
+    guard let controller = NSApp.keyWindow?.windowController as? MainWindowController
+    else { return }
+    let state = controller.windowLayoutState()
+    let dir = Self.windowLayoutsDirectory()
+
+    let alert = NSAlert()
+    alert.messageText = "Save Window Layout"
+    alert.informativeText = "Saves the workspace folder, open tabs and sidebar under a name."
+    alert.addButton(withTitle: "Save")
+    alert.addButton(withTitle: "Cancel")
+    let field = NSComboBox(frame: NSRect(x: 0, y: 0, width: 260, height: 26))
+    field.addItems(withObjectValues: ImpulseCore.windowLayoutNames(in: dir))
+    field.placeholderString = "Layout name"
+    alert.accessoryView = field
+    alert.window.initialFirstResponder = field
+    guard alert.runModal() == .alertFirstButtonReturn else { return }
+
+    let name = field.stringValue.trimmingCharacters(in: .whitespaces)
+    guard !name.isEmpty else { return }
+    switch ImpulseCore.saveWindowLayout(state, named: name, in: dir) {
+    case .success:
+      ImpulseCore.notify(.info, title: "Saved layout \"\(name)\"", source: "layouts")
+    case .failure(let error):
+      ImpulseCore.notify(
+        .error, title: "Saving the layout failed", message: error.message, source: "layouts")
+    }
+  }
+
+  /// "Open Layout": picks a saved layout and opens it in a new window.
+  @objc func openWindowLayout(_ sender: Any?) {
+    let dir = Self.windowLayoutsDirectory()
+    let names = ImpulseCore.windowLayoutNames(in: dir)
+    guard !names.isEmpty else {
+      ImpulseCore.notify(
+        .info, title: "No saved window layouts",
+        message: "Save one with \"Save Window Layout As…\".", source: "layouts")
+      return
+    }
+
+    let alert = NSAlert()
+    alert.messageText = "Open Layout"
+    alert.informativeText = "The layout opens in a new window."
+    alert.addButton(withTitle: "Open")
+    alert.addButton(withTitle: "Cancel")
+    let popup = NSPopUpButton(frame: NSRect(x: 0, y: 0, width: 260, height: 26), pullsDown: false)
+    popup.addItems(withTitles: names)
+    alert.accessoryView = popup
+    guard alert.runModal() == .alertFirstButtonReturn,
+      let name = popup.titleOfSelectedItem
+    else { return }
+
+    switch ImpulseCore.loadWindowLayout(named: name, in: dir) {
+    case .success(let state):
+      openNewWindow(skipInitialTerminal: true)
+      guard let controller = windowControllers.last else { return }
+      DispatchQueue.main.async {
+        if !controller.restoreSessionWindow(state) {
+          controller.tabManager.addTerminalTab()
+        }
+      }
+    case .failure(let error):
+      ImpulseCore.notify(
+        .error, title: "Opening the layout failed", message: error.message, source: "layouts")
+    }
+  }
+}
@@ -12,9 +12,16 @@ final class AppDelegate: NSObject, NSApplicationDelegate {
   /// window and saved on quit.
   var settings: Settings = .default
 
-  /// The current color theme, derived from `settings.colorScheme`.
+  /// The current color theme, derived from `settings.colorScheme` or, when
+  /// following the system appearance, its light/dark counterparts.
   var theme: Theme = ThemeManager.theme(forName: "nord")
 
+  /// ID of the theme in `theme`, to skip re-applying an unchanged theme.
+  private var activeThemeName = "nord"
+
+  /// Whether `theme` is the increased-contrast variant.
+  private var increaseContrast = false
+
   /// The FFI bridge to impulse-core/impulse-editor Rust code.
   let core = ImpulseCore()
 
@@ -23,14 +30,21 @@ final class AppDelegate: NSObject, NSApplicationDelegate {
   /// concurrently from per-window queues.
   let lspQueue = DispatchQueue(label: "dev.impulse.lsp", qos: .userInitiated)
 
-  /// Single app-level LSP event poller. Diagnostics are fanned out to the
-  /// window that still owns the target document.
-  private var lspPollTimer: Timer?
-  private var isPollingLspEvents = false
   private var settingsObserver: NSObjectProtocol?
+  private var cliOpenObserver: NSObjectProtocol?
+  private var editorCloseObserver: NSObjectProtocol?
+  /// `--wait` launches handed over by `CLIOpen` and the files they still
+  /// wait on.
+  private var cliWaiters: [(token: String, paths: Set<String>)] = []
+  /// Picks up control socket calls while `controlSocketEnabled` is on.
+  private var controlSocketTimer: Timer?
+  private var appearanceObservation: NSKeyValueObservation?
+  private var accessibilityDisplayObserver: NSObjectProtocol?
 
   /// File paths to open once the first window is ready (from Finder or CLI).
   var pendingFiles: [String] = []
+  /// Positions for pending files given as `path:line[:column]`.
+  var pendingPositions: [String: (line: UInt32, column: UInt32)] = [:]
 
   /// True once the app-level termination flow has already handled dirty
   /// editor review, so individual windows should not prompt again.
@@ -40,11 +54,35 @@ final class AppDelegate: NSObject, NSApplicationDelegate {
   /// run loop.
   private var windowControllers: [MainWindowController] = []
 
+  /// Backs up unsaved buffers every `hotExitInterval` seconds.
+  private var hotExitTimer: Timer?
+  private static let hotExitInterval: TimeInterval = 10
+
   func applicationDidFinishLaunching(_ notification: Notification) {
+    ImpulseCore.enableStartupProfileIfRequested()
+    installCrashReporter()
     settings = Settings.load()
-    theme = ThemeManager.theme(forName: settings.colorScheme)
+    ImpulseCore.markStartupPhase("settings")
+    ImpulseCore.setSearchIndexEnabled(settings.searchIndexEnabled)
+    ImpulseCore.setWorkspaceTrustEnabled(settings.workspaceTrustEnabled)
+    ImpulseCore.setDirenvEnabled(settings.useDirenv)
+    ImpulseCore.setLanguageOverrides(settings.languageOverrides)
+    ImpulseCore.warmEnvironment()
+    ImpulseCore.setExcludeGlobs(files: settings.filesExclude, search: settings.searchExclude)
+    ImpulseCore.setWalkOptions(
+      followSymlinks: settings.followSymlinks, scanNetworkMounts: settings.scanNetworkMounts)
+    ImpulseCore.setSaveStrategy(settings.saveStrategy)
+    syncIncreaseContrast()
+    activeThemeName = settings.activeColorScheme(systemIsDark: ThemeManager.systemIsDark)
+    theme = ThemeManager.theme(forName: activeThemeName)
+    installCommandHandler()
     rebuildMainMenu()
     observeSettingsChanges()
+    watchSettingsFile()
+    syncSettingsAtLaunch()
+    observeSystemAppearance()
+    observeCLIOpenRequests()
+    updateControlSocket()
 
     // Pre-warm a WebView with Monaco so the first editor tab opens instantly.
     EditorWebViewPool.shared.warmUp()
@@ -64,8 +102,9 @@ final class AppDelegate: NSObject, NSApplicationDelegate {
       rootDir = NSHomeDirectory()
     }
     let rootUri = URL(fileURLWithPath: rootDir).absoluteString
+    subscribeToLspEvents()
     core.initializeLsp(rootUri: rootUri)
-    startLspPolling()
+    ImpulseCore.markStartupPhase("lsp registry")
 
     let sessionToRestore: SessionWindowState?
     if pendingFiles.isEmpty && settings.restoreSession {
@@ -88,6 +127,7 @@ final class AppDelegate: NSObject, NSApplicationDelegate {
     }
 
     openNewWindow(skipInitialTerminal: sessionToRestore != nil || !filesToOpen.isEmpty)
+    ImpulseCore.markStartupPhase("window built")
 
     if let sessionToRestore {
       DispatchQueue.main.async { [weak self] in
@@ -105,31 +145,126 @@ final class AppDelegate: NSObject, NSApplicationDelegate {
       let files = filesToOpen
       pendingFiles.removeAll()
       DispatchQueue.main.async { [weak self] in
-        guard let controller = self?.windowControllers.first else { return }
+        guard let self, let controller = self.windowControllers.first else { return }
         for path in files {
           controller.openFile(path: path)
+          if let position = self.pendingPositions.removeValue(forKey: path) {
+            controller.findEditorTab(forPath: path)?.goToPosition(
+              line: position.line, column: position.column)
+          }
         }
       }
     }
 
     NSApp.activate(ignoringOtherApps: true)
+    ImpulseCore.markStartupPhase("window presented")
+    DispatchQueue.main.async {
+      ImpulseCore.finishStartupProfile("first run loop turn")
+    }
+
+    startHotExit()
+    ImpulseCore.setLocalHistoryStateDir(
+      Settings.settingsPath().deletingLastPathComponent().path)
+    offerCrashReport()
 
     // Check for updates in background if enabled.
     if settings.checkForUpdates {
+      let autoInstall = settings.autoInstallUpdates
       DispatchQueue.global(qos: .utility).async {
         guard let update = ImpulseCore.checkForUpdate() else { return }
+        var installed = false
+        if autoInstall, update.canInstall, let item = update.itemJSON {
+          installed = ImpulseCore.installUpdate(itemJSON: item) == nil
+        }
         DispatchQueue.main.async {
           NotificationCenter.default.post(
             name: .impulseUpdateAvailable,
             object: nil,
             userInfo: [
               "version": update.version, "currentVersion": update.currentVersion, "url": update.url,
+              "installed": installed,
             ])
         }
       }
     }
   }
 
+  // MARK: - Updates
+
+  /// "Check for Updates…": checks now and offers to install, or says where
+  /// the update comes from when this copy can't update itself.
+  @objc func checkForUpdates(_ sender: Any?) {
+    DispatchQueue.global(qos: .userInitiated).async {
+      let result = ImpulseCore.checkForUpdateNow()
+      DispatchQueue.main.async {
+        let alert = NSAlert()
+        switch result {
+        case .failure(let error):
+          alert.messageText = "Couldn’t Check for Updates"
+          alert.informativeText = error.message
+          alert.runModal()
+        case .success(nil):
+          alert.messageText = "Impulse Is Up to Date"
+          alert.informativeText = "Version \(ImpulseCore.version()) is the latest version."
+          alert.runModal()
+        case .success(let update?):
+          alert.messageText = "Impulse \(update.version) Is Available"
+          alert.informativeText =
+            update.canInstall
+            ? "You have version \(update.currentVersion)."
+            : "You have version \(update.currentVersion). \(update.hint)"
+          if update.canInstall {
+            alert.addButton(withTitle: "Install and Relaunch")
+          }
+          alert.addButton(withTitle: "Release Notes")
+          alert.addButton(withTitle: "Later")
+          let response = alert.runModal()
+          let notesResponse: NSApplication.ModalResponse =
+            update.canInstall ? .alertSecondButtonReturn : .alertFirstButtonReturn
+          if update.canInstall, response == .alertFirstButtonReturn, let item = update.itemJSON {
+            self.installUpdateAndRelaunch(itemJSON: item)
+          } else if response == notesResponse, let url = URL(string: update.url) {
+            NSWorkspace.shared.open(url)
+          }
+        }
+      }
+    }
+  }
+
+  private func installUpdateAndRelaunch(itemJSON: String) {
+    DispatchQueue.global(qos: .userInitiated).async {
+      let error = ImpulseCore.installUpdate(itemJSON: itemJSON)
+      DispatchQueue.main.async {
+        if let error {
+          let alert = NSAlert()
+          alert.messageText = "Update Failed"
+          alert.informativeText = error
+          alert.alertStyle = .warning
+          alert.runModal()
+        } else {
+          self.relaunch()
+        }
+      }
+    }
+  }
+
+  /// Quit and reopen the (updated) bundle once this process has exited.
+  /// If quitting is cancelled, the relaunch waits for the eventual quit.
+  func relaunch() {
+    let task = Process()
+    task.executableURL = URL(fileURLWithPath: "/bin/sh")
+    task.arguments = [
+      "-c", "while kill -0 \"$1\" 2>/dev/null; do sleep 0.2; done; /usr/bin/open \"$0\"",
+      Bundle.main.bundlePath, String(ProcessInfo.processInfo.processIdentifier),
+    ]
+    do {
+      try task.run()
+    } catch {
+      NSLog("Failed to schedule relaunch: %@", error.localizedDescription)
+    }
+    NSApp.terminate(nil)
+  }
+
   func application(_ sender: NSApplication, openFiles filenames: [String]) {
     if let controller = windowControllers.first {
       for path in filenames {
@@ -252,6 +387,7 @@ final class AppDelegate: NSObject, NSApplicationDelegate {
       unsavedEditorCount: 0,
       runningTerminalProcessCount: runningTerminalProcessCount(),
       runningCommands: runningCloseRiskCommands(),
+      ignoredCommands: settings.terminalCloseIgnoredCommands,
       nowMs: currentUnixTimeMs(),
       longCommandThresholdSeconds: UInt64(max(1, settings.terminalLongCommandSeconds))
     )
@@ -291,6 +427,8 @@ final class AppDelegate: NSObject, NSApplicationDelegate {
 
   func applicationWillTerminate(_ notification: Notification) {
     persistSessionStateFromOpenWindows()
+    hotExitTimer?.invalidate()
+    ImpulseCore.hotExitFinish()
 
     // Persist window geometry from the frontmost window.
     if let front = windowControllers.first(where: { $0.window?.isKeyWindow == true })
@@ -306,7 +444,6 @@ final class AppDelegate: NSObject, NSApplicationDelegate {
       NotificationCenter.default.removeObserver(settingsObserver)
       self.settingsObserver = nil
     }
-    stopLspPolling()
     core.shutdownLsp()
   }
 
@@ -314,20 +451,6 @@ final class AppDelegate: NSObject, NSApplicationDelegate {
     return true
   }
 
-  /// Pause the LSP event poll timer while the app is in the background —
-  /// no typing can happen, so there is nothing latency-sensitive to drain
-  /// and the 25 ms timer would just burn CPU/battery.
-  func applicationDidResignActive(_ notification: Notification) {
-    stopLspPolling()
-  }
-
-  func applicationDidBecomeActive(_ notification: Notification) {
-    startLspPolling()
-    // Drain anything that queued while inactive (e.g. diagnostics from a
-    // build that touched watched files).
-    pollLspEventsInBackground()
-  }
-
   func applicationSupportsSecureRestorableState(_ app: NSApplication) -> Bool {
     return true
   }
@@ -350,6 +473,24 @@ final class AppDelegate: NSObject, NSApplicationDelegate {
     controller.handleThemeChange(theme)
   }
 
+  /// The front-most Impulse window under `screenPoint`, other than `excluded`.
+  func windowController(
+    at screenPoint: NSPoint, excluding excluded: MainWindowController
+  ) -> MainWindowController? {
+    NSApp.orderedWindows.lazy
+      .compactMap { $0.windowController as? MainWindowController }
+      .first { $0 !== excluded && $0.window?.frame.contains(screenPoint) == true }
+  }
+
+  /// Opens an empty window with its top-left corner at `screenPoint`, for a
+  /// tab dragged out of another window.
+  func openWindowForMovedTab(at screenPoint: NSPoint) -> MainWindowController? {
+    openNewWindow(skipInitialTerminal: true)
+    let controller = windowControllers.last
+    controller?.window?.setFrameTopLeftPoint(screenPoint)
+    return controller
+  }
+
   /// Removes the window controller from our list when its window closes.
   func windowControllerDidClose(_ controller: MainWindowController) {
     windowControllers.removeAll { $0 === controller }
@@ -378,14 +519,63 @@ final class AppDelegate: NSObject, NSApplicationDelegate {
 
   /// Changes the active theme across all windows and persists the choice.
   func applyTheme(named name: String) {
-    theme = ThemeManager.theme(forName: name)
     settings.colorScheme = name
+    applyActiveTheme()
+  }
+
+  /// Resolves the theme from the settings and the system appearance and
+  /// applies it to every window: chrome, Monaco, terminals and previews.
+  func applyActiveTheme() {
+    activeThemeName = settings.activeColorScheme(systemIsDark: ThemeManager.systemIsDark)
+    theme = ThemeManager.theme(forName: activeThemeName)
     for controller in windowControllers {
       controller.handleThemeChange(theme)
     }
     NotificationCenter.default.post(name: .impulseThemeDidChange, object: theme)
   }
 
+  /// Re-applies the theme when the system switches between light and dark
+  /// while the settings follow the system appearance.
+  private func observeSystemAppearance() {
+    appearanceObservation = NSApp.observe(\.effectiveAppearance) { [weak self] _, _ in
+      DispatchQueue.main.async {
+        guard let self, self.settings.followSystemAppearance else { return }
+        self.applyActiveThemeIfChanged()
+      }
+    }
+    // The system Increase Contrast option switches to the increased-contrast
+    // theme variant, like the `increase_contrast` setting.
+    accessibilityDisplayObserver = NSWorkspace.shared.notificationCenter.addObserver(
+      forName: NSWorkspace.accessibilityDisplayOptionsDidChangeNotification,
+      object: nil,
+      queue: .main
+    ) { [weak self] _ in
+      self?.applyActiveThemeIfChanged()
+    }
+  }
+
+  private func applyActiveThemeIfChanged() {
+    let contrastChanged = syncIncreaseContrast()
+    if contrastChanged
+      || settings.activeColorScheme(systemIsDark: ThemeManager.systemIsDark) != activeThemeName
+    {
+      applyActiveTheme()
+    }
+  }
+
+  /// Tells the core whether to resolve increased-contrast themes. Returns
+  /// true when that changed.
+  @discardableResult
+  private func syncIncreaseContrast() -> Bool {
+    let enabled =
+      settings.increaseContrast
+      || NSWorkspace.shared.accessibilityDisplayShouldIncreaseContrast
+    guard enabled != increaseContrast else { return false }
+    increaseContrast = enabled
+    ImpulseCore.setIncreaseContrast(enabled)
+    return true
+  }
+
   // MARK: Menu Actions
 
   @objc func showPreferences(_ sender: Any?) {
@@ -396,64 +586,376 @@ final class AppDelegate: NSObject, NSApplicationDelegate {
     openNewWindow()
   }
 
-  private func startLspPolling() {
-    guard lspPollTimer == nil else { return }
-    // 25 ms floor: fast enough that typing -> diagnostics/completions feels
-    // instant, but still much cheaper than the editor repaint budget.
-    lspPollTimer = Timer.scheduledTimer(withTimeInterval: 0.025, repeats: true) { [weak self] _ in
-      self?.pollLspEventsInBackground()
+  /// Single app-level LSP event subscription. Rust pushes events on its
+  /// own thread; diagnostics are parsed there and fanned out on the main
+  /// thread to the window that still owns the target document. Server
+  /// failures go to the notification history.
+  private func subscribeToLspEvents() {
+    core.setLspEventHandler { [weak self] json in
+      Self.notifyLspServerError(json)
+      guard let event = Self.parseLspDiagnostics(json) else { return }
+      DispatchQueue.main.async { [weak self] in
+        guard let self else { return }
+        for controller in self.windowControllers {
+          controller.applyLspDiagnostics(
+            uri: event.uri,
+            diagnosticsArray: event.diagnostics
+          )
+        }
+      }
     }
   }
 
-  private func stopLspPolling() {
-    lspPollTimer?.invalidate()
-    lspPollTimer = nil
+  /// Server errors already reported since the last server came up, so a
+  /// server failing on every file open is reported once. Only touched on
+  /// the LSP event thread.
+  private static var reportedLspErrors: Set<String> = []
+
+  private static func notifyLspServerError(_ json: String) {
+    guard let data = json.data(using: .utf8),
+      let event = try? JSONSerialization.jsonObject(with: data) as? [String: Any],
+      let type = event["type"] as? String
+    else { return }
+    if type == "initialized" {
+      reportedLspErrors.removeAll()
+      return
+    }
+    guard type == "serverError",
+      let serverId = event["serverId"] as? String,
+      let message = event["message"] as? String,
+      reportedLspErrors.insert("\(serverId)|\(message)").inserted
+    else { return }
+    if message.contains("install-lsp-servers") {
+      ImpulseCore.notify(
+        .warning, title: "LSP '\(serverId)' is not installed", source: "lsp",
+        actions: [.init(label: "Install Web LSP Servers", command: "install_lsp")])
+    } else {
+      ImpulseCore.notify(
+        .error, title: "LSP '\(serverId)' failed to start", message: message, source: "lsp")
+    }
   }
 
-  private func pollLspEventsInBackground() {
-    guard !isPollingLspEvents else { return }
-    isPollingLspEvents = true
+  private static func parseLspDiagnostics(_ json: String) -> LspDiagnosticsEvent? {
+    guard let data = json.data(using: .utf8),
+      let event = try? JSONSerialization.jsonObject(with: data) as? [String: Any],
+      event["type"] as? String == "diagnostics",
+      let uri = event["uri"] as? String,
+      let diagnostics = event["diagnostics"] as? [[String: Any]]
+    else { return nil }
+    return LspDiagnosticsEvent(uri: uri, diagnostics: diagnostics)
+  }
 
-    lspQueue.async { [weak self] in
-      guard let self else { return }
+  // MARK: Hot Exit
 
-      var events: [LspDiagnosticsEvent] = []
-      var count = 0
-      let maxEventsPerTick = 50
+  /// Opens the backup store, starts backing up modified editors, and offers
+  /// to restore whatever a crashed previous run left behind.
+  private func startHotExit() {
+    let stateDir = Settings.settingsPath().deletingLastPathComponent().path
+    let recovered = ImpulseCore.hotExitStart(stateDir: stateDir)
 
-      while count < maxEventsPerTick, let json = self.core.lspPollEvent() {
-        count += 1
-        guard let data = json.data(using: .utf8),
-          let event = try? JSONSerialization.jsonObject(with: data) as? [String: Any],
-          let type = event["type"] as? String
-        else { continue }
+    hotExitTimer = Timer.scheduledTimer(withTimeInterval: Self.hotExitInterval, repeats: true) {
+      [weak self] _ in
+      self?.syncHotExitBackups()
+    }
 
-        switch type {
-        case "diagnostics":
-          guard let uri = event["uri"] as? String,
-            let diagnostics = event["diagnostics"] as? [[String: Any]]
-          else { continue }
-          events.append(LspDiagnosticsEvent(uri: uri, diagnostics: diagnostics))
-        default:
-          break
-        }
+    if !recovered.isEmpty {
+      // After the session restore and file opens queued above.
+      DispatchQueue.main.async { [weak self] in
+        self?.offerHotExitRestore(recovered)
       }
+    }
+  }
 
-      DispatchQueue.main.async { [weak self] in
-        guard let self else { return }
-        self.isPollingLspEvents = false
-        guard !events.isEmpty else { return }
-
-        for event in events {
-          for controller in self.windowControllers {
-            controller.applyLspDiagnostics(
-              uri: event.uri,
-              diagnosticsArray: event.diagnostics
-            )
-          }
+  private func syncHotExitBackups() {
+    var buffers: [(path: String, content: String)] = []
+    if settings.hotExit {
+      for controller in windowControllers {
+        for case .editor(let editor) in controller.tabManager.tabs where editor.isModified {
+          buffers.append((editor.backupKey, editor.content))
         }
       }
     }
+    ImpulseCore.hotExitSync(buffers: buffers)
+  }
+
+  private func offerHotExitRestore(_ recovered: [HotExitBackup]) {
+    guard let controller = windowControllers.first else { return }
+    let names = recovered.map {
+      $0.path.hasPrefix("untitled:/") ? "Untitled" : ($0.path as NSString).lastPathComponent
+    }
+    let alert = NSAlert()
+    alert.messageText = "Restore Unsaved Changes?"
+    alert.informativeText =
+      "Impulse didn't quit cleanly. Unsaved changes were recovered for:\n\n"
+      + names.joined(separator: "\n")
+    alert.addButton(withTitle: "Restore")
+    alert.addButton(withTitle: "Discard")
+    guard alert.runModal() == .alertFirstButtonReturn else {
+      ImpulseCore.hotExitDiscard()
+      return
+    }
+    for backup in recovered {
+      if !backup.path.hasPrefix("untitled:/"),
+        FileManager.default.fileExists(atPath: backup.path)
+      {
+        controller.tabManager.addEditorTab(
+          path: backup.path, projectDirectory: controller.fileTreeRootPath,
+          recoveredContent: backup.content)
+      } else {
+        controller.tabManager.addUntitledEditorTab(cwd: controller.fileTreeRootPath)
+        controller.tabManager.selectedEditor?.restoreContent(backup.content)
+      }
+    }
+  }
+
+  // MARK: Command-Line Open
+
+  private func observeCLIOpenRequests() {
+    cliOpenObserver = DistributedNotificationCenter.default().addObserver(
+      forName: CLIOpen.openNotification, object: nil, queue: .main
+    ) { [weak self] notification in
+      guard let token = notification.object as? String,
+        let json = notification.userInfo?["request"] as? String,
+        let request = CLIOpen.decode(json)
+      else { return }
+      self?.handleCLIOpen(request, token: token)
+    }
+    editorCloseObserver = NotificationCenter.default.addObserver(
+      forName: .impulseEditorDidClose, object: nil, queue: .main
+    ) { [weak self] notification in
+      guard let path = notification.object as? String else { return }
+      self?.cliFileClosed(path)
+    }
+  }
+
+  /// Opens what another launch asked for: in the key window, or a new one
+  /// when asked for, configured, or when a directory is given.
+  private func handleCLIOpen(_ request: CLIOpen.Request, token: String) {
+    let targets = request.targets.filter { FileManager.default.fileExists(atPath: $0.path) }
+    let files = targets.filter { !$0.isDir }
+    let useNewWindow =
+      windowControllers.isEmpty || request.newWindow || settings.cliOpenInNewWindow
+      || targets.contains { $0.isDir }
+    if useNewWindow {
+      openNewWindow(skipInitialTerminal: !files.isEmpty)
+    }
+    let keyController = NSApp.keyWindow?.windowController as? MainWindowController
+    guard
+      let controller = useNewWindow
+        ? windowControllers.last : (keyController ?? windowControllers.first)
+    else { return }
+
+    for target in targets {
+      if target.isDir {
+        controller.openDirectory(target.path)
+        continue
+      }
+      controller.openFile(path: target.path)
+      if let line = target.line {
+        controller.findEditorTab(forPath: target.path)?.goToPosition(
+          line: line, column: target.column ?? 1)
+      }
+    }
+    controller.window?.makeKeyAndOrderFront(nil)
+    NSApp.activate(ignoringOtherApps: true)
+
+    guard request.wait else { return }
+    if files.isEmpty {
+      postCLIDone(token)
+    } else {
+      cliWaiters.append((token, Set(files.map(\.path))))
+    }
+  }
+
+  private func cliFileClosed(_ path: String) {
+    for index in cliWaiters.indices {
+      cliWaiters[index].paths.remove(path)
+    }
+    for waiter in cliWaiters where waiter.paths.isEmpty {
+      postCLIDone(waiter.token)
+    }
+    cliWaiters.removeAll { $0.paths.isEmpty }
+  }
+
+  private func postCLIDone(_ token: String) {
+    DistributedNotificationCenter.default().postNotificationName(
+      CLIOpen.doneNotification, object: token, userInfo: nil, deliverImmediately: true)
+  }
+
+  // MARK: Control Socket
+
+  /// Follows `controlSocketEnabled`. Calls from scripts and tools
+  /// (`impulse_core::control`) are answered on the main loop.
+  private func updateControlSocket() {
+    guard settings.controlSocketEnabled else {
+      controlSocketTimer?.invalidate()
+      controlSocketTimer = nil
+      ImpulseCore.stopControlSocket()
+      return
+    }
+    guard controlSocketTimer == nil else { return }
+    switch ImpulseCore.startControlSocket() {
+    case .success(let socket):
+      NSLog("Control socket listening on %@", socket)
+    case .failure(let error):
+      NSLog("Control socket unavailable: %@", error.message)
+      return
+    }
+    controlSocketTimer = Timer.scheduledTimer(withTimeInterval: 0.1, repeats: true) {
+      [weak self] _ in
+      while let call = ImpulseCore.nextControlCall() {
+        self?.handleControlCall(call)
+      }
+    }
+  }
+
+  /// Carries out a control call; its params were validated by the core.
+  private func handleControlCall(_ call: [String: Any]) {
+    guard let id = (call["id"] as? NSNumber)?.uint64Value else { return }
+    let method = call["method"] as? String ?? ""
+    let params = call["params"] as? [String: Any] ?? [:]
+    let line = (params["line"] as? NSNumber)?.uint32Value
+    let column = (params["column"] as? NSNumber)?.uint32Value
+    let keyController =
+      (NSApp.keyWindow?.windowController as? MainWindowController) ?? windowControllers.last
+    var result: Any?
+    var error: String?
+
+    switch method {
+    case "open-file", "goto-position":
+      if let path = params["path"] as? String {
+        var isDir: ObjCBool = false
+        FileManager.default.fileExists(atPath: path, isDirectory: &isDir)
+        let target = CLIOpen.Target(path: path, line: line, column: column, isDir: isDir.boolValue)
+        let request = CLIOpen.Request(
+          targets: [target], newWindow: params["new_window"] as? Bool ?? false, wait: false)
+        handleCLIOpen(request, token: "")
+      } else if let editor = keyController?.tabManager.selectedEditor, let line {
+        editor.goToPosition(line: line, column: column ?? 1)
+      } else {
+        error = "No file is open in the active tab"
+      }
+    case "run-command":
+      let commandId = params["id"] as? String ?? ""
+      let context = keyController?.commandContext() ?? CommandContext()
+      if let entry = ImpulseCore.commandRegistry(context: context).first(where: { $0.id == commandId }) {
+        error =
+          entry.enabled
+          ? ImpulseCore.executeCommand(commandId)
+          : "\(commandId) isn't available in the active tab"
+      } else {
+        error = "Unknown command: \(commandId)"
+      }
+    case "query-state":
+      let windows: [[String: Any]] = windowControllers.map { controller in
+        let root = controller.fileTreeRootPath
+        return [
+          "focused": controller.window?.isKeyWindow ?? false,
+          "workspace": root.isEmpty ? NSNull() : root,
+          "active_file": controller.tabManager.selectedEditor?.filePath ?? NSNull(),
+          "open_files": controller.editorTabsByPath.keys.sorted(),
+        ]
+      }
+      result = ["windows": windows]
+    default:
+      error = "Unknown method: \(method)"
+    }
+
+    let resultJSON =
+      result
+      .flatMap { try? JSONSerialization.data(withJSONObject: $0) }
+      .flatMap { String(data: $0, encoding: .utf8) } ?? "null"
+    ImpulseCore.respondToControlCall(id: id, resultJSON: resultJSON, error: error)
+  }
+
+  // MARK: Commands
+
+  /// Runs core registry commands (`ImpulseCore.executeCommand`): built-ins
+  /// through the palette's actions, custom commands in a new terminal tab.
+  private func installCommandHandler() {
+    ImpulseCore.setCommandHandler { [weak self] id, _ in
+      guard id.hasPrefix("custom:") else {
+        CommandPaletteWindow.builtinAction(for: id)()
+        return
+      }
+      let custom = self?.settings.customKeybindings.first {
+        ImpulseCore.commandPaletteCustomItem(
+          name: $0.name, shortcut: $0.key, command: $0.command, args: $0.args)?.id == id
+      }
+      guard let custom else { return }
+      NotificationCenter.default.post(
+        name: Notification.Name("impulseCustomCommand"),
+        object: nil,
+        userInfo: ["command": custom.command, "args": custom.args]
+      )
+    }
+  }
+
+  // MARK: Crash Reports
+
+  private func installCrashReporter() {
+    let stateDir = Settings.settingsPath().deletingLastPathComponent().path
+    ImpulseCore.installCrashReporter(stateDir: stateDir)
+    NSSetUncaughtExceptionHandler { exception in
+      ImpulseCore.writeCrashReport(
+        message: "\(exception.name.rawValue): \(exception.reason ?? "")",
+        backtrace: exception.callStackSymbols.joined(separator: "\n"))
+    }
+  }
+
+  /// Tells the user the previous session crashed and shows the newest
+  /// report. It is sent right away when they opted in; otherwise the alert
+  /// offers to send it.
+  private func offerCrashReport() {
+    guard let report = ImpulseCore.takeUnseenCrashReports().first else { return }
+    let endpoint = settings.crashReportEndpoint.trimmingCharacters(in: .whitespaces)
+    let autoSent = settings.sendCrashReports && !endpoint.isEmpty
+    if autoSent {
+      submitCrashReport(report, endpoint: endpoint)
+    }
+
+    DispatchQueue.main.async {
+      let alert = NSAlert()
+      alert.messageText = "Impulse Quit Unexpectedly"
+      alert.informativeText =
+        autoSent
+        ? "A crash report was saved and sent."
+        : "A crash report was saved. Nothing is sent unless you choose to."
+      alert.addButton(withTitle: "Close")
+      alert.addButton(withTitle: "Copy Report")
+      let canSend = !autoSent && !endpoint.isEmpty
+      if canSend {
+        alert.addButton(withTitle: "Send Report")
+      }
+
+      let scrollView = NSScrollView(frame: NSRect(x: 0, y: 0, width: 560, height: 320))
+      scrollView.hasVerticalScroller = true
+      let textView = NSTextView(frame: scrollView.bounds)
+      textView.isEditable = false
+      textView.autoresizingMask = [.width]
+      textView.font = NSFont.monospacedSystemFont(ofSize: 11, weight: .regular)
+      textView.string = report.text
+      scrollView.documentView = textView
+      alert.accessoryView = scrollView
+
+      switch alert.runModal() {
+      case .alertSecondButtonReturn:
+        NSPasteboard.general.clearContents()
+        NSPasteboard.general.setString(report.text, forType: .string)
+      case .alertThirdButtonReturn where canSend:
+        self.submitCrashReport(report, endpoint: endpoint)
+      default:
+        break
+      }
+    }
+  }
+
+  private func submitCrashReport(_ report: CrashReport, endpoint: String) {
+    DispatchQueue.global(qos: .utility).async {
+      if let error = ImpulseCore.submitCrashReport(report, endpoint: endpoint) {
+        NSLog("%@", error)
+      }
+    }
   }
 
   private func observeSettingsChanges() {
@@ -464,11 +966,87 @@ final class AppDelegate: NSObject, NSApplicationDelegate {
     ) { [weak self] notification in
       guard let self, let settings = notification.object as? Settings else { return }
       self.settings = settings
+      ImpulseCore.setSearchIndexEnabled(settings.searchIndexEnabled)
+      ImpulseCore.setWorkspaceTrustEnabled(settings.workspaceTrustEnabled)
+      ImpulseCore.setDirenvEnabled(settings.useDirenv)
+      ImpulseCore.setLanguageOverrides(settings.languageOverrides)
+      ImpulseCore.setExcludeGlobs(files: settings.filesExclude, search: settings.searchExclude)
+      ImpulseCore.setWalkOptions(
+        followSymlinks: settings.followSymlinks, scanNetworkMounts: settings.scanNetworkMounts)
+      ImpulseCore.setSaveStrategy(settings.saveStrategy)
+      self.updateControlSocket()
+      self.applyActiveThemeIfChanged()
       self.rebuildMainMenu()
     }
   }
 
+  // MARK: Settings File
+
+  private var settingsCheckScheduled = false
+
+  /// Applies edits to `settings.json` made outside Impulse (by hand, in
+  /// another editor, by a dotfiles manager). Bursts of file events are
+  /// checked once.
+  private func watchSettingsFile() {
+    ImpulseCore.watchSettingsFile {
+      DispatchQueue.main.async { [weak self] in
+        guard let self, !self.settingsCheckScheduled else { return }
+        self.settingsCheckScheduled = true
+        DispatchQueue.main.asyncAfter(deadline: .now() + 0.5) { [weak self] in
+          self?.settingsCheckScheduled = false
+          self?.checkSettingsFile()
+        }
+      }
+    }
+  }
+
+  /// Applies the settings file if it changed outside Impulse. Callers that
+  /// wrote it themselves (import, sync) pass `announce: false` and report
+  /// it their own way.
+  func checkSettingsFile(announce: Bool = true) {
+    switch ImpulseCore.checkSettingsFileChange() {
+    case .unchanged:
+      break
+    case .reloaded(let reloaded):
+      applyReloadedSettings(reloaded)
+      if announce {
+        ImpulseCore.notify(
+          .info, title: "Settings reloaded", message: "settings.json changed on disk.",
+          source: "settings")
+      }
+    case .invalid(let message):
+      ImpulseCore.notify(
+        .warning, title: "settings.json has errors",
+        message: "\(message). The previous settings stay in effect.", source: "settings",
+        actions: [.init(label: "Open Settings", command: "open_settings")])
+    case .conflict:
+      askAboutSettingsConflict()
+    }
+  }
+
+  /// Both the file and unsaved in-app changes exist: ask which to keep.
+  private func askAboutSettingsConflict() {
+    let alert = NSAlert()
+    alert.messageText = "Settings Changed on Disk"
+    alert.informativeText =
+      "settings.json was changed outside Impulse while Impulse had settings changes it couldn't save. Which version do you want to keep?"
+    alert.addButton(withTitle: "Use File Version")
+    alert.addButton(withTitle: "Keep Impulse Settings")
+    let keepApp = alert.runModal() == .alertSecondButtonReturn
+    let result = ImpulseCore.resolveSettingsConflict(keepApp: keepApp)
+    if let resolved = result.settings {
+      applyReloadedSettings(resolved)
+    } else if let error = result.error {
+      ImpulseCore.notify(.error, title: "Couldn't save settings", message: error, source: "settings")
+    }
+  }
+
+  private func applyReloadedSettings(_ reloaded: Settings) {
+    SettingsWindowController.settingsReloaded(reloaded)
+    NotificationCenter.default.post(name: .impulseSettingsDidChange, object: reloaded)
+  }
+
   private func rebuildMainMenu() {
-    NSApp.mainMenu = MenuBuilder.buildMainMenu(overrides: settings.keybindingOverrides)
+    NSApp.mainMenu = MenuBuilder.buildMainMenu(overrides: settings.effectiveKeybindingOverrides)
   }
 }
@@ -14,6 +14,11 @@ struct SearchResult: Codable {
     let lineContent: String?
     let columnStart: UInt32?
     let columnEnd: UInt32?
+    /// UTF-8 byte range of the match within `lineContent`.
+    let matchStart: UInt32?
+    let matchEnd: UInt32?
+    let contextBefore: [String]?
+    let contextAfter: [String]?
     let matchType: String
 
     /// Stable identity for SwiftUI ForEach diffing. Combines path, line, and
@@ -28,6 +33,10 @@ struct SearchResult: Codable {
         case lineContent = "line_content"
         case columnStart = "column_start"
         case columnEnd = "column_end"
+        case matchStart = "match_start"
+        case matchEnd = "match_end"
+        case contextBefore = "context_before"
+        case contextAfter = "context_after"
         case matchType = "match_type"
     }
 }
@@ -44,6 +53,58 @@ struct CommandPaletteItem: Codable, Hashable {
     let payload: [String: String]?
 }
 
+/// What the active tab is, for a command's `when` in the core registry.
+struct CommandContext: Encodable {
+    var editor = false
+    var terminal = false
+    var file = false
+    /// No assistant UI on macOS yet, so its commands stay hidden.
+    var assistant = false
+    /// No agent access UI on macOS yet either.
+    var agentAccess = false
+
+    func allows(_ when: String) -> Bool {
+        switch when {
+        case "editor": return editor
+        case "terminal": return terminal
+        case "file": return file
+        case "assistant": return assistant
+        case "assistant_editor": return assistant && editor
+        case "agent_access": return agentAccess
+        default: return true
+        }
+    }
+
+    enum CodingKeys: String, CodingKey {
+        case editor, terminal, file, assistant
+        case agentAccess = "agent_access"
+    }
+}
+
+/// A command from the core registry (`impulse_command_registry_json`).
+struct CommandRegistryEntry: Decodable {
+    let id: String
+    let title: String
+    let category: String
+    let keywords: [String]?
+    let source: String
+    let shortcut: String?
+    let defaultShortcut: String?
+    let when: String
+    let enabled: Bool
+
+    enum CodingKeys: String, CodingKey {
+        case id, title, category, keywords, source, shortcut, when, enabled
+        case defaultShortcut = "default_shortcut"
+    }
+
+    var item: CommandPaletteItem {
+        CommandPaletteItem(
+            id: id, title: title, category: category, keywords: keywords ?? [],
+            source: source, shortcut: shortcut, payload: nil)
+    }
+}
+
 struct RecentCommandItem: Codable, Hashable {
     let id: String
     let title: String
@@ -62,6 +123,117 @@ struct RecentCommandStore: Codable, Hashable {
     var items: [RecentCommandItem] = []
 }
 
+// MARK: - Status Bar
+
+/// A status bar segment from impulse-core's registry. `alignment` is
+/// "left" or "right".
+struct StatusBarSegment: Codable, Hashable {
+    let id: String
+    let name: String
+    let alignment: String
+}
+
+/// Which files a project content search looks in; encodes as the core's
+/// `SearchScope`.
+enum SearchScope: Encodable {
+    case workspace
+    /// Open files, searched in their buffer contents.
+    case openFiles([(path: String, content: String)])
+    case directory(String)
+    /// Comma-separated globs, like `src/**/*.rs, !*_test.rs`.
+    case glob(String)
+
+    private enum CodingKeys: String, CodingKey {
+        case kind, files, path, pattern
+    }
+
+    private struct OpenFile: Encodable {
+        let path: String
+        let content: String
+    }
+
+    func encode(to encoder: Encoder) throws {
+        var container = encoder.container(keyedBy: CodingKeys.self)
+        switch self {
+        case .workspace:
+            try container.encode("workspace", forKey: .kind)
+        case .openFiles(let files):
+            try container.encode("open_files", forKey: .kind)
+            try container.encode(
+                files.map { OpenFile(path: $0.path, content: $0.content) }, forKey: .files)
+        case .directory(let path):
+            try container.encode("directory", forKey: .kind)
+            try container.encode(path, forKey: .path)
+        case .glob(let pattern):
+            try container.encode("glob", forKey: .kind)
+            try container.encode(pattern, forKey: .pattern)
+        }
+    }
+}
+
+/// One directory in the status bar's working directory breadcrumb.
+struct PathCrumb: Codable, Hashable {
+    let label: String
+    let path: String
+}
+
+/// Indentation read from a file's content; nil halves mean the content
+/// doesn't say.
+struct DetectedIndentation: Codable {
+    let useSpaces: Bool?
+    let width: Int?
+
+    enum CodingKeys: String, CodingKey {
+        case useSpaces = "use_spaces"
+        case width
+    }
+}
+
+/// An unsaved buffer backed up by the previous run (`impulse_core::hot_exit`).
+/// `path` is the file path, or an `untitled:/` key for a never-saved buffer.
+struct HotExitBackup: Decodable {
+    let path: String
+    let content: String
+}
+
+/// A saved version of a file in local history (`impulse_core::local_history`).
+struct LocalHistoryEntry: Decodable {
+    let id: String
+    let savedAtMs: UInt64
+    let size: UInt64
+
+    enum CodingKeys: String, CodingKey {
+        case id
+        case savedAtMs = "saved_at_ms"
+        case size
+    }
+
+    var savedAt: Date { Date(timeIntervalSince1970: TimeInterval(savedAtMs) / 1000) }
+}
+
+/// Whitespace fixes to make before a save (`impulse_core::save_whitespace`):
+/// the text to write and the same changes as edits for the buffer.
+struct SaveWhitespaceFix: Decodable {
+    let content: String
+    let edits: [MonacoTextEdit]
+}
+
+/// A language a file can be switched to from the status bar
+/// (`impulse_core::languages`). `id` is the language server id, `monaco`
+/// the highlighting.
+struct LanguageChoice: Decodable, Hashable {
+    let id: String
+    let label: String
+    let monaco: String
+}
+
+/// A crash report from an earlier session (`impulse_core::crash_report`).
+/// `json` is the report as stored, passed back unchanged when submitting.
+struct CrashReport {
+    let text: String
+    let json: String
+}
+
 // MARK: - Terminal Completion
 
 /// A byte range within the input text to be replaced by a completion.
@@ -105,10 +277,12 @@ struct CompletionResult: Codable, Equatable {
 // MARK: - Error Type
 
 /// Simple error wrapper so we can use `Result<String, ImpulseError>` (Swift
-/// requires the failure type to conform to `Error`).
-struct ImpulseError: Error, CustomStringConvertible {
+/// requires the failure type to conform to `Error`). `localizedDescription`
+/// is the message.
+struct ImpulseError: LocalizedError, CustomStringConvertible {
     let message: String
     var description: String { message }
+    var errorDescription: String? { message }
 }
 
 // MARK: - ImpulseCore FFI Bridge
@@ -129,6 +303,14 @@ final class ImpulseCore {
     /// directory is established via `initializeLsp(rootUri:)`.
     private var lspRegistry: OpaquePointer?
 
+    /// Handler for LSP events pushed from Rust, re-registered whenever the
+    /// registry is recreated.
+    private var lspEventHandler: ((String) -> Void)?
+
+    /// Retained box passed to Rust as the callback context. Released only
+    /// after the callback has been cleared on the Rust side.
+    private var lspEventContext: Unmanaged<EventHandlerBox>?
+
     init() {}
 
     deinit {
@@ -200,6 +382,63 @@ final class ImpulseCore {
         return cachedShellName
     }
 
+    /// The directory a new terminal tab starts in for the
+    /// `terminal_new_tab_directory` setting, given the workspace root and the
+    /// active tab's directory.
+    static func newTerminalDirectory(
+        mode: String, workspace: String?, current: String?
+    ) -> String {
+        consumeCString(impulse_new_terminal_directory(mode, NSHomeDirectory(), workspace, current))
+            ?? NSHomeDirectory()
+    }
+
+    /// Tooltip text describing what each shell in `shellPids` is running
+    /// (foreground job, CPU and memory), keyed by PID. Idle shells are
+    /// left out. Reads the process table, so call off the main thread.
+    static func terminalProcessDescriptions(shellPids: [Int32]) -> [Int32: String] {
+        struct Entry: Decodable {
+            let shellPid: Int32
+            let description: String?
+
+            enum CodingKeys: String, CodingKey {
+                case shellPid = "shell_pid"
+                case description
+            }
+        }
+        guard let data = try? JSONEncoder().encode(shellPids),
+              let input = String(data: data, encoding: .utf8),
+              let json = consumeCString(impulse_terminal_processes(input)),
+              let output = json.data(using: .utf8) else { return [:] }
+        guard let entries = try? JSONDecoder().decode([Entry].self, from: output) else { return [:] }
+        var descriptions: [Int32: String] = [:]
+        for entry in entries {
+            descriptions[entry.shellPid] = entry.description
+        }
+        return descriptions
+    }
+
+    /// Why pasting `text` into a terminal should be confirmed (several lines
+    /// outside bracketed paste, or a `sudo` command), or nil when it can be
+    /// pasted right away.
+    static func pasteConfirmation(text: String, bracketed: Bool) -> String? {
+        consumeCString(impulse_paste_confirmation(text, bracketed))
+    }
+
+    /// The local paths in dropped `text/uri-list` data, or in text holding
+    /// bare absolute paths; empty for any other text.
+    static func parseURIList(_ text: String) -> [String] {
+        guard let json = consumeCString(impulse_parse_uri_list(text)) else { return [] }
+        return (try? JSONDecoder().decode([String].self, from: Data(json.utf8))) ?? []
+    }
+
+    /// How many processes under `shellPid` closing its terminal should warn
+    /// about, leaving out the `ignored` programs and their children.
+    static func terminalCloseRiskProcessCount(shellPid: Int32, ignoring ignored: [String]) -> Int {
+        let data = (try? JSONEncoder().encode(ignored)) ?? Data("[]".utf8)
+        let json = String(data: data, encoding: .utf8) ?? "[]"
+        return Int(impulse_terminal_close_risk_process_count(shellPid, json))
+    }
+
     // MARK: - Search
 
     /// Searches for files by name under `root` matching `query`.
@@ -231,6 +470,160 @@ final class ImpulseCore {
         return decodeSearchResults(json)
     }
 
+    /// Searches file contents in `scope` under `root`. Blocks until the
+    /// search completes — call off the main thread.
+    static func searchContent(
+        root: String, scope: SearchScope, query: String, caseSensitive: Bool
+    ) -> Result<[SearchResult], ImpulseError> {
+        guard let data = try? JSONEncoder().encode(scope),
+              let scopeJSON = String(data: data, encoding: .utf8),
+              let json = consumeCString(
+                impulse_search_content_in_scope(root, scopeJSON, query, caseSensitive))
+        else { return .failure(ImpulseError(message: "Unexpected response from core")) }
+        if let object = try? JSONSerialization.jsonObject(with: Data(json.utf8)) as? [String: Any],
+           let error = object["error"] as? String {
+            return .failure(ImpulseError(message: error))
+        }
+        return .success(decodeSearchResults(json))
+    }
+
+    /// Opens the hot-exit backup store and returns what a crashed previous
+    /// run left behind (nothing after a clean exit).
+    static func hotExitStart(stateDir: String) -> [HotExitBackup] {
+        guard let json = consumeCString(impulse_hot_exit_start(stateDir)),
+              let data = json.data(using: .utf8) else { return [] }
+        return (try? JSONDecoder().decode([HotExitBackup].self, from: data)) ?? []
+    }
+
+    /// Backs up the given modified buffers and drops every other backup.
+    static func hotExitSync(buffers: [(path: String, content: String)]) {
+        let objects = buffers.map { ["path": $0.path, "content": $0.content] }
+        guard let data = try? JSONSerialization.data(withJSONObject: objects),
+              let json = String(data: data, encoding: .utf8) else { return }
+        impulse_hot_exit_sync(json)
+    }
+
+    /// Removes every backup (the user declined to restore them).
+    static func hotExitDiscard() {
+        impulse_hot_exit_discard()
+    }
+
+    /// Clean exit: removes the backups and the running marker.
+    static func hotExitFinish() {
+        impulse_hot_exit_finish()
+    }
+
+    /// Keeps every saved version of a file under `<stateDir>/history`.
+    static func setLocalHistoryStateDir(_ stateDir: String) {
+        impulse_local_history_set_state_dir(stateDir)
+    }
+
+    /// The saved versions of `path`, newest first.
+    static func localHistoryEntries(path: String) -> [LocalHistoryEntry] {
+        guard let json = consumeCString(impulse_local_history_entries(path)),
+              let data = json.data(using: .utf8) else { return [] }
+        return (try? JSONDecoder().decode([LocalHistoryEntry].self, from: data)) ?? []
+    }
+
+    /// Where version `id` of `path` is stored, if it still exists.
+    static func localHistoryVersionPath(path: String, id: String) -> String? {
+        consumeCString(impulse_local_history_version_path(path, id))
+    }
+
+    /// The content of version `id` of `path`.
+    static func readLocalHistory(path: String, id: String) -> Result<String, ImpulseError> {
+        guard let json = consumeCString(impulse_local_history_read(path, id)),
+              let object = try? JSONSerialization.jsonObject(with: Data(json.utf8)) as? [String: Any]
+        else { return .failure(ImpulseError(message: "Unexpected response from core")) }
+        if let content = object["content"] as? String {
+            return .success(content)
+        }
+        return .failure(ImpulseError(message: object["error"] as? String ?? "Unknown error"))
+    }
+
+    /// Parses `impulse` command-line arguments (without the program name)
+    /// into the open-request JSON `CLIOpen` decodes.
+    static func parseCLIOpenRequest(args: [String], cwd: String) -> String? {
+        consumeCString(impulse_cli_parse_open_request(jsonArgument(args), cwd))
+    }
+
+    /// Starts timing launch phases when the app was started with
+    /// `--profile-startup`.
+    static func enableStartupProfileIfRequested() {
+        if CommandLine.arguments.contains("--profile-startup") {
+            impulse_startup_profile_enable()
+        }
+    }
+
+    /// Records that a launch phase just finished (no-op unless profiling).
+    static func markStartupPhase(_ phase: String) {
+        impulse_startup_profile_mark(phase)
+    }
+
+    /// Records the last launch phase and prints the profile.
+    static func finishStartupProfile(_ phase: String) {
+        impulse_startup_profile_finish(phase)
+    }
+
+    /// Records Rust panics and recent log lines as crash reports under
+    /// `stateDir/crashes`.
+    static func installCrashReporter(stateDir: String) {
+        impulse_crash_reporter_install(stateDir)
+    }
+
+    /// Records a crash caught on the Swift side.
+    static func writeCrashReport(message: String, backtrace: String) {
+        impulse_crash_report_write(message, backtrace)
+    }
+
+    /// Reports from earlier sessions not yet shown, newest first. Marks them
+    /// seen.
+    static func takeUnseenCrashReports() -> [CrashReport] {
+        guard let json = consumeCString(impulse_crash_report_take_unseen()),
+              let data = json.data(using: .utf8),
+              let objects = (try? JSONSerialization.jsonObject(with: data)) as? [[String: Any]]
+        else { return [] }
+        return objects.compactMap { object in
+            guard let text = object["text"] as? String,
+                  let data = try? JSONSerialization.data(withJSONObject: object),
+                  let json = String(data: data, encoding: .utf8) else { return nil }
+            return CrashReport(text: text, json: json)
+        }
+    }
+
+    /// Sends a report to `endpoint`. Blocks; returns an error message on
+    /// failure.
+    static func submitCrashReport(_ report: CrashReport, endpoint: String) -> String? {
+        consumeCString(impulse_crash_report_submit(report.json, endpoint))
+    }
+
+    /// Turn the in-memory content search index on or off.
+    static func setSearchIndexEnabled(_ enabled: Bool) {
+        impulse_search_set_index_enabled(enabled)
+    }
+
+    /// Sets whether search, indexing and file watching follow symlinked
+    /// folders and enter network mounts.
+    static func setWalkOptions(followSymlinks: Bool, scanNetworkMounts: Bool) {
+        impulse_set_walk_options(followSymlinks, scanNetworkMounts)
+    }
+
+    /// Sets the `save_strategy` setting used by `safeWrite`.
+    static func setSaveStrategy(_ strategy: String) {
+        impulse_set_save_strategy(strategy)
+    }
+
+    /// Sets the `files_exclude` / `search_exclude` globs honored by search,
+    /// the file tree and file watching.
+    @discardableResult
+    static func setExcludeGlobs(files: [String], search: [String]) -> Bool {
+        guard let filesData = try? JSONEncoder().encode(files),
+              let filesJson = String(data: filesData, encoding: .utf8),
+              let searchData = try? JSONEncoder().encode(search),
+              let searchJson = String(data: searchData, encoding: .utf8) else { return false }
+        return impulse_set_exclude_globs(filesJson, searchJson) == 0
+    }
+
     /// Decodes a JSON array string into an array of `SearchResult`.
     private static func decodeSearchResults(_ json: String) -> [SearchResult] {
         guard let data = json.data(using: .utf8) else { return [] }
@@ -366,6 +759,8 @@ final class ImpulseCore {
         let size: UInt64
         let modified: UInt64
         let git_status: String?
+        var is_ignored: Bool? = nil
+        var symlink_target: String? = nil
     }
 
     /// Codable struct matching the Rust `FileTreeNode` patch serialization.
@@ -379,6 +774,8 @@ final class ImpulseCore {
         let size: UInt64
         let modified: UInt64
         let git_status: String?
+        let is_ignored: Bool?
+        var symlink_target: String? = nil
     }
 
     struct FileTreePatchBatch: Codable {
@@ -444,6 +841,14 @@ final class ImpulseCore {
         let paths: [String]
     }
 
+    /// Read directory contents through core (directories first, `files_exclude`
+    /// applied, gitignored entries flagged). Returns `nil` on error.
+    static func readDirectoryEntries(path: String, showHidden: Bool) -> [FileEntryFFI]? {
+        guard let json = consumeCString(impulse_read_directory_entries(path, showHidden)) else { return nil }
+        guard let data = json.data(using: .utf8) else { return nil }
+        return try? JSONDecoder().decode([FileEntryFFI].self, from: data)
+    }
+
     /// Read directory contents with git status enrichment in a single FFI call.
     ///
     /// Returns an array of `FileEntryFFI` values, or `nil` on error.
@@ -488,6 +893,12 @@ final class ImpulseCore {
         return dict
     }
 
+    /// Recomputes the cached diff and blame of the file at `filePath` in the
+    /// background, so the next diff markers and blame lookups are ready.
+    static func refreshGitFileCaches(filePath: String) {
+        impulse_git_refresh_file_caches(filePath)
+    }
+
     /// Discards uncommitted changes to a file, restoring it to the HEAD version.
     ///
     /// - Parameter filePath: The absolute path to the file.
@@ -633,6 +1044,49 @@ final class ImpulseCore {
         return try? JSONDecoder().decode(FileHunks.self, from: data)
     }
 
+    /// Diffs the file at `oldPath` against the file at `newPath`, neither of
+    /// which needs to be in a repository. Call off the main thread.
+    static func diffFiles(oldPath: String, newPath: String) -> FileHunks? {
+        guard let json = consumeCString(impulse_diff_files(oldPath, newPath)),
+              let data = json.data(using: .utf8) else { return nil }
+        return try? JSONDecoder().decode(FileHunks.self, from: data)
+    }
+
+    /// Diffs the saved file at `path` against `buffer`, the editor's unsaved
+    /// content. Call off the main thread.
+    static func diffSaved(path: String, buffer: String) -> FileHunks? {
+        guard let json = consumeCString(impulse_diff_saved(path, buffer)),
+              let data = json.data(using: .utf8) else { return nil }
+        return try? JSONDecoder().decode(FileHunks.self, from: data)
+    }
+
+    /// Languages offered by the status bar's language menu.
+    static let languages: [LanguageChoice] = {
+        guard let json = consumeCString(impulse_languages()),
+              let data = json.data(using: .utf8) else { return [] }
+        return (try? JSONDecoder().decode([LanguageChoice].self, from: data)) ?? []
+    }()
+
+    /// Applies the `language_overrides` setting.
+    static func setLanguageOverrides(_ overrides: [String: String]) {
+        guard let data = try? JSONEncoder().encode(overrides),
+              let json = String(data: data, encoding: .utf8) else { return }
+        impulse_languages_set_overrides(json)
+    }
+
+    /// The `language_overrides` key for `path`: `*.ext`, or the file name
+    /// when it has no extension.
+    static func languageOverrideKey(path: String) -> String? {
+        consumeCString(impulse_language_override_key(path))
+    }
+
+    /// The language `path` is overridden to, or nil when it is detected.
+    static func languageOverride(path: String) -> LanguageChoice? {
+        guard let json = consumeCString(impulse_language_override(path)),
+              let data = json.data(using: .utf8) else { return nil }
+        return try? JSONDecoder().decode(LanguageChoice.self, from: data)
+    }
+
     /// Stages all changes and commits with `message`. Always returns a
     /// `CommitResult` (never `nil`) except when an input is empty/invalid, in
     /// which case a synthetic failure result is returned. Call off the main
@@ -655,6 +1109,165 @@ final class ImpulseCore {
         return impulse_git_discard_path(repoPath, filePath) == 0
     }
 
+    /// One path in a `RepoStatus`: staged (`index`) and unstaged
+    /// (`worktree`) status letters, `nil` when that side is clean.
+    struct StatusEntry: Codable {
+        let path: String
+        let index: String?
+        let worktree: String?
+        let oldPath: String?
+
+        enum CodingKeys: String, CodingKey {
+            case path, index, worktree
+            case oldPath = "old_path"
+        }
+    }
+
+    struct AheadBehind: Codable {
+        let ahead: Int
+        let behind: Int
+        let upstream: String
+    }
+
+    /// Repository status, mirroring the FFI JSON from `impulse_git_status`.
+    struct RepoStatus: Codable {
+        let repoRoot: String
+        let branch: String?
+        let aheadBehind: AheadBehind?
+        let entries: [StatusEntry]
+
+        enum CodingKeys: String, CodingKey {
+            case branch, entries
+            case repoRoot = "repo_root"
+            case aheadBehind = "ahead_behind"
+        }
+    }
+
+    /// One commit from `gitLog`.
+    struct CommitSummary: Codable {
+        let commitHash: String
+        let shortHash: String
+        let author: String
+        let timestamp: Int64
+        let summary: String
+
+        enum CodingKeys: String, CodingKey {
+            case author, timestamp, summary
+            case commitHash = "commit_hash"
+            case shortHash = "short_hash"
+        }
+    }
+
+    /// Result of a stage/unstage/checkout, mirroring `{ "ok", "error" }`.
+    struct GitResult: Codable {
+        let ok: Bool
+        let error: String?
+    }
+
+    private static func decodeGitResult(_ json: String?, action: String) -> GitResult {
+        guard let json, let data = json.data(using: .utf8),
+              let result = try? JSONDecoder().decode(GitResult.self, from: data) else {
+            return GitResult(ok: false, error: "\(action) failed")
+        }
+        return result
+    }
+
+    /// Staged/unstaged status of the repository containing `repoPath`, or
+    /// `nil` outside a repository. Call off the main thread.
+    static func gitStatus(repoPath: String) -> RepoStatus? {
+        guard let json = consumeCString(impulse_git_status(repoPath)),
+              let data = json.data(using: .utf8) else { return nil }
+        return try? JSONDecoder().decode(RepoStatus.self, from: data)
+    }
+
+    /// Stages REPO-RELATIVE `paths`. Call off the main thread.
+    static func gitStage(repoPath: String, paths: [String]) -> GitResult {
+        guard let data = try? JSONEncoder().encode(paths),
+              let pathsJson = String(data: data, encoding: .utf8) else {
+            return GitResult(ok: false, error: "Stage failed")
+        }
+        return decodeGitResult(consumeCString(impulse_git_stage(repoPath, pathsJson)), action: "Stage")
+    }
+
+    /// Unstages REPO-RELATIVE `paths`. Call off the main thread.
+    static func gitUnstage(repoPath: String, paths: [String]) -> GitResult {
+        guard let data = try? JSONEncoder().encode(paths),
+              let pathsJson = String(data: data, encoding: .utf8) else {
+            return GitResult(ok: false, error: "Unstage failed")
+        }
+        return decodeGitResult(consumeCString(impulse_git_unstage(repoPath, pathsJson)), action: "Unstage")
+    }
+
+    /// Commits only the staged changes. Call off the main thread.
+    static func gitCommit(repoPath: String, message: String) -> CommitResult {
+        guard let json = consumeCString(impulse_git_commit(repoPath, message)),
+              let data = json.data(using: .utf8),
+              let result = try? JSONDecoder().decode(CommitResult.self, from: data) else {
+            return CommitResult(ok: false, oid: nil, error: "Commit failed")
+        }
+        return result
+    }
+
+    /// Up to `limit` commits from HEAD, newest first. Call off the main
+    /// thread, or use `gitLogAsync`.
+    static func gitLog(repoPath: String, limit: UInt32) -> [CommitSummary] {
+        guard let json = consumeCString(impulse_git_log(repoPath, limit)),
+              let data = json.data(using: .utf8) else { return [] }
+        return (try? JSONDecoder().decode([CommitSummary].self, from: data)) ?? []
+    }
+
+    /// Switches to branch `name`. Call off the main thread.
+    static func gitCheckout(repoPath: String, name: String) -> GitResult {
+        return decodeGitResult(consumeCString(impulse_git_checkout(repoPath, name)), action: "Checkout")
+    }
+
+    // MARK: - Async Operations
+
+    /// Retained handler box for `setOperationHandler`, released on replace.
+    private static var operationContext: Unmanaged<EventHandlerBox>?
+
+    /// Delivers finished async operations (`{"op_id", "result"}` JSON) to
+    /// `handler` on a background thread instead of `pollOperation`. Pass
+    /// `nil` to go back to polling.
+    static func setOperationHandler(_ handler: ((String) -> Void)?) {
+        impulse_operations_clear_callback()
+        operationContext?.release()
+        operationContext = nil
+        guard let handler else { return }
+
+        let context = Unmanaged.passRetained(EventHandlerBox(handler))
+        operationContext = context
+        impulse_operations_set_callback({ context, json in
+            guard let context, let json else { return }
+            let box = Unmanaged<EventHandlerBox>.fromOpaque(context).takeUnretainedValue()
+            box.handler(String(cString: json))
+        }, context.toOpaque())
+    }
+
+    /// Returns the finished result of an async operation once, or `nil`
+    /// while it is still running.
+    static func pollOperation(_ opId: UInt64) -> String? {
+        return consumeCString(impulse_operation_poll(opId))
+    }
+
+    /// Aborts an async operation. Returns `false` if it already finished.
+    @discardableResult
+    static func cancelOperation(_ opId: UInt64) -> Bool {
+        return impulse_cancel(opId) == 0
+    }
+
+    /// Starts a content search without blocking. Returns the operation id,
+    /// or 0 if it could not be started.
+    static func searchContentAsync(root: String, query: String, caseSensitive: Bool) -> UInt64 {
+        return impulse_search_content_async(root, query, caseSensitive)
+    }
+
+    /// Starts listing up to `limit` commits from HEAD without blocking.
+    /// Returns the operation id, or 0 if it could not be started.
+    static func gitLogAsync(repoPath: String, limit: UInt32) -> UInt64 {
+        return impulse_git_log_async(repoPath, limit)
+    }
+
     // MARK: - LSP
 
     /// Creates a new LSP registry for the given workspace root URI.
@@ -729,6 +1342,49 @@ final class ImpulseCore {
     func initializeLsp(rootUri: String) {
         shutdownLsp()
         lspRegistry = impulse_lsp_registry_new(rootUri)
+        registerLspEventHandler()
+    }
+
+    /// Delivers LSP events (JSON) to `handler` as they arrive instead of
+    /// requiring `lspPollEvent()`. The handler is called on a background
+    /// thread, one event at a time. Pass `nil` to go back to polling.
+    func setLspEventHandler(_ handler: ((String) -> Void)?) {
+        lspEventHandler = handler
+        registerLspEventHandler()
+    }
+
+    /// Sets the debounce intervals for completion, hover, signature help and
+    /// code action requests from the `lsp_debounce` settings object (JSON).
+    @discardableResult
+    func lspSetRequestDebounce(json: String) -> Bool {
+        guard let reg = lspRegistry else { return false }
+        return impulse_lsp_set_request_debounce(reg, json) == 0
+    }
+
+    /// Stops using the given server ids (the `disabled_lsp_servers`
+    /// setting): they are no longer started and running ones shut down.
+    @discardableResult
+    func lspSetDisabledServers(_ serverIds: [String]) -> Bool {
+        guard let reg = lspRegistry,
+              let data = try? JSONEncoder().encode(serverIds),
+              let json = String(data: data, encoding: .utf8) else { return false }
+        return impulse_lsp_set_disabled_servers(reg, json) == 0
+    }
+
+    private func registerLspEventHandler() {
+        guard let reg = lspRegistry else { return }
+        impulse_lsp_clear_event_callback(reg)
+        lspEventContext?.release()
+        lspEventContext = nil
+        guard let handler = lspEventHandler else { return }
+
+        let context = Unmanaged.passRetained(EventHandlerBox(handler))
+        lspEventContext = context
+        impulse_lsp_set_event_callback(reg, { context, json in
+            guard let context, let json else { return }
+            let box = Unmanaged<EventHandlerBox>.fromOpaque(context).takeUnretainedValue()
+            box.handler(String(cString: json))
+        }, context.toOpaque())
     }
 
     /// Ensures LSP servers are running for the given language and file
@@ -746,6 +1402,13 @@ final class ImpulseCore {
         return Self.consumeCString(result) ?? "{\"error\":\"null response\"}"
     }
 
+    /// Starts an LSP request using the instance registry without blocking.
+    /// Returns the operation id, or 0 if it could not be started.
+    func lspRequestAsync(languageId: String, fileUri: String, method: String, paramsJson: String) -> UInt64 {
+        guard let reg = lspRegistry else { return 0 }
+        return impulse_lsp_request_async(reg, languageId, fileUri, method, paramsJson)
+    }
+
     /// Sends an LSP notification using the instance registry.
     @discardableResult
     func lspNotify(languageId: String, fileUri: String, method: String, paramsJson: String) -> Int32 {
@@ -780,9 +1443,12 @@ final class ImpulseCore {
     /// Shuts down all running LSP servers and releases the instance registry.
     func shutdownLsp() {
         guard let reg = lspRegistry else { return }
+        impulse_lsp_clear_event_callback(reg)
         impulse_lsp_shutdown_all(reg)
         impulse_lsp_registry_free(reg)
         lspRegistry = nil
+        lspEventContext?.release()
+        lspEventContext = nil
     }
 
     // MARK: - Instance convenience wrappers for non-LSP calls
@@ -872,6 +1538,84 @@ final class ImpulseCore {
         impulse_npm_is_available()
     }
 
+    /// A managed npm package with its pinned and installed versions.
+    struct ManagedLspPackage: Decodable {
+        let name: String
+        let pinnedVersion: String
+        let installedVersion: String?
+        let servers: [String]
+        let enabled: Bool
+        /// "npm" or "binary".
+        let kind: String
+        /// A binary with no prebuilt release for this platform.
+        let unsupported: Bool
+        /// A binary not installed by Impulse but already found in PATH.
+        let onPath: Bool
+
+        enum CodingKeys: String, CodingKey {
+            case name, servers, enabled, kind, unsupported
+            case pinnedVersion = "pinned_version"
+            case installedVersion = "installed_version"
+            case onPath = "on_path"
+        }
+
+        var needsUpdate: Bool {
+            installedVersion.map { $0 != pinnedVersion } ?? false
+        }
+    }
+
+    private static func jsonArgument(_ strings: [String]) -> String? {
+        guard let data = try? JSONEncoder().encode(strings) else { return nil }
+        return String(data: data, encoding: .utf8)
+    }
+
+    /// Installed and pinned versions of every managed package.
+    static func lspManagedStatus(disabledServers: [String]) -> [ManagedLspPackage] {
+        guard let json = consumeCString(impulse_lsp_managed_status(jsonArgument(disabledServers))),
+              let data = json.data(using: .utf8) else { return [] }
+        return (try? JSONDecoder().decode([ManagedLspPackage].self, from: data)) ?? []
+    }
+
+    /// Enabled packages whose installed version differs from the pin.
+    static func lspCheckForUpdates(disabledServers: [String]) -> [ManagedLspPackage] {
+        guard let json = consumeCString(impulse_lsp_check_for_updates(jsonArgument(disabledServers))),
+              let data = json.data(using: .utf8) else { return [] }
+        return (try? JSONDecoder().decode([ManagedLspPackage].self, from: data)) ?? []
+    }
+
+    /// Retained handler box for `setLspInstallHandler`, released on replace.
+    private static var lspInstallContext: Unmanaged<EventHandlerBox>?
+
+    /// Delivers installer progress events (JSON) to `handler` on a
+    /// background thread. Pass `nil` to go back to polling.
+    static func setLspInstallHandler(_ handler: ((String) -> Void)?) {
+        impulse_lsp_install_clear_callback()
+        lspInstallContext?.release()
+        lspInstallContext = nil
+        guard let handler else { return }
+
+        let context = Unmanaged.passRetained(EventHandlerBox(handler))
+        lspInstallContext = context
+        impulse_lsp_install_set_callback({ context, json in
+            guard let context, let json else { return }
+            let box = Unmanaged<EventHandlerBox>.fromOpaque(context).takeUnretainedValue()
+            box.handler(String(cString: json))
+        }, context.toOpaque())
+    }
+
+    /// Starts `action` ("install", "update" or "uninstall") in the
+    /// background for `packages` (empty for every applicable package).
+    /// Returns `false` if another action is still running.
+    static func lspInstallStart(action: String, packages: [String], disabledServers: [String]) -> Bool {
+        let packagesJson = packages.isEmpty ? nil : jsonArgument(packages)
+        return impulse_lsp_install_start(action, packagesJson, jsonArgument(disabledServers))
+    }
+
+    /// Returns the next queued installer event, or `nil`.
+    static func pollLspInstallEvent() -> String? {
+        consumeCString(impulse_lsp_install_poll_event())
+    }
+
     // MARK: - Terminal Backend
 
     /// Creates a new terminal backend and returns an opaque handle.
@@ -957,6 +1701,27 @@ final class ImpulseCore {
         impulse_completion_warm_cache()
     }
 
+    // MARK: - Environment
+
+    /// Starts reading the login shell's environment in the background.
+    static func warmEnvironment() {
+        impulse_environment_warm()
+    }
+
+    static func setDirenvEnabled(_ enabled: Bool) {
+        impulse_environment_set_direnv_enabled(enabled)
+    }
+
+    /// The environment for a process started in `directory`: the login
+    /// shell's, plus the folder's `.envrc` when direnv is on. Blocks until
+    /// the login environment has been read.
+    static func environment(directory: String?) -> [String: String]? {
+        guard let json = consumeCString(impulse_environment_json(directory)),
+              let data = json.data(using: .utf8)
+        else { return nil }
+        return try? JSONDecoder().decode([String: String].self, from: data)
+    }
+
     /// Returns the best inline completion for the input bar, or nil.
     static func terminalCompleteInput(handle: OpaquePointer, input: String, cwd: String?) -> String? {
         let ptr = input.withCString { inputPtr in
@@ -1031,6 +1796,11 @@ final class ImpulseCore {
         return consumeCString(ptr)
     }
 
+    /// Returns the whole buffer as text, scrollback included.
+    static func terminalScrollbackText(handle: OpaquePointer) -> String {
+        consumeCString(impulse_terminal_scrollback_text(UnsafeMutableRawPointer(handle))) ?? ""
+    }
+
     /// Scrolls the terminal viewport by `delta` lines (negative = up).
     static func terminalScroll(handle: OpaquePointer, delta: Int32) {
         impulse_terminal_scroll(UnsafeMutableRawPointer(handle), delta)
@@ -1136,10 +1906,76 @@ final class ImpulseCore {
         }
     }
 
-    // MARK: - Theme API
+    // MARK: - Keymap Presets
 
-    /// Returns the list of all available theme names (built-in + user).
-    static func availableThemes() -> [String] {
+    /// Returns the selectable keymap presets as `(id, name)` pairs.
+    static func keymapPresets() -> [(id: String, name: String)] {
+        guard let json = consumeCString(impulse_keymap_presets()),
+              let data = json.data(using: .utf8),
+              let list = try? JSONSerialization.jsonObject(with: data) as? [[String: String]] else {
+            return []
+        }
+        return list.compactMap { entry in
+            guard let id = entry["id"], let name = entry["name"] else { return nil }
+            return (id, name)
+        }
+    }
+
+    /// Returns the shortcuts a keymap preset assigns, keyed by action ID.
+    static func keymapPresetBindings(preset: String) -> [String: String] {
+        guard let json = consumeCString(impulse_keymap_preset_bindings(preset)),
+              let data = json.data(using: .utf8),
+              let bindings = try? JSONSerialization.jsonObject(with: data) as? [String: String] else {
+            return [:]
+        }
+        return bindings
+    }
+
+    /// Returns the visible status bar segments in display order for the
+    /// given `status_bar_segments` / `status_bar_hidden_segments` settings.
+    static func statusBarSegments(order: [String], hidden: [String]) -> [StatusBarSegment] {
+        func encode(_ ids: [String]) -> String {
+            guard let data = try? JSONEncoder().encode(ids) else { return "[]" }
+            return String(data: data, encoding: .utf8) ?? "[]"
+        }
+        guard let json = consumeCString(impulse_status_bar_segments(encode(order), encode(hidden))),
+              let data = json.data(using: .utf8) else { return [] }
+        return (try? JSONDecoder().decode([StatusBarSegment].self, from: data)) ?? []
+    }
+
+    /// Returns the working directory as breadcrumbs, outermost first.
+    static func cwdBreadcrumbs(_ path: String) -> [PathCrumb] {
+        guard let json = consumeCString(impulse_status_bar_cwd_breadcrumbs(path)),
+              let data = json.data(using: .utf8) else { return [] }
+        return (try? JSONDecoder().decode([PathCrumb].self, from: data)) ?? []
+    }
+
+    /// Guesses tabs vs. spaces and the indent width from file content.
+    static func detectIndentation(content: String) -> DetectedIndentation {
+        guard let json = consumeCString(impulse_detect_indentation(content)),
+              let data = json.data(using: .utf8),
+              let detected = try? JSONDecoder().decode(DetectedIndentation.self, from: data)
+        else { return DetectedIndentation(useSpaces: nil, width: nil) }
+        return detected
+    }
+
+    /// Why the file should open read-only (the lock tooltip text), or nil
+    /// when it's editable.
+    static func readOnlyReason(path: String, content: String) -> String? {
+        consumeCString(impulse_read_only_reason(path, content))
+    }
+
+    /// Returns the rebindable Monaco editor actions.
+    static func editorActions() -> [EditorAction] {
+        guard let json = consumeCString(impulse_editor_actions_json()),
+              let data = json.data(using: .utf8) else { return [] }
+        return (try? JSONDecoder().decode([EditorAction].self, from: data)) ?? []
+    }
+
+    // MARK: - Theme API
+
+    /// Returns the list of all available theme names (built-in + user).
+    static func availableThemes() -> [String] {
         guard let json = consumeCString(impulse_available_themes()) else { return [] }
         guard let data = json.data(using: .utf8),
               let names = try? JSONSerialization.jsonObject(with: data) as? [String] else { return [] }
@@ -1156,6 +1992,24 @@ final class ImpulseCore {
         return consumeCString(impulse_get_theme(name)) ?? "{}"
     }
 
+    /// Imports a VS Code color theme file as a user theme and returns its
+    /// theme id, or the error message on failure.
+    static func importVSCodeTheme(path: String) -> (id: String?, error: String?) {
+        guard let json = consumeCString(impulse_import_vscode_theme(path)),
+              let data = json.data(using: .utf8),
+              let object = try? JSONSerialization.jsonObject(with: data) as? [String: Any] else {
+            return (nil, "Theme import failed")
+        }
+        if let id = object["id"] as? String { return (id, nil) }
+        return (nil, object["error"] as? String ?? "Theme import failed")
+    }
+
+    /// Makes the theme getters return increased-contrast variants (text and
+    /// borders raised to WCAG AAA against their backgrounds).
+    static func setIncreaseContrast(_ enabled: Bool) {
+        impulse_theme_set_increase_contrast(enabled)
+    }
+
     /// Returns the MonacoThemeDefinition as a JSON string.
     static func getMonacoTheme(name: String) -> String {
         return consumeCString(impulse_get_monaco_theme(name)) ?? "{}"
@@ -1184,6 +2038,406 @@ final class ImpulseCore {
         return result
     }
 
+    /// Load the shared settings file. Returns the raw
+    /// `{"path","settings","warning"}` JSON, or nil on failure.
+    static func settingsLoad() -> String? {
+        return consumeCString(CImpulseFFI.impulse_settings_load())
+    }
+
+    /// Merge `json` over the stored settings and write the shared settings
+    /// file. Returns nil on success or the reason the save was refused.
+    static func settingsSave(_ json: String) -> String? {
+        let raw = json.withCString { consumeCString(CImpulseFFI.impulse_settings_save($0)) }
+        let result = decodeGitResult(raw, action: "Saving settings")
+        return result.ok ? nil : result.error
+    }
+
+    /// Retained handler box for `watchSettingsFile`, released on replace.
+    private static var settingsWatchContext: Unmanaged<EventHandlerBox>?
+
+    /// Calls `handler` on a background thread whenever the settings file
+    /// may have changed (including our own saves); follow up with
+    /// `checkSettingsFileChange`. `nil` stops watching.
+    static func watchSettingsFile(_ handler: (() -> Void)?) {
+        impulse_settings_watch(nil, nil)
+        settingsWatchContext?.release()
+        settingsWatchContext = nil
+        guard let handler else { return }
+
+        let context = Unmanaged.passRetained(EventHandlerBox { _ in handler() })
+        settingsWatchContext = context
+        impulse_settings_watch({ context, _ in
+            guard let context else { return }
+            Unmanaged<EventHandlerBox>.fromOpaque(context).takeUnretainedValue().handler("{}")
+        }, context.toOpaque())
+    }
+
+    enum SettingsFileChange {
+        case unchanged
+        /// Edited outside Impulse and reloaded.
+        case reloaded(Settings)
+        /// The edited file doesn't parse; the current settings stay.
+        case invalid(String)
+        /// Edited while in-app changes couldn't be saved; settle with
+        /// `resolveSettingsConflict`.
+        case conflict
+    }
+
+    private struct SettingsFileCheck: Decodable {
+        let status: String
+        let settings: Settings?
+        let message: String?
+    }
+
+    /// Reloads the settings file if it changed outside Impulse.
+    static func checkSettingsFileChange() -> SettingsFileChange {
+        guard let json = consumeCString(impulse_settings_check_external_change()),
+              let check = try? JSONDecoder().decode(SettingsFileCheck.self, from: Data(json.utf8))
+        else { return .unchanged }
+        switch check.status {
+        case "reloaded": return check.settings.map { .reloaded($0) } ?? .unchanged
+        case "invalid": return .invalid(check.message ?? "Invalid settings")
+        case "conflict": return .conflict
+        default: return .unchanged
+        }
+    }
+
+    private struct SettingsConflictResult: Decodable {
+        let ok: Bool
+        let settings: Settings?
+        let error: String?
+    }
+
+    /// Keeps the in-app settings (overwriting the file) or the file's.
+    /// Returns the settings now in effect, or the error.
+    static func resolveSettingsConflict(keepApp: Bool) -> (settings: Settings?, error: String?) {
+        guard let json = consumeCString(impulse_settings_resolve_conflict(keepApp)),
+              let result = try? JSONDecoder().decode(SettingsConflictResult.self, from: Data(json.utf8))
+        else { return (nil, "Failed to resolve settings conflict") }
+        return result.ok ? (result.settings, nil) : (nil, result.error ?? "Failed to save settings")
+    }
+
+    private struct ConfigArchiveResult: Decodable {
+        let ok: Bool
+        let count: Int?
+        let error: String?
+    }
+
+    private static func configArchiveResult(_ ptr: UnsafeMutablePointer<CChar>?) -> Result<Int, ImpulseError> {
+        guard let json = consumeCString(ptr),
+              let result = try? JSONDecoder().decode(ConfigArchiveResult.self, from: Data(json.utf8))
+        else { return .failure(ImpulseError(message: "Unexpected response from core")) }
+        if result.ok { return .success(result.count ?? 0) }
+        return .failure(ImpulseError(message: result.error ?? "Unknown error"))
+    }
+
+    /// Writes settings, keybindings, custom CSS and user themes to the zip
+    /// archive at `path`. Returns how many files it holds.
+    static func exportConfig(to path: String) -> Result<Int, ImpulseError> {
+        configArchiveResult(impulse_config_export(path))
+    }
+
+    /// Restores an archive written by `exportConfig`; apply the settings
+    /// with `checkSettingsFileChange`. Returns how many files were written.
+    static func importConfig(from path: String) -> Result<Int, ImpulseError> {
+        configArchiveResult(impulse_config_import(path))
+    }
+
+    enum ConfigSyncOutcome: String, Decodable {
+        case upToDate = "up_to_date"
+        case pushed
+        /// The repository's files were written; apply them with
+        /// `checkSettingsFileChange`.
+        case pulled
+        /// Both sides changed; settle with `syncConfig(url:keepLocal:)`.
+        case conflict
+    }
+
+    private struct ConfigSyncResult: Decodable {
+        let ok: Bool
+        let outcome: ConfigSyncOutcome?
+        let error: String?
+    }
+
+    /// Syncs the configuration with the git repository at `url`, or with
+    /// `keepLocal` set settles a conflict. Blocks on the network; call off
+    /// the main thread.
+    static func syncConfig(url: String, keepLocal: Bool? = nil) -> Result<ConfigSyncOutcome, ImpulseError> {
+        let mode: Int32 = keepLocal.map { $0 ? 1 : 0 } ?? -1
+        guard let json = consumeCString(impulse_config_sync(url, mode)),
+              let result = try? JSONDecoder().decode(ConfigSyncResult.self, from: Data(json.utf8))
+        else { return .failure(ImpulseError(message: "Unexpected response from core")) }
+        if result.ok, let outcome = result.outcome { return .success(outcome) }
+        return .failure(ImpulseError(message: result.error ?? "Unknown error"))
+    }
+
+    /// The window layouts saved in `dir`, sorted by name.
+    static func windowLayoutNames(in dir: String) -> [String] {
+        guard let json = consumeCString(impulse_window_layout_list(dir)),
+              let names = try? JSONDecoder().decode([String].self, from: Data(json.utf8))
+        else { return [] }
+        return names
+    }
+
+    private struct WindowLayoutResult: Decodable {
+        let ok: Bool
+        let window: SessionWindowState?
+        let error: String?
+    }
+
+    /// Saves `window` in `dir` as the layout `name`, replacing one of that name.
+    static func saveWindowLayout(
+        _ window: SessionWindowState, named name: String, in dir: String
+    ) -> Result<Void, ImpulseError> {
+        guard let data = try? JSONEncoder().encode(window),
+              let windowJSON = String(data: data, encoding: .utf8),
+              let json = consumeCString(impulse_window_layout_save(dir, name, windowJSON)),
+              let result = try? JSONDecoder().decode(WindowLayoutResult.self, from: Data(json.utf8))
+        else { return .failure(ImpulseError(message: "Unexpected response from core")) }
+        if result.ok { return .success(()) }
+        return .failure(ImpulseError(message: result.error ?? "Unknown error"))
+    }
+
+    /// Loads the layout `name` from `dir`.
+    static func loadWindowLayout(named name: String, in dir: String) -> Result<SessionWindowState, ImpulseError> {
+        guard let json = consumeCString(impulse_window_layout_load(dir, name)),
+              let result = try? JSONDecoder().decode(WindowLayoutResult.self, from: Data(json.utf8))
+        else { return .failure(ImpulseError(message: "Unexpected response from core")) }
+        if result.ok, let window = result.window { return .success(window) }
+        return .failure(ImpulseError(message: result.error ?? "Unknown error"))
+    }
+
+    private struct FileActionResult: Decodable {
+        let ok: Bool
+        let path: String?
+        let error: String?
+    }
+
+    private static func fileActionResult(_ json: String?) -> Result<String, ImpulseError> {
+        guard let json,
+              let result = try? JSONDecoder().decode(FileActionResult.self, from: Data(json.utf8))
+        else { return .failure(ImpulseError(message: "Unexpected response from core")) }
+        if result.ok, let path = result.path { return .success(path) }
+        return .failure(ImpulseError(message: result.error ?? "Unknown error"))
+    }
+
+    /// Creates the file or folder `name` in `dir`, returning its path. New
+    /// files start from the first of `templates` matching their name.
+    static func createFile(
+        named name: String, in dir: String, isDirectory: Bool, templates: [FileTemplate]
+    ) -> Result<String, ImpulseError> {
+        let templatesJSON = (try? JSONEncoder().encode(templates))
+            .flatMap { String(data: $0, encoding: .utf8) } ?? "[]"
+        return fileActionResult(
+            consumeCString(impulse_file_create(dir, name, isDirectory, templatesJSON)))
+    }
+
+    /// Renames `path` to `newName` in the same folder, returning the new path.
+    static func renameFile(at path: String, to newName: String) -> Result<String, ImpulseError> {
+        fileActionResult(consumeCString(impulse_file_rename(path, newName)))
+    }
+
+    /// Copies the file or folder at `path` next to it as "name copy",
+    /// returning the copy's path.
+    static func duplicateFile(at path: String) -> Result<String, ImpulseError> {
+        fileActionResult(consumeCString(impulse_file_duplicate(path)))
+    }
+
+    /// A file or folder's properties, for the Properties dialog.
+    struct FileProperties: Decodable {
+        let path: String
+        let name: String
+        /// "file", "directory", "symlink" or "other".
+        let kind: String
+        let size: UInt64
+        /// Seconds since the Unix epoch.
+        let modified: UInt64?
+        let mode: UInt32?
+        let owner: String?
+        let group: String?
+        let symlinkTarget: String?
+        let lineCount: UInt64?
+        let encoding: String?
+        /// Filled in from the response around the properties.
+        var sizeLabel = ""
+
+        enum CodingKeys: String, CodingKey {
+            case path, name, kind, size, modified, mode, owner, group, encoding
+            case symlinkTarget = "symlink_target"
+            case lineCount = "line_count"
+        }
+    }
+
+    private struct FilePropertiesResult: Decodable {
+        let ok: Bool
+        let properties: FileProperties?
+        let sizeLabel: String?
+        let error: String?
+
+        enum CodingKeys: String, CodingKey {
+            case ok, properties, error
+            case sizeLabel = "size_label"
+        }
+    }
+
+    /// Reads the properties of the file or folder at `path`.
+    static func fileProperties(at path: String) -> Result<FileProperties, ImpulseError> {
+        guard let json = consumeCString(impulse_file_properties(path)),
+              let result = try? JSONDecoder().decode(FilePropertiesResult.self, from: Data(json.utf8))
+        else { return .failure(ImpulseError(message: "Unexpected response from core")) }
+        guard result.ok, var properties = result.properties else {
+            return .failure(ImpulseError(message: result.error ?? "Unknown error"))
+        }
+        properties.sizeLabel = result.sizeLabel ?? ""
+        return .success(properties)
+    }
+
+    private struct OkResult: Decodable {
+        let ok: Bool
+        let error: String?
+    }
+
+    /// Sets the permission bits of `path` to `mode`.
+    static func setFileMode(at path: String, mode: UInt32) -> Result<Void, ImpulseError> {
+        guard let json = consumeCString(impulse_file_set_mode(path, mode)),
+              let result = try? JSONDecoder().decode(OkResult.self, from: Data(json.utf8))
+        else { return .failure(ImpulseError(message: "Unexpected response from core")) }
+        if result.ok { return .success(()) }
+        return .failure(ImpulseError(message: result.error ?? "Unknown error"))
+    }
+
+    // MARK: - Privileged Write
+
+    /// Whether `writeAsAdministrator(_:to:)` can retry a denied save.
+    private struct SafeWriteResult: Decodable {
+        let ok: Bool
+        let error: String?
+        let permission_denied: Bool?
+    }
+
+    /// Trailing-whitespace and final-newline fixes for saving `content` to
+    /// `path`; `.editorconfig` files override `trim` and `finalNewline`.
+    /// Returns nil when nothing needs fixing.
+    static func saveWhitespaceFix(
+        path: String, content: String, trim: Bool, finalNewline: Bool
+    ) -> SaveWhitespaceFix? {
+        guard let json = consumeCString(impulse_save_whitespace(path, content, trim, finalNewline))
+        else { return nil }
+        return try? JSONDecoder().decode(SaveWhitespaceFix.self, from: Data(json.utf8))
+    }
+
+    /// Saves `content` to `path` with the `save_strategy` setting (temp file
+    /// + rename by default), keeping the file's permissions, owner and
+    /// extended attributes. Throws `CocoaError(.fileWriteNoPermission)` when
+    /// the file or its folder isn't writable.
+    static func safeWrite(_ content: String, to path: String) throws {
+        guard let json = consumeCString(impulse_safe_write(path, content)),
+              let result = try? JSONDecoder().decode(SafeWriteResult.self, from: Data(json.utf8))
+        else { throw ImpulseError(message: "Unexpected response from core") }
+        if result.ok { return }
+        if result.permission_denied == true {
+            throw CocoaError(.fileWriteNoPermission, userInfo: [
+                NSFilePathErrorKey: path,
+                NSLocalizedFailureReasonErrorKey: result.error ?? "Permission denied",
+            ])
+        }
+        throw ImpulseError(message: result.error ?? "Unknown error")
+    }
+
+    static var canWriteAsAdministrator: Bool {
+        impulse_privileged_write_available()
+    }
+
+    /// Writes `content` to `path` as the administrator, after the system
+    /// authorization prompt. Blocks until the prompt is answered.
+    static func writeAsAdministrator(_ content: String, to path: String) -> Result<Void, ImpulseError> {
+        guard let json = consumeCString(impulse_privileged_write(path, content)),
+              let result = try? JSONDecoder().decode(OkResult.self, from: Data(json.utf8))
+        else { return .failure(ImpulseError(message: "Unexpected response from core")) }
+        if result.ok { return .success(()) }
+        return .failure(ImpulseError(message: result.error ?? "Unknown error"))
+    }
+
+    // MARK: - Remote Files
+
+    private struct RemoteOpenResult: Decodable {
+        let ok: Bool
+        let path: String?
+        let error: String?
+    }
+
+    /// Downloads the `sftp://` or `ssh://` file at `uri` into the cache and
+    /// returns the local copy's path. Blocks on the network.
+    static func openRemoteFile(uri: String) -> Result<String, ImpulseError> {
+        guard let json = consumeCString(impulse_remote_file_open(uri)),
+              let result = try? JSONDecoder().decode(RemoteOpenResult.self, from: Data(json.utf8))
+        else { return .failure(ImpulseError(message: "Unexpected response from core")) }
+        if result.ok, let path = result.path { return .success(path) }
+        return .failure(ImpulseError(message: result.error ?? "Unknown error"))
+    }
+
+    private struct RemoteUploadResult: Decodable {
+        let ok: Bool
+        let changed_on_server: Bool?
+        let error: String?
+    }
+
+    /// Uploads the cached copy at `path`. Returns `false` without writing
+    /// when the server's file changed since the download, unless `force`.
+    /// Blocks on the network.
+    static func uploadRemoteFile(at path: String, force: Bool) -> Result<Bool, ImpulseError> {
+        guard let json = consumeCString(impulse_remote_file_upload(path, force)),
+              let result = try? JSONDecoder().decode(RemoteUploadResult.self, from: Data(json.utf8))
+        else { return .failure(ImpulseError(message: "Unexpected response from core")) }
+        if result.ok { return .success(result.changed_on_server != true) }
+        return .failure(ImpulseError(message: result.error ?? "Unknown error"))
+    }
+
+    /// The URI the cached copy at `path` came from, or nil.
+    static func remoteFileURI(for path: String) -> String? {
+        consumeCString(impulse_remote_file_uri(path))
+    }
+
+    /// Remote files opened before.
+    static func recentRemoteFileURIs() -> [String] {
+        guard let json = consumeCString(impulse_remote_file_recent_uris()),
+              let uris = try? JSONDecoder().decode([String].self, from: Data(json.utf8))
+        else { return [] }
+        return uris
+    }
+
+    /// What `importVscode(over:)` made of the user's VS Code setup.
+    struct VscodeImport: Decodable {
+        let settings: Settings
+        /// The VS Code settings and keybindings that carried over.
+        let imported: [String]
+        /// Those without an Impulse equivalent.
+        let unmapped: [String]
+    }
+
+    private struct VscodeImportResult: Decodable {
+        let ok: Bool
+        let settings: Settings?
+        let imported: [String]?
+        let unmapped: [String]?
+        let error: String?
+    }
+
+    /// Maps the user's VS Code settings and keybindings onto `base` without
+    /// saving anything.
+    static func importVscode(over base: Settings) -> Result<VscodeImport, ImpulseError> {
+        guard let data = try? JSONEncoder().encode(base),
+              let baseJSON = String(data: data, encoding: .utf8),
+              let json = consumeCString(impulse_vscode_import(baseJSON)),
+              let result = try? JSONDecoder().decode(VscodeImportResult.self, from: Data(json.utf8))
+        else { return .failure(ImpulseError(message: "Unexpected response from core")) }
+        guard result.ok, let settings = result.settings else {
+            return .failure(ImpulseError(message: result.error ?? "Unknown error"))
+        }
+        return .success(VscodeImport(
+            settings: settings, imported: result.imported ?? [], unmapped: result.unmapped ?? []))
+    }
+
     /// Parse, migrate, and validate raw settings JSON.
     static func settingsLoadJSON(_ rawJSON: String) -> String {
         guard let raw = rawJSON.withCString({ CImpulseFFI.impulse_settings_load_json($0) }) else {
@@ -1204,6 +2458,24 @@ final class ImpulseCore {
         return result
     }
 
+    /// How settings format documents of `language`: `"lsp"`,
+    /// `"lsp_or_fallback"` or `"external"`.
+    static func formatterMode(language: String, filePath: String) -> String {
+        guard let json = consumeCString(impulse_formatter_choice(language, filePath)),
+              let data = json.data(using: .utf8),
+              let object = try? JSONSerialization.jsonObject(with: data) as? [String: Any],
+              let mode = object["mode"] as? String
+        else { return "lsp" }
+        return mode
+    }
+
+    /// Run the external formatter settings pick for `language` over `text`.
+    /// Blocks; returns LSP `TextEdit`s as JSON, or `{"error": ...}`.
+    static func formatExternal(language: String, filePath: String, text: String) -> String {
+        return consumeCString(impulse_format_external(language, filePath, text))
+            ?? "{\"error\":\"null response\"}"
+    }
+
     /// Check whether a file path matches a glob-style pattern.
     static func matchesFilePattern(path: String, pattern: String) -> Bool {
         return path.withCString { pathPtr in
@@ -1213,6 +2485,404 @@ final class ImpulseCore {
         }
     }
 
+    /// The text a copy path command (`copy_path`, `copy_relative_path`,
+    /// `copy_file_name` or `copy_file_url`) puts on the clipboard for `path`.
+    static func pathForCopy(commandId: String, path: String, root: String) -> String? {
+        return consumeCString(impulse_path_for_copy(commandId, path, root))
+    }
+
+    // MARK: - Command Registry
+
+    /// Every command in the core registry, with the shortcuts the saved
+    /// settings put in effect and `enabled` evaluated for `context`.
+    static func commandRegistry(context: CommandContext = CommandContext()) -> [CommandRegistryEntry] {
+        let contextJSON = (try? JSONEncoder().encode(context)).flatMap {
+            String(data: $0, encoding: .utf8)
+        } ?? "{}"
+        guard let json = consumeCString(impulse_command_registry_json(contextJSON)) else {
+            return []
+        }
+        return (try? JSONDecoder().decode([CommandRegistryEntry].self, from: Data(json.utf8))) ?? []
+    }
+
+    /// Retained handler box for `setCommandHandler`, released on replace.
+    private static var commandHandlerContext: Unmanaged<EventHandlerBox>?
+
+    /// Runs `executeCommand` requests. The handler receives the command id
+    /// and its arguments (`nil` when none were given) on the caller's
+    /// thread.
+    static func setCommandHandler(_ handler: ((String, Any?) -> Void)?) {
+        impulse_set_command_handler(nil, nil)
+        commandHandlerContext?.release()
+        commandHandlerContext = nil
+        guard let handler else { return }
+
+        let context = Unmanaged.passRetained(EventHandlerBox { json in
+            guard let object = try? JSONSerialization.jsonObject(with: Data(json.utf8)) as? [String: Any],
+                  let id = object["id"] as? String
+            else { return }
+            let args = object["args"].flatMap { $0 is NSNull ? nil : $0 }
+            handler(id, args)
+        })
+        commandHandlerContext = context
+        impulse_set_command_handler({ context, json in
+            guard let context, let json else { return }
+            let box = Unmanaged<EventHandlerBox>.fromOpaque(context).takeUnretainedValue()
+            box.handler(String(cString: json))
+        }, context.toOpaque())
+    }
+
+    /// Runs registry command `id` through the handler. Returns an error
+    /// message, or `nil` on success.
+    @discardableResult
+    static func executeCommand(_ id: String, argsJSON: String? = nil) -> String? {
+        return consumeCString(impulse_execute_command(id, argsJSON))
+    }
+
+    // MARK: - Workspace Trust
+
+    enum TrustState: String {
+        case trusted
+        /// The user chose restricted mode.
+        case restricted
+        /// Not asked yet; restricted until the user decides.
+        case unknown
+    }
+
+    static func setWorkspaceTrustEnabled(_ enabled: Bool) {
+        impulse_trust_set_enabled(enabled)
+    }
+
+    static func trustState(path: String) -> TrustState {
+        consumeCString(impulse_trust_state(path)).flatMap(TrustState.init) ?? .unknown
+    }
+
+    static func isTrusted(path: String) -> Bool {
+        trustState(path: path) == .trusted
+    }
+
+    /// The folder a trust decision for `path` applies to.
+    static func trustWorkspaceFolder(path: String) -> String {
+        consumeCString(impulse_trust_workspace_folder(path)) ?? path
+    }
+
+    /// Trusts `folder` or keeps it restricted. Returns an error message on
+    /// failure.
+    @discardableResult
+    static func setTrust(folder: String, trusted: Bool) -> String? {
+        consumeCString(impulse_trust_set(folder, trusted))
+    }
+
+    // MARK: - Assistant
+
+    /// One message of an assistant conversation.
+    struct AssistantMessage: Codable {
+        /// `user` or `assistant`.
+        let role: String
+        let content: String
+    }
+
+    private static func assistantReply(_ json: String?) -> Result<String, ImpulseError> {
+        guard let data = json?.data(using: .utf8),
+              let object = try? JSONSerialization.jsonObject(with: data) as? [String: Any]
+        else { return .failure(ImpulseError(message: "The assistant request failed")) }
+        if let reply = object["reply"] as? String {
+            return .success(reply)
+        }
+        let message = object["error"] as? String ?? "The assistant request failed"
+        return .failure(ImpulseError(message: message))
+    }
+
+    /// The assistant's reply to a conversation. Blocks until the model
+    /// answers; call off the main thread.
+    static func assistantChat(_ messages: [AssistantMessage]) -> Result<String, ImpulseError> {
+        guard let data = try? JSONEncoder().encode(messages),
+              let json = String(data: data, encoding: .utf8)
+        else { return .failure(ImpulseError(message: "Invalid messages")) }
+        return assistantReply(consumeCString(impulse_assistant_chat(json)))
+    }
+
+    /// An explanation of `code`. Blocks; call off the main thread.
+    static func assistantExplainSelection(
+        code: String, language: String, filePath: String?
+    ) -> Result<String, ImpulseError> {
+        assistantReply(consumeCString(
+            impulse_assistant_explain_selection(code, language, filePath ?? "")))
+    }
+
+    /// A commit message for the uncommitted (or staged) changes in
+    /// `repoPath`. Blocks; call off the main thread.
+    static func assistantCommitMessage(repoPath: String) -> Result<String, ImpulseError> {
+        assistantReply(consumeCString(impulse_assistant_commit_message(repoPath)))
+    }
+
+    /// Stores `key` for `provider` in the keychain; an empty key removes it.
+    @discardableResult
+    static func setAssistantAPIKey(_ key: String, provider: String) -> Bool {
+        impulse_assistant_set_api_key(provider, key)
+    }
+
+    static func hasAssistantAPIKey(provider: String) -> Bool {
+        impulse_assistant_has_api_key(provider)
+    }
+
+    // MARK: - Secrets
+
+    /// A secret in the keychain, without its value.
+    struct StoredSecret: Decodable, Identifiable {
+        let id: String
+        /// `git_credential`, `assistant_key`, `remote_password` or
+        /// `ssh_passphrase`.
+        let kind: String
+        let label: String
+        /// A provider id, or `user@host`.
+        let name: String
+        let updatedAt: UInt64
+
+        enum CodingKeys: String, CodingKey {
+            case id, kind, label, name
+            case updatedAt = "updated_at"
+        }
+    }
+
+    /// Every stored secret, by kind and name.
+    static func storedSecrets() -> [StoredSecret] {
+        guard let json = consumeCString(impulse_secrets_list()) else { return [] }
+        return (try? JSONDecoder().decode([StoredSecret].self, from: Data(json.utf8))) ?? []
+    }
+
+    static func secret(kind: String, name: String) -> String? {
+        consumeCString(impulse_secret_get(kind, name))
+    }
+
+    /// Stores `value` in the keychain; an empty value removes the secret.
+    @discardableResult
+    static func setSecret(_ value: String, kind: String, name: String) -> Bool {
+        impulse_secret_set(kind, name, value)
+    }
+
+    @discardableResult
+    static func deleteSecret(id: String) -> Bool {
+        impulse_secret_delete(id)
+    }
+
+    // MARK: - Agent Access (MCP)
+
+    /// `allowed`, `denied` or `unknown` for the workspace `folder`.
+    static func agentAccessConsent(folder: String) -> String {
+        consumeCString(impulse_mcp_consent(folder)) ?? "unknown"
+    }
+
+    @discardableResult
+    static func setAgentAccessConsent(folder: String, allowed: Bool) -> Bool {
+        impulse_mcp_set_consent(folder, allowed)
+    }
+
+    /// Share the workspace containing `path` with agents. Returns the
+    /// socket path.
+    static func startAgentAccess(path: String) -> Result<String, ImpulseError> {
+        guard let data = consumeCString(impulse_mcp_start(path))?.data(using: .utf8),
+              let object = try? JSONSerialization.jsonObject(with: data) as? [String: Any]
+        else { return .failure(ImpulseError(message: "Failed to share the workspace")) }
+        if let socket = object["socket"] as? String {
+            return .success(socket)
+        }
+        let message = object["error"] as? String ?? "Failed to share the workspace"
+        return .failure(ImpulseError(message: message))
+    }
+
+    static func stopAgentAccess() {
+        impulse_mcp_stop()
+    }
+
+    /// The next agent request (`id`, `kind` and for `apply_edits` the
+    /// `files`), or nil.
+    static func nextAgentRequest() -> [String: Any]? {
+        guard let data = consumeCString(impulse_mcp_next_request())?.data(using: .utf8) else {
+            return nil
+        }
+        return try? JSONSerialization.jsonObject(with: data) as? [String: Any]
+    }
+
+    /// Answer an agent request with a JSON result, or fail it with `error`.
+    @discardableResult
+    static func respondToAgentRequest(id: UInt64, resultJSON: String?, error: String?) -> Bool {
+        impulse_mcp_respond(id, resultJSON, error)
+    }
+
+    // MARK: - Control Socket
+
+    /// Listen on the control socket. Returns the socket path.
+    static func startControlSocket() -> Result<String, ImpulseError> {
+        guard let data = consumeCString(impulse_control_start())?.data(using: .utf8),
+              let object = try? JSONSerialization.jsonObject(with: data) as? [String: Any]
+        else { return .failure(ImpulseError(message: "Failed to start the control socket")) }
+        if let socket = object["socket"] as? String {
+            return .success(socket)
+        }
+        let message = object["error"] as? String ?? "Failed to start the control socket"
+        return .failure(ImpulseError(message: message))
+    }
+
+    static func stopControlSocket() {
+        impulse_control_stop()
+    }
+
+    /// The next control call (`id`, `method` and `params`), or nil.
+    static func nextControlCall() -> [String: Any]? {
+        guard let data = consumeCString(impulse_control_next_call())?.data(using: .utf8) else {
+            return nil
+        }
+        return try? JSONSerialization.jsonObject(with: data) as? [String: Any]
+    }
+
+    /// Answer a control call with a JSON result, or fail it with `error`.
+    @discardableResult
+    static func respondToControlCall(id: UInt64, resultJSON: String?, error: String?) -> Bool {
+        impulse_control_respond(id, resultJSON, error)
+    }
+
+    // MARK: - Notifications
+
+    enum NotificationSeverity: String, Codable {
+        case info
+        case warning
+        case error
+    }
+
+    /// A button on a notification; `command` is a core registry command ID.
+    struct NotificationAction: Codable, Hashable {
+        let label: String
+        let command: String
+    }
+
+    /// An entry in the notification history.
+    struct AppNotification: Codable, Identifiable {
+        var id: UInt64 = 0
+        let severity: NotificationSeverity
+        let title: String
+        var message: String = ""
+        var source: String = ""
+        var actions: [NotificationAction] = []
+        var timestampMs: UInt64 = 0
+        var count: UInt32 = 1
+        var read: Bool = false
+
+        enum CodingKeys: String, CodingKey {
+            case id, severity, title, message, source, actions, count, read
+            case timestampMs = "timestamp_ms"
+        }
+    }
+
+    /// Records a notification in the history shown by every window's bell.
+    /// Safe to call from any thread.
+    @discardableResult
+    static func notify(
+        _ severity: NotificationSeverity, title: String, message: String = "",
+        source: String = "", actions: [NotificationAction] = []
+    ) -> UInt64 {
+        let notification = AppNotification(
+            severity: severity, title: title, message: message, source: source, actions: actions)
+        guard let data = try? JSONEncoder().encode(notification),
+              let json = String(data: data, encoding: .utf8)
+        else { return 0 }
+        let id = impulse_notifications_push(json)
+        postNotificationsChanged()
+        return id
+    }
+
+    /// The notification history, newest first.
+    static func notifications() -> [AppNotification] {
+        guard let json = consumeCString(impulse_notifications_json()),
+              let data = json.data(using: .utf8)
+        else { return [] }
+        return (try? JSONDecoder().decode([AppNotification].self, from: data)) ?? []
+    }
+
+    static func markAllNotificationsRead() {
+        impulse_notifications_mark_all_read()
+        postNotificationsChanged()
+    }
+
+    static func dismissNotification(id: UInt64) {
+        impulse_notifications_dismiss(id)
+        postNotificationsChanged()
+    }
+
+    static func clearNotifications() {
+        impulse_notifications_clear()
+        postNotificationsChanged()
+    }
+
+    private static func postNotificationsChanged() {
+        DispatchQueue.main.async {
+            NotificationCenter.default.post(name: .impulseNotificationsDidChange, object: nil)
+        }
+    }
+
+    // MARK: - Tab Sorting and Groups
+
+    /// A tab as `impulse_core::tab_order` sees it.
+    struct TabSummary: Encodable {
+        let title: String
+        /// File behind an editor or image tab.
+        let path: String?
+        let pinned: Bool
+        let modified: Bool
+        /// When the tab was last selected; larger is more recent.
+        let lastUsed: UInt64
+
+        enum CodingKeys: String, CodingKey {
+            case title, path, pinned, modified
+            case lastUsed = "last_used"
+        }
+    }
+
+    private static func tabIndices(
+        _ tabs: [TabSummary], _ call: (String) -> UnsafeMutablePointer<CChar>?
+    ) -> [Int] {
+        guard let data = try? JSONEncoder().encode(tabs),
+              let tabsJSON = String(data: data, encoding: .utf8),
+              let json = consumeCString(call(tabsJSON)),
+              let indices = try? JSONDecoder().decode([Int].self, from: Data(json.utf8))
+        else { return [] }
+        return indices.filter { tabs.indices.contains($0) }
+    }
+
+    /// The new order of `tabs` for `sort` (`"path"` or `"recent_use"`),
+    /// pinned tabs first.
+    static func tabSortedOrder(_ tabs: [TabSummary], sort: String) -> [Int] {
+        tabIndices(tabs) { impulse_tab_sorted_order($0, sort) }
+    }
+
+    /// The unpinned tabs in `group` (`"saved"`, `"right"` or `"left"`)
+    /// relative to the tab at `anchor`.
+    static func tabsToClose(_ tabs: [TabSummary], anchor: Int, group: String) -> [Int] {
+        tabIndices(tabs) { impulse_tabs_to_close($0, UInt32(max(anchor, 0)), group) }
+    }
+
+    /// The tabs matching a "Show All Tabs" search.
+    static func tabFilter(_ tabs: [TabSummary], query: String) -> [Int] {
+        tabIndices(tabs) { impulse_tab_filter($0, query) }
+    }
+
+    // MARK: - Zoom
+
+    /// The zoom after stepping `zoom` by `delta` points over `base`;
+    /// unchanged when that would leave the allowed font sizes.
+    static func zoomStep(base: Int, zoom: Int, delta: Int) -> Int {
+        Int(impulse_zoom_step(Int32(base), Int32(zoom), Int32(delta)))
+    }
+
+    static func zoomedFontSize(base: Int, zoom: Int) -> Int {
+        Int(impulse_zoom_font_size(Int32(base), Int32(zoom)))
+    }
+
+    /// Status bar text like "120%", or nil at the default size.
+    static func zoomLabel(base: Int, zoom: Int) -> String? {
+        consumeCString(impulse_zoom_label(Int32(base), Int32(zoom)))
+    }
+
     // MARK: - Command Palette
 
     static func commandPaletteBuiltinItems() -> [CommandPaletteItem] {
@@ -1335,21 +3005,72 @@ final class ImpulseCore {
         (try? JSONDecoder().decode([CommandPaletteItem].self, from: Data(json.utf8))) ?? []
     }
 
-    /// Check for a newer version on GitHub Releases.
+    /// A newer release found by `checkForUpdate` / `checkForUpdateNow`.
+    struct UpdateCheck {
+        let version: String
+        let currentVersion: String
+        let url: String
+        /// Appcast entry for `installUpdate`; `nil` when the release has no
+        /// appcast.
+        let itemJSON: String?
+        /// Whether this app bundle can install the update itself.
+        let canInstall: Bool
+        /// Where updates come from when it can't.
+        let hint: String
+    }
+
+    /// Check for a newer version (the release appcast, then GitHub
+    /// Releases), at most once a day.
     ///
-    /// Returns `(version, url)` if a new version is available, or `nil` if
+    /// Returns the update if a new version is available, or `nil` if
     /// up-to-date, checked recently, or on error.
-    static func checkForUpdate() -> (version: String, currentVersion: String, url: String)? {
+    static func checkForUpdate() -> UpdateCheck? {
         guard let raw = CImpulseFFI.impulse_check_for_update() else { return nil }
         let result = String(cString: raw)
         impulse_free_string(raw)
-        guard !result.isEmpty, !result.hasPrefix("ERROR:") else { return nil }
+        return try? decodeUpdateCheck(result).get()
+    }
+
+    /// `checkForUpdate` without the once-a-day limit, for "Check for
+    /// Updates". `.success(nil)` means up to date.
+    static func checkForUpdateNow() -> Result<UpdateCheck?, ImpulseError> {
+        guard let raw = CImpulseFFI.impulse_check_for_update_now() else {
+            return .failure(ImpulseError(message: "impulse_check_for_update_now returned null"))
+        }
+        let result = String(cString: raw)
+        impulse_free_string(raw)
+        return decodeUpdateCheck(result)
+    }
+
+    private static func decodeUpdateCheck(_ result: String) -> Result<UpdateCheck?, ImpulseError> {
+        if result.isEmpty { return .success(nil) }
+        if result.hasPrefix("ERROR:") {
+            return .failure(ImpulseError(message: String(result.dropFirst(6))))
+        }
         guard let data = result.data(using: .utf8),
-              let json = try? JSONSerialization.jsonObject(with: data) as? [String: String],
-              let version = json["version"],
-              let currentVersion = json["current_version"],
-              let url = json["url"] else { return nil }
-        return (version, currentVersion, url)
+              let json = try? JSONSerialization.jsonObject(with: data) as? [String: Any],
+              let version = json["version"] as? String,
+              let currentVersion = json["current_version"] as? String,
+              let url = json["url"] as? String else {
+            return .failure(ImpulseError(message: "Unreadable update check result"))
+        }
+        let itemJSON = (json["item"] as? [String: Any]).flatMap {
+            (try? JSONSerialization.data(withJSONObject: $0)).flatMap { String(data: $0, encoding: .utf8) }
+        }
+        return .success(UpdateCheck(
+            version: version,
+            currentVersion: currentVersion,
+            url: url,
+            itemJSON: itemJSON,
+            canInstall: json["can_install"] as? Bool ?? false,
+            hint: json["hint"] as? String ?? ""
+        ))
+    }
+
+    /// Download, verify and install an update over this app bundle; it takes
+    /// effect on the next launch. Returns an error message on failure.
+    static func installUpdate(itemJSON: String) -> String? {
+        consumeCString(impulse_update_install(itemJSON))
     }
 
     /// Return the current application version.
@@ -1360,3 +3081,132 @@ final class ImpulseCore {
         return String(cString: ptr)
     }
 }
+
+/// Recursive filesystem watcher backed by `impulse_watch_*`. The handler
+/// receives event JSON (`{"kind", "paths"}`) on a background thread until
+/// the watcher is deallocated.
+final class CoreFileWatcher {
+    private let handle: OpaquePointer
+    private let context: Unmanaged<EventHandlerBox>
+
+    init?(root: String, handler: @escaping (String) -> Void) {
+        guard let handle = impulse_watch_create(root) else { return nil }
+        self.handle = handle
+        context = Unmanaged.passRetained(EventHandlerBox(handler))
+        impulse_watch_set_event_callback(handle, { context, json in
+            guard let context, let json else { return }
+            let box = Unmanaged<EventHandlerBox>.fromOpaque(context).takeUnretainedValue()
+            box.handler(String(cString: json))
+        }, context.toOpaque())
+    }
+
+    deinit {
+        impulse_watch_free(handle)
+        context.release()
+    }
+}
+
+/// One editor's outgoing command queue backed by `impulse_editor_batch_*`.
+/// Not thread-safe; use it from the main thread.
+final class CoreEditorCommandBatch {
+    private let handle: OpaquePointer
+
+    init?() {
+        guard let handle = impulse_editor_batch_new() else { return nil }
+        self.handle = handle
+    }
+
+    deinit {
+        impulse_editor_batch_free(handle)
+    }
+
+    /// Queue a serialized `EditorCommand`. Returns false if it was rejected.
+    func push(_ commandJson: String) -> Bool {
+        return impulse_editor_batch_push(handle, commandJson)
+    }
+
+    /// Drain the queue as a JSON array, or nil when it is empty.
+    func take() -> String? {
+        guard let raw = impulse_editor_batch_take(handle) else { return nil }
+        defer { impulse_free_string(raw) }
+        return String(cString: raw)
+    }
+}
+
+/// A process in a raw PTY backed by `impulse_pty_*`, for hosts that bring
+/// their own terminal emulator. Handlers run on the PTY reader thread.
+final class CorePtySession {
+    private final class Handlers {
+        let onOutput: (Data) -> Void
+        let onExit: (Int32) -> Void
+
+        init(onOutput: @escaping (Data) -> Void, onExit: @escaping (Int32) -> Void) {
+            self.onOutput = onOutput
+            self.onExit = onExit
+        }
+    }
+
+    private let handle: UnsafeMutableRawPointer
+    private let context: Unmanaged<Handlers>
+
+    /// Spawns `profileJson` (see `impulse_pty_spawn`). Returns `nil` on failure.
+    init?(profileJson: String, onOutput: @escaping (Data) -> Void, onExit: @escaping (Int32) -> Void) {
+        let context = Unmanaged.passRetained(Handlers(onOutput: onOutput, onExit: onExit))
+        guard let handle = impulse_pty_spawn(profileJson, { context, data, len, exitCode in
+            guard let context else { return }
+            let handlers = Unmanaged<Handlers>.fromOpaque(context).takeUnretainedValue()
+            if let data {
+                handlers.onOutput(Data(bytes: data, count: len))
+            } else {
+                handlers.onExit(exitCode)
+            }
+        }, context.toOpaque()) else {
+            context.release()
+            return nil
+        }
+        self.handle = handle
+        self.context = context
+    }
+
+    deinit {
+        impulse_pty_free(handle)
+        context.release()
+    }
+
+    func write(_ data: Data) {
+        data.withUnsafeBytes { buffer in
+            guard let base = buffer.bindMemory(to: UInt8.self).baseAddress else { return }
+            impulse_pty_write(handle, base, buffer.count)
+        }
+    }
+
+    func resize(cols: UInt16, rows: UInt16) {
+        impulse_pty_resize(handle, cols, rows)
+    }
+
+    @discardableResult
+    func kill(signal: Int32 = SIGTERM) -> Bool {
+        return impulse_pty_kill(handle, signal) == 0
+    }
+
+    var childPid: pid_t {
+        return pid_t(impulse_pty_child_pid(handle))
+    }
+
+    /// The child's current working directory as reported by the OS.
+    var cwd: String? {
+        guard let ptr = impulse_pty_cwd(handle) else { return nil }
+        let result = String(cString: ptr)
+        impulse_free_string(ptr)
+        return result
+    }
+}
+
+/// Boxes a Swift closure so it can travel through a C `void *` context.
+private final class EventHandlerBox {
+    let handler: (String) -> Void
+
+    init(_ handler: @escaping (String) -> Void) {
+        self.handler = handler
+    }
+}
@@ -0,0 +1,89 @@
+import AppKit
+
+/// `impulse [--new-window] [--wait] <path[:line[:column]]>...` from a
+/// terminal. A launch that finds Impulse already running hands its request
+/// to that instance over a distributed notification and exits; with
+/// `--wait` it stays until the running instance reports that the files it
+/// opened have been closed, so Impulse can be `$EDITOR` or git's editor.
+enum CLIOpen {
+  struct Target: Decodable {
+    let path: String
+    let line: UInt32?
+    let column: UInt32?
+    let isDir: Bool
+
+    enum CodingKeys: String, CodingKey {
+      case path, line, column
+      case isDir = "is_dir"
+    }
+  }
+
+  struct Request: Decodable {
+    let targets: [Target]
+    let newWindow: Bool
+    let wait: Bool
+
+    enum CodingKeys: String, CodingKey {
+      case targets, wait
+      case newWindow = "new_window"
+    }
+  }
+
+  private static var bundleIdentifier: String {
+    Bundle.main.bundleIdentifier ?? "dev.impulse.Impulse"
+  }
+
+  /// Posted by a second launch. `object` is a token identifying the launch
+  /// and `userInfo["request"]` the request JSON.
+  static var openNotification: Notification.Name {
+    Notification.Name("\(bundleIdentifier).cliOpen")
+  }
+
+  /// Posted back by the running instance when a `--wait` request's files
+  /// are all closed. `object` is the launch's token.
+  static var doneNotification: Notification.Name {
+    Notification.Name("\(bundleIdentifier).cliDone")
+  }
+
+  /// Parses the arguments after the program name, resolving relative paths
+  /// against `cwd`. Returns the request and its JSON for forwarding.
+  static func parse(_ args: [String], cwd: String) -> (request: Request, json: String)? {
+    guard let json = ImpulseCore.parseCLIOpenRequest(args: args, cwd: cwd),
+      let request = decode(json)
+    else { return nil }
+    return (request, json)
+  }
+
+  static func decode(_ json: String) -> Request? {
+    guard let data = json.data(using: .utf8) else { return nil }
+    return try? JSONDecoder().decode(Request.self, from: data)
+  }
+
+  /// Hands the request to another running instance. Returns false when
+  /// there is none. With `wait`, blocks until that instance reports the
+  /// files closed or quits.
+  static func forwardToRunningInstance(json: String, wait: Bool) -> Bool {
+    let ownPid = ProcessInfo.processInfo.processIdentifier
+    guard
+      let running = NSRunningApplication.runningApplications(
+        withBundleIdentifier: bundleIdentifier
+      ).first(where: { $0.processIdentifier != ownPid })
+    else { return false }
+
+    let token = UUID().uuidString
+    let center = DistributedNotificationCenter.default()
+    var done = false
+    let observer = center.addObserver(forName: doneNotification, object: token, queue: nil) { _ in
+      done = true
+    }
+    center.postNotificationName(
+      openNotification, object: token, userInfo: ["request": json], deliverImmediately: true)
+    running.activate()
+
+    while wait && !done && !running.isTerminated {
+      RunLoop.current.run(until: Date(timeIntervalSinceNow: 0.25))
+    }
+    center.removeObserver(observer)
+    return true
+  }
+}
@@ -22,21 +22,39 @@ struct CloseRiskCommand: Codable {
 struct CloseRiskInput: Codable {
   var action: CloseRiskAction
   var unsavedEditorCount: Int
+  /// Paths of the modified editors ("Untitled" for new files); the summary
+  /// lists them by name.
+  var unsavedFiles: [String] = []
   var runningTerminalProcessCount: Int
   var runningCommands: [CloseRiskCommand]
+  /// Programs whose running commands don't count
+  /// (`terminal_close_ignored_commands`).
+  var ignoredCommands: [String] = []
   var nowMs: UInt64
   var longCommandThresholdSeconds: UInt64
 
   enum CodingKeys: String, CodingKey {
     case action
     case unsavedEditorCount = "unsaved_editor_count"
+    case unsavedFiles = "unsaved_files"
     case runningTerminalProcessCount = "running_terminal_process_count"
     case runningCommands = "running_commands"
+    case ignoredCommands = "ignored_commands"
     case nowMs = "now_ms"
     case longCommandThresholdSeconds = "long_command_threshold_seconds"
   }
 }
 
+struct UnsavedFileSummary: Codable {
+  var path: String
+  var displayName: String
+
+  enum CodingKeys: String, CodingKey {
+    case path
+    case displayName = "display_name"
+  }
+}
+
 struct CloseRiskSummary: Codable {
   var hasRisk: Bool
   var title: String
@@ -44,6 +62,7 @@ struct CloseRiskSummary: Codable {
   var detailLines: [String]
   var destructiveActionTitle: String
   var cancelTitle: String
+  var unsavedFiles: [UnsavedFileSummary]
 
   enum CodingKeys: String, CodingKey {
     case hasRisk = "has_risk"
@@ -52,6 +71,7 @@ struct CloseRiskSummary: Codable {
     case detailLines = "detail_lines"
     case destructiveActionTitle = "destructive_action_title"
     case cancelTitle = "cancel_title"
+    case unsavedFiles = "unsaved_files"
   }
 }
 
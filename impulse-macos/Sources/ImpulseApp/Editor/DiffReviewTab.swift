@@ -69,6 +69,18 @@ final class DiffReviewTab: NSView, WKScriptMessageHandler, WKNavigationDelegate
     /// The git repository root being reviewed.
     let repoRoot: String
 
+    /// Two files on disk to diff instead of the repository's changes, e.g.
+    /// a local history version against the file. Read-only, no commit bar.
+    let comparison: (old: String, new: String)?
+
+    /// For "Compare with Saved": the open editor's buffer, diffed against the
+    /// saved `comparison.new` in place of the `comparison.old` file. Returns
+    /// nil once the editor is gone.
+    let buffer: (() -> String?)?
+
+    /// Tab title, when the default "Review: <repo>" doesn't fit.
+    var title: String?
+
     private(set) var webView: WKWebView?
 
     /// Inline error view shown when the review editor can't be loaded (e.g.
@@ -105,12 +117,23 @@ final class DiffReviewTab: NSView, WKScriptMessageHandler, WKNavigationDelegate
 
     // MARK: Init
 
-    init(repoRoot: String, theme: Theme) {
+    init(
+        repoRoot: String, theme: Theme, comparison: (old: String, new: String)? = nil,
+        buffer: (() -> String?)? = nil
+    ) {
         self.repoRoot = repoRoot
+        self.comparison = comparison
+        self.buffer = buffer
         self.theme = theme
         self.chrome = ReviewChromeModel(theme: theme)
         super.init(frame: NSRect(x: 0, y: 0, width: 800, height: 600))
-        self.chrome.repoName = (repoRoot as NSString).lastPathComponent
+        if let comparison {
+            let name = { (path: String) in (path as NSString).lastPathComponent }
+            let old = buffer == nil ? name(comparison.old) : "\(name(comparison.old)) (saved)"
+            self.chrome.repoName = "\(old) \u{2194} \(name(comparison.new))"
+        } else {
+            self.chrome.repoName = (repoRoot as NSString).lastPathComponent
+        }
         wantsLayer = true
         layer?.backgroundColor = theme.bgColor.cgColor
         setupViews()
@@ -154,7 +177,6 @@ final class DiffReviewTab: NSView, WKScriptMessageHandler, WKNavigationDelegate
 
         addSubview(headerView)
         addSubview(wv)
-        addSubview(commitView)
 
         NSLayoutConstraint.activate([
             headerView.topAnchor.constraint(equalTo: topAnchor),
@@ -164,6 +186,15 @@ final class DiffReviewTab: NSView, WKScriptMessageHandler, WKNavigationDelegate
             wv.topAnchor.constraint(equalTo: headerView.bottomAnchor),
             wv.leadingAnchor.constraint(equalTo: leadingAnchor),
             wv.trailingAnchor.constraint(equalTo: trailingAnchor),
+        ])
+
+        // A file comparison has nothing to commit.
+        guard comparison == nil else {
+            wv.bottomAnchor.constraint(equalTo: bottomAnchor).isActive = true
+            return
+        }
+        addSubview(commitView)
+        NSLayoutConstraint.activate([
 
             commitView.topAnchor.constraint(equalTo: wv.bottomAnchor),
             commitView.leadingAnchor.constraint(equalTo: leadingAnchor),
@@ -357,6 +388,10 @@ final class DiffReviewTab: NSView, WKScriptMessageHandler, WKNavigationDelegate
     func reloadAndRender() {
         loadGeneration += 1
         let generation = loadGeneration
+        if let comparison {
+            reloadComparison(generation: generation, old: comparison.old, new: comparison.new)
+            return
+        }
         let repo = repoRoot
         DispatchQueue.global(qos: .userInitiated).async { [weak self] in
             let changeSet = ImpulseCore.listChangedFiles(repoPath: repo)
@@ -376,6 +411,43 @@ final class DiffReviewTab: NSView, WKScriptMessageHandler, WKNavigationDelegate
         }
     }
 
+    /// Diff the two compared files off the main thread and push a Render
+    /// with the single entry plus its hunks.
+    private func reloadComparison(generation: Int, old: String, new: String) {
+        let compare = comparisonDiff()
+        DispatchQueue.global(qos: .userInitiated).async { [weak self] in
+            let hunks =
+                compare()
+                ?? ImpulseCore.FileHunks(
+                    language: "plaintext", isBinary: false, tooLarge: false,
+                    truncated: false, added: 0, removed: 0, hunks: [])
+            DispatchQueue.main.async { [weak self] in
+                guard let self, generation == self.loadGeneration else { return }
+                self.chrome.fileCount = 1
+                self.chrome.totalAdded = hunks.added
+                self.chrome.totalRemoved = hunks.removed
+                self.sendCommand(
+                    .render(files: [
+                        ReviewFileEntry(
+                            path: new, status: "M", oldPath: old,
+                            added: hunks.added, removed: hunks.removed, isBinary: hunks.isBinary)
+                    ]))
+                self.sendCommand(.setHunks(path: new, hunks: hunks))
+            }
+        }
+    }
+
+    /// The diff of the compared pair, to run off the main thread. The
+    /// buffer of a saved comparison is captured now, on the main thread.
+    private func comparisonDiff() -> () -> ImpulseCore.FileHunks? {
+        guard let comparison else { return { nil } }
+        guard let buffer else {
+            return { ImpulseCore.diffFiles(oldPath: comparison.old, newPath: comparison.new) }
+        }
+        guard let text = buffer() else { return { nil } }
+        return { ImpulseCore.diffSaved(path: comparison.new, buffer: text) }
+    }
+
     private func applyChangeSet(_ changeSet: ImpulseCore.ChangeSet) {
         let entries = changeSet.files.map { f in
             ReviewFileEntry(
@@ -394,9 +466,13 @@ final class DiffReviewTab: NSView, WKScriptMessageHandler, WKNavigationDelegate
     /// push a SetHunks command.
     private func loadDiff(forPath path: String) {
         let repo = repoRoot
+        let isComparison = comparison != nil
+        let compare = comparisonDiff()
         let generation = loadGeneration
         DispatchQueue.global(qos: .userInitiated).async { [weak self] in
-            let hunks = ImpulseCore.fileHunks(repoPath: repo, filePath: path)
+            let hunks =
+                isComparison
+                ? compare() : ImpulseCore.fileHunks(repoPath: repo, filePath: path)
             DispatchQueue.main.async { [weak self] in
                 guard let self, generation == self.loadGeneration else { return }
                 guard let hunks else {
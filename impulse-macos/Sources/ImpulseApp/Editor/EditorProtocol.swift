@@ -1,5 +1,16 @@
 import Foundation
 
+// MARK: - Handshake
+
+/// Editor protocol version this host speaks. Must match
+/// `impulse_editor::protocol::PROTOCOL_VERSION`.
+let editorProtocolVersion: UInt32 = 9
+
+/// Optional features (`HostFeature` in impulse-editor) this host handles.
+/// The editor hides Peek, Stage Change and Compare Changes when they are
+/// missing.
+let editorHostFeatures: [String] = []
+
 // MARK: - Editor Commands (Swift -> Monaco)
 
 /// Commands sent from Swift to the Monaco WebView via evaluateJavaScript.
@@ -7,6 +18,7 @@ import Foundation
 /// JSON encoding uses a tagged union format with a "type" field and snake_case
 /// keys, matching the Rust `EditorCommand` serde output from impulse-editor.
 enum EditorCommand: Encodable {
+    case handshake(protocolVersion: UInt32, features: [String])
     case openFile(filePath: String, content: String, language: String)
     case setTheme(theme: MonacoThemeDefinition)
     case updateSettings(options: EditorOptions)
@@ -23,10 +35,28 @@ enum EditorCommand: Encodable {
     case resolveCodeActions(requestId: UInt64, actions: [MonacoCodeAction])
     case resolveRename(requestId: UInt64, edits: [MonacoWorkspaceTextEdit])
     case resolvePrepareRename(requestId: UInt64, range: MonacoRange?, placeholder: String?)
+    /// Replace the user `editor.css`; an empty string removes it.
+    case setCustomCss(css: String)
+    /// Replace the user's rebindings of Monaco actions.
+    case setKeybindings(bindings: [EditorKeybinding])
+    /// Set the file's indentation, rewriting existing lines when `convert`.
+    case setIndentation(tabSize: UInt32, insertSpaces: Bool, convert: Bool)
+    case applyEdits(edits: [MonacoTextEdit])
+    /// Replace the whole buffer as one undoable edit, keeping undo history.
+    case replaceContent(content: String)
+    /// Highlight the buffer as a different Monaco language.
+    case setLanguage(language: String)
+    /// Ask for the current buffer, answered with `EditorEvent.response`.
+    case getContent(requestId: UInt64)
+    /// Ask for the selected text, answered with `EditorEvent.response`.
+    case getSelection(requestId: UInt64)
+    /// Run `command` and answer with `EditorEvent.response` once applied.
+    indirect case request(requestId: UInt64, command: EditorCommand)
 
     // MARK: Tagged Enum Encoding
 
     private enum TypeTag: String, Encodable {
+        case handshake = "Handshake"
         case openFile = "OpenFile"
         case setTheme = "SetTheme"
         case updateSettings = "UpdateSettings"
@@ -43,10 +73,22 @@ enum EditorCommand: Encodable {
         case resolveCodeActions = "ResolveCodeActions"
         case resolveRename = "ResolveRename"
         case resolvePrepareRename = "ResolvePrepareRename"
+        case setCustomCss = "SetCustomCss"
+        case setKeybindings = "SetKeybindings"
+        case setIndentation = "SetIndentation"
+        case applyEdits = "ApplyEdits"
+        case replaceContent = "ReplaceContent"
+        case setLanguage = "SetLanguage"
+        case getContent = "GetContent"
+        case getSelection = "GetSelection"
+        case request = "Request"
     }
 
     private enum CodingKeys: String, CodingKey {
         case type
+        case protocolVersion = "protocol_version"
+        case features
+        case command
         case filePath = "file_path"
         case content
         case language
@@ -67,12 +109,22 @@ enum EditorCommand: Encodable {
         case actions
         case range
         case placeholder
+        case css
+        case bindings
+        case tabSize = "tab_size"
+        case insertSpaces = "insert_spaces"
+        case convert
     }
 
     func encode(to encoder: Encoder) throws {
         var container = encoder.container(keyedBy: CodingKeys.self)
 
         switch self {
+        case let .handshake(protocolVersion, features):
+            try container.encode(TypeTag.handshake, forKey: .type)
+            try container.encode(protocolVersion, forKey: .protocolVersion)
+            try container.encode(features, forKey: .features)
+
         case let .openFile(filePath, content, language):
             try container.encode(TypeTag.openFile, forKey: .type)
             try container.encode(filePath, forKey: .filePath)
@@ -152,6 +204,45 @@ enum EditorCommand: Encodable {
             try container.encode(requestId, forKey: .requestId)
             try container.encodeIfPresent(range, forKey: .range)
             try container.encodeIfPresent(placeholder, forKey: .placeholder)
+
+        case let .setCustomCss(css):
+            try container.encode(TypeTag.setCustomCss, forKey: .type)
+            try container.encode(css, forKey: .css)
+
+        case let .setKeybindings(bindings):
+            try container.encode(TypeTag.setKeybindings, forKey: .type)
+            try container.encode(bindings, forKey: .bindings)
+
+        case let .setIndentation(tabSize, insertSpaces, convert):
+            try container.encode(TypeTag.setIndentation, forKey: .type)
+            try container.encode(tabSize, forKey: .tabSize)
+            try container.encode(insertSpaces, forKey: .insertSpaces)
+            try container.encode(convert, forKey: .convert)
+
+        case let .applyEdits(edits):
+            try container.encode(TypeTag.applyEdits, forKey: .type)
+            try container.encode(edits, forKey: .edits)
+
+        case let .replaceContent(content):
+            try container.encode(TypeTag.replaceContent, forKey: .type)
+            try container.encode(content, forKey: .content)
+
+        case let .setLanguage(language):
+            try container.encode(TypeTag.setLanguage, forKey: .type)
+            try container.encode(language, forKey: .language)
+
+        case let .getContent(requestId):
+            try container.encode(TypeTag.getContent, forKey: .type)
+            try container.encode(requestId, forKey: .requestId)
+
+        case let .getSelection(requestId):
+            try container.encode(TypeTag.getSelection, forKey: .type)
+            try container.encode(requestId, forKey: .requestId)
+
+        case let .request(requestId, command):
+            try container.encode(TypeTag.request, forKey: .type)
+            try container.encode(requestId, forKey: .requestId)
+            try container.encode(command, forKey: .command)
         }
     }
 }
@@ -163,7 +254,8 @@ enum EditorCommand: Encodable {
 /// JSON decoding expects the same tagged union format with a "type" field
 /// and snake_case keys produced by the Monaco JavaScript layer.
 enum EditorEvent: Decodable {
-    case ready
+    /// `protocolVersion` is 0 for editor bundles that predate the handshake.
+    case ready(protocolVersion: UInt32)
     case fileOpened
     case contentChanged(content: String?, changes: [MonacoContentChange], version: UInt32)
     case cursorMoved(line: UInt32, column: UInt32)
@@ -179,6 +271,8 @@ enum EditorEvent: Decodable {
     case codeActionRequested(requestId: UInt64, startLine: UInt32, startColumn: UInt32, endLine: UInt32, endColumn: UInt32, diagnostics: [MonacoDiagnostic])
     case renameRequested(requestId: UInt64, line: UInt32, character: UInt32, newName: String)
     case prepareRenameRequested(requestId: UInt64, line: UInt32, character: UInt32)
+    /// Answer to `EditorCommand.getContent` / `.getSelection` / `.request`.
+    case response(requestId: UInt64, content: String?, error: String?)
 
     private enum TypeTag: String, Decodable {
         case ready = "Ready"
@@ -197,10 +291,12 @@ enum EditorEvent: Decodable {
         case codeActionRequested = "CodeActionRequested"
         case renameRequested = "RenameRequested"
         case prepareRenameRequested = "PrepareRenameRequested"
+        case response = "Response"
     }
 
     private enum CodingKeys: String, CodingKey {
         case type
+        case protocolVersion = "protocol_version"
         case content
         case changes
         case version
@@ -218,6 +314,7 @@ enum EditorEvent: Decodable {
         case endColumn = "end_column"
         case diagnostics
         case newName = "new_name"
+        case error
     }
 
     init(from decoder: Decoder) throws {
@@ -226,7 +323,8 @@ enum EditorEvent: Decodable {
 
         switch tag {
         case .ready:
-            self = .ready
+            let protocolVersion = try container.decodeIfPresent(UInt32.self, forKey: .protocolVersion) ?? 0
+            self = .ready(protocolVersion: protocolVersion)
 
         case .fileOpened:
             self = .fileOpened
@@ -312,6 +410,12 @@ enum EditorEvent: Decodable {
             let line = try container.decode(UInt32.self, forKey: .line)
             let character = try container.decode(UInt32.self, forKey: .character)
             self = .prepareRenameRequested(requestId: requestId, line: line, character: character)
+
+        case .response:
+            let requestId = try container.decode(UInt64.self, forKey: .requestId)
+            let content = try container.decodeIfPresent(String.self, forKey: .content)
+            let error = try container.decodeIfPresent(String.self, forKey: .error)
+            self = .response(requestId: requestId, content: content, error: error)
         }
     }
 }
@@ -476,6 +580,31 @@ struct MonacoTextEdit: Codable {
     var text: String
 }
 
+/// A Monaco action bound to a shortcut in settings notation ("Cmd+/").
+struct EditorKeybinding: Codable, Equatable {
+    var command: String
+    var key: String
+}
+
+/// A Monaco action that can be rebound from settings
+/// (`impulse_editor::keybindings::EDITOR_ACTIONS`).
+struct EditorAction: Decodable {
+    var id: String
+    var description: String
+    var monacoAction: String
+    var defaultShortcut: String
+
+    enum CodingKeys: String, CodingKey {
+        case id
+        case description
+        case monacoAction = "monaco_action"
+        case defaultShortcut = "default_shortcut"
+    }
+
+    /// All rebindable actions, fetched from impulse-editor once.
+    static let all: [EditorAction] = ImpulseCore.editorActions()
+}
+
 struct DiffDecoration: Codable {
     /// 1-based line number.
     var line: UInt32
@@ -23,6 +23,19 @@ private class WeakScriptMessageHandler: NSObject, WKScriptMessageHandler {
     }
 }
 
+// MARK: - EditorIndentation
+
+/// A file's indentation: tabs or spaces, and the width of one level.
+struct EditorIndentation: Equatable {
+    var useSpaces: Bool
+    var width: Int
+
+    /// Status bar label, e.g. "Spaces: 4" or "Tab Size: 8".
+    var label: String {
+        useSpaces ? "Spaces: \(width)" : "Tab Size: \(width)"
+    }
+}
+
 // MARK: - EditorTab
 
 /// Wraps a WKWebView hosting the Monaco code editor.
@@ -49,6 +62,14 @@ class EditorTab: NSView, WKScriptMessageHandler, WKNavigationDelegate {
     /// Whether the content has been modified since the last save.
     private(set) var isModified: Bool = false
 
+    /// This file's indentation: resolved on open, changed from the status bar.
+    private(set) var indentation = EditorIndentation(useSpaces: true, width: 4)
+
+    /// Points added to the `fontSize` setting by zooming this tab.
+    private(set) var zoom = 0
+    /// The `fontSize` setting as of the last `applySettings`.
+    private(set) var baseFontSize = 14
+
     /// The sidebar root directory that was active when this editor tab was opened.
     /// Restored when the user switches back to this tab.
     var projectDirectory: String?
@@ -57,19 +78,40 @@ class EditorTab: NSView, WKScriptMessageHandler, WKNavigationDelegate {
     /// default directory in the save-as dialog.
     var untitledCwd: String?
 
+    /// Tab title for an untitled editor that isn't a new file, like a
+    /// terminal's scrollback; nil shows "Untitled".
+    var untitledTitle: String?
+
     /// The WKWebView hosting Monaco. Nil after `cleanup()` has run.
     private(set) var webView: WKWebView?
 
     /// Whether the Monaco editor has fired its `Ready` event.
     private var isEditorReady: Bool = false
 
-    /// When set, read-only mode is applied as soon as Monaco reports ready,
-    /// before any content is rendered, to avoid a flash of editable content.
-    private var pendingReadOnly: Bool = false
+    /// Why the editor is read-only (the lock tooltip), or nil when editable.
+    /// Applied as soon as Monaco reports ready, before any content is
+    /// rendered, to avoid a flash of editable content.
+    private(set) var readOnlyReason: String?
+
+    /// Whether this is the preview tab: opened with a single click in the
+    /// file tree and replaced by the next one until it's edited or kept.
+    var isPreview: Bool = false
 
     /// Commands queued before the editor was ready.
     private var pendingCommands: [EditorCommand] = []
 
+    /// Commands waiting for the next run-loop turn, sent to Monaco in one
+    /// `impulseReceiveCommands` call with decoration sets coalesced.
+    private let commandBatch = CoreEditorCommandBatch()
+    private var commandFlushScheduled = false
+
+    /// Correlated requests (`getContent`, `request`) waiting for the editor's
+    /// `response`, failed after `requestTimeout` so a hung WebView surfaces
+    /// as an error.
+    private var pendingRequests: [UInt64: (Result<String?, ImpulseError>) -> Void] = [:]
+    private var nextRequestId: UInt64 = 1
+    private static let requestTimeout: TimeInterval = 5
+
     /// JSON encoder configured for the protocol wire format.
     private let jsonEncoder: JSONEncoder = {
         let encoder = JSONEncoder()
@@ -127,6 +169,7 @@ class EditorTab: NSView, WKScriptMessageHandler, WKNavigationDelegate {
                 warmed.leadingAnchor.constraint(equalTo: leadingAnchor),
                 warmed.trailingAnchor.constraint(equalTo: trailingAnchor),
             ])
+            warmed.onFilesDropped = { [weak self] paths in self?.filesDropped(paths) }
             self.webView = warmed
             self.isEditorReady = true
             return
@@ -144,7 +187,8 @@ class EditorTab: NSView, WKScriptMessageHandler, WKNavigationDelegate {
         pagePrefs.allowsContentJavaScript = true
         config.defaultWebpagePreferences = pagePrefs
 
-        let wv = WKWebView(frame: bounds, configuration: config)
+        let wv = EditorWebView(frame: bounds, configuration: config)
+        wv.onFilesDropped = { [weak self] paths in self?.filesDropped(paths) }
         wv.navigationDelegate = self
         wv.translatesAutoresizingMaskIntoConstraints = false
         wv.allowsMagnification = false
@@ -164,6 +208,15 @@ class EditorTab: NSView, WKScriptMessageHandler, WKNavigationDelegate {
         self.webView = wv
     }
 
+    /// Files dropped on the editor open in tabs of their own.
+    private func filesDropped(_ paths: [String]) {
+        NotificationCenter.default.post(
+            name: .editorFilesDropped,
+            object: self,
+            userInfo: ["paths": paths]
+        )
+    }
+
     // MARK: Loading
 
     /// Extract Monaco assets via the FFI bridge and load the editor HTML.
@@ -209,9 +262,16 @@ class EditorTab: NSView, WKScriptMessageHandler, WKNavigationDelegate {
 
     private func handleEvent(_ event: EditorEvent) {
         switch event {
-        case .ready:
+        case let .ready(protocolVersion):
             isEditorReady = true
 
+            if protocolVersion != editorProtocolVersion {
+                os_log(.info, log: Self.log,
+                       "Editor protocol version %u does not match host version %u",
+                       protocolVersion, editorProtocolVersion)
+            }
+            sendCommand(.handshake(protocolVersion: editorProtocolVersion, features: editorHostFeatures))
+
             // Flush any commands that were queued before the editor was ready.
             // This includes any openFile command from openFile() called before ready.
             let queued = pendingCommands
@@ -232,7 +292,7 @@ class EditorTab: NSView, WKScriptMessageHandler, WKNavigationDelegate {
                 sendCommand(.openFile(filePath: path, content: content, language: language))
             }
 
-            if pendingReadOnly {
+            if readOnlyReason != nil {
                 sendCommand(.setReadOnly(readOnly: true))
             }
 
@@ -401,6 +461,34 @@ class EditorTab: NSView, WKScriptMessageHandler, WKNavigationDelegate {
                     "character": character,
                 ]
             )
+
+        case let .response(requestId, content, error):
+            guard let completion = pendingRequests.removeValue(forKey: requestId) else {
+                os_log(.debug, log: Self.log, "Late or unknown editor response %llu", requestId)
+                return
+            }
+            if let error {
+                completion(.failure(ImpulseError(message: "The editor failed: \(error)")))
+            } else {
+                completion(.success(content))
+            }
+        }
+    }
+
+    /// Send the command `makeCommand` builds for a new request id and pass
+    /// the editor's response to `completion`, or a timeout error when none
+    /// arrives within `requestTimeout`.
+    private func sendRequest(
+        _ makeCommand: (UInt64) -> EditorCommand,
+        completion: @escaping (Result<String?, ImpulseError>) -> Void
+    ) {
+        let requestId = nextRequestId
+        nextRequestId += 1
+        pendingRequests[requestId] = completion
+        sendCommand(makeCommand(requestId))
+        DispatchQueue.main.asyncAfter(deadline: .now() + Self.requestTimeout) { [weak self] in
+            guard let completion = self?.pendingRequests.removeValue(forKey: requestId) else { return }
+            completion(.failure(ImpulseError(message: "The editor did not respond")))
         }
     }
 
@@ -477,10 +565,30 @@ class EditorTab: NSView, WKScriptMessageHandler, WKNavigationDelegate {
             return
         }
 
+        guard let commandBatch, commandBatch.push(jsonString) else {
+            evaluateCommandScript("window.impulseReceiveCommand(msg);", json: jsonString)
+            return
+        }
+        if !commandFlushScheduled {
+            commandFlushScheduled = true
+            DispatchQueue.main.async { [weak self] in
+                self?.flushCommands()
+            }
+        }
+    }
+
+    /// Send every batched command in one script evaluation.
+    private func flushCommands() {
+        commandFlushScheduled = false
+        guard let json = commandBatch?.take() else { return }
+        evaluateCommandScript("window.impulseReceiveCommands(msg);", json: json)
+    }
+
+    private func evaluateCommandScript(_ script: String, json: String) {
         guard let webView else { return }
         webView.callAsyncJavaScript(
-            "window.impulseReceiveCommand(msg);",
-            arguments: ["msg": jsonString],
+            script,
+            arguments: ["msg": json],
             in: nil,
             in: .page,
             completionHandler: { result in
@@ -505,19 +613,31 @@ class EditorTab: NSView, WKScriptMessageHandler, WKNavigationDelegate {
         startFileWatching()
     }
 
+    /// Switch the file to another language: re-highlight it and use the
+    /// language servers for it. The caller reopens the file with the servers.
+    func setLanguage(_ monacoLanguage: String) {
+        guard let path = filePath else { return }
+        language = monacoLanguage
+        lspLanguage = Self.lspLanguageForPath(path, monacoLanguage: monacoLanguage)
+        sendCommand(.setLanguage(language: monacoLanguage))
+    }
+
     /// Open a blank untitled editor (no file on disk).
-    func openBlank() {
+    func openBlank(content: String = "") {
         self.filePath = nil
-        self.content = ""
+        self.content = content
         self.language = "plaintext"
         self.lspLanguage = "plaintext"
         self.isModified = false
-        sendCommand(.openFile(filePath: "", content: "", language: "plaintext"))
+        sendCommand(.openFile(filePath: "", content: content, language: "plaintext"))
     }
 
     /// Returns the LSP language ID for a file path, which may differ from the Monaco language.
     /// For example, `.tsx` files use "typescript" in Monaco but "typescriptreact" for LSP.
     private static func lspLanguageForPath(_ path: String, monacoLanguage: String) -> String {
+        if let language = ImpulseCore.languageOverride(path: path) {
+            return language.id
+        }
         let ext = (path as NSString).pathExtension.lowercased()
         switch ext {
         case "tsx": return "typescriptreact"
@@ -536,55 +656,118 @@ class EditorTab: NSView, WKScriptMessageHandler, WKNavigationDelegate {
 
         let contentToSave = content
         do {
-            try contentToSave.write(toFile: path, atomically: true, encoding: .utf8)
+            try ImpulseCore.safeWrite(contentToSave, to: path)
+            ImpulseCore.refreshGitFileCaches(filePath: path)
             isModified = false
+            RemoteFiles.uploadAfterSave(path: path)
             return true
         } catch {
             os_log(.error, log: Self.log, "Failed to save file %{public}@: %{public}@", path, error.localizedDescription)
+            handleSaveFailure(path: path, content: contentToSave, error: error)
             return false
         }
     }
 
+    /// Report a failed save. When the file isn't writable, offer to save it
+    /// as the administrator instead.
+    private func handleSaveFailure(path: String, content: String, error: Error) {
+        let nsError = error as NSError
+        let denied =
+            (nsError.domain == NSCocoaErrorDomain && nsError.code == NSFileWriteNoPermissionError)
+            || (nsError.underlyingErrors.first as NSError?)?.code == Int(EACCES)
+        guard denied, ImpulseCore.canWriteAsAdministrator else {
+            Self.notifySaveFailed(path: path, error: error)
+            return
+        }
+
+        let alert = NSAlert()
+        alert.messageText = "Permission Denied"
+        alert.informativeText =
+            "You don't have permission to write \u{201c}\((path as NSString).lastPathComponent)\u{201d}. "
+            + "Save it as an administrator?"
+        alert.addButton(withTitle: "Retry as Administrator")
+        alert.addButton(withTitle: "Cancel")
+        guard alert.runModal() == .alertFirstButtonReturn else { return }
+
+        DispatchQueue.global(qos: .userInitiated).async { [weak self] in
+            let result = ImpulseCore.writeAsAdministrator(content, to: path)
+            DispatchQueue.main.async {
+                switch result {
+                case .success:
+                    if self?.content == content { self?.isModified = false }
+                case .failure(let error):
+                    ImpulseCore.notify(
+                        .error, title: "Couldn't save \((path as NSString).lastPathComponent)",
+                        message: error.message, source: "save")
+                }
+            }
+        }
+    }
+
+    private static func notifySaveFailed(path: String, error: Error) {
+        ImpulseCore.notify(
+            .error, title: "Couldn't save \((path as NSString).lastPathComponent)",
+            message: error.localizedDescription, source: "save")
+    }
+
     /// Fetch the latest content from Monaco and then call `completion`.
     /// This is necessary because content changes are debounced in JS, so
     /// the Swift `content` property may be stale when a save is triggered
     /// via the menu (Cmd+S) rather than through Monaco's own save handler.
-    func fetchContentAndSave(completion: @escaping (Bool) -> Void) {
+    /// An editor that doesn't answer in time fails the save with an error
+    /// rather than writing possibly stale content. `whitespace` turns on
+    /// trailing-whitespace and final-newline fixes, which `.editorconfig`
+    /// can override; they are written and applied to the buffer.
+    func fetchContentAndSave(
+        whitespace: (trim: Bool, finalNewline: Bool) = (false, false),
+        completion: @escaping (Bool) -> Void
+    ) {
         guard let path = filePath else {
             completion(false)
             return
         }
 
-        guard isEditorReady, let webView else {
+        guard isEditorReady, webView != nil else {
             // Editor not ready, save whatever we have
             completion(saveFile())
             return
         }
 
-        webView.evaluateJavaScript("editor.getValue()") { [weak self] result, error in
+        sendRequest({ .getContent(requestId: $0) }) { [weak self] result in
             guard let self else { completion(false); return }
-            if let latest = result as? String {
-                self.content = latest
-            } else {
-                // Monaco failed to return the buffer — fall back to the
-                // content accumulated from ContentChanged events, but make
-                // the failure visible in the log so a stale save is traceable.
-                os_log(
-                    .error, log: Self.log,
-                    "editor.getValue() failed for %{public}@ (error: %{public}@); saving last-known content",
-                    path, error?.localizedDescription ?? "non-string result")
+            switch result {
+            case let .success(latest):
+                if let latest { self.content = latest }
+            case let .failure(error):
+                os_log(.error, log: Self.log, "Fetching content for %{public}@ failed: %{public}@",
+                       path, error.message)
+                Self.notifySaveFailed(path: path, error: error)
+                completion(false)
+                return
+            }
+            var contentToSave = self.content
+            if let fix = ImpulseCore.saveWhitespaceFix(
+                path: path, content: contentToSave,
+                trim: whitespace.trim, finalNewline: whitespace.finalNewline)
+            {
+                // `content` catches up from the resulting contentChanged.
+                contentToSave = fix.content
+                self.suppressNextModify = true
+                self.sendCommand(.applyEdits(edits: fix.edits))
             }
-            let contentToSave = self.content
             DispatchQueue.global(qos: .userInitiated).async { [weak self] in
                 do {
-                    try contentToSave.write(toFile: path, atomically: true, encoding: .utf8)
+                    try ImpulseCore.safeWrite(contentToSave, to: path)
+                    ImpulseCore.refreshGitFileCaches(filePath: path)
                     DispatchQueue.main.async {
                         self?.isModified = false
+                        RemoteFiles.uploadAfterSave(path: path)
                         completion(true)
                     }
                 } catch {
                     os_log(.error, log: Self.log, "Failed to save file %{public}@: %{public}@", path, error.localizedDescription)
                     DispatchQueue.main.async {
+                        self?.handleSaveFailure(path: path, content: contentToSave, error: error)
                         completion(false)
                     }
                 }
@@ -597,11 +780,49 @@ class EditorTab: NSView, WKScriptMessageHandler, WKNavigationDelegate {
         sendCommand(.setTheme(theme: theme))
     }
 
+    /// Replace the user `editor.css` injected into the page.
+    func applyCustomCss(_ css: String) {
+        sendCommand(.setCustomCss(css: css))
+    }
+
+    /// Replace the user's rebindings of Monaco actions.
+    func applyKeybindings(_ bindings: [EditorKeybinding]) {
+        sendCommand(.setKeybindings(bindings: bindings))
+    }
+
     /// Apply editor settings (font, tab size, etc.).
+    /// Indentation stays per-file, so the options' tab settings are replaced
+    /// with this file's.
     func applySettings(_ options: EditorOptions) {
+        var options = options
+        options.tabSize = UInt32(indentation.width)
+        options.insertSpaces = indentation.useSpaces
+        if let fontSize = options.fontSize {
+            baseFontSize = Int(fontSize)
+            options.fontSize = UInt32(ImpulseCore.zoomedFontSize(base: baseFontSize, zoom: zoom))
+        }
         sendCommand(.updateSettings(options: options))
     }
 
+    /// Zoom this tab by `zoom` points over the `fontSize` setting.
+    func setZoom(_ zoom: Int) {
+        self.zoom = zoom
+        sendCommand(
+            .updateSettings(
+                options: EditorOptions(
+                    fontSize: UInt32(ImpulseCore.zoomedFontSize(base: baseFontSize, zoom: zoom)))))
+    }
+
+    /// Switch this file to `indentation`; with `convert`, existing lines are
+    /// re-indented to match.
+    func setIndentation(_ indentation: EditorIndentation, convert: Bool) {
+        self.indentation = indentation
+        sendCommand(
+            .setIndentation(
+                tabSize: UInt32(indentation.width), insertSpaces: indentation.useSpaces,
+                convert: convert))
+    }
+
     /// Resolve a pending definition request. Pass nil uri for "not found".
     func resolveDefinition(requestId: UInt64, uri: String?, line: UInt32?, column: UInt32?) {
         sendCommand(.resolveDefinition(requestId: requestId, uri: uri, line: line, column: column))
@@ -609,13 +830,57 @@ class EditorTab: NSView, WKScriptMessageHandler, WKNavigationDelegate {
 
     /// Navigate the editor cursor to the given line and column.
     func goToPosition(line: UInt32, column: UInt32) {
-        sendCommand(.goToPosition(line: line, column: column))
+        // Queued until the editor is ready, which may take longer than the
+        // request timeout.
+        guard isEditorReady else {
+            sendCommand(.goToPosition(line: line, column: column))
+            return
+        }
+        sendRequest({ .request(requestId: $0, command: .goToPosition(line: line, column: column)) }) {
+            [weak self] result in
+            if case let .failure(error) = result {
+                os_log(.error, log: Self.log, "Go to %u:%u in %{public}@ failed: %{public}@",
+                       line + 1, column + 1, self?.filePath ?? "untitled", error.message)
+            }
+        }
+    }
+
+    /// Set the editor to read-only (with the reason shown on the tab's lock)
+    /// or, with nil, back to read-write.
+    func setReadOnly(reason: String?) {
+        readOnlyReason = reason
+        sendCommand(.setReadOnly(readOnly: reason != nil))
     }
 
-    /// Set the editor to read-only or read-write mode.
-    func setReadOnly(_ readOnly: Bool) {
-        pendingReadOnly = readOnly
-        sendCommand(.setReadOnly(readOnly: readOnly))
+    /// Key for hot-exit backups: the file path, or a per-tab `untitled:/` key
+    /// for a buffer that was never saved.
+    var backupKey: String {
+        filePath ?? "untitled:/\(UInt(bitPattern: ObjectIdentifier(self).hashValue))"
+    }
+
+    /// Replace the buffer with unsaved content recovered after a crash, as
+    /// one undoable edit that leaves the file modified.
+    func restoreContent(_ text: String) {
+        sendCommand(.replaceContent(content: text))
+    }
+
+    /// Replace the buffer with the file on disk as one undoable edit, so Undo
+    /// brings the discarded changes back, and mark the editor unmodified.
+    func revertToSaved() throws {
+        guard let path = filePath else { return }
+        let saved = try String(contentsOfFile: path, encoding: .utf8)
+        // An identical buffer produces no contentChanged, which would leave
+        // the suppression armed for the next edit.
+        if saved != content {
+            suppressNextModify = true
+            sendCommand(.replaceContent(content: saved))
+        }
+        isModified = false
+    }
+
+    /// Lock or unlock the editor. Unlocking clears whatever made it read-only.
+    func toggleReadOnly() {
+        setReadOnly(reason: readOnlyReason == nil ? "Read-only" : nil)
     }
 
     /// Apply git diff decorations in the gutter.
@@ -944,3 +1209,50 @@ private class PreviewNavigationDelegate: NSObject, WKNavigationDelegate {
         }
     }
 }
+
+// MARK: - Editor WebView
+
+/// The WebView hosting Monaco. Files dragged in from Finder (or any app
+/// that drags file URLs) go to `onFilesDropped` instead of the page, which
+/// would otherwise navigate to them or insert their URLs.
+final class EditorWebView: WKWebView {
+
+    /// Called with the paths of the files dropped on the editor.
+    var onFilesDropped: (([String]) -> Void)?
+
+    /// The regular files being dragged, if any; folders are left out.
+    private func draggedFiles(_ sender: NSDraggingInfo) -> [String] {
+        guard onFilesDropped != nil,
+            let urls = sender.draggingPasteboard.readObjects(
+                forClasses: [NSURL.self],
+                options: [.urlReadingFileURLsOnly: true]) as? [URL]
+        else { return [] }
+        return urls.map(\.path).filter { path in
+            var isDirectory: ObjCBool = false
+            return FileManager.default.fileExists(atPath: path, isDirectory: &isDirectory)
+                && !isDirectory.boolValue
+        }
+    }
+
+    override func draggingEntered(_ sender: NSDraggingInfo) -> NSDragOperation {
+        draggedFiles(sender).isEmpty ? super.draggingEntered(sender) : .copy
+    }
+
+    override func draggingUpdated(_ sender: NSDraggingInfo) -> NSDragOperation {
+        draggedFiles(sender).isEmpty ? super.draggingUpdated(sender) : .copy
+    }
+
+    override func performDragOperation(_ sender: NSDraggingInfo) -> Bool {
+        let files = draggedFiles(sender)
+        guard !files.isEmpty, let onFilesDropped else {
+            return super.performDragOperation(sender)
+        }
+        onFilesDropped(files)
+        return true
+    }
+
+    override func concludeDragOperation(_ sender: NSDraggingInfo?) {
+        if let sender, !draggedFiles(sender).isEmpty { return }
+        super.concludeDragOperation(sender)
+    }
+}
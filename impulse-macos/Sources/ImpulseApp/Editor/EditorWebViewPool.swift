@@ -18,7 +18,7 @@ final class EditorWebViewPool: NSObject, WKScriptMessageHandler, WKNavigationDel
     private static let log = OSLog(subsystem: "dev.impulse.Impulse", category: "EditorWebViewPool")
 
     /// The pre-warmed WebView, ready to be claimed.
-    private var warmWebView: WKWebView?
+    private var warmWebView: EditorWebView?
 
     /// Whether the pre-warmed WebView has received the Monaco "Ready" event.
     private var isReady = false
@@ -26,6 +26,9 @@ final class EditorWebViewPool: NSObject, WKScriptMessageHandler, WKNavigationDel
     /// Cached Monaco directory URL (persists after first extraction).
     private(set) var monacoDir: URL?
 
+    /// Whether Monaco is being extracted on a background queue.
+    private var isExtracting = false
+
     /// JSON decoder for checking the Ready event.
     private let jsonDecoder = JSONDecoder()
 
@@ -57,21 +60,31 @@ final class EditorWebViewPool: NSObject, WKScriptMessageHandler, WKNavigationDel
     /// background. This is idempotent — calling it while a warm-up is already
     /// in progress is a no-op.
     func warmUp() {
-        guard warmWebView == nil else { return }
-
-        // Ensure Monaco assets are extracted (cached after first run).
-        if monacoDir == nil {
-            switch ImpulseCore.ensureMonacoExtracted() {
-            case .failure(let error):
-                os_log(.error, log: Self.log, "Failed to extract Monaco for pre-warm: %{public}@", error.message)
-                return
-            case .success(let pathString):
-                monacoDir = URL(fileURLWithPath: pathString, isDirectory: true)
+        guard warmWebView == nil, !isExtracting else { return }
+
+        // Extract Monaco assets off the main thread (slow on the first launch
+        // after an update, cached afterwards), then come back to warm up.
+        guard let monacoDir = monacoDir else {
+            isExtracting = true
+            ImpulseCore.markStartupPhase("monaco extraction started")
+            DispatchQueue.global(qos: .userInitiated).async {
+                let result = ImpulseCore.ensureMonacoExtracted()
+                DispatchQueue.main.async { [weak self] in
+                    guard let self else { return }
+                    self.isExtracting = false
+                    switch result {
+                    case .failure(let error):
+                        os_log(.error, log: Self.log, "Failed to extract Monaco for pre-warm: %{public}@", error.message)
+                    case .success(let pathString):
+                        ImpulseCore.markStartupPhase("monaco extracted")
+                        self.monacoDir = URL(fileURLWithPath: pathString, isDirectory: true)
+                        self.warmUp()
+                    }
+                }
             }
+            return
         }
 
-        guard let monacoDir = monacoDir else { return }
-
         let config = WKWebViewConfiguration()
         config.userContentController.add(self, name: "impulse")
 
@@ -79,7 +92,7 @@ final class EditorWebViewPool: NSObject, WKScriptMessageHandler, WKNavigationDel
         pagePrefs.allowsContentJavaScript = true
         config.defaultWebpagePreferences = pagePrefs
 
-        let wv = WKWebView(frame: NSRect(x: 0, y: 0, width: 800, height: 600), configuration: config)
+        let wv = EditorWebView(frame: NSRect(x: 0, y: 0, width: 800, height: 600), configuration: config)
         wv.allowsMagnification = false
         wv.underPageBackgroundColor = .clear
         wv.navigationDelegate = self
@@ -102,7 +115,7 @@ final class EditorWebViewPool: NSObject, WKScriptMessageHandler, WKNavigationDel
     /// - Returns: A ready-to-use `WKWebView` with Monaco loaded, or `nil` if
     ///   none is available yet.
     func claim(newHandler: WKScriptMessageHandler & WKNavigationDelegate,
-               weakProxy: WKScriptMessageHandler) -> WKWebView? {
+               weakProxy: WKScriptMessageHandler) -> EditorWebView? {
         guard isReady, let wv = warmWebView else { return nil }
 
         warmWebView = nil
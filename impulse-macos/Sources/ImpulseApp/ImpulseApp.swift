@@ -51,21 +51,25 @@ struct ImpulseApp {
             exit(0)
         }
 
-        // Collect non-flag arguments as file paths to open.
-        let filePaths = args.dropFirst().filter { !$0.hasPrefix("-") }
+        // Paths to open (`path[:line[:column]]`), resolved against the
+        // launching shell's directory. If Impulse is already running they
+        // open there instead.
+        let cliOpen = CLIOpen.parse(
+            Array(args.dropFirst()), cwd: FileManager.default.currentDirectoryPath)
+        if let cliOpen, !cliOpen.request.targets.isEmpty,
+           CLIOpen.forwardToRunningInstance(json: cliOpen.json, wait: cliOpen.request.wait)
+        {
+            exit(0)
+        }
 
         let app = NSApplication.shared
         app.setActivationPolicy(.regular)
 
         let delegate = AppDelegate()
-        if !filePaths.isEmpty {
-            delegate.pendingFiles = filePaths.map { path in
-                // Resolve relative paths against the current working directory.
-                if path.hasPrefix("/") {
-                    return path
-                }
-                let cwd = FileManager.default.currentDirectoryPath
-                return (cwd as NSString).appendingPathComponent(path)
+        for target in cliOpen?.request.targets ?? [] {
+            delegate.pendingFiles.append(target.path)
+            if let line = target.line {
+                delegate.pendingPositions[target.path] = (line, target.column ?? 1)
             }
         }
         app.delegate = delegate
@@ -3,8 +3,7 @@ import AppKit
 // MARK: - Built-in Keybinding
 
 /// A built-in keyboard shortcut with its default key equivalent and modifiers,
-/// mirroring the Rust `BuiltinKeybinding` from the Linux frontend but using
-/// macOS conventions (Cmd instead of Ctrl).
+/// built from the core command registry's macOS shortcut.
 struct BuiltinKeybinding {
     let id: String
     let description: String
@@ -19,193 +18,22 @@ struct BuiltinKeybinding {
 
 // MARK: - Built-in Keybinding Registry
 
-/// All built-in keybindings, using macOS conventions (Cmd instead of Ctrl).
+/// Built-in keybindings, using macOS conventions (Cmd instead of Ctrl).
 enum Keybindings {
 
-    static let builtins: [BuiltinKeybinding] = [
-        // -- Tabs --
-        BuiltinKeybinding(
-            id: "new_tab",
-            description: "New Terminal Tab",
-            category: "Tabs",
-            defaultShortcut: "Cmd+T",
-            keyEquivalent: "t",
-            modifierFlags: [.command]
-        ),
-        BuiltinKeybinding(
-            id: "close_tab",
-            description: "Close Tab",
-            category: "Tabs",
-            defaultShortcut: "Cmd+W",
-            keyEquivalent: "w",
-            modifierFlags: [.command]
-        ),
-        BuiltinKeybinding(
-            id: "reopen_tab",
-            description: "Reopen Closed Tab",
-            category: "Tabs",
-            defaultShortcut: "Cmd+Shift+T",
-            keyEquivalent: "T",
-            modifierFlags: [.command, .shift]
-        ),
-        BuiltinKeybinding(
-            id: "next_tab",
-            description: "Next Tab",
-            category: "Tabs",
-            defaultShortcut: "Ctrl+Tab",
-            keyEquivalent: "\t",
-            modifierFlags: [.control]
-        ),
-        BuiltinKeybinding(
-            id: "prev_tab",
-            description: "Previous Tab",
-            category: "Tabs",
-            defaultShortcut: "Ctrl+Shift+Tab",
-            keyEquivalent: "\u{0019}", // backtab
-            modifierFlags: [.control, .shift]
-        ),
-        // -- Terminal --
-        BuiltinKeybinding(
-            id: "copy",
-            description: "Copy",
-            category: "Terminal",
-            defaultShortcut: "Cmd+C",
-            keyEquivalent: "c",
-            modifierFlags: [.command]
-        ),
-        BuiltinKeybinding(
-            id: "paste",
-            description: "Paste",
-            category: "Terminal",
-            defaultShortcut: "Cmd+V",
-            keyEquivalent: "v",
-            modifierFlags: [.command]
-        ),
-        // -- Editor --
-        BuiltinKeybinding(
-            id: "new_file",
-            description: "New File",
-            category: "Editor",
-            defaultShortcut: "Cmd+N",
-            keyEquivalent: "n",
-            modifierFlags: [.command]
-        ),
-        BuiltinKeybinding(
-            id: "save",
-            description: "Save File",
-            category: "Editor",
-            defaultShortcut: "Cmd+S",
-            keyEquivalent: "s",
-            modifierFlags: [.command]
-        ),
-        BuiltinKeybinding(
-            id: "find",
-            description: "Find",
-            category: "Editor",
-            defaultShortcut: "Cmd+F",
-            keyEquivalent: "f",
-            modifierFlags: [.command]
-        ),
-        BuiltinKeybinding(
-            id: "go_to_line",
-            description: "Go to Line",
-            category: "Editor",
-            defaultShortcut: "Cmd+G",
-            keyEquivalent: "g",
-            modifierFlags: [.command]
-        ),
-        BuiltinKeybinding(
-            id: "toggle_markdown_preview",
-            description: "Toggle Preview",
-            category: "Editor",
-            defaultShortcut: "Cmd+Shift+M",
-            keyEquivalent: "m",
-            modifierFlags: [.command, .shift]
-        ),
-        // -- Navigation --
-        BuiltinKeybinding(
-            id: "toggle_sidebar",
-            description: "Toggle Sidebar",
-            category: "Navigation",
-            defaultShortcut: "Cmd+Shift+B",
-            keyEquivalent: "B",
-            modifierFlags: [.command, .shift]
-        ),
-        BuiltinKeybinding(
-            id: "project_search",
-            description: "Find in Project",
-            category: "Navigation",
-            defaultShortcut: "Cmd+Shift+F",
-            keyEquivalent: "F",
-            modifierFlags: [.command, .shift]
-        ),
-        BuiltinKeybinding(
-            id: "command_palette",
-            description: "Command Palette",
-            category: "Navigation",
-            defaultShortcut: "Cmd+Shift+P",
-            keyEquivalent: "P",
-            modifierFlags: [.command, .shift]
-        ),
-        BuiltinKeybinding(
-            id: "review_changes",
-            description: "Review Changes",
-            category: "Navigation",
-            defaultShortcut: "Cmd+Shift+G",
-            keyEquivalent: "G",
-            modifierFlags: [.command, .shift]
-        ),
-        BuiltinKeybinding(
-            id: "open_settings",
-            description: "Open Settings",
-            category: "Navigation",
-            defaultShortcut: "Cmd+,",
-            keyEquivalent: ",",
-            modifierFlags: [.command]
-        ),
-        // -- Font --
-        BuiltinKeybinding(
-            id: "font_increase",
-            description: "Increase Font Size",
-            category: "Font",
-            defaultShortcut: "Cmd+=",
-            keyEquivalent: "=",
-            modifierFlags: [.command]
-        ),
-        BuiltinKeybinding(
-            id: "font_decrease",
-            description: "Decrease Font Size",
-            category: "Font",
-            defaultShortcut: "Cmd+-",
-            keyEquivalent: "-",
-            modifierFlags: [.command]
-        ),
-        BuiltinKeybinding(
-            id: "font_reset",
-            description: "Reset Font Size",
-            category: "Font",
-            defaultShortcut: "Cmd+0",
-            keyEquivalent: "0",
-            modifierFlags: [.command]
-        ),
-        // -- App --
-        BuiltinKeybinding(
-            id: "new_window",
-            description: "New Window",
-            category: "App",
-            defaultShortcut: "Cmd+Shift+N",
-            keyEquivalent: "N",
-            modifierFlags: [.command, .shift]
-        ),
-        BuiltinKeybinding(
-            id: "fullscreen",
-            description: "Toggle Fullscreen",
-            category: "App",
-            defaultShortcut: "Ctrl+Cmd+F",
-            keyEquivalent: "f",
-            modifierFlags: [.control, .command]
-        ),
-    ]
+    /// The core command registry's commands that have a default shortcut.
+    static let builtins: [BuiltinKeybinding] = ImpulseCore.commandRegistry().compactMap { entry in
+        guard entry.source == "builtin", let shortcut = entry.defaultShortcut else { return nil }
+        let parsed = parseShortcut(shortcut)
+        return BuiltinKeybinding(
+            id: entry.id,
+            description: entry.title,
+            category: entry.category,
+            defaultShortcut: shortcut,
+            keyEquivalent: parsed.keyEquivalent,
+            modifierFlags: parsed.modifierFlags
+        )
+    }
 
     // MARK: Keybinding Lookup Cache
 
@@ -295,7 +123,11 @@ enum Keybindings {
             }
         }
 
-        let keyEquivalent = keyNameToEquivalent(keyPart)
+        var keyEquivalent = keyNameToEquivalent(keyPart)
+        // Shift+Tab arrives as backtab.
+        if keyEquivalent == "\t" && flags.contains(.shift) {
+            keyEquivalent = "\u{0019}"
+        }
         return (keyEquivalent, flags)
     }
 
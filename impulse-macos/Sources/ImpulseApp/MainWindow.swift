@@ -87,9 +87,23 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
   /// Local event monitor for custom keybinding interception.
   private var customKeybindingMonitor: Any?
 
+  /// Local event monitor for Cmd+scroll zoom, and the trackpad scroll
+  /// not yet turned into a zoom step.
+  private var scrollZoomMonitor: Any?
+  private var scrollZoomRemainder: CGFloat = 0
+
   /// Observer tokens from NotificationCenter, removed on window close and deinit.
   private var notificationObservers: [Any] = []
 
+  /// Chrome visibility from before Zen mode, restored when leaving it.
+  private struct ZenSavedLayout {
+    let sidebarVisible: Bool
+    let tabBarHidden: Bool
+    let statusBarHidden: Bool
+    let centeredLayout: Bool
+  }
+  private var zenSavedLayout: ZenSavedLayout?
+
   /// Dictionary mapping file paths to open editor tabs for O(1) lookup.
   ///
   /// `internal` (not `private`) because it is accessed from the
@@ -174,6 +188,10 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
   /// Set of file URIs for which didOpen has been sent.
   var lspOpenFiles: Set<String> = []
 
+  /// LSP error and warning counts per open file URI, summed into the
+  /// status bar's problems segment.
+  var problemCountsByUri: [String: (errors: Int, warnings: Int)] = [:]
+
   // MARK: - Initialization
 
   init(
@@ -196,6 +214,9 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
     self.windowModel.showHiddenFiles = settings.sidebarShowHidden
     self.windowModel.sidebarVisible = settings.sidebarVisible
     self.windowModel.sidebarWidth = CGFloat(settings.sidebarWidth)
+    self.windowModel.contentMaxWidth = CGFloat(settings.zenModeMaxWidth)
+    self.windowModel.statusBarSegments = ImpulseCore.statusBarSegments(
+      order: settings.statusBarSegments, hidden: settings.statusBarHiddenSegments)
     self.windowModel.tabBarPosition = settings.tabBarPosition
     self.windowModel.contextBarEnabled = settings.terminalContextBar
     self.fileTreeData = FileTreeDataController()
@@ -239,6 +260,7 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
     updateSidebarToolbarItems()
     setupNotificationObservers()
     setupCustomKeybindingMonitor()
+    setupScrollZoomMonitor()
 
     // Set initial root path for the file tree.
     // Always start at home; the sidebar will update once the terminal's CWD
@@ -290,6 +312,7 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
 
   deinit {
     teardownCustomKeybindingMonitor()
+    teardownScrollZoomMonitor()
     notificationObservers.forEach { NotificationCenter.default.removeObserver($0) }
   }
 
@@ -330,6 +353,33 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
     windowModel.onRecentCommands = { [weak self] limit in
       self?.tabManager.selectedTerminal?.activeTerminal?.recentCommands(limit: limit) ?? []
     }
+    windowModel.onOpenFileBuffers = { [weak self] in
+      guard let self else { return [] }
+      return self.tabManager.tabs.compactMap { entry in
+        guard case .editor(let editor) = entry, let path = editor.filePath else { return nil }
+        return (path: path, content: editor.content)
+      }
+    }
+    windowModel.onActiveDirectory = { [weak self] in
+      self?.getActiveCwd()
+    }
+    windowModel.onDirectoryHistory = { [weak self] in
+      self?.tabManager.selectedTerminal?.activeTerminal?.directoryHistory ?? []
+    }
+    windowModel.onOpenDirectoryInSidebar = { [weak self] dir in
+      guard let self else { return }
+      if !self.windowModel.sidebarVisible { self.toggleSidebar() }
+      self.windowModel.sidebarPanel = .files
+      self.switchFileTreeRoot(dir, updateStatusBar: false)
+    }
+    windowModel.onOpenWorkspace = { [weak self] dir in
+      self?.openDirectory(dir)
+    }
+    windowModel.onChangeDirectory = { [weak self] dir in
+      guard let terminal = self?.tabManager.selectedTerminal?.activeTerminal else { return }
+      terminal.sendCommand("cd \(dir.shellEscaped)")
+      terminal.focus()
+    }
     windowModel.onSendInterrupt = { [weak self] in
       self?.tabManager.selectedTerminal?.activeTerminal?.sendInterrupt()
     }
@@ -339,15 +389,34 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
     windowModel.onTabMoved = { [weak self] from, to in
       self?.tabManager.moveTab(from: from, to: to)
     }
+    windowModel.onTabDraggedOut = { [weak self] index, screenPoint in
+      self?.moveTab(at: index, toWindowAt: screenPoint) ?? false
+    }
     windowModel.onTabPinToggled = { [weak self] index in
       self?.tabManager.togglePin(index: index)
     }
+    windowModel.onTabGroupClosed = { [weak self] group, index in
+      self?.requestCloseTabGroup(group, anchor: index)
+    }
     windowModel.onSidebarVisibilityChanged = { [weak self] _ in
       self?.updateSidebarToolbarItems()
     }
     windowModel.onPreviewToggle = { [weak self] in
       self?.previewButtonClicked(nil)
     }
+    windowModel.onResetZoom = { [weak self] in
+      self?.zoomSelectedTab(.reset)
+    }
+    windowModel.onSetLanguage = { [weak self] languageId in
+      self?.setLanguageOverride(languageId)
+    }
+    windowModel.onSetIndentation = { [weak self] useSpaces, width, convert in
+      guard let self, let editor = self.tabManager.selectedEditor else { return }
+      let indentation = EditorIndentation(
+        useSpaces: useSpaces, width: width ?? editor.indentation.width)
+      editor.setIndentation(indentation, convert: convert)
+      self.windowModel.currentIndent = indentation.label
+    }
     windowModel.onOpenFile = { path, line in
       NotificationCenter.default.post(
         name: .impulseOpenFile,
@@ -355,6 +424,13 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
         userInfo: ["path": path, "line": line as Any]
       )
     }
+    windowModel.onPreviewFile = { path in
+      NotificationCenter.default.post(
+        name: .impulseOpenFile,
+        object: nil,
+        userInfo: ["path": path, "preview": true]
+      )
+    }
     windowModel.onRefreshTree = { [weak self] in
       guard let self else { return }
       let root = self.fileTreeRootPath
@@ -417,37 +493,24 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
     }
     windowModel.onNewFile = { [weak self] (dirPath: String) in
       guard let self, !dirPath.isEmpty else { return }
-      NameInputDialog.show(
-        title: "New File",
-        message: "Enter a name for the new file:",
-        placeholder: "untitled",
-        defaultValue: ""
-      ) { [weak self] name in
-        guard let self, !name.isEmpty, !name.contains("/") else { return }
-        let fullPath = (dirPath as NSString).appendingPathComponent(name)
-        let resolvedPath = (fullPath as NSString).standardizingPath
-        let resolvedDir = (dirPath as NSString).standardizingPath
-        guard resolvedPath.hasPrefix(resolvedDir) else { return }
-        guard FileManager.default.createFile(atPath: fullPath, contents: nil) else { return }
-        self.windowModel.onRefreshTree?()
-      }
+      self.windowModel.beginNewEntry(in: dirPath, isDirectory: false)
     }
     windowModel.onNewFolder = { [weak self] (dirPath: String) in
       guard let self, !dirPath.isEmpty else { return }
-      NameInputDialog.show(
-        title: "New Folder",
-        message: "Enter a name for the new folder:",
-        placeholder: "untitled-folder",
-        defaultValue: ""
-      ) { [weak self] name in
-        guard let self, !name.isEmpty, !name.contains("/") else { return }
-        let fullPath = (dirPath as NSString).appendingPathComponent(name)
-        let resolvedPath = (fullPath as NSString).standardizingPath
-        let resolvedDir = (dirPath as NSString).standardizingPath
-        guard resolvedPath.hasPrefix(resolvedDir) else { return }
-        try? FileManager.default.createDirectory(
-          atPath: fullPath, withIntermediateDirectories: false)
+      self.windowModel.beginNewEntry(in: dirPath, isDirectory: true)
+    }
+    windowModel.onCommitFileTreeEdit = { [weak self] edit, name in
+      self?.commitFileTreeEdit(edit, name: name)
+    }
+    windowModel.onDuplicateFile = { [weak self] path in
+      guard let self else { return }
+      switch ImpulseCore.duplicateFile(at: path) {
+      case .success(let copy):
         self.windowModel.onRefreshTree?()
+        // Name the copy in place once the refreshed tree shows it.
+        self.windowModel.fileTreeEdit = .rename(path: copy)
+      case .failure(let error):
+        NSLog("MainWindow: \(error.message)")
       }
     }
     // Sidebar action-bar shortcuts: create in the selected tree dir (or root).
@@ -636,10 +699,12 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
   /// Opens a file in an editor tab. Called by AppDelegate for Finder "Open With"
   /// and CLI file arguments. Bypasses the notification path (which requires
   /// isKeyWindow) so it works during startup before the window is key.
-  func openFile(path: String) {
+  /// Without `revealInTree` the file tree stays where it is (remote files'
+  /// cached copies).
+  func openFile(path: String, revealInTree: Bool = true) {
     // Switch the file tree to the file's parent directory.
     let dir = (path as NSString).deletingLastPathComponent
-    if !dir.isEmpty, dir != fileTreeRootPath {
+    if revealInTree, !dir.isEmpty, dir != fileTreeRootPath {
       switchFileTreeRoot(dir)
     }
     tabManager.addEditorTab(path: path, projectDirectory: fileTreeRootPath)
@@ -649,6 +714,13 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
     }
   }
 
+  /// Shows `dir` in the file tree (a directory given on the command line).
+  func openDirectory(_ dir: String) {
+    if dir != fileTreeRootPath {
+      switchFileTreeRoot(dir)
+    }
+  }
+
   // MARK: - Actions
 
   @objc private func toggleHiddenAction(_ sender: Any?) {
@@ -693,48 +765,38 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
   @objc private func newFileAction(_ sender: Any?) {
     let dirPath = selectedDirectoryForFileTreeAction()
     guard !dirPath.isEmpty else { return }
-    NameInputDialog.show(
-      title: "New File",
-      message: "Enter a name for the new file:",
-      placeholder: "untitled",
-      defaultValue: ""
-    ) { [weak self] name in
-      guard let self, !name.isEmpty, !name.contains("/") else { return }
-      let fullPath = (dirPath as NSString).appendingPathComponent(name)
-      let resolvedPath = (fullPath as NSString).standardizingPath
-      let resolvedDir = (dirPath as NSString).standardizingPath
-      guard resolvedPath.hasPrefix(resolvedDir) else { return }
-      guard FileManager.default.createFile(atPath: fullPath, contents: nil) else {
-        NSLog("MainWindow: failed to create file at \(fullPath)")
-        return
-      }
-      self.windowModel.onRefreshTree?()
-    }
+    windowModel.beginNewEntry(in: dirPath, isDirectory: false)
   }
 
   @objc private func newFolderAction(_ sender: Any?) {
     let dirPath = selectedDirectoryForFileTreeAction()
     guard !dirPath.isEmpty else { return }
-    NameInputDialog.show(
-      title: "New Folder",
-      message: "Enter a name for the new folder:",
-      placeholder: "untitled-folder",
-      defaultValue: ""
-    ) { [weak self] name in
-      guard let self, !name.isEmpty, !name.contains("/") else { return }
-      let fullPath = (dirPath as NSString).appendingPathComponent(name)
-      let resolvedPath = (fullPath as NSString).standardizingPath
-      let resolvedDir = (dirPath as NSString).standardizingPath
-      guard resolvedPath.hasPrefix(resolvedDir) else { return }
-      do {
-        try FileManager.default.createDirectory(
-          atPath: fullPath,
-          withIntermediateDirectories: false)
-      } catch {
-        NSLog("MainWindow: failed to create folder at \(fullPath): \(error)")
-        return
+    windowModel.beginNewEntry(in: dirPath, isDirectory: true)
+  }
+
+  /// Creates or renames from the file tree's inline name field. New files
+  /// start from their template and open in an editor. Returns the error to
+  /// show in the field, or nil on success.
+  private func commitFileTreeEdit(_ edit: FileTreeEdit, name: String) -> String? {
+    let result: Result<String, ImpulseError>
+    switch edit {
+    case .newEntry(let dir, let isDirectory):
+      result = ImpulseCore.createFile(
+        named: name, in: dir, isDirectory: isDirectory,
+        templates: settings.fileTemplates)
+    case .rename(let path):
+      result = ImpulseCore.renameFile(at: path, to: name)
+    }
+    switch result {
+    case .success(let path):
+      windowModel.onRefreshTree?()
+      windowModel.selectedFileTreePath = path
+      if case .newEntry(_, false) = edit {
+        windowModel.onOpenFile?(path, nil)
       }
-      self.windowModel.onRefreshTree?()
+      return nil
+    case .failure(let error):
+      return error.message
     }
   }
 
@@ -748,6 +810,29 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
     updateSidebarToolbarItems()
   }
 
+  /// Enters Zen mode (sidebar, tab bar and status bar hidden, content
+  /// centered), or leaves it and restores the previous layout.
+  func toggleZenMode() {
+    if let saved = zenSavedLayout {
+      zenSavedLayout = nil
+      setSidebarVisible(saved.sidebarVisible)
+      windowModel.tabBarHidden = saved.tabBarHidden
+      windowModel.statusBarHidden = saved.statusBarHidden
+      windowModel.centeredLayout = saved.centeredLayout
+    } else {
+      zenSavedLayout = ZenSavedLayout(
+        sidebarVisible: windowModel.sidebarVisible,
+        tabBarHidden: windowModel.tabBarHidden,
+        statusBarHidden: windowModel.statusBarHidden,
+        centeredLayout: windowModel.centeredLayout
+      )
+      setSidebarVisible(false)
+      windowModel.tabBarHidden = true
+      windowModel.statusBarHidden = true
+      windowModel.centeredLayout = true
+    }
+  }
+
   // MARK: - Custom Keybinding Monitor
 
   /// Installs a local event monitor that intercepts key-down events matching
@@ -1017,6 +1102,7 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
       windowModel.lastCommandExitCode = active?.lastCommandExitCode
       windowModel.lastCommandDurationMs = active?.lastCommandDurationMs
       windowModel.terminalDirectInteraction = active?.isDirectInteraction ?? false
+      windowModel.zoomLabel = selectedTabZoomLabel()
     } else if let language = tabInfo.language {
       let cwd = tabInfo.cwd ?? ""
       let branch = cwd.isEmpty ? nil : gitBranch(forDirectory: cwd)
@@ -1030,9 +1116,8 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
       windowModel.cursorLine = tabInfo.cursorLine
       windowModel.cursorCol = tabInfo.cursorCol
       windowModel.currentLanguage = language
-      windowModel.currentIndent =
-        settings.useSpaces
-        ? "Spaces: \(settings.tabWidth)" : "Tab Size: \(settings.tabWidth)"
+      windowModel.currentIndent = tabManager.selectedEditor?.indentation.label
+      windowModel.zoomLabel = selectedTabZoomLabel()
       // Show/hide preview button based on file type
       if let editor = tabManager.selectedEditor,
         let fp = editor.filePath,
@@ -1117,8 +1202,55 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
     editorTabsByPath.removeValue(forKey: path)
   }
 
+  // MARK: - Moving Tabs Between Windows
+
+  /// Moves the tab at `index` to the Impulse window under `screenPoint`, or
+  /// to a new window there, when the point is outside this window.
+  /// Terminals keep their shells and editors their unsaved edits; an
+  /// editor's language server document moves with it.
+  func moveTab(at index: Int, toWindowAt screenPoint: NSPoint) -> Bool {
+    guard let window, !window.frame.contains(screenPoint),
+      index >= 0, index < tabManager.tabs.count,
+      let delegate = NSApp.delegate as? AppDelegate,
+      let target = delegate.windowController(at: screenPoint, excluding: self)
+        ?? delegate.openWindowForMovedTab(at: screenPoint),
+      let tab = tabManager.detachTab(index: index)
+    else { return false }
+
+    if case .editor(let editor) = tab.entry, let path = editor.filePath {
+      untrackEditorTab(forPath: path)
+      lspDidClose(editor: editor)
+    }
+    target.adoptTab(tab)
+    // The last tab left: the window goes with it, like a browser's.
+    if tabManager.tabs.isEmpty {
+      window.close()
+    }
+    return true
+  }
+
+  /// Takes over a tab moved here from another window.
+  func adoptTab(_ tab: TabManager.TransferredTab) {
+    tabManager.adoptTab(tab)
+    if case .editor(let editor) = tab.entry, let path = editor.filePath {
+      trackEditorTab(editor, forPath: path)
+      lspDidOpenIfNeeded(path: path)
+    }
+    window?.makeKeyAndOrderFront(nil)
+  }
+
   // MARK: - Tab Close with Save Confirmation
 
+  /// Closes the unpinned tabs in `group` (`"saved"`, `"right"` or `"left"`)
+  /// relative to the tab at `anchor`. Unsaved editors still ask first.
+  func requestCloseTabGroup(_ group: String, anchor: Int) {
+    let indices = ImpulseCore.tabsToClose(tabManager.tabSummaries(), anchor: anchor, group: group)
+    // From the end, so closing one doesn't shift the ones still to close.
+    for index in indices.reversed() {
+      requestCloseTab(index: index)
+    }
+  }
+
   /// Closes a tab at the given index, showing a save confirmation dialog if
   /// the tab is an editor with unsaved changes. Used by both the Cmd+W
   /// shortcut and the tab bar close button / context menu.
@@ -1230,8 +1362,9 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
       let summary = closeRiskSummary(
         action: .closeTab,
         unsavedEditorCount: 0,
-        runningTerminalProcessCount: container.runningDescendantProcessCount(),
-        runningCommands: container.runningCloseRiskCommands()
+        runningTerminalProcessCount: settings.terminalConfirmClose
+          ? container.runningProcessCount(ignoring: settings.terminalCloseIgnoredCommands) : 0,
+        runningCommands: settings.terminalConfirmClose ? container.runningCloseRiskCommands() : []
       ),
       summary.hasRisk
     else {
@@ -1283,7 +1416,9 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
       switch response {
       case .alertFirstButtonReturn:
         if editor.filePath != nil {
-          editor.fetchContentAndSave { success in completion(success) }
+          editor.fetchContentAndSave(whitespace: self.saveWhitespace) { success in
+            completion(success)
+          }
         } else {
           self.showSaveAsDialog(for: editor) { success in completion(success) }
         }
@@ -1317,7 +1452,7 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
   }
 
   private func configureCommandPalette(_ palette: CommandPaletteWindow, settings: Settings) {
-    palette.registerBuiltinCommands(overrides: settings.keybindingOverrides)
+    palette.registerBuiltinCommands(overrides: settings.effectiveKeybindingOverrides)
     palette.registerCustomCommands(settings.customKeybindings)
     palette.configureDynamicSearch(
       rootProvider: { [weak self] in self?.fileTreeRootPath },
@@ -1358,6 +1493,31 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
         self?.toggleSidebar()
       }
     )
+    notificationObservers.append(
+      nc.addObserver(forName: .impulseToggleZenMode, object: nil, queue: .main) { [weak self] _ in
+        guard self?.window?.isKeyWindow == true else { return }
+        self?.toggleZenMode()
+      }
+    )
+    notificationObservers.append(
+      nc.addObserver(forName: .impulseToggleTabBar, object: nil, queue: .main) { [weak self] _ in
+        guard let self, self.window?.isKeyWindow == true else { return }
+        self.windowModel.tabBarHidden.toggle()
+      }
+    )
+    notificationObservers.append(
+      nc.addObserver(forName: .impulseToggleStatusBar, object: nil, queue: .main) { [weak self] _ in
+        guard let self, self.window?.isKeyWindow == true else { return }
+        self.windowModel.statusBarHidden.toggle()
+      }
+    )
+    notificationObservers.append(
+      nc.addObserver(forName: .impulseToggleCenteredLayout, object: nil, queue: .main) {
+        [weak self] _ in
+        guard let self, self.window?.isKeyWindow == true else { return }
+        self.windowModel.centeredLayout.toggle()
+      }
+    )
     notificationObservers.append(
       nc.addObserver(forName: .impulseNewTerminalTab, object: nil, queue: .main) {
         [weak self] notification in
@@ -1452,7 +1612,8 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
             path: path,
             projectDirectory: self.fileTreeRootPath,
             goToLine: line,
-            goToColumn: line == nil ? nil : 1
+            goToColumn: line == nil ? nil : 1,
+            preview: notification.userInfo?["preview"] as? Bool ?? false
           )
           // Navigate to specific line if provided (e.g. from search results).
           if let editor = self.findEditorTab(forPath: path) {
@@ -1478,6 +1639,7 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
           if let path = editor.filePath {
             self.trackEditorTab(editor, forPath: path)
             self.lspDidOpenIfNeeded(path: path)
+            self.promptForWorkspaceTrustIfNeeded(path: path)
           }
           self.applyGitDiffDecorations(editor: editor)
         }
@@ -1586,7 +1748,7 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
       nc.addObserver(forName: .impulseShowCommandPalette, object: nil, queue: .main) {
         [weak self] _ in
         guard let self, self.window?.isKeyWindow == true, let window = self.window else { return }
-        self.commandPalette.show(relativeTo: window)
+        self.commandPalette.show(relativeTo: window, context: self.commandContext())
       }
     )
 
@@ -1598,6 +1760,22 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
       }
     )
 
+    // Notification history — every window's bell shows the same history.
+    windowModel.notifications = ImpulseCore.notifications()
+    notificationObservers.append(
+      nc.addObserver(forName: .impulseNotificationsDidChange, object: nil, queue: .main) {
+        [weak self] _ in
+        self?.windowModel.notifications = ImpulseCore.notifications()
+      }
+    )
+    notificationObservers.append(
+      nc.addObserver(forName: .impulseShowNotifications, object: nil, queue: .main) {
+        [weak self] _ in
+        guard let self, self.window?.isKeyWindow == true else { return }
+        self.windowModel.notificationsPanelVisible = true
+      }
+    )
+
     // Update available — surface the updater on the visible SwiftUI status bar.
     notificationObservers.append(
       nc.addObserver(forName: .impulseUpdateAvailable, object: nil, queue: .main) {
@@ -1605,6 +1783,7 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
         guard let self else { return }
         self.windowModel.updateAvailableVersion = notification.userInfo?["version"] as? String
         self.windowModel.updateCurrentVersion = notification.userInfo?["currentVersion"] as? String
+        self.windowModel.updateInstalled = notification.userInfo?["installed"] as? Bool ?? false
         if let urlString = notification.userInfo?["url"] as? String {
           self.windowModel.updateURL = URL(string: urlString)
         } else {
@@ -1613,26 +1792,32 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
       }
     )
 
-    // Install LSP — install managed web LSP servers
+    // Reload Custom CSS — every window refreshes its editors
+    notificationObservers.append(
+      nc.addObserver(forName: .impulseReloadCustomCss, object: nil, queue: .main) { [weak self] _ in
+        guard let self else { return }
+        self.tabManager.applyEditorCustomCss(ThemeManager.reloadEditorCustomCss())
+      }
+    )
+
     notificationObservers.append(
       nc.addObserver(forName: .impulseInstallLsp, object: nil, queue: .main) { [weak self] _ in
         guard let self, self.window?.isKeyWindow == true else { return }
         DispatchQueue.global(qos: .userInitiated).async {
           let result = ImpulseCore.lspInstall()
-          DispatchQueue.main.async {
-            let alert = NSAlert()
-            switch result {
-            case .success(let path):
-              alert.messageText = "LSP Servers Installed"
-              alert.informativeText = "Web LSP servers installed to \(path)"
-              alert.alertStyle = .informational
-            case .failure(let error):
-              alert.messageText = "LSP Install Failed"
-              alert.informativeText = error.message
-              alert.alertStyle = .warning
-            }
-            alert.addButton(withTitle: "OK")
-            alert.runModal()
+          switch result {
+          case .success(let path):
+            ImpulseCore.notify(
+              .info, title: "LSP Servers Installed",
+              message: "Web LSP servers installed to \(path)", source: "lsp")
+          case .failure(let error):
+            ImpulseCore.notify(
+              .error, title: "Failed to install web LSP servers", message: error.message,
+              source: "lsp",
+              actions: [.init(label: "Retry", command: "install_lsp")])
+          }
+          DispatchQueue.main.async { [weak self] in
+            self?.windowModel.notificationsPanelVisible = true
           }
         }
       }
@@ -1661,6 +1846,14 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
       }
     )
 
+    // Save All — every modified editor in this window
+    notificationObservers.append(
+      nc.addObserver(forName: .impulseSaveAll, object: nil, queue: .main) { [weak self] _ in
+        guard let self, self.window?.isKeyWindow == true else { return }
+        self.saveAllEditors()
+      }
+    )
+
     // Find — editor: Monaco find widget; terminal: search bar toggle
     notificationObservers.append(
       nc.addObserver(forName: .impulseFind, object: nil, queue: .main) { [weak self] _ in
@@ -1755,6 +1948,10 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
         [weak self] notification in
         guard let self else { return }
         guard let editor = self.ownedEditor(from: notification) else { return }
+        // Editing a preview tab keeps it.
+        if editor.isModified {
+          editor.isPreview = false
+        }
         self.tabManager.refreshSegmentLabels()
         let changes = notification.userInfo?["changes"] as? [MonacoContentChange] ?? []
         self.lspDidChange(editor: editor, changes: changes)
@@ -1833,6 +2030,131 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
       }
     )
 
+    // Terminal menu.
+    notificationObservers.append(
+      nc.addObserver(forName: .impulseClearTerminal, object: nil, queue: .main) {
+        [weak self] _ in
+        guard let self, self.window?.isKeyWindow == true else { return }
+        self.windowModel.onClearTerminal?()
+      }
+    )
+    notificationObservers.append(
+      nc.addObserver(forName: .impulseOpenScrollbackInEditor, object: nil, queue: .main) {
+        [weak self] _ in
+        guard let self, self.window?.isKeyWindow == true,
+          let terminal = self.tabManager.selectedTerminal?.activeTerminal
+        else { return }
+        let text = terminal.scrollbackText()
+        let editor = self.tabManager.addUntitledEditorTab(
+          cwd: terminal.currentWorkingDirectory, content: text,
+          title: "Scrollback: \(terminal.tabTitle)")
+        // Read-only so it can't be mistaken for the live terminal; Toggle
+        // Read-Only lifts it.
+        editor.setReadOnly(reason: "Read-only")
+        let lines = max(1, text.split(separator: "\n", omittingEmptySubsequences: false).count)
+        editor.goToPosition(line: UInt32(lines), column: 1)
+      }
+    )
+    notificationObservers.append(
+      nc.addObserver(forName: .impulseShowCommandHistory, object: nil, queue: .main) {
+        [weak self] _ in
+        guard let self, self.window?.isKeyWindow == true else { return }
+        self.windowModel.onShowCommandHistory?()
+      }
+    )
+
+    // Open Containing Folder — reveal the active editor's file in Finder.
+    notificationObservers.append(
+      nc.addObserver(forName: .impulseOpenContainingFolder, object: nil, queue: .main) {
+        [weak self] _ in
+        guard let self, self.window?.isKeyWindow == true,
+          let path = self.tabManager.selectedEditor?.filePath
+        else { return }
+        NSWorkspace.shared.activateFileViewerSelecting([URL(fileURLWithPath: path)])
+      }
+    )
+
+    // Copy Path and friends — copy the active file's path in the form the
+    // command id names.
+    notificationObservers.append(
+      nc.addObserver(forName: .impulseCopyPath, object: nil, queue: .main) {
+        [weak self] note in
+        guard let self, self.window?.isKeyWindow == true,
+          let commandId = note.object as? String,
+          let path = self.windowModel.tabDisplayInfos.first(where: {
+            $0.index == self.tabManager.selectedIndex
+          })?.filePath,
+          let text = ImpulseCore.pathForCopy(
+            commandId: commandId, path: path, root: self.fileTreeRootPath)
+        else { return }
+        NSPasteboard.general.clearContents()
+        NSPasteboard.general.setString(text, forType: .string)
+      }
+    )
+
+    // Reveal in Sidebar — show the active file in the file tree, switching
+    // to its folder when it is outside the tree.
+    notificationObservers.append(
+      nc.addObserver(forName: .impulseRevealInSidebar, object: nil, queue: .main) {
+        [weak self] _ in
+        guard let self, self.window?.isKeyWindow == true,
+          let path = self.windowModel.tabDisplayInfos.first(where: {
+            $0.index == self.tabManager.selectedIndex
+          })?.filePath
+        else { return }
+        if !self.windowModel.sidebarVisible { self.toggleSidebar() }
+        self.windowModel.sidebarPanel = .files
+        if !self.windowModel.revealInFileTree(path) {
+          self.windowModel.pendingFileTreeReveal = path
+          self.switchFileTreeRoot((path as NSString).deletingLastPathComponent)
+        }
+      }
+    )
+
+    // Toggle Read-Only — lock or unlock the active editor.
+    notificationObservers.append(
+      nc.addObserver(forName: .impulseToggleReadOnly, object: nil, queue: .main) {
+        [weak self] _ in
+        guard let self, self.window?.isKeyWindow == true else { return }
+        self.tabManager.selectedEditor?.toggleReadOnly()
+        self.tabManager.refreshSegmentLabels()
+      }
+    )
+
+    notificationObservers.append(
+      nc.addObserver(forName: .impulseShowLocalHistory, object: nil, queue: .main) {
+        [weak self] _ in
+        guard let self, self.window?.isKeyWindow == true else { return }
+        self.showLocalHistory()
+      }
+    )
+
+    notificationObservers.append(
+      nc.addObserver(forName: .impulseCompareWithSaved, object: nil, queue: .main) {
+        [weak self] _ in
+        guard let self, self.window?.isKeyWindow == true,
+          let editor = self.tabManager.selectedEditor, editor.filePath != nil
+        else { return }
+        self.tabManager.addSavedCompareTab(editor: editor)
+      }
+    )
+
+    notificationObservers.append(
+      nc.addObserver(forName: .impulseRevertFile, object: nil, queue: .main) {
+        [weak self] _ in
+        guard let self, self.window?.isKeyWindow == true else { return }
+        self.revertActiveFile()
+      }
+    )
+
+    notificationObservers.append(
+      nc.addObserver(forName: .impulseManageWorkspaceTrust, object: nil, queue: .main) {
+        [weak self] _ in
+        guard let self, self.window?.isKeyWindow == true else { return }
+        self.showWorkspaceTrust()
+      }
+    )
+
     // Review Changes — open the git diff review tab for the current workspace.
     notificationObservers.append(
       nc.addObserver(forName: .impulseReviewChanges, object: nil, queue: .main) {
@@ -1842,23 +2164,23 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
       }
     )
 
-    // Font size
+    // Zoom
     notificationObservers.append(
       nc.addObserver(forName: .impulseFontIncrease, object: nil, queue: .main) { [weak self] _ in
         guard let self, self.window?.isKeyWindow == true else { return }
-        self.changeFontSize(delta: 1)
+        self.zoomSelectedTab(.in)
       }
     )
     notificationObservers.append(
       nc.addObserver(forName: .impulseFontDecrease, object: nil, queue: .main) { [weak self] _ in
         guard let self, self.window?.isKeyWindow == true else { return }
-        self.changeFontSize(delta: -1)
+        self.zoomSelectedTab(.out)
       }
     )
     notificationObservers.append(
       nc.addObserver(forName: .impulseFontReset, object: nil, queue: .main) { [weak self] _ in
         guard let self, self.window?.isKeyWindow == true else { return }
-        self.resetFontSize()
+        self.zoomSelectedTab(.reset)
       }
     )
 
@@ -1881,6 +2203,30 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
         self.tabManager.selectTab(index: prev)
       }
     )
+    notificationObservers.append(
+      nc.addObserver(forName: .impulseShowAllTabs, object: nil, queue: .main) { [weak self] _ in
+        guard let self, self.window?.isKeyWindow == true else { return }
+        self.windowModel.allTabsPanelVisible = true
+      }
+    )
+    notificationObservers.append(
+      nc.addObserver(forName: .impulseSortTabs, object: nil, queue: .main) {
+        [weak self] notification in
+        guard let self, self.window?.isKeyWindow == true,
+          let sort = notification.object as? String
+        else { return }
+        self.tabManager.sortTabs(by: sort)
+      }
+    )
+    notificationObservers.append(
+      nc.addObserver(forName: .impulseCloseTabGroup, object: nil, queue: .main) {
+        [weak self] notification in
+        guard let self, self.window?.isKeyWindow == true,
+          let group = notification.object as? String
+        else { return }
+        self.requestCloseTabGroup(group, anchor: self.tabManager.selectedIndex)
+      }
+    )
     notificationObservers.append(
       nc.addObserver(forName: .impulseSelectTab, object: nil, queue: .main) {
         [weak self] notification in
@@ -1937,6 +2283,20 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
       }
     )
 
+    // Files dropped on an editor open in tabs, leaving the file tree alone.
+    notificationObservers.append(
+      nc.addObserver(forName: .editorFilesDropped, object: nil, queue: .main) {
+        [weak self] notification in
+        guard let self,
+          self.ownedEditor(from: notification) != nil,
+          let paths = notification.userInfo?["paths"] as? [String]
+        else { return }
+        for path in paths {
+          self.openFile(path: path, revealInTree: false)
+        }
+      }
+    )
+
     // LSP: formatting requested
     notificationObservers.append(
       nc.addObserver(forName: .editorFormattingRequested, object: nil, queue: .main) {
@@ -2049,7 +2409,7 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
 
     // Fetch the latest content from Monaco (content changes are debounced
     // in JS, so the Swift property may be stale when saving via menu Cmd+S).
-    editor.fetchContentAndSave { [weak self, weak editor] success in
+    editor.fetchContentAndSave(whitespace: saveWhitespace) { [weak self, weak editor] success in
       guard let self, let editor, success else { return }
 
       // Format on save — find applicable formatter
@@ -2112,8 +2472,9 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
       }
     }
 
-    // Commands on save: run any matching commands
-    for cmd in settings.commandsOnSave {
+    // Commands on save: run any matching commands (trusted folders only)
+    let commandsOnSave = ImpulseCore.isTrusted(path: path) ? settings.commandsOnSave : []
+    for cmd in commandsOnSave {
       guard !cmd.command.isEmpty else { continue }
       guard Settings.matchesFilePattern(path, pattern: cmd.filePattern) else { continue }
       let cwd = (path as NSString).deletingLastPathComponent
@@ -2200,6 +2561,12 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
     }
   }
 
+  /// The whitespace fixes the settings ask for on save; `.editorconfig`
+  /// can still override them per file.
+  private var saveWhitespace: (trim: Bool, finalNewline: Bool) {
+    (settings.trimTrailingWhitespace, settings.insertFinalNewline)
+  }
+
   /// Resolves the `FormatOnSave` configuration for a file path, checking
   /// file-type overrides first, then falling back to the global setting.
   private func resolveFormatOnSave(forPath path: String) -> FormatOnSave? {
@@ -2248,6 +2615,7 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
         process.arguments = [command] + args
       }
       process.currentDirectoryURL = URL(fileURLWithPath: cwd)
+      process.environment = ImpulseCore.environment(directory: cwd)
       process.standardOutput = FileHandle.nullDevice
       process.standardError = FileHandle.nullDevice
 
@@ -2294,6 +2662,23 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
     tabManager.addTerminalTab(directory: cwd, initialCommand: fullCommand)
   }
 
+  /// What the active tab is, for the command registry's `when`.
+  func commandContext() -> CommandContext {
+    var context = CommandContext()
+    switch tabManager.selectedTab {
+    case .terminal:
+      context.terminal = true
+    case .editor(let editor):
+      context.editor = true
+      context.file = editor.filePath.map { !$0.isEmpty } ?? false
+    case .imagePreview:
+      context.file = true
+    case .diffReview, .none:
+      break
+    }
+    return context
+  }
+
   /// Returns the current working directory from the active tab:
   /// terminal CWD, or the parent directory of the active editor file.
   private func getActiveCwd() -> String? {
@@ -2356,6 +2741,89 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
     }
   }
 
+  // MARK: - Local History
+
+  /// Lists the saved versions of the active editor's file. Compare opens the
+  /// version against the file on disk; Restore puts it into the editor as one
+  /// undoable edit, leaving the tab modified.
+  private func showLocalHistory() {
+    guard let editor = tabManager.selectedEditor, let path = editor.filePath,
+      let window = self.window
+    else { return }
+    let entries = ImpulseCore.localHistoryEntries(path: path)
+    let name = (path as NSString).lastPathComponent
+
+    let alert = NSAlert()
+    alert.messageText = "Local History"
+    guard !entries.isEmpty else {
+      alert.informativeText = "No saved versions of \u{201C}\(name)\u{201D} yet."
+      alert.addButton(withTitle: "OK")
+      alert.beginSheetModal(for: window, completionHandler: nil)
+      return
+    }
+
+    let formatter = DateFormatter()
+    formatter.dateStyle = .medium
+    formatter.timeStyle = .medium
+    let popup = NSPopUpButton(frame: NSRect(x: 0, y: 0, width: 320, height: 26), pullsDown: false)
+    for entry in entries {
+      let size = ByteCountFormatter.string(fromByteCount: Int64(entry.size), countStyle: .file)
+      popup.addItem(withTitle: "\(formatter.string(from: entry.savedAt))  (\(size))")
+    }
+    alert.informativeText =
+      "Saved versions of \u{201C}\(name)\u{201D}, newest first. Restoring replaces the editor's text; save to keep it."
+    alert.accessoryView = popup
+    alert.addButton(withTitle: "Compare")
+    alert.addButton(withTitle: "Restore")
+    alert.addButton(withTitle: "Cancel")
+
+    alert.beginSheetModal(for: window) { [weak self, weak editor] response in
+      guard let self, let editor else { return }
+      let entry = entries[max(0, popup.indexOfSelectedItem)]
+      let time = formatter.string(from: entry.savedAt)
+      switch response {
+      case .alertFirstButtonReturn:
+        guard let version = ImpulseCore.localHistoryVersionPath(path: path, id: entry.id) else {
+          NSSound.beep()
+          return
+        }
+        self.tabManager.addFileCompareTab(
+          old: version, new: path, title: "\(name) (\(time)) \u{2194} \(name)")
+      case .alertSecondButtonReturn:
+        switch ImpulseCore.readLocalHistory(path: path, id: entry.id) {
+        case .success(let text):
+          editor.restoreContent(text)
+        case .failure(let error):
+          let failAlert = NSAlert()
+          failAlert.messageText = "Could Not Restore Version"
+          failAlert.informativeText = error.message
+          failAlert.alertStyle = .warning
+          failAlert.addButton(withTitle: "OK")
+          failAlert.beginSheetModal(for: window, completionHandler: nil)
+        }
+      default:
+        break
+      }
+    }
+  }
+
+  /// Replaces the active editor's text with the saved file. Undo brings the
+  /// discarded changes back.
+  private func revertActiveFile() {
+    guard let editor = tabManager.selectedEditor, let path = editor.filePath else { return }
+    do {
+      try editor.revertToSaved()
+      tabManager.refreshSegmentLabels()
+    } catch {
+      let alert = NSAlert()
+      alert.messageText = "Could Not Revert \u{201C}\((path as NSString).lastPathComponent)\u{201D}"
+      alert.informativeText = error.localizedDescription
+      alert.alertStyle = .warning
+      alert.addButton(withTitle: "OK")
+      if let window { alert.beginSheetModal(for: window, completionHandler: nil) }
+    }
+  }
+
   // MARK: - Go to Line
 
   /// Shows a dialog asking for a line number and navigates the active editor to it.
@@ -2402,56 +2870,68 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
     editor.focus()
   }
 
-  // MARK: - Font Size
-
-  /// Changes both editor and terminal font sizes by the given delta.
-  private func changeFontSize(delta: Int) {
-    let newEditorSize = max(6, min(72, settings.fontSize + delta))
-    let newTerminalSize = max(6, min(72, settings.terminalFontSize + delta))
+  // MARK: - Zoom
 
-    settings.fontSize = newEditorSize
-    settings.terminalFontSize = newTerminalSize
-    syncSettingsToAppDelegate()
-    applyFontSizeToAllTabs()
+  enum ZoomChange {
+    case `in`, out, reset
   }
 
-  /// Resets font sizes to defaults (14 for both editor and terminal).
-  private func resetFontSize() {
-    settings.fontSize = 14
-    settings.terminalFontSize = 14
-    syncSettingsToAppDelegate()
-    applyFontSizeToAllTabs()
+  /// Zooms the selected terminal or editor tab only; the font size
+  /// settings stay the default every tab is zoomed from.
+  private func zoomSelectedTab(_ change: ZoomChange) {
+    func next(base: Int, zoom: Int) -> Int {
+      switch change {
+      case .in: return ImpulseCore.zoomStep(base: base, zoom: zoom, delta: 1)
+      case .out: return ImpulseCore.zoomStep(base: base, zoom: zoom, delta: -1)
+      case .reset: return 0
+      }
+    }
+    if let terminal = tabManager.selectedTerminal?.activeTerminal {
+      terminal.setZoom(next(base: settings.terminalFontSize, zoom: terminal.zoom))
+    } else if let editor = tabManager.selectedEditor {
+      editor.setZoom(next(base: editor.baseFontSize, zoom: editor.zoom))
+    } else {
+      return
+    }
+    windowModel.zoomLabel = selectedTabZoomLabel()
   }
 
-  /// Copies the current window's settings back to the AppDelegate so they
-  /// persist across quit/relaunch.
-  private func syncSettingsToAppDelegate() {
-    if let delegate = NSApp.delegate as? AppDelegate {
-      delegate.settings.fontSize = settings.fontSize
-      delegate.settings.terminalFontSize = settings.terminalFontSize
+  /// Status bar text for the selected tab's zoom, nil at the default size.
+  private func selectedTabZoomLabel() -> String? {
+    if let terminal = tabManager.selectedTerminal?.activeTerminal {
+      return ImpulseCore.zoomLabel(base: settings.terminalFontSize, zoom: terminal.zoom)
+    } else if let editor = tabManager.selectedEditor {
+      return ImpulseCore.zoomLabel(base: editor.baseFontSize, zoom: editor.zoom)
     }
+    return nil
   }
 
-  /// Applies the current font size settings to all open tabs.
-  private func applyFontSizeToAllTabs() {
-    // Build EditorOptions with updated font size from our local settings,
-    // since TabManager's settings copy may not reflect the change yet.
-    let editorOptions = EditorOptions(
-      fontSize: UInt32(settings.fontSize),
-      fontFamily: settings.fontFamily
-    )
-    let termSettings = settings.terminalSettings()
-
-    for tab in tabManager.tabs {
-      switch tab {
-      case .editor(let editor):
-        editor.applySettings(editorOptions)
-      case .terminal(let container):
-        container.applySettings(settings: termSettings)
-      case .imagePreview, .diffReview:
-        break
+  /// Installs a local event monitor that zooms the selected tab on
+  /// Cmd+scroll over this window instead of scrolling it.
+  private func setupScrollZoomMonitor() {
+    teardownScrollZoomMonitor()
+    scrollZoomMonitor = NSEvent.addLocalMonitorForEvents(matching: .scrollWheel) {
+      [weak self] event in
+      guard let self, event.window === self.window,
+        event.modifierFlags.contains(.command), event.scrollingDeltaY != 0
+      else { return event }
+      // Trackpads send many small deltas; a wheel notch is one step.
+      let step: CGFloat = event.hasPreciseScrollingDeltas ? 24 : 1
+      self.scrollZoomRemainder += event.scrollingDeltaY
+      while abs(self.scrollZoomRemainder) >= step {
+        self.zoomSelectedTab(self.scrollZoomRemainder > 0 ? .in : .out)
+        self.scrollZoomRemainder -= self.scrollZoomRemainder > 0 ? step : -step
       }
+      return nil
+    }
+  }
+
+  private func teardownScrollZoomMonitor() {
+    if let monitor = scrollZoomMonitor {
+      NSEvent.removeMonitor(monitor)
+      scrollZoomMonitor = nil
     }
+    scrollZoomRemainder = 0
   }
 
   // MARK: - Apply All Settings
@@ -2460,12 +2940,14 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
   /// via the preferences window.
   private func applyAllSettings() {
     let editorOptions = tabManager.editorOptionsFromSettings()
+    let editorKeybindings = settings.editorKeybindings
     let termSettings = settings.terminalSettings()
 
     for tab in tabManager.tabs {
       switch tab {
       case .editor(let editor):
         editor.applySettings(editorOptions)
+        editor.applyKeybindings(editorKeybindings)
       case .terminal(let container):
         container.applySettings(settings: termSettings)
       case .imagePreview, .diffReview:
@@ -2473,6 +2955,15 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
       }
     }
 
+    if windowModel.contentMaxWidth != CGFloat(settings.zenModeMaxWidth) {
+      windowModel.contentMaxWidth = CGFloat(settings.zenModeMaxWidth)
+    }
+    let statusBarSegments = ImpulseCore.statusBarSegments(
+      order: settings.statusBarSegments, hidden: settings.statusBarHiddenSegments)
+    if windowModel.statusBarSegments != statusBarSegments {
+      windowModel.statusBarSegments = statusBarSegments
+    }
+
     // Re-apply tab strip placement (sidebar vertical list vs top bar).
     if windowModel.tabBarPosition != settings.tabBarPosition {
       windowModel.tabBarPosition = settings.tabBarPosition
@@ -2628,8 +3119,23 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
     tabManager.sessionWindowState(projectRoot: fileTreeRootPath)
   }
 
+  /// The window state with its sidebar, for a saved window layout.
+  func windowLayoutState() -> SessionWindowState {
+    var state = sessionWindowState()
+    state.sidebar = SessionSidebarState(
+      visible: zenSavedLayout?.sidebarVisible ?? windowModel.sidebarVisible,
+      width: Int(currentSidebarWidth()))
+    return state
+  }
+
   @discardableResult
   func restoreSessionWindow(_ state: SessionWindowState) -> Bool {
+    if let sidebar = state.sidebar, sidebar.width > 0 {
+      sidebarTargetWidth = CGFloat(sidebar.width)
+      windowModel.sidebarWidth = sidebarTargetWidth
+      setSidebarVisible(sidebar.visible)
+    }
+
     if let projectRoot = state.projectRoot,
       FileManager.default.fileExists(atPath: projectRoot)
     {
@@ -2642,10 +3148,12 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
       case "editor":
         if let path = tab.path, FileManager.default.fileExists(atPath: path) {
           tabManager.addEditorTab(path: path, projectDirectory: state.projectRoot)
+          if tab.pinned { tabManager.pin(index: tabManager.selectedIndex) }
           restoredAny = true
         }
       case "terminal":
         tabManager.addRestoredTerminalTab(tab)
+        if tab.pinned { tabManager.pin(index: tabManager.selectedIndex) }
         restoredAny = true
       default:
         continue
@@ -2685,7 +3193,16 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
     }
   }
 
-  private func dirtyEditors() -> [EditorTab] {
+  /// Saves every modified editor that has a file. Untitled editors are
+  /// left alone, since each needs a name from Save As first.
+  func saveAllEditors() {
+    for editor in dirtyEditors() where editor.filePath != nil {
+      saveEditorTab(editor)
+    }
+  }
+
+  /// Editor tabs with unsaved changes, in tab order.
+  func dirtyEditors() -> [EditorTab] {
     tabManager.tabs.compactMap { tab in
       if case .editor(let editor) = tab, editor.isModified {
         return editor
@@ -2694,17 +3211,21 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
     }
   }
 
+  /// Processes running in this window's terminals that closing them should
+  /// warn about; none when `terminal_confirm_close` is off.
   func runningTerminalProcessCount() -> Int {
-    tabManager.tabs.reduce(0) { count, tab in
+    guard settings.terminalConfirmClose else { return 0 }
+    return tabManager.tabs.reduce(0) { count, tab in
       if case .terminal(let container) = tab {
-        return count + container.runningDescendantProcessCount()
+        return count + container.runningProcessCount(ignoring: settings.terminalCloseIgnoredCommands)
       }
       return count
     }
   }
 
   func runningCloseRiskCommands() -> [CloseRiskCommand] {
-    tabManager.tabs.flatMap { tab in
+    guard settings.terminalConfirmClose else { return [] }
+    return tabManager.tabs.flatMap { tab in
       if case .terminal(let container) = tab {
         return container.runningCloseRiskCommands()
       }
@@ -2724,14 +3245,17 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
   private func closeRiskSummary(
     action: CloseRiskAction,
     unsavedEditorCount: Int,
+    unsavedFiles: [String] = [],
     runningTerminalProcessCount: Int,
     runningCommands: [CloseRiskCommand]
   ) -> CloseRiskSummary? {
     let input = CloseRiskInput(
       action: action,
       unsavedEditorCount: unsavedEditorCount,
+      unsavedFiles: unsavedFiles,
       runningTerminalProcessCount: runningTerminalProcessCount,
       runningCommands: runningCommands,
+      ignoredCommands: settings.terminalCloseIgnoredCommands,
       nowMs: currentUnixTimeMs(),
       longCommandThresholdSeconds: UInt64(max(1, settings.terminalLongCommandSeconds))
     )
@@ -2764,41 +3288,82 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
     return "\(summary.informativeText)\n\n\(details)"
   }
 
+  /// Asks once about every dirty editor before the window closes: one
+  /// checkbox per file, then Save & Close, Don't Save or Cancel. Checked
+  /// files are saved in turn (untitled ones through Save As) and the window
+  /// only closes once all of them are saved.
   private func reviewDirtyEditorsBeforeWindowClose(_ dirty: [EditorTab]) {
-    reviewingDirtyWindowClose = true
-    var remaining = dirty
+    guard let window = self.window,
+      let summary = closeRiskSummary(
+        action: .closeWindow,
+        unsavedEditorCount: dirty.count,
+        unsavedFiles: dirty.map { $0.filePath ?? "Untitled" },
+        runningTerminalProcessCount: 0,
+        runningCommands: []
+      )
+    else { return }
 
-    func next() {
-      guard !remaining.isEmpty else {
+    let checkboxes = dirty.indices.map { index -> NSButton in
+      let title =
+        index < summary.unsavedFiles.count
+        ? summary.unsavedFiles[index].displayName : (dirty[index].filePath ?? "Untitled")
+      let checkbox = NSButton(checkboxWithTitle: title, target: nil, action: nil)
+      checkbox.state = .on
+      checkbox.toolTip = dirty[index].filePath
+      return checkbox
+    }
+    let stack = NSStackView(views: checkboxes)
+    stack.orientation = .vertical
+    stack.alignment = .leading
+    stack.spacing = 6
+    stack.frame.size = stack.fittingSize
+
+    let alert = NSAlert()
+    alert.messageText = summary.title
+    alert.informativeText = "\(summary.informativeText) Uncheck any files you don't want to save."
+    alert.alertStyle = .warning
+    alert.accessoryView = stack
+    alert.addButton(withTitle: "Save & Close")
+    alert.addButton(withTitle: "Don't Save")
+    alert.addButton(withTitle: "Cancel")
+
+    reviewingDirtyWindowClose = true
+    alert.beginSheetModal(for: window) { [weak self] response in
+      guard let self else { return }
+      let finish: () -> Void = {
         self.reviewingDirtyWindowClose = false
         self.closingAfterDirtyReview = true
         self.window?.close()
-        return
-      }
-
-      let editor = remaining.removeFirst()
-      guard
-        let tabIndex = self.tabManager.tabs.firstIndex(where: {
-          if case .editor(let candidate) = $0 { return candidate === editor }
-          return false
-        })
-      else {
-        next()
-        return
       }
-
-      self.tabManager.selectTab(index: tabIndex)
-      self.reviewAndSave(editor: editor) { proceed in
-        if proceed {
-          DispatchQueue.main.async { next() }
-        } else {
-          self.reviewingDirtyWindowClose = false
-          self.closingAfterDirtyReview = false
+      switch response {
+      case .alertFirstButtonReturn:
+        var remaining = zip(dirty, checkboxes).filter { $0.1.state == .on }.map { $0.0 }
+        func next() {
+          guard !remaining.isEmpty else {
+            finish()
+            return
+          }
+          let editor = remaining.removeFirst()
+          let saved: (Bool) -> Void = { success in
+            if success {
+              DispatchQueue.main.async { next() }
+            } else {
+              self.reviewingDirtyWindowClose = false
+            }
+          }
+          if editor.filePath != nil {
+            editor.fetchContentAndSave(whitespace: self.saveWhitespace, completion: saved)
+          } else {
+            self.showSaveAsDialog(for: editor, completion: saved)
+          }
         }
+        next()
+      case .alertSecondButtonReturn:
+        finish()
+      default:
+        self.reviewingDirtyWindowClose = false
       }
     }
-
-    next()
   }
 
   private func currentSidebarWidth() -> CGFloat {
@@ -2860,12 +3425,16 @@ final class MainWindowController: NSWindowController, NSWindowDelegate, NSToolba
 
   func windowWillClose(_ notification: Notification) {
     teardownCustomKeybindingMonitor()
+    teardownScrollZoomMonitor()
 
-    // Persist restorable window state before tab cleanup clears it.
-    if let delegate = NSApp.delegate as? AppDelegate {
+    // Persist restorable window state before tab cleanup clears it. A
+    // window whose last tab was dragged away has nothing to keep.
+    if !tabManager.tabs.isEmpty, let delegate = NSApp.delegate as? AppDelegate {
       sidebarTargetWidth = currentSidebarWidth()
       windowModel.sidebarWidth = sidebarTargetWidth
-      delegate.settings.sidebarVisible = windowModel.sidebarVisible
+      // Zen mode hides the sidebar temporarily; keep the user's choice.
+      delegate.settings.sidebarVisible =
+        zenSavedLayout?.sidebarVisible ?? windowModel.sidebarVisible
       delegate.settings.sidebarWidth = Int(sidebarTargetWidth)
       delegate.persistSessionStateFromOpenWindows()
     }
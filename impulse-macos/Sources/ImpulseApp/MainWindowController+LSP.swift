@@ -49,6 +49,44 @@ extension MainWindowController {
       )
     }
     editorTab.applyDiagnostics(uri: uri, markers: markers)
+
+    let severities = diagnosticsArray.compactMap { ($0["severity"] as? NSNumber)?.uint8Value }
+    let errors = severities.filter { $0 == 1 }.count
+    let warnings = severities.filter { $0 == 2 }.count
+    problemCountsByUri[uri] = errors + warnings > 0 ? (errors, warnings) : nil
+    refreshProblemCounts()
+  }
+
+  /// Sums the per-file diagnostics into the status bar's problems segment.
+  func refreshProblemCounts() {
+    windowModel.problemErrorCount = problemCountsByUri.values.reduce(0) { $0 + $1.errors }
+    windowModel.problemWarningCount = problemCountsByUri.values.reduce(0) { $0 + $1.warnings }
+  }
+
+  /// Overrides the language of every open file sharing the selected file's
+  /// extension (`nil` returns them to detection), remembers the choice in
+  /// `language_overrides`, and reopens the files with the new language's
+  /// servers.
+  func setLanguageOverride(_ languageId: String?) {
+    guard let path = tabManager.selectedEditor?.filePath,
+      let key = ImpulseCore.languageOverrideKey(path: path),
+      let delegate = NSApp.delegate as? AppDelegate
+    else { return }
+    var settings = delegate.settings
+    settings.languageOverrides[key] = languageId
+    settings.save()
+    ImpulseCore.setLanguageOverrides(settings.languageOverrides)
+    SettingsWindowController.settingsReloaded(settings)
+    NotificationCenter.default.post(name: .impulseSettingsDidChange, object: settings)
+
+    for (filePath, editor) in editorTabsByPath
+    where ImpulseCore.languageOverrideKey(path: filePath) == key {
+      // Close with the old language's servers before switching.
+      lspDidClose(editor: editor)
+      editor.setLanguage(tabManager.detectLanguage(forPath: filePath))
+      lspDidOpenIfNeeded(path: filePath)
+    }
+    windowModel.currentLanguage = tabManager.selectedEditor?.language
   }
 
   /// Sends LSP didOpen for a file if not already tracked.
@@ -94,7 +132,10 @@ extension MainWindowController {
 
     let language = editor.lspLanguage
     let content = editor.content
-    let incrementalChanges = changes.map { change -> [String: Any] in
+    // Ranges refer to the text before the edit; send them last-to-first so
+    // they stay valid when applied in sequence.
+    let ordered = changes.sorted { $0.rangeOffset > $1.rangeOffset }
+    let incrementalChanges = ordered.map { change -> [String: Any] in
       [
         "range": [
           "start": [
@@ -113,13 +154,23 @@ extension MainWindowController {
 
     lspQueue.async { [weak self] in
       guard let self else { return }
-      self.core.lspDidChange(
+      let result = self.core.lspDidChange(
         languageId: language,
         fileUri: uri,
         version: Int32(version),
         fullText: incrementalChanges.isEmpty ? content : nil,
         changesJson: encodeLspJSON(incrementalChanges)
       )
+      // The core's copy of the document is out of sync; resend the buffer.
+      if result == -2 {
+        self.core.lspDidChange(
+          languageId: language,
+          fileUri: uri,
+          version: Int32(version),
+          fullText: content,
+          changesJson: "[]"
+        )
+      }
     }
   }
 
@@ -131,6 +182,9 @@ extension MainWindowController {
     lspOpenFiles.remove(uri)
     lspDocVersions.removeValue(forKey: uri)
     clearPendingLSPRequests(for: uri)
+    if problemCountsByUri.removeValue(forKey: uri) != nil {
+      refreshProblemCounts()
+    }
 
     let language = editor.lspLanguage
     lspQueue.async { [weak self] in
@@ -304,7 +358,8 @@ extension MainWindowController {
     }
   }
 
-  /// Handles a formatting request from the editor by forwarding it to the LSP.
+  /// Handles a formatting request from the editor by forwarding it to the LSP,
+  /// or to the external formatter chosen in settings.
   func handleFormattingRequest(
     editor: EditorTab, requestId: UInt64, tabSize: UInt32, insertSpaces: Bool
   ) {
@@ -315,6 +370,7 @@ extension MainWindowController {
     formattingWorkItems[uri]?.cancel()
 
     let language = editor.lspLanguage
+    let text = editor.content
     let params = encodeLspJSON([
       "textDocument": ["uri": uri],
       "options": ["tabSize": tabSize, "insertSpaces": insertSpaces] as [String: Any],
@@ -322,12 +378,20 @@ extension MainWindowController {
 
     let workItem = DispatchWorkItem { [weak self] in
       guard let self else { return }
-      guard
-        let response = self.core.lspRequest(
+      let mode = ImpulseCore.formatterMode(language: language, filePath: path)
+      var response: String?
+      if mode != "external" {
+        response = self.core.lspRequest(
           languageId: language, fileUri: uri,
           method: "textDocument/formatting", paramsJson: params
         )
-      else { return }
+      }
+      // No server formats this document: use the external formatter.
+      let lspFailed = response.map { $0.hasPrefix("{\"error\"") } ?? true
+      if mode == "external" || (mode == "lsp_or_fallback" && lspFailed) {
+        response = ImpulseCore.formatExternal(language: language, filePath: path, text: text)
+      }
+      guard let response else { return }
 
       let edits = self.parseFormattingResponse(response)
       DispatchQueue.main.async { [weak self] in
@@ -0,0 +1,72 @@
+import AppKit
+
+// MARK: - Workspace Trust
+
+/// The first file opened from a folder without a trust decision asks
+/// whether to trust it; until then the folder stays in restricted mode (no
+/// language servers, commands on save or `.impulse` settings).
+extension MainWindowController {
+
+  /// Folders already asked about this session, so dismissing the prompt
+  /// doesn't bring it back for every file.
+  private static var askedTrustFolders: Set<String> = []
+
+  /// Asks about `path`'s workspace folder if the user hasn't decided on it.
+  func promptForWorkspaceTrustIfNeeded(path: String) {
+    let folder = ImpulseCore.trustWorkspaceFolder(path: path)
+    guard ImpulseCore.trustState(path: folder) == .unknown,
+      Self.askedTrustFolders.insert(folder).inserted
+    else { return }
+    showWorkspaceTrustAlert(
+      folder: folder, title: "Trust This Folder?",
+      message:
+        "Until you trust it, language servers don't start, commands on save don't run, and its .impulse settings are ignored. Only trust folders whose contents you trust."
+    )
+  }
+
+  /// "Manage Workspace Trust": changes the decision for the current file's
+  /// folder, or the file tree's folder when no editor is selected.
+  func showWorkspaceTrust() {
+    let path = tabManager.selectedEditor?.filePath ?? fileTreeRootPath
+    guard !path.isEmpty else { return }
+    let folder = ImpulseCore.trustWorkspaceFolder(path: path)
+    let message: String
+    if (NSApp.delegate as? AppDelegate)?.settings.workspaceTrustEnabled == false {
+      message = "Workspace trust is turned off in Settings, so every folder is trusted."
+    } else if ImpulseCore.isTrusted(path: folder) {
+      message = "This folder is trusted."
+    } else {
+      message = "This folder is in restricted mode."
+    }
+    showWorkspaceTrustAlert(folder: folder, title: "Workspace Trust", message: message)
+  }
+
+  private func showWorkspaceTrustAlert(folder: String, title: String, message: String) {
+    guard let window else { return }
+    let alert = NSAlert()
+    alert.messageText = title
+    alert.informativeText = "\(folder)\n\n\(message)"
+    alert.addButton(withTitle: "Stay in Restricted Mode")
+    alert.addButton(withTitle: "Trust Folder")
+    alert.beginSheetModal(for: window) { [weak self] response in
+      guard let self else { return }
+      let trusted = response == .alertSecondButtonReturn
+      let restricted = self.lspOpenFiles.filter {
+        !ImpulseCore.isTrusted(path: self.uriToFilePath($0))
+      }
+      if let error = ImpulseCore.setTrust(folder: folder, trusted: trusted) {
+        NSLog("Failed to save workspace trust: %@", error)
+        NSSound.beep()
+        return
+      }
+      // Files opened while restricted never reached a language server;
+      // open them again now that they may.
+      for uri in restricted {
+        let path = self.uriToFilePath(uri)
+        guard ImpulseCore.isTrusted(path: path) else { continue }
+        self.lspOpenFiles.remove(uri)
+        self.lspDidOpenIfNeeded(path: path)
+      }
+    }
+  }
+}
@@ -10,6 +10,8 @@ extension Notification.Name {
     static let impulseThemeDidChange = Notification.Name("impulseThemeDidChange")
     /// Posted when settings are changed (e.g. from the settings window).
     static let impulseSettingsDidChange = Notification.Name("impulseSettingsDidChange")
+    /// Posted on the main thread after the notification history changes.
+    static let impulseNotificationsDidChange = Notification.Name("impulseNotificationsDidChange")
 
     // MARK: Tab Management
 
@@ -19,6 +21,8 @@ extension Notification.Name {
     static let impulseNewTerminalTab = Notification.Name("impulseNewTerminalTab")
     /// Requests a new untitled editor tab in the frontmost window.
     static let impulseNewFile = Notification.Name("impulseNewFile")
+    /// Posted after an editor tab closes; `object` is the file path.
+    static let impulseEditorDidClose = Notification.Name("impulseEditorDidClose")
     /// Requests closing the current tab in the frontmost window.
     static let impulseCloseTab = Notification.Name("impulseCloseTab")
     /// Requests reopening the most recently closed tab.
@@ -27,6 +31,14 @@ extension Notification.Name {
     static let impulseNextTab = Notification.Name("impulseNextTab")
     /// Requests switching to the previous tab.
     static let impulsePrevTab = Notification.Name("impulsePrevTab")
+    /// Requests the searchable list of every tab in the frontmost window.
+    static let impulseShowAllTabs = Notification.Name("impulseShowAllTabs")
+    /// Requests sorting the frontmost window's tabs; `object` is the core
+    /// sort name (`"path"` or `"recent_use"`).
+    static let impulseSortTabs = Notification.Name("impulseSortTabs")
+    /// Requests closing a group of tabs around the current one; `object` is
+    /// the core group name (`"saved"`, `"right"` or `"left"`).
+    static let impulseCloseTabGroup = Notification.Name("impulseCloseTabGroup")
     /// Requests switching to a specific tab by index (0-based in userInfo "index").
     static let impulseSelectTab = Notification.Name("impulseSelectTab")
 
@@ -49,6 +61,9 @@ extension Notification.Name {
     static let editorDefinitionRequested = Notification.Name("impulse.editorDefinitionRequested")
     /// Posted when Monaco wants to open a different file (cross-file definition).
     static let editorOpenFileRequested = Notification.Name("impulse.editorOpenFileRequested")
+    /// Posted when files are dropped on an editor. The `userInfo` dictionary
+    /// contains `"paths"` as a `[String]`.
+    static let editorFilesDropped = Notification.Name("impulse.editorFilesDropped")
     /// Posted when the editor focus state changes. The `userInfo` dictionary
     /// contains `"focused"` as a `Bool`.
     static let editorFocusChanged = Notification.Name("impulse.editorFocusChanged")
@@ -80,6 +95,8 @@ extension Notification.Name {
 
     /// Requests saving the current editor tab.
     static let impulseSaveFile = Notification.Name("impulseSaveFile")
+    /// Requests saving every modified editor in the key window.
+    static let impulseSaveAll = Notification.Name("impulseSaveAll")
     /// Requests toggling find in the terminal or editor.
     static let impulseFind = Notification.Name("impulseFind")
     /// Requests showing the go-to-line dialog.
@@ -88,13 +105,13 @@ extension Notification.Name {
     /// The `userInfo` dictionary contains `"path"` (String).
     static let impulseReloadEditorFile = Notification.Name("impulseReloadEditorFile")
 
-    // MARK: Editor Font
+    // MARK: Zoom
 
-    /// Requests increasing the editor and terminal font size.
+    /// Requests zooming in the selected tab.
     static let impulseFontIncrease = Notification.Name("impulseFontIncrease")
-    /// Requests decreasing the editor and terminal font size.
+    /// Requests zooming out the selected tab.
     static let impulseFontDecrease = Notification.Name("impulseFontDecrease")
-    /// Requests resetting the editor and terminal font size to defaults.
+    /// Requests resetting the selected tab's zoom to the settings font size.
     static let impulseFontReset = Notification.Name("impulseFontReset")
 
     // MARK: Terminal Events
@@ -121,6 +138,10 @@ extension Notification.Name {
 
     // MARK: UI Commands
 
+    /// Requests clearing the active terminal.
+    static let impulseClearTerminal = Notification.Name("impulseClearTerminal")
+    /// Requests the active terminal's command history picker.
+    static let impulseShowCommandHistory = Notification.Name("impulseShowCommandHistory")
     /// Requests toggling the sidebar.
     static let impulseToggleSidebar = Notification.Name("impulseToggleSidebar")
     /// Requests showing the command palette.
@@ -129,8 +150,37 @@ extension Notification.Name {
     static let impulseFindInProject = Notification.Name("impulseFindInProject")
     /// Requests toggling markdown preview in the active editor tab.
     static let impulseToggleMarkdownPreview = Notification.Name("impulseToggleMarkdownPreview")
+    /// Requests revealing the active editor's file in Finder.
+    static let impulseOpenContainingFolder = Notification.Name("impulseOpenContainingFolder")
+    /// Requests revealing the active file in the sidebar's file tree.
+    static let impulseRevealInSidebar = Notification.Name("impulseRevealInSidebar")
+    /// Requests copying the active file's path to the clipboard. The object is
+    /// the command id naming the form: path, relative path, file name or URL.
+    static let impulseCopyPath = Notification.Name("impulseCopyPath")
+    /// Requests opening the active terminal's scrollback in a read-only editor tab.
+    static let impulseOpenScrollbackInEditor = Notification.Name("impulseOpenScrollbackInEditor")
+    /// Requests locking or unlocking the active editor tab.
+    static let impulseToggleReadOnly = Notification.Name("impulseToggleReadOnly")
+    /// Requests the local history of the active editor's file.
+    static let impulseShowLocalHistory = Notification.Name("impulseShowLocalHistory")
+    /// Requests a diff of the active editor's saved file against its buffer.
+    static let impulseCompareWithSaved = Notification.Name("impulseCompareWithSaved")
+    /// Requests replacing the active editor's buffer with the saved file.
+    static let impulseRevertFile = Notification.Name("impulseRevertFile")
     /// Requests opening the Review Changes tab for the current workspace.
     static let impulseReviewChanges = Notification.Name("impulseReviewChanges")
+    /// Requests the workspace trust dialog for the current folder.
+    static let impulseManageWorkspaceTrust = Notification.Name("impulseManageWorkspaceTrust")
+    /// Requests opening the notification history in the frontmost window.
+    static let impulseShowNotifications = Notification.Name("impulseShowNotifications")
+    /// Requests entering or leaving Zen mode.
+    static let impulseToggleZenMode = Notification.Name("impulseToggleZenMode")
+    /// Requests showing or hiding the tab bar.
+    static let impulseToggleTabBar = Notification.Name("impulseToggleTabBar")
+    /// Requests showing or hiding the status bar.
+    static let impulseToggleStatusBar = Notification.Name("impulseToggleStatusBar")
+    /// Requests toggling the centered content layout.
+    static let impulseToggleCenteredLayout = Notification.Name("impulseToggleCenteredLayout")
 
     // MARK: File Tree
 
@@ -148,6 +198,8 @@ extension Notification.Name {
     static let impulseQuickOpen = Notification.Name("impulseQuickOpen")
     /// Requests installing managed web LSP servers.
     static let impulseInstallLsp = Notification.Name("impulseInstallLsp")
+    /// Requests re-reading the user's `editor.css` into open editors.
+    static let impulseReloadCustomCss = Notification.Name("impulseReloadCustomCss")
 
     // MARK: Update Notifications
 
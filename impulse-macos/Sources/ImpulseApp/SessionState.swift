@@ -92,16 +92,25 @@ struct SessionWindowState: Codable {
   var projectRoot: String?
   var tabs: [SessionTabState]
   var activeTabIndex: Int?
+  /// Only saved window layouts carry the sidebar; the session keeps it in
+  /// the settings.
+  var sidebar: SessionSidebarState?
   var layout: SessionLayoutState
 
   enum CodingKeys: String, CodingKey {
     case projectRoot = "project_root"
     case tabs
     case activeTabIndex = "active_tab_index"
+    case sidebar
     case layout
   }
 }
 
+struct SessionSidebarState: Codable {
+  var visible: Bool
+  var width: Int
+}
+
 struct SessionTabState: Codable {
   var kind: String
   var path: String?
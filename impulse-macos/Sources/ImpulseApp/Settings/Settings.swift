@@ -9,11 +9,6 @@ struct SettingsLoadWarning: Equatable {
     let message: String
 }
 
-private struct SettingsFileSnapshot: Equatable {
-    let path: String
-    let contentHash: String
-}
-
 /// A formatter command that runs on save before the editor reloads the file.
 struct FormatOnSave: Codable {
     var command: String
@@ -25,6 +20,25 @@ struct FormatOnSave: Codable {
     }
 }
 
+/// Starting content for new files matching `pattern`. `${name}` and
+/// `${filename}` in `content` become the new file's name without and with
+/// its extension.
+struct FileTemplate: Codable {
+    var pattern: String
+    var content: String
+
+    init(pattern: String = "", content: String = "") {
+        self.pattern = pattern
+        self.content = content
+    }
+
+    init(from decoder: Decoder) throws {
+        let c = try decoder.container(keyedBy: CodingKeys.self)
+        pattern = try c.decode(String.self, forKey: .pattern)
+        content = (try? c.decode(String.self, forKey: .content)) ?? ""
+    }
+}
+
 /// Per-file-type overrides for editor settings (tab width, spaces, formatter).
 struct FileTypeOverride: Codable {
     var pattern: String
@@ -100,13 +114,29 @@ struct Settings: Codable {
     var windowHeight: Int
     var sidebarVisible: Bool
     var sidebarWidth: Int
+    /// Maximum width in points of the centered content area in Zen mode.
+    var zenModeMaxWidth: Int
     var confirmCloseWarnings: Bool
     var restoreSession: Bool
+    var cliOpenInNewWindow: Bool
+    /// Listen on the control socket so scripts can drive the app.
+    var controlSocketEnabled: Bool
     var lastDirectory: String
     var openFiles: [String]
 
     // -- Editor --
     var autoSave: Bool
+    /// How files are saved: "atomic" (temporary file + rename) or
+    /// "in_place" for file systems and tools that track inodes.
+    var saveStrategy: String
+    /// Back up unsaved buffers every few seconds and offer to restore them
+    /// after a crash.
+    var hotExit: Bool
+    /// Strip spaces and tabs at line ends on save, unless `.editorconfig`
+    /// says otherwise.
+    var trimTrailingWhitespace: Bool
+    /// End saved files with a newline, unless `.editorconfig` says otherwise.
+    var insertFinalNewline: Bool
     var fontSize: Int
     var fontFamily: String
     var tabWidth: Int
@@ -137,11 +167,21 @@ struct Settings: Codable {
     var terminalFontFamily: String
     var terminalFontSize: Int
     var terminalCopyOnSelect: Bool
+    /// Preview pastes of several lines or `sudo` commands before they reach
+    /// the shell.
+    var terminalConfirmPaste: Bool
+    /// Where new terminal tabs start: "home", "workspace" or "current" (the
+    /// active tab's directory).
+    var terminalNewTabDirectory: String
     var terminalScrollOnOutput: Bool
     var terminalAllowHyperlink: Bool
     var terminalAllowNotifications: Bool
     var terminalAttentionOnLongCommand: Bool
     var terminalLongCommandSeconds: Int
+    /// Ask before closing a terminal whose shell is running something.
+    var terminalConfirmClose: Bool
+    /// Programs that never make closing a terminal ask, e.g. `less`.
+    var terminalCloseIgnoredCommands: [String]
     var terminalBoldIsBright: Bool
     /// Minimum WCAG contrast ratio (1–21) enforced between terminal cell
     /// foreground and background; 1 disables the adjustment.
@@ -154,11 +194,17 @@ struct Settings: Codable {
     /// Show the context bar (shell, cwd, git branch, last command status)
     /// below the terminal.
     var terminalContextBar: Bool
+    var terminalPalette: [String]
+    var terminalCursorColor: String
+    var terminalBackgroundOpacity: Double
 
     // -- Tabs --
     /// Where the tab strip lives: "sidebar" (Warp-style vertical list) or
     /// "top" (classic horizontal bar).
     var tabBarPosition: String
+    /// A single click in the file tree opens a preview tab that the next
+    /// single click replaces.
+    var previewTabs: Bool
 
     // -- Editor (additional) --
     var editorLineHeight: Int
@@ -170,22 +216,57 @@ struct Settings: Codable {
 
     // -- Sidebar --
     var sidebarShowHidden: Bool
+    /// Switching tabs expands the file tree to the active editor's file.
+    var sidebarAutoReveal: Bool
 
     // -- Appearance --
     var colorScheme: String
+    /// Switch between `lightColorScheme` and `darkColorScheme` with the
+    /// system appearance instead of always using `colorScheme`.
+    var followSystemAppearance: Bool
+    var lightColorScheme: String
+    var darkColorScheme: String
+    /// Use the theme's increased-contrast variant. The system Increase
+    /// Contrast option turns it on as well.
+    var increaseContrast: Bool
 
     // -- Custom commands --
     var commandsOnSave: [CommandOnSave]
+    var useDirenv: Bool
     var customKeybindings: [CustomKeybinding]
 
     // -- Keybinding overrides --
+    /// Keymap preset layered under `keybindingOverrides` ("default" for none).
+    var keymapPreset: String
+
+    // -- Status bar --
+    /// Segment IDs in display order; unlisted segments follow by default.
+    var statusBarSegments: [String]
+    var statusBarHiddenSegments: [String]
     var keybindingOverrides: [String: String]
 
     // -- Per-file-type overrides --
     var fileTypeOverrides: [FileTypeOverride]
+    /// Language id per `*.ext` (or extensionless file name), chosen from the
+    /// status bar's language menu.
+    var languageOverrides: [String: String]
+    /// Boilerplate for files created from the sidebar, first match wins.
+    var fileTemplates: [FileTemplate]
 
     // -- Updates --
     var checkForUpdates: Bool
+    var autoInstallUpdates: Bool
+    var workspaceTrustEnabled: Bool
+    var sendCrashReports: Bool
+    var crashReportEndpoint: String
+    var settingsSyncRepository: String
+
+    // -- Search --
+    var searchIndexEnabled: Bool
+    var filesExclude: [String]
+    var searchExclude: [String]
+    var followSymlinks: Bool
+    var scanNetworkMounts: Bool
 
     // MARK: CodingKeys (snake_case to match Linux JSON)
 
@@ -194,11 +275,18 @@ struct Settings: Codable {
         case windowHeight = "window_height"
         case sidebarVisible = "sidebar_visible"
         case sidebarWidth = "sidebar_width"
+        case zenModeMaxWidth = "zen_mode_max_width"
         case confirmCloseWarnings = "confirm_close_warnings"
         case restoreSession = "restore_session"
+        case cliOpenInNewWindow = "cli_open_in_new_window"
+        case controlSocketEnabled = "control_socket_enabled"
         case lastDirectory = "last_directory"
         case openFiles = "open_files"
         case autoSave = "auto_save"
+        case saveStrategy = "save_strategy"
+        case hotExit = "hot_exit"
+        case trimTrailingWhitespace = "trim_trailing_whitespace"
+        case insertFinalNewline = "insert_final_newline"
         case fontSize = "font_size"
         case fontFamily = "font_family"
         case tabWidth = "tab_width"
@@ -227,18 +315,26 @@ struct Settings: Codable {
         case terminalFontFamily = "terminal_font_family"
         case terminalFontSize = "terminal_font_size"
         case terminalCopyOnSelect = "terminal_copy_on_select"
+        case terminalConfirmPaste = "terminal_confirm_paste"
+        case terminalNewTabDirectory = "terminal_new_tab_directory"
         case terminalScrollOnOutput = "terminal_scroll_on_output"
         case terminalAllowHyperlink = "terminal_allow_hyperlink"
         case terminalAllowNotifications = "terminal_allow_notifications"
         case terminalAttentionOnLongCommand = "terminal_attention_on_long_command"
         case terminalLongCommandSeconds = "terminal_long_command_seconds"
+        case terminalConfirmClose = "terminal_confirm_close"
+        case terminalCloseIgnoredCommands = "terminal_close_ignored_commands"
         case terminalBoldIsBright = "terminal_bold_is_bright"
         case terminalMinimumContrast = "terminal_minimum_contrast"
         case terminalAllowOsc52Write = "terminal_allow_osc52_write"
         case terminalAllowOsc52Read = "terminal_allow_osc52_read"
         case terminalBlocks = "terminal_blocks"
         case terminalContextBar = "terminal_context_bar"
+        case terminalPalette = "terminal_palette"
+        case terminalCursorColor = "terminal_cursor_color"
+        case terminalBackgroundOpacity = "terminal_background_opacity"
         case tabBarPosition = "tab_bar_position"
+        case previewTabs = "preview_tabs"
         case editorLineHeight = "editor_line_height"
         case editorAutoClosingBrackets = "editor_auto_closing_brackets"
         case editorCursorSurroundingLines = "editor_cursor_surrounding_lines"
@@ -246,12 +342,33 @@ struct Settings: Codable {
         case editorOccurrencesHighlight = "editor_occurrences_highlight"
         case editorWordBasedSuggestions = "editor_word_based_suggestions"
         case sidebarShowHidden = "sidebar_show_hidden"
+        case sidebarAutoReveal = "sidebar_auto_reveal"
         case colorScheme = "color_scheme"
+        case followSystemAppearance = "follow_system_appearance"
+        case lightColorScheme = "light_color_scheme"
+        case darkColorScheme = "dark_color_scheme"
+        case increaseContrast = "increase_contrast"
         case commandsOnSave = "commands_on_save"
+        case useDirenv = "use_direnv"
         case customKeybindings = "custom_keybindings"
+        case keymapPreset = "keymap_preset"
+        case statusBarSegments = "status_bar_segments"
+        case statusBarHiddenSegments = "status_bar_hidden_segments"
         case keybindingOverrides = "keybinding_overrides"
         case fileTypeOverrides = "file_type_overrides"
+        case languageOverrides = "language_overrides"
+        case fileTemplates = "file_templates"
         case checkForUpdates = "check_for_updates"
+        case autoInstallUpdates = "auto_install_updates"
+        case workspaceTrustEnabled = "workspace_trust_enabled"
+        case sendCrashReports = "send_crash_reports"
+        case crashReportEndpoint = "crash_report_endpoint"
+        case settingsSyncRepository = "settings_sync_repository"
+        case searchIndexEnabled = "search_index_enabled"
+        case filesExclude = "files_exclude"
+        case searchExclude = "search_exclude"
+        case followSymlinks = "follow_symlinks"
+        case scanNetworkMounts = "scan_network_mounts"
     }
 
     // MARK: Defaults
@@ -331,11 +448,20 @@ struct Settings: Codable {
         windowHeight = (try? c.decode(Int.self, forKey: .windowHeight)) ?? d.windowHeight
         sidebarVisible = (try? c.decode(Bool.self, forKey: .sidebarVisible)) ?? d.sidebarVisible
         sidebarWidth = (try? c.decode(Int.self, forKey: .sidebarWidth)) ?? d.sidebarWidth
+        zenModeMaxWidth = (try? c.decode(Int.self, forKey: .zenModeMaxWidth)) ?? d.zenModeMaxWidth
         confirmCloseWarnings = (try? c.decode(Bool.self, forKey: .confirmCloseWarnings)) ?? d.confirmCloseWarnings
         restoreSession = (try? c.decode(Bool.self, forKey: .restoreSession)) ?? d.restoreSession
+        cliOpenInNewWindow = (try? c.decode(Bool.self, forKey: .cliOpenInNewWindow)) ?? d.cliOpenInNewWindow
+        controlSocketEnabled = (try? c.decode(Bool.self, forKey: .controlSocketEnabled)) ?? d.controlSocketEnabled
         lastDirectory = (try? c.decode(String.self, forKey: .lastDirectory)) ?? d.lastDirectory
         openFiles = (try? c.decode([String].self, forKey: .openFiles)) ?? d.openFiles
         autoSave = (try? c.decode(Bool.self, forKey: .autoSave)) ?? d.autoSave
+        saveStrategy = (try? c.decode(String.self, forKey: .saveStrategy)) ?? d.saveStrategy
+        hotExit = (try? c.decode(Bool.self, forKey: .hotExit)) ?? d.hotExit
+        trimTrailingWhitespace =
+            (try? c.decode(Bool.self, forKey: .trimTrailingWhitespace)) ?? d.trimTrailingWhitespace
+        insertFinalNewline =
+            (try? c.decode(Bool.self, forKey: .insertFinalNewline)) ?? d.insertFinalNewline
         fontSize = (try? c.decode(Int.self, forKey: .fontSize)) ?? d.fontSize
         fontFamily = (try? c.decode(String.self, forKey: .fontFamily)) ?? d.fontFamily
         tabWidth = (try? c.decode(Int.self, forKey: .tabWidth)) ?? d.tabWidth
@@ -364,18 +490,28 @@ struct Settings: Codable {
         terminalFontFamily = (try? c.decode(String.self, forKey: .terminalFontFamily)) ?? d.terminalFontFamily
         terminalFontSize = (try? c.decode(Int.self, forKey: .terminalFontSize)) ?? d.terminalFontSize
         terminalCopyOnSelect = (try? c.decode(Bool.self, forKey: .terminalCopyOnSelect)) ?? d.terminalCopyOnSelect
+        terminalConfirmPaste = (try? c.decode(Bool.self, forKey: .terminalConfirmPaste)) ?? d.terminalConfirmPaste
+        terminalNewTabDirectory =
+            (try? c.decode(String.self, forKey: .terminalNewTabDirectory)) ?? d.terminalNewTabDirectory
         terminalScrollOnOutput = (try? c.decode(Bool.self, forKey: .terminalScrollOnOutput)) ?? d.terminalScrollOnOutput
         terminalAllowHyperlink = (try? c.decode(Bool.self, forKey: .terminalAllowHyperlink)) ?? d.terminalAllowHyperlink
         terminalAllowNotifications = (try? c.decode(Bool.self, forKey: .terminalAllowNotifications)) ?? d.terminalAllowNotifications
         terminalAttentionOnLongCommand = (try? c.decode(Bool.self, forKey: .terminalAttentionOnLongCommand)) ?? d.terminalAttentionOnLongCommand
         terminalLongCommandSeconds = (try? c.decode(Int.self, forKey: .terminalLongCommandSeconds)) ?? d.terminalLongCommandSeconds
+        terminalConfirmClose = (try? c.decode(Bool.self, forKey: .terminalConfirmClose)) ?? d.terminalConfirmClose
+        terminalCloseIgnoredCommands =
+            (try? c.decode([String].self, forKey: .terminalCloseIgnoredCommands)) ?? d.terminalCloseIgnoredCommands
         terminalBoldIsBright = (try? c.decode(Bool.self, forKey: .terminalBoldIsBright)) ?? d.terminalBoldIsBright
         terminalMinimumContrast = (try? c.decode(Double.self, forKey: .terminalMinimumContrast)) ?? d.terminalMinimumContrast
         terminalAllowOsc52Write = (try? c.decode(Bool.self, forKey: .terminalAllowOsc52Write)) ?? d.terminalAllowOsc52Write
         terminalAllowOsc52Read = (try? c.decode(Bool.self, forKey: .terminalAllowOsc52Read)) ?? d.terminalAllowOsc52Read
         terminalBlocks = (try? c.decode(Bool.self, forKey: .terminalBlocks)) ?? d.terminalBlocks
         terminalContextBar = (try? c.decode(Bool.self, forKey: .terminalContextBar)) ?? d.terminalContextBar
+        terminalPalette = (try? c.decode([String].self, forKey: .terminalPalette)) ?? d.terminalPalette
+        terminalCursorColor = (try? c.decode(String.self, forKey: .terminalCursorColor)) ?? d.terminalCursorColor
+        terminalBackgroundOpacity = (try? c.decode(Double.self, forKey: .terminalBackgroundOpacity)) ?? d.terminalBackgroundOpacity
         tabBarPosition = (try? c.decode(String.self, forKey: .tabBarPosition)) ?? d.tabBarPosition
+        previewTabs = (try? c.decode(Bool.self, forKey: .previewTabs)) ?? d.previewTabs
         editorLineHeight = (try? c.decode(Int.self, forKey: .editorLineHeight)) ?? d.editorLineHeight
         editorAutoClosingBrackets = (try? c.decode(String.self, forKey: .editorAutoClosingBrackets)) ?? d.editorAutoClosingBrackets
         editorCursorSurroundingLines = (try? c.decode(Int.self, forKey: .editorCursorSurroundingLines)) ?? d.editorCursorSurroundingLines
@@ -383,19 +519,43 @@ struct Settings: Codable {
         editorOccurrencesHighlight = (try? c.decode(Bool.self, forKey: .editorOccurrencesHighlight)) ?? d.editorOccurrencesHighlight
         editorWordBasedSuggestions = (try? c.decode(String.self, forKey: .editorWordBasedSuggestions)) ?? d.editorWordBasedSuggestions
         sidebarShowHidden = (try? c.decode(Bool.self, forKey: .sidebarShowHidden)) ?? d.sidebarShowHidden
+        sidebarAutoReveal = (try? c.decode(Bool.self, forKey: .sidebarAutoReveal)) ?? d.sidebarAutoReveal
         colorScheme = (try? c.decode(String.self, forKey: .colorScheme)) ?? d.colorScheme
+        followSystemAppearance = (try? c.decode(Bool.self, forKey: .followSystemAppearance)) ?? d.followSystemAppearance
+        lightColorScheme = (try? c.decode(String.self, forKey: .lightColorScheme)) ?? d.lightColorScheme
+        darkColorScheme = (try? c.decode(String.self, forKey: .darkColorScheme)) ?? d.darkColorScheme
+        increaseContrast = (try? c.decode(Bool.self, forKey: .increaseContrast)) ?? d.increaseContrast
         commandsOnSave = (try? c.decode([CommandOnSave].self, forKey: .commandsOnSave)) ?? d.commandsOnSave
+        useDirenv = (try? c.decode(Bool.self, forKey: .useDirenv)) ?? d.useDirenv
         customKeybindings = (try? c.decode([CustomKeybinding].self, forKey: .customKeybindings)) ?? d.customKeybindings
+        keymapPreset = (try? c.decode(String.self, forKey: .keymapPreset)) ?? d.keymapPreset
+        statusBarSegments = (try? c.decode([String].self, forKey: .statusBarSegments)) ?? d.statusBarSegments
+        statusBarHiddenSegments = (try? c.decode([String].self, forKey: .statusBarHiddenSegments)) ?? d.statusBarHiddenSegments
         keybindingOverrides = (try? c.decode([String: String].self, forKey: .keybindingOverrides)) ?? d.keybindingOverrides
         fileTypeOverrides = (try? c.decode([FileTypeOverride].self, forKey: .fileTypeOverrides)) ?? d.fileTypeOverrides
+        languageOverrides = (try? c.decode([String: String].self, forKey: .languageOverrides)) ?? d.languageOverrides
+        fileTemplates = (try? c.decode([FileTemplate].self, forKey: .fileTemplates)) ?? d.fileTemplates
         checkForUpdates = (try? c.decode(Bool.self, forKey: .checkForUpdates)) ?? d.checkForUpdates
+        autoInstallUpdates = (try? c.decode(Bool.self, forKey: .autoInstallUpdates)) ?? d.autoInstallUpdates
+        workspaceTrustEnabled = (try? c.decode(Bool.self, forKey: .workspaceTrustEnabled)) ?? d.workspaceTrustEnabled
+        sendCrashReports = (try? c.decode(Bool.self, forKey: .sendCrashReports)) ?? d.sendCrashReports
+        crashReportEndpoint = (try? c.decode(String.self, forKey: .crashReportEndpoint)) ?? d.crashReportEndpoint
+        settingsSyncRepository = (try? c.decode(String.self, forKey: .settingsSyncRepository)) ?? d.settingsSyncRepository
+        searchIndexEnabled = (try? c.decode(Bool.self, forKey: .searchIndexEnabled)) ?? d.searchIndexEnabled
+        filesExclude = (try? c.decode([String].self, forKey: .filesExclude)) ?? d.filesExclude
+        searchExclude = (try? c.decode([String].self, forKey: .searchExclude)) ?? d.searchExclude
+        followSymlinks = (try? c.decode(Bool.self, forKey: .followSymlinks)) ?? d.followSymlinks
+        scanNetworkMounts = (try? c.decode(Bool.self, forKey: .scanNetworkMounts)) ?? d.scanNetworkMounts
     }
 
     /// Memberwise initializer used by `Settings.default`.
     init(windowWidth: Int, windowHeight: Int, sidebarVisible: Bool, sidebarWidth: Int,
-         confirmCloseWarnings: Bool,
-         restoreSession: Bool,
-         lastDirectory: String, openFiles: [String], autoSave: Bool, fontSize: Int,
+         zenModeMaxWidth: Int = 960, confirmCloseWarnings: Bool,
+         restoreSession: Bool, cliOpenInNewWindow: Bool = false, controlSocketEnabled: Bool = true,
+         lastDirectory: String, openFiles: [String], autoSave: Bool, saveStrategy: String = "atomic",
+         hotExit: Bool = true,
+         trimTrailingWhitespace: Bool = false, insertFinalNewline: Bool = false,
+         fontSize: Int,
          fontFamily: String, tabWidth: Int, useSpaces: Bool, showLineNumbers: Bool,
          showRightMargin: Bool, rightMarginPosition: Int, wordWrap: Bool,
          highlightCurrentLine: Bool, minimapEnabled: Bool, renderWhitespace: String,
@@ -405,29 +565,52 @@ struct Settings: Codable {
          terminalScrollback: Int, terminalCursorShape: String, terminalCursorBlink: Bool,
          terminalBell: Bool, terminalAttentionOnBell: Bool,
          terminalFontFamily: String, terminalFontSize: Int,
-         terminalCopyOnSelect: Bool, terminalScrollOnOutput: Bool,
+         terminalCopyOnSelect: Bool, terminalConfirmPaste: Bool = true,
+         terminalNewTabDirectory: String = "home",
+         terminalScrollOnOutput: Bool,
          terminalAllowHyperlink: Bool, terminalAllowNotifications: Bool,
          terminalAttentionOnLongCommand: Bool, terminalLongCommandSeconds: Int,
+         terminalConfirmClose: Bool = true,
+         terminalCloseIgnoredCommands: [String] = ["less", "more", "man", "top", "htop"],
          terminalBoldIsBright: Bool, terminalMinimumContrast: Double = 3.0,
          terminalAllowOsc52Write: Bool, terminalAllowOsc52Read: Bool,
          terminalBlocks: Bool = true, terminalContextBar: Bool = true,
-         tabBarPosition: String = "sidebar",
+         terminalPalette: [String] = [], terminalCursorColor: String = "",
+         terminalBackgroundOpacity: Double = 1.0,
+         tabBarPosition: String = "sidebar", previewTabs: Bool = true,
          editorLineHeight: Int, editorAutoClosingBrackets: String,
          editorCursorSurroundingLines: Int, editorSelectionHighlight: Bool,
          editorOccurrencesHighlight: Bool, editorWordBasedSuggestions: String,
-         sidebarShowHidden: Bool, colorScheme: String,
+         sidebarShowHidden: Bool, sidebarAutoReveal: Bool = false, colorScheme: String,
          commandsOnSave: [CommandOnSave], customKeybindings: [CustomKeybinding],
          keybindingOverrides: [String: String], fileTypeOverrides: [FileTypeOverride],
-         checkForUpdates: Bool) {
+         languageOverrides: [String: String] = [:], fileTemplates: [FileTemplate] = [],
+         checkForUpdates: Bool, autoInstallUpdates: Bool = false,
+         workspaceTrustEnabled: Bool = true, useDirenv: Bool = false, sendCrashReports: Bool = false,
+         crashReportEndpoint: String = "", settingsSyncRepository: String = "",
+         searchIndexEnabled: Bool = true,
+         filesExclude: [String] = [], searchExclude: [String] = [],
+         followSymlinks: Bool = false, scanNetworkMounts: Bool = false,
+         followSystemAppearance: Bool = false, lightColorScheme: String = "github-light",
+         darkColorScheme: String = "nord", increaseContrast: Bool = false,
+         keymapPreset: String = "default",
+         statusBarSegments: [String] = [], statusBarHiddenSegments: [String] = []) {
         self.windowWidth = windowWidth
         self.windowHeight = windowHeight
         self.sidebarVisible = sidebarVisible
         self.sidebarWidth = sidebarWidth
+        self.zenModeMaxWidth = zenModeMaxWidth
         self.confirmCloseWarnings = confirmCloseWarnings
         self.restoreSession = restoreSession
+        self.cliOpenInNewWindow = cliOpenInNewWindow
+        self.controlSocketEnabled = controlSocketEnabled
         self.lastDirectory = lastDirectory
         self.openFiles = openFiles
         self.autoSave = autoSave
+        self.saveStrategy = saveStrategy
+        self.hotExit = hotExit
+        self.trimTrailingWhitespace = trimTrailingWhitespace
+        self.insertFinalNewline = insertFinalNewline
         self.fontSize = fontSize
         self.fontFamily = fontFamily
         self.tabWidth = tabWidth
@@ -456,18 +639,26 @@ struct Settings: Codable {
         self.terminalFontFamily = terminalFontFamily
         self.terminalFontSize = terminalFontSize
         self.terminalCopyOnSelect = terminalCopyOnSelect
+        self.terminalConfirmPaste = terminalConfirmPaste
+        self.terminalNewTabDirectory = terminalNewTabDirectory
         self.terminalScrollOnOutput = terminalScrollOnOutput
         self.terminalAllowHyperlink = terminalAllowHyperlink
         self.terminalAllowNotifications = terminalAllowNotifications
         self.terminalAttentionOnLongCommand = terminalAttentionOnLongCommand
         self.terminalLongCommandSeconds = terminalLongCommandSeconds
+        self.terminalConfirmClose = terminalConfirmClose
+        self.terminalCloseIgnoredCommands = terminalCloseIgnoredCommands
         self.terminalBoldIsBright = terminalBoldIsBright
         self.terminalMinimumContrast = terminalMinimumContrast
         self.terminalAllowOsc52Write = terminalAllowOsc52Write
         self.terminalAllowOsc52Read = terminalAllowOsc52Read
         self.terminalBlocks = terminalBlocks
         self.terminalContextBar = terminalContextBar
+        self.terminalPalette = terminalPalette
+        self.terminalCursorColor = terminalCursorColor
+        self.terminalBackgroundOpacity = terminalBackgroundOpacity
         self.tabBarPosition = tabBarPosition
+        self.previewTabs = previewTabs
         self.editorLineHeight = editorLineHeight
         self.editorAutoClosingBrackets = editorAutoClosingBrackets
         self.editorCursorSurroundingLines = editorCursorSurroundingLines
@@ -475,22 +666,44 @@ struct Settings: Codable {
         self.editorOccurrencesHighlight = editorOccurrencesHighlight
         self.editorWordBasedSuggestions = editorWordBasedSuggestions
         self.sidebarShowHidden = sidebarShowHidden
+        self.sidebarAutoReveal = sidebarAutoReveal
         self.colorScheme = colorScheme
+        self.followSystemAppearance = followSystemAppearance
+        self.lightColorScheme = lightColorScheme
+        self.darkColorScheme = darkColorScheme
+        self.increaseContrast = increaseContrast
         self.commandsOnSave = commandsOnSave
+        self.useDirenv = useDirenv
         self.customKeybindings = customKeybindings
+        self.keymapPreset = keymapPreset
+        self.statusBarSegments = statusBarSegments
+        self.statusBarHiddenSegments = statusBarHiddenSegments
         self.keybindingOverrides = keybindingOverrides
         self.fileTypeOverrides = fileTypeOverrides
+        self.languageOverrides = languageOverrides
+        self.fileTemplates = fileTemplates
         self.checkForUpdates = checkForUpdates
+        self.autoInstallUpdates = autoInstallUpdates
+        self.workspaceTrustEnabled = workspaceTrustEnabled
+        self.sendCrashReports = sendCrashReports
+        self.crashReportEndpoint = crashReportEndpoint
+        self.settingsSyncRepository = settingsSyncRepository
+        self.searchIndexEnabled = searchIndexEnabled
+        self.filesExclude = filesExclude
+        self.searchExclude = searchExclude
+        self.followSymlinks = followSymlinks
+        self.scanNetworkMounts = scanNetworkMounts
     }
 }
 
 // MARK: - Settings I/O
 
 extension Settings {
-    private static var saveBlockedByLoadError = false
-    private static var fileSnapshot: SettingsFileSnapshot?
     static private(set) var loadWarning: SettingsLoadWarning?
 
+    /// Saves run in order on one queue so a later save never lands first.
+    private static let saveQueue = DispatchQueue(label: "dev.impulse.settings-save", qos: .utility)
+
     /// Returns the path to `~/Library/Application Support/impulse/settings.json`.
     static func settingsPath() -> URL {
         guard let appSupport = FileManager.default.urls(
@@ -514,174 +727,46 @@ extension Settings {
     /// Convenience alias used by existing code.
     static var filePath: URL { settingsPath() }
 
-    /// Clamp numeric settings to safe ranges to prevent crashes or resource
-    /// exhaustion from malformed settings files.
-    mutating func validate() {
-        fontSize = max(6, min(72, fontSize))
-        tabWidth = max(1, min(16, tabWidth))
-        terminalFontSize = max(6, min(72, terminalFontSize))
-        terminalScrollback = max(100, min(1_000_000, terminalScrollback))
-        terminalLongCommandSeconds = max(1, min(86_400, terminalLongCommandSeconds))
-        terminalMinimumContrast = max(1.0, min(21.0, terminalMinimumContrast))
-        sidebarWidth = max(100, min(1000, sidebarWidth))
-        rightMarginPosition = max(1, min(500, rightMarginPosition))
-        editorLineHeight = max(0, min(100, editorLineHeight))
-        if tabBarPosition != "top" && tabBarPosition != "sidebar" {
-            tabBarPosition = "sidebar"
-        }
-        windowWidth = max(400, min(10000, windowWidth))
-        windowHeight = max(300, min(10000, windowHeight))
-    }
-
-    /// Migrates the default font from old platform defaults ("monospace",
-    /// "SF Mono") to the bundled "JetBrains Mono". Only changes settings that
-    /// still match an old default — user-customized values are left alone.
-    mutating func migrateDefaultFont() {
-        let oldDefaults = ["monospace", "SF Mono", ""]
-        var changed = false
-        if oldDefaults.contains(fontFamily) {
-            fontFamily = "JetBrains Mono"
-            changed = true
-        }
-        if oldDefaults.contains(terminalFontFamily) {
-            terminalFontFamily = "JetBrains Mono"
-            changed = true
-        }
-        if changed {
-            save()
-        }
-    }
-
-    /// Migrates `format_on_save` entries from `FileTypeOverride` into
-    /// `CommandOnSave` entries with `reloadFile: true`, then clears the
-    /// originals. Matches the Linux `migrate_format_on_save` behavior.
-    mutating func migrateFormatOnSave() {
-        var migrated = false
-        for i in fileTypeOverrides.indices {
-            if let fmt = fileTypeOverrides[i].formatOnSave, !fmt.command.isEmpty {
-                commandsOnSave.append(CommandOnSave(
-                    name: "Format (\(fileTypeOverrides[i].pattern))",
-                    command: fmt.command,
-                    args: fmt.args,
-                    filePattern: fileTypeOverrides[i].pattern,
-                    reloadFile: true
-                ))
-                fileTypeOverrides[i].formatOnSave = nil
-                migrated = true
-            }
-        }
-        if migrated {
-            save()
-        }
-    }
-
-    /// Loads settings from disk, falling back to defaults for any missing or
-    /// corrupt data.
+    /// Loads settings through impulse-core, which owns the file format,
+    /// migrations, validation and invalid-file backups shared with Linux.
     static func load() -> Settings {
-        let url = settingsPath()
-        let data: Data
-        do {
-            data = try Data(contentsOf: url)
-        } catch {
-            // A missing file is expected on first launch; existing unreadable files are not.
-            if FileManager.default.fileExists(atPath: url.path) {
-                loadWarning = SettingsLoadWarning(
-                    settingsPath: url,
-                    backupPath: nil,
-                    message: error.localizedDescription
-                )
-                saveBlockedByLoadError = true
-                fileSnapshot = nil
-                os_log(.error, "Failed to read settings from '%{public}@': %{public}@",
-                       url.path, error.localizedDescription)
-            } else {
-                loadWarning = nil
-                saveBlockedByLoadError = false
-                fileSnapshot = nil
-            }
+        guard let json = ImpulseCore.settingsLoad(),
+              let data = json.data(using: .utf8) else {
+            os_log(.error, "Failed to load settings; using defaults")
             return .default
         }
         do {
-            var settings = try JSONDecoder().decode(Settings.self, from: data)
-            loadWarning = nil
-            saveBlockedByLoadError = false
-            fileSnapshot = SettingsFileSnapshot(
-                path: url.path,
-                contentHash: stableContentHash(data)
-            )
-            settings.migrateFormatOnSave()
-            settings.migrateDefaultFont()
-            settings.validate()
-            return settings
-        } catch {
-            let backupURL = backupInvalidSettingsFile(url: url, data: data)
-            if let backupURL {
-                os_log(.error,
-                       "Backed up invalid settings file to '%{public}@'",
-                       backupURL.path)
+            let loaded = try JSONDecoder().decode(CoreSettingsLoad.self, from: data)
+            loadWarning = loaded.warning.map {
+                SettingsLoadWarning(
+                    settingsPath: URL(fileURLWithPath: $0.settingsPath),
+                    backupPath: $0.backupPath.map { URL(fileURLWithPath: $0) },
+                    message: $0.message
+                )
             }
-            loadWarning = SettingsLoadWarning(
-                settingsPath: url,
-                backupPath: backupURL,
-                message: error.localizedDescription
-            )
-            saveBlockedByLoadError = true
-            fileSnapshot = nil
-            os_log(.error, "Failed to decode settings from '%{public}@': %{public}@",
-                   url.path, error.localizedDescription)
+            return loaded.settings
+        } catch {
+            os_log(.error, "Failed to decode settings: %{public}@", error.localizedDescription)
             return .default
         }
     }
 
-    /// Persists the current settings to disk as pretty-printed JSON.
-    /// Encoding and writing happen on a background queue to avoid blocking the
-    /// main thread. File permissions are set to 0600 (owner read/write only).
+    /// Persists the current settings through impulse-core on a background
+    /// queue. The core merges these fields over the stored file, so settings
+    /// this build does not know about are kept, and it refuses to overwrite a
+    /// file that failed to load or changed on disk since it was loaded.
     func save() {
-        if Settings.saveBlockedByLoadError {
-            let path = Settings.loadWarning?.settingsPath.path ?? Settings.settingsPath().path
-            let message = Settings.loadWarning?.message ?? "settings load failed"
-            os_log(.error,
-                   "Skipping settings save to preserve invalid settings file '%{public}@': %{public}@",
-                   path, message)
-            return
-        }
-
-        let encoder = JSONEncoder()
-        encoder.outputFormatting = [.prettyPrinted, .sortedKeys]
         let data: Data
         do {
-            data = try encoder.encode(self)
+            data = try JSONEncoder().encode(self)
         } catch {
             os_log(.error, "Failed to encode settings: %{public}@", error.localizedDescription)
             return
         }
-        let url = Settings.settingsPath()
-        if let message = Settings.settingsFileChangedSinceLoad(url: url) {
-            os_log(.error,
-                   "Skipping settings save because %{public}@. Open settings.json and reload Impulse before saving settings.",
-                   message)
-            return
-        }
-        let writtenHash = Settings.stableContentHash(data)
-        DispatchQueue.global(qos: .utility).async {
-            if let message = Settings.settingsFileChangedSinceLoad(url: url) {
-                os_log(.error,
-                       "Skipping settings save because %{public}@. Open settings.json and reload Impulse before saving settings.",
-                       message)
-                return
-            }
-            do {
-                try data.write(to: url, options: .atomic)
-                // Restrict permissions to owner-only (0600).
-                try FileManager.default.setAttributes(
-                    [.posixPermissions: 0o600], ofItemAtPath: url.path)
-                Settings.fileSnapshot = SettingsFileSnapshot(
-                    path: url.path,
-                    contentHash: writtenHash
-                )
-            } catch {
-                os_log(.error, "Failed to write settings to '%{public}@': %{public}@",
-                       url.path, error.localizedDescription)
+        guard let json = String(data: data, encoding: .utf8) else { return }
+        Settings.saveQueue.async {
+            if let error = ImpulseCore.settingsSave(json) {
+                os_log(.error, "%{public}@", error)
             }
         }
     }
@@ -690,73 +775,71 @@ extension Settings {
     static func save(_ settings: Settings) {
         settings.save()
     }
+}
 
-    private static func settingsFileChangedSinceLoad(url: URL) -> String? {
-        do {
-            let data = try Data(contentsOf: url)
-            let currentHash = stableContentHash(data)
-            guard let snapshot = fileSnapshot else {
-                return "\(url.path) was created after startup"
-            }
-            guard snapshot.path == url.path else {
-                return "\(url.path) was not the file loaded at startup"
-            }
-            if snapshot.contentHash != currentHash {
-                return "\(url.path) changed on disk since it was loaded"
-            }
-            return nil
-        } catch {
-            if FileManager.default.fileExists(atPath: url.path) {
-                return "\(url.path) could not be checked: \(error.localizedDescription)"
-            }
-            return fileSnapshot == nil ? nil : "\(url.path) was removed after it was loaded"
+/// The `impulse_settings_load` result.
+private struct CoreSettingsLoad: Decodable {
+    struct Warning: Decodable {
+        let settingsPath: String
+        let backupPath: String?
+        let message: String
+
+        enum CodingKeys: String, CodingKey {
+            case settingsPath = "settings_path"
+            case backupPath = "backup_path"
+            case message
         }
     }
 
-    private static func stableContentHash(_ data: Data) -> String {
-        var hash: UInt64 = 0xcbf29ce484222325
-        for byte in data {
-            hash ^= UInt64(byte)
-            hash = hash &* 0x100000001b3
+    let settings: Settings
+    let warning: Warning?
+}
+
+// MARK: - Appearance
+
+extension Settings {
+    /// The theme ID to use for the given system appearance. Only consults
+    /// `systemIsDark` when following the system appearance.
+    func activeColorScheme(systemIsDark: Bool) -> String {
+        guard followSystemAppearance else { return colorScheme }
+        return systemIsDark ? darkColorScheme : lightColorScheme
+    }
+
+    /// Shortcut overrides to resolve against the built-in defaults: the
+    /// keymap preset's bindings with the user's overrides on top.
+    var effectiveKeybindingOverrides: [String: String] {
+        var merged = Settings.presetBindings(for: keymapPreset)
+        for (id, shortcut) in keybindingOverrides where !shortcut.isEmpty {
+            merged[id] = shortcut
         }
-        let hex = String(hash, radix: 16)
-        let paddedHex = String(repeating: "0", count: max(0, 16 - hex.count)) + hex
-        return "fnv1a64:\(data.count):\(paddedHex)"
+        return merged
     }
 
-    private static func backupInvalidSettingsFile(url: URL, data: Data) -> URL? {
-        let directory = url.deletingLastPathComponent()
-        let stem = url.deletingPathExtension().lastPathComponent
-        let ext = url.pathExtension.isEmpty ? "json" : url.pathExtension
-        let timestamp = Int(Date().timeIntervalSince1970)
-
-        for attempt in 0..<100 {
-            let suffix = attempt == 0 ? "" : "-\(attempt)"
-            let backup = directory.appendingPathComponent(
-                "\(stem).invalid-\(timestamp)\(suffix).\(ext)"
-            )
-            if FileManager.default.fileExists(atPath: backup.path) {
-                continue
-            }
-            do {
-                try data.write(to: backup, options: .withoutOverwriting)
-                try FileManager.default.setAttributes(
-                    [.posixPermissions: 0o600],
-                    ofItemAtPath: backup.path
-                )
-                return backup
-            } catch CocoaError.fileWriteFileExists {
-                continue
-            } catch {
-                os_log(.error,
-                       "Failed to back up invalid settings file to '%{public}@': %{public}@",
-                       backup.path, error.localizedDescription)
-                return nil
-            }
+    /// The user's rebindings of Monaco actions. Actions without an override
+    /// are left out so Monaco keeps its defaults.
+    var editorKeybindings: [EditorKeybinding] {
+        let overrides = effectiveKeybindingOverrides
+        return EditorAction.all.compactMap { action in
+            guard let key = overrides[action.id]?.trimmingCharacters(in: .whitespaces),
+                  !key.isEmpty else { return nil }
+            return EditorKeybinding(command: action.monacoAction, key: key)
         }
+    }
+
+    /// The shortcut a binding falls back to without a user override.
+    func baseShortcut(for binding: BuiltinKeybinding) -> String {
+        Settings.presetBindings(for: keymapPreset)[binding.id] ?? binding.defaultShortcut
+    }
 
-        os_log(.error, "Failed to choose a unique invalid settings backup path")
-        return nil
+    private static var presetBindingsCache: [String: [String: String]] = [:]
+
+    /// Preset bindings from impulse-core, cached since key handling asks
+    /// for them on every event.
+    static func presetBindings(for preset: String) -> [String: String] {
+        if let cached = presetBindingsCache[preset] { return cached }
+        let bindings = ImpulseCore.keymapPresetBindings(preset: preset)
+        presetBindingsCache[preset] = bindings
+        return bindings
     }
 }
 
@@ -773,6 +856,7 @@ extension Settings {
             terminalScrollback: terminalScrollback,
             lastDirectory: directory ?? lastDirectory,
             terminalCopyOnSelect: terminalCopyOnSelect,
+            terminalConfirmPaste: terminalConfirmPaste,
             terminalBell: terminalBell,
             terminalAttentionOnBell: terminalAttentionOnBell,
             terminalScrollOnOutput: terminalScrollOnOutput,
@@ -786,7 +870,10 @@ extension Settings {
             terminalAllowOsc52Read: terminalAllowOsc52Read,
             terminalBlocks: terminalBlocks,
             terminalContextBar: terminalContextBar,
-            keybindingOverrides: keybindingOverrides
+            terminalPalette: terminalPalette,
+            terminalCursorColor: terminalCursorColor,
+            terminalBackgroundOpacity: terminalBackgroundOpacity,
+            keybindingOverrides: effectiveKeybindingOverrides
         )
     }
 }
@@ -801,4 +888,19 @@ extension Settings {
     static func matchesFilePattern(_ path: String, pattern: String) -> Bool {
         return ImpulseCore.matchesFilePattern(path: path, pattern: pattern)
     }
+
+    /// The indentation to open a file with: these settings, then whatever the
+    /// content shows, then the first matching file-type override.
+    func indentation(forPath path: String, content: String) -> EditorIndentation {
+        let detected = ImpulseCore.detectIndentation(content: content)
+        var indentation = EditorIndentation(
+            useSpaces: detected.useSpaces ?? useSpaces, width: detected.width ?? tabWidth)
+        if let override_ = fileTypeOverrides.first(where: {
+            Settings.matchesFilePattern(path, pattern: $0.pattern)
+        }) {
+            if let width = override_.tabWidth { indentation.width = width }
+            if let spaces = override_.useSpaces { indentation.useSpaces = spaces }
+        }
+        return indentation
+    }
 }
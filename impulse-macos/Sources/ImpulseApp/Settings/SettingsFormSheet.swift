@@ -5,7 +5,10 @@ import AppKit
 struct FormField {
     enum FieldType {
         case text(placeholder: String, value: String)
+        /// A masked field for tokens and passwords, always starting empty.
+        case secureText(placeholder: String)
         case popup(options: [String], selected: String)
+        case multilineText(value: String)
     }
     let label: String
     let key: String
@@ -62,6 +65,13 @@ enum SettingsFormSheet {
                 grid.addRow(with: [label, textField])
                 controls.append((field.key, textField))
 
+            case .secureText(let placeholder):
+                let textField = NSSecureTextField()
+                textField.placeholderString = placeholder
+                textField.translatesAutoresizingMaskIntoConstraints = false
+                grid.addRow(with: [label, textField])
+                controls.append((field.key, textField))
+
             case .popup(let options, let selected):
                 let popup = NSPopUpButton()
                 popup.addItems(withTitles: options)
@@ -69,6 +79,21 @@ enum SettingsFormSheet {
                 popup.translatesAutoresizingMaskIntoConstraints = false
                 grid.addRow(with: [label, popup])
                 controls.append((field.key, popup))
+
+            case .multilineText(let value):
+                let scrollView = NSTextView.scrollableTextView()
+                scrollView.borderType = .bezelBorder
+                scrollView.translatesAutoresizingMaskIntoConstraints = false
+                scrollView.heightAnchor.constraint(equalToConstant: 140).isActive = true
+                guard let textView = scrollView.documentView as? NSTextView else { continue }
+                textView.string = value
+                textView.font = NSFont.monospacedSystemFont(ofSize: 12, weight: .regular)
+                textView.isRichText = false
+                textView.isAutomaticQuoteSubstitutionEnabled = false
+                textView.isAutomaticDashSubstitutionEnabled = false
+                grid.addRow(with: [label, scrollView])
+                grid.cell(for: label)?.yPlacement = .top
+                controls.append((field.key, textView))
             }
         }
 
@@ -152,6 +177,8 @@ private class SheetHandler: NSObject {
                 values[key] = textField.stringValue
             } else if let popup = control as? NSPopUpButton {
                 values[key] = popup.titleOfSelectedItem ?? ""
+            } else if let textView = control as? NSTextView {
+                values[key] = textView.string
             }
         }
         parent?.endSheet(panel, returnCode: .OK)
@@ -1,4 +1,5 @@
 import AppKit
+import UniformTypeIdentifiers
 
 // MARK: - Toolbar Item Identifiers
 
@@ -41,6 +42,7 @@ final class SettingsWindowController: NSWindowController {
   private var currentPaneId: String = "general"
   private var saveTimer: Timer?
   private var managedLspStatuses: [[String: Any]] = []
+  private var storedSecrets: [ImpulseCore.StoredSecret] = []
 
   /// The singleton preferences window. Only one is shown at a time.
   private static var shared: SettingsWindowController?
@@ -58,6 +60,17 @@ final class SettingsWindowController: NSWindowController {
     controller.showWindow(nil)
   }
 
+  /// Shows settings reloaded from disk in an open window, dropping a
+  /// pending save of the values they replace.
+  static func settingsReloaded(_ settings: Settings) {
+    guard let existing = shared else { return }
+    existing.saveTimer?.invalidate()
+    existing.saveTimer = nil
+    existing.settings = settings
+    existing.paneCache.removeAll()
+    existing.switchToPane(existing.currentPaneId, animated: false)
+  }
+
   init(settings: Settings) {
     self.settings = settings
 
@@ -155,14 +168,69 @@ final class SettingsWindowController: NSWindowController {
       target: self, action: #selector(checkForUpdatesChanged(_:)))
     updatesCheck.state = settings.checkForUpdates ? .on : .off
 
+    let autoInstallCheck = NSButton(
+      checkboxWithTitle: "Install updates automatically",
+      target: self, action: #selector(autoInstallUpdatesChanged(_:)))
+    autoInstallCheck.state = settings.autoInstallUpdates ? .on : .off
+    autoInstallCheck.toolTip = "Updates found on launch are installed and take effect when Impulse relaunches"
+
     addSection(
       to: stack, title: "Startup",
       subtitle: "Restoring the session reopens editor tabs and terminal working directories",
       rows: [
         restoreSessionCheck,
         updatesCheck,
+        autoInstallCheck,
       ], addSeparator: false)
 
+    // -- Crash Reports Section --
+
+    let sendCrashReportsCheck = NSButton(
+      checkboxWithTitle: "Send crash reports automatically",
+      target: self, action: #selector(sendCrashReportsChanged(_:)))
+    sendCrashReportsCheck.state = settings.sendCrashReports ? .on : .off
+
+    let crashEndpointField = NSTextField(string: settings.crashReportEndpoint)
+    crashEndpointField.placeholderString = "https://"
+    crashEndpointField.target = self
+    crashEndpointField.action = #selector(crashReportEndpointChanged(_:))
+
+    addSection(
+      to: stack, title: "Crash Reports",
+      subtitle: "Reports are always saved locally and shown after a crash",
+      rows: [
+        sendCrashReportsCheck,
+        makeRow(label: "Report Endpoint:", control: crashEndpointField),
+      ])
+
+    // -- Settings Sync Section --
+
+    let syncRepositoryField = NSTextField(string: settings.settingsSyncRepository)
+    syncRepositoryField.placeholderString = "git@github.com:you/impulse-settings.git"
+    syncRepositoryField.target = self
+    syncRepositoryField.action = #selector(settingsSyncRepositoryChanged(_:))
+
+    addSection(
+      to: stack, title: "Settings Sync",
+      subtitle:
+        "Keep settings, keybindings, custom CSS and themes in a git repository; synced at launch and with Sync Settings",
+      rows: [
+        makeRow(label: "Repository URL:", control: syncRepositoryField)
+      ])
+
+    // -- Workspace Trust Section --
+
+    let workspaceTrustCheck = NSButton(
+      checkboxWithTitle: "Open folders in restricted mode until trusted",
+      target: self, action: #selector(workspaceTrustEnabledChanged(_:)))
+    workspaceTrustCheck.state = settings.workspaceTrustEnabled ? .on : .off
+
+    addSection(
+      to: stack, title: "Workspace Trust",
+      subtitle:
+        "Restricted folders don't start language servers, run commands on save, or read .impulse settings",
+      rows: [workspaceTrustCheck])
+
     // -- Window Section --
 
     let closeWarningsCheck = NSButton(
@@ -170,11 +238,38 @@ final class SettingsWindowController: NSWindowController {
       target: self, action: #selector(closeWarningsChanged(_:)))
     closeWarningsCheck.state = settings.confirmCloseWarnings ? .on : .off
 
+    let cliNewWindowCheck = NSButton(
+      checkboxWithTitle: "Open command-line files in a new window",
+      target: self, action: #selector(cliOpenInNewWindowChanged(_:)))
+    cliNewWindowCheck.state = settings.cliOpenInNewWindow ? .on : .off
+
+    let controlSocketCheck = NSButton(
+      checkboxWithTitle: "Let scripts control Impulse (impulse --control)",
+      target: self, action: #selector(controlSocketEnabledChanged(_:)))
+    controlSocketCheck.state = settings.controlSocketEnabled ? .on : .off
+
+    let zenWidthStepper = NSStepper()
+    zenWidthStepper.minValue = 400
+    zenWidthStepper.maxValue = 4000
+    zenWidthStepper.increment = 20
+    zenWidthStepper.integerValue = settings.zenModeMaxWidth
+    zenWidthStepper.target = self
+    zenWidthStepper.action = #selector(zenModeMaxWidthStepperChanged(_:))
+    let zenWidthField = NSTextField(string: "\(settings.zenModeMaxWidth)")
+    zenWidthField.isEditable = false
+    zenWidthField.tag = 103
+    let zenWidthRow = NSStackView(views: [zenWidthField, zenWidthStepper])
+    zenWidthRow.orientation = .horizontal
+    zenWidthRow.spacing = 4
+
     addSection(
       to: stack, title: "Window",
       subtitle: "Confirm before closing windows with unsaved files or running commands",
       rows: [
-        closeWarningsCheck
+        closeWarningsCheck,
+        cliNewWindowCheck,
+        controlSocketCheck,
+        makeRow(label: "Zen Mode Width:", control: zenWidthRow),
       ])
 
     // -- Sidebar Section --
@@ -189,13 +284,93 @@ final class SettingsWindowController: NSWindowController {
     tabPositionPopup.addItems(withTitles: ["Sidebar (vertical)", "Top"])
     tabPositionPopup.selectItem(at: settings.tabBarPosition == "top" ? 1 : 0)
 
+    let previewTabsCheck = NSButton(
+      checkboxWithTitle: "Open files from the sidebar in a preview tab",
+      target: self, action: #selector(previewTabsChanged(_:)))
+    previewTabsCheck.state = settings.previewTabs ? .on : .off
+
+    let autoRevealCheck = NSButton(
+      checkboxWithTitle: "Reveal the active file in the sidebar when switching tabs",
+      target: self, action: #selector(sidebarAutoRevealChanged(_:)))
+    autoRevealCheck.state = settings.sidebarAutoReveal ? .on : .off
+
     addSection(
       to: stack, title: "Sidebar",
       rows: [
         hiddenCheck,
         makeRow(label: "Tab Bar Position:", control: tabPositionPopup),
+        previewTabsCheck,
+        autoRevealCheck,
       ])
 
+    // -- Status Bar Section --
+
+    let segmentChecks: [NSView] = ImpulseCore.statusBarSegments(
+      order: settings.statusBarSegments, hidden: []
+    ).map { segment in
+      let check = NSButton(
+        checkboxWithTitle: segment.name,
+        target: self, action: #selector(statusBarSegmentChanged(_:)))
+      check.identifier = NSUserInterfaceItemIdentifier(segment.id)
+      check.state = settings.statusBarHiddenSegments.contains(segment.id) ? .off : .on
+      return check
+    }
+
+    addSection(
+      to: stack, title: "Status Bar",
+      subtitle: "Segments to show; reorder them with status_bar_segments in settings.json",
+      rows: segmentChecks)
+
+    // -- Stored Secrets Section --
+
+    let secretsScrollView = NSScrollView()
+    secretsScrollView.translatesAutoresizingMaskIntoConstraints = false
+    secretsScrollView.hasVerticalScroller = true
+    secretsScrollView.borderType = .bezelBorder
+
+    let secretsTable = NSTableView()
+    secretsTable.tag = 800
+    secretsTable.headerView = NSTableHeaderView()
+    secretsTable.usesAlternatingRowBackgroundColors = true
+
+    let secretNameCol = NSTableColumn(identifier: NSUserInterfaceItemIdentifier("secret_name"))
+    secretNameCol.title = "Name"
+    secretNameCol.width = 200
+    secretsTable.addTableColumn(secretNameCol)
+
+    let secretKindCol = NSTableColumn(identifier: NSUserInterfaceItemIdentifier("secret_kind"))
+    secretKindCol.title = "Kind"
+    secretKindCol.width = 160
+    secretsTable.addTableColumn(secretKindCol)
+
+    let secretUpdatedCol = NSTableColumn(identifier: NSUserInterfaceItemIdentifier("secret_updated"))
+    secretUpdatedCol.title = "Stored"
+    secretUpdatedCol.width = 120
+    secretsTable.addTableColumn(secretUpdatedCol)
+
+    storedSecrets = ImpulseCore.storedSecrets()
+    secretsTable.delegate = self
+    secretsTable.dataSource = self
+    secretsTable.columnAutoresizingStyle = .lastColumnOnlyAutoresizingStyle
+    secretsScrollView.documentView = secretsTable
+
+    secretsScrollView.heightAnchor.constraint(equalToConstant: 120).isActive = true
+
+    let addTokenButton = NSButton(
+      title: "Add Git Token…", target: self, action: #selector(addGitToken(_:)))
+    let deleteSecretButton = NSButton(
+      title: "Delete", target: self, action: #selector(deleteSecret(_:)))
+    deleteSecretButton.setAccessibilityLabel("Delete the selected secret from the keychain")
+    let secretButtonRow = NSStackView(views: [addTokenButton, deleteSecretButton])
+    secretButtonRow.orientation = .horizontal
+    secretButtonRow.spacing = 8
+
+    addSection(
+      to: stack, title: "Stored Secrets",
+      subtitle:
+        "Tokens, API keys and passwords kept in the keychain, never in settings.json. Git tokens are used to push and pull over HTTPS before credential helpers.",
+      rows: [secretsScrollView, secretButtonRow])
+
     return wrapInScrollView(stack)
   }
 
@@ -406,12 +581,36 @@ final class SettingsWindowController: NSWindowController {
       target: self, action: #selector(autoSaveChanged(_:)))
     autoSaveCheck.state = settings.autoSave ? .on : .off
 
+    let saveStrategyPopup = NSPopUpButton(
+      title: "", target: self, action: #selector(saveStrategyChanged(_:)))
+    saveStrategyPopup.addItems(withTitles: ["Atomic (write a copy, then replace)", "In place"])
+    saveStrategyPopup.selectItem(at: settings.saveStrategy == "in_place" ? 1 : 0)
+
+    let hotExitCheck = NSButton(
+      checkboxWithTitle: "Back up unsaved changes and restore them after a crash",
+      target: self, action: #selector(hotExitChanged(_:)))
+    hotExitCheck.state = settings.hotExit ? .on : .off
+
+    let trimCheck = NSButton(
+      checkboxWithTitle: "Trim trailing whitespace on save",
+      target: self, action: #selector(trimTrailingWhitespaceChanged(_:)))
+    trimCheck.state = settings.trimTrailingWhitespace ? .on : .off
+
+    let finalNewlineCheck = NSButton(
+      checkboxWithTitle: "Insert a final newline on save",
+      target: self, action: #selector(insertFinalNewlineChanged(_:)))
+    finalNewlineCheck.state = settings.insertFinalNewline ? .on : .off
+
     addSection(
       to: stack, title: "Behavior",
       rows: [
         makeRow(label: "Auto-Close Brackets:", control: autoClosePopup),
         foldingCheck,
         autoSaveCheck,
+        makeRow(label: "Save Strategy:", control: saveStrategyPopup),
+        hotExitCheck,
+        trimCheck,
+        finalNewlineCheck,
       ])
 
     return wrapInScrollView(stack)
@@ -463,11 +662,60 @@ final class SettingsWindowController: NSWindowController {
       target: self, action: #selector(termCursorBlinkChanged(_:)))
     cursorBlinkCheck.state = settings.terminalCursorBlink ? .on : .off
 
+    let termTheme =
+      (NSApp.delegate as? AppDelegate)?.theme ?? ThemeManager.currentTheme(from: settings)
+
+    let cursorColorWell = NSColorWell()
+    cursorColorWell.color = NSColor(
+      hex: settings.terminalCursorColor.isEmpty ? termTheme.cursor : settings.terminalCursorColor)
+    cursorColorWell.identifier = NSUserInterfaceItemIdentifier("termCursorColorWell")
+    cursorColorWell.target = self
+    cursorColorWell.action = #selector(termCursorColorChanged(_:))
+
     addSection(
       to: stack, title: "Cursor",
       rows: [
         makeRow(label: "Cursor Shape:", control: cursorPopup),
         cursorBlinkCheck,
+        makeRow(label: "Cursor Color:", control: cursorColorWell),
+      ])
+
+    // -- Colors Section --
+
+    let opacitySlider = NSSlider(
+      value: settings.terminalBackgroundOpacity, minValue: 0.3, maxValue: 1.0,
+      target: self, action: #selector(termBackgroundOpacityChanged(_:)))
+
+    var paletteRows: [NSView] = []
+    for start in stride(from: 0, to: 16, by: 8) {
+      let wells = (start..<start + 8).map { index -> NSColorWell in
+        let well = NSColorWell()
+        let override = settings.terminalPalette.indices.contains(index)
+          ? settings.terminalPalette[index] : ""
+        well.color = NSColor(hex: override.isEmpty ? termTheme.terminalPalette[index] : override)
+        well.tag = index
+        well.identifier = NSUserInterfaceItemIdentifier("termPaletteWell")
+        well.target = self
+        well.action = #selector(termPaletteColorChanged(_:))
+        return well
+      }
+      let row = NSStackView(views: wells)
+      row.orientation = .horizontal
+      row.spacing = 4
+      paletteRows.append(row)
+    }
+
+    let resetColorsButton = NSButton(
+      title: "Use Theme Colors", target: self, action: #selector(termResetColors(_:)))
+    resetColorsButton.bezelStyle = .rounded
+
+    addSection(
+      to: stack, title: "Colors", subtitle: "Override the theme's terminal colors",
+      rows: [
+        makeRow(label: "Background Opacity:", control: opacitySlider),
+        makeRow(label: "Normal:", control: paletteRows[0]),
+        makeRow(label: "Bright:", control: paletteRows[1]),
+        makeRow(label: "", control: resetColorsButton),
       ])
 
     // -- Behavior Section --
@@ -477,6 +725,11 @@ final class SettingsWindowController: NSWindowController {
       target: self, action: #selector(termCopyOnSelectChanged(_:)))
     copyOnSelectCheck.state = settings.terminalCopyOnSelect ? .on : .off
 
+    let confirmPasteCheck = NSButton(
+      checkboxWithTitle: "Confirm multi-line and sudo pastes",
+      target: self, action: #selector(termConfirmPasteChanged(_:)))
+    confirmPasteCheck.state = settings.terminalConfirmPaste ? .on : .off
+
     let scrollOutputCheck = NSButton(
       checkboxWithTitle: "Scroll on output",
       target: self, action: #selector(termScrollOnOutputChanged(_:)))
@@ -492,13 +745,36 @@ final class SettingsWindowController: NSWindowController {
       target: self, action: #selector(termBoldIsBrightChanged(_:)))
     boldBrightCheck.state = settings.terminalBoldIsBright ? .on : .off
 
+    let newTabDirectoryPopup = NSPopUpButton(
+      title: "", target: self, action: #selector(termNewTabDirectoryChanged(_:)))
+    newTabDirectoryPopup.addItems(
+      withTitles: Self.newTabDirectoryModes.map { $0.title })
+    newTabDirectoryPopup.selectItem(
+      at: Self.newTabDirectoryModes.firstIndex { $0.mode == settings.terminalNewTabDirectory } ?? 0)
+
+    let confirmCloseCheck = NSButton(
+      checkboxWithTitle: "Confirm closing terminals with running processes",
+      target: self, action: #selector(termConfirmCloseChanged(_:)))
+    confirmCloseCheck.state = settings.terminalConfirmClose ? .on : .off
+
+    let closeIgnoredField = NSTextField(
+      string: settings.terminalCloseIgnoredCommands.joined(separator: ", "))
+    closeIgnoredField.placeholderString = "less, vim"
+    closeIgnoredField.toolTip = "Running these never makes closing a terminal ask first"
+    closeIgnoredField.target = self
+    closeIgnoredField.action = #selector(termCloseIgnoredCommandsChanged(_:))
+
     addSection(
       to: stack, title: "Behavior",
       rows: [
         copyOnSelectCheck,
+        confirmPasteCheck,
         scrollOutputCheck,
         hyperlinkCheck,
         boldBrightCheck,
+        makeRow(label: "New Tabs Start In:", control: newTabDirectoryPopup),
+        confirmCloseCheck,
+        makeRow(label: "Never Ask For:", control: closeIgnoredField),
       ])
 
     // -- Command Blocks Section --
@@ -586,6 +862,38 @@ final class SettingsWindowController: NSWindowController {
       title: "", target: self, action: #selector(colorSchemeChanged(_:)))
     schemePopup.addItems(withTitles: ThemeManager.availableThemes())
     schemePopup.selectItem(withTitle: settings.colorScheme)
+    schemePopup.identifier = NSUserInterfaceItemIdentifier("colorSchemePopup")
+    schemePopup.isEnabled = !settings.followSystemAppearance
+
+    let followCheck = NSButton(
+      checkboxWithTitle: "Follow system appearance",
+      target: self, action: #selector(followSystemAppearanceChanged(_:)))
+    followCheck.state = settings.followSystemAppearance ? .on : .off
+
+    let lightPopup = NSPopUpButton(
+      title: "", target: self, action: #selector(lightColorSchemeChanged(_:)))
+    lightPopup.addItems(withTitles: ThemeManager.availableThemes())
+    lightPopup.selectItem(withTitle: settings.lightColorScheme)
+    lightPopup.identifier = NSUserInterfaceItemIdentifier("lightColorSchemePopup")
+    lightPopup.isEnabled = settings.followSystemAppearance
+
+    let darkPopup = NSPopUpButton(
+      title: "", target: self, action: #selector(darkColorSchemeChanged(_:)))
+    darkPopup.addItems(withTitles: ThemeManager.availableThemes())
+    darkPopup.selectItem(withTitle: settings.darkColorScheme)
+    darkPopup.identifier = NSUserInterfaceItemIdentifier("darkColorSchemePopup")
+    darkPopup.isEnabled = settings.followSystemAppearance
+
+    let contrastCheck = NSButton(
+      checkboxWithTitle: "Increase contrast",
+      target: self, action: #selector(increaseContrastChanged(_:)))
+    contrastCheck.state = settings.increaseContrast ? .on : .off
+    contrastCheck.toolTip =
+      "Strengthen text, syntax and border colors (also on with Increase Contrast in System Settings)"
+
+    let importButton = NSButton(
+      title: "Import VS Code Theme…", target: self, action: #selector(importVSCodeTheme(_:)))
+    importButton.bezelStyle = .rounded
 
     // Color preview
     let previewBox = NSBox()
@@ -607,6 +915,11 @@ final class SettingsWindowController: NSWindowController {
       to: stack, title: "Color Scheme", subtitle: "Theme applied to editor, terminal, and UI",
       rows: [
         makeRow(label: "Color Scheme:", control: schemePopup),
+        makeRow(label: "", control: followCheck),
+        makeRow(label: "Light Theme:", control: lightPopup),
+        makeRow(label: "Dark Theme:", control: darkPopup),
+        makeRow(label: "", control: contrastCheck),
+        makeRow(label: "", control: importButton),
         previewBox,
       ], addSeparator: false)
 
@@ -745,6 +1058,20 @@ final class SettingsWindowController: NSWindowController {
       subtitle: "Commands that run automatically when a file matching the pattern is saved.",
       rows: [cmdScrollView, cmdButtonRow], addSeparator: false)
 
+    // -- Environment Section --
+
+    let direnvCheck = NSButton(
+      checkboxWithTitle: "Load .envrc files with direnv",
+      target: self, action: #selector(useDirenvChanged(_:)))
+    direnvCheck.state = settings.useDirenv ? .on : .off
+    direnvCheck.toolTip = "Only in trusted folders, and only .envrc files you have allowed with `direnv allow`"
+
+    addSection(
+      to: stack, title: "Environment",
+      subtitle:
+        "Language servers, formatters and commands on save get your login shell's environment (nvm, asdf, pyenv).",
+      rows: [direnvCheck])
+
     // -- File Type Overrides Section --
 
     let ftoScrollView = NSScrollView()
@@ -799,6 +1126,51 @@ final class SettingsWindowController: NSWindowController {
       subtitle: "Per-file-type overrides for tab width, spaces vs tabs, and format-on-save.",
       rows: [ftoScrollView, ftoButtonRow])
 
+    // -- File Templates Section --
+
+    let templatesScrollView = NSScrollView()
+    templatesScrollView.translatesAutoresizingMaskIntoConstraints = false
+    templatesScrollView.hasVerticalScroller = true
+    templatesScrollView.borderType = .bezelBorder
+
+    let templatesTable = NSTableView()
+    templatesTable.tag = 601
+    templatesTable.headerView = NSTableHeaderView()
+    templatesTable.usesAlternatingRowBackgroundColors = true
+    templatesTable.doubleAction = #selector(fileTemplateDoubleClicked(_:))
+    templatesTable.target = self
+
+    let templatePatternCol = NSTableColumn(identifier: NSUserInterfaceItemIdentifier("ft_pattern"))
+    templatePatternCol.title = "Pattern"
+    templatePatternCol.width = 100
+    templatesTable.addTableColumn(templatePatternCol)
+
+    let templateContentCol = NSTableColumn(identifier: NSUserInterfaceItemIdentifier("ft_content"))
+    templateContentCol.title = "Content"
+    templateContentCol.width = 280
+    templatesTable.addTableColumn(templateContentCol)
+
+    templatesTable.delegate = self
+    templatesTable.dataSource = self
+    templatesTable.columnAutoresizingStyle = .lastColumnOnlyAutoresizingStyle
+    templatesScrollView.documentView = templatesTable
+
+    templatesScrollView.heightAnchor.constraint(equalToConstant: 120).isActive = true
+
+    let addTemplateButton = NSButton(
+      title: "Add", target: self, action: #selector(addFileTemplate(_:)))
+    let removeTemplateButton = NSButton(
+      title: "Remove", target: self, action: #selector(removeFileTemplate(_:)))
+    let templateButtonRow = NSStackView(views: [addTemplateButton, removeTemplateButton])
+    templateButtonRow.orientation = .horizontal
+    templateButtonRow.spacing = 8
+
+    addSection(
+      to: stack, title: "File Templates",
+      subtitle:
+        "Starting content for files created from the sidebar, first matching pattern wins. ${name} and ${filename} become the new file's name without and with its extension.",
+      rows: [templatesScrollView, templateButtonRow])
+
     return wrapInScrollView(stack)
   }
 
@@ -810,6 +1182,23 @@ final class SettingsWindowController: NSWindowController {
     stack.alignment = .leading
     stack.spacing = 10
 
+    // -- Keymap Preset --
+
+    let presetPopup = NSPopUpButton(
+      title: "", target: self, action: #selector(keymapPresetChanged(_:)))
+    for preset in ImpulseCore.keymapPresets() {
+      presetPopup.addItem(withTitle: preset.name)
+      presetPopup.lastItem?.representedObject = preset.id
+      if preset.id == settings.keymapPreset {
+        presetPopup.select(presetPopup.lastItem)
+      }
+    }
+
+    addSection(
+      to: stack, title: "Keymap",
+      subtitle: "Shortcuts from another editor. Your own overrides still win.",
+      rows: [makeRow(label: "Preset:", control: presetPopup)], addSeparator: false)
+
     // -- Built-in Keybindings --
 
     let scrollView = NSScrollView()
@@ -853,7 +1242,7 @@ final class SettingsWindowController: NSWindowController {
     addSection(
       to: stack, title: "Built-in Keybindings",
       subtitle: "Double-click a shortcut to edit it. Overrides are saved to settings.",
-      rows: [scrollView, resetButton], addSeparator: false)
+      rows: [scrollView, resetButton])
 
     // -- Custom Keybindings --
 
@@ -1285,6 +1674,26 @@ final class SettingsWindowController: NSWindowController {
     persistSettings()
   }
 
+  @objc private func saveStrategyChanged(_ sender: NSPopUpButton) {
+    settings.saveStrategy = sender.indexOfSelectedItem == 1 ? "in_place" : "atomic"
+    persistSettings()
+  }
+
+  @objc private func hotExitChanged(_ sender: NSButton) {
+    settings.hotExit = sender.state == .on
+    persistSettings()
+  }
+
+  @objc private func trimTrailingWhitespaceChanged(_ sender: NSButton) {
+    settings.trimTrailingWhitespace = sender.state == .on
+    persistSettings()
+  }
+
+  @objc private func insertFinalNewlineChanged(_ sender: NSButton) {
+    settings.insertFinalNewline = sender.state == .on
+    persistSettings()
+  }
+
   @objc private func renderWhitespaceChanged(_ sender: NSPopUpButton) {
     settings.renderWhitespace = sender.titleOfSelectedItem ?? "selection"
     persistSettings()
@@ -1377,6 +1786,37 @@ final class SettingsWindowController: NSWindowController {
     persistSettings()
   }
 
+  /// `terminal_new_tab_directory` values, in popup order.
+  private static let newTabDirectoryModes: [(mode: String, title: String)] = [
+    ("home", "Home Folder"),
+    ("workspace", "Workspace Folder"),
+    ("current", "Active Tab's Folder"),
+  ]
+
+  @objc private func termNewTabDirectoryChanged(_ sender: NSPopUpButton) {
+    let index = max(0, sender.indexOfSelectedItem)
+    settings.terminalNewTabDirectory = Self.newTabDirectoryModes[index].mode
+    persistSettings()
+  }
+
+  @objc private func termConfirmCloseChanged(_ sender: NSButton) {
+    settings.terminalConfirmClose = sender.state == .on
+    persistSettings()
+  }
+
+  @objc private func termCloseIgnoredCommandsChanged(_ sender: NSTextField) {
+    settings.terminalCloseIgnoredCommands = sender.stringValue
+      .split(separator: ",")
+      .map { $0.trimmingCharacters(in: .whitespaces) }
+      .filter { !$0.isEmpty }
+    persistSettings()
+  }
+
+  @objc private func termConfirmPasteChanged(_ sender: NSButton) {
+    settings.terminalConfirmPaste = sender.state == .on
+    persistSettings()
+  }
+
   @objc private func termCopyOnSelectChanged(_ sender: NSButton) {
     settings.terminalCopyOnSelect = sender.state == .on
     persistSettings()
@@ -1417,6 +1857,35 @@ final class SettingsWindowController: NSWindowController {
     persistSettings()
   }
 
+  @objc private func cliOpenInNewWindowChanged(_ sender: NSButton) {
+    settings.cliOpenInNewWindow = sender.state == .on
+    persistSettings()
+  }
+
+  @objc private func controlSocketEnabledChanged(_ sender: NSButton) {
+    settings.controlSocketEnabled = sender.state == .on
+    persistSettings()
+  }
+
+  @objc private func statusBarSegmentChanged(_ sender: NSButton) {
+    guard let id = sender.identifier?.rawValue else { return }
+    settings.statusBarHiddenSegments.removeAll { $0 == id }
+    if sender.state == .off {
+      settings.statusBarHiddenSegments.append(id)
+    }
+    persistSettings()
+  }
+
+  @objc private func zenModeMaxWidthStepperChanged(_ sender: NSStepper) {
+    settings.zenModeMaxWidth = sender.integerValue
+    if let label = sender.superview?.subviews.compactMap({ $0 as? NSTextField }).first(where: {
+      $0.tag == 103
+    }) {
+      label.stringValue = "\(settings.zenModeMaxWidth)"
+    }
+    persistSettings()
+  }
+
   @objc private func restoreSessionChanged(_ sender: NSButton) {
     settings.restoreSession = sender.state == .on
     persistSettings()
@@ -1433,6 +1902,39 @@ final class SettingsWindowController: NSWindowController {
     persistSettings()
   }
 
+  @objc private func termCursorColorChanged(_ sender: NSColorWell) {
+    settings.terminalCursorColor = sender.color.hexString
+    persistSettings()
+  }
+
+  @objc private func termBackgroundOpacityChanged(_ sender: NSSlider) {
+    settings.terminalBackgroundOpacity = sender.doubleValue
+    persistSettings()
+  }
+
+  @objc private func termPaletteColorChanged(_ sender: NSColorWell) {
+    if settings.terminalPalette.count < 16 {
+      settings.terminalPalette += Array(repeating: "", count: 16 - settings.terminalPalette.count)
+    }
+    settings.terminalPalette[sender.tag] = sender.color.hexString
+    persistSettings()
+  }
+
+  @objc private func termResetColors(_ sender: NSButton) {
+    settings.terminalPalette = []
+    settings.terminalCursorColor = ""
+    persistSettings()
+    let termTheme =
+      (NSApp.delegate as? AppDelegate)?.theme ?? ThemeManager.currentTheme(from: settings)
+    (findView(withIdentifier: "termCursorColorWell", in: window?.contentView) as? NSColorWell)?
+      .color = NSColor(hex: termTheme.cursor)
+    for well in findViews(withIdentifier: "termPaletteWell", in: window?.contentView) {
+      guard let well = well as? NSColorWell, termTheme.terminalPalette.indices.contains(well.tag)
+      else { continue }
+      well.color = NSColor(hex: termTheme.terminalPalette[well.tag])
+    }
+  }
+
   @objc private func termBlocksChanged(_ sender: NSButton) {
     settings.terminalBlocks = sender.state == .on
     persistSettings()
@@ -1448,6 +1950,16 @@ final class SettingsWindowController: NSWindowController {
     persistSettings()
   }
 
+  @objc private func previewTabsChanged(_ sender: NSButton) {
+    settings.previewTabs = sender.state == .on
+    persistSettings()
+  }
+
+  @objc private func sidebarAutoRevealChanged(_ sender: NSButton) {
+    settings.sidebarAutoReveal = sender.state == .on
+    persistSettings()
+  }
+
   // MARK: - Appearance Actions
 
   @objc private func colorSchemeChanged(_ sender: NSPopUpButton) {
@@ -1469,6 +1981,70 @@ final class SettingsWindowController: NSWindowController {
     NotificationCenter.default.post(name: .impulseThemeDidChange, object: theme)
   }
 
+  @objc private func followSystemAppearanceChanged(_ sender: NSButton) {
+    settings.followSystemAppearance = sender.state == .on
+    for (id, enabled) in [
+      ("colorSchemePopup", !settings.followSystemAppearance),
+      ("lightColorSchemePopup", settings.followSystemAppearance),
+      ("darkColorSchemePopup", settings.followSystemAppearance),
+    ] {
+      (findView(withIdentifier: id, in: window?.contentView) as? NSControl)?.isEnabled = enabled
+    }
+    persistSettings()
+  }
+
+  @objc private func lightColorSchemeChanged(_ sender: NSPopUpButton) {
+    guard let name = sender.titleOfSelectedItem else { return }
+    settings.lightColorScheme = name
+    persistSettings()
+  }
+
+  @objc private func darkColorSchemeChanged(_ sender: NSPopUpButton) {
+    guard let name = sender.titleOfSelectedItem else { return }
+    settings.darkColorScheme = name
+    persistSettings()
+  }
+
+  @objc private func increaseContrastChanged(_ sender: NSButton) {
+    settings.increaseContrast = sender.state == .on
+    persistSettings()
+  }
+
+  @objc private func importVSCodeTheme(_ sender: NSButton) {
+    let panel = NSOpenPanel()
+    panel.title = "Import VS Code Theme"
+    panel.allowedContentTypes = [.json]
+    panel.allowsMultipleSelection = false
+    guard panel.runModal() == .OK, let url = panel.url else { return }
+
+    let result = ImpulseCore.importVSCodeTheme(path: url.path)
+    guard let id = result.id else {
+      let alert = NSAlert()
+      alert.messageText = "Could not import theme"
+      alert.informativeText = result.error ?? ""
+      alert.runModal()
+      return
+    }
+    for id in ["lightColorSchemePopup", "darkColorSchemePopup"] {
+      guard
+        let variantPopup = findView(withIdentifier: id, in: window?.contentView)
+          as? NSPopUpButton
+      else { continue }
+      let selected = variantPopup.titleOfSelectedItem
+      variantPopup.removeAllItems()
+      variantPopup.addItems(withTitles: ThemeManager.availableThemes())
+      if let selected { variantPopup.selectItem(withTitle: selected) }
+    }
+    guard
+      let popup = findView(withIdentifier: "colorSchemePopup", in: window?.contentView)
+        as? NSPopUpButton
+    else { return }
+    popup.removeAllItems()
+    popup.addItems(withTitles: ThemeManager.availableThemes())
+    popup.selectItem(withTitle: id)
+    colorSchemeChanged(popup)
+  }
+
   private func findView(withIdentifier id: String, in view: NSView?) -> NSView? {
     guard let view else { return nil }
     if view.identifier?.rawValue == id { return view }
@@ -1478,6 +2054,12 @@ final class SettingsWindowController: NSWindowController {
     return nil
   }
 
+  private func findViews(withIdentifier id: String, in view: NSView?) -> [NSView] {
+    guard let view else { return [] }
+    let match = view.identifier?.rawValue == id ? [view] : []
+    return match + view.subviews.flatMap { findViews(withIdentifier: id, in: $0) }
+  }
+
   @objc private func sidebarShowHiddenChanged(_ sender: NSButton) {
     settings.sidebarShowHidden = sender.state == .on
     persistSettings()
@@ -1488,8 +2070,38 @@ final class SettingsWindowController: NSWindowController {
     persistSettings()
   }
 
+  @objc private func autoInstallUpdatesChanged(_ sender: NSButton) {
+    settings.autoInstallUpdates = sender.state == .on
+    persistSettings()
+  }
+
+  @objc private func workspaceTrustEnabledChanged(_ sender: NSButton) {
+    settings.workspaceTrustEnabled = sender.state == .on
+    persistSettings()
+  }
+
+  @objc private func sendCrashReportsChanged(_ sender: NSButton) {
+    settings.sendCrashReports = sender.state == .on
+    persistSettings()
+  }
+
+  @objc private func crashReportEndpointChanged(_ sender: NSTextField) {
+    settings.crashReportEndpoint = sender.stringValue
+    persistSettings()
+  }
+
+  @objc private func settingsSyncRepositoryChanged(_ sender: NSTextField) {
+    settings.settingsSyncRepository = sender.stringValue.trimmingCharacters(in: .whitespaces)
+    persistSettings()
+  }
+
   // MARK: - Automation Actions
 
+  @objc private func useDirenvChanged(_ sender: NSButton) {
+    settings.useDirenv = sender.state == .on
+    persistSettings()
+  }
+
   @objc private func addCommandOnSave(_ sender: Any?) {
     settings.commandsOnSave.append(
       CommandOnSave(
@@ -1600,15 +2212,126 @@ final class SettingsWindowController: NSWindowController {
     }
   }
 
+  // MARK: - File Template Actions
+
+  @objc private func addFileTemplate(_ sender: Any?) {
+    settings.fileTemplates.append(FileTemplate(pattern: "*.ext"))
+    persistSettings()
+    findTableView(withTag: 601)?.reloadData()
+  }
+
+  @objc private func removeFileTemplate(_ sender: Any?) {
+    guard let tableView = findTableView(withTag: 601) else { return }
+    let row = tableView.selectedRow
+    guard row >= 0 && row < settings.fileTemplates.count else { return }
+    settings.fileTemplates.remove(at: row)
+    persistSettings()
+    tableView.reloadData()
+  }
+
+  @objc private func fileTemplateDoubleClicked(_ sender: NSTableView) {
+    let row = sender.clickedRow
+    guard row >= 0 && row < settings.fileTemplates.count else { return }
+    let template = settings.fileTemplates[row]
+    guard let parentWindow = window else { return }
+
+    SettingsFormSheet.present(
+      on: parentWindow,
+      title: "Edit File Template",
+      fields: [
+        FormField(
+          label: "Pattern:", key: "pattern",
+          type: .text(placeholder: "e.g. *.rs", value: template.pattern)),
+        FormField(
+          label: "Content:", key: "content",
+          type: .multilineText(value: template.content)),
+      ]
+    ) { [weak self] values in
+      guard let self else { return }
+      let pattern = values["pattern"]?.trimmingCharacters(in: .whitespaces) ?? ""
+      guard !pattern.isEmpty, row < self.settings.fileTemplates.count else { return }
+      self.settings.fileTemplates[row] = FileTemplate(
+        pattern: pattern, content: values["content"] ?? "")
+      self.persistSettings()
+      sender.reloadData()
+    }
+  }
+
+  // MARK: - Stored Secret Actions
+
+  private func reloadStoredSecrets() {
+    storedSecrets = ImpulseCore.storedSecrets()
+    findTableView(withTag: 800)?.reloadData()
+  }
+
+  @objc private func addGitToken(_ sender: Any?) {
+    guard let parentWindow = window else { return }
+    SettingsFormSheet.present(
+      on: parentWindow,
+      title: "Add Git HTTPS Token",
+      fields: [
+        FormField(label: "Host:", key: "host", type: .text(placeholder: "e.g. github.com", value: "")),
+        FormField(label: "Username:", key: "user", type: .text(placeholder: "", value: "")),
+        FormField(label: "Token:", key: "token", type: .secureText(placeholder: "")),
+      ]
+    ) { [weak self] values in
+      guard let self else { return }
+      let host = values["host"]?.trimmingCharacters(in: .whitespaces) ?? ""
+      let user = values["user"]?.trimmingCharacters(in: .whitespaces) ?? ""
+      let token = values["token"] ?? ""
+      guard !host.isEmpty, !user.isEmpty, !token.isEmpty else { return }
+      // The keychain may ask to unlock, so store off the main thread.
+      DispatchQueue.global(qos: .userInitiated).async {
+        ImpulseCore.setSecret(token, kind: "git_credential", name: "\(user)@\(host)")
+        DispatchQueue.main.async { self.reloadStoredSecrets() }
+      }
+    }
+  }
+
+  @objc private func deleteSecret(_ sender: Any?) {
+    guard let tableView = findTableView(withTag: 800) else { return }
+    let row = tableView.selectedRow
+    guard row >= 0 && row < storedSecrets.count else { return }
+    let id = storedSecrets[row].id
+    DispatchQueue.global(qos: .userInitiated).async { [weak self] in
+      ImpulseCore.deleteSecret(id: id)
+      DispatchQueue.main.async { self?.reloadStoredSecrets() }
+    }
+  }
+
   // MARK: - Keybinding Actions
 
+  /// A row of the built-in keybindings table: native shortcuts followed by
+  /// the Monaco editor actions, which share the same overrides map.
+  private struct KeybindingRow {
+    let id: String
+    let description: String
+    let category: String
+    /// Shortcut without a user override.
+    let base: String
+  }
+
+  private var keybindingRows: [KeybindingRow] {
+    Keybindings.builtins.map {
+      KeybindingRow(
+        id: $0.id, description: $0.description, category: $0.category,
+        base: settings.baseShortcut(for: $0))
+    }
+      + EditorAction.all.map {
+        KeybindingRow(
+          id: $0.id, description: $0.description, category: "Editor Actions",
+          base: $0.defaultShortcut)
+      }
+  }
+
   @objc private func keybindingDoubleClicked(_ sender: NSTableView) {
     let row = sender.clickedRow
-    guard row >= 0 && row < Keybindings.builtins.count else { return }
-    let binding = Keybindings.builtins[row]
+    let rows = keybindingRows
+    guard row >= 0 && row < rows.count else { return }
+    let binding = rows[row]
     guard let parentWindow = window else { return }
 
-    let currentShortcut = settings.keybindingOverrides[binding.id] ?? binding.defaultShortcut
+    let currentShortcut = settings.effectiveKeybindingOverrides[binding.id] ?? binding.base
 
     SettingsFormSheet.present(
       on: parentWindow,
@@ -1623,7 +2346,7 @@ final class SettingsWindowController: NSWindowController {
       let newShortcut = (values["shortcut"] ?? "").trimmingCharacters(in: .whitespaces)
       guard !newShortcut.isEmpty else { return }
 
-      if newShortcut == binding.defaultShortcut {
+      if newShortcut == binding.base {
         self.settings.keybindingOverrides.removeValue(forKey: binding.id)
       } else {
         self.settings.keybindingOverrides[binding.id] = newShortcut
@@ -1633,6 +2356,13 @@ final class SettingsWindowController: NSWindowController {
     }
   }
 
+  @objc private func keymapPresetChanged(_ sender: NSPopUpButton) {
+    guard let id = sender.selectedItem?.representedObject as? String else { return }
+    settings.keymapPreset = id
+    persistSettings()
+    findTableView(withTag: 500)?.reloadData()
+  }
+
   @objc private func resetKeybindings(_ sender: Any?) {
     settings.keybindingOverrides.removeAll()
     persistSettings()
@@ -1758,10 +2488,12 @@ extension SettingsWindowController: NSTableViewDataSource, NSTableViewDelegate {
   func numberOfRows(in tableView: NSTableView) -> Int {
     switch tableView.tag {
     case 400: return settings.commandsOnSave.count
-    case 500: return Keybindings.builtins.count
+    case 500: return keybindingRows.count
     case 501: return settings.customKeybindings.count
     case 600: return settings.fileTypeOverrides.count
+    case 601: return settings.fileTemplates.count
     case 700: return managedLspStatuses.count
+    case 800: return storedSecrets.count
     default: return 0
     }
   }
@@ -1796,13 +2528,14 @@ extension SettingsWindowController: NSTableViewDataSource, NSTableViewDelegate {
       }
 
     case 500:
-      guard row < Keybindings.builtins.count else { break }
-      let binding = Keybindings.builtins[row]
+      let rows = keybindingRows
+      guard row < rows.count else { break }
+      let binding = rows[row]
       switch identifier.rawValue {
       case "kb_description": cell.stringValue = binding.description
       case "kb_category": cell.stringValue = binding.category
       case "kb_shortcut":
-        let shortcut = settings.keybindingOverrides[binding.id] ?? binding.defaultShortcut
+        let shortcut = settings.effectiveKeybindingOverrides[binding.id] ?? binding.base
         cell.stringValue = shortcut
         if settings.keybindingOverrides[binding.id] != nil {
           cell.textColor = .systemBlue
@@ -1839,6 +2572,17 @@ extension SettingsWindowController: NSTableViewDataSource, NSTableViewDelegate {
       default: break
       }
 
+    case 601:
+      guard row < settings.fileTemplates.count else { break }
+      let template = settings.fileTemplates[row]
+      switch identifier.rawValue {
+      case "ft_pattern": cell.stringValue = template.pattern
+      case "ft_content":
+        cell.stringValue =
+          template.content.split(separator: "\n").first.map(String.init) ?? "Empty file"
+      default: break
+      }
+
     case 700:
       guard row < managedLspStatuses.count else { break }
       let status = managedLspStatuses[row]
@@ -1854,6 +2598,19 @@ extension SettingsWindowController: NSTableViewDataSource, NSTableViewDelegate {
       default: break
       }
 
+    case 800:
+      guard row < storedSecrets.count else { break }
+      let secret = storedSecrets[row]
+      switch identifier.rawValue {
+      case "secret_name": cell.stringValue = secret.name
+      case "secret_kind": cell.stringValue = secret.label
+      case "secret_updated":
+        let date = Date(timeIntervalSince1970: TimeInterval(secret.updatedAt))
+        cell.stringValue = DateFormatter.localizedString(
+          from: date, dateStyle: .medium, timeStyle: .none)
+      default: break
+      }
+
     default:
       break
     }
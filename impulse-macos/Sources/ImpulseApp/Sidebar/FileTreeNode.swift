@@ -5,8 +5,8 @@ import Observation
 
 /// Model node for the sidebar file tree. Each node represents a file or directory
 /// at a given path. Directory children are lazily loaded on first expansion.
-/// @Observable so SwiftUI re-renders when children load, expand state changes,
-/// or git status updates.
+/// @Observable so the outline's cells update when children load, expand state
+/// changes, or git status updates.
 @Observable
 final class FileTreeNode: Identifiable {
 
@@ -14,6 +14,8 @@ final class FileTreeNode: Identifiable {
     let path: String
     let isDirectory: Bool
     var isSymlink: Bool
+    /// The link text of a symlink, shown next to its name.
+    var symlinkTarget: String?
     var size: UInt64
     var modified: UInt64
 
@@ -47,6 +49,7 @@ final class FileTreeNode: Identifiable {
         path: String,
         isDirectory: Bool,
         isSymlink: Bool = false,
+        symlinkTarget: String? = nil,
         size: UInt64 = 0,
         modified: UInt64 = 0,
         gitStatus: GitStatus = .none
@@ -55,6 +58,7 @@ final class FileTreeNode: Identifiable {
         self.path = path
         self.isDirectory = isDirectory
         self.isSymlink = isSymlink
+        self.symlinkTarget = symlinkTarget
         self.size = size
         self.modified = modified
         self.gitStatus = gitStatus
@@ -62,9 +66,8 @@ final class FileTreeNode: Identifiable {
 
     // MARK: Loading Children
 
-    /// Populate `children` by reading the directory at `path` with FileManager.
-    /// Directories are sorted before files; both groups are sorted alphabetically
-    /// (case-insensitive).
+    /// Populate `children` from the core directory listing: directories
+    /// before files, `files_exclude` applied, gitignored entries marked.
     func loadChildren(showHidden: Bool) {
         guard isDirectory else { return }
         children = Self.buildChildren(path: path, showHidden: showHidden)
@@ -74,49 +77,20 @@ final class FileTreeNode: Identifiable {
     /// Intended for background-thread use: call this off main thread, then
     /// assign the result to `node.children` on the main thread.
     static func buildChildren(path: String, showHidden: Bool) -> [FileTreeNode] {
-        let fm = FileManager.default
-        let url = URL(fileURLWithPath: path, isDirectory: true)
-
-        guard let contents = try? fm.contentsOfDirectory(
-            at: url,
-            includingPropertiesForKeys: [.isDirectoryKey, .isSymbolicLinkKey],
-            options: showHidden ? [] : [.skipsHiddenFiles]
-        ) else {
-            return []
+        guard let entries = ImpulseCore.readDirectoryEntries(path: path, showHidden: showHidden)
+        else { return [] }
+        return entries.map { entry in
+            FileTreeNode(
+                name: entry.name,
+                path: entry.path,
+                isDirectory: entry.is_dir,
+                isSymlink: entry.is_symlink,
+                symlinkTarget: entry.symlink_target,
+                size: entry.size,
+                modified: entry.modified,
+                gitStatus: entry.is_ignored == true ? .ignored : .none
+            )
         }
-
-        var nodes: [FileTreeNode] = []
-        for itemURL in contents {
-            let itemName = itemURL.lastPathComponent
-            if !showHidden && itemName.hasPrefix(".") { continue }
-            if itemName == ".DS_Store" { continue }
-            let values = try? itemURL.resourceValues(forKeys: [
-                .isDirectoryKey,
-                .isSymbolicLinkKey,
-                .fileSizeKey,
-                .contentModificationDateKey
-            ])
-            let isDir = values?.isDirectory ?? false
-            let isSymlink = values?.isSymbolicLink ?? false
-            let size = UInt64(max(values?.fileSize ?? 0, 0))
-            let modified = values?.contentModificationDate
-                .map { UInt64(max($0.timeIntervalSince1970, 0)) } ?? 0
-            nodes.append(FileTreeNode(
-                name: itemName,
-                path: itemURL.path,
-                isDirectory: isDir,
-                isSymlink: isSymlink,
-                size: size,
-                modified: modified
-            ))
-        }
-
-        nodes.sort { lhs, rhs in
-            if lhs.isDirectory != rhs.isDirectory { return lhs.isDirectory }
-            return lhs.name.localizedCaseInsensitiveCompare(rhs.name) == .orderedAscending
-        }
-
-        return nodes
     }
 
     // MARK: Building a Top-Level Tree
@@ -188,7 +162,9 @@ final class FileTreeNode: Identifiable {
             is_symlink: isSymlink,
             size: size,
             modified: modified,
-            git_status: Self.statusCode(from: gitStatus)
+            git_status: Self.statusCode(from: gitStatus),
+            is_ignored: gitStatus == .ignored,
+            symlink_target: symlinkTarget
         )
     }
 
@@ -198,14 +174,17 @@ final class FileTreeNode: Identifiable {
             path: node.path,
             isDirectory: node.is_dir,
             isSymlink: node.is_symlink,
+            symlinkTarget: node.symlink_target,
             size: node.size,
             modified: node.modified,
-            gitStatus: statusFromCode(node.git_status)
+            gitStatus: node.is_ignored == true && node.git_status == nil
+                ? .ignored : statusFromCode(node.git_status)
         )
     }
 
     func updateMetadata(from node: ImpulseCore.FileTreePatchNode) {
         isSymlink = node.is_symlink
+        symlinkTarget = node.symlink_target
         size = node.size
         modified = node.modified
         gitStatus = Self.statusFromCode(node.git_status)
@@ -277,8 +256,8 @@ final class FileTreeNode: Identifiable {
 
 // MARK: - Flat Tree Entry
 
-/// A single entry in the flattened file tree. Used by FileTreeListView for
-/// efficient virtualized rendering via LazyVStack (instead of recursive views).
+/// A single entry in the flattened file tree. A new snapshot tells the
+/// outline view (`FileTreeOutlineView`) the tree's shape changed.
 struct FlatTreeEntry: Identifiable {
     let id: String
     let node: FileTreeNode
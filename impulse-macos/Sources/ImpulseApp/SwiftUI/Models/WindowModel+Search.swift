@@ -1,6 +1,25 @@
 import AppKit
 import Foundation
 
+/// The sidebar search's scope choices.
+enum SearchScopeKind: String, CaseIterable, Identifiable {
+  case workspace
+  case openFiles
+  case directory
+  case glob
+
+  var id: String { rawValue }
+
+  var title: String {
+    switch self {
+    case .workspace: return "Entire Workspace"
+    case .openFiles: return "Open Files"
+    case .directory: return "Current Directory"
+    case .glob: return "Custom Glob"
+    }
+  }
+}
+
 /// Search lifecycle for the sidebar search bar.
 ///
 /// Execution state (the generation counter, debounce work, and the in-flight
@@ -39,6 +58,7 @@ extension WindowModel {
     searchGeneration &+= 1
     searchQuery = ""
     searchResults = []
+    searchError = nil
     isSearching = false
     sidebarPanel = .files
   }
@@ -64,12 +84,23 @@ extension WindowModel {
   private func performSearch() {
     guard !searchQuery.isEmpty, !fileTreeRootPath.isEmpty else {
       searchResults = []
+      searchError = nil
       isSearching = false
       return
     }
     let query = searchQuery
     let root = fileTreeRootPath
     let caseSensitive = searchCaseSensitive
+    // Buffers are read here, on the main thread, so the search sees what the
+    // editors show right now. With no current directory the whole workspace
+    // is searched.
+    let scope: SearchScope
+    switch searchScopeKind {
+    case .workspace: scope = .workspace
+    case .openFiles: scope = .openFiles(onOpenFileBuffers?() ?? [])
+    case .directory: scope = onActiveDirectory?().map { .directory($0) } ?? .workspace
+    case .glob: scope = .glob(searchGlob)
+    }
 
     searchGeneration &+= 1
     let generation = searchGeneration
@@ -77,13 +108,28 @@ extension WindowModel {
     isSearching = true
 
     searchTask = Task.detached {
-      let combined = ImpulseCore.searchAll(
-        root: root, query: query, caseSensitive: caseSensitive)
+      // The whole workspace also matches file names; narrower scopes search
+      // contents only.
+      let result: Result<[SearchResult], ImpulseError>
+      if case .workspace = scope {
+        result = .success(
+          ImpulseCore.searchAll(root: root, query: query, caseSensitive: caseSensitive))
+      } else {
+        result = ImpulseCore.searchContent(
+          root: root, scope: scope, query: query, caseSensitive: caseSensitive)
+      }
 
       await MainActor.run { [weak self] in
         // Only apply if this is still the latest search.
         guard let self, generation == self.searchGeneration else { return }
-        self.searchResults = combined
+        switch result {
+        case .success(let results):
+          self.searchResults = results
+          self.searchError = nil
+        case .failure(let error):
+          self.searchResults = []
+          self.searchError = error.message
+        }
         self.isSearching = false
       }
     }
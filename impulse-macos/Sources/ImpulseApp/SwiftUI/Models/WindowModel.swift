@@ -8,6 +8,13 @@ enum SidebarPanel {
   case search
 }
 
+/// An inline name field in the file tree: a new file or folder in a
+/// directory, or a rename.
+enum FileTreeEdit: Equatable {
+  case newEntry(directory: String, isDirectory: Bool)
+  case rename(path: String)
+}
+
 // MARK: - Tab Display Info
 
 /// Lightweight snapshot of a tab for the SwiftUI tab bar.
@@ -27,6 +34,23 @@ struct TabDisplayInfo: Identifiable {
   /// terminal's grid. When true the tab title shows the program name instead
   /// of the folder, so the subtitle shows the folder alongside the branch.
   var isDirectInteractionActive: Bool = false
+  /// Opened with a single click in the file tree; the next one replaces it.
+  /// Shown in italics.
+  var isPreview: Bool = false
+  /// Lock tooltip when the tab's editor is read-only.
+  var readOnlyReason: String? = nil
+  /// File behind an editor or image tab, for "Reveal in Finder".
+  var filePath: String? = nil
+  /// What a terminal tab's shell is running (foreground job, CPU, memory),
+  /// shown in the tab tooltip. Nil while the shell is idle.
+  var processDescription: String? = nil
+
+  /// Tooltip for the tab: the title, plus the running processes of a busy
+  /// terminal.
+  var tooltip: String {
+    guard let processDescription else { return title }
+    return "\(title)\n\(processDescription)"
+  }
 }
 
 // MARK: - Window Model
@@ -53,15 +77,23 @@ final class WindowModel {
   var sidebarWidth: CGFloat = 250
   var showHiddenFiles: Bool = false
 
+  // MARK: Layout (Zen mode and chrome toggles)
+
+  var tabBarHidden: Bool = false
+  var statusBarHidden: Bool = false
+  /// Caps the content width at `contentMaxWidth`, centered.
+  var centeredLayout: Bool = false
+  var contentMaxWidth: CGFloat = 960
+
   // MARK: File tree (populated by MainWindowController)
 
   var fileTreeNodes: [FileTreeNode] = []
   var fileTreeRootPath: String = ""
 
-  /// Flattened view of the file tree for LazyVStack rendering.
+  /// Flattened view of the file tree; a new value reloads the outline view.
   /// Rebuilt explicitly when tree structure changes (expand/collapse/rebuild),
-  /// NOT on git status changes — individual row views observe those directly
-  /// via @Bindable on each node.
+  /// NOT on git status changes — individual cells observe those directly
+  /// on each node.
   var flatFileTree: [FlatTreeEntry] = []
 
   /// Path of the file currently open in the active editor tab.
@@ -72,11 +104,24 @@ final class WindowModel {
   /// so they act on the SwiftUI sidebar selection.
   var selectedFileTreePath: String? = nil
 
+  /// Bumped when the file tree should scroll `selectedFileTreePath` into view.
+  var fileTreeRevealToken: Int = 0
+  /// A path to reveal once the tree of a new root has loaded.
+  var pendingFileTreeReveal: String? = nil
+  /// The inline name field open in the file tree, if any.
+  var fileTreeEdit: FileTreeEdit? = nil
+
   // MARK: Search
 
   var searchQuery: String = ""
   var searchResults: [SearchResult] = []
   var searchCaseSensitive: Bool = false
+  /// Which files the search looks in.
+  var searchScopeKind: SearchScopeKind = .workspace
+  /// Globs for the custom glob scope, like `src/**/*.rs, !*_test.rs`.
+  var searchGlob: String = ""
+  /// Why the last search failed (e.g. an invalid glob), shown in place of results.
+  var searchError: String? = nil
   /// True while a search FFI call is in flight (drives the results spinner).
   var isSearching: Bool = false
   /// Bumped whenever the sidebar search field should grab keyboard focus.
@@ -121,11 +166,18 @@ final class WindowModel {
 
   // MARK: Status bar — right group
 
+  /// Visible status bar segments in display order (see `StatusBarSegment`).
+  var statusBarSegments: [StatusBarSegment] = ImpulseCore.statusBarSegments(order: [], hidden: [])
+  /// Error and warning totals across open editors, for the problems segment.
+  var problemErrorCount: Int = 0
+  var problemWarningCount: Int = 0
   var cursorLine: Int? = nil
   var cursorCol: Int? = nil
   var currentLanguage: String? = nil
   var currentEncoding: String = "UTF-8"
   var currentIndent: String? = nil
+  /// The selected tab's zoom like "120%", nil at the default font size.
+  var zoomLabel: String? = nil
   var isPreviewable: Bool = false
   var isPreviewing: Bool = false
 
@@ -134,10 +186,21 @@ final class WindowModel {
   var updateAvailableVersion: String? = nil
   var updateCurrentVersion: String? = nil
   var updateURL: URL? = nil
+  /// The update is already installed and applies on relaunch.
+  var updateInstalled: Bool = false
+
+  // MARK: Notifications
+
+  /// The notification history, newest first (see `ImpulseCore.notifications`).
+  var notifications: [ImpulseCore.AppNotification] = []
+  var notificationsPanelVisible: Bool = false
+  var unreadNotificationCount: Int { notifications.filter { !$0.read }.count }
 
   // MARK: Overlays
 
   var commandPaletteVisible: Bool = false
+  /// The searchable "Show All Tabs" list.
+  var allTabsPanelVisible: Bool = false
   var settingsLoadWarning: SettingsLoadWarning? = nil
 
   // MARK: Theme
@@ -154,7 +217,12 @@ final class WindowModel {
   var onTabSelected: ((Int) -> Void)?
   var onTabClosed: ((Int) -> Void)?
   var onTabMoved: ((Int, Int) -> Void)?
+  /// A tab drag ended at a screen point. Returns `true` when the point was
+  /// outside the window and the tab moved to another one.
+  var onTabDraggedOut: ((Int, NSPoint) -> Bool)?
   var onTabPinToggled: ((Int) -> Void)?
+  /// Close a group of tabs (`"saved"`, `"right"` or `"left"`) around a tab.
+  var onTabGroupClosed: ((String, Int) -> Void)?
   var onNewTab: (() -> Void)?
   var onShowCommandHistory: (() -> Void)?
   var onClearTerminal: (() -> Void)?
@@ -167,15 +235,43 @@ final class WindowModel {
   var onCompletionCandidates: ((String) -> CompletionResult?)?
   /// Most recent commands, newest first, for ↑/↓ cycling in the input bar.
   var onRecentCommands: ((Int) -> [String])?
+  /// Open files with their current (possibly unsaved) editor contents, for
+  /// the open files search scope.
+  var onOpenFileBuffers: (() -> [(path: String, content: String)])?
+  /// The selected tab's directory, for the current directory search scope.
+  var onActiveDirectory: (() -> String?)?
+  /// The active terminal's directory history, most recent first, for the
+  /// working directory breadcrumb's menu.
+  var onDirectoryHistory: (() -> [String])?
+  /// Open a directory from the working directory breadcrumb in the sidebar.
+  var onOpenDirectoryInSidebar: ((String) -> Void)?
+  /// Open a folder dropped on the file tree as the window's workspace.
+  var onOpenWorkspace: ((String) -> Void)?
+  /// Change the active terminal to a directory from its history.
+  var onChangeDirectory: ((String) -> Void)?
   /// Send SIGINT to the active terminal (input-bar Stop button / ⌃C).
   var onSendInterrupt: (() -> Void)?
   /// Move keyboard focus into the terminal grid (Esc from the input bar).
   var onFocusTerminal: (() -> Void)?
   var onSidebarVisibilityChanged: ((Bool) -> Void)?
   var onPreviewToggle: (() -> Void)?
+  /// Change the current file's indentation from the status bar: spaces or
+  /// tabs, a new width (nil keeps the current one), and whether to convert
+  /// the existing lines.
+  var onSetIndentation: ((Bool, Int?, Bool) -> Void)?
+  /// Override the current file's language from the status bar; `nil`
+  /// returns it to automatic detection.
+  var onSetLanguage: ((String?) -> Void)?
+  var onResetZoom: (() -> Void)?
   var onOpenFile: ((String, Int?) -> Void)?
+  /// Single click on a file in the tree: opens it in the preview tab.
+  var onPreviewFile: ((String) -> Void)?
   var onNewFile: ((String) -> Void)?
   var onNewFolder: ((String) -> Void)?
+  /// Create or rename from the inline name field. Returns the error to
+  /// show in the field, or nil once done.
+  var onCommitFileTreeEdit: ((FileTreeEdit, String) -> String?)?
+  var onDuplicateFile: ((String) -> Void)?
   /// Sidebar action-bar buttons (act on the selected tree dir, or the root):
   /// new file, new folder.
   var onCreateFile: (() -> Void)?
@@ -203,6 +299,48 @@ final class WindowModel {
     fileTreeNodes = nodes
     if let rootPath { fileTreeRootPath = rootPath }
     rebuildFlatTree()
+    if let path = pendingFileTreeReveal, !nodes.isEmpty {
+      pendingFileTreeReveal = nil
+      revealInFileTree(path)
+    }
+  }
+
+  /// Expands the directories leading to `path`, loading them as needed,
+  /// then selects it and scrolls it into view. Returns false, doing
+  /// nothing, when `path` is outside the tree's root.
+  @discardableResult
+  func revealInFileTree(_ path: String) -> Bool {
+    let root = fileTreeRootPath.hasSuffix("/") ? fileTreeRootPath : fileTreeRootPath + "/"
+    guard !fileTreeRootPath.isEmpty, path.hasPrefix(root) else { return false }
+    revealInFileTree(path, among: fileTreeNodes)
+    return true
+  }
+
+  private func revealInFileTree(_ path: String, among nodes: [FileTreeNode]) {
+    guard let node = nodes.first(where: { path == $0.path || path.hasPrefix($0.path + "/") })
+    else { return }
+    if node.path == path {
+      selectedFileTreePath = path
+      fileTreeRevealToken += 1
+      return
+    }
+    guard node.isDirectory else { return }
+    if node.isExpanded, let children = node.children {
+      revealInFileTree(path, among: children)
+    } else {
+      expandDirectory(node) { [weak self] in
+        self?.revealInFileTree(path, among: node.children ?? [])
+      }
+    }
+  }
+
+  /// Opens an inline New File or New Folder field among the children of
+  /// `directory`, expanding it first.
+  func beginNewEntry(in directory: String, isDirectory: Bool) {
+    if let node = flatFileTree.first(where: { $0.node.path == directory })?.node {
+      expandDirectory(node)
+    }
+    fileTreeEdit = .newEntry(directory: directory, isDirectory: isDirectory)
   }
 
   /// Rebuild the flat tree from current nodes. Call after any structural
@@ -214,14 +352,16 @@ final class WindowModel {
 
   /// Expand a directory node, lazily loading its children off the main
   /// thread if they haven't been loaded yet. Shared by mouse and keyboard
-  /// navigation in the sidebar.
-  func expandDirectory(_ node: FileTreeNode) {
+  /// navigation in the sidebar. `completion` runs once the children are in
+  /// the tree.
+  func expandDirectory(_ node: FileTreeNode, completion: (() -> Void)? = nil) {
     guard node.isDirectory, !node.isExpanded else { return }
     if node.isLoaded {
       node.isExpanded = true
       rebuildFlatTree()
       onFileTreeExpansionChanged?()
       refreshGitStatusForChildren(of: node)
+      completion?()
     } else {
       // Flip the chevron immediately so the user knows the action registered.
       node.isExpanded = true
@@ -235,6 +375,7 @@ final class WindowModel {
           self.rebuildFlatTree()
           self.onFileTreeExpansionChanged?()
           self.refreshGitStatusForChildren(of: node)
+          completion?()
         }
       }
     }
@@ -0,0 +1,818 @@
+import AppKit
+import Observation
+import SwiftUI
+
+/// The sidebar file tree as an AppKit `NSOutlineView`: native disclosure,
+/// arrow-key navigation, type-select and VoiceOver outline semantics, with
+/// themed icons and git status badges.
+///
+/// The outline reads `FileTreeNode`s straight from `WindowModel`. The values
+/// passed in are what SwiftUI watches: a new `entries` snapshot (expand,
+/// collapse, children loaded, tree rebuilt) reloads the outline, and the
+/// selection, inline edit and reveal requests are applied to it. Git status
+/// changes don't reload anything — each cell observes its own node.
+struct FileTreeOutlineView: NSViewRepresentable {
+  var model: WindowModel
+  let entries: [FlatTreeEntry]
+  let selectedPath: String?
+  let activePath: String?
+  let edit: FileTreeEdit?
+  let revealToken: Int
+  let theme: Theme
+
+  func makeCoordinator() -> FileTreeOutlineController {
+    FileTreeOutlineController(model: model)
+  }
+
+  func makeNSView(context: Context) -> NSScrollView {
+    context.coordinator.scrollView
+  }
+
+  func updateNSView(_ nsView: NSScrollView, context: Context) {
+    context.coordinator.update(
+      entries: entries,
+      selectedPath: selectedPath,
+      activePath: activePath,
+      edit: edit,
+      revealToken: revealToken,
+      theme: theme
+    )
+  }
+}
+
+// MARK: - Controller
+
+/// Data source and delegate of the file tree outline. Expanding, collapsing
+/// and selecting in the outline go through `WindowModel`, which rebuilds the
+/// flat entries and so reloads the outline in the shape the model holds.
+final class FileTreeOutlineController: NSObject, NSOutlineViewDataSource,
+  NSOutlineViewDelegate, NSMenuDelegate, NSTextFieldDelegate
+{
+  let scrollView = NSScrollView()
+  private let outlineView = FileTreeOutline()
+  private let model: WindowModel
+
+  /// Set while the outline is brought in line with the model, so the
+  /// expansion and selection notifications it posts aren't fed back.
+  private var isSyncing = false
+  private var shape: [ObjectIdentifier] = []
+  private var newEntry: NewEntryItem?
+  private var revealToken = 0
+  private var activePath: String?
+  private var theme: Theme?
+
+  /// The inline name field being edited.
+  private var editing: InlineEdit?
+  /// Whether the drag in progress is over empty space, where a folder
+  /// from outside opens as the workspace.
+  private var dropOpensWorkspace = false
+
+  private static let cellIdentifier = NSUserInterfaceItemIdentifier("FileTreeCell")
+  private static let rowIdentifier = NSUserInterfaceItemIdentifier("FileTreeRow")
+
+  init(model: WindowModel) {
+    self.model = model
+    super.init()
+
+    let column = NSTableColumn(identifier: NSUserInterfaceItemIdentifier("name"))
+    column.resizingMask = .autoresizingMask
+    outlineView.addTableColumn(column)
+    outlineView.outlineTableColumn = column
+    outlineView.headerView = nil
+    outlineView.style = .sourceList
+    outlineView.rowSizeStyle = .custom
+    outlineView.rowHeight = 22
+    outlineView.indentationPerLevel = 14
+    outlineView.backgroundColor = .clear
+    outlineView.focusRingType = .none
+    outlineView.allowsMultipleSelection = false
+    outlineView.columnAutoresizingStyle = .uniformColumnAutoresizingStyle
+    outlineView.setAccessibilityLabel("Project files")
+    outlineView.dataSource = self
+    outlineView.delegate = self
+    outlineView.target = self
+    outlineView.action = #selector(rowClicked(_:))
+    outlineView.doubleAction = #selector(rowDoubleClicked(_:))
+    outlineView.onActivate = { [weak self] in self?.activateSelection() }
+
+    let menu = NSMenu()
+    menu.delegate = self
+    outlineView.menu = menu
+
+    outlineView.registerForDraggedTypes([.fileURL])
+    outlineView.setDraggingSourceOperationMask(.move, forLocal: true)
+    outlineView.setDraggingSourceOperationMask(.copy, forLocal: false)
+
+    scrollView.documentView = outlineView
+    scrollView.drawsBackground = false
+    scrollView.hasVerticalScroller = true
+    scrollView.autohidesScrollers = true
+  }
+
+  // MARK: Model → Outline
+
+  func update(
+    entries: [FlatTreeEntry],
+    selectedPath: String?,
+    activePath: String?,
+    edit: FileTreeEdit?,
+    revealToken: Int,
+    theme: Theme
+  ) {
+    let themeChanged = self.theme?.id != theme.id
+    self.theme = theme
+
+    var wantedNewEntry: NewEntryItem?
+    if case .newEntry(let directory, let isDirectory) = edit {
+      if let current = newEntry, current.directory == directory,
+        current.isDirectory == isDirectory
+      {
+        wantedNewEntry = current
+      } else {
+        wantedNewEntry = NewEntryItem(directory: directory, isDirectory: isDirectory)
+      }
+    }
+
+    let newShape = entries.map { ObjectIdentifier($0.node) }
+    if newShape != shape || wantedNewEntry !== newEntry || themeChanged {
+      shape = newShape
+      newEntry = wantedNewEntry
+      reload()
+    }
+
+    if self.activePath != activePath {
+      self.activePath = activePath
+      outlineView.enumerateAvailableRowViews { rowView, row in
+        (rowView as? FileTreeRowView)?.isActive = isActive(outlineView.item(atRow: row))
+      }
+    }
+
+    select(path: selectedPath)
+    if self.revealToken != revealToken {
+      self.revealToken = revealToken
+      if let path = selectedPath, let row = row(forPath: path) {
+        outlineView.scrollRowToVisible(row)
+      }
+    }
+
+    if edit != editing?.edit {
+      endEditing()
+      if let edit { beginEditing(edit) }
+    }
+  }
+
+  /// Reload the outline and expand what the model has expanded.
+  private func reload() {
+    let interrupted = editing
+    editing = nil
+    isSyncing = true
+    outlineView.reloadData()
+    syncExpansion(model.fileTreeNodes)
+    isSyncing = false
+    // The reload dropped the field; open it again on the new cell with
+    // what was typed so far.
+    if let interrupted {
+      beginEditing(interrupted.edit, text: interrupted.field.stringValue)
+    }
+  }
+
+  private func syncExpansion(_ nodes: [FileTreeNode]) {
+    for node in nodes where node.isDirectory {
+      if node.isExpanded {
+        outlineView.expandItem(node)
+        syncExpansion(node.children ?? [])
+      } else if outlineView.isItemExpanded(node) {
+        outlineView.collapseItem(node)
+      }
+    }
+  }
+
+  private func select(path: String?) {
+    let selected = (outlineView.item(atRow: outlineView.selectedRow) as? FileTreeNode)?.path
+    guard selected != path else { return }
+    isSyncing = true
+    if let path, let row = row(forPath: path) {
+      outlineView.selectRowIndexes(IndexSet(integer: row), byExtendingSelection: false)
+    } else {
+      outlineView.deselectAll(nil)
+    }
+    isSyncing = false
+  }
+
+  private func row(forPath path: String) -> Int? {
+    guard let node = node(at: path) else { return nil }
+    let row = outlineView.row(forItem: node)
+    return row >= 0 ? row : nil
+  }
+
+  private func node(at path: String) -> FileTreeNode? {
+    model.flatFileTree.first(where: { $0.node.path == path })?.node
+  }
+
+  private func isActive(_ item: Any?) -> Bool {
+    guard let node = item as? FileTreeNode, let activePath else { return false }
+    return !node.isDirectory && node.path == activePath
+  }
+
+  // MARK: Data Source
+
+  func outlineView(_ outlineView: NSOutlineView, numberOfChildrenOfItem item: Any?) -> Int {
+    let (nodes, hasNewEntry) = children(of: item)
+    return nodes.count + (hasNewEntry ? 1 : 0)
+  }
+
+  func outlineView(_ outlineView: NSOutlineView, child index: Int, ofItem item: Any?) -> Any {
+    let (nodes, hasNewEntry) = children(of: item)
+    if hasNewEntry, let newEntry {
+      return index == 0 ? newEntry : nodes[index - 1]
+    }
+    return nodes[index]
+  }
+
+  func outlineView(_ outlineView: NSOutlineView, isItemExpandable item: Any) -> Bool {
+    (item as? FileTreeNode)?.isDirectory ?? false
+  }
+
+  /// The nodes under `item` (the roots for `nil`), and whether the New
+  /// File / New Folder row goes first among them.
+  private func children(of item: Any?) -> (nodes: [FileTreeNode], hasNewEntry: Bool) {
+    let directory: String
+    let nodes: [FileTreeNode]
+    if let node = item as? FileTreeNode {
+      directory = node.path
+      nodes = node.children ?? []
+    } else if item == nil {
+      directory = model.fileTreeRootPath
+      nodes = model.fileTreeNodes
+    } else {
+      return ([], false)
+    }
+    return (nodes, newEntry?.directory == directory)
+  }
+
+  // MARK: Delegate
+
+  func outlineView(
+    _ outlineView: NSOutlineView, viewFor tableColumn: NSTableColumn?, item: Any
+  ) -> NSView? {
+    let cell =
+      outlineView.makeView(withIdentifier: Self.cellIdentifier, owner: nil) as? FileTreeCellView
+      ?? FileTreeCellView(identifier: Self.cellIdentifier)
+    guard let theme else { return cell }
+    if let node = item as? FileTreeNode {
+      cell.configure(node: node, theme: theme, iconCache: model.iconCache)
+    } else if let newEntry = item as? NewEntryItem {
+      cell.configure(newEntryIsDirectory: newEntry.isDirectory, theme: theme)
+    }
+    return cell
+  }
+
+  func outlineView(_ outlineView: NSOutlineView, rowViewForItem item: Any) -> NSTableRowView? {
+    let rowView =
+      outlineView.makeView(withIdentifier: Self.rowIdentifier, owner: nil) as? FileTreeRowView
+      ?? FileTreeRowView()
+    rowView.identifier = Self.rowIdentifier
+    rowView.activeColor = theme.map { NSColor(hex: $0.accent).withAlphaComponent(0.2) }
+    rowView.isActive = isActive(item)
+    return rowView
+  }
+
+  func outlineView(_ outlineView: NSOutlineView, shouldSelectItem item: Any) -> Bool {
+    item is FileTreeNode
+  }
+
+  func outlineViewSelectionDidChange(_ notification: Notification) {
+    guard !isSyncing,
+      let node = outlineView.item(atRow: outlineView.selectedRow) as? FileTreeNode
+    else { return }
+    model.selectedFileTreePath = node.path
+  }
+
+  func outlineViewItemDidExpand(_ notification: Notification) {
+    guard !isSyncing, let node = notification.userInfo?["NSObject"] as? FileTreeNode else {
+      return
+    }
+    model.expandDirectory(node)
+  }
+
+  func outlineViewItemDidCollapse(_ notification: Notification) {
+    guard !isSyncing, let node = notification.userInfo?["NSObject"] as? FileTreeNode else {
+      return
+    }
+    model.collapseDirectory(node)
+  }
+
+  // MARK: Clicks and Keys
+
+  /// Single click: a directory toggles, a file opens in the preview tab.
+  @objc private func rowClicked(_ sender: Any?) {
+    guard let node = outlineView.item(atRow: outlineView.clickedRow) as? FileTreeNode else {
+      return
+    }
+    // A click on the disclosure triangle has already toggled the row.
+    if node.isDirectory {
+      let point = outlineView.convert(NSApp.currentEvent?.locationInWindow ?? .zero, from: nil)
+      let cellFrame = outlineView.frameOfCell(atColumn: 0, row: outlineView.clickedRow)
+      guard point.x >= cellFrame.minX else { return }
+      node.isExpanded ? model.collapseDirectory(node) : model.expandDirectory(node)
+    } else if let onPreviewFile = model.onPreviewFile {
+      onPreviewFile(node.path)
+    } else {
+      model.onOpenFile?(node.path, nil)
+    }
+  }
+
+  /// Double click keeps the file open (the first click previewed it).
+  @objc private func rowDoubleClicked(_ sender: Any?) {
+    guard let node = outlineView.item(atRow: outlineView.clickedRow) as? FileTreeNode,
+      !node.isDirectory
+    else { return }
+    model.onOpenFile?(node.path, nil)
+  }
+
+  /// Return key: open the selected file, or toggle the selected directory.
+  private func activateSelection() {
+    guard let node = outlineView.item(atRow: outlineView.selectedRow) as? FileTreeNode else {
+      return
+    }
+    if node.isDirectory {
+      node.isExpanded ? model.collapseDirectory(node) : model.expandDirectory(node)
+    } else {
+      model.onOpenFile?(node.path, nil)
+    }
+  }
+
+  // MARK: Inline Name Field
+
+  private func beginEditing(_ edit: FileTreeEdit, text: String? = nil) {
+    let item: Any?
+    let initialName: String
+    switch edit {
+    case .newEntry:
+      item = newEntry
+      initialName = ""
+    case .rename(let path):
+      // A duplicated file isn't in the tree until it refreshes; the next
+      // update tries again.
+      let node = node(at: path)
+      item = node
+      initialName = node?.name ?? ""
+    }
+    let row = item.map { outlineView.row(forItem: $0) } ?? -1
+    guard row >= 0,
+      let cell = outlineView.view(atColumn: 0, row: row, makeIfNecessary: true)
+        as? FileTreeCellView
+    else { return }
+    outlineView.scrollRowToVisible(row)
+    let field = cell.beginEditing(name: text ?? initialName, delegate: self)
+    editing = InlineEdit(edit: edit, cell: cell, field: field, initialName: initialName)
+  }
+
+  /// Drop the field without committing, e.g. when the edit was cancelled
+  /// elsewhere.
+  private func endEditing() {
+    guard let current = editing else { return }
+    editing = nil
+    if let editor = current.field.currentEditor(),
+      outlineView.window?.firstResponder === editor
+    {
+      outlineView.window?.makeFirstResponder(outlineView)
+    }
+    current.cell.endEditing()
+  }
+
+  func control(
+    _ control: NSControl, textView: NSTextView, doCommandBy commandSelector: Selector
+  ) -> Bool {
+    guard commandSelector == #selector(NSResponder.cancelOperation(_:)) else { return false }
+    model.fileTreeEdit = nil
+    return true
+  }
+
+  /// Return commits, keeping the field open with the error if that fails;
+  /// leaving the field commits too, and gives up on an error.
+  func controlTextDidEndEditing(_ notification: Notification) {
+    guard let current = editing, notification.object as? NSTextField === current.field else {
+      return
+    }
+    let (edit, field, initialName) = (current.edit, current.field, current.initialName)
+    let movement = notification.userInfo?["NSTextMovement"] as? Int
+    let keepOnError = movement == NSTextMovement.return.rawValue
+    let name = field.stringValue
+
+    guard model.fileTreeEdit == edit else { return }
+    guard !name.trimmingCharacters(in: .whitespaces).isEmpty, name != initialName else {
+      model.fileTreeEdit = nil
+      return
+    }
+    guard let message = model.onCommitFileTreeEdit?(edit, name) else {
+      model.fileTreeEdit = nil
+      return
+    }
+    if keepOnError {
+      current.cell.showError(message)
+      DispatchQueue.main.async { field.window?.makeFirstResponder(field) }
+    } else {
+      NSLog("FileTree: \(message)")
+      model.fileTreeEdit = nil
+    }
+  }
+
+  func controlTextDidChange(_ notification: Notification) {
+    editing?.cell.showError(nil)
+  }
+
+  // MARK: Context Menu
+
+  func menuNeedsUpdate(_ menu: NSMenu) {
+    menu.removeAllItems()
+    guard let node = outlineView.item(atRow: outlineView.clickedRow) as? FileTreeNode else {
+      return
+    }
+    let root = model.fileTreeRootPath
+
+    if node.isDirectory {
+      menu.addItem(actionItem("New File") { [weak self] in self?.model.onNewFile?(node.path) })
+      menu.addItem(actionItem("New Folder") { [weak self] in self?.model.onNewFolder?(node.path) })
+      menu.addItem(.separator())
+    }
+
+    menu.addItem(
+      actionItem("Reveal in Finder") {
+        if node.isDirectory {
+          NSWorkspace.shared.selectFile(nil, inFileViewerRootedAtPath: node.path)
+        } else {
+          NSWorkspace.shared.activateFileViewerSelecting([URL(fileURLWithPath: node.path)])
+        }
+      })
+    for command in copyPathCommands {
+      menu.addItem(
+        actionItem(command.title) {
+          guard
+            let text = ImpulseCore.pathForCopy(commandId: command.id, path: node.path, root: root)
+          else { return }
+          NSPasteboard.general.clearContents()
+          NSPasteboard.general.setString(text, forType: .string)
+        })
+    }
+
+    if !node.isDirectory {
+      menu.addItem(.separator())
+      menu.addItem(
+        actionItem("Open with Default App") {
+          NSWorkspace.shared.open(URL(fileURLWithPath: node.path))
+        })
+    }
+
+    if !node.isDirectory, node.gitStatus == .modified || node.gitStatus == .added {
+      menu.addItem(.separator())
+      menu.addItem(actionItem("Discard Changes") { [weak self] in self?.discardChanges(node) })
+    }
+
+    menu.addItem(.separator())
+    menu.addItem(
+      actionItem("Rename") { [weak self] in self?.model.fileTreeEdit = .rename(path: node.path) })
+    menu.addItem(
+      actionItem("Duplicate") { [weak self] in self?.model.onDuplicateFile?(node.path) })
+    menu.addItem(actionItem("Move to Trash") { [weak self] in self?.moveToTrash(node) })
+    menu.addItem(.separator())
+    menu.addItem(actionItem("Properties") { FilePropertiesAlert.run(path: node.path) })
+  }
+
+  private func actionItem(_ title: String, action: @escaping () -> Void) -> NSMenuItem {
+    let item = NSMenuItem(title: title, action: #selector(performMenuAction(_:)), keyEquivalent: "")
+    item.target = self
+    item.representedObject = MenuAction(action)
+    return item
+  }
+
+  @objc private func performMenuAction(_ sender: NSMenuItem) {
+    (sender.representedObject as? MenuAction)?.run()
+  }
+
+  private func discardChanges(_ node: FileTreeNode) {
+    let alert = NSAlert()
+    alert.messageText = "Discard Changes"
+    alert.informativeText =
+      "Are you sure you want to discard all changes to \"\(node.name)\"? This cannot be undone."
+    alert.alertStyle = .warning
+    alert.addButton(withTitle: "Discard")
+    alert.addButton(withTitle: "Cancel")
+    guard alert.runModal() == .alertFirstButtonReturn else { return }
+    if ImpulseCore.gitDiscardChanges(filePath: node.path, workspaceRoot: model.fileTreeRootPath) {
+      NotificationCenter.default.post(
+        name: .impulseReloadEditorFile,
+        object: nil,
+        userInfo: ["path": node.path]
+      )
+      model.onRefreshTree?()
+    }
+  }
+
+  private func moveToTrash(_ node: FileTreeNode) {
+    do {
+      try FileManager.default.trashItem(at: URL(fileURLWithPath: node.path), resultingItemURL: nil)
+      model.onRefreshTree?()
+    } catch {
+      let alert = NSAlert()
+      alert.messageText = "Move to Trash Failed"
+      alert.informativeText = error.localizedDescription
+      alert.alertStyle = .warning
+      alert.runModal()
+    }
+  }
+
+  // MARK: Drag and Drop
+
+  func outlineView(
+    _ outlineView: NSOutlineView, pasteboardWriterForItem item: Any
+  ) -> NSPasteboardWriting? {
+    guard let node = item as? FileTreeNode else { return nil }
+    return NSURL(fileURLWithPath: node.path)
+  }
+
+  /// Drops land in a directory: the one under the pointer, a file's parent,
+  /// or the root between rows.
+  func outlineView(
+    _ outlineView: NSOutlineView, validateDrop info: NSDraggingInfo, proposedItem item: Any?,
+    proposedChildIndex index: Int
+  ) -> NSDragOperation {
+    let isInternal = info.draggingSource as? NSOutlineView === outlineView
+    var target = item as? FileTreeNode
+    if let node = target, !node.isDirectory {
+      target = outlineView.parent(forItem: node) as? FileTreeNode
+    }
+    dropOpensWorkspace = item == nil && !isInternal
+    outlineView.setDropItem(target, dropChildIndex: NSOutlineViewDropOnItemIndex)
+    return isInternal ? .move : .copy
+  }
+
+  func outlineView(
+    _ outlineView: NSOutlineView, acceptDrop info: NSDraggingInfo, item: Any?, childIndex index: Int
+  ) -> Bool {
+    let urls =
+      info.draggingPasteboard.readObjects(
+        forClasses: [NSURL.self], options: [.urlReadingFileURLsOnly: true]) as? [URL] ?? []
+    let paths = urls.map(\.path)
+    guard !paths.isEmpty else { return false }
+    let root = model.fileTreeRootPath
+    let onComplete = { [weak self] in self?.model.onRefreshTree?() }
+    if let node = item as? FileTreeNode {
+      FileDropHelper.handleDrop(
+        paths: paths, targetDir: node.path, projectRoot: root, onComplete: onComplete)
+    } else if !dropOpensWorkspace {
+      FileDropHelper.handleDrop(
+        paths: paths, targetDir: root, projectRoot: root, onComplete: onComplete)
+    } else {
+      FileDropHelper.handleRootDrop(
+        paths: paths, projectRoot: root,
+        onOpenWorkspace: { [weak self] in self?.model.onOpenWorkspace?($0) },
+        onComplete: onComplete)
+    }
+    return true
+  }
+}
+
+// MARK: - Outline Pieces
+
+/// An open inline name field and the edit it commits.
+private struct InlineEdit {
+  let edit: FileTreeEdit
+  let cell: FileTreeCellView
+  let field: NSTextField
+  let initialName: String
+}
+
+/// The New File / New Folder row, shown first in its directory while the
+/// name is typed.
+private final class NewEntryItem {
+  let directory: String
+  let isDirectory: Bool
+
+  init(directory: String, isDirectory: Bool) {
+    self.directory = directory
+    self.isDirectory = isDirectory
+  }
+}
+
+private final class MenuAction {
+  let run: () -> Void
+
+  init(_ run: @escaping () -> Void) {
+    self.run = run
+  }
+}
+
+/// Outline view that opens the selection on Return.
+final class FileTreeOutline: NSOutlineView {
+  var onActivate: (() -> Void)?
+
+  override func keyDown(with event: NSEvent) {
+    // Return and keypad Enter.
+    if event.keyCode == 36 || event.keyCode == 76 {
+      onActivate?()
+    } else {
+      super.keyDown(with: event)
+    }
+  }
+}
+
+/// Row view tinting the file open in the active editor tab.
+final class FileTreeRowView: NSTableRowView {
+  var activeColor: NSColor?
+  var isActive = false {
+    didSet { if isActive != oldValue { needsDisplay = true } }
+  }
+
+  override func drawBackground(in dirtyRect: NSRect) {
+    super.drawBackground(in: dirtyRect)
+    guard isActive, !isSelected, let activeColor else { return }
+    activeColor.setFill()
+    NSBezierPath(roundedRect: bounds.insetBy(dx: 4, dy: 0), xRadius: 5, yRadius: 5).fill()
+  }
+}
+
+/// A file tree row: themed icon, name colored by git status, symlink
+/// target, and the git status badge. Watches its node so a status change
+/// repaints just this row.
+final class FileTreeCellView: NSTableCellView {
+  private let icon = NSImageView()
+  private let name = NSTextField(labelWithString: "")
+  private let linkTarget = NSTextField(labelWithString: "")
+  private let badge = NSTextField(labelWithString: "")
+  private weak var node: FileTreeNode?
+  private var theme: Theme?
+  private weak var iconCache: IconCache?
+
+  init(identifier: NSUserInterfaceItemIdentifier) {
+    super.init(frame: .zero)
+    self.identifier = identifier
+    imageView = icon
+    textField = name
+
+    name.font = .systemFont(ofSize: 13)
+    name.lineBreakMode = .byTruncatingMiddle
+    name.cell?.truncatesLastVisibleLine = true
+    linkTarget.font = .systemFont(ofSize: 11)
+    linkTarget.lineBreakMode = .byTruncatingHead
+    badge.font = .monospacedSystemFont(ofSize: 10, weight: .bold)
+    badge.alignment = .right
+
+    name.maximumNumberOfLines = 1
+    let spacer = NSView()
+    spacer.setContentHuggingPriority(.init(1), for: .horizontal)
+    let stack = NSStackView(views: [icon, name, linkTarget, spacer, badge])
+    stack.orientation = .horizontal
+    stack.spacing = 6
+    stack.translatesAutoresizingMaskIntoConstraints = false
+    name.setContentHuggingPriority(.defaultHigh, for: .horizontal)
+    name.setContentCompressionResistancePriority(.defaultLow, for: .horizontal)
+    linkTarget.setContentCompressionResistancePriority(.defaultLow - 1, for: .horizontal)
+    badge.setContentCompressionResistancePriority(.required, for: .horizontal)
+    addSubview(stack)
+    NSLayoutConstraint.activate([
+      icon.widthAnchor.constraint(equalToConstant: 16),
+      icon.heightAnchor.constraint(equalToConstant: 16),
+      stack.leadingAnchor.constraint(equalTo: leadingAnchor),
+      stack.trailingAnchor.constraint(equalTo: trailingAnchor, constant: -8),
+      stack.centerYAnchor.constraint(equalTo: centerYAnchor),
+    ])
+  }
+
+  required init?(coder: NSCoder) {
+    fatalError("init(coder:) has not been implemented")
+  }
+
+  func configure(node: FileTreeNode, theme: Theme, iconCache: IconCache?) {
+    self.node = node
+    self.theme = theme
+    self.iconCache = iconCache
+    endEditing()
+    refresh()
+  }
+
+  func configure(newEntryIsDirectory isDirectory: Bool, theme: Theme) {
+    node = nil
+    self.theme = theme
+    icon.image = NSImage(
+      systemSymbolName: isDirectory ? "folder.fill" : "doc.fill", accessibilityDescription: nil)
+    icon.contentTintColor = NSColor(hex: isDirectory ? theme.accent : theme.fgMuted)
+    name.stringValue = ""
+    name.textColor = NSColor(hex: theme.fg)
+    linkTarget.isHidden = true
+    badge.isHidden = true
+    toolTip = nil
+    setAccessibilityLabel(isDirectory ? "New folder name" : "New file name")
+  }
+
+  /// Make the name editable and focus it with `name` selected.
+  func beginEditing(name value: String, delegate: NSTextFieldDelegate) -> NSTextField {
+    name.isEditable = true
+    name.isBordered = true
+    name.isBezeled = true
+    name.bezelStyle = .squareBezel
+    name.drawsBackground = true
+    name.stringValue = value
+    name.delegate = delegate
+    window?.makeFirstResponder(name)
+    name.currentEditor()?.selectAll(nil)
+    return name
+  }
+
+  func endEditing() {
+    guard name.isEditable else { return }
+    name.delegate = nil
+    name.isEditable = false
+    name.isBordered = false
+    name.isBezeled = false
+    name.drawsBackground = false
+    name.toolTip = nil
+    refresh()
+  }
+
+  /// Mark the field red with `message` as its tooltip; `nil` clears it.
+  func showError(_ message: String?) {
+    guard let theme else { return }
+    name.textColor = NSColor(hex: message == nil ? theme.fg : theme.red)
+    name.toolTip = message
+  }
+
+  private func refresh() {
+    guard let node, let theme else { return }
+    icon.image =
+      iconCache?.icon(filename: node.name, isDirectory: node.isDirectory, expanded: node.isExpanded)
+      ?? NSImage(
+        systemSymbolName: node.isDirectory ? "folder.fill" : "doc.fill",
+        accessibilityDescription: nil)
+    icon.contentTintColor = NSColor(hex: node.isDirectory ? theme.accent : theme.fgMuted)
+    if !name.isEditable {
+      name.stringValue = node.name
+    }
+
+    let status = Self.gitBadge(node.gitStatus, theme: theme)
+    name.textColor =
+      status?.color ?? NSColor(hex: node.gitStatus == .ignored ? theme.gitIgnored : theme.fg)
+    badge.stringValue = status?.letter ?? ""
+    badge.textColor = status?.color
+    badge.isHidden = status == nil
+
+    linkTarget.isHidden = !node.isSymlink
+    linkTarget.stringValue = "\u{2192} \(node.symlinkTarget ?? "?")"
+    linkTarget.textColor = NSColor(hex: theme.fgMuted)
+    toolTip = node.isSymlink ? "Symbolic link to \(node.symlinkTarget ?? "?")" : nil
+    setAccessibilityLabel(Self.accessibilityDescription(of: node))
+
+    observe(node)
+  }
+
+  /// Repaint when the node's status, expansion (folder icon) or link
+  /// changes. Observation fires once, so `refresh` registers again.
+  private func observe(_ node: FileTreeNode) {
+    withObservationTracking {
+      _ = node.gitStatus
+      _ = node.isExpanded
+      _ = node.symlinkTarget
+    } onChange: { [weak self, weak node] in
+      DispatchQueue.main.async {
+        guard let self, let node, self.node === node else { return }
+        self.refresh()
+      }
+    }
+  }
+
+  /// What VoiceOver reads for `node`'s row, e.g. "main.rs, file, modified",
+  /// matching the Linux tree (`FileEntry::accessible_label`). The outline
+  /// reports the expanded state itself.
+  static func accessibilityDescription(of node: FileTreeNode) -> String {
+    var parts = [node.name]
+    switch (node.isDirectory, node.isSymlink) {
+    case (true, false): parts.append("folder")
+    case (true, true): parts.append("folder link")
+    case (false, false): parts.append("file")
+    case (false, true): parts.append("file link")
+    }
+    switch node.gitStatus {
+    case .none: break
+    case .conflict: parts.append("conflicted")
+    default: parts.append(node.gitStatus.rawValue)
+    }
+    return parts.joined(separator: ", ")
+  }
+
+  /// Badge letter and color for both name and badge. Uses the theme's
+  /// audited git tones — system colors like `.yellow` are unreadable on
+  /// light sidebar canvases (e.g. Harbor).
+  private static func gitBadge(
+    _ status: FileTreeNode.GitStatus, theme: Theme
+  ) -> (letter: String, color: NSColor)? {
+    switch status {
+    case .modified: return ("M", NSColor(hex: theme.gitModified))
+    case .added: return ("A", NSColor(hex: theme.gitAdded))
+    case .untracked: return ("?", NSColor(hex: theme.gitAdded))
+    case .deleted: return ("D", NSColor(hex: theme.gitDeleted))
+    case .renamed: return ("R", NSColor(hex: theme.gitRenamed))
+    case .conflict: return ("C", NSColor(hex: theme.gitConflict))
+    case .ignored, .none: return nil
+    }
+  }
+}
@@ -0,0 +1,113 @@
+import SwiftUI
+
+/// "Show All Tabs": every open tab with a search, for when the tab bar has
+/// too many to read. Choosing one selects it.
+struct AllTabsPanelView: View {
+  var model: WindowModel
+  @State private var query = ""
+  @State private var highlighted = 0
+  @FocusState private var searchFocused: Bool
+
+  /// The tabs matching `query`, in tab order.
+  private var matches: [TabDisplayInfo] {
+    let tabs = model.tabDisplayInfos
+    let summaries = tabs.map {
+      ImpulseCore.TabSummary(
+        title: $0.title, path: $0.filePath, pinned: $0.isPinned, modified: false, lastUsed: 0)
+    }
+    return ImpulseCore.tabFilter(summaries, query: query).map { tabs[$0] }
+  }
+
+  var body: some View {
+    let matches = matches
+    VStack(alignment: .leading, spacing: 6) {
+      TextField("Search open tabs", text: $query)
+        .textFieldStyle(.roundedBorder)
+        .focused($searchFocused)
+        .onSubmit { choose(matches, at: highlighted) }
+        .onKeyPress(.downArrow) {
+          highlighted = min(highlighted + 1, max(matches.count - 1, 0))
+          return .handled
+        }
+        .onKeyPress(.upArrow) {
+          highlighted = max(highlighted - 1, 0)
+          return .handled
+        }
+        .onChange(of: query) { _, _ in highlighted = 0 }
+      if matches.isEmpty {
+        Text("No matching tabs")
+          .foregroundStyle(.secondary)
+          .frame(maxWidth: .infinity)
+          .padding(.vertical, 12)
+      } else {
+        ScrollViewReader { proxy in
+          ScrollView {
+            LazyVStack(alignment: .leading, spacing: 1) {
+              ForEach(Array(matches.enumerated()), id: \.element.id) { position, tab in
+                row(tab, highlighted: position == highlighted)
+                  .id(tab.id)
+                  .onTapGesture { choose(matches, at: position) }
+              }
+            }
+          }
+          .frame(maxHeight: 420)
+          .onChange(of: highlighted) { _, position in
+            if matches.indices.contains(position) {
+              proxy.scrollTo(matches[position].id)
+            }
+          }
+        }
+      }
+    }
+    .padding(10)
+    .frame(width: 420)
+    .onAppear {
+      highlighted = matches.firstIndex { $0.index == model.selectedTabIndex } ?? 0
+      searchFocused = true
+    }
+  }
+
+  private func row(_ tab: TabDisplayInfo, highlighted: Bool) -> some View {
+    HStack(spacing: 8) {
+      if let icon = tab.icon {
+        Image(nsImage: icon)
+          .resizable()
+          .interpolation(.high)
+          .frame(width: 14, height: 14)
+          .accessibilityHidden(true)
+      }
+      VStack(alignment: .leading, spacing: 1) {
+        Text(tab.title)
+          .lineLimit(1)
+          .fontWeight(tab.index == model.selectedTabIndex ? .semibold : .regular)
+        if let path = tab.filePath {
+          Text(path)
+            .font(.caption)
+            .foregroundStyle(.secondary)
+            .lineLimit(1)
+            .truncationMode(.head)
+        }
+      }
+      Spacer(minLength: 0)
+      if tab.isPinned {
+        Image(systemName: "pin.fill")
+          .font(.system(size: 9))
+          .foregroundStyle(.tertiary)
+          .accessibilityLabel("Pinned")
+      }
+    }
+    .padding(.horizontal, 6)
+    .padding(.vertical, 4)
+    .background(
+      RoundedRectangle(cornerRadius: 5)
+        .fill(highlighted ? model.theme.colorBgHighlight : .clear)
+    )
+    .contentShape(Rectangle())
+  }
+
+  private func choose(_ matches: [TabDisplayInfo], at position: Int) {
+    guard matches.indices.contains(position) else { return }
+    model.allTabsPanelVisible = false
+    model.onTabSelected?(matches[position].index)
+  }
+}
@@ -97,7 +97,7 @@ struct CompletionPopupView: View {
 
   /// Folder icon for directories, file-type icon otherwise. Falls back to SF
   /// Symbols when the icon cache can't resolve an image (mirrors
-  /// `FileTreeListView.fileIcon`).
+  /// `FileTreeCellView`).
   @ViewBuilder
   private func icon(for candidate: CompletionCandidate) -> some View {
     if let nsImage = iconCache?.icon(
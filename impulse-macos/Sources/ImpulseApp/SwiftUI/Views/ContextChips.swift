@@ -92,6 +92,60 @@ struct BranchChip: View {
   }
 }
 
+/// The working directory as a breadcrumb: each directory opens in the
+/// sidebar, and the last one opens a menu of the active terminal's directory
+/// history that cds the terminal.
+struct CwdBreadcrumbChip: View {
+  var model: WindowModel
+  let cwd: String
+
+  var body: some View {
+    let crumbs = ImpulseCore.cwdBreadcrumbs(cwd)
+    HStack(spacing: 0) {
+      Image(systemName: "folder")
+        .font(.system(size: 9.5, weight: .medium))
+        .foregroundStyle(model.theme.colorFgComment)
+        .padding(.trailing, 4)
+      ForEach(Array(crumbs.enumerated()), id: \.offset) { index, crumb in
+        if index > 0 && !(index == 1 && crumbs[0].label == "/") {
+          Text("/").foregroundStyle(model.theme.colorFgComment)
+        }
+        if index == crumbs.count - 1 {
+          Menu {
+            Button("Open in Sidebar") { model.onOpenDirectoryInSidebar?(crumb.path) }
+            let history = (model.onDirectoryHistory?() ?? []).filter { $0 != crumb.path }
+            if !history.isEmpty {
+              Divider()
+              ForEach(history, id: \.self) { dir in
+                Button(TabManager.abbreviateHomePath(dir)) { model.onChangeDirectory?(dir) }
+              }
+            }
+          } label: {
+            Text(crumb.label).foregroundStyle(model.theme.colorFgMuted)
+          }
+          .menuStyle(.borderlessButton)
+          .menuIndicator(.hidden)
+          .fixedSize()
+          .help("Directory History")
+        } else {
+          Button {
+            model.onOpenDirectoryInSidebar?(crumb.path)
+          } label: {
+            Text(crumb.label).foregroundStyle(model.theme.colorFgMuted)
+          }
+          .buttonStyle(.plain)
+          .help("Open \(TabManager.abbreviateHomePath(crumb.path)) in Sidebar")
+        }
+      }
+    }
+    .font(.system(size: 11, design: .monospaced))
+    .lineLimit(1)
+    .padding(.horizontal, 8)
+    .padding(.vertical, 3)
+    .background(Capsule().fill(model.theme.colorFg.opacity(0.07)))
+  }
+}
+
 /// Warp-style "Review Changes" summary chip: a page glyph, the changed-file
 /// count, and the aggregate +added / -removed line counts (green/red). Sits next
 /// to the branch chip and opens the Review Changes tab when tapped. Rendered only
@@ -1,423 +1,36 @@
 import AppKit
 import SwiftUI
 
-/// Displays the project file tree as a flat, virtualized scrollable list.
-/// Each expanded directory's children appear as separate entries with
-/// calculated indentation — no recursive view nesting. This ensures
-/// LazyVStack only materializes visible rows regardless of how many
-/// folders are expanded.
-struct FileTreeListView: View {
-  var model: WindowModel
-  @State private var isRootDropTarget = false
-
-  var body: some View {
-    ScrollViewReader { proxy in
-      ScrollView {
-        LazyVStack(alignment: .leading, spacing: 0) {
-          ForEach(model.flatFileTree) { entry in
-            FlatFileRowView(node: entry.node, depth: entry.depth, model: model)
-              .id(entry.id)
-          }
-        }
-        .frame(maxWidth: .infinity, alignment: .leading)
-      }
-      .focusable()
-      // Keyboard focus is needed for arrow-key navigation, but the system
-      // focus ring around the whole tree is not standard sidebar behavior
-      // (Finder/Xcode draw none).
-      .focusEffectDisabled()
-      .onMoveCommand { direction in
-        handleMoveCommand(direction, proxy: proxy)
-      }
-      .onKeyPress(.return) {
-        activateSelection()
-        return .handled
-      }
-      .accessibilityLabel("Project files")
-      .onDrop(of: [.fileURL], isTargeted: $isRootDropTarget) { providers in
-        FileDropHelper.handleDrop(
-          providers: providers,
-          targetDir: model.fileTreeRootPath,
-          projectRoot: model.fileTreeRootPath,
-          onComplete: { model.onRefreshTree?() }
-        )
-        return true
-      }
-      .background(isRootDropTarget ? model.theme.colorAccent.opacity(0.08) : Color.clear)
-    }
-  }
-
-  // MARK: - Keyboard Navigation
-
-  private var selectedIndex: Int? {
-    guard let selected = model.selectedFileTreePath else { return nil }
-    return model.flatFileTree.firstIndex { $0.node.path == selected }
-  }
-
-  private func handleMoveCommand(_ direction: MoveCommandDirection, proxy: ScrollViewProxy) {
-    switch direction {
-    case .down:
-      moveSelection(by: 1, proxy: proxy)
-    case .up:
-      moveSelection(by: -1, proxy: proxy)
-    case .right:
-      guard let index = selectedIndex else { return }
-      let node = model.flatFileTree[index].node
-      if node.isDirectory && !node.isExpanded {
-        model.expandDirectory(node)
-      } else if node.isDirectory {
-        // Already expanded — move into the first child, like Finder.
-        moveSelection(by: 1, proxy: proxy)
-      }
-    case .left:
-      handleLeftCommand(proxy: proxy)
-    @unknown default:
-      break
-    }
-  }
-
-  private func moveSelection(by delta: Int, proxy: ScrollViewProxy) {
-    let entries = model.flatFileTree
-    guard !entries.isEmpty else { return }
-    let newIndex: Int
-    if let current = selectedIndex {
-      newIndex = min(max(current + delta, 0), entries.count - 1)
-    } else {
-      newIndex = delta > 0 ? 0 : entries.count - 1
-    }
-    let entry = entries[newIndex]
-    model.selectedFileTreePath = entry.node.path
-    proxy.scrollTo(entry.id)
-  }
-
-  /// Left arrow: collapse an expanded directory, otherwise jump to the parent.
-  private func handleLeftCommand(proxy: ScrollViewProxy) {
-    guard let index = selectedIndex else { return }
-    let entries = model.flatFileTree
-    let entry = entries[index]
-    if entry.node.isDirectory && entry.node.isExpanded {
-      model.collapseDirectory(entry.node)
-      return
-    }
-    var i = index - 1
-    while i >= 0 {
-      if entries[i].depth < entry.depth {
-        model.selectedFileTreePath = entries[i].node.path
-        proxy.scrollTo(entries[i].id)
-        return
-      }
-      i -= 1
-    }
-  }
-
-  /// Return key: open the selected file, or toggle the selected directory.
-  private func activateSelection() {
-    guard let index = selectedIndex else { return }
-    let node = model.flatFileTree[index].node
-    if node.isDirectory {
-      node.isExpanded ? model.collapseDirectory(node) : model.expandDirectory(node)
-    } else {
-      model.onOpenFile?(node.path, nil)
-    }
-  }
+/// A copy path command offered in the file tree and tab context menus.
+struct CopyPathCommand: Identifiable {
+  let id: String
+  let title: String
 }
 
-/// A single flat row in the file tree. Not recursive — children are separate
-/// entries in the flat list with incremented depth.
-private struct FlatFileRowView: View {
-  @Bindable var node: FileTreeNode
-  let depth: Int
+let copyPathCommands = [
+  CopyPathCommand(id: "copy_path", title: "Copy Path"),
+  CopyPathCommand(id: "copy_relative_path", title: "Copy Relative Path"),
+  CopyPathCommand(id: "copy_file_name", title: "Copy File Name"),
+  CopyPathCommand(id: "copy_file_url", title: "Copy File URL"),
+]
+
+/// The project file tree: an `NSOutlineView` (`FileTreeOutlineView`) fed
+/// from `WindowModel`. Passing the tree's flat entries, selection, inline
+/// edit and reveal token as values makes SwiftUI update the outline whenever
+/// one of them changes.
+struct FileTreeListView: View {
   var model: WindowModel
-  @State private var isHovered = false
-  @State private var isDropTarget = false
-
-  private var isActiveFile: Bool {
-    !node.isDirectory && node.path == model.activeFilePath
-  }
-
-  private var isSelectedTreeItem: Bool {
-    node.path == model.selectedFileTreePath
-  }
-
-  private var rowBackground: Color {
-    if isDropTarget && node.isDirectory {
-      return model.theme.colorAccent.opacity(0.3)
-    } else if isActiveFile {
-      return model.theme.colorAccent.opacity(0.2)
-    } else if isSelectedTreeItem {
-      return Color.primary.opacity(0.08)
-    } else if isHovered {
-      return Color.primary.opacity(0.06)
-    }
-    return Color.clear
-  }
 
   var body: some View {
-    HStack(spacing: 0) {
-      if depth > 0 {
-        Spacer()
-          .frame(width: CGFloat(depth) * 16)
-      }
-
-      if node.isDirectory {
-        Image(systemName: node.isExpanded ? "chevron.down" : "chevron.right")
-          .font(.system(size: 10, weight: .medium))
-          .foregroundStyle(model.theme.colorFgMuted.opacity(0.7))
-          .frame(width: 16, height: 16)
-      } else {
-        Spacer().frame(width: 16)
-      }
-
-      FileTreeRow(node: node, theme: model.theme, iconCache: model.iconCache)
-    }
-    .padding(.vertical, 3)
-    .padding(.horizontal, 8)
-    .background(
-      RoundedRectangle(cornerRadius: 5)
-        .fill(rowBackground)
+    FileTreeOutlineView(
+      model: model,
+      entries: model.flatFileTree,
+      selectedPath: model.selectedFileTreePath,
+      activePath: model.activeFilePath,
+      edit: model.fileTreeEdit,
+      revealToken: model.fileTreeRevealToken,
+      theme: model.theme
     )
-    .contentShape(Rectangle())
-    .onHover { hovering in
-      isHovered = hovering
-    }
-    .onDrag { [nodePath = node.path] in
-      NSItemProvider(object: nodePath as NSString)
-    }
-    .onDrop(of: [.fileURL, .text], isTargeted: $isDropTarget) { providers in
-      handleDrop(providers: providers)
-    }
-    .onTapGesture {
-      model.selectedFileTreePath = node.path
-      if node.isDirectory {
-        handleDirectoryTap()
-      } else {
-        model.onOpenFile?(node.path, nil)
-      }
-    }
-    .contextMenu { nodeContextMenu(for: node) }
-  }
-
-  // MARK: - Directory Expand/Collapse
-
-  private func handleDirectoryTap() {
-    if node.isExpanded {
-      model.collapseDirectory(node)
-    } else {
-      model.expandDirectory(node)
-    }
-  }
-
-  /// Handles dropping files/folders onto this node.
-  /// Internal project files are moved; external files (from Finder) are copied.
-  private func handleDrop(providers: [NSItemProvider]) -> Bool {
-    let targetDir: String
-    if node.isDirectory {
-      targetDir = node.path
-    } else {
-      targetDir = (node.path as NSString).deletingLastPathComponent
-    }
-    FileDropHelper.handleDrop(
-      providers: providers,
-      targetDir: targetDir,
-      projectRoot: model.fileTreeRootPath,
-      onComplete: { model.onRefreshTree?() }
-    )
-    return true
-  }
-
-  @ViewBuilder
-  private func nodeContextMenu(for node: FileTreeNode) -> some View {
-    if node.isDirectory {
-      Button("New File...") {
-        model.onNewFile?(node.path)
-      }
-      Button("New Folder...") {
-        model.onNewFolder?(node.path)
-      }
-      Divider()
-    }
-
-    Button("Reveal in Finder") {
-      if node.isDirectory {
-        NSWorkspace.shared.selectFile(nil, inFileViewerRootedAtPath: node.path)
-      } else {
-        NSWorkspace.shared.activateFileViewerSelecting(
-          [URL(fileURLWithPath: node.path)]
-        )
-      }
-    }
-
-    Button("Copy Path") {
-      NSPasteboard.general.clearContents()
-      NSPasteboard.general.setString(node.path, forType: .string)
-    }
-
-    Button("Copy Relative Path") {
-      let relativePath: String
-      if node.path.hasPrefix(model.fileTreeRootPath) {
-        relativePath = String(
-          node.path.dropFirst(model.fileTreeRootPath.count)
-            .drop(while: { $0 == "/" })
-        )
-      } else {
-        relativePath = node.name
-      }
-      NSPasteboard.general.clearContents()
-      NSPasteboard.general.setString(relativePath, forType: .string)
-    }
-
-    if !node.isDirectory {
-      Divider()
-      Button("Open with Default App") {
-        NSWorkspace.shared.open(URL(fileURLWithPath: node.path))
-      }
-    }
-
-    if !node.isDirectory,
-      node.gitStatus == .modified || node.gitStatus == .added
-    {
-      Divider()
-      Button("Discard Changes", role: .destructive) {
-        let alert = NSAlert()
-        alert.messageText = "Discard Changes"
-        alert.informativeText =
-          "Are you sure you want to discard all changes to \"\(node.name)\"? This cannot be undone."
-        alert.alertStyle = .warning
-        alert.addButton(withTitle: "Discard")
-        alert.addButton(withTitle: "Cancel")
-        guard alert.runModal() == .alertFirstButtonReturn else { return }
-        if ImpulseCore.gitDiscardChanges(filePath: node.path, workspaceRoot: model.fileTreeRootPath)
-        {
-          NotificationCenter.default.post(
-            name: .impulseReloadEditorFile,
-            object: nil,
-            userInfo: ["path": node.path]
-          )
-          model.onRefreshTree?()
-        }
-      }
-    }
-
-    Divider()
-
-    Button("Rename…") {
-      let currentName = (node.path as NSString).lastPathComponent
-      let alert = NSAlert()
-      alert.messageText = "Rename"
-      alert.informativeText = "Enter a new name:"
-      alert.addButton(withTitle: "Rename")
-      alert.addButton(withTitle: "Cancel")
-      let input = NSTextField(frame: NSRect(x: 0, y: 0, width: 260, height: 24))
-      input.stringValue = currentName
-      alert.accessoryView = input
-      alert.window.initialFirstResponder = input
-      guard alert.runModal() == .alertFirstButtonReturn else { return }
-      let newName = input.stringValue.trimmingCharacters(in: .whitespaces)
-      guard !newName.isEmpty, newName != currentName,
-        !newName.contains("/"), !newName.contains("\0"),
-        !newName.contains("..")
-      else { return }
-      let parentDir = (node.path as NSString).deletingLastPathComponent
-      let newPath = ((parentDir as NSString).appendingPathComponent(newName) as NSString)
-        .standardizingPath
-      let normalizedParent = (parentDir as NSString).standardizingPath
-      guard (newPath as NSString).deletingLastPathComponent == normalizedParent else { return }
-      do {
-        try FileManager.default.moveItem(atPath: node.path, toPath: newPath)
-        model.onRefreshTree?()
-      } catch {
-        let errAlert = NSAlert()
-        errAlert.messageText = "Rename Failed"
-        errAlert.informativeText = error.localizedDescription
-        errAlert.alertStyle = .warning
-        errAlert.runModal()
-      }
-    }
-
-    Button("Move to Trash", role: .destructive) {
-      do {
-        try FileManager.default.trashItem(
-          at: URL(fileURLWithPath: node.path),
-          resultingItemURL: nil
-        )
-        model.onRefreshTree?()
-      } catch {
-        let errAlert = NSAlert()
-        errAlert.messageText = "Move to Trash Failed"
-        errAlert.informativeText = error.localizedDescription
-        errAlert.alertStyle = .warning
-        errAlert.runModal()
-      }
-    }
-  }
-}
-
-/// A single row: themed icon + file name + git status badge.
-struct FileTreeRow: View {
-  let node: FileTreeNode
-  let theme: Theme
-  var iconCache: IconCache?
-
-  var body: some View {
-    HStack(spacing: 6) {
-      fileIcon
-        .frame(width: 16, height: 16)
-
-      Text(node.name)
-        .font(.system(size: 13))
-        .foregroundStyle(gitNameColor)
-        .lineLimit(1)
-        .truncationMode(.middle)
-
-      Spacer()
-
-      if let info = gitInfo {
-        Text(info.letter)
-          .font(.system(size: 10, weight: .bold, design: .monospaced))
-          .foregroundStyle(info.color)
-      }
-    }
-  }
-
-  @ViewBuilder
-  private var fileIcon: some View {
-    if let nsImage = iconCache?.icon(
-      filename: node.name,
-      isDirectory: node.isDirectory,
-      expanded: node.isExpanded
-    ) {
-      Image(nsImage: nsImage)
-        .resizable()
-        .interpolation(.high)
-    } else if node.isDirectory {
-      Image(systemName: "folder.fill")
-        .font(.system(size: 13))
-        .foregroundStyle(theme.colorAccent)
-    } else {
-      Image(systemName: "doc.fill")
-        .font(.system(size: 13))
-        .foregroundStyle(theme.colorFgMuted)
-    }
-  }
-
-  /// Git status display info: badge letter, color for both name and badge.
-  /// Uses the theme's audited git tones — system colors like `.yellow` are
-  /// unreadable on light sidebar canvases (e.g. Harbor).
-  private var gitInfo: (letter: String, color: Color)? {
-    switch node.gitStatus {
-    case .modified: return ("M", theme.colorGitModified)
-    case .added: return ("A", theme.colorGitAdded)
-    case .untracked: return ("?", theme.colorGitAdded)
-    case .deleted: return ("D", theme.colorGitDeleted)
-    case .renamed: return ("R", theme.colorGitRenamed)
-    case .conflict: return ("C", theme.colorGitConflict)
-    case .ignored: return nil
-    case .none: return nil
-    }
-  }
-
-  private var gitNameColor: Color {
-    gitInfo?.color ?? (node.gitStatus == .ignored ? theme.colorGitIgnored : theme.colorFg)
   }
 }
 
@@ -425,43 +38,37 @@ struct FileTreeRow: View {
 
 /// Shared logic for handling file drops from Finder or internal tree moves.
 enum FileDropHelper {
-  /// Processes drop providers, copying external files or moving internal ones.
+  /// Copies external files into `targetDir`, or moves ones from the project.
   static func handleDrop(
-    providers: [NSItemProvider],
+    paths: [String],
     targetDir: String,
     projectRoot: String,
     onComplete: @escaping () -> Void
   ) {
-    for provider in providers {
-      // Prefer file URL (Finder drops and modern pasteboard).
-      if provider.hasItemConformingToTypeIdentifier("public.file-url") {
-        provider.loadItem(forTypeIdentifier: "public.file-url", options: nil) { data, _ in
-          guard let urlData = data as? Data,
-            let url = URL(dataRepresentation: urlData, relativeTo: nil),
-            url.isFileURL
-          else { return }
-          let sourcePath = url.path
-          DispatchQueue.main.async {
-            processFile(
-              source: sourcePath, targetDir: targetDir,
-              projectRoot: projectRoot, onComplete: onComplete)
-          }
-        }
-      } else if provider.hasItemConformingToTypeIdentifier("public.text") {
-        // Fallback for internal drags that only provide text.
-        provider.loadItem(forTypeIdentifier: "public.text", options: nil) { data, _ in
-          guard let pathData = data as? Data,
-            let sourcePath = String(data: pathData, encoding: .utf8),
-            sourcePath.hasPrefix("/"),
-            FileManager.default.fileExists(atPath: sourcePath)
-          else { return }
-          DispatchQueue.main.async {
-            processFile(
-              source: sourcePath, targetDir: targetDir,
-              projectRoot: projectRoot, onComplete: onComplete)
-          }
-        }
-      }
+    for path in paths {
+      processFile(
+        source: path, targetDir: targetDir,
+        projectRoot: projectRoot, onComplete: onComplete)
+    }
+  }
+
+  /// Processes a drop outside the tree's rows: a single folder opens as the
+  /// workspace, anything else is copied into the project root.
+  static func handleRootDrop(
+    paths: [String],
+    projectRoot: String,
+    onOpenWorkspace: @escaping (String) -> Void,
+    onComplete: @escaping () -> Void
+  ) {
+    var isDirectory: ObjCBool = false
+    if paths.count == 1, let path = paths.first,
+      FileManager.default.fileExists(atPath: path, isDirectory: &isDirectory),
+      isDirectory.boolValue
+    {
+      onOpenWorkspace(path)
+    } else {
+      handleDrop(
+        paths: paths, targetDir: projectRoot, projectRoot: projectRoot, onComplete: onComplete)
     }
   }
 
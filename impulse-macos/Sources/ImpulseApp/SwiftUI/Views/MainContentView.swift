@@ -29,13 +29,15 @@ struct MainContentView: View {
             dismissAction: { windowModel.onDismissSettingsWarning?() }
           )
         }
-        TabBarView(windowModel: windowModel)
+        if !windowModel.tabBarHidden {
+          TabBarView(windowModel: windowModel)
+        }
         contentArea
         if showContextBar {
           // Warp-style: the input bar replaces the status bar for terminals —
           // its chips already carry the shell, cwd, branch, and last status.
           TerminalContextBarView(model: windowModel)
-        } else {
+        } else if !windowModel.statusBarHidden {
           StatusBarView(model: windowModel)
         }
       }
@@ -68,11 +70,23 @@ struct MainContentView: View {
 
   /// The content area (terminal or editor) always renders edge-to-edge to
   /// match the Warp-style terminal — no floating "card" surface, even on
-  /// card-surface themes like Harbor.
+  /// card-surface themes like Harbor. The centered layout (Zen mode) caps
+  /// its width.
   @ViewBuilder
   private var contentArea: some View {
     ContentAreaRepresentable(contentView: tabManagerContentView)
+      .frame(maxWidth: windowModel.centeredLayout ? windowModel.contentMaxWidth : .infinity)
       .frame(maxWidth: .infinity, maxHeight: .infinity)
+      .overlay(alignment: .topTrailing) {
+        // Anchors "Show All Tabs", which also opens while the tab bar is
+        // hidden or in the sidebar.
+        Color.clear
+          .frame(width: 1, height: 1)
+          .padding(.trailing, 16)
+          .popover(isPresented: $windowModel.allTabsPanelVisible, arrowEdge: .top) {
+            AllTabsPanelView(model: windowModel)
+          }
+      }
   }
 }
 
@@ -0,0 +1,96 @@
+import SwiftUI
+
+/// The notification history behind the status bar bell. Opening it marks
+/// everything read; actions run core registry commands.
+struct NotificationsPanelView: View {
+  var model: WindowModel
+
+  var body: some View {
+    VStack(alignment: .leading, spacing: 6) {
+      HStack {
+        Text("Notifications")
+          .font(.headline)
+        Spacer()
+        Button("Clear All") { ImpulseCore.clearNotifications() }
+          .buttonStyle(.borderless)
+          .disabled(model.notifications.isEmpty)
+      }
+      if model.notifications.isEmpty {
+        Text("No notifications")
+          .foregroundStyle(.secondary)
+          .frame(maxWidth: .infinity)
+          .padding(.vertical, 12)
+      } else {
+        ScrollView {
+          LazyVStack(alignment: .leading, spacing: 8) {
+            ForEach(model.notifications) { notification in
+              row(notification)
+            }
+          }
+        }
+        .frame(maxHeight: 420)
+      }
+    }
+    .padding(10)
+    .frame(width: 380)
+    .onAppear { ImpulseCore.markAllNotificationsRead() }
+  }
+
+  private func row(_ notification: ImpulseCore.AppNotification) -> some View {
+    HStack(alignment: .top, spacing: 8) {
+      Image(systemName: symbol(for: notification.severity))
+        .foregroundStyle(tint(for: notification.severity))
+      VStack(alignment: .leading, spacing: 3) {
+        Text(
+          notification.count > 1
+            ? "\(notification.title) (\(notification.count)×)" : notification.title
+        )
+        .fontWeight(.semibold)
+        if !notification.message.isEmpty {
+          Text(notification.message)
+            .font(.callout)
+            .foregroundStyle(.secondary)
+            .textSelection(.enabled)
+        }
+        if !notification.actions.isEmpty {
+          HStack {
+            ForEach(notification.actions, id: \.self) { action in
+              Button(action.label) {
+                model.notificationsPanelVisible = false
+                // After the popover closes so the window is key again.
+                DispatchQueue.main.async {
+                  CommandPaletteWindow.builtinAction(for: action.command)()
+                }
+              }
+            }
+          }
+          .padding(.top, 2)
+        }
+      }
+      Spacer(minLength: 0)
+      Button {
+        ImpulseCore.dismissNotification(id: notification.id)
+      } label: {
+        Image(systemName: "xmark")
+      }
+      .buttonStyle(.borderless)
+      .help("Dismiss")
+    }
+  }
+
+  private func symbol(for severity: ImpulseCore.NotificationSeverity) -> String {
+    switch severity {
+    case .info: return "info.circle"
+    case .warning: return "exclamationmark.triangle"
+    case .error: return "xmark.octagon"
+    }
+  }
+
+  private func tint(for severity: ImpulseCore.NotificationSeverity) -> Color {
+    switch severity {
+    case .info: return model.theme.colorBlue
+    case .warning: return model.theme.colorYellow
+    case .error: return model.theme.colorRed
+    }
+  }
+}
@@ -17,6 +17,47 @@ struct SidebarSearchBar: View {
   @FocusState private var fieldFocused: Bool
 
   var body: some View {
+    VStack(spacing: 4) {
+      searchRow
+      scopeRow
+    }
+    .padding(.horizontal, 10)
+    .padding(.vertical, 6)
+    // Defer focus to the next runloop tick: setting @FocusState synchronously
+    // in onAppear (or in onChange while the view is still being committed)
+    // races the field joining the responder chain inside NavigationSplitView /
+    // NSHostingView, and the focus is silently dropped.
+    .onAppear { focusField() }
+    .onChange(of: model.searchFocusToken) { _, _ in focusField() }
+  }
+
+  /// Where to search, plus the globs for the custom glob scope.
+  private var scopeRow: some View {
+    HStack(spacing: 6) {
+      Picker("Scope", selection: $model.searchScopeKind) {
+        ForEach(SearchScopeKind.allCases) { kind in
+          Text(kind.title).tag(kind)
+        }
+      }
+      .labelsHidden()
+      .pickerStyle(.menu)
+      .controlSize(.small)
+      .fixedSize()
+      .help("Search Scope")
+      .onChange(of: model.searchScopeKind) { _, _ in model.runSearchNow() }
+
+      if model.searchScopeKind == .glob {
+        TextField("src/**/*.rs, !*_test.rs", text: $model.searchGlob)
+          .textFieldStyle(.plain)
+          .font(.system(size: 11, design: .monospaced))
+          .onSubmit { model.runSearchNow() }
+          .onChange(of: model.searchGlob) { _, _ in model.scheduleSearch() }
+      }
+      Spacer(minLength: 0)
+    }
+  }
+
+  private var searchRow: some View {
     HStack(spacing: 6) {
       Image(systemName: "magnifyingglass")
         .font(.system(size: 11))
@@ -70,14 +111,6 @@ struct SidebarSearchBar: View {
       .buttonStyle(.plain)
       .help("Close Search")
     }
-    .padding(.horizontal, 10)
-    .padding(.vertical, 6)
-    // Defer focus to the next runloop tick: setting @FocusState synchronously
-    // in onAppear (or in onChange while the view is still being committed)
-    // races the field joining the responder chain inside NavigationSplitView /
-    // NSHostingView, and the focus is silently dropped.
-    .onAppear { focusField() }
-    .onChange(of: model.searchFocusToken) { _, _ in focusField() }
   }
 
   private func focusField() {
@@ -99,6 +132,13 @@ struct SearchResultsList: View {
         ProgressView()
           .controlSize(.small)
           .frame(maxWidth: .infinity, maxHeight: .infinity)
+      } else if let error = model.searchError {
+        Text(error)
+          .font(.system(size: 12))
+          .foregroundStyle(.secondary)
+          .multilineTextAlignment(.center)
+          .padding(.horizontal, 10)
+          .frame(maxWidth: .infinity, maxHeight: .infinity)
       } else if model.searchResults.isEmpty {
         Text("No results")
           .font(.system(size: 12))
@@ -160,13 +200,48 @@ private struct SearchResultRow: View {
         }
       }
 
+      ForEach(Array((result.contextBefore ?? []).enumerated()), id: \.offset) { _, line in
+        contextText(line)
+      }
+
       if let lineContent = result.lineContent {
-        Text(lineContent.trimmingCharacters(in: .whitespaces))
+        Text(highlighted(lineContent))
           .font(.system(size: 11, design: .monospaced))
           .foregroundStyle(.secondary)
           .lineLimit(1)
           .truncationMode(.tail)
       }
+
+      ForEach(Array((result.contextAfter ?? []).enumerated()), id: \.offset) { _, line in
+        contextText(line)
+      }
     }
   }
+
+  private func contextText(_ line: String) -> some View {
+    Text(line.trimmingCharacters(in: .whitespaces))
+      .font(.system(size: 11, design: .monospaced))
+      .foregroundStyle(.tertiary)
+      .lineLimit(1)
+      .truncationMode(.tail)
+  }
+
+  /// The trimmed line with the matched bytes in bold.
+  private func highlighted(_ line: String) -> AttributedString {
+    let trimmed = line.trimmingCharacters(in: .whitespaces)
+    var text = AttributedString(trimmed)
+    guard let start = result.matchStart, let end = result.matchEnd else { return text }
+    let lead = line.utf8.count - line.drop(while: { $0 == " " || $0 == "\t" }).utf8.count
+    let utf8 = trimmed.utf8
+    let from = max(Int(start) - lead, 0)
+    let to = min(Int(end) - lead, utf8.count)
+    guard from < to,
+      let lower = utf8.index(utf8.startIndex, offsetBy: from, limitedBy: utf8.endIndex),
+      let upper = utf8.index(utf8.startIndex, offsetBy: to, limitedBy: utf8.endIndex),
+      let range = Range(lower..<upper, in: text)
+    else { return text }
+    text[range].font = .system(size: 11, weight: .bold, design: .monospaced)
+    text[range].foregroundColor = .primary
+    return text
+  }
 }
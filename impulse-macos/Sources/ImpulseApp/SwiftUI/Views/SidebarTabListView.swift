@@ -79,6 +79,7 @@ struct SidebarTabListView: View {
       VStack(alignment: .leading, spacing: 1) {
         Text(tab.title)
           .font(.system(size: 12.5, weight: isSelected ? .semibold : .medium))
+          .italic(tab.isPreview)
           .lineLimit(1)
           .truncationMode(.middle)
           .foregroundStyle(
@@ -113,6 +114,13 @@ struct SidebarTabListView: View {
           .accessibilityHidden(true)
       }
 
+      if let reason = tab.readOnlyReason {
+        Image(systemName: "lock.fill")
+          .font(.system(size: 9))
+          .foregroundStyle(.tertiary)
+          .help(reason)
+      }
+
       if tab.isPinned && !isHovered {
         Image(systemName: "pin.fill")
           .font(.system(size: 8))
@@ -136,6 +144,7 @@ struct SidebarTabListView: View {
     .frame(height: Self.rowHeight)
     .background(rowBackground(isSelected: isSelected || isDragging, isHovered: isHovered))
     .contentShape(Rectangle())
+    .help(tab.tooltip)
     .simultaneousGesture(
       TapGesture().onEnded {
         windowModel.onTabSelected?(tab.index)
@@ -148,6 +157,20 @@ struct SidebarTabListView: View {
       Button("Close Tab") {
         windowModel.onTabClosed?(tab.index)
       }
+      Button("Close Tabs to the Right") {
+        windowModel.onTabGroupClosed?("right", tab.index)
+      }
+      Button("Close Tabs to the Left") {
+        windowModel.onTabGroupClosed?("left", tab.index)
+      }
+      Button("Close Saved Tabs") {
+        windowModel.onTabGroupClosed?("saved", tab.index)
+      }
+      if let path = tab.filePath {
+        Button("Reveal in Finder") {
+          NSWorkspace.shared.activateFileViewerSelecting([URL(fileURLWithPath: path)])
+        }
+      }
       Divider()
       Button("New Tab") {
         windowModel.onNewTab?()
@@ -246,6 +269,14 @@ struct SidebarTabListView: View {
       return
     }
 
+    // Dropped outside the window: the tab moves to the window under the
+    // pointer, or to a new one.
+    if windowModel.onTabDraggedOut?(sourceIdx, NSEvent.mouseLocation) == true {
+      draggedTabId = nil
+      dragOffset = 0
+      return
+    }
+
     let draggedCenter = draggedFrame.midY + dragOffset
 
     var targetIdx = sourceIdx
@@ -21,32 +21,16 @@ struct StatusBarView: View {
     .overlay(alignment: .top) {
       Rectangle().fill(model.theme.colorBorder).frame(height: 1)
     }
+    .accessibilityElement(children: .contain)
+    .accessibilityLabel("Status bar")
   }
 
   // MARK: - Left Group
 
   @ViewBuilder
   private var leftGroup: some View {
-    if !model.shellName.isEmpty {
-      ContextChip(symbol: "terminal", text: model.shellName, theme: model.theme)
-    }
-    if !model.currentCwd.isEmpty {
-      ContextChip(
-        symbol: "folder", text: TabManager.abbreviateHomePath(model.currentCwd),
-        theme: model.theme)
-    }
-    if let branch = model.gitBranch, !branch.isEmpty {
-      // Inert while a TUI owns the grid — a checkout would type into the program.
-      BranchChip(model: model, branch: branch, interactive: !model.terminalDirectInteraction)
-    }
-    // Surface uncommitted changes even while a TUI (e.g. Claude Code) owns the
-    // grid — opening the diff review is a mouse action, so it's safe here.
-    if model.reviewChangedFileCount > 0 {
-      ReviewChip(
-        model: model,
-        fileCount: model.reviewChangedFileCount,
-        added: model.reviewAddedLines,
-        removed: model.reviewRemovedLines)
+    ForEach(segments(aligned: "left"), id: \.id) { info in
+      labeledSegment(info)
     }
   }
 
@@ -55,39 +39,25 @@ struct StatusBarView: View {
   @ViewBuilder
   private var rightGroup: some View {
     // Update available
-    if let updateVersion = model.updateAvailableVersion,
-      let updateURL = model.updateURL
-    {
+    if let updateVersion = model.updateAvailableVersion {
       actionChip(
-        symbol: "arrow.down.circle",
-        text: "Update \(updateVersion)",
+        symbol: model.updateInstalled ? "arrow.clockwise.circle" : "arrow.down.circle",
+        text: model.updateInstalled ? "Relaunch to Update" : "Update \(updateVersion)",
         tint: model.theme.colorGreen,
         filled: true,
         help: updateHelpText(version: updateVersion)
       ) {
-        NSWorkspace.shared.open(updateURL)
+        let delegate = NSApp.delegate as? AppDelegate
+        if model.updateInstalled {
+          delegate?.relaunch()
+        } else {
+          delegate?.checkForUpdates(nil)
+        }
       }
     }
 
-    // Language
-    if let lang = model.currentLanguage {
-      ContextChip(
-        symbol: "chevron.left.forwardslash.chevron.right", text: lang, theme: model.theme)
-    }
-
-    // Encoding (editor tabs only)
-    if model.cursorLine != nil {
-      ContextChip(text: model.currentEncoding, theme: model.theme)
-    }
-
-    // Indent info
-    if let indent = model.currentIndent {
-      ContextChip(text: indent, theme: model.theme)
-    }
-
-    // Cursor position
-    if let line = model.cursorLine, let col = model.cursorCol {
-      ContextChip(text: "Ln \(line + 1), Col \(col + 1)", theme: model.theme)
+    ForEach(segments(aligned: "right"), id: \.id) { info in
+      labeledSegment(info)
     }
 
     // Preview toggle
@@ -102,6 +72,168 @@ struct StatusBarView: View {
         model.onPreviewToggle?()
       }
     }
+
+    notificationsBell
+  }
+
+  // MARK: - Notifications
+
+  /// Bell with the unread count; opens the notification history.
+  private var notificationsBell: some View {
+    let unread = model.notifications.filter { !$0.read }
+    let hasErrors = unread.contains { $0.severity == .error }
+    let isPresented = Binding(
+      get: { model.notificationsPanelVisible },
+      set: { model.notificationsPanelVisible = $0 })
+    return Button {
+      model.notificationsPanelVisible.toggle()
+    } label: {
+      HStack(spacing: 3) {
+        Image(systemName: unread.isEmpty ? "bell" : "bell.badge")
+          .font(.system(size: 10.5, weight: .medium))
+        if !unread.isEmpty {
+          Text("\(unread.count)")
+            .font(.system(size: 11, weight: .medium))
+        }
+      }
+      .foregroundStyle(hasErrors ? model.theme.colorRed : model.theme.colorFgComment)
+      .padding(.horizontal, 4)
+      .contentShape(Rectangle())
+    }
+    .buttonStyle(.plain)
+    .help("Notifications")
+    .popover(isPresented: isPresented, arrowEdge: .top) {
+      NotificationsPanelView(model: model)
+    }
+  }
+
+  // MARK: - Segments
+
+  /// The visible segments on one side, in the user's order.
+  private func segments(aligned alignment: String) -> [StatusBarSegment] {
+    model.statusBarSegments.filter { $0.alignment == alignment }
+  }
+
+  /// A segment grouped under its name, so VoiceOver reads e.g. "Git Branch"
+  /// before the chip's text.
+  private func labeledSegment(_ info: StatusBarSegment) -> some View {
+    segment(info.id)
+      .accessibilityElement(children: .contain)
+      .accessibilityLabel(info.name)
+  }
+
+  /// The chip for one segment ID. Segments this frontend doesn't provide
+  /// (e.g. the Linux CI badge) render nothing.
+  @ViewBuilder
+  private func segment(_ id: String) -> some View {
+    switch id {
+    case "shell":
+      if !model.shellName.isEmpty {
+        ContextChip(symbol: "terminal", text: model.shellName, theme: model.theme)
+      }
+    case "cwd":
+      if !model.currentCwd.isEmpty {
+        CwdBreadcrumbChip(model: model, cwd: model.currentCwd)
+      }
+    case "branch":
+      if let branch = model.gitBranch, !branch.isEmpty {
+        // Inert while a TUI owns the grid — a checkout would type into the program.
+        BranchChip(model: model, branch: branch, interactive: !model.terminalDirectInteraction)
+      }
+    case "review":
+      // Surface uncommitted changes even while a TUI (e.g. Claude Code) owns the
+      // grid — opening the diff review is a mouse action, so it's safe here.
+      if model.reviewChangedFileCount > 0 {
+        ReviewChip(
+          model: model,
+          fileCount: model.reviewChangedFileCount,
+          added: model.reviewAddedLines,
+          removed: model.reviewRemovedLines)
+      }
+    case "problems":
+      if model.problemErrorCount + model.problemWarningCount > 0 {
+        ContextChip(
+          symbol: "xmark.octagon",
+          text: "\(model.problemErrorCount)  ⚠ \(model.problemWarningCount)",
+          theme: model.theme)
+      }
+    case "language":
+      if let lang = model.currentLanguage {
+        languageMenu(lang)
+      }
+    case "encoding":
+      // Editor tabs only
+      if model.cursorLine != nil {
+        ContextChip(text: model.currentEncoding, theme: model.theme)
+      }
+    case "indent":
+      if let indent = model.currentIndent {
+        indentMenu(indent)
+      }
+    case "zoom":
+      if let zoom = model.zoomLabel {
+        Button(action: { model.onResetZoom?() }) {
+          ContextChip(symbol: "plus.magnifyingglass", text: zoom, theme: model.theme)
+        }
+        .buttonStyle(.plain)
+        .help("Reset Zoom")
+      }
+    case "cursor":
+      if let line = model.cursorLine, let col = model.cursorCol {
+        ContextChip(text: "Ln \(line + 1), Col \(col + 1)", theme: model.theme)
+      }
+    default:
+      EmptyView()
+    }
+  }
+
+  // MARK: - Indentation menu
+
+  /// Indent widths offered in the menu (`INDENT_WIDTHS` in impulse-core).
+  private static let indentWidths = [2, 4, 8]
+
+  private func indentMenu(_ indent: String) -> some View {
+    Menu {
+      Button("Convert Indentation to Spaces") { model.onSetIndentation?(true, nil, true) }
+      Button("Convert Indentation to Tabs") { model.onSetIndentation?(false, nil, true) }
+      Divider()
+      Menu("Indent Using Spaces") {
+        ForEach(Self.indentWidths, id: \.self) { width in
+          Button("\(width)") { model.onSetIndentation?(true, width, false) }
+        }
+      }
+      Menu("Indent Using Tabs") {
+        ForEach(Self.indentWidths, id: \.self) { width in
+          Button("\(width)") { model.onSetIndentation?(false, width, false) }
+        }
+      }
+    } label: {
+      ContextChip(text: indent, showsChevron: true, theme: model.theme)
+    }
+    .menuStyle(.borderlessButton)
+    .menuIndicator(.hidden)
+    .fixedSize()
+    .help("Change indentation")
+  }
+
+  // MARK: - Language menu
+
+  private func languageMenu(_ language: String) -> some View {
+    Menu {
+      Button("Auto Detect") { model.onSetLanguage?(nil) }
+      Divider()
+      ForEach(ImpulseCore.languages, id: \.id) { choice in
+        Button(choice.label) { model.onSetLanguage?(choice.id) }
+      }
+    } label: {
+      ContextChip(
+        symbol: "chevron.left.forwardslash.chevron.right", text: language, showsChevron: true,
+        theme: model.theme)
+    }
+    .menuStyle(.borderlessButton)
+    .menuIndicator(.hidden)
+    .fixedSize()
+    .help("Change language")
   }
 
   // MARK: - Action chip
@@ -140,6 +272,9 @@ struct StatusBarView: View {
   // MARK: - Helpers
 
   private func updateHelpText(version: String) -> String {
+    if model.updateInstalled {
+      return "Impulse \(version) is installed. Relaunch to start using it."
+    }
     if let current = model.updateCurrentVersion, !current.isEmpty {
       return "Impulse \(version) is available. Current version: \(current)."
     }
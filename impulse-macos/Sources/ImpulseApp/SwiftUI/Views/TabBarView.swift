@@ -2,263 +2,39 @@ import AppKit
 import SwiftUI
 
 /// Tab bar styled like Finder: full-width pill tabs dividing the strip equally.
-/// Hidden when there is only one tab. Supports smooth drag-reorder.
+/// Hidden when there is only one tab. The strip itself is AppKit
+/// (`TabStripView`), which handles drag-reorder, hover and the context menu.
 struct TabBarView: View {
   var windowModel: WindowModel
-  @State private var hoveredIndex: Int? = nil
-  @State private var draggedTabId: Int? = nil
-  @State private var dragOffset: CGFloat = 0
-  @State private var tabFrames: [Int: CGRect] = [:]
 
   var body: some View {
     // Hidden entirely when tabs live in the sidebar (Warp-style layout).
     if windowModel.tabBarPosition == "top", windowModel.tabDisplayInfos.count > 1 {
-      HStack(spacing: 2) {
-        ForEach(windowModel.tabDisplayInfos) { tab in
-          singleTab(tab)
-            .id(tab.id)
-            .background(
-              GeometryReader { geo in
-                Color.clear.preference(
-                  key: TabFrameKey.self,
-                  value: [tab.id: geo.frame(in: .named("tabBar"))]
-                )
-              }
-            )
-            .offset(x: tabOffset(for: tab))
-            .animation(
-              draggedTabId == tab.id
-                ? nil : .interactiveSpring(response: 0.25, dampingFraction: 0.8),
-              value: tabOffset(for: tab)
-            )
-            .zIndex(draggedTabId == tab.id ? 1 : 0)
-            .simultaneousGesture(
-              DragGesture(minimumDistance: 5, coordinateSpace: .named("tabBar"))
-                .onChanged { value in
-                  if draggedTabId == nil {
-                    draggedTabId = tab.id
-                    // Select the tab on drag start, like Finder.
-                    windowModel.onTabSelected?(tab.index)
-                  }
-                  dragOffset = value.translation.width
-                }
-                .onEnded { _ in
-                  commitDrag()
-                }
-            )
-        }
-      }
-      .coordinateSpace(name: "tabBar")
-      .onPreferenceChange(TabFrameKey.self) { tabFrames = $0 }
-      .padding(.horizontal, 8)
-      .padding(.vertical, 5)
+      TabStrip(
+        model: windowModel,
+        tabs: windowModel.tabDisplayInfos,
+        selectedIndex: windowModel.selectedTabIndex,
+        theme: windowModel.theme
+      )
       .frame(maxWidth: .infinity)
-      .overlay(alignment: .bottom) {
-        // Card-surface themes (Harbor) keep the canvas hairline-free; the
-        // floating content card already separates the regions.
-        if !isCardSurface { Divider() }
-      }
+      .frame(height: TabStripView.height)
     }
   }
+}
 
-  /// Card-surface themes (e.g. Harbor) style the selected tab as a raised
-  /// content-colored pill with a soft warm shadow instead of material + border.
-  private var isCardSurface: Bool {
-    windowModel.theme.surfaceStyle == "card"
-  }
-
-  /// Warm-hued shadow (#5c5142) per the Harbor spec — never pure black.
-  private var chipShadowColor: Color {
-    Color(red: 0.36, green: 0.32, blue: 0.26)
-  }
-
-  // MARK: - Drag Logic
-
-  /// Returns the visual x-offset for a tab during drag.
-  /// The dragged tab follows the cursor; other tabs slide to make room.
-  private func tabOffset(for tab: TabDisplayInfo) -> CGFloat {
-    // Dragged tab follows cursor directly.
-    if tab.id == draggedTabId {
-      return dragOffset
-    }
-
-    guard let draggedId = draggedTabId,
-      let draggedFrame = tabFrames[draggedId],
-      let tabFrame = tabFrames[tab.id]
-    else { return 0 }
-
-    let tabs = windowModel.tabDisplayInfos
-    guard let draggedIdx = tabs.firstIndex(where: { $0.id == draggedId }),
-      let tabIdx = tabs.firstIndex(where: { $0.id == tab.id })
-    else { return 0 }
-
-    let draggedCenter = draggedFrame.midX + dragOffset
-    let shiftAmount = draggedFrame.width + 2  // width + spacing
-
-    // Tab is to the right of the dragged tab and dragged has crossed its midpoint.
-    if draggedIdx < tabIdx && draggedCenter > tabFrame.midX {
-      return -shiftAmount
-    }
-    // Tab is to the left of the dragged tab and dragged has crossed its midpoint.
-    if draggedIdx > tabIdx && draggedCenter < tabFrame.midX {
-      return shiftAmount
-    }
-
-    return 0
-  }
-
-  /// On drag end, calculate the final target index and commit the move.
-  private func commitDrag() {
-    guard let draggedId = draggedTabId else { return }
-
-    let tabs = windowModel.tabDisplayInfos
-    guard let sourceIdx = tabs.firstIndex(where: { $0.id == draggedId }),
-      let draggedFrame = tabFrames[draggedId]
-    else {
-      draggedTabId = nil
-      dragOffset = 0
-      return
-    }
-
-    let draggedCenter = draggedFrame.midX + dragOffset
-
-    // Walk tabs to find the rightmost tab we've crossed to the right,
-    // or the leftmost tab we've crossed to the left.
-    var targetIdx = sourceIdx
-    for (idx, tab) in tabs.enumerated() {
-      if tab.id == draggedId { continue }
-      guard let frame = tabFrames[tab.id] else { continue }
-      if sourceIdx < idx && draggedCenter > frame.midX {
-        targetIdx = idx
-      } else if sourceIdx > idx && draggedCenter < frame.midX && idx < targetIdx {
-        targetIdx = idx
-      }
-    }
-
-    // Reset drag state first so the offset doesn't compound with
-    // the new layout position after reorder.
-    draggedTabId = nil
-    dragOffset = 0
-
-    if sourceIdx != targetIdx {
-      windowModel.onTabMoved?(sourceIdx, targetIdx)
-    }
-  }
-
-  // MARK: - Single Tab
-
-  private func singleTab(_ tab: TabDisplayInfo) -> some View {
-    let isSelected = tab.index == windowModel.selectedTabIndex
-    let isHovered = hoveredIndex == tab.id
-    let isDragging = draggedTabId == tab.id
-
-    return HStack(spacing: 5) {
-      if let icon = tab.icon {
-        Image(nsImage: icon)
-          .resizable()
-          .interpolation(.high)
-          .frame(width: 14, height: 14)
-          .accessibilityHidden(true)
-      }
-
-      Text(tab.title)
-        .font(.system(size: 11.5))
-        .lineLimit(1)
-        .truncationMode(.middle)
-
-      Spacer(minLength: 0)
-
-      if tab.needsAttention && !isSelected {
-        Circle()
-          .fill(windowModel.theme.colorAccent)
-          .frame(width: 6, height: 6)
-          .accessibilityHidden(true)
-      }
+/// Hosts `TabStripView`. The tabs, selection and theme are passed as values
+/// so SwiftUI calls `updateNSView` whenever any of them change.
+private struct TabStrip: NSViewRepresentable {
+  let model: WindowModel
+  let tabs: [TabDisplayInfo]
+  let selectedIndex: Int
+  let theme: Theme
 
-      if !tab.isPinned && isHovered {
-        Button(action: { windowModel.onTabClosed?(tab.index) }) {
-          Image(systemName: "xmark")
-            .font(.system(size: 8, weight: .bold))
-            .foregroundStyle(.secondary)
-            .frame(width: 16, height: 16)
-            .contentShape(Circle())
-        }
-        .buttonStyle(.plain)
-        .accessibilityLabel("Close \(tab.isTerminal ? "Terminal" : "Editor") Tab \(tab.title)")
-      }
-    }
-    .padding(.horizontal, 12)
-    .frame(maxWidth: .infinity)
-    .frame(height: 30)
-    .background(
-      Group {
-        if isSelected || isDragging {
-          if isCardSurface {
-            Capsule()
-              .fill(windowModel.theme.colorBg)
-              .shadow(color: chipShadowColor.opacity(0.18), radius: 1.5, x: 0, y: 1)
-          } else {
-            Capsule().fill(.thickMaterial)
-          }
-        } else if isHovered {
-          if isCardSurface {
-            Capsule().fill(windowModel.theme.colorBgHighlight.opacity(0.7))
-          } else {
-            Capsule().fill(Color.primary.opacity(0.04))
-          }
-        }
-      }
-    )
-    .overlay(
-      Capsule()
-        .strokeBorder(
-          isCardSurface
-            ? .clear
-            : isSelected || isDragging
-              ? Color.primary.opacity(0.2)
-              : isHovered ? Color.primary.opacity(0.08) : .clear,
-          lineWidth: 1
-        )
-    )
-    .foregroundStyle(
-      isCardSurface
-        ? AnyShapeStyle(
-          isSelected || isDragging
-            ? windowModel.theme.colorFg : windowModel.theme.colorFgMuted)
-        : AnyShapeStyle(isSelected || isDragging ? Color.primary : Color.secondary)
-    )
-    .contentShape(Capsule())
-    .simultaneousGesture(
-      TapGesture().onEnded {
-        windowModel.onTabSelected?(tab.index)
-      }
-    )
-    .contextMenu {
-      Button(tab.isPinned ? "Unpin Tab" : "Pin Tab") {
-        windowModel.onTabPinToggled?(tab.index)
-      }
-      Button("Close Tab") {
-        windowModel.onTabClosed?(tab.index)
-      }
-      Divider()
-      Button("New Tab") {
-        windowModel.onNewTab?()
-      }
-    }
-    .onHover { hovering in
-      hoveredIndex = hovering ? tab.id : nil
-    }
-    .accessibilityElement(children: .combine)
-    .accessibilityLabel("\(tab.isTerminal ? "Terminal" : "Editor"): \(tab.title)")
-    .accessibilityAddTraits(isSelected ? [.isSelected, .isButton] : [.isButton])
+  func makeNSView(context: Context) -> TabStripView {
+    TabStripView(frame: .zero)
   }
-}
-
-// MARK: - Preference Key for Tab Frames
 
-private struct TabFrameKey: PreferenceKey {
-  static var defaultValue: [Int: CGRect] = [:]
-  static func reduce(value: inout [Int: CGRect], nextValue: () -> [Int: CGRect]) {
-    value.merge(nextValue()) { $1 }
+  func updateNSView(_ nsView: TabStripView, context: Context) {
+    nsView.update(model: model, tabs: tabs, selectedIndex: selectedIndex, theme: theme)
   }
 }
@@ -70,10 +70,12 @@ enum TabEntry {
         let name = (path as NSString).lastPathComponent
         return editor.isModified ? "\(name) *" : name
       }
-      return editor.isModified ? "Untitled *" : "Untitled"
+      let name = editor.untitledTitle ?? "Untitled"
+      return editor.isModified ? "\(name) *" : name
     case .imagePreview(let path, _):
       return (path as NSString).lastPathComponent
-    case .diffReview(let repoRoot, _):
+    case .diffReview(let repoRoot, let view):
+      if let title = view.title { return title }
       let name = (repoRoot as NSString).lastPathComponent
       return name.isEmpty ? "Review Changes" : "Review: \(name)"
     }
@@ -209,6 +211,10 @@ final class TabManager: NSObject {
   /// Stable tab id to return to when the corresponding tab closes.
   private var tabCloseReturnIds: [Int?] = []
 
+  /// When each tab (by unique ID) was last selected, for sorting by recent use.
+  private var tabLastUsed: [Int: UInt64] = [:]
+  private var tabUseClock: UInt64 = 0
+
   /// Set of file paths currently open in editor/image tabs for O(1) deduplication.
   private var openFilePaths: Set<String> = []
 
@@ -232,6 +238,12 @@ final class TabManager: NSObject {
   private var tabBranchCache: [String: (branch: String, at: Date)] = [:]
   private var tabBranchPending: Set<String> = []
 
+  /// What each terminal shell (by PID) is running, for tab tooltips.
+  /// Refreshed every few seconds by `processPollTimer`.
+  private var terminalProcessDescriptions: [pid_t: String] = [:]
+  private var processPollTimer: Timer?
+  private var processPollPending = false
+
   /// The container view that hosts the active tab's view.
   let contentView: NSView
 
@@ -266,14 +278,24 @@ final class TabManager: NSObject {
     contentView.layer?.backgroundColor = theme.bgColor.cgColor
 
     super.init()
+
+    processPollTimer = Timer.scheduledTimer(withTimeInterval: 3.0, repeats: true) {
+      [weak self] _ in
+      self?.pollTerminalProcesses()
+    }
+  }
+
+  deinit {
+    processPollTimer?.invalidate()
   }
 
   // MARK: - Adding Tabs
 
   /// Creates a new terminal tab (wrapped in a TerminalContainer for split
-  /// support) and makes it active.
+  /// support) and makes it active. Without a `directory` it starts where the
+  /// `terminal_new_tab_directory` setting says.
   func addTerminalTab(directory: String? = nil, initialCommand: String? = nil) {
-    let dir = directory ?? NSHomeDirectory()
+    let dir = directory ?? newTerminalDirectory()
     let termSettings = settings.terminalSettings(directory: dir)
     let termTheme = TerminalTheme(
       bg: theme.terminalBg,
@@ -297,6 +319,28 @@ final class TabManager: NSObject {
     insertTab(entry)
   }
 
+  /// Where a new terminal tab starts: the home folder, the workspace root
+  /// or the active tab's directory (a file's folder for editors).
+  private func newTerminalDirectory() -> String {
+    var current: String?
+    if selectedIndex >= 0, selectedIndex < tabs.count {
+      switch tabs[selectedIndex] {
+      case .terminal(let container):
+        current = container.activeTerminal?.currentWorkingDirectory
+      case .editor(let editor):
+        current = editor.filePath.map { ($0 as NSString).deletingLastPathComponent }
+      case .imagePreview(let path, _):
+        current = (path as NSString).deletingLastPathComponent
+      case .diffReview(let repoRoot, _):
+        current = repoRoot
+      }
+    }
+    return ImpulseCore.newTerminalDirectory(
+      mode: settings.terminalNewTabDirectory,
+      workspace: windowModel?.fileTreeRootPath,
+      current: current)
+  }
+
   func addRestoredTerminalTab(_ tab: SessionTabState) {
     let dir = nonEmpty(tab.cwd) ?? NSHomeDirectory()
     let termSettings = settings.terminalSettings(directory: dir)
@@ -327,9 +371,13 @@ final class TabManager: NSObject {
   /// If a tab for the same file is already open, it is selected instead of
   /// creating a duplicate. Image files are opened in a preview tab. Binary
   /// files (>10 MB or containing null bytes) are skipped with an alert.
+  /// With `preview` (a single click in the file tree, when preview tabs are
+  /// enabled) the new tab replaces the current preview tab; opening an
+  /// already-open file any other way keeps it. `recoveredContent` (unsaved
+  /// edits restored after a crash) replaces the file's content as an edit.
   func addEditorTab(
     path: String, projectDirectory: String? = nil, goToLine: UInt32? = nil,
-    goToColumn: UInt32? = nil
+    goToColumn: UInt32? = nil, preview: Bool = false, recoveredContent: String? = nil
   ) {
     // O(1) deduplication using the openFilePaths set.
     if openFilePaths.contains(path) {
@@ -341,6 +389,12 @@ final class TabManager: NSObject {
         }
       }) {
         updateCloseReturnTarget(forTabAt: existingIndex, sourceIndex: selectedIndex)
+        if !preview, case .editor(let editor) = tabs[existingIndex] {
+          editor.isPreview = false
+          if let recoveredContent {
+            editor.restoreContent(recoveredContent)
+          }
+        }
         selectTab(index: existingIndex)
         // Navigate to position in the already-open editor.
         if let line = goToLine, let column = goToColumn,
@@ -370,6 +424,11 @@ final class TabManager: NSObject {
       return
     }
 
+    // File > Open Recent and the Dock menu. Preview tabs are transient.
+    if !preview {
+      NSDocumentController.shared.noteNewRecentDocumentURL(URL(fileURLWithPath: path))
+    }
+
     // Read file content off the main thread, then create the editor tab on main.
     let editorOptions = editorOptionsFromSettings()
     let themeDef = ThemeManager.monacoTheme(forName: theme.id)
@@ -380,6 +439,11 @@ final class TabManager: NSObject {
       let largeFile =
         (try? FileManager.default.attributesOfItem(atPath: path)[.size] as? Int).flatMap({ $0 })
         ?? 0 > 5 * 1024 * 1024
+      // Large files open read-only to avoid WebView freezes.
+      let readOnlyReason =
+        largeFile
+        ? "Read-only: file is too large to edit"
+        : ImpulseCore.readOnlyReason(path: path, content: fileContent)
 
       DispatchQueue.main.async { [weak self] in
         guard let self else { return }
@@ -392,15 +456,21 @@ final class TabManager: NSObject {
           projectDirectory
           ?? (path as NSString).deletingLastPathComponent
         editorTab.openFile(path: path, content: fileContent, language: language)
+        editorTab.setIndentation(
+          self.settings.indentation(forPath: path, content: fileContent), convert: false)
         editorTab.loadEditor()
 
         // Apply editor settings (font, tab size, etc.) from the current settings.
         editorTab.applySettings(editorOptions)
+        editorTab.applyKeybindings(self.settings.editorKeybindings)
         editorTab.applyTheme(themeDef)
+        editorTab.applyCustomCss(ThemeManager.editorCustomCss)
 
-        // Open large files in read-only mode to avoid WebView freezes.
-        if largeFile {
-          editorTab.setReadOnly(true)
+        if let readOnlyReason {
+          editorTab.setReadOnly(reason: readOnlyReason)
+        }
+        if let recoveredContent {
+          editorTab.restoreContent(recoveredContent)
         }
 
         // Queue go-to-position; pendingCommands will flush after Monaco fires Ready.
@@ -408,28 +478,59 @@ final class TabManager: NSObject {
           editorTab.goToPosition(line: line, column: column)
         }
 
+        let isPreview = preview && self.settings.previewTabs
+        let replacedPreview = isPreview ? self.previewEditorIndex() : nil
+        let replacedEditor = replacedPreview.flatMap { i -> EditorTab? in
+          if case .editor(let editor) = self.tabs[i] { return editor }
+          return nil
+        }
+        editorTab.isPreview = isPreview
         let entry = TabEntry.editor(editorTab)
         self.insertTab(entry)
+
+        // The new preview replaces the old one unless it was edited meanwhile.
+        if let replacedEditor, !replacedEditor.isModified,
+          let index = self.tabs.firstIndex(where: {
+            if case .editor(let editor) = $0 { return editor === replacedEditor }
+            return false
+          })
+        {
+          self.closeTab(index: index)
+        }
       }
     }
   }
 
+  /// Index of the preview editor tab, if there is one.
+  private func previewEditorIndex() -> Int? {
+    tabs.firstIndex {
+      if case .editor(let editor) = $0 { return editor.isPreview }
+      return false
+    }
+  }
+
   /// Creates a new untitled editor tab with no file on disk.
-  func addUntitledEditorTab(cwd: String?) {
+  @discardableResult
+  func addUntitledEditorTab(cwd: String?, content: String = "", title: String? = nil) -> EditorTab {
     let editorTab = EditorTab(frame: NSRect(x: 0, y: 0, width: 800, height: 600))
     editorTab.untitledCwd = cwd
+    editorTab.untitledTitle = title
     editorTab.projectDirectory = cwd
-    editorTab.openBlank()
+    editorTab.openBlank(content: content)
+    editorTab.setIndentation(settings.indentation(forPath: "", content: content), convert: false)
     editorTab.loadEditor()
 
     let editorOptions = editorOptionsFromSettings()
     editorTab.applySettings(editorOptions)
+    editorTab.applyKeybindings(settings.editorKeybindings)
     let themeDef = ThemeManager.monacoTheme(forName: theme.id)
     editorTab.applyTheme(themeDef)
+    editorTab.applyCustomCss(ThemeManager.editorCustomCss)
 
     let entry = TabEntry.editor(editorTab)
     insertTab(entry)
     // filePath is nil, so insertTab won't add to openFilePaths — correct.
+    return editorTab
   }
 
   /// Register a file path in the open-file dedup set (e.g. after save-as).
@@ -443,7 +544,7 @@ final class TabManager: NSObject {
   /// so the user never reviews/commits/discards against a stale repository.
   func addDiffReviewTab(repoRoot: String) {
     if let existingIndex = tabs.firstIndex(where: {
-      if case .diffReview(let r, _) = $0 { return r == repoRoot }
+      if case .diffReview(let r, let view) = $0 { return r == repoRoot && view.comparison == nil }
       return false
     }) {
       selectTab(index: existingIndex)
@@ -460,6 +561,29 @@ final class TabManager: NSObject {
     insertTab(entry)
   }
 
+  /// Opens a read-only tab diffing the file at `old` against the file at
+  /// `new`.
+  func addFileCompareTab(old: String, new: String, title: String) {
+    let reviewTab = DiffReviewTab(
+      repoRoot: (new as NSString).deletingLastPathComponent, theme: theme,
+      comparison: (old: old, new: new))
+    reviewTab.title = title
+    insertTab(TabEntry.diffReview(repoRoot: reviewTab.repoRoot, view: reviewTab))
+  }
+
+  /// Opens a read-only tab diffing `editor`'s saved file against its
+  /// unsaved buffer. Refreshing the tab picks up new edits.
+  func addSavedCompareTab(editor: EditorTab) {
+    guard let path = editor.filePath else { return }
+    let reviewTab = DiffReviewTab(
+      repoRoot: (path as NSString).deletingLastPathComponent, theme: theme,
+      comparison: (old: path, new: path),
+      buffer: { [weak editor] in editor?.content })
+    let name = (path as NSString).lastPathComponent
+    reviewTab.title = "\(name) (saved) \u{2194} \(name)"
+    insertTab(TabEntry.diffReview(repoRoot: reviewTab.repoRoot, view: reviewTab))
+  }
+
   /// Detect the Monaco language ID for a file path.
   func detectLanguage(forPath path: String) -> String {
     languageIdForPath(path)
@@ -618,9 +742,14 @@ final class TabManager: NSObject {
 
     tabs.remove(at: index)
     pinnedTabs.remove(at: index)
+    tabLastUsed.removeValue(forKey: tabUniqueIds[index])
     tabUniqueIds.remove(at: index)
     tabCloseReturnIds.remove(at: index)
 
+    if case .editor(let e) = entry, let path = e.filePath {
+      NotificationCenter.default.post(name: .impulseEditorDidClose, object: path)
+    }
+
     if tabs.isEmpty {
       selectedIndex = -1
       // Auto-create a new terminal tab so the window is never empty,
@@ -657,10 +786,82 @@ final class TabManager: NSObject {
     }
   }
 
+  // MARK: - Moving Tabs Between Windows
+
+  /// A tab on its way from one window to another.
+  struct TransferredTab {
+    let entry: TabEntry
+    let pinned: Bool
+  }
+
+  /// Removes the tab at `index` without closing it, so another window can
+  /// adopt it: terminal processes and editor WebViews keep running. Unlike
+  /// `closeTab`, taking the last tab leaves the window empty.
+  func detachTab(index: Int) -> TransferredTab? {
+    guard index >= 0, index < tabs.count else { return nil }
+
+    let entry = tabs[index]
+    let pinned = pinnedTabs[index]
+    let detachingSelectedTab = index == selectedIndex
+    switch entry {
+    case .editor(let e):
+      if let p = e.filePath { openFilePaths.remove(p) }
+    case .imagePreview(let p, _):
+      openFilePaths.remove(p)
+    default:
+      break
+    }
+    if detachingSelectedTab {
+      entry.view.removeFromSuperview()
+    }
+
+    let uniqueId = tabUniqueIds[index]
+    tabs.remove(at: index)
+    pinnedTabs.remove(at: index)
+    tabLastUsed.removeValue(forKey: uniqueId)
+    tabUniqueIds.remove(at: index)
+    tabCloseReturnIds.remove(at: index)
+    tabCloseReturnIds = tabCloseReturnIds.map { $0 == uniqueId ? nil : $0 }
+
+    if tabs.isEmpty {
+      selectedIndex = -1
+      rebuildSegments()
+    } else if detachingSelectedTab {
+      selectTab(index: min(index, tabs.count - 1))
+    } else {
+      if index < selectedIndex {
+        selectedIndex -= 1
+      }
+      rebuildSegments()
+    }
+    return TransferredTab(entry: entry, pinned: pinned)
+  }
+
+  /// Adds a tab detached from another window and selects it.
+  func adoptTab(_ tab: TransferredTab) {
+    insertTab(tab.entry)
+    if tab.pinned {
+      pin(index: selectedIndex)
+    }
+  }
+
   /// Toggles the pinned state of the tab at the given index.
   func togglePin(index: Int) {
     guard index >= 0, index < tabs.count else { return }
     pinnedTabs[index].toggle()
+    if case .editor(let editor) = tabs[index] {
+      editor.isPreview = false
+    }
+    refreshSegmentLabels()
+  }
+
+  /// Pins the tab at the given index (used when restoring a session).
+  func pin(index: Int) {
+    guard index >= 0, index < tabs.count else { return }
+    pinnedTabs[index] = true
+    if case .editor(let editor) = tabs[index] {
+      editor.isPreview = false
+    }
     refreshSegmentLabels()
   }
 
@@ -671,6 +872,44 @@ final class TabManager: NSObject {
     refreshSegmentLabels()
   }
 
+  // MARK: - Sorting and Groups
+
+  /// The tabs in order, for `ImpulseCore`'s tab sorting and groups.
+  func tabSummaries() -> [ImpulseCore.TabSummary] {
+    tabs.enumerated().map { i, tab in
+      let path: String?
+      var modified = false
+      switch tab {
+      case .editor(let editor):
+        path = editor.filePath
+        modified = editor.isModified
+      case .imagePreview(let imagePath, _):
+        path = imagePath
+      default:
+        path = nil
+      }
+      return ImpulseCore.TabSummary(
+        title: tab.title, path: path, pinned: pinnedTabs[i], modified: modified,
+        lastUsed: tabLastUsed[tabUniqueIds[i]] ?? 0)
+    }
+  }
+
+  /// Reorders the tabs by `sort` (`"path"` or `"recent_use"`), keeping
+  /// pinned tabs first and the selection on the same tab.
+  func sortTabs(by sort: String) {
+    let order = ImpulseCore.tabSortedOrder(tabSummaries(), sort: sort)
+    guard order.count == tabs.count, order != Array(tabs.indices) else { return }
+    let selectedId = tabUniqueIds.indices.contains(selectedIndex) ? tabUniqueIds[selectedIndex] : nil
+    tabs = order.map { tabs[$0] }
+    pinnedTabs = order.map { pinnedTabs[$0] }
+    tabUniqueIds = order.map { tabUniqueIds[$0] }
+    tabCloseReturnIds = order.map { tabCloseReturnIds[$0] }
+    if let selectedId, let index = tabUniqueIds.firstIndex(of: selectedId) {
+      selectedIndex = index
+    }
+    rebuildSegments()
+  }
+
   // MARK: - Reopening Closed Tabs
 
   /// Reopens the most recently closed editor or image preview tab.
@@ -730,6 +969,8 @@ final class TabManager: NSObject {
     }
 
     selectedIndex = index
+    tabUseClock += 1
+    tabLastUsed[tabUniqueIds[index]] = tabUseClock
     if case .terminal(let container) = tabs[index] {
       container.activeTerminal?.clearAttention()
     }
@@ -894,6 +1135,13 @@ final class TabManager: NSObject {
     }
   }
 
+  /// Push the user's `editor.css` to every open editor.
+  func applyEditorCustomCss(_ css: String) {
+    for case .editor(let editor) in tabs {
+      editor.applyCustomCss(css)
+    }
+  }
+
   // MARK: - Segmented Control
 
   /// Rebuilds the tab display to match the current tab list.
@@ -932,16 +1180,34 @@ final class TabManager: NSObject {
         needsAttention: tab.needsAttention,
         gitBranch: directory.flatMap { cachedGitBranch(forDirectory: $0) },
         directory: directory.map(Self.abbreviateHomePath),
-        isDirectInteractionActive: isDirectInteractionActive
+        isDirectInteractionActive: isDirectInteractionActive,
+        isPreview: { if case .editor(let e) = tab { return e.isPreview } else { return false } }(),
+        readOnlyReason: { if case .editor(let e) = tab { return e.readOnlyReason } else { return nil } }(),
+        filePath: {
+          switch tab {
+          case .editor(let e): return e.filePath
+          case .imagePreview(let path, _): return path
+          default: return nil
+          }
+        }(),
+        processDescription: {
+          guard case .terminal(let container) = tab,
+            let pid = container.activeTerminal?.shellPid, pid > 0
+          else { return nil }
+          return terminalProcessDescriptions[pid]
+        }()
       )
     }
     ws.refreshTabs(infos, selectedIndex: selectedIndex)
 
-    // Update the active file path for sidebar highlighting.
-    if let editor = selectedEditor {
-      ws.activeFilePath = editor.filePath
-    } else {
-      ws.activeFilePath = nil
+    // Update the active file path for sidebar highlighting, revealing a
+    // newly active file when the setting asks for it.
+    let activePath = selectedEditor?.filePath
+    if activePath != ws.activeFilePath {
+      ws.activeFilePath = activePath
+      if let activePath, settings.sidebarAutoReveal {
+        ws.revealInFileTree(activePath)
+      }
     }
   }
 
@@ -998,6 +1264,32 @@ final class TabManager: NSObject {
     return tabBranchCache[dir].flatMap { $0.branch.isEmpty ? nil : $0.branch }
   }
 
+  /// Re-read what every terminal's shell is running, off the main thread,
+  /// and re-sync the tab tooltips when it changed.
+  private func pollTerminalProcesses() {
+    guard !processPollPending else { return }
+    let pids = tabs.compactMap { tab -> pid_t? in
+      guard case .terminal(let container) = tab,
+        let pid = container.activeTerminal?.shellPid, pid > 0
+      else { return nil }
+      return pid
+    }
+    guard !pids.isEmpty || !terminalProcessDescriptions.isEmpty else { return }
+    processPollPending = true
+    DispatchQueue.global(qos: .utility).async { [weak self] in
+      let descriptions = pids.isEmpty
+        ? [:] : ImpulseCore.terminalProcessDescriptions(shellPids: pids)
+      DispatchQueue.main.async {
+        guard let self else { return }
+        self.processPollPending = false
+        if descriptions != self.terminalProcessDescriptions {
+          self.terminalProcessDescriptions = descriptions
+          self.syncToWindowModel()
+        }
+      }
+    }
+  }
+
   /// "/Users/me/Code/x" → "~/Code/x" for compact sidebar subtitles.
   static func abbreviateHomePath(_ path: String) -> String {
     let home = NSHomeDirectory()
@@ -1068,6 +1360,9 @@ final class TabManager: NSObject {
 
   /// Maps a file path to its Monaco language identifier based on extension.
   private func languageIdForPath(_ path: String) -> String {
+    if let language = ImpulseCore.languageOverride(path: path) {
+      return language.monaco
+    }
     // Check filename (without extension) for special cases.
     let filename = (path as NSString).lastPathComponent.lowercased()
     if filename == "dockerfile" || filename == "containerfile" || filename.hasPrefix("dockerfile.")
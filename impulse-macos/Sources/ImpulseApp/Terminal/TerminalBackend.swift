@@ -611,6 +611,11 @@ final class TerminalBackend {
         return ImpulseCore.terminalSelectedText(handle: handle)
     }
 
+    func scrollbackText() -> String {
+        guard let handle, !isShutdown else { return "" }
+        return ImpulseCore.terminalScrollbackText(handle: handle)
+    }
+
     // MARK: - Scroll
 
     func scroll(delta: Int32) {
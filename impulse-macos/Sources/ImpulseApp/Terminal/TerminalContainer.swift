@@ -90,8 +90,8 @@ class TerminalContainer: NSView {
     }
   }
 
-  func runningDescendantProcessCount() -> Int {
-    terminals.reduce(0) { $0 + $1.runningDescendantProcessCount() }
+  func runningProcessCount(ignoring ignored: [String]) -> Int {
+    terminals.reduce(0) { $0 + $1.runningProcessCount(ignoring: ignored) }
   }
 
   func runningCloseRiskCommands() -> [CloseRiskCommand] {
@@ -16,6 +16,10 @@ class TerminalTab: NSView {
   /// Current working directory reported by the shell via CWD polling.
   private(set) var currentWorkingDirectory: String
 
+  /// Directories the shell has been in, most recent first, for the status
+  /// bar's directory history menu.
+  private(set) var directoryHistory: [String] = []
+
   /// Whether this terminal has produced output that needs user attention.
   private(set) var needsAttention: Bool = false
 
@@ -47,6 +51,9 @@ class TerminalTab: NSView {
   private var currentSettings: TerminalSettings?
   private var currentTheme: TerminalTheme?
 
+  /// Points added to `terminalFontSize` by zooming this pane.
+  private(set) var zoom = 0
+
   /// Timer for polling the child process CWD.
   private var cwdPollTimer: Timer?
 
@@ -224,6 +231,7 @@ class TerminalTab: NSView {
       )
     case .cwdChanged(let path):
       currentWorkingDirectory = path
+      recordDirectory(path)
       NotificationCenter.default.post(
         name: .terminalCwdChanged,
         object: self,
@@ -404,14 +412,20 @@ class TerminalTab: NSView {
     }
   }
 
-  /// Number of descendant processes currently running under the shell.
-  /// A plain idle shell usually has zero descendants; foreground commands
-  /// and long-running jobs show up here.
-  func runningDescendantProcessCount() -> Int {
+  /// PID of the shell, or 0 once it has exited.
+  var shellPid: pid_t {
     guard let backend, !backend.isShutdown else { return 0 }
-    let pid = backend.childPid()
+    return backend.childPid()
+  }
+
+  /// Number of processes running under the shell that closing it should
+  /// warn about. A plain idle shell usually has none; foreground commands
+  /// and long-running jobs count unless they run one of the `ignored`
+  /// programs.
+  func runningProcessCount(ignoring ignored: [String]) -> Int {
+    let pid = shellPid
     guard pid > 0 else { return 0 }
-    return collectDescendants(of: pid).count
+    return ImpulseCore.terminalCloseRiskProcessCount(shellPid: pid, ignoring: ignored)
   }
 
   func runningCloseRiskCommand() -> CloseRiskCommand? {
@@ -492,6 +506,13 @@ class TerminalTab: NSView {
       guard !text.isEmpty else { return }
       guard let backend else { return }
 
+      if currentSettings?.terminalConfirmPaste ?? true,
+        let message = ImpulseCore.pasteConfirmation(
+          text: text, bracketed: backend.mode()?.bracketedPaste ?? false)
+      {
+        confirmPaste(text, message: message)
+        return
+      }
       writePastePayload(text, to: backend)
       return
     }
@@ -522,6 +543,40 @@ class TerminalTab: NSView {
     }
   }
 
+  /// Show `text` in an editable preview and paste what the user confirms.
+  private func confirmPaste(_ text: String, message: String) {
+    let alert = NSAlert()
+    alert.messageText = "Paste into Terminal?"
+    alert.informativeText = message
+    alert.alertStyle = .warning
+    alert.addButton(withTitle: "Paste")
+    alert.addButton(withTitle: "Cancel")
+
+    let scrollView = NSScrollView(frame: NSRect(x: 0, y: 0, width: 420, height: 180))
+    scrollView.hasVerticalScroller = true
+    scrollView.borderType = .bezelBorder
+    let textView = NSTextView(frame: scrollView.bounds)
+    textView.autoresizingMask = [.width]
+    textView.isRichText = false
+    textView.font = NSFont.monospacedSystemFont(ofSize: 12, weight: .regular)
+    textView.string = text
+    scrollView.documentView = textView
+    alert.accessoryView = scrollView
+
+    let finish: (NSApplication.ModalResponse) -> Void = { [weak self] response in
+      guard let self else { return }
+      if response == .alertFirstButtonReturn, !textView.string.isEmpty, let backend = self.backend {
+        self.writePastePayload(textView.string, to: backend)
+      }
+      self.focus()
+    }
+    if let window = self.window {
+      alert.beginSheetModal(for: window, completionHandler: finish)
+    } else {
+      finish(alert.runModal())
+    }
+  }
+
   private func writePastePayload(_ payload: String, to backend: TerminalBackend) {
     guard backend.mode()?.bracketedPaste ?? false else {
       backend.write(payload)
@@ -537,6 +592,11 @@ class TerminalTab: NSView {
     backend.write("\u{1b}[200~\(sanitized)\u{1b}[201~")
   }
 
+  /// The whole buffer as text, scrollback included.
+  func scrollbackText() -> String {
+    backend?.scrollbackText() ?? ""
+  }
+
   /// Copy the current selection to the system clipboard.
   func copySelection() {
     guard let text = backend?.selectedText(), !text.isEmpty else { return }
@@ -789,33 +849,34 @@ class TerminalTab: NSView {
   // MARK: Drag & Drop
 
   private func setupDragAndDrop() {
-    registerForDraggedTypes([.fileURL])
+    registerForDraggedTypes([.fileURL, .string])
   }
 
-  override func draggingEntered(_ sender: NSDraggingInfo) -> NSDragOperation {
-    guard
-      sender.draggingPasteboard.canReadObject(
-        forClasses: [NSURL.self],
-        options: [.urlReadingFileURLsOnly: true])
-    else {
-      return []
+  /// The paths being dragged: file URLs, or text holding a URI list (apps
+  /// that drag files as text send one).
+  private func draggedPaths(_ sender: NSDraggingInfo) -> [String] {
+    let pasteboard = sender.draggingPasteboard
+    if let urls = pasteboard.readObjects(
+      forClasses: [NSURL.self],
+      options: [.urlReadingFileURLsOnly: true]) as? [URL], !urls.isEmpty
+    {
+      return urls.map(\.path)
     }
-    return .copy
+    guard let text = pasteboard.string(forType: .string) else { return [] }
+    return ImpulseCore.parseURIList(text)
   }
 
-  override func performDragOperation(_ sender: NSDraggingInfo) -> Bool {
-    guard
-      let urls = sender.draggingPasteboard.readObjects(
-        forClasses: [NSURL.self],
-        options: [.urlReadingFileURLsOnly: true]) as? [URL]
-    else {
-      return false
-    }
+  override func draggingEntered(_ sender: NSDraggingInfo) -> NSDragOperation {
+    draggedPaths(sender).isEmpty ? [] : .copy
+  }
 
-    let paths = urls.map { $0.path.shellEscaped }.joined(separator: " ")
+  override func performDragOperation(_ sender: NSDraggingInfo) -> Bool {
+    let paths = draggedPaths(sender)
     guard !paths.isEmpty, let backend else { return false }
 
-    writePastePayload(paths, to: backend)
+    // Trailing space so the user can keep typing.
+    let words = paths.map(\.shellEscaped).joined(separator: " ") + " "
+    writePastePayload(words, to: backend)
     return true
   }
 
@@ -833,16 +894,29 @@ class TerminalTab: NSView {
 
   // MARK: Configuration
 
+  /// Zoom this pane by `zoom` points over the `terminalFontSize` setting.
+  func setZoom(_ zoom: Int) {
+    self.zoom = zoom
+    if let settings = currentSettings {
+      applyFont(settings)
+    }
+  }
+
+  private func applyFont(_ settings: TerminalSettings) {
+    let fontSize = CGFloat(
+      ImpulseCore.zoomedFontSize(base: settings.terminalFontSize, zoom: zoom))
+    let fontFamily =
+      settings.terminalFontFamily.isEmpty ? "JetBrains Mono" : settings.terminalFontFamily
+    renderer.updateFont(family: fontFamily, size: fontSize)
+  }
+
   /// Apply visual settings and theme to the terminal.
   func configureTerminal(settings: TerminalSettings, theme: TerminalTheme) {
     self.currentSettings = settings
     self.currentTheme = theme
+    let theme = theme.customized(by: settings)
 
-    // Font
-    let fontSize = CGFloat(settings.terminalFontSize)
-    let fontFamily =
-      settings.terminalFontFamily.isEmpty ? "JetBrains Mono" : settings.terminalFontFamily
-    renderer.updateFont(family: fontFamily, size: fontSize)
+    applyFont(settings)
 
     // Cursor shape (0=Block, 1=Beam, 2=Underline)
     renderer.cursorShapeOverride =
@@ -855,7 +929,7 @@ class TerminalTab: NSView {
     // Cursor blink
     renderer.cursorBlinkEnabled = settings.terminalCursorBlink
 
-    renderer.defaultBackgroundColor = cgColorFromHex(theme.bg)
+    renderer.defaultBackgroundColor = terminalBackground(theme, settings)
     renderer.defaultBackgroundRgb = hexToRgbBytes(theme.bg)
     renderer.selectionColor = cgColorFromHex(theme.selection)
     renderer.cursorColor = cgColorFromHex(theme.cursor)
@@ -877,6 +951,18 @@ class TerminalTab: NSView {
 
     // Copy on select
     setCopyOnSelect(enabled: settings.terminalCopyOnSelect)
+
+    // Palette overrides may have changed; push them to a running backend.
+    backend?.setColors(
+      config: TerminalBackendConfig.from(
+        settings: settings,
+        theme: theme,
+        shellPath: "",
+        shellArgs: [],
+        environment: [:],
+        workingDirectory: nil
+      ))
+    renderer.needsDisplay = true
   }
 
   /// Update terminal colors from a theme at runtime.
@@ -884,6 +970,7 @@ class TerminalTab: NSView {
     currentTheme = theme
     // Build a config with the new colors and push it to the backend.
     let settings = currentSettings ?? TerminalSettings()
+    let theme = theme.customized(by: settings)
     let config = TerminalBackendConfig.from(
       settings: settings,
       theme: theme,
@@ -893,7 +980,7 @@ class TerminalTab: NSView {
       workingDirectory: nil
     )
     backend?.setColors(config: config)
-    renderer.defaultBackgroundColor = cgColorFromHex(theme.bg)
+    renderer.defaultBackgroundColor = terminalBackground(theme, settings)
     renderer.defaultBackgroundRgb = hexToRgbBytes(theme.bg)
     renderer.selectionColor = cgColorFromHex(theme.selection)
     renderer.cursorColor = cgColorFromHex(theme.cursor)
@@ -1031,7 +1118,7 @@ class TerminalTab: NSView {
 
     let workingDir = initialDirectory ?? currentWorkingDirectory
     let settings = currentSettings ?? TerminalSettings()
-    let theme = currentTheme ?? TerminalTheme()
+    let theme = (currentTheme ?? TerminalTheme()).customized(by: settings)
 
     let config = TerminalBackendConfig.from(
       settings: settings,
@@ -1108,12 +1195,24 @@ class TerminalTab: NSView {
 
   // MARK: CWD Polling
 
+  /// Move `dir` to the front of the directory history, keeping as many
+  /// entries as `impulse_core::status_bar::DIRECTORY_HISTORY_LIMIT`.
+  private func recordDirectory(_ dir: String) {
+    guard !dir.isEmpty else { return }
+    directoryHistory.removeAll { $0 == dir }
+    directoryHistory.insert(dir, at: 0)
+    if directoryHistory.count > 20 {
+      directoryHistory.removeLast(directoryHistory.count - 20)
+    }
+  }
+
   private func startCwdPolling() {
     cwdPollTimer?.invalidate()
     cwdPollTimer = Timer.scheduledTimer(withTimeInterval: 5.0, repeats: true) { [weak self] _ in
       guard let self, let backend = self.backend, !backend.isShutdown else { return }
       if let cwd = backend.queryCwd(), cwd != self.currentWorkingDirectory {
         self.currentWorkingDirectory = cwd
+        self.recordDirectory(cwd)
         NotificationCenter.default.post(
           name: .terminalCwdChanged,
           object: self,
@@ -1135,6 +1234,7 @@ struct TerminalSettings {
   var terminalScrollback: Int = 10_000
   var lastDirectory: String = ""
   var terminalCopyOnSelect: Bool = true
+  var terminalConfirmPaste: Bool = true
   var terminalBell: Bool = true
   var terminalAttentionOnBell: Bool = true
   var terminalScrollOnOutput: Bool = true
@@ -1148,6 +1248,10 @@ struct TerminalSettings {
   var terminalAllowOsc52Read: Bool = false
   var terminalBlocks: Bool = true
   var terminalContextBar: Bool = true
+  /// ANSI palette overrides ("" keeps the theme color) and cursor override.
+  var terminalPalette: [String] = []
+  var terminalCursorColor: String = ""
+  var terminalBackgroundOpacity: Double = 1.0
   var keybindingOverrides: [String: String] = [:]
 }
 
@@ -1194,3 +1298,30 @@ struct TerminalTheme {
     "#7FB4CA", "#938AA9", "#7AA89F", "#DCD7BA",
   ]
 }
+
+extension TerminalTheme {
+  /// The theme with the settings' palette and cursor overrides applied.
+  /// Entries that aren't `#rrggbb` keep the theme color.
+  func customized(by settings: TerminalSettings) -> TerminalTheme {
+    var theme = self
+    for (index, color) in settings.terminalPalette.prefix(theme.terminalPalette.count).enumerated()
+    where isHexColor(color) {
+      theme.terminalPalette[index] = color
+    }
+    if isHexColor(settings.terminalCursorColor) {
+      theme.cursor = settings.terminalCursorColor
+    }
+    return theme
+  }
+}
+
+private func isHexColor(_ color: String) -> Bool {
+  color.count == 7 && color.hasPrefix("#") && color.dropFirst().allSatisfy(\.isHexDigit)
+}
+
+/// The default cell background, faded to the terminal background opacity.
+private func terminalBackground(_ theme: TerminalTheme, _ settings: TerminalSettings) -> CGColor {
+  let color = cgColorFromHex(theme.bg)
+  let opacity = min(max(settings.terminalBackgroundOpacity, 0.3), 1.0)
+  return color.copy(alpha: CGFloat(opacity)) ?? color
+}
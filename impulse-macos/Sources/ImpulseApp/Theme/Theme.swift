@@ -362,7 +362,33 @@ enum ThemeManager {
 
     /// Convenience: returns the theme for the given settings.
     static func currentTheme(from settings: Settings) -> Theme {
-        theme(forName: settings.colorScheme)
+        theme(forName: settings.activeColorScheme(systemIsDark: systemIsDark))
+    }
+
+    /// The user's `editor.css` (next to `settings.json`), injected into every
+    /// Monaco editor. Read once; `reloadEditorCustomCss()` re-reads it.
+    private(set) static var editorCustomCss: String = readEditorCustomCss()
+
+    /// Re-read `editor.css` after the user edits it.
+    @discardableResult
+    static func reloadEditorCustomCss() -> String {
+        editorCustomCss = readEditorCustomCss()
+        return editorCustomCss
+    }
+
+    private static func readEditorCustomCss() -> String {
+        let url = Settings.settingsPath().deletingLastPathComponent()
+            .appendingPathComponent("editor.css")
+        guard let css = try? String(contentsOf: url, encoding: .utf8),
+              !css.trimmingCharacters(in: .whitespacesAndNewlines).isEmpty else {
+            return ""
+        }
+        return css
+    }
+
+    /// Whether the system (not any window override) is in dark mode.
+    static var systemIsDark: Bool {
+        NSApp.effectiveAppearance.bestMatch(from: [.aqua, .darkAqua]) == .darkAqua
     }
 
     /// Returns a `MonacoThemeDefinition` for the named theme, decoded from FFI JSON.
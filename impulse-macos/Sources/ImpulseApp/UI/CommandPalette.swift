@@ -34,6 +34,9 @@ final class CommandPaletteWindow: NSPanel, NSTextFieldDelegate, NSTableViewDataS
   private(set) var commands: [PaletteCommand] = []
   private(set) var filteredCommands: [PaletteCommand] = []
   private var dynamicCommands: [PaletteCommand] = []
+  /// Each built-in command's `when` from the core registry.
+  private var commandWhen: [String: String] = [:]
+  private var commandContext = CommandContext()
   private var recentCommands = RecentCommandStore()
   private var dynamicSearchGeneration = 0
   private let dynamicSearchQueue = DispatchQueue(
@@ -73,6 +76,9 @@ final class CommandPaletteWindow: NSPanel, NSTextFieldDelegate, NSTableViewDataS
     level = .floating
     isMovableByWindowBackground = false
     hidesOnDeactivate = false
+    // The panel is borderless, so VoiceOver would otherwise have no name for it.
+    title = "Command Palette"
+    setAccessibilityLabel("Command Palette")
 
     let container = NSVisualEffectView()
     container.material = .hudWindow
@@ -100,6 +106,7 @@ final class CommandPaletteWindow: NSPanel, NSTextFieldDelegate, NSTableViewDataS
     searchField.drawsBackground = false
     searchField.textColor = .labelColor
     searchField.delegate = self
+    searchField.setAccessibilityLabel("Command Palette")
 
     container.addSubview(searchField)
 
@@ -131,6 +138,7 @@ final class CommandPaletteWindow: NSPanel, NSTextFieldDelegate, NSTableViewDataS
     tableView.addTableColumn(shortcutColumn)
 
     tableView.headerView = nil
+    tableView.setAccessibilityLabel("Commands")
     tableView.rowHeight = Self.rowHeight
     tableView.intercellSpacing = NSSize(width: 0, height: 0)
     tableView.backgroundColor = .clear
@@ -164,14 +172,16 @@ final class CommandPaletteWindow: NSPanel, NSTextFieldDelegate, NSTableViewDataS
 
   // MARK: - Command Registration
 
-  /// Registers all built-in commands from the Keybindings registry and any
-  /// custom keybindings from the current settings.
+  /// Registers all built-in commands from the core command registry.
   func registerBuiltinCommands(overrides: [String: String] = [:]) {
     var result: [PaletteCommand] = []
+    commandWhen = [:]
 
-    for item in ImpulseCore.commandPaletteBuiltinItems() {
-      let shortcut = item.shortcut ?? Keybindings.shortcutDisplay(forId: item.id, overrides: overrides)
+    for entry in ImpulseCore.commandRegistry() where entry.source == "builtin" {
+      let item = entry.item
+      let shortcut = overrides[item.id].flatMap { $0.isEmpty ? nil : $0 } ?? item.shortcut
       let action = Self.builtinAction(for: item.id)
+      commandWhen[item.id] = entry.when
 
       result.append(
         PaletteCommand(
@@ -185,27 +195,45 @@ final class CommandPaletteWindow: NSPanel, NSTextFieldDelegate, NSTableViewDataS
     filteredCommands = result
   }
 
-  private static func builtinAction(for id: String) -> () -> Void {
+  /// The action for a core built-in command id. `impulse_execute_command`
+  /// (and so the main menu's registry items) dispatches through this too.
+  static func builtinAction(for id: String) -> () -> Void {
     let notificationMap: [String: Notification.Name] = [
       "new_tab": .impulseNewTerminalTab,
       "close_tab": .impulseCloseTab,
       "reopen_tab": .impulseReopenTab,
       "next_tab": .impulseNextTab,
       "prev_tab": .impulsePrevTab,
+      "show_all_tabs": .impulseShowAllTabs,
       "new_file": .impulseNewFile,
       "save": .impulseSaveFile,
+      "save_all": .impulseSaveAll,
       "find": .impulseFind,
       "go_to_line": .impulseGoToLine,
+      "toggle_read_only": .impulseToggleReadOnly,
+      "local_history": .impulseShowLocalHistory,
+      "compare_with_saved": .impulseCompareWithSaved,
+      "revert_file": .impulseRevertFile,
       "toggle_markdown_preview": .impulseToggleMarkdownPreview,
+      "open_containing_folder": .impulseOpenContainingFolder,
+      "reveal_in_sidebar": .impulseRevealInSidebar,
+      "open_scrollback_in_editor": .impulseOpenScrollbackInEditor,
       "toggle_sidebar": .impulseToggleSidebar,
+      "toggle_zen_mode": .impulseToggleZenMode,
+      "toggle_tab_bar": .impulseToggleTabBar,
+      "toggle_status_bar": .impulseToggleStatusBar,
+      "toggle_centered_layout": .impulseToggleCenteredLayout,
       "project_search": .impulseFindInProject,
       "command_palette": .impulseShowCommandPalette,
       "quick_open": .impulseQuickOpen,
       "install_lsp": .impulseInstallLsp,
+      "reload_custom_css": .impulseReloadCustomCss,
       "font_increase": .impulseFontIncrease,
       "font_decrease": .impulseFontDecrease,
       "font_reset": .impulseFontReset,
       "review_changes": .impulseReviewChanges,
+      "manage_workspace_trust": .impulseManageWorkspaceTrust,
+      "show_notifications": .impulseShowNotifications,
     ]
 
     if let notificationName = notificationMap[id] {
@@ -214,6 +242,21 @@ final class CommandPaletteWindow: NSPanel, NSTextFieldDelegate, NSTableViewDataS
       }
     }
 
+    let tabCommands: [String: (Notification.Name, String)] = [
+      "sort_tabs_by_path": (.impulseSortTabs, "path"),
+      "sort_tabs_by_recent_use": (.impulseSortTabs, "recent_use"),
+      "close_saved_tabs": (.impulseCloseTabGroup, "saved"),
+      "close_tabs_to_right": (.impulseCloseTabGroup, "right"),
+      "close_tabs_to_left": (.impulseCloseTabGroup, "left"),
+      "copy_path": (.impulseCopyPath, "copy_path"),
+      "copy_relative_path": (.impulseCopyPath, "copy_relative_path"),
+      "copy_file_name": (.impulseCopyPath, "copy_file_name"),
+      "copy_file_url": (.impulseCopyPath, "copy_file_url"),
+    ]
+    if let (name, argument) = tabCommands[id] {
+      return { NotificationCenter.default.post(name: name, object: argument) }
+    }
+
     switch id {
     case "copy":
       return { NSApp.sendAction(#selector(NSText.copy(_:)), to: nil, from: nil) }
@@ -223,6 +266,22 @@ final class CommandPaletteWindow: NSPanel, NSTextFieldDelegate, NSTableViewDataS
       return { (NSApp.delegate as? AppDelegate)?.showPreferences(nil) }
     case "new_window":
       return { (NSApp.delegate as? AppDelegate)?.newWindow(nil) }
+    case "save_window_layout":
+      return { (NSApp.delegate as? AppDelegate)?.saveWindowLayout(nil) }
+    case "open_window_layout":
+      return { (NSApp.delegate as? AppDelegate)?.openWindowLayout(nil) }
+    case "open_remote_file":
+      return { (NSApp.delegate as? AppDelegate)?.openRemoteFile(nil) }
+    case "export_settings":
+      return { (NSApp.delegate as? AppDelegate)?.exportSettings(nil) }
+    case "import_settings":
+      return { (NSApp.delegate as? AppDelegate)?.importSettings(nil) }
+    case "import_vscode_settings":
+      return { (NSApp.delegate as? AppDelegate)?.importVscodeSettings(nil) }
+    case "sync_settings":
+      return { (NSApp.delegate as? AppDelegate)?.syncSettings(nil) }
+    case "check_for_updates":
+      return { (NSApp.delegate as? AppDelegate)?.checkForUpdates(nil) }
     case "fullscreen":
       return { NSApp.keyWindow?.toggleFullScreen(nil) }
     default:
@@ -294,8 +353,11 @@ final class CommandPaletteWindow: NSPanel, NSTextFieldDelegate, NSTableViewDataS
 
   /// Positions the palette centered horizontally near the top of the given
   /// window and makes it key.
-  func show(relativeTo parentWindow: NSWindow) {
+  /// Shows the palette over `parentWindow`, listing the commands that
+  /// apply to `context` (the active tab).
+  func show(relativeTo parentWindow: NSWindow, context: CommandContext = CommandContext()) {
     self.ownerWindow = parentWindow
+    commandContext = context
     searchField.stringValue = ""
     dynamicSearchGeneration += 1
     dynamicCommands.removeAll()
@@ -397,7 +459,9 @@ final class CommandPaletteWindow: NSPanel, NSTextFieldDelegate, NSTableViewDataS
   }
 
   private func filteredCommandPaletteCommands(for query: String) -> [PaletteCommand] {
-    let allCommands = commands + dynamicCommands
+    let allCommands = commands.filter {
+      commandContext.allows(commandWhen[$0.id] ?? "always")
+    } + dynamicCommands
     var commandsById: [String: PaletteCommand] = [:]
     for command in allCommands {
       commandsById[command.id] = command
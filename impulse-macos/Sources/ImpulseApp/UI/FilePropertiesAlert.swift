@@ -0,0 +1,147 @@
+import AppKit
+
+/// The file tree's "Properties" alert: size, modified time, owner, symlink
+/// target, and the line count and encoding of text files, with checkboxes
+/// to change the permission bits.
+enum FilePropertiesAlert {
+
+  static func run(path: String) {
+    let properties: ImpulseCore.FileProperties
+    switch ImpulseCore.fileProperties(at: path) {
+    case .success(let props):
+      properties = props
+    case .failure(let error):
+      ImpulseCore.notify(
+        .error, title: "Reading properties failed", message: error.message, source: "files")
+      return
+    }
+
+    let alert = NSAlert()
+    alert.messageText = properties.name
+    alert.informativeText = (properties.path as NSString).deletingLastPathComponent
+
+    var rows: [(String, String)] = []
+    switch properties.kind {
+    case "directory": rows.append(("Kind", "Folder"))
+    case "symlink": rows.append(("Kind", "Symbolic link"))
+    case "file": rows.append(("Kind", "File"))
+    default: rows.append(("Kind", "Special file"))
+    }
+    if let target = properties.symlinkTarget { rows.append(("Link Target", target)) }
+    var isDirectory: ObjCBool = false
+    if FileManager.default.fileExists(atPath: path, isDirectory: &isDirectory),
+      !isDirectory.boolValue
+    {
+      rows.append(("Size", properties.sizeLabel))
+    }
+    if let lines = properties.lineCount { rows.append(("Lines", String(lines))) }
+    if let encoding = properties.encoding { rows.append(("Encoding", encoding)) }
+    if let modified = properties.modified {
+      let date = Date(timeIntervalSince1970: TimeInterval(modified))
+      rows.append(("Modified", date.formatted(date: .abbreviated, time: .standard)))
+    }
+    if let owner = properties.owner { rows.append(("Owner", owner)) }
+    if let group = properties.group { rows.append(("Group", group)) }
+
+    let grid = NSGridView(views: rows.map { title, value in
+      let titleLabel = NSTextField(labelWithString: title + ":")
+      titleLabel.alignment = .right
+      titleLabel.textColor = .secondaryLabelColor
+      let valueLabel = NSTextField(labelWithString: value)
+      valueLabel.isSelectable = true
+      valueLabel.lineBreakMode = .byTruncatingMiddle
+      valueLabel.widthAnchor.constraint(lessThanOrEqualToConstant: 280).isActive = true
+      return [titleLabel, valueLabel]
+    })
+    grid.column(at: 0).xPlacement = .trailing
+    grid.rowSpacing = 4
+
+    // Permission checkboxes in `ls -l` order: owner, group, others; read,
+    // write, execute.
+    var checks: [(UInt32, NSButton)] = []
+    let summary = NSTextField(labelWithString: "")
+    summary.font = .monospacedSystemFont(ofSize: NSFont.smallSystemFontSize, weight: .regular)
+    summary.textColor = .secondaryLabelColor
+    if let mode = properties.mode {
+      grid.addRow(with: [NSGridCell.emptyContentView, NSGridCell.emptyContentView])
+      let header = ["Permissions:", "Read", "Write", "Execute"].map { title -> NSView in
+        let label = NSTextField(labelWithString: title)
+        label.textColor = .secondaryLabelColor
+        return label
+      }
+      let permissions = NSGridView(views: [header])
+      for (row, who) in ["Owner", "Group", "Others"].enumerated() {
+        var views: [NSView] = [NSTextField(labelWithString: who)]
+        for col in 0..<3 {
+          let bit = UInt32(0o400) >> UInt32(row * 3 + col)
+          let check = NSButton(checkboxWithTitle: "", target: nil, action: nil)
+          check.state = mode & bit != 0 ? .on : .off
+          checks.append((bit, check))
+          views.append(check)
+        }
+        permissions.addRow(with: views)
+      }
+      for col in 1..<4 { permissions.column(at: col).xPlacement = .center }
+      permissions.column(at: 0).xPlacement = .trailing
+      permissions.addRow(with: [NSGridCell.emptyContentView, summary])
+      permissions.cell(for: summary)?.row?.mergeCells(in: NSRange(location: 1, length: 3))
+
+      let updateSummary = {
+        let newMode = chosenMode(base: mode, checks: checks)
+        summary.stringValue = "\(modeString(newMode)) (\(String(newMode & 0o777, radix: 8)))"
+      }
+      updateSummary()
+      for (_, check) in checks {
+        check.target = CheckboxAction.shared
+        check.action = #selector(CheckboxAction.toggled(_:))
+      }
+      CheckboxAction.shared.onToggle = updateSummary
+
+      let stack = NSStackView(views: [grid, permissions])
+      stack.orientation = .vertical
+      stack.alignment = .leading
+      stack.spacing = 12
+      stack.frame.size = stack.fittingSize
+      alert.accessoryView = stack
+      alert.addButton(withTitle: "Apply Permissions")
+      alert.addButton(withTitle: "Close")
+    } else {
+      grid.frame.size = grid.fittingSize
+      alert.accessoryView = grid
+      alert.addButton(withTitle: "Close")
+    }
+
+    let response = alert.runModal()
+    CheckboxAction.shared.onToggle = nil
+    guard let mode = properties.mode, response == .alertFirstButtonReturn else { return }
+    let newMode = chosenMode(base: mode, checks: checks)
+    guard newMode != mode else { return }
+    if case .failure(let error) = ImpulseCore.setFileMode(at: path, mode: newMode) {
+      ImpulseCore.notify(
+        .error, title: "Changing permissions failed", message: error.message, source: "files")
+    }
+  }
+
+  /// `base` with its rwx bits replaced by the checked boxes.
+  private static func chosenMode(base: UInt32, checks: [(UInt32, NSButton)]) -> UInt32 {
+    checks.reduce(base & ~UInt32(0o777)) { mode, check in
+      check.1.state == .on ? mode | check.0 : mode
+    }
+  }
+
+  /// `ls -l` style permissions, like "rwxr-xr--".
+  private static func modeString(_ mode: UInt32) -> String {
+    let letters: [Character] = ["r", "w", "x"]
+    return String((0..<9).map { i in
+      mode & (UInt32(0o400) >> UInt32(i)) != 0 ? letters[i % 3] : "-"
+    })
+  }
+
+  /// Target for the permission checkboxes while the modal alert runs.
+  private final class CheckboxAction: NSObject {
+    static let shared = CheckboxAction()
+    var onToggle: (() -> Void)?
+
+    @objc func toggled(_ sender: NSButton) { onToggle?() }
+  }
+}
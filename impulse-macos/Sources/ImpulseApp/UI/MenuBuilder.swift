@@ -17,6 +17,8 @@ enum MenuBuilder {
         mainMenu.addItem(buildFileMenu(overrides: overrides))
         mainMenu.addItem(buildEditMenu(overrides: overrides))
         mainMenu.addItem(buildViewMenu(overrides: overrides))
+        mainMenu.addItem(buildGoMenu(overrides: overrides))
+        mainMenu.addItem(buildTerminalMenu(overrides: overrides))
         mainMenu.addItem(buildWindowMenu(overrides: overrides))
         mainMenu.addItem(buildHelpMenu())
 
@@ -34,6 +36,25 @@ enum MenuBuilder {
         item.keyEquivalentModifierMask = keybinding.modifierFlags
     }
 
+    private static let builtinCommands = ImpulseCore.commandRegistry()
+
+    /// A menu item for a core registry command: its title, its shortcut,
+    /// and `impulse_execute_command` as the action.
+    private static func commandItem(
+        _ id: String,
+        title: String? = nil,
+        overrides: [String: String]
+    ) -> NSMenuItem {
+        let registered = builtinCommands.first { $0.id == id }
+        let item = NSMenuItem(title: title ?? registered?.title ?? id,
+                              action: #selector(MenuActions.menuRunCommand(_:)),
+                              keyEquivalent: "")
+        item.target = MenuActions.shared
+        item.representedObject = id
+        applyKeybinding(id, overrides: overrides, to: item)
+        return item
+    }
+
     // MARK: - Impulse (App) Menu
 
     private static func buildAppMenu(overrides: [String: String]) -> NSMenuItem {
@@ -45,6 +66,11 @@ enum MenuBuilder {
                      action: #selector(NSApplication.orderFrontStandardAboutPanel(_:)),
                      keyEquivalent: "")
 
+        let updatesItem = NSMenuItem(title: "Check for Updates...",
+                                     action: #selector(AppDelegate.checkForUpdates(_:)),
+                                     keyEquivalent: "")
+        menu.addItem(updatesItem)
+
         menu.addItem(.separator())
 
         let prefsItem = NSMenuItem(title: "Settings...",
@@ -113,6 +139,13 @@ enum MenuBuilder {
         applyKeybinding("new_window", overrides: overrides, to: newWindowItem)
         menu.addItem(newWindowItem)
 
+        menu.addItem(NSMenuItem(title: "Save Window Layout As\u{2026}",
+                                action: #selector(AppDelegate.saveWindowLayout(_:)),
+                                keyEquivalent: ""))
+        menu.addItem(NSMenuItem(title: "Open Layout\u{2026}",
+                                action: #selector(AppDelegate.openWindowLayout(_:)),
+                                keyEquivalent: ""))
+
         menu.addItem(.separator())
 
         let openItem = NSMenuItem(title: "Open...",
@@ -121,6 +154,15 @@ enum MenuBuilder {
         openItem.target = MenuActions.shared
         openItem.keyEquivalentModifierMask = [.command]
         menu.addItem(openItem)
+        menu.addItem(NSMenuItem(title: "Open Remote File\u{2026}",
+                                action: #selector(AppDelegate.openRemoteFile(_:)),
+                                keyEquivalent: ""))
+
+        let recentMenu = NSMenu(title: "Open Recent")
+        recentMenu.delegate = RecentFilesMenuDelegate.shared
+        let recentItem = NSMenuItem(title: "Open Recent", action: nil, keyEquivalent: "")
+        recentItem.submenu = recentMenu
+        menu.addItem(recentItem)
 
         menu.addItem(.separator())
 
@@ -153,6 +195,14 @@ enum MenuBuilder {
         applyKeybinding("save", overrides: overrides, to: saveItem)
         menu.addItem(saveItem)
 
+        let saveAllItem = NSMenuItem(title: "Save All",
+                                     action: #selector(MenuActions.menuSaveAll(_:)),
+                                     keyEquivalent: "s")
+        saveAllItem.keyEquivalentModifierMask = [.command, .option]
+        saveAllItem.target = MenuActions.shared
+        applyKeybinding("save_all", overrides: overrides, to: saveAllItem)
+        menu.addItem(saveAllItem)
+
         return item
     }
 
@@ -210,13 +260,6 @@ enum MenuBuilder {
         applyKeybinding("find", overrides: overrides, to: findItem)
         menu.addItem(findItem)
 
-        let goToLineItem = NSMenuItem(title: "Go to Line...",
-                                      action: #selector(MenuActions.menuGoToLine(_:)),
-                                      keyEquivalent: "g")
-        goToLineItem.target = MenuActions.shared
-        applyKeybinding("go_to_line", overrides: overrides, to: goToLineItem)
-        menu.addItem(goToLineItem)
-
         return item
     }
 
@@ -267,21 +310,21 @@ enum MenuBuilder {
 
         menu.addItem(.separator())
 
-        let fontIncreaseItem = NSMenuItem(title: "Increase Font Size",
+        let fontIncreaseItem = NSMenuItem(title: "Zoom In",
                                           action: #selector(MenuActions.menuFontIncrease(_:)),
                                           keyEquivalent: "=")
         fontIncreaseItem.target = MenuActions.shared
         applyKeybinding("font_increase", overrides: overrides, to: fontIncreaseItem)
         menu.addItem(fontIncreaseItem)
 
-        let fontDecreaseItem = NSMenuItem(title: "Decrease Font Size",
+        let fontDecreaseItem = NSMenuItem(title: "Zoom Out",
                                           action: #selector(MenuActions.menuFontDecrease(_:)),
                                           keyEquivalent: "-")
         fontDecreaseItem.target = MenuActions.shared
         applyKeybinding("font_decrease", overrides: overrides, to: fontDecreaseItem)
         menu.addItem(fontDecreaseItem)
 
-        let fontResetItem = NSMenuItem(title: "Reset Font Size",
+        let fontResetItem = NSMenuItem(title: "Reset Zoom",
                                        action: #selector(MenuActions.menuFontReset(_:)),
                                        keyEquivalent: "0")
         fontResetItem.target = MenuActions.shared
@@ -296,11 +339,78 @@ enum MenuBuilder {
         applyKeybinding("fullscreen", overrides: overrides, to: fullscreenItem)
         menu.addItem(fullscreenItem)
 
+        let zenItem = NSMenuItem(title: "Toggle Zen Mode",
+                                 action: #selector(MenuActions.menuToggleZenMode(_:)),
+                                 keyEquivalent: "z")
+        zenItem.target = MenuActions.shared
+        applyKeybinding("toggle_zen_mode", overrides: overrides, to: zenItem)
+        menu.addItem(zenItem)
+
+        return item
+    }
+
+    // MARK: - Go Menu
+
+    private static func buildGoMenu(overrides: [String: String]) -> NSMenuItem {
+        let menu = NSMenu(title: "Go")
+        let item = NSMenuItem()
+        item.submenu = menu
+
+        menu.addItem(commandItem("quick_open", title: "Go to File...", overrides: overrides))
+        menu.addItem(commandItem("go_to_line", title: "Go to Line...", overrides: overrides))
+
+        menu.addItem(.separator())
+
+        // Window > Show Next/Previous Tab carry the shortcuts.
+        menu.addItem(commandItem("next_tab", title: "Next Tab", overrides: overrides)
+            .withoutShortcut())
+        menu.addItem(commandItem("prev_tab", title: "Previous Tab", overrides: overrides)
+            .withoutShortcut())
+
+        menu.addItem(.separator())
+
+        menu.addItem(commandItem("open_containing_folder", title: "Reveal in Finder",
+                                 overrides: overrides))
+        menu.addItem(commandItem("reveal_in_sidebar", overrides: overrides))
+        menu.addItem(commandItem("copy_path", overrides: overrides))
+        menu.addItem(commandItem("copy_relative_path", overrides: overrides))
+        menu.addItem(commandItem("copy_file_name", overrides: overrides))
+        menu.addItem(commandItem("copy_file_url", overrides: overrides))
+
         return item
     }
 
     // MARK: - Terminal Menu
 
+    private static func buildTerminalMenu(overrides: [String: String]) -> NSMenuItem {
+        let menu = NSMenu(title: "Terminal")
+        let item = NSMenuItem()
+        item.submenu = menu
+
+        // Cmd+T already belongs to File > New Tab.
+        menu.addItem(commandItem("new_tab", title: "New Terminal Tab", overrides: overrides)
+            .withoutShortcut())
+
+        menu.addItem(.separator())
+
+        // No Cmd+K: Monaco's chords start with it.
+        let clearItem = NSMenuItem(title: "Clear",
+                                   action: #selector(MenuActions.menuClearTerminal(_:)),
+                                   keyEquivalent: "")
+        clearItem.target = MenuActions.shared
+        menu.addItem(clearItem)
+
+        let historyItem = NSMenuItem(title: "Command History...",
+                                     action: #selector(MenuActions.menuShowCommandHistory(_:)),
+                                     keyEquivalent: "")
+        historyItem.target = MenuActions.shared
+        menu.addItem(historyItem)
+
+        menu.addItem(commandItem("open_scrollback_in_editor", overrides: overrides))
+
+        return item
+    }
+
     // MARK: - Window Menu
 
     private static func buildWindowMenu(overrides: [String: String]) -> NSMenuItem {
@@ -337,6 +447,15 @@ enum MenuBuilder {
         prevTabItem.target = MenuActions.shared
         applyKeybinding("prev_tab", overrides: overrides, to: prevTabItem)
         menu.addItem(prevTabItem)
+        menu.addItem(commandItem("show_all_tabs", title: "Show All Tabs...", overrides: overrides))
+
+        menu.addItem(.separator())
+
+        menu.addItem(commandItem("sort_tabs_by_path", overrides: overrides))
+        menu.addItem(commandItem("sort_tabs_by_recent_use", overrides: overrides))
+        menu.addItem(commandItem("close_tabs_to_right", overrides: overrides))
+        menu.addItem(commandItem("close_tabs_to_left", overrides: overrides))
+        menu.addItem(commandItem("close_saved_tabs", overrides: overrides))
 
         menu.addItem(.separator())
 
@@ -424,6 +543,10 @@ final class MenuActions: NSObject {
         NotificationCenter.default.post(name: .impulseSaveFile, object: nil)
     }
 
+    @objc func menuSaveAll(_ sender: Any?) {
+        NotificationCenter.default.post(name: .impulseSaveAll, object: nil)
+    }
+
     @objc func menuFind(_ sender: Any?) {
         NotificationCenter.default.post(name: .impulseFind, object: nil)
     }
@@ -432,6 +555,10 @@ final class MenuActions: NSObject {
         NotificationCenter.default.post(name: .impulseToggleSidebar, object: nil)
     }
 
+    @objc func menuToggleZenMode(_ sender: Any?) {
+        NotificationCenter.default.post(name: .impulseToggleZenMode, object: nil)
+    }
+
     @objc func menuShowCommandPalette(_ sender: Any?) {
         NotificationCenter.default.post(name: .impulseShowCommandPalette, object: nil)
     }
@@ -448,10 +575,6 @@ final class MenuActions: NSObject {
         NotificationCenter.default.post(name: .impulseReviewChanges, object: nil)
     }
 
-    @objc func menuGoToLine(_ sender: Any?) {
-        NotificationCenter.default.post(name: .impulseGoToLine, object: nil)
-    }
-
     @objc func menuFontIncrease(_ sender: Any?) {
         NotificationCenter.default.post(name: .impulseFontIncrease, object: nil)
     }
@@ -472,6 +595,22 @@ final class MenuActions: NSObject {
         NotificationCenter.default.post(name: .impulsePrevTab, object: nil)
     }
 
+    /// Runs the registry command in the item's `representedObject`.
+    @objc func menuRunCommand(_ sender: Any?) {
+        guard let id = (sender as? NSMenuItem)?.representedObject as? String else { return }
+        if let error = ImpulseCore.executeCommand(id) {
+            NSLog("Menu command %@ failed: %@", id, error)
+        }
+    }
+
+    @objc func menuClearTerminal(_ sender: Any?) {
+        NotificationCenter.default.post(name: .impulseClearTerminal, object: nil)
+    }
+
+    @objc func menuShowCommandHistory(_ sender: Any?) {
+        NotificationCenter.default.post(name: .impulseShowCommandHistory, object: nil)
+    }
+
     @objc func menuSelectTab(_ sender: Any?) {
         guard let menuItem = sender as? NSMenuItem else { return }
         NotificationCenter.default.post(
@@ -481,3 +620,55 @@ final class MenuActions: NSObject {
         )
     }
 }
+
+private extension NSMenuItem {
+    /// For a second menu entry of a command whose shortcut another menu
+    /// already shows.
+    func withoutShortcut() -> NSMenuItem {
+        keyEquivalent = ""
+        keyEquivalentModifierMask = []
+        return self
+    }
+}
+
+// MARK: - Open Recent
+
+/// Fills File > Open Recent from `NSDocumentController`'s recent documents
+/// (recorded when editor tabs open), which also feeds the Dock menu.
+final class RecentFilesMenuDelegate: NSObject, NSMenuDelegate {
+
+    static let shared = RecentFilesMenuDelegate()
+
+    func menuNeedsUpdate(_ menu: NSMenu) {
+        menu.removeAllItems()
+        let urls = NSDocumentController.shared.recentDocumentURLs
+        for url in urls {
+            let item = NSMenuItem(title: url.lastPathComponent,
+                                  action: #selector(openRecent(_:)),
+                                  keyEquivalent: "")
+            item.target = self
+            item.representedObject = url
+            item.toolTip = url.path
+            item.image = NSWorkspace.shared.icon(forFile: url.path)
+            item.image?.size = NSSize(width: 16, height: 16)
+            menu.addItem(item)
+        }
+        if !urls.isEmpty {
+            menu.addItem(.separator())
+        }
+        let clearItem = NSMenuItem(title: "Clear Menu",
+                                   action: urls.isEmpty ? nil : #selector(clearRecent(_:)),
+                                   keyEquivalent: "")
+        clearItem.target = self
+        menu.addItem(clearItem)
+    }
+
+    @objc private func openRecent(_ sender: NSMenuItem) {
+        guard let url = sender.representedObject as? URL else { return }
+        NSApp.delegate?.application?(NSApp, openFiles: [url.path])
+    }
+
+    @objc private func clearRecent(_ sender: Any?) {
+        NSDocumentController.shared.clearRecentDocuments(nil)
+    }
+}
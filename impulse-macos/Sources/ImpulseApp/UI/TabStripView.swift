@@ -0,0 +1,492 @@
+import AppKit
+
+/// The horizontal tab bar in AppKit: Finder-style pill tabs dividing the
+/// strip equally, a close button on the tab under the pointer, drag to
+/// reorder (or out of the window to move the tab), the tab context menu and
+/// the "Show All Tabs" button. `TabBarView` hosts it and passes it
+/// `WindowModel`'s tabs.
+final class TabStripView: NSView {
+  static let height: CGFloat = 40
+
+  private static let tabHeight: CGFloat = 30
+  private static let spacing: CGFloat = 2
+  private static let padding = NSEdgeInsets(top: 5, left: 8, bottom: 5, right: 8)
+  private static let allTabsWidth: CGFloat = 22
+
+  private weak var model: WindowModel?
+  private var tabs: [TabDisplayInfo] = []
+  private var selectedIndex = -1
+  private var theme: Theme?
+  /// Tab views by stable tab id, reused across updates.
+  private var itemViews: [Int: TabItemView] = [:]
+  private let allTabsButton = NSButton()
+  private let separator = NSView()
+
+  /// The tab being dragged: where the drag started in window coordinates,
+  /// and how far it has moved since. `offset` is nil until the pointer has
+  /// moved far enough to count as a drag.
+  private var drag: (id: Int, startX: CGFloat, offset: CGFloat?)?
+
+  override var isFlipped: Bool { true }
+
+  override init(frame: NSRect) {
+    super.init(frame: frame)
+    setAccessibilityElement(true)
+    setAccessibilityRole(.tabGroup)
+    setAccessibilityLabel("Tabs")
+
+    allTabsButton.image = NSImage(
+      systemSymbolName: "chevron.down", accessibilityDescription: "Show All Tabs")?
+      .withSymbolConfiguration(.init(pointSize: 10, weight: .semibold))
+    allTabsButton.isBordered = false
+    allTabsButton.imagePosition = .imageOnly
+    allTabsButton.contentTintColor = .secondaryLabelColor
+    allTabsButton.target = self
+    allTabsButton.action = #selector(toggleAllTabs(_:))
+    // With many tabs the titles get too narrow to read; this lists them all
+    // with a search.
+    allTabsButton.toolTip = "Show All Tabs"
+    allTabsButton.setAccessibilityLabel("Show All Tabs")
+    addSubview(allTabsButton)
+
+    separator.wantsLayer = true
+    addSubview(separator)
+  }
+
+  required init?(coder: NSCoder) {
+    fatalError("init(coder:) has not been implemented")
+  }
+
+  func update(model: WindowModel, tabs: [TabDisplayInfo], selectedIndex: Int, theme: Theme) {
+    self.model = model
+    self.tabs = tabs
+    self.selectedIndex = selectedIndex
+    self.theme = theme
+
+    let ids = Set(tabs.map(\.id))
+    for (id, view) in itemViews where !ids.contains(id) {
+      view.removeFromSuperview()
+      itemViews[id] = nil
+    }
+    for tab in tabs {
+      let view: TabItemView
+      if let existing = itemViews[tab.id] {
+        view = existing
+      } else {
+        view = TabItemView(strip: self)
+        addSubview(view, positioned: .below, relativeTo: allTabsButton)
+        itemViews[tab.id] = view
+      }
+      view.configure(
+        tab: tab, isSelected: tab.index == selectedIndex,
+        isDragging: drag?.id == tab.id && drag?.offset != nil, theme: theme)
+    }
+    if let drag, !ids.contains(drag.id) {
+      self.drag = nil
+    }
+
+    // Card-surface themes (Harbor) keep the canvas hairline-free; the
+    // floating content card already separates the regions.
+    separator.isHidden = theme.surfaceStyle == "card"
+    updateSeparatorColor()
+    needsLayout = true
+  }
+
+  override func viewDidChangeEffectiveAppearance() {
+    super.viewDidChangeEffectiveAppearance()
+    updateSeparatorColor()
+  }
+
+  private func updateSeparatorColor() {
+    effectiveAppearance.performAsCurrentDrawingAppearance {
+      separator.layer?.backgroundColor = NSColor.separatorColor.cgColor
+    }
+  }
+
+  @objc private func toggleAllTabs(_ sender: Any?) {
+    model?.allTabsPanelVisible.toggle()
+  }
+
+  // MARK: Layout
+
+  override func layout() {
+    super.layout()
+    let pad = Self.padding
+    allTabsButton.frame = NSRect(
+      x: bounds.maxX - pad.right - Self.allTabsWidth,
+      y: (bounds.height - Self.allTabsWidth) / 2,
+      width: Self.allTabsWidth, height: Self.allTabsWidth)
+    separator.frame = NSRect(x: 0, y: bounds.maxY - 1, width: bounds.width, height: 1)
+    layoutTabs(animated: false)
+  }
+
+  /// Where tab `index` sits when nothing is being dragged.
+  private func slot(_ index: Int) -> NSRect {
+    let pad = Self.padding
+    let count = CGFloat(max(tabs.count, 1))
+    let available =
+      bounds.width - pad.left - pad.right - Self.allTabsWidth - Self.spacing
+      - Self.spacing * (count - 1)
+    let width = max(available / count, 0)
+    return NSRect(
+      x: pad.left + CGFloat(index) * (width + Self.spacing), y: pad.top,
+      width: width, height: Self.tabHeight)
+  }
+
+  /// Place every tab; while dragging, the dragged tab follows the pointer
+  /// and the others slide aside once its center crosses theirs.
+  private func layoutTabs(animated: Bool) {
+    let draggedIndex = drag.flatMap { drag in tabs.firstIndex(where: { $0.id == drag.id }) }
+    let offset = drag?.offset ?? 0
+    for (index, tab) in tabs.enumerated() {
+      guard let view = itemViews[tab.id] else { continue }
+      var frame = slot(index)
+      if let draggedIndex, drag?.offset != nil {
+        if index == draggedIndex {
+          frame.origin.x += offset
+          view.frame = frame
+          continue
+        }
+        let draggedCenter = slot(draggedIndex).midX + offset
+        let shift = frame.width + Self.spacing
+        if draggedIndex < index && draggedCenter > frame.midX {
+          frame.origin.x -= shift
+        } else if draggedIndex > index && draggedCenter < frame.midX {
+          frame.origin.x += shift
+        }
+      }
+      if animated {
+        NSAnimationContext.runAnimationGroup { context in
+          context.duration = 0.2
+          context.allowsImplicitAnimation = true
+          view.animator().frame = frame
+        }
+      } else {
+        view.frame = frame
+      }
+    }
+  }
+
+  // MARK: Mouse
+
+  fileprivate func itemMouseDown(_ view: TabItemView, with event: NSEvent) {
+    drag = (view.tab.id, event.locationInWindow.x, nil)
+  }
+
+  fileprivate func itemMouseDragged(_ view: TabItemView, with event: NSEvent) {
+    guard let current = drag, current.id == view.tab.id else { return }
+    let offset = event.locationInWindow.x - current.startX
+    if current.offset == nil {
+      guard abs(offset) >= 5 else { return }
+      // Select the tab on drag start, like Finder.
+      model?.onTabSelected?(view.tab.index)
+      view.isDragging = true
+    }
+    drag = (current.id, current.startX, offset)
+    view.layer?.zPosition = 1
+    layoutTabs(animated: true)
+  }
+
+  fileprivate func itemMouseUp(_ view: TabItemView, with event: NSEvent) {
+    guard let current = drag, current.id == view.tab.id else { return }
+    drag = nil
+    view.isDragging = false
+    view.layer?.zPosition = 0
+    guard let offset = current.offset else {
+      model?.onTabSelected?(view.tab.index)
+      return
+    }
+    guard let source = tabs.firstIndex(where: { $0.id == current.id }) else { return }
+
+    // Dropped outside the window: the tab moves to the window under the
+    // pointer, or to a new one.
+    if model?.onTabDraggedOut?(source, NSEvent.mouseLocation) == true {
+      return
+    }
+
+    // The rightmost tab crossed to the right, or the leftmost crossed to
+    // the left.
+    let draggedCenter = slot(source).midX + offset
+    var target = source
+    for index in tabs.indices where index != source {
+      let midX = slot(index).midX
+      if source < index && draggedCenter > midX {
+        target = index
+      } else if source > index && draggedCenter < midX && index < target {
+        target = index
+      }
+    }
+    if source != target {
+      model?.onTabMoved?(source, target)
+    } else {
+      layoutTabs(animated: true)
+    }
+  }
+
+  fileprivate func select(_ view: TabItemView) {
+    model?.onTabSelected?(view.tab.index)
+  }
+
+  fileprivate func close(_ view: TabItemView) {
+    model?.onTabClosed?(view.tab.index)
+  }
+
+  // MARK: Context Menu
+
+  fileprivate func contextMenu(for view: TabItemView) -> NSMenu {
+    let tab = view.tab
+    let menu = NSMenu()
+    menu.addItem(
+      actionItem(tab.isPinned ? "Unpin Tab" : "Pin Tab") { [weak self] in
+        self?.model?.onTabPinToggled?(tab.index)
+      })
+    menu.addItem(actionItem("Close Tab") { [weak self] in self?.model?.onTabClosed?(tab.index) })
+    menu.addItem(
+      actionItem("Close Tabs to the Right") { [weak self] in
+        self?.model?.onTabGroupClosed?("right", tab.index)
+      })
+    menu.addItem(
+      actionItem("Close Tabs to the Left") { [weak self] in
+        self?.model?.onTabGroupClosed?("left", tab.index)
+      })
+    menu.addItem(
+      actionItem("Close Saved Tabs") { [weak self] in
+        self?.model?.onTabGroupClosed?("saved", tab.index)
+      })
+    if let path = tab.filePath {
+      menu.addItem(
+        actionItem("Reveal in Finder") {
+          NSWorkspace.shared.activateFileViewerSelecting([URL(fileURLWithPath: path)])
+        })
+      let root = model?.fileTreeRootPath ?? ""
+      for command in copyPathCommands {
+        menu.addItem(
+          actionItem(command.title) {
+            guard let text = ImpulseCore.pathForCopy(commandId: command.id, path: path, root: root)
+            else { return }
+            NSPasteboard.general.clearContents()
+            NSPasteboard.general.setString(text, forType: .string)
+          })
+      }
+    }
+    menu.addItem(.separator())
+    menu.addItem(actionItem("New Tab") { [weak self] in self?.model?.onNewTab?() })
+    return menu
+  }
+
+  private func actionItem(_ title: String, action: @escaping () -> Void) -> NSMenuItem {
+    let item = NSMenuItem(title: title, action: #selector(performMenuAction(_:)), keyEquivalent: "")
+    item.target = self
+    item.representedObject = TabMenuAction(action)
+    return item
+  }
+
+  @objc private func performMenuAction(_ sender: NSMenuItem) {
+    (sender.representedObject as? TabMenuAction)?.run()
+  }
+}
+
+private final class TabMenuAction {
+  let run: () -> Void
+
+  init(_ run: @escaping () -> Void) {
+    self.run = run
+  }
+}
+
+// MARK: - Tab Item
+
+/// One pill tab: icon, title (italic for a preview tab), read-only lock,
+/// attention dot and, on hover, a close button. Mouse events go to the
+/// strip, which owns selection and dragging.
+private final class TabItemView: NSView {
+  private weak var strip: TabStripView?
+  private(set) var tab: TabDisplayInfo
+  private var theme: Theme?
+  private var isSelected = false
+  private var isHovered = false {
+    didSet { if isHovered != oldValue { applyState() } }
+  }
+  var isDragging = false {
+    didSet { if isDragging != oldValue { applyState() } }
+  }
+
+  private let icon = NSImageView()
+  private let title = NSTextField(labelWithString: "")
+  private let lock = NSImageView()
+  private let attentionDot = NSView()
+  private let closeButton = NSButton()
+
+  init(strip: TabStripView) {
+    self.strip = strip
+    self.tab = TabDisplayInfo(
+      id: -1, index: -1, title: "", icon: nil, isPinned: false, isTerminal: false,
+      needsAttention: false)
+    super.init(frame: .zero)
+    wantsLayer = true
+    layer?.cornerRadius = 15
+    layer?.cornerCurve = .continuous
+    layer?.borderWidth = 1
+
+    setAccessibilityElement(true)
+    setAccessibilityRole(.radioButton)
+
+    icon.imageScaling = .scaleProportionallyUpOrDown
+    icon.setAccessibilityElement(false)
+    title.lineBreakMode = .byTruncatingMiddle
+    title.maximumNumberOfLines = 1
+    title.setContentCompressionResistancePriority(.defaultLow, for: .horizontal)
+    title.setAccessibilityElement(false)
+    lock.image = NSImage(systemSymbolName: "lock.fill", accessibilityDescription: "Read-only")?
+      .withSymbolConfiguration(.init(pointSize: 8, weight: .regular))
+    lock.contentTintColor = .tertiaryLabelColor
+    attentionDot.wantsLayer = true
+    attentionDot.layer?.cornerRadius = 3
+    attentionDot.setAccessibilityElement(false)
+    closeButton.image = NSImage(systemSymbolName: "xmark", accessibilityDescription: nil)?
+      .withSymbolConfiguration(.init(pointSize: 8, weight: .bold))
+    closeButton.isBordered = false
+    closeButton.imagePosition = .imageOnly
+    closeButton.contentTintColor = .secondaryLabelColor
+    closeButton.target = self
+    closeButton.action = #selector(closeClicked(_:))
+
+    let spacer = NSView()
+    spacer.setContentHuggingPriority(.init(1), for: .horizontal)
+    let stack = NSStackView(views: [icon, title, lock, spacer, attentionDot, closeButton])
+    stack.orientation = .horizontal
+    stack.spacing = 5
+    stack.edgeInsets = NSEdgeInsets(top: 0, left: 12, bottom: 0, right: 8)
+    stack.translatesAutoresizingMaskIntoConstraints = false
+    addSubview(stack)
+    NSLayoutConstraint.activate([
+      icon.widthAnchor.constraint(equalToConstant: 14),
+      icon.heightAnchor.constraint(equalToConstant: 14),
+      attentionDot.widthAnchor.constraint(equalToConstant: 6),
+      attentionDot.heightAnchor.constraint(equalToConstant: 6),
+      closeButton.widthAnchor.constraint(equalToConstant: 16),
+      closeButton.heightAnchor.constraint(equalToConstant: 16),
+      stack.leadingAnchor.constraint(equalTo: leadingAnchor),
+      stack.trailingAnchor.constraint(equalTo: trailingAnchor),
+      stack.topAnchor.constraint(equalTo: topAnchor),
+      stack.bottomAnchor.constraint(equalTo: bottomAnchor),
+    ])
+  }
+
+  required init?(coder: NSCoder) {
+    fatalError("init(coder:) has not been implemented")
+  }
+
+  func configure(tab: TabDisplayInfo, isSelected: Bool, isDragging: Bool, theme: Theme) {
+    self.tab = tab
+    self.theme = theme
+    self.isSelected = isSelected
+    self.isDragging = isDragging
+
+    icon.image = tab.icon
+    icon.isHidden = tab.icon == nil
+    title.stringValue = tab.title
+    let font = NSFont.systemFont(ofSize: 11.5)
+    title.font =
+      tab.isPreview ? NSFontManager.shared.convert(font, toHaveTrait: .italicFontMask) : font
+    lock.isHidden = tab.readOnlyReason == nil
+    lock.toolTip = tab.readOnlyReason
+    toolTip = tab.tooltip
+
+    let kind = tab.isTerminal ? "Terminal" : "Editor"
+    setAccessibilityLabel("\(kind): \(tab.title)")
+    closeButton.setAccessibilityLabel("Close \(kind) Tab \(tab.title)")
+    applyState()
+  }
+
+  private var isCardSurface: Bool { theme?.surfaceStyle == "card" }
+
+  /// Colors for the selected, dragged and hovered states. Card-surface
+  /// themes (Harbor) raise the selected tab as a content-colored pill with
+  /// a soft warm shadow instead of a fill and border.
+  private func applyState() {
+    guard let theme else { return }
+    let isRaised = isSelected || isDragging
+    attentionDot.isHidden = !tab.needsAttention || isSelected
+    closeButton.isHidden = tab.isPinned || !isHovered
+    setAccessibilitySelected(isSelected)
+
+    effectiveAppearance.performAsCurrentDrawingAppearance {
+      attentionDot.layer?.backgroundColor = NSColor(hex: theme.accent).cgColor
+      let fill: NSColor
+      let border: NSColor
+      if isCardSurface {
+        fill =
+          isRaised
+          ? NSColor(hex: theme.bg)
+          : isHovered ? NSColor(hex: theme.bgHighlight).withAlphaComponent(0.7) : .clear
+        border = .clear
+        title.textColor = NSColor(hex: isRaised ? theme.fg : theme.fgMuted)
+      } else {
+        fill =
+          isRaised
+          ? .controlBackgroundColor
+          : isHovered ? NSColor.labelColor.withAlphaComponent(0.04) : .clear
+        border =
+          isRaised
+          ? NSColor.labelColor.withAlphaComponent(0.2)
+          : isHovered ? NSColor.labelColor.withAlphaComponent(0.08) : .clear
+        title.textColor = isRaised ? .labelColor : .secondaryLabelColor
+      }
+      layer?.backgroundColor = fill.cgColor
+      layer?.borderColor = border.cgColor
+      // Warm-hued shadow (#5c5142) per the Harbor spec — never pure black.
+      layer?.shadowColor = NSColor(red: 0.36, green: 0.32, blue: 0.26, alpha: 1).cgColor
+      layer?.shadowOpacity = isCardSurface && isRaised ? 0.18 : 0
+      layer?.shadowRadius = 1.5
+      layer?.shadowOffset = CGSize(width: 0, height: -1)
+    }
+  }
+
+  override func viewDidChangeEffectiveAppearance() {
+    super.viewDidChangeEffectiveAppearance()
+    applyState()
+  }
+
+  override func updateTrackingAreas() {
+    super.updateTrackingAreas()
+    trackingAreas.forEach(removeTrackingArea)
+    addTrackingArea(
+      NSTrackingArea(
+        rect: bounds, options: [.mouseEnteredAndExited, .activeInKeyWindow, .inVisibleRect],
+        owner: self, userInfo: nil))
+  }
+
+  override func mouseEntered(with event: NSEvent) {
+    isHovered = true
+  }
+
+  override func mouseExited(with event: NSEvent) {
+    isHovered = false
+  }
+
+  override func mouseDown(with event: NSEvent) {
+    strip?.itemMouseDown(self, with: event)
+  }
+
+  override func mouseDragged(with event: NSEvent) {
+    strip?.itemMouseDragged(self, with: event)
+  }
+
+  override func mouseUp(with event: NSEvent) {
+    strip?.itemMouseUp(self, with: event)
+  }
+
+  override func menu(for event: NSEvent) -> NSMenu? {
+    strip?.contextMenu(for: self)
+  }
+
+  override func accessibilityPerformPress() -> Bool {
+    strip?.select(self)
+    return true
+  }
+
+  @objc private func closeClicked(_ sender: Any?) {
+    strip?.close(self)
+  }
+}
@@ -234,6 +234,16 @@ if [[ ! -f "${SWIFT_BIN}" ]]; then
 fi
 echo "    OK: ${SWIFT_BIN}"
 
+# The app must only link system libraries. A Homebrew dylib (libssl,
+# libssh2, ...) would crash at launch on machines without Homebrew.
+NON_SYSTEM_LIBS=$(otool -L "${SWIFT_BIN}" | tail -n +2 | awk '{print $1}' \
+    | grep -Ev '^(/usr/lib/|/System/Library/|@rpath/|@executable_path/)' || true)
+if [[ -n "${NON_SYSTEM_LIBS}" ]]; then
+    echo "ERROR: ${SWIFT_BIN} links non-system libraries:" >&2
+    echo "${NON_SYSTEM_LIBS}" >&2
+    exit 1
+fi
+
 # ── Step 4: Create .app bundle ────────────────────────────────────────
 
 echo "==> Creating ${APP_NAME}.app bundle..."
@@ -304,6 +314,7 @@ cat > "${CONTENTS}/Info.plist" << PLIST
                 <string>public.xml</string>
                 <string>public.yaml</string>
                 <string>public.plain-text</string>
+                <string>public.text</string>
                 <string>com.netscape.javascript-source</string>
                 <string>public.python-script</string>
                 <string>org.rust-lang.rust-source</string>
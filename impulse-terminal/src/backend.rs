@@ -126,6 +126,23 @@ fn absolute_cursor_row<T: EventListener>(term: &Term<T>, row_base: i64) -> i64 {
     row_base + grid.history_size() as i64 + i64::from(grid.cursor.point.line.0)
 }
 
+/// All of the term's text, scrollback included, with trailing blank lines
+/// dropped.
+fn buffer_text<T: EventListener>(term: &Term<T>) -> String {
+    let grid = term.grid();
+    if grid.columns() == 0 || grid.total_lines() == 0 {
+        return String::new();
+    }
+    let start = alacritty_terminal::index::Point::new(
+        grid.topmost_line(),
+        alacritty_terminal::index::Column(0),
+    );
+    let end = alacritty_terminal::index::Point::new(grid.bottommost_line(), grid.last_column());
+    let mut text = term.bounds_to_string(start, end);
+    text.truncate(text.trim_end().len());
+    text
+}
+
 /// Read and reset the term's accumulated damage.
 ///
 /// Returns `None` for full damage, or `Some(rows)` with the damaged viewport
@@ -396,7 +413,7 @@ impl SelectionKind {
     }
 }
 
-fn spawn_pty(pty_options: &PtyOptions, window_size: WindowSize) -> io::Result<tty::Pty> {
+pub(crate) fn spawn_pty(pty_options: &PtyOptions, window_size: WindowSize) -> io::Result<tty::Pty> {
     let _guard = CHILD_ENV_LOCK
         .lock()
         .expect("child environment lock poisoned");
@@ -448,7 +465,10 @@ enum BackendMsg {
     Shutdown,
 }
 
-fn flush_pending_input<W: Write>(writer: &mut W, pending: &mut VecDeque<u8>) -> io::Result<()> {
+pub(crate) fn flush_pending_input<W: Write>(
+    writer: &mut W,
+    pending: &mut VecDeque<u8>,
+) -> io::Result<()> {
     while !pending.is_empty() {
         let (front, _) = pending.as_slices();
         if front.is_empty() {
@@ -478,7 +498,7 @@ fn flush_pending_input<W: Write>(writer: &mut W, pending: &mut VecDeque<u8>) ->
 // TerminalBackend
 // ---------------------------------------------------------------------------
 
-const READ_THREAD_JOIN_TIMEOUT: Duration = Duration::from_secs(2);
+pub(crate) const READ_THREAD_JOIN_TIMEOUT: Duration = Duration::from_secs(2);
 
 /// The main terminal backend. One instance per terminal tab/split.
 pub struct TerminalBackend {
@@ -1419,6 +1439,12 @@ impl TerminalBackend {
         self.term.lock().selection_to_string()
     }
 
+    /// The whole buffer as text, scrollback included, for viewing in an
+    /// editor.
+    pub fn scrollback_text(&self) -> String {
+        buffer_text(&*self.term.lock())
+    }
+
     /// Scroll the viewport. Positive = up (towards history), negative = down.
     pub fn scroll(&self, delta: i32) {
         self.term
@@ -1720,8 +1746,8 @@ fn convert_flags(flags: AlacFlags) -> CellFlags {
 #[cfg(test)]
 mod tests {
     use super::{
-        apply_minimum_contrast, collect_term_damage, contrast_ratio, flush_pending_input,
-        send_wakeup, ConfiguredColors, EventProxy, TermSize,
+        apply_minimum_contrast, buffer_text, collect_term_damage, contrast_ratio,
+        flush_pending_input, send_wakeup, ConfiguredColors, EventProxy, TermSize,
     };
     use crate::config::{TerminalColors, TerminalConfig};
     use crate::event::TerminalEvent;
@@ -1926,4 +1952,22 @@ mod tests {
         let rows = collect_term_damage(&mut term).expect("expected partial damage");
         assert!(rows.iter().all(|&r| r < 5));
     }
+
+    #[test]
+    fn buffer_text_includes_scrollback() {
+        use alacritty_terminal::event::VoidListener;
+        use alacritty_terminal::term::{Config, Term};
+        use alacritty_terminal::vte::ansi::Processor;
+
+        let size = TermSize {
+            columns: 20,
+            screen_lines: 3,
+        };
+        let mut term = Term::new(Config::default(), &size, VoidListener);
+        assert_eq!(buffer_text(&term), "");
+
+        let mut processor: Processor = Processor::new();
+        processor.advance(&mut term, b"one\r\ntwo\r\nthree\r\nfour\r\nfive");
+        assert_eq!(buffer_text(&term), "one\ntwo\nthree\nfour\nfive");
+    }
 }
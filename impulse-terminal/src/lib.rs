@@ -11,6 +11,7 @@ mod event;
 mod grid;
 mod history;
 pub mod osc_scanner;
+pub mod pty;
 mod search;
 
 pub use backend::{
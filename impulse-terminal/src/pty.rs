@@ -0,0 +1,396 @@
+//! Raw PTY sessions without terminal emulation, for hosts that run their own
+//! emulator (e.g. xterm.js) and only need process management: spawn from a
+//! profile, stream output bytes, write input, resize, signal, and query the
+//! child's working directory.
+
+use std::collections::{HashMap, VecDeque};
+use std::io::{self, Read};
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread::JoinHandle;
+
+use alacritty_terminal::event::{OnResize, WindowSize};
+use alacritty_terminal::tty::{self, EventedPty, EventedReadWrite, Options as PtyOptions, Shell};
+use crossbeam_channel::{Receiver, Sender};
+use polling::{Event as PollingEvent, Events, PollMode, Poller};
+use serde::Deserialize;
+
+use crate::backend::{flush_pending_input, spawn_pty, READ_THREAD_JOIN_TIMEOUT};
+
+/// What to run in a PTY session (deserialized from JSON). An empty
+/// `shell_path` runs the user's login shell.
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct PtyProfile {
+    pub shell_path: String,
+    pub shell_args: Vec<String>,
+    pub working_directory: Option<String>,
+    pub env_vars: HashMap<String, String>,
+    pub cols: u16,
+    pub rows: u16,
+}
+
+impl Default for PtyProfile {
+    fn default() -> Self {
+        Self {
+            shell_path: String::new(),
+            shell_args: Vec::new(),
+            working_directory: None,
+            env_vars: HashMap::new(),
+            cols: 80,
+            rows: 24,
+        }
+    }
+}
+
+impl PtyProfile {
+    fn to_pty_options(&self) -> PtyOptions {
+        let shell = if self.shell_path.is_empty() {
+            None
+        } else {
+            Some(Shell::new(self.shell_path.clone(), self.shell_args.clone()))
+        };
+        PtyOptions {
+            shell,
+            working_directory: self.working_directory.as_ref().map(PathBuf::from),
+            drain_on_exit: true,
+            env: self.env_vars.clone(),
+        }
+    }
+}
+
+/// Output from a PTY session, delivered on its reader thread.
+#[derive(Debug, PartialEq, Eq)]
+pub enum PtyEvent {
+    Output(Vec<u8>),
+    /// The child exited with this status code (`-1` if unknown). No events
+    /// follow.
+    Exited(i32),
+}
+
+enum PtyMsg {
+    Input(Vec<u8>),
+    Resize { cols: u16, rows: u16 },
+    Shutdown,
+}
+
+/// A child process attached to a PTY. Dropping it (or calling `shutdown`)
+/// closes the PTY, which hangs up the child.
+pub struct PtySession {
+    cmd_tx: Sender<PtyMsg>,
+    poller: Arc<Poller>,
+    child_pid: u32,
+    read_thread: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl PtySession {
+    /// Spawn `profile` in a new PTY. `on_event` runs on a dedicated reader
+    /// thread for every output chunk and once when the child exits.
+    pub fn spawn(
+        profile: &PtyProfile,
+        on_event: impl FnMut(PtyEvent) + Send + 'static,
+    ) -> Result<Self, String> {
+        let window_size = WindowSize {
+            num_lines: profile.rows.max(1),
+            num_cols: profile.cols.max(1),
+            cell_width: 1,
+            cell_height: 1,
+        };
+        let pty = spawn_pty(&profile.to_pty_options(), window_size)
+            .map_err(|e| format!("Failed to create PTY: {e}"))?;
+        let child_pid = pty.child().id();
+        let poller =
+            Arc::new(Poller::new().map_err(|e| format!("Failed to create PTY poller: {e}"))?);
+        let (cmd_tx, cmd_rx) = crossbeam_channel::unbounded();
+        let poller_clone = Arc::clone(&poller);
+        let read_thread = std::thread::Builder::new()
+            .name("impulse-raw-pty-reader".into())
+            .spawn(move || read_loop(pty, cmd_rx, poller_clone, on_event))
+            .map_err(|e| format!("Failed to spawn read thread: {e}"))?;
+        Ok(Self {
+            cmd_tx,
+            poller,
+            child_pid,
+            read_thread: Mutex::new(Some(read_thread)),
+        })
+    }
+
+    /// Queue bytes for the child's input.
+    pub fn write(&self, data: &[u8]) {
+        if data.is_empty() {
+            return;
+        }
+        let _ = self.cmd_tx.send(PtyMsg::Input(data.to_vec()));
+        let _ = self.poller.notify();
+    }
+
+    pub fn resize(&self, cols: u16, rows: u16) {
+        let _ = self.cmd_tx.send(PtyMsg::Resize {
+            cols: cols.max(1),
+            rows: rows.max(1),
+        });
+        let _ = self.poller.notify();
+    }
+
+    pub fn child_pid(&self) -> u32 {
+        self.child_pid
+    }
+
+    /// Send `signal` (e.g. `libc::SIGTERM`) to the child process.
+    pub fn kill(&self, signal: i32) -> Result<(), String> {
+        // SAFETY: kill(2) has no memory-safety preconditions.
+        if unsafe { libc::kill(self.child_pid as libc::pid_t, signal) } == 0 {
+            Ok(())
+        } else {
+            Err(format!(
+                "Failed to signal process {}: {}",
+                self.child_pid,
+                io::Error::last_os_error()
+            ))
+        }
+    }
+
+    /// The child's current working directory, read from the OS (works even
+    /// without shell integration).
+    pub fn cwd(&self) -> Option<String> {
+        process_cwd(self.child_pid)
+    }
+
+    /// Stop the reader thread and close the PTY. Waits briefly for the
+    /// thread; see `TerminalBackend::shutdown`.
+    pub fn shutdown(&self) {
+        let _ = self.cmd_tx.send(PtyMsg::Shutdown);
+        let _ = self.poller.notify();
+        let handle = self.read_thread.lock().ok().and_then(|mut h| h.take());
+        let Some(handle) = handle else {
+            return;
+        };
+        if handle.thread().id() == std::thread::current().id() {
+            // Shut down from inside `on_event`; the loop exits on its own.
+            return;
+        }
+        let (done_tx, done_rx) = mpsc::channel();
+        let joiner = std::thread::Builder::new()
+            .name("impulse-raw-pty-reader-join".into())
+            .spawn(move || {
+                let _ = handle.join();
+                let _ = done_tx.send(());
+            });
+        match joiner {
+            Ok(joiner) => {
+                if done_rx.recv_timeout(READ_THREAD_JOIN_TIMEOUT).is_ok() {
+                    let _ = joiner.join();
+                } else {
+                    log::warn!("timed out waiting for raw PTY reader thread shutdown");
+                }
+            }
+            Err(err) => log::warn!("failed to spawn raw PTY reader joiner thread: {err}"),
+        }
+    }
+}
+
+impl Drop for PtySession {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+fn read_loop(
+    mut pty: tty::Pty,
+    cmd_rx: Receiver<PtyMsg>,
+    poller: Arc<Poller>,
+    mut on_event: impl FnMut(PtyEvent),
+) {
+    let mut buf = [0u8; 0x10000];
+    let mut pending_input: VecDeque<u8> = VecDeque::new();
+    let poll_opts = PollMode::Level;
+    let mut interest = PollingEvent::readable(0);
+    // SAFETY: the PTY is deregistered when `pty` drops at the end of this
+    // function, before the poller can be dropped by the last Arc holder.
+    if let Err(err) = unsafe { pty.register(&poller, interest, poll_opts) } {
+        log::error!("failed to register raw PTY poller: {err}");
+        on_event(PtyEvent::Exited(-1));
+        return;
+    }
+    let mut events = Events::with_capacity(NonZeroUsize::new(64).unwrap());
+    let mut writable_registered = false;
+
+    // Returns false on shutdown.
+    let handle_cmd = |msg: PtyMsg, pty: &mut tty::Pty, pending: &mut VecDeque<u8>| match msg {
+        PtyMsg::Input(data) => {
+            pending.extend(data);
+            true
+        }
+        PtyMsg::Resize { cols, rows } => {
+            pty.on_resize(WindowSize {
+                num_lines: rows,
+                num_cols: cols,
+                cell_width: 1,
+                cell_height: 1,
+            });
+            true
+        }
+        PtyMsg::Shutdown => false,
+    };
+
+    'event_loop: loop {
+        while let Ok(msg) = cmd_rx.try_recv() {
+            if !handle_cmd(msg, &mut pty, &mut pending_input) {
+                break 'event_loop;
+            }
+        }
+        if !pending_input.is_empty() {
+            if let Err(err) = flush_pending_input(pty.writer(), &mut pending_input) {
+                log::warn!("failed to write raw PTY input: {err}");
+                pending_input.clear();
+            }
+        }
+        let needs_write = !pending_input.is_empty();
+        if needs_write != writable_registered {
+            interest.writable = needs_write;
+            if let Err(err) = pty.reregister(&poller, interest, poll_opts) {
+                log::error!("failed to update raw PTY poll interest: {err}");
+                on_event(PtyEvent::Exited(-1));
+                break;
+            }
+            writable_registered = needs_write;
+        }
+
+        events.clear();
+        if let Err(err) = poller.wait(&mut events, None) {
+            if err.kind() == io::ErrorKind::Interrupted {
+                continue;
+            }
+            log::error!("raw PTY poll failed: {err}");
+            on_event(PtyEvent::Exited(-1));
+            break;
+        }
+
+        let readable = events.iter().any(|e| !e.is_interrupt() && e.readable);
+        if readable {
+            loop {
+                match pty.reader().read(&mut buf) {
+                    Ok(0) => break,
+                    Ok(n) => on_event(PtyEvent::Output(buf[..n].to_vec())),
+                    Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                    // EIO is how Linux reports a hung-up PTY; the child
+                    // event below carries the exit status.
+                    Err(_) => break,
+                }
+            }
+        }
+
+        if let Some(tty::ChildEvent::Exited(status)) = pty.next_child_event() {
+            on_event(PtyEvent::Exited(
+                status.and_then(|s| s.code()).unwrap_or(-1),
+            ));
+            break;
+        }
+    }
+    let _ = pty.deregister(&poller);
+}
+
+#[cfg(target_os = "linux")]
+fn process_cwd(pid: u32) -> Option<String> {
+    std::fs::read_link(format!("/proc/{pid}/cwd"))
+        .ok()
+        .map(|p| p.to_string_lossy().to_string())
+}
+
+#[cfg(target_os = "macos")]
+fn process_cwd(pid: u32) -> Option<String> {
+    let mut info: libc::proc_vnodepathinfo = unsafe { std::mem::zeroed() };
+    let size = std::mem::size_of::<libc::proc_vnodepathinfo>() as libc::c_int;
+    // SAFETY: `info` is a writable buffer of exactly `size` bytes.
+    let written = unsafe {
+        libc::proc_pidinfo(
+            pid as libc::c_int,
+            libc::PROC_PIDVNODEPATHINFO,
+            0,
+            &mut info as *mut _ as *mut libc::c_void,
+            size,
+        )
+    };
+    if written != size {
+        return None;
+    }
+    // SAFETY: `vip_path` is a NUL-terminated MAXPATHLEN buffer.
+    let path = unsafe { std::ffi::CStr::from_ptr(info.pvi_cdir.vip_path.as_ptr() as *const _) };
+    let path = path.to_string_lossy().to_string();
+    (!path.is_empty()).then_some(path)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn process_cwd(_pid: u32) -> Option<String> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn session_reports_cwd_and_can_be_killed() {
+        let dir = tempfile::tempdir().unwrap();
+        let profile = PtyProfile {
+            shell_path: "/bin/sh".into(),
+            shell_args: vec!["-c".into(), "sleep 30".into()],
+            working_directory: Some(dir.path().to_string_lossy().to_string()),
+            ..Default::default()
+        };
+        let (tx, rx) = std::sync::mpsc::channel();
+        let session = PtySession::spawn(&profile, move |event| {
+            if let PtyEvent::Exited(code) = event {
+                let _ = tx.send(code);
+            }
+        })
+        .unwrap();
+        let expected = dir.path().canonicalize().unwrap();
+        assert_eq!(
+            session.cwd().map(PathBuf::from),
+            Some(expected.clone()),
+            "cwd of pid {}",
+            session.child_pid()
+        );
+        session.kill(libc::SIGKILL).unwrap();
+        assert!(rx.recv_timeout(Duration::from_secs(10)).is_ok());
+    }
+
+    #[test]
+    fn session_streams_output_and_reports_exit() {
+        let dir = tempfile::tempdir().unwrap();
+        let profile = PtyProfile {
+            shell_path: "/bin/sh".into(),
+            shell_args: vec!["-c".into(), "read line; echo got:$line; pwd".into()],
+            working_directory: Some(dir.path().to_string_lossy().to_string()),
+            ..Default::default()
+        };
+        let (tx, rx) = std::sync::mpsc::channel();
+        let session = PtySession::spawn(&profile, move |event| {
+            let _ = tx.send(event);
+        })
+        .unwrap();
+        assert!(session.child_pid() > 0);
+        session.resize(100, 30);
+        session.write(b"hello\n");
+
+        let mut output = Vec::new();
+        let mut exit = None;
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while exit.is_none() && Instant::now() < deadline {
+            match rx.recv_timeout(Duration::from_millis(200)) {
+                Ok(PtyEvent::Output(bytes)) => output.extend(bytes),
+                Ok(PtyEvent::Exited(code)) => exit = Some(code),
+                Err(_) => {}
+            }
+        }
+        let output = String::from_utf8_lossy(&output);
+        assert!(output.contains("got:hello"), "output: {output}");
+        let dir_name = dir.path().file_name().unwrap().to_string_lossy();
+        assert!(output.contains(dir_name.as_ref()), "output: {output}");
+        assert_eq!(exit, Some(0));
+    }
+}
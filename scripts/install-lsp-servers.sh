@@ -28,9 +28,12 @@ fi
 
 echo "Installing Impulse managed web LSP servers into ${LSP_ROOT} ..."
 
+# Keep these pins in sync with MANAGED_PACKAGES in impulse-core/src/lsp_install.rs.
 npm install \
   --prefix "${LSP_ROOT}" \
+  --no-audit \
   --no-fund \
+  --save-exact \
   typescript@5.7.3 \
   typescript-language-server@4.3.3 \
   intelephense@1.12.6 \